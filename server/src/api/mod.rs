@@ -7,4 +7,5 @@ pub mod comments;
 pub mod ai_config;
 pub mod stats;
 pub mod auth;
-pub mod admin;
\ No newline at end of file
+pub mod admin;
+pub mod realtime;
@@ -0,0 +1,312 @@
+// server/src/api/realtime.rs
+// module: lead-server | layer: api | role: 实时看板通道
+// summary: 桌面端经 WebSocket 上行业务事件（任务进度 / 动作计数 / 设备状态），服务端转发给在线看板；看板的订阅条件汇总后下发给桌面端作为过滤器
+
+use std::collections::{BTreeSet, HashMap};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use axum::{
+    extract::{
+        ws::{Message, WebSocket, WebSocketUpgrade},
+        Extension, Query, State,
+    },
+    http::{HeaderMap, StatusCode},
+    response::Response,
+};
+use futures::{SinkExt, StreamExt};
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc;
+
+use super::auth::verify_token;
+use crate::db::{self, DbPool};
+
+/// 超过该时间没有收到任何消息（含心跳）即断开
+const IDLE_TIMEOUT: Duration = Duration::from_secs(90);
+
+/// 订阅条件：主题集合 + 可选的设备范围（None 表示全部设备）
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubscriptionFilter {
+    #[serde(default)]
+    pub topics: BTreeSet<String>,
+    #[serde(default)]
+    pub device_ids: Option<BTreeSet<String>>,
+}
+
+impl SubscriptionFilter {
+    pub fn matches(&self, topic: &str, device_id: Option<&str>) -> bool {
+        if !self.topics.contains(topic) {
+            return false;
+        }
+        match (&self.device_ids, device_id) {
+            (None, _) | (Some(_), None) => true,
+            (Some(ids), Some(id)) => ids.contains(id),
+        }
+    }
+
+    /// 多个看板订阅的并集（任一看板不限设备则不限设备）
+    pub fn union<'a>(filters: impl IntoIterator<Item = &'a SubscriptionFilter>) -> SubscriptionFilter {
+        let mut merged = SubscriptionFilter { topics: BTreeSet::new(), device_ids: Some(BTreeSet::new()) };
+        let mut any = false;
+        for filter in filters {
+            any = true;
+            merged.topics.extend(filter.topics.iter().cloned());
+            merged.device_ids = match (merged.device_ids, &filter.device_ids) {
+                (Some(mut ids), Some(more)) => {
+                    ids.extend(more.iter().cloned());
+                    Some(ids)
+                }
+                _ => None,
+            };
+        }
+        if !any {
+            merged.device_ids = None;
+        }
+        merged
+    }
+}
+
+/// 桌面端 → 服务端
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+enum DesktopMessage {
+    #[serde(rename_all = "camelCase")]
+    Hello { client_id: String, #[serde(default)] device_ids: Vec<String> },
+    #[serde(rename_all = "camelCase")]
+    Event { topic: String, device_id: Option<String>, payload: serde_json::Value, ts: i64 },
+    Heartbeat { ts: i64 },
+}
+
+/// 服务端 → 桌面端
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+enum DesktopCommand {
+    #[serde(rename_all = "camelCase")]
+    Subscribe { topics: BTreeSet<String>, device_ids: Option<BTreeSet<String>> },
+    HeartbeatAck { ts: i64 },
+}
+
+/// 看板 → 服务端
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+enum DashboardMessage {
+    Subscribe(SubscriptionFilter),
+}
+
+/// 服务端 → 看板
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct DashboardEvent<'a> {
+    #[serde(rename = "type")]
+    kind: &'static str,
+    user_id: i32,
+    client_id: Option<&'a str>,
+    topic: &'a str,
+    device_id: Option<&'a str>,
+    payload: &'a serde_json::Value,
+    ts: i64,
+}
+
+struct DesktopConn {
+    user_id: i32,
+    client_id: Option<String>,
+    tx: mpsc::UnboundedSender<Message>,
+}
+
+struct DashboardConn {
+    user_id: i32,
+    /// 管理员看所有用户的桌面端，其余只看自己的
+    admin: bool,
+    filter: SubscriptionFilter,
+    tx: mpsc::UnboundedSender<Message>,
+}
+
+/// 连接登记表
+#[derive(Default)]
+pub struct RealtimeHub {
+    next_id: AtomicU64,
+    desktops: Mutex<HashMap<u64, DesktopConn>>,
+    dashboards: Mutex<HashMap<u64, DashboardConn>>,
+}
+
+fn to_message<T: Serialize>(value: &T) -> Option<Message> {
+    serde_json::to_string(value).ok().map(Message::Text)
+}
+
+impl RealtimeHub {
+    fn next_id(&self) -> u64 {
+        self.next_id.fetch_add(1, Ordering::Relaxed)
+    }
+
+    /// 某用户的桌面端应当上行的过滤器：能看到该用户的看板订阅并集
+    fn filter_for(&self, user_id: i32) -> SubscriptionFilter {
+        let dashboards = self.dashboards.lock().unwrap();
+        SubscriptionFilter::union(dashboards.values().filter(|d| d.admin || d.user_id == user_id).map(|d| &d.filter))
+    }
+
+    fn subscribe_command(filter: SubscriptionFilter) -> Option<Message> {
+        to_message(&DesktopCommand::Subscribe { topics: filter.topics, device_ids: filter.device_ids })
+    }
+
+    /// 看板订阅变化后，把新的过滤器推给所有桌面端
+    fn push_filters(&self) {
+        let desktops: Vec<(i32, mpsc::UnboundedSender<Message>)> =
+            self.desktops.lock().unwrap().values().map(|d| (d.user_id, d.tx.clone())).collect();
+        for (user_id, tx) in desktops {
+            if let Some(message) = Self::subscribe_command(self.filter_for(user_id)) {
+                let _ = tx.send(message);
+            }
+        }
+    }
+
+    fn forward(&self, desktop_id: u64, topic: &str, device_id: Option<&str>, payload: &serde_json::Value, ts: i64) {
+        let (user_id, client_id) = match self.desktops.lock().unwrap().get(&desktop_id) {
+            Some(d) => (d.user_id, d.client_id.clone()),
+            None => return,
+        };
+        let event = DashboardEvent { kind: "event", user_id, client_id: client_id.as_deref(), topic, device_id, payload, ts };
+        let Some(message) = to_message(&event) else {
+            return;
+        };
+        for dashboard in self.dashboards.lock().unwrap().values() {
+            if (dashboard.admin || dashboard.user_id == user_id) && dashboard.filter.matches(topic, device_id) {
+                let _ = dashboard.tx.send(message.clone());
+            }
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TokenQuery {
+    pub token: Option<String>,
+}
+
+/// 认证：优先 Authorization 头，浏览器 WebSocket 无法设置请求头时用 `?token=`
+fn authenticate(headers: &HeaderMap, query_token: Option<&str>) -> Option<i32> {
+    let header_token = headers
+        .get("Authorization")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|s| s.strip_prefix("Bearer "));
+    verify_token(header_token.or(query_token)?)
+}
+
+/// 把通道中的消息写入 socket，返回写任务句柄
+fn spawn_writer(
+    mut sink: futures::stream::SplitSink<WebSocket, Message>,
+    mut rx: mpsc::UnboundedReceiver<Message>,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        while let Some(message) = rx.recv().await {
+            if sink.send(message).await.is_err() {
+                break;
+            }
+        }
+    })
+}
+
+/// 读取下一条文本消息；超时、关闭或出错返回 None
+async fn next_text(stream: &mut futures::stream::SplitStream<WebSocket>) -> Option<Option<String>> {
+    match tokio::time::timeout(IDLE_TIMEOUT, stream.next()).await {
+        Ok(Some(Ok(Message::Text(text)))) => Some(Some(text)),
+        Ok(Some(Ok(Message::Close(_)))) | Ok(Some(Err(_))) | Ok(None) | Err(_) => None,
+        Ok(Some(Ok(_))) => Some(None),
+    }
+}
+
+/// GET /api/realtime/desktop（WebSocket，需要 Token）
+/// 桌面端上行通道
+pub async fn desktop_ws(
+    ws: WebSocketUpgrade,
+    headers: HeaderMap,
+    Query(query): Query<TokenQuery>,
+    Extension(hub): Extension<Arc<RealtimeHub>>,
+) -> Result<Response, StatusCode> {
+    let user_id = authenticate(&headers, query.token.as_deref()).ok_or(StatusCode::UNAUTHORIZED)?;
+    Ok(ws.on_upgrade(move |socket| run_desktop(hub, user_id, socket)))
+}
+
+async fn run_desktop(hub: Arc<RealtimeHub>, user_id: i32, socket: WebSocket) {
+    let (sink, mut stream) = socket.split();
+    let (tx, rx) = mpsc::unbounded_channel();
+    let writer = spawn_writer(sink, rx);
+    let id = hub.next_id();
+    hub.desktops.lock().unwrap().insert(id, DesktopConn { user_id, client_id: None, tx: tx.clone() });
+    if let Some(message) = RealtimeHub::subscribe_command(hub.filter_for(user_id)) {
+        let _ = tx.send(message);
+    }
+    tracing::info!("Desktop uplink connected: user={} conn={}", user_id, id);
+
+    while let Some(text) = next_text(&mut stream).await {
+        let Some(text) = text else { continue };
+        match serde_json::from_str::<DesktopMessage>(&text) {
+            Ok(DesktopMessage::Hello { client_id, device_ids }) => {
+                tracing::info!("Desktop uplink hello: conn={} client={} devices={}", id, client_id, device_ids.len());
+                if let Some(conn) = hub.desktops.lock().unwrap().get_mut(&id) {
+                    conn.client_id = Some(client_id);
+                }
+            }
+            Ok(DesktopMessage::Event { topic, device_id, payload, ts }) => {
+                hub.forward(id, &topic, device_id.as_deref(), &payload, ts);
+            }
+            Ok(DesktopMessage::Heartbeat { ts }) => {
+                if let Some(message) = to_message(&DesktopCommand::HeartbeatAck { ts }) {
+                    let _ = tx.send(message);
+                }
+            }
+            Err(e) => tracing::warn!("Invalid desktop uplink message: {}", e),
+        }
+    }
+
+    hub.desktops.lock().unwrap().remove(&id);
+    writer.abort();
+    tracing::info!("Desktop uplink disconnected: user={} conn={}", user_id, id);
+}
+
+/// GET /api/realtime/dashboard（WebSocket，需要 Token）
+/// 管理端看板：发送 subscribe 设置订阅条件，接收匹配的桌面端事件
+pub async fn dashboard_ws(
+    ws: WebSocketUpgrade,
+    headers: HeaderMap,
+    Query(query): Query<TokenQuery>,
+    State(pool): State<DbPool>,
+    Extension(hub): Extension<Arc<RealtimeHub>>,
+) -> Result<Response, StatusCode> {
+    let user_id = authenticate(&headers, query.token.as_deref()).ok_or(StatusCode::UNAUTHORIZED)?;
+    let user = db::users::find_by_id(&pool, user_id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::UNAUTHORIZED)?;
+    let admin = user.role.as_deref() == Some("admin");
+    Ok(ws.on_upgrade(move |socket| run_dashboard(hub, user_id, admin, socket)))
+}
+
+async fn run_dashboard(hub: Arc<RealtimeHub>, user_id: i32, admin: bool, socket: WebSocket) {
+    let (sink, mut stream) = socket.split();
+    let (tx, rx) = mpsc::unbounded_channel();
+    let writer = spawn_writer(sink, rx);
+    let id = hub.next_id();
+    hub.dashboards
+        .lock()
+        .unwrap()
+        .insert(id, DashboardConn { user_id, admin, filter: SubscriptionFilter::default(), tx });
+
+    while let Some(text) = next_text(&mut stream).await {
+        let Some(text) = text else { continue };
+        match serde_json::from_str::<DashboardMessage>(&text) {
+            Ok(DashboardMessage::Subscribe(filter)) => {
+                if let Some(conn) = hub.dashboards.lock().unwrap().get_mut(&id) {
+                    conn.filter = filter;
+                }
+                hub.push_filters();
+            }
+            Err(e) => tracing::warn!("Invalid dashboard message: {}", e),
+        }
+    }
+
+    hub.dashboards.lock().unwrap().remove(&id);
+    hub.push_filters();
+    writer.abort();
+}
+
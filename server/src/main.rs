@@ -6,9 +6,11 @@ mod config;
 mod db;
 mod api;
 
+use std::sync::Arc;
+
 use axum::{
     routing::{get, post, put},
-    Router,
+    Extension, Router,
 };
 use tower_http::cors::{Any, CorsLayer};
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
@@ -60,8 +62,12 @@ async fn main() -> anyhow::Result<()> {
         .route("/api/stats", get(api::stats::get_stats))
         // 导出 API
         .route("/api/export/csv", get(api::stats::export_csv))
+        // 实时看板通道（WebSocket）
+        .route("/api/realtime/desktop", get(api::realtime::desktop_ws))
+        .route("/api/realtime/dashboard", get(api::realtime::dashboard_ws))
         // 共享状态
         .with_state(pool)
+        .layer(Extension(Arc::new(api::realtime::RealtimeHub::default())))
         // CORS
         .layer(
             CorsLayer::new()
@@ -14,6 +14,12 @@ pub struct AISettings {
     pub concurrency: u32, // AI批量请求并发数
     pub base_url_openai: Option<String>,
     pub base_url_hunyuan: Option<String>,
+    /// 单次 AI 调用超时（秒）
+    #[serde(default = "default_request_timeout_secs")]
+    pub request_timeout_secs: u64,
+    /// 批量向量化每次请求的文本条数
+    #[serde(default = "default_embed_batch_size")]
+    pub embed_batch_size: usize,
     #[serde(skip)]
     pub openai_api_key: String,
     #[serde(skip)]
@@ -32,12 +38,22 @@ impl Default for AISettings {
             concurrency: 5,
             base_url_openai: None,
             base_url_hunyuan: None,
+            request_timeout_secs: default_request_timeout_secs(),
+            embed_batch_size: default_embed_batch_size(),
             openai_api_key: String::new(),
             hunyuan_api_key: String::new(),
         }
     }
 }
 
+fn default_request_timeout_secs() -> u64 {
+    crate::ai::cancellation::DEFAULT_AI_CALL_TIMEOUT_SECS
+}
+
+fn default_embed_batch_size() -> usize {
+    crate::ai::batch_embed::DEFAULT_EMBED_BATCH_SIZE
+}
+
 pub fn config_path() -> Result<PathBuf> {
     let dir = dirs::config_dir()
         .ok_or_else(|| anyhow::anyhow!("Cannot resolve config dir"))?
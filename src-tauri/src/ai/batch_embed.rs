@@ -0,0 +1,147 @@
+// src-tauri/src/ai/batch_embed.rs
+// module: ai | layer: infrastructure | role: batch-embedding
+// summary: 批量向量化 - 每次请求 N 条文本，失败批次退避重试，仍失败时二分拆批隔离问题文本，返回逐条结果
+
+use std::future::Future;
+use std::time::Duration;
+
+use anyhow::{anyhow, Result};
+use serde_json::Value;
+use tracing::warn;
+
+/// 默认每批条数
+pub const DEFAULT_EMBED_BATCH_SIZE: usize = 64;
+/// 重试退避基数（毫秒），按次数线性增长
+const RETRY_BACKOFF_MS: u64 = 500;
+
+/// 批量向量化结果（与输入一一对应）
+#[derive(Debug, Default)]
+pub struct BatchEmbedOutcome {
+    pub vectors: Vec<Option<Vec<f32>>>,
+    /// 最终失败的输入下标
+    pub failed: Vec<usize>,
+    /// 实际发出的请求数（含重试）
+    pub requests: usize,
+}
+
+impl BatchEmbedOutcome {
+    pub fn succeeded(&self) -> usize {
+        self.vectors.iter().filter(|v| v.is_some()).count()
+    }
+}
+
+/// 分批调用 `embed`
+///
+/// 每批失败后最多重试 `max_retries` 次；仍失败且批次多于 1 条时拆成两半分别处理，
+/// 单条仍失败则记入 `failed`，不影响其他文本。
+pub async fn embed_in_batches<F, Fut>(
+    texts: &[String],
+    batch_size: usize,
+    max_retries: u32,
+    embed: F,
+) -> BatchEmbedOutcome
+where
+    F: Fn(Vec<String>) -> Fut,
+    Fut: Future<Output = Result<Vec<Vec<f32>>>>,
+{
+    let mut outcome = BatchEmbedOutcome {
+        vectors: vec![None; texts.len()],
+        ..Default::default()
+    };
+    let batch_size = batch_size.max(1);
+    let mut pending: Vec<(usize, usize)> = (0..texts.len())
+        .step_by(batch_size)
+        .map(|start| (start, (start + batch_size).min(texts.len())))
+        .rev()
+        .collect();
+
+    while let Some((start, end)) = pending.pop() {
+        let mut last_error = None;
+        for attempt in 0..=max_retries {
+            if attempt > 0 {
+                tokio::time::sleep(Duration::from_millis(RETRY_BACKOFF_MS * attempt as u64)).await;
+            }
+            outcome.requests += 1;
+            match embed(texts[start..end].to_vec()).await.and_then(|v| check_count(v, end - start)) {
+                Ok(vectors) => {
+                    for (offset, vector) in vectors.into_iter().enumerate() {
+                        outcome.vectors[start + offset] = Some(vector);
+                    }
+                    last_error = None;
+                    break;
+                }
+                Err(e) => last_error = Some(e),
+            }
+        }
+
+        let Some(error) = last_error else { continue };
+        if end - start > 1 {
+            // 拆批：先处理前半，保持输出顺序稳定
+            let mid = start + (end - start) / 2;
+            pending.push((mid, end));
+            pending.push((start, mid));
+        } else {
+            warn!("⚠️ 文本 #{} 向量化失败: {}", start, error);
+            outcome.failed.push(start);
+        }
+    }
+    outcome
+}
+
+/// 解析 OpenAI 兼容的 embeddings 响应（按 `index` 排序，格式异常时返回错误而不是 panic）
+pub fn parse_embeddings_response(v: &Value) -> Result<Vec<Vec<f32>>> {
+    let data = v["data"].as_array().ok_or_else(|| match v["error"]["message"].as_str() {
+        Some(message) => anyhow!("embeddings 请求失败: {}", message),
+        None => anyhow!("bad embeddings response"),
+    })?;
+    let mut items = data.iter()
+        .enumerate()
+        .map(|(i, item)| {
+            let index = item["index"].as_u64().map(|n| n as usize).unwrap_or(i);
+            let vector = item["embedding"].as_array()
+                .ok_or_else(|| anyhow!("embeddings 第 {} 项缺少向量", i))?
+                .iter()
+                .map(|n| n.as_f64().map(|f| f as f32).ok_or_else(|| anyhow!("embeddings 第 {} 项含非数字", i)))
+                .collect::<Result<Vec<f32>>>()?;
+            Ok((index, vector))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    items.sort_by_key(|(index, _)| *index);
+    Ok(items.into_iter().map(|(_, vector)| vector).collect())
+}
+
+fn check_count(vectors: Vec<Vec<f32>>, expected: usize) -> Result<Vec<Vec<f32>>> {
+    if vectors.len() != expected {
+        return Err(anyhow!("向量数量不匹配: 期望 {}，实际 {}", expected, vectors.len()));
+    }
+    if vectors.iter().any(|v| v.is_empty()) {
+        return Err(anyhow!("返回了空向量"));
+    }
+    Ok(vectors)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[tokio::test]
+    async fn isolates_bad_texts_and_retries_flaky_batches() {
+        let texts: Vec<String> = ["a", "b", "bad", "c", "d"].iter().map(|s| s.to_string()).collect();
+        let calls = AtomicUsize::new(0);
+        let outcome = embed_in_batches(&texts, 4, 1, |batch| {
+            let call = calls.fetch_add(1, Ordering::SeqCst);
+            async move {
+                // 第一次请求偶发失败，含 "bad" 的批次始终失败
+                if call == 0 || batch.iter().any(|t| t == "bad") {
+                    return Err(anyhow!("upstream error"));
+                }
+                Ok(batch.iter().map(|t| vec![t.len() as f32]).collect())
+            }
+        }).await;
+
+        assert_eq!(outcome.failed, vec![2]);
+        assert_eq!(outcome.succeeded(), 4);
+        assert_eq!(outcome.vectors[4], Some(vec![1.0]));
+    }
+}
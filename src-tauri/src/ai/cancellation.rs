@@ -0,0 +1,225 @@
+// src-tauri/src/ai/cancellation.rs
+// module: ai | layer: infrastructure | role: call-cancellation
+// summary: AI 调用的取消令牌与单次超时 - 取消或超时时丢弃请求 future，底层 HTTP 连接随之中断
+
+use std::collections::HashMap;
+use std::fmt;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+
+use parking_lot::Mutex;
+use tokio::sync::watch;
+
+/// 默认单次 AI 调用超时（秒）
+pub const DEFAULT_AI_CALL_TIMEOUT_SECS: u64 = 90;
+
+/// 取消令牌
+///
+/// 可以自己持有触发端（`new`），也可以挂在已有的停止信号上（`from_signal`），
+/// 例如 Agent 循环的 `stop_tx`，这样点击停止时进行中的 AI 请求也会一起中断。
+#[derive(Debug, Clone)]
+pub struct CancellationToken {
+    signal: watch::Receiver<bool>,
+    trigger: Option<Arc<watch::Sender<bool>>>,
+}
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        let (tx, rx) = watch::channel(false);
+        Self { signal: rx, trigger: Some(Arc::new(tx)) }
+    }
+
+    /// 跟随外部停止信号（值变为 true 即视为取消）
+    pub fn from_signal(signal: watch::Receiver<bool>) -> Self {
+        Self { signal, trigger: None }
+    }
+
+    /// 触发取消；跟随外部信号的令牌由信号持有方取消
+    pub fn cancel(&self) {
+        if let Some(trigger) = &self.trigger {
+            let _ = trigger.send(true);
+        }
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        *self.signal.borrow()
+    }
+
+    /// 等待取消；信号源被丢弃时永不返回
+    pub async fn cancelled(&self) {
+        let mut signal = self.signal.clone();
+        loop {
+            if *signal.borrow_and_update() {
+                return;
+            }
+            if signal.changed().await.is_err() {
+                std::future::pending::<()>().await;
+            }
+        }
+    }
+}
+
+impl Default for CancellationToken {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// AI 调用失败分类
+#[derive(Debug, Clone, PartialEq)]
+pub enum AiCallError {
+    /// 超过单次调用时限
+    Timeout { secs: u64 },
+    /// 用户停止或调用方取消
+    Cancelled,
+    /// 提供商返回的其他错误
+    Failed(String),
+}
+
+impl AiCallError {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            AiCallError::Timeout { .. } => "timeout",
+            AiCallError::Cancelled => "cancelled",
+            AiCallError::Failed(_) => "failed",
+        }
+    }
+}
+
+impl fmt::Display for AiCallError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AiCallError::Timeout { secs } => write!(f, "[timeout] AI 调用超时（{} 秒）", secs),
+            AiCallError::Cancelled => write!(f, "[cancelled] AI 调用已取消"),
+            AiCallError::Failed(message) => write!(f, "[failed] {}", message),
+        }
+    }
+}
+
+impl std::error::Error for AiCallError {}
+
+/// 带超时与取消地执行一次 AI 调用
+///
+/// 超时或取消时请求 future 被直接丢弃，reqwest 会关闭对应连接，不再继续消耗 token。
+pub async fn with_deadline<T, E, F>(
+    call: F,
+    timeout: Duration,
+    cancel: Option<&CancellationToken>,
+) -> Result<T, AiCallError>
+where
+    E: fmt::Display,
+    F: Future<Output = Result<T, E>>,
+{
+    if cancel.is_some_and(|c| c.is_cancelled()) {
+        return Err(AiCallError::Cancelled);
+    }
+
+    let cancelled = async {
+        match cancel {
+            Some(token) => token.cancelled().await,
+            None => std::future::pending().await,
+        }
+    };
+
+    tokio::select! {
+        biased;
+        _ = cancelled => Err(AiCallError::Cancelled),
+        result = tokio::time::timeout(timeout, call) => match result {
+            Ok(Ok(value)) => Ok(value),
+            Ok(Err(e)) => Err(AiCallError::Failed(e.to_string())),
+            Err(_) => Err(AiCallError::Timeout { secs: timeout.as_secs() }),
+        },
+    }
+}
+
+/// 进行中的 AI 调用登记表（按请求 ID 取消）
+#[derive(Debug, Default)]
+pub struct InflightCalls {
+    calls: Mutex<HashMap<String, CancellationToken>>,
+}
+
+impl InflightCalls {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 登记一次调用，返回其取消令牌
+    pub fn register(&self, request_id: &str) -> CancellationToken {
+        let token = CancellationToken::new();
+        self.calls.lock().insert(request_id.to_string(), token.clone());
+        token
+    }
+
+    /// 调用结束后移除
+    pub fn finish(&self, request_id: &str) {
+        self.calls.lock().remove(request_id);
+    }
+
+    /// 取消指定调用；`request_id` 为空时取消全部，返回取消的数量
+    pub fn cancel(&self, request_id: Option<&str>) -> usize {
+        let mut calls = self.calls.lock();
+        match request_id {
+            Some(id) => calls.remove(id).map(|token| token.cancel()).map_or(0, |_| 1),
+            None => {
+                let count = calls.len();
+                calls.drain().for_each(|(_, token)| token.cancel());
+                count
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn classifies_timeout_and_cancellation() {
+        let slow = async {
+            tokio::time::sleep(Duration::from_secs(5)).await;
+            Ok::<_, String>("done")
+        };
+        let result = with_deadline(slow, Duration::from_millis(20), None).await;
+        assert!(matches!(result, Err(AiCallError::Timeout { .. })));
+
+        let token = CancellationToken::new();
+        let canceller = token.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            canceller.cancel();
+        });
+        let slow = async {
+            tokio::time::sleep(Duration::from_secs(5)).await;
+            Ok::<_, String>("done")
+        };
+        let result = with_deadline(slow, Duration::from_secs(5), Some(&token)).await;
+        assert_eq!(result, Err(AiCallError::Cancelled));
+
+        let failed = with_deadline(async { Err::<(), _>("quota") }, Duration::from_secs(1), None).await;
+        assert_eq!(failed.unwrap_err().kind(), "failed");
+    }
+
+    #[tokio::test]
+    async fn follows_external_stop_signal() {
+        let (stop_tx, stop_rx) = watch::channel(false);
+        let token = CancellationToken::from_signal(stop_rx);
+        assert!(!token.is_cancelled());
+        stop_tx.send(true).unwrap();
+        assert!(token.is_cancelled());
+        let result = with_deadline(async { Ok::<_, String>(1) }, Duration::from_secs(1), Some(&token)).await;
+        assert_eq!(result, Err(AiCallError::Cancelled));
+    }
+
+    #[test]
+    fn inflight_calls_cancel_by_id_or_all() {
+        let calls = InflightCalls::new();
+        let a = calls.register("a");
+        let b = calls.register("b");
+        assert_eq!(calls.cancel(Some("a")), 1);
+        assert!(a.is_cancelled() && !b.is_cancelled());
+        assert_eq!(calls.cancel(Some("a")), 0);
+        assert_eq!(calls.cancel(None), 1);
+        assert!(b.is_cancelled());
+    }
+}
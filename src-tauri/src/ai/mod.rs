@@ -1,10 +1,12 @@
 // src-tauri/src/ai/mod.rs
 pub mod ai_config;
+pub mod batch_embed;
+pub mod cancellation;
 pub mod provider;
 pub mod providers {
     pub mod hunyuan;
     pub mod openai;
 }
 pub mod router;
+pub mod sse;
 pub mod ai_types;
-pub mod commands;
@@ -5,7 +5,6 @@ use anyhow::Result;
 use reqwest::Client;
 use serde_json::{json, Value};
 use std::time::Duration;
-use futures_util::StreamExt;
 
 pub struct HunyuanProvider {
     pub api_key: String,
@@ -63,15 +62,12 @@ impl AIProvider for HunyuanProvider {
             .await?;
 
         if req.stream.unwrap_or(false) {
-            let mut s = res.bytes_stream();
-            while let Some(b) = s.next().await {
+            crate::ai::sse::collect_stream(res, move |delta| {
                 if let Some(cb) = &on_stream {
-                    cb(ChatChunk {
-                        delta: String::from_utf8_lossy(&b?).into(),
-                    });
+                    cb(ChatChunk { delta });
                 }
-            }
-            Ok(Value::Null)
+            })
+            .await
         } else {
             Ok(res.json().await?)
         }
@@ -89,17 +85,6 @@ impl AIProvider for HunyuanProvider {
             .await?
             .json()
             .await?;
-        let arr = v["data"].as_array().unwrap();
-        Ok(arr
-            .iter()
-            .map(|x| {
-                x["embedding"]
-                    .as_array()
-                    .unwrap()
-                    .iter()
-                    .map(|n| n.as_f64().unwrap() as f32)
-                    .collect()
-            })
-            .collect())
+        crate::ai::batch_embed::parse_embeddings_response(&v)
     }
 }
@@ -5,7 +5,6 @@ use anyhow::{anyhow, Result};
 use reqwest::{header::HeaderMap, Client};
 use serde_json::{json, Value};
 use std::time::Duration;
-use futures_util::StreamExt;
 
 pub struct OpenAIProvider {
     pub api_key: String,
@@ -66,16 +65,16 @@ impl AIProvider for OpenAIProvider {
                 .json(&body)
                 .send()
                 .await?;
-            let mut lines = res.bytes_stream();
-            while let Some(item) = lines.next().await {
-                let chunk = String::from_utf8_lossy(&item?).to_string();
+            if !res.status().is_success() {
+                let status = res.status();
+                return Err(anyhow!("AI API 错误 [{}]: {}", status, res.text().await.unwrap_or_default()));
+            }
+            crate::ai::sse::collect_stream(res, move |delta| {
                 if let Some(cb) = &on_stream {
-                    cb(ChatChunk {
-                        delta: chunk.clone(),
-                    });
+                    cb(ChatChunk { delta });
                 }
-            }
-            Ok(Value::Null)
+            })
+            .await
         } else {
             let res = self
                 .client()
@@ -99,19 +98,6 @@ impl AIProvider for OpenAIProvider {
             .send()
             .await?;
         let v: Value = res.json().await?;
-        let arr = v["data"]
-            .as_array()
-            .ok_or_else(|| anyhow!("bad embeddings response"))?;
-        Ok(arr
-            .iter()
-            .map(|x| {
-                x["embedding"]
-                    .as_array()
-                    .unwrap()
-                    .iter()
-                    .map(|n| n.as_f64().unwrap() as f32)
-                    .collect()
-            })
-            .collect())
+        crate::ai::batch_embed::parse_embeddings_response(&v)
     }
 }
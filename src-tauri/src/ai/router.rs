@@ -22,20 +22,28 @@ pub struct AIRouter {
 impl AIRouter {
     pub fn new(settings: AISettings) -> Self {
         let p = match settings.provider.as_str() {
-            "hunyuan" => ProviderEnum::Hunyuan(Arc::new(HunyuanProvider::new(
-                settings.hunyuan_api_key.clone(),
-                settings
-                    .base_url_hunyuan
-                    .clone()
-                    .unwrap_or_else(|| "https://api.hunyuan.cloud.tencent.com/v1".into()),
-            ))),
-            _ => ProviderEnum::OpenAI(Arc::new(OpenAIProvider::new(
-                settings.openai_api_key.clone(),
-                settings
-                    .base_url_openai
-                    .clone()
-                    .unwrap_or_else(|| "https://api.openai.com/v1".into()),
-            ))),
+            "hunyuan" => {
+                let mut provider = HunyuanProvider::new(
+                    settings.hunyuan_api_key.clone(),
+                    settings
+                        .base_url_hunyuan
+                        .clone()
+                        .unwrap_or_else(|| "https://api.hunyuan.cloud.tencent.com/v1".into()),
+                );
+                provider.timeout = settings.request_timeout_secs;
+                ProviderEnum::Hunyuan(Arc::new(provider))
+            }
+            _ => {
+                let mut provider = OpenAIProvider::new(
+                    settings.openai_api_key.clone(),
+                    settings
+                        .base_url_openai
+                        .clone()
+                        .unwrap_or_else(|| "https://api.openai.com/v1".into()),
+                );
+                provider.timeout = settings.request_timeout_secs;
+                ProviderEnum::OpenAI(Arc::new(provider))
+            }
         };
         Self { p, settings }
     }
@@ -0,0 +1,197 @@
+// src-tauri/src/ai/sse.rs
+// module: ai | layer: infrastructure | role: sse-decoder
+// summary: OpenAI 兼容接口的 SSE 流解析 - 按行切分 data 帧并累积增量文本与工具调用
+
+use futures_util::StreamExt;
+use serde_json::{json, Value};
+
+/// 流结束标记
+const DONE_MARKER: &str = "[DONE]";
+
+/// SSE 行缓冲：网络分块可能在任意位置截断，按完整行输出 `data:` 负载
+#[derive(Debug, Default)]
+pub struct SseDecoder {
+    /// 按字节缓冲，避免多字节字符被分块截断后解码出错
+    buffer: Vec<u8>,
+    done: bool,
+}
+
+impl SseDecoder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 是否已收到 `[DONE]`
+    pub fn is_done(&self) -> bool {
+        self.done
+    }
+
+    /// 输入一个网络分块，返回其中完整的 data 负载（JSON 文本）
+    pub fn push(&mut self, chunk: &[u8]) -> Vec<String> {
+        self.buffer.extend_from_slice(chunk);
+
+        let mut payloads = Vec::new();
+        while let Some(pos) = self.buffer.iter().position(|b| *b == b'\n') {
+            let line: Vec<u8> = self.buffer.drain(..=pos).collect();
+            let line = String::from_utf8_lossy(&line);
+            let line = line.trim();
+            let Some(data) = line.strip_prefix("data:") else {
+                continue;
+            };
+            let data = data.trim();
+            if data == DONE_MARKER {
+                self.done = true;
+            } else if !data.is_empty() {
+                payloads.push(data.to_string());
+            }
+        }
+        payloads
+    }
+}
+
+/// 流式过程中累积的工具调用
+#[derive(Debug, Clone, Default)]
+pub struct StreamedToolCall {
+    pub id: String,
+    pub call_type: String,
+    pub name: String,
+    pub arguments: String,
+}
+
+/// 累积 `choices[0].delta`，流结束后得到完整回复
+#[derive(Debug, Default)]
+pub struct StreamAccumulator {
+    pub content: String,
+    pub tool_calls: Vec<StreamedToolCall>,
+    pub finish_reason: Option<String>,
+}
+
+impl StreamAccumulator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 应用一个 data 负载，返回本次新增的文本（没有则为 None）
+    pub fn apply(&mut self, payload: &str) -> Result<Option<String>, String> {
+        let value: Value = serde_json::from_str(payload).map_err(|e| format!("解析流式数据失败: {}", e))?;
+        if let Some(message) = value.pointer("/error/message").and_then(|v| v.as_str()) {
+            return Err(message.to_string());
+        }
+
+        let Some(choice) = value.pointer("/choices/0") else {
+            return Ok(None);
+        };
+        if let Some(reason) = choice.get("finish_reason").and_then(|v| v.as_str()) {
+            self.finish_reason = Some(reason.to_string());
+        }
+        let Some(delta) = choice.get("delta") else {
+            return Ok(None);
+        };
+
+        if let Some(calls) = delta.get("tool_calls").and_then(|v| v.as_array()) {
+            for call in calls {
+                let index = call.get("index").and_then(|v| v.as_u64()).unwrap_or(0) as usize;
+                if self.tool_calls.len() <= index {
+                    self.tool_calls.resize_with(index + 1, StreamedToolCall::default);
+                }
+                let slot = &mut self.tool_calls[index];
+                if let Some(id) = call.get("id").and_then(|v| v.as_str()) {
+                    slot.id = id.to_string();
+                }
+                if let Some(call_type) = call.get("type").and_then(|v| v.as_str()) {
+                    slot.call_type = call_type.to_string();
+                }
+                if let Some(name) = call.pointer("/function/name").and_then(|v| v.as_str()) {
+                    slot.name.push_str(name);
+                }
+                if let Some(arguments) = call.pointer("/function/arguments").and_then(|v| v.as_str()) {
+                    slot.arguments.push_str(arguments);
+                }
+            }
+        }
+
+        match delta.get("content").and_then(|v| v.as_str()) {
+            Some(text) if !text.is_empty() => {
+                self.content.push_str(text);
+                Ok(Some(text.to_string()))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// 转换为与非流式接口一致的 completion 结构，调用方无需区分两种模式
+    pub fn into_completion(self) -> Value {
+        let mut message = json!({ "role": "assistant", "content": self.content });
+        if !self.tool_calls.is_empty() {
+            message["tool_calls"] = json!(self
+                .tool_calls
+                .iter()
+                .map(|tc| json!({
+                    "id": tc.id,
+                    "type": if tc.call_type.is_empty() { "function" } else { tc.call_type.as_str() },
+                    "function": { "name": tc.name, "arguments": tc.arguments },
+                }))
+                .collect::<Vec<_>>());
+        }
+        json!({
+            "choices": [{ "index": 0, "message": message, "finish_reason": self.finish_reason }],
+        })
+    }
+}
+
+/// 消费 SSE 响应：每段新增文本回调一次，结束后返回完整 completion
+pub async fn collect_stream<F: Fn(String)>(response: reqwest::Response, on_delta: F) -> anyhow::Result<Value> {
+    let mut decoder = SseDecoder::new();
+    let mut acc = StreamAccumulator::new();
+    let mut stream = response.bytes_stream();
+
+    while let Some(chunk) = stream.next().await {
+        for payload in decoder.push(&chunk?) {
+            if let Some(delta) = acc.apply(&payload).map_err(anyhow::Error::msg)? {
+                on_delta(delta);
+            }
+        }
+        if decoder.is_done() {
+            break;
+        }
+    }
+    Ok(acc.into_completion())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decodes_frames_split_across_chunks() {
+        let mut decoder = SseDecoder::new();
+        let mut acc = StreamAccumulator::new();
+        let chunks: [&[u8]; 3] = [
+            b"data: {\"choices\":[{\"delta\":{\"content\":\"\xE4\xBD\xA0\"}}]}\n\ndata: {\"choices\":[{\"delta\":{\"content\":\"\xE5",
+            b"\xA5\xBD\"},\"finish_reason\":\"stop\"}]}\n",
+            b"\ndata: [DONE]\n\n",
+        ];
+
+        let mut deltas = Vec::new();
+        for chunk in chunks {
+            for payload in decoder.push(chunk) {
+                deltas.extend(acc.apply(&payload).unwrap());
+            }
+        }
+        assert_eq!(deltas, vec!["你", "好"]);
+        assert_eq!(acc.content, "你好");
+        assert_eq!(acc.finish_reason.as_deref(), Some("stop"));
+        assert!(decoder.is_done());
+    }
+
+    #[test]
+    fn accumulates_tool_call_fragments() {
+        let mut acc = StreamAccumulator::new();
+        acc.apply(r#"{"choices":[{"delta":{"tool_calls":[{"index":0,"id":"call_1","type":"function","function":{"name":"tap","arguments":"{\"x\":"}}]}}]}"#).unwrap();
+        acc.apply(r#"{"choices":[{"delta":{"tool_calls":[{"index":0,"function":{"arguments":"1}"}}]}}]}"#).unwrap();
+        assert_eq!(acc.tool_calls.len(), 1);
+        assert_eq!(acc.tool_calls[0].name, "tap");
+        assert_eq!(acc.tool_calls[0].arguments, "{\"x\":1}");
+        assert!(acc.apply(r#"{"error":{"message":"quota"}}"#).is_err());
+    }
+}
@@ -1,4 +1,5 @@
 //! Application layer modules (kept decoupled while integrating gradually).
 pub mod normalizer;
 pub mod device_metrics;
+pub mod scroll_until_found;
 
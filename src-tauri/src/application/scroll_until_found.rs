@@ -1,13 +1,20 @@
 use anyhow::Result;
 
+use crate::services::list_tracking::ListTracker;
+
 #[allow(dead_code)]
 pub struct ScrollUntilFoundConfig {
     pub max_attempts: u32,
     pub direction: &'static str, // simple placeholder: "down" | "up"
     pub settle_ms: u64,
+    /// List container resource-id for end-of-list tracking (None = largest scrollable node)
+    pub container_id: Option<String>,
+    /// Consecutive pages without new list items before giving up
+    pub max_stale_pages: u32,
 }
 
-/// Skeleton: perform (dump → match → swipe) loop until found or attempts exhausted.
+/// Skeleton: perform (dump → match → swipe) loop until found, attempts exhausted,
+/// or list tracking reports that scrolling no longer reveals new items.
 #[allow(dead_code)]
 pub async fn scroll_until_found<FMatch, FSwipe, FDump, TMatch>(
     mut try_match: FMatch,
@@ -20,11 +27,19 @@ where
     FSwipe: FnMut() -> Result<()>,
     FDump: FnMut() -> Result<String>,
 {
+    let mut tracker = ListTracker::new(None, cfg.max_stale_pages);
     for _ in 0..cfg.max_attempts {
         let xml = dump_ui()?;
         if let Some(found) = try_match(&xml) {
             return Ok(Some(found));
         }
+        // Pages without a recognizable list container don't count towards end-of-list
+        if let Ok(page) = tracker.observe_xml(&xml, cfg.container_id.as_deref()) {
+            if page.end_reached {
+                tracing::info!("scroll_until_found: end of list after {} pages, {} items seen", page.page, tracker.progress().seen);
+                return Ok(None);
+            }
+        }
         do_swipe()?;
         tokio::time::sleep(std::time::Duration::from_millis(cfg.settle_ms)).await;
     }
@@ -42,6 +42,7 @@ impl MarketingStorageAdapter {
             publish_time: comment.publish_time.to_rfc3339(),
             region: comment.region.clone(),
             source_target_id,
+            language: None,
         };
 
         let id = repo::insert_comment(&conn, &payload)?;
@@ -74,6 +75,7 @@ impl MarketingStorageAdapter {
             dedup_key: dedup_key.unwrap_or_else(|| format!("task:{}", comment_id.0)),
             priority: None,
             deadline_at: None,
+            campaign_id: None,
         };
 
         let id = repo::insert_task(&conn, &payload)?;
@@ -4,6 +4,7 @@
 
 use anyhow::Result;
 use crate::automation::types::InlineStep;
+use crate::services::execution::timeline::{self, Phase};
 
 /// 执行智能分析生成的步骤
 /// 
@@ -27,9 +28,11 @@ pub async fn execute_step(
     
     // 2. 尝试结构化匹配
     use crate::automation::matching::structural::try_structural_matching_flow;
-    if let Some(coords) = try_structural_matching_flow(device_id, ui_xml, &merged_params).await? {
+    let match_span = timeline::span(Phase::Match, &inline.step_id);
+    if let Some(coords) = try_structural_matching_flow(device_id, ui_xml, &merged_params, &inline.step_id).await? {
         return Ok(coords);
     }
+    drop(match_span);
 
     // 3. 动作分发（无需元素匹配的动作）
     use crate::automation::pipeline::dispatcher::try_dispatch_direct_action;
@@ -41,6 +44,7 @@ pub async fn execute_step(
     // 检查批量模式
     let batch_mode = merged_params.get("selection_mode").and_then(|v| v.as_str());
     
+    let match_span = timeline::span(Phase::Match, &inline.step_id);
     let (x, y) = if batch_mode == Some("all") {
         use crate::automation::matching::legacy::try_batch_matching_flow;
         try_batch_matching_flow(device_id, ui_xml, &merged_params, &inline.step_id).await?
@@ -48,6 +52,7 @@ pub async fn execute_step(
         use crate::automation::matching::legacy::try_legacy_matching_flow;
         try_legacy_matching_flow(ui_xml, &merged_params, &inline.step_id)?
     };
+    drop(match_span);
     
     // 5. 执行动作 (Click, Input, LongPress, etc.)
     // 注意：try_batch_matching_flow 已经执行了动作，返回 (0,0)
@@ -80,7 +85,7 @@ async fn execute_matched_action(
                 .map_err(|e| e.to_string())?;
         },
         "longPress" | "long_press" => {
-            let duration = params.get("duration").and_then(|v| v.as_u64()).unwrap_or(1000) as u32;
+            let duration = params.get("duration_ms").or_else(|| params.get("duration")).and_then(|v| v.as_u64()).unwrap_or(1000) as u32;
             crate::automation::actions::tap::execute_long_press(device_id, x, y, duration).await
                 .map_err(|e| e.to_string())?;
         },
@@ -5,6 +5,11 @@
 use tauri::{AppHandle, Emitter};
 use super::types::*;
 
+/// 分析进度事件名
+pub const ANALYSIS_PROGRESS_EVENT: &str = "analysis:progress";
+/// 分析完成事件名
+pub const ANALYSIS_COMPLETE_EVENT: &str = "analysis:complete";
+
 /// 发射进度事件
 pub fn emit_progress(
     app: &AppHandle,
@@ -24,7 +29,7 @@ pub fn emit_progress(
         meta,
     };
     
-    app.emit("analysis:progress", &event)
+    app.emit(ANALYSIS_PROGRESS_EVENT, &event)
         .map_err(|e| format!("发射 progress 事件失败: {}", e))
 }
 
@@ -49,7 +54,7 @@ pub fn emit_complete(
         result,
     };
     
-    let emit_result = app.emit("analysis:complete", &event);
+    let emit_result = app.emit(ANALYSIS_COMPLETE_EVENT, &event);
     
     match &emit_result {
         Ok(_) => tracing::info!("✅ [事件] analysis:complete 事件发射成功 - analysis_id={:?}", analysis_id),
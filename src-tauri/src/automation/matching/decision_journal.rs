@@ -0,0 +1,336 @@
+// src-tauri/src/automation/matching/decision_journal.rs
+// module: automation | layer: matching | role: 匹配决策回溯日志
+// summary: 保存最近 N 次步骤执行中每个候选的完整评分明细，事后解释"为什么选中了这个节点"
+
+use std::collections::VecDeque;
+use std::sync::Mutex;
+
+use serde::Serialize;
+
+use crate::services::universal_ui_page_analyzer::UIElement;
+
+/// 最多保留的决策记录数（按执行顺序淘汰最旧的记录）
+pub const MAX_RECORDED_DECISIONS: usize = 50;
+
+lazy_static::lazy_static! {
+    /// 全局决策日志（环形缓冲）
+    static ref DECISION_JOURNAL: Mutex<VecDeque<MatchDecision>> =
+        Mutex::new(VecDeque::with_capacity(MAX_RECORDED_DECISIONS));
+}
+
+/// 评分项类别
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ComponentCategory {
+    /// 字段对比（resource-id / text / bounds ...）
+    Field,
+    /// 结构性奖励
+    Bonus,
+    /// 惩罚项
+    Penalty,
+    /// 说明信息（不影响分数）
+    Info,
+}
+
+/// 单个评分项
+#[derive(Debug, Clone, Serialize)]
+pub struct ScoreComponent {
+    pub category: ComponentCategory,
+    pub field: String,
+    pub delta: f32,
+    pub detail: String,
+}
+
+impl ScoreComponent {
+    pub fn new(category: ComponentCategory, field: &str, delta: f32, detail: impl Into<String>) -> Self {
+        Self {
+            category,
+            field: field.to_string(),
+            delta,
+            detail: detail.into(),
+        }
+    }
+}
+
+/// 评分轨迹：评分过程中逐项记录，与上一次记录之间的分数变化即为该项贡献
+#[derive(Debug, Clone, Default)]
+pub struct ScoreTrail {
+    components: Vec<ScoreComponent>,
+    last_score: f32,
+}
+
+impl ScoreTrail {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn field(&mut self, field: &str, score: f32, detail: impl Into<String>) {
+        self.push(ComponentCategory::Field, field, score, detail);
+    }
+
+    pub fn bonus(&mut self, field: &str, score: f32, detail: impl Into<String>) {
+        self.push(ComponentCategory::Bonus, field, score, detail);
+    }
+
+    pub fn penalty(&mut self, field: &str, score: f32, detail: impl Into<String>) {
+        self.push(ComponentCategory::Penalty, field, score, detail);
+    }
+
+    pub fn info(&mut self, field: &str, score: f32, detail: impl Into<String>) {
+        self.push(ComponentCategory::Info, field, score, detail);
+    }
+
+    fn push(&mut self, category: ComponentCategory, field: &str, score: f32, detail: impl Into<String>) {
+        let delta = score - self.last_score;
+        self.last_score = score;
+        self.components.push(ScoreComponent::new(category, field, delta, detail));
+    }
+
+    /// 兼容旧的文字原因列表
+    pub fn reasons(&self) -> Vec<String> {
+        self.components.iter().map(|c| c.detail.clone()).collect()
+    }
+
+    pub fn into_components(self) -> Vec<ScoreComponent> {
+        self.components
+    }
+}
+
+/// 单个候选的评分记录
+#[derive(Debug, Clone, Serialize)]
+pub struct CandidateRecord {
+    /// 评分排名（从 1 开始）
+    pub rank: usize,
+    pub score: f32,
+    pub text: String,
+    pub content_desc: String,
+    pub resource_id: Option<String>,
+    pub class_name: Option<String>,
+    pub bounds: String,
+    pub components: Vec<ScoreComponent>,
+}
+
+impl CandidateRecord {
+    pub fn from_element(rank: usize, element: &UIElement, score: f32, components: Vec<ScoreComponent>) -> Self {
+        Self {
+            rank,
+            score,
+            text: element.text.clone(),
+            content_desc: element.content_desc.clone(),
+            resource_id: element.resource_id.clone(),
+            class_name: element.class_name.clone(),
+            bounds: element.bounds.to_string(),
+            components,
+        }
+    }
+
+    /// 仅有边界与分数的候选（结构匹配返回的节点不带属性）
+    pub fn from_bounds(rank: usize, bounds: String, score: f32, components: Vec<ScoreComponent>) -> Self {
+        Self {
+            rank,
+            score,
+            text: String::new(),
+            content_desc: String::new(),
+            resource_id: None,
+            class_name: None,
+            bounds,
+            components,
+        }
+    }
+}
+
+/// 一次匹配决策
+#[derive(Debug, Clone, Serialize)]
+pub struct MatchDecision {
+    pub step_id: String,
+    /// 记录时间（毫秒时间戳）
+    pub recorded_at: i64,
+    /// 候选按评分降序排列
+    pub candidates: Vec<CandidateRecord>,
+    /// 最终选中的候选在 `candidates` 中的下标
+    pub selected: Option<usize>,
+    /// 决策附注（如 prefer_last 决胜、低分拒绝）
+    pub notes: Vec<String>,
+}
+
+impl MatchDecision {
+    pub fn new(step_id: &str, candidates: Vec<CandidateRecord>, selected: Option<usize>) -> Self {
+        Self {
+            step_id: step_id.to_string(),
+            recorded_at: chrono::Utc::now().timestamp_millis(),
+            candidates,
+            selected,
+            notes: Vec::new(),
+        }
+    }
+
+    pub fn with_note(mut self, note: impl Into<String>) -> Self {
+        self.notes.push(note.into());
+        self
+    }
+}
+
+/// 解释树节点
+#[derive(Debug, Clone, Serialize)]
+pub struct ExplanationNode {
+    pub label: String,
+    pub score: Option<f32>,
+    pub children: Vec<ExplanationNode>,
+}
+
+impl ExplanationNode {
+    fn leaf(label: String, score: Option<f32>) -> Self {
+        Self { label, score, children: Vec::new() }
+    }
+}
+
+/// 记录一次匹配决策
+pub fn record_decision(decision: MatchDecision) {
+    let Ok(mut journal) = DECISION_JOURNAL.lock() else {
+        tracing::warn!("⚠️ 匹配决策日志锁失败，跳过记录: step_id={}", decision.step_id);
+        return;
+    };
+    if journal.len() >= MAX_RECORDED_DECISIONS {
+        journal.pop_front();
+    }
+    journal.push_back(decision);
+}
+
+/// 获取某个步骤最近一次的匹配决策
+pub fn last_decision(step_id: &str) -> Option<MatchDecision> {
+    let journal = DECISION_JOURNAL.lock().ok()?;
+    journal.iter().rev().find(|d| d.step_id == step_id).cloned()
+}
+
+/// 为某个步骤最近一次的匹配决策生成解释树
+pub fn explain_last_match(step_id: &str) -> Option<ExplanationNode> {
+    last_decision(step_id).map(|d| build_explanation(&d))
+}
+
+/// 决策 → 解释树：决策 / 候选 / 字段 / 评分项 四层
+pub fn build_explanation(decision: &MatchDecision) -> ExplanationNode {
+    let label = match decision.selected.and_then(|i| decision.candidates.get(i)) {
+        Some(chosen) => format!(
+            "步骤 {} 从 {} 个候选中选中 #{} (text={:?}, bounds={})",
+            decision.step_id,
+            decision.candidates.len(),
+            chosen.rank,
+            chosen.text,
+            chosen.bounds
+        ),
+        None => format!("步骤 {} 未选中任何候选（共 {} 个）", decision.step_id, decision.candidates.len()),
+    };
+
+    let mut children: Vec<ExplanationNode> = decision
+        .notes
+        .iter()
+        .map(|note| ExplanationNode::leaf(format!("📝 {}", note), None))
+        .collect();
+
+    for (index, candidate) in decision.candidates.iter().enumerate() {
+        let marker = if decision.selected == Some(index) { "✅ 选中" } else { "候选" };
+        children.push(ExplanationNode {
+            label: format!(
+                "{} #{} text={:?} desc={:?} id={:?} bounds={}",
+                marker, candidate.rank, candidate.text, candidate.content_desc, candidate.resource_id, candidate.bounds
+            ),
+            score: Some(candidate.score),
+            children: group_by_field(&candidate.components),
+        });
+    }
+
+    let score = decision.selected.and_then(|i| decision.candidates.get(i)).map(|c| c.score);
+    ExplanationNode { label, score, children }
+}
+
+/// 按字段聚合评分项，保持首次出现的顺序
+fn group_by_field(components: &[ScoreComponent]) -> Vec<ExplanationNode> {
+    let mut groups: Vec<ExplanationNode> = Vec::new();
+    for component in components {
+        let leaf = ExplanationNode::leaf(
+            format!("[{:?}] {}", component.category, component.detail),
+            Some(component.delta),
+        );
+        match groups.iter_mut().find(|g| g.label == component.field) {
+            Some(group) => {
+                group.score = Some(group.score.unwrap_or(0.0) + component.delta);
+                group.children.push(leaf);
+            }
+            None => groups.push(ExplanationNode {
+                label: component.field.clone(),
+                score: Some(component.delta),
+                children: vec![leaf],
+            }),
+        }
+    }
+    groups
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candidate(rank: usize, score: f32, components: Vec<ScoreComponent>) -> CandidateRecord {
+        CandidateRecord {
+            rank,
+            score,
+            text: format!("候选{}", rank),
+            content_desc: String::new(),
+            resource_id: None,
+            class_name: None,
+            bounds: "[0,0][10,10]".to_string(),
+            components,
+        }
+    }
+
+    #[test]
+    fn trail_attributes_score_deltas_to_each_component() {
+        let mut trail = ScoreTrail::new();
+        let mut score = 0.0;
+        score += 0.7;
+        trail.field("bounds", score, "bounds 完全匹配");
+        trail.info("bounds", score, "说明");
+        score -= 2.0;
+        trail.penalty("child_text", score, "反义词");
+
+        let deltas: Vec<f32> = trail.clone().into_components().iter().map(|c| c.delta).collect();
+        assert_eq!(deltas, vec![0.7, 0.0, -2.0]);
+        assert_eq!(trail.reasons().len(), 3);
+    }
+
+    #[test]
+    fn explanation_groups_components_per_field_and_marks_selection() {
+        let decision = MatchDecision::new(
+            "journal_test_step",
+            vec![
+                candidate(1, 1.2, vec![
+                    ScoreComponent::new(ComponentCategory::Field, "text", 0.5, "文本匹配"),
+                    ScoreComponent::new(ComponentCategory::Field, "text", 0.2, "子文本"),
+                    ScoreComponent::new(ComponentCategory::Bonus, "position", 0.05, "最后一个"),
+                ]),
+                candidate(2, 0.4, vec![]),
+            ],
+            Some(0),
+        )
+        .with_note("prefer_last 决胜");
+
+        let tree = build_explanation(&decision);
+        assert_eq!(tree.score, Some(1.2));
+        assert_eq!(tree.children.len(), 3);
+        let chosen = &tree.children[1];
+        assert!(chosen.label.starts_with("✅ 选中 #1"));
+        assert_eq!(chosen.children.len(), 2);
+        assert!((chosen.children[0].score.unwrap() - 0.7).abs() < 1e-6);
+    }
+
+    #[test]
+    fn journal_keeps_latest_decision_per_step_and_is_bounded() {
+        for i in 0..MAX_RECORDED_DECISIONS + 5 {
+            record_decision(MatchDecision::new("journal_bounded_step", vec![candidate(1, i as f32, vec![])], Some(0)));
+        }
+        let last = last_decision("journal_bounded_step").unwrap();
+        assert_eq!(last.candidates[0].score, (MAX_RECORDED_DECISIONS + 4) as f32);
+        assert!(DECISION_JOURNAL.lock().unwrap().len() <= MAX_RECORDED_DECISIONS);
+        assert!(explain_last_match("journal_missing_step").is_none());
+    }
+}
@@ -6,6 +6,9 @@ use serde_json::Value;
 use crate::automation::matching::strategy::{collect_candidate_elements, evaluate_best_candidate};
 use crate::automation::matching::recovery::attempt_element_recovery;
 use crate::automation::matching::utils::{ensure_clickable_element, calculate_center};
+use crate::automation::matching::decision_journal::{record_decision, CandidateRecord, MatchDecision};
+use crate::commands::run_step_v2::{StaticEvidence, UnifiedScoringCore};
+use crate::services::universal_ui_page_analyzer::UIElement;
 
 /// 尝试执行传统匹配流程
 /// 
@@ -95,6 +98,7 @@ pub fn try_legacy_matching_flow(
         .and_then(|v| v.as_str());
 
     let mut target_element_option = evaluate_best_candidate(
+        step_id,
         candidate_elements,
         merged_params,
         ui_xml,
@@ -168,6 +172,7 @@ pub async fn try_batch_matching_flow(
         merged_params
     );
     
+    record_decision(decision_for_batch(step_id, &candidate_elements, merged_params));
     if candidate_elements.is_empty() {
         return Err(format!("批量模式未找到任何匹配元素: xpath={}", xpath));
     }
@@ -178,6 +183,25 @@ pub async fn try_batch_matching_flow(
         .map_err(|e| e.to_string())
 }
 
+/// 批量模式的决策记录：不择优，但仍按三态证据给每个候选打分，便于事后解释
+fn decision_for_batch(step_id: &str, candidates: &[&UIElement], params: &Value) -> MatchDecision {
+    let evidence = StaticEvidence::from_step_params(params);
+    let records = candidates
+        .iter()
+        .enumerate()
+        .map(|(i, elem)| {
+            let components = evidence
+                .as_ref()
+                .map(|ev| UnifiedScoringCore::calculate_tristate_breakdown(ev, elem))
+                .unwrap_or_default();
+            let score: f32 = components.iter().map(|c| c.delta).sum();
+            CandidateRecord::from_element(i + 1, elem, score, components)
+        })
+        .collect();
+    MatchDecision::new(step_id, records, None)
+        .with_note(format!("批量模式：全部 {} 个候选按收集顺序执行，不做评分择优", candidates.len()))
+}
+
 /// 提取目标文本（支持多层嵌套）
 fn extract_target_text_from_params(params: &Value) -> String {
     params.get("smartSelection")
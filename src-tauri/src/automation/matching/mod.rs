@@ -8,9 +8,10 @@ pub mod recovery;
 pub mod utils;
 pub mod evaluator;
 pub mod element_matching;
+pub mod decision_journal;
 
 // Re-export common types
-pub use scorer::{MatchCandidate, MultiCandidateEvaluator, EvaluationCriteria};
+pub use scorer::{MatchCandidate, MultiCandidateEvaluator, EvaluationCriteria, RankedCandidates};
 pub use strategy::{collect_candidate_elements, evaluate_best_candidate};
 pub use recovery::{attempt_element_recovery, RecoveryResult, RecoveryContext};
 pub use utils::{ensure_clickable_element, calculate_center};
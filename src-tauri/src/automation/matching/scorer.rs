@@ -5,6 +5,8 @@
 use crate::services::universal_ui_page_analyzer::UIElement;
 use crate::exec::element_matching::text_comparator::TextComparator;
 use crate::exec::semantic_analyzer::SemanticAnalyzer;
+use crate::automation::matching::decision_journal::{ComponentCategory, ScoreComponent, ScoreTrail};
+use crate::commands::run_step_v2::{matching_config, StaticEvidence, UnifiedScoringCore};
 
 /// 匹配候选
 #[derive(Debug, Clone)]
@@ -13,6 +15,24 @@ pub struct MatchCandidate<'a> {
     pub xpath: String,
     pub score: f32,
     pub reasons: Vec<String>,
+    /// 结构化评分明细（与 reasons 一一对应）
+    pub components: Vec<ScoreComponent>,
+}
+
+/// 完整评估结果：所有候选按评分降序排列 + 最终选择
+#[derive(Debug, Clone)]
+pub struct RankedCandidates<'a> {
+    pub ranked: Vec<MatchCandidate<'a>>,
+    /// 最终选中的候选在 `ranked` 中的下标
+    pub selected: Option<usize>,
+    /// prefer_last 决胜说明
+    pub tie_break: Option<String>,
+}
+
+impl<'a> RankedCandidates<'a> {
+    pub fn best(&self) -> Option<&MatchCandidate<'a>> {
+        self.selected.and_then(|i| self.ranked.get(i))
+    }
 }
 
 /// 父元素信息（用于安全模式检测）
@@ -53,6 +73,8 @@ pub struct EvaluationCriteria {
     pub xml_content: Option<String>,
     /// 🆕 语义分析器（可选，用于配置化的反义词检测）
     pub semantic_analyzer: Option<SemanticAnalyzer>,
+    /// 🆕 静态证据包（有 original_data 时参与三态评分，权重取自匹配配置）
+    pub static_evidence: Option<StaticEvidence>,
 }
 
 /// 多候选评估器
@@ -97,19 +119,35 @@ impl MultiCandidateEvaluator {
         candidates: Vec<&'a UIElement>,
         criteria: &EvaluationCriteria,
     ) -> Option<MatchCandidate<'a>> {
+        let mut result = Self::evaluate_candidates_ranked(candidates, criteria);
+        result.selected.map(|i| result.ranked.swap_remove(i))
+    }
+
+    /// 与 [`Self::evaluate_candidates`] 相同的评估逻辑，但保留全部候选的评分明细
+    pub fn evaluate_candidates_ranked<'a>(
+        candidates: Vec<&'a UIElement>,
+        criteria: &EvaluationCriteria,
+    ) -> RankedCandidates<'a> {
         if candidates.is_empty() {
-            return None;
+            return RankedCandidates { ranked: Vec::new(), selected: None, tie_break: None };
         }
         
         // 单个候选直接返回
         if candidates.len() == 1 {
             tracing::info!("🎯 [候选评估] 只有1个候选，直接选择");
-            return Some(MatchCandidate {
-                element: candidates[0],
-                xpath: "".to_string(),
-                score: 1.0,
-                reasons: vec!["唯一候选".to_string()],
-            });
+            let mut trail = ScoreTrail::new();
+            trail.info("candidate", 1.0, "唯一候选");
+            return RankedCandidates {
+                ranked: vec![MatchCandidate {
+                    element: candidates[0],
+                    xpath: "".to_string(),
+                    score: 1.0,
+                    reasons: trail.reasons(),
+                    components: trail.into_components(),
+                }],
+                selected: Some(0),
+                tie_break: None,
+            };
         }
         
         tracing::warn!(
@@ -121,12 +159,13 @@ impl MultiCandidateEvaluator {
         let mut scored_candidates: Vec<MatchCandidate> = candidates.iter()
             .enumerate()
             .map(|(index, elem)| {
-                let (score, reasons) = Self::score_candidate(elem, criteria, index, candidates.len());
+                let (score, trail) = Self::score_candidate(elem, criteria, index, candidates.len());
                 MatchCandidate {
                     element: elem,
                     xpath: "".to_string(),
                     score,
-                    reasons,
+                    reasons: trail.reasons(),
+                    components: trail.into_components(),
                 }
             })
             .collect();
@@ -177,12 +216,17 @@ impl MultiCandidateEvaluator {
                 if let Some(pos) = scored_candidates.iter().position(|c| {
                     candidates.iter().position(|e| std::ptr::eq(*e, c.element)).unwrap_or(0) == last_close_index
                 }) {
-                    return scored_candidates.into_iter().nth(pos);
+                    let tie_break = format!(
+                        "前{}名评分接近（差距<0.05），根据prefer_last选择原列表第{}个",
+                        close_candidates.len(),
+                        last_close_index + 1
+                    );
+                    return RankedCandidates { ranked: scored_candidates, selected: Some(pos), tie_break: Some(tie_break) };
                 }
             }
         }
         
-        scored_candidates.into_iter().next()
+        RankedCandidates { ranked: scored_candidates, selected: Some(0), tie_break: None }
     }
     
     /// 对单个候选元素进行评分
@@ -191,9 +235,9 @@ impl MultiCandidateEvaluator {
         criteria: &EvaluationCriteria,
         index: usize,
         total: usize,
-    ) -> (f32, Vec<String>) {
+    ) -> (f32, ScoreTrail) {
         let mut score = 0.0f32;
-        let mut reasons = Vec::new();
+        let mut trail = ScoreTrail::new();
         
         // 🔥🔥🔥🔥🔥 XPath安全模式检测：防止无文本锚点时乱点
         // 检查是否应该使用XPath安全模式（无子/兄弟/父元素文本时）
@@ -205,7 +249,7 @@ impl MultiCandidateEvaluator {
         );
         
         if should_use_xpath_mode {
-            reasons.push("⚠️ [安全模式] 无文本锚点，启用Bounds严格匹配（防止乱点）".to_string());
+            trail.info("safety_mode", score, "⚠️ [安全模式] 无文本锚点，启用Bounds严格匹配（防止乱点）".to_string());
             
             // 🔥 Bounds严格匹配（XPath安全模式下的主要策略）
             if let Some(ref original_bounds) = &criteria.original_bounds {
@@ -216,12 +260,12 @@ impl MultiCandidateEvaluator {
                 if bounds_match.is_exact {
                     // Bounds完全匹配，高分
                     score += 3.0;
-                    reasons.push(format!("✅✅✅✅✅ Bounds完全匹配 (+3.0, 安全模式)"));
+                    trail.field("bounds", score, format!("✅✅✅✅✅ Bounds完全匹配 (+3.0, 安全模式)"));
                 } else if bounds_match.match_quality > 0.9 {
                     // Bounds高度相似（IOU > 0.9 或包含关系），中高分
                     let bounds_score = 2.5 * bounds_match.match_quality;
                     score += bounds_score;
-                    reasons.push(format!(
+                    trail.field("bounds", score, format!(
                         "✅✅✅✅ Bounds高质量匹配: quality={:.2}, IOU={:.2} (+{:.2}, 安全模式)",
                         bounds_match.match_quality, bounds_match.iou, bounds_score
                     ));
@@ -229,7 +273,7 @@ impl MultiCandidateEvaluator {
                     // Bounds中高相似度
                     let bounds_score = 2.0 * bounds_match.match_quality;
                     score += bounds_score;
-                    reasons.push(format!(
+                    trail.field("bounds", score, format!(
                         "✅✅✅ Bounds中高匹配: quality={:.2}, IOU={:.2} (+{:.2}, 安全模式)",
                         bounds_match.match_quality, bounds_match.iou, bounds_score
                     ));
@@ -237,7 +281,7 @@ impl MultiCandidateEvaluator {
                     // Bounds中等相似度
                     let bounds_score = 1.5 * bounds_match.match_quality;
                     score += bounds_score;
-                    reasons.push(format!(
+                    trail.field("bounds", score, format!(
                         "🟡🟡 Bounds中等匹配: quality={:.2}, IOU={:.2} (+{:.2}, 安全模式)",
                         bounds_match.match_quality, bounds_match.iou, bounds_score
                     ));
@@ -245,7 +289,7 @@ impl MultiCandidateEvaluator {
                     // Bounds相似度太低
                     let bounds_score = 0.5 * bounds_match.match_quality;
                     score += bounds_score;
-                    reasons.push(format!(
+                    trail.field("bounds", score, format!(
                         "⚠️ Bounds低相似度: quality={:.2}, IOU={:.2} (+{:.2}, 可能不是目标元素)",
                         bounds_match.match_quality, bounds_match.iou, bounds_score
                     ));
@@ -256,11 +300,11 @@ impl MultiCandidateEvaluator {
                     (&criteria.original_resource_id, &elem.resource_id) {
                     if !target_resource_id.is_empty() && target_resource_id == elem_resource_id {
                         score += 0.5;
-                        reasons.push(format!("✅ Resource-id匹配 (+0.5, 安全模式加成)"));
+                        trail.field("resource_id", score, format!("✅ Resource-id匹配 (+0.5, 安全模式加成)"));
                     } else if !target_resource_id.is_empty() && target_resource_id != elem_resource_id {
                         // Resource-id不匹配，严重扣分
                         score -= 0.5;
-                        reasons.push(format!(
+                        trail.field("resource_id", score, format!(
                             "⚠️ Resource-id不匹配: '{}' vs '{}' (-0.5, 安全模式惩罚)",
                             elem_resource_id,
                             target_resource_id
@@ -269,16 +313,16 @@ impl MultiCandidateEvaluator {
                 }
                 
                 // 安全模式总结
-                reasons.push(format!("🔒 [安全模式总结] 基于Bounds严格匹配，总分: {:.2}", score));
+                trail.info("safety_mode", score, format!("🔒 [安全模式总结] 基于Bounds严格匹配，总分: {:.2}", score));
                 
             } else {
                 // 没有Bounds信息，无法安全匹配
-                reasons.push("❌ [安全模式失败] 缺少Bounds信息，无法安全匹配".to_string());
-                return (0.0, reasons); // 返回0分，防止乱点
+                trail.info("safety_mode", score, "❌ [安全模式失败] 缺少Bounds信息，无法安全匹配".to_string());
+                return (0.0, trail); // 返回0分，防止乱点
             }
             
             // 提前返回，不再评估其他项（防止乱点）
-            return (score, reasons);
+            return (score, trail);
         }
         
         // 🔥🔥🔥🔥 评分项0: Bounds完全匹配（0-0.7分）用户精确选择，次高优先级
@@ -291,7 +335,7 @@ impl MultiCandidateEvaluator {
             
             if orig_normalized == elem_normalized {
                 score += 0.7;  // ✅ 提升到0.7 - 用户精确选择
-                reasons.push(format!("✅✅✅✅ Bounds完全匹配: '{}' (用户精确选择!)", elem_bounds_str));
+                trail.field("bounds", score, format!("✅✅✅✅ Bounds完全匹配: '{}' (用户精确选择!)", elem_bounds_str));
             }
         }
         
@@ -310,27 +354,27 @@ impl MultiCandidateEvaluator {
                 // 🚨 特殊处理：如果检测到语义相反，严重降分
                 if matches!(child_text_match.match_source, MatchSource::SemanticOpposite) {
                     score -= 2.0; // 严重降分，确保反义词不会被选中
-                    reasons.push(format!(
+                    trail.penalty("child_text", score, format!(
                         "🚨🚨🚨 检测到语义相反状态: 目标='{}' vs 候选='{}' (-2.0, 反义词惩罚)",
                         target_text,
                         child_text_match.matched_text.unwrap_or_default()
                     ));
                 } else if child_text_match.is_complete {
                     score += 1.0;  // ✅ 提升到1.0 - Android核心UI模式，最高优先级！
-                    reasons.push(format!(
+                    trail.field("child_text", score, format!(
                         "✅✅✅✅✅✅ 子元素文本完全匹配: '{}' (父容器+子文本模式 - Android核心架构, 来源: {:?})",
                         target_text,
                         child_text_match.match_source
                     ));
                 } else if child_text_match.is_partial {
                     score += 0.5;  // ✅ 提升到0.5
-                    reasons.push(format!(
+                    trail.field("child_text", score, format!(
                         "🟡🟡🟡 子元素文本部分匹配: '{}' (来源: {:?})",
                         target_text,
                         child_text_match.match_source
                     ));
                 } else {
-                    reasons.push(format!("⚠️ 子元素中未找到目标文本: '{}'", target_text));
+                    trail.field("child_text", score, format!("⚠️ 子元素中未找到目标文本: '{}'", target_text));
                 }
             }
         }
@@ -359,15 +403,15 @@ impl MultiCandidateEvaluator {
                     let text_score = TextComparator::calculate_similarity(target_text, elem_text);
                     if text_score >= 0.95 {
                         score += 0.5;
-                        reasons.push(format!("✅✅✅ 自身文本完全匹配: '{}'", elem_text));
+                        trail.field("text", score, format!("✅✅✅ 自身文本完全匹配: '{}'", elem_text));
                     } else if text_score >= 0.3 {
                         // 文本部分匹配，但有content-desc保底，给予少量加分
                         let partial_score = 0.2 * text_score;
                         score += partial_score;
-                        reasons.push(format!("🟡 自身文本部分匹配: '{}' (相似度: {:.2}, 有content-desc保底)", elem_text, text_score));
+                        trail.field("text", score, format!("🟡 自身文本部分匹配: '{}' (相似度: {:.2}, 有content-desc保底)", elem_text, text_score));
                     } else {
                         // 文本不匹配，但content-desc匹配，这是正常情况（如"我" vs "我，按钮"）
-                        reasons.push(format!("ℹ️ 自身text='{}' 不同于target='{}', 但content-desc已匹配（父容器+子文本模式）", elem_text, target_text));
+                        trail.field("text", score, format!("ℹ️ 自身text='{}' 不同于target='{}', 但content-desc已匹配（父容器+子文本模式）", elem_text, target_text));
                     }
                 } else {
                     // 没有content-desc匹配，正常进行语义检查
@@ -379,7 +423,7 @@ impl MultiCandidateEvaluator {
 
                     if !should_match {
                         score += score_adjustment; // 通常是负分
-                        reasons.push(format!(
+                        trail.penalty("text", score, format!(
                             "🚨🚨🚨 自身文本语义检查: 目标='{}' vs 元素='{}' ({:.1}分, {})",
                             target_text, elem_text, score_adjustment, reason
                         ));
@@ -388,18 +432,18 @@ impl MultiCandidateEvaluator {
                         
                         if text_score >= 0.95 {
                             score += 0.5;  // ✅ 提升到0.5
-                            reasons.push(format!("✅✅✅ 自身文本完全匹配: '{}'", elem_text));
+                            trail.field("text", score, format!("✅✅✅ 自身文本完全匹配: '{}'", elem_text));
                         } else if text_score >= 0.7 {
                             let partial_score = 0.5 * text_score;  // ✅ 基于0.5计算
                             score += partial_score;
-                            reasons.push(format!("🟡🟡 自身文本部分匹配: '{}' (相似度: {:.2})", elem_text, text_score));
+                            trail.field("text", score, format!("🟡🟡 自身文本部分匹配: '{}' (相似度: {:.2})", elem_text, text_score));
                         } else {
-                            reasons.push(format!("❌ 自身文本不匹配: '{}' vs '{}'", elem_text, target_text));
+                            trail.field("text", score, format!("❌ 自身文本不匹配: '{}' vs '{}'", elem_text, target_text));
                         }
                     }
                 }
             } else {
-                reasons.push("⚠️ 元素无text属性".to_string());
+                trail.field("text", score, "⚠️ 元素无text属性".to_string());
             }
         }
         
@@ -408,12 +452,12 @@ impl MultiCandidateEvaluator {
             if !elem.content_desc.is_empty() {
                 if elem_desc == target_desc {
                     score += 0.3;  // ✅ 提升到0.3
-                    reasons.push(format!("✅✅ Content-desc完全匹配: '{}'", elem_desc));
+                    trail.field("content_desc", score, format!("✅✅ Content-desc完全匹配: '{}'", elem_desc));
                 } else if elem_desc.contains(target_desc) || target_desc.contains(elem_desc) {
                     score += 0.15;  // ✅ 提升到0.15
-                    reasons.push(format!("🟡 Content-desc部分匹配: '{}'", elem_desc));
+                    trail.field("content_desc", score, format!("🟡 Content-desc部分匹配: '{}'", elem_desc));
                 } else {
-                    reasons.push(format!("❌ Content-desc不匹配: '{}' vs '{}'", elem_desc, target_desc));
+                    trail.field("content_desc", score, format!("❌ Content-desc不匹配: '{}' vs '{}'", elem_desc, target_desc));
                 }
             }
         }
@@ -422,9 +466,9 @@ impl MultiCandidateEvaluator {
         let is_clickable = elem.clickable; {
             if is_clickable {
                 score += 0.15;  // ✅ 提升到0.15 - 可点击性非常重要
-                reasons.push("✅ 元素可点击 (+0.15)".to_string());
+                trail.field("clickable", score, "✅ 元素可点击 (+0.15)".to_string());
             } else {
-                reasons.push("⚠️ 元素不可点击 (0.0)".to_string());
+                trail.field("clickable", score, "⚠️ 元素不可点击 (0.0)".to_string());
             }
         }
         
@@ -434,22 +478,57 @@ impl MultiCandidateEvaluator {
                 if let Some(ref elem_resource_id) = elem.resource_id {
                     if elem_resource_id == target_resource_id {
                         score += 0.1;  // ✅ 提升到0.1
-                        reasons.push(format!("✅ Resource-id完全匹配: '{}'", elem_resource_id));
+                        trail.field("resource_id", score, format!("✅ Resource-id完全匹配: '{}'", elem_resource_id));
                     } else {
-                        reasons.push(format!("❌ Resource-id不匹配: '{}' vs '{}'", elem_resource_id, target_resource_id));
+                        trail.field("resource_id", score, format!("❌ Resource-id不匹配: '{}' vs '{}'", elem_resource_id, target_resource_id));
                     }
                 }
             }
         }
         
         
-        // 📍 评分项6: 位置偏好（最后一个候选 +0.05，仅作为决胜因素）
+        // 🧬 评分项6: 三态证据对比（权重取自 matching_config，可热重载）
+        if let Some(evidence) = &criteria.static_evidence {
+            score = Self::apply_tristate_evidence(evidence, elem, score, &mut trail);
+        }
+        
+        // 📍 评分项7: 位置偏好（最后一个候选 +0.05，仅作为决胜因素）
         if criteria.prefer_last && index == total - 1 {
             score += 0.05;
-            reasons.push("🎯 位置偏好: 最后一个候选 (+0.05)".to_string());
+            trail.bonus("position", score, "🎯 位置偏好: 最后一个候选 (+0.05)".to_string());
         }
         
-        (score, reasons)
+        (score, trail)
+    }
+    
+    /// 三态证据评分：逐项累加 [`UnifiedScoringCore`] 的评分明细
+    /// 
+    /// 各项按"五个字段全部匹配"的权重之和归一化，满分证据贡献 +1.0，与上面各评分项同量级
+    fn apply_tristate_evidence(
+        evidence: &StaticEvidence,
+        elem: &UIElement,
+        mut score: f32,
+        trail: &mut ScoreTrail,
+    ) -> f32 {
+        let config = matching_config::current();
+        let w = &config.weights;
+        let full = w.resource_id.matched + w.xpath.matched + w.text.matched + w.content_desc.matched + w.class_name.matched;
+        if full <= 0.0 {
+            return score;
+        }
+        
+        for component in UnifiedScoringCore::calculate_tristate_breakdown_with(&config, evidence, elem) {
+            score += component.delta / full;
+            let field = format!("tristate.{}", component.field);
+            let detail = format!("🧬 [三态] {}", component.detail);
+            match component.category {
+                ComponentCategory::Field => trail.field(&field, score, detail),
+                ComponentCategory::Bonus => trail.bonus(&field, score, detail),
+                ComponentCategory::Penalty => trail.penalty(&field, score, detail),
+                ComponentCategory::Info => trail.info(&field, score, detail),
+            }
+        }
+        score
     }
     
     /// 🔥 检查子元素文本匹配（核心架构特征 - 增强版）
@@ -982,6 +1061,7 @@ mod tests {
             selected_xpath: None,
             xml_content: None,
             semantic_analyzer: None,
+            static_evidence: None,
         };
         
         let result = MultiCandidateEvaluator::evaluate_candidates(candidates, &criteria);
@@ -1015,6 +1095,7 @@ mod tests {
             selected_xpath: None,
             xml_content,
             semantic_analyzer: None,
+            static_evidence: None,
         };
         
         let result = MultiCandidateEvaluator::evaluate_candidates(candidates, &criteria);
@@ -1029,4 +1110,51 @@ mod tests {
         assert!(reasons_str.contains("子元素文本") || reasons_str.contains("content-desc"), 
                 "评分原因应该包含子元素文本匹配的说明, 实际: {:?}", match_result.reasons);
     }
+    
+    #[test]
+    fn test_tristate_evidence_ranks_by_configured_weights() {
+        // 两个候选文本完全相同，仅 resource-id 不同：排序只能由三态证据决定
+        let mut other = create_test_element(Some("关注"), Some("[0,0][100,100]"), None, true);
+        other.resource_id = Some("app:id/other".to_string());
+        let mut follow = create_test_element(Some("关注"), Some("[0,200][100,300]"), None, true);
+        follow.resource_id = Some("app:id/follow".to_string());
+        
+        let criteria = EvaluationCriteria {
+            target_text: Some("关注".to_string()),
+            target_content_desc: None,
+            original_bounds: None,
+            original_resource_id: None,
+            children_texts: vec![],
+            sibling_texts: vec!["推荐".to_string()],
+            parent_info: None,
+            matching_strategy: None,
+            prefer_last: false,
+            selected_xpath: None,
+            xml_content: None,
+            semantic_analyzer: None,
+            static_evidence: Some(StaticEvidence {
+                resource_id: Some("app:id/follow".to_string()),
+                xpath: None,
+                text: Some(vec!["关注".to_string()]),
+                content_desc: None,
+                class_name: None,
+                container_scoped: false,
+                parent_clickable: false,
+                local_index: None,
+                global_index: None,
+                has_light_checks: false,
+            }),
+        };
+        
+        let ranked = MultiCandidateEvaluator::evaluate_candidates_ranked(vec![&other, &follow], &criteria);
+        let best = ranked.best().expect("应选中一个候选");
+        assert_eq!(best.element.resource_id.as_deref(), Some("app:id/follow"));
+        
+        // 记录的评分明细之和等于实际得分
+        for candidate in &ranked.ranked {
+            let total: f32 = candidate.components.iter().map(|c| c.delta).sum();
+            assert!((total - candidate.score).abs() < 1e-4, "明细之和 {} != 得分 {}", total, candidate.score);
+            assert!(candidate.components.iter().any(|c| c.field == "tristate.resource_id"));
+        }
+    }
 }
@@ -16,7 +16,16 @@ use crate::automation::matching::scorer::{
     MultiCandidateEvaluator,
     EvaluationCriteria,
     ParentInfo,
+    RankedCandidates,
 };
+use crate::automation::matching::decision_journal::{
+    record_decision,
+    CandidateRecord,
+    MatchDecision,
+    ScoreComponent,
+    ComponentCategory,
+};
+use crate::commands::run_step_v2::StaticEvidence;
 use crate::exec::semantic_analyzer::SemanticAnalyzer;
 use crate::exec::semantic_analyzer::config::TextMatchingMode;
 
@@ -279,6 +288,7 @@ pub fn collect_candidate_elements<'a>(
 
 /// 评估最佳候选元素
 pub fn evaluate_best_candidate<'a>(
+    step_id: &str,
     candidate_elements: Vec<&'a UIElement>,
     params: &serde_json::Value,
     ui_xml: &str,  // 🔥 新增：当前XML内容，用于子元素文本提取
@@ -460,16 +470,24 @@ pub fn evaluate_best_candidate<'a>(
             sibling_texts, // 🆕 NEW: 兄弟元素文本
             parent_info, // 🆕 NEW: 父元素信息
             semantic_analyzer: Some(semantic_analyzer), // 🆕 NEW: 语义分析器
+            static_evidence: StaticEvidence::from_step_params(params), // 🧬 三态证据（权重取自匹配配置）
         };
         
         // ✅ 使用 MultiCandidateEvaluator 进行综合评估
         tracing::info!("🧠 [多候选评估] 开始综合评分，criteria.selected_xpath={:?}", criteria.selected_xpath);
         
-        if let Some(best_candidate) = MultiCandidateEvaluator::evaluate_candidates(candidate_elements.clone(), &criteria) {
+        let evaluation = MultiCandidateEvaluator::evaluate_candidates_ranked(candidate_elements.clone(), &criteria);
+        if let Some(best_candidate) = evaluation.best() {
             // 🚨 检查分数是否达到最低有效阈值
             const MIN_VALID_SCORE: f32 = 0.3; // 设置最低有效分数
             
             if best_candidate.score < MIN_VALID_SCORE {
+                record_decision(
+                    decision_from_evaluation(step_id, &evaluation, false).with_note(format!(
+                        "最佳候选分数 {:.3} 低于最低有效阈值 {:.1}，拒绝执行",
+                        best_candidate.score, MIN_VALID_SCORE
+                    )),
+                );
                 tracing::error!("🚨 [目标不存在] 最佳候选分数过低 ({:.3} < {:.1})，当前页面可能不存在真正的目标元素", 
                                best_candidate.score, MIN_VALID_SCORE);
                 tracing::error!("   📍 最佳候选详情: text={:?}, content-desc={:?}, bounds={:?}", 
@@ -497,6 +515,7 @@ pub fn evaluate_best_candidate<'a>(
                 ));
             }
             
+            record_decision(decision_from_evaluation(step_id, &evaluation, true));
             tracing::info!("✅ [多候选评估] 最佳匹配: score={:.3}", best_candidate.score);
             tracing::info!("   📍 详情: text={:?}, content-desc={:?}, bounds={:?}", 
                          best_candidate.element.text, 
@@ -514,6 +533,27 @@ pub fn evaluate_best_candidate<'a>(
         }
     } else {
         // 只有一个或零个候选，直接使用
+        if let Some(only) = candidate_elements.first() {
+            let component = ScoreComponent::new(ComponentCategory::Info, "candidate", 0.0, "唯一候选，未进入多候选评分");
+            let record = CandidateRecord::from_element(1, only, 1.0, vec![component]);
+            record_decision(MatchDecision::new(step_id, vec![record], Some(0)));
+        }
         Ok(candidate_elements.first().copied())
     }
 }
+
+/// 将多候选评估结果转换为决策日志记录
+fn decision_from_evaluation(step_id: &str, evaluation: &RankedCandidates, accepted: bool) -> MatchDecision {
+    let candidates = evaluation
+        .ranked
+        .iter()
+        .enumerate()
+        .map(|(i, c)| CandidateRecord::from_element(i + 1, c.element, c.score, c.components.clone()))
+        .collect();
+    let selected = if accepted { evaluation.selected } else { None };
+    let mut decision = MatchDecision::new(step_id, candidates, selected);
+    if let Some(ref tie_break) = evaluation.tie_break {
+        decision = decision.with_note(tie_break.clone());
+    }
+    decision
+}
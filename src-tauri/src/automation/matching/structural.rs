@@ -4,9 +4,12 @@
 
 use serde_json::Value;
 use crate::commands::structure_match_runtime::{
-    sm_match_once, SmMatchRequest, SmConfigDTO,
+    sm_match_once, SmMatchRequest, SmConfigDTO, SmResultDTO,
 };
 use crate::automation::matching::strategy::evaluate_best_candidate;
+use crate::automation::matching::decision_journal::{
+    record_decision, CandidateRecord, ComponentCategory, MatchDecision, ScoreComponent,
+};
 
 /// 🔧 从前端格式的structural_signatures中提取skeleton规则
 fn extract_skeleton_rules_from_frontend_format(structural_sigs: &Value) -> Result<Option<String>, String> {
@@ -99,6 +102,7 @@ pub async fn try_structural_matching_flow(
     device_id: &str,
     ui_xml: &str,
     merged_params: &Value,
+    step_id: &str,
 ) -> Result<Option<(i32, i32)>, String> {
     // 1. 检查是否存在 structural_signatures
     let structural_sigs = match merged_params.get("structural_signatures") {
@@ -129,6 +133,7 @@ pub async fn try_structural_matching_flow(
         Ok(response) => {
             if response.success {
                 if let Some(result) = response.result {
+                    record_decision(decision_from_sm_result(step_id, &result));
                     // 尝试获取第一个匹配项的边界
                     if let Some(first_item) = result.items.first() {
                         let bounds = &first_item.bounds;
@@ -156,3 +161,30 @@ pub async fn try_structural_matching_flow(
     tracing::info!("🔄 [SM Integration] 结构匹配失败，降级到传统匹配流程");
     Ok(None)
 }
+
+/// 将结构匹配结果转换为决策日志记录（SM Runtime 已按分数排序，取第一项执行）
+fn decision_from_sm_result(step_id: &str, result: &SmResultDTO) -> MatchDecision {
+    let candidates = result
+        .items
+        .iter()
+        .enumerate()
+        .map(|(i, item)| {
+            let b = &item.bounds;
+            let component = ScoreComponent::new(
+                ComponentCategory::Field,
+                "structure",
+                item.score,
+                format!("结构匹配节点 #{} (容器={}, 布局={})", item.node_id, result.container_id, result.layout_type),
+            );
+            CandidateRecord::from_bounds(
+                i + 1,
+                format!("[{},{}][{},{}]", b.left, b.top, b.right, b.bottom),
+                item.score,
+                vec![component],
+            )
+        })
+        .collect::<Vec<_>>();
+    let selected = if candidates.is_empty() { None } else { Some(0) };
+    MatchDecision::new(step_id, candidates, selected)
+        .with_note(format!("结构匹配总分 {:.3}", result.score))
+}
@@ -0,0 +1,286 @@
+// src-tauri/src/automation/pipeline/cancellation.rs
+// module: automation | layer: pipeline | role: V3 执行取消
+// summary: 任务 → 链 → 步骤三级取消令牌；任务令牌登记在插件托管状态中，执行器在各 await 点协作检查
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+
+use chrono::{DateTime, Utc};
+use futures::future::{select_all, FutureExt};
+use serde::Serialize;
+use tokio::sync::watch;
+
+/// 取消后执行器返回的错误
+pub const CANCELLED_ERROR: &str = "执行已被用户取消";
+
+tokio::task_local! {
+    static CURRENT: CancelToken;
+}
+
+/// 令牌层级
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CancelLevel {
+    Task,
+    Chain,
+    Step,
+}
+
+/// 任务内共享的执行位置（取消时写入部分 trace）
+#[derive(Debug, Default)]
+struct Position {
+    chain: Option<String>,
+    step: Option<String>,
+}
+
+/// 取消令牌：自身或任一上级被取消即视为取消；取消上级会级联到全部下级
+#[derive(Debug, Clone)]
+pub struct CancelToken {
+    level: CancelLevel,
+    trigger: Arc<watch::Sender<bool>>,
+    /// 自身在前，其后依次为各级上级
+    signals: Vec<watch::Receiver<bool>>,
+    position: Arc<Mutex<Position>>,
+}
+
+impl CancelToken {
+    pub fn root() -> Self {
+        let (tx, rx) = watch::channel(false);
+        Self { level: CancelLevel::Task, trigger: Arc::new(tx), signals: vec![rx], position: Arc::default() }
+    }
+
+    /// 派生下级令牌，并把 `label` 记为当前链 / 步骤
+    pub fn child(&self, level: CancelLevel, label: &str) -> Self {
+        if let Ok(mut position) = self.position.lock() {
+            match level {
+                CancelLevel::Chain => position.chain = Some(label.to_string()),
+                CancelLevel::Step => position.step = Some(label.to_string()),
+                CancelLevel::Task => {}
+            }
+        }
+        let (tx, rx) = watch::channel(false);
+        let mut signals = vec![rx];
+        signals.extend(self.signals.iter().cloned());
+        Self { level, trigger: Arc::new(tx), signals, position: self.position.clone() }
+    }
+
+    pub fn level(&self) -> CancelLevel {
+        self.level
+    }
+
+    pub fn cancel(&self) {
+        let _ = self.trigger.send(true);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.signals.iter().any(|signal| *signal.borrow())
+    }
+
+    /// 等待自身或任一上级被取消
+    pub async fn cancelled(&self) {
+        let mut signals = self.signals.clone();
+        loop {
+            if signals.iter_mut().any(|signal| *signal.borrow_and_update()) {
+                return;
+            }
+            if signals.is_empty() {
+                return std::future::pending().await;
+            }
+            let changes = signals.iter_mut().map(|signal| signal.changed().boxed());
+            let (result, index, pending) = select_all(changes).await;
+            drop(pending);
+            if result.is_err() {
+                // 触发端已释放的信号不会再变化
+                signals.remove(index);
+            }
+        }
+    }
+
+    /// 运行 future，取消时丢弃它并返回 [`CANCELLED_ERROR`]
+    pub async fn guard<T, F: Future<Output = Result<T, String>>>(&self, fut: F) -> Result<T, String> {
+        if self.is_cancelled() {
+            return Err(CANCELLED_ERROR.to_string());
+        }
+        tokio::select! {
+            biased;
+            _ = self.cancelled() => Err(CANCELLED_ERROR.to_string()),
+            result = fut => result,
+        }
+    }
+
+    /// 取消时所在的链与步骤
+    pub fn position(&self) -> (Option<String>, Option<String>) {
+        self.position.lock().map(|p| (p.chain.clone(), p.step.clone())).unwrap_or_default()
+    }
+}
+
+/// 在令牌范围内运行，期间的 [`checkpoint`] / [`cancellable`] 都以它为准
+pub async fn scope<F: Future>(token: CancelToken, fut: F) -> F::Output {
+    CURRENT.scope(token, fut).await
+}
+
+/// 在当前令牌的下级范围内运行（不在任务范围内时直接运行）
+pub async fn child_scope<F: Future>(level: CancelLevel, label: &str, fut: F) -> F::Output {
+    match current() {
+        Some(parent) => CURRENT.scope(parent.child(level, label), fut).await,
+        None => fut.await,
+    }
+}
+
+pub fn current() -> Option<CancelToken> {
+    CURRENT.try_with(|token| token.clone()).ok()
+}
+
+/// 协作检查点：已取消时返回 [`CANCELLED_ERROR`]
+pub fn checkpoint() -> Result<(), String> {
+    match current() {
+        Some(token) if token.is_cancelled() => Err(CANCELLED_ERROR.to_string()),
+        _ => Ok(()),
+    }
+}
+
+/// 可被当前令牌打断的 await
+pub async fn cancellable<T, F: Future<Output = Result<T, String>>>(fut: F) -> Result<T, String> {
+    match current() {
+        Some(token) => token.guard(fut).await,
+        None => fut.await,
+    }
+}
+
+pub fn is_cancelled_error(error: &str) -> bool {
+    error.contains(CANCELLED_ERROR)
+}
+
+/// 进行中的 V3 任务
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActiveTaskInfo {
+    pub task_id: String,
+    pub kind: String,
+    pub device_id: String,
+    pub started_at: DateTime<Utc>,
+    pub chain: Option<String>,
+    pub step: Option<String>,
+}
+
+struct ActiveTask {
+    info: ActiveTaskInfo,
+    token: CancelToken,
+}
+
+/// 任务令牌登记表（插件托管状态）
+#[derive(Default, Clone)]
+pub struct V3CancellationRegistry {
+    tasks: Arc<Mutex<HashMap<String, ActiveTask>>>,
+}
+
+/// 登记凭证：析构时注销任务
+pub struct TaskRegistration {
+    task_id: String,
+    tasks: Arc<Mutex<HashMap<String, ActiveTask>>>,
+}
+
+impl Drop for TaskRegistration {
+    fn drop(&mut self) {
+        if let Ok(mut tasks) = self.tasks.lock() {
+            tasks.remove(&self.task_id);
+        }
+    }
+}
+
+impl V3CancellationRegistry {
+    /// 登记任务并返回其根令牌；同一任务 ID 正在执行时拒绝
+    pub fn register(&self, task_id: &str, kind: &str, device_id: &str) -> Result<(CancelToken, TaskRegistration), String> {
+        let mut tasks = self.tasks.lock().map_err(|e| e.to_string())?;
+        if tasks.contains_key(task_id) {
+            return Err(format!("任务 {} 正在执行中", task_id));
+        }
+        let token = CancelToken::root();
+        let info = ActiveTaskInfo {
+            task_id: task_id.to_string(),
+            kind: kind.to_string(),
+            device_id: device_id.to_string(),
+            started_at: Utc::now(),
+            chain: None,
+            step: None,
+        };
+        tasks.insert(task_id.to_string(), ActiveTask { info, token: token.clone() });
+        Ok((token, TaskRegistration { task_id: task_id.to_string(), tasks: self.tasks.clone() }))
+    }
+
+    /// 取消任务（级联到链与步骤），返回是否找到进行中的任务
+    pub fn cancel(&self, task_id: &str) -> bool {
+        let Ok(tasks) = self.tasks.lock() else { return false };
+        match tasks.get(task_id) {
+            Some(task) => {
+                task.token.cancel();
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn active(&self) -> Vec<ActiveTaskInfo> {
+        let Ok(tasks) = self.tasks.lock() else { return Vec::new() };
+        let mut active: Vec<ActiveTaskInfo> = tasks
+            .values()
+            .map(|task| {
+                let (chain, step) = task.token.position();
+                ActiveTaskInfo { chain, step, ..task.info.clone() }
+            })
+            .collect();
+        active.sort_by_key(|info| info.started_at);
+        active
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn cancelling_task_cascades_to_running_step() {
+        let registry = V3CancellationRegistry::default();
+        let (token, registration) = registry.register("task-1", "chain", "dev-a").unwrap();
+        assert!(registry.register("task-1", "chain", "dev-a").is_err());
+
+        let run = token.guard(scope(token.clone(), async {
+            child_scope(CancelLevel::Chain, "chain-a", async {
+                checkpoint()?;
+                child_scope(CancelLevel::Step, "step-2", async {
+                    assert_eq!(current().map(|t| t.level()), Some(CancelLevel::Step));
+                    cancellable(async {
+                        tokio::time::sleep(Duration::from_secs(30)).await;
+                        Ok::<_, String>("finished")
+                    })
+                    .await
+                })
+                .await
+            })
+            .await
+        }));
+        let canceller = async {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            let active = registry.active();
+            assert_eq!(active[0].step.as_deref(), Some("step-2"));
+            assert!(registry.cancel("task-1"));
+        };
+        let (result, _) = tokio::join!(run, canceller);
+        assert!(is_cancelled_error(&result.unwrap_err()));
+        assert_eq!(token.position(), (Some("chain-a".to_string()), Some("step-2".to_string())));
+
+        // 取消步骤不影响上级
+        let step = token.child(CancelLevel::Step, "step-3");
+        let other = CancelToken::root();
+        let other_step = other.child(CancelLevel::Step, "x");
+        other_step.cancel();
+        assert!(other_step.is_cancelled() && !other.is_cancelled());
+        assert!(step.is_cancelled());
+
+        drop(registration);
+        assert!(registry.active().is_empty());
+        assert!(!registry.cancel("task-1"));
+    }
+}
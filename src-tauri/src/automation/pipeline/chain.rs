@@ -15,6 +15,18 @@ use tauri::AppHandle;
 // 🆕 集成执行中止服务
 use crate::services::execution_abort_service::{should_abort_execution, register_execution, finish_execution};
 
+// 🛑 V3 取消令牌（任务 → 链 → 步骤）
+use super::cancellation::{self, cancellable, checkpoint, CancelLevel};
+
+/// 中止服务登记守卫：正常结束、出错或被取消丢弃时都会清理登记
+struct AbortRegistration(String);
+
+impl Drop for AbortRegistration {
+    fn drop(&mut self) {
+        finish_execution(&self.0);
+    }
+}
+
 // 添加必要的导入以支持真实设备操作
  // 添加 UIElement 导入
 
@@ -107,16 +119,20 @@ pub async fn execute_chain(
                 ordered_steps.len()
             );
 
-            execute_chain_by_inline(
-                app,
-                envelope,
+            cancellation::child_scope(
+                CancelLevel::Chain,
                 analysis_id,
-                ordered_steps,
-                *threshold,
-                mode,
-                quality,
-                constraints,
-                validation,
+                execute_chain_by_inline(
+                    app,
+                    envelope,
+                    analysis_id,
+                    ordered_steps,
+                    *threshold,
+                    mode,
+                    quality,
+                    constraints,
+                    validation,
+                ),
             )
             .await
         }
@@ -230,17 +246,20 @@ fn execute_chain_by_inline<'a>(
         // 🆕 【执行注册】注册执行到中止服务
         let execution_id = format!("v3_chain_{}", analysis_id);
         register_execution(execution_id.clone(), device_id.clone());
+        let _abort_registration = AbortRegistration(execution_id.clone());
         
         // 🔒 【统一锁定入口】使用 RAII 守卫确保所有路径都能正确释放
         // 这会在函数开始时锁定，在函数结束时（无论成功/失败）自动释放
         let _execution_guard = execution_tracker::lock_with_guard(analysis_id)?;
 
         // 🆕 【提前智能分析检测】在Legacy引擎执行前检查参数，直接触发智能分析
+        checkpoint()?;
         if let Some(intelligent_steps) =
-            check_and_trigger_early_analysis(app, analysis_id, device_id, ordered_steps).await?
+            cancellable(check_and_trigger_early_analysis(app, analysis_id, device_id, ordered_steps)).await?
         {
-            // ✅ 显式释放当前锁（让守卫析构）
+            // ✅ 显式释放当前锁和中止登记（让守卫析构）
             drop(_execution_guard);
+            drop(_abort_registration);
 
             // 递归执行智能生成的步骤（递归调用时会重新锁定）
             return execute_chain_by_inline(
@@ -259,14 +278,14 @@ fn execute_chain_by_inline<'a>(
 
         // 🎯 V3修复：智能策略分析策略调整
         // 只有在缺少候选步骤或步骤质量不佳时才触发智能分析，避免不必要的重复生成
-        let generated_steps = optimize_steps_with_intelligent_analysis(
+        let generated_steps = cancellable(optimize_steps_with_intelligent_analysis(
             app,
             analysis_id,
             device_id,
             ordered_steps,
             quality,
             threshold,
-        )
+        ))
         .await?;
 
         let final_ordered_steps = &generated_steps;
@@ -297,7 +316,7 @@ fn execute_chain_by_inline<'a>(
 
         // 1. 校验设备连接状态 - 简化版本（暂时跳过，避免复杂的依赖）
         // 注意：在生产环境中应该进行设备连接检查
-        device_manager::check_device_connection(device_id).await?;
+        cancellable(device_manager::check_device_connection(device_id)).await?;
 
         // ====== Phase 2: snapshot_ready ======
         emit_progress(
@@ -311,7 +330,7 @@ fn execute_chain_by_inline<'a>(
         )?;
 
         // 2. 🎯 获取 XML 数据源（三级降级：全局缓存 → 步骤快照 → 实时设备）
-        let ui_xml = crate::exec::helpers::xml_source_resolver::resolve_xml_source(app, envelope).await?;
+        let ui_xml = cancellable(crate::exec::helpers::xml_source_resolver::resolve_xml_source(app, envelope)).await?;
         
         // 计算当前屏幕哈希（用于判断是否需要重评分）
         let screen_hash = crate::exec::helpers::device_manager::calculate_screen_hash(&ui_xml);
@@ -331,7 +350,7 @@ fn execute_chain_by_inline<'a>(
         }
 
         // ====== Phase 4: 决定是否重新评分（Strict vs Relaxed） ======
-        let mut step_scores = score_steps_by_mode(
+        let mut step_scores = cancellable(score_steps_by_mode(
             device_id,
             &ui_xml,
             &final_ordered_steps,
@@ -339,7 +358,7 @@ fn execute_chain_by_inline<'a>(
             &envelope.execution_mode,
             &screen_hash,
             envelope.snapshot.screen_hash.as_deref(),
-        )
+        ))
         .await?;
 
         // ====== Phase 5: matched (发送所有评分结果) ======
@@ -367,9 +386,9 @@ fn execute_chain_by_inline<'a>(
             // 🛑 【中止检查】在每个步骤执行前检查是否应该中止
             if should_abort_execution(&execution_id) {
                 tracing::warn!("🛑 [V3执行] 检测到中止信号，停止执行链: {}", analysis_id);
-                finish_execution(&execution_id);
                 return Err("执行已被用户中止".to_string());
             }
+            checkpoint()?;
 
             if score.confidence < threshold {
                 tracing::info!(
@@ -430,14 +449,16 @@ fn execute_chain_by_inline<'a>(
                 validation: validation.clone(),
             };
             
-            // 调用智能单步执行器（复用单步逻辑）
-            match super::single_step::execute_single_step_internal(
-                app,
-                envelope,
-                single_step_spec,
+            // 调用智能单步执行器（复用单步逻辑），每个候选步骤持有独立的步骤级令牌
+            let step_outcome = cancellation::child_scope(
+                CancelLevel::Step,
+                &score.step_id,
+                cancellable(super::single_step::execute_single_step_internal(app, envelope, single_step_spec)),
             )
-            .await
-            {
+            .await;
+            // 任务或链被取消时不再回退到下一个候选
+            checkpoint()?;
+            match step_outcome {
                 Ok(result) => {
                     // 从返回结果中提取坐标信息
                     let click_coords = if let Some(coords_val) = result.get("coords") {
@@ -499,7 +520,7 @@ fn execute_chain_by_inline<'a>(
             );
         } else {
             // 🧠 传统匹配失败，触发智能分析作为后备方案
-            let fallback_result = handle_intelligent_fallback(
+            let fallback_result = cancellable(handle_intelligent_fallback(
                 app,
                 analysis_id,
                 device_id,
@@ -508,7 +529,7 @@ fn execute_chain_by_inline<'a>(
                 quality,
                 validation,
                 threshold,
-            )
+            ))
             .await?;
 
             adopted_step_id = fallback_result.0;
@@ -558,8 +579,7 @@ fn execute_chain_by_inline<'a>(
         // 🔓 【执行保护】RAII 守卫会在函数结束时自动释放锁
         // 不再需要手动 unlock，由 _execution_guard 的 Drop 实现自动管理
 
-        // 🆕 【执行清理】完成执行后清理中止服务状态
-        finish_execution(&execution_id);
+        // 🆕 【执行清理】中止服务登记由 _abort_registration 析构时清理
 
         Ok(())
     })
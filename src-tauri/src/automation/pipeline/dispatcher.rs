@@ -47,7 +47,7 @@ pub async fn try_dispatch_direct_action(
             params.get("end_x").and_then(|v| v.as_i64()),
             params.get("end_y").and_then(|v| v.as_i64())
         ) {
-             let duration = params.get("duration").and_then(|v| v.as_u64()).unwrap_or(1000) as u32;
+             let duration = params.get("duration_ms").or_else(|| params.get("duration")).and_then(|v| v.as_u64()).unwrap_or(1000) as u32;
              swipe::execute_swipe(device_id, sx as i32, sy as i32, ex as i32, ey as i32, duration).await
                 .map_err(|e| e.to_string())?;
              return Ok(Some((ex as i32, ey as i32)));
@@ -91,6 +91,7 @@ impl ExecutionGate {
         live_xml: &str,
         static_confidence: f64,
     ) -> Result<GateVerification, String> {
+        let _timeline = crate::services::execution::timeline::phase(crate::services::execution::timeline::Phase::Gate);
         tracing::info!("🔒 [执行网关] 开始验证策略: xpath=\"{}\"", xpath);
 
         // 1. 解析真机 XML
@@ -39,6 +39,7 @@ pub async fn execute_step_unified(
 ) -> Result<StepExecutionResult, String> {
     
     tracing::info!("🎯 [统一执行器] 开始执行步骤: {}", inline_step.step_id);
+    super::cancellation::checkpoint()?;
     
     // 调用新的自动化引擎执行器
     // 这个执行器已经包含了所有高级功能：
@@ -46,11 +47,11 @@ pub async fn execute_step_unified(
     // - 批量执行模式
     // - 多候选评估
     // - 结构签名匹配
-    let (coords_x, coords_y) = engine::execute_step(
+    let (coords_x, coords_y) = super::cancellation::cancellable(engine::execute_step(
         &envelope.device_id,
         inline_step,
         ui_xml,
-    )
+    ))
     .await
     .map_err(|e| {
         tracing::error!("❌ [统一执行器] 步骤执行失败: {}", e);
@@ -13,5 +13,7 @@ pub mod tracker;
 pub mod phases;
 pub mod protocol;
 pub mod execution_gate;
+pub mod cancellation;
+pub mod multi_device;
 
 pub use execution_gate::{ExecutionGate, GateConfig, GateVerification, GateRecommendation};
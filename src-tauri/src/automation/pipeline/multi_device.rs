@@ -0,0 +1,357 @@
+// src-tauri/src/automation/pipeline/multi_device.rs
+// module: automation | layer: pipeline | role: 多设备并行执行
+// summary: 同一脚本按并发上限同时分发到多台设备，逐设备推送 device:{serial}:progress 进度事件，结束后汇总每台设备的结果报告
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Instant;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use tokio::sync::Semaphore;
+
+use crate::services::execution::model::SmartExecutionResult;
+
+/// 未指定时的默认并发设备数
+pub const DEFAULT_MAX_CONCURRENCY: usize = 4;
+
+tokio::task_local! {
+    static REPORTER: DeviceReporter;
+}
+
+/// 设备进度阶段
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DeviceProgressPhase {
+    /// 等待并发名额
+    Queued,
+    Started,
+    StepStarted,
+    StepFinished,
+    Completed,
+    Failed,
+}
+
+/// `device:{serial}:progress` 事件负载
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeviceProgress {
+    pub batch_id: String,
+    pub device_id: String,
+    pub phase: DeviceProgressPhase,
+    /// 从 0 开始的步骤序号（步骤阶段才有）
+    pub step_index: Option<usize>,
+    pub total_steps: usize,
+    pub step_id: Option<String>,
+    pub step_name: Option<String>,
+    pub success: Option<bool>,
+    pub message: Option<String>,
+    pub at: DateTime<Utc>,
+}
+
+pub type ProgressSink = Arc<dyn Fn(DeviceProgress) + Send + Sync>;
+
+#[derive(Clone)]
+struct DeviceReporter {
+    batch_id: String,
+    device_id: String,
+    total_steps: usize,
+    sink: ProgressSink,
+}
+
+impl DeviceReporter {
+    fn progress(&self, phase: DeviceProgressPhase) -> DeviceProgress {
+        DeviceProgress {
+            batch_id: self.batch_id.clone(),
+            device_id: self.device_id.clone(),
+            phase,
+            step_index: None,
+            total_steps: self.total_steps,
+            step_id: None,
+            step_name: None,
+            success: None,
+            message: None,
+            at: Utc::now(),
+        }
+    }
+
+    fn emit(&self, progress: DeviceProgress) {
+        (self.sink)(progress);
+    }
+}
+
+/// 进度事件名。Tauri 事件名只允许字母数字与 `-` `/` `:` `_`，序列号中的其他字符（如 IP 中的 `.`）替换为 `_`
+pub fn progress_event_name(device_id: &str) -> String {
+    let serial: String = device_id
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || matches!(c, '-' | '/' | ':' | '_') { c } else { '_' })
+        .collect();
+    format!("device:{}:progress", serial)
+}
+
+/// 执行器在步骤开始时调用；不在多设备批次内时为空操作
+pub fn report_step_started(index: usize, step_id: &str, step_name: &str) {
+    let _ = REPORTER.try_with(|reporter| {
+        reporter.emit(DeviceProgress {
+            step_index: Some(index),
+            step_id: Some(step_id.to_string()),
+            step_name: Some(step_name.to_string()),
+            ..reporter.progress(DeviceProgressPhase::StepStarted)
+        })
+    });
+}
+
+/// 执行器在步骤结束时调用；不在多设备批次内时为空操作
+pub fn report_step_finished(index: usize, step_id: &str, step_name: &str, success: bool, message: Option<String>) {
+    let _ = REPORTER.try_with(|reporter| {
+        reporter.emit(DeviceProgress {
+            step_index: Some(index),
+            step_id: Some(step_id.to_string()),
+            step_name: Some(step_name.to_string()),
+            success: Some(success),
+            message,
+            ..reporter.progress(DeviceProgressPhase::StepFinished)
+        })
+    });
+}
+
+/// 单台设备的执行报告
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeviceRunReport {
+    pub device_id: String,
+    pub success: bool,
+    /// 执行器返回的完整结果；执行器本身报错时为 None
+    pub result: Option<SmartExecutionResult>,
+    pub error: Option<String>,
+    /// 等待并发名额的时间
+    pub queued_ms: u64,
+    pub duration_ms: u64,
+    pub started_at: Option<DateTime<Utc>>,
+    pub finished_at: DateTime<Utc>,
+}
+
+/// 多设备批次汇总
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MultiDeviceReport {
+    pub batch_id: String,
+    pub max_concurrency: usize,
+    pub total_devices: usize,
+    pub succeeded: usize,
+    pub failed: usize,
+    pub duration_ms: u64,
+    /// 与请求中的设备顺序一致
+    pub devices: Vec<DeviceRunReport>,
+}
+
+impl MultiDeviceReport {
+    /// 兼容旧接口的「设备 → 结果」映射；执行器报错的设备补一个失败结果
+    pub fn into_results(self, total_steps: usize) -> HashMap<String, SmartExecutionResult> {
+        self.devices
+            .into_iter()
+            .map(|device| {
+                let result = device.result.unwrap_or_else(|| {
+                    let message = format!("设备执行失败: {}", device.error.unwrap_or_default());
+                    SmartExecutionResult {
+                        success: false,
+                        total_steps: total_steps as u32,
+                        executed_steps: 0,
+                        failed_steps: total_steps as u32,
+                        skipped_steps: 0,
+                        duration_ms: device.duration_ms,
+                        logs: vec![message.clone()],
+                        final_page_state: None,
+                        extracted_data: HashMap::new(),
+                        message,
+                        run_id: None,
+                        dry_run: None,
+                    }
+                });
+                (device.device_id, result)
+            })
+            .collect()
+    }
+}
+
+/// 把同一脚本并行分发到多台设备，最多同时执行 `max_concurrency` 台。
+///
+/// `run_device` 负责在单台设备上执行整套脚本，在其中调用 [`report_step_started`] / [`report_step_finished`]
+/// 的进度会带上设备 ID 经 `sink` 推送；重复的设备 ID 只执行一次。
+pub async fn execute_parallel<F, Fut>(
+    batch_id: &str,
+    device_ids: Vec<String>,
+    total_steps: usize,
+    max_concurrency: Option<usize>,
+    sink: ProgressSink,
+    run_device: F,
+) -> MultiDeviceReport
+where
+    F: Fn(String) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = Result<SmartExecutionResult, String>> + Send + 'static,
+{
+    let started = Instant::now();
+    let mut unique = Vec::with_capacity(device_ids.len());
+    for device_id in device_ids {
+        if !unique.contains(&device_id) {
+            unique.push(device_id);
+        }
+    }
+    let limit = max_concurrency.unwrap_or(DEFAULT_MAX_CONCURRENCY).clamp(1, unique.len().max(1));
+    let permits = Arc::new(Semaphore::new(limit));
+    let run_device = Arc::new(run_device);
+
+    let handles: Vec<_> = unique
+        .iter()
+        .map(|device_id| {
+            let reporter = DeviceReporter {
+                batch_id: batch_id.to_string(),
+                device_id: device_id.clone(),
+                total_steps,
+                sink: sink.clone(),
+            };
+            reporter.emit(reporter.progress(DeviceProgressPhase::Queued));
+            let permits = permits.clone();
+            let run_device = run_device.clone();
+            let device_id = device_id.clone();
+            // 每台设备单独起任务，执行器内的阻塞 adb 调用不会拖慢其他设备
+            tokio::spawn(async move {
+                let queued_at = Instant::now();
+                let _permit = permits.acquire_owned().await;
+                let queued_ms = queued_at.elapsed().as_millis() as u64;
+                let started_at = Utc::now();
+                let running = Instant::now();
+                reporter.emit(reporter.progress(DeviceProgressPhase::Started));
+                let outcome = REPORTER.scope(reporter.clone(), run_device(device_id.clone())).await;
+                let (success, message) = match &outcome {
+                    Ok(result) => (result.success, result.message.clone()),
+                    Err(e) => (false, e.clone()),
+                };
+                let phase = if success { DeviceProgressPhase::Completed } else { DeviceProgressPhase::Failed };
+                reporter.emit(DeviceProgress { success: Some(success), message: Some(message), ..reporter.progress(phase) });
+                let (result, error) = match outcome {
+                    Ok(result) => (Some(result), None),
+                    Err(e) => (None, Some(e)),
+                };
+                DeviceRunReport {
+                    device_id,
+                    success,
+                    result,
+                    error,
+                    queued_ms,
+                    duration_ms: running.elapsed().as_millis() as u64,
+                    started_at: Some(started_at),
+                    finished_at: Utc::now(),
+                }
+            })
+        })
+        .collect();
+
+    let mut devices = Vec::with_capacity(handles.len());
+    for (device_id, handle) in unique.into_iter().zip(handles) {
+        let report = handle.await.unwrap_or_else(|e| DeviceRunReport {
+            device_id,
+            success: false,
+            result: None,
+            error: Some(format!("设备任务异常退出: {}", e)),
+            queued_ms: 0,
+            duration_ms: 0,
+            started_at: None,
+            finished_at: Utc::now(),
+        });
+        devices.push(report);
+    }
+
+    let succeeded = devices.iter().filter(|d| d.success).count();
+    MultiDeviceReport {
+        batch_id: batch_id.to_string(),
+        max_concurrency: limit,
+        total_devices: devices.len(),
+        succeeded,
+        failed: devices.len() - succeeded,
+        duration_ms: started.elapsed().as_millis() as u64,
+        devices,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Mutex;
+    use std::time::Duration;
+
+    fn result(success: bool) -> SmartExecutionResult {
+        SmartExecutionResult {
+            success,
+            total_steps: 1,
+            executed_steps: success as u32,
+            failed_steps: (!success) as u32,
+            skipped_steps: 0,
+            duration_ms: 0,
+            logs: vec![],
+            final_page_state: None,
+            extracted_data: HashMap::new(),
+            message: String::new(),
+            run_id: None,
+            dry_run: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn runs_devices_concurrently_within_limit() {
+        let events = Arc::new(Mutex::new(Vec::<DeviceProgress>::new()));
+        let sink: ProgressSink = {
+            let events = events.clone();
+            Arc::new(move |progress| events.lock().unwrap().push(progress))
+        };
+        let running = Arc::new(AtomicUsize::new(0));
+        let peak = Arc::new(AtomicUsize::new(0));
+        let (running_c, peak_c) = (running.clone(), peak.clone());
+        let devices = vec!["a", "b", "192.168.1.5:5555", "d", "a"].into_iter().map(String::from).collect();
+
+        let report = execute_parallel("batch-1", devices, 1, Some(2), sink, move |device_id| {
+            let (running, peak) = (running_c.clone(), peak_c.clone());
+            async move {
+                let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+                peak.fetch_max(now, Ordering::SeqCst);
+                report_step_started(0, "s1", "点击");
+                tokio::time::sleep(Duration::from_millis(20)).await;
+                report_step_finished(0, "s1", "点击", device_id != "d", None);
+                running.fetch_sub(1, Ordering::SeqCst);
+                match device_id.as_str() {
+                    "b" => Err("设备离线".to_string()),
+                    other => Ok(result(other != "d")),
+                }
+            }
+        })
+        .await;
+
+        assert_eq!(peak.load(Ordering::SeqCst), 2);
+        assert_eq!(report.max_concurrency, 2);
+        assert_eq!(
+            report.devices.iter().map(|d| (d.device_id.as_str(), d.success)).collect::<Vec<_>>(),
+            vec![("a", true), ("b", false), ("192.168.1.5:5555", true), ("d", false)]
+        );
+        assert_eq!((report.succeeded, report.failed), (2, 2));
+        assert_eq!(report.devices[1].error.as_deref(), Some("设备离线"));
+
+        let events = events.lock().unwrap();
+        let phases: Vec<_> = events.iter().filter(|e| e.device_id == "a").map(|e| e.phase).collect();
+        assert_eq!(
+            phases,
+            vec![
+                DeviceProgressPhase::Queued,
+                DeviceProgressPhase::Started,
+                DeviceProgressPhase::StepStarted,
+                DeviceProgressPhase::StepFinished,
+                DeviceProgressPhase::Completed
+            ]
+        );
+        assert_eq!(progress_event_name("192.168.1.5:5555"), "device:192_168_1_5:5555:progress");
+
+        let results = report.into_results(1);
+        assert!(!results["b"].success && results["b"].message.contains("设备离线"));
+    }
+}
@@ -9,6 +9,8 @@ use crate::automation::types::*;
 use crate::automation::events::*;
 use crate::types::smart_selection::*;
 
+use super::cancellation;
+
 /// 智能单步执行（内部实现）
 pub async fn execute_single_step_internal(
     app: &AppHandle,
@@ -17,6 +19,7 @@ pub async fn execute_single_step_internal(
 ) -> Result<Value, String> {
     // #[allow(unused_variables)]
     let _start_time = std::time::Instant::now();
+    cancellation::checkpoint()?;
     
     // 根据 by-ref 或 by-inline 处理
     match step {
@@ -632,6 +635,8 @@ async fn execute_action_unified(
     use crate::commands::strategy_matching::{match_element_by_criteria, MatchCriteriaDTO};
     
     tracing::info!("🎯 开始执行统一操作");
+    // 🛑 注入设备操作前的取消检查点
+    cancellation::checkpoint()?;
     
     // 1. 解析操作类型，默认为点击
     let action = params.get("action_type")
@@ -1635,6 +1640,7 @@ async fn execute_smart_tap_by_bounds(
 ) -> Result<(f32, Option<(i32, i32)>), String> {
     use crate::services::action_executor::ActionExecutor;
     use crate::types::action_types::{ActionType, ActionContext, ElementBounds};
+    cancellation::checkpoint()?;
     
     // 解析 bounds: "[left,top][right,bottom]"
     let re = regex::Regex::new(r"\[(\d+),(\d+)\]\[(\d+),(\d+)\]").unwrap();
@@ -0,0 +1,31 @@
+// src-tauri/src/bin/gen_ts_bindings.rs
+// module: infrastructure | layer: tools | role: TypeScript 绑定生成工具
+// summary: 从登记的事件负载类型生成 TypeScript 定义（每类型一个 .ts + events.ts 映射），默认输出到前端 src/types/bindings
+//
+// 用法: cargo run --bin gen_ts_bindings -- [输出目录]
+
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use employee_gui::infrastructure::event_bindings::export_bindings;
+
+/// 相对 src-tauri 的默认输出目录
+const DEFAULT_OUT_DIR: &str = "../src/types/bindings";
+
+fn main() -> ExitCode {
+    let out_dir = std::env::args()
+        .nth(1)
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(DEFAULT_OUT_DIR));
+
+    match export_bindings(&out_dir) {
+        Ok(types) => {
+            println!("已生成 {} 个事件负载类型 → {}", types.len(), out_dir.display());
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,66 @@
+// src-tauri/src/bin/license_signer.rs
+// module: licensing | layer: tools | role: 授权码签发工具
+// summary: 厂商侧离线签发授权码（Ed25519）；私钥只在本工具读取，客户端应用只内置公钥
+//
+// 用法:
+//   cargo run --bin license_signer -- pubkey <private-key-file>
+//   cargo run --bin license_signer -- sign <private-key-file> <license.json | ->
+//
+// 私钥文件为 hex 编码的 32 字节种子（如 `openssl rand -hex 32 > license.sk`）；
+// 发布构建时把 `pubkey` 的输出设为环境变量 LICENSE_PUBLIC_KEY。
+
+use std::io::Read;
+use std::process::ExitCode;
+
+use ed25519_dalek::{Signer, SigningKey};
+use employee_gui::services::licensing::{license_payload, License};
+
+fn read_signing_key(path: &str) -> Result<SigningKey, String> {
+    let content = std::fs::read_to_string(path).map_err(|e| format!("读取私钥失败 {}: {}", path, e))?;
+    let seed: [u8; 32] = hex::decode(content.trim())
+        .ok()
+        .and_then(|b| b.try_into().ok())
+        .ok_or("私钥格式错误：需要 hex 编码的 32 字节种子")?;
+    Ok(SigningKey::from_bytes(&seed))
+}
+
+fn read_license(path: &str) -> Result<License, String> {
+    let content = if path == "-" {
+        let mut buf = String::new();
+        std::io::stdin().read_to_string(&mut buf).map_err(|e| e.to_string())?;
+        buf
+    } else {
+        std::fs::read_to_string(path).map_err(|e| format!("读取授权内容失败 {}: {}", path, e))?
+    };
+    let license: License = serde_json::from_str(&content).map_err(|e| format!("授权内容解析失败: {}", e))?;
+    if license.machine_ids.is_empty() || license.machine_ids.len() > license.seats as usize {
+        return Err(format!("machine_ids 必须非空且不超过席位数 {}", license.seats));
+    }
+    Ok(license)
+}
+
+fn run(args: &[String]) -> Result<String, String> {
+    match args {
+        [cmd, key] if cmd == "pubkey" => Ok(hex::encode(read_signing_key(key)?.verifying_key().to_bytes())),
+        [cmd, key, license] if cmd == "sign" => {
+            let signing_key = read_signing_key(key)?;
+            let payload = license_payload(&read_license(license)?)?;
+            Ok(format!("{}.{}", payload, hex::encode(signing_key.sign(payload.as_bytes()).to_bytes())))
+        }
+        _ => Err("用法: license_signer pubkey <private-key-file> | sign <private-key-file> <license.json | ->".into()),
+    }
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match run(&args) {
+        Ok(output) => {
+            println!("{}", output);
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            ExitCode::from(2)
+        }
+    }
+}
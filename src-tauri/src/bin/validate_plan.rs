@@ -0,0 +1,49 @@
+// src-tauri/src/bin/validate_plan.rs
+// module: v2-execution | layer: tools | role: Plan 契约校验工具
+// summary: 离线校验 DecisionChainPlan JSON 文件（`-` 读标准输入），逐文件输出 JSON 报告；存在错误时退出码为 1，读取失败为 2
+//
+// 用法: cargo run --bin validate_plan -- plan.json [more.json ...]
+
+use std::io::Read;
+use std::process::ExitCode;
+
+use employee_gui::commands::run_step_v2::plan_contract::validate_plan_json;
+
+fn read_input(path: &str) -> std::io::Result<String> {
+    if path == "-" {
+        let mut buf = String::new();
+        std::io::stdin().read_to_string(&mut buf)?;
+        Ok(buf)
+    } else {
+        std::fs::read_to_string(path)
+    }
+}
+
+fn main() -> ExitCode {
+    let paths: Vec<String> = std::env::args().skip(1).collect();
+    if paths.is_empty() || paths.iter().any(|p| p == "-h" || p == "--help") {
+        eprintln!("用法: validate_plan <plan.json | -> [...]");
+        return ExitCode::from(2);
+    }
+
+    let mut invalid = false;
+    for path in &paths {
+        let raw = match read_input(path) {
+            Ok(raw) => raw,
+            Err(e) => {
+                eprintln!("{}: 读取失败: {}", path, e);
+                return ExitCode::from(2);
+            }
+        };
+        let report = validate_plan_json(&raw);
+        invalid |= !report.valid;
+        let output = serde_json::json!({ "file": path, "report": report });
+        println!("{}", serde_json::to_string_pretty(&output).unwrap_or_default());
+    }
+
+    if invalid {
+        ExitCode::from(1)
+    } else {
+        ExitCode::SUCCESS
+    }
+}
@@ -1,4 +1,4 @@
-use tauri::{command, State, AppHandle, Emitter};
+use tauri::{command, AppHandle, Emitter};
 use futures::{stream, StreamExt};
 use tracing::{info, error};
 use crate::services::smart_app_manager::{SmartAppManager, SmartAppManagerState, AppInfo, AppLaunchResult, PagedApps};
@@ -9,14 +9,13 @@ use crate::services::smart_app::fetch::{list_packages, fetch_app_info};
 /// 获取设备应用列表
 /// filter_mode: "all" | "only_user" | "only_system"
 /// refresh_strategy: "cache_first" | "force_refresh"
-#[command]
 pub async fn get_device_apps(
     device_id: String,
     include_system_apps: Option<bool>, // backward compatibility
     force_refresh: Option<bool>, // backward compatibility
     filter_mode: Option<String>,
     refresh_strategy: Option<String>,
-    state: State<'_, SmartAppManagerState>,
+    state: &SmartAppManagerState,
 ) -> Result<Vec<AppInfo>, String> {
     info!("📱 获取设备 {} 的应用列表", device_id);
     
@@ -38,7 +37,6 @@ pub async fn get_device_apps(
 }
 
 /// 分页获取应用列表
-#[command]
 pub async fn get_device_apps_paged(
     device_id: String,
     filter_mode: Option<String>,
@@ -46,7 +44,7 @@ pub async fn get_device_apps_paged(
     page: Option<u32>,
     page_size: Option<u32>,
     query: Option<String>,
-    state: State<'_, SmartAppManagerState>,
+    state: &SmartAppManagerState,
 ) -> Result<PagedApps, String> {
     info!("📱 分页获取设备 {} 的应用列表", device_id);
     let mut managers = state.managers.lock().await;
@@ -110,11 +108,10 @@ pub async fn get_app_icon(
 }
 
 /// 搜索设备应用
-#[command]
 pub async fn search_device_apps(
     device_id: String,
     query: String,
-    state: State<'_, SmartAppManagerState>,
+    state: &SmartAppManagerState,
 ) -> Result<Vec<AppInfo>, String> {
     info!("🔍 在设备 {} 上搜索应用: {}", device_id, query);
     
@@ -127,11 +124,10 @@ pub async fn search_device_apps(
 }
 
 /// 启动应用
-#[command]
 pub async fn launch_device_app(
     device_id: String,
     package_name: String,
-    state: State<'_, SmartAppManagerState>,
+    state: &SmartAppManagerState,
 ) -> Result<AppLaunchResult, String> {
     info!("🚀 在设备 {} 上启动应用: {}", device_id, package_name);
     
@@ -147,10 +143,9 @@ pub async fn launch_device_app(
 }
 
 /// 获取缓存的应用列表
-#[command]
 pub async fn get_cached_device_apps(
     device_id: String,
-    state: State<'_, SmartAppManagerState>,
+    state: &SmartAppManagerState,
 ) -> Result<Vec<AppInfo>, String> {
     let managers = state.managers.lock().await;
     if let Some(manager) = managers.get(&device_id) {
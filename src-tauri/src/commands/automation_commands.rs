@@ -2,15 +2,76 @@
 // module: exec | layer: application | role: V3 执行命令
 // summary: 三条执行链的统一命令入口
 
+use std::future::Future;
+
 use anyhow::Result;
-use serde_json::Value;
-use tauri::AppHandle;
+use serde_json::{json, Value};
+use tauri::{AppHandle, Manager};
 
+use crate::automation::events::emit_complete;
 use crate::automation::types::*;
+use crate::automation::pipeline::cancellation::{self, V3CancellationRegistry, CANCELLED_ERROR};
 use crate::automation::pipeline::single_step::execute_single_step_internal;
 use crate::automation::pipeline::chain::execute_chain; // 启用 V3 智能链执行引擎
 use crate::automation::pipeline::static_exec::execute_static;
 use crate::exec::helpers::analysis_helpers::truncate_xml_in_json;
+use crate::services::concurrency::{self, WorkRequest};
+use crate::services::execution::timeline;
+use crate::services::failure_annotation::capture_failure_report;
+
+/// 托管的取消登记表（插件 setup 时注册，直接从主进程调用时按需补注册）
+pub fn cancellation_registry(app: &AppHandle) -> V3CancellationRegistry {
+    if let Some(registry) = app.try_state::<V3CancellationRegistry>() {
+        return registry.inner().clone();
+    }
+    app.manage(V3CancellationRegistry::default());
+    app.state::<V3CancellationRegistry>().inner().clone()
+}
+
+/// 以可取消任务运行 V3 执行：登记根令牌、占用设备并记录执行时间线
+///
+/// 取消时丢弃执行 future（设备许可与执行锁随之释放），写入部分 trace 并发送失败的 complete 事件
+async fn run_task<F>(app: &AppHandle, task_id: &str, device_id: &str, kind: &str, fut: F) -> Result<Value, String>
+where
+    F: Future<Output = Result<Value, String>>,
+{
+    let (token, _registration) = cancellation_registry(app).register(task_id, kind, device_id)?;
+    let label = format!("v3 {} {}", kind, task_id);
+    let work = concurrency::run(
+        WorkRequest::device(device_id, "v3", label.clone()),
+        timeline::scope_run(task_id, &label, cancellation::scope(token.clone(), fut)),
+    );
+    let started = std::time::Instant::now();
+    let result = token.guard(work).await;
+    if matches!(&result, Err(e) if cancellation::is_cancelled_error(e)) {
+        let (chain, step) = token.position();
+        tracing::warn!("🛑 [V3] 任务已取消: taskId={}, chain={:?}, step={:?}", task_id, chain, step);
+        let params = json!({ "taskId": task_id, "kind": kind, "chain": chain, "step": step, "cancelled": true });
+        let step_id = step.as_deref().unwrap_or(task_id);
+        if let Some(dir) = capture_failure_report(device_id, step_id, CANCELLED_ERROR, &params, None, None).await {
+            if let Some(trace) = timeline::export_chrome_trace(task_id) {
+                let written = serde_json::to_vec_pretty(&trace)
+                    .map_err(|e| e.to_string())
+                    .and_then(|bytes| std::fs::write(dir.join("timeline.json"), bytes).map_err(|e| e.to_string()));
+                if let Err(e) = written {
+                    tracing::warn!("⚠️ [V3] 写入取消时间线失败: {}", e);
+                }
+            }
+        }
+        let _ = emit_complete(
+            app,
+            Some(task_id.to_string()),
+            Some(Summary {
+                adopted_step_id: None,
+                elapsed_ms: Some(started.elapsed().as_millis() as u64),
+                reason: Some("用户取消".to_string()),
+            }),
+            None,
+            Some(ResultPayload { ok: false, coords: None, candidate_count: None, screen_hash_now: None, validation: None }),
+        );
+    }
+    result
+}
 
 /// 执行智能单步测试（V3）
 #[tauri::command]
@@ -25,10 +86,9 @@ pub async fn execute_single_step_test_v3(
     };
     
     tracing::info!("🧪 [V3] 收到智能单步测试请求: stepId={}", step_id);
-    
-    execute_single_step_internal(&app, &envelope, step)
-        .await
-        .map_err(|e| e.to_string())
+
+    let task_id = envelope.snapshot.analysis_id.clone().unwrap_or(step_id);
+    run_task(&app, &task_id, &envelope.device_id, "step", execute_single_step_internal(&app, &envelope, step)).await
 }
 
 /// 执行智能自动链测试（V3）
@@ -73,11 +133,14 @@ pub async fn execute_chain_test_v3(
         analysis_id, steps_count, threshold
     );
     
-    let result = execute_chain(&app, &envelope, &parsed_spec)
-        .await
-        .map_err(|e| e.to_string())?;
-    
-    serde_json::to_value(&result).map_err(|e| e.to_string())
+    let task_id = analysis_id
+        .or_else(|| envelope.snapshot.analysis_id.clone())
+        .unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+    run_task(&app, &task_id, &envelope.device_id, "chain", async {
+        execute_chain(&app, &envelope, &parsed_spec).await?;
+        Ok(Value::Null)
+    })
+    .await
 }
 
 /// 执行静态策略测试（V3）
@@ -87,25 +150,31 @@ pub async fn execute_static_strategy_test_v3(
     envelope: ContextEnvelope,
     spec: StaticSpecV3,
 ) -> Result<Value, String> {
-    let strategy_info = match &spec {
+    let (strategy_info, strategy_key) = match &spec {
         StaticSpecV3::ByRef { script_id, static_step_id, .. } => {
-            format!("scriptId={}, stepId={}", script_id, static_step_id)
+            (format!("scriptId={}, stepId={}", script_id, static_step_id), Some(static_step_id.clone()))
         }
         StaticSpecV3::ByInline { strategy_id, .. } => {
-            format!("strategyId={:?} (inline)", strategy_id)
+            (format!("strategyId={:?} (inline)", strategy_id), strategy_id.clone())
         }
     };
     
     tracing::info!("🎯 [V3] 收到静态策略测试请求: {}", strategy_info);
     
-    let result = execute_static(&app, &envelope, &spec)
-        .await
-        .map_err(|e| e.to_string())?;
-    
-    serde_json::to_value(&result).map_err(|e| e.to_string())
+    let task_id = envelope
+        .snapshot
+        .analysis_id
+        .clone()
+        .or(strategy_key)
+        .unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+    run_task(&app, &task_id, &envelope.device_id, "static", async {
+        execute_static(&app, &envelope, &spec).await?;
+        Ok(Value::Null)
+    })
+    .await
 }
 
-/// 可选：统一任务入口（根据 kind 路由）
+/// 可选：统一任务入口（根据 kind 路由，各分支自行登记可取消任务）
 #[tauri::command]
 pub async fn execute_task_v3(
     app: AppHandle,
@@ -117,9 +186,10 @@ pub async fn execute_task_v3(
             tracing::info!("📍 [V3] 任务路由 → 智能单步");
             execute_single_step_test_v3(app, envelope, step).await
         }
-        TaskV3::Chain { spec: _ } => {
-            tracing::info!("📍 [V3] 任务路由 → 智能自动链 (暂时禁用)");
-            Err("Chain execution temporarily disabled for refactoring".to_string())
+        TaskV3::Chain { spec } => {
+            tracing::info!("📍 [V3] 任务路由 → 智能自动链");
+            let spec = serde_json::to_value(&spec).map_err(|e| e.to_string())?;
+            execute_chain_test_v3(app, envelope, spec).await
         }
         TaskV3::Static { spec } => {
             tracing::info!("📍 [V3] 任务路由 → 静态策略");
@@ -7,19 +7,27 @@ use std::sync::Arc;
 
 use crate::services::lead_hunt::{RawComment, ReplayPlan, save_comments, list_comments, write_replay_plan, get_replay_plan};
 use crate::device::{MockDumpProvider, ReplayOrchestrator};
+use crate::services::marketing_storage::facade::MarketingStorageFacade;
+use crate::services::marketing_storage::task_queue::{QueueWeights, QueuedTask};
+use crate::services::marketing_storage::campaign_control::{CampaignControlState, CampaignRunStatus, CampaignTimelineEntry};
+use crate::services::protected_accounts::{ProtectedAccount, ProtectedAccountStore, ProtectedImportReport, ProtectedSkipRecord};
 
 #[tauri::command]
 pub async fn lh_save_comments(app_handle: AppHandle, items: Vec<RawComment>) -> Result<(), String> {
-    save_comments(&app_handle, items).map_err(|e| e.to_string())
+    crate::core::plugin_isolation::guard("lead_hunt", async move { save_comments(&app_handle, items).map_err(|e| e.to_string()) }).await
 }
 
 #[tauri::command]
 pub async fn lh_list_comments(app_handle: AppHandle) -> Result<Vec<RawComment>, String> {
-    list_comments(&app_handle).map_err(|e| e.to_string())
+    crate::core::plugin_isolation::guard("lead_hunt", async move { list_comments(&app_handle).map_err(|e| e.to_string()) }).await
 }
 
 #[tauri::command]
 pub async fn lh_import_comments(app_handle: AppHandle) -> Result<(), String> {
+    crate::core::plugin_isolation::guard("lead_hunt", lh_import_comments_impl(app_handle)).await
+}
+
+async fn lh_import_comments_impl(app_handle: AppHandle) -> Result<(), String> {
     let mock = include_str!("../mock/social_comments.json");
     let items: Vec<RawComment> = serde_json::from_str(mock).map_err(|e| e.to_string())?;
     save_comments(&app_handle, items).map_err(|e| e.to_string())
@@ -27,11 +35,15 @@ pub async fn lh_import_comments(app_handle: AppHandle) -> Result<(), String> {
 
 #[tauri::command]
 pub async fn lh_create_replay_plan(app_handle: AppHandle, plan: ReplayPlan) -> Result<(), String> {
-    write_replay_plan(&app_handle, plan).map_err(|e| e.to_string())
+    crate::core::plugin_isolation::guard("lead_hunt", async move { write_replay_plan(&app_handle, plan).map_err(|e| e.to_string()) }).await
 }
 
 #[tauri::command]
 pub async fn lh_run_replay_plan(app_handle: AppHandle, plan_id: String) -> Result<(), String> {
+    crate::core::plugin_isolation::guard("lead_hunt", lh_run_replay_plan_impl(app_handle, plan_id)).await
+}
+
+async fn lh_run_replay_plan_impl(app_handle: AppHandle, plan_id: String) -> Result<(), String> {
     // 1. 读取计划
     let plan = get_replay_plan(&app_handle, &plan_id).map_err(|e| e.to_string())?;
 
@@ -75,6 +87,16 @@ pub async fn lh_analyze_comments(
     batch_id: String,
     concurrency: Option<u32>,
     max_retries: Option<u32>,
+) -> Result<(), String> {
+    crate::core::plugin_isolation::guard("lead_hunt", lh_analyze_comments_impl(app_handle, comment_ids, batch_id, concurrency, max_retries)).await
+}
+
+async fn lh_analyze_comments_impl(
+    app_handle: AppHandle,
+    comment_ids: Vec<String>,
+    batch_id: String,
+    concurrency: Option<u32>,
+    max_retries: Option<u32>,
 ) -> Result<(), String> {
     use crate::services::batch_analysis::{BatchAnalysisService, BatchAnalysisRequest};
     
@@ -135,3 +157,169 @@ pub async fn lh_get_stats(app_handle: AppHandle) -> Result<serde_json::Value, St
         }
     }))
 }
+
+// ==================== 目标优先队列 ====================
+
+
+/// 预览活动接下来会执行的 n 个目标（置顶优先，其次按综合分）
+#[tauri::command]
+pub async fn preview_next_targets(
+    app_handle: AppHandle,
+    campaign_id: Option<String>,
+    n: Option<usize>,
+    weights: Option<QueueWeights>,
+) -> Result<Vec<QueuedTask>, String> {
+    crate::core::plugin_isolation::guard("lead_hunt", preview_next_targets_impl(app_handle, campaign_id, n, weights)).await
+}
+
+async fn preview_next_targets_impl(
+    app_handle: AppHandle,
+    campaign_id: Option<String>,
+    n: Option<usize>,
+    weights: Option<QueueWeights>,
+) -> Result<Vec<QueuedTask>, String> {
+    crate::services::telemetry::record_feature("target_queue.preview");
+    MarketingStorageFacade::preview_next_targets(&app_handle, campaign_id.as_deref(), n.unwrap_or(10), weights)
+}
+
+/// 立即重算队列评分，返回评分的任务数
+#[tauri::command]
+pub async fn rescore_target_queue(
+    app_handle: AppHandle,
+    campaign_id: Option<String>,
+    weights: Option<QueueWeights>,
+) -> Result<usize, String> {
+    crate::core::plugin_isolation::guard("lead_hunt", async move { MarketingStorageFacade::rescore_target_queue(&app_handle, campaign_id.as_deref(), weights) }).await
+}
+
+#[tauri::command]
+pub async fn pin_target_to_top(app_handle: AppHandle, task_id: String, pinned: Option<bool>) -> Result<(), String> {
+    crate::core::plugin_isolation::guard("lead_hunt", async move { MarketingStorageFacade::pin_target_to_top(&app_handle, &task_id, pinned.unwrap_or(true)) }).await
+}
+
+#[tauri::command]
+pub async fn set_task_lead_score(app_handle: AppHandle, task_id: String, lead_score: Option<f64>) -> Result<(), String> {
+    crate::core::plugin_isolation::guard("lead_hunt", async move { MarketingStorageFacade::set_task_lead_score(&app_handle, &task_id, lead_score) }).await
+}
+
+// ==================== 活动暂停 / 恢复 ====================
+
+/// 请求暂停活动：停止派发，等待在途任务完成当前原子动作；超过排空期限后强制落地
+#[tauri::command]
+pub async fn pause_campaign(
+    app_handle: AppHandle,
+    campaign_id: String,
+    drain_seconds: Option<i64>,
+    reason: Option<String>,
+) -> Result<CampaignControlState, String> {
+    crate::core::plugin_isolation::guard("lead_hunt", pause_campaign_impl(app_handle, campaign_id, drain_seconds, reason)).await
+}
+
+async fn pause_campaign_impl(
+    app_handle: AppHandle,
+    campaign_id: String,
+    drain_seconds: Option<i64>,
+    reason: Option<String>,
+) -> Result<CampaignControlState, String> {
+    let state = MarketingStorageFacade::pause_campaign(&app_handle, &campaign_id, drain_seconds, reason.as_deref())?;
+    if state.status == CampaignRunStatus::Draining {
+        let deadline = drain_seconds.filter(|s| *s > 0).unwrap_or(crate::services::marketing_storage::campaign_control::DEFAULT_DRAIN_SECONDS);
+        tauri::async_runtime::spawn(async move {
+            tokio::time::sleep(std::time::Duration::from_secs(deadline as u64 + 1)).await;
+            if let Err(e) = MarketingStorageFacade::enforce_campaign_drain(&app_handle, &campaign_id) {
+                tracing::warn!("活动 {} 排空期限处理失败: {}", campaign_id, e);
+            }
+        });
+    }
+    Ok(state)
+}
+
+/// 恢复活动：从暂停检查点继续，并恢复频控退避
+#[tauri::command]
+pub async fn resume_campaign(app_handle: AppHandle, campaign_id: String) -> Result<CampaignControlState, String> {
+    crate::core::plugin_isolation::guard("lead_hunt", async move { MarketingStorageFacade::resume_campaign(&app_handle, &campaign_id) }).await
+}
+
+#[tauri::command]
+pub async fn get_campaign_state(app_handle: AppHandle, campaign_id: String) -> Result<CampaignControlState, String> {
+    crate::core::plugin_isolation::guard("lead_hunt", async move { MarketingStorageFacade::get_campaign_state(&app_handle, &campaign_id) }).await
+}
+
+#[tauri::command]
+pub async fn list_campaign_timeline(
+    app_handle: AppHandle,
+    campaign_id: String,
+    limit: Option<usize>,
+) -> Result<Vec<CampaignTimelineEntry>, String> {
+    crate::core::plugin_isolation::guard("lead_hunt", async move { MarketingStorageFacade::list_campaign_timeline(&app_handle, &campaign_id, limit.unwrap_or(50)) }).await
+}
+
+/// 执行器在原子动作之间轮询：返回 true 时应完成当前动作后立即上报结果
+#[tauri::command]
+pub async fn campaign_should_yield(app_handle: AppHandle, task_id: String) -> Result<bool, String> {
+    crate::core::plugin_isolation::guard("lead_hunt", async move { MarketingStorageFacade::campaign_should_yield(&app_handle, &task_id) }).await
+}
+
+// ==================== 受保护账号名单 ====================
+
+#[tauri::command]
+pub async fn list_protected_accounts() -> Result<Vec<ProtectedAccount>, String> {
+    crate::core::plugin_isolation::guard("lead_hunt", list_protected_accounts_impl()).await
+}
+
+async fn list_protected_accounts_impl() -> Result<Vec<ProtectedAccount>, String> {
+    let store = ProtectedAccountStore::global().lock().map_err(|e| e.to_string())?;
+    Ok(store.list())
+}
+
+#[tauri::command]
+pub async fn add_protected_account(
+    platform: String,
+    account: String,
+    reason: Option<String>,
+    added_by: Option<String>,
+) -> Result<ProtectedAccount, String> {
+    crate::core::plugin_isolation::guard("lead_hunt", add_protected_account_impl(platform, account, reason, added_by)).await
+}
+
+async fn add_protected_account_impl(
+    platform: String,
+    account: String,
+    reason: Option<String>,
+    added_by: Option<String>,
+) -> Result<ProtectedAccount, String> {
+    let mut store = ProtectedAccountStore::global().lock().map_err(|e| e.to_string())?;
+    store.add(&platform, &account, reason.as_deref().unwrap_or(""), added_by.as_deref().unwrap_or("manual"))
+}
+
+#[tauri::command]
+pub async fn remove_protected_account(platform: String, account: String) -> Result<bool, String> {
+    crate::core::plugin_isolation::guard("lead_hunt", remove_protected_account_impl(platform, account)).await
+}
+
+async fn remove_protected_account_impl(platform: String, account: String) -> Result<bool, String> {
+    let mut store = ProtectedAccountStore::global().lock().map_err(|e| e.to_string())?;
+    store.remove(&platform, &account)
+}
+
+/// 批量导入（每行 `平台,账号[,原因]`，平台写 `*` 表示全平台）
+#[tauri::command]
+pub async fn import_protected_accounts(content: String, added_by: Option<String>) -> Result<ProtectedImportReport, String> {
+    crate::core::plugin_isolation::guard("lead_hunt", import_protected_accounts_impl(content, added_by)).await
+}
+
+async fn import_protected_accounts_impl(content: String, added_by: Option<String>) -> Result<ProtectedImportReport, String> {
+    let mut store = ProtectedAccountStore::global().lock().map_err(|e| e.to_string())?;
+    store.bulk_import(&content, added_by.as_deref().unwrap_or("import"))
+}
+
+/// 最近因命中名单而跳过的外发动作
+#[tauri::command]
+pub async fn list_protected_skips(limit: Option<usize>) -> Result<Vec<ProtectedSkipRecord>, String> {
+    crate::core::plugin_isolation::guard("lead_hunt", list_protected_skips_impl(limit)).await
+}
+
+async fn list_protected_skips_impl(limit: Option<usize>) -> Result<Vec<ProtectedSkipRecord>, String> {
+    let store = ProtectedAccountStore::global().lock().map_err(|e| e.to_string())?;
+    Ok(store.recent_skips(limit.unwrap_or(200)))
+}
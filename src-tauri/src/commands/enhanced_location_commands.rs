@@ -5,11 +5,12 @@
 
 use crate::services::execution::matching::{
     EnhancedElementMatcher, EnhancedMatchingConfig, AttributeWeights,
-    SmartXPathGenerator, XPathCandidate, ElementAttributes
+    SmartXPathGenerator, XPathCandidate, ElementAttributes, XPathValidation
 };
 use std::collections::HashMap;
 use serde::{Deserialize, Serialize};
 use tauri::State;
+use crate::core::app_services::SharedAppServices;
 use std::sync::Mutex;
 
 /// 前端增强匹配配置
@@ -165,6 +166,14 @@ pub async fn match_element_enhanced(
     target_criteria: HashMap<String, String>,
     device_id: String,
     config: Option<FrontendEnhancedConfig>,
+) -> Result<FrontendMatchResult, String> {
+    crate::core::plugin_isolation::guard("enhanced_location", match_element_enhanced_impl(target_criteria, device_id, config)).await
+}
+
+async fn match_element_enhanced_impl(
+    target_criteria: HashMap<String, String>,
+    device_id: String,
+    config: Option<FrontendEnhancedConfig>,
 ) -> Result<FrontendMatchResult, String> {
     let backend_config = convert_frontend_config(config);
     let matcher = EnhancedElementMatcher::new(backend_config);
@@ -182,9 +191,9 @@ pub async fn match_element_enhanced(
 #[tauri::command]
 pub fn generate_xpath_candidates(
     attributes: ElementAttributes,
-    xpath_generator_state: State<XPathGeneratorState>,
+    services: State<'_, SharedAppServices>,
 ) -> Result<Vec<FrontendXPathCandidate>, String> {
-    let generator = xpath_generator_state.lock().map_err(|e| {
+    let generator = services.xpath_generator.lock().map_err(|e| {
         format!("无法获取 XPath 生成器锁: {}", e)
     })?;
 
@@ -201,9 +210,9 @@ pub fn generate_xpath_candidates(
 #[tauri::command]
 pub fn generate_best_xpath(
     attributes: ElementAttributes,
-    xpath_generator_state: State<XPathGeneratorState>,
+    services: State<'_, SharedAppServices>,
 ) -> Result<Option<FrontendXPathCandidate>, String> {
-    let generator = xpath_generator_state.lock().map_err(|e| {
+    let generator = services.xpath_generator.lock().map_err(|e| {
         format!("无法获取 XPath 生成器锁: {}", e)
     })?;
 
@@ -211,17 +220,50 @@ pub fn generate_best_xpath(
     Ok(best_candidate.map(convert_xpath_candidate))
 }
 
-/// 验证 XPath 语法
+/// 验证 XPath：只给 xpath 时检查语法；指定缓存 XML 文件时还要求在该页面上至少命中一个节点
 #[tauri::command]
 pub fn validate_xpath(
     xpath: String,
-    xpath_generator_state: State<XPathGeneratorState>,
+    xml_cache_file: Option<String>,
+    services: State<'_, SharedAppServices>,
 ) -> Result<bool, String> {
-    let generator = xpath_generator_state.lock().map_err(|e| {
+    let generator = services.xpath_generator.lock().map_err(|e| {
+        format!("无法获取 XPath 生成器锁: {}", e)
+    })?;
+
+    match xml_cache_file {
+        Some(file_name) => {
+            let xml_content = read_cached_xml(&file_name)?;
+            Ok(generator.validate_xpath_against_xml(&xpath, &xml_content).valid)
+        }
+        None => Ok(generator.validate_xpath(&xpath)),
+    }
+}
+
+/// 在缓存 XML（文件名或直接传入的内容）上求值 XPath，返回命中节点明细
+#[tauri::command]
+pub fn evaluate_xpath(
+    xpath: String,
+    xml_cache_file: Option<String>,
+    xml_content: Option<String>,
+    services: State<'_, SharedAppServices>,
+) -> Result<XPathValidation, String> {
+    let xml_content = match (xml_content, xml_cache_file) {
+        (Some(content), _) => content,
+        (None, Some(file_name)) => read_cached_xml(&file_name)?,
+        (None, None) => return Err("需要提供 xmlCacheFile 或 xmlContent".to_string()),
+    };
+    let generator = services.xpath_generator.lock().map_err(|e| {
         format!("无法获取 XPath 生成器锁: {}", e)
     })?;
 
-    Ok(generator.validate_xpath(&xpath))
+    Ok(generator.validate_xpath_against_xml(&xpath, &xml_content))
+}
+
+/// 读取 XML 缓存目录中的页面快照
+fn read_cached_xml(file_name: &str) -> Result<String, String> {
+    let path = crate::modules::xml_cache::get_debug_xml_dir().join(file_name);
+    std::fs::read_to_string(&path).map_err(|e| format!("读取XML缓存文件失败: {} - {}", file_name, e))
 }
 
 /// 更新 XPath 策略成功率
@@ -229,9 +271,9 @@ pub fn validate_xpath(
 pub fn update_xpath_strategy_success_rate(
     strategy: String,
     success: bool,
-    xpath_generator_state: State<XPathGeneratorState>,
+    services: State<'_, SharedAppServices>,
 ) -> Result<(), String> {
-    let mut generator = xpath_generator_state.lock().map_err(|e| {
+    let mut generator = services.xpath_generator.lock().map_err(|e| {
         format!("无法获取 XPath 生成器锁: {}", e)
     })?;
 
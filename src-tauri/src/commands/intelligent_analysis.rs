@@ -431,23 +431,76 @@ async fn execute_analysis_workflow(
 
     emit_progress(&app_handle, &job_id, 25, "解析页面结构").await;
 
-    // 2. 构建 V3 请求
+    // 2. 空闲时预计算过的候选（快照未变化）直接复用
+    let precomputed = config.step_id.as_deref().and_then(|step_id| {
+        crate::services::selector_precompute::lookup(
+            step_id,
+            &config.element_context.snapshot_id,
+            &config.element_context.element_path,
+            &xml_content,
+        )
+    });
+    let result = match precomputed {
+        Some(mut cached) => {
+            tracing::info!("⚡ 命中预计算候选: step_id={:?}", config.step_id);
+            cached.selection_hash = selection_hash.clone();
+            cached
+        }
+        None => {
+            emit_progress(&app_handle, &job_id, 65, "生成智能策略").await;
+            analyze_selection(&job_id, xml_content, &config.element_context, &selection_hash, config.step_id.clone()).await?
+        }
+    };
+    let best_candidate = result.fallback_strategy.clone();
+
+    emit_progress(&app_handle, &job_id, 85, "评估策略质量").await;
+
+    emit_progress(&app_handle, &job_id, 100, "分析完成").await;
+    
+    tracing::info!(
+        "✅ 分析完成: job_id={}, 推荐策略={}, 置信度={:.1}%", 
+        job_id, result.recommended_key, result.recommended_confidence * 100.0
+    );
+    
+    // 🆕 发送增强的完成事件 (包含置信度和证据)
+    emit_and_trace(&app_handle, "analysis:done", &AnalysisDoneEvent {
+        job_id: job_id.clone(),
+        selection_hash: selection_hash.clone(),
+        result,
+        confidence: best_candidate.confidence,
+        evidence: Evidence::default(), // 简化处理
+        origin: "single".to_string(), // 单步分析
+        element_uid: Some(config.element_context.element_path.clone()),
+        card_id: config.step_id.clone(),
+    }).map_err(|e| e.to_string())?;
+    
+    Ok(())
+}
+
+/// 对快照中选中的元素生成策略候选（Step 0-6），编辑器分析与空闲预计算共用
+pub(crate) async fn analyze_selection(
+    analysis_id: &str,
+    xml_content: String,
+    element_context: &ElementSelectionContext,
+    selection_hash: &str,
+    step_id: Option<String>,
+) -> Result<AnalysisResult, String> {
     let request = IntelligentAnalysisRequest {
-        analysis_id: job_id.clone(),
+        analysis_id: analysis_id.to_string(),
         device_id: "unknown".to_string(),
         ui_xml_content: xml_content,
         user_selection: Some(UserSelectionContext {
-            selected_xpath: config.element_context.element_path.clone(),
-            bounds: config.element_context.element_bounds.clone(),
-            text: config.element_context.element_text.clone(),
-            resource_id: config.element_context.key_attributes.as_ref().and_then(|m| m.get("resource-id").cloned()),
-            class_name: config.element_context.key_attributes.as_ref().and_then(|m| m.get("class").cloned()),
-            content_desc: config.element_context.key_attributes.as_ref().and_then(|m| m.get("content-desc").cloned()),
+            selected_xpath: element_context.element_path.clone(),
+            bounds: element_context.element_bounds.clone(),
+            text: element_context.element_text.clone(),
+            resource_id: element_context.key_attributes.as_ref().and_then(|m| m.get("resource-id").cloned()),
+            class_name: element_context.key_attributes.as_ref().and_then(|m| m.get("class").cloned()),
+            content_desc: element_context.key_attributes.as_ref().and_then(|m| m.get("content-desc").cloned()),
             ancestors: vec![], 
-            children_texts: config.element_context.children_texts.clone().unwrap_or_default(),
+            children_texts: element_context.children_texts.clone().unwrap_or_default(),
             i18n_variants: None,
             // ✅ 传递 index_path
-            index_path: config.element_context.index_path.clone(),
+            index_path: element_context.index_path.clone(),
             match_mode: None, // 🆕 初始化 match_mode
         }),
         target_element_hint: None,
@@ -456,15 +509,11 @@ async fn execute_analysis_workflow(
         min_confidence: 0.5,
     };
 
-    emit_progress(&app_handle, &job_id, 65, "生成智能策略").await;
-
-    // 3. 调用新服务 (mock_intelligent_analysis 已包含 UnifiedMatchService)
+    // 调用新服务 (mock_intelligent_analysis 已包含 UnifiedMatchService)
     let service_result = intelligent_analysis_service::mock_intelligent_analysis(request).await
         .map_err(|e| format!("智能分析服务失败: {}", e))?;
 
-    emit_progress(&app_handle, &job_id, 85, "评估策略质量").await;
-
-    // 4. 转换结果为 V2 格式 (AnalysisResult)
+    // 转换结果为 V2 格式 (AnalysisResult)
     let smart_candidates: Vec<StrategyCandidate> = service_result.candidates.iter().map(|c| {
         StrategyCandidate {
             key: c.strategy.clone(),
@@ -496,36 +545,15 @@ async fn execute_analysis_workflow(
         enabled: true, is_recommended: true, selection_mode: None, batch_config: None, structural_signatures: None,
     });
 
-    let result = AnalysisResult {
-        selection_hash: selection_hash.clone(),
-        step_id: config.step_id.clone(),
+    Ok(AnalysisResult {
+        selection_hash: selection_hash.to_string(),
+        step_id,
         smart_candidates,
         static_candidates: vec![], // 暂时为空
         recommended_key: best_candidate.key.clone(),
         recommended_confidence: best_candidate.confidence,
-        fallback_strategy: best_candidate.clone(),
-    };
-
-    emit_progress(&app_handle, &job_id, 100, "分析完成").await;
-    
-    tracing::info!(
-        "✅ 分析完成: job_id={}, 推荐策略={}, 置信度={:.1}%", 
-        job_id, result.recommended_key, result.recommended_confidence * 100.0
-    );
-    
-    // 🆕 发送增强的完成事件 (包含置信度和证据)
-    emit_and_trace(&app_handle, "analysis:done", &AnalysisDoneEvent {
-        job_id: job_id.clone(),
-        selection_hash: selection_hash.clone(),
-        result,
-        confidence: best_candidate.confidence,
-        evidence: Evidence::default(), // 简化处理
-        origin: "single".to_string(), // 单步分析
-        element_uid: Some(config.element_context.element_path.clone()),
-        card_id: config.step_id.clone(),
-    }).map_err(|e| e.to_string())?;
-    
-    Ok(())
+        fallback_strategy: best_candidate,
+    })
 }
 
 /// 发送进度事件
@@ -179,7 +179,7 @@ async fn execute_long_press_action(
 ) -> Result<String, String> {
     let (x, y) = calculate_coords(step, match_candidate);
     
-    let duration = step.get("duration")
+    let duration = step.get("duration_ms")
         .and_then(|v| v.as_u64())
         .unwrap_or(2000) as u32;
     
@@ -222,7 +222,7 @@ async fn execute_swipe_action(
     let start_y = step.get("start_y").and_then(|v| v.as_i64()).unwrap_or(1200) as i32;
     let end_x = step.get("end_x").and_then(|v| v.as_i64()).unwrap_or(540) as i32;
     let end_y = step.get("end_y").and_then(|v| v.as_i64()).unwrap_or(600) as i32;
-    let duration = step.get("duration").and_then(|v| v.as_u64()).unwrap_or(300) as u32;
+    let duration = step.get("duration_ms").and_then(|v| v.as_u64()).unwrap_or(300) as u32;
     
     tracing::info!("🎯 执行坐标滑动: ({},{}) → ({},{}) 时长:{}ms", start_x, start_y, end_x, end_y, duration);
     
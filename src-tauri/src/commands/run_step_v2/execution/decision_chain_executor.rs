@@ -5,6 +5,7 @@
 use tauri::AppHandle;
 use super::super::{DecisionChainPlan, ExecutionEnvironment, StrategyVariant, VariantKind, VariantSelectors, SelfSelector};
 use crate::services::adb::AdbService;
+use super::super::validation::post_assertions::verify_on_device;
 use crate::engine::{FallbackController, XmlIndexer, strategy_plugin::StrategyRegistry};
 
 /// 执行插件化决策链
@@ -48,7 +49,18 @@ pub async fn run_decision_chain_v2(
         .map_err(|e| format!("决策链执行失败: {}", e))?;
     
     // 7. 包装返回结果
-    let response = build_response(&result, &env, &plan, &registry);
+    let mut response = build_response(&result, &env, &plan, &registry);
+    
+    // 8. 后置断言（仅在执行成功后基于新鲜Dump评估）
+    if let Some(assertions) = plan.strategy.post_assertions.as_ref().filter(|a| !a.is_empty()) {
+        if result.success {
+            let report = verify_on_device(&device_id, assertions).await?;
+            response["verify_passed"] = serde_json::json!(report.passed());
+            append_raw_logs(&mut response, report.log_lines());
+        } else {
+            response["verify_passed"] = serde_json::json!(false);
+        }
+    }
     
     if result.success {
         tracing::info!("✅ 决策链执行成功: {} 在 {}ms", result.used_variant, result.execution_time_ms);
@@ -136,6 +148,14 @@ fn build_response(
     })
 }
 
+/// 追加到已有的 raw_logs 之后，不覆盖执行阶段写入的日志
+fn append_raw_logs(response: &mut serde_json::Value, lines: Vec<String>) {
+    match response.get_mut("raw_logs").and_then(|v| v.as_array_mut()) {
+        Some(existing) => existing.extend(lines.into_iter().map(serde_json::Value::String)),
+        None => response["raw_logs"] = serde_json::json!(lines),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -154,6 +174,14 @@ mod tests {
         assert_eq!(response["success"], true);
         assert_eq!(response["telemetry"]["plan_version"], "v2");
     }
+
+    #[test]
+    fn test_append_raw_logs_keeps_existing_lines() {
+        let mut response = serde_json::json!({ "success": true });
+        append_raw_logs(&mut response, vec!["tap ok".to_string()]);
+        append_raw_logs(&mut response, vec!["exists(text=\"已关注\") ✓".to_string()]);
+        assert_eq!(response["raw_logs"], serde_json::json!(["tap ok", "exists(text=\"已关注\") ✓"]));
+    }
 }
 
 
@@ -2,8 +2,9 @@
 // module: step-execution | layer: matching | role: 坐标命中测试
 // summary: 坐标兜底策略 - 对指定坐标进行hit-test，找到最小覆盖节点
 
-use super::super::{RunStepRequestV2, MatchCandidate};  // 引用 mod.rs 中的运行时类型
-use super::super::validation::{parse_xml_attribute, parse_bounds_from_string, check_fullscreen_node, check_container_node};
+use super::super::{RunStepRequestV2, MatchCandidate, Bounds};  // 引用 mod.rs 中的运行时类型
+use super::super::validation::{check_fullscreen_node, check_container_node};
+use crate::engine::ui_tree::{Attr, UiTree};
 
 /// 坐标兜底：对指定坐标进行hit-test，找到最小覆盖节点
 pub async fn coord_fallback_hit_test(ui_xml: &str, req: &RunStepRequestV2) -> Result<MatchCandidate, String> {
@@ -23,52 +24,46 @@ pub async fn coord_fallback_hit_test(ui_xml: &str, req: &RunStepRequestV2) -> Re
     let mut best_candidate: Option<MatchCandidate> = None;
     let mut smallest_area = i64::MAX;
     
-    let node_regex = regex::Regex::new(r#"<node[^>]*>"#).unwrap();
+    let tree = UiTree::parse_lenient(ui_xml);
     
-    for node_match in node_regex.find_iter(ui_xml) {
-        let node_str = node_match.as_str();
+    for node in tree.nodes() {
+        let Some((node_left, node_top, node_right, node_bottom)) = node.bounds() else { continue };
+        // 检查点是否在节点内
+        if center_x < node_left || center_x > node_right || center_y < node_top || center_y > node_bottom {
+            continue;
+        }
+        
+        let area = ((node_right - node_left) as i64) * ((node_bottom - node_top) as i64);
         
-        if let Some(bounds_str) = parse_xml_attribute(node_str, "bounds") {
-            if let Ok(node_bounds) = parse_bounds_from_string(&bounds_str) {
-                // 检查点是否在节点内
-                if center_x >= node_bounds.left && center_x <= node_bounds.right &&
-                   center_y >= node_bounds.top && center_y <= node_bounds.bottom {
-                    
-                    let area = ((node_bounds.right - node_bounds.left) as i64) * 
-                              ((node_bounds.bottom - node_bounds.top) as i64);
-                    
-                    // 选择面积最小的节点（最精确的匹配）
-                    if area < smallest_area {
-                        let class_name = parse_xml_attribute(node_str, "class");
-                        
-                        // 🛡️ 安全检查：拒绝整屏或容器类节点
-                        if check_fullscreen_node(&(node_bounds.left, node_bounds.top, node_bounds.right, node_bounds.bottom)) {
-                            tracing::warn!("🚫 Hit-Test命中整屏节点，跳过");
-                            continue;
-                        }
-                        
-                        if check_container_node(&class_name) {
-                            tracing::warn!("🚫 Hit-Test命中容器节点: {:?}，跳过", class_name);
-                            continue;
-                        }
-                        
-                        smallest_area = area;
-                        tracing::debug!("🎯 Hit-Test更新候选: 面积={}, 类名={:?}", area, &class_name);
-                        tracing::info!("✅ 自测坐标Hit-Test: leaf={:?} 面积={} 坐标=({},{})", 
-                                      &class_name, area, center_x, center_y);
-                        
-                        best_candidate = Some(MatchCandidate {
-                            id: format!("hit_test_{}", center_x),
-                            score: 0.75, // 坐标兜底给保守分数
-                            confidence: 0.75,
-                            bounds: node_bounds,
-                            text: parse_xml_attribute(node_str, "text"),
-                            class_name,
-                            package_name: parse_xml_attribute(node_str, "package"),
-                        });
-                    }
-                }
+        // 选择面积最小的节点（最精确的匹配）
+        if area < smallest_area {
+            let class_name = node.class_name().map(str::to_string);
+            
+            // 🛡️ 安全检查：拒绝整屏或容器类节点
+            if check_fullscreen_node(&(node_left, node_top, node_right, node_bottom)) {
+                tracing::warn!("🚫 Hit-Test命中整屏节点，跳过");
+                continue;
+            }
+            
+            if check_container_node(&class_name) {
+                tracing::warn!("🚫 Hit-Test命中容器节点: {:?}，跳过", class_name);
+                continue;
             }
+            
+            smallest_area = area;
+            tracing::debug!("🎯 Hit-Test更新候选: 面积={}, 类名={:?}", area, &class_name);
+            tracing::info!("✅ 自测坐标Hit-Test: leaf={:?} 面积={} 坐标=({},{})", 
+                          &class_name, area, center_x, center_y);
+            
+            best_candidate = Some(MatchCandidate {
+                id: format!("hit_test_{}", center_x),
+                score: 0.75, // 坐标兜底给保守分数
+                confidence: 0.75,
+                bounds: Bounds { left: node_left, top: node_top, right: node_right, bottom: node_bottom },
+                text: node.attr(Attr::Text).map(str::to_string),
+                class_name,
+                package_name: node.attr(Attr::Package).map(str::to_string),
+            });
         }
     }
     
@@ -0,0 +1,283 @@
+// src-tauri/src/commands/run_step_v2/matching/matching_config.rs
+// module: step-execution | layer: matching | role: 匹配参数配置
+// summary: 三态评分权重、奖惩项与安全闸门阈值外置 - 启动时加载配置文件，可热重载，校验失败回退内置默认值
+
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use once_cell::sync::Lazy;
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tracing::{info, warn};
+
+/// 配置文件名（位于数据目录 employee-gui 下）
+const CONFIG_FILE_NAME: &str = "matching_config.json";
+
+/// 内置默认配置（带版本号），配置文件缺失或无效时使用
+const DEFAULT_CONFIG_JSON: &str = include_str!("matching_config.default.json");
+
+static CURRENT: Lazy<RwLock<LoadedConfig>> = Lazy::new(|| RwLock::new(LoadedConfig::load_or_default()));
+
+/// 单个字段的三态评分：匹配 / 不一致 / 丢失 / 意外出现 / 双方缺失
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FieldWeights {
+    pub matched: f32,
+    pub mismatched: f32,
+    pub lost: f32,
+    pub unexpected: f32,
+    pub both_missing: f32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TristateWeights {
+    pub resource_id: FieldWeights,
+    pub xpath: FieldWeights,
+    pub text: FieldWeights,
+    pub content_desc: FieldWeights,
+    pub class_name: FieldWeights,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct StructuralBonuses {
+    pub container_scoped: f32,
+    pub parent_clickable: f32,
+    /// 有轻校验时对局部索引惩罚的回补
+    pub light_checks: f32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct IndexPenalties {
+    pub local_index: f32,
+    pub global_index: f32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SafetyThresholds {
+    /// 步骤未指定 min_confidence 时的最低置信度
+    pub min_confidence: f32,
+    /// Top1 与 Top2 的最小分差（间隔唯一性）
+    pub uniqueness_gap: f32,
+    /// 节点面积占屏幕比例超过该值视为整屏节点
+    pub fullscreen_area_ratio: f32,
+    pub screen_width: u32,
+    pub screen_height: u32,
+}
+
+/// 匹配参数配置；配置文件只需写出要覆盖的项，其余取内置默认值
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct MatchingConfig {
+    pub version: u32,
+    pub weights: TristateWeights,
+    pub bonuses: StructuralBonuses,
+    pub penalties: IndexPenalties,
+    pub safety: SafetyThresholds,
+}
+
+impl Default for MatchingConfig {
+    fn default() -> Self {
+        serde_json::from_str(DEFAULT_CONFIG_JSON).expect("内置匹配配置无效")
+    }
+}
+
+impl MatchingConfig {
+    /// 内置默认配置的版本号
+    pub fn default_version() -> u32 {
+        Self::default().version
+    }
+
+    /// 校验取值范围，返回全部问题
+    pub fn validate(&self) -> Result<(), String> {
+        let mut problems = Vec::new();
+        if self.version > Self::default_version() {
+            problems.push(format!("配置版本 {} 高于程序支持的版本 {}", self.version, Self::default_version()));
+        }
+        let fields = [
+            ("resource_id", &self.weights.resource_id),
+            ("xpath", &self.weights.xpath),
+            ("text", &self.weights.text),
+            ("content_desc", &self.weights.content_desc),
+            ("class_name", &self.weights.class_name),
+        ];
+        for (name, w) in fields {
+            for (kind, value) in [
+                ("matched", w.matched),
+                ("mismatched", w.mismatched),
+                ("lost", w.lost),
+                ("unexpected", w.unexpected),
+                ("both_missing", w.both_missing),
+            ] {
+                if !(-1.0..=1.0).contains(&value) {
+                    problems.push(format!("weights.{}.{} 超出 [-1, 1]: {}", name, kind, value));
+                }
+            }
+            if w.matched <= w.mismatched {
+                problems.push(format!("weights.{}: matched 必须大于 mismatched", name));
+            }
+        }
+        for (name, value) in [
+            ("bonuses.container_scoped", self.bonuses.container_scoped),
+            ("bonuses.parent_clickable", self.bonuses.parent_clickable),
+            ("bonuses.light_checks", self.bonuses.light_checks),
+        ] {
+            if !(0.0..=1.0).contains(&value) {
+                problems.push(format!("{} 超出 [0, 1]: {}", name, value));
+            }
+        }
+        for (name, value) in [
+            ("penalties.local_index", self.penalties.local_index),
+            ("penalties.global_index", self.penalties.global_index),
+        ] {
+            if !(-1.0..=0.0).contains(&value) {
+                problems.push(format!("{} 超出 [-1, 0]: {}", name, value));
+            }
+        }
+        let safety = &self.safety;
+        if !(safety.min_confidence > 0.0 && safety.min_confidence <= 1.0) {
+            problems.push(format!("safety.min_confidence 超出 (0, 1]: {}", safety.min_confidence));
+        }
+        if !(0.0..=1.0).contains(&safety.uniqueness_gap) {
+            problems.push(format!("safety.uniqueness_gap 超出 [0, 1]: {}", safety.uniqueness_gap));
+        }
+        if !(safety.fullscreen_area_ratio > 0.0 && safety.fullscreen_area_ratio <= 1.0) {
+            problems.push(format!("safety.fullscreen_area_ratio 超出 (0, 1]: {}", safety.fullscreen_area_ratio));
+        }
+        if safety.screen_width == 0 || safety.screen_height == 0 {
+            problems.push("safety.screen_width / screen_height 必须大于 0".to_string());
+        }
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(problems.join("; "))
+        }
+    }
+}
+
+/// 当前生效配置的来源
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConfigSource {
+    File,
+    EmbeddedDefaults,
+}
+
+/// 当前生效配置及来源
+#[derive(Debug, Clone, Serialize)]
+pub struct MatchingConfigStatus {
+    pub source: ConfigSource,
+    pub path: Option<String>,
+    pub config: MatchingConfig,
+}
+
+struct LoadedConfig {
+    source: ConfigSource,
+    config: Arc<MatchingConfig>,
+}
+
+impl LoadedConfig {
+    fn embedded() -> Self {
+        Self { source: ConfigSource::EmbeddedDefaults, config: Arc::new(MatchingConfig::default()) }
+    }
+
+    fn load_or_default() -> Self {
+        match read_config_file() {
+            Ok(Some(config)) => {
+                info!("🎛️ 已加载匹配配置 v{}", config.version);
+                Self { source: ConfigSource::File, config: Arc::new(config) }
+            }
+            Ok(None) => Self::embedded(),
+            Err(e) => {
+                warn!("⚠️ 匹配配置无效，使用内置默认值: {}", e);
+                Self::embedded()
+            }
+        }
+    }
+}
+
+fn config_path() -> Option<PathBuf> {
+    dirs::data_dir().map(|d| d.join("employee-gui").join(CONFIG_FILE_NAME))
+}
+
+/// 解析并校验配置文本：先叠加到内置默认值上再整体反序列化，未知字段视为错误
+pub fn parse_config(content: &str) -> Result<MatchingConfig, String> {
+    let overrides: Value = serde_json::from_str(content).map_err(|e| format!("解析匹配配置失败: {}", e))?;
+    let mut merged: Value = serde_json::from_str(DEFAULT_CONFIG_JSON).expect("内置匹配配置无效");
+    merge_json(&mut merged, overrides);
+    let config: MatchingConfig = serde_json::from_value(merged).map_err(|e| format!("匹配配置字段无效: {}", e))?;
+    config.validate()?;
+    Ok(config)
+}
+
+fn merge_json(base: &mut Value, overlay: Value) {
+    match (base, overlay) {
+        (Value::Object(base), Value::Object(overlay)) => {
+            for (key, value) in overlay {
+                merge_json(base.entry(key).or_insert(Value::Null), value);
+            }
+        }
+        (base, overlay) => *base = overlay,
+    }
+}
+
+/// 读取配置文件；文件不存在时返回 None
+fn read_config_file() -> Result<Option<MatchingConfig>, String> {
+    let Some(path) = config_path().filter(|p| p.exists()) else {
+        return Ok(None);
+    };
+    let content = std::fs::read_to_string(&path).map_err(|e| format!("读取 {} 失败: {}", path.display(), e))?;
+    parse_config(&content).map(Some)
+}
+
+/// 当前生效的匹配配置
+pub fn current() -> Arc<MatchingConfig> {
+    Arc::clone(&CURRENT.read().config)
+}
+
+pub fn status() -> MatchingConfigStatus {
+    let loaded = CURRENT.read();
+    MatchingConfigStatus {
+        source: loaded.source,
+        path: config_path().map(|p| p.to_string_lossy().to_string()),
+        config: (*loaded.config).clone(),
+    }
+}
+
+/// 重新读取配置文件：文件无效时保留当前配置并返回错误，文件被删除时回到内置默认值
+pub fn reload() -> Result<MatchingConfigStatus, String> {
+    let loaded = match read_config_file()? {
+        Some(config) => LoadedConfig { source: ConfigSource::File, config: Arc::new(config) },
+        None => LoadedConfig::embedded(),
+    };
+    info!("🎛️ 匹配配置已重载: v{} ({:?})", loaded.config.version, loaded.source);
+    *CURRENT.write() = loaded;
+    Ok(status())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_partial_config_falls_back_to_embedded_sections() {
+        let defaults = MatchingConfig::default();
+        assert!(defaults.validate().is_ok());
+        assert_eq!(defaults.weights.resource_id.matched, 0.85);
+
+        let config = parse_config(r#"{ "version": 1, "safety": { "min_confidence": 0.8 } }"#).unwrap();
+        assert_eq!(config.safety.min_confidence, 0.8);
+        assert_eq!(config.safety.uniqueness_gap, defaults.safety.uniqueness_gap);
+        assert_eq!(config.weights, defaults.weights);
+
+        assert!(parse_config(r#"{ "safety": { "min_confidence": 1.5 } }"#).is_err());
+        assert!(parse_config(r#"{ "version": 99 }"#).is_err());
+        assert!(parse_config(r#"{ "safety": { "min_confidense": 0.8 } }"#).is_err());
+        let inverted = r#"{ "weights": { "text": { "matched": -0.3, "mismatched": 0.2, "lost": 0, "unexpected": 0, "both_missing": 0 } } }"#;
+        assert!(parse_config(inverted).is_err());
+    }
+}
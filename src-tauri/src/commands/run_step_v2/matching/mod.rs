@@ -3,6 +3,7 @@
 // summary: 聚合所有匹配逻辑 - 评分、选择器解析、坐标测试
 
 pub mod tristate_scorer;
+pub mod matching_config;
 pub mod selector_resolver;
 pub mod coord_hit_tester;
 
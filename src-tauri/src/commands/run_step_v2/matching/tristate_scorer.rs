@@ -5,6 +5,8 @@
 use crate::services::universal_ui_page_analyzer::UIElement;
 use super::super::types::StaticEvidence;  // 从 types 模块引用
 use super::super::MatchCandidate;  // 从 mod.rs 引用运行时类型
+use crate::automation::matching::decision_journal::{ComponentCategory, ScoreComponent};
+use super::matching_config::{self, FieldWeights, MatchingConfig};
 
 /// 三态评分引擎（同构评分逻辑）
 pub struct UnifiedScoringCore;
@@ -15,100 +17,135 @@ impl UnifiedScoringCore {
         static_evidence: &StaticEvidence,
         runtime_node: &UIElement
     ) -> f32 {
-        let mut score = 0.0f32;
+        let total: f32 = Self::calculate_tristate_breakdown(static_evidence, runtime_node)
+            .iter()
+            .map(|c| c.delta)
+            .sum();
+        total.max(0.0)
+    }
+    
+    /// 三态评分明细：逐字段得分 + 结构奖励 + 惩罚项（供匹配决策回溯使用）
+    pub fn calculate_tristate_breakdown(
+        static_evidence: &StaticEvidence,
+        runtime_node: &UIElement
+    ) -> Vec<ScoreComponent> {
+        Self::calculate_tristate_breakdown_with(&matching_config::current(), static_evidence, runtime_node)
+    }
+    
+    /// 使用指定配置快照计算三态评分明细（调用方需要同一快照做归一化时使用）
+    pub fn calculate_tristate_breakdown_with(
+        config: &MatchingConfig,
+        static_evidence: &StaticEvidence,
+        runtime_node: &UIElement
+    ) -> Vec<ScoreComponent> {
+        let weights = &config.weights;
+        let mut components = Vec::new();
+        let mut field = |name: &str, delta: f32, expected: &dyn std::fmt::Debug, actual: &dyn std::fmt::Debug| {
+            components.push(ScoreComponent::new(
+                ComponentCategory::Field,
+                name,
+                delta,
+                format!("静态={:?} 真机={:?} ({:+.2})", expected, actual, delta),
+            ));
+        };
         
-        // P1: 最强证据 - ResourceId + XPath (权重0.85)
-        score += Self::score_resource_id(&static_evidence.resource_id, &runtime_node.resource_id);
-        score += Self::score_xpath(&static_evidence.xpath, &runtime_node.class_name);
+        // P1: 最强证据 - ResourceId + XPath (默认权重0.85)
+        field("resource_id", Self::score_resource_id(&weights.resource_id, &static_evidence.resource_id, &runtime_node.resource_id),
+              &static_evidence.resource_id, &runtime_node.resource_id);
+        field("xpath", Self::score_xpath(&weights.xpath, &static_evidence.xpath, &runtime_node.class_name),
+              &static_evidence.xpath, &runtime_node.class_name);
         
-        // P2: 中等证据 - Text + ContentDesc (权重0.60-0.70)
-        score += Self::score_text(&static_evidence.text, &runtime_node.text);
-        score += Self::score_content_desc(&static_evidence.content_desc, &runtime_node.content_desc);
+        // P2: 中等证据 - Text + ContentDesc (默认权重0.60-0.70)
+        field("text", Self::score_text(&weights.text, &static_evidence.text, &runtime_node.text),
+              &static_evidence.text, &runtime_node.text);
+        field("content_desc", Self::score_content_desc(&weights.content_desc, &static_evidence.content_desc, &runtime_node.content_desc),
+              &static_evidence.content_desc, &runtime_node.content_desc);
         
-        // P3: 弱证据 - ClassName (权重0.30)
-        score += Self::score_class_name(&static_evidence.class_name, &runtime_node.class_name);
+        // P3: 弱证据 - ClassName (默认权重0.30)
+        field("class_name", Self::score_class_name(&weights.class_name, &static_evidence.class_name, &runtime_node.class_name),
+              &static_evidence.class_name, &runtime_node.class_name);
         
         // 结构性奖励
         if static_evidence.container_scoped {
-            score += 0.30; // 容器限定奖励
+            components.push(ScoreComponent::new(ComponentCategory::Bonus, "container_scoped", config.bonuses.container_scoped, "容器限定奖励"));
         }
         if static_evidence.parent_clickable {
-            score += 0.20; // 父可点击奖励
+            components.push(ScoreComponent::new(ComponentCategory::Bonus, "parent_clickable", config.bonuses.parent_clickable, "父可点击奖励"));
         }
         
         // 惩罚项
-        if let Some(_index) = static_evidence.local_index {
-            score -= 0.15; // 索引依赖惩罚
+        if let Some(index) = static_evidence.local_index {
+            components.push(ScoreComponent::new(ComponentCategory::Penalty, "local_index", config.penalties.local_index, format!("索引依赖惩罚 (index={})", index)));
             if static_evidence.has_light_checks {
-                score += 0.10; // 轻校验回补
+                components.push(ScoreComponent::new(ComponentCategory::Bonus, "local_index", config.bonuses.light_checks, "轻校验回补"));
             }
         }
         if static_evidence.global_index.is_some() {
-            score -= 0.60; // 全局索引重度惩罚
+            components.push(ScoreComponent::new(ComponentCategory::Penalty, "global_index", config.penalties.global_index, "全局索引重度惩罚"));
         }
         
-        score.max(0.0)
+        components
     }
     
     /// 评分单项：ResourceId 匹配/缺失/不一致
-    fn score_resource_id(static_val: &Option<String>, runtime_val: &Option<String>) -> f32 {
+    fn score_resource_id(w: &FieldWeights, static_val: &Option<String>, runtime_val: &Option<String>) -> f32 {
         match (static_val, runtime_val) {
-            (Some(s), Some(r)) if s == r => 0.85,      // 完全匹配
-            (Some(_), Some(_)) => -0.50,               // 不一致（严重）
-            (Some(_), None) => -0.35,                  // 退化（失去强锚点）
-            (None, Some(_)) => -0.08,                  // 意外出现（轻微）
-            (None, None) => 0.02,                      // 缺失一致
+            (Some(s), Some(r)) if s == r => w.matched, // 完全匹配
+            (Some(_), Some(_)) => w.mismatched,        // 不一致（严重）
+            (Some(_), None) => w.lost,                 // 退化（失去强锚点）
+            (None, Some(_)) => w.unexpected,           // 意外出现（轻微）
+            (None, None) => w.both_missing,            // 缺失一致
         }
     }
     
     /// 评分单项：XPath 包含匹配
-    fn score_xpath(static_xpath: &Option<String>, runtime_class: &Option<String>) -> f32 {
+    fn score_xpath(w: &FieldWeights, static_xpath: &Option<String>, runtime_class: &Option<String>) -> f32 {
         match (static_xpath, runtime_class) {
-            (Some(xpath), Some(class)) if xpath.contains(class) => 0.85,
-            (Some(_), Some(_)) => -0.45,               // XPath路径失效
-            (Some(_), None) => -0.30,                  // 路径退化
-            (None, Some(_)) => -0.05,                  // 意外出现
-            (None, None) => 0.01,                      // 路径缺失一致
+            (Some(xpath), Some(class)) if xpath.contains(class) => w.matched,
+            (Some(_), Some(_)) => w.mismatched,        // XPath路径失效
+            (Some(_), None) => w.lost,                 // 路径退化
+            (None, Some(_)) => w.unexpected,           // 意外出现
+            (None, None) => w.both_missing,            // 路径缺失一致
         }
     }
     
     /// 评分单项：Text 匹配（支持I18N别名）
-    fn score_text(static_text: &Option<Vec<String>>, runtime_text: &String) -> f32 {
+    fn score_text(w: &FieldWeights, static_text: &Option<Vec<String>>, runtime_text: &String) -> f32 {
         let rt_opt = if runtime_text.is_empty() { None } else { Some(runtime_text) };
         match (static_text, rt_opt) {
             (Some(aliases), Some(rt)) => {
                 if aliases.iter().any(|alias| rt.contains(alias) || alias.contains(rt)) {
-                    0.70 // 文本匹配（含I18N）
+                    w.matched // 文本匹配（含I18N）
                 } else {
-                    -0.25 // 文本不匹配
+                    w.mismatched // 文本不匹配
                 }
             },
-            (Some(_), None) => -0.20,                  // 文本丢失
-            (None, Some(_)) => -0.03,                  // 意外出现文本
-            (None, None) => 0.02,                      // 文本缺失一致
+            (Some(_), None) => w.lost,                 // 文本丢失
+            (None, Some(_)) => w.unexpected,           // 意外出现文本
+            (None, None) => w.both_missing,            // 文本缺失一致
         }
     }
     
     /// 评分单项：ContentDesc 匹配
-    fn score_content_desc(static_desc: &Option<String>, runtime_desc: &String) -> f32 {
+    fn score_content_desc(w: &FieldWeights, static_desc: &Option<String>, runtime_desc: &String) -> f32 {
         let rd_opt = if runtime_desc.is_empty() { None } else { Some(runtime_desc) };
         match (static_desc, rd_opt) {
-            (Some(s), Some(r)) if r.contains(s) || s.contains(r) => 0.60,
-            (Some(_), Some(_)) => -0.20,               // ContentDesc不匹配
-            (Some(_), None) => -0.15,                  // ContentDesc丢失
-            (None, Some(_)) => -0.02,                  // 意外出现
-            (None, None) => 0.01,                      // 缺失一致
+            (Some(s), Some(r)) if r.contains(s) || s.contains(r) => w.matched,
+            (Some(_), Some(_)) => w.mismatched,        // ContentDesc不匹配
+            (Some(_), None) => w.lost,                 // ContentDesc丢失
+            (None, Some(_)) => w.unexpected,           // 意外出现
+            (None, None) => w.both_missing,            // 缺失一致
         }
     }
     
     /// 评分单项：ClassName 匹配
-    fn score_class_name(static_class: &Option<String>, runtime_class: &Option<String>) -> f32 {
+    fn score_class_name(w: &FieldWeights, static_class: &Option<String>, runtime_class: &Option<String>) -> f32 {
         match (static_class, runtime_class) {
-            (Some(s), Some(r)) if r.contains(s) || s.contains(r) => 0.30,
-            (Some(_), Some(_)) => -0.15,               // 类名不匹配
-            (Some(_), None) => -0.10,                  // 类名丢失
-            (None, Some(_)) => -0.02,                  // 意外出现
-            (None, None) => 0.01,                      // 缺失一致
+            (Some(s), Some(r)) if r.contains(s) || s.contains(r) => w.matched,
+            (Some(_), Some(_)) => w.mismatched,        // 类名不匹配
+            (Some(_), None) => w.lost,                 // 类名丢失
+            (None, Some(_)) => w.unexpected,           // 意外出现
+            (None, None) => w.both_missing,            // 缺失一致
         }
     }
     
@@ -127,10 +164,11 @@ impl UnifiedScoringCore {
         let threshold_unique = top1.confidence >= min_confidence as f64 && 
             candidates.iter().filter(|c| c.confidence >= min_confidence as f64).count() == 1;
         
-        // 间隔唯一性：Top1 - Top2 >= 0.15
+        // 间隔唯一性：Top1 - Top2 >= uniqueness_gap（默认 0.15）
+        let gap = matching_config::current().safety.uniqueness_gap as f64;
         let gap_unique = if candidates.len() >= 2 {
             let top2 = &candidates[1];
-            (top1.confidence - top2.confidence) >= 0.15
+            (top1.confidence - top2.confidence) >= gap
         } else {
             true // 只有一个候选时自动通过间隔检查
         };
@@ -148,7 +186,8 @@ mod tests {
     fn test_resource_id_exact_match() {
         let static_val = Some("com.app:id/button".to_string());
         let runtime_val = Some("com.app:id/button".to_string());
-        let score = UnifiedScoringCore::score_resource_id(&static_val, &runtime_val);
+        let weights = matching_config::MatchingConfig::default().weights;
+        let score = UnifiedScoringCore::score_resource_id(&weights.resource_id, &static_val, &runtime_val);
         assert_eq!(score, 0.85);
     }
     
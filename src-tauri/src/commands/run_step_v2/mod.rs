@@ -8,18 +8,21 @@
 
 // 🏗️ 子模块声明
 mod sm_integration;
-mod validation;
+pub(crate) mod validation;
 mod types;
 mod matching;
 mod execution;
 mod utils;
 mod legacy;
+pub mod plan_contract; // Plan JSON 契约校验（命令与 validate_plan 工具共用）
 
 // 重导出 types 模块的公共类型（供外部模块使用）
 pub use types::*;
 
 // 重导出 matching 模块的功能
 use matching::{resolve_selector_with_priority, SelectorSource, coord_fallback_hit_test};
+pub use matching::matching_config; // 评分权重 / 安全阈值配置（供热重载命令使用）
+pub use matching::tristate_scorer::UnifiedScoringCore; // 三态评分（供自动化多候选评估器复用）
 
 // 重导出 execution 模块的功能
 use execution::{execute_v2_action_with_coords, run_decision_chain_v2 as run_decision_chain_v2_impl};
@@ -54,7 +57,17 @@ use crate::infra::adb::input_helper::tap_injector_first;
 use crate::engine::strategy_plugin::{StrategyRegistry, ExecutionEnvironment};
 
 // 导入 validation 模块的安全检查函数
-use validation::{check_fullscreen_node, check_container_node, parse_xml_attribute, parse_bounds_from_string};
+use validation::{check_fullscreen_node, check_container_node};
+use crate::engine::ui_tree::{Attr, UiTree};
+use validation::post_assertions::{post_assertions_from_step, verify_on_device};
+use crate::services::failure_annotation::capture_failure_report;
+use crate::services::execution::timeline::{self, Phase};
+use crate::services::screen_stability::{self, IdleOptions};
+use crate::services::scoped_dump::{self, DumpScope};
+use crate::services::campaign_sim::record_step_duration;
+use crate::services::interaction_heatmap::{self, InteractionTarget};
+use crate::types::{AppError, AppResult, ErrorCode};
+use crate::core::shared::event_bus::{self, AppEvent};
 
 // V2 执行模式（匹配前端枚举）
 #[derive(Debug, Clone, Deserialize)]
@@ -87,6 +100,9 @@ pub struct RunStepRequestV2 {
     pub mode: StepRunMode,
     pub strategy: StrategyKind,
     pub step: serde_json::Value, // StepPayload 复杂结构，暂用 Value
+    /// 所属运行 ID；提供时各阶段耗时记入执行时间线
+    #[serde(default)]
+    pub run_id: Option<String>,
 }
 
 fn default_true() -> bool { true }
@@ -187,42 +203,65 @@ pub struct StepExecutionResult {
 
 // V2 统一执行入口（前端兼容接口）
 #[command]
-pub async fn run_step_v2(app_handle: AppHandle, request: RunStepRequestV2) -> Result<StepResponseV2, String> {
+pub async fn run_step_v2(app_handle: AppHandle, request: RunStepRequestV2) -> AppResult<StepResponseV2> {
     tracing::info!(
         "engine=v2 device_id={} mode={:?} strategy={:?}",
         request.device_id, request.mode, request.strategy
     );
     tracing::info!("bridge=ADB shadow=false dump_source=Device");
+    crate::services::telemetry::record_feature("run_step_v2");
     
     // 简化处理：当前只实现 step 执行链.
-    execute_v2_step(app_handle, &request).await
+    let started = std::time::Instant::now();
+    let result = match request.run_id.clone() {
+        Some(run_id) => timeline::scope_run(&run_id, "run_step_v2", execute_v2_step(app_handle, &request)).await,
+        None => execute_v2_step(app_handle, &request).await,
+    };
+
+    // 真实耗时计入步骤统计，供活动模拟器使用
+    let action = request.step.get("action").and_then(|v| v.as_str()).unwrap_or("tap");
+    let succeeded = matches!(&result, Ok(response) if response.verify_passed != Some(false));
+    record_step_duration(action, started.elapsed().as_millis() as u64, succeeded);
+    event_bus::publish(AppEvent::ActionExecuted {
+        action: action.to_string(),
+        device_id: request.device_id.clone(),
+        success: succeeded,
+    });
+    result
 }
  
 
 // V2 步骤执行（匹配前端数据结构）
-async fn execute_v2_step(_app_handle: AppHandle, req: &RunStepRequestV2) -> Result<StepResponseV2, String> {
+async fn execute_v2_step(_app_handle: AppHandle, req: &RunStepRequestV2) -> AppResult<StepResponseV2> {
     tracing::info!("🚀 [V2->V3 Migration] Delegating to automation::engine");
 
     // 1. Expand params
     let step_with_coords = expand_coordinate_params(&req.step);
     let action_str = step_with_coords.get("action").and_then(|v| v.as_str()).unwrap_or("tap");
+    let step_id = step_with_coords.get("id").and_then(|v| v.as_str()).unwrap_or("v2_step").to_string();
+    let mut step_span = timeline::span(Phase::Step, &step_id);
 
     // 2. Check if direct action
     let is_direct = is_selector_free_action(action_str) || is_coordinate_swipe(&step_with_coords, action_str);
 
-    // 3. Dump UI if needed
+    // 3. Dump UI if needed（步骤声明 dump_scope 时只保留容器子树以缩小匹配范围）
     let ui_xml = if is_direct {
         String::new()
     } else {
-        AdbService::new().dump_ui_hierarchy(&req.device_id).await
-            .map_err(|e| format!("Failed to dump hierarchy: {}", e))?
+        let full_xml = AdbService::new().dump_ui_hierarchy(&req.device_id).await
+            .map_err(|e| AppError::ui_dump_failed(&req.device_id, e))?;
+        match DumpScope::from_step(&step_with_coords) {
+            Some(scope) => scoped_dump::apply_scope(full_xml, &scope).map_err(|e| AppError::new(ErrorCode::NoMatch, e))?.xml,
+            None => full_xml,
+        }
     };
 
+    // 失败标注按选择器在这份 dump 中定位期望元素
+    let annotation_xml = (!ui_xml.is_empty()).then_some(ui_xml.as_str());
+
     // 4. Construct InlineStep
     let action_enum = serde_json::from_value::<SingleStepAction>(serde_json::Value::String(action_str.to_string()))
         .unwrap_or(SingleStepAction::Unknown);
-    
-    let step_id = step_with_coords.get("id").and_then(|v| v.as_str()).unwrap_or("v2_step").to_string();
 
     let inline_step = InlineStep {
         step_id,
@@ -230,23 +269,68 @@ async fn execute_v2_step(_app_handle: AppHandle, req: &RunStepRequestV2) -> Resu
         params: step_with_coords.clone(),
     };
 
-    // 5. Execute via Engine
-    let (x, y) = engine::execute_step(&req.device_id, &inline_step, &ui_xml).await?;
+    // 5. Execute via Engine（失败时保存标注截图与 trace）
+    let (x, y) = match engine::execute_step(&req.device_id, &inline_step, &ui_xml).await {
+        Ok(coords) => coords,
+        Err(e) => {
+            step_span.fail();
+            // 执行引擎内部仍以 `CODE: 消息` 形式返回错误，在此转换为结构化错误
+            let error = AppError::from_legacy(e.as_str());
+            let code = serde_json::to_value(error.code).ok().and_then(|v| v.as_str().map(str::to_string));
+            interaction_heatmap::record_interaction(&ui_xml, InteractionTarget::Selector(&step_with_coords), action_str, Some(code.as_deref().unwrap_or("UNKNOWN")));
+            let report = capture_failure_report(&req.device_id, &inline_step.step_id, &e, &step_with_coords, None, annotation_xml).await;
+            return Err(match report {
+                Some(dir) => error.with_context(format!("失败记录: {}", dir.display())),
+                None => error,
+            });
+        }
+    };
+    let mut raw_logs = vec![format!("Executed at ({}, {})", x, y)];
+
+    // 6. Optional wait for the screen to settle, then post-assertions against a fresh dump
+    if let Some(options) = IdleOptions::from_step(&step_with_coords) {
+        let idle = screen_stability::wait_for_idle(&req.device_id, &options).await;
+        raw_logs.push(format!("Wait for idle: idle={} waited={}ms changes={}", idle.idle, idle.waited_ms, idle.changes));
+    }
+    let post_assertions = post_assertions_from_step(&step_with_coords);
+    let verify_passed = if post_assertions.is_empty() {
+        true
+    } else {
+        let _verify_span = timeline::span(Phase::Verify, &inline_step.step_id);
+        let report = verify_on_device(&req.device_id, &post_assertions)
+            .await
+            .map_err(|e| AppError::ui_dump_failed(&req.device_id, e))?;
+        raw_logs.extend(report.log_lines());
+        report.passed()
+    };
+    // 热力统计记录的是执行前 dump 中的落点元素
+    interaction_heatmap::record_interaction(&ui_xml, InteractionTarget::Point(x, y), action_str, (!verify_passed).then_some("POST_ASSERTION_FAILED"));
+    if !verify_passed {
+        step_span.fail();
+        let error = "post-assertions failed";
+        if let Some(dir) = capture_failure_report(&req.device_id, &inline_step.step_id, error, &step_with_coords, Some((x, y)), annotation_xml).await {
+            raw_logs.push(format!("Failure report: {}", dir.display()));
+        }
+    }
 
-    // 6. Return Response
+    // 7. Return Response
     Ok(StepResponseV2 {
         ok: true,
-        message: "Executed via automation engine".to_string(),
+        message: if verify_passed {
+            "Executed via automation engine".to_string()
+        } else {
+            "Executed via automation engine, post-assertions failed".to_string()
+        },
         matched: None,
         executed_action: Some(action_str.to_string()),
-        verify_passed: Some(true),
-        error_code: None,
-        raw_logs: Some(vec![format!("Executed at ({}, {})", x, y)]),
+        verify_passed: Some(verify_passed),
+        error_code: if verify_passed { None } else { Some("POST_ASSERTION_FAILED".to_string()) },
+        raw_logs: Some(raw_logs),
     })
 }
 
 // 在UI dump中查找匹配的元素
-async fn find_element_in_ui(ui_xml: &str, req: &RunStepRequestV2, selection_mode: Option<String>) -> Result<(MatchInfo, Vec<MatchCandidate>), String> {
+async fn find_element_in_ui(ui_xml: &str, req: &RunStepRequestV2, selection_mode: Option<String>) -> AppResult<(MatchInfo, Vec<MatchCandidate>)> {
     // 🔥 关键调试：输出接收到的selection_mode
     tracing::info!("🔥 [find_element_in_ui] 接收到 selection_mode: {:?}", selection_mode);
     
@@ -300,7 +384,7 @@ async fn find_element_in_ui(ui_xml: &str, req: &RunStepRequestV2, selection_mode
                 return Ok((_match_info, vec![candidate])); // 返回Vec而不是单个
             }
             Err(e) => {
-                return Err(format!("坐标兜底失败: {}", e));
+                return Err(AppError::new(ErrorCode::NoMatch, format!("坐标兜底失败: {}", e)));
             }
         }
     }
@@ -312,7 +396,7 @@ async fn find_element_in_ui(ui_xml: &str, req: &RunStepRequestV2, selection_mode
     
     if !has_any_selector {
         tracing::error!("❌ 没有提供任何选择器条件，拒绝执行");
-        return Err("NO_SELECTOR: 必须提供至少一个选择器条件 (text/xpath/resourceId/className/contentDesc)".to_string());
+        return Err(AppError::new(ErrorCode::NoSelector, "必须提供至少一个选择器条件 (text/xpath/resourceId/className/contentDesc)"));
     }
     
     // 简单的XML解析 - 查找匹配的节点
@@ -321,22 +405,20 @@ async fn find_element_in_ui(ui_xml: &str, req: &RunStepRequestV2, selection_mode
     let mut elements_found = 0;
     let mut matching_candidates = Vec::new(); // 收集所有匹配的候选
     
-    // 使用正则表达式解析XML节点
-    let node_regex = regex::Regex::new(r#"<node[^>]*>"#).unwrap();
+    // 解析为节点树（与索引器共享同一解析器）
+    let tree = UiTree::parse_lenient(ui_xml);
     
-    for node_match in node_regex.find_iter(ui_xml) {
+    for node in tree.nodes() {
         elements_found += 1;
-        let node_str = node_match.as_str();
         
         let mut score = 0.0f64;
         let _matches = 0;
         
         // 提取节点属性
-        let text = parse_xml_attribute(node_str, "text");
-        let resource_id = parse_xml_attribute(node_str, "resource-id");
-        let class_name = parse_xml_attribute(node_str, "class");
-        let content_desc = parse_xml_attribute(node_str, "content-desc");
-        let bounds_str = parse_xml_attribute(node_str, "bounds");
+        let text = node.attr(Attr::Text).map(str::to_string);
+        let resource_id = node.attr(Attr::ResourceId).map(str::to_string);
+        let class_name = node.attr(Attr::Class).map(str::to_string);
+        let content_desc = node.attr(Attr::ContentDesc).map(str::to_string);
         
         // 一致性评分：考虑与静态分析结果的一致性
         let mut successful_matches = 0;
@@ -515,11 +597,9 @@ async fn find_element_in_ui(ui_xml: &str, req: &RunStepRequestV2, selection_mode
         }
         
         // 解析bounds
-        let bounds = if let Some(bounds_str) = bounds_str {
-            parse_bounds_from_string(&bounds_str).unwrap_or(Bounds { left: 0, top: 0, right: 100, bottom: 100 })
-        } else {
-            Bounds { left: 0, top: 0, right: 100, bottom: 100 }
-        };
+        let bounds = node.bounds()
+            .map(|(left, top, right, bottom)| Bounds { left, top, right, bottom })
+            .unwrap_or(Bounds { left: 0, top: 0, right: 100, bottom: 100 });
         
         // 收集所有有效匹配
         let candidate = MatchCandidate {
@@ -581,7 +661,7 @@ async fn find_element_in_ui(ui_xml: &str, req: &RunStepRequestV2, selection_mode
                           block_type, candidate.class_name, 
                           candidate.bounds.left, candidate.bounds.top,
                           candidate.bounds.right, candidate.bounds.bottom);
-            return Err(format!("CONTAINER_BLOCKED: {}节点不允许直接点击", block_type));
+            return Err(AppError::new(ErrorCode::ContainerBlocked, format!("{}节点不允许直接点击", block_type)));
         } else {
             tracing::info!("✅ 自测通过: 非容器/整屏节点 class={:?} bounds=({},{},{},{})",
                           candidate.class_name, candidate.bounds.left, candidate.bounds.top,
@@ -597,11 +677,11 @@ async fn find_element_in_ui(ui_xml: &str, req: &RunStepRequestV2, selection_mode
         // �️ 安全检查：最低置信度
         let min_confidence = req.step.get("min_confidence")
             .and_then(|v| v.as_f64())
-            .unwrap_or(0.70); // 提升默认阈值到0.70
+            .unwrap_or(matching::matching_config::current().safety.min_confidence as f64); // 默认阈值取自匹配配置（0.70）
             
         if best_score < min_confidence {
             tracing::warn!("⚠️ 最佳匹配置信度({:.2})低于阈值({:.2})", best_score, min_confidence);
-            return Err(format!("LOW_CONFIDENCE: 最佳置信度{:.2}低于阈值{:.2}", best_score, min_confidence));
+            return Err(AppError::new(ErrorCode::LowConfidence, format!("最佳置信度{:.2}低于阈值{:.2}", best_score, min_confidence)));
         }
         
         // 🛡️ 安全检查：整屏/容器拒绝（双阶段拦截）
@@ -616,12 +696,15 @@ async fn find_element_in_ui(ui_xml: &str, req: &RunStepRequestV2, selection_mode
             if check_fullscreen_node(&candidate_bounds) {
                 tracing::warn!("🚫 自测检查: 整屏节点被拦截 bounds=({},{},{},{})", 
                               candidate.bounds.left, candidate.bounds.top, candidate.bounds.right, candidate.bounds.bottom);
-                return Err("FULLSCREEN_BLOCKED: 匹配到整屏节点，拒绝执行".to_string());
+                return Err(AppError::new(ErrorCode::FullscreenBlocked, "匹配到整屏节点，拒绝执行"));
             }
             
             if check_container_node(&candidate.class_name) {
                 tracing::warn!("🚫 自测检查: 容器节点被拦截 class={:?}", candidate.class_name);
-                return Err(format!("CONTAINER_BLOCKED: 匹配到容器节点({:?})，拒绝执行", candidate.class_name.as_deref().unwrap_or("unknown")));
+                return Err(AppError::new(
+                    ErrorCode::ContainerBlocked,
+                    format!("匹配到容器节点({:?})，拒绝执行", candidate.class_name.as_deref().unwrap_or("unknown")),
+                ));
             }
             
             tracing::info!("✅ 自测通过: 非容器/整屏节点 class={:?} bounds=({},{},{},{})",
@@ -642,7 +725,10 @@ async fn find_element_in_ui(ui_xml: &str, req: &RunStepRequestV2, selection_mode
             // 生成解歧建议
             let disambiguation_suggestions = generate_disambiguation_suggestions(&matching_candidates, req);
             tracing::warn!("⚠️ 匹配到{}个元素，违反唯一性约束。建议: {:?}", uniqueness, disambiguation_suggestions);
-            return Err(format!("NON_UNIQUE: 匹配到{}个元素。建议添加: {}", uniqueness, disambiguation_suggestions.join(", ")));
+            return Err(AppError::new(
+                ErrorCode::NonUnique,
+                format!("匹配到{}个元素。建议添加: {}", uniqueness, disambiguation_suggestions.join(", ")),
+            ));
         }
         
         if is_batch_mode {
@@ -673,10 +759,15 @@ async fn find_element_in_ui(ui_xml: &str, req: &RunStepRequestV2, selection_mode
         // 检查是否没有提供匹配条件
         if target_text.is_none() && target_xpath.is_none() && target_resource_id.is_none() && 
            target_class.is_none() && target_content_desc.is_none() {
-            return Err("❌ 没有提供任何匹配条件 (text, xpath, resourceId, className, contentDesc)。请在步骤中指定至少一个匹配条件。".to_string());
+            return Err(AppError::new(
+                ErrorCode::NoSelector,
+                "没有提供任何匹配条件 (text, xpath, resourceId, className, contentDesc)。请在步骤中指定至少一个匹配条件。",
+            ));
         }
-        Err(format!("❌ 未找到匹配的元素。搜索条件: text={:?}, xpath={:?}, resourceId={:?}, className={:?}, contentDesc={:?}",
-                   target_text, target_xpath, target_resource_id, target_class, target_content_desc))
+        Err(AppError::new(ErrorCode::NoMatch, "未找到匹配的元素").with_context(format!(
+            "text={:?}, xpath={:?}, resourceId={:?}, className={:?}, contentDesc={:?}",
+            target_text, target_xpath, target_resource_id, target_class, target_content_desc
+        )))
     }
 }
 
@@ -699,14 +790,14 @@ struct EnhancedSelectorInfo {
 
 // 🚀 新增：插件化决策链执行入口（Command 包装器）
 #[command]
-pub async fn run_decision_chain_v2(app_handle: AppHandle, plan_json: String, device_id: String) -> Result<serde_json::Value, String> {
+pub async fn run_decision_chain_v2(app_handle: AppHandle, plan_json: String, device_id: String) -> AppResult<serde_json::Value> {
     // 委托给 execution 模块的实现
-    run_decision_chain_v2_impl(app_handle, plan_json, device_id).await
+    Ok(run_decision_chain_v2_impl(app_handle, plan_json, device_id).await?)
 }
 
 // 📊 决策链统计和健康检查
 #[command]
-pub async fn get_decision_chain_stats() -> Result<serde_json::Value, String> {
+pub async fn get_decision_chain_stats() -> AppResult<serde_json::Value> {
     let registry = StrategyRegistry::new();
     
     let stats = serde_json::json!({
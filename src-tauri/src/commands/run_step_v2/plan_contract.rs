@@ -0,0 +1,471 @@
+// src-tauri/src/commands/run_step_v2/plan_contract.rs
+// module: v2-execution | layer: commands | role: Plan 契约校验
+// summary: 按 /shared/plan_schema.json 与 Rust 类型校验 DecisionChainPlan，并检查语义规则（static_score 有序、VariantKind 已知、预算合理），返回带 JSON Pointer 的机器可读问题列表；命令与 validate_plan 工具共用
+
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::collections::HashSet;
+
+use super::types::{DecisionChainPlan, VariantKind};
+
+/// 当前支持的契约版本
+pub const SUPPORTED_VERSION: &str = "v2";
+/// 与 plan_schema.json 一致的默认预算
+const DEFAULT_TIME_BUDGET_MS: u64 = 1200;
+const DEFAULT_PER_CANDIDATE_BUDGET_MS: u64 = 180;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum IssueSeverity {
+    Error,
+    Warning,
+}
+
+/// 单个校验问题
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlanIssue {
+    /// RFC 6901 JSON Pointer，根为 ""
+    pub pointer: String,
+    /// 机器可读的问题码（`MISSING_FIELD`、`SCORE_ORDER` …）
+    pub code: &'static str,
+    pub severity: IssueSeverity,
+    pub message: String,
+}
+
+/// 校验报告：`valid` 只看错误，警告不影响执行
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlanValidationReport {
+    pub valid: bool,
+    pub errors: usize,
+    pub warnings: usize,
+    pub issues: Vec<PlanIssue>,
+}
+
+/// 解析 VariantKind（同时接受 schema 的 `SelfId` 与 Rust 的 `self_id` 写法）
+pub fn parse_variant_kind(kind: &str) -> Option<VariantKind> {
+    serde_json::from_value(Value::String(kind.to_string())).ok()
+}
+
+fn escape_token(token: &str) -> String {
+    token.replace('~', "~0").replace('/', "~1")
+}
+
+fn child(pointer: &str, token: &str) -> String {
+    format!("{}/{}", pointer, escape_token(token))
+}
+
+#[derive(Default)]
+struct Checker {
+    issues: Vec<PlanIssue>,
+}
+
+impl Checker {
+    fn push(&mut self, pointer: impl Into<String>, code: &'static str, severity: IssueSeverity, message: impl Into<String>) {
+        self.issues.push(PlanIssue { pointer: pointer.into(), code, severity, message: message.into() });
+    }
+
+    fn error(&mut self, pointer: impl Into<String>, code: &'static str, message: impl Into<String>) {
+        self.push(pointer, code, IssueSeverity::Error, message);
+    }
+
+    fn warn(&mut self, pointer: impl Into<String>, code: &'static str, message: impl Into<String>) {
+        self.push(pointer, code, IssueSeverity::Warning, message);
+    }
+
+    fn has_errors(&self) -> bool {
+        self.issues.iter().any(|i| i.severity == IssueSeverity::Error)
+    }
+
+    /// 必填对象字段
+    fn object<'a>(&mut self, parent: &'a Map<String, Value>, pointer: &str, key: &str) -> Option<&'a Map<String, Value>> {
+        match parent.get(key) {
+            None => {
+                self.error(child(pointer, key), "MISSING_FIELD", format!("缺少必填字段 {}", key));
+                None
+            }
+            Some(Value::Object(map)) => Some(map),
+            Some(_) => {
+                self.error(child(pointer, key), "TYPE_MISMATCH", format!("{} 应为对象", key));
+                None
+            }
+        }
+    }
+
+    /// 可选字段的类型检查：存在但类型不符时报错并返回 None
+    fn optional<'a>(&mut self, parent: &'a Map<String, Value>, pointer: &str, key: &str, expected: &str, ok: fn(&Value) -> bool) -> Option<&'a Value> {
+        let value = parent.get(key).filter(|v| !v.is_null())?;
+        if ok(value) {
+            Some(value)
+        } else {
+            self.error(child(pointer, key), "TYPE_MISMATCH", format!("{} 应为{}", key, expected));
+            None
+        }
+    }
+
+    fn required_string<'a>(&mut self, parent: &'a Map<String, Value>, pointer: &str, key: &str) -> Option<&'a str> {
+        match parent.get(key) {
+            None | Some(Value::Null) => {
+                self.error(child(pointer, key), "MISSING_FIELD", format!("缺少必填字段 {}", key));
+                None
+            }
+            Some(Value::String(s)) if s.trim().is_empty() => {
+                self.error(child(pointer, key), "EMPTY_VALUE", format!("{} 不能为空", key));
+                None
+            }
+            Some(Value::String(s)) => Some(s),
+            Some(_) => {
+                self.error(child(pointer, key), "TYPE_MISMATCH", format!("{} 应为字符串", key));
+                None
+            }
+        }
+    }
+
+    fn integer_in(&mut self, parent: &Map<String, Value>, pointer: &str, key: &str, min: u64, max: u64) -> Option<u64> {
+        let value = self.optional(parent, pointer, key, "非负整数", Value::is_u64)?.as_u64()?;
+        if value < min || value > max {
+            self.error(child(pointer, key), "OUT_OF_RANGE", format!("{} 应在 {}..={} 之间，实际 {}", key, min, max, value));
+            return None;
+        }
+        Some(value)
+    }
+
+    fn ratio(&mut self, parent: &Map<String, Value>, pointer: &str, key: &str) -> Option<f64> {
+        let value = self.optional(parent, pointer, key, "数字", Value::is_number)?.as_f64()?;
+        if !(0.0..=1.0).contains(&value) {
+            self.error(child(pointer, key), "OUT_OF_RANGE", format!("{} 应在 0..=1 之间，实际 {}", key, value));
+            return None;
+        }
+        Some(value)
+    }
+
+    fn one_of(&mut self, parent: &Map<String, Value>, pointer: &str, key: &str, allowed: &[&str]) {
+        if let Some(value) = self.optional(parent, pointer, key, "字符串", Value::is_string).and_then(Value::as_str) {
+            if !allowed.contains(&value) {
+                self.error(child(pointer, key), "INVALID_ENUM", format!("{} 只能是 {:?}，实际 {}", key, allowed, value));
+            }
+        }
+    }
+}
+
+fn check_strategy(c: &mut Checker, strategy: &Map<String, Value>, variant_count: usize) -> Option<String> {
+    let pointer = "/strategy";
+    let selected = c.required_string(strategy, pointer, "selected").map(str::to_string);
+    for key in ["allow_backend_fallback", "require_uniqueness", "forbid_containers"] {
+        c.optional(strategy, pointer, key, "布尔值", Value::is_boolean);
+    }
+    let time_budget = c.integer_in(strategy, pointer, "time_budget_ms", 100, 5000);
+    let per_candidate = c.integer_in(strategy, pointer, "per_candidate_budget_ms", 50, 1000);
+    c.ratio(strategy, pointer, "min_confidence");
+    if let Some(Value::Array(items)) = c.optional(strategy, pointer, "post_assertions", "字符串数组", Value::is_array) {
+        for (i, item) in items.iter().enumerate() {
+            if !item.is_string() {
+                c.error(format!("{}/post_assertions/{}", pointer, i), "TYPE_MISMATCH", "后置断言应为字符串");
+            }
+        }
+    }
+
+    let time_budget = time_budget.unwrap_or(DEFAULT_TIME_BUDGET_MS);
+    let per_candidate = per_candidate.unwrap_or(DEFAULT_PER_CANDIDATE_BUDGET_MS);
+    if per_candidate > time_budget {
+        c.error(
+            child(pointer, "per_candidate_budget_ms"),
+            "BUDGET_INCONSISTENT",
+            format!("单候选预算 {}ms 超过总预算 {}ms", per_candidate, time_budget),
+        );
+    } else if per_candidate * variant_count as u64 > time_budget {
+        c.warn(
+            child(pointer, "time_budget_ms"),
+            "BUDGET_EXHAUSTED",
+            format!("总预算 {}ms 不足以尝试全部 {} 个候选（每个 {}ms），靠后的候选可能不会执行", time_budget, variant_count, per_candidate),
+        );
+    }
+    selected
+}
+
+fn check_context(c: &mut Checker, context: &Map<String, Value>) {
+    let pointer = "/context";
+    for key in ["package", "activity", "absolute_xpath", "xml_hash", "node_fingerprint"] {
+        c.optional(context, pointer, key, "字符串", Value::is_string);
+    }
+    if let Some(Value::Object(screen)) = c.optional(context, pointer, "screen", "对象", Value::is_object) {
+        let screen_pointer = "/context/screen";
+        for key in ["width", "height", "dpi"] {
+            match screen.get(key) {
+                Some(v) if v.as_u64().is_some_and(|n| n > 0) => {}
+                Some(_) => c.error(child(screen_pointer, key), "OUT_OF_RANGE", format!("{} 应为正整数", key)),
+                None => c.error(child(screen_pointer, key), "MISSING_FIELD", format!("缺少必填字段 {}", key)),
+            }
+        }
+        if screen.contains_key("orientation") {
+            c.one_of(screen, screen_pointer, "orientation", &["portrait", "landscape"]);
+        } else {
+            c.error(child(screen_pointer, "orientation"), "MISSING_FIELD", "缺少必填字段 orientation");
+        }
+    }
+    if let Some(Value::Object(hint)) = c.optional(context, pointer, "clickable_parent_hint", "对象", Value::is_object) {
+        if !hint.contains_key("up_levels") {
+            c.error("/context/clickable_parent_hint/up_levels", "MISSING_FIELD", "缺少必填字段 up_levels");
+        }
+        c.integer_in(hint, "/context/clickable_parent_hint", "up_levels", 0, 10);
+    }
+}
+
+/// 取 selectors.self（兼容 Rust 字段名 self_）
+fn self_selector(selectors: &Map<String, Value>) -> Option<(&'static str, &Map<String, Value>)> {
+    ["self", "self_"].into_iter().find_map(|key| selectors.get(key).and_then(Value::as_object).map(|m| (key, m)))
+}
+
+fn has_string(map: Option<&Map<String, Value>>, key: &str) -> bool {
+    map.and_then(|m| m.get(key)).and_then(Value::as_str).is_some_and(|s| !s.trim().is_empty())
+}
+
+fn check_variant(c: &mut Checker, index: usize, variant: &Map<String, Value>, ids: &mut HashSet<String>) -> Option<f64> {
+    let pointer = format!("/plan/{}", index);
+    if let Some(id) = c.required_string(variant, &pointer, "id") {
+        if !ids.insert(id.to_string()) {
+            c.error(child(&pointer, "id"), "DUPLICATE_ID", format!("候选 ID {} 重复", id));
+        }
+    }
+    c.required_string(variant, &pointer, "explain");
+    let kind = match c.required_string(variant, &pointer, "kind") {
+        Some(raw) => {
+            let kind = parse_variant_kind(raw);
+            if kind.is_none() {
+                c.error(child(&pointer, "kind"), "UNKNOWN_VARIANT_KIND", format!("未知的策略类型 {}", raw));
+            }
+            kind
+        }
+        None => None,
+    };
+    if variant.contains_key("scope") {
+        c.one_of(variant, &pointer, "scope", &["regional", "global"]);
+    } else {
+        c.error(child(&pointer, "scope"), "MISSING_FIELD", "缺少必填字段 scope");
+    }
+    let selectors = c.object(variant, &pointer, "selectors");
+    let score = match variant.get("static_score") {
+        None => {
+            c.error(child(&pointer, "static_score"), "MISSING_FIELD", "缺少必填字段 static_score");
+            None
+        }
+        Some(_) => c.ratio(variant, &pointer, "static_score"),
+    };
+
+    let index_hint = c.optional(variant, &pointer, "index", "对象", Value::is_object).and_then(Value::as_object);
+    if let Some(hint) = index_hint {
+        c.integer_in(hint, &child(&pointer, "index"), "local_index", 1, u32::MAX as u64);
+        c.integer_in(hint, &child(&pointer, "index"), "global_index", 1, u32::MAX as u64);
+    }
+    let checks = match c.optional(variant, &pointer, "checks", "数组", Value::is_array) {
+        Some(Value::Array(items)) => items.len(),
+        _ => 0,
+    };
+    if let Some(Value::Object(structure)) = c.optional(variant, &pointer, "structure", "对象", Value::is_object) {
+        let structure_pointer = child(&pointer, "structure");
+        if structure.contains_key("relation") {
+            c.one_of(structure, &structure_pointer, "relation", &["parent_child", "ancestor_descendant", "sibling"]);
+        } else {
+            c.error(child(&structure_pointer, "relation"), "MISSING_FIELD", "缺少必填字段 relation");
+        }
+        c.one_of(structure, &structure_pointer, "direction", &["up", "down", "next", "prev"]);
+        c.integer_in(structure, &structure_pointer, "levels", 1, 10);
+    }
+
+    // 各策略类型的必备条件
+    let selectors_pointer = child(&pointer, "selectors");
+    let self_sel = selectors.and_then(self_selector);
+    let self_pointer = child(&selectors_pointer, self_sel.map_or("self", |(key, _)| key));
+    let self_map = self_sel.map(|(_, m)| m);
+    let has_child = selectors.and_then(|s| s.get("child")).is_some_and(Value::is_object);
+    let is_regional = variant.get("scope").and_then(Value::as_str) == Some("regional");
+    let local_index = index_hint.and_then(|h| h.get("local_index")).is_some();
+    let global_index = index_hint.and_then(|h| h.get("global_index")).is_some();
+    match kind {
+        Some(VariantKind::SelfId) if !has_string(self_map, "resource_id") => {
+            c.error(child(&self_pointer, "resource_id"), "MISSING_SELECTOR", "SelfId 需要 selectors.self.resource_id");
+        }
+        Some(VariantKind::SelfDesc) if !has_string(self_map, "content_desc") => {
+            c.error(child(&self_pointer, "content_desc"), "MISSING_SELECTOR", "SelfDesc 需要 selectors.self.content_desc");
+        }
+        Some(VariantKind::ChildToParent | VariantKind::RegionTextToParent) if !has_child => {
+            c.error(child(&selectors_pointer, "child"), "MISSING_SELECTOR", "子锚点策略需要 selectors.child");
+        }
+        Some(VariantKind::RegionLocalIndexWithCheck) if !local_index => {
+            c.error(child(&pointer, "index"), "MISSING_INDEX", "RegionLocalIndexWithCheck 需要 index.local_index");
+        }
+        Some(VariantKind::GlobalIndexWithStrongChecks) if !global_index => {
+            c.error(child(&pointer, "index"), "MISSING_INDEX", "GlobalIndexWithStrongChecks 需要 index.global_index");
+        }
+        _ => {}
+    }
+    match kind {
+        Some(VariantKind::RegionTextToParent | VariantKind::RegionLocalIndexWithCheck) => {
+            if !is_regional {
+                c.warn(child(&pointer, "scope"), "SCOPE_MISMATCH", "区域策略的 scope 应为 regional");
+            }
+            if !has_string(Some(variant), "container_xpath") {
+                c.warn(child(&pointer, "container_xpath"), "MISSING_CONTAINER", "区域策略缺少 container_xpath，将退化为全局匹配");
+            }
+            if matches!(kind, Some(VariantKind::RegionLocalIndexWithCheck)) && checks == 0 {
+                c.warn(child(&pointer, "checks"), "WEAK_CHECKS", "索引定位建议至少配置一个轻校验");
+            }
+        }
+        Some(VariantKind::GlobalIndexWithStrongChecks) if checks < 2 => {
+            c.warn(child(&pointer, "checks"), "WEAK_CHECKS", "全局索引兜底建议至少配置两个校验");
+        }
+        _ => {}
+    }
+    score
+}
+
+/// 校验 Plan JSON 文本（语法错误也以问题形式返回）
+pub fn validate_plan_json(raw: &str) -> PlanValidationReport {
+    match serde_json::from_str::<Value>(raw) {
+        Ok(value) => validate_plan_value(&value),
+        Err(e) => {
+            let mut c = Checker::default();
+            c.error("", "INVALID_JSON", format!("JSON 解析失败（第 {} 行第 {} 列）: {}", e.line(), e.column(), e));
+            finish(c)
+        }
+    }
+}
+
+/// 校验已解析的 Plan
+pub fn validate_plan_value(value: &Value) -> PlanValidationReport {
+    let mut c = Checker::default();
+    let Some(root) = value.as_object() else {
+        c.error("", "TYPE_MISMATCH", "Plan 应为 JSON 对象");
+        return finish(c);
+    };
+
+    match root.get("version").and_then(Value::as_str) {
+        None => c.warn("/version", "MISSING_FIELD", format!("缺少 version，按 {} 处理", SUPPORTED_VERSION)),
+        Some(SUPPORTED_VERSION) => {}
+        Some(other) => c.error("/version", "UNSUPPORTED_VERSION", format!("不支持的契约版本 {}，当前为 {}", other, SUPPORTED_VERSION)),
+    }
+
+    let variants = match root.get("plan") {
+        None => {
+            c.error("/plan", "MISSING_FIELD", "缺少必填字段 plan");
+            &[][..]
+        }
+        Some(Value::Array(items)) if items.is_empty() => {
+            c.error("/plan", "EMPTY_PLAN", "plan 至少需要一个候选");
+            &[][..]
+        }
+        Some(Value::Array(items)) => items.as_slice(),
+        Some(_) => {
+            c.error("/plan", "TYPE_MISMATCH", "plan 应为数组");
+            &[][..]
+        }
+    };
+
+    let selected = c.object(root, "", "strategy").and_then(|strategy| check_strategy(&mut c, strategy, variants.len()));
+    if let Some(context) = c.object(root, "", "context") {
+        check_context(&mut c, context);
+    }
+
+    // 候选按 static_score 从强到弱排列
+    let mut ids = HashSet::new();
+    let mut previous: Option<(usize, f64)> = None;
+    for (i, variant) in variants.iter().enumerate() {
+        let Some(map) = variant.as_object() else {
+            c.error(format!("/plan/{}", i), "TYPE_MISMATCH", "候选应为对象");
+            continue;
+        };
+        if let Some(score) = check_variant(&mut c, i, map, &mut ids) {
+            if let Some((prev_index, prev_score)) = previous {
+                if score > prev_score {
+                    c.error(
+                        format!("/plan/{}/static_score", i),
+                        "SCORE_ORDER",
+                        format!("static_score {} 高于前一个候选 /plan/{} 的 {}，plan 应按从强到弱排序", score, prev_index, prev_score),
+                    );
+                }
+            }
+            previous = Some((i, score));
+        }
+    }
+    if let Some(selected) = selected {
+        if !ids.is_empty() && !ids.contains(&selected) {
+            c.error("/strategy/selected", "UNKNOWN_SELECTED", format!("selected 指向不存在的候选 {}", selected));
+        }
+    }
+
+    // 结构与语义都通过后，再确认能反序列化为执行端的 Rust 类型
+    if !c.has_errors() {
+        if let Err(e) = serde_json::from_value::<DecisionChainPlan>(value.clone()) {
+            c.error("", "TYPE_MISMATCH", format!("无法解析为 DecisionChainPlan: {}", e));
+        }
+    }
+    finish(c)
+}
+
+fn finish(c: Checker) -> PlanValidationReport {
+    let errors = c.issues.iter().filter(|i| i.severity == IssueSeverity::Error).count();
+    PlanValidationReport { valid: errors == 0, errors, warnings: c.issues.len() - errors, issues: c.issues }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn plan() -> Value {
+        json!({
+            "version": "v2",
+            "strategy": { "selected": "SelfId#1", "time_budget_ms": 1200, "per_candidate_budget_ms": 180 },
+            "context": { "package": "com.app", "screen": { "width": 1080, "height": 1920, "dpi": 440, "orientation": "portrait" } },
+            "plan": [
+                { "id": "SelfId#1", "kind": "SelfId", "scope": "global", "static_score": 0.9, "explain": "id",
+                  "selectors": { "self": { "resource_id": "com.app:id/follow" } } },
+                { "id": "RegionTextToParent#1", "kind": "region_text_to_parent", "scope": "regional", "container_xpath": "//list",
+                  "static_score": 0.7, "explain": "text", "selectors": { "child": { "text": { "equals": "关注" } } } }
+            ]
+        })
+    }
+
+    fn codes(report: &PlanValidationReport) -> Vec<(&str, &str)> {
+        report.issues.iter().map(|i| (i.pointer.as_str(), i.code)).collect()
+    }
+
+    #[test]
+    fn reports_schema_and_semantic_issues_with_pointers() {
+        let report = validate_plan_value(&plan());
+        assert!(report.valid, "{:?}", report.issues);
+        assert_eq!(report.warnings, 0);
+
+        let mut broken = plan();
+        broken["version"] = json!("v3");
+        broken["strategy"]["selected"] = json!("Missing#1");
+        broken["strategy"]["per_candidate_budget_ms"] = json!(2000);
+        broken["plan"][1]["static_score"] = json!(0.95);
+        broken["plan"][1]["kind"] = json!("MagicTap");
+        broken["plan"][0]["selectors"] = json!({ "self": { "text": { "equals": "x" } } });
+        let report = validate_plan_value(&broken);
+        assert!(!report.valid);
+        let found = codes(&report);
+        for expected in [
+            ("/version", "UNSUPPORTED_VERSION"),
+            ("/strategy/selected", "UNKNOWN_SELECTED"),
+            ("/strategy/per_candidate_budget_ms", "OUT_OF_RANGE"),
+            ("/plan/1/static_score", "SCORE_ORDER"),
+            ("/plan/1/kind", "UNKNOWN_VARIANT_KIND"),
+            ("/plan/0/selectors/self/resource_id", "MISSING_SELECTOR"),
+        ] {
+            assert!(found.contains(&expected), "missing {:?} in {:?}", expected, found);
+        }
+
+        let mut tight = plan();
+        tight["strategy"]["time_budget_ms"] = json!(300);
+        tight["plan"][1]["container_xpath"] = Value::Null;
+        let report = validate_plan_value(&tight);
+        assert!(report.valid);
+        assert_eq!(codes(&report), vec![("/strategy/time_budget_ms", "BUDGET_EXHAUSTED"), ("/plan/1/container_xpath", "MISSING_CONTAINER")]);
+
+        let report = validate_plan_json("{ \"plan\": [ }");
+        assert_eq!(codes(&report), vec![("", "INVALID_JSON")]);
+    }
+}
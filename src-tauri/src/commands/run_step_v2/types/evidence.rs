@@ -16,3 +16,57 @@ pub struct StaticEvidence {
     pub global_index: Option<i32>,          // 全局索引
     pub has_light_checks: bool,             // 是否有轻校验
 }
+
+impl StaticEvidence {
+    /// 从步骤参数的 `original_data`（静态分析快照）构建证据包
+    ///
+    /// 没有 `original_data` 时返回 None，调用方跳过三态评分
+    pub fn from_step_params(params: &serde_json::Value) -> Option<Self> {
+        let original_data = params.get("original_data")?;
+        let key_attr = |name: &str| {
+            original_data
+                .get("key_attributes")
+                .and_then(|ka| ka.get(name))
+                .and_then(|v| v.as_str())
+                .filter(|s| !s.is_empty())
+                .map(|s| s.to_string())
+        };
+
+        // 文本别名：自身文本优先，其次子元素文本（父容器+子文本模式）
+        let mut aliases: Vec<String> = original_data
+            .get("element_text")
+            .and_then(|v| v.as_str())
+            .filter(|s| !s.is_empty())
+            .map(|s| vec![s.to_string()])
+            .unwrap_or_default();
+        if let Some(children) = original_data.get("children_texts").and_then(|v| v.as_array()) {
+            aliases.extend(
+                children
+                    .iter()
+                    .filter_map(|v| v.as_str())
+                    .filter(|s| !s.is_empty())
+                    .map(|s| s.to_string()),
+            );
+        }
+
+        Some(Self {
+            resource_id: key_attr("resource-id"),
+            xpath: original_data
+                .get("selected_xpath")
+                .and_then(|v| v.as_str())
+                .filter(|s| !s.is_empty())
+                .map(|s| s.to_string()),
+            text: if aliases.is_empty() { None } else { Some(aliases) },
+            content_desc: key_attr("content-desc"),
+            class_name: key_attr("class"),
+            container_scoped: original_data
+                .get("matching_strategy")
+                .and_then(|v| v.as_str())
+                == Some("middleLayerContainer"),
+            parent_clickable: false,
+            local_index: None,
+            global_index: None,
+            has_light_checks: false,
+        })
+    }
+}
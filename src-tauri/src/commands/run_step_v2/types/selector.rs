@@ -22,6 +22,7 @@ pub struct ElementSelector {
 pub struct VariantSelectors {
     pub parent: Option<ParentSelector>,
     pub child: Option<ChildSelector>,
+    #[serde(alias = "self")] // plan_schema.json 中的字段名
     pub self_: Option<SelfSelector>,
 }
 
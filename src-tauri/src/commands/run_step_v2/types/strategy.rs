@@ -74,13 +74,22 @@ pub struct StrategyVariant {
 #[derive(Debug, Clone, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum VariantKind {
+    // plan_schema.json 使用 PascalCase，两种写法都接受
+    #[serde(alias = "SelfId")]
     SelfId,                      // 直接resource-id匹配
+    #[serde(alias = "SelfDesc")]
     SelfDesc,                    // 直接content-desc匹配  
+    #[serde(alias = "ChildToParent")]
     ChildToParent,               // 子锚点→父执行
+    #[serde(alias = "RegionTextToParent")]
     RegionTextToParent,          // 容器内子锚点→父执行
+    #[serde(alias = "RegionLocalIndexWithCheck")]
     RegionLocalIndexWithCheck,   // 容器内索引+轻校验
+    #[serde(alias = "NeighborRelative")]
     NeighborRelative,            // 邻居相对定位
+    #[serde(alias = "GlobalIndexWithStrongChecks")]
     GlobalIndexWithStrongChecks, // 全局索引+强校验（最后兜底）
+    #[serde(alias = "BoundsTap")]
     BoundsTap,                   // 坐标兜底
 }
 
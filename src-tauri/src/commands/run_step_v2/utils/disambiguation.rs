@@ -79,6 +79,7 @@ mod tests {
             mode: StepRunMode::ExecuteStep,
             strategy: StrategyKind::Standard,
             step: serde_json::Value::Null,
+            run_id: None,
         }
     }
 
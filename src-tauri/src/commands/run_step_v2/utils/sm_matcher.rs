@@ -148,6 +148,7 @@ mod tests {
             mode: crate::commands::run_step_v2::StepRunMode::ExecuteStep,
             strategy: crate::commands::run_step_v2::StrategyKind::Standard,
             step: json!({"action": "tap"}),
+            run_id: None,
         };
         
         // 应该立即返回 None（无需 async runtime）
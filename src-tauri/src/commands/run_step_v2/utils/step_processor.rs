@@ -4,20 +4,19 @@
 
 use serde_json::Value;
 use crate::commands::run_step_v2::{MatchCandidate, Bounds};
+use crate::types::dto::execution::normalize_step_params;
 
 /// 展开 coordinateParams 参数到步骤对象
 /// 
-/// 处理前端发送的嵌套参数结构，将 coordinateParams 中的字段展开到 step 对象根层级
-/// 
-/// # 参数映射
-/// - `duration` → `duration_ms`（前后端参数名映射）
+/// 处理前端发送的嵌套参数结构，将 coordinateParams 中的字段展开到 step 对象根层级；
+/// 键名与时长的规范化由 DTO 层统一完成（`duration` / `durationMs` → `duration_ms`）
 /// 
 /// # 示例
 /// ```json
 /// {
 ///   "action": "swipe",
 ///   "coordinateParams": {
-///     "start_x": 100,
+///     "startX": 100,
 ///     "duration": 500
 ///   }
 /// }
@@ -31,24 +30,7 @@ use crate::commands::run_step_v2::{MatchCandidate, Bounds};
 /// }
 /// ```
 pub fn expand_coordinate_params(step: &Value) -> Value {
-    let mut step_with_coords = step.clone();
-    
-    // 如果前端发送了coordinateParams，展开到step对象中
-    if let Some(coord_params) = step.get("coordinateParams") {
-        if let Some(obj) = coord_params.as_object() {
-            tracing::info!("🔧 展开coordinateParams到step对象: {:?}", obj);
-            for (key, value) in obj {
-                // 🔧 参数名称映射：处理前后端参数名不匹配问题
-                let mapped_key = match key.as_str() {
-                    "duration" => "duration_ms",  // 延时参数映射
-                    _ => key
-                };
-                step_with_coords[mapped_key] = value.clone();
-            }
-        }
-    }
-    
-    step_with_coords
+    normalize_step_params(step)
 }
 
 /// 检测操作是否需要元素选择器
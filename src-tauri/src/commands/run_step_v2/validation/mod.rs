@@ -6,6 +6,7 @@ pub mod xml_parser;
 pub mod safety_checker;
 pub mod bounds_validator;
 pub mod disambiguation;
+pub mod post_assertions;
 
 // 重导出公开API
 pub use xml_parser::{parse_xml_attribute, parse_bounds_from_string};
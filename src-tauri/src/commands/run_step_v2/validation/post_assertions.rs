@@ -0,0 +1,415 @@
+// src-tauri/src/commands/run_step_v2/validation/post_assertions.rs
+// module: step-execution | layer: validation | role: 步骤后置断言
+// summary: 后置条件DSL - 动作执行后基于新鲜UI Dump校验元素存在/消失、文本、Activity、Toast
+//
+// 语法（每条断言一个字符串）：
+//   exists(text="已关注")                     元素存在
+//   absent(id="com.app:id/loading")           元素不存在
+//   text_equals(id="com.app:id/title", "我")  元素文本等于
+//   activity_is("com.app/.MainActivity")      当前Activity（支持只写类名后缀）
+//   toast_contains("关注成功")                 Toast 文本包含
+//
+// 选择器键：text / id / desc / class，多个键之间为 AND 关系。
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+use crate::engine::ui_tree::{Attr, NodeRef, UiTree};
+use crate::services::adb::AdbService;
+
+/// 解析后的断言
+#[derive(Debug, Clone, PartialEq)]
+pub enum PostAssertion {
+    Exists(ElementQuery),
+    Absent(ElementQuery),
+    TextEquals(ElementQuery, String),
+    ActivityIs(String),
+    ToastContains(String),
+}
+
+/// 元素查询条件
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ElementQuery {
+    pub text: Option<String>,
+    pub resource_id: Option<String>,
+    pub content_desc: Option<String>,
+    pub class_name: Option<String>,
+}
+
+/// 单条断言结果
+#[derive(Debug, Clone)]
+pub struct AssertionOutcome {
+    pub expression: String,
+    pub passed: bool,
+    pub message: String,
+}
+
+/// 后置断言评估报告
+#[derive(Debug, Clone, Default)]
+pub struct PostAssertionReport {
+    pub outcomes: Vec<AssertionOutcome>,
+}
+
+impl PostAssertionReport {
+    pub fn passed(&self) -> bool {
+        self.outcomes.iter().all(|o| o.passed)
+    }
+
+    pub fn failed_count(&self) -> usize {
+        self.outcomes.iter().filter(|o| !o.passed).count()
+    }
+
+    /// 生成写入 raw_logs 的日志行
+    pub fn log_lines(&self) -> Vec<String> {
+        self.outcomes
+            .iter()
+            .map(|o| {
+                let mark = if o.passed { "✅" } else { "❌" };
+                format!("{} 后置断言 {}: {}", mark, o.expression, o.message)
+            })
+            .collect()
+    }
+}
+
+/// 断言评估所需的设备快照
+pub struct PostAssertionContext<'a> {
+    pub ui_xml: &'a str,
+    /// 当前前台 Activity（仅在存在 activity_is 断言时需要获取）
+    pub current_activity: Option<String>,
+}
+
+impl PostAssertion {
+    /// 解析单条断言表达式
+    pub fn parse(expression: &str) -> Result<Self, String> {
+        let expr = expression.trim();
+        let open = expr.find('(').ok_or_else(|| format!("断言缺少参数括号: {}", expr))?;
+        if !expr.ends_with(')') {
+            return Err(format!("断言缺少右括号: {}", expr));
+        }
+        let name = expr[..open].trim();
+        let args = split_args(&expr[open + 1..expr.len() - 1])?;
+
+        match name {
+            "exists" => Ok(Self::Exists(parse_query(&args, name)?)),
+            "absent" => Ok(Self::Absent(parse_query(&args, name)?)),
+            "text_equals" => {
+                let (expected, query_args) = args
+                    .split_last()
+                    .filter(|(last, _)| last.key.is_none())
+                    .ok_or_else(|| format!("text_equals 最后一个参数必须是期望文本: {}", expr))?;
+                Ok(Self::TextEquals(parse_query(query_args, name)?, expected.value.clone()))
+            }
+            "activity_is" => Ok(Self::ActivityIs(single_value(&args, name)?)),
+            "toast_contains" => Ok(Self::ToastContains(single_value(&args, name)?)),
+            other => Err(format!("未知断言类型: {}", other)),
+        }
+    }
+
+    /// 是否需要查询当前 Activity
+    pub fn needs_activity(&self) -> bool {
+        matches!(self, Self::ActivityIs(_))
+    }
+
+    fn evaluate(&self, ctx: &PostAssertionContext, tree: &UiTree) -> (bool, String) {
+        match self {
+            Self::Exists(query) => {
+                let count = find_nodes(tree, query).count();
+                if count > 0 {
+                    (true, format!("找到 {} 个匹配元素", count))
+                } else {
+                    (false, format!("期望元素存在，但未找到 {}", query))
+                }
+            }
+            Self::Absent(query) => {
+                let count = find_nodes(tree, query).count();
+                if count == 0 {
+                    (true, "元素已不存在".to_string())
+                } else {
+                    (false, format!("期望元素不存在，但仍找到 {} 个 {}", count, query))
+                }
+            }
+            Self::TextEquals(query, expected) => match find_nodes(tree, query).next() {
+                Some(node) => {
+                    let actual = node.text();
+                    if actual == expected.as_str() {
+                        (true, format!("文本为 '{}'", actual))
+                    } else {
+                        (false, format!("期望文本 '{}'，实际为 '{}'", expected, actual))
+                    }
+                }
+                None => (false, format!("未找到用于比较文本的元素 {}", query)),
+            },
+            Self::ActivityIs(expected) => match ctx.current_activity.as_deref() {
+                Some(actual) if activity_matches(actual, expected) => (true, format!("当前Activity为 {}", actual)),
+                Some(actual) => (false, format!("期望Activity '{}'，实际为 '{}'", expected, actual)),
+                None => (false, "无法获取当前Activity".to_string()),
+            },
+            Self::ToastContains(expected) => {
+                // Toast 在部分ROM上会以 android.widget.Toast 节点出现在 dump 中
+                let toast_texts: Vec<&str> = tree
+                    .nodes()
+                    .filter(|node| node.attr(Attr::Class).map_or(false, |c| c.contains("Toast")))
+                    .filter_map(|node| node.attr(Attr::Text))
+                    .collect();
+                if toast_texts.iter().any(|t| t.contains(expected.as_str())) {
+                    (true, format!("Toast 包含 '{}'", expected))
+                } else if toast_texts.is_empty() {
+                    (false, format!("未捕获到Toast，期望包含 '{}'", expected))
+                } else {
+                    (false, format!("Toast {:?} 不包含 '{}'", toast_texts, expected))
+                }
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for ElementQuery {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let parts: Vec<String> = [
+            ("text", &self.text),
+            ("id", &self.resource_id),
+            ("desc", &self.content_desc),
+            ("class", &self.class_name),
+        ]
+        .iter()
+        .filter_map(|(k, v)| v.as_ref().map(|v| format!("{}=\"{}\"", k, v)))
+        .collect();
+        write!(f, "[{}]", parts.join(", "))
+    }
+}
+
+/// 解析并评估一组断言；解析失败的断言记为失败，不会中断其他断言
+pub fn evaluate_post_assertions(expressions: &[String], ctx: &PostAssertionContext) -> PostAssertionReport {
+    let tree = UiTree::parse_lenient(ctx.ui_xml);
+    let outcomes = expressions
+        .iter()
+        .map(|expression| {
+            let (passed, message) = match PostAssertion::parse(expression) {
+                Ok(assertion) => assertion.evaluate(ctx, &tree),
+                Err(e) => (false, format!("断言语法错误: {}", e)),
+            };
+            AssertionOutcome { expression: expression.clone(), passed, message }
+        })
+        .collect();
+    PostAssertionReport { outcomes }
+}
+
+/// 动作执行后重新获取 UI Dump（必要时查询 Activity）并评估断言
+pub async fn verify_on_device(device_id: &str, expressions: &[String]) -> Result<PostAssertionReport, String> {
+    let adb = AdbService::new();
+    let ui_xml = adb
+        .dump_ui_hierarchy(device_id)
+        .await
+        .map_err(|e| format!("后置断言获取UI Dump失败: {}", e))?;
+
+    let current_activity = if requires_activity(expressions) {
+        adb.get_current_activity(device_id)
+            .await
+            .map_err(|e| format!("后置断言获取Activity失败: {}", e))
+            .map(|raw| extract_activity_component(&raw))?
+    } else {
+        None
+    };
+
+    let report = evaluate_post_assertions(expressions, &PostAssertionContext { ui_xml: &ui_xml, current_activity });
+    tracing::info!("🧪 后置断言: {}/{} 通过", report.outcomes.len() - report.failed_count(), report.outcomes.len());
+    Ok(report)
+}
+
+/// 从步骤参数中读取后置断言：`post_assertions` 或 `strategy.post_assertions`
+pub fn post_assertions_from_step(step: &serde_json::Value) -> Vec<String> {
+    step.get("post_assertions")
+        .or_else(|| step.get("strategy").and_then(|s| s.get("post_assertions")))
+        .and_then(|v| v.as_array())
+        .map(|items| items.iter().filter_map(|v| v.as_str().map(String::from)).collect())
+        .unwrap_or_default()
+}
+
+/// 断言列表中是否包含需要查询 Activity 的断言
+pub fn requires_activity(expressions: &[String]) -> bool {
+    expressions
+        .iter()
+        .filter_map(|e| PostAssertion::parse(e).ok())
+        .any(|a| a.needs_activity())
+}
+
+static ACTIVITY_COMPONENT_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"([A-Za-z0-9_.]+/[A-Za-z0-9_.$]+)").expect("valid activity regex"));
+
+/// 从 `dumpsys` 输出中提取 `package/.Activity` 组件名
+pub fn extract_activity_component(dumpsys_output: &str) -> Option<String> {
+    ACTIVITY_COMPONENT_RE
+        .captures(dumpsys_output)
+        .map(|c| c[1].to_string())
+}
+
+fn activity_matches(actual: &str, expected: &str) -> bool {
+    if actual == expected {
+        return true;
+    }
+    // 支持 "com.app/.Main" 与 "com.app/com.app.Main" 以及只写类名后缀
+    let (pkg, class) = actual.split_once('/').unwrap_or(("", actual));
+    let full_class = if class.starts_with('.') { format!("{}{}", pkg, class) } else { class.to_string() };
+    match expected.split_once('/') {
+        Some((exp_pkg, exp_class)) => {
+            let exp_full = if exp_class.starts_with('.') { format!("{}{}", exp_pkg, exp_class) } else { exp_class.to_string() };
+            exp_pkg == pkg && exp_full == full_class
+        }
+        None => full_class.ends_with(expected),
+    }
+}
+
+fn find_nodes<'a>(tree: &'a UiTree, query: &'a ElementQuery) -> impl Iterator<Item = NodeRef<'a>> + 'a {
+    tree.nodes().filter(move |node| {
+        let attr_is = |attr: Attr, expected: &Option<String>| match expected {
+            Some(value) => node.attr(attr) == Some(value.as_str()),
+            None => true,
+        };
+        attr_is(Attr::Text, &query.text)
+            && attr_is(Attr::ResourceId, &query.resource_id)
+            && attr_is(Attr::ContentDesc, &query.content_desc)
+            && attr_is(Attr::Class, &query.class_name)
+    })
+}
+
+/// 参数：`key="value"` 或 `"value"`
+#[derive(Debug)]
+struct Arg {
+    key: Option<String>,
+    value: String,
+}
+
+fn split_args(raw: &str) -> Result<Vec<Arg>, String> {
+    let mut args = Vec::new();
+    let mut current = String::new();
+    let mut in_quotes = false;
+    for ch in raw.chars() {
+        match ch {
+            '"' => {
+                in_quotes = !in_quotes;
+                current.push(ch);
+            }
+            ',' if !in_quotes => {
+                args.push(parse_arg(&current)?);
+                current.clear();
+            }
+            _ => current.push(ch),
+        }
+    }
+    if in_quotes {
+        return Err(format!("引号未闭合: {}", raw));
+    }
+    if !current.trim().is_empty() {
+        args.push(parse_arg(&current)?);
+    }
+    Ok(args)
+}
+
+fn parse_arg(raw: &str) -> Result<Arg, String> {
+    let raw = raw.trim();
+    let unquote = |s: &str| -> Result<String, String> {
+        let s = s.trim();
+        if s.len() >= 2 && s.starts_with('"') && s.ends_with('"') {
+            Ok(s[1..s.len() - 1].to_string())
+        } else {
+            Err(format!("参数值需要用双引号包裹: {}", s))
+        }
+    };
+    if raw.starts_with('"') {
+        return Ok(Arg { key: None, value: unquote(raw)? });
+    }
+    let (key, value) = raw.split_once('=').ok_or_else(|| format!("无法解析参数: {}", raw))?;
+    Ok(Arg { key: Some(key.trim().to_string()), value: unquote(value)? })
+}
+
+fn parse_query(args: &[Arg], assertion: &str) -> Result<ElementQuery, String> {
+    let mut query = ElementQuery::default();
+    for arg in args {
+        let slot = match arg.key.as_deref() {
+            Some("text") => &mut query.text,
+            Some("id") => &mut query.resource_id,
+            Some("desc") => &mut query.content_desc,
+            Some("class") => &mut query.class_name,
+            Some(other) => return Err(format!("{} 不支持的选择器键: {}", assertion, other)),
+            None => return Err(format!("{} 的选择器参数需要 key=\"value\" 形式", assertion)),
+        };
+        *slot = Some(arg.value.clone());
+    }
+    if query == ElementQuery::default() {
+        return Err(format!("{} 至少需要一个选择器条件", assertion));
+    }
+    Ok(query)
+}
+
+fn single_value(args: &[Arg], assertion: &str) -> Result<String, String> {
+    match args {
+        [Arg { key: None, value }] => Ok(value.clone()),
+        _ => Err(format!("{} 需要且仅需要一个字符串参数", assertion)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const XML: &str = r#"<hierarchy>
+<node text="已关注" resource-id="com.app:id/follow" class="android.widget.Button" content-desc="" />
+<node text="标题" resource-id="com.app:id/title" class="android.widget.TextView" content-desc="" />
+<node text="关注成功" resource-id="" class="android.widget.Toast" content-desc="" />
+</hierarchy>"#;
+
+    fn ctx() -> PostAssertionContext<'static> {
+        PostAssertionContext { ui_xml: XML, current_activity: Some("com.app/.MainActivity".to_string()) }
+    }
+
+    #[test]
+    fn parses_all_assertion_kinds() {
+        assert!(matches!(PostAssertion::parse(r#"exists(text="已关注")"#), Ok(PostAssertion::Exists(_))));
+        assert!(matches!(
+            PostAssertion::parse(r#"text_equals(id="com.app:id/title", "标题")"#),
+            Ok(PostAssertion::TextEquals(_, ref t)) if t == "标题"
+        ));
+        assert!(PostAssertion::parse(r#"exists(foo="x")"#).is_err());
+        assert!(PostAssertion::parse(r#"exists()"#).is_err());
+        assert!(PostAssertion::parse(r#"unknown("x")"#).is_err());
+    }
+
+    #[test]
+    fn evaluates_against_dump_and_activity() {
+        let assertions: Vec<String> = vec![
+            r#"exists(text="已关注", class="android.widget.Button")"#,
+            r#"absent(text="关注")"#,
+            r#"text_equals(id="com.app:id/title", "标题")"#,
+            r#"activity_is(".MainActivity")"#,
+            r#"activity_is("com.app/com.app.MainActivity")"#,
+            r#"toast_contains("成功")"#,
+        ]
+        .into_iter()
+        .map(String::from)
+        .collect();
+        let report = evaluate_post_assertions(&assertions, &ctx());
+        assert!(report.passed(), "{:?}", report.log_lines());
+        assert!(requires_activity(&assertions));
+    }
+
+    #[test]
+    fn failures_carry_clear_messages() {
+        let assertions = vec![
+            r#"text_equals(id="com.app:id/title", "首页")"#.to_string(),
+            r#"exists(id="com.app:id/missing""#.to_string(),
+        ];
+        let report = evaluate_post_assertions(&assertions, &ctx());
+        assert_eq!(report.failed_count(), 2);
+        assert!(report.outcomes[0].message.contains("期望文本 '首页'，实际为 '标题'"));
+        assert!(report.outcomes[1].message.starts_with("断言语法错误"));
+    }
+
+    #[test]
+    fn extracts_activity_component_from_dumpsys() {
+        let line = "  mResumedActivity: ActivityRecord{a1b2 u0 com.ss.android.ugc.aweme/.main.MainActivity t123}";
+        assert_eq!(
+            extract_activity_component(line).as_deref(),
+            Some("com.ss.android.ugc.aweme/.main.MainActivity")
+        );
+    }
+}
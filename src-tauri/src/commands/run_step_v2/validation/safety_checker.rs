@@ -2,6 +2,8 @@
 // module: step-execution | layer: validation | role: 安全检查
 // summary: 执行前安全闸门 - 检测整屏节点和容器类节点，防止误操作
 
+use super::super::matching::matching_config;
+
 /// 检查是否为整屏节点（占屏幕95%以上面积）
 /// 
 /// # 参数
@@ -16,16 +18,17 @@ pub fn check_fullscreen_node(bounds: &(i32, i32, i32, i32)) -> bool {
     let height = (bottom - top) as f32;
     let area = width * height;
     
-    // 假设屏幕大小为 1080x2400（可以后续从设备信息获取）
-    let screen_area = 1080.0 * 2400.0;
+    // 屏幕尺寸与阈值取自匹配配置（默认 1080x2400、95%，后续可从设备信息获取）
+    let safety = &matching_config::current().safety;
+    let screen_area = safety.screen_width as f32 * safety.screen_height as f32;
     let area_ratio = area / screen_area;
     
     tracing::debug!(
-        "🔍 节点面积检查: {}x{} = {:.1}%, 阈值95%", 
-        width as i32, height as i32, area_ratio * 100.0
+        "🔍 节点面积检查: {}x{} = {:.1}%, 阈值{:.0}%", 
+        width as i32, height as i32, area_ratio * 100.0, safety.fullscreen_area_ratio * 100.0
     );
     
-    area_ratio > 0.95
+    area_ratio > safety.fullscreen_area_ratio
 }
 
 /// 检查是否为容器类节点（不应该被直接点击）
@@ -17,6 +17,7 @@ use crate::core::domain::mde_extraction::{
     MdeSelector, MdeSelectorCandidates, MdeDataType, MdeExtractionResult,
     MdeExtractedItem, MdeFieldValue, MdeExtractionMethod,
 };
+use crate::services::list_tracking::{ListTracker, DEFAULT_MAX_STALE_PAGES};
 
 // ============================================================================
 // MDE 工具注册
@@ -253,9 +254,8 @@ async fn handle_scroll_and_collect(
     info!("📜 开始滚动收集: 最大滚动 {} 次", max_scroll);
     
     let mut all_items: Vec<MdeExtractedItem> = vec![];
-    let mut seen_keys: std::collections::HashSet<String> = std::collections::HashSet::new();
-    let mut no_new_data_count = 0;
-    const MAX_NO_NEW_DATA: usize = 2; // 连续2次没有新数据则停止
+    // 按数据项唯一键追踪已见项；连续 2 页无新数据或滚动后页面不变则停止
+    let mut tracker = ListTracker::new(None, DEFAULT_MAX_STALE_PAGES);
     
     for scroll_idx in 0..=max_scroll {
         // 获取当前屏幕
@@ -276,32 +276,21 @@ async fn handle_scroll_and_collect(
             }
         };
         
-        // 去重合并
-        let mut new_count = 0;
-        for item in items {
-            // 使用字段组合作为唯一键
-            let key = generate_item_key(&item);
-            if !seen_keys.contains(&key) {
-                seen_keys.insert(key);
-                all_items.push(item);
-                new_count += 1;
-            }
-        }
+        // 去重合并（使用字段组合作为唯一键）
+        let keys: Vec<String> = items.iter().map(generate_item_key).collect();
+        let page = tracker.observe(&keys);
+        let mut items: Vec<Option<MdeExtractedItem>> = items.into_iter().map(Some).collect();
+        all_items.extend(page.new_indices.iter().filter_map(|&i| items[i].take()));
         
         info!(
-            "📜 滚动 {}/{}: 新增 {} 条, 总计 {} 条",
-            scroll_idx, max_scroll, new_count, all_items.len()
+            "📜 滚动 {}/{}: 新增 {} 条, 重复 {} 条, 总计 {} 条",
+            scroll_idx, max_scroll, page.new_indices.len(), page.repeated, all_items.len()
         );
         
-        // 检查是否有新数据
-        if new_count == 0 {
-            no_new_data_count += 1;
-            if no_new_data_count >= MAX_NO_NEW_DATA {
-                info!("📜 连续 {} 次无新数据，停止滚动", MAX_NO_NEW_DATA);
-                break;
-            }
-        } else {
-            no_new_data_count = 0;
+        // 检查是否到底
+        if page.end_reached {
+            info!("📜 列表已到底（无新数据或页面不再变化），停止滚动");
+            break;
         }
         
         // 如果还没到最大次数，执行滚动
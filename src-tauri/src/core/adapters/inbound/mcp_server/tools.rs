@@ -7,7 +7,7 @@ use serde_json::{json, Value};
 use tracing::{info, error};
 
 use super::protocol::{McpTool, ToolResult};
-use crate::core::application::AppContext;
+use crate::core::application::{AppContext, sandbox_service};
 use crate::core::domain::script::{Script, ScriptStep, ClickTarget, StepAction, InputContent, WaitParams, SwipeParams};
 
 /// 注册所有 MCP 工具
@@ -598,6 +598,13 @@ pub async fn execute_tool(
 ) -> ToolResult {
     info!("🔧 MCP 工具调用: {} with {:?}", tool_name, params);
 
+    // 沙箱检查（MDE 工具同样受限）
+    let device_id = params.get("device_id").and_then(|v| v.as_str());
+    let package = params.get("package_name").and_then(|v| v.as_str());
+    if let Err(e) = sandbox_service::check_action(tool_name, device_id, package) {
+        return ToolResult::error(e.to_string());
+    }
+
     // 先尝试 MDE 工具
     if let Some(result) = super::mde_tools::execute_mde_tool(tool_name, params.clone(), ctx).await {
         return result;
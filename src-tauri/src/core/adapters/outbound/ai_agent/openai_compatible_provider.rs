@@ -3,14 +3,16 @@
 // summary: OpenAI 兼容 API 提供商实现 - 支持 OpenAI、混元、DeepSeek 等
 
 use async_trait::async_trait;
+use futures_util::StreamExt;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 use tracing::{info, warn, error, debug};
 
+use crate::ai::sse::{SseDecoder, StreamAccumulator};
 use crate::core::domain::agent::{
     AgentMessage, AgentTool, AiProvider, AiProviderConfig,
-    MessageRole, ToolCall, FunctionCall,
+    MessageRole, ToolCall, FunctionCall, StreamEvent,
 };
 use crate::core::shared::{CoreError, CoreResult};
 
@@ -134,6 +136,54 @@ impl OpenAiCompatibleProvider {
             message.content.clone().unwrap_or_default()
         ))
     }
+
+    /// 发送请求并检查状态码
+    async fn send(&self, body: &Value) -> CoreResult<reqwest::Response> {
+        let url = format!("{}/chat/completions", self.config.base_url);
+
+        let response = self.client
+            .post(&url)
+            .header("Authorization", format!("Bearer {}", self.config.api_key))
+            .header("Content-Type", "application/json")
+            .json(body)
+            .send()
+            .await
+            .map_err(|e| CoreError::external_service(format!("请求失败: {}", e)))?;
+
+        let status = response.status();
+
+        if !status.is_success() {
+            let error_text = response.text().await.unwrap_or_default();
+            error!("❌ AI API 错误 [{}]: {}", status, error_text);
+            return Err(CoreError::external_service(format!(
+                "AI API 错误 [{}]: {}",
+                status, error_text
+            )));
+        }
+
+        Ok(response)
+    }
+
+    /// 将流式累积结果转换为完整消息
+    fn accumulated_message(acc: StreamAccumulator) -> AgentMessage {
+        if acc.tool_calls.is_empty() {
+            return AgentMessage::assistant(acc.content);
+        }
+
+        let calls = acc.tool_calls.into_iter().map(|tc| ToolCall {
+            id: tc.id,
+            call_type: if tc.call_type.is_empty() { "function".to_string() } else { tc.call_type },
+            function: FunctionCall {
+                name: tc.name,
+                arguments: tc.arguments,
+            },
+        }).collect();
+
+        AgentMessage::assistant_with_tools(
+            if acc.content.is_empty() { None } else { Some(acc.content) },
+            calls,
+        )
+    }
 }
 
 #[async_trait]
@@ -155,8 +205,6 @@ impl AiProvider for OpenAiCompatibleProvider {
         messages: Vec<AgentMessage>,
         tools: Vec<AgentTool>,
     ) -> CoreResult<AgentMessage> {
-        let url = format!("{}/chat/completions", self.config.base_url);
-        
         let body = self.build_request_body(
             &messages,
             if tools.is_empty() { None } else { Some(&tools) },
@@ -164,25 +212,7 @@ impl AiProvider for OpenAiCompatibleProvider {
 
         debug!("🤖 发送 AI 请求到 {}: {:?}", self.config.name, body);
 
-        let response = self.client
-            .post(&url)
-            .header("Authorization", format!("Bearer {}", self.config.api_key))
-            .header("Content-Type", "application/json")
-            .json(&body)
-            .send()
-            .await
-            .map_err(|e| CoreError::external_service(format!("请求失败: {}", e)))?;
-
-        let status = response.status();
-        
-        if !status.is_success() {
-            let error_text = response.text().await.unwrap_or_default();
-            error!("❌ AI API 错误 [{}]: {}", status, error_text);
-            return Err(CoreError::external_service(format!(
-                "AI API 错误 [{}]: {}",
-                status, error_text
-            )));
-        }
+        let response = self.send(&body).await?;
 
         let response_body: ChatCompletionResponse = response.json().await
             .map_err(|e| CoreError::external_service(format!("解析响应失败: {}", e)))?;
@@ -199,6 +229,67 @@ impl AiProvider for OpenAiCompatibleProvider {
 
         self.parse_response(&response_body)
     }
+
+    async fn chat_stream(
+        &self,
+        messages: Vec<AgentMessage>,
+        tools: Vec<AgentTool>,
+        callback: Box<dyn Fn(StreamEvent) + Send>,
+    ) -> CoreResult<()> {
+        let mut body = self.build_request_body(
+            &messages,
+            if tools.is_empty() { None } else { Some(&tools) },
+        );
+        body["stream"] = json!(true);
+
+        debug!("🤖 发送流式 AI 请求到 {}", self.config.name);
+
+        let response = self.send(&body).await?;
+        let mut decoder = SseDecoder::new();
+        let mut acc = StreamAccumulator::new();
+        let mut announced_calls = 0;
+        let mut stream = response.bytes_stream();
+
+        while let Some(chunk) = stream.next().await {
+            let chunk = chunk.map_err(|e| {
+                callback(StreamEvent::Error(e.to_string()));
+                CoreError::external_service(format!("读取流式响应失败: {}", e))
+            })?;
+
+            for payload in decoder.push(&chunk) {
+                match acc.apply(&payload) {
+                    Ok(Some(delta)) => callback(StreamEvent::Delta(delta)),
+                    Ok(None) => {}
+                    Err(e) => {
+                        callback(StreamEvent::Error(e.clone()));
+                        return Err(CoreError::external_service(format!("流式响应错误: {}", e)));
+                    }
+                }
+
+                // 新出现的工具调用在拿到名称后通知一次
+                while announced_calls < acc.tool_calls.len() && !acc.tool_calls[announced_calls].name.is_empty() {
+                    let tc = &acc.tool_calls[announced_calls];
+                    callback(StreamEvent::ToolCallStart(ToolCall {
+                        id: tc.id.clone(),
+                        call_type: "function".to_string(),
+                        function: FunctionCall {
+                            name: tc.name.clone(),
+                            arguments: String::new(),
+                        },
+                    }));
+                    announced_calls += 1;
+                }
+            }
+
+            if decoder.is_done() {
+                break;
+            }
+        }
+
+        callback(StreamEvent::Message(Self::accumulated_message(acc)));
+        callback(StreamEvent::Done);
+        Ok(())
+    }
 }
 
 // ============================================================================
@@ -33,7 +33,7 @@ impl FileScriptRepository {
     async fn ensure_dir(&self) -> CoreResult<()> {
         if !self.base_path.exists() {
             fs::create_dir_all(&self.base_path).await.map_err(|e| {
-                CoreError::new(ErrorCode::FileWriteError, format!("创建目录失败: {}", e))
+                CoreError::new(ErrorCode::StorageError, format!("创建目录失败: {}", e))
             })?;
         }
         Ok(())
@@ -49,7 +49,7 @@ impl ScriptRepository for FileScriptRepository {
         let content = serde_json::to_string_pretty(script)?;
         
         fs::write(&path, content).await.map_err(|e| {
-            CoreError::new(ErrorCode::FileWriteError, format!("写入文件失败: {}", e))
+            CoreError::new(ErrorCode::StorageError, format!("写入文件失败: {}", e))
         })?;
         
         debug!("💾 脚本已保存: {:?}", path);
@@ -64,7 +64,7 @@ impl ScriptRepository for FileScriptRepository {
         }
         
         let content = fs::read_to_string(&path).await.map_err(|e| {
-            CoreError::new(ErrorCode::FileReadError, format!("读取文件失败: {}", e))
+            CoreError::new(ErrorCode::StorageError, format!("读取文件失败: {}", e))
         })?;
         
         // 🔥 使用统一加载函数，自动检测并转换脚本格式
@@ -82,7 +82,7 @@ impl ScriptRepository for FileScriptRepository {
         }
         
         fs::remove_file(&path).await.map_err(|e| {
-            CoreError::new(ErrorCode::FileWriteError, format!("删除文件失败: {}", e))
+            CoreError::new(ErrorCode::StorageError, format!("删除文件失败: {}", e))
         })?;
         
         debug!("🗑️ 脚本已删除: {:?}", path);
@@ -94,11 +94,11 @@ impl ScriptRepository for FileScriptRepository {
         
         let mut scripts = Vec::new();
         let mut entries = fs::read_dir(&self.base_path).await.map_err(|e| {
-            CoreError::new(ErrorCode::FileReadError, format!("读取目录失败: {}", e))
+            CoreError::new(ErrorCode::StorageError, format!("读取目录失败: {}", e))
         })?;
         
         while let Some(entry) = entries.next_entry().await.map_err(|e| {
-            CoreError::new(ErrorCode::FileReadError, format!("读取目录项失败: {}", e))
+            CoreError::new(ErrorCode::StorageError, format!("读取目录项失败: {}", e))
         })? {
             let path = entry.path();
             
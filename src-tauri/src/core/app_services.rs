@@ -0,0 +1,106 @@
+// src-tauri/src/core/app_services.rs
+// module: core | layer: infrastructure | role: composition-root
+// summary: 应用服务容器 - 在组合根中一次性构建所有共享服务，统一注入 Tauri 命令
+
+use std::sync::Arc;
+
+use parking_lot::Mutex;
+use tauri::{AppHandle, Manager, Runtime};
+use tokio::sync::RwLock;
+use tracing::{info, warn};
+
+use crate::commands::enhanced_location_commands::XPathGeneratorState;
+use crate::core::application::AppContext;
+use crate::modules::ai::AiState;
+use crate::services::adb::AdbService;
+use crate::services::employee_service::EmployeeService;
+use crate::services::execution::matching::SmartXPathGenerator;
+use crate::services::smart_app_manager::SmartAppManagerState;
+
+/// 应用共享服务容器
+///
+/// main.rs 中原先分散 `manage` 的共享服务都以类型化句柄的形式挂在这里，
+/// 服务之间可以直接互相调用，无需经过 Tauri State。
+/// 插件私有状态（如 ProspectingState、AgentState）仍由各插件在 setup 中注册。
+///
+/// 会阻塞的服务（ADB 子进程、员工 SQLite）只能通过 `run_adb` / `with_employees`
+/// 在阻塞线程池中调用，锁只在阻塞线程内持有，不会卡住异步运行时。
+pub struct AppServices {
+    /// 无状态，调用时不需要加锁
+    adb: AdbService,
+    /// 员工数据库可能打开失败，失败时保留错误信息，命令调用时返回
+    employees: Result<Arc<Mutex<EmployeeService>>, String>,
+    /// 各设备的应用管理器（内部为异步锁）
+    pub apps: SmartAppManagerState,
+    /// 智能 XPath 生成器（增强定位命令共享）
+    pub xpath_generator: XPathGeneratorState,
+    /// AI 配置与进行中的请求（ai 插件与潜客评论向量化共享）
+    pub ai: AiState,
+    /// 六边形架构上下文（MCP 服务器启动后异步注入）
+    core_context: RwLock<Option<Arc<AppContext>>>,
+}
+
+/// 注册到 Tauri 的共享句柄类型
+pub type SharedAppServices = Arc<AppServices>;
+
+impl AppServices {
+    /// 组合根：构建全部共享服务
+    pub fn build() -> SharedAppServices {
+        let employees = EmployeeService::new()
+            .map(|service| Arc::new(Mutex::new(service)))
+            .map_err(|e| {
+                warn!("⚠️ 员工数据库初始化失败: {}", e);
+                format!("员工数据库初始化失败: {}", e)
+            });
+
+        info!("🧱 应用服务容器已构建");
+
+        Arc::new(Self {
+            adb: AdbService::new(),
+            employees,
+            apps: SmartAppManagerState::new(),
+            xpath_generator: XPathGeneratorState::new(SmartXPathGenerator::new()),
+            ai: AiState::new(),
+            core_context: RwLock::new(None),
+        })
+    }
+
+    /// 在阻塞线程池中执行 ADB 调用
+    pub async fn run_adb<T, F>(&self, f: F) -> Result<T, String>
+    where
+        T: Send + 'static,
+        F: FnOnce(&AdbService) -> Result<T, String> + Send + 'static,
+    {
+        let adb = self.adb.clone();
+        tokio::task::spawn_blocking(move || f(&adb))
+            .await
+            .map_err(|e| format!("ADB 任务执行失败: {}", e))?
+    }
+
+    /// 在阻塞线程池中持锁访问员工服务
+    pub async fn with_employees<T, F>(&self, f: F) -> Result<T, String>
+    where
+        T: Send + 'static,
+        F: FnOnce(&mut EmployeeService) -> Result<T, String> + Send + 'static,
+    {
+        let employees = self.employees.clone()?;
+        tokio::task::spawn_blocking(move || f(&mut employees.lock()))
+            .await
+            .map_err(|e| format!("员工服务任务执行失败: {}", e))?
+    }
+
+    /// 注入六边形架构上下文
+    pub async fn set_core_context(&self, ctx: Arc<AppContext>) {
+        *self.core_context.write().await = Some(ctx);
+    }
+
+    /// 获取六边形架构上下文（尚未启动时返回 None）
+    pub async fn core_context(&self) -> Option<Arc<AppContext>> {
+        self.core_context.read().await.clone()
+    }
+}
+
+/// 从 AppHandle 获取共享服务容器（供非命令代码使用）
+pub fn app_services<R: Runtime>(app: &AppHandle<R>) -> Option<SharedAppServices> {
+    app.try_state::<SharedAppServices>().map(|s| s.inner().clone())
+}
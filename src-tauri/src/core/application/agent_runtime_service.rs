@@ -7,6 +7,7 @@ use crate::core::domain::agent::{AgentSession, AiProvider, ToolProvider};
 use std::sync::Arc;
 use tokio::sync::{mpsc, RwLock, watch};
 use tracing::{info, warn, error};
+use ts_rs::TS;
 
 /// Agent 控制命令
 #[derive(Debug, Clone)]
@@ -26,7 +27,7 @@ pub enum AgentCommand {
 }
 
 /// Agent 事件（用于通知前端）
-#[derive(Debug, Clone, serde::Serialize)]
+#[derive(Debug, Clone, serde::Serialize, TS)]
 #[serde(tag = "type", rename_all = "camelCase")]
 pub enum AgentEvent {
     /// 状态变化
@@ -37,12 +38,29 @@ pub enum AgentEvent {
     ActionExecuted { action: String, result: String, success: bool },
     /// 需要人工确认
     ApprovalRequired { action: String, risk_level: String },
+    /// Agent 在沙箱目录写入了文件（路径相对沙箱目录）
+    FileWritten {
+        path: String,
+        #[ts(type = "number")]
+        bytes: u64,
+        sha256: String,
+        appended: bool,
+    },
     /// 目标完成
     GoalCompleted { goal_id: String },
     /// 目标失败
     GoalFailed { goal_id: String, reason: String },
     /// AI 思考
     AiThinking { thought: String },
+    /// AI 流式输出的增量文本（同一次调用共享 stream_id，只推送不记录）
+    ThinkingDelta { stream_id: String, delta: String },
+    /// 副驾驶建议（仅建议，不执行）
+    Suggestion {
+        suggestion_id: String,
+        action: String,
+        params: serde_json::Value,
+        thought: String,
+    },
     /// 错误
     Error { message: String },
 }
@@ -87,6 +105,21 @@ impl AgentRuntime {
         }
     }
 
+    /// 获取运行时配置
+    pub fn config(&self) -> &AgentConfig {
+        &self.config
+    }
+
+    /// 获取运行模式
+    pub fn mode(&self) -> AgentMode {
+        self.mode
+    }
+
+    /// 设置运行模式（启动目标时按前端选择切换）
+    pub fn set_mode(&mut self, mode: AgentMode) {
+        self.mode = mode;
+    }
+
     /// 获取当前状态快照
     pub fn snapshot(&self) -> AgentStateSnapshot {
         AgentStateSnapshot {
@@ -8,10 +8,13 @@ use tracing::{info, warn, error};
 
 use crate::core::domain::agent::{
     AgentSession, AgentMessage, AgentTool, AiProvider, AiProviderConfig,
-    ToolProvider, ToolCall, SessionStatus,
+    ToolProvider, ToolCall, SessionStatus, StreamEvent, fit_messages_to_budget,
 };
 use crate::core::shared::{CoreError, CoreResult};
 
+/// 流式增量文本回调
+pub type DeltaCallback = Arc<dyn Fn(&str) + Send + Sync>;
+
 /// AI Agent 应用服务
 /// 
 /// 这是 AI 代理相关所有用例的统一入口。
@@ -21,6 +24,9 @@ use crate::core::shared::{CoreError, CoreResult};
 pub struct AgentAppService {
     /// AI 提供商（用于内嵌模式）
     ai_provider: Option<Arc<dyn AiProvider>>,
+
+    /// 摘要等辅助任务使用的廉价提供商（为空时使用主提供商）
+    summary_provider: Option<Arc<dyn AiProvider>>,
     
     /// 工具提供商（MCP 工具桥接）
     tool_provider: Arc<dyn ToolProvider>,
@@ -34,6 +40,7 @@ impl AgentAppService {
     pub fn new(tool_provider: Arc<dyn ToolProvider>) -> Self {
         Self {
             ai_provider: None,
+            summary_provider: None,
             tool_provider,
             active_session: RwLock::new(None),
         }
@@ -45,6 +52,29 @@ impl AgentAppService {
         self
     }
 
+    /// 设置摘要用的廉价提供商
+    pub fn with_summary_provider(mut self, provider: Arc<dyn AiProvider>) -> Self {
+        self.summary_provider = Some(provider);
+        self
+    }
+
+    /// 主模型可用于输入的 token 预算
+    pub fn input_budget_tokens(&self) -> Option<usize> {
+        self.ai_provider.as_ref().map(|p| p.config().input_budget_tokens())
+    }
+
+    /// 单次无状态调用（不带工具、不写入会话），用于摘要历史等辅助任务
+    pub async fn summarize(&self, prompt: &str) -> CoreResult<String> {
+        let provider = self.summary_provider.as_ref()
+            .or(self.ai_provider.as_ref())
+            .ok_or_else(|| CoreError::not_configured("AI 提供商未配置"))?;
+
+        let budget = provider.config().input_budget_tokens();
+        let messages = fit_messages_to_budget(vec![AgentMessage::user(prompt)], budget);
+        let response = provider.chat(messages).await?;
+        Ok(response.content)
+    }
+
     /// 配置 AI 提供商
     pub fn set_ai_provider(&mut self, provider: Arc<dyn AiProvider>) {
         self.ai_provider = Some(provider);
@@ -88,6 +118,40 @@ impl AgentAppService {
 
     /// 发送消息并获取回复（自动处理工具调用）
     pub async fn chat(&self, user_message: &str) -> CoreResult<String> {
+        self.run_chat(user_message, None).await
+    }
+
+    /// 流式发送消息：AI 输出的每段增量文本实时回调，返回值仍是最终完整回复
+    pub async fn chat_streaming(&self, user_message: &str, on_delta: DeltaCallback) -> CoreResult<String> {
+        self.run_chat(user_message, Some(on_delta)).await
+    }
+
+    /// 单轮流式请求，收集完整消息
+    async fn stream_once(
+        provider: &Arc<dyn AiProvider>,
+        messages: Vec<AgentMessage>,
+        tools: Vec<AgentTool>,
+        on_delta: DeltaCallback,
+    ) -> CoreResult<AgentMessage> {
+        let final_message: Arc<std::sync::Mutex<Option<AgentMessage>>> = Arc::new(std::sync::Mutex::new(None));
+        let sink = final_message.clone();
+
+        provider.chat_stream(messages, tools, Box::new(move |event| match event {
+            StreamEvent::Delta(text) => on_delta(&text),
+            StreamEvent::Message(message) => {
+                if let Ok(mut slot) = sink.lock() {
+                    *slot = Some(message);
+                }
+            }
+            StreamEvent::Error(e) => warn!("⚠️ AI 流式响应错误: {}", e),
+            StreamEvent::ToolCallStart(_) | StreamEvent::Done => {}
+        })).await?;
+
+        let message = final_message.lock().ok().and_then(|mut slot| slot.take());
+        message.ok_or_else(|| CoreError::external_service("AI 流式响应未返回完整消息"))
+    }
+
+    async fn run_chat(&self, user_message: &str, on_delta: Option<DeltaCallback>) -> CoreResult<String> {
         let provider = self.ai_provider.as_ref()
             .ok_or_else(|| CoreError::not_configured("AI 提供商未配置"))?;
 
@@ -124,9 +188,15 @@ impl AgentAppService {
                 break;
             }
 
-            // 发送给 AI
-            let messages = session.build_messages_for_ai();
-            let response = provider.chat_with_tools(messages, tools.clone()).await?;
+            // 发送给 AI（按模型上下文上限裁剪旧轮次）
+            let messages = fit_messages_to_budget(
+                session.build_messages_for_ai(),
+                provider.config().input_budget_tokens(),
+            );
+            let response = match &on_delta {
+                Some(cb) => Self::stream_once(provider, messages, tools.clone(), cb.clone()).await?,
+                None => provider.chat_with_tools(messages, tools.clone()).await?,
+            };
 
             // 检查是否有工具调用
             if let Some(tool_calls) = &response.tool_calls {
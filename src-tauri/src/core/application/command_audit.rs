@@ -0,0 +1,342 @@
+// src-tauri/src/core/application/command_audit.rs
+// module: core/application | layer: application | role: command-audit
+// summary: 命令审计记录 - 在插件分发层记录每个 Tauri 命令的名称、脱敏参数摘要、调用方、耗时与分发结果，滚动保留并支持筛选查询
+
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use chrono::{DateTime, Duration as ChronoDuration, Utc};
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use tracing::warn;
+
+const DB_FILE_NAME: &str = "command_audit.db";
+/// 最多保留的记录数
+const MAX_ROWS: i64 = 100_000;
+/// 记录保留天数
+const RETENTION_DAYS: i64 = 30;
+/// 每写入多少条做一次清理
+const PRUNE_EVERY: u64 = 500;
+/// 参数快照最多保留的字符数
+const SNAPSHOT_MAX_CHARS: usize = 512;
+const DEFAULT_QUERY_LIMIT: usize = 200;
+const MAX_QUERY_LIMIT: usize = 5_000;
+/// 参数名（去掉 `_` / `-` 并转小写后）包含这些片段时值被替换为 `***`，`licenseKey` 与 `license_key` 同样命中
+const SENSITIVE_KEY_PARTS: &[&str] =
+    &["password", "passwd", "token", "secret", "apikey", "cookie", "authorization", "licensekey", "privatekey"];
+
+/// 全局命令审计
+pub static COMMAND_AUDIT: Lazy<CommandAudit> = Lazy::new(CommandAudit::open_default);
+
+/// 分发结果。异步命令在分发层只能看到是否成功派发，命令自身返回的错误不在此记录
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditStatus {
+    /// 已交给命令处理（同步命令此时已执行完毕）
+    Dispatched,
+    /// 分发前被拒绝（如只读模式）
+    Rejected,
+    /// 命令处理中 panic
+    Panicked,
+}
+
+impl AuditStatus {
+    fn as_str(self) -> &'static str {
+        match self {
+            AuditStatus::Dispatched => "dispatched",
+            AuditStatus::Rejected => "rejected",
+            AuditStatus::Panicked => "panicked",
+        }
+    }
+
+    fn parse(s: &str) -> AuditStatus {
+        match s {
+            "rejected" => AuditStatus::Rejected,
+            "panicked" => AuditStatus::Panicked,
+            _ => AuditStatus::Dispatched,
+        }
+    }
+}
+
+/// 脱敏后的参数：摘要用于比对“是不是同一批参数”，快照用于直接查看
+#[derive(Debug, Clone, PartialEq)]
+pub struct AuditArgs {
+    pub digest: String,
+    pub snapshot: String,
+}
+
+impl AuditArgs {
+    pub fn from_json(args: &Value) -> Self {
+        let sanitized = sanitize(args);
+        let text = sanitized.to_string();
+        let digest = hex::encode(Sha256::digest(text.as_bytes()))[..16].to_string();
+        let snapshot = if text.chars().count() > SNAPSHOT_MAX_CHARS {
+            format!("{}…", text.chars().take(SNAPSHOT_MAX_CHARS).collect::<String>())
+        } else {
+            text
+        };
+        Self { digest, snapshot }
+    }
+
+    /// 二进制参数只记录长度
+    pub fn from_raw(len: usize) -> Self {
+        Self::from_json(&serde_json::json!({ "raw_bytes": len }))
+    }
+}
+
+fn is_sensitive(key: &str) -> bool {
+    let key: String = key.chars().filter(|c| !matches!(c, '_' | '-')).collect::<String>().to_ascii_lowercase();
+    SENSITIVE_KEY_PARTS.iter().any(|part| key.contains(part))
+}
+
+fn sanitize(value: &Value) -> Value {
+    match value {
+        Value::Object(map) => Value::Object(
+            map.iter()
+                .map(|(k, v)| {
+                    let v = if is_sensitive(k) { Value::String("***".to_string()) } else { sanitize(v) };
+                    (k.clone(), v)
+                })
+                .collect(),
+        ),
+        Value::Array(items) => Value::Array(items.iter().map(sanitize).collect()),
+        other => other.clone(),
+    }
+}
+
+/// 一条待写入的审计记录
+#[derive(Debug, Clone)]
+pub struct AuditRecord {
+    pub command: String,
+    pub module: String,
+    pub caller: String,
+    pub args: AuditArgs,
+    pub duration_ms: u64,
+    pub status: AuditStatus,
+    pub error: Option<String>,
+}
+
+/// 查询结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditEntry {
+    pub id: i64,
+    pub at: DateTime<Utc>,
+    pub command: String,
+    pub module: String,
+    pub caller: String,
+    pub args_digest: String,
+    pub args_snapshot: String,
+    pub duration_ms: u64,
+    pub status: AuditStatus,
+    pub error: Option<String>,
+}
+
+/// 查询条件，均可省略；结果按时间倒序
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditQuery {
+    /// 命令名，包含匹配
+    pub command: Option<String>,
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+    pub status: Option<AuditStatus>,
+    pub limit: Option<usize>,
+}
+
+pub struct CommandAudit {
+    conn: Mutex<Connection>,
+    writes: AtomicU64,
+}
+
+impl CommandAudit {
+    fn open_default() -> Self {
+        let dir = dirs::data_dir().unwrap_or_else(|| PathBuf::from(".")).join("employee-gui");
+        let opened = std::fs::create_dir_all(&dir)
+            .map_err(|e| e.to_string())
+            .and_then(|_| Connection::open(dir.join(DB_FILE_NAME)).map_err(|e| e.to_string()))
+            .and_then(|conn| Self::new(conn).map_err(|e| e.to_string()));
+        opened.unwrap_or_else(|e| {
+            warn!("⚠️ 命令审计库打开失败，改用内存库: {}", e);
+            Self::in_memory()
+        })
+    }
+
+    pub fn new(conn: Connection) -> rusqlite::Result<Self> {
+        conn.execute_batch(
+            "PRAGMA journal_mode = WAL;
+            PRAGMA synchronous = NORMAL;
+            CREATE TABLE IF NOT EXISTS command_audit (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                at_ms INTEGER NOT NULL,
+                command TEXT NOT NULL,
+                module TEXT NOT NULL,
+                caller TEXT NOT NULL,
+                args_digest TEXT NOT NULL,
+                args_snapshot TEXT NOT NULL,
+                duration_ms INTEGER NOT NULL,
+                status TEXT NOT NULL,
+                error TEXT
+            );
+            CREATE INDEX IF NOT EXISTS idx_command_audit_at ON command_audit(at_ms);
+            CREATE INDEX IF NOT EXISTS idx_command_audit_command ON command_audit(command, at_ms);",
+        )?;
+        Ok(Self { conn: Mutex::new(conn), writes: AtomicU64::new(0) })
+    }
+
+    pub fn in_memory() -> Self {
+        Self::new(Connection::open_in_memory().expect("open in-memory sqlite")).expect("init audit schema")
+    }
+
+    /// 写入一条记录；失败只记日志，不影响命令分发
+    pub fn record(&self, record: AuditRecord) {
+        self.record_at(record, Utc::now());
+    }
+
+    fn record_at(&self, record: AuditRecord, at: DateTime<Utc>) {
+        let conn = self.conn.lock();
+        let inserted = conn.execute(
+            "INSERT INTO command_audit
+                (at_ms, command, module, caller, args_digest, args_snapshot, duration_ms, status, error)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+            params![
+                at.timestamp_millis(),
+                record.command,
+                record.module,
+                record.caller,
+                record.args.digest,
+                record.args.snapshot,
+                record.duration_ms as i64,
+                record.status.as_str(),
+                record.error,
+            ],
+        );
+        if let Err(e) = inserted {
+            warn!("⚠️ 命令审计写入失败: {}", e);
+            return;
+        }
+        if self.writes.fetch_add(1, Ordering::Relaxed) % PRUNE_EVERY == 0 {
+            if let Err(e) = Self::prune(&conn, at, MAX_ROWS) {
+                warn!("⚠️ 命令审计清理失败: {}", e);
+            }
+        }
+    }
+
+    /// 删除超出保留天数和条数上限的旧记录
+    fn prune(conn: &Connection, now: DateTime<Utc>, max_rows: i64) -> rusqlite::Result<()> {
+        let cutoff = (now - ChronoDuration::days(RETENTION_DAYS)).timestamp_millis();
+        conn.execute("DELETE FROM command_audit WHERE at_ms < ?1", params![cutoff])?;
+        conn.execute(
+            "DELETE FROM command_audit WHERE id <= (SELECT id FROM command_audit ORDER BY id DESC LIMIT 1 OFFSET ?1)",
+            params![max_rows],
+        )?;
+        Ok(())
+    }
+
+    pub fn query(&self, query: &AuditQuery) -> Result<Vec<AuditEntry>, String> {
+        let limit = query.limit.unwrap_or(DEFAULT_QUERY_LIMIT).clamp(1, MAX_QUERY_LIMIT) as i64;
+        let conn = self.conn.lock();
+        let mut stmt = conn
+            .prepare(
+                "SELECT id, at_ms, command, module, caller, args_digest, args_snapshot, duration_ms, status, error
+                 FROM command_audit
+                 WHERE (?1 IS NULL OR command LIKE '%' || ?1 || '%')
+                   AND (?2 IS NULL OR at_ms >= ?2)
+                   AND (?3 IS NULL OR at_ms <= ?3)
+                   AND (?4 IS NULL OR status = ?4)
+                 ORDER BY id DESC
+                 LIMIT ?5",
+            )
+            .map_err(|e| e.to_string())?;
+        let rows = stmt
+            .query_map(
+                params![
+                    query.command.as_deref().map(str::trim).filter(|c| !c.is_empty()),
+                    query.from.map(|t| t.timestamp_millis()),
+                    query.to.map(|t| t.timestamp_millis()),
+                    query.status.map(AuditStatus::as_str),
+                    limit,
+                ],
+                |row| {
+                    let at_ms: i64 = row.get(1)?;
+                    let status: String = row.get(8)?;
+                    Ok(AuditEntry {
+                        id: row.get(0)?,
+                        at: DateTime::from_timestamp_millis(at_ms).unwrap_or_default(),
+                        command: row.get(2)?,
+                        module: row.get(3)?,
+                        caller: row.get(4)?,
+                        args_digest: row.get(5)?,
+                        args_snapshot: row.get(6)?,
+                        duration_ms: row.get::<_, i64>(7)? as u64,
+                        status: AuditStatus::parse(&status),
+                        error: row.get(9)?,
+                    })
+                },
+            )
+            .map_err(|e| e.to_string())?;
+        rows.collect::<rusqlite::Result<Vec<_>>>().map_err(|e| e.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn record(command: &str, status: AuditStatus) -> AuditRecord {
+        AuditRecord {
+            command: command.to_string(),
+            module: "contacts".to_string(),
+            caller: "webview:main".to_string(),
+            args: AuditArgs::from_json(&json!({ "filePath": "a.txt", "apiToken": "abc" })),
+            duration_ms: 3,
+            status,
+            error: None,
+        }
+    }
+
+    #[test]
+    fn test_records_sanitized_args_filters_and_prunes() {
+        let audit = CommandAudit::in_memory();
+        let now = Utc::now();
+        audit.record_at(record("import_file", AuditStatus::Dispatched), now - ChronoDuration::days(40));
+        audit.record_at(record("import_file", AuditStatus::Dispatched), now - ChronoDuration::hours(2));
+        audit.record_at(record("delete_contacts", AuditStatus::Rejected), now);
+
+        let all = audit.query(&AuditQuery::default()).unwrap();
+        assert_eq!(all.len(), 3);
+        assert!(all[0].args_snapshot.contains("\"apiToken\":\"***\""));
+        assert_eq!(all[0].args_digest, all[1].args_digest);
+
+        let imports = AuditQuery {
+            command: Some("import".into()),
+            from: Some(now - ChronoDuration::days(1)),
+            ..Default::default()
+        };
+        assert_eq!(audit.query(&imports).unwrap().len(), 1);
+        let rejected = AuditQuery { status: Some(AuditStatus::Rejected), ..Default::default() };
+        assert_eq!(audit.query(&rejected).unwrap()[0].command, "delete_contacts");
+
+        CommandAudit::prune(&audit.conn.lock(), now, 1).unwrap();
+        let kept = audit.query(&AuditQuery::default()).unwrap();
+        assert_eq!(kept.len(), 1);
+        assert_eq!(kept[0].command, "delete_contacts");
+    }
+
+    #[test]
+    fn test_sensitive_keys_match_across_naming_styles() {
+        for key in ["licenseKey", "license_key", "LICENSE-KEY", "privateKey", "private_key", "apiKey", "x-api-key", "authToken"] {
+            assert!(is_sensitive(key), "{}", key);
+        }
+        for key in ["filePath", "deviceId", "keyword"] {
+            assert!(!is_sensitive(key), "{}", key);
+        }
+        let args = AuditArgs::from_json(&json!({ "licenseKey": "L-1", "nested": { "privateKey": "pk" } }));
+        assert!(!args.snapshot.contains("L-1") && !args.snapshot.contains("\"pk\""), "{}", args.snapshot);
+    }
+}
@@ -126,7 +126,7 @@ impl DeviceAppService {
             Ok(xml)
         } else {
             Err(CoreError::new(
-                ErrorCode::DeviceError,
+                ErrorCode::AdbCommandFailed,
                 "无法获取屏幕内容"
             ))
         }
@@ -162,7 +162,7 @@ impl DeviceAppService {
                     let stderr = String::from_utf8_lossy(&output.stderr).to_string();
                     error!("❌ ADB 命令失败: {}", stderr);
                     Err(CoreError::new(
-                        ErrorCode::DeviceError,
+                        ErrorCode::AdbCommandFailed,
                         format!("ADB 命令失败: {}", stderr)
                     ))
                 }
@@ -170,7 +170,7 @@ impl DeviceAppService {
             Err(e) => {
                 error!("❌ 无法执行 ADB: {}", e);
                 Err(CoreError::new(
-                    ErrorCode::DeviceError,
+                    ErrorCode::AdbCommandFailed,
                     format!("无法执行 ADB: {}", e)
                 ))
             }
@@ -242,14 +242,14 @@ impl DeviceAppService {
             .args(["-s", device_id, "exec-out", "screencap", "-p"])
             .output()
             .map_err(|e| CoreError::new(
-                ErrorCode::DeviceError,
+                ErrorCode::AdbCommandFailed,
                 format!("执行截图命令失败: {}", e)
             ))?;
         
         if output.status.success() {
             if output.stdout.is_empty() {
                 return Err(CoreError::new(
-                    ErrorCode::DeviceError,
+                    ErrorCode::AdbCommandFailed,
                     "截图输出为空"
                 ));
             }
@@ -258,7 +258,7 @@ impl DeviceAppService {
         } else {
             let stderr = String::from_utf8_lossy(&output.stderr);
             Err(CoreError::new(
-                ErrorCode::DeviceError,
+                ErrorCode::AdbCommandFailed,
                 format!("截图命令失败: {}", stderr)
             ))
         }
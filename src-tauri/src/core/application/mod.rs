@@ -10,6 +10,9 @@ pub mod agent_loop;
 pub mod mde_extractor_service;
 pub mod mde_storage_service;
 pub mod mde_ai_extractor;
+pub mod sandbox_service;
+pub mod read_only_mode;
+pub mod command_audit;
 
 pub use script_service::ScriptAppService;
 pub use device_service::DeviceAppService;
@@ -22,6 +25,8 @@ pub use agent_loop::{AgentLoop, AgentLoopConfig};
 pub use mde_extractor_service::{MdeExtractorService, MdeXmlParser, MdeXmlNode};
 pub use mde_storage_service::{MdeStorageService, MdeSaveResult, MdeSaveOptions};
 pub use mde_ai_extractor::{MdeAiExtractorService, MdeAiConfig, MdeAiExtractionRequest};
+pub use sandbox_service::{SandboxService, SandboxScope, SANDBOX};
+pub use read_only_mode::{ReadOnlyMode, READ_ONLY};
 
 use std::sync::Arc;
 use std::path::PathBuf;
@@ -0,0 +1,343 @@
+// src-tauri/src/core/application/read_only_mode.rs
+// module: core/application | layer: application | role: read-only-observer-mode
+// summary: 全局只读观察模式 - 开启后只放行设备观察（dump / 截图 / 分析）与数据查询，在命令分发层与工具分发层统一拦截所有变更操作
+
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use chrono::Utc;
+use once_cell::sync::Lazy;
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use crate::core::domain::sandbox::ActionCategory;
+use crate::core::shared::{error::ErrorCode, CoreError, CoreResult};
+
+const READ_ONLY_FILE_NAME: &str = "read_only_mode.json";
+
+/// 全局只读模式
+pub static READ_ONLY: Lazy<ReadOnlyMode> = Lazy::new(ReadOnlyMode::load_default);
+
+/// 只读模式下放行的 Tauri 命令，按 `插件|命令` 逐个列出（插件名即 `plugin_isolation::isolate` 的模块名）
+///
+/// 不按命令名前缀推断：`capture_and_share` 会上传截图、`export_*` 会写出文件，前缀看起来只读但并不是。
+/// 同名命令在不同插件里含义可能不同（如 `adb|connect` 与 `chat`），因此必须带上插件名。
+/// 新增命令默认被拦截，确认不改变账号 / 设备 / 数据 / 文件后再加入此表。
+/// ADB 连接 / 断开 / 启动服务、`dump_and_save`（写文件）、模块重启、开始跟踪与手动异常巡检都会改变状态，不在此表。
+/// `ai|get_settings` 不含密钥（序列化时跳过），`ai|list_models` 为本地列表；
+/// `prospecting|semantic_search_comments` 只向量化查询文本、不写库，与 `ai|chat` 一样放行。
+const READ_ONLY_COMMANDS: &[&str] = &[
+    // adb
+    "adb|adb_screenshot", "adb|capture_device_screenshot", "adb|check_file", "adb|detect_ldplayer", "adb|detect_path",
+    "adb|dump_ui", "adb|dump_ui_scoped", "adb|get_bundled_agent_apk", "adb|get_cached_apps",
+    "adb|get_current_app_info", "adb|get_device_ui_xml", "adb|get_icon", "adb|get_popular_apps", "adb|get_properties",
+    "adb|get_screen_resolution", "adb|get_screen_share_config", "adb|get_shared_artifact", "adb|get_tracking_list",
+    "adb|get_ui_dump", "adb|list_apps", "adb|list_apps_paged", "adb|list_device_windows", "adb|list_devices",
+    "adb|search_apps", "adb|stop_device_mirror", "adb|stop_device_mirror_session", "adb|stop_tracking",
+    "adb|validate_connection", "adb|version",
+    // agent
+    "agent|analyze_script", "agent|chat", "agent|get_config_status", "agent|get_session", "agent|list_tools",
+    "agent|test_connection",
+    // agent_runtime
+    "agent_runtime|cancel_agent_goal", "agent_runtime|get_agent_file_sandbox", "agent_runtime|get_agent_goal_events",
+    "agent_runtime|get_collaboration_settings", "agent_runtime|get_events", "agent_runtime|get_saved_agent_session",
+    "agent_runtime|list_agent_goals", "agent_runtime|list_agent_written_files", "agent_runtime|stop_copilot",
+    // ai
+    "ai|cancel_chat", "ai|chat", "ai|get_settings", "ai|list_models",
+    // automation
+    "automation|abort_script_execution", "automation|cancel_current_operation", "automation|end_list_tracking",
+    "automation|get_billing_journal_config", "automation|get_circuit_breaker_status",
+    "automation|get_failure_statistics", "automation|get_location_config", "automation|get_network_profiles",
+    "automation|get_self_test_config", "automation|get_step_duration_stats", "automation|get_warmup_readiness",
+    "automation|list_emulator_quirk_reports", "automation|list_execution_timelines", "automation|list_network_runs",
+    "automation|list_self_test_reports", "automation|list_smoke_test_history", "automation|list_smoke_tests",
+    "automation|list_warmup_enrollments", "automation|list_warmup_programs", "automation|observe_list_page",
+    "automation|pause_script_execution", "automation|simulate_campaign", "automation|start_list_tracking",
+    "automation|stop_loop_test", "automation|stop_script_execution", "automation|stop_warmup",
+    "automation|verify_device_location", "automation|verify_device_proxy", "automation|wait_for_idle",
+    // cloud_sync
+    "cloud_sync|get_cloud_server_url", "cloud_sync|get_config_sync_policy", "cloud_sync|get_device_settings",
+    "cloud_sync|get_lead_server_stats", "cloud_sync|get_machine_id", "cloud_sync|get_realtime_uplink_config",
+    "cloud_sync|get_realtime_uplink_status", "cloud_sync|get_sync_status",
+    // contacts
+    "contacts|cancel_recipe_run", "contacts|check_file_imported", "contacts|check_industry_quota",
+    "contacts|fetch_contact_numbers", "contacts|fetch_contact_numbers_by_id_range",
+    "contacts|fetch_unclassified_contact_numbers", "contacts|get_contact_watch_history",
+    "contacts|get_device_contact_count", "contacts|get_distinct_industries", "contacts|get_file_stats",
+    "contacts|get_folder_import_session", "contacts|get_imported_files", "contacts|get_numbers_by_files",
+    "contacts|get_stats", "contacts|get_wechat_check_summary", "contacts|get_wechat_verification_status",
+    "contacts|list", "contacts|list_batches", "contacts|list_by_batch", "contacts|list_contact_watch_sources",
+    "contacts|list_for_vcf_batch", "contacts|list_import_records", "contacts|list_industry_quota_rules",
+    "contacts|list_recipe_runs", "contacts|list_recipes", "contacts|list_without_batch",
+    "contacts|pause_contact_task", "contacts|pause_contact_watch", "contacts|plan_number_allocation",
+    "contacts|preview_contact_file", "contacts|preview_destructive_operation", "contacts|preview_xlsx_columns",
+    "contacts|stop_contact_task", "contacts|stop_wechat_verification", "contacts|validate_recipe",
+    "contacts|verify_contacts_fast",
+    // employees
+    "employees|list",
+    // enhanced_location
+    "enhanced_location|generate_best_xpath", "enhanced_location|generate_xpath_candidates",
+    "enhanced_location|match_element_by_criteria", "enhanced_location|match_element_enhanced",
+    "enhanced_location|validate_xpath",
+    // execution_v3
+    "execution_v3|cancel_execution_v3", "execution_v3|list_active_executions_v3",
+    // file_manager
+    "file_manager|device_file_checksum", "file_manager|list_device_dir", "file_manager|read_as_data_url",
+    "file_manager|read_text",
+    // image_optimization
+    "image_optimization|list_screenshot_gallery", "image_optimization|load",
+    // intelligent_analysis
+    "intelligent_analysis|cancel_intelligent_analysis", "intelligent_analysis|dry_run_structure_match",
+    "intelligent_analysis|explain_last_match", "intelligent_analysis|get_matching_config",
+    "intelligent_analysis|get_screen_heatmap", "intelligent_analysis|get_selector_precompute_stats",
+    "intelligent_analysis|get_step_strategy", "intelligent_analysis|list_heatmap_screens",
+    "intelligent_analysis|recommend_structure_mode", "intelligent_analysis|recommend_structure_mode_v2",
+    "intelligent_analysis|validate_plan_json",
+    // lead_hunt
+    "lead_hunt|get_campaign_state", "lead_hunt|lh_list_comments", "lead_hunt|list_campaign_timeline",
+    "lead_hunt|list_protected_accounts", "lead_hunt|list_protected_skips", "lead_hunt|pause_campaign",
+    "lead_hunt|preview_next_targets",
+    // prospecting
+    "prospecting|calculate_content_hash", "prospecting|calculate_content_similarity", "prospecting|cancel_task",
+    "prospecting|cluster_comments", "prospecting|get_collected_comments", "prospecting|get_comment_by_id",
+    "prospecting|get_comments", "prospecting|get_comments_by_ids", "prospecting|get_cross_device_operations",
+    "prospecting|get_device_id", "prospecting|get_due_reply_plans", "prospecting|get_keyword_rules",
+    "prospecting|get_operation_history", "prospecting|get_precise_acquisition_stats",
+    "prospecting|get_rate_control_stats", "prospecting|get_reply_plans", "prospecting|get_reply_plans_by_ids",
+    "prospecting|get_reply_templates", "prospecting|get_statistics", "prospecting|get_task_progress",
+    "prospecting|get_watch_target_by_dedup_key", "prospecting|get_watch_target_by_id", "prospecting|list_comments",
+    "prospecting|list_content_calendar", "prospecting|list_import_profiles", "prospecting|list_media_assets",
+    "prospecting|list_media_sends", "prospecting|list_tasks", "prospecting|list_watch_targets",
+    "prospecting|pause_task", "prospecting|preview_comment_import", "prospecting|semantic_search_comments",
+    "prospecting|stop_task", "prospecting|test_keyword_rules",
+    // scheduler
+    "scheduler|list_schedule_runs", "scheduler|list_script_schedules", "scheduler|preview_cron",
+    // script_manager
+    "script_manager|check_sandbox_action", "script_manager|debug_abort", "script_manager|debug_inspect",
+    "script_manager|get_recording", "script_manager|get_reference_blast_radius",
+    "script_manager|list_active_sandboxes", "script_manager|list_sandbox_profiles",
+    "script_manager|list_script_templates", "script_manager|list_smart_scripts", "script_manager|search_scripts",
+    "script_manager|stop_recording",
+    // smart_selection
+    "smart_selection|get_stats", "smart_selection|preview", "smart_selection|test_connectivity",
+    // system_diagnostic
+    "system_diagnostic|analyze_xml_structure", "system_diagnostic|get_adb_path",
+    "system_diagnostic|get_anomaly_config", "system_diagnostic|get_concurrency_status",
+    "system_diagnostic|get_env_info", "system_diagnostic|get_event_schema", "system_diagnostic|get_license_status",
+    "system_diagnostic|get_locale", "system_diagnostic|get_pending_telemetry", "system_diagnostic|get_plugin_status",
+    "system_diagnostic|get_read_only_mode", "system_diagnostic|get_recent_anomalies",
+    "system_diagnostic|get_support_session_status", "system_diagnostic|get_telemetry_config",
+    "system_diagnostic|get_timeseries", "system_diagnostic|health_check",
+    "system_diagnostic|list_quick_action_bindings", "system_diagnostic|list_quick_actions", "system_diagnostic|ping",
+    "system_diagnostic|query_command_audit", "system_diagnostic|run_diagnostic", "system_diagnostic|set_locale",
+    "system_diagnostic|set_read_only_mode", "system_diagnostic|stop_support_session",
+    "system_diagnostic|test_click_normalization", "system_diagnostic|test_device",
+    // ui_dump
+    "ui_dump|check_android_app_status", "ui_dump|diagnose_android_app", "ui_dump|dump", "ui_dump|get_config",
+    "ui_dump|get_diagnostic_summary", "ui_dump|get_diagnostics", "ui_dump|get_mode", "ui_dump|list_modes",
+    "ui_dump|test_mode",
+    // universal_ui
+    "universal_ui|analyze_page", "universal_ui|classify_elements", "universal_ui|extract_elements",
+    "universal_ui|get_page_analyses_by_app", "universal_ui|get_page_analyses_by_device",
+    "universal_ui|get_page_analyses_by_type", "universal_ui|get_page_analysis_by_id",
+    "universal_ui|get_page_analysis_statistics", "universal_ui|identify_page",
+    // version_control
+    "version_control|analyze_selector_stability", "version_control|check_version_integrity",
+    "version_control|compute_xml_diff", "version_control|get_version_storage_stats", "version_control|list_branches",
+    "version_control|query_versions",
+    // xml_cache
+    "xml_cache|analyze_xml_cache_file", "xml_cache|debug_xml_cache_paths", "xml_cache|enhanced_cache_file_exists",
+    "xml_cache|get_all_snapshot_references", "xml_cache|get_cache_stats_cmd", "xml_cache|get_cache_system_status",
+    "xml_cache|get_enhanced_cache_metadata", "xml_cache|get_enhanced_cache_stats",
+    "xml_cache|get_snapshot_reference_info", "xml_cache|get_subtree_metrics_cmd",
+    "xml_cache|get_xml_file_absolute_path", "xml_cache|get_xml_file_size", "xml_cache|get_xml_index_cache_stats",
+    "xml_cache|list_app_profiles", "xml_cache|list_xml_cache_files", "xml_cache|list_xml_cache_files_quick",
+    "xml_cache|list_xml_cache_files_with_metadata", "xml_cache|parse_cached_xml_to_elements",
+    "xml_cache|read_enhanced_cache_file", "xml_cache|read_xml_cache_file", "xml_cache|validate_cache_consistency_cmd",
+];
+
+/// 工具 / 动作名中不属于 DeviceObserve、FileRead 类别的只读动作
+const READ_ACTIONS: &[&str] = &["list_scripts", "get_script", "validate_script", "wait"];
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct ReadOnlySettings {
+    enabled: bool,
+    #[serde(default)]
+    since: Option<String>,
+}
+
+/// 只读模式状态
+#[derive(Debug, Clone, Serialize)]
+pub struct ReadOnlyStatus {
+    pub enabled: bool,
+    pub since: Option<String>,
+}
+
+pub struct ReadOnlyMode {
+    enabled: AtomicBool,
+    since: RwLock<Option<String>>,
+    storage_path: Option<PathBuf>,
+}
+
+impl ReadOnlyMode {
+    /// 纯内存实例（不落盘）
+    pub fn in_memory() -> Self {
+        Self { enabled: AtomicBool::new(false), since: RwLock::new(None), storage_path: None }
+    }
+
+    fn load_default() -> Self {
+        let Some(path) = dirs::data_dir().map(|d| d.join("employee-gui").join(READ_ONLY_FILE_NAME)) else {
+            return Self::in_memory();
+        };
+        let settings: ReadOnlySettings = std::fs::read_to_string(&path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+        if settings.enabled {
+            info!("👁️ 只读观察模式已开启（自 {:?}）", settings.since);
+        }
+        Self { enabled: AtomicBool::new(settings.enabled), since: RwLock::new(settings.since), storage_path: Some(path) }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.load(Ordering::SeqCst)
+    }
+
+    pub fn status(&self) -> ReadOnlyStatus {
+        ReadOnlyStatus { enabled: self.is_enabled(), since: self.since.read().clone() }
+    }
+
+    pub fn set_enabled(&self, enabled: bool) -> CoreResult<ReadOnlyStatus> {
+        self.enabled.store(enabled, Ordering::SeqCst);
+        *self.since.write() = enabled.then(|| Utc::now().to_rfc3339());
+        info!("👁️ 只读观察模式: {}", if enabled { "开启" } else { "关闭" });
+        if let Some(path) = &self.storage_path {
+            if let Some(dir) = path.parent() {
+                std::fs::create_dir_all(dir)?;
+            }
+            let settings = ReadOnlySettings { enabled, since: self.since.read().clone() };
+            std::fs::write(path, serde_json::to_string_pretty(&settings)?)
+                .map_err(|e| CoreError::new(ErrorCode::StorageError, e.to_string()))?;
+        }
+        Ok(self.status())
+    }
+
+    fn blocked(&self, kind: &str, name: &str) -> CoreError {
+        warn!("👁️ 只读模式拦截{}: {}", kind, name);
+        CoreError::new(ErrorCode::ReadOnlyMode, format!("只读观察模式已开启，已拦截变更操作: {}", name))
+            .with_details(kind.to_string())
+    }
+
+    /// 命令分发层检查（`plugin` 为插件模块名）
+    pub fn check_command(&self, plugin: &str, command: &str) -> CoreResult<()> {
+        if !self.is_enabled() || is_read_only_command(plugin, command) {
+            return Ok(());
+        }
+        Err(self.blocked("命令", &format!("{}|{}", plugin, command)))
+    }
+
+    /// 工具 / 动作分发层检查（MCP 工具、Agent 动作）
+    pub fn check_action(&self, action: &str) -> CoreResult<()> {
+        if !self.is_enabled() || is_read_only_action(action) {
+            return Ok(());
+        }
+        Err(self.blocked("动作", action))
+    }
+}
+
+pub fn is_read_only_command(plugin: &str, command: &str) -> bool {
+    READ_ONLY_COMMANDS
+        .iter()
+        .any(|entry| entry.split_once('|') == Some((plugin, command)))
+}
+
+pub fn is_read_only_action(action: &str) -> bool {
+    READ_ACTIONS.contains(&action)
+        || matches!(ActionCategory::classify(action), ActionCategory::DeviceObserve | ActionCategory::FileRead)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn blocks_mutations_only_when_enabled() {
+        let mode = ReadOnlyMode::in_memory();
+        assert!(mode.check_command("contacts", "delete_contacts").is_ok());
+
+        mode.set_enabled(true).unwrap();
+        for (plugin, allowed) in [
+            ("adb", "get_ui_dump"), ("adb", "dump_ui_scoped"), ("adb", "list_devices"),
+            ("adb", "capture_device_screenshot"), ("system_diagnostic", "set_read_only_mode"),
+        ] {
+            assert!(mode.check_command(plugin, allowed).is_ok(), "{}|{}", plugin, allowed);
+        }
+        for (plugin, blocked) in [
+            ("automation", "tap"), ("contacts", "import_file"), ("contacts", "delete_contacts"),
+            ("adb", "capture_and_share"), ("adb", "connect"), ("adb", "start_server"), ("ui_dump", "dump_and_save"),
+            ("system_diagnostic", "restart_module"), ("adb", "get_ui_dump_anything"),
+            // 命令名在别的插件里放行，不代表在这个插件里也放行
+            ("contacts", "get_ui_dump"),
+        ] {
+            let err = mode.check_command(plugin, blocked).unwrap_err();
+            assert_eq!(err.code, ErrorCode::ReadOnlyMode, "{}|{}", plugin, blocked);
+        }
+        assert!(mode.check_action("get_screen").is_ok());
+        assert!(mode.check_action("tap_element").is_err());
+        assert!(mode.check_action("save_agent_script").is_err());
+        assert!(mode.check_action("mystery_tool").is_err());
+
+        mode.set_enabled(false).unwrap();
+        assert!(mode.check_action("tap").is_ok());
+    }
+
+    /// 从各插件源码的 `isolate("插件", generate_handler![..])` 中读出实际注册的命令
+    fn registered_commands() -> Vec<(String, String)> {
+        let modules = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("src").join("modules");
+        let mut commands = Vec::new();
+        for entry in std::fs::read_dir(&modules).unwrap().flatten() {
+            let Ok(source) = std::fs::read_to_string(entry.path().join("mod.rs")) else { continue };
+            let mut rest = source.as_str();
+            while let Some(pos) = rest.find("isolate(\"") {
+                rest = &rest[pos + "isolate(\"".len()..];
+                let plugin = &rest[..rest.find('"').unwrap()];
+                let list_start = rest.find("generate_handler![").unwrap() + "generate_handler![".len();
+                let list = &rest[list_start..list_start + rest[list_start..].find(']').unwrap()];
+                for line in list.lines() {
+                    let line = line.split("//").next().unwrap_or_default();
+                    for name in line.split(',').map(str::trim).filter(|n| !n.is_empty()) {
+                        let name = name.rsplit("::").next().unwrap_or(name);
+                        commands.push((plugin.to_string(), name.to_string()));
+                    }
+                }
+            }
+        }
+        commands
+    }
+
+    #[test]
+    fn allowlist_entries_are_registered_and_scoped_to_their_plugin() {
+        let registered = registered_commands();
+        assert!(registered.len() > 100, "未能解析插件命令表");
+
+        for entry in READ_ONLY_COMMANDS {
+            let (plugin, command) = entry.split_once('|').unwrap_or_else(|| panic!("缺少插件前缀: {}", entry));
+            assert!(
+                registered.iter().any(|(p, c)| p == plugin && c == command),
+                "只读白名单条目未在插件中注册: {}",
+                entry
+            );
+        }
+
+        // 放行的命令名若在其他插件中也存在，那个插件的同名命令必须单独列出才放行
+        for (plugin, command) in &registered {
+            let listed = READ_ONLY_COMMANDS.contains(&format!("{}|{}", plugin, command).as_str());
+            assert_eq!(is_read_only_command(plugin, command), listed, "{}|{}", plugin, command);
+        }
+        for mutating in [("adb", "connect"), ("adb", "disconnect"), ("adb", "start_server"), ("ui_dump", "dump_and_save")] {
+            assert!(registered.iter().any(|(p, c)| (p.as_str(), c.as_str()) == mutating));
+            assert!(!is_read_only_command(mutating.0, mutating.1), "{:?}", mutating);
+        }
+    }
+}
@@ -0,0 +1,392 @@
+// src-tauri/src/core/application/sandbox_service.rs
+// module: core/application | layer: application | role: sandbox-enforcement
+// summary: 执行沙箱服务 - 管理沙箱配置/绑定/设备分组，并在工具分发层统一拦截越权动作
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use once_cell::sync::Lazy;
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use crate::core::domain::sandbox::{SandboxProfile, SandboxRequest};
+use crate::core::shared::{CoreError, CoreResult, error::ErrorCode};
+
+/// 配置文件名
+const SANDBOX_FILE_NAME: &str = "sandbox_profiles.json";
+
+/// 全局沙箱服务（工具分发层共用）
+pub static SANDBOX: Lazy<SandboxService> = Lazy::new(SandboxService::load_default);
+
+/// 持久化内容：自定义配置、归属绑定、设备分组
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SandboxSettings {
+    #[serde(default)]
+    pub profiles: Vec<SandboxProfile>,
+    /// 归属 → 配置 ID，归属格式为 `campaign:<id>` 或 `script:<id>`
+    #[serde(default)]
+    pub bindings: HashMap<String, String>,
+    /// 设备 ID → 所属分组
+    #[serde(default)]
+    pub device_groups: HashMap<String, Vec<String>>,
+}
+
+/// 当前生效的沙箱作用域
+#[derive(Debug, Clone, Serialize)]
+pub struct ActiveSandbox {
+    pub token: u64,
+    pub owner: String,
+    pub profile_id: String,
+    pub device_id: String,
+}
+
+/// 作用域守卫：离开作用域时自动退出沙箱
+pub struct SandboxScope<'a> {
+    service: &'a SandboxService,
+    token: u64,
+}
+
+impl SandboxScope<'_> {
+    pub fn token(&self) -> u64 {
+        self.token
+    }
+}
+
+impl Drop for SandboxScope<'_> {
+    fn drop(&mut self) {
+        self.service.exit(self.token);
+    }
+}
+
+/// 执行沙箱服务
+pub struct SandboxService {
+    settings: RwLock<SandboxSettings>,
+    active: RwLock<Vec<ActiveSandbox>>,
+    /// 活动运行 ID → 作用域令牌（活动开始 / 结束时自动进出）
+    run_scopes: RwLock<HashMap<String, u64>>,
+    next_token: AtomicU64,
+    storage_path: Option<PathBuf>,
+}
+
+impl SandboxService {
+    /// 纯内存实例（不落盘）
+    pub fn in_memory() -> Self {
+        Self::with_settings(SandboxSettings::default(), None)
+    }
+
+    fn with_settings(settings: SandboxSettings, storage_path: Option<PathBuf>) -> Self {
+        Self {
+            settings: RwLock::new(settings),
+            active: RwLock::new(Vec::new()),
+            run_scopes: RwLock::new(HashMap::new()),
+            next_token: AtomicU64::new(1),
+            storage_path,
+        }
+    }
+
+    /// 从数据目录加载（失败时退化为内存实例）
+    fn load_default() -> Self {
+        let Some(path) = dirs::data_dir().map(|d| d.join("employee-gui").join(SANDBOX_FILE_NAME)) else {
+            warn!("⚠️ 无法获取数据目录，沙箱配置仅保存在内存中");
+            return Self::in_memory();
+        };
+
+        let settings = match std::fs::read_to_string(&path) {
+            Ok(content) => serde_json::from_str(&content).unwrap_or_else(|e| {
+                warn!("⚠️ 沙箱配置解析失败，使用默认配置: {}", e);
+                SandboxSettings::default()
+            }),
+            Err(_) => SandboxSettings::default(),
+        };
+        Self::with_settings(settings, Some(path))
+    }
+
+    fn persist(&self) -> CoreResult<()> {
+        let Some(path) = &self.storage_path else {
+            return Ok(());
+        };
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let content = serde_json::to_string_pretty(&*self.settings.read())?;
+        std::fs::write(path, content)
+            .map_err(|e| CoreError::new(ErrorCode::StorageError, e.to_string()))
+    }
+
+    // ========================================================================
+    // 配置管理
+    // ========================================================================
+
+    /// 所有配置（内置 + 自定义，自定义同名覆盖内置）
+    pub fn list_profiles(&self) -> Vec<SandboxProfile> {
+        let settings = self.settings.read();
+        let mut profiles: Vec<SandboxProfile> = SandboxProfile::builtin()
+            .into_iter()
+            .filter(|b| !settings.profiles.iter().any(|p| p.id == b.id))
+            .collect();
+        profiles.extend(settings.profiles.iter().cloned());
+        profiles
+    }
+
+    pub fn get_profile(&self, profile_id: &str) -> Option<SandboxProfile> {
+        self.list_profiles().into_iter().find(|p| p.id == profile_id)
+    }
+
+    pub fn save_profile(&self, profile: SandboxProfile) -> CoreResult<()> {
+        if profile.id.trim().is_empty() {
+            return Err(CoreError::invalid_input("沙箱配置 ID 不能为空"));
+        }
+        {
+            let mut settings = self.settings.write();
+            settings.profiles.retain(|p| p.id != profile.id);
+            info!("🛡️ 保存沙箱配置: {} ({:?})", profile.id, profile.allowed_categories);
+            settings.profiles.push(profile);
+        }
+        self.persist()
+    }
+
+    pub fn delete_profile(&self, profile_id: &str) -> CoreResult<bool> {
+        if self.active.read().iter().any(|s| s.profile_id == profile_id) {
+            return Err(CoreError::invalid_input(format!("沙箱配置 {} 正在生效，无法删除", profile_id)));
+        }
+        let removed = {
+            let mut settings = self.settings.write();
+            if settings.bindings.values().any(|p| p == profile_id) {
+                return Err(CoreError::invalid_input(format!("沙箱配置 {} 仍被绑定，无法删除", profile_id)));
+            }
+            let before = settings.profiles.len();
+            settings.profiles.retain(|p| p.id != profile_id);
+            before != settings.profiles.len()
+        };
+        self.persist()?;
+        Ok(removed)
+    }
+
+    /// 绑定（或解绑）归属的沙箱配置
+    pub fn bind(&self, owner: &str, profile_id: Option<&str>) -> CoreResult<()> {
+        if let Some(profile_id) = profile_id {
+            if self.get_profile(profile_id).is_none() {
+                return Err(CoreError::not_found("沙箱配置", profile_id));
+            }
+        }
+        {
+            let mut settings = self.settings.write();
+            match profile_id {
+                Some(profile_id) => {
+                    settings.bindings.insert(owner.to_string(), profile_id.to_string());
+                }
+                None => {
+                    settings.bindings.remove(owner);
+                }
+            }
+        }
+        self.persist()
+    }
+
+    pub fn binding_for(&self, owner: &str) -> Option<String> {
+        self.settings.read().bindings.get(owner).cloned()
+    }
+
+    pub fn set_device_groups(&self, device_id: &str, groups: Vec<String>) -> CoreResult<()> {
+        {
+            let mut settings = self.settings.write();
+            if groups.is_empty() {
+                settings.device_groups.remove(device_id);
+            } else {
+                settings.device_groups.insert(device_id.to_string(), groups);
+            }
+        }
+        self.persist()
+    }
+
+    pub fn device_groups(&self, device_id: &str) -> Vec<String> {
+        self.settings.read().device_groups.get(device_id).cloned().unwrap_or_default()
+    }
+
+    /// 分组内的设备（按设备 ID 排序）
+    pub fn devices_in_group(&self, group: &str) -> Vec<String> {
+        let mut devices: Vec<String> = self
+            .settings
+            .read()
+            .device_groups
+            .iter()
+            .filter(|(_, groups)| groups.iter().any(|g| g == group))
+            .map(|(device_id, _)| device_id.clone())
+            .collect();
+        devices.sort();
+        devices
+    }
+
+    // ========================================================================
+    // 作用域
+    // ========================================================================
+
+    /// 以指定配置进入沙箱，返回作用域令牌；只能通过 `scope` / `enter_run` 进入，保证成对退出
+    fn enter(&self, owner: &str, profile_id: &str, device_id: &str) -> CoreResult<u64> {
+        if self.get_profile(profile_id).is_none() {
+            return Err(CoreError::not_found("沙箱配置", profile_id));
+        }
+        let token = self.next_token.fetch_add(1, Ordering::Relaxed);
+        info!("🛡️ 进入沙箱: owner={}, profile={}, device={}", owner, profile_id, device_id);
+        self.active.write().push(ActiveSandbox {
+            token,
+            owner: owner.to_string(),
+            profile_id: profile_id.to_string(),
+            device_id: device_id.to_string(),
+        });
+        Ok(token)
+    }
+
+    /// 退出沙箱
+    fn exit(&self, token: u64) -> bool {
+        let mut active = self.active.write();
+        let before = active.len();
+        active.retain(|s| s.token != token);
+        before != active.len()
+    }
+
+    /// 以 RAII 方式进入沙箱；`profile_id` 为空时按归属绑定查找，未绑定则不进入
+    pub fn scope(&self, owner: &str, profile_id: Option<&str>, device_id: &str) -> CoreResult<Option<SandboxScope<'_>>> {
+        let Some(profile_id) = profile_id.map(str::to_string).or_else(|| self.binding_for(owner)) else {
+            return Ok(None);
+        };
+        let token = self.enter(owner, &profile_id, device_id)?;
+        Ok(Some(SandboxScope { service: self, token }))
+    }
+
+    /// 活动运行开始时按活动绑定进入沙箱，令牌记在运行 ID 下；未绑定时不进入，返回是否进入
+    pub fn enter_run(&self, run_id: &str, owner: &str, device_id: &str) -> CoreResult<bool> {
+        let Some(profile_id) = self.binding_for(owner) else {
+            return Ok(false);
+        };
+        let token = self.enter(owner, &profile_id, device_id)?;
+        if let Some(previous) = self.run_scopes.write().insert(run_id.to_string(), token) {
+            self.exit(previous);
+        }
+        Ok(true)
+    }
+
+    /// 活动运行结束时退出对应沙箱
+    pub fn exit_run(&self, run_id: &str) -> bool {
+        match self.run_scopes.write().remove(run_id) {
+            Some(token) => self.exit(token),
+            None => false,
+        }
+    }
+
+    pub fn active_scopes(&self) -> Vec<ActiveSandbox> {
+        self.active.read().clone()
+    }
+
+    // ========================================================================
+    // 检查
+    // ========================================================================
+
+    /// 检查动作是否被当前生效的沙箱允许
+    ///
+    /// 带设备的动作受该设备上的作用域约束；不带设备的动作（PC 命令、脚本管理等）
+    /// 必须通过所有生效作用域的检查。
+    pub fn check(&self, action: &str, device_id: Option<&str>, package: Option<&str>) -> CoreResult<()> {
+        let active = self.active.read();
+        let scopes: Vec<&ActiveSandbox> = active
+            .iter()
+            .filter(|s| device_id.map_or(true, |d| s.device_id == d))
+            .collect();
+        if scopes.is_empty() {
+            return Ok(());
+        }
+
+        let groups = device_id.map(|d| self.device_groups(d)).unwrap_or_default();
+        let request = SandboxRequest { action, device_id, package, device_groups: &groups };
+
+        for scope in scopes {
+            // 配置被删除时按最严格处理
+            let Some(profile) = self.get_profile(&scope.profile_id) else {
+                return Err(CoreError::sandbox_violation(format!(
+                    "沙箱配置 {} 不存在，拒绝动作 '{}'",
+                    scope.profile_id, action
+                )));
+            };
+            if let Err(violation) = profile.evaluate(&request) {
+                warn!("🚫 {} (owner={})", violation, scope.owner);
+                return Err(CoreError::sandbox_violation(violation.to_string()).with_details(scope.owner.clone()));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// 检查动作是否被全局只读模式与沙箱允许
+pub fn check_action(action: &str, device_id: Option<&str>, package: Option<&str>) -> CoreResult<()> {
+    super::read_only_mode::READ_ONLY.check_action(action)?;
+    SANDBOX.check(action, device_id, package)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn checks_only_apply_inside_active_scope() {
+        let service = SandboxService::in_memory();
+        service.bind("campaign:client_a", Some("client_account")).unwrap();
+
+        assert!(service.check("run_command", Some("d1"), None).is_ok());
+        {
+            let _scope = service.scope("campaign:client_a", None, "d1").unwrap().unwrap();
+            let err = service.check("run_command", Some("d1"), None).unwrap_err();
+            assert_eq!(err.code, ErrorCode::PermissionDenied);
+            assert!(service.check("tap", Some("d1"), None).is_ok());
+            // 其他设备不受影响，但无设备的动作必须通过所有作用域
+            assert!(service.check("run_adb_command", Some("d2"), None).is_ok());
+            assert!(service.check("delete_script", None, None).is_err());
+        }
+        assert!(service.active_scopes().is_empty());
+        assert!(service.check("run_command", Some("d1"), None).is_ok());
+    }
+
+    #[test]
+    fn unbound_owner_does_not_enter_sandbox() {
+        let service = SandboxService::in_memory();
+        assert!(service.scope("script:abc", None, "d1").unwrap().is_none());
+        assert!(service.bind("script:abc", Some("missing")).is_err());
+    }
+
+    #[test]
+    fn device_groups_restrict_profile() {
+        let service = SandboxService::in_memory();
+        let mut profile = SandboxProfile::client_account();
+        profile.id = "client_a_only".to_string();
+        profile.allowed_device_groups = vec!["client_a".to_string()];
+        service.save_profile(profile).unwrap();
+        service.set_device_groups("d1", vec!["client_a".to_string()]).unwrap();
+
+        let token = service.enter("campaign:x", "client_a_only", "d1").unwrap();
+        let other = service.enter("campaign:x", "client_a_only", "d2").unwrap();
+        assert!(service.check("tap", Some("d1"), None).is_ok());
+        assert!(service.check("tap", Some("d2"), None).is_err());
+
+        // 生效中的配置不能删除
+        assert!(service.delete_profile("client_a_only").is_err());
+        assert!(service.exit(token));
+        assert!(service.exit(other));
+        assert!(service.delete_profile("client_a_only").is_ok());
+    }
+
+    #[test]
+    fn campaign_runs_enter_and_exit_bound_sandbox() {
+        let service = SandboxService::in_memory();
+        assert!(!service.enter_run("run_0", "campaign:unbound", "d1").unwrap());
+
+        service.bind("campaign:client_a", Some("client_account")).unwrap();
+        assert!(service.enter_run("run_1", "campaign:client_a", "d1").unwrap());
+        assert!(service.check("run_command", Some("d1"), None).is_err());
+        assert!(service.delete_profile("client_account").is_err());
+
+        assert!(service.exit_run("run_1"));
+        assert!(!service.exit_run("run_1"));
+        assert!(service.check("run_command", Some("d1"), None).is_ok());
+    }
+}
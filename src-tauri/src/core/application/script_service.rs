@@ -10,6 +10,7 @@ use crate::core::domain::script::{
     ScriptExecutor, ScriptExecutionResult, StepExecutionResult,
 };
 use crate::core::shared::{CoreError, CoreResult, error::ErrorCode};
+use super::sandbox_service::SANDBOX;
 
 /// 脚本应用服务
 /// 
@@ -127,10 +128,17 @@ impl ScriptAppService {
         // 2. 验证脚本
         script.validate()?;
         
-        // 3. 执行
+        // 3. 进入脚本绑定的沙箱，并预检所有启用步骤的动作
+        let profile_id = script.metadata.get("sandbox_profile").and_then(|v| v.as_str());
+        let _sandbox = SANDBOX.scope(&format!("script:{}", script.id), profile_id, device_id)?;
+        for step in script.steps.iter().filter(|s| s.enabled) {
+            SANDBOX.check(step.action_name(), Some(device_id), None)?;
+        }
+        
+        // 4. 执行
         let result = self.executor.execute(&script, device_id).await?;
         
-        // 4. 记录日志
+        // 5. 记录日志
         if result.success {
             info!(
                 "✅ 脚本执行成功: {} ({}/{}步骤, {}ms)",
@@ -28,6 +28,10 @@ impl CoreBootstrap {
     pub async fn initialize(&mut self, config: CoreConfig) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
         info!("🏛️ 初始化六边形架构核心...");
 
+        if let Some(locale) = config.locale {
+            crate::core::shared::i18n::apply_locale(locale);
+        }
+
         // 1. 创建出站适配器
         let script_repo = Arc::new(FileScriptRepository::new(&config.script_storage.scripts_dir));
         let script_executor = Arc::new(LegacyScriptExecutor::new());
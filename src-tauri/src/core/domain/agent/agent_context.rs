@@ -0,0 +1,143 @@
+// src-tauri/src/core/domain/agent/agent_context.rs
+// module: core/domain/agent | layer: domain | role: context-budget
+// summary: 上下文预算 - 估算 token 数，按轮次裁剪消息列表，保证发送给 AI 的内容不超过模型上下文上限
+
+use super::{AgentMessage, MessageRole};
+
+/// 每条消息的固定开销（角色、分隔符等）
+const MESSAGE_OVERHEAD_TOKENS: usize = 4;
+/// 截断标记
+const TRUNCATED_MARKER: &str = "...(已截断)";
+
+fn is_cjk(c: char) -> bool {
+    matches!(c,
+        '\u{4E00}'..='\u{9FFF}'
+        | '\u{3400}'..='\u{4DBF}'
+        | '\u{3000}'..='\u{303F}'
+        | '\u{FF00}'..='\u{FFEF}'
+    )
+}
+
+/// 估算文本 token 数（偏保守）：中日韩字符按 1 字 1 token，其余按 4 字符 1 token
+pub fn estimate_tokens(text: &str) -> usize {
+    let (cjk, other) = text.chars().fold((0, 0), |(cjk, other), c| {
+        if is_cjk(c) { (cjk + 1, other) } else { (cjk, other + 1) }
+    });
+    cjk + (other + 3) / 4
+}
+
+/// 估算单条消息 token 数（含工具调用参数）
+pub fn estimate_message_tokens(message: &AgentMessage) -> usize {
+    let tool_tokens: usize = message.tool_calls.as_ref()
+        .map(|calls| calls.iter()
+            .map(|c| estimate_tokens(&c.function.name) + estimate_tokens(&c.function.arguments))
+            .sum())
+        .unwrap_or(0);
+    MESSAGE_OVERHEAD_TOKENS + estimate_tokens(&message.content) + tool_tokens
+}
+
+/// 将文本截断到约 `max_tokens`，保留开头并追加截断标记
+pub fn truncate_to_tokens(text: &str, max_tokens: usize) -> String {
+    if estimate_tokens(text) <= max_tokens {
+        return text.to_string();
+    }
+    let budget = max_tokens.saturating_sub(estimate_tokens(TRUNCATED_MARKER));
+    let mut used = 0;
+    let mut out = String::new();
+    let mut ascii_run = 0;
+    for c in text.chars() {
+        let cost = if is_cjk(c) {
+            ascii_run = 0;
+            1
+        } else {
+            ascii_run += 1;
+            // 每 4 个非中文字符计 1 token
+            usize::from(ascii_run % 4 == 1)
+        };
+        if used + cost > budget {
+            break;
+        }
+        used += cost;
+        out.push(c);
+    }
+    out.push_str(TRUNCATED_MARKER);
+    out
+}
+
+/// 按预算裁剪消息列表
+///
+/// 1. 保留首条系统消息与最后一轮（从最后一条用户消息开始）；
+/// 2. 从最旧的轮次开始整轮丢弃（避免留下孤立的工具结果）；
+/// 3. 仍超出时截断最长的消息内容。
+pub fn fit_messages_to_budget(messages: Vec<AgentMessage>, budget_tokens: usize) -> Vec<AgentMessage> {
+    let mut messages = messages;
+    let total = |msgs: &[AgentMessage]| msgs.iter().map(estimate_message_tokens).sum::<usize>();
+    if total(&messages) <= budget_tokens {
+        return messages;
+    }
+
+    let head = usize::from(messages.first().is_some_and(|m| m.role == MessageRole::System));
+    loop {
+        if total(&messages) <= budget_tokens {
+            return messages;
+        }
+        let turn_starts: Vec<usize> = messages.iter().enumerate()
+            .skip(head)
+            .filter(|(_, m)| m.role == MessageRole::User)
+            .map(|(i, _)| i)
+            .collect();
+        if turn_starts.len() < 2 {
+            break;
+        }
+        messages.drain(head..turn_starts[1]);
+    }
+
+    // 只剩一轮仍超出：逐条截断最长的消息
+    for _ in 0..messages.len() * 2 {
+        let over = total(&messages).saturating_sub(budget_tokens);
+        if over == 0 {
+            break;
+        }
+        let Some((idx, _)) = messages.iter().enumerate().max_by_key(|(_, m)| estimate_tokens(&m.content)) else {
+            break;
+        };
+        let current = estimate_tokens(&messages[idx].content);
+        let target = current.saturating_sub(over).max(1);
+        messages[idx].content = truncate_to_tokens(&messages[idx].content, target);
+        if estimate_tokens(&messages[idx].content) >= current {
+            break;
+        }
+    }
+    messages
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn estimates_cjk_heavier_than_ascii() {
+        assert_eq!(estimate_tokens("你好世界"), 4);
+        assert_eq!(estimate_tokens("abcdefgh"), 2);
+        let truncated = truncate_to_tokens(&"中".repeat(100), 20);
+        assert!(estimate_tokens(&truncated) <= 20);
+        assert!(truncated.ends_with(TRUNCATED_MARKER));
+    }
+
+    #[test]
+    fn drops_oldest_turns_then_truncates() {
+        let messages = vec![
+            AgentMessage::system("系统"),
+            AgentMessage::user("旧问题".repeat(50)),
+            AgentMessage::assistant("旧回答".repeat(50)),
+            AgentMessage::user("新问题"),
+        ];
+        let fitted = fit_messages_to_budget(messages, 60);
+        assert_eq!(fitted.len(), 2);
+        assert_eq!(fitted[1].content, "新问题");
+
+        let huge = vec![AgentMessage::system("系统"), AgentMessage::user("屏".repeat(500))];
+        let fitted = fit_messages_to_budget(huge, 100);
+        assert!(fitted.iter().map(estimate_message_tokens).sum::<usize>() <= 100);
+    }
+}
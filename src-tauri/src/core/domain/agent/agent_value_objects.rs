@@ -244,6 +244,14 @@ pub struct AiProviderConfig {
     
     /// Temperature
     pub temperature: Option<f32>,
+
+    /// 模型上下文窗口（tokens），未知时按保守默认值处理
+    #[serde(default)]
+    pub context_window: Option<u32>,
+
+    /// 用于摘要等辅助任务的廉价模型（为空时使用主模型）
+    #[serde(default)]
+    pub summary_model: Option<String>,
 }
 
 impl AiProviderConfig {
@@ -256,6 +264,8 @@ impl AiProviderConfig {
             model: "gpt-4o".to_string(),
             max_tokens: Some(4096),
             temperature: Some(0.7),
+            context_window: Self::context_window_for_model("gpt-4o"),
+            summary_model: Some("gpt-4o-mini".to_string()),
         }
     }
 
@@ -268,6 +278,8 @@ impl AiProviderConfig {
             model: "hunyuan-pro".to_string(),
             max_tokens: Some(4096),
             temperature: Some(0.7),
+            context_window: Self::context_window_for_model("hunyuan-pro"),
+            summary_model: Some("hunyuan-lite".to_string()),
         }
     }
 
@@ -280,6 +292,8 @@ impl AiProviderConfig {
             model: "deepseek-chat".to_string(),
             max_tokens: Some(4096),
             temperature: Some(0.7),
+            context_window: Self::context_window_for_model("deepseek-chat"),
+            summary_model: None,
         }
     }
 
@@ -290,13 +304,67 @@ impl AiProviderConfig {
         api_key: impl Into<String>,
         model: impl Into<String>,
     ) -> Self {
+        let model = model.into();
         Self {
             name: name.into(),
             base_url: base_url.into(),
             api_key: api_key.into(),
-            model: model.into(),
+            context_window: Self::context_window_for_model(&model),
+            model,
             max_tokens: Some(4096),
             temperature: Some(0.7),
+            summary_model: None,
         }
     }
+
+    /// 常见模型的上下文窗口（按名称匹配，未知返回 None）
+    pub fn context_window_for_model(model: &str) -> Option<u32> {
+        let model = model.to_ascii_lowercase();
+        let window = if model.contains("gpt-4o") || model.contains("gpt-4-turbo") || model.contains("gpt-4.1") {
+            128_000
+        } else if model.contains("gpt-3.5") {
+            16_385
+        } else if model.contains("gpt-4") {
+            8_192
+        } else if model.contains("deepseek") {
+            64_000
+        } else if model.contains("hunyuan") {
+            32_000
+        } else {
+            return None;
+        };
+        Some(window)
+    }
+
+    /// 更换模型并同步上下文窗口
+    pub fn with_model(mut self, model: impl Into<String>) -> Self {
+        self.model = model.into();
+        self.context_window = Self::context_window_for_model(&self.model);
+        self
+    }
+
+    /// 上下文窗口上限（未配置时取保守默认值）
+    pub fn context_limit_tokens(&self) -> usize {
+        self.context_window.unwrap_or(DEFAULT_CONTEXT_WINDOW) as usize
+    }
+
+    /// 可用于输入的 token 预算（上下文上限减去回复预留）
+    pub fn input_budget_tokens(&self) -> usize {
+        let reserve = self.max_tokens.unwrap_or(1024) as usize;
+        self.context_limit_tokens().saturating_sub(reserve).max(self.context_limit_tokens() / 2)
+    }
+
+    /// 摘要用的配置：换成廉价模型，回复更短、更稳定
+    pub fn summary_config(&self) -> Self {
+        let mut config = self.clone();
+        if let Some(model) = &self.summary_model {
+            config.model = model.clone();
+        }
+        config.max_tokens = Some(512);
+        config.temperature = Some(0.2);
+        config
+    }
 }
+
+/// 未知模型的默认上下文窗口
+pub const DEFAULT_CONTEXT_WINDOW: u32 = 8192;
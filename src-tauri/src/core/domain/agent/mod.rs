@@ -5,7 +5,9 @@
 mod agent_entity;
 mod agent_value_objects;
 mod agent_ports;
+mod agent_context;
 
 pub use agent_entity::*;
 pub use agent_value_objects::*;
 pub use agent_ports::*;
+pub use agent_context::*;
@@ -4,9 +4,10 @@
 
 use serde::{Deserialize, Serialize};
 use std::time::SystemTime;
+use ts_rs::TS;
 
 /// Agent 运行状态
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
 pub enum AgentRunState {
     /// 空闲：没有目标，等待用户指令
     Idle,
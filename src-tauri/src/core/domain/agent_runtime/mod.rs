@@ -28,6 +28,13 @@ pub struct AgentConfig {
     pub auto_retry: bool,
     /// 是否需要人工确认高风险操作
     pub require_human_approval_for_risky: bool,
+    /// 单次 AI 调用超时（超时后本轮思考失败，不会无限等待）
+    #[serde(default = "default_ai_call_timeout")]
+    pub ai_call_timeout: Duration,
+}
+
+fn default_ai_call_timeout() -> Duration {
+    Duration::from_secs(90)
 }
 
 impl Default for AgentConfig {
@@ -38,6 +45,7 @@ impl Default for AgentConfig {
             goal_timeout: Duration::from_secs(300), // 5分钟
             auto_retry: true,
             require_human_approval_for_risky: true,
+            ai_call_timeout: default_ai_call_timeout(),
         }
     }
 }
@@ -7,10 +7,12 @@ pub mod device;
 pub mod agent;
 pub mod agent_runtime;
 pub mod mde_extraction;
+pub mod sandbox;
 
 // 导出核心类型
 pub use script::{Script, ScriptStep, ScriptSummary, ScriptRepository};
 pub use device::{Device, DeviceStatus};
+pub use sandbox::{ActionCategory, SandboxProfile, SandboxRequest, SandboxViolation};
 pub use agent::{AgentSession, AgentMessage, AiProvider, ToolProvider, AiProviderConfig};
 pub use agent_runtime::{
     AgentConfig, AgentMode,
@@ -0,0 +1,247 @@
+// src-tauri/src/core/domain/sandbox/mod.rs
+// module: core/domain/sandbox | layer: domain | role: execution-sandbox-policy
+// summary: 执行沙箱配置 - 按活动/脚本限制允许的动作类型、目标应用包名和设备分组
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// 动作类别（工具名/动作名归类后再做权限判断）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ActionCategory {
+    /// 设备 UI 输入：点击、滑动、输入、按键、等待
+    DeviceInput,
+    /// 只读观察：获取屏幕、查找元素、提取数据
+    DeviceObserve,
+    /// 启动应用
+    AppLaunch,
+    /// 设备 shell（adb shell 任意命令）
+    DeviceShell,
+    /// PC 本地命令行
+    PcShell,
+    /// PC 本地文件读取/列目录
+    FileRead,
+    /// 删除文件/脚本等破坏性操作
+    FileDelete,
+    /// 脚本编辑与保存
+    ScriptEdit,
+    /// 执行脚本
+    ScriptExecute,
+    /// 数据落盘（提取结果保存）
+    DataWrite,
+    /// 未识别的动作
+    Unknown,
+}
+
+impl ActionCategory {
+    /// 将 MCP 工具名 / Agent 动作名 / 脚本动作类型归类
+    pub fn classify(action: &str) -> Self {
+        match action {
+            "tap" | "direct_tap" | "tap_element" | "direct_tap_element" | "click"
+            | "swipe" | "direct_swipe" | "swipe_screen" | "input" | "input_text" | "direct_input_text"
+            | "press_key" | "direct_press_key" | "back" | "wait" => Self::DeviceInput,
+            "get_screen" | "direct_screenshot" | "adb_get_screen_xml" | "screenshot" | "list_devices"
+            | "find_elements" | "extract_comments" | "analyze_screen" | "mde_detect_page"
+            | "mde_extract" | "mde_list_supported_apps" => Self::DeviceObserve,
+            "launch_app" | "direct_open_app" => Self::AppLaunch,
+            "run_adb_command" => Self::DeviceShell,
+            "run_command" | "execute_command" | "shell" => Self::PcShell,
+            "read_file" | "list_dir" | "ls" => Self::FileRead,
+            "delete_script" | "delete_file" => Self::FileDelete,
+            "list_scripts" | "get_script" | "create_script" | "add_step" | "update_step" | "remove_step"
+            | "reorder_steps" | "duplicate_script" | "validate_script" | "generate_script"
+            | "save_agent_script" => Self::ScriptEdit,
+            "execute_script" => Self::ScriptExecute,
+            "mde_save" | "write_file" => Self::DataWrite,
+            _ => Self::Unknown,
+        }
+    }
+}
+
+/// 沙箱配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SandboxProfile {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    /// 允许的动作类别
+    pub allowed_categories: Vec<ActionCategory>,
+    /// 额外禁止的具体动作名（优先于类别放行）
+    #[serde(default)]
+    pub denied_actions: Vec<String>,
+    /// 允许操作的应用包名（为空表示不限制）
+    #[serde(default)]
+    pub allowed_packages: Vec<String>,
+    /// 允许使用的设备分组（为空表示不限制）
+    #[serde(default)]
+    pub allowed_device_groups: Vec<String>,
+}
+
+/// 一次待检查的动作请求
+#[derive(Debug, Clone, Default)]
+pub struct SandboxRequest<'a> {
+    pub action: &'a str,
+    pub device_id: Option<&'a str>,
+    /// 动作涉及的应用包名（启动应用、当前前台应用等）
+    pub package: Option<&'a str>,
+    /// 设备所属分组
+    pub device_groups: &'a [String],
+}
+
+/// 沙箱拦截结果
+#[derive(Debug, Clone, Serialize)]
+pub struct SandboxViolation {
+    pub profile_id: String,
+    pub action: String,
+    pub reason: String,
+}
+
+impl fmt::Display for SandboxViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "沙箱 [{}] 禁止动作 '{}': {}", self.profile_id, self.action, self.reason)
+    }
+}
+
+impl std::error::Error for SandboxViolation {}
+
+impl SandboxProfile {
+    /// 不受限配置（默认）
+    pub fn unrestricted() -> Self {
+        Self {
+            id: "unrestricted".to_string(),
+            name: "不受限".to_string(),
+            description: "允许所有动作（内部测试使用）".to_string(),
+            allowed_categories: vec![
+                ActionCategory::DeviceInput,
+                ActionCategory::DeviceObserve,
+                ActionCategory::AppLaunch,
+                ActionCategory::DeviceShell,
+                ActionCategory::PcShell,
+                ActionCategory::FileRead,
+                ActionCategory::FileDelete,
+                ActionCategory::ScriptEdit,
+                ActionCategory::ScriptExecute,
+                ActionCategory::DataWrite,
+                ActionCategory::Unknown,
+            ],
+            denied_actions: Vec::new(),
+            allowed_packages: Vec::new(),
+            allowed_device_groups: Vec::new(),
+        }
+    }
+
+    /// 客户账号配置：只允许设备 UI 操作与数据采集，禁止任何 shell 和删除
+    pub fn client_account() -> Self {
+        Self {
+            id: "client_account".to_string(),
+            name: "客户账号".to_string(),
+            description: "仅允许设备界面操作、启动应用和数据采集".to_string(),
+            allowed_categories: vec![
+                ActionCategory::DeviceInput,
+                ActionCategory::DeviceObserve,
+                ActionCategory::AppLaunch,
+                ActionCategory::DataWrite,
+            ],
+            denied_actions: Vec::new(),
+            allowed_packages: Vec::new(),
+            allowed_device_groups: Vec::new(),
+        }
+    }
+
+    /// 内置配置
+    pub fn builtin() -> Vec<Self> {
+        vec![Self::unrestricted(), Self::client_account()]
+    }
+
+    /// 检查动作是否被允许
+    pub fn evaluate(&self, request: &SandboxRequest) -> Result<(), SandboxViolation> {
+        let deny = |reason: String| SandboxViolation {
+            profile_id: self.id.clone(),
+            action: request.action.to_string(),
+            reason,
+        };
+
+        if self.denied_actions.iter().any(|a| a == request.action) {
+            return Err(deny("动作在禁止列表中".to_string()));
+        }
+
+        let category = ActionCategory::classify(request.action);
+        if !self.allowed_categories.contains(&category) {
+            return Err(deny(format!("动作类别 {:?} 未被允许", category)));
+        }
+
+        if let Some(package) = request.package.filter(|p| !p.is_empty()) {
+            if !self.allowed_packages.is_empty() && !self.allowed_packages.iter().any(|p| p == package) {
+                return Err(deny(format!("应用 {} 不在允许列表中", package)));
+            }
+        }
+
+        if request.device_id.is_some() && !self.allowed_device_groups.is_empty() {
+            let in_allowed_group = request
+                .device_groups
+                .iter()
+                .any(|g| self.allowed_device_groups.contains(g));
+            if !in_allowed_group {
+                return Err(deny(format!(
+                    "设备 {} 不属于允许的分组 {:?}",
+                    request.device_id.unwrap_or_default(),
+                    self.allowed_device_groups
+                )));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(action: &str) -> SandboxRequest<'_> {
+        SandboxRequest { action, device_id: Some("emulator-5554"), ..Default::default() }
+    }
+
+    #[test]
+    fn client_account_blocks_shell_and_delete() {
+        let profile = SandboxProfile::client_account();
+        assert!(profile.evaluate(&request("tap")).is_ok());
+        assert!(profile.evaluate(&request("get_screen")).is_ok());
+        assert!(profile.evaluate(&request("run_command")).is_err());
+        assert!(profile.evaluate(&request("run_adb_command")).is_err());
+        assert!(profile.evaluate(&request("delete_script")).is_err());
+        assert!(profile.evaluate(&request("some_new_tool")).is_err());
+    }
+
+    #[test]
+    fn packages_and_device_groups_are_enforced() {
+        let mut profile = SandboxProfile::client_account();
+        profile.allowed_packages = vec!["com.ss.android.ugc.aweme".to_string()];
+        profile.allowed_device_groups = vec!["client_a".to_string()];
+        let groups = vec!["client_a".to_string()];
+
+        let ok = SandboxRequest {
+            action: "launch_app",
+            device_id: Some("d1"),
+            package: Some("com.ss.android.ugc.aweme"),
+            device_groups: &groups,
+        };
+        assert!(profile.evaluate(&ok).is_ok());
+
+        let wrong_package = SandboxRequest { package: Some("com.tencent.mm"), ..ok.clone() };
+        assert!(profile.evaluate(&wrong_package).is_err());
+
+        let wrong_group = SandboxRequest { device_groups: &[], ..ok.clone() };
+        let violation = profile.evaluate(&wrong_group).unwrap_err();
+        assert!(violation.reason.contains("分组"));
+    }
+
+    #[test]
+    fn denied_actions_override_categories() {
+        let mut profile = SandboxProfile::unrestricted();
+        profile.denied_actions = vec!["execute_script".to_string()];
+        assert!(profile.evaluate(&request("execute_script")).is_err());
+        assert!(profile.evaluate(&request("run_command")).is_ok());
+    }
+}
@@ -118,7 +118,7 @@ impl Script {
         // 规则2: 至少有一个步骤
         if self.steps.is_empty() {
             return Err(CoreError::new(
-                ErrorCode::ScriptInvalid,
+                ErrorCode::InvalidArgument,
                 "脚本至少需要一个步骤",
             ));
         }
@@ -127,7 +127,7 @@ impl Script {
         for (index, step) in self.steps.iter().enumerate() {
             step.validate().map_err(|e| {
                 CoreError::new(
-                    ErrorCode::ScriptInvalid,
+                    ErrorCode::InvalidArgument,
                     format!("步骤 {} 无效: {}", index + 1, e),
                 )
             })?;
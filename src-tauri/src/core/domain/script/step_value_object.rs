@@ -205,6 +205,19 @@ impl ScriptStep {
     pub fn is_control_flow(&self) -> bool {
         matches!(self.step_type, StepType::Loop | StepType::Conditional)
     }
+
+    /// 动作名（用于沙箱检查等按动作类型归类的场景）
+    pub fn action_name(&self) -> &str {
+        match &self.action {
+            StepAction::Click(_) => "click",
+            StepAction::Input(_) => "input",
+            StepAction::Swipe(_) => "swipe",
+            StepAction::Wait(_) => "wait",
+            StepAction::Back => "back",
+            StepAction::Screenshot => "screenshot",
+            StepAction::Custom(cmd) => &cmd.command_type,
+        }
+    }
 }
 
 impl ClickTarget {
@@ -7,6 +7,9 @@ pub mod application;
 pub mod adapters;
 pub mod shared;
 pub mod bootstrap;
+pub mod plugin_bootstrap;
+pub mod plugin_isolation;
+pub mod app_services;
 
 // 重导出启动器
 pub use bootstrap::{CoreBootstrap, quick_start, start_mcp_server, start_mcp_server_with_context};
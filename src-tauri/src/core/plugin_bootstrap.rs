@@ -0,0 +1,443 @@
+// src-tauri/src/core/plugin_bootstrap.rs
+// module: core | layer: infrastructure | role: plugin-bootstrap
+// summary: 插件启动框架 - 声明插件依赖、拓扑排序、懒加载服务与启动耗时统计
+
+use std::collections::{HashMap, VecDeque};
+use std::fmt::Display;
+use std::time::Instant;
+
+use once_cell::sync::{Lazy, OnceCell};
+use parking_lot::RwLock;
+use serde::Serialize;
+use tracing::{info, warn};
+
+/// 插件描述：名称 + 依赖 + 是否懒加载
+#[derive(Debug, Clone, Copy)]
+pub struct PluginDescriptor {
+    pub name: &'static str,
+    pub depends_on: &'static [&'static str],
+    /// 懒加载插件只在 setup 中注册轻量状态，重型服务在首次使用时初始化
+    pub lazy: bool,
+}
+
+impl PluginDescriptor {
+    pub const fn eager(name: &'static str, depends_on: &'static [&'static str]) -> Self {
+        Self { name, depends_on, lazy: false }
+    }
+
+    pub const fn lazy(name: &'static str, depends_on: &'static [&'static str]) -> Self {
+        Self { name, depends_on, lazy: true }
+    }
+}
+
+/// 应用内所有插件的依赖声明（main.rs 按这里解析出的拓扑顺序注册插件）
+pub const PLUGIN_DESCRIPTORS: &[PluginDescriptor] = &[
+    PluginDescriptor::eager("dialog", &[]),
+    PluginDescriptor::eager("smart_selection", &[]),
+    PluginDescriptor::eager("universal_ui", &[]),
+    PluginDescriptor::eager("adb", &[]),
+    PluginDescriptor::eager("employees", &[]),
+    PluginDescriptor::eager("contacts", &["adb"]),
+    PluginDescriptor::eager("image_optimization", &[]),
+    PluginDescriptor::eager("system_diagnostic", &["adb"]),
+    PluginDescriptor::eager("file_manager", &[]),
+    PluginDescriptor::eager("xml_cache", &[]),
+    PluginDescriptor::eager("intelligent_analysis", &["xml_cache"]),
+    PluginDescriptor::eager("execution_v3", &["adb", "intelligent_analysis"]),
+    PluginDescriptor::eager("version_control", &["xml_cache"]),
+    PluginDescriptor::eager("automation", &["adb"]),
+    PluginDescriptor::eager("enhanced_location", &["xml_cache"]),
+    PluginDescriptor::eager("lead_hunt", &[]),
+    PluginDescriptor::eager("script_manager", &["adb"]),
+    PluginDescriptor::eager("ai", &[]),
+    PluginDescriptor::lazy("prospecting", &["ai"]),
+    PluginDescriptor::eager("ui_dump", &["adb"]),
+    PluginDescriptor::lazy("agent", &[]),
+    PluginDescriptor::eager("agent_runtime", &["agent", "adb"]),
+    PluginDescriptor::eager("cloud_sync", &[]),
+    PluginDescriptor::eager("scheduler", &["script_manager"]),
+];
+
+/// 插件生命周期阶段
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PluginPhase {
+    /// 已声明，尚未执行 setup
+    Registered,
+    /// setup 完成，重型服务等待首次使用
+    Deferred,
+    Ready,
+    Failed,
+    /// 命令执行中发生过 panic，状态可能不一致，等待 `restart_module`
+    Degraded,
+}
+
+/// 单个插件的运行状态
+#[derive(Debug, Clone, Serialize)]
+pub struct PluginStatus {
+    pub name: String,
+    pub depends_on: Vec<String>,
+    pub lazy: bool,
+    pub phase: PluginPhase,
+    /// setup 耗时（毫秒）
+    pub setup_ms: Option<u64>,
+    /// 懒加载服务首次初始化耗时（毫秒）
+    pub lazy_init_ms: Option<u64>,
+    /// 相对进程启动的就绪时间点（毫秒）
+    pub ready_at_ms: Option<u64>,
+    pub error: Option<String>,
+    /// 命令 panic 次数（累计，重启不清零）
+    pub panic_count: u32,
+    pub restart_count: u32,
+}
+
+/// `get_plugin_status` 返回的整体报告
+#[derive(Debug, Clone, Serialize)]
+pub struct PluginStatusReport {
+    pub uptime_ms: u64,
+    pub init_order: Vec<String>,
+    pub plugins: Vec<PluginStatus>,
+}
+
+/// 按依赖关系对插件进行拓扑排序（Kahn 算法），保持声明顺序稳定
+pub fn resolve_order(descriptors: &[PluginDescriptor]) -> Result<Vec<&'static str>, String> {
+    let index: HashMap<&str, usize> = descriptors
+        .iter()
+        .enumerate()
+        .map(|(i, d)| (d.name, i))
+        .collect();
+
+    let mut in_degree = vec![0usize; descriptors.len()];
+    let mut dependents: Vec<Vec<usize>> = vec![Vec::new(); descriptors.len()];
+
+    for (i, desc) in descriptors.iter().enumerate() {
+        for dep in desc.depends_on {
+            let &dep_idx = index
+                .get(dep)
+                .ok_or_else(|| format!("插件 {} 依赖未声明的插件 {}", desc.name, dep))?;
+            in_degree[i] += 1;
+            dependents[dep_idx].push(i);
+        }
+    }
+
+    let mut queue: VecDeque<usize> = (0..descriptors.len()).filter(|&i| in_degree[i] == 0).collect();
+    let mut order = Vec::with_capacity(descriptors.len());
+
+    while let Some(i) = queue.pop_front() {
+        order.push(descriptors[i].name);
+        for &next in &dependents[i] {
+            in_degree[next] -= 1;
+            if in_degree[next] == 0 {
+                queue.push_back(next);
+            }
+        }
+    }
+
+    if order.len() != descriptors.len() {
+        let cyclic: Vec<&str> = descriptors
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| in_degree[*i] > 0)
+            .map(|(_, d)| d.name)
+            .collect();
+        return Err(format!("插件依赖存在循环: {}", cyclic.join(", ")));
+    }
+
+    Ok(order)
+}
+
+/// 插件状态注册表
+pub struct PluginRegistry {
+    boot_started: Instant,
+    order: Vec<String>,
+    entries: RwLock<HashMap<String, PluginStatus>>,
+}
+
+impl PluginRegistry {
+    fn new(descriptors: &[PluginDescriptor]) -> Self {
+        let order = match resolve_order(descriptors) {
+            Ok(order) => order.into_iter().map(String::from).collect(),
+            Err(e) => {
+                warn!("⚠️ 插件依赖图无效，回退为声明顺序: {}", e);
+                descriptors.iter().map(|d| d.name.to_string()).collect()
+            }
+        };
+
+        let entries = descriptors
+            .iter()
+            .map(|d| {
+                (
+                    d.name.to_string(),
+                    PluginStatus {
+                        name: d.name.to_string(),
+                        depends_on: d.depends_on.iter().map(|s| s.to_string()).collect(),
+                        lazy: d.lazy,
+                        phase: PluginPhase::Registered,
+                        setup_ms: None,
+                        lazy_init_ms: None,
+                        ready_at_ms: None,
+                        error: None,
+                        panic_count: 0,
+                        restart_count: 0,
+                    },
+                )
+            })
+            .collect();
+
+        Self {
+            boot_started: Instant::now(),
+            order,
+            entries: RwLock::new(entries),
+        }
+    }
+
+    fn uptime_ms(&self) -> u64 {
+        self.boot_started.elapsed().as_millis() as u64
+    }
+
+    /// 按拓扑顺序排列待注册的插件（Tauri 按注册顺序执行 setup），
+    /// 未在 PLUGIN_DESCRIPTORS 中声明的插件保持原顺序追加在末尾
+    pub fn in_init_order<T>(&self, mut plugins: Vec<(&str, T)>) -> Vec<T> {
+        let rank = |name: &str| self.order.iter().position(|n| n == name).unwrap_or(usize::MAX);
+        for (name, _) in &plugins {
+            if !self.contains(name) {
+                warn!("⚠️ 插件 {} 未在 PLUGIN_DESCRIPTORS 中声明", name);
+            }
+        }
+        // 稳定排序：同为未声明的插件保持传入顺序
+        plugins.sort_by_key(|(name, _)| rank(name));
+        plugins.into_iter().map(|(_, plugin)| plugin).collect()
+    }
+
+    /// 包装插件 setup，记录耗时与结果，并检查依赖是否已就绪
+    pub fn track_setup<T, E: Display>(
+        &self,
+        name: &str,
+        setup: impl FnOnce() -> Result<T, E>,
+    ) -> Result<T, E> {
+        self.warn_unready_dependencies(name);
+
+        let started = Instant::now();
+        let result = setup();
+        let elapsed = started.elapsed().as_millis() as u64;
+        let now = self.uptime_ms();
+
+        let mut entries = self.entries.write();
+        if let Some(entry) = entries.get_mut(name) {
+            entry.setup_ms = Some(elapsed);
+            match &result {
+                Ok(_) => {
+                    entry.phase = if entry.lazy { PluginPhase::Deferred } else { PluginPhase::Ready };
+                    if !entry.lazy {
+                        entry.ready_at_ms = Some(now);
+                    }
+                }
+                Err(e) => {
+                    entry.phase = PluginPhase::Failed;
+                    entry.error = Some(e.to_string());
+                }
+            }
+        } else {
+            warn!("⚠️ 插件 {} 未在 PLUGIN_DESCRIPTORS 中声明", name);
+        }
+        drop(entries);
+
+        info!("🧩 插件 {} setup 完成，耗时 {}ms", name, elapsed);
+        result
+    }
+
+    /// 记录懒加载服务首次初始化结果
+    pub fn record_lazy_init(&self, name: &str, elapsed_ms: u64, error: Option<String>) {
+        let now = self.uptime_ms();
+        let mut entries = self.entries.write();
+        if let Some(entry) = entries.get_mut(name) {
+            entry.lazy_init_ms = Some(elapsed_ms);
+            match error {
+                None => {
+                    entry.phase = PluginPhase::Ready;
+                    entry.ready_at_ms = Some(now);
+                    entry.error = None;
+                }
+                Some(e) => {
+                    entry.phase = PluginPhase::Failed;
+                    entry.error = Some(e);
+                }
+            }
+        }
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.entries.read().contains_key(name)
+    }
+
+    pub fn phase(&self, name: &str) -> Option<PluginPhase> {
+        self.entries.read().get(name).map(|e| e.phase)
+    }
+
+    /// 记录命令 panic，插件标记为降级
+    pub fn record_panic(&self, name: &str, message: &str) {
+        let mut entries = self.entries.write();
+        if let Some(entry) = entries.get_mut(name) {
+            entry.phase = PluginPhase::Degraded;
+            entry.panic_count += 1;
+            entry.error = Some(message.to_string());
+        }
+    }
+
+    /// 插件状态重新初始化后恢复为就绪
+    pub fn record_restart(&self, name: &str) {
+        let now = self.uptime_ms();
+        let mut entries = self.entries.write();
+        if let Some(entry) = entries.get_mut(name) {
+            entry.phase = PluginPhase::Ready;
+            entry.restart_count += 1;
+            entry.ready_at_ms = Some(now);
+            entry.error = None;
+        }
+    }
+
+    /// 应用 setup 阶段调用：所有插件 setup 已执行完毕，
+    /// 没有自定义 setup 钩子的非懒加载插件视为就绪
+    pub fn finish_boot(&self) {
+        let now = self.uptime_ms();
+        let mut entries = self.entries.write();
+        for entry in entries.values_mut() {
+            if entry.phase == PluginPhase::Registered {
+                entry.phase = if entry.lazy { PluginPhase::Deferred } else { PluginPhase::Ready };
+                if !entry.lazy {
+                    entry.ready_at_ms = Some(now);
+                }
+            }
+        }
+        info!("🚀 插件启动完成，总耗时 {}ms", now);
+    }
+
+    fn warn_unready_dependencies(&self, name: &str) {
+        let entries = self.entries.read();
+        let Some(entry) = entries.get(name) else { return };
+        for dep in &entry.depends_on {
+            let ready = entries
+                .get(dep)
+                .map(|d| matches!(d.phase, PluginPhase::Ready | PluginPhase::Deferred))
+                .unwrap_or(false);
+            if !ready {
+                warn!("⚠️ 插件 {} 的依赖 {} 尚未完成 setup", name, dep);
+            }
+        }
+    }
+
+    /// 生成状态快照（按拓扑顺序）
+    pub fn report(&self) -> PluginStatusReport {
+        let entries = self.entries.read();
+        let plugins = self
+            .order
+            .iter()
+            .filter_map(|name| entries.get(name).cloned())
+            .collect();
+
+        PluginStatusReport {
+            uptime_ms: self.uptime_ms(),
+            init_order: self.order.clone(),
+            plugins,
+        }
+    }
+}
+
+static REGISTRY: Lazy<PluginRegistry> = Lazy::new(|| PluginRegistry::new(PLUGIN_DESCRIPTORS));
+
+/// 全局插件注册表
+pub fn registry() -> &'static PluginRegistry {
+    &REGISTRY
+}
+
+/// 懒加载服务容器：首次访问时初始化，并把耗时记入所属插件
+pub struct LazyService<T> {
+    plugin: &'static str,
+    cell: OnceCell<T>,
+}
+
+impl<T> LazyService<T> {
+    pub const fn new(plugin: &'static str) -> Self {
+        Self { plugin, cell: OnceCell::new() }
+    }
+
+    pub fn get(&self) -> Option<&T> {
+        self.cell.get()
+    }
+
+    pub fn is_initialized(&self) -> bool {
+        self.cell.get().is_some()
+    }
+
+    /// 获取服务，未初始化时执行 `init`；失败不会缓存，下次调用会重试
+    pub fn get_or_try_init<E: Display>(&self, init: impl FnOnce() -> Result<T, E>) -> Result<&T, E> {
+        if let Some(value) = self.cell.get() {
+            return Ok(value);
+        }
+
+        self.cell.get_or_try_init(|| {
+            let started = Instant::now();
+            let result = init();
+            let elapsed = started.elapsed().as_millis() as u64;
+            let error = result.as_ref().err().map(|e| e.to_string());
+            registry().record_lazy_init(self.plugin, elapsed, error);
+            if result.is_ok() {
+                info!("💤 插件 {} 懒加载服务已初始化，耗时 {}ms", self.plugin, elapsed);
+            }
+            result
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn declared_plugins_form_a_dag() {
+        let order = resolve_order(PLUGIN_DESCRIPTORS).expect("插件依赖图应无环");
+        let pos = |name: &str| order.iter().position(|n| *n == name).unwrap();
+        assert!(pos("adb") < pos("contacts"));
+        assert!(pos("agent") < pos("agent_runtime"));
+        assert!(pos("xml_cache") < pos("intelligent_analysis"));
+        assert!(pos("ai") < pos("prospecting"));
+    }
+
+    #[test]
+    fn detects_cycles_and_unknown_dependencies() {
+        let cyclic = [
+            PluginDescriptor::eager("a", &["b"]),
+            PluginDescriptor::eager("b", &["a"]),
+        ];
+        assert!(resolve_order(&cyclic).unwrap_err().contains("循环"));
+
+        let unknown = [PluginDescriptor::eager("a", &["missing"])];
+        assert!(resolve_order(&unknown).unwrap_err().contains("missing"));
+    }
+
+    #[test]
+    fn plugins_are_registered_in_dependency_order() {
+        let registry = PluginRegistry::new(&[
+            PluginDescriptor::eager("runtime", &["agent", "adb"]),
+            PluginDescriptor::lazy("agent", &[]),
+            PluginDescriptor::eager("adb", &[]),
+        ]);
+        let ordered = registry.in_init_order(vec![("runtime", 1), ("extra", 9), ("adb", 3), ("agent", 2)]);
+        assert_eq!(ordered, vec![2, 3, 1, 9]);
+    }
+
+    #[test]
+    fn lazy_service_records_first_init_only() {
+        let registry = PluginRegistry::new(&[PluginDescriptor::lazy("demo", &[])]);
+        registry
+            .track_setup("demo", || Ok::<_, String>(()))
+            .unwrap();
+        assert_eq!(registry.report().plugins[0].phase, PluginPhase::Deferred);
+
+        let service: LazyService<u32> = LazyService::new("unknown_plugin");
+        let mut calls = 0;
+        assert_eq!(*service.get_or_try_init(|| { calls += 1; Ok::<_, String>(7) }).unwrap(), 7);
+        assert_eq!(*service.get_or_try_init(|| { calls += 1; Ok::<_, String>(8) }).unwrap(), 7);
+        assert_eq!(calls, 1);
+    }
+}
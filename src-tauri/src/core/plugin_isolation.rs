@@ -0,0 +1,253 @@
+// src-tauri/src/core/plugin_isolation.rs
+// module: core | layer: infrastructure | role: plugin-isolation
+// summary: 插件级命令分发包装 - 只读模式拦截、命令审计；把同步 / 异步命令的 panic 与拒绝转为统一的 AppError 响应、标记插件降级，并支持按插件重新初始化状态
+
+use std::any::Any;
+use std::cell::Cell;
+use std::collections::HashMap;
+use std::future::Future;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::Once;
+use std::time::Instant;
+
+use futures_util::FutureExt;
+use once_cell::sync::Lazy;
+use parking_lot::RwLock;
+use tauri::ipc::{Invoke, InvokeBody};
+use tauri::Runtime;
+use tracing::{error, info, warn};
+
+use crate::core::application::command_audit::{AuditArgs, AuditRecord, AuditStatus, COMMAND_AUDIT};
+use crate::core::application::read_only_mode::{is_read_only_command, READ_ONLY};
+use crate::core::plugin_bootstrap::{registry, PluginStatus};
+use crate::core::shared::error::{CoreError, ErrorCode};
+use crate::types::AppError;
+
+type RestartHook = Box<dyn Fn() -> Result<(), String> + Send + Sync>;
+
+static RESTART_HOOKS: Lazy<RwLock<HashMap<String, RestartHook>>> = Lazy::new(|| RwLock::new(HashMap::new()));
+static PANIC_HOOK: Once = Once::new();
+
+thread_local! {
+    /// 当前线程正在分发命令的插件（同步命令 / 参数解析阶段的 panic 据此归属）
+    static DISPATCHING: Cell<Option<&'static str>> = const { Cell::new(None) };
+}
+
+tokio::task_local! {
+    /// 当前异步命令所属插件（命令调用到 services/ 等插件目录外的代码 panic 时同样据此归属）
+    static COMMAND_MODULE: &'static str;
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    payload
+        .downcast_ref::<&str>()
+        .map(|s| s.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "未知 panic".to_string())
+}
+
+/// 从源码路径推断所属插件：`src/modules/<name>/...`
+fn module_from_path(file: &str) -> Option<String> {
+    let normalized = file.replace('\\', "/");
+    let rest = normalized.split("modules/").nth(1)?;
+    let name = rest.split('/').next()?;
+    registry().contains(name).then(|| name.to_string())
+}
+
+/// 异步命令的错误类型：命令 panic 时转换为 `MODULE_PANIC` 拒绝
+pub trait PanicRejection {
+    fn from_panic(error: AppError) -> Self;
+}
+
+impl PanicRejection for AppError {
+    fn from_panic(error: AppError) -> Self {
+        error
+    }
+}
+
+/// 旧式字符串错误沿用 `CODE: message` 前缀，前端经 `AppError::from_legacy` 还原错误码
+impl PanicRejection for String {
+    fn from_panic(error: AppError) -> Self {
+        error.to_string()
+    }
+}
+
+/// 包装插件的异步命令
+///
+/// 异步命令的 future 由 Tauri 在后台任务中执行，`isolate` 的 `catch_unwind` 只覆盖参数解析与任务派发，
+/// 因此每个异步命令的函数体都经此包装：执行期间标记所属插件，panic 时标记插件降级并以 `MODULE_PANIC` 拒绝，
+/// 避免前端 Promise 永远挂起。单表达式的命令直接包一层 `async move { .. }`，多行函数体拆到同名的 `<命令>_impl`。
+/// 命令本身不返回 `Result` 时改为 `Result<T, String>`，成功时前端收到的值不变。
+pub async fn guard<T, E, F>(module: &'static str, command: F) -> Result<T, E>
+where
+    F: Future<Output = Result<T, E>>,
+    E: PanicRejection,
+{
+    match COMMAND_MODULE.scope(module, AssertUnwindSafe(command).catch_unwind()).await {
+        Ok(result) => result,
+        Err(payload) => {
+            let message = panic_message(payload.as_ref());
+            error!("💥 插件 {} 的异步命令 panic: {}", module, message);
+            registry().record_panic(module, &message);
+            Err(E::from_panic(AppError::from(
+                CoreError::new(ErrorCode::ModulePanic, format!("插件 {} 内部错误，已标记为降级", module))
+                    .with_details(format!("panic={}", message)),
+            )))
+        }
+    }
+}
+
+/// 包装插件的命令分发：同步 panic 被捕获并以 `MODULE_PANIC` 错误响应前端，插件标记为降级；每次分发写入命令审计
+pub fn isolate<R, H>(module: &'static str, handler: H) -> impl Fn(Invoke<R>) -> bool + Send + Sync + 'static
+where
+    R: Runtime,
+    H: Fn(Invoke<R>) -> bool + Send + Sync + 'static,
+{
+    move |invoke: Invoke<R>| {
+        let resolver = invoke.resolver.clone();
+        let command = invoke.message.command().to_string();
+        let started = Instant::now();
+        let caller = format!("webview:{}", invoke.message.webview().label());
+        let args = match invoke.message.payload() {
+            InvokeBody::Json(value) => AuditArgs::from_json(value),
+            InvokeBody::Raw(bytes) => AuditArgs::from_raw(bytes.len()),
+        };
+        let audit = |status: AuditStatus, error: Option<String>| {
+            COMMAND_AUDIT.record(AuditRecord {
+                command: command.clone(),
+                module: module.to_string(),
+                caller: caller.clone(),
+                args: args.clone(),
+                duration_ms: started.elapsed().as_millis() as u64,
+                status,
+                error,
+            })
+        };
+
+        // 只读观察模式：变更类命令在分发前统一拒绝
+        if let Err(e) = READ_ONLY.check_command(module, &command) {
+            audit(AuditStatus::Rejected, Some(e.to_string()));
+            resolver.reject(AppError::from(e));
+            return true;
+        }
+
+        // 变更类命令视为用户活动，推迟空闲预计算
+        if !is_read_only_command(module, &command) {
+            crate::services::selector_precompute::note_activity();
+        }
+
+        let previous = DISPATCHING.with(|d| d.replace(Some(module)));
+        let result = panic::catch_unwind(AssertUnwindSafe(|| handler(invoke)));
+        DISPATCHING.with(|d| d.set(previous));
+
+        match result {
+            Ok(handled) => {
+                audit(AuditStatus::Dispatched, None);
+                handled
+            }
+            Err(payload) => {
+                let message = panic_message(payload.as_ref());
+                error!("💥 插件 {} 的命令 {} panic: {}", module, command, message);
+                registry().record_panic(module, &message);
+                audit(AuditStatus::Panicked, Some(message.clone()));
+                resolver.reject(AppError::from(
+                    CoreError::new(ErrorCode::ModulePanic, format!("插件 {} 内部错误，已标记为降级", module))
+                        .with_details(format!("command={} panic={}", command, message)),
+                ));
+                true
+            }
+        }
+    }
+}
+
+/// 安装全局 panic 钩子：命令之外的后台任务（插件自行 spawn 的循环等）panic 时按源码路径把所属插件标记为降级
+///
+/// 命令分发与异步命令中的 panic 由 `isolate` / `guard` 捕获并记录，这里跳过以免重复计数。
+pub fn install_panic_hook() {
+    PANIC_HOOK.call_once(|| {
+        let previous = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            let in_command = DISPATCHING.with(|d| d.get()).is_some() || COMMAND_MODULE.try_with(|_| ()).is_ok();
+            if !in_command {
+                if let Some(module) = info.location().and_then(|l| module_from_path(l.file())) {
+                    registry().record_panic(&module, &panic_message(info.payload()));
+                }
+            }
+            previous(info);
+        }));
+    });
+}
+
+/// 注册插件的重新初始化逻辑（清理中毒的锁、停止后台循环、重建内部状态等）
+pub fn register_restart(module: &str, hook: impl Fn() -> Result<(), String> + Send + Sync + 'static) {
+    RESTART_HOOKS.write().insert(module.to_string(), Box::new(hook));
+}
+
+/// 重新初始化插件状态并恢复为就绪；未注册钩子的插件只重置状态标记
+pub fn restart_module(module: &str) -> Result<PluginStatus, String> {
+    if !registry().contains(module) {
+        return Err(format!("未知插件: {}", module));
+    }
+    let hooks = RESTART_HOOKS.read();
+    if let Some(hook) = hooks.get(module) {
+        match panic::catch_unwind(AssertUnwindSafe(hook)) {
+            Ok(Ok(())) => {}
+            Ok(Err(e)) => return Err(format!("插件 {} 重新初始化失败: {}", module, e)),
+            Err(payload) => {
+                return Err(format!("插件 {} 重新初始化时 panic: {}", module, panic_message(payload.as_ref())))
+            }
+        }
+    } else {
+        warn!("⚠️ 插件 {} 未注册重新初始化逻辑，仅重置状态标记", module);
+    }
+    drop(hooks);
+
+    registry().record_restart(module);
+    info!("♻️ 插件 {} 已重新初始化", module);
+    registry()
+        .report()
+        .plugins
+        .into_iter()
+        .find(|p| p.name == module)
+        .ok_or_else(|| format!("未知插件: {}", module))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::plugin_bootstrap::PluginPhase;
+
+    #[test]
+    fn attributes_panics_by_source_path_and_restarts() {
+        assert_eq!(module_from_path("src\\modules\\automation\\mod.rs").as_deref(), Some("automation"));
+        assert_eq!(module_from_path("src/modules/not_a_plugin/x.rs"), None);
+        assert_eq!(module_from_path("src/services/adb/mod.rs"), None);
+
+        registry().record_panic("version_control", "boom");
+        assert_eq!(registry().phase("version_control"), Some(PluginPhase::Degraded));
+
+        register_restart("version_control", || Ok(()));
+        let status = restart_module("version_control").unwrap();
+        assert_eq!(status.phase, PluginPhase::Ready);
+        assert!(status.panic_count >= 1 && status.restart_count >= 1);
+        assert!(restart_module("missing").is_err());
+    }
+
+    #[tokio::test]
+    async fn async_command_panics_reject_with_module_panic() {
+        async fn explode() -> Result<(), String> {
+            panic!("boom in services");
+        }
+
+        let before = registry().report().plugins.into_iter().find(|p| p.name == "scheduler").unwrap().panic_count;
+        let err = guard("scheduler", explode()).await.unwrap_err();
+        assert!(err.starts_with("MODULE_PANIC"), "{}", err);
+        assert_eq!(AppError::from_legacy(err).code, crate::types::ErrorCode::ModulePanic);
+        assert_eq!(registry().phase("scheduler"), Some(PluginPhase::Degraded));
+        let after = registry().report().plugins.into_iter().find(|p| p.name == "scheduler").unwrap().panic_count;
+        assert_eq!(after, before + 1);
+
+        let ok: Result<u8, AppError> = guard("scheduler", async { Ok(7) }).await;
+        assert_eq!(ok.unwrap(), 7);
+    }
+}
@@ -48,4 +48,7 @@ impl Default for ScriptStorageConfig {
 pub struct CoreConfig {
     pub mcp: McpServerConfig,
     pub script_storage: ScriptStorageConfig,
+    /// 报表/导出语言（为空时沿用用户保存的设置）
+    #[serde(default)]
+    pub locale: Option<super::i18n::Locale>,
 }
@@ -13,33 +13,96 @@ pub struct CoreError {
     pub details: Option<String>,
 }
 
-/// 错误码枚举
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
-#[serde(rename_all = "snake_case")]
+/// 错误码（全应用唯一的错误码枚举，CoreError 与命令层 AppError 共用；序列化为 `NO_SELECTOR` 形式，前端据此分支）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum ErrorCode {
-    // 通用错误
-    Unknown,
-    InvalidInput,
+    // 参数 / 状态
+    InvalidArgument,
     NotFound,
     AlreadyExists,
     NotConfigured,
-    Internal,
-    ExternalService,
-    
-    // 脚本相关
-    ScriptNotFound,
-    ScriptInvalid,
-    ScriptExecutionFailed,
-    
-    // 设备相关
+    PermissionDenied,
+    ReadOnlyMode,
+    LicenseRequired,
+
+    // 设备
     DeviceNotConnected,
-    DeviceCommandFailed,
-    DeviceError,
-    
-    // IO 相关
-    FileReadError,
-    FileWriteError,
+    AdbCommandFailed,
+    UiDumpFailed,
+    Timeout,
+
+    // 元素匹配 / 执行安全门
+    NoSelector,
+    NoMatch,
+    NonUnique,
+    LowConfidence,
+    ContainerBlocked,
+    FullscreenBlocked,
+    UnsafeTarget,
+    InvalidBounds,
+    PostAssertionFailed,
+
+    // 存储 / 外部
+    StorageError,
     NetworkError,
+    ExternalService,
+
+    // 运行时
+    ModulePanic,
+    Internal,
+    Unknown,
+}
+
+/// 错误分类：前端据此决定提示方式（提示修改输入 / 检查设备 / 重试 / 上报）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCategory {
+    Validation,
+    Device,
+    Matching,
+    Storage,
+    External,
+    Internal,
+}
+
+impl ErrorCode {
+    pub fn category(self) -> ErrorCategory {
+        use ErrorCode::*;
+        match self {
+            InvalidArgument | NotFound | AlreadyExists | NotConfigured | PermissionDenied | ReadOnlyMode | LicenseRequired => {
+                ErrorCategory::Validation
+            }
+            DeviceNotConnected | AdbCommandFailed | UiDumpFailed | Timeout => ErrorCategory::Device,
+            NoSelector | NoMatch | NonUnique | LowConfidence | ContainerBlocked | FullscreenBlocked | UnsafeTarget | InvalidBounds
+            | PostAssertionFailed => ErrorCategory::Matching,
+            StorageError => ErrorCategory::Storage,
+            NetworkError | ExternalService => ErrorCategory::External,
+            ModulePanic | Internal | Unknown => ErrorCategory::Internal,
+        }
+    }
+
+    /// 解析旧消息里的错误码前缀（`NO_SELECTOR: ...`）；`NON_UNIQUE` / `NOT_UNIQUE` 两种写法都存在
+    pub fn from_prefix(prefix: &str) -> Option<Self> {
+        use ErrorCode::*;
+        Some(match prefix {
+            "NO_SELECTOR" => NoSelector,
+            "NO_MATCH" | "MATCH_FAILED" => NoMatch,
+            "NON_UNIQUE" | "NOT_UNIQUE" => NonUnique,
+            "LOW_CONFIDENCE" => LowConfidence,
+            "CONTAINER_BLOCKED" => ContainerBlocked,
+            "FULLSCREEN_BLOCKED" => FullscreenBlocked,
+            "UNSAFE_TARGET" => UnsafeTarget,
+            "INVALID_BOUNDS" => InvalidBounds,
+            "POST_ASSERTION_FAILED" => PostAssertionFailed,
+            "UI_DUMP_FAILED" => UiDumpFailed,
+            "DEVICE_NOT_CONNECTED" => DeviceNotConnected,
+            "ADB_COMMAND_FAILED" => AdbCommandFailed,
+            "TIMEOUT" => Timeout,
+            "MODULE_PANIC" => ModulePanic,
+            _ => return None,
+        })
+    }
 }
 
 impl CoreError {
@@ -65,11 +128,11 @@ impl CoreError {
     }
 
     pub fn script_not_found(id: &str) -> Self {
-        Self::new(ErrorCode::ScriptNotFound, format!("脚本不存在: {}", id))
+        Self::new(ErrorCode::NotFound, format!("脚本不存在: {}", id))
     }
 
     pub fn invalid_input(msg: impl Into<String>) -> Self {
-        Self::new(ErrorCode::InvalidInput, msg)
+        Self::new(ErrorCode::InvalidArgument, msg)
     }
 
     pub fn not_configured(msg: impl Into<String>) -> Self {
@@ -83,6 +146,10 @@ impl CoreError {
     pub fn external_service(msg: impl Into<String>) -> Self {
         Self::new(ErrorCode::ExternalService, msg)
     }
+
+    pub fn sandbox_violation(msg: impl Into<String>) -> Self {
+        Self::new(ErrorCode::PermissionDenied, msg)
+    }
 }
 
 impl fmt::Display for CoreError {
@@ -99,13 +166,13 @@ impl std::error::Error for CoreError {}
 
 impl From<std::io::Error> for CoreError {
     fn from(err: std::io::Error) -> Self {
-        CoreError::new(ErrorCode::FileReadError, err.to_string())
+        CoreError::new(ErrorCode::StorageError, err.to_string())
     }
 }
 
 impl From<serde_json::Error> for CoreError {
     fn from(err: serde_json::Error) -> Self {
-        CoreError::new(ErrorCode::InvalidInput, err.to_string())
+        CoreError::new(ErrorCode::InvalidArgument, err.to_string())
     }
 }
 
@@ -0,0 +1,38 @@
+// src-tauri/src/core/shared/event_bus.rs
+// module: core/shared | layer: shared | role: event-bus
+// summary: 进程内业务事件总线 - 导入、动作执行、设备在线变化等事件广播给后台订阅者（统计汇总等），与发往前端的 Tauri 事件互不影响
+
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use tokio::sync::broadcast;
+
+/// 订阅者处理不过来时最多积压的事件数
+const BUS_CAPACITY: usize = 1024;
+
+static BUS: Lazy<broadcast::Sender<AppEvent>> = Lazy::new(|| broadcast::channel(BUS_CAPACITY).0);
+
+/// 业务事件
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum AppEvent {
+    /// 号码导入号码池（count 为实际入库数）
+    ContactsImported { source: String, count: u64 },
+    /// 一次设备动作执行完成
+    ActionExecuted { action: String, device_id: String, success: bool },
+    /// 账号被平台限制 / 封禁（自动化检测到或操作员上报）
+    AccountRestricted { account_id: String, device_id: String, reason: String },
+    /// 当前在线设备全集（设备跟踪器每次检测到变化时发布）
+    DevicesOnline { device_ids: Vec<String> },
+    /// 长时间任务的进度（养号执行等）
+    JobProgress { job_id: String, kind: String, device_id: Option<String>, completed: u64, total: u64, finished: bool },
+}
+
+/// 发布事件；没有订阅者时直接丢弃
+pub fn publish(event: AppEvent) {
+    let _ = BUS.send(event);
+}
+
+/// 订阅事件
+pub fn subscribe() -> broadcast::Receiver<AppEvent> {
+    BUS.subscribe()
+}
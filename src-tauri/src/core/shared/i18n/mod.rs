@@ -0,0 +1,172 @@
+// src-tauri/src/core/shared/i18n/mod.rs
+// module: core/shared | layer: shared | role: i18n
+// summary: 后端消息目录 - 按 key 查找报表/导出/通知文案，支持中英文消息包与当前语言设置
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use once_cell::sync::Lazy;
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+/// 语言设置文件名
+const LOCALE_FILE_NAME: &str = "locale.json";
+
+/// 支持的语言
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub enum Locale {
+    #[default]
+    #[serde(rename = "zh-CN")]
+    ZhCn,
+    #[serde(rename = "en-US")]
+    EnUs,
+}
+
+impl Locale {
+    pub const ALL: [Locale; 2] = [Locale::ZhCn, Locale::EnUs];
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            Locale::ZhCn => "zh-CN",
+            Locale::EnUs => "en-US",
+        }
+    }
+
+    /// 宽松解析：`en` / `en_US` / `EN-us` 都视为 en-US
+    pub fn parse(code: &str) -> Option<Locale> {
+        let normalized = code.trim().replace('_', "-").to_ascii_lowercase();
+        match normalized.split('-').next() {
+            Some("zh") => Some(Locale::ZhCn),
+            Some("en") => Some(Locale::EnUs),
+            _ => None,
+        }
+    }
+
+    fn bundle_source(&self) -> &'static str {
+        match self {
+            Locale::ZhCn => include_str!("zh-CN.lang"),
+            Locale::EnUs => include_str!("en-US.lang"),
+        }
+    }
+}
+
+/// 消息包：`key = value`，`#` 开头为注释
+fn parse_bundle(source: &str) -> HashMap<String, String> {
+    source
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| line.split_once('='))
+        .map(|(key, value)| (key.trim().to_string(), value.trim().to_string()))
+        .collect()
+}
+
+static CATALOG: Lazy<HashMap<Locale, HashMap<String, String>>> = Lazy::new(|| {
+    Locale::ALL
+        .iter()
+        .map(|locale| (*locale, parse_bundle(locale.bundle_source())))
+        .collect()
+});
+
+static CURRENT_LOCALE: Lazy<RwLock<Locale>> = Lazy::new(|| RwLock::new(load_saved_locale().unwrap_or_default()));
+
+fn locale_file_path() -> PathBuf {
+    dirs::data_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("employee-gui")
+        .join(LOCALE_FILE_NAME)
+}
+
+fn load_saved_locale() -> Option<Locale> {
+    let content = std::fs::read_to_string(locale_file_path()).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+/// 当前报表/导出语言
+pub fn current_locale() -> Locale {
+    *CURRENT_LOCALE.read()
+}
+
+/// 设置当前语言（仅内存，用于启动时应用配置）
+pub fn apply_locale(locale: Locale) {
+    *CURRENT_LOCALE.write() = locale;
+}
+
+/// 设置并持久化当前语言
+pub fn set_locale(locale: Locale) -> Result<(), String> {
+    let path = locale_file_path();
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| format!("创建配置目录失败: {}", e))?;
+    }
+    let content = serde_json::to_string(&locale).map_err(|e| e.to_string())?;
+    std::fs::write(&path, content).map_err(|e| format!("保存语言设置失败: {}", e))?;
+    apply_locale(locale);
+    info!("🌐 报表语言已切换为 {}", locale.code());
+    Ok(())
+}
+
+/// 调用方显式传入的语言优先，无法识别时回退到当前设置
+pub fn resolve_locale(code: Option<&str>) -> Locale {
+    match code.filter(|c| !c.trim().is_empty()) {
+        Some(code) => Locale::parse(code).unwrap_or_else(|| {
+            warn!("⚠️ 未知语言 {}，使用当前设置", code);
+            current_locale()
+        }),
+        None => current_locale(),
+    }
+}
+
+/// 在当前语言下查找消息
+pub fn t(key: &str) -> String {
+    t_in(current_locale(), key)
+}
+
+/// 在指定语言下查找消息；缺失时回退到中文，再缺失返回 key 本身
+pub fn t_in(locale: Locale, key: &str) -> String {
+    CATALOG
+        .get(&locale)
+        .and_then(|bundle| bundle.get(key))
+        .or_else(|| CATALOG.get(&Locale::ZhCn).and_then(|bundle| bundle.get(key)))
+        .cloned()
+        .unwrap_or_else(|| key.to_string())
+}
+
+/// 查找消息并替换 `{name}` 占位符
+pub fn t_args(locale: Locale, key: &str, args: &[(&str, &str)]) -> String {
+    args.iter()
+        .fold(t_in(locale, key), |message, (name, value)| message.replace(&format!("{{{}}}", name), value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bundles_define_the_same_keys() {
+        let zh = &CATALOG[&Locale::ZhCn];
+        let en = &CATALOG[&Locale::EnUs];
+        let mut missing: Vec<&String> = zh.keys().filter(|k| !en.contains_key(*k)).collect();
+        missing.extend(en.keys().filter(|k| !zh.contains_key(*k)));
+        assert!(missing.is_empty(), "消息包 key 不一致: {:?}", missing);
+    }
+
+    #[test]
+    fn lookup_substitutes_placeholders_and_falls_back_to_key() {
+        assert_eq!(t_in(Locale::EnUs, "audit.export.operator"), "Operator");
+        assert_eq!(t_in(Locale::ZhCn, "audit.export.operator"), "操作人");
+        assert_eq!(
+            t_args(Locale::EnUs, "audit.export.unsupported_format", &[("format", "xml")]),
+            "Unsupported export format: xml"
+        );
+        assert_eq!(t_in(Locale::EnUs, "no.such.key"), "no.such.key");
+    }
+
+    #[test]
+    fn parses_locale_codes_leniently() {
+        assert_eq!(Locale::parse("en_US"), Some(Locale::EnUs));
+        assert_eq!(Locale::parse("EN"), Some(Locale::EnUs));
+        assert_eq!(Locale::parse("zh-TW"), Some(Locale::ZhCn));
+        assert_eq!(Locale::parse("fr"), None);
+    }
+}
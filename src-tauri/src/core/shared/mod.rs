@@ -4,5 +4,7 @@
 
 pub mod error;
 pub mod config;
+pub mod i18n;
+pub mod event_bus;
 
 pub use error::{CoreError, CoreResult};
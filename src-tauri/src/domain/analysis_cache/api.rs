@@ -89,7 +89,7 @@ fn load_dom_from_disk(snapshot_id: &SnapshotId) -> Option<DomIndex> {
 }
 
 /// 获取debug_xml目录路径
-fn get_debug_xml_dir() -> PathBuf {
+pub(crate) fn get_debug_xml_dir() -> PathBuf {
     use std::env;
     use std::path::PathBuf;
     
@@ -18,6 +18,8 @@ pub mod version_storage;   // 存储层实现
 pub mod xml_diff;         // XML差异算法
 pub mod xml_rebuilder;    // XML差异应用和重建引擎
 pub mod version_commands; // Tauri 命令接口
+pub mod selector_stability; // 选择器跨版本稳定性分析
+pub mod screen_changelog; // 屏幕版本间的元素级语义变更
 
 // 测试模块
 #[cfg(test)]
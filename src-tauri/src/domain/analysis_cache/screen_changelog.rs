@@ -0,0 +1,208 @@
+// src-tauri/src/domain/analysis_cache/screen_changelog.rs
+// module: analysis_cache | layer: domain | role: 屏幕版本语义变更
+// summary: 对比同一屏幕的两个历史版本，按元素（而非 XML 文本）给出新增 / 删除 / 移动 / 改名变更及新旧选择器，告诉运营应用更新改动了脚本依赖的哪些控件
+
+use serde::Serialize;
+use std::collections::HashMap;
+
+use super::selector_stability::{StabilitySelector, VersionSnapshot};
+use crate::services::universal_ui_page_analyzer::{parse_ui_elements_simple, UIElement};
+use crate::types::page_analysis::ElementBounds;
+
+/// 位置偏移不超过该像素数视为未移动（状态栏高度微调等噪声）
+const MOVE_TOLERANCE_PX: i32 = 8;
+
+/// 变更类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ScreenChangeKind {
+    Added,
+    Removed,
+    /// 标识未变，位置或层级变化
+    Moved,
+    /// 同一控件的 resource-id / 文本 / 描述 / 类名变化（可能同时移动）
+    Renamed,
+}
+
+/// 变更前后的元素
+#[derive(Debug, Clone, Serialize)]
+pub struct ScreenNode {
+    /// 可直接用于 analyze_selector_stability 或脚本步骤的选择器
+    pub selector: StabilitySelector,
+    pub bounds: ElementBounds,
+    pub index_path: Vec<u32>,
+    pub clickable: bool,
+}
+
+/// 一条元素级变更
+#[derive(Debug, Clone, Serialize)]
+pub struct ScreenChange {
+    pub kind: ScreenChangeKind,
+    pub old: Option<ScreenNode>,
+    pub new: Option<ScreenNode>,
+    /// 改名时变化的字段（resource_id / text / content_desc / class_name），移动时含 position / hierarchy
+    pub changed_fields: Vec<String>,
+}
+
+/// 两个版本之间的语义变更日志
+#[derive(Debug, Clone, Serialize)]
+pub struct ScreenChangelog {
+    pub version_a: String,
+    pub version_b: String,
+    /// 参与对比的元素数（有 id / 文本 / 描述的元素，纯布局容器不计）
+    pub nodes_a: usize,
+    pub nodes_b: usize,
+    pub unchanged: usize,
+    pub added: usize,
+    pub removed: usize,
+    pub moved: usize,
+    pub renamed: usize,
+    pub changes: Vec<ScreenChange>,
+}
+
+impl ScreenNode {
+    fn from_element(element: &UIElement) -> Self {
+        let non_empty = |s: &str| Some(s.to_string()).filter(|s| !s.is_empty());
+        Self {
+            selector: StabilitySelector {
+                resource_id: element.resource_id.as_deref().and_then(non_empty),
+                text: non_empty(&element.text),
+                content_desc: non_empty(&element.content_desc),
+                class_name: element.class_name.as_deref().and_then(non_empty),
+            },
+            bounds: element.bounds.clone(),
+            index_path: element.index_path.clone().unwrap_or_default(),
+            clickable: element.clickable,
+        }
+    }
+
+    fn has_identity(&self) -> bool {
+        let s = &self.selector;
+        s.resource_id.is_some() || s.text.is_some() || s.content_desc.is_some()
+    }
+
+    fn identity_fields(&self) -> [(&'static str, Option<&str>); 4] {
+        let s = &self.selector;
+        [
+            ("resource_id", s.resource_id.as_deref()),
+            ("text", s.text.as_deref()),
+            ("content_desc", s.content_desc.as_deref()),
+            ("class_name", s.class_name.as_deref()),
+        ]
+    }
+}
+
+fn changed_identity(old: &ScreenNode, new: &ScreenNode) -> Vec<String> {
+    old.identity_fields()
+        .into_iter()
+        .zip(new.identity_fields())
+        .filter(|((_, a), (_, b))| a != b)
+        .map(|((field, _), _)| field.to_string())
+        .collect()
+}
+
+fn changed_position(old: &ScreenNode, new: &ScreenNode) -> Vec<String> {
+    let (a, b) = (&old.bounds, &new.bounds);
+    let shifted = [(a.left, b.left), (a.top, b.top), (a.right, b.right), (a.bottom, b.bottom)]
+        .iter()
+        .any(|(x, y)| (x - y).abs() > MOVE_TOLERANCE_PX);
+    let mut fields = Vec::new();
+    if shifted {
+        fields.push("position".to_string());
+    }
+    if old.index_path != new.index_path {
+        fields.push("hierarchy".to_string());
+    }
+    fields
+}
+
+/// 按给定键在两侧剩余元素间配对（同键多个元素按文档顺序一一对应），返回 (旧下标, 新下标)
+fn pair_by<K, F>(old: &[ScreenNode], new: &[ScreenNode], old_left: &mut Vec<usize>, new_left: &mut Vec<usize>, key: F) -> Vec<(usize, usize)>
+where
+    K: std::hash::Hash + Eq,
+    F: Fn(&ScreenNode) -> Option<K>,
+{
+    let mut buckets: HashMap<K, Vec<usize>> = HashMap::new();
+    for &j in new_left.iter() {
+        if let Some(k) = key(&new[j]) {
+            buckets.entry(k).or_default().push(j);
+        }
+    }
+    for bucket in buckets.values_mut() {
+        bucket.reverse();
+    }
+    let mut pairs = Vec::new();
+    old_left.retain(|&i| {
+        let Some(j) = key(&old[i]).and_then(|k| buckets.get_mut(&k)).and_then(|b| b.pop()) else { return true };
+        pairs.push((i, j));
+        false
+    });
+    new_left.retain(|j| !pairs.iter().any(|(_, p)| p == j));
+    pairs
+}
+
+fn parse_nodes(snapshot: &VersionSnapshot) -> Result<Vec<ScreenNode>, String> {
+    let elements = parse_ui_elements_simple(&snapshot.xml).map_err(|e| format!("解析版本 {} 失败: {}", snapshot.version_id, e))?;
+    Ok(elements.iter().map(ScreenNode::from_element).filter(ScreenNode::has_identity).collect())
+}
+
+/// 对比两个版本（a 为旧版本，b 为新版本）
+///
+/// 先按完整标识配对（仅可能移动），再依次按 resource-id、文本、描述配对剩余元素（视为改名），
+/// 仍未配对的即为删除 / 新增。
+pub fn compare_snapshots(a: &VersionSnapshot, b: &VersionSnapshot) -> Result<ScreenChangelog, String> {
+    let old = parse_nodes(a)?;
+    let new = parse_nodes(b)?;
+    let mut old_left: Vec<usize> = (0..old.len()).collect();
+    let mut new_left: Vec<usize> = (0..new.len()).collect();
+    let mut changes = Vec::new();
+    let mut unchanged = 0;
+
+    let exact = pair_by(&old, &new, &mut old_left, &mut new_left, |n| {
+        Some((n.selector.resource_id.clone(), n.selector.text.clone(), n.selector.content_desc.clone(), n.selector.class_name.clone()))
+    });
+    for (i, j) in exact {
+        let fields = changed_position(&old[i], &new[j]);
+        if fields.is_empty() {
+            unchanged += 1;
+        } else {
+            changes.push(ScreenChange { kind: ScreenChangeKind::Moved, old: Some(old[i].clone()), new: Some(new[j].clone()), changed_fields: fields });
+        }
+    }
+
+    let mut renamed = pair_by(&old, &new, &mut old_left, &mut new_left, |n| n.selector.resource_id.clone());
+    renamed.extend(pair_by(&old, &new, &mut old_left, &mut new_left, |n| n.selector.text.clone()));
+    renamed.extend(pair_by(&old, &new, &mut old_left, &mut new_left, |n| n.selector.content_desc.clone()));
+    for (i, j) in renamed {
+        let mut fields = changed_identity(&old[i], &new[j]);
+        fields.extend(changed_position(&old[i], &new[j]));
+        changes.push(ScreenChange { kind: ScreenChangeKind::Renamed, old: Some(old[i].clone()), new: Some(new[j].clone()), changed_fields: fields });
+    }
+
+    for i in old_left {
+        changes.push(ScreenChange { kind: ScreenChangeKind::Removed, old: Some(old[i].clone()), new: None, changed_fields: Vec::new() });
+    }
+    for j in new_left {
+        changes.push(ScreenChange { kind: ScreenChangeKind::Added, old: None, new: Some(new[j].clone()), changed_fields: Vec::new() });
+    }
+
+    // 按屏幕位置（新版本优先）排序，便于前端自上而下展示
+    changes.sort_by_key(|c| {
+        let node = c.new.as_ref().or(c.old.as_ref()).expect("change has a node");
+        (node.bounds.top, node.bounds.left)
+    });
+    let count = |kind: ScreenChangeKind| changes.iter().filter(|c| c.kind == kind).count();
+
+    Ok(ScreenChangelog {
+        version_a: a.version_id.clone(),
+        version_b: b.version_id.clone(),
+        nodes_a: old.len(),
+        nodes_b: new.len(),
+        unchanged,
+        added: count(ScreenChangeKind::Added),
+        removed: count(ScreenChangeKind::Removed),
+        moved: count(ScreenChangeKind::Moved),
+        renamed: count(ScreenChangeKind::Renamed),
+        changes,
+    })
+}
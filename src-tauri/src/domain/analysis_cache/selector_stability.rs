@@ -0,0 +1,156 @@
+// src-tauri/src/domain/analysis_cache/selector_stability.rs
+// module: analysis_cache | layer: domain | role: 选择器稳定性分析
+// summary: 将选择器回放到同一屏幕的历史版本上，统计命中率和 id/文本漂移，帮助挑选经得起应用更新的锚点
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::services::universal_ui_page_analyzer::{parse_ui_elements_simple, UIElement};
+
+/// 默认回放的历史版本数
+pub const DEFAULT_STABILITY_DEPTH: usize = 10;
+
+/// 被分析的选择器（字段之间为 AND 关系，未填写的字段不参与匹配）
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct StabilitySelector {
+    pub resource_id: Option<String>,
+    pub text: Option<String>,
+    pub content_desc: Option<String>,
+    pub class_name: Option<String>,
+}
+
+impl StabilitySelector {
+    /// 已填写的字段（字段名, 期望值）
+    fn fields(&self) -> Vec<(&'static str, &str)> {
+        [
+            ("resource_id", self.resource_id.as_deref()),
+            ("text", self.text.as_deref()),
+            ("content_desc", self.content_desc.as_deref()),
+            ("class_name", self.class_name.as_deref()),
+        ]
+        .into_iter()
+        .filter_map(|(name, value)| value.filter(|v| !v.is_empty()).map(|v| (name, v)))
+        .collect()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.fields().is_empty()
+    }
+
+    pub fn matches(&self, element: &UIElement) -> bool {
+        self.fields().iter().all(|(field, expected)| field_matches(element, field, expected))
+    }
+}
+
+fn field_value<'a>(element: &'a UIElement, field: &str) -> &'a str {
+    match field {
+        "resource_id" => element.resource_id.as_deref().unwrap_or_default(),
+        "text" => &element.text,
+        "content_desc" => &element.content_desc,
+        "class_name" => element.class_name.as_deref().unwrap_or_default(),
+        _ => "",
+    }
+}
+
+fn field_matches(element: &UIElement, field: &str, expected: &str) -> bool {
+    field_value(element, field) == expected
+}
+
+/// 单个历史版本上的回放结果
+#[derive(Debug, Clone, Serialize)]
+pub struct VersionMatch {
+    pub version_id: String,
+    pub timestamp: String,
+    pub match_count: usize,
+    /// 首个命中元素的 resource-id / 文本（用于计算漂移）
+    pub matched_resource_id: Option<String>,
+    pub matched_text: Option<String>,
+}
+
+/// 稳定性报告
+#[derive(Debug, Clone, Serialize)]
+pub struct SelectorStabilityReport {
+    pub snapshot_series_id: String,
+    pub versions_analyzed: usize,
+    /// 至少命中一个元素的版本占比
+    pub match_rate: f32,
+    /// 恰好命中一个元素的版本占比
+    pub unique_match_rate: f32,
+    /// 相邻命中版本之间 resource-id 变化的比例
+    pub id_churn: f32,
+    /// 相邻命中版本之间文本变化的比例
+    pub text_churn: f32,
+    /// 每个字段单独作为选择器时的命中率（用于挑选更稳的锚点）
+    pub field_match_rates: HashMap<String, f32>,
+    /// 按时间从旧到新
+    pub versions: Vec<VersionMatch>,
+}
+
+/// 待回放的一个版本（由调用方从版本库重建）
+pub struct VersionSnapshot {
+    pub version_id: String,
+    pub timestamp: String,
+    pub xml: String,
+}
+
+/// 在一组历史版本（从旧到新）上回放选择器
+pub fn analyze_stability(
+    snapshot_series_id: &str,
+    selector: &StabilitySelector,
+    snapshots: &[VersionSnapshot],
+) -> Result<SelectorStabilityReport, String> {
+    if selector.is_empty() {
+        return Err("选择器至少需要一个字段".to_string());
+    }
+
+    let fields = selector.fields();
+    let mut field_hits: HashMap<String, usize> = fields.iter().map(|(f, _)| (f.to_string(), 0)).collect();
+    let mut versions = Vec::with_capacity(snapshots.len());
+
+    for snapshot in snapshots {
+        let elements = parse_ui_elements_simple(&snapshot.xml)
+            .map_err(|e| format!("解析版本 {} 失败: {}", snapshot.version_id, e))?;
+
+        for (field, expected) in &fields {
+            if elements.iter().any(|e| field_matches(e, field, expected)) {
+                *field_hits.entry(field.to_string()).or_default() += 1;
+            }
+        }
+
+        let matched: Vec<&UIElement> = elements.iter().filter(|e| selector.matches(e)).collect();
+        let first = matched.first();
+        versions.push(VersionMatch {
+            version_id: snapshot.version_id.clone(),
+            timestamp: snapshot.timestamp.clone(),
+            match_count: matched.len(),
+            matched_resource_id: first.and_then(|e| e.resource_id.clone()),
+            matched_text: first.map(|e| e.text.clone()),
+        });
+    }
+
+    let total = versions.len();
+    let rate = |count: usize| if total == 0 { 0.0 } else { count as f32 / total as f32 };
+
+    let matched_versions: Vec<&VersionMatch> = versions.iter().filter(|v| v.match_count > 0).collect();
+    let transitions = matched_versions.len().saturating_sub(1);
+    let churn = |changed: usize| if transitions == 0 { 0.0 } else { changed as f32 / transitions as f32 };
+    let id_changes = matched_versions
+        .windows(2)
+        .filter(|w| w[0].matched_resource_id != w[1].matched_resource_id)
+        .count();
+    let text_changes = matched_versions
+        .windows(2)
+        .filter(|w| w[0].matched_text != w[1].matched_text)
+        .count();
+
+    Ok(SelectorStabilityReport {
+        snapshot_series_id: snapshot_series_id.to_string(),
+        versions_analyzed: total,
+        match_rate: rate(matched_versions.len()),
+        unique_match_rate: rate(versions.iter().filter(|v| v.match_count == 1).count()),
+        id_churn: churn(id_changes),
+        text_churn: churn(text_changes),
+        field_match_rates: field_hits.into_iter().map(|(f, hits)| (f, rate(hits))).collect(),
+        versions,
+    })
+}
@@ -2,4 +2,6 @@
 // module: analysis_cache | layer: domain | role: 测试模块入口
 // summary: Phase 3 版本控制系统测试模块
 
-pub mod version_control_test;
\ No newline at end of file
+pub mod version_control_test;
+pub mod selector_stability_test;
+pub mod screen_changelog_test;
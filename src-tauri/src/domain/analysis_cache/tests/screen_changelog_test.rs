@@ -0,0 +1,69 @@
+// src-tauri/src/domain/analysis_cache/tests/screen_changelog_test.rs
+// module: analysis_cache | layer: domain | role: 屏幕版本语义变更测试
+// summary: 验证两个版本之间新增 / 删除 / 移动 / 改名元素的识别及新旧选择器
+
+#[cfg(test)]
+mod tests {
+    use crate::domain::analysis_cache::screen_changelog::*;
+    use crate::domain::analysis_cache::selector_stability::VersionSnapshot;
+
+    /// (resource-id, 文本, 所在行)
+    fn snapshot(version_id: &str, nodes: &[(&str, &str, usize)]) -> VersionSnapshot {
+        let body: String = nodes
+            .iter()
+            .enumerate()
+            .map(|(i, (id, text, row))| {
+                format!(
+                    r#"<node index="{i}" text="{text}" resource-id="{id}" class="android.widget.TextView" content-desc="" clickable="true" bounds="[0,{top}][100,{bottom}]" />"#,
+                    i = i,
+                    text = text,
+                    id = id,
+                    top = row * 100,
+                    bottom = row * 100 + 50
+                )
+            })
+            .collect();
+        VersionSnapshot {
+            version_id: version_id.to_string(),
+            timestamp: String::new(),
+            xml: format!(
+                r#"<?xml version="1.0" encoding="UTF-8"?><hierarchy rotation="0"><node index="0" text="" resource-id="" class="android.widget.FrameLayout" content-desc="" bounds="[0,0][1080,2400]">{}</node></hierarchy>"#,
+                body
+            ),
+        }
+    }
+
+    #[test]
+    fn detects_added_removed_moved_and_renamed_elements() {
+        let old = snapshot(
+            "v1",
+            &[("com.app:id/follow", "关注", 0), ("com.app:id/share", "分享", 1), ("com.app:id/settings", "设置", 2), ("com.app:id/title", "主页", 3)],
+        );
+        let new = snapshot(
+            "v2",
+            &[("com.app:id/btn_follow", "关注", 0), ("com.app:id/message", "私信", 1), ("com.app:id/share", "分享", 2), ("com.app:id/title", "主页", 3)],
+        );
+
+        let log = compare_snapshots(&old, &new).unwrap();
+        assert_eq!((log.version_a.as_str(), log.version_b.as_str()), ("v1", "v2"));
+        assert_eq!((log.nodes_a, log.nodes_b), (4, 4));
+        assert_eq!((log.unchanged, log.added, log.removed, log.moved, log.renamed), (1, 1, 1, 1, 1));
+
+        let renamed = log.changes.iter().find(|c| c.kind == ScreenChangeKind::Renamed).unwrap();
+        assert_eq!(renamed.changed_fields, vec!["resource_id"]);
+        assert_eq!(renamed.old.as_ref().unwrap().selector.resource_id.as_deref(), Some("com.app:id/follow"));
+        assert_eq!(renamed.new.as_ref().unwrap().selector.resource_id.as_deref(), Some("com.app:id/btn_follow"));
+
+        let moved = log.changes.iter().find(|c| c.kind == ScreenChangeKind::Moved).unwrap();
+        assert_eq!(moved.new.as_ref().unwrap().selector.text.as_deref(), Some("分享"));
+        assert_eq!(moved.changed_fields, vec!["position", "hierarchy"]);
+
+        let removed = log.changes.iter().find(|c| c.kind == ScreenChangeKind::Removed).unwrap();
+        assert_eq!(removed.old.as_ref().unwrap().selector.text.as_deref(), Some("设置"));
+        assert!(removed.new.is_none());
+
+        // 按屏幕位置自上而下排列
+        let tops: Vec<i32> = log.changes.iter().map(|c| c.new.as_ref().or(c.old.as_ref()).unwrap().bounds.top).collect();
+        assert!(tops.windows(2).all(|w| w[0] <= w[1]));
+    }
+}
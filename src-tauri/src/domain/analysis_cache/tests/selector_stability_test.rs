@@ -0,0 +1,69 @@
+// src-tauri/src/domain/analysis_cache/tests/selector_stability_test.rs
+// module: analysis_cache | layer: domain | role: 选择器稳定性分析测试
+// summary: 验证跨版本回放的命中率、唯一命中率和 id/文本漂移统计
+
+#[cfg(test)]
+mod tests {
+    use crate::domain::analysis_cache::selector_stability::*;
+
+    fn snapshot(version_id: &str, nodes: &[(&str, &str)]) -> VersionSnapshot {
+        let body: String = nodes
+            .iter()
+            .enumerate()
+            .map(|(i, (id, text))| {
+                format!(
+                    r#"<node index="{i}" text="{text}" resource-id="{id}" class="android.widget.TextView" content-desc="" clickable="true" bounds="[0,{top}][100,{bottom}]" />"#,
+                    i = i,
+                    text = text,
+                    id = id,
+                    top = i * 100,
+                    bottom = i * 100 + 50
+                )
+            })
+            .collect();
+        VersionSnapshot {
+            version_id: version_id.to_string(),
+            timestamp: String::new(),
+            xml: format!(
+                r#"<?xml version="1.0" encoding="UTF-8"?><hierarchy rotation="0"><node index="0" text="" resource-id="" class="android.widget.FrameLayout" content-desc="" bounds="[0,0][1080,2400]">{}</node></hierarchy>"#,
+                body
+            ),
+        }
+    }
+
+    #[test]
+    fn text_anchor_survives_id_churn() {
+        let snapshots = vec![
+            snapshot("v1", &[("com.app:id/follow", "关注")]),
+            snapshot("v2", &[("com.app:id/btn_follow", "关注")]),
+            snapshot("v3", &[("com.app:id/btn_follow", "关注"), ("com.app:id/other", "关注")]),
+        ];
+        let selector = StabilitySelector { text: Some("关注".to_string()), ..Default::default() };
+
+        let report = analyze_stability("douyin_profile", &selector, &snapshots).unwrap();
+        assert_eq!(report.versions_analyzed, 3);
+        assert!((report.match_rate - 1.0).abs() < 1e-6);
+        assert!((report.unique_match_rate - 2.0 / 3.0).abs() < 1e-6);
+        assert!((report.id_churn - 0.5).abs() < 1e-6);
+        assert_eq!(report.text_churn, 0.0);
+    }
+
+    #[test]
+    fn field_rates_show_which_anchor_broke() {
+        let snapshots = vec![
+            snapshot("v1", &[("com.app:id/follow", "关注")]),
+            snapshot("v2", &[("com.app:id/btn_follow", "关注")]),
+        ];
+        let selector = StabilitySelector {
+            resource_id: Some("com.app:id/follow".to_string()),
+            text: Some("关注".to_string()),
+            ..Default::default()
+        };
+
+        let report = analyze_stability("douyin_profile", &selector, &snapshots).unwrap();
+        assert!((report.match_rate - 0.5).abs() < 1e-6);
+        assert!((report.field_match_rates["resource_id"] - 0.5).abs() < 1e-6);
+        assert!((report.field_match_rates["text"] - 1.0).abs() < 1e-6);
+        assert!(analyze_stability("x", &StabilitySelector::default(), &snapshots).is_err());
+    }
+}
@@ -5,6 +5,10 @@
 use super::version_control::*;
 use super::version_storage::VERSION_STORAGE;
 use super::xml_diff::{XmlDiffEngine, DiffConfig};
+use super::selector_stability::{
+    analyze_stability, SelectorStabilityReport, StabilitySelector, VersionSnapshot, DEFAULT_STABILITY_DEPTH,
+};
+use super::screen_changelog::{compare_snapshots, ScreenChangelog};
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -264,6 +268,70 @@ pub async fn delete_version(version_id: String) -> Result<String, String> {
     }
 }
 
+/// 🚀 Phase 3: 选择器稳定性分析
+///
+/// `snapshot_series_id` 为记录同一屏幕历史的分支名，回放最近 `depth` 个版本。
+#[command]
+pub async fn analyze_selector_stability(
+    snapshot_series_id: String,
+    selector: StabilitySelector,
+    depth: Option<usize>,
+) -> Result<SelectorStabilityReport, String> {
+    let storage = VERSION_STORAGE.read().await;
+
+    let history = storage
+        .get_branch_history(&snapshot_series_id, Some(depth.unwrap_or(DEFAULT_STABILITY_DEPTH).max(1)))
+        .await
+        .map_err(|e| format!("查询分支历史失败: {}", e))?;
+
+    // 分支历史从新到旧，回放按从旧到新
+    let mut snapshots = Vec::with_capacity(history.len());
+    for version in history.iter().rev() {
+        let xml_data = storage
+            .rebuild_version(&version.id)
+            .await
+            .map_err(|e| format!("重建版本 {} 失败: {}", version.id, e))?;
+        let xml = String::from_utf8((*xml_data).clone())
+            .map_err(|e| format!("XML数据编码错误: {}", e))?;
+        snapshots.push(VersionSnapshot {
+            version_id: version.id.clone(),
+            timestamp: version.timestamp.to_rfc3339(),
+            xml,
+        });
+    }
+
+    analyze_stability(&snapshot_series_id, &selector, &snapshots)
+}
+
+/// 🚀 Phase 3: 屏幕版本对比（"这个屏幕改了什么"）
+///
+/// `version_a` 为旧版本、`version_b` 为新版本，返回元素级的新增 / 删除 / 移动 / 改名变更。
+#[command]
+pub async fn compare_screen_versions(version_a: String, version_b: String) -> Result<ScreenChangelog, String> {
+    let storage = VERSION_STORAGE.read().await;
+
+    let mut snapshots = Vec::with_capacity(2);
+    for version_id in [&version_a, &version_b] {
+        let version = storage
+            .load_version(version_id)
+            .await
+            .map_err(|e| format!("加载版本 {} 失败: {}", version_id, e))?;
+        let xml_data = storage
+            .rebuild_version(version_id)
+            .await
+            .map_err(|e| format!("重建版本 {} 失败: {}", version_id, e))?;
+        let xml = String::from_utf8((*xml_data).clone())
+            .map_err(|e| format!("XML数据编码错误: {}", e))?;
+        snapshots.push(VersionSnapshot {
+            version_id: version.id.clone(),
+            timestamp: version.timestamp.to_rfc3339(),
+            xml,
+        });
+    }
+
+    compare_snapshots(&snapshots[0], &snapshots[1])
+}
+
 // 辅助函数
 
 /// 计算版本间的差异
@@ -537,6 +537,7 @@ mod tests {
                 container_index: HashMap::new(),
                 all_nodes: Vec::new(),
                 raw_xml: String::new(),
+                tree: Default::default(),
             },
             node_map: HashMap::new(),
             xml_hash: "test_hash".to_string(),
@@ -701,6 +701,7 @@ mod tests {
             container_index: Default::default(),
             all_nodes: vec![],
             raw_xml: String::new(),
+            tree: Default::default(),
         };
         
         let normalizer = ClickNormalizer::new(&indexer);
@@ -764,6 +765,7 @@ mod tests {
             container_index: Default::default(),
             all_nodes: vec![],
             raw_xml: String::new(),
+            tree: Default::default(),
         };
         
         let normalizer = ClickNormalizer::new(&indexer);
@@ -803,6 +805,7 @@ mod tests {
             container_index: Default::default(),
             all_nodes: vec![],
             raw_xml: String::new(),
+            tree: Default::default(),
         };
         
         let normalizer = ClickNormalizer::new(&indexer);
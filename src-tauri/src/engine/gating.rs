@@ -9,6 +9,7 @@ use crate::engine::strategy_plugin::{
     StrategyRegistry, ExecutionEnvironment, ExecutionResult, 
 };
 use crate::commands::run_step_v2::{DecisionChainPlan, StrategyVariant, MatchCandidate};
+use crate::commands::run_step_v2::matching_config;
 
 // 🛡️ 安全闸门：三重验证机制
 pub struct SafetyGatekeeper;
@@ -30,13 +31,14 @@ impl SafetyGatekeeper {
         
         let threshold_unique = top1.confidence >= min_confidence as f64 && high_quality_count == 1;
         
-        // 间隔唯一性：Top1 - Top2 >= 0.15
+        // 间隔唯一性：Top1 - Top2 >= uniqueness_gap（取自匹配配置，默认 0.15）
+        let required_gap = matching_config::current().safety.uniqueness_gap as f64;
         let gap_unique = if candidates.len() >= 2 {
             let top2 = &candidates[1];
             let gap = top1.confidence - top2.confidence;
-            tracing::debug!("🔍 置信度间隔: Top1={:.3}, Top2={:.3}, Gap={:.3}", 
-                          top1.confidence, top2.confidence, gap);
-            gap >= 0.15
+            tracing::debug!("🔍 置信度间隔: Top1={:.3}, Top2={:.3}, Gap={:.3}, 要求>={:.3}", 
+                          top1.confidence, top2.confidence, gap, required_gap);
+            gap >= required_gap
         } else {
             tracing::debug!("🔍 单一候选，自动通过间隔检查");
             true
@@ -57,15 +59,16 @@ impl SafetyGatekeeper {
             return true;
         }
         
-        // 检查整屏节点（面积占比>95%）
+        // 检查整屏节点（面积占比 > fullscreen_area_ratio，屏幕尺寸取自匹配配置）
+        let safety = &matching_config::current().safety;
         let width = (candidate.bounds.right - candidate.bounds.left) as f32;
         let height = (candidate.bounds.bottom - candidate.bounds.top) as f32;
         let area = width * height;
-        let screen_area = 1080.0 * 2400.0; // 应从设备信息获取
+        let screen_area = safety.screen_width as f32 * safety.screen_height as f32;
         let area_ratio = area / screen_area;
         
-        if area_ratio > 0.95 {
-            tracing::warn!("🚫 整屏拦截: 面积占比{:.1}% > 95%", area_ratio * 100.0);
+        if area_ratio > safety.fullscreen_area_ratio {
+            tracing::warn!("🚫 整屏拦截: 面积占比{:.1}% > {:.1}%", area_ratio * 100.0, safety.fullscreen_area_ratio * 100.0);
             return false;
         }
         
@@ -213,10 +216,15 @@ impl FallbackController {
             tracing::info!("🔄 开始回退流程，剩余预算: {}ms", 
                           total_budget.saturating_sub(start_time.elapsed().as_millis() as u64));
             
-            for variant in &plan.plan {
-                if variant.id == plan.strategy.selected {
-                    continue; // 跳过已尝试的
-                }
+            // 有应用画像时按该应用的策略适配度预排序回退顺序
+            let fallbacks: Vec<&StrategyVariant> = plan.plan.iter()
+                .filter(|v| v.id != plan.strategy.selected) // 跳过已尝试的
+                .collect();
+            let fallbacks = crate::services::app_profiles::rank_by_profile(
+                env.package.as_deref(), fallbacks, |v| v.kind.to_str()
+            );
+            
+            for variant in fallbacks {
                 
                 // 检查剩余时间预算
                 let elapsed = start_time.elapsed().as_millis() as u64;
@@ -289,7 +297,7 @@ impl FallbackController {
         tracing::debug!("🎯 找到 {} 个候选节点", sorted_candidates.len());
         
         // 安全闸门验证
-        let min_confidence = 0.70; // 应从plan获取
+        let min_confidence = matching_config::current().safety.min_confidence;
         let forbid_containers = true; // 应从plan获取
         
         if let Some(validated_target) = SafetyGatekeeper::comprehensive_validation(
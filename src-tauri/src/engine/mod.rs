@@ -7,7 +7,9 @@ pub mod self_anchor; // 🆕 新增：模块化Self-Anchor系统
 // 🚀 新增：插件化决策链系统
 pub mod strategy_plugin;
 pub mod gating;
+pub mod ui_tree; // 🌲 UI Dump 节点树（quick-xml 拉取式解析）
 pub mod xml_indexer;
+pub mod xml_index_store; // 💾 XML索引持久化（按 dump 哈希存入制品库）
 pub mod index_path_locator; // 🎯 新增：绝对路径定位模块
 
 pub use strategy_engine::{
@@ -23,4 +25,5 @@ pub use strategy_engine::{
 
 pub use gating::FallbackController;
 
+pub use ui_tree::UiTree;
 pub use xml_indexer::XmlIndexer;
\ No newline at end of file
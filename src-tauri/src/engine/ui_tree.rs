@@ -0,0 +1,496 @@
+// src-tauri/src/engine/ui_tree.rs
+// module: engine | layer: domain | role: UI Dump 节点树
+// summary: 用 quick-xml 拉取式解析 uiautomator dump，节点与属性文本集中存放在连续数组（arena）中，供匹配器、索引器与失败标注共享
+
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+
+/// 节点边界 (left, top, right, bottom)
+pub type NodeBounds = (i32, i32, i32, i32);
+
+/// 树中保存文本的属性
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Attr {
+    Text,
+    ResourceId,
+    Class,
+    Package,
+    ContentDesc,
+    Bounds,
+    Index,
+}
+
+const ATTR_COUNT: usize = 7;
+
+impl Attr {
+    fn from_key(key: &[u8]) -> Option<Attr> {
+        Some(match key {
+            b"text" => Attr::Text,
+            b"resource-id" => Attr::ResourceId,
+            b"class" => Attr::Class,
+            b"package" => Attr::Package,
+            b"content-desc" => Attr::ContentDesc,
+            b"bounds" => Attr::Bounds,
+            b"index" => Attr::Index,
+            _ => return None,
+        })
+    }
+
+    /// dump 中的属性名
+    pub fn name(self) -> &'static str {
+        match self {
+            Attr::Text => "text",
+            Attr::ResourceId => "resource-id",
+            Attr::Class => "class",
+            Attr::Package => "package",
+            Attr::ContentDesc => "content-desc",
+            Attr::Bounds => "bounds",
+            Attr::Index => "index",
+        }
+    }
+}
+
+/// 布尔属性（按位存放）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Flag {
+    Clickable,
+    LongClickable,
+    Enabled,
+    Focusable,
+    Focused,
+    Scrollable,
+    Checkable,
+    Checked,
+    Selected,
+    Password,
+}
+
+impl Flag {
+    fn from_key(key: &[u8]) -> Option<Flag> {
+        Some(match key {
+            b"clickable" => Flag::Clickable,
+            b"long-clickable" => Flag::LongClickable,
+            b"enabled" => Flag::Enabled,
+            b"focusable" => Flag::Focusable,
+            b"focused" => Flag::Focused,
+            b"scrollable" => Flag::Scrollable,
+            b"checkable" => Flag::Checkable,
+            b"checked" => Flag::Checked,
+            b"selected" => Flag::Selected,
+            b"password" => Flag::Password,
+            _ => return None,
+        })
+    }
+
+    fn bit(self) -> u16 {
+        1 << self as u16
+    }
+}
+
+/// 属性文本在 `UiTree::text` 中的位置
+#[derive(Debug, Clone, Copy)]
+struct Span {
+    start: u32,
+    len: u32,
+}
+
+#[derive(Debug, Clone)]
+struct NodeData {
+    parent: Option<u32>,
+    first_child: Option<u32>,
+    next_sibling: Option<u32>,
+    depth: u32,
+    /// 在同级元素中的 0 基下标（与 index_path 约定一致）
+    sibling_index: u32,
+    attrs: [Option<Span>; ATTR_COUNT],
+    bounds: Option<NodeBounds>,
+    flags: u16,
+}
+
+/// 解析后的 UI 树：节点按文档顺序排列，下标即 `element_N` / `all_nodes[N]`
+#[derive(Debug, Clone, Default)]
+pub struct UiTree {
+    nodes: Vec<NodeData>,
+    /// 所有属性文本拼接在一起，节点只保存区间
+    text: String,
+    /// 解析中途出错时为 true（保留已解析的节点）
+    truncated: bool,
+}
+
+/// 解析时每一层的状态
+struct Frame {
+    node: Option<u32>,
+    last_child: Option<u32>,
+    children: u32,
+}
+
+impl UiTree {
+    /// 严格解析：XML 不完整时返回错误
+    pub fn parse(xml: &str) -> Result<UiTree, String> {
+        let tree = Self::parse_lenient(xml);
+        if tree.truncated {
+            return Err(format!("UI Dump 解析失败（已解析 {} 个节点）", tree.nodes.len()));
+        }
+        Ok(tree)
+    }
+
+    /// 宽松解析：遇到错误时保留已解析的节点（dump 被截断时仍可匹配）
+    pub fn parse_lenient(xml: &str) -> UiTree {
+        let mut tree = UiTree {
+            // 每个节点约 250 字节，属性文本约占一半
+            nodes: Vec::with_capacity(xml.len() / 250),
+            text: String::with_capacity(xml.len() / 2),
+            truncated: false,
+        };
+        let mut stack = vec![Frame { node: None, last_child: None, children: 0 }];
+        let mut reader = Reader::from_str(xml);
+        loop {
+            match reader.read_event() {
+                Ok(Event::Start(e)) if e.name().as_ref() == b"node" => {
+                    let id = tree.push_node(&e, &mut stack);
+                    stack.push(Frame { node: Some(id), last_child: None, children: 0 });
+                }
+                Ok(Event::Empty(e)) if e.name().as_ref() == b"node" => {
+                    tree.push_node(&e, &mut stack);
+                }
+                Ok(Event::End(e)) if e.name().as_ref() == b"node" => {
+                    if stack.len() > 1 {
+                        stack.pop();
+                    }
+                }
+                Ok(Event::Eof) => {
+                    // 未闭合的 <node> 说明 dump 被截断
+                    tree.truncated = stack.len() > 1;
+                    break;
+                }
+                Ok(_) => {}
+                Err(e) => {
+                    tracing::warn!("⚠️ UI Dump 在 {} 字节处解析中断: {}", reader.buffer_position(), e);
+                    tree.truncated = true;
+                    break;
+                }
+            }
+        }
+        tree
+    }
+
+    fn push_node(&mut self, element: &BytesStart, stack: &mut [Frame]) -> u32 {
+        let id = self.nodes.len() as u32;
+        let depth = stack.len() as u32 - 1;
+        let frame = stack.last_mut().expect("根帧始终存在");
+        let mut node = NodeData {
+            parent: frame.node,
+            first_child: None,
+            next_sibling: None,
+            depth,
+            sibling_index: frame.children,
+            attrs: [None; ATTR_COUNT],
+            bounds: None,
+            flags: 0,
+        };
+
+        for attr in element.attributes().with_checks(false).flatten() {
+            let key = attr.key.as_ref();
+            if let Some(flag) = Flag::from_key(key) {
+                if attr.value.as_ref() == b"true" {
+                    node.flags |= flag.bit();
+                }
+            } else if let Some(kind) = Attr::from_key(key) {
+                let value = attr.unescape_value().unwrap_or_else(|_| String::from_utf8_lossy(&attr.value).into_owned().into());
+                if kind == Attr::Bounds {
+                    node.bounds = parse_bounds(&value);
+                }
+                let start = self.text.len() as u32;
+                self.text.push_str(&value);
+                node.attrs[kind as usize] = Some(Span { start, len: value.len() as u32 });
+            }
+        }
+
+        match frame.last_child {
+            Some(prev) => self.nodes[prev as usize].next_sibling = Some(id),
+            None => {
+                if let Some(parent) = frame.node {
+                    self.nodes[parent as usize].first_child = Some(id);
+                }
+            }
+        }
+        frame.last_child = Some(id);
+        frame.children += 1;
+        self.nodes.push(node);
+        id
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    pub fn is_truncated(&self) -> bool {
+        self.truncated
+    }
+
+    pub fn node(&self, id: usize) -> Option<NodeRef<'_>> {
+        (id < self.nodes.len()).then_some(NodeRef { tree: self, id })
+    }
+
+    /// 按文档顺序遍历全部节点
+    pub fn nodes(&self) -> impl Iterator<Item = NodeRef<'_>> + '_ {
+        (0..self.nodes.len()).map(move |id| NodeRef { tree: self, id })
+    }
+
+    /// `<hierarchy>` 下的顶层节点
+    pub fn roots(&self) -> impl Iterator<Item = NodeRef<'_>> + '_ {
+        self.nodes().filter(|n| n.data().parent.is_none())
+    }
+
+    /// 沿 index_path（从 `<hierarchy>` 开始的同级下标链）定位节点
+    pub fn find_by_index_path(&self, index_path: &[usize]) -> Option<NodeRef<'_>> {
+        let (&first, rest) = index_path.split_first()?;
+        let mut current = self.roots().nth(first)?;
+        for &index in rest {
+            current = current.children().nth(index)?;
+        }
+        Some(current)
+    }
+
+    /// 包含该点的面积最小节点
+    pub fn node_at(&self, x: i32, y: i32) -> Option<NodeRef<'_>> {
+        self.nodes()
+            .filter(|n| n.bounds().is_some_and(|(l, t, r, b)| x >= l && x <= r && y >= t && y <= b))
+            .min_by_key(|n| n.area())
+    }
+}
+
+/// 节点视图（借用所属的树）
+#[derive(Clone, Copy)]
+pub struct NodeRef<'a> {
+    tree: &'a UiTree,
+    id: usize,
+}
+
+impl<'a> NodeRef<'a> {
+    fn data(&self) -> &'a NodeData {
+        &self.tree.nodes[self.id]
+    }
+
+    fn wrap(&self, id: Option<u32>) -> Option<NodeRef<'a>> {
+        id.map(|id| NodeRef { tree: self.tree, id: id as usize })
+    }
+
+    /// 文档顺序下标
+    pub fn id(&self) -> usize {
+        self.id
+    }
+
+    /// 属性原值（已反转义）；属性缺失时为 None，空值为 `Some("")`
+    pub fn attr(&self, attr: Attr) -> Option<&'a str> {
+        let span = self.data().attrs[attr as usize]?;
+        let start = span.start as usize;
+        Some(&self.tree.text[start..start + span.len as usize])
+    }
+
+    pub fn text(&self) -> &'a str {
+        self.attr(Attr::Text).unwrap_or_default()
+    }
+
+    pub fn content_desc(&self) -> &'a str {
+        self.attr(Attr::ContentDesc).unwrap_or_default()
+    }
+
+    /// 非空的 resource-id
+    pub fn resource_id(&self) -> Option<&'a str> {
+        self.attr(Attr::ResourceId).filter(|s| !s.is_empty())
+    }
+
+    pub fn class_name(&self) -> Option<&'a str> {
+        self.attr(Attr::Class).filter(|s| !s.is_empty())
+    }
+
+    pub fn package(&self) -> Option<&'a str> {
+        self.attr(Attr::Package).filter(|s| !s.is_empty())
+    }
+
+    pub fn bounds(&self) -> Option<NodeBounds> {
+        self.data().bounds
+    }
+
+    pub fn area(&self) -> i64 {
+        self.bounds()
+            .map(|(l, t, r, b)| ((r - l).max(0) as i64) * ((b - t).max(0) as i64))
+            .unwrap_or(0)
+    }
+
+    pub fn flag(&self, flag: Flag) -> bool {
+        self.data().flags & flag.bit() != 0
+    }
+
+    /// 深度（顶层节点为 0）
+    pub fn depth(&self) -> usize {
+        self.data().depth as usize
+    }
+
+    pub fn parent(&self) -> Option<NodeRef<'a>> {
+        self.wrap(self.data().parent)
+    }
+
+    pub fn children(&self) -> impl Iterator<Item = NodeRef<'a>> + 'a {
+        let tree = self.tree;
+        let mut next = self.data().first_child;
+        std::iter::from_fn(move || {
+            let id = next? as usize;
+            next = tree.nodes[id].next_sibling;
+            Some(NodeRef { tree, id })
+        })
+    }
+
+    /// 从 `<hierarchy>` 到本节点的同级下标链
+    pub fn index_path(&self) -> Vec<usize> {
+        let mut path = Vec::with_capacity(self.depth() + 1);
+        let mut current = Some(*self);
+        while let Some(node) = current {
+            path.push(node.data().sibling_index as usize);
+            current = node.parent();
+        }
+        path.reverse();
+        path
+    }
+}
+
+impl std::fmt::Debug for NodeRef<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("NodeRef")
+            .field("id", &self.id)
+            .field("class", &self.class_name())
+            .field("text", &self.text())
+            .field("bounds", &self.bounds())
+            .finish()
+    }
+}
+
+/// 解析 `[l,t][r,b]`（允许负数）
+pub fn parse_bounds(raw: &str) -> Option<NodeBounds> {
+    let mut numbers = raw
+        .split(|c: char| !(c.is_ascii_digit() || c == '-'))
+        .filter(|s| !s.is_empty())
+        .map(str::parse::<i32>);
+    match (numbers.next(), numbers.next(), numbers.next(), numbers.next(), numbers.next()) {
+        (Some(Ok(left)), Some(Ok(top)), Some(Ok(right)), Some(Ok(bottom)), None) => Some((left, top, right, bottom)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DUMP: &str = r#"<?xml version='1.0' encoding='UTF-8' standalone='yes' ?>
+<hierarchy rotation="0">
+  <node index="0" text="" class="android.widget.FrameLayout" package="com.app" clickable="false" bounds="[0,0][1080,2400]">
+    <node index="0" text="关注 &amp; 私信" resource-id="com.app:id/follow" class="android.widget.Button" package="com.app" clickable="true" enabled="true" bounds="[100,200][300,260]" />
+    <node index="1" text="" resource-id="" class="android.widget.LinearLayout" bounds="[0,300][1080,900]">
+      <node index="0" text="列表项" class="android.widget.TextView" content-desc="item" bounds="[10,310][500,400]" />
+    </node>
+  </node>
+</hierarchy>"#;
+
+    /// 改造前 run_step_v2 的节点提取循环，属性与 bounds 解析直接调用仍在使用的 `validation::xml_parser`
+    #[cfg(not(debug_assertions))]
+    fn legacy_extract(xml: &str) -> usize {
+        use crate::commands::run_step_v2::validation::{parse_bounds_from_string, parse_xml_attribute};
+
+        let node_regex = regex::Regex::new(r#"<node[^>]*>"#).unwrap();
+        let mut found = 0;
+        for node_match in node_regex.find_iter(xml) {
+            let node_str = node_match.as_str();
+            let text = parse_xml_attribute(node_str, "text");
+            let resource_id = parse_xml_attribute(node_str, "resource-id");
+            let class_name = parse_xml_attribute(node_str, "class");
+            let content_desc = parse_xml_attribute(node_str, "content-desc");
+            let bounds = parse_xml_attribute(node_str, "bounds").and_then(|b| parse_bounds_from_string(&b).ok());
+            if text.is_some() || resource_id.is_some() || class_name.is_some() || content_desc.is_some() || bounds.is_some() {
+                found += 1;
+            }
+        }
+        found
+    }
+
+    #[cfg(not(debug_assertions))]
+    fn large_dump(target_bytes: usize) -> String {
+        let mut xml = String::from("<?xml version='1.0' encoding='UTF-8' standalone='yes' ?><hierarchy rotation=\"0\">");
+        let mut i = 0;
+        while xml.len() < target_bytes {
+            xml.push_str(&format!(
+                r#"<node index="{i}" text="第{i}项" resource-id="com.app:id/item_{i}" class="android.widget.TextView" package="com.app" content-desc="" checkable="false" checked="false" clickable="true" enabled="true" focusable="true" focused="false" scrollable="false" long-clickable="false" password="false" selected="false" bounds="[0,{t}][1080,{b}]"><node index="0" text="" resource-id="" class="android.widget.ImageView" package="com.app" content-desc="头像" clickable="false" enabled="true" bounds="[10,{t}][90,{b}]" /></node>"#,
+                i = i,
+                t = i * 10,
+                b = i * 10 + 80
+            ));
+            i += 1;
+        }
+        xml.push_str("</hierarchy>");
+        xml
+    }
+
+    #[test]
+    fn builds_tree_with_attributes_and_structure() {
+        let tree = UiTree::parse(DUMP).unwrap();
+        assert_eq!(tree.len(), 4);
+
+        let button = tree.node(1).unwrap();
+        assert_eq!(button.text(), "关注 & 私信");
+        assert_eq!(button.resource_id(), Some("com.app:id/follow"));
+        assert_eq!(button.bounds(), Some((100, 200, 300, 260)));
+        assert!(button.flag(Flag::Clickable) && !button.flag(Flag::Scrollable));
+        assert_eq!(button.parent().unwrap().id(), 0);
+
+        let layout = tree.node(2).unwrap();
+        assert_eq!(layout.attr(Attr::ResourceId), Some(""));
+        assert_eq!(layout.resource_id(), None);
+        assert_eq!(layout.index_path(), vec![0, 1]);
+        assert_eq!(tree.node(0).unwrap().children().map(|n| n.id()).collect::<Vec<_>>(), vec![1, 2]);
+
+        assert_eq!(tree.find_by_index_path(&[0, 1, 0]).unwrap().content_desc(), "item");
+        assert_eq!(tree.node_at(120, 230).unwrap().id(), 1);
+        assert_eq!(parse_bounds("[-5,0][10,-1]"), Some((-5, 0, 10, -1)));
+        assert_eq!(parse_bounds("[1,2][3]"), None);
+    }
+
+    #[test]
+    fn keeps_parsed_nodes_of_truncated_dump() {
+        let cut = &DUMP[..DUMP.find("<node index=\"0\" text=\"列表项").unwrap()];
+        assert!(UiTree::parse(cut).is_err());
+        let tree = UiTree::parse_lenient(cut);
+        assert!(tree.is_truncated());
+        assert_eq!(tree.len(), 3);
+    }
+
+    /// 基准门槛：3MB dump 上树解析至少比改造前的正则提取快 5 倍
+    /// 只在 release 构建中编译运行（CI 单独执行 `cargo test --release -p employee-gui --lib ui_tree_parse_is_5x_faster`）
+    #[cfg(not(debug_assertions))]
+    #[test]
+    fn ui_tree_parse_is_5x_faster_than_regex_extraction() {
+        let xml = large_dump(3 * 1024 * 1024);
+
+        let start = std::time::Instant::now();
+        let legacy = legacy_extract(&xml);
+        let legacy_elapsed = start.elapsed();
+
+        let start = std::time::Instant::now();
+        let tree = UiTree::parse(&xml).unwrap();
+        let tree_elapsed = start.elapsed();
+
+        assert_eq!(legacy, tree.len());
+        let speedup = legacy_elapsed.as_secs_f64() / tree_elapsed.as_secs_f64().max(1e-9);
+        assert!(
+            speedup >= 5.0,
+            "解析加速不足 5 倍: legacy={:?} tree={:?} nodes={} speedup={:.1}x",
+            legacy_elapsed,
+            tree_elapsed,
+            tree.len(),
+            speedup
+        );
+    }
+}
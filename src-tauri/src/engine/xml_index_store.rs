@@ -0,0 +1,239 @@
+// src-tauri/src/engine/xml_index_store.rs
+// module: decision-chain | layer: engine | role: XML索引持久化
+// summary: 按 dump 内容哈希把构建好的索引节点（含几何父子关系）以 CBOR+zstd 存进制品库，LRU 限制条数；相同页面再次出现时直接加载，并统计内存 / 磁盘命中与未命中
+
+use chrono::{Duration as ChronoDuration, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::sync::atomic::{AtomicU64, Ordering};
+use tracing::{debug, warn};
+
+use super::xml_indexer::IndexedNode;
+use crate::services::artifact_store::ArtifactStore;
+
+/// 制品类型
+pub const ARTIFACT_KIND: &str = "xml_index";
+/// 序列化格式版本（IndexedNode 结构变化时递增，旧条目视为未命中）
+const FORMAT_VERSION: u32 = 1;
+/// 磁盘最多保留的索引条数，超出后按最近使用时间淘汰
+const MAX_ENTRIES: usize = 64;
+/// 单条索引的有效期
+const ENTRY_TTL_DAYS: i64 = 30;
+/// zstd 压缩级别
+const COMPRESSION_LEVEL: i32 = 3;
+/// 命中时刷新 lastUsed 的最小间隔，避免每次命中都重写制品索引
+const TOUCH_INTERVAL_SECS: i64 = 60;
+
+static MEMORY_HITS: AtomicU64 = AtomicU64::new(0);
+static DISK_HITS: AtomicU64 = AtomicU64::new(0);
+static MISSES: AtomicU64 = AtomicU64::new(0);
+static STORES: AtomicU64 = AtomicU64::new(0);
+static EVICTIONS: AtomicU64 = AtomicU64::new(0);
+
+#[derive(Serialize, Deserialize)]
+struct PersistedIndex {
+    version: u32,
+    xml_hash: u64,
+    nodes: Vec<IndexedNode>,
+}
+
+/// 索引缓存统计（计数从进程启动起累计）
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct XmlIndexCacheStats {
+    pub memory_hits: u64,
+    pub disk_hits: u64,
+    pub misses: u64,
+    /// 命中率（内存 + 磁盘），无请求时为 0
+    pub hit_rate: f64,
+    pub stores: u64,
+    pub evictions: u64,
+    /// 磁盘条目数与占用字节
+    pub disk_entries: usize,
+    pub disk_bytes: u64,
+    pub disk_capacity: usize,
+    /// 内存缓存条目数与上限
+    pub memory_entries: usize,
+    pub memory_capacity: usize,
+}
+
+fn hash_key(xml_hash: u64) -> String {
+    format!("{:016x}", xml_hash)
+}
+
+fn encode(xml_hash: u64, nodes: &[IndexedNode]) -> Result<Vec<u8>, String> {
+    let persisted = PersistedIndex { version: FORMAT_VERSION, xml_hash, nodes: nodes.to_vec() };
+    let mut raw = Vec::new();
+    ciborium::ser::into_writer(&persisted, &mut raw).map_err(|e| format!("序列化索引失败: {}", e))?;
+    zstd::stream::encode_all(raw.as_slice(), COMPRESSION_LEVEL).map_err(|e| format!("压缩索引失败: {}", e))
+}
+
+fn decode(xml_hash: u64, bytes: &[u8]) -> Result<Vec<IndexedNode>, String> {
+    let raw = zstd::stream::decode_all(bytes).map_err(|e| format!("解压索引失败: {}", e))?;
+    let persisted: PersistedIndex = ciborium::de::from_reader(raw.as_slice()).map_err(|e| format!("反序列化索引失败: {}", e))?;
+    if persisted.version != FORMAT_VERSION {
+        return Err(format!("索引格式版本不符: {} != {}", persisted.version, FORMAT_VERSION));
+    }
+    if persisted.xml_hash != xml_hash {
+        return Err("索引哈希不符".to_string());
+    }
+    Ok(persisted.nodes)
+}
+
+/// 从制品库加载索引；损坏或版本不符的条目会被删除并视为未命中
+pub fn load_from(store: &mut ArtifactStore, xml_hash: u64) -> Option<Vec<IndexedNode>> {
+    let key = hash_key(xml_hash);
+    let (artifact, path) = store.list(ARTIFACT_KIND).into_iter().find(|(a, _)| a.meta["hash"] == key.as_str())?;
+    let decoded = std::fs::read(&path).map_err(|e| format!("读取索引失败: {}", e)).and_then(|bytes| decode(xml_hash, &bytes));
+    match decoded {
+        Ok(nodes) => {
+            let now = Utc::now().timestamp();
+            let last_used = artifact.meta["lastUsed"].as_i64().unwrap_or(0);
+            if now - last_used >= TOUCH_INTERVAL_SECS {
+                if let Err(e) = store.set_meta(&[(artifact.token.clone(), "lastUsed", json!(now))]) {
+                    warn!("⚠️ 更新索引使用时间失败: {}", e);
+                }
+            }
+            Some(nodes)
+        }
+        Err(e) => {
+            warn!("⚠️ 丢弃无效的持久化索引 {}: {}", key, e);
+            let _ = store.remove(&[artifact.token]);
+            None
+        }
+    }
+}
+
+/// 写入制品库（同一哈希只保留一份），超出上限时淘汰最久未使用的条目；返回淘汰数
+pub fn save_to(store: &mut ArtifactStore, xml_hash: u64, nodes: &[IndexedNode]) -> Result<usize, String> {
+    let key = hash_key(xml_hash);
+    let bytes = encode(xml_hash, nodes)?;
+    let stale: Vec<String> =
+        store.list(ARTIFACT_KIND).into_iter().filter(|(a, _)| a.meta["hash"] == key.as_str()).map(|(a, _)| a.token).collect();
+    store.remove(&stale)?;
+    store.put(
+        ARTIFACT_KIND,
+        &bytes,
+        "cbor.zst",
+        "application/cbor+zstd",
+        ChronoDuration::days(ENTRY_TTL_DAYS),
+        json!({ "hash": key, "nodes": nodes.len(), "lastUsed": Utc::now().timestamp() }),
+    )?;
+
+    let mut entries = store.list(ARTIFACT_KIND);
+    if entries.len() <= MAX_ENTRIES {
+        return Ok(0);
+    }
+    // 最近使用的在前，同一秒内以创建时间（list 已按新到旧排序）为次序
+    entries.sort_by_key(|(a, _)| std::cmp::Reverse(a.meta["lastUsed"].as_i64().unwrap_or(0)));
+    let evicted: Vec<String> = entries.into_iter().skip(MAX_ENTRIES).map(|(a, _)| a.token).collect();
+    store.remove(&evicted)
+}
+
+/// 记录一次内存缓存命中
+pub fn record_memory_hit() {
+    MEMORY_HITS.fetch_add(1, Ordering::Relaxed);
+}
+
+/// 从全局制品库加载索引并计数
+pub fn load(xml_hash: u64) -> Option<Vec<IndexedNode>> {
+    let loaded = match ArtifactStore::global().lock() {
+        Ok(mut store) => load_from(&mut store, xml_hash),
+        Err(e) => {
+            warn!("⚠️ 制品库加锁失败: {}", e);
+            None
+        }
+    };
+    let counter = if loaded.is_some() { &DISK_HITS } else { &MISSES };
+    counter.fetch_add(1, Ordering::Relaxed);
+    loaded
+}
+
+/// 写入全局制品库并计数；失败只记日志，不影响本次执行
+pub fn store(xml_hash: u64, nodes: &[IndexedNode]) {
+    let result = ArtifactStore::global().lock().map_err(|e| e.to_string()).and_then(|mut store| save_to(&mut store, xml_hash, nodes));
+    match result {
+        Ok(evicted) => {
+            STORES.fetch_add(1, Ordering::Relaxed);
+            EVICTIONS.fetch_add(evicted as u64, Ordering::Relaxed);
+            debug!("💾 XML索引已持久化 (hash={}, 淘汰={})", hash_key(xml_hash), evicted);
+        }
+        Err(e) => warn!("⚠️ 持久化XML索引失败: {}", e),
+    }
+}
+
+/// 当前统计
+pub fn stats() -> XmlIndexCacheStats {
+    let (disk_entries, disk_bytes) = ArtifactStore::global()
+        .lock()
+        .map(|store| {
+            let entries = store.list(ARTIFACT_KIND);
+            (entries.len(), entries.iter().map(|(a, _)| a.size).sum())
+        })
+        .unwrap_or_default();
+    let (memory_entries, memory_capacity) = super::xml_indexer::XmlIndexer::get_cache_stats();
+    let memory_hits = MEMORY_HITS.load(Ordering::Relaxed);
+    let disk_hits = DISK_HITS.load(Ordering::Relaxed);
+    let misses = MISSES.load(Ordering::Relaxed);
+    let total = memory_hits + disk_hits + misses;
+    XmlIndexCacheStats {
+        memory_hits,
+        disk_hits,
+        misses,
+        hit_rate: if total == 0 { 0.0 } else { (memory_hits + disk_hits) as f64 / total as f64 },
+        stores: STORES.load(Ordering::Relaxed),
+        evictions: EVICTIONS.load(Ordering::Relaxed),
+        disk_entries,
+        disk_bytes,
+        disk_capacity: MAX_ENTRIES,
+        memory_entries,
+        memory_capacity,
+    }
+}
+
+/// 清空磁盘索引并重置计数，返回删除的条目数
+pub fn clear() -> Result<usize, String> {
+    let mut store = ArtifactStore::global().lock().map_err(|e| e.to_string())?;
+    let tokens: Vec<String> = store.list(ARTIFACT_KIND).into_iter().map(|(a, _)| a.token).collect();
+    let removed = store.remove(&tokens)?;
+    for counter in [&MEMORY_HITS, &DISK_HITS, &MISSES, &STORES, &EVICTIONS] {
+        counter.store(0, Ordering::Relaxed);
+    }
+    Ok(removed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::xml_indexer::XmlIndexer;
+
+    const XML: &str = r#"<hierarchy><node index="0" class="android.widget.FrameLayout" bounds="[0,0][1080,1920]"><node index="0" text="关注" resource-id="com.app:id/follow" class="android.widget.Button" clickable="true" bounds="[10,10][200,100]"/></node></hierarchy>"#;
+
+    #[test]
+    fn test_round_trip_and_lru_eviction() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut store = ArtifactStore::open(dir.path().to_path_buf());
+        let nodes = XmlIndexer::build_from_xml_internal(XML).unwrap().all_nodes;
+
+        assert!(load_from(&mut store, 1).is_none());
+        assert_eq!(save_to(&mut store, 1, &nodes).unwrap(), 0);
+        let loaded = load_from(&mut store, 1).unwrap();
+        assert_eq!(loaded, nodes);
+        assert_eq!(loaded[1].parent_index, Some(0));
+        assert!(loaded[1].element.clickable);
+
+        // 同一哈希重复写入只保留一份
+        save_to(&mut store, 1, &nodes).unwrap();
+        assert_eq!(store.list(ARTIFACT_KIND).len(), 1);
+
+        // 超出上限后淘汰最久未使用的条目，刚用过的保留
+        store.set_meta(&[(store.list(ARTIFACT_KIND)[0].0.token.clone(), "lastUsed", json!(i64::MAX))]).unwrap();
+        let mut evicted = 0;
+        for hash in 2..=(MAX_ENTRIES as u64 + 1) {
+            evicted += save_to(&mut store, hash, &nodes[..1]).unwrap();
+        }
+        assert_eq!(evicted, 1);
+        assert_eq!(store.list(ARTIFACT_KIND).len(), MAX_ENTRIES);
+        assert!(load_from(&mut store, 1).is_some());
+    }
+}
@@ -1,22 +1,19 @@
 // src-tauri/src/engine/xml_indexer.rs
 // module: decision-chain | layer: engine | role: XML快速索引与检索
-// summary: 按id/class/text建立索引桶，支持容器限定的高效搜索
+// summary: 按id/class/text建立索引桶，支持容器限定的高效搜索；节点来自共享的 UiTree（quick-xml 解析）
 
 use std::collections::HashMap;
 use std::sync::{Arc, RwLock};
-use std::hash::{Hash, Hasher};
-use std::collections::hash_map::DefaultHasher;
 use anyhow::Result;
-use regex::Regex;
 use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use xxhash_rust::xxh3::xxh3_64;
 
 use crate::services::universal_ui_page_analyzer::{UIElement, UIElementType};
 use crate::commands::run_step_v2::{MatchCandidate, Bounds};
 use crate::types::page_analysis::ElementBounds;
-
-// 🎯 性能优化：预编译正则表达式
-static NODE_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r#"<node[^>]*>"#).unwrap());
-static BOUNDS_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r#"\[(\d+),(\d+)\]\[(\d+),(\d+)\]"#).unwrap());
+use super::ui_tree::{Attr, Flag, NodeRef, UiTree};
+use super::xml_index_store;
 
 // 🚀 性能优化：XML索引全局缓存
 // 使用 XML 内容哈希作为 key，避免重复构建索引（节省 ~574ms）
@@ -47,12 +44,15 @@ pub struct XmlIndexer {
     /// 全部节点列表（用于全局检索）
     pub all_nodes: Vec<IndexedNode>,
     
-    /// 🎯 原始XML文本（用于支持 index_path 定位）
+    /// 🎯 原始XML文本
     pub raw_xml: String,
+
+    /// 🌲 解析后的节点树（下标与 all_nodes 一致，用于 index_path 定位）
+    pub tree: Arc<UiTree>,
 }
 
-// 🏷️ 索引节点结构
-#[derive(Debug, Clone, PartialEq)]
+// 🏷️ 索引节点结构（可序列化，供 xml_index_store 持久化）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct IndexedNode {
     pub id: String,
     pub element: UIElement,
@@ -83,11 +83,9 @@ impl IndexedNode {
 }
 
 impl XmlIndexer {
-    /// 🚀 计算XML内容哈希（用于缓存键）
+    /// 🚀 计算XML内容哈希（用于缓存键，跨进程稳定，内存与磁盘缓存共用）
     fn compute_xml_hash(xml: &str) -> u64 {
-        let mut hasher = DefaultHasher::new();
-        xml.hash(&mut hasher);
-        hasher.finish()
+        xxh3_64(xml.as_bytes())
     }
 
     /// 🚀 从缓存获取或构建索引
@@ -99,13 +97,24 @@ impl XmlIndexer {
             let cache = XML_INDEXER_CACHE.read().unwrap();
             if let Some(cached) = cache.get(&xml_hash) {
                 tracing::debug!("⚡ XML索引命中缓存 (hash={}), 跳过构建", xml_hash);
+                xml_index_store::record_memory_hit();
                 // 返回缓存的克隆（Arc 内部克隆是廉价的）
                 return Ok((**cached).clone());
             }
         }
         
-        // 📦 缓存未命中，构建新索引
-        let indexer = Self::build_from_xml_internal(ui_xml)?;
+        // 💽 其次检查持久化索引（常回访的页面跨执行复用），都未命中才构建并落盘
+        let indexer = match xml_index_store::load(xml_hash) {
+            Some(nodes) => {
+                tracing::debug!("⚡ XML索引命中持久化缓存 (hash={}), 跳过构建", xml_hash);
+                Self::from_persisted(ui_xml, nodes)
+            }
+            None => {
+                let indexer = Self::build_from_xml_internal(ui_xml)?;
+                xml_index_store::store(xml_hash, &indexer.all_nodes);
+                indexer
+            }
+        };
         
         // 💾 存入缓存
         {
@@ -128,8 +137,8 @@ impl XmlIndexer {
         Ok(indexer)
     }
 
-    /// 🔧 内部构建方法（实际执行索引构建）
-    fn build_from_xml_internal(ui_xml: &str) -> Result<Self> {
+    /// 由持久化的节点恢复索引：父子关系已随节点保存，只需重建索引桶与节点树
+    fn from_persisted(ui_xml: &str, nodes: Vec<IndexedNode>) -> Self {
         let mut indexer = Self {
             resource_id_index: HashMap::new(),
             class_name_index: HashMap::new(),
@@ -137,21 +146,40 @@ impl XmlIndexer {
             content_desc_index: HashMap::new(),
             container_index: HashMap::new(),
             all_nodes: Vec::new(),
-            raw_xml: ui_xml.to_string(), // 🎯 保存原始XML
+            raw_xml: ui_xml.to_string(),
+            tree: Arc::new(UiTree::parse_lenient(ui_xml)),
         };
-        
+        for node in &nodes {
+            indexer.add_to_indexes(node);
+        }
+        indexer.all_nodes = nodes;
+        indexer
+    }
+
+    /// 🔧 内部构建方法（实际执行索引构建，不经过缓存）
+    pub(crate) fn build_from_xml_internal(ui_xml: &str) -> Result<Self> {
         tracing::info!("🔧 开始构建XML索引...");
         let start_time = std::time::Instant::now();
-        
-        // 🎯 性能优化：使用预编译的正则表达式
-        for (index, node_match) in NODE_REGEX.find_iter(ui_xml).enumerate() {
-            let node_str = node_match.as_str();
-            
-            if let Ok(indexed_node) = Self::parse_node_to_indexed(node_str, index) {
-                // 添加到各种索引
-                indexer.add_to_indexes(&indexed_node);
-                indexer.all_nodes.push(indexed_node);
-            }
+
+        let tree = Arc::new(UiTree::parse_lenient(ui_xml));
+        let mut indexer = Self {
+            resource_id_index: HashMap::new(),
+            class_name_index: HashMap::new(),
+            text_index: HashMap::new(),
+            content_desc_index: HashMap::new(),
+            container_index: HashMap::new(),
+            all_nodes: Vec::new(),
+            raw_xml: ui_xml.to_string(), // 🎯 保存原始XML
+            tree: tree.clone(),
+        };
+        indexer.all_nodes.reserve(tree.len());
+
+        // 🎯 下标与 UiTree 一致（element_N 即第 N 个 <node>）
+        for node in tree.nodes() {
+            let indexed_node = Self::parse_node_to_indexed(node);
+            // 添加到各种索引
+            indexer.add_to_indexes(&indexed_node);
+            indexer.all_nodes.push(indexed_node);
         }
         
         // 🎯 构建父子关系树（性能优化关键）
@@ -178,68 +206,50 @@ impl XmlIndexer {
         (cache.len(), XML_CACHE_MAX_SIZE)
     }
     
-    /// 解析单个节点为索引节点
-    fn parse_node_to_indexed(node_str: &str, index: usize) -> Result<IndexedNode> {
+    /// 树节点转换为索引节点
+    fn parse_node_to_indexed(node: NodeRef<'_>) -> IndexedNode {
+        let index = node.id();
         let id = format!("node_{}", index);
-        
-        // 提取属性（简化实现）
-        let resource_id = Self::extract_attribute(node_str, "resource-id");
-        let text = Self::extract_attribute(node_str, "text");
-        let class_name = Self::extract_attribute(node_str, "class");
-        let content_desc = Self::extract_attribute(node_str, "content-desc");
-        let bounds_str = Self::extract_attribute(node_str, "bounds");
-        let clickable = Self::extract_attribute(node_str, "clickable");
-        let enabled = Self::extract_attribute(node_str, "enabled");
-        let package = Self::extract_attribute(node_str, "package");
-        
-        // 解析bounds
-        let bounds = if let Some(bounds_str) = &bounds_str {
-            match Self::parse_bounds(bounds_str) {
-                Ok(b) => {
-                    // 🎯 性能优化：降低日志级别为 trace（避免逐节点打印）
-                    // #[cfg(feature = "trace_xml_bounds")]
-                    // tracing::trace!("✅ [XmlIndexer] 成功解析bounds: '{}' -> {:?}", bounds_str, b);
-                    b
-                }
-                Err(e) => {
-                    tracing::error!("❌ [XmlIndexer] 解析bounds失败: '{}', 错误: {}, 使用默认值", bounds_str, e);
-                    (0, 0, 100, 100)
-                }
+
+        let resource_id = node.attr(Attr::ResourceId).map(str::to_string);
+        let class_name = node.attr(Attr::Class).map(str::to_string);
+        let package = node.attr(Attr::Package).map(str::to_string);
+
+        // bounds 缺失或无法解析时使用默认值
+        let bounds = node.bounds().unwrap_or_else(|| {
+            if let Some(raw) = node.attr(Attr::Bounds) {
+                tracing::error!("❌ [XmlIndexer] 解析bounds失败: '{}', 使用默认值", raw);
             }
-        } else {
-            // 🎯 性能优化：降低日志级别为 trace
-            // #[cfg(feature = "trace_xml_bounds")]
-            // tracing::trace!("⚠️ [XmlIndexer] 节点无bounds属性，使用默认值");
             (0, 0, 100, 100)
-        };
-        
+        });
+
         // 生成简化的xpath（实际应更精确）
         let xpath = format!("//*[@class='{}'][{}]", 
                           class_name.as_deref().unwrap_or("unknown"), 
                           index + 1);
         
-        let bounds_obj = bounds_str.as_ref()
-            .and_then(|s| ElementBounds::from_string(s))
+        let bounds_obj = node.bounds()
+            .map(|(left, top, right, bottom)| ElementBounds { left, top, right, bottom })
             .unwrap_or(ElementBounds { left: 0, top: 0, right: 0, bottom: 0 });
 
         let element = UIElement {
             id: "".to_string(),
             element_type: UIElementType::Other,
-            text: text.clone().unwrap_or_default(),
-            resource_id: resource_id.clone(),
-            class_name: class_name.clone(),
-            package_name: package.clone(),
-            content_desc: content_desc.clone().unwrap_or_default(),
-            clickable: clickable.and_then(|s| s.parse().ok()).unwrap_or(false),
-            enabled: enabled.and_then(|s| s.parse().ok()).unwrap_or(false),
+            text: node.text().to_string(),
+            resource_id,
+            class_name,
+            package_name: package,
+            content_desc: node.content_desc().to_string(),
+            clickable: node.flag(Flag::Clickable),
+            enabled: node.flag(Flag::Enabled),
             bounds: bounds_obj,
             xpath: xpath.clone(),
-            scrollable: false,
-            focused: false,
-            checkable: false,
-            checked: false,
-            selected: false,
-            password: false,
+            scrollable: node.flag(Flag::Scrollable),
+            focused: node.flag(Flag::Focused),
+            checkable: node.flag(Flag::Checkable),
+            checked: node.flag(Flag::Checked),
+            selected: node.flag(Flag::Selected),
+            password: node.flag(Flag::Password),
             children: Vec::new(),
             parent: None,
             depth: 0,
@@ -247,7 +257,7 @@ impl XmlIndexer {
             region: None,
         };
         
-        Ok(IndexedNode {
+        IndexedNode {
             id,
             element,
             bounds,
@@ -258,7 +268,7 @@ impl XmlIndexer {
             parent_index: None,
             children_indices: Vec::new(),
             depth: 0,
-        })
+        }
     }
     
     /// 添加节点到各种索引
@@ -461,39 +471,32 @@ impl XmlIndexer {
     /// }
     /// ```
     pub fn find_node_by_index_path(&self, index_path: &[usize]) -> Option<usize> {
-        use crate::engine::index_path_locator::find_node_index_by_index_path;
-        
         if index_path.is_empty() {
             tracing::warn!("⚠️ [XmlIndexer] index_path 为空");
             return None;
         }
 
-        match find_node_index_by_index_path(&self.raw_xml, index_path) {
-            Ok(idx) => {
-                if idx < self.all_nodes.len() {
-                    tracing::debug!(
-                        "✅ [XmlIndexer] 通过 index_path 找到节点: {:?} -> index {}",
-                        index_path,
-                        idx
-                    );
-                    Some(idx)
-                } else {
-                    tracing::error!(
-                        "❌ [XmlIndexer] index_path 返回的索引超出 all_nodes 范围: {} >= {}",
-                        idx,
-                        self.all_nodes.len()
-                    );
-                    None
-                }
-            }
-            Err(err) => {
-                tracing::warn!(
-                    "⚠️ [XmlIndexer] find_node_by_index_path 失败: index_path={:?}, err={}",
+        match self.tree.find_by_index_path(index_path).map(|node| node.id()) {
+            Some(idx) if idx < self.all_nodes.len() => {
+                tracing::debug!(
+                    "✅ [XmlIndexer] 通过 index_path 找到节点: {:?} -> index {}",
                     index_path,
-                    err
+                    idx
+                );
+                Some(idx)
+            }
+            Some(idx) => {
+                tracing::error!(
+                    "❌ [XmlIndexer] index_path 返回的索引超出 all_nodes 范围: {} >= {}",
+                    idx,
+                    self.all_nodes.len()
                 );
                 None
             }
+            None => {
+                tracing::warn!("⚠️ [XmlIndexer] find_node_by_index_path 失败: index_path={:?} 不存在", index_path);
+                None
+            }
         }
     }
 
@@ -511,34 +514,6 @@ impl XmlIndexer {
     }
     
     
-    /// 工具方法：提取XML属性
-    /// 🎯 性能优化版：提取XML属性（手动解析，避免Regex编译开销）
-    fn extract_attribute(node_str: &str, attr_name: &str) -> Option<String> {
-        // 查找属性名位置
-        let search_pattern = format!("{}=\"", attr_name);
-        if let Some(start_pos) = node_str.find(&search_pattern) {
-            let value_start = start_pos + search_pattern.len();
-            // 查找结束引号
-            if let Some(end_pos) = node_str[value_start..].find('"') {
-                return Some(node_str[value_start..value_start + end_pos].to_string());
-            }
-        }
-        None
-    }
-    
-    /// 工具方法：解析bounds
-    fn parse_bounds(bounds_str: &str) -> Result<(i32, i32, i32, i32)> {
-        // 🎯 性能优化：使用预编译的正则表达式
-        if let Some(captures) = BOUNDS_REGEX.captures(bounds_str) {
-            let left = captures.get(1).unwrap().as_str().parse()?;
-            let top = captures.get(2).unwrap().as_str().parse()?;
-            let right = captures.get(3).unwrap().as_str().parse()?;
-            let bottom = captures.get(4).unwrap().as_str().parse()?;
-            return Ok((left, top, right, bottom));
-        }
-        Err(anyhow::anyhow!("Invalid bounds format: {}", bounds_str))
-    }
-
     pub fn get_children(&self, node_idx: usize) -> Vec<usize> {
         self.all_nodes.get(node_idx).map(|n| n.children_indices.clone()).unwrap_or_default()
     }
@@ -798,6 +773,7 @@ mod tests {
             container_index: HashMap::new(),
             all_nodes: nodes,
             raw_xml: "".to_string(),
+            tree: Default::default(),
         };
 
         indexer.build_parent_child_relationships();
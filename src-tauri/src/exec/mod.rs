@@ -25,4 +25,5 @@ pub use crate::automation::pipeline::chain as chain_engine;
 pub use crate::automation::pipeline::single_step;
 pub use crate::automation::pipeline::static_exec;
 pub use crate::automation::pipeline::executor::*;
+pub use crate::automation::pipeline::multi_device;
 
@@ -0,0 +1,372 @@
+// src-tauri/src/infra/adb/agent_input_injector.rs
+// module: infra | layer: adb | role: 设备端代理注入器
+// summary: 经 adb forward 的长连接把点击 / 滑动 / 多指手势（及悬浮状态文字）交给手机端注入代理（无障碍或 Instrumentation），省去每次 `input` 进程启动的开销；代理不可用时短期退避，由调用方回退 shell
+
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::TcpStream;
+use tracing::{debug, info, warn};
+
+use super::input_injector::InputInjector;
+
+/// 手机端注入代理监听的端口
+pub const DEFAULT_AGENT_PORT: u16 = 11452;
+const DEFAULT_CONNECT_TIMEOUT_MS: u64 = 800;
+const DEFAULT_IO_TIMEOUT_MS: u64 = 1_500;
+/// 代理连接失败后在这段时间内直接回退 shell，不再反复探测
+const UNAVAILABLE_BACKOFF: Duration = Duration::from_secs(30);
+/// Android 同时触点上限
+const MAX_POINTERS: usize = 10;
+
+/// 每台设备一条长连接（本地转发端口 + 已建立的连接）
+#[derive(Default)]
+struct AgentConnection {
+    local_port: Option<u16>,
+    stream: Option<BufReader<TcpStream>>,
+}
+
+type SharedConnection = Arc<tokio::sync::Mutex<AgentConnection>>;
+
+static CONNECTIONS: OnceLock<Mutex<HashMap<String, SharedConnection>>> = OnceLock::new();
+static UNAVAILABLE_UNTIL: OnceLock<Mutex<HashMap<String, Instant>>> = OnceLock::new();
+
+/// 手势中的一个采样点，`t_ms` 为相对手势开始的时间
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TouchPoint {
+    pub x: u32,
+    pub y: u32,
+    pub t_ms: u32,
+}
+
+/// 单根手指的轨迹
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TouchPointer {
+    pub points: Vec<TouchPoint>,
+}
+
+/// 多指手势（各手指同时开始，按各自时间轴移动）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MultiTouchGesture {
+    pub pointers: Vec<TouchPointer>,
+}
+
+impl MultiTouchGesture {
+    /// 以 (cx, cy) 为中心的双指捏合 / 张开，`from_radius` → `to_radius`
+    pub fn pinch(cx: u32, cy: u32, from_radius: u32, to_radius: u32, duration_ms: u32) -> Self {
+        let finger = |sign: i64| TouchPointer {
+            points: [(from_radius, 0), (to_radius, duration_ms)]
+                .into_iter()
+                .map(|(radius, t_ms)| TouchPoint { x: (cx as i64 + sign * radius as i64).max(0) as u32, y: cy, t_ms })
+                .collect(),
+        };
+        Self { pointers: vec![finger(-1), finger(1)] }
+    }
+
+    /// 手势总时长
+    pub fn duration_ms(&self) -> u32 {
+        self.pointers.iter().filter_map(|p| p.points.last()).map(|p| p.t_ms).max().unwrap_or(0)
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        if self.pointers.is_empty() || self.pointers.len() > MAX_POINTERS {
+            anyhow::bail!("手势手指数必须在 1..={} 之间", MAX_POINTERS);
+        }
+        for (index, pointer) in self.pointers.iter().enumerate() {
+            if pointer.points.is_empty() {
+                anyhow::bail!("第 {} 根手指没有轨迹点", index + 1);
+            }
+            if pointer.points.windows(2).any(|w| w[1].t_ms < w[0].t_ms) {
+                anyhow::bail!("第 {} 根手指的轨迹时间必须单调递增", index + 1);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// 发给代理的一行 JSON 指令
+#[derive(Debug, Serialize)]
+#[serde(tag = "op", rename_all = "camelCase")]
+enum AgentCommand<'a> {
+    #[serde(rename_all = "camelCase")]
+    Tap { x: u32, y: u32, duration_ms: Option<u32> },
+    #[serde(rename_all = "camelCase")]
+    Swipe { x1: u32, y1: u32, x2: u32, y2: u32, duration_ms: u32 },
+    Gesture { pointers: &'a [TouchPointer] },
+    Key { code: &'a str },
+    Text { text: &'a str },
+    /// 悬浮状态文字；`text` 为空时移除
+    Overlay { text: Option<&'a str> },
+}
+
+#[derive(Debug, Deserialize)]
+struct AgentReply {
+    ok: bool,
+    #[serde(default)]
+    error: Option<String>,
+}
+
+/// 失败类别：连接 / 读写问题会触发重连与退避，代理明确拒绝则不会
+enum SendError {
+    Transport(anyhow::Error),
+    Rejected(String),
+}
+
+/// 设备端代理注入器
+pub struct AgentInputInjector {
+    adb_path: String,
+    remote_port: u16,
+    /// 固定的本地端口（跳过 adb forward，测试或手动转发时使用）
+    fixed_local_port: Option<u16>,
+    connect_timeout: Duration,
+    io_timeout: Duration,
+    enabled: bool,
+}
+
+impl AgentInputInjector {
+    pub fn new(adb_path: String) -> Self {
+        Self {
+            adb_path,
+            remote_port: DEFAULT_AGENT_PORT,
+            fixed_local_port: None,
+            connect_timeout: Duration::from_millis(DEFAULT_CONNECT_TIMEOUT_MS),
+            io_timeout: Duration::from_millis(DEFAULT_IO_TIMEOUT_MS),
+            enabled: true,
+        }
+    }
+
+    /// 根据环境变量构建：
+    /// - `INJECTOR_BACKEND`: `auto`（默认，代理优先、失败回退 shell）| `shell`（禁用代理）
+    /// - `INJECTOR_AGENT_PORT`: 手机端代理端口，默认 11452
+    pub fn from_env(adb_path: String) -> Self {
+        let mut injector = Self::new(adb_path);
+        injector.enabled = !std::env::var("INJECTOR_BACKEND").map(|v| v.eq_ignore_ascii_case("shell")).unwrap_or(false);
+        if let Some(port) = std::env::var("INJECTOR_AGENT_PORT").ok().and_then(|v| v.parse::<u16>().ok()) {
+            injector.remote_port = port;
+        }
+        injector
+    }
+
+    pub fn with_fixed_local_port(mut self, port: u16) -> Self {
+        self.fixed_local_port = Some(port);
+        self
+    }
+
+    pub fn with_timeouts(mut self, connect_ms: u64, io_ms: u64) -> Self {
+        self.connect_timeout = Duration::from_millis(connect_ms);
+        self.io_timeout = Duration::from_millis(io_ms);
+        self
+    }
+
+    /// 是否值得尝试代理：未被禁用，且不在连接失败后的退避期内
+    pub fn is_available(&self, serial: &str) -> bool {
+        if !self.enabled {
+            return false;
+        }
+        let mut until = UNAVAILABLE_UNTIL.get_or_init(Default::default).lock().unwrap_or_else(|e| e.into_inner());
+        match until.get(serial) {
+            Some(deadline) if Instant::now() < *deadline => false,
+            Some(_) => {
+                until.remove(serial);
+                true
+            }
+            None => true,
+        }
+    }
+
+    fn mark_unavailable(serial: &str) {
+        UNAVAILABLE_UNTIL
+            .get_or_init(Default::default)
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(serial.to_string(), Instant::now() + UNAVAILABLE_BACKOFF);
+    }
+
+    fn connection(serial: &str) -> SharedConnection {
+        CONNECTIONS
+            .get_or_init(Default::default)
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .entry(serial.to_string())
+            .or_default()
+            .clone()
+    }
+
+    /// `adb forward tcp:0 tcp:<remote>` 由 adb 分配本地端口，多设备互不冲突
+    async fn forward(&self, serial: &str) -> Result<u16> {
+        let output = tokio::process::Command::new(&self.adb_path)
+            .args(["-s", serial, "forward", "tcp:0", &format!("tcp:{}", self.remote_port)])
+            .output()
+            .await
+            .context("执行 adb forward 失败")?;
+        if !output.status.success() {
+            anyhow::bail!("adb forward 失败: {}", String::from_utf8_lossy(&output.stderr).trim());
+        }
+        let port = String::from_utf8_lossy(&output.stdout).trim().parse::<u16>().context("无法解析 adb forward 分配的端口")?;
+        info!("📡 注入代理端口转发: {} tcp:{} -> tcp:{}", serial, port, self.remote_port);
+        Ok(port)
+    }
+
+    async fn send_on(&self, conn: &mut AgentConnection, serial: &str, line: &str, extra: Duration) -> Result<(), SendError> {
+        if conn.stream.is_none() {
+            let port = match (self.fixed_local_port, conn.local_port) {
+                (Some(port), _) | (None, Some(port)) => port,
+                (None, None) => self.forward(serial).await.map_err(SendError::Transport)?,
+            };
+            conn.local_port = Some(port);
+            let stream = tokio::time::timeout(self.connect_timeout, TcpStream::connect(("127.0.0.1", port)))
+                .await
+                .map_err(|_| SendError::Transport(anyhow!("连接注入代理超时")))?
+                .map_err(|e| SendError::Transport(anyhow!("连接注入代理失败: {}", e)))?;
+            stream.set_nodelay(true).map_err(|e| SendError::Transport(e.into()))?;
+            conn.stream = Some(BufReader::new(stream));
+        }
+
+        let stream = conn.stream.as_mut().expect("stream connected above");
+        let exchange = async {
+            stream.get_mut().write_all(line.as_bytes()).await?;
+            let mut reply = String::new();
+            if stream.read_line(&mut reply).await? == 0 {
+                return Err(std::io::Error::new(std::io::ErrorKind::UnexpectedEof, "注入代理关闭了连接"));
+            }
+            Ok(reply)
+        };
+        let reply = tokio::time::timeout(self.io_timeout + extra, exchange)
+            .await
+            .map_err(|_| SendError::Transport(anyhow!("等待注入代理响应超时")))?
+            .map_err(|e| SendError::Transport(e.into()))?;
+        let reply: AgentReply =
+            serde_json::from_str(reply.trim()).map_err(|e| SendError::Transport(anyhow!("注入代理响应无效: {}", e)))?;
+        if reply.ok {
+            Ok(())
+        } else {
+            Err(SendError::Rejected(reply.error.unwrap_or_else(|| "未知错误".to_string())))
+        }
+    }
+
+    /// 发送一条指令；连接断开时重连一次，仍失败则进入退避期
+    async fn request(&self, serial: &str, command: AgentCommand<'_>, extra: Duration) -> Result<()> {
+        if !self.is_available(serial) {
+            anyhow::bail!("注入代理不可用");
+        }
+        let mut line = serde_json::to_string(&command)?;
+        line.push('\n');
+
+        let shared = Self::connection(serial);
+        let mut conn = shared.lock().await;
+        let mut last_error = None;
+        for attempt in 0..2 {
+            match self.send_on(&mut conn, serial, &line, extra).await {
+                Ok(()) => return Ok(()),
+                Err(SendError::Rejected(reason)) => anyhow::bail!("注入代理拒绝执行: {}", reason),
+                Err(SendError::Transport(e)) => {
+                    debug!("注入代理第 {} 次发送失败: {}", attempt + 1, e);
+                    conn.stream = None;
+                    // 转发可能随 adb 重启失效，重连时重新分配
+                    conn.local_port = None;
+                    last_error = Some(e);
+                }
+            }
+        }
+        Self::mark_unavailable(serial);
+        let e = last_error.unwrap_or_else(|| anyhow!("注入代理不可用"));
+        warn!("⚠️ 注入代理不可用，{}s 内改用 shell 注入: {}", UNAVAILABLE_BACKOFF.as_secs(), e);
+        Err(e)
+    }
+
+    /// 多指手势（shell `input` 无法实现）
+    pub async fn gesture(&self, serial: &str, gesture: &MultiTouchGesture) -> Result<()> {
+        gesture.validate()?;
+        let extra = Duration::from_millis(gesture.duration_ms() as u64);
+        self.request(serial, AgentCommand::Gesture { pointers: &gesture.pointers }, extra).await
+    }
+
+    /// 在屏幕上显示（或更新）一行悬浮状态文字
+    pub async fn show_overlay(&self, serial: &str, text: &str) -> Result<()> {
+        self.request(serial, AgentCommand::Overlay { text: Some(text) }, Duration::ZERO).await
+    }
+
+    pub async fn clear_overlay(&self, serial: &str) -> Result<()> {
+        self.request(serial, AgentCommand::Overlay { text: None }, Duration::ZERO).await
+    }
+}
+
+#[async_trait::async_trait]
+impl InputInjector for AgentInputInjector {
+    async fn tap(&self, serial: &str, x: u32, y: u32, duration_ms: Option<u32>) -> Result<()> {
+        let extra = Duration::from_millis(duration_ms.unwrap_or(0) as u64);
+        self.request(serial, AgentCommand::Tap { x, y, duration_ms }, extra).await
+    }
+
+    async fn swipe(&self, serial: &str, x1: u32, y1: u32, x2: u32, y2: u32, duration_ms: u32) -> Result<()> {
+        let extra = Duration::from_millis(duration_ms as u64);
+        self.request(serial, AgentCommand::Swipe { x1, y1, x2, y2, duration_ms }, extra).await
+    }
+
+    async fn keyevent(&self, serial: &str, code: i32) -> Result<()> {
+        self.request(serial, AgentCommand::Key { code: &code.to_string() }, Duration::ZERO).await
+    }
+
+    async fn keyevent_symbolic(&self, serial: &str, code: &str) -> Result<()> {
+        self.request(serial, AgentCommand::Key { code }, Duration::ZERO).await
+    }
+
+    async fn input_text(&self, serial: &str, text: &str) -> Result<()> {
+        self.request(serial, AgentCommand::Text { text }, Duration::ZERO).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::net::TcpListener;
+
+    #[tokio::test]
+    async fn test_agent_round_trip_reuses_connection_and_backs_off() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let server = tokio::spawn(async move {
+            let (socket, _) = listener.accept().await.unwrap();
+            let mut reader = BufReader::new(socket);
+            let mut ops = Vec::new();
+            let mut line = String::new();
+            while reader.read_line(&mut line).await.unwrap() > 0 {
+                let command: serde_json::Value = serde_json::from_str(line.trim()).unwrap();
+                let reply = if command["op"] == "key" { r#"{"ok":false,"error":"unsupported"}"# } else { r#"{"ok":true}"# };
+                reader.get_mut().write_all(format!("{}\n", reply).as_bytes()).await.unwrap();
+                ops.push(command);
+                line.clear();
+            }
+            ops
+        });
+
+        let serial = "agent-test-device";
+        let injector = AgentInputInjector::new("adb".into()).with_fixed_local_port(port).with_timeouts(500, 500);
+        injector.tap(serial, 10, 20, None).await.unwrap();
+        injector.swipe(serial, 1, 2, 3, 4, 50).await.unwrap();
+        injector.gesture(serial, &MultiTouchGesture::pinch(500, 800, 300, 100, 40)).await.unwrap();
+        // 代理拒绝不触发退避
+        assert!(injector.keyevent(serial, 4).await.is_err());
+        assert!(injector.is_available(serial));
+
+        CONNECTIONS.get().unwrap().lock().unwrap().remove(serial);
+        let ops = server.await.unwrap();
+        assert_eq!(ops.len(), 4, "所有指令应复用同一条连接");
+        assert_eq!(ops[0]["durationMs"], serde_json::Value::Null);
+        assert_eq!(ops[1]["durationMs"], 50);
+        assert_eq!(ops[2]["pointers"][0]["points"][1], serde_json::json!({ "x": 400, "y": 800, "tMs": 40 }));
+
+        // 代理下线后进入退避期
+        let offline = "agent-offline-device";
+        let closed = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let closed_port = closed.local_addr().unwrap().port();
+        drop(closed);
+        let injector = AgentInputInjector::new("adb".into()).with_fixed_local_port(closed_port).with_timeouts(200, 200);
+        assert!(injector.tap(offline, 1, 1, None).await.is_err());
+        assert!(!injector.is_available(offline));
+    }
+}
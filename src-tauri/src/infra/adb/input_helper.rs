@@ -1,13 +1,42 @@
 use anyhow::{Context, Result};
 use tracing::{info, warn};
 
+use super::agent_input_injector::{AgentInputInjector, MultiTouchGesture};
 use super::input_injector::{AdbShellInputInjector, InputInjector};
 use super::safe_input_injector::SafeInputInjector;
 use crate::infra::device::metrics_provider::RealDeviceMetricsProvider;
 use crate::application::device_metrics::DeviceMetricsProvider;
+use crate::services::execution::timeline::{self, Phase};
+
+/// 设备端注入代理可用时先走代理；返回 true 表示已执行，false 表示应回退 shell
+async fn try_agent<F, Fut>(adb_path: &str, serial: &str, op: &str, f: F) -> bool
+where
+    F: FnOnce(AgentInputInjector) -> Fut,
+    Fut: std::future::Future<Output = Result<()>>,
+{
+    let agent = AgentInputInjector::from_env(adb_path.to_string());
+    if !agent.is_available(serial) {
+        return false;
+    }
+    match f(agent).await {
+        Ok(()) => {
+            info!("🪄 injector-agent: {} 已通过设备端代理执行", op);
+            true
+        }
+        Err(e) => {
+            warn!("🪄 injector-agent: 代理 {} 失败，回退 shell 注入。错误: {}", op, e);
+            false
+        }
+    }
+}
 
 /// 注入器优先的点击；支持可选长按（通过 swipe 同点实现）
 pub async fn tap_injector_first(adb_path: &str, serial: &str, x: i32, y: i32, long_press_ms: Option<u32>) -> Result<()> {
+    let _timeline = timeline::phase(Phase::Action);
+    let (ax, ay) = (x.max(0) as u32, y.max(0) as u32);
+    if try_agent(adb_path, serial, "tap", |agent| async move { agent.tap(serial, ax, ay, long_press_ms).await }).await {
+        return Ok(());
+    }
     let injector = SafeInputInjector::from_env(AdbShellInputInjector::new(adb_path.to_string()));
     let x_u32 = x as u32;
     let y_u32 = y as u32;
@@ -44,6 +73,11 @@ pub async fn tap_injector_first(adb_path: &str, serial: &str, x: i32, y: i32, lo
 
 /// 注入器优先的滑动
 pub async fn swipe_injector_first(adb_path: &str, serial: &str, x1: i32, y1: i32, x2: i32, y2: i32, duration_ms: u32) -> Result<()> {
+    let _timeline = timeline::phase(Phase::Action);
+    let (ax1, ay1, ax2, ay2) = (x1.max(0) as u32, y1.max(0) as u32, x2.max(0) as u32, y2.max(0) as u32);
+    if try_agent(adb_path, serial, "swipe", |agent| async move { agent.swipe(serial, ax1, ay1, ax2, ay2, duration_ms).await }).await {
+        return Ok(());
+    }
     let injector = SafeInputInjector::from_env(AdbShellInputInjector::new(adb_path.to_string()));
     match injector.swipe(serial, x1 as u32, y1 as u32, x2 as u32, y2 as u32, duration_ms).await {
         Ok(()) => {
@@ -71,8 +105,29 @@ pub async fn swipe_injector_first(adb_path: &str, serial: &str, x1: i32, y1: i32
     }
 }
 
+/// 多指手势：只能由设备端代理执行；单指手势在代理不可用时退化为 shell swipe（取首尾点）
+pub async fn multitouch_injector_first(adb_path: &str, serial: &str, gesture: &MultiTouchGesture) -> Result<()> {
+    gesture.validate()?;
+    {
+        // 回退的 swipe 自带阶段打点，这里只覆盖代理尝试
+        let _timeline = timeline::phase(Phase::Action);
+        if try_agent(adb_path, serial, "gesture", |agent| async move { agent.gesture(serial, gesture).await }).await {
+            return Ok(());
+        }
+    }
+    match gesture.pointers.as_slice() {
+        [single] => {
+            let (first, last) = (single.points[0], single.points[single.points.len() - 1]);
+            let duration_ms = last.t_ms.saturating_sub(first.t_ms).max(1);
+            swipe_injector_first(adb_path, serial, first.x as i32, first.y as i32, last.x as i32, last.y as i32, duration_ms).await
+        }
+        _ => anyhow::bail!("多指手势需要手机端注入代理（端口 {}），shell input 不支持多点触控", super::agent_input_injector::DEFAULT_AGENT_PORT),
+    }
+}
+
 /// 注入器优先的文本输入（简单版：空格转 %s，IME 策略后续可扩展）
 pub async fn input_text_injector_first(adb_path: &str, serial: &str, text: &str) -> Result<()> {
+    let _timeline = timeline::phase(Phase::Action);
     let injector = SafeInputInjector::from_env(AdbShellInputInjector::new(adb_path.to_string()));
     match injector.input_text(serial, text).await {
         Ok(()) => {
@@ -1,4 +1,5 @@
 pub mod input_injector;
+pub mod agent_input_injector;
 pub mod safe_input_injector;
 pub mod keyevent_helper;
 pub mod input_helper;
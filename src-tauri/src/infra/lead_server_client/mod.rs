@@ -0,0 +1,517 @@
+// src-tauri/src/infra/lead_server_client/mod.rs
+// module: lead_server_client | layer: infra | role: lead-server 类型化客户端
+// summary: 封装 lead-server 全部接口（认证 / 设备配置 / 评论批量上传 / 统计）- 失败退避重试、令牌到期与 401 自动重新登录、离线队列补发与同步状态
+
+mod offline_queue;
+mod session;
+mod types;
+
+pub use offline_queue::{OfflineQueue, QueuedItem, QueuedRequest};
+pub use session::{SessionStore, StoredToken};
+pub use types::*;
+
+use chrono::{DateTime, Utc};
+use reqwest::{Method, StatusCode, Url};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+use tracing::{info, warn};
+
+/// 未配置 `LEAD_SERVER_URL` 时使用的服务器地址
+pub const DEFAULT_SERVER_URL: &str = "http://119.91.19.232:8080";
+const REQUEST_TIMEOUT_SECS: u64 = 15;
+/// 单次上传的评论条数上限
+const UPLOAD_CHUNK_SIZE: usize = 200;
+
+static CLIENT: OnceLock<LeadServerClient> = OnceLock::new();
+
+/// 当前配置的服务器地址
+pub fn server_url_from_env() -> String {
+    std::env::var("LEAD_SERVER_URL").unwrap_or_else(|_| DEFAULT_SERVER_URL.to_string())
+}
+
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum LeadServerError {
+    #[error("无法连接服务器: {0}")]
+    Network(String),
+    #[error("服务器错误: HTTP {0}")]
+    Server(u16),
+    #[error("未登录或登录已过期")]
+    Unauthorized,
+    #[error("{0}")]
+    Rejected(String),
+}
+
+impl LeadServerError {
+    /// 网络不通或服务端 5xx：可以重试，也应进入离线队列
+    pub fn is_retryable(&self) -> bool {
+        matches!(self, Self::Network(_) | Self::Server(_))
+    }
+}
+
+impl From<LeadServerError> for String {
+    fn from(e: LeadServerError) -> Self {
+        e.to_string()
+    }
+}
+
+/// 退避重试策略
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RetryPolicy {
+    pub max_attempts: u32,
+    pub initial_backoff_ms: u64,
+    pub max_backoff_ms: u64,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self { max_attempts: 3, initial_backoff_ms: 500, max_backoff_ms: 8000 }
+    }
+}
+
+impl RetryPolicy {
+    /// 第 `retry` 次重试前的等待时间（1 起，指数增长）
+    pub fn backoff_delay(&self, retry: u32) -> Duration {
+        let factor = 1u64 << retry.saturating_sub(1).min(20);
+        Duration::from_millis(self.initial_backoff_ms.saturating_mul(factor).min(self.max_backoff_ms))
+    }
+}
+
+/// 同步状态（供前端展示）
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncStatus {
+    pub server_url: String,
+    /// 最近一次请求是否连通；还没请求过为 None
+    pub online: Option<bool>,
+    pub logged_in: bool,
+    pub user: Option<UserInfo>,
+    pub token_expires_at: Option<DateTime<Utc>>,
+    pub last_success_at: Option<DateTime<Utc>>,
+    pub last_error: Option<String>,
+    pub pending_requests: usize,
+    pub pending_comments: usize,
+}
+
+/// 上传 / 推送结果：送达或已进入离线队列
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeliveryReport {
+    pub delivered: bool,
+    pub queued: bool,
+    pub inserted_count: usize,
+    pub message: Option<String>,
+}
+
+/// 离线队列补发结果
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FlushReport {
+    pub sent: usize,
+    /// 被服务端拒绝（非网络原因）而丢弃的请求
+    pub dropped: usize,
+    pub remaining: usize,
+    pub last_error: Option<String>,
+}
+
+#[derive(Default)]
+struct ConnectionState {
+    online: Option<bool>,
+    last_success_at: Option<DateTime<Utc>>,
+    last_error: Option<String>,
+}
+
+pub struct LeadServerClient {
+    base_url: String,
+    http: reqwest::Client,
+    retry: RetryPolicy,
+    session: Mutex<SessionStore>,
+    queue: Mutex<OfflineQueue>,
+    connection: Mutex<ConnectionState>,
+    /// 同一时间只允许一个补发过程，保证队列顺序
+    flush_lock: tokio::sync::Mutex<()>,
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+impl LeadServerClient {
+    pub fn global() -> &'static LeadServerClient {
+        CLIENT.get_or_init(|| {
+            Self::new(server_url_from_env(), SessionStore::open(SessionStore::default_path()), OfflineQueue::open(OfflineQueue::default_path()))
+        })
+    }
+
+    pub fn new(base_url: String, session: SessionStore, queue: OfflineQueue) -> Self {
+        let http = reqwest::Client::builder()
+            .timeout(Duration::from_secs(REQUEST_TIMEOUT_SECS))
+            .build()
+            .unwrap_or_default();
+        Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            http,
+            retry: RetryPolicy::default(),
+            session: Mutex::new(session),
+            queue: Mutex::new(queue),
+            connection: Mutex::new(ConnectionState::default()),
+            flush_lock: tokio::sync::Mutex::new(()),
+        }
+    }
+
+    pub fn with_retry(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    /// 当前可用的访问令牌（临近过期时先重新登录），供 WebSocket 等非 JSON 通道使用
+    pub async fn access_token(&self) -> Option<String> {
+        self.fresh_token().await
+    }
+
+    /// 按路径段拼接地址（设备 ID 等会被正确转义）
+    fn url(&self, segments: &[&str]) -> Result<Url, LeadServerError> {
+        let mut url = Url::parse(&self.base_url).map_err(|e| LeadServerError::Rejected(format!("服务器地址无效: {}", e)))?;
+        url.path_segments_mut()
+            .map_err(|_| LeadServerError::Rejected("服务器地址无效".to_string()))?
+            .pop_if_empty()
+            .extend(segments);
+        Ok(url)
+    }
+
+    fn record(&self, result: &Result<(), &LeadServerError>) {
+        let mut state = lock(&self.connection);
+        match result {
+            Ok(()) => {
+                state.online = Some(true);
+                state.last_success_at = Some(Utc::now());
+                state.last_error = None;
+            }
+            Err(e) => {
+                if matches!(e, LeadServerError::Network(_)) {
+                    state.online = Some(false);
+                }
+                state.last_error = Some(e.to_string());
+            }
+        }
+    }
+
+    async fn send_once<T: DeserializeOwned>(
+        &self,
+        method: Method,
+        url: Url,
+        body: Option<&serde_json::Value>,
+        token: Option<&str>,
+    ) -> Result<T, LeadServerError> {
+        let mut request = self.http.request(method, url);
+        if let Some(token) = token {
+            request = request.bearer_auth(token);
+        }
+        if let Some(body) = body {
+            request = request.json(body);
+        }
+        let response = request.send().await.map_err(|e| LeadServerError::Network(e.to_string()))?;
+        let status = response.status();
+        if status == StatusCode::UNAUTHORIZED {
+            return Err(LeadServerError::Unauthorized);
+        }
+        if status.is_server_error() {
+            return Err(LeadServerError::Server(status.as_u16()));
+        }
+        if !status.is_success() {
+            return Err(LeadServerError::Rejected(format!("请求被拒绝: HTTP {}", status.as_u16())));
+        }
+        response.json::<T>().await.map_err(|e| LeadServerError::Rejected(format!("服务器响应格式错误: {}", e)))
+    }
+
+    /// 发送请求：网络错误与 5xx 按策略退避重试
+    async fn execute<T: DeserializeOwned>(
+        &self,
+        method: Method,
+        segments: &[&str],
+        body: Option<&serde_json::Value>,
+        token: Option<&str>,
+    ) -> Result<T, LeadServerError> {
+        let url = self.url(segments)?;
+        let mut attempt = 1;
+        loop {
+            let result = self.send_once(method.clone(), url.clone(), body, token).await;
+            self.record(&result.as_ref().map(|_| ()));
+            match result {
+                Err(e) if e.is_retryable() && attempt < self.retry.max_attempts => {
+                    let delay = self.retry.backoff_delay(attempt);
+                    warn!("⚠️ lead-server 请求失败（第 {} 次）: {}，{:?} 后重试", attempt, e, delay);
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
+                }
+                other => return other,
+            }
+        }
+    }
+
+    /// 带令牌的请求：令牌临近过期先刷新，401 时用本次运行的凭据重新登录后再试一次
+    async fn authed<T: DeserializeOwned>(
+        &self,
+        method: Method,
+        segments: &[&str],
+        body: Option<&serde_json::Value>,
+    ) -> Result<T, LeadServerError> {
+        let token = self.fresh_token().await;
+        match self.execute(method.clone(), segments, body, token.as_deref()).await {
+            Err(LeadServerError::Unauthorized) if self.has_credentials() => {
+                self.relogin().await?;
+                let token = lock(&self.session).token().map(|t| t.token.clone());
+                self.execute(method, segments, body, token.as_deref()).await
+            }
+            other => other,
+        }
+    }
+
+    fn has_credentials(&self) -> bool {
+        lock(&self.session).credentials().is_some()
+    }
+
+    async fn fresh_token(&self) -> Option<String> {
+        let needs_refresh = lock(&self.session).token().is_some_and(|t| t.needs_refresh(Utc::now()));
+        if needs_refresh && self.has_credentials() {
+            if let Err(e) = self.relogin().await {
+                warn!("⚠️ lead-server 令牌刷新失败: {}", e);
+            }
+        }
+        lock(&self.session).token().map(|t| t.token.clone())
+    }
+
+    async fn relogin(&self) -> Result<(), LeadServerError> {
+        let credentials = lock(&self.session).credentials().cloned().ok_or(LeadServerError::Unauthorized)?;
+        self.login_with(credentials).await.map(|_| ())
+    }
+
+    async fn login_with(&self, credentials: LoginRequest) -> Result<UserInfo, LeadServerError> {
+        let body = serde_json::to_value(&credentials).map_err(|e| LeadServerError::Rejected(e.to_string()))?;
+        let response: AuthResponse = self.execute(Method::POST, &["api", "auth", "login"], Some(&body), None).await?;
+        let (Some(token), Some(user)) = (response.token.filter(|_| response.success), response.user) else {
+            return Err(LeadServerError::Rejected(response.message.unwrap_or_else(|| "登录失败".to_string())));
+        };
+        let mut session = lock(&self.session);
+        session
+            .set_token(StoredToken { token, user: Some(user.clone()), obtained_at: Utc::now() })
+            .map_err(LeadServerError::Rejected)?;
+        session.set_credentials(credentials);
+        Ok(user)
+    }
+
+    // ========== 认证 ==========
+
+    pub async fn login(&self, username: &str, password: &str) -> Result<UserInfo, LeadServerError> {
+        let user = self.login_with(LoginRequest { username: username.to_string(), password: password.to_string() }).await?;
+        info!("🔐 已登录 lead-server: {}", user.username);
+        Ok(user)
+    }
+
+    pub fn logout(&self) -> Result<(), LeadServerError> {
+        lock(&self.session).clear().map_err(LeadServerError::Rejected)
+    }
+
+    pub async fn current_user(&self) -> Result<UserInfo, LeadServerError> {
+        let response: AuthResponse = self.authed(Method::GET, &["api", "auth", "me"], None).await?;
+        response.user.filter(|_| response.success).ok_or(LeadServerError::Unauthorized)
+    }
+
+    // ========== 设备配置 ==========
+
+    pub async fn get_device_config(&self, device_id: &str) -> Result<Option<RemoteDeviceConfig>, LeadServerError> {
+        let response: DeviceConfigResponse = self.authed(Method::GET, &["api", "device", device_id, "config"], None).await?;
+        if !response.success {
+            return Err(LeadServerError::Rejected(response.message.unwrap_or_else(|| "获取设备配置失败".to_string())));
+        }
+        Ok(response.data)
+    }
+
+    async fn send_device_config(&self, device_id: &str, update: &DeviceConfigUpdate) -> Result<(), LeadServerError> {
+        let body = serde_json::to_value(update).map_err(|e| LeadServerError::Rejected(e.to_string()))?;
+        let response: DeviceConfigResponse =
+            self.authed(Method::PUT, &["api", "device", device_id, "config"], Some(&body)).await?;
+        if !response.success {
+            return Err(LeadServerError::Rejected(response.message.unwrap_or_else(|| "更新设备配置失败".to_string())));
+        }
+        Ok(())
+    }
+
+    /// 推送设备配置；服务器不可达时进入离线队列
+    pub async fn push_device_config(&self, device_id: &str, update: DeviceConfigUpdate) -> Result<DeliveryReport, LeadServerError> {
+        self.flush_before_send().await;
+        match self.send_device_config(device_id, &update).await {
+            Ok(()) => Ok(DeliveryReport { delivered: true, queued: false, inserted_count: 0, message: None }),
+            Err(e) if e.is_retryable() => {
+                let request = QueuedRequest::DeviceConfig { device_id: device_id.to_string(), update };
+                self.enqueue(request, &e)
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    // ========== 评论上传 ==========
+
+    async fn send_comments(&self, device_id: &str, comments: &[UploadComment]) -> Result<usize, LeadServerError> {
+        let body = serde_json::to_value(BatchUploadRequest { device_id, comments })
+            .map_err(|e| LeadServerError::Rejected(e.to_string()))?;
+        let response: BatchUploadResponse = self.authed(Method::POST, &["api", "comments", "batch"], Some(&body)).await?;
+        if !response.success {
+            return Err(LeadServerError::Rejected(response.message.unwrap_or_else(|| "评论上传失败".to_string())));
+        }
+        Ok(response.inserted_count)
+    }
+
+    /// 分批上传评论；服务器不可达时剩余批次进入离线队列
+    pub async fn upload_comments(&self, device_id: &str, comments: Vec<UploadComment>) -> Result<DeliveryReport, LeadServerError> {
+        self.flush_before_send().await;
+        let mut inserted_count = 0;
+        let mut chunks = comments.chunks(UPLOAD_CHUNK_SIZE);
+        while let Some(chunk) = chunks.next() {
+            match self.send_comments(device_id, chunk).await {
+                Ok(inserted) => inserted_count += inserted,
+                Err(e) if e.is_retryable() => {
+                    let rest: Vec<UploadComment> = std::iter::once(chunk).chain(chunks).flatten().cloned().collect();
+                    let request = QueuedRequest::CommentBatch { device_id: device_id.to_string(), comments: rest };
+                    let mut report = self.enqueue(request, &e)?;
+                    report.inserted_count = inserted_count;
+                    return Ok(report);
+                }
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(DeliveryReport { delivered: true, queued: false, inserted_count, message: None })
+    }
+
+    // ========== 统计 ==========
+
+    pub async fn stats(&self) -> Result<ServerStats, LeadServerError> {
+        self.authed(Method::GET, &["api", "stats"], None).await
+    }
+
+    /// GET /health（返回纯文本），只用于探测连通性
+    pub async fn ping(&self) -> bool {
+        let result = match self.url(&["health"]) {
+            Ok(url) => match self.http.get(url).send().await {
+                Ok(response) if response.status().is_success() => Ok(()),
+                Ok(response) => Err(LeadServerError::Server(response.status().as_u16())),
+                Err(e) => Err(LeadServerError::Network(e.to_string())),
+            },
+            Err(e) => Err(e),
+        };
+        self.record(&result.as_ref().map(|_| ()));
+        result.is_ok()
+    }
+
+    // ========== 离线队列 ==========
+
+    fn enqueue(&self, request: QueuedRequest, error: &LeadServerError) -> Result<DeliveryReport, LeadServerError> {
+        lock(&self.queue).push(request, &error.to_string()).map_err(LeadServerError::Rejected)?;
+        info!("📥 lead-server 不可达，已加入离线队列: {}", error);
+        Ok(DeliveryReport { delivered: false, queued: true, inserted_count: 0, message: Some(error.to_string()) })
+    }
+
+    /// 有积压时先补发，保证新数据排在旧数据之后
+    async fn flush_before_send(&self) {
+        let has_backlog = lock(&self.queue).front().is_some();
+        if has_backlog {
+            let report = self.flush_queue().await;
+            if report.remaining > 0 {
+                warn!("⚠️ 离线队列仍有 {} 条未补发", report.remaining);
+            }
+        }
+    }
+
+    /// 按入队顺序补发；遇到网络错误即停止，被服务端拒绝的请求丢弃
+    pub async fn flush_queue(&self) -> FlushReport {
+        let _guard = self.flush_lock.lock().await;
+        let mut report = FlushReport::default();
+        loop {
+            let Some(item) = lock(&self.queue).front().cloned() else {
+                break;
+            };
+            let result = match &item.request {
+                QueuedRequest::CommentBatch { device_id, comments } => self.send_comments(device_id, comments).await.map(|_| ()),
+                QueuedRequest::DeviceConfig { device_id, update } => self.send_device_config(device_id, update).await,
+            };
+            let mut queue = lock(&self.queue);
+            let saved = match result {
+                Ok(()) => {
+                    report.sent += 1;
+                    queue.remove(&item.id)
+                }
+                Err(e) if e.is_retryable() || e == LeadServerError::Unauthorized => {
+                    report.last_error = Some(e.to_string());
+                    if let Err(save_error) = queue.mark_failed(&item.id, &e.to_string()) {
+                        warn!("⚠️ 保存离线队列失败: {}", save_error);
+                    }
+                    break;
+                }
+                Err(e) => {
+                    warn!("⚠️ 离线请求被服务端拒绝，已丢弃: {} ({})", item.id, e);
+                    report.dropped += 1;
+                    report.last_error = Some(e.to_string());
+                    queue.remove(&item.id)
+                }
+            };
+            if let Err(e) = saved {
+                warn!("⚠️ 保存离线队列失败: {}", e);
+                break;
+            }
+        }
+        report.remaining = lock(&self.queue).items().len();
+        report
+    }
+
+    pub fn queued_items(&self) -> Vec<QueuedItem> {
+        lock(&self.queue).items().to_vec()
+    }
+
+    pub fn status(&self) -> SyncStatus {
+        let session = lock(&self.session);
+        let queue = lock(&self.queue);
+        let connection = lock(&self.connection);
+        SyncStatus {
+            server_url: self.base_url.clone(),
+            online: connection.online,
+            logged_in: session.token().is_some_and(|t| t.expires_at() > Utc::now()),
+            user: session.token().and_then(|t| t.user.clone()),
+            token_expires_at: session.token().map(StoredToken::expires_at),
+            last_success_at: connection.last_success_at,
+            last_error: connection.last_error.clone(),
+            pending_requests: queue.items().len(),
+            pending_comments: queue.pending_comments(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backoff_and_url_building() {
+        let retry = RetryPolicy::default();
+        let delays: Vec<u64> = (1..=6).map(|n| retry.backoff_delay(n).as_millis() as u64).collect();
+        assert_eq!(delays, vec![500, 1000, 2000, 4000, 8000, 8000]);
+        assert!(LeadServerError::Server(502).is_retryable());
+        assert!(!LeadServerError::Unauthorized.is_retryable());
+
+        let dir = tempfile::tempdir().unwrap();
+        let client = LeadServerClient::new(
+            "http://127.0.0.1:8080/".to_string(),
+            SessionStore::open(dir.path().join("session.json")),
+            OfflineQueue::open(dir.path().join("queue.json")),
+        );
+        let url = client.url(&["api", "device", "192.168.1.8:5555/x", "config"]).unwrap();
+        assert_eq!(url.as_str(), "http://127.0.0.1:8080/api/device/192.168.1.8:5555%2Fx/config");
+        let status = client.status();
+        assert_eq!((status.online, status.logged_in, status.pending_requests), (None, false, 0));
+    }
+}
@@ -0,0 +1,157 @@
+// src-tauri/src/infra/lead_server_client/offline_queue.rs
+// module: lead_server_client | layer: infra | role: 离线队列
+// summary: 服务器不可达时暂存评论批次与设备配置更新（落盘），恢复连接后按入队顺序补发
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+use super::types::{DeviceConfigUpdate, UploadComment};
+
+const QUEUE_FILE_NAME: &str = "lead_server_queue.json";
+/// 队列上限（批次数），超出时丢弃最旧的
+const MAX_QUEUED_REQUESTS: usize = 500;
+
+/// 待补发的请求
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum QueuedRequest {
+    #[serde(rename_all = "camelCase")]
+    CommentBatch { device_id: String, comments: Vec<UploadComment> },
+    #[serde(rename_all = "camelCase")]
+    DeviceConfig { device_id: String, update: DeviceConfigUpdate },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueuedItem {
+    pub id: String,
+    pub request: QueuedRequest,
+    pub enqueued_at: DateTime<Utc>,
+    pub attempts: u32,
+    pub last_error: Option<String>,
+}
+
+/// 落盘的离线队列（先进先出）
+pub struct OfflineQueue {
+    path: PathBuf,
+    items: Vec<QueuedItem>,
+}
+
+impl OfflineQueue {
+    pub fn default_path() -> PathBuf {
+        dirs::data_dir().unwrap_or_else(|| PathBuf::from(".")).join("employee-gui").join(QUEUE_FILE_NAME)
+    }
+
+    pub fn open(path: PathBuf) -> Self {
+        let items = std::fs::read_to_string(&path).ok().and_then(|content| serde_json::from_str(&content).ok()).unwrap_or_default();
+        Self { path, items }
+    }
+
+    pub fn items(&self) -> &[QueuedItem] {
+        &self.items
+    }
+
+    pub fn front(&self) -> Option<&QueuedItem> {
+        self.items.first()
+    }
+
+    pub fn pending_comments(&self) -> usize {
+        self.items
+            .iter()
+            .map(|item| match &item.request {
+                QueuedRequest::CommentBatch { comments, .. } => comments.len(),
+                QueuedRequest::DeviceConfig { .. } => 0,
+            })
+            .sum()
+    }
+
+    /// 入队；同一设备的配置更新只保留最新一条
+    pub fn push(&mut self, request: QueuedRequest, error: &str) -> Result<(), String> {
+        if let QueuedRequest::DeviceConfig { device_id, .. } = &request {
+            self.items.retain(|item| !matches!(&item.request, QueuedRequest::DeviceConfig { device_id: d, .. } if d == device_id));
+        }
+        self.items.push(QueuedItem {
+            id: uuid::Uuid::new_v4().to_string(),
+            request,
+            enqueued_at: Utc::now(),
+            attempts: 0,
+            last_error: Some(error.to_string()),
+        });
+        let overflow = self.items.len().saturating_sub(MAX_QUEUED_REQUESTS);
+        self.items.drain(..overflow);
+        self.save()
+    }
+
+    /// 补发成功后移除
+    pub fn remove(&mut self, id: &str) -> Result<(), String> {
+        self.items.retain(|item| item.id != id);
+        self.save()
+    }
+
+    /// 补发失败：记录次数与原因，保持原位置
+    pub fn mark_failed(&mut self, id: &str, error: &str) -> Result<(), String> {
+        if let Some(item) = self.items.iter_mut().find(|item| item.id == id) {
+            item.attempts += 1;
+            item.last_error = Some(error.to_string());
+        }
+        self.save()
+    }
+
+    fn save(&self) -> Result<(), String> {
+        if let Some(dir) = self.path.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+        }
+        let content = serde_json::to_string(&self.items).map_err(|e| e.to_string())?;
+        let tmp = self.path.with_extension("json.tmp");
+        std::fs::write(&tmp, content).map_err(|e| format!("保存离线队列失败: {}", e))?;
+        std::fs::rename(&tmp, &self.path).map_err(|e| format!("保存离线队列失败: {}", e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn comment(id: &str) -> UploadComment {
+        UploadComment {
+            id: id.into(),
+            platform: "xiaohongshu".into(),
+            video_url: None,
+            author: "a".into(),
+            content: "想了解一下".into(),
+            ts: None,
+        }
+    }
+
+    #[test]
+    fn queues_in_order_and_survives_restart() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(QUEUE_FILE_NAME);
+        let mut queue = OfflineQueue::open(path.clone());
+        let batch = |ids: &[&str]| QueuedRequest::CommentBatch {
+            device_id: "emulator-5554".into(),
+            comments: ids.iter().map(|id| comment(id)).collect(),
+        };
+        let config = |name: &str| QueuedRequest::DeviceConfig {
+            device_id: "emulator-5554".into(),
+            update: DeviceConfigUpdate { device_name: Some(name.into()), ..Default::default() },
+        };
+        queue.push(batch(&["c1", "c2"]), "连接超时").unwrap();
+        queue.push(config("旧名"), "连接超时").unwrap();
+        queue.push(batch(&["c3"]), "连接超时").unwrap();
+        queue.push(config("新名"), "连接超时").unwrap();
+        assert_eq!(queue.items().len(), 3);
+        assert_eq!(queue.pending_comments(), 3);
+
+        let first = queue.front().unwrap().id.clone();
+        queue.mark_failed(&first, "HTTP 502").unwrap();
+        let mut reopened = OfflineQueue::open(path.clone());
+        assert_eq!(reopened.items(), queue.items());
+        assert_eq!((reopened.front().unwrap().attempts, reopened.front().unwrap().last_error.as_deref()), (1, Some("HTTP 502")));
+        assert!(matches!(&reopened.items()[2].request, QueuedRequest::DeviceConfig { update, .. } if update.device_name.as_deref() == Some("新名")));
+
+        reopened.remove(&first).unwrap();
+        assert_eq!(OfflineQueue::open(path).pending_comments(), 1);
+    }
+}
@@ -0,0 +1,128 @@
+// src-tauri/src/infra/lead_server_client/session.rs
+// module: lead_server_client | layer: infra | role: 登录会话
+// summary: 保存 lead-server 令牌（落盘）与本次运行的登录凭据（仅内存），按令牌签发时间判断是否需要刷新
+
+use base64::{engine::general_purpose, Engine as _};
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+use super::types::{LoginRequest, UserInfo};
+
+const SESSION_FILE_NAME: &str = "lead_server_session.json";
+/// 服务端令牌 30 天过期，提前 5 天刷新
+const TOKEN_LIFETIME_DAYS: i64 = 30;
+const TOKEN_REFRESH_MARGIN_DAYS: i64 = 5;
+
+/// 落盘的令牌
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StoredToken {
+    pub token: String,
+    pub user: Option<UserInfo>,
+    pub obtained_at: DateTime<Utc>,
+}
+
+impl StoredToken {
+    /// 令牌签发时间：`base64(user_id:timestamp):signature`，解析失败时按获取时间计
+    pub fn issued_at(&self) -> DateTime<Utc> {
+        let issued = self
+            .token
+            .split_once(':')
+            .and_then(|(payload, _)| general_purpose::STANDARD.decode(payload).ok())
+            .and_then(|bytes| String::from_utf8(bytes).ok())
+            .and_then(|payload| payload.split_once(':').and_then(|(_, ts)| ts.parse::<i64>().ok()))
+            .and_then(|ts| Utc.timestamp_opt(ts, 0).single());
+        issued.unwrap_or(self.obtained_at)
+    }
+
+    pub fn expires_at(&self) -> DateTime<Utc> {
+        self.issued_at() + Duration::days(TOKEN_LIFETIME_DAYS)
+    }
+
+    pub fn needs_refresh(&self, now: DateTime<Utc>) -> bool {
+        now >= self.expires_at() - Duration::days(TOKEN_REFRESH_MARGIN_DAYS)
+    }
+}
+
+/// 令牌与凭据
+pub struct SessionStore {
+    path: PathBuf,
+    token: Option<StoredToken>,
+    /// 只保存在内存中，用于令牌过期或 401 时自动重新登录
+    credentials: Option<LoginRequest>,
+}
+
+impl SessionStore {
+    pub fn default_path() -> PathBuf {
+        dirs::data_dir().unwrap_or_else(|| PathBuf::from(".")).join("employee-gui").join(SESSION_FILE_NAME)
+    }
+
+    pub fn open(path: PathBuf) -> Self {
+        let token = std::fs::read_to_string(&path).ok().and_then(|content| serde_json::from_str(&content).ok());
+        Self { path, token, credentials: None }
+    }
+
+    pub fn token(&self) -> Option<&StoredToken> {
+        self.token.as_ref()
+    }
+
+    pub fn credentials(&self) -> Option<&LoginRequest> {
+        self.credentials.as_ref()
+    }
+
+    pub fn set_credentials(&mut self, credentials: LoginRequest) {
+        self.credentials = Some(credentials);
+    }
+
+    pub fn set_token(&mut self, token: StoredToken) -> Result<(), String> {
+        if let Some(dir) = self.path.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+        }
+        let content = serde_json::to_string_pretty(&token).map_err(|e| e.to_string())?;
+        std::fs::write(&self.path, content).map_err(|e| format!("保存登录令牌失败: {}", e))?;
+        self.token = Some(token);
+        Ok(())
+    }
+
+    /// 退出登录：清除令牌与凭据
+    pub fn clear(&mut self) -> Result<(), String> {
+        self.token = None;
+        self.credentials = None;
+        match std::fs::remove_file(&self.path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(format!("删除登录令牌失败: {}", e)),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn token_age_and_persistence() {
+        let issued = Utc.with_ymd_and_hms(2026, 1, 1, 0, 0, 0).unwrap();
+        let payload = general_purpose::STANDARD.encode(format!("7:{}", issued.timestamp()));
+        let token = StoredToken { token: format!("{}:0123456789abcdef", payload), user: None, obtained_at: Utc::now() };
+        assert_eq!(token.issued_at(), issued);
+        assert!(!token.needs_refresh(issued + Duration::days(24)));
+        assert!(token.needs_refresh(issued + Duration::days(25)));
+
+        let opaque = StoredToken { token: "not-a-token".into(), user: None, obtained_at: issued };
+        assert_eq!(opaque.expires_at(), issued + Duration::days(30));
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(SESSION_FILE_NAME);
+        let mut store = SessionStore::open(path.clone());
+        store.set_credentials(LoginRequest { username: "ops".into(), password: "secret1".into() });
+        store.set_token(token.clone()).unwrap();
+        assert!(!std::fs::read_to_string(&path).unwrap().contains("secret1"));
+
+        let mut reopened = SessionStore::open(path.clone());
+        assert_eq!(reopened.token(), Some(&token));
+        assert!(reopened.credentials().is_none());
+        reopened.clear().unwrap();
+        assert!(SessionStore::open(path).token().is_none());
+    }
+}
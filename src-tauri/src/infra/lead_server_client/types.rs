@@ -0,0 +1,117 @@
+// src-tauri/src/infra/lead_server_client/types.rs
+// module: lead_server_client | layer: infra | role: 接口数据结构
+// summary: 与 lead-server（server/src/api）一一对应的请求 / 响应结构，字段统一 camelCase
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// POST /api/auth/login 请求
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LoginRequest {
+    pub username: String,
+    pub password: String,
+}
+
+/// 登录 / 当前用户响应
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuthResponse {
+    pub success: bool,
+    pub message: Option<String>,
+    pub token: Option<String>,
+    pub user: Option<UserInfo>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UserInfo {
+    pub id: i32,
+    pub username: String,
+    pub nickname: Option<String>,
+    pub role: Option<String>,
+}
+
+/// 服务端保存的设备配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoteDeviceConfig {
+    pub device_id: String,
+    pub device_type: String,
+    pub device_name: Option<String>,
+    pub ai_api_key: Option<String>,
+    pub ai_provider: Option<String>,
+    pub config_json: serde_json::Value,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// PUT /api/device/:device_id/config 请求（增量合并，未填字段保持不变）
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeviceConfigUpdate {
+    pub device_type: Option<String>,
+    pub device_name: Option<String>,
+    pub ai_api_key: Option<String>,
+    pub ai_provider: Option<String>,
+    pub config_json: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeviceConfigResponse {
+    pub success: bool,
+    pub data: Option<RemoteDeviceConfig>,
+    pub message: Option<String>,
+}
+
+/// 上传的评论（服务端按 id 去重）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UploadComment {
+    pub id: String,
+    pub platform: String,
+    pub video_url: Option<String>,
+    pub author: String,
+    pub content: String,
+    pub ts: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchUploadRequest<'a> {
+    pub device_id: &'a str,
+    pub comments: &'a [UploadComment],
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchUploadResponse {
+    pub success: bool,
+    pub inserted_count: usize,
+    pub message: Option<String>,
+}
+
+/// GET /api/stats 响应
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ServerStats {
+    pub success: bool,
+    pub total_comments: i64,
+    pub by_platform: Vec<PlatformCount>,
+    pub by_device: Vec<DeviceCount>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlatformCount {
+    pub platform: String,
+    pub count: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeviceCount {
+    pub device_id: String,
+    pub count: i64,
+}
@@ -1,2 +1,3 @@
 pub mod adb;
 pub mod device;
+pub mod lead_server_client;
@@ -0,0 +1,153 @@
+// src-tauri/src/infrastructure/event_bindings.rs
+// module: infrastructure | layer: infrastructure | role: event-bindings
+// summary: 前端事件名 → Rust 负载类型的登记表；由 ts-rs 生成 TypeScript 定义（gen_ts_bindings）并支持运行时按事件名查询
+
+use serde::Serialize;
+use std::fs;
+use std::path::Path;
+use ts_rs::TS;
+
+use crate::automation::events::{ANALYSIS_COMPLETE_EVENT, ANALYSIS_PROGRESS_EVENT};
+use crate::automation::types::{ExecEventV3, Phase, Point, ResultPayload, StepScore, Summary, ValidationResult};
+use crate::core::application::agent_runtime_service::AgentEvent;
+use crate::core::domain::agent_runtime::AgentRunState;
+use crate::modules::agent_runtime::agent_runtime_events::*;
+use crate::modules::contacts::{
+    FolderImportProgress, DEDUP_PROGRESS_EVENT, FOLDER_IMPORT_PROGRESS_EVENT, VERIFY_PROGRESS_EVENT,
+};
+use crate::services::contact_storage::repositories::contact_numbers::global_dedup::DedupProgress;
+use crate::services::contact_verification::VerificationProgress;
+
+/// 事件映射索引文件名（与各类型的 `<Type>.ts` 同目录）
+pub const EVENTS_INDEX_FILE: &str = "events.ts";
+
+/// 单个事件的登记项
+pub struct EventBinding {
+    pub event_name: &'static str,
+    /// 负载类型在 TypeScript 中的名称
+    pub payload_type: fn() -> String,
+    /// 负载类型及其引用类型的声明
+    declarations: fn() -> Vec<String>,
+    /// 把负载类型及其依赖写成 `<Type>.ts`
+    export: fn(&Path) -> Result<(), ts_rs::ExportError>,
+}
+
+/// `get_event_schema` 的返回值
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EventSchema {
+    pub event_name: String,
+    pub payload_type: String,
+    /// 可直接粘贴到前端的 TypeScript 声明（负载类型在前，引用类型在后）
+    pub typescript: String,
+}
+
+macro_rules! event_bindings {
+    ($($event:expr => $ty:ty $([$($dep:ty),* $(,)?])?;)*) => {
+        vec![$(EventBinding {
+            event_name: $event,
+            payload_type: || <$ty as TS>::name(),
+            declarations: || vec![<$ty as TS>::decl() $($(, <$dep as TS>::decl())*)?],
+            export: |dir| <$ty as TS>::export_all_to(dir),
+        }),*]
+    };
+}
+
+/// 已登记的事件；新增前端事件时在这里补一行，负载类型加 `#[derive(TS)]`
+pub fn event_bindings() -> Vec<EventBinding> {
+    event_bindings! {
+        EVENT_STATE_CHANGED => AgentEvent [AgentRunState];
+        EVENT_PROGRESS => AgentEvent [AgentRunState];
+        EVENT_ACTION => AgentEvent [AgentRunState];
+        EVENT_THINKING => AgentEvent [AgentRunState];
+        EVENT_THINKING_DELTA => AgentEvent [AgentRunState];
+        EVENT_SUGGESTION => AgentEvent [AgentRunState];
+        EVENT_FILE_WRITTEN => AgentEvent [AgentRunState];
+        EVENT_ERROR => AgentEvent [AgentRunState];
+        EVENT_COMPLETED => AgentEvent [AgentRunState];
+        ANALYSIS_PROGRESS_EVENT => ExecEventV3 [Phase, Summary, StepScore, ResultPayload, Point, ValidationResult];
+        ANALYSIS_COMPLETE_EVENT => ExecEventV3 [Phase, Summary, StepScore, ResultPayload, Point, ValidationResult];
+        FOLDER_IMPORT_PROGRESS_EVENT => FolderImportProgress;
+        DEDUP_PROGRESS_EVENT => DedupProgress;
+        VERIFY_PROGRESS_EVENT => VerificationProgress;
+    }
+}
+
+/// 按事件名查询负载的 TypeScript 定义
+pub fn get_event_schema(event_name: &str) -> Result<EventSchema, String> {
+    let bindings = event_bindings();
+    let binding = bindings.iter().find(|b| b.event_name == event_name).ok_or_else(|| {
+        let known: Vec<&str> = bindings.iter().map(|b| b.event_name).collect();
+        format!("未登记的事件: {}（已登记: {}）", event_name, known.join(", "))
+    })?;
+    let typescript = (binding.declarations)()
+        .into_iter()
+        .map(|decl| format!("export {}", decl))
+        .collect::<Vec<_>>()
+        .join("\n\n");
+    Ok(EventSchema { event_name: event_name.to_string(), payload_type: (binding.payload_type)(), typescript })
+}
+
+/// 生成 TypeScript 绑定：每个负载类型（含依赖）一个 `<Type>.ts`，再写 `events.ts` 事件名 → 负载类型映射
+pub fn export_bindings(out_dir: &Path) -> Result<Vec<String>, String> {
+    fs::create_dir_all(out_dir).map_err(|e| format!("创建输出目录失败 {}: {}", out_dir.display(), e))?;
+    let bindings = event_bindings();
+
+    let mut types: Vec<String> = Vec::new();
+    for binding in &bindings {
+        let name = (binding.payload_type)();
+        if !types.contains(&name) {
+            (binding.export)(out_dir).map_err(|e| format!("导出 {} 失败: {}", name, e))?;
+            types.push(name);
+        }
+    }
+
+    fs::write(out_dir.join(EVENTS_INDEX_FILE), render_events_index(&bindings))
+        .map_err(|e| format!("写入 {} 失败: {}", EVENTS_INDEX_FILE, e))?;
+    Ok(types)
+}
+
+fn render_events_index(bindings: &[EventBinding]) -> String {
+    let mut types: Vec<String> = bindings.iter().map(|b| (b.payload_type)()).collect();
+    types.sort();
+    types.dedup();
+
+    let mut out = String::from("// 由 src-tauri/src/bin/gen_ts_bindings.rs 生成，请勿手动修改\n\n");
+    for ty in &types {
+        out.push_str(&format!("import type {{ {ty} }} from \"./{ty}\";\n"));
+    }
+    out.push_str("\n/** 事件名 → 负载类型 */\nexport interface EventPayloadMap {\n");
+    for binding in bindings {
+        out.push_str(&format!("  \"{}\": {};\n", binding.event_name, (binding.payload_type)()));
+    }
+    out.push_str("}\n\nexport type EventName = keyof EventPayloadMap;\n");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn registry_is_unique_and_queryable() {
+        let bindings = event_bindings();
+        let mut names: Vec<&str> = bindings.iter().map(|b| b.event_name).collect();
+        names.sort();
+        names.dedup();
+        assert_eq!(names.len(), bindings.len());
+
+        let schema = get_event_schema("contact-dedup-progress").unwrap();
+        assert_eq!(schema.payload_type, "DedupProgress");
+        assert!(schema.typescript.contains("export type DedupProgress"));
+        assert!(schema.typescript.contains("groups_found"));
+
+        let agent = get_event_schema("agent_runtime:state_changed").unwrap();
+        assert!(agent.typescript.contains("stateChanged"));
+        assert!(agent.typescript.contains("export type AgentRunState"));
+        assert!(get_event_schema("nope").unwrap_err().contains("agent_runtime:error"));
+
+        let index = render_events_index(&bindings);
+        assert!(index.contains("import type { ExecEventV3 } from \"./ExecEventV3\";"));
+        assert!(index.contains("  \"analysis:complete\": ExecEventV3;\n"));
+    }
+}
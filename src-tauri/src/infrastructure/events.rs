@@ -24,10 +24,23 @@ pub fn emit_and_trace<T: Serialize>(
     app: &AppHandle,
     event_name: &'static str,
     payload: &T,
+) -> Result<(), String> {
+    emit_and_trace_for_device(app, None, event_name, payload)
+}
+
+/// 同 [`emit_and_trace`]，带设备 ID 时不发往其他设备的独立窗口
+pub fn emit_and_trace_for_device<T: Serialize>(
+    app: &AppHandle,
+    device_id: Option<&str>,
+    event_name: &'static str,
+    payload: &T,
 ) -> Result<(), String> {
     // 1) 发射事件到前端
-    app.emit(event_name, payload)
-        .map_err(|e| format!("Failed to emit event '{}': {}", event_name, e))?;
+    match device_id {
+        Some(device_id) => crate::services::device_windows::emit_for_device(app, device_id, event_name, payload),
+        None => app.emit(event_name, payload),
+    }
+    .map_err(|e| format!("Failed to emit event '{}': {}", event_name, e))?;
 
     // 2) 开发模式: 落盘JSONL
     #[cfg(debug_assertions)]
@@ -3,4 +3,5 @@
 // summary: 基础设施模块导出
 
 pub mod events;
+pub mod event_bindings;
 pub mod database;
@@ -32,13 +32,13 @@ mod modules; // ✅ 新增模块化插件系统
 
 // ==================== 📦 核心依赖导入 ====================
 use std::sync::Mutex;
+use tauri::Manager;
 use tauri_plugin_dialog;
-use tracing::info;
+use tracing::{info, warn};
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, fmt::format::FmtSpan};
 
 // ==================== 🔧 服务层导入 ====================
 use services::adb::{AdbService, initialize_adb_system};
-use services::employee_service::EmployeeService;
 use services::log_bridge::LOG_COLLECTOR;
 use services::scrcpy_manager::cleanup_all;
 use services::contact_storage::commands::{
@@ -86,7 +86,6 @@ use services::script_manager::ScriptManagerState;
 // };
 // use services::adb::commands::{safe_adb_push, safe_adb_shell_command};
 use utils::device_utils::validate_device_connection;
-use services::smart_app_manager::SmartAppManagerState;
 // use services::smart_element_finder_service::{click_detected_element, smart_element_finder}; // 已废弃
  // 兼容层
 use services::vcf::smart_vcf_opener;
@@ -169,48 +168,103 @@ fn main() {
         // 不阻断启动，但记录错误
     }
 
-    // 注意: MCP 服务器在 Tauri setup hook 中启动，确保 Tokio runtime 已就绪
+    // 初始化插件注册表（校验依赖图并开始统计启动耗时）
+    let plugin_order = core::plugin_bootstrap::registry().report().init_order;
+    info!("🧩 插件初始化顺序: {}", plugin_order.join(" → "));
+    // 命令 panic 归属到所属插件并标记降级（异步命令的 panic 只能在钩子里感知）
+    core::plugin_isolation::install_panic_hook();
+
+    // 🧱 组合根：一次性构建所有共享服务
+    let app_services = core::app_services::AppServices::build();
 
-    tauri::Builder::default()
-        .plugin(tauri_plugin_dialog::init())
-        .plugin(modules::smart_selection::init()) // ✅ 注册智能选择插件
-        .plugin(modules::universal_ui::init())    // ✅ 注册Universal UI分析插件
-        .plugin(modules::adb::init())             // ✅ 注册ADB插件
-        .plugin(modules::employees::init())       // ✅ 注册员工管理插件
-        .plugin(modules::contacts::init())        // ✅ 注册联系人插件
-        .plugin(modules::image_optimization::init()) // ✅ 注册图片优化插件
-        .plugin(modules::system_diagnostic::init())  // ✅ 注册系统诊断插件
-        .plugin(modules::file_manager::init())       // ✅ 注册文件管理插件
-        .plugin(modules::xml_cache::init())          // ✅ 注册XML缓存插件
-        .plugin(modules::intelligent_analysis::init()) // ✅ 注册智能分析插件
-        .plugin(modules::execution_v3::init())       // ✅ 注册V3执行引擎插件
-        .plugin(modules::version_control::init())    // ✅ 注册版本控制插件
-        .plugin(modules::automation::init())         // ✅ 注册自动化插件
-        .plugin(modules::enhanced_location::init())  // ✅ 注册增强定位插件
-        .plugin(modules::lead_hunt::init())          // ✅ 注册精准获客插件
-        .plugin(modules::script_manager::init())     // ✅ 注册脚本管理插件
-        .plugin(modules::prospecting::init())        // ✅ 注册潜客挖掘插件
-        .plugin(modules::ui_dump::init())            // ✅ 注册 UI Dump 多模式插件
-        .plugin(modules::agent::init())              // ✅ 注册 AI Agent 插件
-        .plugin(modules::agent_runtime::init())      // ✅ 注册 Agent 自主运行时插件
-        .plugin(modules::cloud_sync::init())         // ✅ 注册云同步插件
-        .manage(Mutex::new(AdbService::new()))
-        .manage(Mutex::new(EmployeeService::new()))
-        .manage(SmartAppManagerState::new())
+    // 注意: MCP 服务器在 Tauri setup hook 中启动，确保 Tokio runtime 已就绪
 
+    // 插件按依赖拓扑顺序注册（Tauri 按注册顺序执行各插件 setup）
+    let plugins = vec![
+        ("dialog", tauri_plugin_dialog::init()),
+        ("smart_selection", modules::smart_selection::init()), // ✅ 注册智能选择插件
+        ("universal_ui", modules::universal_ui::init()), // ✅ 注册Universal UI分析插件
+        ("adb", modules::adb::init()), // ✅ 注册ADB插件
+        ("employees", modules::employees::init()), // ✅ 注册员工管理插件
+        ("contacts", modules::contacts::init()), // ✅ 注册联系人插件
+        ("image_optimization", modules::image_optimization::init()), // ✅ 注册图片优化插件
+        ("system_diagnostic", modules::system_diagnostic::init()), // ✅ 注册系统诊断插件
+        ("file_manager", modules::file_manager::init()), // ✅ 注册文件管理插件
+        ("xml_cache", modules::xml_cache::init()), // ✅ 注册XML缓存插件
+        ("intelligent_analysis", modules::intelligent_analysis::init()), // ✅ 注册智能分析插件
+        ("execution_v3", modules::execution_v3::init()), // ✅ 注册V3执行引擎插件
+        ("version_control", modules::version_control::init()), // ✅ 注册版本控制插件
+        ("automation", modules::automation::init()), // ✅ 注册自动化插件
+        ("enhanced_location", modules::enhanced_location::init()), // ✅ 注册增强定位插件
+        ("lead_hunt", modules::lead_hunt::init()), // ✅ 注册精准获客插件
+        ("script_manager", modules::script_manager::init()), // ✅ 注册脚本管理插件
+        ("ai", modules::ai::init()), // ✅ 注册 AI 对话 / 向量化插件
+        ("prospecting", modules::prospecting::init()), // ✅ 注册潜客挖掘插件
+        ("ui_dump", modules::ui_dump::init()), // ✅ 注册 UI Dump 多模式插件
+        ("agent", modules::agent::init()), // ✅ 注册 AI Agent 插件
+        ("agent_runtime", modules::agent_runtime::init()), // ✅ 注册 Agent 自主运行时插件
+        ("cloud_sync", modules::cloud_sync::init()), // ✅ 注册云同步插件
+        ("scheduler", modules::scheduler::init()), // ✅ 注册脚本定时执行插件
+    ];
+    let mut builder = tauri::Builder::default();
+    for plugin in core::plugin_bootstrap::registry().in_init_order(plugins) {
+        builder = builder.plugin(plugin);
+    }
 
-        .manage(commands::enhanced_location_commands::XPathGeneratorState::new(
-            services::execution::matching::SmartXPathGenerator::new(),
-        ))
+    builder
+        .manage(app_services.clone()) // 🧱 组合根：共享服务容器（ADB / 员工 / 应用管理 / XPath 生成器 / 核心上下文）
         // .manage(commands::smart_selection::SmartSelectionState::new()) // Removed as part of refactoring
         
         // ✅ 在 Tauri runtime 就绪后启动 MCP 服务器，并将 AppContext 传递给 Agent 插件
-        .setup(|app| {
+        .setup(move |app| {
+            // 所有插件 setup 已执行完毕，汇总启动耗时
+            core::plugin_bootstrap::registry().finish_boot();
+
+            // 匿名使用统计定期上报（未开启时不会发送任何数据）
+            tauri::async_runtime::spawn(services::telemetry::run_uploader());
+
+            // 仪表盘趋势汇总：从号码池回填后订阅事件总线增量更新
+            let contacts_conn = services::contact_storage::repositories::common::database::get_connection(app.handle())
+                .map_err(|e| warn!("⚠️ 趋势汇总无法打开号码池: {}", e))
+                .ok();
+            tauri::async_runtime::spawn(services::trend_rollup::run(contacts_conn));
+            tauri::async_runtime::spawn(services::trend_rollup::anomaly::run_detector(app.handle().clone()));
+
+            // 设备配置同步：设备上线时从 lead-server 拉取配置并按优先级规则生效
+            tauri::async_runtime::spawn(services::device_config_sync::run());
+
+            // 实时看板通道：开启后把任务进度 / 动作计数 / 设备状态推送给 lead-server
+            tauri::async_runtime::spawn(services::realtime_uplink::run());
+
+            // 自动化配方：执行队列（逐阶段检查点）+ 按周定时
+            modules::contacts::start_recipe_runner(app.handle());
+
+            // 联系人来源文件夹监听：新文件去抖后自动导入（可暂停 / 恢复）
+            modules::contacts::start_contact_watcher(app.handle());
+
+            // 模拟器自检：按配置每晚定时执行（默认关闭）
+            tauri::async_runtime::spawn(services::self_test::run_scheduler(app.handle().clone()));
+
+            // 模拟器怪癖：设备上线后检测空树 / 分辨率 / 输入法问题并自动修复
+            tauri::async_runtime::spawn(services::self_test::quirks::run_on_attach());
+
+            // 目标应用冒烟测试：按各定义的 cron 定时执行（默认无计划）
+            tauri::async_runtime::spawn(services::smoke_test::run_scheduler(app.handle().clone()));
+
+            // 短期制品（截图 / 索引缓存）：启动时与定时清理过期文件
+            tauri::async_runtime::spawn(services::artifact_store::run_purger());
+
+            // 匹配评分权重 / 安全阈值：启动时加载配置文件（无效时使用内置默认值），之后可热重载
+            let matching = commands::run_step_v2::matching_config::current();
+            info!("🎛️ 匹配配置生效版本: v{}", matching.version);
+
             let app_handle = app.handle().clone();
+            let services = app_services.clone();
             // 在 Tauri 的异步 runtime 中启动 MCP 服务器
             tauri::async_runtime::spawn(async move {
                 info!("🔌 正在启动 MCP 服务器...");
                 if let Some(ctx) = core::start_mcp_server_with_context().await {
+                    services.set_core_context(ctx.clone()).await;
                     // 将 AppContext 设置到 Agent 插件
                     modules::agent::set_app_context(&app_handle, ctx).await;
                 }
@@ -218,11 +272,13 @@ fn main() {
             Ok(())
         })
 
-        // 应用关闭清理外部进程（scrcpy 等）
-        .on_window_event(|_window, event| {
-            if let tauri::WindowEvent::CloseRequested { .. } = event {
-                cleanup_all();
+        // 主窗口关闭时清理外部进程（scrcpy 等）；设备窗口关闭只清理自身登记
+        .on_window_event(|window, event| match event {
+            tauri::WindowEvent::CloseRequested { .. } if window.label() == "main" => cleanup_all(),
+            tauri::WindowEvent::Destroyed => {
+                services::device_windows::on_window_destroyed(window.app_handle(), window.label())
             }
+            _ => {}
         })
         .invoke_handler(tauri::generate_handler![
             // ==================== 🏢 员工管理 (4个命令) ====================
@@ -1,73 +1,84 @@
 use tauri::{plugin::{Builder, TauriPlugin}, Wry, Manager, State};
-use std::sync::Mutex;
-use crate::services::adb::AdbService;
+use crate::core::app_services::SharedAppServices;
 use crate::services::log_bridge::LOG_COLLECTOR;
 #[cfg(windows)]
 use std::os::windows::process::CommandExt;
 use std::process::Command;
 use std::time::Instant;
 use tauri::AppHandle;
-use crate::services::smart_app_manager::SmartAppManagerState;
 use crate::services::smart_app_manager::{AppInfo, PagedApps};
 use crate::services::adb::tracking::adb_device_tracker::TrackedDevice;
 use crate::utils::adb_utils::get_adb_path;
 
 use crate::services::adb::commands::adb_file::safe_adb_push;
+use crate::services::device_files::PushOptions;
+use crate::services::device_windows::{self, DeviceWindow, DeviceWindowRegistry};
 use crate::services::adb::commands::ui_automation::{adb_dump_ui_xml, adb_tap_coordinate};
+use crate::services::scoped_dump::{DumpScope, ScopedDump};
 use crate::services::adb::tracking::adb_device_tracker::{start_device_tracking, stop_device_tracking, get_tracked_devices};
 
+mod screen_share;
+use screen_share::*;
+
+const PUSH_PROGRESS_EVENT: &str = "adb://push_progress";
+
 #[tauri::command]
-async fn execute(adb_path: String, args: Vec<String>, service: State<'_, Mutex<AdbService>>) -> Result<String, String> {
-    let service = service.lock().map_err(|e| e.to_string())?;
-    service.execute_command(&adb_path, &args).map_err(|e| e.to_string())
+async fn execute(adb_path: String, args: Vec<String>, services: State<'_, SharedAppServices>) -> Result<String, String> {
+    crate::core::plugin_isolation::guard("adb", async move { services.run_adb(move |adb| adb.execute_command(&adb_path, &args).map_err(|e| e.to_string())).await }).await
 }
 
 #[tauri::command]
-async fn check_file(path: String, service: State<'_, Mutex<AdbService>>) -> Result<bool, String> {
-    let service = service.lock().map_err(|e| e.to_string())?;
-    Ok(service.check_file_exists(&path))
+async fn check_file(path: String, services: State<'_, SharedAppServices>) -> Result<bool, String> {
+    crate::core::plugin_isolation::guard("adb", async move { services.run_adb(move |adb| Ok(adb.check_file_exists(&path))).await }).await
 }
 
 #[tauri::command]
-async fn detect_ldplayer(service: State<'_, Mutex<AdbService>>) -> Result<Option<String>, String> {
-    let service = service.lock().map_err(|e| e.to_string())?;
-    Ok(service.detect_ldplayer_adb())
+async fn detect_ldplayer(services: State<'_, SharedAppServices>) -> Result<Option<String>, String> {
+    crate::core::plugin_isolation::guard("adb", async move { services.run_adb(|adb| Ok(adb.detect_ldplayer_adb())).await }).await
 }
 
 #[tauri::command]
-async fn detect_path(service: State<'_, Mutex<AdbService>>) -> Result<String, String> {
-    let service = service.lock().map_err(|e| e.to_string())?;
-    if let Some(detected_path) = service.detect_ldplayer_adb() {
-        Ok(detected_path)
-    } else {
-        match service.execute_command("adb.exe", &["version".to_string()]) {
-            Ok(_) => Ok("adb.exe".to_string()),
-            Err(_) => {
-                let current_dir = std::env::current_dir().map_err(|e| format!("Failed to get current directory: {}", e))?;
-                let adb_path = current_dir.join("platform-tools").join("adb.exe");
-                if adb_path.exists() {
-                    Ok(adb_path.to_string_lossy().to_string())
-                } else {
-                    let parent_adb_path = current_dir.parent().ok_or("No parent directory")?.join("platform-tools").join("adb.exe");
-                    if parent_adb_path.exists() {
-                        Ok(parent_adb_path.to_string_lossy().to_string())
+async fn detect_path(services: State<'_, SharedAppServices>) -> Result<String, String> {
+    crate::core::plugin_isolation::guard("adb", detect_path_impl(services)).await
+}
+
+async fn detect_path_impl(services: State<'_, SharedAppServices>) -> Result<String, String> {
+    services.run_adb(|adb| {
+        if let Some(detected_path) = adb.detect_ldplayer_adb() {
+            Ok(detected_path)
+        } else {
+            match adb.execute_command("adb.exe", &["version".to_string()]) {
+                Ok(_) => Ok("adb.exe".to_string()),
+                Err(_) => {
+                    let current_dir = std::env::current_dir().map_err(|e| format!("Failed to get current directory: {}", e))?;
+                    let adb_path = current_dir.join("platform-tools").join("adb.exe");
+                    if adb_path.exists() {
+                        Ok(adb_path.to_string_lossy().to_string())
                     } else {
-                        Err("未找到可用的ADB路径".to_string())
+                        let parent_adb_path = current_dir.parent().ok_or("No parent directory")?.join("platform-tools").join("adb.exe");
+                        if parent_adb_path.exists() {
+                            Ok(parent_adb_path.to_string_lossy().to_string())
+                        } else {
+                            Err("未找到可用的ADB路径".to_string())
+                        }
                     }
                 }
             }
         }
-    }
+    }).await
 }
 
 #[tauri::command]
-async fn list_devices(adb_path: String, service: State<'_, Mutex<AdbService>>) -> Result<String, String> {
-    let service = service.lock().map_err(|e| e.to_string())?;
-    service.get_devices(&adb_path).map_err(|e| e.to_string())
+async fn list_devices(adb_path: String, services: State<'_, SharedAppServices>) -> Result<String, String> {
+    crate::core::plugin_isolation::guard("adb", async move { services.run_adb(move |adb| adb.get_devices(&adb_path).map_err(|e| e.to_string())).await }).await
 }
 
 #[tauri::command]
 async fn version() -> Result<String, String> {
+    crate::core::plugin_isolation::guard("adb", version_impl()).await
+}
+
+async fn version_impl() -> Result<String, String> {
     let adb_path = "platform-tools/adb.exe";
     let mut cmd = Command::new(adb_path);
     cmd.arg("version");
@@ -88,6 +99,10 @@ async fn version() -> Result<String, String> {
 
 #[tauri::command]
 async fn start_server_simple() -> Result<String, String> {
+    crate::core::plugin_isolation::guard("adb", start_server_simple_impl()).await
+}
+
+async fn start_server_simple_impl() -> Result<String, String> {
     let adb_path = "platform-tools/adb.exe";
     let mut cmd = Command::new(adb_path);
     cmd.arg("start-server");
@@ -140,6 +155,10 @@ async fn start_server_simple() -> Result<String, String> {
 
 #[tauri::command]
 async fn kill_server_simple() -> Result<String, String> {
+    crate::core::plugin_isolation::guard("adb", kill_server_simple_impl()).await
+}
+
+async fn kill_server_simple_impl() -> Result<String, String> {
     let adb_path = "platform-tools/adb.exe";
     let mut cmd = Command::new(adb_path);
     cmd.arg("kill-server");
@@ -192,6 +211,10 @@ async fn kill_server_simple() -> Result<String, String> {
 
 #[tauri::command]
 async fn execute_simple(command: String) -> Result<String, String> {
+    crate::core::plugin_isolation::guard("adb", execute_simple_impl(command)).await
+}
+
+async fn execute_simple_impl(command: String) -> Result<String, String> {
     let adb_path = "platform-tools/adb.exe";
     let args: Vec<&str> = command.split_whitespace().collect();
     let mut cmd = Command::new(adb_path);
@@ -243,68 +266,102 @@ async fn execute_simple(command: String) -> Result<String, String> {
 }
 
 #[tauri::command]
-async fn connect(adb_path: String, address: String, service: State<'_, Mutex<AdbService>>) -> Result<String, String> {
-    let service = service.lock().map_err(|e| e.to_string())?;
-    service.connect_device(&adb_path, &address).map_err(|e| e.to_string())
+async fn connect(adb_path: String, address: String, services: State<'_, SharedAppServices>) -> Result<String, String> {
+    crate::core::plugin_isolation::guard("adb", async move { services.run_adb(move |adb| adb.connect_device(&adb_path, &address).map_err(|e| e.to_string())).await }).await
 }
 
 #[tauri::command]
-async fn disconnect(adb_path: String, address: String, service: State<'_, Mutex<AdbService>>) -> Result<String, String> {
-    let service = service.lock().map_err(|e| e.to_string())?;
-    service.disconnect_device(&adb_path, &address).map_err(|e| e.to_string())
+async fn disconnect(adb_path: String, address: String, services: State<'_, SharedAppServices>) -> Result<String, String> {
+    crate::core::plugin_isolation::guard("adb", async move { services.run_adb(move |adb| adb.disconnect_device(&adb_path, &address).map_err(|e| e.to_string())).await }).await
 }
 
 #[tauri::command]
-async fn start_server(adb_path: String, service: State<'_, Mutex<AdbService>>) -> Result<String, String> {
-    let service = service.lock().map_err(|e| e.to_string())?;
-    service.start_server(&adb_path).map_err(|e| e.to_string())
+async fn start_server(adb_path: String, services: State<'_, SharedAppServices>) -> Result<String, String> {
+    crate::core::plugin_isolation::guard("adb", async move { services.run_adb(move |adb| adb.start_server(&adb_path).map_err(|e| e.to_string())).await }).await
 }
 
 #[tauri::command]
-async fn kill_server(adb_path: String, service: State<'_, Mutex<AdbService>>) -> Result<String, String> {
-    let service = service.lock().map_err(|e| e.to_string())?;
-    service.kill_server(&adb_path).map_err(|e| e.to_string())
+async fn kill_server(adb_path: String, services: State<'_, SharedAppServices>) -> Result<String, String> {
+    crate::core::plugin_isolation::guard("adb", async move { services.run_adb(move |adb| adb.kill_server(&adb_path).map_err(|e| e.to_string())).await }).await
 }
 
 #[tauri::command]
-async fn get_properties(adb_path: String, device_id: String, service: State<'_, Mutex<AdbService>>) -> Result<String, String> {
-    let service = service.lock().map_err(|e| e.to_string())?;
-    service.get_device_properties(&adb_path, &device_id).map_err(|e| e.to_string())
+async fn get_properties(adb_path: String, device_id: String, services: State<'_, SharedAppServices>) -> Result<String, String> {
+    crate::core::plugin_isolation::guard("adb", async move { services.run_adb(move |adb| adb.get_device_properties(&adb_path, &device_id).map_err(|e| e.to_string())).await }).await
 }
 
 #[tauri::command]
 async fn shell(device_id: String, command: String) -> Result<String, String> {
-    crate::services::adb::commands::adb_shell::safe_adb_shell_command(device_id, command).await
+    crate::core::plugin_isolation::guard("adb", async move { crate::services::adb::commands::adb_shell::safe_adb_shell_command(device_id, command).await }).await
 }
 
+/// 推送文件并校验哈希（进度通过 adb://push_progress 推送）
 #[tauri::command]
-async fn push(device_id: String, local_path: String, remote_path: String) -> Result<String, String> {
-    crate::services::adb::commands::adb_file::safe_adb_push(device_id, local_path, remote_path).await
+async fn push(
+    app_handle: AppHandle,
+    device_id: String,
+    local_path: String,
+    remote_path: String,
+    options: Option<PushOptions>,
+) -> Result<String, String> {
+    crate::core::plugin_isolation::guard("adb", push_impl(app_handle, device_id, local_path, remote_path, options)).await
+}
+
+async fn push_impl(
+    app_handle: AppHandle,
+    device_id: String,
+    local_path: String,
+    remote_path: String,
+    options: Option<PushOptions>,
+) -> Result<String, String> {
+    let device = device_id.clone();
+    safe_adb_push(device_id, local_path, remote_path, options, move |progress| {
+        let _ = device_windows::emit_for_device(&app_handle, &device, PUSH_PROGRESS_EVENT, progress);
+    })
+    .await
+}
+
+/// 打开设备独立控制窗口（已打开时聚焦）
+#[tauri::command]
+async fn open_device_window(app_handle: AppHandle, device_id: String) -> Result<DeviceWindow, String> {
+    crate::core::plugin_isolation::guard("adb", async move { device_windows::open(&app_handle, &device_id) }).await
+}
+
+/// 已打开的设备窗口
+#[tauri::command]
+async fn list_device_windows() -> Result<Vec<DeviceWindow>, String> {
+    crate::core::plugin_isolation::guard("adb", async move { Ok(DeviceWindowRegistry::global().lock().map_err(|e| e.to_string())?.list()) }).await
 }
 
 #[tauri::command]
 async fn dump_ui(device_id: String) -> Result<String, String> {
-    crate::services::adb::commands::ui_automation::adb_dump_ui_xml(device_id).await
+    crate::core::plugin_isolation::guard("adb", async move { crate::services::adb::commands::ui_automation::adb_dump_ui_xml(device_id).await }).await
+}
+
+/// 抓取 UI 并只保留容器子树（容器缺失且未要求 required 时返回整屏）
+#[tauri::command]
+async fn dump_ui_scoped(device_id: String, scope: DumpScope) -> Result<ScopedDump, String> {
+    crate::core::plugin_isolation::guard("adb", async move { crate::services::scoped_dump::dump_scoped(&device_id, &scope).await }).await
 }
 
 #[tauri::command]
 async fn tap(device_id: String, x: i32, y: i32) -> Result<bool, String> {
-    crate::services::adb::commands::ui_automation::adb_tap_coordinate(device_id, x, y).await
+    crate::core::plugin_isolation::guard("adb", async move { crate::services::adb::commands::ui_automation::adb_tap_coordinate(device_id, x, y).await }).await
 }
 
 #[tauri::command]
 async fn start_tracking(app_handle: AppHandle) -> Result<(), String> {
-    crate::services::adb::tracking::adb_device_tracker::start_device_tracking(app_handle).await
+    crate::core::plugin_isolation::guard("adb", async move { crate::services::adb::tracking::adb_device_tracker::start_device_tracking(app_handle).await }).await
 }
 
 #[tauri::command]
 async fn stop_tracking() -> Result<(), String> {
-    crate::services::adb::tracking::adb_device_tracker::stop_device_tracking().await
+    crate::core::plugin_isolation::guard("adb", async move { crate::services::adb::tracking::adb_device_tracker::stop_device_tracking().await }).await
 }
 
 #[tauri::command]
 async fn get_tracking_list() -> Result<Vec<TrackedDevice>, String> {
-    crate::services::adb::tracking::adb_device_tracker::get_tracked_devices().await
+    crate::core::plugin_isolation::guard("adb", async move { crate::services::adb::tracking::adb_device_tracker::get_tracked_devices().await }).await
 }
 
 #[tauri::command]
@@ -314,7 +371,18 @@ async fn list_apps(
     force_refresh: Option<bool>,
     filter_mode: Option<String>,
     refresh_strategy: Option<String>,
-    state: State<'_, SmartAppManagerState>,
+    services: State<'_, SharedAppServices>,
+) -> Result<Vec<AppInfo>, String> {
+    crate::core::plugin_isolation::guard("adb", list_apps_impl(device_id, include_system_apps, force_refresh, filter_mode, refresh_strategy, services)).await
+}
+
+async fn list_apps_impl(
+    device_id: String,
+    include_system_apps: Option<bool>,
+    force_refresh: Option<bool>,
+    filter_mode: Option<String>,
+    refresh_strategy: Option<String>,
+    services: State<'_, SharedAppServices>,
 ) -> Result<Vec<AppInfo>, String> {
     crate::commands::apps::get_device_apps(
         device_id,
@@ -322,7 +390,7 @@ async fn list_apps(
         force_refresh,
         filter_mode,
         refresh_strategy,
-        state,
+        &services.apps,
     ).await
 }
 
@@ -334,7 +402,19 @@ async fn list_apps_paged(
     page: Option<u32>,
     page_size: Option<u32>,
     query: Option<String>,
-    state: State<'_, SmartAppManagerState>,
+    services: State<'_, SharedAppServices>,
+) -> Result<PagedApps, String> {
+    crate::core::plugin_isolation::guard("adb", list_apps_paged_impl(device_id, filter_mode, refresh_strategy, page, page_size, query, services)).await
+}
+
+async fn list_apps_paged_impl(
+    device_id: String,
+    filter_mode: Option<String>,
+    refresh_strategy: Option<String>,
+    page: Option<u32>,
+    page_size: Option<u32>,
+    query: Option<String>,
+    services: State<'_, SharedAppServices>,
 ) -> Result<PagedApps, String> {
     crate::commands::apps::get_device_apps_paged(
         device_id,
@@ -343,7 +423,7 @@ async fn list_apps_paged(
         page,
         page_size,
         query,
-        state,
+        &services.apps,
     ).await
 }
 
@@ -353,7 +433,7 @@ async fn scan_apps(
     device_id: String,
     filter_mode: Option<String>,
 ) -> Result<(), String> {
-    crate::commands::apps::scan_device_apps(app_handle, device_id, filter_mode).await
+    crate::core::plugin_isolation::guard("adb", async move { crate::commands::apps::scan_device_apps(app_handle, device_id, filter_mode).await }).await
 }
 
 #[tauri::command]
@@ -362,52 +442,56 @@ async fn get_icon(
     package_name: String,
     force_refresh: Option<bool>,
 ) -> Result<Vec<u8>, String> {
-    crate::commands::apps::get_app_icon(device_id, package_name, force_refresh).await
+    crate::core::plugin_isolation::guard("adb", async move { crate::commands::apps::get_app_icon(device_id, package_name, force_refresh).await }).await
 }
 
 #[tauri::command]
 async fn validate_connection(device_id: String) -> Result<bool, String> {
-    crate::utils::device_utils::validate_device_connection(device_id).await
+    crate::core::plugin_isolation::guard("adb", async move { crate::utils::device_utils::validate_device_connection(device_id).await }).await
 }
 
 #[tauri::command]
 async fn get_ui_dump(device_id: String) -> Result<String, String> {
-    crate::commands::ui_dump::get_ui_dump(device_id).await
+    crate::core::plugin_isolation::guard("adb", async move { crate::commands::ui_dump::get_ui_dump(device_id).await }).await
 }
 
 #[tauri::command]
 async fn search_apps(
     device_id: String,
     query: String,
-    state: State<'_, SmartAppManagerState>,
+    services: State<'_, SharedAppServices>,
 ) -> Result<Vec<AppInfo>, String> {
-    crate::commands::apps::search_device_apps(device_id, query, state).await
+    crate::core::plugin_isolation::guard("adb", async move { crate::commands::apps::search_device_apps(device_id, query, &services.apps).await }).await
 }
 
 #[tauri::command]
 async fn launch_app(
     device_id: String,
     package_name: String,
-    state: State<'_, SmartAppManagerState>,
+    services: State<'_, SharedAppServices>,
 ) -> Result<crate::services::smart_app_manager::AppLaunchResult, String> {
-    crate::commands::apps::launch_device_app(device_id, package_name, state).await
+    crate::core::plugin_isolation::guard("adb", async move { crate::commands::apps::launch_device_app(device_id, package_name, &services.apps).await }).await
 }
 
 #[tauri::command]
 async fn get_cached_apps(
     device_id: String,
-    state: State<'_, SmartAppManagerState>,
+    services: State<'_, SharedAppServices>,
 ) -> Result<Vec<AppInfo>, String> {
-    crate::commands::apps::get_cached_device_apps(device_id, state).await
+    crate::core::plugin_isolation::guard("adb", async move { crate::commands::apps::get_cached_device_apps(device_id, &services.apps).await }).await
 }
 
 #[tauri::command]
 async fn get_popular_apps() -> Result<Vec<AppInfo>, String> {
-    crate::commands::apps::get_popular_apps().await
+    crate::core::plugin_isolation::guard("adb", async move { crate::commands::apps::get_popular_apps().await }).await
 }
 
 #[tauri::command]
 async fn capture_device_screenshot(_device_id: String) -> Result<serde_json::Value, String> {
+    crate::core::plugin_isolation::guard("adb", capture_device_screenshot_impl(_device_id)).await
+}
+
+async fn capture_device_screenshot_impl(_device_id: String) -> Result<serde_json::Value, String> {
     Ok(serde_json::json!({
         "success": true,
         "screenshot_path": "stub_path.png",
@@ -417,11 +501,15 @@ async fn capture_device_screenshot(_device_id: String) -> Result<serde_json::Val
 
 #[tauri::command]
 async fn get_device_ui_xml(_device_id: String) -> Result<String, String> {
-    Ok("<node></node>".to_string())
+    crate::core::plugin_isolation::guard("adb", async move { Ok("<node></node>".to_string()) }).await
 }
 
 #[tauri::command]
 async fn get_current_app_info(_device_id: String) -> Result<serde_json::Value, String> {
+    crate::core::plugin_isolation::guard("adb", get_current_app_info_impl(_device_id)).await
+}
+
+async fn get_current_app_info_impl(_device_id: String) -> Result<serde_json::Value, String> {
     Ok(serde_json::json!({
         "package": "com.example.app",
         "activity": "MainActivity"
@@ -430,6 +518,10 @@ async fn get_current_app_info(_device_id: String) -> Result<serde_json::Value, S
 
 #[tauri::command]
 async fn get_screen_resolution(_device_id: String) -> Result<serde_json::Value, String> {
+    crate::core::plugin_isolation::guard("adb", get_screen_resolution_impl(_device_id)).await
+}
+
+async fn get_screen_resolution_impl(_device_id: String) -> Result<serde_json::Value, String> {
     Ok(serde_json::json!({
         "width": 1080,
         "height": 1920
@@ -438,42 +530,42 @@ async fn get_screen_resolution(_device_id: String) -> Result<serde_json::Value,
 
 #[tauri::command]
 async fn execute_ui_action(_device_id: String, _action: serde_json::Value) -> Result<(), String> {
-    Ok(())
+    crate::core::plugin_isolation::guard("adb", async move { Ok(()) }).await
 }
 
 #[tauri::command]
 async fn stop_device_mirror(_device_id: String) -> Result<(), String> {
-    Ok(())
+    crate::core::plugin_isolation::guard("adb", async move { Ok(()) }).await
 }
 
 #[tauri::command]
 async fn stop_device_mirror_session(_device_id: String, _session_name: String) -> Result<(), String> {
-    Ok(())
+    crate::core::plugin_isolation::guard("adb", async move { Ok(()) }).await
 }
 
 #[tauri::command]
 async fn adb_swipe(_device_id: String, _start_x: i32, _start_y: i32, _end_x: i32, _end_y: i32, _duration: i32) -> Result<(), String> {
-    Ok(())
+    crate::core::plugin_isolation::guard("adb", async move { Ok(()) }).await
 }
 
 #[tauri::command]
 async fn adb_input_text(_device_id: String, _text: String) -> Result<(), String> {
-    Ok(())
+    crate::core::plugin_isolation::guard("adb", async move { Ok(()) }).await
 }
 
 #[tauri::command]
 async fn adb_screenshot(_device_id: String) -> Result<String, String> {
-    Ok("stub_path.png".to_string())
+    crate::core::plugin_isolation::guard("adb", async move { Ok("stub_path.png".to_string()) }).await
 }
 
 #[tauri::command]
 async fn adb_press_key(_device_id: String, _key_code: i32) -> Result<(), String> {
-    Ok(())
+    crate::core::plugin_isolation::guard("adb", async move { Ok(()) }).await
 }
 
 #[tauri::command]
 async fn adb_close_app(_device_id: String, _package_name: String) -> Result<(), String> {
-    Ok(())
+    crate::core::plugin_isolation::guard("adb", async move { Ok(()) }).await
 }
 
 /// 安装 APK 到指定设备
@@ -481,6 +573,10 @@ async fn adb_close_app(_device_id: String, _package_name: String) -> Result<(),
 /// - apkPath: APK 文件的完整路径
 #[tauri::command]
 async fn adb_install_apk(device_id: String, apk_path: String) -> Result<String, String> {
+    crate::core::plugin_isolation::guard("adb", adb_install_apk_impl(device_id, apk_path)).await
+}
+
+async fn adb_install_apk_impl(device_id: String, apk_path: String) -> Result<String, String> {
     let adb_path = get_adb_path();
     
     // 检查 APK 文件是否存在
@@ -554,7 +650,7 @@ async fn adb_install_apk(device_id: String, apk_path: String) -> Result<String,
 
 #[tauri::command]
 async fn adb_uninstall_app(_device_id: String, _package_name: String) -> Result<(), String> {
-    Ok(())
+    crate::core::plugin_isolation::guard("adb", async move { Ok(()) }).await
 }
 
 /// 获取内置 Agent APK 的路径
@@ -562,6 +658,10 @@ async fn adb_uninstall_app(_device_id: String, _package_name: String) -> Result<
 /// 生产环境: 返回打包后资源目录下的路径
 #[tauri::command]
 async fn get_bundled_agent_apk() -> Result<String, String> {
+    crate::core::plugin_isolation::guard("adb", get_bundled_agent_apk_impl()).await
+}
+
+async fn get_bundled_agent_apk_impl() -> Result<String, String> {
     // 方法1: 检查当前目录的 agent-apk 文件夹 (开发环境 - 如果从项目根目录运行)
     let dev_path = std::path::Path::new("agent-apk/employee-agent.apk");
     if dev_path.exists() {
@@ -615,7 +715,7 @@ async fn get_bundled_agent_apk() -> Result<String, String> {
 
 pub fn init() -> TauriPlugin<Wry> {
     Builder::new("adb")
-        .invoke_handler(tauri::generate_handler![
+        .invoke_handler(crate::core::plugin_isolation::isolate("adb", tauri::generate_handler![
             execute,
             check_file,
             detect_ldplayer,
@@ -633,6 +733,7 @@ pub fn init() -> TauriPlugin<Wry> {
             shell,
             push,
             dump_ui,
+            dump_ui_scoped,
             tap,
             start_tracking,
             stop_tracking,
@@ -661,7 +762,13 @@ pub fn init() -> TauriPlugin<Wry> {
             adb_close_app,
             adb_install_apk,
             adb_uninstall_app,
-            get_bundled_agent_apk
-        ])
+            get_bundled_agent_apk,
+            capture_and_share,
+            get_shared_artifact,
+            get_screen_share_config,
+            set_screen_share_config,
+            open_device_window,
+            list_device_windows
+        ]))
         .build()
 }
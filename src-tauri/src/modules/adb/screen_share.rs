@@ -0,0 +1,43 @@
+// src-tauri/src/modules/adb/screen_share.rs
+// module: adb | layer: commands | role: screen-share-commands
+// summary: 截图分享命令 - 截取设备屏幕存为短期制品并按配置上传，返回可贴进工单的引用
+
+use crate::services::artifact_store::{Artifact, ArtifactStore};
+use crate::services::screen_share::{self, ScreenShareConfig, ShareReference};
+
+/// 截取设备当前屏幕并分享；`upload` 省略时配置了上传地址就上传，`run_id` / `step_id` 用于图库归档
+#[tauri::command]
+pub async fn capture_and_share(
+    device_id: String,
+    upload: Option<bool>,
+    run_id: Option<String>,
+    step_id: Option<String>,
+) -> Result<ShareReference, String> {
+    crate::core::plugin_isolation::guard("adb", async move { screen_share::capture_and_share(&device_id, upload, run_id.as_deref(), step_id.as_deref()).await }).await
+}
+
+/// 按令牌取回分享过的制品（元信息 + 本地路径）
+#[tauri::command]
+pub async fn get_shared_artifact(token: String) -> Result<(Artifact, String), String> {
+    crate::core::plugin_isolation::guard("adb", get_shared_artifact_impl(token)).await
+}
+
+async fn get_shared_artifact_impl(token: String) -> Result<(Artifact, String), String> {
+    let (artifact, path) = ArtifactStore::global().lock().map_err(|e| e.to_string())?.get(&token)?;
+    Ok((artifact, path.to_string_lossy().to_string()))
+}
+
+#[tauri::command]
+pub async fn get_screen_share_config() -> Result<ScreenShareConfig, String> {
+    crate::core::plugin_isolation::guard("adb", async move { Ok(screen_share::load_config().masked()) }).await
+}
+
+#[tauri::command]
+pub async fn set_screen_share_config(config: ScreenShareConfig) -> Result<ScreenShareConfig, String> {
+    crate::core::plugin_isolation::guard("adb", set_screen_share_config_impl(config)).await
+}
+
+async fn set_screen_share_config_impl(config: ScreenShareConfig) -> Result<ScreenShareConfig, String> {
+    screen_share::save_config(&config)?;
+    Ok(config.masked())
+}
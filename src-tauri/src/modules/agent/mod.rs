@@ -5,13 +5,16 @@
 mod agent_config;
 
 use std::sync::Arc;
+use std::time::Instant;
 use tauri::{plugin::{Builder, TauriPlugin}, Runtime, Manager, State};
 use tokio::sync::RwLock;
 use serde::{Deserialize, Serialize};
 use tracing::{info, warn, error};
 
+use crate::core::plugin_bootstrap::{self, PluginPhase};
 use crate::core::domain::agent::{AiProviderConfig, AgentSession, ToolProvider, AiProvider};
 use crate::core::application::{AppContext, AgentAppService};
+use crate::core::application::agent_service::DeltaCallback;
 use crate::core::adapters::outbound::{OpenAiCompatibleProvider, McpToolProvider};
 
 pub use agent_config::{AgentConfig, FullAgentConfig};
@@ -30,6 +33,15 @@ impl AgentState {
         }
     }
 
+    /// 保存已构建的 Agent 服务；首次配置成功即视为懒加载服务就绪（重新配置不重复记录）
+    async fn install_service(&self, agent_service: AgentAppService, started: Instant) {
+        *self.service.write().await = Some(agent_service);
+        let registry = plugin_bootstrap::registry();
+        if registry.phase("agent") == Some(PluginPhase::Deferred) {
+            registry.record_lazy_init("agent", started.elapsed().as_millis() as u64, None);
+        }
+    }
+
     /// 设置应用上下文
     pub async fn set_app_context(&self, ctx: Arc<AppContext>) {
         let mut context = self.app_context.write().await;
@@ -45,6 +57,29 @@ impl AgentState {
         agent.chat(message).await.map_err(|e| e.to_string())
     }
 
+    /// 公共接口：流式发送消息，增量文本通过 `on_delta` 实时回调，返回完整回复
+    pub async fn chat_with_ai_streaming(&self, message: &str, on_delta: DeltaCallback) -> Result<String, String> {
+        let service = self.service.read().await;
+        let agent = service.as_ref()
+            .ok_or("AI Agent 未配置，请先调用 configure")?;
+
+        agent.chat_streaming(message, on_delta).await.map_err(|e| e.to_string())
+    }
+
+    /// 公共接口：单次无状态摘要（廉价模型，不写入会话）
+    pub async fn summarize_with_ai(&self, prompt: &str) -> Result<String, String> {
+        let service = self.service.read().await;
+        let agent = service.as_ref()
+            .ok_or("AI Agent 未配置，请先调用 configure")?;
+
+        agent.summarize(prompt).await.map_err(|e| e.to_string())
+    }
+
+    /// 公共接口：当前模型可用于输入的 token 预算
+    pub async fn input_budget_tokens(&self) -> Option<usize> {
+        self.service.read().await.as_ref().and_then(|agent| agent.input_budget_tokens())
+    }
+
     /// 公共接口：检查 AI 是否已配置
     pub async fn is_configured(&self) -> bool {
         self.service.read().await.is_some()
@@ -112,6 +147,13 @@ pub struct ToolInfo {
 async fn configure(
     request: ConfigureAgentRequest,
     state: State<'_, AgentState>,
+) -> Result<AgentResponse, String> {
+    crate::core::plugin_isolation::guard("agent", configure_impl(request, state)).await
+}
+
+async fn configure_impl(
+    request: ConfigureAgentRequest,
+    state: State<'_, AgentState>,
 ) -> Result<AgentResponse, String> {
     info!("🔧 配置 AI Agent: provider={}", request.provider);
 
@@ -150,21 +192,21 @@ async fn configure(
         "openai" => {
             let mut cfg = AiProviderConfig::openai(api_key);
             if let Some(model) = request.model {
-                cfg.model = model;
+                cfg = cfg.with_model(model);
             }
             cfg
         }
         "hunyuan" => {
             let mut cfg = AiProviderConfig::hunyuan(api_key);
             if let Some(model) = request.model {
-                cfg.model = model;
+                cfg = cfg.with_model(model);
             }
             cfg
         }
         "deepseek" => {
             let mut cfg = AiProviderConfig::deepseek(api_key);
             if let Some(model) = request.model {
-                cfg.model = model;
+                cfg = cfg.with_model(model);
             }
             cfg
         }
@@ -183,7 +225,9 @@ async fn configure(
         _ => return Err(format!("不支持的提供商: {}", request.provider)),
     };
 
-    // 创建 AI 提供商
+    // 创建 AI 提供商（摘要等辅助任务使用廉价模型）
+    let started = Instant::now();
+    let summary_provider: Arc<dyn AiProvider> = Arc::new(OpenAiCompatibleProvider::new(ai_config.summary_config()));
     let ai_provider: Arc<dyn AiProvider> = Arc::new(OpenAiCompatibleProvider::new(ai_config));
 
     // 获取 AppContext
@@ -197,11 +241,11 @@ async fn configure(
 
     // 创建 Agent 服务
     let agent_service = AgentAppService::new(tool_provider)
-        .with_ai_provider(ai_provider);
+        .with_ai_provider(ai_provider)
+        .with_summary_provider(summary_provider);
 
     // 保存服务
-    let mut service = state.service.write().await;
-    *service = Some(agent_service);
+    state.install_service(agent_service, started).await;
 
     info!("✅ AI Agent 配置成功");
 
@@ -218,8 +262,17 @@ async fn configure(
 async fn chat(
     message: String,
     state: State<'_, AgentState>,
+) -> Result<ChatResponse, String> {
+    crate::core::plugin_isolation::guard("agent", chat_impl(message, state)).await
+}
+
+async fn chat_impl(
+    message: String,
+    state: State<'_, AgentState>,
 ) -> Result<ChatResponse, String> {
     info!("💬 用户消息: {}", message);
+    crate::services::licensing::require_feature(crate::services::licensing::LicenseFeature::Agent)?;
+    crate::services::telemetry::record_feature("agent.chat");
 
     let service = state.service.read().await;
     let agent = service.as_ref()
@@ -252,6 +305,13 @@ async fn chat(
 async fn analyze_script(
     script_id: String,
     state: State<'_, AgentState>,
+) -> Result<ChatResponse, String> {
+    crate::core::plugin_isolation::guard("agent", analyze_script_impl(script_id, state)).await
+}
+
+async fn analyze_script_impl(
+    script_id: String,
+    state: State<'_, AgentState>,
 ) -> Result<ChatResponse, String> {
     info!("🔍 分析脚本: {}", script_id);
 
@@ -281,6 +341,14 @@ async fn fix_script(
     script_id: String,
     issue: String,
     state: State<'_, AgentState>,
+) -> Result<ChatResponse, String> {
+    crate::core::plugin_isolation::guard("agent", fix_script_impl(script_id, issue, state)).await
+}
+
+async fn fix_script_impl(
+    script_id: String,
+    issue: String,
+    state: State<'_, AgentState>,
 ) -> Result<ChatResponse, String> {
     info!("🔧 修复脚本: {} - {}", script_id, issue);
 
@@ -309,8 +377,17 @@ async fn fix_script(
 async fn execute_task(
     task: String,
     state: State<'_, AgentState>,
+) -> Result<ChatResponse, String> {
+    crate::core::plugin_isolation::guard("agent", execute_task_impl(task, state)).await
+}
+
+async fn execute_task_impl(
+    task: String,
+    state: State<'_, AgentState>,
 ) -> Result<ChatResponse, String> {
     info!("📋 执行任务: {}", task);
+    crate::services::licensing::require_feature(crate::services::licensing::LicenseFeature::Agent)?;
+    crate::services::telemetry::record_feature("agent.execute_task");
 
     let service = state.service.read().await;
     let agent = service.as_ref()
@@ -336,6 +413,12 @@ async fn execute_task(
 #[tauri::command]
 async fn get_session(
     state: State<'_, AgentState>,
+) -> Result<Option<AgentSession>, String> {
+    crate::core::plugin_isolation::guard("agent", get_session_impl(state)).await
+}
+
+async fn get_session_impl(
+    state: State<'_, AgentState>,
 ) -> Result<Option<AgentSession>, String> {
     let service = state.service.read().await;
     match &*service {
@@ -348,6 +431,12 @@ async fn get_session(
 #[tauri::command]
 async fn clear_session(
     state: State<'_, AgentState>,
+) -> Result<AgentResponse, String> {
+    crate::core::plugin_isolation::guard("agent", clear_session_impl(state)).await
+}
+
+async fn clear_session_impl(
+    state: State<'_, AgentState>,
 ) -> Result<AgentResponse, String> {
     let service = state.service.read().await;
     if let Some(agent) = &*service {
@@ -366,6 +455,12 @@ async fn clear_session(
 #[tauri::command]
 async fn list_tools(
     state: State<'_, AgentState>,
+) -> Result<Vec<ToolInfo>, String> {
+    crate::core::plugin_isolation::guard("agent", list_tools_impl(state)).await
+}
+
+async fn list_tools_impl(
+    state: State<'_, AgentState>,
 ) -> Result<Vec<ToolInfo>, String> {
     let service = state.service.read().await;
     let agent = service.as_ref()
@@ -383,6 +478,12 @@ async fn list_tools(
 #[tauri::command]
 async fn test_connection(
     state: State<'_, AgentState>,
+) -> Result<AgentResponse, String> {
+    crate::core::plugin_isolation::guard("agent", test_connection_impl(state)).await
+}
+
+async fn test_connection_impl(
+    state: State<'_, AgentState>,
 ) -> Result<AgentResponse, String> {
     // 简单测试：尝试发送一条消息
     let service = state.service.read().await;
@@ -417,6 +518,12 @@ pub struct ConfigStatus {
 #[tauri::command]
 async fn get_config_status(
     state: State<'_, AgentState>,
+) -> Result<ConfigStatus, String> {
+    crate::core::plugin_isolation::guard("agent", get_config_status_impl(state)).await
+}
+
+async fn get_config_status_impl(
+    state: State<'_, AgentState>,
 ) -> Result<ConfigStatus, String> {
     let service = state.service.read().await;
     let is_configured = service.is_some();
@@ -438,6 +545,12 @@ async fn get_config_status(
 #[tauri::command]
 async fn restore_config(
     state: State<'_, AgentState>,
+) -> Result<AgentResponse, String> {
+    crate::core::plugin_isolation::guard("agent", restore_config_impl(state)).await
+}
+
+async fn restore_config_impl(
+    state: State<'_, AgentState>,
 ) -> Result<AgentResponse, String> {
     info!("🔄 尝试恢复 Agent 配置...");
     
@@ -452,21 +565,21 @@ async fn restore_config(
         "openai" => {
             let mut cfg = AiProviderConfig::openai(&full_config.api_key);
             if let Some(model) = &full_config.model {
-                cfg.model = model.clone();
+                cfg = cfg.with_model(model.clone());
             }
             cfg
         }
         "hunyuan" => {
             let mut cfg = AiProviderConfig::hunyuan(&full_config.api_key);
             if let Some(model) = &full_config.model {
-                cfg.model = model.clone();
+                cfg = cfg.with_model(model.clone());
             }
             cfg
         }
         "deepseek" => {
             let mut cfg = AiProviderConfig::deepseek(&full_config.api_key);
             if let Some(model) = &full_config.model {
-                cfg.model = model.clone();
+                cfg = cfg.with_model(model.clone());
             }
             cfg
         }
@@ -485,7 +598,9 @@ async fn restore_config(
         _ => return Err(format!("不支持的提供商: {}", full_config.provider)),
     };
 
-    // 创建 AI 提供商
+    // 创建 AI 提供商（摘要等辅助任务使用廉价模型）
+    let started = Instant::now();
+    let summary_provider: Arc<dyn AiProvider> = Arc::new(OpenAiCompatibleProvider::new(ai_config.summary_config()));
     let ai_provider: Arc<dyn AiProvider> = Arc::new(OpenAiCompatibleProvider::new(ai_config));
 
     // 获取 AppContext
@@ -499,11 +614,11 @@ async fn restore_config(
 
     // 创建 Agent 服务
     let agent_service = AgentAppService::new(tool_provider)
-        .with_ai_provider(ai_provider);
+        .with_ai_provider(ai_provider)
+        .with_summary_provider(summary_provider);
 
     // 保存服务
-    let mut service = state.service.write().await;
-    *service = Some(agent_service);
+    state.install_service(agent_service, started).await;
 
     info!("✅ AI Agent 配置已自动恢复 ({})", full_config.provider);
 
@@ -519,6 +634,12 @@ async fn restore_config(
 #[tauri::command]
 async fn clear_saved_config(
     state: State<'_, AgentState>,
+) -> Result<AgentResponse, String> {
+    crate::core::plugin_isolation::guard("agent", clear_saved_config_impl(state)).await
+}
+
+async fn clear_saved_config_impl(
+    state: State<'_, AgentState>,
 ) -> Result<AgentResponse, String> {
     // 获取当前配置以知道要删除哪个 API Key
     if let Some(config) = agent_config::load_config() {
@@ -546,7 +667,7 @@ async fn clear_saved_config(
 /// 初始化 AI Agent 插件
 pub fn init<R: Runtime>() -> TauriPlugin<R> {
     Builder::new("agent")
-        .invoke_handler(tauri::generate_handler![
+        .invoke_handler(crate::core::plugin_isolation::isolate("agent", tauri::generate_handler![
             configure,
             chat,
             analyze_script,
@@ -559,9 +680,12 @@ pub fn init<R: Runtime>() -> TauriPlugin<R> {
             get_config_status,
             restore_config,
             clear_saved_config,
-        ])
+        ]))
         .setup(|app, _api| {
-            app.manage(AgentState::new());
+            plugin_bootstrap::registry().track_setup("agent", || {
+                app.manage(AgentState::new());
+                Ok::<_, String>(())
+            })?;
             info!("🤖 AI Agent 插件已初始化");
             
             // 检查是否有保存的配置
@@ -0,0 +1,173 @@
+// src-tauri/src/modules/agent_runtime/agent_runtime_collab_settings.rs
+// module: agent_runtime | layer: tauri-plugin | role: PC-手机协同设置
+// summary: 协同传输方式（WebSocket / gRPC）的选择与 gRPC 的截止时间、保活、TLS 参数，持久化为 JSON；gRPC 需启用 grpc-bridge 功能
+
+use super::*;
+use std::sync::{Mutex, OnceLock};
+
+const SETTINGS_FILE_NAME: &str = "collaboration_settings.json";
+const DEFAULT_WEBSOCKET_PORT: u16 = 8765;
+const DEFAULT_GRPC_PORT: u16 = 50051;
+/// 未启用 grpc-bridge 功能时选择 gRPC 的错误
+pub const GRPC_DISABLED_ERROR: &str = "当前构建未启用 grpc-bridge 功能，无法使用 gRPC 传输";
+
+static COLLABORATION_SETTINGS_STORE: OnceLock<Mutex<CollaborationSettingsStore>> = OnceLock::new();
+
+/// PC-手机传输方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CollaborationTransport {
+    /// 自定义 JSON 消息的 WebSocket 协议
+    #[default]
+    WebSocket,
+    /// protobuf + HTTP/2，自带截止时间与流式推送，适合不稳定的 Wi-Fi
+    Grpc,
+}
+
+/// gRPC TLS 设置
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct GrpcTlsSettings {
+    pub enabled: bool,
+    /// 手机端证书的签发 CA（PEM）；为空时使用系统根证书
+    pub ca_cert_path: Option<PathBuf>,
+    /// 证书中的域名；手机以 IP 访问时通常需要指定
+    pub domain_name: Option<String>,
+    /// 双向 TLS 的客户端证书与私钥（PEM），需成对配置
+    pub client_cert_path: Option<PathBuf>,
+    pub client_key_path: Option<PathBuf>,
+}
+
+/// gRPC 传输设置
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct GrpcTransportSettings {
+    pub port: u16,
+    /// 单次下发的截止时间
+    pub deadline_ms: u64,
+    pub connect_timeout_ms: u64,
+    /// HTTP/2 保活间隔，Wi-Fi 断开时据此尽快发现
+    pub keep_alive_secs: u64,
+    pub tls: GrpcTlsSettings,
+}
+
+impl Default for GrpcTransportSettings {
+    fn default() -> Self {
+        Self { port: DEFAULT_GRPC_PORT, deadline_ms: 5_000, connect_timeout_ms: 3_000, keep_alive_secs: 10, tls: GrpcTlsSettings::default() }
+    }
+}
+
+/// 协同设置
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct CollaborationSettings {
+    pub transport: CollaborationTransport,
+    pub websocket_port: u16,
+    pub grpc: GrpcTransportSettings,
+}
+
+impl Default for CollaborationSettings {
+    fn default() -> Self {
+        Self { transport: CollaborationTransport::default(), websocket_port: DEFAULT_WEBSOCKET_PORT, grpc: GrpcTransportSettings::default() }
+    }
+}
+
+impl CollaborationSettings {
+    /// 当前传输方式的默认端口
+    pub fn default_port(&self) -> u16 {
+        match self.transport {
+            CollaborationTransport::WebSocket => self.websocket_port,
+            CollaborationTransport::Grpc => self.grpc.port,
+        }
+    }
+
+    fn validated(self) -> Result<Self, String> {
+        if self.transport == CollaborationTransport::Grpc && !cfg!(feature = "grpc-bridge") {
+            return Err(GRPC_DISABLED_ERROR.to_string());
+        }
+        if self.websocket_port == 0 || self.grpc.port == 0 {
+            return Err("端口必须大于 0".to_string());
+        }
+        if self.grpc.deadline_ms == 0 || self.grpc.connect_timeout_ms == 0 || self.grpc.keep_alive_secs == 0 {
+            return Err("gRPC 截止时间、连接超时与保活间隔必须大于 0".to_string());
+        }
+        let tls = &self.grpc.tls;
+        if tls.client_cert_path.is_some() != tls.client_key_path.is_some() {
+            return Err("客户端证书与私钥需要同时配置".to_string());
+        }
+        let paths = [&tls.ca_cert_path, &tls.client_cert_path, &tls.client_key_path];
+        if paths.into_iter().flatten().any(|path| !path.is_absolute()) {
+            return Err("证书路径必须是绝对路径".to_string());
+        }
+        Ok(self)
+    }
+}
+
+/// 协同设置存储
+pub struct CollaborationSettingsStore {
+    dir: PathBuf,
+    settings: CollaborationSettings,
+}
+
+impl CollaborationSettingsStore {
+    pub fn global() -> &'static Mutex<CollaborationSettingsStore> {
+        COLLABORATION_SETTINGS_STORE.get_or_init(|| {
+            let dir = dirs::data_dir().unwrap_or_else(|| PathBuf::from(".")).join("employee-gui");
+            Mutex::new(Self::open(dir))
+        })
+    }
+
+    /// 读取设置；文件缺失、损坏或选择了当前构建不支持的传输时回退到默认设置
+    pub fn open(dir: PathBuf) -> Self {
+        let settings = std::fs::read_to_string(dir.join(SETTINGS_FILE_NAME))
+            .ok()
+            .and_then(|content| serde_json::from_str::<CollaborationSettings>(&content).ok())
+            .and_then(|settings| settings.validated().ok())
+            .unwrap_or_default();
+        Self { dir, settings }
+    }
+
+    pub fn settings(&self) -> &CollaborationSettings {
+        &self.settings
+    }
+
+    pub fn save(&mut self, settings: CollaborationSettings) -> Result<CollaborationSettings, String> {
+        let settings = settings.validated()?;
+        std::fs::create_dir_all(&self.dir).map_err(|e| e.to_string())?;
+        let content = serde_json::to_string_pretty(&settings).map_err(|e| e.to_string())?;
+        std::fs::write(self.dir.join(SETTINGS_FILE_NAME), content).map_err(|e| format!("保存协同设置失败: {}", e))?;
+        self.settings = settings.clone();
+        Ok(settings)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_settings_validation_and_persistence() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut store = CollaborationSettingsStore::open(dir.path().to_path_buf());
+        assert_eq!(store.settings(), &CollaborationSettings::default());
+        assert_eq!(store.settings().default_port(), DEFAULT_WEBSOCKET_PORT);
+
+        let mut unpaired = CollaborationSettings::default();
+        unpaired.grpc.tls.client_cert_path = Some(dir.path().join("client.pem"));
+        assert!(store.save(unpaired).is_err());
+
+        let mut grpc = CollaborationSettings { transport: CollaborationTransport::Grpc, ..Default::default() };
+        grpc.grpc.tls = GrpcTlsSettings { enabled: true, domain_name: Some("phone.local".into()), ..Default::default() };
+        let saved = store.save(grpc.clone());
+        if cfg!(feature = "grpc-bridge") {
+            assert_eq!(saved.unwrap().default_port(), DEFAULT_GRPC_PORT);
+            assert_eq!(CollaborationSettingsStore::open(dir.path().to_path_buf()).settings(), &grpc);
+        } else {
+            assert_eq!(saved.unwrap_err(), GRPC_DISABLED_ERROR);
+        }
+
+        let json = serde_json::to_value(CollaborationSettings::default()).unwrap();
+        assert_eq!(json["transport"], "websocket");
+        assert_eq!(json["grpc"]["deadlineMs"], 5_000);
+    }
+}
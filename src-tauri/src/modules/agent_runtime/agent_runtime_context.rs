@@ -0,0 +1,209 @@
+// src-tauri/src/modules/agent_runtime/agent_runtime_context.rs
+// module: agent_runtime | layer: tauri-plugin | role: 对话上下文管理
+// summary: 按 token 预算组装自主循环的上下文 - 固定项（目标、最近屏幕）常驻，较早轮次用廉价模型摘要，保证不超过模型上限
+
+use super::*;
+use crate::core::domain::agent::estimate_tokens;
+use std::collections::VecDeque;
+use std::time::Duration;
+
+/// 未配置模型时使用的上下文预算（token）
+pub const DEFAULT_HISTORY_BUDGET_TOKENS: usize = 6000;
+/// 摘要时保留原文的最近轮次数
+const KEEP_RECENT_TURNS: usize = 4;
+/// 超过预算的该比例时触发摘要（百分比）
+const COMPACT_THRESHOLD_PERCENT: usize = 75;
+/// 摘要调用超时（秒），失败时退回本地摘要
+const SUMMARY_TIMEOUT_SECS: u64 = 30;
+/// 分段分隔符
+const SECTION_SEPARATOR: &str = "\n---\n";
+
+/// 自主循环的上下文
+///
+/// 组装顺序：固定项（按插入顺序）→ 早期摘要 → 最近轮次。
+#[derive(Debug, Clone)]
+pub struct ContextManager {
+    budget_tokens: usize,
+    pinned: Vec<(String, String)>,
+    summary: Option<String>,
+    turns: VecDeque<String>,
+}
+
+impl ContextManager {
+    pub fn new(budget_tokens: usize) -> Self {
+        Self {
+            budget_tokens: budget_tokens.max(256),
+            pinned: Vec::new(),
+            summary: None,
+            turns: VecDeque::new(),
+        }
+    }
+
+    /// 设置固定项；同名固定项原位替换（例如每轮更新的"最近屏幕"）
+    pub fn pin(&mut self, key: &str, content: impl Into<String>) {
+        let content = content.into();
+        match self.pinned.iter_mut().find(|(k, _)| k == key) {
+            Some(slot) => slot.1 = content,
+            None => self.pinned.push((key.to_string(), content)),
+        }
+    }
+
+    /// 追加一轮记录（单条不超过预算的四分之一）
+    pub fn push_turn(&mut self, content: impl Into<String>) {
+        let content = content.into();
+        self.turns.push_back(truncate_to_tokens(&content, self.budget_tokens / 4));
+    }
+
+    pub fn summary(&self) -> Option<&str> {
+        self.summary.as_deref()
+    }
+
+    /// 当前全部内容的估算 token 数
+    pub fn total_tokens(&self) -> usize {
+        self.sections().iter().map(|s| estimate_tokens(s)).sum()
+    }
+
+    /// 是否需要摘要较早轮次
+    pub fn needs_compaction(&self) -> bool {
+        self.turns.len() > KEEP_RECENT_TURNS
+            && self.total_tokens() * 100 > self.budget_tokens * COMPACT_THRESHOLD_PERCENT
+    }
+
+    /// 取出待摘要的较早轮次（保留最近几轮原文）
+    pub fn take_turns_for_summary(&mut self) -> Vec<String> {
+        let count = self.turns.len().saturating_sub(KEEP_RECENT_TURNS);
+        self.turns.drain(..count).collect()
+    }
+
+    /// 写入摘要（不超过预算的六分之一）
+    pub fn apply_summary(&mut self, summary: &str) {
+        self.summary = Some(truncate_to_tokens(summary.trim(), self.budget_tokens / 6));
+    }
+
+    /// 组装上下文，保证不超过预算
+    ///
+    /// 先丢弃最旧的轮次（至少保留最后一轮），仍超出时逐段截断最长的部分。
+    pub fn assemble(&self) -> String {
+        let mut sections = self.sections();
+        let fixed = self.pinned.len() + usize::from(self.summary.is_some());
+        let separator_tokens = estimate_tokens(SECTION_SEPARATOR);
+        let total = |sections: &[String]| {
+            sections.iter().map(|s| estimate_tokens(s) + separator_tokens).sum::<usize>()
+        };
+
+        while total(&sections) > self.budget_tokens && sections.len() > fixed + 1 {
+            sections.remove(fixed);
+        }
+
+        for _ in 0..sections.len() * 2 {
+            let over = total(&sections).saturating_sub(self.budget_tokens);
+            if over == 0 {
+                break;
+            }
+            let Some((idx, current)) = sections.iter().map(|s| estimate_tokens(s)).enumerate().max_by_key(|(_, t)| *t) else {
+                break;
+            };
+            sections[idx] = truncate_to_tokens(&sections[idx], current.saturating_sub(over).max(1));
+            if estimate_tokens(&sections[idx]) >= current {
+                break;
+            }
+        }
+        sections.join(SECTION_SEPARATOR)
+    }
+
+    fn sections(&self) -> Vec<String> {
+        let mut sections: Vec<String> = self.pinned.iter().map(|(_, content)| content.clone()).collect();
+        if let Some(summary) = &self.summary {
+            sections.push(format!("System: [早期执行摘要] {}", summary));
+        }
+        sections.extend(self.turns.iter().cloned());
+        sections
+    }
+}
+
+/// 摘要提示词：合并已有摘要与新取出的轮次
+pub fn build_summary_prompt(previous_summary: Option<&str>, turns: &[String]) -> String {
+    format!(r#"请把下面的 Agent 执行记录压缩成一段简短摘要，供后续决策参考。
+必须保留：已执行的动作及结果、失败原因、已到达的页面、尚未完成的事项。
+不要输出 JSON，不超过 200 字。
+
+## 已有摘要
+{previous}
+
+## 新增记录
+{turns}"#,
+        previous = previous_summary.unwrap_or("（无）"),
+        turns = turns.join(SECTION_SEPARATOR)
+    )
+}
+
+/// 本地摘要（AI 摘要不可用时）：每轮保留首行开头
+pub fn fallback_summary(previous_summary: Option<&str>, turns: &[String]) -> String {
+    let mut lines: Vec<String> = previous_summary.map(|s| vec![s.to_string()]).unwrap_or_default();
+    lines.extend(turns.iter().map(|turn| {
+        let first_line = turn.lines().next().unwrap_or_default();
+        first_line.chars().take(80).collect::<String>()
+    }));
+    lines.join("；")
+}
+
+/// 需要时用廉价模型摘要较早轮次；摘要失败时退回本地摘要，不中断循环
+pub async fn compact_context(context: &mut ContextManager, agent_state: &AgentState) {
+    if !context.needs_compaction() {
+        return;
+    }
+    let turns = context.take_turns_for_summary();
+    let prompt = build_summary_prompt(context.summary(), &turns);
+    let timeout = Duration::from_secs(SUMMARY_TIMEOUT_SECS);
+
+    let summary = match with_deadline(agent_state.summarize_with_ai(&prompt), timeout, None).await {
+        Ok(summary) if !summary.trim().is_empty() => summary,
+        Ok(_) => fallback_summary(context.summary(), &turns),
+        Err(e) => {
+            warn!("⚠️ 历史摘要失败，使用本地摘要: {}", e);
+            fallback_summary(context.summary(), &turns)
+        }
+    };
+    info!("📜 已摘要 {} 轮较早记录", turns.len());
+    context.apply_summary(&summary);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn assembled_context_stays_within_budget() {
+        let mut context = ContextManager::new(400);
+        context.pin("goal", "目标：打开抖音并关注账号");
+        for i in 0..20 {
+            context.push_turn(format!("AI: 第{}轮 {}", i, "思考".repeat(40)));
+        }
+        context.pin("screen", format!("System: {}", "屏幕".repeat(500)));
+
+        let assembled = context.assemble();
+        assert!(estimate_tokens(&assembled) <= 400);
+        assert!(assembled.starts_with("目标：打开抖音并关注账号"));
+        assert!(assembled.contains("第19轮"));
+    }
+
+    #[test]
+    fn compaction_keeps_recent_turns_and_pins() {
+        let mut context = ContextManager::new(300);
+        context.pin("goal", "目标");
+        for i in 0..10 {
+            context.push_turn(format!("System: 动作 {} 执行成功 {}", i, "结果".repeat(10)));
+        }
+        assert!(context.needs_compaction());
+
+        let turns = context.take_turns_for_summary();
+        assert_eq!(turns.len(), 10 - KEEP_RECENT_TURNS);
+        context.apply_summary(&fallback_summary(None, &turns));
+        context.pin("goal", "新目标");
+
+        let assembled = context.assemble();
+        assert!(assembled.starts_with("新目标"));
+        assert!(assembled.contains("[早期执行摘要] System: 动作 0"));
+        assert!(assembled.contains("动作 9"));
+    }
+}
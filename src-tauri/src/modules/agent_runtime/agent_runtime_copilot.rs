@@ -0,0 +1,245 @@
+// src-tauri/src/modules/agent_runtime/agent_runtime_copilot.rs
+// module: agent_runtime | layer: tauri-plugin | role: 副驾驶模式
+// summary: 用户手动操作镜像设备时观察屏幕变化，询问 AI 下一步建议并以事件推送（只建议，不执行）
+
+use super::*;
+use crate::ai::cancellation::DEFAULT_AI_CALL_TIMEOUT_SECS;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+/// 默认轮询间隔（毫秒）
+pub const DEFAULT_COPILOT_POLL_MS: u64 = 1500;
+/// 最小轮询间隔，避免频繁 dump 拖慢设备
+const MIN_COPILOT_POLL_MS: u64 = 500;
+/// 提示词中保留的最近屏幕数
+const RECENT_SCREENS_IN_PROMPT: usize = 3;
+
+/// 副驾驶统计
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CopilotStats {
+    pub goal: Option<String>,
+    pub device_id: Option<String>,
+    /// 观察到的屏幕变化次数（近似用户操作次数）
+    pub observed_changes: u32,
+    pub suggestions: u32,
+    pub failed_suggestions: u32,
+    pub last_suggestion: Option<CopilotSuggestion>,
+}
+
+/// 一条下一步建议
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CopilotSuggestion {
+    pub suggestion_id: String,
+    pub thought: String,
+    pub action: String,
+    pub params: serde_json::Value,
+}
+
+/// 屏幕变化检测：屏幕摘要连续两次一致（已稳定）且与上次建议时不同，才触发一次建议
+#[derive(Debug, Default)]
+pub struct ScreenChangeDetector {
+    pending: Option<u64>,
+    last_suggested: Option<u64>,
+}
+
+impl ScreenChangeDetector {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 输入本次屏幕摘要，返回是否应请求新的建议
+    pub fn observe(&mut self, screen_summary: &str) -> bool {
+        let mut hasher = DefaultHasher::new();
+        screen_summary.hash(&mut hasher);
+        let signature = hasher.finish();
+
+        if self.last_suggested == Some(signature) {
+            self.pending = None;
+            return false;
+        }
+        if self.pending == Some(signature) {
+            self.pending = None;
+            self.last_suggested = Some(signature);
+            return true;
+        }
+        self.pending = Some(signature);
+        false
+    }
+}
+
+/// 构建副驾驶提示词
+pub fn build_copilot_prompt(goal: &str, screen_summary: &str, recent_screens: &[String]) -> String {
+    let history = if recent_screens.is_empty() {
+        "（刚开始观察）".to_string()
+    } else {
+        recent_screens
+            .iter()
+            .enumerate()
+            .map(|(i, s)| format!("### 之前的屏幕 {}\n{}", i + 1, s))
+            .collect::<Vec<_>>()
+            .join("\n")
+    };
+
+    format!(r#"用户正在手动操作手机，你作为副驾驶只提供建议，不会执行任何动作。
+
+## 用户目标
+{goal}
+
+## 用户最近经过的屏幕
+{history}
+
+## 当前屏幕
+{screen_summary}
+
+## 要求
+如果你来操作，为了达成目标下一步会做什么？只给出一个动作。
+
+## 输出格式（JSON）
+{{
+    "thought": "判断依据",
+    "action": "tap_element/tap/swipe/input_text/press_key/launch_app/wait/done",
+    "params": {{ 参数 }}
+}}
+
+请直接返回 JSON，不要包含其他内容。"#,
+        goal = goal,
+        history = history,
+        screen_summary = screen_summary
+    )
+}
+
+/// 从 AI 响应解析建议
+pub fn parse_copilot_response(response: &str) -> Result<CopilotSuggestion, String> {
+    let parsed = parse_structured(response, &copilot_schema())
+        .map_err(|errors| errors.join("; "))?
+        .value;
+
+    let action = parsed
+        .get("action")
+        .and_then(|v| v.as_str())
+        .filter(|a| !a.is_empty())
+        .ok_or("建议缺少 action")?;
+
+    Ok(CopilotSuggestion {
+        suggestion_id: uuid::Uuid::new_v4().to_string(),
+        thought: parsed.get("thought").and_then(|v| v.as_str()).unwrap_or_default().to_string(),
+        action: action.to_string(),
+        params: parsed.get("params").cloned().unwrap_or(serde_json::Value::Null),
+    })
+}
+
+/// 副驾驶观察循环
+///
+/// 只走观察链路（UI dump → 摘要 → AI），从不调用 `execute_agent_tool`。
+pub async fn run_copilot_loop<R: Runtime>(
+    mut stop_rx: watch::Receiver<bool>,
+    event_log: Arc<RwLock<Vec<AgentEvent>>>,
+    stats: Arc<RwLock<CopilotStats>>,
+    app_handle: AppHandle<R>,
+    goal: String,
+    device_id: String,
+    poll_interval_ms: u64,
+) {
+    use tokio::time::Duration;
+
+    let adb_path = crate::utils::adb_utils::get_adb_path();
+    let interval = Duration::from_millis(poll_interval_ms.max(MIN_COPILOT_POLL_MS));
+    let mut detector = ScreenChangeDetector::new();
+    let mut recent_screens: Vec<String> = Vec::new();
+    // 停止观察时同时中断进行中的 AI 请求
+    let ai_cancel = CancellationToken::from_signal(stop_rx.clone());
+    let ai_timeout = Duration::from_secs(DEFAULT_AI_CALL_TIMEOUT_SECS);
+
+    info!("🧭 副驾驶开始观察: goal={}, device={}", goal, device_id);
+
+    while !*stop_rx.borrow() {
+        let summary = match get_screen_xml(&adb_path, &device_id).await {
+            Ok(xml) => extract_screen_summary(&xml),
+            Err(e) => {
+                warn!("⚠️ 副驾驶获取屏幕失败: {}", e);
+                String::new()
+            }
+        };
+
+        if !summary.is_empty() && detector.observe(&summary) {
+            stats.write().await.observed_changes += 1;
+
+            let Some(agent_state) = app_handle.try_state::<AgentState>() else {
+                error!("❌ AgentState 不可用，副驾驶停止");
+                break;
+            };
+            let prompt = build_copilot_prompt(&goal, &summary, &recent_screens);
+            let suggestion = with_deadline(agent_state.chat_with_ai(&prompt), ai_timeout, Some(&ai_cancel))
+                .await
+                .map_err(|e| e.to_string())
+                .and_then(|response| parse_copilot_response(&response));
+
+            match suggestion {
+                Err(_) if ai_cancel.is_cancelled() => break,
+                Ok(suggestion) => {
+                    info!("💡 副驾驶建议: {} {}", suggestion.action, suggestion.params);
+                    {
+                        let mut s = stats.write().await;
+                        s.suggestions += 1;
+                        s.last_suggestion = Some(suggestion.clone());
+                    }
+                    send_agent_event(&event_log, &app_handle, AgentEvent::Suggestion {
+                        suggestion_id: suggestion.suggestion_id,
+                        action: suggestion.action,
+                        params: suggestion.params,
+                        thought: suggestion.thought,
+                    }).await;
+                }
+                Err(e) => {
+                    warn!("⚠️ 副驾驶建议生成失败: {}", e);
+                    stats.write().await.failed_suggestions += 1;
+                    send_agent_event(&event_log, &app_handle, AgentEvent::Error {
+                        message: format!("副驾驶建议生成失败: {}", e),
+                    }).await;
+                }
+            }
+
+            recent_screens.push(summary);
+            if recent_screens.len() > RECENT_SCREENS_IN_PROMPT {
+                recent_screens.remove(0);
+            }
+        }
+
+        tokio::select! {
+            _ = tokio::time::sleep(interval) => {}
+            _ = stop_rx.changed() => {}
+        }
+    }
+
+    info!("🧭 副驾驶结束观察");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detector_waits_for_stable_screen_and_skips_repeats() {
+        let mut detector = ScreenChangeDetector::new();
+        assert!(!detector.observe("首页"));
+        assert!(detector.observe("首页"));
+        // 屏幕没变，不重复建议
+        assert!(!detector.observe("首页"));
+        // 过渡动画中的中间帧不触发
+        assert!(!detector.observe("过渡"));
+        assert!(!detector.observe("搜索页"));
+        assert!(detector.observe("搜索页"));
+    }
+
+    #[test]
+    fn parses_suggestion_from_wrapped_response() {
+        let response = "好的：\n{\"thought\": \"搜索框可见\", \"action\": \"tap_element\", \"params\": {\"text\": \"搜索\"}}";
+        let suggestion = parse_copilot_response(response).unwrap();
+        assert_eq!(suggestion.action, "tap_element");
+        assert_eq!(suggestion.params["text"], "搜索");
+        assert!(parse_copilot_response("{\"thought\": \"无\"}").is_err());
+        assert!(parse_copilot_response("没有 JSON").is_err());
+    }
+}
@@ -0,0 +1,427 @@
+// src-tauri/src/modules/agent_runtime/agent_runtime_file_write.rs
+// module: agent_runtime | layer: tauri-plugin | role: 沙箱写文件工具
+// summary: write_file 只能写入可配置的沙箱目录（相对路径、大小上限、扩展名白名单），监督模式下强制人工审批，每次写入都记入运行事件与写入台账
+
+use super::*;
+use chrono::{DateTime, Utc};
+use sha2::{Digest, Sha256};
+use std::io::Write;
+use std::path::{Component, Path};
+use std::sync::{Mutex, OnceLock};
+
+/// 工具名
+pub const WRITE_FILE_TOOL: &str = "write_file";
+const CONFIG_FILE_NAME: &str = "agent_file_sandbox.json";
+const JOURNAL_FILE_NAME: &str = "agent_file_journal.jsonl";
+const DEFAULT_ROOT_DIR_NAME: &str = "agent_outputs";
+const DEFAULT_MAX_FILE_BYTES: u64 = 1024 * 1024;
+const APPROVAL_POLL_MS: u64 = 200;
+
+static FILE_SANDBOX_STORE: OnceLock<Mutex<FileSandboxStore>> = OnceLock::new();
+
+/// 写文件沙箱配置
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct FileSandboxConfig {
+    /// 沙箱根目录；为空时使用 数据目录/employee-gui/agent_outputs
+    pub root_dir: Option<PathBuf>,
+    /// 单个文件写入后的最大字节数（追加时按追加后的总大小计算）
+    pub max_file_bytes: u64,
+    /// 允许的扩展名（小写、不带点）
+    pub allowed_extensions: Vec<String>,
+    /// 是否允许覆盖已存在的文件（追加不受此限制）
+    pub allow_overwrite: bool,
+}
+
+impl Default for FileSandboxConfig {
+    fn default() -> Self {
+        Self {
+            root_dir: None,
+            max_file_bytes: DEFAULT_MAX_FILE_BYTES,
+            allowed_extensions: ["txt", "md", "csv", "json"].iter().map(|s| s.to_string()).collect(),
+            allow_overwrite: false,
+        }
+    }
+}
+
+impl FileSandboxConfig {
+    pub fn root(&self, data_dir: &Path) -> PathBuf {
+        self.root_dir.clone().unwrap_or_else(|| data_dir.join(DEFAULT_ROOT_DIR_NAME))
+    }
+
+    /// 扩展名统一成小写、去掉前导点并去重
+    fn normalized(mut self) -> Result<Self, String> {
+        if self.max_file_bytes == 0 {
+            return Err("单个文件大小上限必须大于 0".to_string());
+        }
+        if self.root_dir.as_ref().is_some_and(|dir| !dir.is_absolute()) {
+            return Err("沙箱目录必须是绝对路径".to_string());
+        }
+        let mut extensions: Vec<String> = self
+            .allowed_extensions
+            .iter()
+            .map(|ext| ext.trim().trim_start_matches('.').to_lowercase())
+            .filter(|ext| !ext.is_empty())
+            .collect();
+        extensions.sort();
+        extensions.dedup();
+        if extensions.is_empty() {
+            return Err("至少需要允许一种扩展名".to_string());
+        }
+        self.allowed_extensions = extensions;
+        Ok(self)
+    }
+
+    /// 校验 AI 给出的路径：只接受沙箱内的相对路径与白名单扩展名，返回规整后的相对路径
+    pub fn validate_relative(&self, relative: &str) -> Result<PathBuf, String> {
+        let mut cleaned = PathBuf::new();
+        for component in Path::new(relative.trim()).components() {
+            match component {
+                Component::Normal(part) => cleaned.push(part),
+                Component::CurDir => {}
+                _ => return Err(format!("路径必须是沙箱目录内的相对路径: {}", relative)),
+            }
+        }
+        if cleaned.as_os_str().is_empty() {
+            return Err("缺少文件路径".to_string());
+        }
+        let extension = cleaned.extension().map(|e| e.to_string_lossy().to_lowercase()).unwrap_or_default();
+        if !self.allowed_extensions.contains(&extension) {
+            return Err(format!(
+                "不允许写入扩展名 '{}'，允许: {}",
+                extension,
+                self.allowed_extensions.join(", ")
+            ));
+        }
+        Ok(cleaned)
+    }
+}
+
+/// 一次成功写入的结果
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WrittenFile {
+    /// 相对沙箱目录的路径（统一使用 `/`）
+    pub path: String,
+    pub absolute_path: String,
+    pub bytes_written: u64,
+    /// 写入后的文件大小
+    pub file_size: u64,
+    /// 写入后整个文件的 SHA-256
+    pub sha256: String,
+    pub appended: bool,
+}
+
+/// 写入台账的一条记录
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileWriteRecord {
+    pub written_at: DateTime<Utc>,
+    pub goal_id: String,
+    pub device_id: String,
+    /// 是否经过人工审批
+    pub approved: bool,
+    #[serde(flatten)]
+    pub file: WrittenFile,
+}
+
+/// 在沙箱目录内写文件：拒绝越界路径与符号链接，超过大小上限时不落盘
+pub fn write_in_sandbox(
+    config: &FileSandboxConfig,
+    root: &Path,
+    relative: &str,
+    content: &str,
+    append: bool,
+) -> Result<WrittenFile, String> {
+    let cleaned = config.validate_relative(relative)?;
+    std::fs::create_dir_all(root).map_err(|e| format!("创建沙箱目录失败: {}", e))?;
+    let root = root.canonicalize().map_err(|e| format!("无法访问沙箱目录: {}", e))?;
+
+    let target = root.join(&cleaned);
+    let parent = target.parent().unwrap_or(&root);
+    std::fs::create_dir_all(parent).map_err(|e| format!("创建目录失败: {}", e))?;
+    // 已存在的子目录可能是指向沙箱外的符号链接
+    let parent = parent.canonicalize().map_err(|e| e.to_string())?;
+    if !parent.starts_with(&root) {
+        return Err(format!("路径越出沙箱目录: {}", relative));
+    }
+    let target = parent.join(target.file_name().ok_or("缺少文件名")?);
+
+    let existing = match std::fs::symlink_metadata(&target) {
+        Ok(meta) if meta.file_type().is_symlink() || !meta.is_file() => {
+            return Err(format!("目标不是普通文件: {}", relative));
+        }
+        Ok(meta) => Some(meta.len()),
+        Err(_) => None,
+    };
+    if existing.is_some() && !append && !config.allow_overwrite {
+        return Err(format!("文件已存在且不允许覆盖: {}（可改用 append 追加）", relative));
+    }
+
+    let bytes_written = content.len() as u64;
+    let file_size = if append { existing.unwrap_or(0) + bytes_written } else { bytes_written };
+    if file_size > config.max_file_bytes {
+        return Err(format!("文件大小 {} 字节超过上限 {} 字节", file_size, config.max_file_bytes));
+    }
+
+    let mut options = std::fs::OpenOptions::new();
+    if append {
+        options.create(true).append(true);
+    } else {
+        options.create(true).write(true).truncate(true);
+    }
+    let mut file = options.open(&target).map_err(|e| format!("打开文件失败: {}", e))?;
+    file.write_all(content.as_bytes()).map_err(|e| format!("写入文件失败: {}", e))?;
+    file.flush().map_err(|e| format!("写入文件失败: {}", e))?;
+    drop(file);
+
+    let written = std::fs::read(&target).map_err(|e| format!("读取已写入文件失败: {}", e))?;
+    Ok(WrittenFile {
+        path: cleaned.components().map(|c| c.as_os_str().to_string_lossy()).collect::<Vec<_>>().join("/"),
+        absolute_path: target.to_string_lossy().to_string(),
+        bytes_written,
+        file_size: written.len() as u64,
+        sha256: hex::encode(Sha256::digest(&written)),
+        appended: append,
+    })
+}
+
+/// 沙箱配置与写入台账（JSONL，只追加）
+pub struct FileSandboxStore {
+    dir: PathBuf,
+    config: FileSandboxConfig,
+}
+
+impl FileSandboxStore {
+    pub fn global() -> &'static Mutex<FileSandboxStore> {
+        FILE_SANDBOX_STORE.get_or_init(|| {
+            let dir = dirs::data_dir().unwrap_or_else(|| PathBuf::from(".")).join("employee-gui");
+            Mutex::new(Self::open(dir))
+        })
+    }
+
+    pub fn open(dir: PathBuf) -> Self {
+        let config = std::fs::read_to_string(dir.join(CONFIG_FILE_NAME))
+            .ok()
+            .and_then(|content| serde_json::from_str::<FileSandboxConfig>(&content).ok())
+            .and_then(|config| config.normalized().ok())
+            .unwrap_or_default();
+        Self { dir, config }
+    }
+
+    pub fn config(&self) -> &FileSandboxConfig {
+        &self.config
+    }
+
+    pub fn root(&self) -> PathBuf {
+        self.config.root(&self.dir)
+    }
+
+    pub fn save_config(&mut self, config: FileSandboxConfig) -> Result<FileSandboxConfig, String> {
+        let config = config.normalized()?;
+        std::fs::create_dir_all(&self.dir).map_err(|e| e.to_string())?;
+        let content = serde_json::to_string_pretty(&config).map_err(|e| e.to_string())?;
+        std::fs::write(self.dir.join(CONFIG_FILE_NAME), content).map_err(|e| format!("保存写文件沙箱配置失败: {}", e))?;
+        self.config = config.clone();
+        Ok(config)
+    }
+
+    pub fn append_record(&self, record: &FileWriteRecord) -> Result<(), String> {
+        std::fs::create_dir_all(&self.dir).map_err(|e| e.to_string())?;
+        let line = serde_json::to_string(record).map_err(|e| e.to_string())?;
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(self.dir.join(JOURNAL_FILE_NAME))
+            .map_err(|e| format!("打开写入台账失败: {}", e))?;
+        writeln!(file, "{}", line).map_err(|e| format!("写入台账失败: {}", e))
+    }
+
+    /// 最近的写入记录（新的在前）
+    pub fn records(&self, limit: usize) -> Vec<FileWriteRecord> {
+        let content = std::fs::read_to_string(self.dir.join(JOURNAL_FILE_NAME)).unwrap_or_default();
+        content
+            .lines()
+            .rev()
+            .filter_map(|line| serde_json::from_str::<FileWriteRecord>(line).ok())
+            .take(limit)
+            .collect()
+    }
+}
+
+/// 等待用户批准 / 拒绝待审批的写入（只用于前台目标）；返回是否批准
+async fn wait_for_approval(
+    runtime: &SharedAgentRuntime,
+    stop_rx: &watch::Receiver<bool>,
+    params: &serde_json::Value,
+) -> Result<bool, String> {
+    {
+        let mut rt = runtime.write().await;
+        rt.set_pending_action(WRITE_FILE_TOOL.to_string(), params.to_string());
+        if let Err(e) = rt.transition_approval_required() {
+            rt.clear_pending_action();
+            return Err(format!("当前状态无法请求审批: {}", e));
+        }
+        record_transition(&rt);
+    }
+
+    loop {
+        tokio::time::sleep(std::time::Duration::from_millis(APPROVAL_POLL_MS)).await;
+        let mut rt = runtime.write().await;
+        let approved = match rt.current_state() {
+            AgentRunState::WaitingForApproval if !*stop_rx.borrow() => continue,
+            AgentRunState::Executing => true,
+            _ => false,
+        };
+        rt.clear_pending_action();
+        record_transition(&rt);
+        return Ok(approved);
+    }
+}
+
+/// 执行 write_file：沙箱检查 → 路径 / 大小预检 → （监督模式）人工审批 → 写入 → 台账与运行事件
+pub(super) async fn run_write_file_tool<R: Runtime>(
+    runtime: &SharedAgentRuntime,
+    stop_rx: &watch::Receiver<bool>,
+    event_log: &Arc<RwLock<Vec<AgentEvent>>>,
+    app_handle: &AppHandle<R>,
+    goal_id: &str,
+    device_id: &str,
+    params: &serde_json::Value,
+) -> ToolExecutionResult {
+    let fail = |message: String| ToolExecutionResult { success: false, message };
+
+    if let Err(e) = crate::core::application::sandbox_service::check_action(WRITE_FILE_TOOL, Some(device_id), None) {
+        return fail(e.to_string());
+    }
+    let path = params.get("path").and_then(|v| v.as_str()).unwrap_or("");
+    let content = params.get("content").and_then(|v| v.as_str()).unwrap_or("");
+    let append = params.get("append").and_then(|v| v.as_bool()).unwrap_or(false);
+
+    let (config, root) = match FileSandboxStore::global().lock() {
+        Ok(store) => (store.config().clone(), store.root()),
+        Err(e) => return fail(e.to_string()),
+    };
+    // 注定失败的写入不必打扰用户审批
+    if let Err(e) = config.validate_relative(path) {
+        return fail(e);
+    }
+    if content.len() as u64 > config.max_file_bytes {
+        return fail(format!("内容 {} 字节超过上限 {} 字节", content.len(), config.max_file_bytes));
+    }
+
+    let supervised = runtime.read().await.mode() == AgentMode::Supervised;
+    if supervised {
+        // 审批命令只作用于前台运行时，队列目标无法等到审批
+        if goal_id != PRIMARY_GOAL_ID {
+            return fail("监督模式下队列目标不能写文件（无法人工审批）".to_string());
+        }
+        send_goal_event(goal_id, event_log, app_handle, AgentEvent::ApprovalRequired {
+            action: format!("{} {}（{} 字节{}）", WRITE_FILE_TOOL, path, content.len(), if append { "，追加" } else { "" }),
+            risk_level: "Medium".to_string(),
+        }).await;
+        match wait_for_approval(runtime, stop_rx, params).await {
+            Ok(true) => {}
+            Ok(false) => return fail(format!("用户拒绝写入 {}", path)),
+            Err(e) => return fail(e),
+        }
+    }
+
+    let result = write_in_sandbox(&config, &root, path, content, append);
+    if supervised {
+        // 批准后状态机处于 Executing，写完回到思考
+        let mut rt = runtime.write().await;
+        let _ = rt.transition_action_completed();
+        let _ = rt.transition_start_thinking();
+        record_transition(&rt);
+    }
+    let written = match result {
+        Ok(written) => written,
+        Err(e) => return fail(e),
+    };
+
+    let record = FileWriteRecord {
+        written_at: Utc::now(),
+        goal_id: goal_id.to_string(),
+        device_id: device_id.to_string(),
+        approved: supervised,
+        file: written.clone(),
+    };
+    let journaled = FileSandboxStore::global().lock().map_err(|e| e.to_string()).and_then(|store| store.append_record(&record));
+    if let Err(e) = journaled {
+        warn!("⚠️ 写入台账失败: {}", e);
+    }
+    info!("📝 Agent 写入文件: {} ({} 字节, sha256={})", written.absolute_path, written.bytes_written, written.sha256);
+    send_goal_event(goal_id, event_log, app_handle, AgentEvent::FileWritten {
+        path: written.path.clone(),
+        bytes: written.bytes_written,
+        sha256: written.sha256.clone(),
+        appended: written.appended,
+    }).await;
+
+    ToolExecutionResult {
+        success: true,
+        message: format!(
+            "已{} {}（{} 字节，文件共 {} 字节）",
+            if written.appended { "追加写入" } else { "写入" },
+            written.path,
+            written.bytes_written,
+            written.file_size
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn writes_only_inside_sandbox_within_limits() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().join(DEFAULT_ROOT_DIR_NAME);
+        let config = FileSandboxConfig { max_file_bytes: 16, ..FileSandboxConfig::default() };
+
+        for bad in ["../escape.txt", "/etc/passwd.txt", "", "notes.exe", "a/../../b.txt"] {
+            assert!(write_in_sandbox(&config, &root, bad, "x", false).is_err(), "{}", bad);
+        }
+
+        let written = write_in_sandbox(&config, &root, "./report/今日.TXT", "hello", false).unwrap();
+        assert_eq!((written.path.as_str(), written.file_size), ("report/今日.TXT", 5));
+        assert_eq!(written.sha256, hex::encode(Sha256::digest(b"hello")));
+        assert!(write_in_sandbox(&config, &root, "report/今日.TXT", "again", false).unwrap_err().contains("已存在"));
+        let appended = write_in_sandbox(&config, &root, "report/今日.TXT", " world", true).unwrap();
+        assert_eq!((appended.bytes_written, appended.file_size), (6, 11));
+        assert!(write_in_sandbox(&config, &root, "report/今日.TXT", "0123456789", true).unwrap_err().contains("上限"));
+        assert_eq!(std::fs::read_to_string(root.join("report/今日.TXT")).unwrap(), "hello world");
+
+        #[cfg(unix)]
+        {
+            let outside = dir.path().join("outside");
+            std::fs::create_dir_all(&outside).unwrap();
+            std::os::unix::fs::symlink(&outside, root.join("link")).unwrap();
+            assert!(write_in_sandbox(&config, &root, "link/leak.txt", "x", false).unwrap_err().contains("越出"));
+        }
+
+        let mut store = FileSandboxStore::open(dir.path().to_path_buf());
+        let saved = store
+            .save_config(FileSandboxConfig { allowed_extensions: vec![".CSV".into(), "csv".into()], ..config.clone() })
+            .unwrap();
+        assert_eq!(saved.allowed_extensions, vec!["csv".to_string()]);
+        assert!(store.save_config(FileSandboxConfig { max_file_bytes: 0, ..config }).is_err());
+        assert_eq!(FileSandboxStore::open(dir.path().to_path_buf()).config(), &saved);
+
+        for goal in ["current", "goal-2"] {
+            let record = FileWriteRecord {
+                written_at: Utc::now(),
+                goal_id: goal.to_string(),
+                device_id: "emulator-5554".to_string(),
+                approved: false,
+                file: appended.clone(),
+            };
+            store.append_record(&record).unwrap();
+        }
+        let records = store.records(10);
+        assert_eq!(records.len(), 2);
+        assert_eq!((records[0].goal_id.as_str(), records[0].file.sha256.as_str()), ("goal-2", appended.sha256.as_str()));
+    }
+}
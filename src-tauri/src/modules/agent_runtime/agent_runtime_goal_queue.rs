@@ -0,0 +1,346 @@
+// src-tauri/src/modules/agent_runtime/agent_runtime_goal_queue.rs
+// module: agent_runtime | layer: tauri-plugin | role: 多目标调度
+// summary: 目标队列按优先级排队，同一设备串行、不同设备可并行执行；每个目标独立运行时与停止信号，支持调整顺序、取消，并按目标推送进度
+
+use super::*;
+use chrono::{DateTime, Utc};
+use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
+
+use crate::services::concurrency::{self, WorkRequest};
+use crate::services::event_outbox;
+
+/// 已结束目标最多保留条数
+const MAX_FINISHED_GOALS: usize = 50;
+
+/// 队列中目标的状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum QueuedGoalStatus {
+    Queued,
+    Running,
+    Completed,
+    Failed,
+    Cancelled,
+}
+
+impl QueuedGoalStatus {
+    pub fn is_finished(self) -> bool {
+        matches!(self, Self::Completed | Self::Failed | Self::Cancelled)
+    }
+}
+
+/// 队列中的目标
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueuedGoal {
+    pub id: String,
+    pub goal: String,
+    pub device_id: String,
+    /// 越大越靠前；同优先级按提交顺序
+    pub priority: i32,
+    pub status: QueuedGoalStatus,
+    pub progress: u8,
+    pub progress_description: Option<String>,
+    /// 运行中的目标已请求取消
+    pub cancel_requested: bool,
+    pub error: Option<String>,
+    pub submitted_at: DateTime<Utc>,
+    pub started_at: Option<DateTime<Utc>>,
+    pub finished_at: Option<DateTime<Utc>>,
+}
+
+/// 目标队列（`goals` 中排队项的先后即执行顺序）
+#[derive(Debug)]
+pub struct GoalQueue {
+    goals: Vec<QueuedGoal>,
+    /// 为 false 时全局一次只执行一个目标
+    pub parallel_across_devices: bool,
+}
+
+impl Default for GoalQueue {
+    fn default() -> Self {
+        Self { goals: Vec::new(), parallel_across_devices: true }
+    }
+}
+
+impl GoalQueue {
+    /// 插入到优先级不低于它的排队目标之后
+    pub fn submit(&mut self, goal: &str, device_id: &str, priority: i32) -> QueuedGoal {
+        let queued = QueuedGoal {
+            id: uuid::Uuid::new_v4().to_string(),
+            goal: goal.to_string(),
+            device_id: device_id.to_string(),
+            priority,
+            status: QueuedGoalStatus::Queued,
+            progress: 0,
+            progress_description: None,
+            cancel_requested: false,
+            error: None,
+            submitted_at: Utc::now(),
+            started_at: None,
+            finished_at: None,
+        };
+        let index = self
+            .goals
+            .iter()
+            .position(|g| g.status == QueuedGoalStatus::Queued && g.priority < priority)
+            .unwrap_or(self.goals.len());
+        self.goals.insert(index, queued.clone());
+        queued
+    }
+
+    pub fn get(&self, id: &str) -> Option<&QueuedGoal> {
+        self.goals.iter().find(|g| g.id == id)
+    }
+
+    fn get_mut(&mut self, id: &str) -> Result<&mut QueuedGoal, String> {
+        self.goals.iter_mut().find(|g| g.id == id).ok_or_else(|| format!("目标不存在: {}", id))
+    }
+
+    /// 运行中 → 排队中（执行顺序）→ 已结束（新的在前）
+    pub fn list(&self) -> Vec<QueuedGoal> {
+        let by_status = |status: QueuedGoalStatus| self.goals.iter().filter(move |g| g.status == status).cloned();
+        let mut finished: Vec<_> = self.goals.iter().filter(|g| g.status.is_finished()).cloned().collect();
+        finished.sort_by(|a, b| b.finished_at.cmp(&a.finished_at));
+        by_status(QueuedGoalStatus::Running).chain(by_status(QueuedGoalStatus::Queued)).chain(finished).collect()
+    }
+
+    /// 把排队中的目标移到排队序列的第 `position` 位（从 0 开始，超出则放到末尾）
+    pub fn reorder(&mut self, id: &str, position: usize) -> Result<(), String> {
+        let index = self.goals.iter().position(|g| g.id == id).ok_or_else(|| format!("目标不存在: {}", id))?;
+        if self.goals[index].status != QueuedGoalStatus::Queued {
+            return Err("只能调整排队中的目标".to_string());
+        }
+        let goal = self.goals.remove(index);
+        let target = self
+            .goals
+            .iter()
+            .enumerate()
+            .filter(|(_, g)| g.status == QueuedGoalStatus::Queued)
+            .nth(position)
+            .map(|(i, _)| i)
+            .unwrap_or(self.goals.len());
+        self.goals.insert(target, goal);
+        Ok(())
+    }
+
+    /// 取消目标：排队中的直接取消；运行中的标记后由调用方发送停止信号
+    pub fn cancel(&mut self, id: &str) -> Result<QueuedGoal, String> {
+        let goal = self.get_mut(id)?;
+        match goal.status {
+            QueuedGoalStatus::Queued => {
+                goal.status = QueuedGoalStatus::Cancelled;
+                goal.finished_at = Some(Utc::now());
+            }
+            QueuedGoalStatus::Running => goal.cancel_requested = true,
+            status => return Err(format!("目标已结束: {:?}", status)),
+        }
+        Ok(goal.clone())
+    }
+
+    /// 取出可以开始的目标并标记为运行中：设备空闲，且未开启并行时全局只有一个在运行
+    pub fn take_runnable(&mut self, busy_devices: &HashSet<String>) -> Vec<QueuedGoal> {
+        let mut busy = busy_devices.clone();
+        busy.extend(self.goals.iter().filter(|g| g.status == QueuedGoalStatus::Running).map(|g| g.device_id.clone()));
+        let mut started = Vec::new();
+        for goal in self.goals.iter_mut().filter(|g| g.status == QueuedGoalStatus::Queued) {
+            if !self.parallel_across_devices && !busy.is_empty() {
+                break;
+            }
+            if busy.contains(&goal.device_id) {
+                continue;
+            }
+            goal.status = QueuedGoalStatus::Running;
+            goal.started_at = Some(Utc::now());
+            busy.insert(goal.device_id.clone());
+            started.push(goal.clone());
+        }
+        started
+    }
+
+    pub fn set_progress(&mut self, id: &str, progress: u8, description: &str) {
+        if let Ok(goal) = self.get_mut(id) {
+            goal.progress = progress.min(100);
+            goal.progress_description = Some(description.to_string());
+        }
+    }
+
+    /// 记录运行结束；请求过取消的目标记为已取消
+    pub fn finish(&mut self, id: &str, result: Result<(), String>) -> Option<QueuedGoal> {
+        let goal = self.get_mut(id).ok()?;
+        goal.status = match (&result, goal.cancel_requested) {
+            (_, true) => QueuedGoalStatus::Cancelled,
+            (Ok(()), false) => QueuedGoalStatus::Completed,
+            (Err(_), false) => QueuedGoalStatus::Failed,
+        };
+        if result.is_ok() {
+            goal.progress = 100;
+        }
+        goal.error = result.err();
+        goal.finished_at = Some(Utc::now());
+        let finished = goal.clone();
+        self.prune_finished();
+        Some(finished)
+    }
+
+    fn prune_finished(&mut self) {
+        let mut finished: Vec<_> = self.goals.iter().filter(|g| g.status.is_finished()).map(|g| (g.finished_at, g.id.clone())).collect();
+        if finished.len() <= MAX_FINISHED_GOALS {
+            return;
+        }
+        finished.sort();
+        let excess = finished.len() - MAX_FINISHED_GOALS;
+        let stale: HashSet<String> = finished.into_iter().take(excess).map(|(_, id)| id).collect();
+        self.goals.retain(|g| !stale.contains(&g.id));
+    }
+}
+
+/// 调度器：队列 + 运行中目标的停止信号与事件日志
+#[derive(Default)]
+pub struct GoalScheduler {
+    pub queue: GoalQueue,
+    stops: HashMap<String, watch::Sender<bool>>,
+    logs: HashMap<String, Arc<RwLock<Vec<AgentEvent>>>>,
+}
+
+impl GoalScheduler {
+    pub fn events(&self, goal_id: &str) -> Option<Arc<RwLock<Vec<AgentEvent>>>> {
+        self.logs.get(goal_id).cloned()
+    }
+
+    /// 取消目标；运行中的目标发送停止信号
+    pub fn cancel(&mut self, goal_id: &str) -> Result<QueuedGoal, String> {
+        let goal = self.queue.cancel(goal_id)?;
+        if let Some(stop) = self.stops.get(goal_id) {
+            let _ = stop.send(true);
+        }
+        Ok(goal)
+    }
+}
+
+pub type SharedGoalScheduler = Arc<Mutex<GoalScheduler>>;
+
+/// 队列变化时推送完整列表
+pub fn emit_goal_queue<R: Runtime>(app: &AppHandle<R>, scheduler: &GoalScheduler) {
+    event_outbox::emit(app, EVENT_GOAL_QUEUE, "goal_queue", &scheduler.queue.list());
+}
+
+/// 更新队列目标的进度（由 GoalProgress 事件驱动）
+pub fn update_queued_goal_progress<R: Runtime>(app: &AppHandle<R>, goal_id: &str, progress: u8, description: &str) {
+    let Some(state) = app.try_state::<AgentRuntimeState>() else {
+        return;
+    };
+    if let Ok(mut scheduler) = state.goal_scheduler.lock() {
+        scheduler.queue.set_progress(goal_id, progress, description);
+        emit_goal_queue(app, &scheduler);
+    };
+}
+
+/// 启动所有可以开始的目标；每个目标结束后再次调度
+pub fn pump_goal_queue<R: Runtime>(app: &AppHandle<R>, busy_devices: HashSet<String>) {
+    let Some(state) = app.try_state::<AgentRuntimeState>() else {
+        return;
+    };
+    let started = {
+        let Ok(mut scheduler) = state.goal_scheduler.lock() else {
+            return;
+        };
+        let started = scheduler.queue.take_runnable(&busy_devices);
+        for goal in &started {
+            let (stop_tx, _) = watch::channel(false);
+            scheduler.stops.insert(goal.id.clone(), stop_tx);
+            scheduler.logs.insert(goal.id.clone(), Arc::new(RwLock::new(Vec::new())));
+        }
+        if !started.is_empty() {
+            emit_goal_queue(app, &scheduler);
+        }
+        started
+            .into_iter()
+            .filter_map(|goal| {
+                let stop_rx = scheduler.stops.get(&goal.id)?.subscribe();
+                let log = scheduler.logs.get(&goal.id)?.clone();
+                Some((goal, stop_rx, log))
+            })
+            .collect::<Vec<_>>()
+    };
+
+    for (goal, stop_rx, event_log) in started {
+        let app = app.clone();
+        tokio::spawn(async move {
+            info!("🗂️ 开始执行队列目标 {}: {} (设备 {})", goal.id, goal.goal, goal.device_id);
+            let runtime = create_shared_runtime(AgentConfig::default(), AgentMode::SemiAutonomous);
+            let started = runtime.write().await.handle_command(AgentCommand::Start {
+                goal: goal.goal.clone(),
+                device_id: goal.device_id.clone(),
+            });
+            let result = match started {
+                Ok(()) => {
+                    let request = WorkRequest::device(&goal.device_id, "agent", goal.goal.clone());
+                    let run = run_agent_loop(runtime, stop_rx, event_log, app.clone(), goal.id.clone(), goal.goal.clone(), goal.device_id.clone(), None);
+                    concurrency::run(request, run).await
+                }
+                Err(e) => Err(e),
+            };
+            if let Err(e) = &result {
+                warn!("⚠️ 队列目标 {} 未完成: {}", goal.id, e);
+            }
+
+            let Some(state) = app.try_state::<AgentRuntimeState>() else {
+                return;
+            };
+            if let Ok(mut scheduler) = state.goal_scheduler.lock() {
+                scheduler.stops.remove(&goal.id);
+                scheduler.queue.finish(&goal.id, result);
+                // 已被清出队列的目标不再保留事件日志
+                let stale: Vec<String> = scheduler.logs.keys().filter(|id| scheduler.queue.get(id).is_none()).cloned().collect();
+                for id in stale {
+                    scheduler.logs.remove(&id);
+                }
+                emit_goal_queue(&app, &scheduler);
+            }
+            let busy = state.primary_busy_devices().await;
+            pump_goal_queue(&app, busy);
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn priorities_devices_reorder_and_cancel() {
+        let mut queue = GoalQueue::default();
+        let a = queue.submit("a", "d1", 0);
+        let b = queue.submit("b", "d1", 5);
+        let c = queue.submit("c", "d2", 0);
+        let d = queue.submit("d", "d3", 0);
+        let ids = |goals: Vec<QueuedGoal>| goals.into_iter().map(|g| g.goal).collect::<Vec<_>>();
+        assert_eq!(ids(queue.list()), vec!["b", "a", "c", "d"]);
+
+        queue.reorder(&d.id, 0).unwrap();
+        assert_eq!(ids(queue.list()), vec!["d", "b", "a", "c"]);
+        queue.reorder(&d.id, 99).unwrap();
+        assert_eq!(ids(queue.list()), vec!["b", "a", "c", "d"]);
+
+        // d3 被前台运行占用；d1 上 b 先于 a
+        let started = queue.take_runnable(&HashSet::from(["d3".to_string()]));
+        assert_eq!(ids(started), vec!["b", "c"]);
+        assert!(queue.take_runnable(&HashSet::new()).iter().map(|g| g.goal.as_str()).eq(["d"]));
+        assert!(queue.reorder(&b.id, 0).is_err());
+
+        assert_eq!(queue.cancel(&a.id).unwrap().status, QueuedGoalStatus::Cancelled);
+        assert!(queue.cancel(&c.id).unwrap().cancel_requested);
+        assert_eq!(queue.finish(&c.id, Err("已停止".into())).unwrap().status, QueuedGoalStatus::Cancelled);
+        assert_eq!(queue.finish(&b.id, Ok(())).unwrap().progress, 100);
+        assert!(queue.take_runnable(&HashSet::new()).is_empty());
+        assert!(queue.cancel(&b.id).is_err());
+
+        let mut serial = GoalQueue { parallel_across_devices: false, ..Default::default() };
+        serial.submit("x", "d1", 0);
+        serial.submit("y", "d2", 0);
+        assert_eq!(serial.take_runnable(&HashSet::new()).len(), 1);
+        assert!(serial.take_runnable(&HashSet::new()).is_empty());
+    }
+}
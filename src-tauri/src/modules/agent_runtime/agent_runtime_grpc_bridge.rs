@@ -0,0 +1,470 @@
+// src-tauri/src/modules/agent_runtime/agent_runtime_grpc_bridge.rs
+// module: agent_runtime | layer: tauri-plugin | role: PC-手机 gRPC 通道
+// summary: 按 proto/phone_companion.proto 手写的 protobuf 消息与 tonic 客户端；下发走带截止时间的 Send，反馈走 Events 服务端流并在断线后退避重订阅
+
+use super::*;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tonic::codec::ProstCodec;
+use tonic::codegen::http::uri::PathAndQuery;
+use tonic::transport::{Certificate, Channel, ClientTlsConfig, Endpoint, Identity};
+
+const SEND_PATH: &str = "/companion.v1.PhoneCompanion/Send";
+const EVENTS_PATH: &str = "/companion.v1.PhoneCompanion/Events";
+const KEEP_ALIVE_TIMEOUT_SECS: u64 = 5;
+const RESUBSCRIBE_MIN_BACKOFF_MS: u64 = 500;
+const RESUBSCRIBE_MAX_BACKOFF_MS: u64 = 30_000;
+
+/// protobuf 消息（字段编号与 proto/phone_companion.proto 保持一致）
+pub mod pb {
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Goal {
+        #[prost(string, tag = "1")]
+        pub description: String,
+        #[prost(uint32, tag = "2")]
+        pub max_steps: u32,
+        #[prost(uint32, tag = "3")]
+        pub timeout_seconds: u32,
+    }
+
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, prost::Enumeration)]
+    #[repr(i32)]
+    pub enum PhoneCommand {
+        Unspecified = 0,
+        Pause = 1,
+        Resume = 2,
+        Stop = 3,
+        GetStatus = 4,
+        GetScreen = 5,
+        Screenshot = 6,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Command {
+        #[prost(enumeration = "PhoneCommand", tag = "1")]
+        pub command: i32,
+        #[prost(string, tag = "2")]
+        pub params_json: String,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct RequestScreen {
+        #[prost(bool, tag = "1")]
+        pub include_screenshot: bool,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct ExecuteAction {
+        #[prost(string, tag = "1")]
+        pub action_type: String,
+        #[prost(string, tag = "2")]
+        pub target: String,
+        #[prost(string, tag = "3")]
+        pub params_json: String,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Query {}
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct PcToPhone {
+        #[prost(string, tag = "1")]
+        pub message_id: String,
+        #[prost(oneof = "pc_to_phone::Kind", tags = "2, 3, 4, 5, 6")]
+        pub kind: Option<pc_to_phone::Kind>,
+    }
+
+    pub mod pc_to_phone {
+        #[derive(Clone, PartialEq, prost::Oneof)]
+        pub enum Kind {
+            #[prost(message, tag = "2")]
+            Goal(super::Goal),
+            #[prost(message, tag = "3")]
+            Command(super::Command),
+            #[prost(message, tag = "4")]
+            RequestScreen(super::RequestScreen),
+            #[prost(message, tag = "5")]
+            ExecuteAction(super::ExecuteAction),
+            #[prost(message, tag = "6")]
+            Query(super::Query),
+        }
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Ack {
+        #[prost(string, tag = "1")]
+        pub message_id: String,
+        #[prost(bool, tag = "2")]
+        pub accepted: bool,
+        #[prost(string, tag = "3")]
+        pub error: String,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct EventsRequest {
+        #[prost(string, tag = "1")]
+        pub client_id: String,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Status {
+        #[prost(string, tag = "1")]
+        pub state: String,
+        #[prost(string, optional, tag = "2")]
+        pub current_goal: Option<String>,
+        #[prost(float, tag = "3")]
+        pub progress: f32,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Progress {
+        #[prost(uint32, tag = "1")]
+        pub step_number: u32,
+        #[prost(uint32, tag = "2")]
+        pub total_steps: u32,
+        #[prost(string, tag = "3")]
+        pub current_task: String,
+        #[prost(string, tag = "4")]
+        pub task_status: String,
+        #[prost(uint32, tag = "5")]
+        pub progress_percent: u32,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Screen {
+        #[prost(string, optional, tag = "1")]
+        pub app_package: Option<String>,
+        #[prost(string, optional, tag = "2")]
+        pub activity: Option<String>,
+        #[prost(string, repeated, tag = "3")]
+        pub visible_texts: Vec<String>,
+        #[prost(string, repeated, tag = "4")]
+        pub clickable_elements: Vec<String>,
+        #[prost(string, optional, tag = "5")]
+        pub screenshot_base64: Option<String>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Result {
+        #[prost(string, tag = "1")]
+        pub goal_id: String,
+        #[prost(bool, tag = "2")]
+        pub success: bool,
+        #[prost(uint32, tag = "3")]
+        pub steps_executed: u32,
+        #[prost(string, tag = "4")]
+        pub message: String,
+        #[prost(uint64, tag = "5")]
+        pub duration_ms: u64,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Error {
+        #[prost(string, tag = "1")]
+        pub code: String,
+        #[prost(string, tag = "2")]
+        pub message: String,
+        #[prost(string, optional, tag = "3")]
+        pub details: Option<String>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Log {
+        #[prost(string, tag = "1")]
+        pub level: String,
+        #[prost(string, tag = "2")]
+        pub tag: String,
+        #[prost(string, tag = "3")]
+        pub message: String,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Thinking {
+        #[prost(string, tag = "1")]
+        pub thought: String,
+        #[prost(string, optional, tag = "2")]
+        pub decision: Option<String>,
+        #[prost(string, optional, tag = "3")]
+        pub action: Option<String>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct PhoneToPc {
+        #[prost(oneof = "phone_to_pc::Kind", tags = "1, 2, 3, 4, 5, 6, 7")]
+        pub kind: Option<phone_to_pc::Kind>,
+    }
+
+    pub mod phone_to_pc {
+        #[derive(Clone, PartialEq, prost::Oneof)]
+        pub enum Kind {
+            #[prost(message, tag = "1")]
+            Status(super::Status),
+            #[prost(message, tag = "2")]
+            Progress(super::Progress),
+            #[prost(message, tag = "3")]
+            Screen(super::Screen),
+            #[prost(message, tag = "4")]
+            Result(super::Result),
+            #[prost(message, tag = "5")]
+            Error(super::Error),
+            #[prost(message, tag = "6")]
+            Log(super::Log),
+            #[prost(message, tag = "7")]
+            Thinking(super::Thinking),
+        }
+    }
+}
+
+/// JSON 参数编码为字符串；Null 编码为空串
+fn params_to_json(params: &serde_json::Value) -> String {
+    if params.is_null() {
+        String::new()
+    } else {
+        params.to_string()
+    }
+}
+
+impl From<&PhoneCommand> for pb::PhoneCommand {
+    fn from(command: &PhoneCommand) -> Self {
+        match command {
+            PhoneCommand::Pause => Self::Pause,
+            PhoneCommand::Resume => Self::Resume,
+            PhoneCommand::Stop => Self::Stop,
+            PhoneCommand::GetStatus => Self::GetStatus,
+            PhoneCommand::GetScreen => Self::GetScreen,
+            PhoneCommand::Screenshot => Self::Screenshot,
+        }
+    }
+}
+
+/// PC → 手机消息转为 protobuf
+pub fn to_proto(message: &PcToPhoneMessage, message_id: String) -> pb::PcToPhone {
+    use pb::pc_to_phone::Kind;
+    let kind = match message {
+        PcToPhoneMessage::Goal { description, max_steps, timeout_seconds } => {
+            Kind::Goal(pb::Goal { description: description.clone(), max_steps: *max_steps, timeout_seconds: *timeout_seconds })
+        }
+        PcToPhoneMessage::Command { command, params } => {
+            Kind::Command(pb::Command { command: pb::PhoneCommand::from(command) as i32, params_json: params_to_json(params) })
+        }
+        PcToPhoneMessage::RequestScreen { include_screenshot } => {
+            Kind::RequestScreen(pb::RequestScreen { include_screenshot: *include_screenshot })
+        }
+        PcToPhoneMessage::ExecuteAction { action_type, target, params } => Kind::ExecuteAction(pb::ExecuteAction {
+            action_type: action_type.clone(),
+            target: target.clone(),
+            params_json: params_to_json(params),
+        }),
+        PcToPhoneMessage::Query => Kind::Query(pb::Query {}),
+    };
+    pb::PcToPhone { message_id, kind: Some(kind) }
+}
+
+impl TryFrom<pb::PhoneToPc> for PhoneTopcMessage {
+    type Error = String;
+
+    fn try_from(message: pb::PhoneToPc) -> Result<Self, String> {
+        use pb::phone_to_pc::Kind;
+        let kind = message.kind.ok_or_else(|| "手机消息缺少内容".to_string())?;
+        Ok(match kind {
+            Kind::Status(pb::Status { state, current_goal, progress }) => Self::Status { state, current_goal, progress },
+            Kind::Progress(pb::Progress { step_number, total_steps, current_task, task_status, progress_percent }) => Self::Progress {
+                step_number,
+                total_steps,
+                current_task,
+                task_status,
+                progress_percent: progress_percent.min(100) as u8,
+            },
+            Kind::Screen(pb::Screen { app_package, activity, visible_texts, clickable_elements, screenshot_base64 }) => {
+                Self::Screen { app_package, activity, visible_texts, clickable_elements, screenshot_base64 }
+            }
+            Kind::Result(pb::Result { goal_id, success, steps_executed, message, duration_ms }) => {
+                Self::Result { goal_id, success, steps_executed, message, duration_ms }
+            }
+            Kind::Error(pb::Error { code, message, details }) => Self::Error { code, message, details },
+            Kind::Log(pb::Log { level, tag, message }) => Self::Log { level, tag, message },
+            Kind::Thinking(pb::Thinking { thought, decision, action }) => Self::Thinking { thought, decision, action },
+        })
+    }
+}
+
+fn now_secs() -> u64 {
+    std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0)
+}
+
+fn read_pem(path: &std::path::Path) -> Result<Vec<u8>, String> {
+    std::fs::read(path).map_err(|e| format!("读取证书 {} 失败: {}", path.display(), e))
+}
+
+/// 按设置构建端点：连接超时、HTTP/2 保活与可选 TLS
+fn build_endpoint(address: &str, settings: &GrpcTransportSettings) -> Result<Endpoint, String> {
+    let scheme = if settings.tls.enabled { "https" } else { "http" };
+    let endpoint = Endpoint::from_shared(format!("{}://{}", scheme, address))
+        .map_err(|e| format!("手机地址无效: {}", e))?
+        .connect_timeout(Duration::from_millis(settings.connect_timeout_ms))
+        .http2_keep_alive_interval(Duration::from_secs(settings.keep_alive_secs))
+        .keep_alive_timeout(Duration::from_secs(KEEP_ALIVE_TIMEOUT_SECS))
+        .keep_alive_while_idle(true);
+    if !settings.tls.enabled {
+        return Ok(endpoint);
+    }
+
+    let tls_settings = &settings.tls;
+    let mut tls = ClientTlsConfig::new();
+    if let Some(domain) = &tls_settings.domain_name {
+        tls = tls.domain_name(domain.clone());
+    }
+    tls = match &tls_settings.ca_cert_path {
+        Some(path) => tls.ca_certificate(Certificate::from_pem(read_pem(path)?)),
+        None => tls.with_native_roots(),
+    };
+    if let (Some(cert), Some(key)) = (&tls_settings.client_cert_path, &tls_settings.client_key_path) {
+        tls = tls.identity(Identity::from_pem(read_pem(cert)?, read_pem(key)?));
+    }
+    endpoint.tls_config(tls).map_err(|e| format!("TLS 配置无效: {}", e))
+}
+
+/// PC → 手机的 gRPC 通道
+pub struct GrpcPhoneBridge {
+    client: tonic::client::Grpc<Channel>,
+    deadline: Duration,
+    events_task: JoinHandle<()>,
+}
+
+impl GrpcPhoneBridge {
+    /// 建立连接并开始订阅手机反馈，反馈写入协同会话
+    pub async fn connect(
+        address: &str,
+        settings: &GrpcTransportSettings,
+        session: Arc<RwLock<CollaborationSession>>,
+    ) -> Result<Self, String> {
+        let channel = build_endpoint(address, settings)?.connect().await.map_err(|e| format!("gRPC 连接手机失败: {}", e))?;
+        let client = tonic::client::Grpc::new(channel);
+        let events_task = tokio::spawn(pump_events(client.clone(), session));
+        Ok(Self { client, deadline: Duration::from_millis(settings.deadline_ms), events_task })
+    }
+
+    /// 下发一条消息，等待手机确认（受截止时间约束）
+    pub async fn send(&self, message: &PcToPhoneMessage) -> Result<(), String> {
+        let message_id = uuid::Uuid::new_v4().to_string();
+        let mut client = self.client.clone();
+        client.ready().await.map_err(|e| format!("gRPC 通道不可用: {}", e))?;
+        let mut request = tonic::Request::new(to_proto(message, message_id.clone()));
+        request.set_timeout(self.deadline);
+        let ack = client
+            .unary(request, PathAndQuery::from_static(SEND_PATH), ProstCodec::<pb::PcToPhone, pb::Ack>::default())
+            .await
+            .map_err(|status| format!("gRPC 下发失败 ({:?}): {}", status.code(), status.message()))?
+            .into_inner();
+        if !ack.accepted {
+            return Err(format!("手机拒绝了消息 {}: {}", message_id, ack.error));
+        }
+        Ok(())
+    }
+}
+
+impl Drop for GrpcPhoneBridge {
+    fn drop(&mut self) {
+        self.events_task.abort();
+    }
+}
+
+/// 订阅手机反馈；流结束或出错时标记为重连中，按指数退避重新订阅，直到通道被释放
+async fn pump_events(mut client: tonic::client::Grpc<Channel>, session: Arc<RwLock<CollaborationSession>>) {
+    let client_id = format!("pc-{}", uuid::Uuid::new_v4());
+    let mut backoff_ms = RESUBSCRIBE_MIN_BACKOFF_MS;
+    loop {
+        let subscribed = match client.ready().await {
+            Ok(()) => client
+                .server_streaming(
+                    tonic::Request::new(pb::EventsRequest { client_id: client_id.clone() }),
+                    PathAndQuery::from_static(EVENTS_PATH),
+                    ProstCodec::<pb::EventsRequest, pb::PhoneToPc>::default(),
+                )
+                .await
+                .map_err(|status| status.message().to_string()),
+            Err(e) => Err(e.to_string()),
+        };
+
+        match subscribed {
+            Ok(response) => {
+                let mut stream = response.into_inner();
+                {
+                    let mut session = session.write().await;
+                    session.connection_state = PhoneConnectionState::Connected;
+                    session.last_heartbeat = Some(now_secs());
+                }
+                backoff_ms = RESUBSCRIBE_MIN_BACKOFF_MS;
+                loop {
+                    match stream.message().await {
+                        Ok(Some(message)) => match PhoneTopcMessage::try_from(message) {
+                            Ok(message) => {
+                                let mut session = session.write().await;
+                                session.last_heartbeat = Some(now_secs());
+                                session.phone_status = Some(message);
+                            }
+                            Err(e) => warn!("⚠️ 忽略无法识别的手机消息: {}", e),
+                        },
+                        Ok(None) => {
+                            info!("📱 手机结束了反馈流");
+                            break;
+                        }
+                        Err(status) => {
+                            warn!("⚠️ 手机反馈流中断: {}", status.message());
+                            break;
+                        }
+                    }
+                }
+            }
+            Err(e) => warn!("⚠️ 订阅手机反馈失败: {}", e),
+        }
+
+        session.write().await.connection_state = PhoneConnectionState::Reconnecting;
+        tokio::time::sleep(Duration::from_millis(backoff_ms)).await;
+        backoff_ms = (backoff_ms * 2).min(RESUBSCRIBE_MAX_BACKOFF_MS);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use prost::Message;
+
+    #[test]
+    fn test_proto_round_trip_mirrors_json_messages() {
+        let action = PcToPhoneMessage::ExecuteAction {
+            action_type: "click".into(),
+            target: "关注按钮".into(),
+            params: serde_json::json!({ "x": 10 }),
+        };
+        let bytes = to_proto(&action, "m1".into()).encode_to_vec();
+        let decoded = pb::PcToPhone::decode(bytes.as_slice()).unwrap();
+        assert_eq!(decoded.message_id, "m1");
+        match decoded.kind {
+            Some(pb::pc_to_phone::Kind::ExecuteAction(action)) => assert_eq!(action.params_json, r#"{"x":10}"#),
+            other => panic!("unexpected kind: {:?}", other),
+        }
+
+        let command = to_proto(&PcToPhoneMessage::Command { command: PhoneCommand::Stop, params: serde_json::Value::Null }, "m2".into());
+        assert!(matches!(command.kind, Some(pb::pc_to_phone::Kind::Command(pb::Command { command: 3, ref params_json })) if params_json.is_empty()));
+
+        let progress = pb::PhoneToPc {
+            kind: Some(pb::phone_to_pc::Kind::Progress(pb::Progress {
+                step_number: 2,
+                total_steps: 5,
+                current_task: "搜索".into(),
+                task_status: "running".into(),
+                progress_percent: 250,
+            })),
+        };
+        let decoded = pb::PhoneToPc::decode(progress.encode_to_vec().as_slice()).unwrap();
+        match PhoneTopcMessage::try_from(decoded).unwrap() {
+            PhoneTopcMessage::Progress { step_number, progress_percent, .. } => {
+                assert_eq!(step_number, 2);
+                assert_eq!(progress_percent, 100);
+            }
+            other => panic!("unexpected message: {:?}", other),
+        }
+        assert!(PhoneTopcMessage::try_from(pb::PhoneToPc { kind: None }).is_err());
+    }
+}
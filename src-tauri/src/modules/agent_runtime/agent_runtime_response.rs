@@ -0,0 +1,312 @@
+// src-tauri/src/modules/agent_runtime/agent_runtime_response.rs
+// module: agent_runtime | layer: tauri-plugin | role: AI 响应格式校验
+// summary: 按 JSON Schema（子集）校验 AI 输出，先做自动修复（去代码块、去尾逗号），仍失败时生成引用校验错误的约束重提示
+
+use serde_json::{json, Value};
+
+/// 格式校验失败后最多追加的约束重提示次数
+pub const MAX_FORMAT_REPAIR_PROMPTS: u32 = 2;
+/// 重提示中引用的上次回复长度上限（字符）
+const PREVIOUS_REPLY_QUOTE_CHARS: usize = 300;
+
+/// 规划响应：`{"tasks": [{"id", "description", "action_hint"}]}`
+pub fn planning_schema() -> Value {
+    json!({
+        "type": "object",
+        "required": ["tasks"],
+        "properties": {
+            "tasks": {
+                "type": "array",
+                "minItems": 1,
+                "items": {
+                    "type": "object",
+                    "required": ["description"],
+                    "properties": {
+                        "id": { "type": ["string", "integer"] },
+                        "description": { "type": "string" },
+                        "action_hint": { "type": "string" }
+                    }
+                }
+            }
+        }
+    })
+}
+
+/// 子任务执行响应：要么给出动作，要么声明子任务完成
+pub fn task_step_schema() -> Value {
+    json!({
+        "type": "object",
+        "required": ["thought"],
+        "properties": {
+            "thought": { "type": "string" },
+            "action": { "type": "string" },
+            "params": { "type": "object" },
+            "task_complete": { "type": "boolean" },
+            "task_result": { "type": "string" }
+        },
+        "anyOf": [
+            { "required": ["action"] },
+            { "required": ["task_complete"], "properties": { "task_complete": { "enum": [true] } } }
+        ]
+    })
+}
+
+/// 自主循环（旧版）决策响应
+pub fn decision_schema() -> Value {
+    json!({
+        "type": "object",
+        "properties": {
+            "thought": { "type": "string" },
+            "action": { "type": "string" },
+            "params": { "type": ["object", "null"] },
+            "is_complete": { "type": "boolean" }
+        }
+    })
+}
+
+/// 副驾驶建议响应
+pub fn copilot_schema() -> Value {
+    json!({
+        "type": "object",
+        "required": ["action"],
+        "properties": {
+            "thought": { "type": "string" },
+            "action": { "type": "string", "minLength": 1 },
+            "params": { "type": ["object", "null"] }
+        }
+    })
+}
+
+/// 解析结果
+#[derive(Debug, Clone)]
+pub struct ParsedResponse {
+    pub value: Value,
+    /// 是否经过自动修复才解析成功
+    pub repaired: bool,
+}
+
+/// 解析并校验 AI 输出；失败时返回全部校验错误
+pub fn parse_structured(text: &str, schema: &Value) -> Result<ParsedResponse, Vec<String>> {
+    let (value, repaired) = match serde_json::from_str::<Value>(text.trim()) {
+        Ok(value) => (value, false),
+        Err(_) => {
+            let fixed = repair_json(text).ok_or_else(|| vec!["响应中没有 JSON".to_string()])?;
+            let value = serde_json::from_str::<Value>(&fixed)
+                .map_err(|e| vec![format!("JSON 解析失败: {}", e)])?;
+            (value, true)
+        }
+    };
+
+    let errors = validate(&value, schema);
+    if errors.is_empty() {
+        Ok(ParsedResponse { value, repaired })
+    } else {
+        Err(errors)
+    }
+}
+
+/// 自动修复常见格式问题：Markdown 代码块、JSON 前后的说明文字、尾逗号
+pub fn repair_json(text: &str) -> Option<String> {
+    let text = text.trim().trim_start_matches('\u{feff}');
+    let body = strip_code_fence(text).unwrap_or(text);
+
+    let start = body.find(|c| c == '{' || c == '[')?;
+    let closer = if body[start..].starts_with('{') { '}' } else { ']' };
+    let end = body.rfind(closer)?;
+    if end < start {
+        return None;
+    }
+    Some(remove_trailing_commas(&body[start..=end]))
+}
+
+fn strip_code_fence(text: &str) -> Option<&str> {
+    let open = text.find("```")?;
+    let after_open = &text[open + 3..];
+    // 跳过语言标记（```json）
+    let content_start = after_open.find('\n').map(|i| i + 1).unwrap_or(0);
+    let content = &after_open[content_start..];
+    let close = content.find("```").unwrap_or(content.len());
+    Some(content[..close].trim())
+}
+
+/// 删除 `}` / `]` 前的多余逗号（字符串内部的逗号保持不变）
+fn remove_trailing_commas(json: &str) -> String {
+    let chars: Vec<char> = json.chars().collect();
+    let mut out = String::with_capacity(json.len());
+    let mut in_string = false;
+    let mut escaped = false;
+
+    for (i, &c) in chars.iter().enumerate() {
+        if in_string {
+            out.push(c);
+            if escaped {
+                escaped = false;
+            } else if c == '\\' {
+                escaped = true;
+            } else if c == '"' {
+                in_string = false;
+            }
+            continue;
+        }
+        if c == '"' {
+            in_string = true;
+        } else if c == ',' {
+            let next = chars[i + 1..].iter().find(|c| !c.is_whitespace());
+            if matches!(next, Some('}') | Some(']')) {
+                continue;
+            }
+        }
+        out.push(c);
+    }
+    out
+}
+
+/// 按 JSON Schema 子集校验（type / required / properties / items / enum / minItems / minLength / anyOf）
+pub fn validate(value: &Value, schema: &Value) -> Vec<String> {
+    let mut errors = Vec::new();
+    validate_at(value, schema, "$", &mut errors);
+    errors
+}
+
+fn type_matches(value: &Value, expected: &str) -> bool {
+    match expected {
+        "object" => value.is_object(),
+        "array" => value.is_array(),
+        "string" => value.is_string(),
+        "boolean" => value.is_boolean(),
+        "number" => value.is_number(),
+        "integer" => value.is_i64() || value.is_u64(),
+        "null" => value.is_null(),
+        _ => true,
+    }
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+fn validate_at(value: &Value, schema: &Value, path: &str, errors: &mut Vec<String>) {
+    if let Some(expected) = schema.get("type") {
+        let allowed: Vec<&str> = match expected {
+            Value::String(t) => vec![t.as_str()],
+            Value::Array(ts) => ts.iter().filter_map(|t| t.as_str()).collect(),
+            _ => Vec::new(),
+        };
+        if !allowed.is_empty() && !allowed.iter().any(|t| type_matches(value, t)) {
+            errors.push(format!("{}: 类型应为 {}，实际为 {}", path, allowed.join("/"), type_name(value)));
+            return;
+        }
+    }
+
+    if let Some(options) = schema.get("enum").and_then(|v| v.as_array()) {
+        if !options.contains(value) {
+            errors.push(format!("{}: 取值应为 {}", path, Value::Array(options.clone())));
+        }
+    }
+
+    if let (Some(min), Some(text)) = (schema.get("minLength").and_then(|v| v.as_u64()), value.as_str()) {
+        if (text.chars().count() as u64) < min {
+            errors.push(format!("{}: 长度至少为 {}", path, min));
+        }
+    }
+
+    if let Some(object) = value.as_object() {
+        if let Some(required) = schema.get("required").and_then(|v| v.as_array()) {
+            for field in required.iter().filter_map(|f| f.as_str()) {
+                if !object.contains_key(field) {
+                    errors.push(format!("{}: 缺少必填字段 \"{}\"", path, field));
+                }
+            }
+        }
+        if let Some(properties) = schema.get("properties").and_then(|v| v.as_object()) {
+            for (name, property_schema) in properties {
+                if let Some(child) = object.get(name) {
+                    validate_at(child, property_schema, &format!("{}.{}", path, name), errors);
+                }
+            }
+        }
+    }
+
+    if let Some(items) = value.as_array() {
+        if let Some(min) = schema.get("minItems").and_then(|v| v.as_u64()) {
+            if (items.len() as u64) < min {
+                errors.push(format!("{}: 至少需要 {} 项", path, min));
+            }
+        }
+        if let Some(item_schema) = schema.get("items") {
+            for (i, item) in items.iter().enumerate() {
+                validate_at(item, item_schema, &format!("{}[{}]", path, i), errors);
+            }
+        }
+    }
+
+    if let Some(branches) = schema.get("anyOf").and_then(|v| v.as_array()) {
+        let branch_errors: Vec<Vec<String>> = branches.iter().map(|b| validate(value, b)).collect();
+        if !branch_errors.iter().any(|e| e.is_empty()) {
+            let reasons: Vec<String> = branch_errors.into_iter().map(|e| e.join("，")).collect();
+            errors.push(format!("{}: 不满足任一可选格式（{}）", path, reasons.join(" | ")));
+        }
+    }
+}
+
+/// 约束重提示：引用校验错误与 Schema，要求只返回合法 JSON
+pub fn build_format_correction_prompt(errors: &[String], schema: &Value, previous_reply: &str) -> String {
+    let quoted: String = previous_reply.chars().take(PREVIOUS_REPLY_QUOTE_CHARS).collect();
+    let error_list = errors.iter().map(|e| format!("- {}", e)).collect::<Vec<_>>().join("\n");
+    let schema_text = serde_json::to_string_pretty(schema).unwrap_or_else(|_| schema.to_string());
+
+    format!(r#"你上一次的回复不符合要求的 JSON 格式，系统无法使用。
+
+## 校验错误
+{error_list}
+
+## 你上一次的回复（节选）
+{quoted}
+
+## 必须满足的 JSON Schema
+{schema_text}
+
+请针对上述错误修正后重新回答：只返回一个符合该 Schema 的 JSON 对象，不要使用 Markdown 代码块，不要包含注释或其他文字。"#,
+        error_list = error_list,
+        quoted = quoted,
+        schema_text = schema_text
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn repairs_fenced_json_with_trailing_commas() {
+        let response = "好的，计划如下：\n```json\n{\"tasks\": [{\"id\": \"1\", \"description\": \"打开抖音, 进入搜索\",},],}\n```";
+        let parsed = parse_structured(response, &planning_schema()).unwrap();
+        assert!(parsed.repaired);
+        assert_eq!(parsed.value["tasks"][0]["description"], "打开抖音, 进入搜索");
+
+        let direct = parse_structured(r#"{"tasks": [{"description": "点击"}]}"#, &planning_schema()).unwrap();
+        assert!(!direct.repaired);
+    }
+
+    #[test]
+    fn reports_schema_violations_with_paths() {
+        let errors = parse_structured(r#"{"tasks": [{"id": "1"}]}"#, &planning_schema()).unwrap_err();
+        assert_eq!(errors, vec!["$.tasks[0]: 缺少必填字段 \"description\"".to_string()]);
+
+        let errors = parse_structured(r#"{"thought": "看一下", "task_complete": false}"#, &task_step_schema()).unwrap_err();
+        assert!(errors[0].contains("不满足任一可选格式"));
+        assert!(parse_structured(r#"{"thought": "完成", "task_complete": true}"#, &task_step_schema()).is_ok());
+        assert!(parse_structured("没有 JSON", &task_step_schema()).is_err());
+
+        let prompt = build_format_correction_prompt(&errors, &task_step_schema(), "{\"thought\": \"看一下\"}");
+        assert!(prompt.contains("不满足任一可选格式"));
+        assert!(prompt.contains("\"task_complete\""));
+    }
+}
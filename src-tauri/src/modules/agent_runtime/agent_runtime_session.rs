@@ -0,0 +1,251 @@
+// src-tauri/src/modules/agent_runtime/agent_runtime_session.rs
+// module: agent_runtime | layer: tauri-plugin | role: 会话持久化
+// summary: 每次状态转换把目标、执行计划（含子任务状态）、对话摘要与待审批动作写盘；应用重启后经用户确认从最近的安全点（子任务边界）继续
+
+use super::*;
+use chrono::{DateTime, Utc};
+use std::sync::{Mutex, OnceLock};
+
+const SESSION_FILE_NAME: &str = "agent_session.json";
+const SESSION_VERSION: u32 = 1;
+/// 对话摘要保留的最近条数
+const SUMMARY_MAX_LINES: usize = 30;
+const SUMMARY_LINE_MAX_CHARS: usize = 120;
+
+static SESSION_STORE: OnceLock<Mutex<SessionStore>> = OnceLock::new();
+
+/// 中断时等待审批的动作（恢复后不会自动执行）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PendingAction {
+    pub name: String,
+    /// JSON 字符串
+    pub params: String,
+}
+
+/// 落盘的 Agent 会话
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PersistedAgentSession {
+    pub version: u32,
+    pub session_id: String,
+    pub goal: String,
+    pub device_id: String,
+    /// 所属营销活动；恢复会话时据此重新进入活动绑定的沙箱
+    #[serde(default)]
+    pub campaign_id: Option<String>,
+    pub run_state: AgentRunState,
+    /// 最近一个安全点的执行计划；规划完成前为空
+    pub plan: Option<ExecutionPlan>,
+    /// 最近的动作 / 进度摘要（从旧到新）
+    pub conversation_summary: Vec<String>,
+    pub pending_action: Option<PendingAction>,
+    pub started_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl PersistedAgentSession {
+    pub fn new(goal: &str, device_id: &str, run_state: AgentRunState) -> Self {
+        let now = Utc::now();
+        Self {
+            version: SESSION_VERSION,
+            session_id: uuid::Uuid::new_v4().to_string(),
+            goal: goal.to_string(),
+            device_id: device_id.to_string(),
+            campaign_id: None,
+            run_state,
+            plan: None,
+            conversation_summary: Vec::new(),
+            pending_action: None,
+            started_at: now,
+            updated_at: now,
+        }
+    }
+
+    pub fn push_summary(&mut self, line: &str) {
+        let line: String = line.chars().take(SUMMARY_LINE_MAX_CHARS).collect();
+        if self.conversation_summary.last() == Some(&line) {
+            return;
+        }
+        self.conversation_summary.push(line);
+        let overflow = self.conversation_summary.len().saturating_sub(SUMMARY_MAX_LINES);
+        self.conversation_summary.drain(..overflow);
+    }
+
+    /// 恢复用的计划：当前子任务回到未开始（重新观察屏幕后再决定动作），已累计的重试次数保留
+    pub fn resume_plan(&self) -> Option<ExecutionPlan> {
+        let mut plan = self.plan.clone()?;
+        if let Some(task) = plan.tasks.front_mut() {
+            task.status = SubTaskStatus::Pending;
+        }
+        Some(plan)
+    }
+}
+
+/// 会话文件（同一时间只有一个自主运行的目标）
+pub struct SessionStore {
+    path: PathBuf,
+    current: Option<PersistedAgentSession>,
+}
+
+impl SessionStore {
+    pub fn global() -> &'static Mutex<SessionStore> {
+        SESSION_STORE.get_or_init(|| {
+            let path = dirs::data_dir().unwrap_or_else(|| PathBuf::from(".")).join("employee-gui").join(SESSION_FILE_NAME);
+            Mutex::new(Self::open(path))
+        })
+    }
+
+    pub fn open(path: PathBuf) -> Self {
+        let current = std::fs::read_to_string(&path)
+            .ok()
+            .and_then(|content| serde_json::from_str::<PersistedAgentSession>(&content).ok())
+            .filter(|s| s.version == SESSION_VERSION);
+        Self { path, current }
+    }
+
+    pub fn current(&self) -> Option<&PersistedAgentSession> {
+        self.current.as_ref()
+    }
+
+    pub fn begin(&mut self, session: PersistedAgentSession) -> Result<(), String> {
+        self.current = Some(session);
+        self.save()
+    }
+
+    /// 修改当前会话并立即写盘；没有会话时忽略
+    pub fn update(&mut self, f: impl FnOnce(&mut PersistedAgentSession)) -> Result<(), String> {
+        let Some(session) = self.current.as_mut() else {
+            return Ok(());
+        };
+        f(session);
+        session.updated_at = Utc::now();
+        self.save()
+    }
+
+    pub fn clear(&mut self) -> Result<(), String> {
+        self.current = None;
+        match std::fs::remove_file(&self.path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(format!("删除 Agent 会话失败: {}", e)),
+            _ => Ok(()),
+        }
+    }
+
+    /// 先写临时文件再替换，避免中途退出留下半截 JSON
+    fn save(&self) -> Result<(), String> {
+        let Some(session) = &self.current else {
+            return Ok(());
+        };
+        if let Some(dir) = self.path.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+        }
+        let content = serde_json::to_string_pretty(session).map_err(|e| e.to_string())?;
+        let tmp = self.path.with_extension("json.tmp");
+        std::fs::write(&tmp, content).map_err(|e| format!("保存 Agent 会话失败: {}", e))?;
+        std::fs::rename(&tmp, &self.path).map_err(|e| format!("保存 Agent 会话失败: {}", e))
+    }
+}
+
+fn with_store(f: impl FnOnce(&mut SessionStore) -> Result<(), String>) {
+    let result = match SessionStore::global().lock() {
+        Ok(mut store) => f(&mut store),
+        Err(e) => Err(e.to_string()),
+    };
+    if let Err(e) = result {
+        warn!("⚠️ Agent 会话持久化失败: {}", e);
+    }
+}
+
+/// 新目标开始：覆盖旧会话
+pub fn begin_session(goal: &str, device_id: &str, campaign_id: Option<&str>, run_state: AgentRunState) {
+    let mut session = PersistedAgentSession::new(goal, device_id, run_state);
+    session.campaign_id = campaign_id.map(String::from);
+    with_store(|store| store.begin(session));
+}
+
+/// 状态转换后记录运行状态与待审批动作
+pub fn record_transition(runtime: &AgentRuntime) {
+    let pending_action = match runtime.get_pending_action() {
+        (Some(name), params) => Some(PendingAction { name, params: params.unwrap_or_default() }),
+        _ => None,
+    };
+    let run_state = runtime.current_state();
+    with_store(|store| {
+        store.update(|session| {
+            session.run_state = run_state;
+            session.pending_action = pending_action;
+        })
+    });
+}
+
+/// 到达安全点（子任务边界）时记录计划
+pub fn record_plan(plan: &ExecutionPlan) {
+    with_store(|store| store.update(|session| session.plan = Some(plan.clone())));
+}
+
+/// 从推送给前端的事件里摘取对话摘要
+pub fn record_event(event: &AgentEvent) {
+    let line = match event {
+        AgentEvent::ActionExecuted { action, result, success } if result != "执行中..." => {
+            format!("{} {}: {}", if *success { "✓" } else { "✗" }, action, result)
+        }
+        AgentEvent::GoalProgress { description, .. } => description.clone(),
+        AgentEvent::FileWritten { path, bytes, .. } => format!("📝 写入 {} ({} 字节)", path, bytes),
+        AgentEvent::Error { message } => format!("错误: {}", message),
+        _ => return,
+    };
+    with_store(|store| store.update(|session| session.push_summary(&line)));
+}
+
+/// 目标结束（完成 / 失败 / 用户停止）后不再需要恢复
+pub fn finish_session() {
+    with_store(|store| store.clear());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn persists_and_restores_from_safe_point() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(SESSION_FILE_NAME);
+        let mut store = SessionStore::open(path.clone());
+        assert!(store.current().is_none());
+        store.update(|s| s.goal = "ignored".into()).unwrap();
+        assert!(!path.exists());
+
+        store.begin(PersistedAgentSession::new("给张三发消息", "emulator-5554", AgentRunState::Thinking)).unwrap();
+        let mut plan = ExecutionPlan::new("给张三发消息".into(), vec![SubTask::new("1", "打开微信"), SubTask::new("2", "搜索张三")]);
+        plan.complete_current("已打开".into());
+        plan.fail_current("未找到搜索框".into());
+        plan.tasks.front_mut().unwrap().status = SubTaskStatus::InProgress;
+        store
+            .update(|s| {
+                s.plan = Some(plan.clone());
+                s.run_state = AgentRunState::WaitingForApproval;
+                s.pending_action = Some(PendingAction { name: "tap".into(), params: "{}".into() });
+                for i in 0..SUMMARY_MAX_LINES + 5 {
+                    s.push_summary(&format!("步骤 {}", i));
+                }
+                s.push_summary(&format!("步骤 {}", SUMMARY_MAX_LINES + 4));
+            })
+            .unwrap();
+
+        let reopened = SessionStore::open(path.clone());
+        let session = reopened.current().unwrap();
+        assert_eq!(session.run_state, AgentRunState::WaitingForApproval);
+        assert_eq!(session.conversation_summary.len(), SUMMARY_MAX_LINES);
+        assert_eq!(session.conversation_summary.first().map(String::as_str), Some("步骤 5"));
+        let resumed = session.resume_plan().unwrap();
+        assert_eq!(resumed.current_task_index, 1);
+        assert_eq!(resumed.completed_tasks.len(), 1);
+        let current = resumed.current_task().unwrap();
+        assert_eq!((current.id.as_str(), current.status.clone(), current.retries), ("2", SubTaskStatus::Pending, 1));
+
+        let mut store = reopened;
+        store.clear().unwrap();
+        assert!(!path.exists());
+        assert!(SessionStore::open(path).current().is_none());
+    }
+}
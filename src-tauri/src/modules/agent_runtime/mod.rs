@@ -5,17 +5,23 @@
 use crate::core::application::{
     AgentRuntime, AgentCommand, AgentEvent,
     SharedAgentRuntime, create_shared_runtime,
+    SandboxScope, SANDBOX,
 };
 use crate::core::domain::agent_runtime::{
     AgentConfig, AgentMode, AgentRunState, AgentStateSnapshot,
 };
+use crate::core::domain::agent::truncate_to_tokens;
+use crate::ai::cancellation::{with_deadline, AiCallError, CancellationToken};
+use crate::core::application::agent_service::DeltaCallback;
 use crate::modules::agent::AgentState;
 use crate::screenshot_service::ScreenshotService;
+use crate::engine::ui_tree::{Flag as UiFlag, UiTree};
 use tauri::{
     plugin::{Builder, TauriPlugin},
     AppHandle, Emitter, Manager, Runtime, State,
 };
 use tokio::sync::{mpsc, watch, RwLock};
+use std::collections::HashSet;
 use std::sync::Arc;
 use std::path::PathBuf;
 use tracing::{info, warn, error};
@@ -37,19 +43,79 @@ pub struct AgentRuntimeState {
     event_log: Arc<RwLock<Vec<AgentEvent>>>,
     /// AI 聊天函数（延迟初始化）
     ai_chat_fn: Arc<RwLock<Option<AiChatFn>>>,
+    /// 副驾驶停止信号
+    copilot_stop_tx: watch::Sender<bool>,
+    /// 副驾驶是否正在观察
+    copilot_running: Arc<RwLock<bool>>,
+    /// 副驾驶统计
+    copilot_stats: Arc<RwLock<CopilotStats>>,
+    /// 多目标队列调度
+    goal_scheduler: SharedGoalScheduler,
 }
 
 impl AgentRuntimeState {
     fn new() -> Self {
         let (stop_tx, _) = watch::channel(false);
+        let (copilot_stop_tx, _) = watch::channel(false);
         Self {
             runtime: create_shared_runtime(AgentConfig::default(), AgentMode::SemiAutonomous),
             stop_tx,
             loop_running: Arc::new(RwLock::new(false)),
             event_log: Arc::new(RwLock::new(Vec::new())),
             ai_chat_fn: Arc::new(RwLock::new(None)),
+            copilot_stop_tx,
+            copilot_running: Arc::new(RwLock::new(false)),
+            copilot_stats: Arc::new(RwLock::new(CopilotStats::default())),
+            goal_scheduler: Arc::new(std::sync::Mutex::new(GoalScheduler::default())),
         }
     }
+
+    /// 停止自主循环与当前目标（stop 命令、快捷动作紧急停止共用）
+    pub(crate) async fn stop_agent(&self) {
+        let _ = self.stop_tx.send(true);
+        let mut runtime = self.runtime.write().await;
+        let _ = runtime.handle_command(AgentCommand::Stop);
+        finish_session();
+    }
+
+    /// 通知副驾驶退出
+    pub(crate) fn stop_copilot(&self) {
+        let _ = self.copilot_stop_tx.send(true);
+    }
+
+    /// 前台单目标运行占用的设备（队列目标需避开）
+    async fn primary_busy_devices(&self) -> HashSet<String> {
+        if !*self.loop_running.read().await {
+            return HashSet::new();
+        }
+        self.runtime.read().await.get_current_device_id().into_iter().collect()
+    }
+
+    /// 重新初始化（restart_module）：通知自主循环与副驾驶退出，清空事件与统计；
+    /// 循环 panic 时运行标记不会被复位，这里一并清除
+    fn reset(&self) -> Result<(), String> {
+        let _ = self.stop_tx.send(true);
+        let _ = self.copilot_stop_tx.send(true);
+        for flag in [&self.loop_running, &self.copilot_running] {
+            *flag.try_write().map_err(|_| "Agent 运行状态被占用，请稍后重试".to_string())? = false;
+        }
+        if let Ok(mut events) = self.event_log.try_write() {
+            events.clear();
+        }
+        if let Ok(mut stats) = self.copilot_stats.try_write() {
+            *stats = CopilotStats::default();
+        }
+        if let Ok(mut scheduler) = self.goal_scheduler.lock() {
+            let running: Vec<String> = scheduler.queue.list().into_iter()
+                .filter(|g| g.status == QueuedGoalStatus::Running)
+                .map(|g| g.id)
+                .collect();
+            for id in running {
+                let _ = scheduler.cancel(&id);
+            }
+        }
+        Ok(())
+    }
 }
 
 /// 启动参数
@@ -62,6 +128,18 @@ pub struct StartAgentParams {
     pub device_id: String,
     /// 运行模式（可选）: "autonomous", "semi", "supervised"
     pub mode: Option<String>,
+    /// 所属营销活动（可选）：活动绑定了沙箱配置时，Agent 循环在该沙箱内运行
+    pub campaign_id: Option<String>,
+}
+
+/// 解析前端传入的运行模式
+fn parse_agent_mode(mode: &str) -> Result<AgentMode, String> {
+    match mode {
+        "autonomous" => Ok(AgentMode::Autonomous),
+        "semi" | "semi_autonomous" => Ok(AgentMode::SemiAutonomous),
+        "supervised" => Ok(AgentMode::Supervised),
+        other => Err(format!("未知的运行模式: {}", other)),
+    }
 }
 
 /// 状态响应
@@ -100,8 +178,18 @@ async fn start<R: Runtime>(
     app: AppHandle<R>,
     params: StartAgentParams,
     state: State<'_, AgentRuntimeState>,
+) -> Result<AgentResponse, String> {
+    crate::core::plugin_isolation::guard("agent_runtime", start_impl(app, params, state)).await
+}
+
+async fn start_impl<R: Runtime>(
+    app: AppHandle<R>,
+    params: StartAgentParams,
+    state: State<'_, AgentRuntimeState>,
 ) -> Result<AgentResponse, String> {
     info!("🚀 启动 Agent: goal={}, device={}", params.goal, params.device_id);
+    crate::services::licensing::require_feature(crate::services::licensing::LicenseFeature::Agent)?;
+    crate::services::telemetry::record_feature("agent_runtime.start");
 
     // 检查 AI Agent 是否已配置
     let agent_state: tauri::State<'_, AgentState> = app.try_state::<AgentState>()
@@ -112,6 +200,13 @@ async fn start<R: Runtime>(
         return Err("AI Agent 未配置，请先配置 API Key".to_string());
     }
 
+    if *state.copilot_running.read().await {
+        return Err("副驾驶正在观察，请先停止副驾驶再启动自主运行".to_string());
+    }
+    ensure_device_not_in_queue(&state, &params.device_id)?;
+    let mode = params.mode.as_deref().map(parse_agent_mode).transpose()?;
+    let sandbox = enter_campaign_sandbox(params.campaign_id.as_deref(), &params.device_id)?;
+
     // 重置停止信号
     let _ = state.stop_tx.send(false);
 
@@ -122,8 +217,38 @@ async fn start<R: Runtime>(
             goal: params.goal.clone(),
             device_id: params.device_id.clone(),
         }).map_err(|e| e.to_string())?;
+        runtime.set_mode(mode.unwrap_or_default());
+        begin_session(&params.goal, &params.device_id, params.campaign_id.as_deref(), runtime.current_state());
     }
 
+    spawn_agent_loop(&app, &state, params.goal.clone(), params.device_id.clone(), None, sandbox).await;
+
+    Ok(AgentResponse {
+        success: true,
+        message: format!("Agent 已启动，目标: {}", params.goal),
+        error: None,
+    })
+}
+
+/// 活动绑定了沙箱配置时进入该沙箱；作用域随 Agent 循环结束自动退出
+fn enter_campaign_sandbox(campaign_id: Option<&str>, device_id: &str) -> Result<Option<SandboxScope<'static>>, String> {
+    match campaign_id {
+        Some(campaign_id) => SANDBOX
+            .scope(&format!("campaign:{}", campaign_id), None, device_id)
+            .map_err(|e| e.to_string()),
+        None => Ok(None),
+    }
+}
+
+/// 清空事件日志并在后台启动 Agent 循环（已在运行时不重复启动）
+async fn spawn_agent_loop<R: Runtime>(
+    app: &AppHandle<R>,
+    state: &AgentRuntimeState,
+    goal: String,
+    device_id: String,
+    restored_plan: Option<ExecutionPlan>,
+    sandbox: Option<SandboxScope<'static>>,
+) {
     // 清空事件日志
     {
         let mut log = state.event_log.write().await;
@@ -138,38 +263,39 @@ async fn start<R: Runtime>(
         let stop_rx = state.stop_tx.subscribe();
         let loop_running = state.loop_running.clone();
         let event_log = state.event_log.clone();
-        let goal = params.goal.clone();
-        let device_id = params.device_id.clone();
-        
+
         // 创建 AI 调用闭包（通过 AppHandle 在 spawn 中获取 AgentState）
         let app_handle = app.app_handle().clone();
 
         tokio::spawn(async move {
+            let _sandbox = sandbox;
             *loop_running.write().await = true;
             info!("🔄 Agent 循环启动");
 
             // 运行 Agent 循环（集成真正的 AI）
-            run_agent_loop(runtime, stop_rx, event_log, app_handle, goal, device_id).await;
+            let goal_id = PRIMARY_GOAL_ID.to_string();
+            let _ = run_agent_loop(runtime, stop_rx, event_log, app_handle.clone(), goal_id, goal, device_id, restored_plan).await;
 
             *loop_running.write().await = false;
             info!("🛑 Agent 循环结束");
+            // 释放的设备可能有排队中的目标
+            pump_goal_queue(&app_handle, HashSet::new());
         });
     }
-
-    Ok(AgentResponse {
-        success: true,
-        message: format!("Agent 已启动，目标: {}", params.goal),
-        error: None,
-    })
 }
 
 /// 暂停 Agent
 #[tauri::command]
 async fn pause(state: State<'_, AgentRuntimeState>) -> Result<AgentResponse, String> {
+    crate::core::plugin_isolation::guard("agent_runtime", pause_impl(state)).await
+}
+
+async fn pause_impl(state: State<'_, AgentRuntimeState>) -> Result<AgentResponse, String> {
     info!("⏸️ 暂停 Agent");
     let mut runtime = state.runtime.write().await;
     runtime.handle_command(AgentCommand::Pause)
         .map_err(|e| e.to_string())?;
+    record_transition(&runtime);
 
     Ok(AgentResponse {
         success: true,
@@ -181,10 +307,15 @@ async fn pause(state: State<'_, AgentRuntimeState>) -> Result<AgentResponse, Str
 /// 恢复 Agent
 #[tauri::command]
 async fn resume(state: State<'_, AgentRuntimeState>) -> Result<AgentResponse, String> {
+    crate::core::plugin_isolation::guard("agent_runtime", resume_impl(state)).await
+}
+
+async fn resume_impl(state: State<'_, AgentRuntimeState>) -> Result<AgentResponse, String> {
     info!("▶️ 恢复 Agent");
     let mut runtime = state.runtime.write().await;
     runtime.handle_command(AgentCommand::Resume)
         .map_err(|e| e.to_string())?;
+    record_transition(&runtime);
 
     Ok(AgentResponse {
         success: true,
@@ -196,14 +327,12 @@ async fn resume(state: State<'_, AgentRuntimeState>) -> Result<AgentResponse, St
 /// 停止 Agent
 #[tauri::command]
 async fn stop(state: State<'_, AgentRuntimeState>) -> Result<AgentResponse, String> {
+    crate::core::plugin_isolation::guard("agent_runtime", stop_impl(state)).await
+}
+
+async fn stop_impl(state: State<'_, AgentRuntimeState>) -> Result<AgentResponse, String> {
     info!("🛑 停止 Agent");
-    
-    // 发送停止信号
-    let _ = state.stop_tx.send(true);
-    
-    // 发送停止命令
-    let mut runtime = state.runtime.write().await;
-    let _ = runtime.handle_command(AgentCommand::Stop);
+    state.stop_agent().await;
 
     Ok(AgentResponse {
         success: true,
@@ -215,10 +344,15 @@ async fn stop(state: State<'_, AgentRuntimeState>) -> Result<AgentResponse, Stri
 /// 批准待定行动
 #[tauri::command]
 async fn approve(state: State<'_, AgentRuntimeState>) -> Result<AgentResponse, String> {
+    crate::core::plugin_isolation::guard("agent_runtime", approve_impl(state)).await
+}
+
+async fn approve_impl(state: State<'_, AgentRuntimeState>) -> Result<AgentResponse, String> {
     info!("✅ 批准行动");
     let mut runtime = state.runtime.write().await;
     runtime.handle_command(AgentCommand::Approve)
         .map_err(|e| e.to_string())?;
+    record_transition(&runtime);
 
     Ok(AgentResponse {
         success: true,
@@ -230,10 +364,15 @@ async fn approve(state: State<'_, AgentRuntimeState>) -> Result<AgentResponse, S
 /// 拒绝待定行动
 #[tauri::command]
 async fn reject(state: State<'_, AgentRuntimeState>) -> Result<AgentResponse, String> {
+    crate::core::plugin_isolation::guard("agent_runtime", reject_impl(state)).await
+}
+
+async fn reject_impl(state: State<'_, AgentRuntimeState>) -> Result<AgentResponse, String> {
     info!("❌ 拒绝行动");
     let mut runtime = state.runtime.write().await;
     runtime.handle_command(AgentCommand::Reject)
         .map_err(|e| e.to_string())?;
+    record_transition(&runtime);
 
     Ok(AgentResponse {
         success: true,
@@ -245,6 +384,10 @@ async fn reject(state: State<'_, AgentRuntimeState>) -> Result<AgentResponse, St
 /// 获取 Agent 状态
 #[tauri::command]
 async fn status(state: State<'_, AgentRuntimeState>) -> Result<AgentStatusResponse, String> {
+    crate::core::plugin_isolation::guard("agent_runtime", status_impl(state)).await
+}
+
+async fn status_impl(state: State<'_, AgentRuntimeState>) -> Result<AgentStatusResponse, String> {
     let runtime = state.runtime.read().await;
     let snapshot = runtime.snapshot();
     let state_str = format!("{:?}", snapshot.run_state);
@@ -259,6 +402,401 @@ async fn status(state: State<'_, AgentRuntimeState>) -> Result<AgentStatusRespon
     })
 }
 
+// ========== 会话恢复：应用重启后从最近的安全点继续 ==========
+
+mod agent_runtime_session;
+pub use agent_runtime_session::*;
+
+/// 获取上次未完成的 Agent 会话（供前端提示用户是否恢复）
+#[tauri::command]
+async fn get_saved_agent_session() -> Result<Option<PersistedAgentSession>, String> {
+    crate::core::plugin_isolation::guard("agent_runtime", async move { Ok(SessionStore::global().lock().map_err(|e| e.to_string())?.current().cloned()) }).await
+}
+
+/// 放弃上次未完成的会话
+#[tauri::command]
+async fn discard_agent_session() -> Result<AgentResponse, String> {
+    crate::core::plugin_isolation::guard("agent_runtime", discard_agent_session_impl()).await
+}
+
+async fn discard_agent_session_impl() -> Result<AgentResponse, String> {
+    SessionStore::global().lock().map_err(|e| e.to_string())?.clear()?;
+    Ok(AgentResponse {
+        success: true,
+        message: "已放弃未完成的 Agent 会话".to_string(),
+        error: None,
+    })
+}
+
+/// 经用户确认后重建运行时并从最近的安全点继续；中断时待审批的动作不会自动执行
+#[tauri::command]
+async fn resume_agent_session<R: Runtime>(
+    app: AppHandle<R>,
+    confirmed: bool,
+    state: State<'_, AgentRuntimeState>,
+) -> Result<AgentResponse, String> {
+    crate::core::plugin_isolation::guard("agent_runtime", resume_agent_session_impl(app, confirmed, state)).await
+}
+
+async fn resume_agent_session_impl<R: Runtime>(
+    app: AppHandle<R>,
+    confirmed: bool,
+    state: State<'_, AgentRuntimeState>,
+) -> Result<AgentResponse, String> {
+    if !confirmed {
+        return Err("恢复 Agent 会话需要用户确认".to_string());
+    }
+    crate::services::licensing::require_feature(crate::services::licensing::LicenseFeature::Agent)?;
+
+    let saved = SessionStore::global()
+        .lock()
+        .map_err(|e| e.to_string())?
+        .current()
+        .cloned()
+        .ok_or("没有可恢复的 Agent 会话")?;
+    info!("♻️ 恢复 Agent 会话: session={}, goal={}, state={:?}", saved.session_id, saved.goal, saved.run_state);
+
+    let agent_state: tauri::State<'_, AgentState> = app.try_state::<AgentState>()
+        .ok_or("AI Agent 未初始化，请先配置 API Key")?;
+    if !agent_state.is_configured().await {
+        return Err("AI Agent 未配置，请先配置 API Key".to_string());
+    }
+    if *state.copilot_running.read().await {
+        return Err("副驾驶正在观察，请先停止副驾驶再恢复会话".to_string());
+    }
+    if *state.loop_running.read().await {
+        return Err("Agent 正在运行，请先停止当前目标".to_string());
+    }
+    ensure_device_not_in_queue(&state, &saved.device_id)?;
+    let sandbox = enter_campaign_sandbox(saved.campaign_id.as_deref(), &saved.device_id)?;
+
+    let _ = state.stop_tx.send(false);
+    {
+        let mut runtime = state.runtime.write().await;
+        runtime.handle_command(AgentCommand::Start {
+            goal: saved.goal.clone(),
+            device_id: saved.device_id.clone(),
+        }).map_err(|e| e.to_string())?;
+        record_transition(&runtime);
+    }
+
+    let restored_plan = saved.resume_plan();
+    let mut message = match &restored_plan {
+        Some(plan) => format!("已从断点恢复: {}", plan.summary()),
+        None => "规划尚未完成，将重新规划".to_string(),
+    };
+    if let Some(pending) = &saved.pending_action {
+        message.push_str(&format!("；中断前待审批的动作 {} 已丢弃，将重新观察屏幕后决定", pending.name));
+    }
+    spawn_agent_loop(&app, &state, saved.goal, saved.device_id, restored_plan, sandbox).await;
+
+    Ok(AgentResponse {
+        success: true,
+        message,
+        error: None,
+    })
+}
+
+// ========== 沙箱写文件：write_file 工具的目录 / 大小 / 扩展名限制与写入台账 ==========
+
+mod agent_runtime_file_write;
+pub use agent_runtime_file_write::*;
+
+/// 获取写文件沙箱配置（含实际生效的沙箱目录）
+#[tauri::command]
+async fn get_agent_file_sandbox() -> Result<serde_json::Value, String> {
+    crate::core::plugin_isolation::guard("agent_runtime", get_agent_file_sandbox_impl()).await
+}
+
+async fn get_agent_file_sandbox_impl() -> Result<serde_json::Value, String> {
+    let store = FileSandboxStore::global().lock().map_err(|e| e.to_string())?;
+    Ok(serde_json::json!({
+        "config": store.config(),
+        "effectiveRootDir": store.root(),
+    }))
+}
+
+/// 保存写文件沙箱配置
+#[tauri::command]
+async fn save_agent_file_sandbox(config: FileSandboxConfig) -> Result<FileSandboxConfig, String> {
+    crate::core::plugin_isolation::guard("agent_runtime", async move { FileSandboxStore::global().lock().map_err(|e| e.to_string())?.save_config(config) }).await
+}
+
+/// Agent 写入文件的台账（新的在前）
+#[tauri::command]
+async fn list_agent_written_files(limit: Option<usize>) -> Result<Vec<FileWriteRecord>, String> {
+    crate::core::plugin_isolation::guard("agent_runtime", async move { Ok(FileSandboxStore::global().lock().map_err(|e| e.to_string())?.records(limit.unwrap_or(100))) }).await
+}
+
+// ========== 多目标队列：按优先级排队，同设备串行、跨设备并行 ==========
+
+mod agent_runtime_goal_queue;
+pub use agent_runtime_goal_queue::*;
+
+/// 前台单目标运行使用的目标 ID（事件与旧版前端兼容）
+pub const PRIMARY_GOAL_ID: &str = "current";
+
+/// 设备上有队列目标在运行时不允许再启动前台目标
+fn ensure_device_not_in_queue(state: &AgentRuntimeState, device_id: &str) -> Result<(), String> {
+    let scheduler = state.goal_scheduler.lock().map_err(|e| e.to_string())?;
+    let running = scheduler.queue.list().into_iter()
+        .any(|g| g.status == QueuedGoalStatus::Running && g.device_id == device_id);
+    if running {
+        return Err(format!("设备 {} 正在执行队列中的目标，请等待或取消后再启动", device_id));
+    }
+    Ok(())
+}
+
+/// 提交目标参数
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubmitGoalParams {
+    pub goal: String,
+    pub device_id: String,
+    /// 越大越优先，默认 0
+    pub priority: Option<i32>,
+}
+
+/// 提交目标到队列（设备空闲时立即开始）
+#[tauri::command]
+async fn submit_agent_goal<R: Runtime>(
+    app: AppHandle<R>,
+    params: SubmitGoalParams,
+    state: State<'_, AgentRuntimeState>,
+) -> Result<QueuedGoal, String> {
+    crate::core::plugin_isolation::guard("agent_runtime", submit_agent_goal_impl(app, params, state)).await
+}
+
+async fn submit_agent_goal_impl<R: Runtime>(
+    app: AppHandle<R>,
+    params: SubmitGoalParams,
+    state: State<'_, AgentRuntimeState>,
+) -> Result<QueuedGoal, String> {
+    crate::services::licensing::require_feature(crate::services::licensing::LicenseFeature::Agent)?;
+    if params.goal.trim().is_empty() || params.device_id.trim().is_empty() {
+        return Err("目标与设备不能为空".to_string());
+    }
+    let agent_state: tauri::State<'_, AgentState> = app.try_state::<AgentState>()
+        .ok_or("AI Agent 未初始化，请先配置 API Key")?;
+    if !agent_state.is_configured().await {
+        return Err("AI Agent 未配置，请先配置 API Key".to_string());
+    }
+
+    let queued = {
+        let mut scheduler = state.goal_scheduler.lock().map_err(|e| e.to_string())?;
+        let queued = scheduler.queue.submit(&params.goal, &params.device_id, params.priority.unwrap_or(0));
+        emit_goal_queue(&app, &scheduler);
+        queued
+    };
+    info!("🗂️ 目标已入队 {}: {} (设备 {}, 优先级 {})", queued.id, queued.goal, queued.device_id, queued.priority);
+    pump_goal_queue(&app, state.primary_busy_devices().await);
+
+    let scheduler = state.goal_scheduler.lock().map_err(|e| e.to_string())?;
+    Ok(scheduler.queue.get(&queued.id).cloned().unwrap_or(queued))
+}
+
+/// 列出队列中的目标（运行中 → 排队中 → 已结束）
+#[tauri::command]
+async fn list_agent_goals(state: State<'_, AgentRuntimeState>) -> Result<Vec<QueuedGoal>, String> {
+    crate::core::plugin_isolation::guard("agent_runtime", async move { Ok(state.goal_scheduler.lock().map_err(|e| e.to_string())?.queue.list()) }).await
+}
+
+/// 调整排队中目标的位置（0 为下一个执行）
+#[tauri::command]
+async fn reorder_agent_goal<R: Runtime>(
+    app: AppHandle<R>,
+    goal_id: String,
+    position: usize,
+    state: State<'_, AgentRuntimeState>,
+) -> Result<Vec<QueuedGoal>, String> {
+    crate::core::plugin_isolation::guard("agent_runtime", reorder_agent_goal_impl(app, goal_id, position, state)).await
+}
+
+async fn reorder_agent_goal_impl<R: Runtime>(
+    app: AppHandle<R>,
+    goal_id: String,
+    position: usize,
+    state: State<'_, AgentRuntimeState>,
+) -> Result<Vec<QueuedGoal>, String> {
+    let mut scheduler = state.goal_scheduler.lock().map_err(|e| e.to_string())?;
+    scheduler.queue.reorder(&goal_id, position)?;
+    emit_goal_queue(&app, &scheduler);
+    Ok(scheduler.queue.list())
+}
+
+/// 取消目标（运行中的目标会收到停止信号）
+#[tauri::command]
+async fn cancel_agent_goal<R: Runtime>(
+    app: AppHandle<R>,
+    goal_id: String,
+    state: State<'_, AgentRuntimeState>,
+) -> Result<QueuedGoal, String> {
+    crate::core::plugin_isolation::guard("agent_runtime", cancel_agent_goal_impl(app, goal_id, state)).await
+}
+
+async fn cancel_agent_goal_impl<R: Runtime>(
+    app: AppHandle<R>,
+    goal_id: String,
+    state: State<'_, AgentRuntimeState>,
+) -> Result<QueuedGoal, String> {
+    let mut scheduler = state.goal_scheduler.lock().map_err(|e| e.to_string())?;
+    let goal = scheduler.cancel(&goal_id)?;
+    info!("🗂️ 取消目标 {} ({:?})", goal_id, goal.status);
+    emit_goal_queue(&app, &scheduler);
+    Ok(goal)
+}
+
+/// 是否允许不同设备上的目标并行执行（关闭后全局串行）
+#[tauri::command]
+async fn set_agent_goal_parallel<R: Runtime>(
+    app: AppHandle<R>,
+    enabled: bool,
+    state: State<'_, AgentRuntimeState>,
+) -> Result<bool, String> {
+    crate::core::plugin_isolation::guard("agent_runtime", set_agent_goal_parallel_impl(app, enabled, state)).await
+}
+
+async fn set_agent_goal_parallel_impl<R: Runtime>(
+    app: AppHandle<R>,
+    enabled: bool,
+    state: State<'_, AgentRuntimeState>,
+) -> Result<bool, String> {
+    state.goal_scheduler.lock().map_err(|e| e.to_string())?.queue.parallel_across_devices = enabled;
+    if enabled {
+        pump_goal_queue(&app, state.primary_busy_devices().await);
+    }
+    Ok(enabled)
+}
+
+/// 获取队列目标的事件日志
+#[tauri::command]
+async fn get_agent_goal_events(goal_id: String, state: State<'_, AgentRuntimeState>) -> Result<AgentEventsResponse, String> {
+    crate::core::plugin_isolation::guard("agent_runtime", get_agent_goal_events_impl(goal_id, state)).await
+}
+
+async fn get_agent_goal_events_impl(goal_id: String, state: State<'_, AgentRuntimeState>) -> Result<AgentEventsResponse, String> {
+    let log = state.goal_scheduler.lock().map_err(|e| e.to_string())?.events(&goal_id)
+        .ok_or_else(|| format!("目标没有事件记录: {}", goal_id))?;
+    let events = log.read().await.clone();
+    Ok(AgentEventsResponse {
+        success: true,
+        events,
+    })
+}
+
+// ========== 副驾驶模式：用户手动操作，AI 只给建议 ==========
+
+mod agent_runtime_copilot;
+pub use agent_runtime_copilot::*;
+mod agent_runtime_response;
+pub use agent_runtime_response::*;
+
+/// 启动副驾驶参数
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StartCopilotParams {
+    /// 用户要达成的目标
+    pub goal: String,
+    /// 设备 ID
+    pub device_id: String,
+    /// 屏幕轮询间隔（毫秒）
+    pub poll_interval_ms: Option<u64>,
+}
+
+/// 启动副驾驶：观察用户操作并推送下一步建议（不执行任何动作）
+#[tauri::command]
+async fn start_copilot<R: Runtime>(
+    app: AppHandle<R>,
+    params: StartCopilotParams,
+    state: State<'_, AgentRuntimeState>,
+) -> Result<AgentResponse, String> {
+    crate::core::plugin_isolation::guard("agent_runtime", start_copilot_impl(app, params, state)).await
+}
+
+async fn start_copilot_impl<R: Runtime>(
+    app: AppHandle<R>,
+    params: StartCopilotParams,
+    state: State<'_, AgentRuntimeState>,
+) -> Result<AgentResponse, String> {
+    info!("🧭 启动副驾驶: goal={}, device={}", params.goal, params.device_id);
+    crate::services::licensing::require_feature(crate::services::licensing::LicenseFeature::Agent)?;
+    crate::services::telemetry::record_feature("agent_runtime.copilot");
+
+    let agent_state: tauri::State<'_, AgentState> = app.try_state::<AgentState>()
+        .ok_or("AI Agent 未初始化，请先配置 API Key")?;
+    if !agent_state.is_configured().await {
+        return Err("AI Agent 未配置，请先配置 API Key".to_string());
+    }
+    if *state.loop_running.read().await {
+        return Err("Agent 正在自主运行，请先停止后再进入副驾驶模式".to_string());
+    }
+    // 检查与置位在同一把写锁内完成，并发启动时只有一个能通过
+    {
+        let mut copilot_running = state.copilot_running.write().await;
+        if *copilot_running {
+            return Err("副驾驶已在运行".to_string());
+        }
+        *copilot_running = true;
+    }
+
+    let _ = state.copilot_stop_tx.send(false);
+    *state.copilot_stats.write().await = CopilotStats {
+        goal: Some(params.goal.clone()),
+        device_id: Some(params.device_id.clone()),
+        ..Default::default()
+    };
+
+    let stop_rx = state.copilot_stop_tx.subscribe();
+    let copilot_running = state.copilot_running.clone();
+    let event_log = state.event_log.clone();
+    let stats = state.copilot_stats.clone();
+    let app_handle = app.app_handle().clone();
+    let goal = params.goal.clone();
+    let device_id = params.device_id;
+    let poll_interval_ms = params.poll_interval_ms.unwrap_or(DEFAULT_COPILOT_POLL_MS);
+
+    tokio::spawn(async move {
+        run_copilot_loop(stop_rx, event_log, stats, app_handle, goal, device_id, poll_interval_ms).await;
+        *copilot_running.write().await = false;
+    });
+
+    Ok(AgentResponse {
+        success: true,
+        message: format!("副驾驶已启动，目标: {}", params.goal),
+        error: None,
+    })
+}
+
+/// 停止副驾驶
+#[tauri::command]
+async fn stop_copilot(state: State<'_, AgentRuntimeState>) -> Result<AgentResponse, String> {
+    crate::core::plugin_isolation::guard("agent_runtime", stop_copilot_impl(state)).await
+}
+
+async fn stop_copilot_impl(state: State<'_, AgentRuntimeState>) -> Result<AgentResponse, String> {
+    info!("🧭 停止副驾驶");
+    state.stop_copilot();
+    Ok(AgentResponse {
+        success: true,
+        message: "副驾驶已停止".to_string(),
+        error: None,
+    })
+}
+
+/// 副驾驶状态与统计
+#[tauri::command]
+async fn copilot_status(state: State<'_, AgentRuntimeState>) -> Result<serde_json::Value, String> {
+    crate::core::plugin_isolation::guard("agent_runtime", copilot_status_impl(state)).await
+}
+
+async fn copilot_status_impl(state: State<'_, AgentRuntimeState>) -> Result<serde_json::Value, String> {
+    let stats = state.copilot_stats.read().await.clone();
+    Ok(serde_json::json!({
+        "running": *state.copilot_running.read().await,
+        "stats": stats,
+    }))
+}
+
 // ========== P2: 任务分解规划器 ==========
 
 mod agent_runtime_planner {
@@ -429,19 +967,10 @@ mod agent_runtime_planner {
     
     /// 从 AI 响应解析任务列表
     pub fn parse_planning_response(response: &str) -> Result<Vec<SubTask>, String> {
-        // 尝试提取 JSON
-        let json_str = if let Some(start) = response.find('{') {
-            if let Some(end) = response.rfind('}') {
-                &response[start..=end]
-            } else {
-                return Err("未找到完整的 JSON".to_string());
-            }
-        } else {
-            return Err("响应中没有 JSON".to_string());
-        };
-        
-        let parsed: serde_json::Value = serde_json::from_str(json_str)
-            .map_err(|e| format!("JSON 解析失败: {}", e))?;
+        // 解析并校验 JSON（含自动修复）
+        let parsed = parse_structured(response, &planning_schema())
+            .map_err(|errors| errors.join("; "))?
+            .value;
         
         let tasks_array = parsed.get("tasks")
             .and_then(|v| v.as_array())
@@ -528,6 +1057,7 @@ mod agent_runtime_planner {
 2. 执行一个动作后观察结果
 3. 如果子任务完成，返回 "task_complete": true
 4. 参考历史经验，避免已知的失败模式
+5. 需要保存结果时使用 write_file 工具，参数 {{"path": "沙箱内相对路径", "content": "内容", "append": false}}
 
 ## 输出格式
 {{
@@ -560,6 +1090,10 @@ pub use agent_runtime_planner::*;
 /// 获取最新事件（轮询模式）
 #[tauri::command]
 async fn get_events(state: State<'_, AgentRuntimeState>) -> Result<AgentEventsResponse, String> {
+    crate::core::plugin_isolation::guard("agent_runtime", get_events_impl(state)).await
+}
+
+async fn get_events_impl(state: State<'_, AgentRuntimeState>) -> Result<AgentEventsResponse, String> {
     let mut log = state.event_log.write().await;
     let events = std::mem::take(&mut *log);
 
@@ -579,20 +1113,28 @@ pub struct CollaborationStatusResponse {
     pub connection_state: String,
     pub phone_address: Option<String>,
     pub mode: String,
+    pub transport: CollaborationTransport,
     pub error: Option<String>,
 }
 
-/// 连接到手机
+/// 连接到手机（端口缺省时取协同设置中当前传输方式的端口）
 #[tauri::command]
 async fn connect_phone(
     phone_ip: String,
     port: Option<u16>,
 ) -> Result<CollaborationStatusResponse, String> {
-    let port = port.unwrap_or(8765);
-    info!("📱 尝试连接手机: {}:{}", phone_ip, port);
+    crate::core::plugin_isolation::guard("agent_runtime", connect_phone_impl(phone_ip, port)).await
+}
+
+async fn connect_phone_impl(
+    phone_ip: String,
+    port: Option<u16>,
+) -> Result<CollaborationStatusResponse, String> {
+    let settings = CollaborationSettingsStore::global().lock().map_err(|e| e.to_string())?.settings().clone();
+    let port = port.unwrap_or_else(|| settings.default_port());
+    info!("📱 尝试连接手机: {}:{} ({:?})", phone_ip, port, settings.transport);
     
-    // 创建协同管理器（实际使用时应该是单例状态）
-    let manager = CollaborationManager::new();
+    let manager = CollaborationManager::global();
     
     match manager.connect(&phone_ip, port).await {
         Ok(_) => {
@@ -602,6 +1144,7 @@ async fn connect_phone(
                 connection_state: format!("{:?}", session.connection_state),
                 phone_address: session.phone_address,
                 mode: format!("{:?}", session.mode),
+                transport: session.transport,
                 error: None,
             })
         }
@@ -611,6 +1154,7 @@ async fn connect_phone(
                 connection_state: "Disconnected".to_string(),
                 phone_address: None,
                 mode: "PcAsBrain".to_string(),
+                transport: settings.transport,
                 error: Some(e),
             })
         }
@@ -620,8 +1164,12 @@ async fn connect_phone(
 /// 断开手机连接
 #[tauri::command]
 async fn disconnect_phone() -> Result<AgentResponse, String> {
+    crate::core::plugin_isolation::guard("agent_runtime", disconnect_phone_impl()).await
+}
+
+async fn disconnect_phone_impl() -> Result<AgentResponse, String> {
     info!("📱 断开手机连接");
-    // TODO: 实际断开连接
+    CollaborationManager::global().disconnect().await;
     Ok(AgentResponse {
         success: true,
         message: "已断开手机连接".to_string(),
@@ -635,13 +1183,21 @@ async fn send_goal_to_phone(
     goal: String,
     max_steps: Option<u32>,
     timeout_seconds: Option<u32>,
+) -> Result<AgentResponse, String> {
+    crate::core::plugin_isolation::guard("agent_runtime", send_goal_to_phone_impl(goal, max_steps, timeout_seconds)).await
+}
+
+async fn send_goal_to_phone_impl(
+    goal: String,
+    max_steps: Option<u32>,
+    timeout_seconds: Option<u32>,
 ) -> Result<AgentResponse, String> {
     let max_steps = max_steps.unwrap_or(20);
     let timeout = timeout_seconds.unwrap_or(60);
     
     info!("📱 发送目标到手机: {} (最大步骤: {}, 超时: {}s)", goal, max_steps, timeout);
     
-    let manager = CollaborationManager::new();
+    let manager = CollaborationManager::global();
     match manager.send_goal(&goal, max_steps, timeout).await {
         Ok(_) => Ok(AgentResponse {
             success: true,
@@ -662,12 +1218,20 @@ async fn execute_action_on_phone(
     action_type: String,
     target: String,
     params: Option<serde_json::Value>,
+) -> Result<AgentResponse, String> {
+    crate::core::plugin_isolation::guard("agent_runtime", execute_action_on_phone_impl(action_type, target, params)).await
+}
+
+async fn execute_action_on_phone_impl(
+    action_type: String,
+    target: String,
+    params: Option<serde_json::Value>,
 ) -> Result<AgentResponse, String> {
     let params = params.unwrap_or(serde_json::Value::Null);
     
     info!("📱 请求手机执行: {} -> {}", action_type, target);
     
-    let manager = CollaborationManager::new();
+    let manager = CollaborationManager::global();
     match manager.execute_on_phone(&action_type, &target, params).await {
         Ok(_) => Ok(AgentResponse {
             success: true,
@@ -682,42 +1246,30 @@ async fn execute_action_on_phone(
     }
 }
 
-// ========== Agent 对话历史管理 ==========
+/// 读取协同设置（grpcAvailable 表示当前构建是否启用了 grpc-bridge 功能）
+#[tauri::command]
+async fn get_collaboration_settings() -> Result<serde_json::Value, String> {
+    crate::core::plugin_isolation::guard("agent_runtime", get_collaboration_settings_impl()).await
+}
 
-/// 对话历史配置（使用 agent_runtime_ 前缀避免命名冲突）
-mod agent_runtime_history {
-    /// 最大历史条数（超过后滑动窗口）
-    pub const MAX_HISTORY_SIZE: usize = 20;
-    /// 保留的最近消息数（滑动时保留）
-    pub const KEEP_RECENT_COUNT: usize = 15;
-    /// 单条消息最大字符数（超过截断）
-    pub const MAX_MESSAGE_LENGTH: usize = 2000;
-    
-    /// 截断过长消息
-    pub fn truncate_message(msg: &str) -> String {
-        if msg.len() > MAX_MESSAGE_LENGTH {
-            format!("{}...(已截断，原长度:{})", &msg[..MAX_MESSAGE_LENGTH], msg.len())
-        } else {
-            msg.to_string()
-        }
-    }
-    
-    /// 维护对话历史大小（滑动窗口）
-    pub fn maintain_history(history: &mut Vec<String>, system_prompt: &str) {
-        if history.len() > MAX_HISTORY_SIZE {
-            // 保留系统提示词 + 最近的消息
-            let keep_start = history.len() - KEEP_RECENT_COUNT;
-            let recent: Vec<String> = history.drain(keep_start..).collect();
-            history.clear();
-            history.push(system_prompt.to_string());
-            history.push("[...历史消息已压缩...]".to_string());
-            history.extend(recent);
-            tracing::info!("📜 对话历史已压缩: 保留最近 {} 条", KEEP_RECENT_COUNT);
-        }
-    }
+async fn get_collaboration_settings_impl() -> Result<serde_json::Value, String> {
+    let store = CollaborationSettingsStore::global().lock().map_err(|e| e.to_string())?;
+    Ok(serde_json::json!({
+        "settings": store.settings(),
+        "grpcAvailable": cfg!(feature = "grpc-bridge"),
+    }))
+}
+
+/// 保存协同设置（下次连接手机时生效）
+#[tauri::command]
+async fn save_collaboration_settings(settings: CollaborationSettings) -> Result<CollaborationSettings, String> {
+    crate::core::plugin_isolation::guard("agent_runtime", async move { CollaborationSettingsStore::global().lock().map_err(|e| e.to_string())?.save(settings) }).await
 }
 
-use agent_runtime_history::*;
+// ========== Agent 对话上下文管理 ==========
+
+mod agent_runtime_context;
+use agent_runtime_context::*;
 
 // ========== 多模态屏幕分析（P1 改进）==========
 
@@ -852,13 +1404,21 @@ use agent_runtime_recovery::*;
 // ========== Tauri 事件推送（替代轮询）==========
 
 /// Agent 事件名称常量（使用 agent_runtime_ 前缀避免冲突）
-mod agent_runtime_events {
+pub(crate) mod agent_runtime_events {
     pub const EVENT_STATE_CHANGED: &str = "agent_runtime:state_changed";
     pub const EVENT_PROGRESS: &str = "agent_runtime:progress";
     pub const EVENT_ACTION: &str = "agent_runtime:action";
     pub const EVENT_THINKING: &str = "agent_runtime:thinking";
+    pub const EVENT_THINKING_DELTA: &str = "agent_runtime:thinking_delta";
     pub const EVENT_ERROR: &str = "agent_runtime:error";
     pub const EVENT_COMPLETED: &str = "agent_runtime:completed";
+    pub const EVENT_SUGGESTION: &str = "agent_runtime:suggestion";
+    /// 队列目标的事件（带 goalId）
+    pub const EVENT_GOAL_EVENT: &str = "agent_runtime:goal_event";
+    /// 目标队列变化（完整列表）
+    pub const EVENT_GOAL_QUEUE: &str = "agent_runtime:goal_queue";
+    /// 沙箱目录写入了文件
+    pub const EVENT_FILE_WRITTEN: &str = "agent_runtime:file_written";
 }
 
 use agent_runtime_events::*;
@@ -870,6 +1430,9 @@ fn emit_agent_event<R: Runtime>(app: &AppHandle<R>, event: &AgentEvent) {
         AgentEvent::GoalProgress { .. } => EVENT_PROGRESS,
         AgentEvent::ActionExecuted { .. } => EVENT_ACTION,
         AgentEvent::AiThinking { .. } => EVENT_THINKING,
+        AgentEvent::ThinkingDelta { .. } => EVENT_THINKING_DELTA,
+        AgentEvent::Suggestion { .. } => EVENT_SUGGESTION,
+        AgentEvent::FileWritten { .. } => EVENT_FILE_WRITTEN,
         AgentEvent::Error { .. } => EVENT_ERROR,
         AgentEvent::GoalCompleted { .. } | AgentEvent::GoalFailed { .. } => EVENT_COMPLETED,
         _ => EVENT_STATE_CHANGED,
@@ -880,6 +1443,68 @@ fn emit_agent_event<R: Runtime>(app: &AppHandle<R>, event: &AgentEvent) {
     }
 }
 
+/// 流式调用 AI：增量文本以 thinking_delta 事件实时推送（不写入事件日志），返回累积的完整文本
+///
+/// 停止信号或超时会直接丢弃请求，底层 HTTP 连接随之中断。
+async fn chat_with_thinking_stream<R: Runtime>(
+    agent_state: &AgentState,
+    app_handle: &AppHandle<R>,
+    prompt: &str,
+    cancel: &CancellationToken,
+    timeout: std::time::Duration,
+) -> Result<String, AiCallError> {
+    let stream_id = uuid::Uuid::new_v4().to_string();
+    let app = app_handle.clone();
+    let on_delta: DeltaCallback = Arc::new(move |delta: &str| {
+        emit_agent_event(&app, &AgentEvent::ThinkingDelta {
+            stream_id: stream_id.clone(),
+            delta: delta.to_string(),
+        });
+    });
+    with_deadline(agent_state.chat_with_ai_streaming(prompt, on_delta), timeout, Some(cancel)).await
+}
+
+/// 结构化 AI 回复
+struct StructuredReply {
+    /// 最后一次 AI 回复原文
+    raw: String,
+    /// 校验通过的 JSON，或最后一次的校验错误
+    value: Result<serde_json::Value, Vec<String>>,
+}
+
+/// 请求 AI 并按 schema 校验回复：先自动修复，仍不合格时追加引用校验错误的约束重提示
+async fn chat_structured<R: Runtime>(
+    agent_state: &AgentState,
+    app_handle: &AppHandle<R>,
+    prompt: &str,
+    schema: &serde_json::Value,
+    cancel: &CancellationToken,
+    timeout: std::time::Duration,
+) -> Result<StructuredReply, AiCallError> {
+    let mut raw = chat_with_thinking_stream(agent_state, app_handle, prompt, cancel, timeout).await?;
+    let mut repair_prompts = 0;
+
+    loop {
+        match parse_structured(&raw, schema) {
+            Ok(parsed) => {
+                if parsed.repaired {
+                    info!("🩹 AI 响应经自动修复后通过校验");
+                }
+                return Ok(StructuredReply { raw, value: Ok(parsed.value) });
+            }
+            Err(errors) if repair_prompts >= MAX_FORMAT_REPAIR_PROMPTS => {
+                return Ok(StructuredReply { raw, value: Err(errors) });
+            }
+            Err(errors) => {
+                repair_prompts += 1;
+                warn!("⚠️ AI 响应未通过格式校验（第 {} 次重提示）: {}", repair_prompts, errors.join("; "));
+                let correction = build_format_correction_prompt(&errors, schema, &raw);
+                raw = chat_with_thinking_stream(agent_state, app_handle, &correction, cancel, timeout).await?;
+            }
+        }
+    }
+}
+
 // ========== Agent 循环实现 ==========
 
 /// 发送事件的辅助函数
@@ -896,32 +1521,85 @@ async fn send_agent_event<R: Runtime>(
     l.push(event);
 }
 
+/// 队列目标事件负载
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct GoalEventPayload<'a> {
+    goal_id: &'a str,
+    event: &'a AgentEvent,
+}
+
+/// 按目标推送事件：前台目标沿用原有事件并写入会话摘要；
+/// 队列目标只推送带 goalId 的 goal_event、记录到各自的事件日志，并更新队列进度
+async fn send_goal_event<R: Runtime>(
+    goal_id: &str,
+    event_log: &Arc<RwLock<Vec<AgentEvent>>>,
+    app_handle: &AppHandle<R>,
+    event: AgentEvent,
+) {
+    if goal_id == PRIMARY_GOAL_ID {
+        // 写入会话摘要，供重启后恢复
+        record_event(&event);
+        send_agent_event(event_log, app_handle, event).await;
+        return;
+    }
+    if let AgentEvent::GoalProgress { progress, description, .. } = &event {
+        update_queued_goal_progress(app_handle, goal_id, *progress, description);
+    }
+    if let Err(e) = app_handle.emit(EVENT_GOAL_EVENT, GoalEventPayload { goal_id, event: &event }) {
+        warn!("发送目标事件失败: {}", e);
+    }
+    let mut l = event_log.write().await;
+    if l.len() > 100 { l.drain(0..50); }
+    l.push(event);
+}
+
 /// Agent 自主循环（集成任务规划器）
+///
+/// `goal_id` 为 [`PRIMARY_GOAL_ID`] 时是前台单目标运行（会话写盘以便重启恢复），
+/// 否则是目标队列中的一项；目标未完成（停止 / 规划失败）时返回原因
 async fn run_agent_loop<R: Runtime>(
     runtime: SharedAgentRuntime,
     mut stop_rx: watch::Receiver<bool>,
     event_log: Arc<RwLock<Vec<AgentEvent>>>,
     app_handle: AppHandle<R>,
+    goal_id: String,
     goal: String,
     device_id: String,
-) {
+    restored_plan: Option<ExecutionPlan>,
+) -> Result<(), String> {
     use tokio::time::Duration;
 
-    info!("🚀 启动带规划的 Agent 循环: goal={}", goal);
-
-    // ========== 阶段1: 任务规划 ==========
-    send_agent_event(&event_log, &app_handle, AgentEvent::AiThinking {
-        thought: "正在分析目标并制定执行计划...".to_string(),
+    info!("🚀 启动带规划的 Agent 循环: goal_id={}, goal={}, 恢复={}", goal_id, goal, restored_plan.is_some());
+    let persist = goal_id == PRIMARY_GOAL_ID;
+
+    // 停止时同时中断进行中的 AI 请求
+    let ai_cancel = CancellationToken::from_signal(stop_rx.clone());
+    let ai_timeout = runtime.read().await.config().ai_call_timeout;
+    // 屏幕摘要最多占输入预算的一半，其余留给计划与记忆
+    let screen_budget = match app_handle.try_state::<AgentState>() {
+        Some(agent_state) => agent_state.input_budget_tokens().await,
+        None => None,
+    }.unwrap_or(DEFAULT_HISTORY_BUDGET_TOKENS) / 2;
+
+    // ========== 阶段1: 任务规划（从断点恢复时沿用已保存的计划） ==========
+    send_goal_event(&goal_id, &event_log, &app_handle, AgentEvent::AiThinking {
+        thought: match &restored_plan {
+            Some(plan) => format!("从上次中断处继续: {}", plan.summary()),
+            None => "正在分析目标并制定执行计划...".to_string(),
+        },
     }).await;
 
     // 调用 AI 进行任务分解
-    let execution_plan = if let Some(agent_state) = app_handle.try_state::<AgentState>() {
+    let execution_plan = if restored_plan.is_some() {
+        restored_plan
+    } else if let Some(agent_state) = app_handle.try_state::<AgentState>() {
         let planning_prompt = build_planning_prompt(&goal);
         
-        match agent_state.chat_with_ai(&planning_prompt).await {
-            Ok(response) => {
-                info!("📋 收到规划响应: {}", &response[..response.len().min(300)]);
-                match parse_planning_response(&response) {
+        match chat_structured(&agent_state, &app_handle, &planning_prompt, &planning_schema(), &ai_cancel, ai_timeout).await {
+            Ok(reply) => {
+                info!("📋 收到规划响应: {}", reply.raw.chars().take(300).collect::<String>());
+                match parse_planning_response(&reply.raw) {
                     Ok(tasks) => {
                         info!("✅ 任务分解成功: {} 个子任务", tasks.len());
                         Some(ExecutionPlan::new(goal.clone(), tasks))
@@ -936,9 +1614,13 @@ async fn run_agent_loop<R: Runtime>(
                     }
                 }
             }
+            Err(AiCallError::Cancelled) => {
+                info!("⏹️ 规划阶段已停止");
+                return Err("规划阶段已停止".to_string());
+            }
             Err(e) => {
                 error!("❌ 规划 AI 调用失败: {}", e);
-                send_agent_event(&event_log, &app_handle, AgentEvent::Error {
+                send_goal_event(&goal_id, &event_log, &app_handle, AgentEvent::Error {
                     message: format!("规划失败: {}", e),
                 }).await;
                 None
@@ -952,16 +1634,19 @@ async fn run_agent_loop<R: Runtime>(
     let mut plan = match execution_plan {
         Some(p) => p,
         None => {
-            send_agent_event(&event_log, &app_handle, AgentEvent::GoalFailed {
-                goal_id: "current".to_string(),
+            send_goal_event(&goal_id, &event_log, &app_handle, AgentEvent::GoalFailed {
+                goal_id: goal_id.clone(),
                 reason: "无法创建执行计划".to_string(),
             }).await;
-            return;
+            if persist {
+                finish_session();
+            }
+            return Err("无法创建执行计划".to_string());
         }
     };
 
     // 通知前端计划已创建
-    send_agent_event(&event_log, &app_handle, AgentEvent::AiThinking {
+    send_goal_event(&goal_id, &event_log, &app_handle, AgentEvent::AiThinking {
         thought: format!("计划已创建: {} 个步骤\n{}", plan.total_tasks, plan.summary()),
     }).await;
 
@@ -973,11 +1658,16 @@ async fn run_agent_loop<R: Runtime>(
             Some(t) => t.clone(),
             None => break,
         };
+        // 安全点：子任务开始前尚未对设备做任何操作，重启后从这里继续
+        if persist {
+            record_plan(&plan);
+            record_transition(&*runtime.read().await);
+        }
 
         info!("📌 执行子任务 {}: {}", current_task.id, current_task.description);
         
-        send_agent_event(&event_log, &app_handle, AgentEvent::GoalProgress {
-            goal_id: "current".to_string(),
+        send_goal_event(&goal_id, &event_log, &app_handle, AgentEvent::GoalProgress {
+            goal_id: goal_id.clone(),
             progress: plan.progress_percent(),
             description: format!("步骤 {}/{}: {}", 
                 plan.current_task_index + 1, 
@@ -988,7 +1678,7 @@ async fn run_agent_loop<R: Runtime>(
 
         // 获取屏幕上下文
         let screen_context = match get_screen_xml(&adb_path, &device_id).await {
-            Ok(xml) => extract_screen_summary(&xml),
+            Ok(xml) => truncate_to_tokens(&extract_screen_summary(&xml), screen_budget),
             Err(e) => format!("无法获取屏幕: {}", e),
         };
 
@@ -1001,8 +1691,9 @@ async fn run_agent_loop<R: Runtime>(
 
         // 调用 AI 决定动作
         let ai_response = if let Some(agent_state) = app_handle.try_state::<AgentState>() {
-            match agent_state.chat_with_ai(&task_prompt).await {
-                Ok(r) => Some(r),
+            match chat_structured(&agent_state, &app_handle, &task_prompt, &task_step_schema(), &ai_cancel, ai_timeout).await {
+                Ok(reply) => Some(reply),
+                Err(AiCallError::Cancelled) => break,
                 Err(e) => {
                     error!("❌ AI 调用失败: {}", e);
                     None
@@ -1013,15 +1704,15 @@ async fn run_agent_loop<R: Runtime>(
         };
 
         // 处理 AI 响应
-        if let Some(response) = ai_response {
-            info!("🤖 AI 响应: {}", &response[..response.len().min(200)]);
+        if let Some(reply) = ai_response {
+            info!("🤖 AI 响应: {}", reply.raw.chars().take(200).collect::<String>());
             
-            if let Ok(parsed) = serde_json::from_str::<serde_json::Value>(&response) {
+            if let Ok(parsed) = &reply.value {
                 let thought = parsed.get("thought")
                     .and_then(|v| v.as_str())
                     .unwrap_or("");
                 
-                send_agent_event(&event_log, &app_handle, AgentEvent::AiThinking {
+                send_goal_event(&goal_id, &event_log, &app_handle, AgentEvent::AiThinking {
                     thought: thought.to_string(),
                 }).await;
 
@@ -1039,7 +1730,7 @@ async fn run_agent_loop<R: Runtime>(
                     info!("✅ 子任务 {} 完成: {}", current_task.id, result);
                     plan.complete_current(result);
                     
-                    send_agent_event(&event_log, &app_handle, AgentEvent::ActionExecuted {
+                    send_goal_event(&goal_id, &event_log, &app_handle, AgentEvent::ActionExecuted {
                         action: format!("完成子任务: {}", current_task.description),
                         result: "成功".to_string(),
                         success: true,
@@ -1049,15 +1740,19 @@ async fn run_agent_loop<R: Runtime>(
                     let params = parsed.get("params").cloned()
                         .unwrap_or(serde_json::json!({}));
                     
-                    send_agent_event(&event_log, &app_handle, AgentEvent::ActionExecuted {
+                    send_goal_event(&goal_id, &event_log, &app_handle, AgentEvent::ActionExecuted {
                         action: action.to_string(),
                         result: "执行中...".to_string(),
                         success: true,
                     }).await;
 
-                    let result = execute_agent_tool(action, &params, &device_id).await;
+                    let result = if action == WRITE_FILE_TOOL {
+                        run_write_file_tool(&runtime, &stop_rx, &event_log, &app_handle, &goal_id, &device_id, &params).await
+                    } else {
+                        execute_agent_tool(action, &params, &device_id).await
+                    };
                     
-                    send_agent_event(&event_log, &app_handle, AgentEvent::ActionExecuted {
+                    send_goal_event(&goal_id, &event_log, &app_handle, AgentEvent::ActionExecuted {
                         action: action.to_string(),
                         result: result.message.clone(),
                         success: result.success,
@@ -1073,8 +1768,8 @@ async fn run_agent_loop<R: Runtime>(
                     // 等待动作生效
                     tokio::time::sleep(Duration::from_millis(500)).await;
                 }
-            } else {
-                warn!("⚠️ AI 响应非 JSON: {}", &response[..response.len().min(100)]);
+            } else if let Err(errors) = &reply.value {
+                warn!("⚠️ AI 响应格式错误: {}", errors.join("; "));
                 plan.fail_current("AI 响应格式错误".to_string());
             }
         } else {
@@ -1120,20 +1815,27 @@ async fn run_agent_loop<R: Runtime>(
         
         if failed_count == 0 {
             info!("🎉 目标完成: {} 个子任务全部成功", completed_count);
-            send_agent_event(&event_log, &app_handle, AgentEvent::GoalCompleted {
-                goal_id: "current".to_string(),
+            send_goal_event(&goal_id, &event_log, &app_handle, AgentEvent::GoalCompleted {
+                goal_id: goal_id.clone(),
             }).await;
         } else {
             info!("⚠️ 目标部分完成: {}/{} 成功", completed_count, plan.total_tasks);
-            send_agent_event(&event_log, &app_handle, AgentEvent::GoalCompleted {
-                goal_id: "current".to_string(),
+            send_goal_event(&goal_id, &event_log, &app_handle, AgentEvent::GoalCompleted {
+                goal_id: goal_id.clone(),
             }).await;
         }
         
         let mut rt = runtime.write().await;
         rt.complete_current_goal();
+        if persist {
+            finish_session();
+        }
+        Ok(())
     } else if *stop_rx.borrow() {
         info!("🛑 用户停止执行");
+        Err("已停止".to_string())
+    } else {
+        Err("目标未完成".to_string())
     }
 }
 
@@ -1166,6 +1868,10 @@ async fn run_agent_loop_legacy<R: Runtime>(
         }
     };
 
+    // 停止时同时中断进行中的 AI 请求
+    let ai_cancel = CancellationToken::from_signal(stop_rx.clone());
+    let ai_timeout = runtime.read().await.config().ai_call_timeout;
+
     // 构建系统提示词
     let system_prompt = format!(
         r#"你是一个自主执行任务的 AI Agent。你的当前目标是：{goal}
@@ -1201,8 +1907,13 @@ async fn run_agent_loop_legacy<R: Runtime>(
         device_id = device_id
     );
 
-    // 记录历史对话用于上下文
-    let mut conversation_history: Vec<String> = vec![system_prompt.clone()];
+    // 按模型上下文预算管理对话：系统提示词与最近屏幕常驻，较早轮次摘要
+    let history_budget = match app_handle.try_state::<AgentState>() {
+        Some(agent_state) => agent_state.input_budget_tokens().await,
+        None => None,
+    }.unwrap_or(DEFAULT_HISTORY_BUDGET_TOKENS);
+    let mut context = ContextManager::new(history_budget);
+    context.pin("system", system_prompt);
 
     loop {
         // 检查停止信号
@@ -1231,14 +1942,16 @@ async fn run_agent_loop_legacy<R: Runtime>(
 
                 // 获取 AgentState 并调用 AI
                 let ai_response = if let Some(agent_state) = app_handle.try_state::<AgentState>() {
-                    // 构建上下文消息
-                    let context_message = conversation_history.join("\n---\n");
+                    // 构建上下文消息（必要时先摘要较早轮次）
+                    compact_context(&mut context, &agent_state).await;
+                    let context_message = context.assemble();
                     
-                    match agent_state.chat_with_ai(&context_message).await {
+                    match chat_with_thinking_stream(&agent_state, &app_handle, &context_message, &ai_cancel, ai_timeout).await {
                         Ok(response) => {
                             info!("🧠 AI 响应: {}", &response[..response.len().min(200)]);
                             Some(response)
                         }
+                        Err(AiCallError::Cancelled) => break,
                         Err(e) => {
                             error!("❌ AI 调用失败: {}", e);
                             add_and_emit_event(&event_log, &app_handle, AgentEvent::Error {
@@ -1254,13 +1967,12 @@ async fn run_agent_loop_legacy<R: Runtime>(
 
                 // 解析 AI 响应
                 if let Some(response) = ai_response {
-                    // 将响应添加到历史（截断过长内容）
-                    conversation_history.push(truncate_message(&format!("AI: {}", response)));
-                    // 维护历史大小（滑动窗口）
-                    maintain_history(&mut conversation_history, &system_prompt);
+                    // 将响应添加到历史
+                    context.push_turn(format!("AI: {}", response));
 
-                    // 尝试解析 JSON 响应
-                    if let Ok(parsed) = serde_json::from_str::<serde_json::Value>(&response) {
+                    // 解析并校验 JSON 响应（含自动修复）
+                    let parsed_response = parse_structured(&response, &decision_schema());
+                    if let Ok(ParsedResponse { value: parsed, .. }) = &parsed_response {
                         let thought = parsed.get("thought")
                             .and_then(|v| v.as_str())
                             .unwrap_or("思考中...");
@@ -1299,10 +2011,11 @@ async fn run_agent_loop_legacy<R: Runtime>(
                             // 没有行动，继续思考
                             tokio::time::sleep(Duration::from_secs(1)).await;
                         }
-                    } else {
-                        // JSON 解析失败，记录错误并重试
-                        warn!("⚠️ AI 响应非 JSON 格式: {}", &response[..response.len().min(100)]);
-                        conversation_history.push("System: 请用 JSON 格式回复。".to_string());
+                    } else if let Err(errors) = &parsed_response {
+                        // 修复后仍不合格：引用校验错误约束下一轮回复
+                        warn!("⚠️ AI 响应格式错误: {}", errors.join("; "));
+                        let correction = build_format_correction_prompt(errors, &decision_schema(), &response);
+                        context.push_turn(format!("System: {}", correction));
                         tokio::time::sleep(Duration::from_secs(1)).await;
                     }
                 } else {
@@ -1341,8 +2054,7 @@ async fn run_agent_loop_legacy<R: Runtime>(
                     } else {
                         format!("动作 {} 执行失败: {}", action_name, result.message)
                     };
-                    conversation_history.push(truncate_message(&format!("System: {}", result_text)));
-                    maintain_history(&mut conversation_history, &system_prompt);
+                    context.push_turn(format!("System: {}", result_text));
 
                     {
                         let mut rt = runtime.write().await;
@@ -1366,8 +2078,13 @@ async fn run_agent_loop_legacy<R: Runtime>(
                 }
             }
             AgentRunState::Observing => {
-                // ========== 观察阶段：获取屏幕状态并反馈给 AI ==========
-                tokio::time::sleep(Duration::from_millis(300)).await;
+                // ========== 观察阶段：等待屏幕稳定后获取屏幕状态并反馈给 AI ==========
+                let idle_options = crate::services::screen_stability::IdleOptions {
+                    quiet_ms: 300,
+                    timeout_ms: 3_000,
+                    ..Default::default()
+                };
+                crate::services::screen_stability::wait_for_idle(&device_id, &idle_options).await;
 
                 // 获取当前屏幕状态
                 let adb_path = crate::utils::adb_utils::get_adb_path();
@@ -1382,9 +2099,8 @@ async fn run_agent_loop_legacy<R: Runtime>(
                     }
                 };
 
-                // 添加屏幕状态到对话历史（截断过长内容）
-                conversation_history.push(truncate_message(&format!("System: {}", screen_info)));
-                maintain_history(&mut conversation_history, &system_prompt);
+                // 最近屏幕作为固定项，只保留最新一份
+                context.pin("screen", format!("System: 当前屏幕: {}", screen_info));
 
                 let progress = {
                     let rt = runtime.read().await;
@@ -1505,6 +2221,15 @@ async fn execute_agent_tool(
     
     info!("🔧 执行工具: {} params={:?} device={}", action, params, device_id);
     
+    // 沙箱检查：无论 AI 建议什么动作，越权动作在这里统一拦截
+    let package = params.get("package_name").or_else(|| params.get("package")).and_then(|v| v.as_str());
+    if let Err(e) = crate::core::application::sandbox_service::check_action(action, Some(device_id), package) {
+        return ToolExecutionResult {
+            success: false,
+            message: e.to_string(),
+        };
+    }
+    
     match action {
         "direct_tap" | "tap" => {
             let x = params.get("x").and_then(|v| v.as_i64()).unwrap_or(0) as i32;
@@ -1608,6 +2333,11 @@ async fn execute_agent_tool(
             let path = params.get("path").and_then(|v| v.as_str()).unwrap_or(".");
             list_directory(path).await
         }
+        // 写文件需要审批与台账，只在自主循环中经 run_write_file_tool 执行
+        WRITE_FILE_TOOL => ToolExecutionResult {
+            success: false,
+            message: "write_file 只能在 Agent 自主运行中使用".to_string(),
+        },
         _ => {
             ToolExecutionResult {
                 success: false,
@@ -1930,40 +2660,18 @@ fn calculate_swipe_coords(direction: &str, distance: &str) -> (i32, i32, i32, i3
 
 /// 从屏幕 XML 提取关键元素摘要（供 AI 分析）
 fn extract_screen_summary(xml: &str) -> String {
-    let mut elements: Vec<String> = Vec::new();
-    let mut element_count = 0;
-    
-    for line in xml.lines() {
-        // 提取有文本的元素
-        if let Some(start) = line.find("text=\"") {
-            let text_start = start + 6;
-            if let Some(end) = line[text_start..].find('"') {
-                let text_value = &line[text_start..text_start + end];
-                if !text_value.is_empty() && element_count < 30 {
-                    // 提取类名
-                    let class = if let Some(class_start) = line.find("class=\"") {
-                        let cs = class_start + 7;
-                        if let Some(ce) = line[cs..].find('"') {
-                            let full_class = &line[cs..cs + ce];
-                            // 简化类名（只保留最后一部分）
-                            full_class.rsplit('.').next().unwrap_or(full_class)
-                        } else {
-                            "?"
-                        }
-                    } else {
-                        "?"
-                    };
-                    
-                    // 检查是否可点击
-                    let clickable = line.contains("clickable=\"true\"");
-                    let click_mark = if clickable { "🔘" } else { "  " };
-                    
-                    elements.push(format!("{} [{}] \"{}\"", click_mark, class, text_value));
-                    element_count += 1;
-                }
-            }
-        }
-    }
+    let tree = UiTree::parse_lenient(xml);
+    let elements: Vec<String> = tree
+        .nodes()
+        .filter(|node| !node.text().is_empty())
+        .take(30)
+        .map(|node| {
+            // 简化类名（只保留最后一部分）
+            let class = node.class_name().map_or("?", |full| full.rsplit('.').next().unwrap_or(full));
+            let click_mark = if node.flag(UiFlag::Clickable) { "🔘" } else { "  " };
+            format!("{} [{}] \"{}\"", click_mark, class, node.text())
+        })
+        .collect();
     
     if elements.is_empty() {
         "屏幕上没有检测到文本元素".to_string()
@@ -1977,14 +2685,17 @@ fn extract_screen_summary(xml: &str) -> String {
 /// PC-手机协同模块
 /// 
 /// 职责：
-/// - PC 作为 WebSocket 客户端连接手机（手机是服务端）
+/// - PC 作为客户端连接手机（手机是服务端），传输方式见协同设置：WebSocket 或 gRPC（grpc-bridge 功能）
 /// - 发送目标/命令给手机执行
 /// - 接收手机的状态、屏幕、日志等反馈
 /// - 实现"大脑(PC) + 执行器(手机)"的分离架构
 mod agent_runtime_collaboration {
+    use super::{CollaborationSettings, CollaborationSettingsStore, CollaborationTransport};
     use serde::{Deserialize, Serialize};
-    use std::sync::Arc;
+    use std::sync::{Arc, OnceLock};
     use tokio::sync::RwLock;
+
+    static COLLABORATION_MANAGER: OnceLock<CollaborationManager> = OnceLock::new();
     
     /// 协同模式
     #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -2113,6 +2824,9 @@ mod agent_runtime_collaboration {
         pub phone_address: Option<String>,
         /// 协同模式
         pub mode: CollaborationMode,
+        /// 本次连接使用的传输方式
+        #[serde(default)]
+        pub transport: CollaborationTransport,
         /// 最后一次心跳
         pub last_heartbeat: Option<u64>,
         /// 当前手机状态
@@ -2127,6 +2841,7 @@ mod agent_runtime_collaboration {
                 connection_state: PhoneConnectionState::Disconnected,
                 phone_address: None,
                 mode: CollaborationMode::default(),
+                transport: CollaborationTransport::default(),
                 last_heartbeat: None,
                 phone_status: None,
                 pending_messages: Vec::new(),
@@ -2137,6 +2852,9 @@ mod agent_runtime_collaboration {
     /// 协同管理器（单例）
     pub struct CollaborationManager {
         session: Arc<RwLock<CollaborationSession>>,
+        /// gRPC 通道（选择 gRPC 传输并连接成功后存在）
+        #[cfg(feature = "grpc-bridge")]
+        grpc: RwLock<Option<super::GrpcPhoneBridge>>,
         // TODO: 添加 WebSocket 客户端
     }
     
@@ -2144,21 +2862,41 @@ mod agent_runtime_collaboration {
         pub fn new() -> Self {
             Self {
                 session: Arc::new(RwLock::new(CollaborationSession::default())),
+                #[cfg(feature = "grpc-bridge")]
+                grpc: RwLock::new(None),
             }
         }
+
+        /// 全局实例，协同命令之间共享连接
+        pub fn global() -> &'static CollaborationManager {
+            COLLABORATION_MANAGER.get_or_init(Self::new)
+        }
         
-        /// 连接到手机
+        /// 按协同设置中的传输方式连接到手机
         pub async fn connect(&self, phone_ip: &str, port: u16) -> Result<(), String> {
             let address = format!("{}:{}", phone_ip, port);
+            let settings = CollaborationSettingsStore::global().lock().map_err(|e| e.to_string())?.settings().clone();
+            self.disconnect().await;
             
             {
                 let mut session = self.session.write().await;
                 session.connection_state = PhoneConnectionState::Connecting;
                 session.phone_address = Some(address.clone());
+                session.transport = settings.transport;
             }
             
-            // TODO: 实现实际的 WebSocket 连接
-            // let ws = tokio_tungstenite::connect_async(&format!("ws://{}", address)).await?;
+            match settings.transport {
+                CollaborationTransport::WebSocket => {
+                    // TODO: 实现实际的 WebSocket 连接
+                    // let ws = tokio_tungstenite::connect_async(&format!("ws://{}", address)).await?;
+                }
+                CollaborationTransport::Grpc => {
+                    if let Err(e) = self.connect_grpc(&address, &settings).await {
+                        self.disconnect().await;
+                        return Err(e);
+                    }
+                }
+            }
             
             {
                 let mut session = self.session.write().await;
@@ -2171,20 +2909,50 @@ mod agent_runtime_collaboration {
             
             Ok(())
         }
+
+        #[cfg(feature = "grpc-bridge")]
+        async fn connect_grpc(&self, address: &str, settings: &CollaborationSettings) -> Result<(), String> {
+            let bridge = super::GrpcPhoneBridge::connect(address, &settings.grpc, self.session.clone()).await?;
+            *self.grpc.write().await = Some(bridge);
+            Ok(())
+        }
+
+        #[cfg(not(feature = "grpc-bridge"))]
+        async fn connect_grpc(&self, _address: &str, _settings: &CollaborationSettings) -> Result<(), String> {
+            Err(super::GRPC_DISABLED_ERROR.to_string())
+        }
         
         /// 断开连接
         pub async fn disconnect(&self) {
+            #[cfg(feature = "grpc-bridge")]
+            self.grpc.write().await.take();
             let mut session = self.session.write().await;
             session.connection_state = PhoneConnectionState::Disconnected;
             session.phone_address = None;
         }
-        
-        /// 发送目标到手机
-        pub async fn send_goal(&self, description: &str, max_steps: u32, timeout: u32) -> Result<(), String> {
+
+        async fn ensure_connected(&self) -> Result<(), String> {
             let session = self.session.read().await;
             if session.connection_state != PhoneConnectionState::Connected {
                 return Err("未连接到手机".to_string());
             }
+            Ok(())
+        }
+
+        /// 经当前传输下发消息
+        async fn dispatch(&self, msg: PcToPhoneMessage) -> Result<(), String> {
+            #[cfg(feature = "grpc-bridge")]
+            if let Some(bridge) = self.grpc.read().await.as_ref() {
+                return bridge.send(&msg).await;
+            }
+            // TODO: 发送到 WebSocket
+            tracing::debug!("📱 WebSocket 尚未接入，消息未实际发送: {:?}", msg);
+            Ok(())
+        }
+        
+        /// 发送目标到手机
+        pub async fn send_goal(&self, description: &str, max_steps: u32, timeout: u32) -> Result<(), String> {
+            self.ensure_connected().await?;
             
             let msg = PcToPhoneMessage::Goal {
                 description: description.to_string(),
@@ -2192,18 +2960,13 @@ mod agent_runtime_collaboration {
                 timeout_seconds: timeout,
             };
             
-            // TODO: 发送到 WebSocket
             tracing::info!("📱 发送目标到手机: {:?}", msg);
-            
-            Ok(())
+            self.dispatch(msg).await
         }
         
         /// 发送命令到手机
         pub async fn send_command(&self, command: PhoneCommand) -> Result<(), String> {
-            let session = self.session.read().await;
-            if session.connection_state != PhoneConnectionState::Connected {
-                return Err("未连接到手机".to_string());
-            }
+            self.ensure_connected().await?;
             
             let msg = PcToPhoneMessage::Command {
                 command,
@@ -2211,8 +2974,7 @@ mod agent_runtime_collaboration {
             };
             
             tracing::info!("📱 发送命令到手机: {:?}", msg);
-            
-            Ok(())
+            self.dispatch(msg).await
         }
         
         /// 请求手机执行动作（PC 做决策后）
@@ -2222,10 +2984,7 @@ mod agent_runtime_collaboration {
             target: &str,
             params: serde_json::Value,
         ) -> Result<(), String> {
-            let session = self.session.read().await;
-            if session.connection_state != PhoneConnectionState::Connected {
-                return Err("未连接到手机".to_string());
-            }
+            self.ensure_connected().await?;
             
             let msg = PcToPhoneMessage::ExecuteAction {
                 action_type: action_type.to_string(),
@@ -2234,8 +2993,7 @@ mod agent_runtime_collaboration {
             };
             
             tracing::info!("📱 请求手机执行动作: {:?}", msg);
-            
-            Ok(())
+            self.dispatch(msg).await
         }
         
         /// 获取会话状态
@@ -2245,6 +3003,13 @@ mod agent_runtime_collaboration {
     }
 }
 
+mod agent_runtime_collab_settings;
+pub use agent_runtime_collab_settings::*;
+#[cfg(feature = "grpc-bridge")]
+mod agent_runtime_grpc_bridge;
+#[cfg(feature = "grpc-bridge")]
+pub use agent_runtime_grpc_bridge::GrpcPhoneBridge;
+
 // 导出协同模块类型
 pub use agent_runtime_collaboration::{
     CollaborationMode, CollaborationManager, CollaborationSession,
@@ -2708,7 +3473,7 @@ pub use agent_runtime_memory::{
 
 pub fn init<R: Runtime>() -> TauriPlugin<R> {
     Builder::new("agent-runtime")
-        .invoke_handler(tauri::generate_handler![
+        .invoke_handler(crate::core::plugin_isolation::isolate("agent_runtime", tauri::generate_handler![
             start,
             pause,
             resume,
@@ -2717,14 +3482,42 @@ pub fn init<R: Runtime>() -> TauriPlugin<R> {
             reject,
             status,
             get_events,
+            // 会话恢复
+            get_saved_agent_session,
+            resume_agent_session,
+            discard_agent_session,
+            // 多目标队列
+            submit_agent_goal,
+            list_agent_goals,
+            reorder_agent_goal,
+            cancel_agent_goal,
+            set_agent_goal_parallel,
+            get_agent_goal_events,
+            // 沙箱写文件
+            get_agent_file_sandbox,
+            save_agent_file_sandbox,
+            list_agent_written_files,
+            // 副驾驶模式
+            start_copilot,
+            stop_copilot,
+            copilot_status,
             // PC-手机协同命令
             connect_phone,
             disconnect_phone,
             send_goal_to_phone,
             execute_action_on_phone,
-        ])
+            get_collaboration_settings,
+            save_collaboration_settings,
+        ]))
         .setup(|app, _| {
-            app.manage(AgentRuntimeState::new());
+            crate::core::plugin_bootstrap::registry().track_setup("agent_runtime", || {
+                app.manage(AgentRuntimeState::new());
+                let handle = app.clone();
+                crate::core::plugin_isolation::register_restart("agent_runtime", move || {
+                    handle.state::<AgentRuntimeState>().reset()
+                });
+                Ok::<_, String>(())
+            })?;
             info!("🤖 Agent Runtime 插件已初始化（含 PC-手机协同）");
             Ok(())
         })
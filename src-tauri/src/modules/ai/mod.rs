@@ -1,13 +1,20 @@
 use crate::ai::{ai_config::{self, AISettings}, router::AIRouter, ai_types::*, provider::ChatChunk};
+use crate::ai::cancellation::{with_deadline, InflightCalls};
+use crate::services::concurrency::{self, WorkRequest};
 use anyhow::Result;
+use std::time::Duration;
 use serde_json::Value;
 use tauri::{
     plugin::{Builder, TauriPlugin},
-    Runtime, Manager, State, AppHandle, Emitter
+    Runtime, State, AppHandle, Emitter
 };
+use crate::core::app_services::SharedAppServices;
 
+/// AI 配置与进行中的请求（由 AppServices 持有，ai 插件与潜客向量化共用）
 pub struct AiState {
     pub settings: parking_lot::RwLock<AISettings>,
+    /// 进行中的对话请求（用于停止时中断）
+    pub inflight: InflightCalls,
 }
 
 impl AiState {
@@ -16,22 +23,47 @@ impl AiState {
         let settings = ai_config::load_settings();
         Self {
             settings: parking_lot::RwLock::new(settings),
+            inflight: InflightCalls::new(),
         }
     }
+
+    /// 当前配置并合并系统凭据库中的密钥（密钥不落盘，每次调用前读取）
+    pub fn settings_with_keys(&self) -> Result<AISettings, String> {
+        let mut s = self.settings.read().clone();
+        s.openai_api_key = keyring::Entry::new("marketing-automation-desktop", "OPENAI")
+            .map_err(err)?
+            .get_password()
+            .unwrap_or_default();
+        s.hunyuan_api_key = keyring::Entry::new("marketing-automation-desktop", "HUNYUAN")
+            .map_err(err)?
+            .get_password()
+            .unwrap_or_default();
+        Ok(s)
+    }
 }
 
 #[tauri::command]
-async fn get_settings(state: State<'_, AiState>) -> Result<AISettings, String> {
-    Ok(state.settings.read().clone())
+async fn get_settings(services: State<'_, SharedAppServices>) -> Result<AISettings, String> {
+    crate::core::plugin_isolation::guard("ai", async move { Ok(services.ai.settings.read().clone()) }).await
 }
 
 #[tauri::command]
 async fn save_settings(
-    state: State<'_, AiState>,
+    services: State<'_, SharedAppServices>,
+    settings: AISettings,
+    openai_key: Option<String>,
+    hunyuan_key: Option<String>,
+) -> Result<(), String> {
+    crate::core::plugin_isolation::guard("ai", save_settings_impl(services, settings, openai_key, hunyuan_key)).await
+}
+
+async fn save_settings_impl(
+    services: State<'_, SharedAppServices>,
     settings: AISettings,
     openai_key: Option<String>,
     hunyuan_key: Option<String>,
 ) -> Result<(), String> {
+    let state = &services.ai;
     // 保存密钥到系统凭据库
     if let Some(k) = openai_key {
         keyring::Entry::new("marketing-automation-desktop", "OPENAI")
@@ -55,8 +87,12 @@ async fn save_settings(
 }
 
 #[tauri::command]
-async fn list_models(state: State<'_, AiState>) -> Result<Vec<String>, String> {
-    let s = state.settings.read();
+async fn list_models(services: State<'_, SharedAppServices>) -> Result<Vec<String>, String> {
+    crate::core::plugin_isolation::guard("ai", list_models_impl(services)).await
+}
+
+async fn list_models_impl(services: State<'_, SharedAppServices>) -> Result<Vec<String>, String> {
+    let s = services.ai.settings.read();
     Ok(match s.provider.as_str() {
         "hunyuan" => vec!["hunyuan-turbo-latest".into(), "hunyuan-embedding".into()],
         _ => vec![
@@ -74,22 +110,28 @@ async fn list_models(state: State<'_, AiState>) -> Result<Vec<String>, String> {
 #[tauri::command]
 async fn chat<R: Runtime>(
     app: AppHandle<R>,
-    state: State<'_, AiState>,
+    services: State<'_, SharedAppServices>,
+    messages: Vec<ChatMessage>,
+    tools: Option<Vec<ToolSpec>>,
+    tool_choice: Option<Value>,
+    stream: Option<bool>,
+    request_id: Option<String>,
+) -> Result<Value, String> {
+    crate::core::plugin_isolation::guard("ai", chat_impl(app, services, messages, tools, tool_choice, stream, request_id)).await
+}
+
+async fn chat_impl<R: Runtime>(
+    app: AppHandle<R>,
+    services: State<'_, SharedAppServices>,
     messages: Vec<ChatMessage>,
     tools: Option<Vec<ToolSpec>>,
     tool_choice: Option<Value>,
     stream: Option<bool>,
+    request_id: Option<String>,
 ) -> Result<Value, String> {
+    let state = &services.ai;
     // 合并运行态密钥
-    let mut s = state.settings.read().clone();
-    s.openai_api_key = keyring::Entry::new("marketing-automation-desktop", "OPENAI")
-        .map_err(err)?
-        .get_password()
-        .unwrap_or_default();
-    s.hunyuan_api_key = keyring::Entry::new("marketing-automation-desktop", "HUNYUAN")
-        .map_err(err)?
-        .get_password()
-        .unwrap_or_default();
+    let s = state.settings_with_keys()?;
 
     let router = AIRouter::new(s.clone());
     let req = ChatRequest {
@@ -101,37 +143,49 @@ async fn chat<R: Runtime>(
         stream,
     };
 
-    if stream.unwrap_or(false) {
+    let timeout = Duration::from_secs(s.request_timeout_secs);
+    let request_id = request_id.unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+    let token = state.inflight.register(&request_id);
+
+    // 排队等待并发配额的时间也计入超时，cancel_chat 可在排队阶段中断
+    let work = WorkRequest::ai("ai_chat", request_id.clone());
+    let result = if stream.unwrap_or(false) {
         let app2 = app.clone();
-        let out = router
-            .chat(req, Some(move |chunk: ChatChunk| {
-                let _ = app2.emit("ai://stream", &chunk.delta);
-            }))
-            .await
-            .map_err(err)?;
-        Ok(out)
+        let call = router.chat(req, Some(move |chunk: ChatChunk| {
+            let _ = app2.emit("ai://stream", &chunk.delta);
+        }));
+        with_deadline(concurrency::run(work, call), timeout, Some(&token)).await
     } else {
-        router.chat::<fn(ChatChunk)>(req, None).await.map_err(err)
-    }
+        let call = router.chat::<fn(ChatChunk)>(req, None);
+        with_deadline(concurrency::run(work, call), timeout, Some(&token)).await
+    };
+
+    state.inflight.finish(&request_id);
+    result.map_err(err)
+}
+
+/// 取消进行中的对话请求；`request_id` 为空时取消全部，返回取消数量
+#[tauri::command]
+async fn cancel_chat(services: State<'_, SharedAppServices>, request_id: Option<String>) -> Result<usize, String> {
+    crate::core::plugin_isolation::guard("ai", async move { Ok(services.ai.inflight.cancel(request_id.as_deref())) }).await
 }
 
 #[tauri::command]
 async fn embed(
-    state: State<'_, AiState>,
+    services: State<'_, SharedAppServices>,
+    input: Vec<String>,
+) -> Result<Vec<Vec<f32>>, String> {
+    crate::core::plugin_isolation::guard("ai", embed_impl(services, input)).await
+}
+
+async fn embed_impl(
+    services: State<'_, SharedAppServices>,
     input: Vec<String>,
 ) -> Result<Vec<Vec<f32>>, String> {
-    let mut s = state.settings.read().clone();
-    s.openai_api_key = keyring::Entry::new("marketing-automation-desktop", "OPENAI")
-        .map_err(err)?
-        .get_password()
-        .unwrap_or_default();
-    s.hunyuan_api_key = keyring::Entry::new("marketing-automation-desktop", "HUNYUAN")
-        .map_err(err)?
-        .get_password()
-        .unwrap_or_default();
+    let s = services.ai.settings_with_keys()?;
     let router = AIRouter::new(s.clone());
-    router
-        .embed(&s.default_embed_model, input)
+    let request = WorkRequest::ai("ai_embed", format!("{} 条文本", input.len()));
+    concurrency::run(request, router.embed(&s.default_embed_model, input))
         .await
         .map_err(err)
 }
@@ -142,16 +196,13 @@ fn err<E: std::fmt::Display>(e: E) -> String {
 
 pub fn init<R: Runtime>() -> TauriPlugin<R> {
     Builder::<R>::new("ai")
-        .setup(|app, _api| {
-            app.manage(AiState::new());
-            Ok(())
-        })
-        .invoke_handler(tauri::generate_handler![
+        .invoke_handler(crate::core::plugin_isolation::isolate("ai", tauri::generate_handler![
             get_settings,
             save_settings,
             list_models,
             chat,
+            cancel_chat,
             embed
-        ])
+        ]))
         .build()
 }
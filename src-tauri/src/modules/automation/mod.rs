@@ -1,12 +1,33 @@
 use tauri::{
     plugin::{Builder, TauriPlugin},
-    Runtime,
+    AppHandle, Emitter, Runtime,
 };
 use serde_json::Value;
+use crate::core::application::SANDBOX;
+use crate::core::shared::event_bus::{self, AppEvent};
 use crate::services::duplication_guard::{
     check_duplication_action_cmd, record_duplication_action_cmd,
     DuplicationCheckRequest, DuplicationCheckResult, ActionRecord
 };
+use crate::services::billing_journal::client_report::{self, ClientReport, ClientReportRequest};
+use crate::services::billing_journal::{
+    record_billable, render_summary_csv, summary_columns, summary_rows, BillableAction, BillingEntry, BillingJournal, BillingJournalConfig,
+    ClientMonthlySummary,
+};
+use crate::services::network_profile::{
+    self, AdbDeviceShell, DeviceProxyStatus, NetworkProfileConfig, NetworkProfileManager, NetworkRunRecord,
+};
+use crate::services::device_location::{LocationConfig, LocationManager, LocationMethod, LocationStatus, SystemTransport};
+use crate::services::execution::timeline::{self, TimelineSummary};
+use crate::services::screen_stability::{self, IdleOptions, IdleReport};
+use crate::services::list_tracking::{self, PageObservation, ScrollProgress};
+use crate::services::warmup::{self, executor as warmup_executor, WarmupEnrollment, WarmupProgram, WarmupReadiness, WarmupRunReport, WarmupStatus, WarmupStore};
+use crate::services::event_outbox;
+use crate::services::self_test::quirks::{self, QuirkReport};
+use crate::services::self_test::{self, SelfTestConfig, SelfTestReport, SelfTestStore, SelfTestTrigger};
+use crate::services::smoke_test::{self, spec::SmokeTestSpec, SmokeTestRun, SmokeTestStore, SmokeTrigger};
+use crate::services::report_export::{self, ExportOptions, ExportOutcome};
+use crate::services::campaign_sim::{self, CampaignPlan, DurationStat, ExecutionEstimate, ExecutionWindow, SimulationReport, StepDurationStats};
 
 #[tauri::command]
 fn check_duplication(req: DuplicationCheckRequest) -> DuplicationCheckResult {
@@ -15,13 +36,575 @@ fn check_duplication(req: DuplicationCheckRequest) -> DuplicationCheckResult {
 
 #[tauri::command]
 fn record_action(record: ActionRecord) {
+    event_bus::publish(AppEvent::ActionExecuted {
+        action: record.action.clone(),
+        device_id: record.device_id.clone(),
+        success: true,
+    });
+    record_billable(BillableAction {
+        action: record.action.clone(),
+        account_id: record.account_id.clone(),
+        campaign_id: record.campaign_id.clone(),
+        device_id: Some(record.device_id.clone()),
+        target: Some(record.target_id.clone()),
+        ..Default::default()
+    });
     record_duplication_action_cmd(record)
 }
 
+/// 上报账号被平台限制 / 封禁（计入封号率统计与异常检测）
+#[tauri::command]
+fn report_account_restricted(account_id: String, device_id: String, reason: Option<String>) {
+    tracing::warn!("⛔ 账号受限: account={} device={} reason={:?}", account_id, device_id, reason);
+    event_bus::publish(AppEvent::AccountRestricted { account_id, device_id, reason: reason.unwrap_or_default() });
+}
+
+/// 养号执行进度事件
+const WARMUP_PROGRESS_EVENT: &str = "warmup-progress";
+
+#[tauri::command]
+fn list_warmup_programs() -> Result<Vec<WarmupProgram>, String> {
+    Ok(WarmupStore::global().lock().map_err(|e| e.to_string())?.programs())
+}
+
+#[tauri::command]
+fn save_warmup_program(program: WarmupProgram) -> Result<(), String> {
+    WarmupStore::global().lock().map_err(|e| e.to_string())?.save_program(program)
+}
+
+#[tauri::command]
+fn delete_warmup_program(program_id: String) -> Result<bool, String> {
+    WarmupStore::global().lock().map_err(|e| e.to_string())?.delete_program(&program_id)
+}
+
+/// 账号加入养号计划（从今天算第 1 天；已入组时重新开始）
+#[tauri::command]
+fn enroll_warmup_account(account_id: String, device_id: String, program_id: String) -> Result<WarmupEnrollment, String> {
+    let mut store = WarmupStore::global().lock().map_err(|e| e.to_string())?;
+    store.enroll(&account_id, &device_id, &program_id, warmup::today())
+}
+
+#[tauri::command]
+fn list_warmup_enrollments() -> Result<Vec<WarmupEnrollment>, String> {
+    Ok(WarmupStore::global().lock().map_err(|e| e.to_string())?.enrollments())
+}
+
+/// 暂停 / 恢复养号（暂停时同时停止正在进行的执行）
+#[tauri::command]
+fn set_warmup_paused(account_id: String, paused: bool) -> Result<(), String> {
+    let status = if paused { WarmupStatus::Paused } else { WarmupStatus::Active };
+    WarmupStore::global().lock().map_err(|e| e.to_string())?.set_status(&account_id, status)?;
+    if paused {
+        warmup_executor::stop(&account_id);
+    }
+    Ok(())
+}
+
+/// 执行账号今天的养号目标，逐动作推送 `warmup-progress` 事件
+#[tauri::command]
+async fn run_warmup_today<R: Runtime>(app: AppHandle<R>, account_id: String) -> Result<WarmupRunReport, String> {
+    crate::core::plugin_isolation::guard("automation", run_warmup_today_impl(app, account_id)).await
+}
+
+async fn run_warmup_today_impl<R: Runtime>(app: AppHandle<R>, account_id: String) -> Result<WarmupRunReport, String> {
+    warmup_executor::run_today(&account_id, |progress| {
+        let _ = app.emit(WARMUP_PROGRESS_EVENT, progress);
+    })
+    .await
+}
+
+#[tauri::command]
+fn stop_warmup(account_id: String) -> bool {
+    warmup_executor::stop(&account_id)
+}
+
+/// 养号就绪度报告；`account_ids` 为空时报告全部入组账号
+#[tauri::command]
+fn get_warmup_readiness(account_ids: Option<Vec<String>>) -> Result<Vec<WarmupReadiness>, String> {
+    let store = WarmupStore::global().lock().map_err(|e| e.to_string())?;
+    let ids = account_ids.unwrap_or_else(|| store.enrollments().into_iter().map(|e| e.account_id).collect());
+    let today = warmup::today();
+    Ok(ids.iter().map(|id| store.readiness(id, today)).collect())
+}
+
+/// 立即执行一轮模拟器自检；`device_ids` 省略时跑全部已连接的模拟器
+#[tauri::command]
+async fn run_self_test<R: Runtime>(app: AppHandle<R>, device_ids: Option<Vec<String>>) -> Result<SelfTestReport, String> {
+    crate::core::plugin_isolation::guard("automation", run_self_test_impl(app, device_ids)).await
+}
+
+async fn run_self_test_impl<R: Runtime>(app: AppHandle<R>, device_ids: Option<Vec<String>>) -> Result<SelfTestReport, String> {
+    let report = self_test::run(device_ids, SelfTestTrigger::Manual).await?;
+    event_outbox::emit(&app, self_test::SELF_TEST_EVENT, &report.id, &report);
+    Ok(report)
+}
+
+#[tauri::command]
+fn list_self_test_reports(limit: Option<usize>) -> Result<Vec<SelfTestReport>, String> {
+    Ok(SelfTestStore::global().lock().map_err(|e| e.to_string())?.reports(limit.unwrap_or(20)))
+}
+
+#[tauri::command]
+fn get_self_test_config() -> Result<SelfTestConfig, String> {
+    Ok(SelfTestStore::global().lock().map_err(|e| e.to_string())?.config().clone())
+}
+
+#[tauri::command]
+fn save_self_test_config(config: SelfTestConfig) -> Result<SelfTestConfig, String> {
+    SelfTestStore::global().lock().map_err(|e| e.to_string())?.set_config(config.clone())?;
+    Ok(config)
+}
+
+/// 清除自检耗时基线（确认环境升级正常后重新建立）
+#[tauri::command]
+fn reset_self_test_baselines(device_id: Option<String>) -> Result<usize, String> {
+    SelfTestStore::global().lock().map_err(|e| e.to_string())?.reset_baselines(device_id.as_deref())
+}
+
+/// 立即检测单台设备的模拟器怪癖；`remediate` 省略时按配置决定是否自动修复
+#[tauri::command]
+async fn check_emulator_quirks(device_id: String, remediate: Option<bool>) -> Result<QuirkReport, String> {
+    crate::core::plugin_isolation::guard("automation", async move { quirks::check_and_record(&device_id, remediate).await }).await
+}
+
+/// 各设备最近一次怪癖检测报告
+#[tauri::command]
+fn list_emulator_quirk_reports() -> Result<Vec<QuirkReport>, String> {
+    Ok(SelfTestStore::global().lock().map_err(|e| e.to_string())?.quirk_reports())
+}
+
+/// 冒烟测试定义（含内置的小红书 / 微信 / 通讯录）
+#[tauri::command]
+fn list_smoke_tests() -> Result<Vec<SmokeTestSpec>, String> {
+    Ok(SmokeTestStore::global().lock().map_err(|e| e.to_string())?.specs().to_vec())
+}
+
+/// 新增或按 id 覆盖冒烟测试定义
+#[tauri::command]
+fn save_smoke_test(spec: SmokeTestSpec) -> Result<SmokeTestSpec, String> {
+    SmokeTestStore::global().lock().map_err(|e| e.to_string())?.save_spec(spec.clone())?;
+    Ok(spec)
+}
+
+#[tauri::command]
+fn delete_smoke_test(spec_id: String) -> Result<bool, String> {
+    SmokeTestStore::global().lock().map_err(|e| e.to_string())?.delete_spec(&spec_id)
+}
+
+/// 立即执行冒烟测试；`device_ids` 省略时用定义中的设备，仍为空则全部已连接设备
+#[tauri::command]
+async fn run_smoke_test<R: Runtime>(app: AppHandle<R>, spec_id: String, device_ids: Option<Vec<String>>) -> Result<Vec<SmokeTestRun>, String> {
+    crate::core::plugin_isolation::guard("automation", async move { smoke_test::run(&app, &spec_id, device_ids, SmokeTrigger::Manual).await }).await
+}
+
+/// 冒烟测试历史结果（新的在前）
+#[tauri::command]
+fn list_smoke_test_history(spec_id: Option<String>, device_id: Option<String>, limit: Option<usize>) -> Result<Vec<SmokeTestRun>, String> {
+    let store = SmokeTestStore::global().lock().map_err(|e| e.to_string())?;
+    Ok(store.history(spec_id.as_deref(), device_id.as_deref(), limit.unwrap_or(50)))
+}
+
+/// 获取计费日志配置
+#[tauri::command]
+fn get_billing_journal_config() -> Result<BillingJournalConfig, String> {
+    let journal = BillingJournal::global().lock().map_err(|e| e.to_string())?;
+    Ok(journal.config().clone())
+}
+
+/// 保存计费日志配置
+#[tauri::command]
+fn save_billing_journal_config(config: BillingJournalConfig) -> Result<(), String> {
+    let mut journal = BillingJournal::global().lock().map_err(|e| e.to_string())?;
+    journal.save_config(config)
+}
+
+/// 手动记录一次可计费动作（前端执行的动作）
+#[tauri::command]
+fn record_billable_action(action: BillableAction) -> Result<Option<BillingEntry>, String> {
+    let journal = BillingJournal::global().lock().map_err(|e| e.to_string())?;
+    journal.record(&action)
+}
+
+/// 客户月度计费汇总（month: YYYY-MM）
+#[tauri::command]
+fn summarize_billing_month(month: String, client_id: Option<String>) -> Result<Vec<ClientMonthlySummary>, String> {
+    let journal = BillingJournal::global().lock().map_err(|e| e.to_string())?;
+    journal.monthly_summary(&month, client_id.as_deref())
+}
+
+/// 导出客户月度计费汇总 CSV（locale 为空时使用当前报表语言）
+#[tauri::command]
+fn export_billing_summary(month: String, client_id: Option<String>, locale: Option<String>) -> Result<String, String> {
+    let locale = crate::core::shared::i18n::resolve_locale(locale.as_deref());
+    let journal = BillingJournal::global().lock().map_err(|e| e.to_string())?;
+    let summaries = journal.monthly_summary(&month, client_id.as_deref())?;
+    render_summary_csv(&summaries, locale)
+}
+
+/// 生成面向客户的聚合报表（仅计数 / 比率，不含任何行级数据）
+#[tauri::command]
+fn generate_client_report(request: ClientReportRequest) -> Result<ClientReport, String> {
+    client_report::generate(&request)
+}
+
+/// 将客户月度计费汇总导出为文件（csv / xlsx / json），进度通过统一导出事件上报
+#[tauri::command]
+async fn export_billing_report<R: Runtime>(
+    app: AppHandle<R>,
+    month: String,
+    client_id: Option<String>,
+    format: Option<String>,
+    locale: Option<String>,
+    output_path: Option<String>,
+) -> Result<ExportOutcome, String> {
+    crate::core::plugin_isolation::guard("automation", export_billing_report_impl(app, month, client_id, format, locale, output_path)).await
+}
+
+async fn export_billing_report_impl<R: Runtime>(
+    app: AppHandle<R>,
+    month: String,
+    client_id: Option<String>,
+    format: Option<String>,
+    locale: Option<String>,
+    output_path: Option<String>,
+) -> Result<ExportOutcome, String> {
+    let options = ExportOptions::from_args(format.as_deref(), locale.as_deref(), output_path)?;
+    let summaries = {
+        let journal = BillingJournal::global().lock().map_err(|e| e.to_string())?;
+        journal.monthly_summary(&month, client_id.as_deref())?
+    };
+    tokio::task::spawn_blocking(move || {
+        let columns = summary_columns(&summaries, options.locale);
+        report_export::export_table("billing", options, columns, &summary_rows(&summaries), report_export::emit_progress(&app))
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// 生成客户报表并导出为文件（每个分节一张表，附说明表）
+#[tauri::command]
+async fn export_client_report<R: Runtime>(
+    app: AppHandle<R>,
+    request: ClientReportRequest,
+    format: Option<String>,
+    output_path: Option<String>,
+) -> Result<ExportOutcome, String> {
+    crate::core::plugin_isolation::guard("automation", export_client_report_impl(app, request, format, output_path)).await
+}
+
+async fn export_client_report_impl<R: Runtime>(
+    app: AppHandle<R>,
+    request: ClientReportRequest,
+    format: Option<String>,
+    output_path: Option<String>,
+) -> Result<ExportOutcome, String> {
+    let options = ExportOptions::from_args(format.as_deref(), request.locale.as_deref(), output_path)?;
+    tokio::task::spawn_blocking(move || {
+        let report = client_report::generate(&request)?;
+        client_report::export_report(&report, options, report_export::emit_progress(&app))
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// 获取网络配置档与设备分配
+#[tauri::command]
+fn get_network_profiles() -> Result<NetworkProfileConfig, String> {
+    let manager = NetworkProfileManager::global().lock().map_err(|e| e.to_string())?;
+    Ok(manager.config().clone())
+}
+
+/// 保存网络配置档与设备分配
+#[tauri::command]
+fn save_network_profiles(config: NetworkProfileConfig) -> Result<(), String> {
+    let mut manager = NetworkProfileManager::global().lock().map_err(|e| e.to_string())?;
+    manager.save_config(config)
+}
+
+/// 为设备分配配置档（profile_id 为空表示取消分配）
+#[tauri::command]
+fn assign_network_profile(device_id: String, profile_id: Option<String>) -> Result<(), String> {
+    let mut manager = NetworkProfileManager::global().lock().map_err(|e| e.to_string())?;
+    manager.assign(&device_id, profile_id.as_deref())
+}
+
+/// 立即向设备下发已分配的配置档（未分配时恢复直连）
+#[tauri::command]
+async fn apply_network_profile(device_id: String) -> Result<DeviceProxyStatus, String> {
+    crate::core::plugin_isolation::guard("automation", apply_network_profile_impl(device_id)).await
+}
+
+async fn apply_network_profile_impl(device_id: String) -> Result<DeviceProxyStatus, String> {
+    tokio::task::spawn_blocking(move || {
+        let profile = {
+            let manager = NetworkProfileManager::global().lock().map_err(|e| e.to_string())?;
+            manager.profile_for_device(&device_id).cloned()
+        };
+        match profile {
+            Some(profile) => network_profile::apply_profile(&AdbDeviceShell, &device_id, &profile),
+            None => network_profile::restore_clean(&AdbDeviceShell, &device_id),
+        }
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// 回读设备代理并与分配的配置档比对
+#[tauri::command]
+async fn verify_device_proxy(device_id: String) -> Result<DeviceProxyStatus, String> {
+    crate::core::plugin_isolation::guard("automation", verify_device_proxy_impl(device_id)).await
+}
+
+async fn verify_device_proxy_impl(device_id: String) -> Result<DeviceProxyStatus, String> {
+    tokio::task::spawn_blocking(move || {
+        let expected = {
+            let manager = NetworkProfileManager::global().lock().map_err(|e| e.to_string())?;
+            manager.profile_for_device(&device_id).map(|p| p.proxy_value())
+        };
+        network_profile::verify_proxy(&AdbDeviceShell, &device_id, expected.as_deref())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// 清除设备代理设置
+#[tauri::command]
+async fn restore_device_network(device_id: String) -> Result<DeviceProxyStatus, String> {
+    crate::core::plugin_isolation::guard("automation", restore_device_network_impl(device_id)).await
+}
+
+async fn restore_device_network_impl(device_id: String) -> Result<DeviceProxyStatus, String> {
+    tokio::task::spawn_blocking(move || network_profile::restore_clean(&AdbDeviceShell, &device_id))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// 活动运行开始：下发并校验设备配置档、进入活动绑定的沙箱，返回运行记录
+#[tauri::command]
+async fn begin_network_run(campaign_id: String, device_id: String) -> Result<NetworkRunRecord, String> {
+    crate::core::plugin_isolation::guard("automation", begin_network_run_impl(campaign_id, device_id)).await
+}
+
+async fn begin_network_run_impl(campaign_id: String, device_id: String) -> Result<NetworkRunRecord, String> {
+    tokio::task::spawn_blocking(move || {
+        let mut manager = NetworkProfileManager::global().lock().map_err(|e| e.to_string())?;
+        let record = manager.begin_run(&AdbDeviceShell, &campaign_id, &device_id)?;
+        // 活动绑定了沙箱配置时，本次运行期间该设备上的动作受沙箱约束
+        if let Err(e) = SANDBOX.enter_run(&record.run_id, &format!("campaign:{}", campaign_id), &device_id) {
+            let _ = manager.end_run(&AdbDeviceShell, &record.run_id);
+            return Err(e.to_string());
+        }
+        Ok(record)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// 活动运行结束：退出活动沙箱、恢复干净网络设置并补全运行记录
+#[tauri::command]
+async fn end_network_run(run_id: String) -> Result<NetworkRunRecord, String> {
+    crate::core::plugin_isolation::guard("automation", end_network_run_impl(run_id)).await
+}
+
+async fn end_network_run_impl(run_id: String) -> Result<NetworkRunRecord, String> {
+    tokio::task::spawn_blocking(move || {
+        SANDBOX.exit_run(&run_id);
+        let mut manager = NetworkProfileManager::global().lock().map_err(|e| e.to_string())?;
+        manager.end_run(&AdbDeviceShell, &run_id)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// 查询网络运行记录（可按活动过滤）
+#[tauri::command]
+fn list_network_runs(campaign_id: Option<String>) -> Result<Vec<NetworkRunRecord>, String> {
+    let manager = NetworkProfileManager::global().lock().map_err(|e| e.to_string())?;
+    Ok(manager.runs(campaign_id.as_deref()))
+}
+
+/// 获取定位预设与活动绑定
+#[tauri::command]
+fn get_location_config() -> Result<LocationConfig, String> {
+    let manager = LocationManager::global().lock().map_err(|e| e.to_string())?;
+    Ok(manager.config().clone())
+}
+
+/// 保存定位预设与活动绑定
+#[tauri::command]
+fn save_location_config(config: LocationConfig) -> Result<(), String> {
+    let mut manager = LocationManager::global().lock().map_err(|e| e.to_string())?;
+    manager.save_config(config)
+}
+
+/// 设置设备定位（method 为空时按设备类型自动选择）
+#[tauri::command]
+async fn set_device_location(
+    device_id: String,
+    lat: f64,
+    lng: f64,
+    method: Option<LocationMethod>,
+) -> Result<LocationStatus, String> {
+    crate::core::plugin_isolation::guard("automation", set_device_location_impl(device_id, lat, lng, method)).await
+}
+
+async fn set_device_location_impl(
+    device_id: String,
+    lat: f64,
+    lng: f64,
+    method: Option<LocationMethod>,
+) -> Result<LocationStatus, String> {
+    tokio::task::spawn_blocking(move || {
+        let manager = LocationManager::global().lock().map_err(|e| e.to_string())?;
+        manager.set_location(&SystemTransport, &device_id, lat, lng, method.unwrap_or_default())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// 校验设备当前生效的定位
+#[tauri::command]
+async fn verify_device_location(device_id: String, lat: f64, lng: f64) -> Result<LocationStatus, String> {
+    crate::core::plugin_isolation::guard("automation", verify_device_location_impl(device_id, lat, lng)).await
+}
+
+async fn verify_device_location_impl(device_id: String, lat: f64, lng: f64) -> Result<LocationStatus, String> {
+    tokio::task::spawn_blocking(move || {
+        let manager = LocationManager::global().lock().map_err(|e| e.to_string())?;
+        manager.verify_location(&SystemTransport, &device_id, lat, lng)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// 脚本启动前应用活动绑定的定位预设（未绑定时返回 null）
+#[tauri::command]
+async fn apply_campaign_location(campaign_id: String, device_id: String) -> Result<Option<LocationStatus>, String> {
+    crate::core::plugin_isolation::guard("automation", apply_campaign_location_impl(campaign_id, device_id)).await
+}
+
+async fn apply_campaign_location_impl(campaign_id: String, device_id: String) -> Result<Option<LocationStatus>, String> {
+    tokio::task::spawn_blocking(move || {
+        let manager = LocationManager::global().lock().map_err(|e| e.to_string())?;
+        manager.apply_campaign_preset(&SystemTransport, &campaign_id, &device_id)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// 等待屏幕稳定（quiet_ms 内 UI 签名无变化）；超时返回 idle=false 的报告而非错误
+#[tauri::command]
+async fn wait_for_idle(
+    device_id: String,
+    quiet_ms: Option<u64>,
+    timeout_ms: Option<u64>,
+    subtree: Option<String>,
+) -> Result<IdleReport, String> {
+    crate::core::plugin_isolation::guard("automation", wait_for_idle_impl(device_id, quiet_ms, timeout_ms, subtree)).await
+}
+
+async fn wait_for_idle_impl(
+    device_id: String,
+    quiet_ms: Option<u64>,
+    timeout_ms: Option<u64>,
+    subtree: Option<String>,
+) -> Result<IdleReport, String> {
+    let defaults = IdleOptions::default();
+    let options = IdleOptions {
+        quiet_ms: quiet_ms.unwrap_or(defaults.quiet_ms),
+        timeout_ms: timeout_ms.unwrap_or(defaults.timeout_ms),
+        subtree: subtree.filter(|s| !s.trim().is_empty()),
+        ..defaults
+    };
+    Ok(screen_stability::wait_for_idle(&device_id, &options).await)
+}
+
+/// 开始（或重置）列表采集会话
+#[tauri::command]
+fn start_list_tracking(session_id: String, expected_total: Option<usize>, max_stale_pages: Option<u32>) -> Result<(), String> {
+    list_tracking::start_session(&session_id, expected_total, max_stale_pages)
+}
+
+/// 抓取当前页并记录可见列表项，返回新项下标与是否到底
+#[tauri::command]
+async fn observe_list_page(session_id: String, device_id: String, container_id: Option<String>) -> Result<PageObservation, String> {
+    crate::core::plugin_isolation::guard("automation", observe_list_page_impl(session_id, device_id, container_id)).await
+}
+
+async fn observe_list_page_impl(session_id: String, device_id: String, container_id: Option<String>) -> Result<PageObservation, String> {
+    let xml = crate::services::adb::AdbService::new()
+        .dump_ui_hierarchy(&device_id)
+        .await
+        .map_err(|e| format!("Failed to dump hierarchy: {}", e))?;
+    list_tracking::observe_session_page(&session_id, &xml, container_id.as_deref())
+}
+
+/// 结束列表采集会话，返回最终进度
+#[tauri::command]
+fn end_list_tracking(session_id: String) -> Result<ScrollProgress, String> {
+    list_tracking::end_session(&session_id)
+}
+
+/// 模拟活动计划（不连接设备），返回 ETA、设备利用率与每日动作量
+#[tauri::command]
+fn simulate_campaign(plan: CampaignPlan) -> Result<SimulationReport, String> {
+    crate::services::telemetry::record_feature("campaign.simulate");
+    campaign_sim::simulate_campaign(&plan)
+}
+
+/// 启动前估算脚本在设备分组上的耗时与受频控动作消耗，返回逐设备 ETA 与超出时间窗的警告
+#[tauri::command]
+fn estimate_execution(
+    state: tauri::State<'_, crate::services::script_manager::ScriptManagerState>,
+    script_id: String,
+    device_group: String,
+    window: Option<ExecutionWindow>,
+) -> Result<ExecutionEstimate, String> {
+    let script = state.0.lock().load_script(&script_id).map_err(|e| format!("加载脚本失败: {}", e))?;
+    campaign_sim::estimate_execution(&script, &device_group, &window.unwrap_or_default())
+}
+
+/// 查看模拟器使用的步骤耗时统计
+#[tauri::command]
+fn get_step_duration_stats() -> Result<std::collections::BTreeMap<String, DurationStat>, String> {
+    Ok(StepDurationStats::global().lock().map_err(|e| e.to_string())?.snapshot())
+}
+
+/// 清空步骤耗时统计
+#[tauri::command]
+fn reset_step_duration_stats() -> Result<(), String> {
+    StepDurationStats::global().lock().map_err(|e| e.to_string())?.clear();
+    Ok(())
+}
+
+/// 最近的执行时间线概览（新的在前）
+#[tauri::command]
+fn list_execution_timelines() -> Vec<TimelineSummary> {
+    timeline::list_runs()
+}
+
+/// 导出运行的执行时间线（chrome://tracing 格式）；提供 output_path 时同时写入文件
+#[tauri::command]
+fn export_execution_timeline(run_id: String, output_path: Option<String>) -> Result<Value, String> {
+    let trace = timeline::export_chrome_trace(&run_id).ok_or_else(|| format!("未找到运行时间线: {}", run_id))?;
+    if let Some(path) = output_path {
+        let content = serde_json::to_string(&trace).map_err(|e| e.to_string())?;
+        std::fs::write(&path, content).map_err(|e| format!("写入时间线文件失败: {}", e))?;
+    }
+    Ok(trace)
+}
+
 #[tauri::command]
 async fn execute_script(
     _device_id: String,
     _steps: Vec<Value>,
+) -> Result<Value, String> {
+    crate::core::plugin_isolation::guard("automation", execute_script_impl(_device_id, _steps)).await
+}
+
+async fn execute_script_impl(
+    _device_id: String,
+    _steps: Vec<Value>,
 ) -> Result<Value, String> {
     // TODO: Implement actual execution logic or delegate to script manager
     Ok(serde_json::json!({"status": "success", "message": "Stub execution"}))
@@ -33,41 +616,45 @@ async fn abort_script_execution(
     _reason: String,
     _force: bool,
 ) -> Result<Value, String> {
-    Ok(serde_json::json!({}))
+    crate::core::plugin_isolation::guard("automation", async move { Ok(serde_json::json!({})) }).await
 }
 
 #[tauri::command]
 async fn cancel_current_operation() -> Result<(), String> {
-    Ok(())
+    crate::core::plugin_isolation::guard("automation", async move { Ok(()) }).await
 }
 
 #[tauri::command]
 async fn force_stop_all_adb_operations() -> Result<(), String> {
-    Ok(())
+    crate::core::plugin_isolation::guard("automation", async move { Ok(()) }).await
 }
 
 #[tauri::command]
 async fn stop_loop_test(_loop_id: String) -> Result<(), String> {
-    Ok(())
+    crate::core::plugin_isolation::guard("automation", async move { Ok(()) }).await
 }
 
 #[tauri::command]
 async fn record_circuit_breaker_operation(_service: String, _operation: String, _success: bool) -> Result<(), String> {
-    Ok(())
+    crate::core::plugin_isolation::guard("automation", async move { Ok(()) }).await
 }
 
 #[tauri::command]
 async fn update_circuit_breaker_state(_service: String, _state: String) -> Result<(), String> {
-    Ok(())
+    crate::core::plugin_isolation::guard("automation", async move { Ok(()) }).await
 }
 
 #[tauri::command]
 async fn reset_circuit_breaker(_service: String) -> Result<(), String> {
-    Ok(())
+    crate::core::plugin_isolation::guard("automation", async move { Ok(()) }).await
 }
 
 #[tauri::command]
 async fn get_circuit_breaker_status(_account_id: String, _task_type: String) -> Result<Value, String> {
+    crate::core::plugin_isolation::guard("automation", get_circuit_breaker_status_impl(_account_id, _task_type)).await
+}
+
+async fn get_circuit_breaker_status_impl(_account_id: String, _task_type: String) -> Result<Value, String> {
     Ok(serde_json::json!({
         "state": "closed",
         "failure_count": 0,
@@ -79,6 +666,10 @@ async fn get_circuit_breaker_status(_account_id: String, _task_type: String) ->
 
 #[tauri::command]
 async fn get_failure_statistics(_account_id: String, _task_type: String, _time_window_minutes: i32) -> Result<Value, String> {
+    crate::core::plugin_isolation::guard("automation", get_failure_statistics_impl(_account_id, _task_type, _time_window_minutes)).await
+}
+
+async fn get_failure_statistics_impl(_account_id: String, _task_type: String, _time_window_minutes: i32) -> Result<Value, String> {
     Ok(serde_json::json!({
         "total_operations": 0,
         "failure_count": 0,
@@ -88,59 +679,113 @@ async fn get_failure_statistics(_account_id: String, _task_type: String, _time_w
 
 #[tauri::command]
 async fn execute_script_with_monitoring(_script: Value, _execution_id: String) -> Result<String, String> {
-    Ok("stub_execution_id".to_string())
+    crate::core::plugin_isolation::guard("automation", async move { Ok("stub_execution_id".to_string()) }).await
 }
 
 #[tauri::command]
 async fn pause_script_execution(_execution_id: String) -> Result<(), String> {
-    Ok(())
+    crate::core::plugin_isolation::guard("automation", async move { Ok(()) }).await
 }
 
 #[tauri::command]
 async fn resume_script_execution(_execution_id: String) -> Result<(), String> {
-    Ok(())
+    crate::core::plugin_isolation::guard("automation", async move { Ok(()) }).await
 }
 
 #[tauri::command]
 async fn stop_script_execution(_execution_id: String) -> Result<(), String> {
-    Ok(())
+    crate::core::plugin_isolation::guard("automation", async move { Ok(()) }).await
 }
 
 #[tauri::command]
 async fn click_detected_element(_device_id: String, _element: Value, _click_type: String) -> Result<(), String> {
-    Ok(())
+    crate::core::plugin_isolation::guard("automation", async move { Ok(()) }).await
 }
 
 #[tauri::command]
 async fn run_step_v2(_step: Value, _device_id: String) -> Result<Value, String> {
-    Ok(serde_json::json!({}))
+    crate::core::plugin_isolation::guard("automation", async move { Ok(serde_json::json!({})) }).await
 }
 
 #[tauri::command]
 async fn execute_chain_test_v3(_device_id: String, _steps: Vec<Value>, _threshold: Option<f64>, _dry_run: Option<bool>) -> Result<Value, String> {
-    Ok(serde_json::json!({}))
+    crate::core::plugin_isolation::guard("automation", async move { Ok(serde_json::json!({})) }).await
 }
 
 #[tauri::command]
 async fn execute_xpath_action(_device_id: String, _xpath: String, _action: String) -> Result<String, String> {
-    Ok("stub_result".to_string())
+    crate::core::plugin_isolation::guard("automation", async move { Ok("stub_result".to_string()) }).await
 }
 
 #[tauri::command]
 async fn execute_single_step_test(_device_id: String, _step: Value) -> Result<Value, String> {
-    Ok(serde_json::json!({}))
+    crate::core::plugin_isolation::guard("automation", async move { Ok(serde_json::json!({})) }).await
 }
 
 #[tauri::command]
 async fn execute_smart_automation_script(_device_id: String, _script: Value) -> Result<Value, String> {
-    Ok(serde_json::json!({}))
+    crate::core::plugin_isolation::guard("automation", async move { Ok(serde_json::json!({})) }).await
 }
 
 pub fn init<R: Runtime>() -> TauriPlugin<R> {
     Builder::<R>::new("automation")
-        .invoke_handler(tauri::generate_handler![
+        .invoke_handler(crate::core::plugin_isolation::isolate("automation", tauri::generate_handler![
             check_duplication,
             record_action,
+            report_account_restricted,
+            list_warmup_programs,
+            save_warmup_program,
+            delete_warmup_program,
+            enroll_warmup_account,
+            list_warmup_enrollments,
+            set_warmup_paused,
+            run_warmup_today,
+            stop_warmup,
+            get_warmup_readiness,
+            run_self_test,
+            list_self_test_reports,
+            get_self_test_config,
+            save_self_test_config,
+            reset_self_test_baselines,
+            check_emulator_quirks,
+            list_emulator_quirk_reports,
+            list_smoke_tests,
+            save_smoke_test,
+            delete_smoke_test,
+            run_smoke_test,
+            list_smoke_test_history,
+            get_billing_journal_config,
+            save_billing_journal_config,
+            record_billable_action,
+            summarize_billing_month,
+            export_billing_summary,
+            generate_client_report,
+            export_billing_report,
+            export_client_report,
+            get_network_profiles,
+            save_network_profiles,
+            assign_network_profile,
+            apply_network_profile,
+            verify_device_proxy,
+            restore_device_network,
+            begin_network_run,
+            end_network_run,
+            list_network_runs,
+            get_location_config,
+            save_location_config,
+            set_device_location,
+            verify_device_location,
+            apply_campaign_location,
+            wait_for_idle,
+            start_list_tracking,
+            observe_list_page,
+            end_list_tracking,
+            simulate_campaign,
+            estimate_execution,
+            get_step_duration_stats,
+            reset_step_duration_stats,
+            list_execution_timelines,
+            export_execution_timeline,
             execute_script,
             abort_script_execution,
             cancel_current_operation,
@@ -161,6 +806,14 @@ pub fn init<R: Runtime>() -> TauriPlugin<R> {
             execute_xpath_action,
             execute_single_step_test,
             execute_smart_automation_script
-        ])
+        ]))
+        .setup(|_app, _api| {
+            crate::core::plugin_isolation::register_restart("automation", || {
+                list_tracking::clear_sessions();
+                StepDurationStats::global().clear_poison();
+                Ok(())
+            });
+            Ok(())
+        })
         .build()
 }
@@ -7,28 +7,165 @@ mod sync_service;
 
 use tauri::{plugin::{Builder, TauriPlugin}, Runtime, Manager};
 
+use crate::infra::lead_server_client::{
+    self, DeliveryReport, DeviceConfigUpdate, FlushReport, LeadServerClient, RemoteDeviceConfig, ServerStats,
+    SyncStatus, UploadComment, UserInfo,
+};
+use crate::services::realtime_uplink::{self, UplinkConfig, UplinkStatus};
+use crate::services::device_config_sync::{self, ConfigSyncStore, DeviceSettings, DeviceSettingsView, PushReport, SyncPolicy};
+
 pub use device_id::get_device_id;
 pub use sync_service::*;
 
 /// 获取本机设备ID
 #[tauri::command]
 async fn get_machine_id() -> Result<String, String> {
-    device_id::get_device_id().map_err(|e| e.to_string())
+    crate::core::plugin_isolation::guard("cloud_sync", async move { device_id::get_device_id().map_err(|e| e.to_string()) }).await
 }
 
 /// 获取云同步服务器地址
 #[tauri::command]
 fn get_cloud_server_url() -> String {
-    std::env::var("LEAD_SERVER_URL")
-        .unwrap_or_else(|_| "http://119.91.19.232:8080".to_string())
+    lead_server_client::server_url_from_env()
+}
+
+/// 获取同步状态；`check` 为 true 时先探测一次服务器连通性
+#[tauri::command]
+async fn get_sync_status(check: Option<bool>) -> Result<SyncStatus, String> {
+    crate::core::plugin_isolation::guard("cloud_sync", get_sync_status_impl(check)).await
+}
+
+async fn get_sync_status_impl(check: Option<bool>) -> Result<SyncStatus, String> {
+    let client = LeadServerClient::global();
+    if check.unwrap_or(false) {
+        client.ping().await;
+    }
+    Ok(client.status())
+}
+
+/// 立即补发离线队列
+#[tauri::command]
+async fn flush_sync_queue() -> Result<FlushReport, String> {
+    crate::core::plugin_isolation::guard("cloud_sync", async move { Ok(LeadServerClient::global().flush_queue().await) }).await
+}
+
+/// 登录云同步服务器
+#[tauri::command]
+async fn lead_server_login(username: String, password: String) -> Result<UserInfo, String> {
+    crate::core::plugin_isolation::guard("cloud_sync", async move { Ok(LeadServerClient::global().login(&username, &password).await?) }).await
+}
+
+/// 退出登录
+#[tauri::command]
+fn lead_server_logout() -> Result<(), String> {
+    Ok(LeadServerClient::global().logout()?)
+}
+
+/// 批量上传评论；服务器不可达时进入离线队列
+#[tauri::command]
+async fn upload_comment_batch(device_id: String, comments: Vec<UploadComment>) -> Result<DeliveryReport, String> {
+    crate::core::plugin_isolation::guard("cloud_sync", async move { Ok(LeadServerClient::global().upload_comments(&device_id, comments).await?) }).await
+}
+
+/// 拉取服务端保存的设备配置
+#[tauri::command]
+async fn pull_device_config(device_id: String) -> Result<Option<RemoteDeviceConfig>, String> {
+    crate::core::plugin_isolation::guard("cloud_sync", async move { Ok(LeadServerClient::global().get_device_config(&device_id).await?) }).await
+}
+
+/// 推送设备配置（增量合并）；服务器不可达时进入离线队列
+#[tauri::command]
+async fn push_device_config(device_id: String, update: DeviceConfigUpdate) -> Result<DeliveryReport, String> {
+    crate::core::plugin_isolation::guard("cloud_sync", async move { Ok(LeadServerClient::global().push_device_config(&device_id, update).await?) }).await
+}
+
+/// 获取服务端评论统计
+#[tauri::command]
+async fn get_lead_server_stats() -> Result<ServerStats, String> {
+    crate::core::plugin_isolation::guard("cloud_sync", async move { Ok(LeadServerClient::global().stats().await?) }).await
+}
+
+/// 获取设备设置（生效值 / 本地覆盖 / 服务端基线）
+#[tauri::command]
+fn get_device_settings(device_id: String) -> Result<DeviceSettingsView, String> {
+    let store = ConfigSyncStore::global().lock().map_err(|e| e.to_string())?;
+    Ok(store.view(&device_id))
+}
+
+/// 保存设备的本地覆盖
+#[tauri::command]
+fn set_device_overrides(device_id: String, overrides: DeviceSettings) -> Result<DeviceSettingsView, String> {
+    let mut store = ConfigSyncStore::global().lock().map_err(|e| e.to_string())?;
+    store.set_overrides(&device_id, overrides)
+}
+
+/// 从服务端拉取设备设置并生效
+#[tauri::command]
+async fn pull_device_settings(device_id: String) -> Result<DeviceSettingsView, String> {
+    crate::core::plugin_isolation::guard("cloud_sync", async move { device_config_sync::pull(&device_id).await }).await
+}
+
+/// 推送本地覆盖；存在冲突且未 `force` 时不推送并返回冲突字段
+#[tauri::command]
+async fn push_device_settings(device_id: String, force: Option<bool>) -> Result<PushReport, String> {
+    crate::core::plugin_isolation::guard("cloud_sync", async move { device_config_sync::push(&device_id, force.unwrap_or(false)).await }).await
+}
+
+/// 获取设备设置的优先级规则
+#[tauri::command]
+fn get_config_sync_policy() -> Result<SyncPolicy, String> {
+    let store = ConfigSyncStore::global().lock().map_err(|e| e.to_string())?;
+    Ok(store.policy().clone())
+}
+
+/// 保存设备设置的优先级规则
+#[tauri::command]
+fn set_config_sync_policy(policy: SyncPolicy) -> Result<(), String> {
+    let mut store = ConfigSyncStore::global().lock().map_err(|e| e.to_string())?;
+    store.set_policy(policy)
+}
+
+/// 获取实时看板通道状态
+#[tauri::command]
+fn get_realtime_uplink_status() -> UplinkStatus {
+    realtime_uplink::status()
+}
+
+/// 获取实时看板通道配置
+#[tauri::command]
+fn get_realtime_uplink_config() -> UplinkConfig {
+    realtime_uplink::config()
+}
+
+/// 保存实时看板通道配置（开启 / 关闭立即生效）
+#[tauri::command]
+fn set_realtime_uplink_config(config: UplinkConfig) -> Result<(), String> {
+    realtime_uplink::set_config(config)
 }
 
 /// 初始化插件
 pub fn init<R: Runtime>() -> TauriPlugin<R> {
     Builder::new("cloud_sync")
-        .invoke_handler(tauri::generate_handler![
+        .invoke_handler(crate::core::plugin_isolation::isolate("cloud_sync", tauri::generate_handler![
             get_machine_id,
             get_cloud_server_url,
-        ])
+            get_sync_status,
+            flush_sync_queue,
+            lead_server_login,
+            lead_server_logout,
+            upload_comment_batch,
+            pull_device_config,
+            push_device_config,
+            get_lead_server_stats,
+            get_device_settings,
+            set_device_overrides,
+            pull_device_settings,
+            push_device_settings,
+            get_config_sync_policy,
+            set_config_sync_policy,
+            get_realtime_uplink_status,
+            get_realtime_uplink_config,
+            set_realtime_uplink_config,
+        ]))
         .build()
 }
@@ -33,5 +33,5 @@ pub struct BatchUploadRequest {
     pub comments: Vec<CommentData>,
 }
 
-// 注意：实际的 HTTP 调用将在前端 TypeScript 中实现
+// 注意：HTTP 调用由 crate::infra::lead_server_client 完成（重试 / 令牌刷新 / 离线队列）
 // 这里只定义数据结构供跨层使用
@@ -1,55 +1,113 @@
-use tauri::{plugin::{Builder, TauriPlugin}, Runtime, AppHandle, Manager};
+use tauri::{plugin::{Builder, TauriPlugin}, Runtime, AppHandle, Emitter, Manager};
 use crate::services::contact_storage::repository_facade::ContactStorageFacade;
 use crate::services::contact_storage::models::{self, ContactStatus, ImportRecordStatus};
-use crate::services::contact_storage::parser::extract_numbers_from_text;
+use crate::services::contact_storage::parser::{extract_numbers_from_table, extract_numbers_from_text, extract_numbers_from_vcard};
+use crate::services::contact_storage::parser::formats::table_format::ColumnMapping;
+use crate::services::contact_storage::parser::readers::{self, parse_contact_file, ContactFilePreview};
+use crate::services::prospecting::prospecting_import_readers::{read_table, ImportFormat, RawTable, ReadOptions};
+use std::collections::HashMap;
+use crate::core::shared::event_bus::{self, AppEvent};
+use crate::services::contact_storage::repositories::contact_numbers::allocation_planner::{AllocationExecutionResult, AllocationPlan, AllocationPlanRequest};
+use crate::services::contact_storage::repositories::contact_numbers::destructive_preview::{
+    DestructiveExecution, DestructiveOperation, DestructivePreview, NumberSelection, DEFAULT_SAMPLE_SIZE,
+};
+use crate::services::contact_storage::repositories::contact_numbers::global_dedup::{DedupStrategy, DuplicateNumberGroup, DEFAULT_CHUNK_SIZE};
+use crate::services::contact_storage::repositories::contact_numbers::industry_quota::{IndustryQuotaRule, QuotaViolation};
+use crate::services::contact_storage::repositories::contact_numbers::vcf_chunk_export::VcfChunkExportResult;
+use crate::services::contact_storage::repositories::folder_import_sessions_repo::FolderImportFileDto;
+use crate::types::dto::contacts::{
+    DedupReportDto, FolderImportFileErrorDto, FolderImportResultDto, FolderImportSessionStateDto, ImportNumbersResultDto, NumberStatsDto,
+};
 use std::path::Path;
 use std::fs;
 use std::str::FromStr;
 use tokio::process::Command as AsyncCommand;
-use std::process::Command;
-use serde::{Deserialize, Serialize};
+use crate::services::contact_verification::{self, VerificationResult};
+use serde::Serialize;
+use ts_rs::TS;
 use crate::utils::adb_utils::execute_adb_command;
-use crate::services::vcf::{VcfOpenResult, MultiBrandVcfImporter, MultiBrandImportResult};
+use crate::services::vcf::{VcfOpenResult, MultiBrandVcfImporter, MultiBrandImportResult, VcfExportOptions};
 use tracing::{info, warn};
+use std::sync::Arc;
+use crate::services::recipes::{self, runner as recipe_runner, Recipe, RecipeIssue, RecipeRun, RecipeStore, RunTrigger};
+use crate::core::shared::i18n::Locale;
+use crate::services::report_export::{self, CellFormat, Column, ExportOptions, ExportOutcome, ReportExporter};
+use crate::services::contact_watch::{self, ContactWatchStatus, ContactWatchStore, WatchSource};
+use crate::services::wechat_verification::{self, VerificationProgress, WechatVerificationOptions};
+use crate::types::{AppError, AppResult, ErrorCode};
+
+mod recipe_executor;
+mod watch_importer;
+mod wechat_checker;
 
 // ==================== Contact Numbers ====================
 
+/// 导入号码文件：按扩展名识别 TXT / CSV / Excel / vCard，表格文件按 `mapping` 取列（未给时按表头推断）
 #[tauri::command]
 async fn import_file(
     app_handle: tauri::AppHandle,
     file_path: String,
-) -> Result<models::ImportNumbersResult, String> {
-    if !Path::new(&file_path).exists() {
-        return Err(format!("文件不存在: {}", file_path));
-    }
-
-    let content = fs::read_to_string(&file_path).map_err(|e| format!("读取文件失败: {}", e))?;
-    let total_lines = content.lines().count() as i64;
-    let parse_result = extract_numbers_from_text(&content);
-    let numbers = parse_result.contacts;
+    mapping: Option<ColumnMapping>,
+) -> AppResult<ImportNumbersResultDto> {
+    crate::core::plugin_isolation::guard("contacts", import_file_impl(app_handle, file_path, mapping)).await
+}
 
-    let file_name = Path::new(&file_path)
-        .file_name()
-        .and_then(|f| f.to_str())
-        .unwrap_or("unknown.txt")
-        .to_string();
+async fn import_file_impl(
+    app_handle: tauri::AppHandle,
+    file_path: String,
+    mapping: Option<ColumnMapping>,
+) -> AppResult<ImportNumbersResultDto> {
+    let parsed = parse_contact_file(Path::new(&file_path), mapping)?;
+    info!(
+        "📥 号码文件解析完成: {:?} {} 行, 有效 {} 个, 无效 {} 个, 重复 {} 个",
+        parsed.format, parsed.total_lines, parsed.result.stats.parsed_count, parsed.result.stats.invalid_count, parsed.result.stats.duplicate_count
+    );
 
     let facade = ContactStorageFacade::new(&app_handle);
-    let (inserted, duplicates, errors) = facade.insert_numbers(&numbers, &file_path)?;
-    
-    let status_str = if errors.is_empty() { 
+    Ok(store_parsed_numbers(&facade, &file_path, parsed.total_lines as i64, &parsed.result.contacts, Some(&parsed.industries))?.into())
+}
+
+/// 预览号码文件的前 `limit` 条解析结果（默认 20），确认列映射后再调用 `import_file`
+#[tauri::command]
+async fn preview_contact_file(
+    file_path: String,
+    mapping: Option<ColumnMapping>,
+    limit: Option<usize>,
+) -> AppResult<ContactFilePreview> {
+    crate::core::plugin_isolation::guard("contacts", async move { Ok(readers::preview_contact_file(Path::new(&file_path), mapping, limit.unwrap_or(20).clamp(1, 500))?) }).await
+}
+
+/// 入库解析出的号码并写入导入记录（TXT / Excel / vCard 共用）
+fn store_parsed_numbers(
+    facade: &ContactStorageFacade,
+    file_path: &str,
+    total_lines: i64,
+    numbers: &[(String, String)],
+    industries: Option<&HashMap<String, String>>,
+) -> Result<models::ImportNumbersResult, String> {
+    let (inserted, duplicates, mut errors) = facade.insert_numbers(numbers, file_path)?;
+    if inserted > 0 {
+        event_bus::publish(AppEvent::ContactsImported { source: "contacts".to_string(), count: inserted as u64 });
+    }
+    if let Some(industries) = industries.filter(|m| !m.is_empty()) {
+        if let Err(e) = facade.apply_import_industries(industries, file_path) {
+            errors.push(format!("写入行业标签失败: {}", e));
+        }
+    }
+
+    let status_str = if errors.is_empty() {
         if numbers.is_empty() { "empty" } else if inserted == 0 && duplicates > 0 { "all_duplicates" } else { "success" }
-    } else { 
-        "partial" 
+    } else {
+        "partial"
     };
-    
+
     let status_enum = ImportRecordStatus::from_str(status_str).unwrap_or(ImportRecordStatus::Pending);
     let error_message = if errors.is_empty() { None } else { Some(errors.join("; ")) };
-    
+
     let _ = facade.create_txt_import_record(
-        &file_path, total_lines, numbers.len() as i64, inserted, duplicates, status_enum, error_message.as_deref(),
+        file_path, total_lines, numbers.len() as i64, inserted, duplicates, status_enum, error_message.as_deref(),
     );
-    
+
     Ok(models::ImportNumbersResult {
         success: true,
         total_files: 1,
@@ -60,75 +118,274 @@ async fn import_file(
     })
 }
 
+/// Excel 列映射预览：表头 + 前几行样例，供前端选择号码 / 姓名 / 行业列
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct XlsxColumnsPreview {
+    headers: Vec<String>,
+    sample_rows: Vec<Vec<String>>,
+    total_rows: usize,
+}
+
+fn read_xlsx_table(file_path: &str, sheet: Option<String>) -> Result<RawTable, String> {
+    if !Path::new(file_path).exists() {
+        return Err(format!("文件不存在: {}", file_path));
+    }
+    let options = ReadOptions { format: Some(ImportFormat::Xlsx), sheet, ..Default::default() };
+    read_table(Path::new(file_path), &options).map_err(|e| format!("读取 Excel 失败: {}", e))
+}
+
+#[tauri::command]
+async fn preview_xlsx_columns(file_path: String, sheet: Option<String>) -> AppResult<XlsxColumnsPreview> {
+    crate::core::plugin_isolation::guard("contacts", preview_xlsx_columns_impl(file_path, sheet)).await
+}
+
+async fn preview_xlsx_columns_impl(file_path: String, sheet: Option<String>) -> AppResult<XlsxColumnsPreview> {
+    let table = read_xlsx_table(&file_path, sheet)?;
+    Ok(XlsxColumnsPreview {
+        sample_rows: table.rows.iter().take(5).cloned().collect(),
+        total_rows: table.rows.len(),
+        headers: table.headers,
+    })
+}
+
+/// 按列映射导入 Excel(.xlsx) 文件
+#[tauri::command]
+async fn import_xlsx_file(
+    app_handle: tauri::AppHandle,
+    file_path: String,
+    mapping: ColumnMapping,
+) -> AppResult<ImportNumbersResultDto> {
+    crate::core::plugin_isolation::guard("contacts", import_xlsx_file_impl(app_handle, file_path, mapping)).await
+}
+
+async fn import_xlsx_file_impl(
+    app_handle: tauri::AppHandle,
+    file_path: String,
+    mapping: ColumnMapping,
+) -> AppResult<ImportNumbersResultDto> {
+    let table = read_xlsx_table(&file_path, mapping.sheet.clone())?;
+    let (parse_result, industries) = extract_numbers_from_table(&table.headers, &table.rows, &mapping)?;
+    info!(
+        "📊 Excel 解析完成: {} 行, 有效 {} 个, 无效 {} 个, 重复 {} 个",
+        table.rows.len(), parse_result.stats.parsed_count, parse_result.stats.invalid_count, parse_result.stats.duplicate_count
+    );
+
+    let facade = ContactStorageFacade::new(&app_handle);
+    Ok(store_parsed_numbers(&facade, &file_path, table.rows.len() as i64, &parse_result.contacts, Some(&industries))?.into())
+}
+
+/// 导入 vCard(.vcf) 文件：把已有名片反向解析为号码
+#[tauri::command]
+async fn import_vcf_file(
+    app_handle: tauri::AppHandle,
+    file_path: String,
+) -> AppResult<ImportNumbersResultDto> {
+    crate::core::plugin_isolation::guard("contacts", import_vcf_file_impl(app_handle, file_path)).await
+}
+
+async fn import_vcf_file_impl(
+    app_handle: tauri::AppHandle,
+    file_path: String,
+) -> AppResult<ImportNumbersResultDto> {
+    if !Path::new(&file_path).exists() {
+        return Err(AppError::not_found(format!("文件不存在: {}", file_path)));
+    }
+
+    let bytes = fs::read(&file_path).map_err(|e| format!("读取文件失败: {}", e))?;
+    let content = String::from_utf8_lossy(&bytes);
+    let total_cards = content.matches("BEGIN:VCARD").count() as i64;
+    let parse_result = extract_numbers_from_vcard(&content);
+
+    let facade = ContactStorageFacade::new(&app_handle);
+    Ok(store_parsed_numbers(&facade, &file_path, total_cards, &parse_result.contacts, None)?.into())
+}
+
+/// 文件夹导入进度事件名
+pub(crate) const FOLDER_IMPORT_PROGRESS_EVENT: &str = "contact-folder-import-progress";
+/// 全库去重进度事件名
+pub(crate) const DEDUP_PROGRESS_EVENT: &str = "contact-dedup-progress";
+/// 通讯录导入核验进度事件名
+pub(crate) const VERIFY_PROGRESS_EVENT: &str = "contact-verify-progress";
+
+/// `contact-folder-import-progress` 事件负载
+#[derive(Debug, Clone, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct FolderImportProgress {
+    #[ts(type = "number")]
+    session_id: i64,
+    file_path: String,
+    index: usize,
+    total: usize,
+    /// skipped | completed | failed
+    status: &'static str,
+    #[ts(type = "number")]
+    inserted: i64,
+    #[ts(type = "number")]
+    duplicates: i64,
+    error: Option<String>,
+}
+
+/// 读取并入库单个 TXT / vCard 文件
+fn import_folder_file(
+    facade: &ContactStorageFacade,
+    path: &Path,
+    ext: &str,
+) -> Result<models::ImportNumbersResult, String> {
+    let bytes = fs::read(path).map_err(|e| format!("读取文件失败: {}", e))?;
+    let content = String::from_utf8_lossy(&bytes);
+    let (total_lines, parse_result) = if ext == "vcf" {
+        (content.matches("BEGIN:VCARD").count() as i64, extract_numbers_from_vcard(&content))
+    } else {
+        (content.lines().count() as i64, extract_numbers_from_text(&content))
+    };
+    store_parsed_numbers(facade, &path.to_string_lossy(), total_lines, &parse_result.contacts, None)
+}
+
+/// 导入文件夹下的 TXT / vCard 文件（可续导）：
+/// 已导入完成且未修改的文件直接跳过，单个文件失败时记录错误并继续，`restart` 时从头导入
 #[tauri::command]
 async fn import_folder(
     app_handle: tauri::AppHandle,
     folder_path: String,
-) -> Result<models::ImportNumbersResult, String> {
+    restart: Option<bool>,
+) -> AppResult<FolderImportResultDto> {
+    crate::core::plugin_isolation::guard("contacts", import_folder_impl(app_handle, folder_path, restart)).await
+}
+
+async fn import_folder_impl(
+    app_handle: tauri::AppHandle,
+    folder_path: String,
+    restart: Option<bool>,
+) -> AppResult<FolderImportResultDto> {
     let folder = Path::new(&folder_path);
     if !folder.exists() || !folder.is_dir() {
-        return Err(format!("文件夹不存在或不是目录: {}", folder_path));
+        return Err(AppError::not_found(format!("文件夹不存在或不是目录: {}", folder_path)));
     }
 
-    let facade = ContactStorageFacade::new(&app_handle);
-    let mut total_files: i64 = 0;
-    let mut total_numbers: i64 = 0;
-    let mut total_inserted: i64 = 0;
-    let mut total_duplicates: i64 = 0;
-    let mut all_errors: Vec<String> = Vec::new();
-
+    // Excel 需要列映射，只能单文件导入；文件夹导入处理 TXT 与 vCard
+    let mut files: Vec<(std::path::PathBuf, String)> = Vec::new();
     for entry in fs::read_dir(folder).map_err(|e| format!("读取目录失败: {}", e))? {
-        let entry = entry.map_err(|e| format!("读取目录项失败: {}", e))?;
-        let path = entry.path();
-        if path.is_file() {
-            if let Some(ext) = path.extension() {
-                if ext.to_string_lossy().to_lowercase() == "txt" {
-                    total_files += 1;
-                    let file_path_str = path.to_string_lossy().to_string();
-                    
-                    match fs::read_to_string(&path) {
-                        Ok(content) => {
-                            let total_lines = content.lines().count() as i64;
-                            let parse_result = extract_numbers_from_text(&content);
-                            let numbers = parse_result.contacts;
-                            let (inserted, duplicates, mut errors) = facade.insert_numbers(&numbers, &file_path_str)?;
-                            
-                            total_numbers += numbers.len() as i64;
-                            total_inserted += inserted;
-                            total_duplicates += duplicates;
-                            all_errors.append(&mut errors);
-                            
-                            let status_str = if errors.is_empty() { 
-                                if numbers.is_empty() { "empty" } else if inserted == 0 && duplicates > 0 { "all_duplicates" } else { "success" }
-                            } else { 
-                                "partial" 
-                            };
-                            
-                            let status_enum = ImportRecordStatus::from_str(status_str).unwrap_or(ImportRecordStatus::Pending);
-                            let error_message = if errors.is_empty() { None } else { Some(errors.join("; ")) };
-                            
-                            let _ = facade.create_txt_import_record(
-                                &file_path_str, total_lines, numbers.len() as i64, inserted, duplicates, status_enum, error_message.as_deref(),
-                            );
-                        }
-                        Err(e) => {
-                            let err_msg = format!("读取文件失败 {}: {}", path.to_string_lossy(), e);
-                            all_errors.push(err_msg);
-                        }
-                    }
-                }
+        let path = match entry {
+            Ok(entry) => entry.path(),
+            Err(e) => {
+                warn!("⚠️ 跳过无法读取的目录项: {}", e);
+                continue;
             }
+        };
+        let ext = path.extension().map(|e| e.to_string_lossy().to_lowercase()).unwrap_or_default();
+        if path.is_file() && (ext == "txt" || ext == "vcf") {
+            files.push((path, ext));
         }
     }
+    files.sort();
 
-    Ok(models::ImportNumbersResult {
+    let facade = ContactStorageFacade::new(&app_handle);
+    let session_key = folder.canonicalize().unwrap_or_else(|_| folder.to_path_buf()).to_string_lossy().to_string();
+    let session_id = facade.open_folder_import_session(&session_key, restart.unwrap_or(false))?;
+
+    let total = files.len();
+    let mut summary = models::ImportNumbersResult {
         success: true,
-        total_files,
-        total_numbers,
-        inserted: total_inserted,
-        duplicates: total_duplicates,
-        errors: all_errors,
-    })
+        total_files: total as i64,
+        total_numbers: 0,
+        inserted: 0,
+        duplicates: 0,
+        errors: Vec::new(),
+    };
+    let mut skipped_files = 0;
+    let mut failed_files = Vec::new();
+
+    for (index, (path, ext)) in files.iter().enumerate() {
+        let file_path = path.to_string_lossy().to_string();
+        let metadata = fs::metadata(path).ok();
+        let file_size = metadata.as_ref().map_or(0, |m| m.len() as i64);
+        let modified_at = metadata
+            .and_then(|m| m.modified().ok())
+            .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+            .map_or(0, |d| d.as_secs() as i64);
+
+        let mut progress = FolderImportProgress {
+            session_id,
+            file_path: file_path.clone(),
+            index,
+            total,
+            status: "skipped",
+            inserted: 0,
+            duplicates: 0,
+            error: None,
+        };
+        if facade.is_folder_file_completed(session_id, &file_path, file_size, modified_at)? {
+            skipped_files += 1;
+            let _ = app_handle.emit(FOLDER_IMPORT_PROGRESS_EVENT, &progress);
+            continue;
+        }
+
+        let mut record = FolderImportFileDto {
+            file_path: file_path.clone(),
+            file_size,
+            modified_at,
+            status: "completed".to_string(),
+            total_numbers: 0,
+            inserted: 0,
+            duplicates: 0,
+            error_message: None,
+            updated_at: String::new(),
+        };
+        match import_folder_file(&facade, path, ext) {
+            Ok(mut result) => {
+                summary.total_numbers += result.total_numbers;
+                summary.inserted += result.inserted;
+                summary.duplicates += result.duplicates;
+                summary.errors.append(&mut result.errors);
+                record.total_numbers = result.total_numbers;
+                record.inserted = result.inserted;
+                record.duplicates = result.duplicates;
+                progress.status = "completed";
+            }
+            Err(e) => {
+                warn!("⚠️ 文件导入失败，继续处理其余文件 {}: {}", file_path, e);
+                summary.errors.push(format!("{}: {}", file_path, e));
+                failed_files.push(FolderImportFileErrorDto { file_path: file_path.clone(), error: e.clone() });
+                record.status = "failed".to_string();
+                record.error_message = Some(e.clone());
+                progress.status = "failed";
+                progress.error = Some(e);
+            }
+        }
+        progress.inserted = record.inserted;
+        progress.duplicates = record.duplicates;
+        facade.record_folder_import_file(session_id, &record)?;
+        let _ = app_handle.emit(FOLDER_IMPORT_PROGRESS_EVENT, &progress);
+    }
+
+    let status = if failed_files.is_empty() { "completed" } else { "partial" };
+    facade.finish_folder_import_session(session_id, status)?;
+    info!(
+        "📁 文件夹导入完成 {}: {} 个文件, 跳过 {}, 失败 {}, 新增 {} 个号码",
+        folder_path, total, skipped_files, failed_files.len(), summary.inserted
+    );
+
+    Ok(FolderImportResultDto { summary: summary.into(), session_id, skipped_files, failed_files })
+}
+
+/// 查询文件夹的导入会话及逐文件进度
+#[tauri::command]
+async fn get_folder_import_session(
+    app_handle: tauri::AppHandle,
+    folder_path: String,
+) -> AppResult<Option<FolderImportSessionStateDto>> {
+    crate::core::plugin_isolation::guard("contacts", get_folder_import_session_impl(app_handle, folder_path)).await
+}
+
+async fn get_folder_import_session_impl(
+    app_handle: tauri::AppHandle,
+    folder_path: String,
+) -> AppResult<Option<FolderImportSessionStateDto>> {
+    let folder = Path::new(&folder_path);
+    let session_key = folder.canonicalize().unwrap_or_else(|_| folder.to_path_buf()).to_string_lossy().to_string();
+    let facade = ContactStorageFacade::new(&app_handle);
+    Ok(facade.get_folder_import_session(&session_key)?.map(Into::into))
 }
 
 #[tauri::command]
@@ -139,13 +396,100 @@ async fn list(
     search: Option<String>,
     industry: Option<String>,
     status: Option<String>,
-) -> Result<models::ContactNumberList, String> {
+) -> AppResult<models::ContactNumberList> {
+    crate::core::plugin_isolation::guard("contacts", list_impl(app_handle, limit, offset, search, industry, status)).await
+}
+
+async fn list_impl(
+    app_handle: tauri::AppHandle,
+    limit: i64,
+    offset: i64,
+    search: Option<String>,
+    industry: Option<String>,
+    status: Option<String>,
+) -> AppResult<models::ContactNumberList> {
     let facade = ContactStorageFacade::new(&app_handle);
     let status_enum = match status {
         Some(s) => Some(ContactStatus::from_str(&s)?),
         None => None,
     };
-    facade.list_numbers_filtered(limit, offset, status_enum, industry, search)
+    Ok(facade.list_numbers_filtered(limit, offset, status_enum, industry, search)?)
+}
+
+/// 号码导出分页大小
+const CONTACT_EXPORT_PAGE_SIZE: i64 = 1000;
+
+/// 号码导出列
+fn contact_number_columns(locale: Locale) -> Vec<Column> {
+    vec![
+        Column::localized("id", "export.contact.id", locale).format(CellFormat::Integer),
+        Column::localized("phone", "export.contact.phone", locale),
+        Column::localized("name", "export.contact.name", locale),
+        Column::localized("industry", "export.contact.industry", locale),
+        Column::localized("status", "export.contact.status", locale),
+        Column::localized("source_file", "export.contact.source_file", locale),
+        Column::localized("created_at", "export.contact.created_at", locale).format(CellFormat::DateTime),
+        Column::localized("assigned_batch_id", "export.contact.batch", locale),
+        Column::localized("assigned_at", "export.contact.assigned_at", locale).format(CellFormat::DateTime),
+        Column::localized("imported_device_id", "export.contact.device", locale),
+    ]
+}
+
+/// 按筛选条件分页导出号码（csv / xlsx / json），进度通过统一导出事件上报
+#[tauri::command]
+async fn export_contact_numbers(
+    app_handle: tauri::AppHandle,
+    search: Option<String>,
+    industry: Option<String>,
+    status: Option<String>,
+    format: Option<String>,
+    locale: Option<String>,
+    output_path: Option<String>,
+) -> AppResult<ExportOutcome> {
+    crate::core::plugin_isolation::guard("contacts", export_contact_numbers_impl(app_handle, search, industry, status, format, locale, output_path)).await
+}
+
+async fn export_contact_numbers_impl(
+    app_handle: tauri::AppHandle,
+    search: Option<String>,
+    industry: Option<String>,
+    status: Option<String>,
+    format: Option<String>,
+    locale: Option<String>,
+    output_path: Option<String>,
+) -> AppResult<ExportOutcome> {
+    let options = ExportOptions::from_args(format.as_deref(), locale.as_deref(), output_path)?;
+    let status_enum = match status.filter(|s| !s.is_empty()) {
+        Some(s) => Some(ContactStatus::from_str(&s)?),
+        None => None,
+    };
+    tokio::task::spawn_blocking(move || {
+        let facade = ContactStorageFacade::new(&app_handle);
+        let page = |offset: i64| {
+            facade.list_numbers_filtered(CONTACT_EXPORT_PAGE_SIZE, offset, status_enum.clone(), industry.clone(), search.clone())
+        };
+        let mut current = page(0)?;
+        let columns = contact_number_columns(options.locale);
+        let total = current.total.max(0) as u64;
+        let mut exporter = ReportExporter::create("contacts", options, Some(total), report_export::emit_progress(&app_handle))?;
+        exporter.start_table(None, columns)?;
+        let mut offset = 0;
+        loop {
+            let fetched = current.items.len() as i64;
+            for item in &current.items {
+                exporter.write_row(&serde_json::to_value(item).map_err(|e| e.to_string())?)?;
+            }
+            offset += fetched;
+            if fetched < CONTACT_EXPORT_PAGE_SIZE || offset >= current.total {
+                break;
+            }
+            current = page(offset)?;
+        }
+        exporter.finish()
+    })
+    .await
+    .map_err(|e| AppError::internal(e.to_string()))?
+    .map_err(AppError::from)
 }
 
 #[tauri::command]
@@ -155,14 +499,24 @@ async fn list_without_batch(
     offset: i64,
     industry: Option<String>,
     status: Option<String>,
-) -> Result<models::ContactNumberList, String> {
+) -> AppResult<models::ContactNumberList> {
+    crate::core::plugin_isolation::guard("contacts", list_without_batch_impl(app_handle, limit, offset, industry, status)).await
+}
+
+async fn list_without_batch_impl(
+    app_handle: tauri::AppHandle,
+    limit: i64,
+    offset: i64,
+    industry: Option<String>,
+    status: Option<String>,
+) -> AppResult<models::ContactNumberList> {
     let facade = ContactStorageFacade::new(&app_handle);
     let status_enum = if let Some(s) = status {
         if s.is_empty() { None } else { Some(ContactStatus::from_str(&s).map_err(|e| format!("Invalid status: {}", e))?) }
     } else {
         None
     };
-    facade.list_numbers_without_batch_filtered(limit, offset, None, industry, status_enum)
+    Ok(facade.list_numbers_without_batch_filtered(limit, offset, None, industry, status_enum)?)
 }
 
 #[tauri::command]
@@ -172,9 +526,19 @@ async fn list_by_batch(
     only_used: Option<bool>,
     limit: i64,
     offset: i64,
-) -> Result<models::ContactNumberList, String> {
+) -> AppResult<models::ContactNumberList> {
+    crate::core::plugin_isolation::guard("contacts", list_by_batch_impl(app_handle, batch_id, only_used, limit, offset)).await
+}
+
+async fn list_by_batch_impl(
+    app_handle: tauri::AppHandle,
+    batch_id: String,
+    only_used: Option<bool>,
+    limit: i64,
+    offset: i64,
+) -> AppResult<models::ContactNumberList> {
     let facade = ContactStorageFacade::new(&app_handle);
-    facade.list_numbers_by_batch_filtered(&batch_id, limit, offset, only_used.unwrap_or(false))
+    Ok(facade.list_numbers_by_batch_filtered(&batch_id, limit, offset, only_used.unwrap_or(false))?)
 }
 
 #[tauri::command]
@@ -183,15 +547,30 @@ async fn list_for_vcf_batch(
     batch_id: String,
     limit: i64,
     offset: i64,
-) -> Result<models::ContactNumberList, String> {
+) -> AppResult<models::ContactNumberList> {
+    crate::core::plugin_isolation::guard("contacts", list_for_vcf_batch_impl(app_handle, batch_id, limit, offset)).await
+}
+
+async fn list_for_vcf_batch_impl(
+    app_handle: tauri::AppHandle,
+    batch_id: String,
+    limit: i64,
+    offset: i64,
+) -> AppResult<models::ContactNumberList> {
     let facade = ContactStorageFacade::new(&app_handle);
-    facade.list_numbers_for_vcf_batch(&batch_id, limit, offset)
+    Ok(facade.list_numbers_for_vcf_batch(&batch_id, limit, offset)?)
 }
 
 #[tauri::command]
 async fn get_stats(
     app_handle: tauri::AppHandle,
-) -> Result<models::ContactNumberStatsDto, String> {
+) -> AppResult<NumberStatsDto> {
+    crate::core::plugin_isolation::guard("contacts", get_stats_impl(app_handle)).await
+}
+
+async fn get_stats_impl(
+    app_handle: tauri::AppHandle,
+) -> AppResult<NumberStatsDto> {
     let facade = ContactStorageFacade::new(&app_handle);
     let stats = facade.get_contact_number_stats()?;
     let per_industry = Vec::new(); // Placeholder
@@ -202,44 +581,299 @@ async fn get_stats(
         imported: stats.get("imported").and_then(|v| v.as_i64()).unwrap_or(0),
         unclassified: stats.get("available").and_then(|v| v.as_i64()).unwrap_or(0),
         per_industry,
+    }
+    .into())
+}
+
+#[tauri::command]
+async fn get_distinct_industries(
+    app_handle: tauri::AppHandle,
+) -> AppResult<Vec<String>> {
+    crate::core::plugin_isolation::guard("contacts", get_distinct_industries_impl(app_handle)).await
+}
+
+async fn get_distinct_industries_impl(
+    app_handle: tauri::AppHandle,
+) -> AppResult<Vec<String>> {
+    let facade = ContactStorageFacade::new(&app_handle);
+    Ok(facade.get_distinct_industries()?)
+}
+
+#[tauri::command]
+async fn set_industry_by_id_range(
+    app_handle: tauri::AppHandle,
+    start_id: i64,
+    end_id: i64,
+    industry: String,
+) -> AppResult<i64> {
+    crate::core::plugin_isolation::guard("contacts", set_industry_by_id_range_impl(app_handle, start_id, end_id, industry)).await
+}
+
+async fn set_industry_by_id_range_impl(
+    app_handle: tauri::AppHandle,
+    start_id: i64,
+    end_id: i64,
+    industry: String,
+) -> AppResult<i64> {
+    let facade = ContactStorageFacade::new(&app_handle);
+    Ok(facade.set_industry_by_id_range(start_id, end_id, &industry)?)
+}
+
+#[tauri::command]
+async fn mark_as_not_imported(
+    app_handle: tauri::AppHandle,
+    number_ids: Vec<i64>,
+) -> AppResult<i64> {
+    crate::core::plugin_isolation::guard("contacts", mark_as_not_imported_impl(app_handle, number_ids)).await
+}
+
+async fn mark_as_not_imported_impl(
+    app_handle: tauri::AppHandle,
+    number_ids: Vec<i64>,
+) -> AppResult<i64> {
+    let facade = ContactStorageFacade::new(&app_handle);
+    Ok(facade.mark_numbers_as_not_imported_by_ids(&number_ids)?)
+}
+
+#[tauri::command]
+async fn delete_numbers(
+    app_handle: tauri::AppHandle,
+    number_ids: Vec<i64>,
+) -> AppResult<i64> {
+    crate::core::plugin_isolation::guard("contacts", delete_numbers_impl(app_handle, number_ids)).await
+}
+
+async fn delete_numbers_impl(
+    app_handle: tauri::AppHandle,
+    number_ids: Vec<i64>,
+) -> AppResult<i64> {
+    let facade = ContactStorageFacade::new(&app_handle);
+    Ok(facade.delete_numbers_by_ids(&number_ids)?)
+}
+
+/// 预览破坏性操作（删除 / 标记未导入 / 改行业）：受影响行数、样例、关联批次与导入会话，返回执行令牌
+#[tauri::command]
+async fn preview_destructive_operation(
+    app_handle: tauri::AppHandle,
+    operation: DestructiveOperation,
+    selection: NumberSelection,
+    sample_size: Option<usize>,
+) -> AppResult<DestructivePreview> {
+    crate::core::plugin_isolation::guard("contacts", preview_destructive_operation_impl(app_handle, operation, selection, sample_size)).await
+}
+
+async fn preview_destructive_operation_impl(
+    app_handle: tauri::AppHandle,
+    operation: DestructiveOperation,
+    selection: NumberSelection,
+    sample_size: Option<usize>,
+) -> AppResult<DestructivePreview> {
+    let facade = ContactStorageFacade::new(&app_handle);
+    Ok(facade.preview_destructive_operation(operation, selection, sample_size.unwrap_or(DEFAULT_SAMPLE_SIZE))?)
+}
+
+/// 凭预览令牌执行破坏性操作（令牌一次有效）
+#[tauri::command]
+async fn execute_destructive_operation(
+    app_handle: tauri::AppHandle,
+    preview_token: String,
+) -> AppResult<DestructiveExecution> {
+    crate::core::plugin_isolation::guard("contacts", execute_destructive_operation_impl(app_handle, preview_token)).await
+}
+
+async fn execute_destructive_operation_impl(
+    app_handle: tauri::AppHandle,
+    preview_token: String,
+) -> AppResult<DestructiveExecution> {
+    let facade = ContactStorageFacade::new(&app_handle);
+    let result = facade.execute_destructive_operation(&preview_token)?;
+    info!("🗑️ 已执行号码操作 {:?}: 影响 {} 条", result.operation, result.affected);
+    Ok(result)
+}
+
+/// 全库号码去重：扫描历史导入遗留的规范化重复号码并按策略合并，`dry_run` 时只返回报告
+#[tauri::command]
+async fn dedup_contact_numbers(
+    app_handle: tauri::AppHandle,
+    strategy: Option<DedupStrategy>,
+    dry_run: Option<bool>,
+    chunk_size: Option<usize>,
+) -> AppResult<DedupReportDto> {
+    crate::core::plugin_isolation::guard("contacts", dedup_contact_numbers_impl(app_handle, strategy, dry_run, chunk_size)).await
+}
+
+async fn dedup_contact_numbers_impl(
+    app_handle: tauri::AppHandle,
+    strategy: Option<DedupStrategy>,
+    dry_run: Option<bool>,
+    chunk_size: Option<usize>,
+) -> AppResult<DedupReportDto> {
+    let strategy = strategy.unwrap_or_default();
+    let dry_run = dry_run.unwrap_or(true);
+    let chunk_size = chunk_size.unwrap_or(DEFAULT_CHUNK_SIZE);
+    tokio::task::spawn_blocking(move || {
+        let facade = ContactStorageFacade::new(&app_handle);
+        let report = facade.run_global_dedup(strategy, dry_run, chunk_size, |progress| {
+            let _ = app_handle.emit(DEDUP_PROGRESS_EVENT, progress);
+        })?;
+        info!(
+            "🧹 号码去重完成: 扫描 {} 条, 重复组 {}, 移除 {} 条 (dry_run={})",
+            report.scanned, report.duplicate_groups, report.removed, report.dry_run
+        );
+        Ok(DedupReportDto::from(report))
+    })
+    .await
+    .map_err(|e| AppError::internal(format!("去重任务异常: {}", e)))?
+}
+
+/// 查找跨批次的重复号码（+86 / 空格 / 破折号等写法不同但规范化后相同），只读
+#[tauri::command]
+async fn find_duplicate_numbers(
+    app_handle: tauri::AppHandle,
+    cross_batch_only: Option<bool>,
+) -> AppResult<Vec<DuplicateNumberGroup>> {
+    crate::core::plugin_isolation::guard("contacts", find_duplicate_numbers_impl(app_handle, cross_batch_only)).await
+}
+
+async fn find_duplicate_numbers_impl(
+    app_handle: tauri::AppHandle,
+    cross_batch_only: Option<bool>,
+) -> AppResult<Vec<DuplicateNumberGroup>> {
+    let cross_batch_only = cross_batch_only.unwrap_or(false);
+    Ok(tokio::task::spawn_blocking(move || ContactStorageFacade::new(&app_handle).find_duplicate_numbers(cross_batch_only))
+        .await
+        .map_err(|e| AppError::internal(format!("查重任务异常: {}", e)))??)
+}
+
+/// 合并重复号码：默认保留元数据最完整的记录，并把其余记录的姓名 / 行业 / 分配信息补进来
+#[tauri::command]
+async fn merge_duplicates(
+    app_handle: tauri::AppHandle,
+    normalized_phones: Option<Vec<String>>,
+    strategy: Option<DedupStrategy>,
+    dry_run: Option<bool>,
+) -> AppResult<DedupReportDto> {
+    crate::core::plugin_isolation::guard("contacts", merge_duplicates_impl(app_handle, normalized_phones, strategy, dry_run)).await
+}
+
+async fn merge_duplicates_impl(
+    app_handle: tauri::AppHandle,
+    normalized_phones: Option<Vec<String>>,
+    strategy: Option<DedupStrategy>,
+    dry_run: Option<bool>,
+) -> AppResult<DedupReportDto> {
+    let strategy = strategy.unwrap_or(DedupStrategy::KeepRichest);
+    let dry_run = dry_run.unwrap_or(false);
+    tokio::task::spawn_blocking(move || {
+        let facade = ContactStorageFacade::new(&app_handle);
+        let report = facade.merge_duplicates(normalized_phones.as_deref(), strategy, dry_run)?;
+        info!("🧹 重复号码合并: {} 组, 移除 {} 条 (dry_run={})", report.duplicate_groups, report.removed, report.dry_run);
+        Ok(DedupReportDto::from(report))
     })
+    .await
+    .map_err(|e| AppError::internal(format!("合并任务异常: {}", e)))?
+}
+
+/// 生成多设备号码分配方案（只读，返回给操作员审阅）
+#[tauri::command]
+async fn plan_number_allocation(
+    app_handle: tauri::AppHandle,
+    request: AllocationPlanRequest,
+) -> AppResult<AllocationPlan> {
+    crate::core::plugin_isolation::guard("contacts", plan_number_allocation_impl(app_handle, request)).await
+}
+
+async fn plan_number_allocation_impl(
+    app_handle: tauri::AppHandle,
+    request: AllocationPlanRequest,
+) -> AppResult<AllocationPlan> {
+    if request.devices.is_empty() {
+        return Err(AppError::invalid_argument("至少需要一台设备"));
+    }
+    if let Some(d) = request.devices.iter().find(|d| d.capacity < 0) {
+        return Err(AppError::invalid_argument(format!("设备 {} 的容量不能为负数", d.device_id)));
+    }
+    let facade = ContactStorageFacade::new(&app_handle);
+    Ok(facade.plan_allocation(&request)?)
+}
+
+/// 执行审阅（可能调整）后的分配方案
+#[tauri::command]
+async fn execute_number_allocation(
+    app_handle: tauri::AppHandle,
+    plan: AllocationPlan,
+) -> AppResult<AllocationExecutionResult> {
+    crate::core::plugin_isolation::guard("contacts", execute_number_allocation_impl(app_handle, plan)).await
+}
+
+async fn execute_number_allocation_impl(
+    app_handle: tauri::AppHandle,
+    plan: AllocationPlan,
+) -> AppResult<AllocationExecutionResult> {
+    let facade = ContactStorageFacade::new(&app_handle);
+    let result = facade.execute_allocation_plan(&plan)?;
+    info!("📦 分配方案 {} 已执行: {} 台设备, {} 个号码", result.plan_id, result.batches.len(), result.assigned);
+    Ok(result)
 }
 
+// ==================== Industry Quotas ====================
+
 #[tauri::command]
-async fn get_distinct_industries(
-    app_handle: tauri::AppHandle,
-) -> Result<Vec<String>, String> {
+async fn list_industry_quota_rules(app_handle: tauri::AppHandle) -> AppResult<Vec<IndustryQuotaRule>> {
+    crate::core::plugin_isolation::guard("contacts", list_industry_quota_rules_impl(app_handle)).await
+}
+
+async fn list_industry_quota_rules_impl(app_handle: tauri::AppHandle) -> AppResult<Vec<IndustryQuotaRule>> {
     let facade = ContactStorageFacade::new(&app_handle);
-    facade.get_distinct_industries()
+    Ok(facade.list_industry_quota_rules()?)
 }
 
+/// 新建（`id` 为空）或更新行业配额规则
 #[tauri::command]
-async fn set_industry_by_id_range(
+async fn save_industry_quota_rule(
     app_handle: tauri::AppHandle,
-    start_id: i64,
-    end_id: i64,
-    industry: String,
-) -> Result<i64, String> {
+    rule: IndustryQuotaRule,
+) -> AppResult<IndustryQuotaRule> {
+    crate::core::plugin_isolation::guard("contacts", save_industry_quota_rule_impl(app_handle, rule)).await
+}
+
+async fn save_industry_quota_rule_impl(
+    app_handle: tauri::AppHandle,
+    rule: IndustryQuotaRule,
+) -> AppResult<IndustryQuotaRule> {
     let facade = ContactStorageFacade::new(&app_handle);
-    facade.set_industry_by_id_range(start_id, end_id, &industry)
+    let saved = facade.save_industry_quota_rule(&rule)?;
+    info!("📏 行业配额规则已保存: #{:?} {} 上限 {}", saved.id, saved.industry, saved.max_numbers);
+    Ok(saved)
 }
 
 #[tauri::command]
-async fn mark_as_not_imported(
-    app_handle: tauri::AppHandle,
-    number_ids: Vec<i64>,
-) -> Result<i64, String> {
+async fn delete_industry_quota_rule(app_handle: tauri::AppHandle, id: i64) -> AppResult<bool> {
+    crate::core::plugin_isolation::guard("contacts", delete_industry_quota_rule_impl(app_handle, id)).await
+}
+
+async fn delete_industry_quota_rule_impl(app_handle: tauri::AppHandle, id: i64) -> AppResult<bool> {
     let facade = ContactStorageFacade::new(&app_handle);
-    facade.mark_numbers_as_not_imported_by_ids(&number_ids)
+    Ok(facade.delete_industry_quota_rule(id)?)
 }
 
+/// 预检：把这些号码建批次给设备会被哪些配额规则拦截
 #[tauri::command]
-async fn delete_numbers(
+async fn check_industry_quota(
+    app_handle: tauri::AppHandle,
+    device_id: String,
+    number_ids: Vec<i64>,
+) -> AppResult<Vec<QuotaViolation>> {
+    crate::core::plugin_isolation::guard("contacts", check_industry_quota_impl(app_handle, device_id, number_ids)).await
+}
+
+async fn check_industry_quota_impl(
     app_handle: tauri::AppHandle,
+    device_id: String,
     number_ids: Vec<i64>,
-) -> Result<i64, String> {
+) -> AppResult<Vec<QuotaViolation>> {
     let facade = ContactStorageFacade::new(&app_handle);
-    facade.delete_numbers_by_ids(&number_ids)
+    Ok(facade.check_industry_quota(&device_id, &number_ids)?)
 }
 
 // ==================== Import Records ====================
@@ -249,11 +883,19 @@ async fn list_import_records(
     app_handle: tauri::AppHandle,
     limit: Option<i64>, 
     offset: Option<i64>
-) -> Result<models::TxtImportRecordList, String> {
+) -> AppResult<models::TxtImportRecordList> {
+    crate::core::plugin_isolation::guard("contacts", list_import_records_impl(app_handle, limit, offset)).await
+}
+
+async fn list_import_records_impl(
+    app_handle: tauri::AppHandle,
+    limit: Option<i64>, 
+    offset: Option<i64>
+) -> AppResult<models::TxtImportRecordList> {
     let limit = limit.unwrap_or(50);
     let offset = offset.unwrap_or(0);
     let facade = ContactStorageFacade::new(&app_handle);
-    facade.list_txt_import_records(limit, offset, None)
+    Ok(facade.list_txt_import_records(limit, offset, None)?)
 }
 
 #[tauri::command]
@@ -261,7 +903,15 @@ async fn delete_import_record(
     app_handle: tauri::AppHandle,
     record_id: i64, 
     archive_numbers: Option<bool>
-) -> Result<models::DeleteTxtImportRecordResult, String> {
+) -> AppResult<models::DeleteTxtImportRecordResult> {
+    crate::core::plugin_isolation::guard("contacts", delete_import_record_impl(app_handle, record_id, archive_numbers)).await
+}
+
+async fn delete_import_record_impl(
+    app_handle: tauri::AppHandle,
+    record_id: i64, 
+    archive_numbers: Option<bool>
+) -> AppResult<models::DeleteTxtImportRecordResult> {
     let archive = archive_numbers.unwrap_or(false);
     let facade = ContactStorageFacade::new(&app_handle);
     let affected_rows = facade.delete_txt_import_record(record_id, archive)?;
@@ -277,9 +927,15 @@ async fn delete_import_record(
 #[tauri::command]
 async fn get_imported_files(
     app_handle: tauri::AppHandle,
-) -> Result<Vec<models::FileInfoDto>, String> {
+) -> AppResult<Vec<models::FileInfoDto>> {
+    crate::core::plugin_isolation::guard("contacts", get_imported_files_impl(app_handle)).await
+}
+
+async fn get_imported_files_impl(
+    app_handle: tauri::AppHandle,
+) -> AppResult<Vec<models::FileInfoDto>> {
     let facade = ContactStorageFacade::new(&app_handle);
-    facade.get_imported_file_list()
+    Ok(facade.get_imported_file_list()?)
 }
 
 #[tauri::command]
@@ -287,28 +943,50 @@ async fn get_numbers_by_files(
     app_handle: tauri::AppHandle,
     file_paths: Vec<String>,
     only_available: Option<bool>,
-) -> Result<Vec<models::ContactNumberDto>, String> {
+) -> AppResult<Vec<models::ContactNumberDto>> {
+    crate::core::plugin_isolation::guard("contacts", get_numbers_by_files_impl(app_handle, file_paths, only_available)).await
+}
+
+async fn get_numbers_by_files_impl(
+    app_handle: tauri::AppHandle,
+    file_paths: Vec<String>,
+    only_available: Option<bool>,
+) -> AppResult<Vec<models::ContactNumberDto>> {
     let only_available_value = only_available.unwrap_or(true);
     let facade = ContactStorageFacade::new(&app_handle);
-    facade.get_numbers_by_files(&file_paths, only_available_value)
+    Ok(facade.get_numbers_by_files(&file_paths, only_available_value)?)
 }
 
 #[tauri::command]
 async fn check_file_imported(
     app_handle: tauri::AppHandle,
     file_path: String,
-) -> Result<bool, String> {
+) -> AppResult<bool> {
+    crate::core::plugin_isolation::guard("contacts", check_file_imported_impl(app_handle, file_path)).await
+}
+
+async fn check_file_imported_impl(
+    app_handle: tauri::AppHandle,
+    file_path: String,
+) -> AppResult<bool> {
     let facade = ContactStorageFacade::new(&app_handle);
-    facade.check_file_imported(&file_path)
+    Ok(facade.check_file_imported(&file_path)?)
 }
 
 #[tauri::command]
 async fn get_file_stats(
     app_handle: tauri::AppHandle,
     file_path: String,
-) -> Result<Option<models::FileInfoDto>, String> {
+) -> AppResult<Option<models::FileInfoDto>> {
+    crate::core::plugin_isolation::guard("contacts", get_file_stats_impl(app_handle, file_path)).await
+}
+
+async fn get_file_stats_impl(
+    app_handle: tauri::AppHandle,
+    file_path: String,
+) -> AppResult<Option<models::FileInfoDto>> {
     let facade = ContactStorageFacade::new(&app_handle);
-    facade.get_file_stats(&file_path)
+    Ok(facade.get_file_stats(&file_path)?)
 }
 
 // ==================== VCF Batches ====================
@@ -318,22 +996,79 @@ async fn list_batches(
     app_handle: tauri::AppHandle,
     limit: i64,
     offset: i64,
-) -> Result<models::VcfBatchList, String> {
+) -> AppResult<models::VcfBatchList> {
+    crate::core::plugin_isolation::guard("contacts", list_batches_impl(app_handle, limit, offset)).await
+}
+
+async fn list_batches_impl(
+    app_handle: tauri::AppHandle,
+    limit: i64,
+    offset: i64,
+) -> AppResult<models::VcfBatchList> {
     let facade = ContactStorageFacade::new(&app_handle);
-    facade.list_vcf_batches(limit, offset)
+    Ok(facade.list_vcf_batches(limit, offset)?)
 }
 
 #[tauri::command]
 async fn create_batch_with_numbers(
     app_handle: tauri::AppHandle,
     batch_name: String,
-    source_type: String,
-    generation_method: String,
+    _source_type: String,
+    _generation_method: String,
     _description: Option<String>,
     number_ids: Vec<i64>,
-) -> Result<models::VcfBatchCreationResult, String> {
+    device_id: Option<String>,
+) -> AppResult<models::VcfBatchCreationResult> {
+    crate::core::plugin_isolation::guard("contacts", create_batch_with_numbers_impl(app_handle, batch_name, _source_type, _generation_method, _description, number_ids, device_id)).await
+}
+
+async fn create_batch_with_numbers_impl(
+    app_handle: tauri::AppHandle,
+    batch_name: String,
+    _source_type: String,
+    _generation_method: String,
+    _description: Option<String>,
+    number_ids: Vec<i64>,
+    device_id: Option<String>,
+) -> AppResult<models::VcfBatchCreationResult> {
     let facade = ContactStorageFacade::new(&app_handle);
-    facade.create_vcf_batch_with_numbers(&batch_name, number_ids.len() as i64, &source_type, &generation_method)
+    Ok(facade.create_vcf_batch_with_numbers(&batch_name, &number_ids, device_id.as_deref())?)
+}
+
+/// 按选中顺序把号码导出为 VCF：支持分片（如每 500 个一个文件）、名称模板与 vCard 2.1/3.0/4.0，
+/// 每个分片文件单独记为一条 VCF 批次
+#[tauri::command]
+async fn export_numbers_to_vcf(
+    app_handle: tauri::AppHandle,
+    number_ids: Vec<i64>,
+    output_path: String,
+    options: Option<VcfExportOptions>,
+) -> AppResult<VcfChunkExportResult> {
+    crate::core::plugin_isolation::guard("contacts", export_numbers_to_vcf_impl(app_handle, number_ids, output_path, options)).await
+}
+
+async fn export_numbers_to_vcf_impl(
+    app_handle: tauri::AppHandle,
+    number_ids: Vec<i64>,
+    output_path: String,
+    options: Option<VcfExportOptions>,
+) -> AppResult<VcfChunkExportResult> {
+    if number_ids.is_empty() {
+        return Err(AppError::invalid_argument("未选择号码"));
+    }
+    let options = options.unwrap_or_default();
+    tokio::task::spawn_blocking(move || {
+        let result = ContactStorageFacade::new(&app_handle).export_numbers_to_vcf(&number_ids, &output_path, &options)?;
+        info!(
+            "📇 VCF分片导出: {} 个号码 → {} 个文件 (vCard {})",
+            result.total_contacts,
+            result.chunks.len(),
+            result.version.as_str()
+        );
+        Ok(result)
+    })
+    .await
+    .map_err(|e| AppError::internal(format!("导出任务异常: {}", e)))?
 }
 
 #[tauri::command]
@@ -341,18 +1076,33 @@ async fn tag_numbers_industry_by_vcf_batch(
     app_handle: tauri::AppHandle,
     batch_id: String,
     industry: String,
-) -> Result<i64, String> {
+) -> AppResult<i64> {
+    crate::core::plugin_isolation::guard("contacts", tag_numbers_industry_by_vcf_batch_impl(app_handle, batch_id, industry)).await
+}
+
+async fn tag_numbers_industry_by_vcf_batch_impl(
+    app_handle: tauri::AppHandle,
+    batch_id: String,
+    industry: String,
+) -> AppResult<i64> {
     let facade = ContactStorageFacade::new(&app_handle);
-    facade.tag_numbers_industry_by_vcf_batch(&batch_id, &industry)
+    Ok(facade.tag_numbers_industry_by_vcf_batch(&batch_id, &industry)?)
 }
 
 #[tauri::command]
 pub async fn fetch_contact_numbers(
     app_handle: tauri::AppHandle,
     count: i64,
-) -> Result<Vec<models::ContactNumberDto>, String> {
+) -> AppResult<Vec<models::ContactNumberDto>> {
+    crate::core::plugin_isolation::guard("contacts", fetch_contact_numbers_impl(app_handle, count)).await
+}
+
+async fn fetch_contact_numbers_impl(
+    app_handle: tauri::AppHandle,
+    count: i64,
+) -> AppResult<Vec<models::ContactNumberDto>> {
     let facade = ContactStorageFacade::new(&app_handle);
-    facade.fetch_numbers(count)
+    Ok(facade.fetch_numbers(count)?)
 }
 
 #[tauri::command]
@@ -360,9 +1110,17 @@ pub async fn fetch_unclassified_contact_numbers(
     app_handle: tauri::AppHandle,
     count: i64,
     _only_unconsumed: bool,
-) -> Result<Vec<models::ContactNumberDto>, String> {
+) -> AppResult<Vec<models::ContactNumberDto>> {
+    crate::core::plugin_isolation::guard("contacts", fetch_unclassified_contact_numbers_impl(app_handle, count, _only_unconsumed)).await
+}
+
+async fn fetch_unclassified_contact_numbers_impl(
+    app_handle: tauri::AppHandle,
+    count: i64,
+    _only_unconsumed: bool,
+) -> AppResult<Vec<models::ContactNumberDto>> {
     let facade = ContactStorageFacade::new(&app_handle);
-    facade.fetch_unclassified_numbers(count, "")
+    Ok(facade.fetch_unclassified_numbers(count, "")?)
 }
 
 #[tauri::command]
@@ -370,9 +1128,17 @@ pub async fn fetch_contact_numbers_by_id_range(
     app_handle: tauri::AppHandle,
     start_id: i64,
     end_id: i64,
-) -> Result<Vec<models::ContactNumberDto>, String> {
+) -> AppResult<Vec<models::ContactNumberDto>> {
+    crate::core::plugin_isolation::guard("contacts", fetch_contact_numbers_by_id_range_impl(app_handle, start_id, end_id)).await
+}
+
+async fn fetch_contact_numbers_by_id_range_impl(
+    app_handle: tauri::AppHandle,
+    start_id: i64,
+    end_id: i64,
+) -> AppResult<Vec<models::ContactNumberDto>> {
     let facade = ContactStorageFacade::new(&app_handle);
-    facade.fetch_numbers_by_id_range(start_id, end_id)
+    Ok(facade.fetch_numbers_by_id_range(start_id, end_id)?)
 }
 
 #[tauri::command]
@@ -380,9 +1146,17 @@ pub async fn fetch_contact_numbers_by_id_range_unconsumed(
     app_handle: tauri::AppHandle,
     start_id: i64,
     end_id: i64,
-) -> Result<Vec<models::ContactNumberDto>, String> {
+) -> AppResult<Vec<models::ContactNumberDto>> {
+    crate::core::plugin_isolation::guard("contacts", fetch_contact_numbers_by_id_range_unconsumed_impl(app_handle, start_id, end_id)).await
+}
+
+async fn fetch_contact_numbers_by_id_range_unconsumed_impl(
+    app_handle: tauri::AppHandle,
+    start_id: i64,
+    end_id: i64,
+) -> AppResult<Vec<models::ContactNumberDto>> {
     let facade = ContactStorageFacade::new(&app_handle);
-    facade.fetch_numbers_by_id_range_unconsumed(start_id, end_id)
+    Ok(facade.fetch_numbers_by_id_range_unconsumed(start_id, end_id)?)
 }
 
 #[tauri::command]
@@ -391,9 +1165,18 @@ pub async fn mark_contact_numbers_used_by_id_range(
     start_id: i64,
     end_id: i64,
     batch_id: String,
-) -> Result<i64, String> {
+) -> AppResult<i64> {
+    crate::core::plugin_isolation::guard("contacts", mark_contact_numbers_used_by_id_range_impl(app_handle, start_id, end_id, batch_id)).await
+}
+
+async fn mark_contact_numbers_used_by_id_range_impl(
+    app_handle: tauri::AppHandle,
+    start_id: i64,
+    end_id: i64,
+    batch_id: String,
+) -> AppResult<i64> {
     let facade = ContactStorageFacade::new(&app_handle);
-    facade.mark_numbers_used_by_id_range(start_id, end_id, &batch_id)
+    Ok(facade.mark_numbers_used_by_id_range(start_id, end_id, &batch_id)?)
 }
 
 // ==================== Device Contact Metrics ====================
@@ -475,13 +1258,21 @@ async fn try_query_contact_count(device_id: &str) -> Result<i32, String> {
 pub async fn get_device_contact_count(
     device_id: Option<String>,
     deviceId: Option<String>,
-) -> Result<i32, String> {
+) -> AppResult<i32> {
+    crate::core::plugin_isolation::guard("contacts", get_device_contact_count_impl(device_id, deviceId)).await
+}
+
+#[allow(non_snake_case)]
+async fn get_device_contact_count_impl(
+    device_id: Option<String>,
+    deviceId: Option<String>,
+) -> AppResult<i32> {
     let id = match (device_id.clone(), deviceId.clone()) {
         (Some(id), _) => id,
         (None, Some(id)) => id,
         (None, None) => {
             warn!("❌ get_device_contact_count 缺少参数: device_id/deviceId 皆为 None");
-            return Err("缺少参数：device_id / deviceId".to_string());
+            return Err(AppError::invalid_argument("缺少参数：device_id / deviceId"));
         },
     };
 
@@ -494,143 +1285,32 @@ pub async fn get_device_contact_count(
         },
         Err(e) => {
             warn!("❌ 设备 {} 联系人查询失败: {}", id, e);
-            Err(e)
+            Err(AppError::new(ErrorCode::AdbCommandFailed, e))
         }
     }
 }
 
 // ==================== Contact Verification ====================
 
-/// 验证结果
-#[derive(Debug, Serialize, Deserialize, Clone)]
-#[serde(rename_all = "camelCase")]
-pub struct VerificationResult {
-    pub success: bool,
-    pub total_expected: i32,
-    pub sampled_count: i32,
-    pub found_count: i32,
-    pub success_rate: f64,
-    pub estimated_imported: i32,
-    pub method: String,
-    pub verified_phones: Vec<String>,
-}
-
-/// 🎯 智能选择验证样本
-fn select_verification_samples(phones: &[String]) -> Vec<String> {
-    if phones.len() <= 5 {
-        // 少于5个，全部验证
-        return phones.to_vec();
-    }
-    
-    // 字典序排序（130开头 > 135开头 > 138开头...）
-    let mut sorted = phones.to_vec();
-    sorted.sort();
-    
-    // 取前5个（最容易在联系人列表首页找到）
-    sorted.into_iter().take(5).collect()
-}
-
-/// 🚀 快速检查号码是否存在
-async fn check_contact_exists_fast(device_id: &str, phone: &str) -> Result<bool, String> {
-    // 规范化号码（去除空格、横线等）
-    let normalized = phone.replace(&[' ', '-', '(', ')', '+'][..], "");
-    
-    // 构建ADB查询命令
-    let adb_path = crate::utils::adb_utils::get_adb_path();
-    
-    let query = format!(
-        "content query --uri content://com.android.contacts/data \
-         --projection data1 \
-         --where \"mimetype='vnd.android.cursor.item/phone_v2' AND data1 LIKE '%{}%'\"",
-        normalized
-    );
-    
-    // 执行ADB命令
-    let output = Command::new(adb_path)
-        .args(&["-s", device_id, "shell", &query])
-        .output()
-        .map_err(|e| format!("执行ADB命令失败: {}", e))?;
-    
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let stderr = String::from_utf8_lossy(&output.stderr);
-    
-    // 检查是否找到号码
-    let found = stdout.contains("data1=") || stdout.contains(&normalized);
-    
-    if !stderr.is_empty() && !stderr.contains("Row:") {
-        warn!("ADB查询警告: {}", stderr);
-    }
-    
-    Ok(found)
-}
-
-/// 🚀 快速验证联系人（Tauri命令）
+/// 🚀 批量验证号码是否已导入设备通讯录，进度通过 `contact-verify-progress` 事件推送
 #[tauri::command]
 pub async fn verify_contacts_fast(
+    app_handle: tauri::AppHandle,
     device_id: String,
     phone_numbers: Vec<String>,
-) -> Result<VerificationResult, String> {
-    info!("🔍 开始快速验证 {} 个号码", phone_numbers.len());
-    
-    if phone_numbers.is_empty() {
-        return Ok(VerificationResult {
-            success: false,
-            total_expected: 0,
-            sampled_count: 0,
-            found_count: 0,
-            success_rate: 0.0,
-            estimated_imported: 0,
-            method: "empty_input".to_string(),
-            verified_phones: vec![],
-        });
-    }
-    
-    // 步骤1: 智能选择样本
-    let samples = select_verification_samples(&phone_numbers);
-    info!("📊 从 {} 个中选择 {} 个样本进行验证", phone_numbers.len(), samples.len());
-    
-    // 步骤2: 快速检查每个样本
-    let mut found_count = 0;
-    for phone in &samples {
-        match check_contact_exists_fast(&device_id, phone).await {
-            Ok(true) => {
-                found_count += 1;
-                info!("✅ 找到号码: {}", phone);
-            }
-            Ok(false) => {
-                info!("❌ 未找到号码: {}", phone);
-            }
-            Err(e) => {
-                warn!("⚠️ 检查号码失败 {}: {}", phone, e);
-            }
-        }
-    }
-    
-    // 步骤3: 计算结果
-    let success_rate = found_count as f64 / samples.len() as f64;
-    let estimated_imported = (phone_numbers.len() as f64 * success_rate) as i32;
-    
-    let method = match found_count {
-        n if n == samples.len() => "fast_sample_all_success".to_string(),
-        0 => "fast_sample_all_failed".to_string(),
-        _ => "fast_sample_partial".to_string(),
-    };
-    
-    info!(
-        "📊 验证完成: {}/{} 成功, 成功率: {:.1}%, 推断导入: {} 个",
-        found_count, samples.len(), success_rate * 100.0, estimated_imported
-    );
-    
-    Ok(VerificationResult {
-        success: found_count > 0,
-        total_expected: phone_numbers.len() as i32,
-        sampled_count: samples.len() as i32,
-        found_count: found_count as i32,
-        success_rate,
-        estimated_imported,
-        method,
-        verified_phones: samples,
+) -> AppResult<VerificationResult> {
+    crate::core::plugin_isolation::guard("contacts", verify_contacts_fast_impl(app_handle, device_id, phone_numbers)).await
+}
+
+async fn verify_contacts_fast_impl(
+    app_handle: tauri::AppHandle,
+    device_id: String,
+    phone_numbers: Vec<String>,
+) -> AppResult<VerificationResult> {
+    Ok(contact_verification::verify_contacts_fast(device_id, phone_numbers, move |progress| {
+        let _ = app_handle.emit(VERIFY_PROGRESS_EVENT, progress);
     })
+    .await?)
 }
 
 // ==================== Smart VCF Opener ====================
@@ -919,7 +1599,11 @@ async fn analyze_and_act(device_id: &str, ui_content: &str) -> Result<ActionResu
 /// 基于实时UI状态的智能VCF打开器
 /// 根据当前屏幕内容自动执行正确的操作
 #[tauri::command]
-pub async fn smart_vcf_opener(device_id: String) -> Result<VcfOpenResult, String> {
+pub async fn smart_vcf_opener(device_id: String) -> AppResult<VcfOpenResult> {
+    crate::core::plugin_isolation::guard("contacts", smart_vcf_opener_impl(device_id)).await
+}
+
+async fn smart_vcf_opener_impl(device_id: String) -> AppResult<VcfOpenResult> {
     println!("🤖 启动智能VCF打开器，设备: {}", device_id);
     
     let mut steps_completed = Vec::new();
@@ -965,76 +1649,289 @@ pub async fn smart_vcf_opener(device_id: String) -> Result<VcfOpenResult, String
         tokio::time::sleep(tokio::time::Duration::from_millis(2000)).await;
     }
     
-    Err(format!("超过最大尝试次数 ({})，操作未完成", MAX_ATTEMPTS))
+    Err(AppError::new(ErrorCode::Timeout, format!("超过最大尝试次数 ({})，操作未完成", MAX_ATTEMPTS)))
 }
 
 #[tauri::command]
 async fn import_vcf_contacts_multi_brand(
     device_id: String,
     contacts_file_path: String,
-) -> Result<MultiBrandImportResult, String> {
-    let mut importer = MultiBrandVcfImporter::new(device_id);
-    importer.import_vcf_contacts_multi_brand(&contacts_file_path).await
-        .map_err(|e| e.to_string())
+) -> AppResult<MultiBrandImportResult> {
+    crate::core::plugin_isolation::guard("contacts", import_vcf_contacts_multi_brand_impl(device_id, contacts_file_path)).await
+}
+
+async fn import_vcf_contacts_multi_brand_impl(
+    device_id: String,
+    contacts_file_path: String,
+) -> AppResult<MultiBrandImportResult> {
+    let mut importer = MultiBrandVcfImporter::new(device_id.clone());
+    let result = importer.import_vcf_contacts_multi_brand(&contacts_file_path).await
+        .map_err(|e| e.to_string())?;
+
+    if result.imported_contacts > 0 {
+        crate::services::billing_journal::record_billable(crate::services::billing_journal::BillableAction {
+            action: "import".to_string(),
+            device_id: Some(device_id),
+            target: Some(contacts_file_path),
+            quantity: Some(result.imported_contacts as u64),
+            ..Default::default()
+        });
+    }
+    Ok(result)
 }
 
 #[tauri::command]
-async fn delete_contact_document(_document_id: String) -> Result<(), String> {
-    Ok(())
+async fn delete_contact_document(_document_id: String) -> AppResult<()> {
+    crate::core::plugin_isolation::guard("contacts", async move { Ok(()) }).await
 }
 
 #[tauri::command]
-async fn update_contact(_contact: serde_json::Value) -> Result<(), String> {
-    Ok(())
+async fn update_contact(_contact: serde_json::Value) -> AppResult<()> {
+    crate::core::plugin_isolation::guard("contacts", async move { Ok(()) }).await
 }
 
 #[tauri::command]
-async fn delete_contact(_contact_id: String) -> Result<(), String> {
-    Ok(())
+async fn delete_contact(_contact_id: String) -> AppResult<()> {
+    crate::core::plugin_isolation::guard("contacts", async move { Ok(()) }).await
 }
 
 #[tauri::command]
-async fn delete_contacts(_contact_ids: Vec<String>) -> Result<(), String> {
-    Ok(())
+async fn delete_contacts(_contact_ids: Vec<String>) -> AppResult<()> {
+    crate::core::plugin_isolation::guard("contacts", async move { Ok(()) }).await
 }
 
 #[tauri::command]
-async fn start_contact_task(_task_id: String) -> Result<(), String> {
-    Ok(())
+async fn start_contact_task(_task_id: String) -> AppResult<()> {
+    crate::core::plugin_isolation::guard("contacts", async move { Ok(()) }).await
 }
 
 #[tauri::command]
-async fn pause_contact_task(_task_id: String) -> Result<(), String> {
-    Ok(())
+async fn pause_contact_task(_task_id: String) -> AppResult<()> {
+    crate::core::plugin_isolation::guard("contacts", async move { Ok(()) }).await
 }
 
 #[tauri::command]
-async fn stop_contact_task(_task_id: String) -> Result<(), String> {
-    Ok(())
+async fn stop_contact_task(_task_id: String) -> AppResult<()> {
+    crate::core::plugin_isolation::guard("contacts", async move { Ok(()) }).await
 }
 
 #[tauri::command]
-async fn delete_contact_task(_task_id: String) -> Result<(), String> {
-    Ok(())
+async fn delete_contact_task(_task_id: String) -> AppResult<()> {
+    crate::core::plugin_isolation::guard("contacts", async move { Ok(()) }).await
+}
+
+#[tauri::command]
+async fn create_contact_task(_task: serde_json::Value) -> AppResult<serde_json::Value> {
+    crate::core::plugin_isolation::guard("contacts", async move { Ok(serde_json::json!({})) }).await
+}
+
+#[tauri::command]
+async fn submit_contact_task(_data: serde_json::Value) -> AppResult<serde_json::Value> {
+    crate::core::plugin_isolation::guard("contacts", async move { Ok(serde_json::json!({})) }).await
+}
+
+// ==================== Recipes ====================
+
+#[tauri::command]
+async fn list_recipes() -> AppResult<Vec<Recipe>> {
+    crate::core::plugin_isolation::guard("contacts", async move { Ok(RecipeStore::global().lock().map_err(|e| e.to_string())?.list()) }).await
+}
+
+/// 校验配方（不保存），返回逐阶段问题
+#[tauri::command]
+async fn validate_recipe(recipe: Recipe) -> AppResult<Vec<RecipeIssue>> {
+    crate::core::plugin_isolation::guard("contacts", async move { Ok(recipes::validate(&recipe)) }).await
+}
+
+/// 新建（`id` 为空）或更新配方；校验不通过时拒绝
+#[tauri::command]
+async fn save_recipe(recipe: Recipe) -> AppResult<Recipe> {
+    crate::core::plugin_isolation::guard("contacts", async move { Ok(RecipeStore::global().lock().map_err(|e| e.to_string())?.save_recipe(recipe)?) }).await
+}
+
+#[tauri::command]
+async fn delete_recipe(recipe_id: String) -> AppResult<bool> {
+    crate::core::plugin_isolation::guard("contacts", async move { Ok(RecipeStore::global().lock().map_err(|e| e.to_string())?.delete_recipe(&recipe_id)?) }).await
+}
+
+/// 一键执行：入队后由配方工作协程逐阶段执行
+#[tauri::command]
+async fn run_recipe(recipe_id: String) -> AppResult<RecipeRun> {
+    crate::core::plugin_isolation::guard("contacts", async move { Ok(recipes::enqueue(&recipe_id, RunTrigger::Manual)?) }).await
+}
+
+/// 从失败 / 取消的阶段续跑
+#[tauri::command]
+async fn resume_recipe_run(run_id: String) -> AppResult<RecipeRun> {
+    crate::core::plugin_isolation::guard("contacts", async move { Ok(recipe_runner::resume(&run_id)?) }).await
+}
+
+#[tauri::command]
+async fn cancel_recipe_run(run_id: String) -> AppResult<RecipeRun> {
+    crate::core::plugin_isolation::guard("contacts", async move { Ok(recipe_runner::cancel(&run_id)?) }).await
+}
+
+/// 逆序回滚失败 / 取消的执行，返回附带回滚报告的执行记录
+#[tauri::command]
+async fn rollback_recipe_run(app_handle: tauri::AppHandle, run_id: String) -> AppResult<RecipeRun> {
+    crate::core::plugin_isolation::guard("contacts", rollback_recipe_run_impl(app_handle, run_id)).await
+}
+
+async fn rollback_recipe_run_impl(app_handle: tauri::AppHandle, run_id: String) -> AppResult<RecipeRun> {
+    let executor = recipe_executor::ContactRecipeExecutor::new(app_handle);
+    Ok(recipes::rollback_run(&executor, &run_id).await?)
+}
+
+#[tauri::command]
+async fn list_recipe_runs(recipe_id: Option<String>) -> AppResult<Vec<RecipeRun>> {
+    crate::core::plugin_isolation::guard("contacts", async move { Ok(RecipeStore::global().lock().map_err(|e| e.to_string())?.runs(recipe_id.as_deref())) }).await
+}
+
+// ==================== 监听来源自动导入 ====================
+
+/// 监听来源及运行状态
+#[tauri::command]
+async fn list_contact_watch_sources() -> AppResult<ContactWatchStatus> {
+    crate::core::plugin_isolation::guard("contacts", async move { Ok(contact_watch::status()?) }).await
+}
+
+/// 新增或更新监听来源，保存后立即重建监听并补扫目录
+#[tauri::command]
+async fn save_contact_watch_source(source: WatchSource) -> AppResult<WatchSource> {
+    crate::core::plugin_isolation::guard("contacts", save_contact_watch_source_impl(source)).await
+}
+
+async fn save_contact_watch_source_impl(source: WatchSource) -> AppResult<WatchSource> {
+    let saved = ContactWatchStore::global().lock().map_err(|e| e.to_string())?.save_source(source)?;
+    contact_watch::reload();
+    Ok(saved)
+}
+
+#[tauri::command]
+async fn remove_contact_watch_source(source_id: String) -> AppResult<bool> {
+    crate::core::plugin_isolation::guard("contacts", remove_contact_watch_source_impl(source_id)).await
+}
+
+async fn remove_contact_watch_source_impl(source_id: String) -> AppResult<bool> {
+    let removed = ContactWatchStore::global().lock().map_err(|e| e.to_string())?.remove_source(&source_id)?;
+    contact_watch::reload();
+    Ok(removed)
+}
+
+/// 暂停自动导入；不传 `source_id` 时暂停全部来源
+#[tauri::command]
+async fn pause_contact_watch(source_id: Option<String>) -> AppResult<ContactWatchStatus> {
+    crate::core::plugin_isolation::guard("contacts", pause_contact_watch_impl(source_id)).await
+}
+
+async fn pause_contact_watch_impl(source_id: Option<String>) -> AppResult<ContactWatchStatus> {
+    ContactWatchStore::global().lock().map_err(|e| e.to_string())?.set_paused(source_id.as_deref(), true)?;
+    contact_watch::reload();
+    Ok(contact_watch::status()?)
+}
+
+/// 恢复自动导入，暂停期间放入目录的文件会被补扫导入
+#[tauri::command]
+async fn resume_contact_watch(source_id: Option<String>) -> AppResult<ContactWatchStatus> {
+    crate::core::plugin_isolation::guard("contacts", resume_contact_watch_impl(source_id)).await
+}
+
+async fn resume_contact_watch_impl(source_id: Option<String>) -> AppResult<ContactWatchStatus> {
+    ContactWatchStore::global().lock().map_err(|e| e.to_string())?.set_paused(source_id.as_deref(), false)?;
+    contact_watch::reload();
+    Ok(contact_watch::status()?)
+}
+
+/// 监听来源的逐文件导入记录
+#[tauri::command]
+async fn get_contact_watch_history(
+    app_handle: tauri::AppHandle,
+    source_id: String,
+) -> AppResult<Option<FolderImportSessionStateDto>> {
+    crate::core::plugin_isolation::guard("contacts", get_contact_watch_history_impl(app_handle, source_id)).await
+}
+
+async fn get_contact_watch_history_impl(
+    app_handle: tauri::AppHandle,
+    source_id: String,
+) -> AppResult<Option<FolderImportSessionStateDto>> {
+    let source = ContactWatchStore::global()
+        .lock()
+        .map_err(|e| e.to_string())?
+        .sources()
+        .into_iter()
+        .find(|s| s.id == source_id)
+        .ok_or_else(|| format!("监听来源不存在: {}", source_id))?;
+    let facade = ContactStorageFacade::new(&app_handle);
+    Ok(facade.get_folder_import_session(&source.session_key())?.map(Into::into))
+}
+
+// ==================== 微信注册核验 ====================
+
+/// 在后台开始核验设备上已导入号码是否注册微信，进度通过 `contacts://wechat-verification` 事件推送
+#[tauri::command]
+async fn start_wechat_verification(
+    app_handle: tauri::AppHandle,
+    device_id: String,
+    options: Option<WechatVerificationOptions>,
+) -> AppResult<VerificationProgress> {
+    crate::core::plugin_isolation::guard("contacts", async move { Ok(wechat_checker::spawn_verification(app_handle, device_id, options.unwrap_or_default())?) }).await
+}
+
+#[tauri::command]
+async fn stop_wechat_verification(device_id: String) -> AppResult<bool> {
+    crate::core::plugin_isolation::guard("contacts", async move { Ok(wechat_verification::stop(&device_id)) }).await
 }
 
+/// 各设备最近一次核验的进度
 #[tauri::command]
-async fn create_contact_task(_task: serde_json::Value) -> Result<serde_json::Value, String> {
-    Ok(serde_json::json!({}))
+async fn get_wechat_verification_status() -> AppResult<Vec<VerificationProgress>> {
+    crate::core::plugin_isolation::guard("contacts", async move { Ok(wechat_verification::status()) }).await
 }
 
+/// 已导入号码的核验结论汇总（registered / not_registered / abnormal / pending）
 #[tauri::command]
-async fn submit_contact_task(_data: serde_json::Value) -> Result<serde_json::Value, String> {
-    Ok(serde_json::json!({}))
+async fn get_wechat_check_summary(
+    app_handle: tauri::AppHandle,
+    device_id: Option<String>,
+) -> AppResult<std::collections::BTreeMap<String, i64>> {
+    crate::core::plugin_isolation::guard("contacts", get_wechat_check_summary_impl(app_handle, device_id)).await
+}
+
+async fn get_wechat_check_summary_impl(
+    app_handle: tauri::AppHandle,
+    device_id: Option<String>,
+) -> AppResult<std::collections::BTreeMap<String, i64>> {
+    let facade = ContactStorageFacade::new(&app_handle);
+    Ok(facade.summarize_wechat_checks(device_id.as_deref())?)
+}
+
+/// 启动监听来源的文件夹监听（在应用 setup 中调用）
+pub fn start_contact_watcher(app: &tauri::AppHandle) {
+    let importer = Arc::new(watch_importer::ContactWatchFileImporter::new(app.clone()));
+    tauri::async_runtime::spawn(contact_watch::run(importer));
+}
+
+/// 启动配方执行队列与定时器（在应用 setup 中调用）
+pub fn start_recipe_runner(app: &tauri::AppHandle) {
+    let executor = Arc::new(recipe_executor::ContactRecipeExecutor::new(app.clone()));
+    tauri::async_runtime::spawn(recipes::run_worker(executor));
+    tauri::async_runtime::spawn(recipes::run_scheduler());
 }
 
 pub fn init() -> TauriPlugin<tauri::Wry> {
     Builder::new("contacts")
-        .invoke_handler(tauri::generate_handler![
+        .invoke_handler(crate::core::plugin_isolation::isolate("contacts", tauri::generate_handler![
             import_vcf_contacts_multi_brand,
             import_file,
             import_folder,
+            get_folder_import_session,
+            preview_contact_file,
+            preview_xlsx_columns,
+            import_xlsx_file,
+            import_vcf_file,
             list,
+            export_contact_numbers,
             list_without_batch,
             list_by_batch,
             list_for_vcf_batch,
@@ -1043,6 +1940,36 @@ pub fn init() -> TauriPlugin<tauri::Wry> {
             set_industry_by_id_range,
             mark_as_not_imported,
             delete_numbers,
+            preview_destructive_operation,
+            execute_destructive_operation,
+            dedup_contact_numbers,
+            find_duplicate_numbers,
+            merge_duplicates,
+            plan_number_allocation,
+            execute_number_allocation,
+            list_recipes,
+            validate_recipe,
+            save_recipe,
+            delete_recipe,
+            run_recipe,
+            resume_recipe_run,
+            cancel_recipe_run,
+            rollback_recipe_run,
+            list_recipe_runs,
+            list_contact_watch_sources,
+            save_contact_watch_source,
+            remove_contact_watch_source,
+            pause_contact_watch,
+            resume_contact_watch,
+            get_contact_watch_history,
+            start_wechat_verification,
+            stop_wechat_verification,
+            get_wechat_verification_status,
+            get_wechat_check_summary,
+            list_industry_quota_rules,
+            save_industry_quota_rule,
+            delete_industry_quota_rule,
+            check_industry_quota,
             list_import_records,
             delete_import_record,
             get_imported_files,
@@ -1051,6 +1978,7 @@ pub fn init() -> TauriPlugin<tauri::Wry> {
             get_file_stats,
             list_batches,
             create_batch_with_numbers,
+            export_numbers_to_vcf,
             tag_numbers_industry_by_vcf_batch,
             fetch_contact_numbers,
             fetch_unclassified_contact_numbers,
@@ -1070,6 +1998,6 @@ pub fn init() -> TauriPlugin<tauri::Wry> {
             delete_contact_task,
             create_contact_task,
             submit_contact_task
-        ])
+        ]))
         .build()
 }
@@ -0,0 +1,296 @@
+// src-tauri/src/modules/contacts/recipe_executor.rs
+// module: contacts | layer: tauri-plugin | role: 配方阶段执行器
+// summary: 用联系人插件现有能力执行配方各阶段（文件夹导入 / 分配方案 / 批次 VCF / 多品牌导入 / 关注任务），失败时返回已完成的设备以便续跑跳过；回滚时释放分配批次、删除生成的 VCF 与推送到设备的文件
+
+use async_trait::async_trait;
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use tauri::AppHandle;
+
+use crate::services::concurrency::{self, WorkRequest};
+use crate::services::contact_storage::repositories::contact_numbers::allocation_planner::AllocationPlanRequest;
+use crate::services::contact_storage::repository_facade::ContactStorageFacade;
+use crate::services::event_outbox;
+use crate::services::marketing_storage::facade::MarketingStorageFacade;
+use crate::services::marketing_storage::models::{ExecutorMode, TaskPayload, TaskType};
+use crate::services::recipes::{
+    RecipeRun, RecipeStage, RollbackOutcome, StageContext, StageExecutor, StageFailure, RECIPE_RUN_EVENT,
+};
+use crate::utils::adb_utils::execute_adb_command;
+use crate::services::vcf::{generate_vcf_file, Contact};
+
+/// 单个批次读取号码的上限
+const MAX_BATCH_NUMBERS: i64 = 100_000;
+
+/// 多品牌导入可能推送到的固定位置（另有 `/sdcard/Download/<文件名>`）
+const PUSHED_VCF_PATHS: [&str; 4] = [
+    "/sdcard/contacts_import.vcf",
+    "/sdcard/Download/contacts_import.vcf",
+    "/sdcard/Android/data/com.android.contacts/files/contacts_import.vcf",
+    "/storage/emulated/0/Download/contacts_import.vcf",
+];
+
+pub struct ContactRecipeExecutor {
+    app: AppHandle,
+}
+
+impl ContactRecipeExecutor {
+    pub fn new(app: AppHandle) -> Self {
+        Self { app }
+    }
+
+    fn facade(&self) -> ContactStorageFacade {
+        ContactStorageFacade::new(&self.app)
+    }
+
+    fn batch_phones(&self, batch_id: &str) -> Result<Vec<(i64, String, String)>, String> {
+        let list = self.facade().list_numbers_by_batch_filtered(batch_id, MAX_BATCH_NUMBERS, 0, false)?;
+        Ok(list.items.into_iter().map(|n| (n.id, n.name, n.phone)).collect())
+    }
+}
+
+/// 从输出中读取 `字段 → { 设备: 字符串 }`
+fn string_map(output: Option<&Value>, field: &str) -> BTreeMap<String, String> {
+    output
+        .and_then(|o| o.get(field))
+        .and_then(|v| serde_json::from_value(v.clone()).ok())
+        .unwrap_or_default()
+}
+
+fn count_map(output: Option<&Value>, field: &str) -> BTreeMap<String, u64> {
+    output
+        .and_then(|o| o.get(field))
+        .and_then(|v| serde_json::from_value(v.clone()).ok())
+        .unwrap_or_default()
+}
+
+fn allocated_batches(ctx: &StageContext) -> Result<BTreeMap<String, String>, StageFailure> {
+    let batches = string_map(ctx.output_of("allocate_numbers"), "batches");
+    if batches.is_empty() {
+        return Err("分配阶段没有生成任何批次".to_string().into());
+    }
+    Ok(batches)
+}
+
+fn to_value<T: serde::Serialize>(value: &T) -> Result<Value, StageFailure> {
+    serde_json::to_value(value).map_err(|e| StageFailure::from(e.to_string()))
+}
+
+fn failure(message: String, partial: Value) -> StageFailure {
+    StageFailure { message, partial: Some(partial) }
+}
+
+/// 删除推送到设备上的 VCF 文件，返回执行失败的路径
+fn remove_pushed_files(device_id: &str, local_path: &str) -> Vec<String> {
+    let mut targets: Vec<String> = PUSHED_VCF_PATHS.iter().map(|p| p.to_string()).collect();
+    if let Some(name) = std::path::Path::new(local_path).file_name() {
+        targets.push(format!("/sdcard/Download/{}", name.to_string_lossy()));
+    }
+    targets
+        .into_iter()
+        .filter(|target| {
+            !execute_adb_command(&["-s", device_id, "shell", "rm", "-f", target.as_str()])
+                .is_ok_and(|output| output.status.success())
+        })
+        .collect()
+}
+
+#[async_trait]
+impl StageExecutor for ContactRecipeExecutor {
+    async fn execute(&self, stage: &RecipeStage, ctx: &StageContext) -> Result<Value, StageFailure> {
+        match stage {
+            RecipeStage::ImportFolder { folder_path, restart } => {
+                let result = super::import_folder(self.app.clone(), folder_path.clone(), Some(*restart))
+                    .await
+                    .map_err(|e| e.to_string())?;
+                to_value(&result)
+            }
+            RecipeStage::AllocateNumbers { devices, limit, strict_affinity } => {
+                let request = AllocationPlanRequest {
+                    devices: devices.clone(),
+                    number_ids: None,
+                    limit: *limit,
+                    strict_affinity: *strict_affinity,
+                };
+                let facade = self.facade();
+                let plan = facade.plan_allocation(&request)?;
+                if plan.allocations.iter().all(|a| a.number_ids.is_empty()) {
+                    return Err(format!("没有可分配的号码（候选 {} 个）", plan.candidate_count).into());
+                }
+                to_value(&facade.execute_allocation_plan(&plan)?)
+            }
+            RecipeStage::GenerateVcf { output_dir } => {
+                let batches = allocated_batches(ctx)?;
+                let dir = match output_dir {
+                    Some(dir) if !dir.trim().is_empty() => PathBuf::from(dir),
+                    _ => dirs::data_dir()
+                        .unwrap_or_else(|| PathBuf::from("."))
+                        .join("employee-gui")
+                        .join("recipe_vcf")
+                        .join(&ctx.run_id),
+                };
+                std::fs::create_dir_all(&dir).map_err(|e| format!("创建 VCF 目录失败: {}", e))?;
+
+                let mut files = string_map(ctx.partial.as_ref(), "files");
+                for (device_id, batch_id) in &batches {
+                    if files.contains_key(device_id) {
+                        continue;
+                    }
+                    let contacts: Vec<Contact> = self
+                        .batch_phones(batch_id)
+                        .map_err(|e| failure(e, json!({ "files": files })))?
+                        .into_iter()
+                        .map(|(id, name, phone)| Contact {
+                            id: id.to_string(),
+                            name,
+                            phone,
+                            email: String::new(),
+                            address: String::new(),
+                            occupation: String::new(),
+                        })
+                        .collect();
+                    let path = dir.join(format!("{}.vcf", batch_id)).to_string_lossy().to_string();
+                    generate_vcf_file(contacts, &path)
+                        .await
+                        .map_err(|e| failure(format!("生成 {} 的 VCF 失败: {}", device_id, e), json!({ "files": files })))?;
+                    self.facade()
+                        .set_vcf_batch_file_path(batch_id, &path)
+                        .map_err(|e| failure(e, json!({ "files": files })))?;
+                    files.insert(device_id.clone(), path);
+                }
+                Ok(json!({ "files": files }))
+            }
+            RecipeStage::RunImportScript { devices } => {
+                let files = string_map(ctx.output_of("generate_vcf"), "files");
+                let mut imported = count_map(ctx.partial.as_ref(), "imported");
+                for (device_id, path) in files.iter().filter(|(d, _)| devices.is_empty() || devices.contains(*d)) {
+                    if imported.contains_key(device_id) {
+                        continue;
+                    }
+                    let request = WorkRequest::device(device_id, "recipe", format!("导入 {}", path));
+                    let result = concurrency::run(request, super::import_vcf_contacts_multi_brand(device_id.clone(), path.clone()))
+                        .await
+                        .map_err(|e| failure(format!("设备 {} 导入失败: {}", device_id, e), json!({ "imported": imported })))?;
+                    if !result.success {
+                        return Err(failure(
+                            format!("设备 {} 导入失败: {}", device_id, result.message),
+                            json!({ "imported": imported }),
+                        ));
+                    }
+                    imported.insert(device_id.clone(), result.imported_contacts as u64);
+                }
+                Ok(json!({ "imported": imported }))
+            }
+            RecipeStage::StartFollowCampaign { campaign_id, accounts, priority } => {
+                let batches = allocated_batches(ctx)?;
+                let mut created = count_map(ctx.partial.as_ref(), "created");
+                let mut skipped = count_map(ctx.partial.as_ref(), "skipped");
+                for (device_id, batch_id) in &batches {
+                    if created.contains_key(device_id) {
+                        continue;
+                    }
+                    let partial = || json!({ "created": created, "skipped": skipped });
+                    let account_id = accounts
+                        .get(device_id)
+                        .ok_or_else(|| failure(format!("设备 {} 未指定执行账号", device_id), partial()))?;
+                    crate::services::warmup::ensure_ready_for_campaign(account_id).map_err(|e| failure(e, partial()))?;
+                    let numbers = self.batch_phones(batch_id).map_err(|e| failure(e, partial()))?;
+
+                    let (mut ok, mut dup) = (0u64, 0u64);
+                    for (_, _, phone) in numbers {
+                        let task = TaskPayload {
+                            task_type: TaskType::Follow,
+                            comment_id: None,
+                            target_user_id: Some(phone.clone()),
+                            assign_account_id: account_id.clone(),
+                            executor_mode: ExecutorMode::Api,
+                            dedup_key: format!("follow:{}:{}:{}", campaign_id, account_id, phone),
+                            priority: *priority,
+                            deadline_at: None,
+                            campaign_id: Some(campaign_id.clone()),
+                        };
+                        // 重复的查重键视为已建过的任务
+                        match MarketingStorageFacade::insert_task(&self.app, task) {
+                            Ok(_) => ok += 1,
+                            Err(_) => dup += 1,
+                        }
+                    }
+                    created.insert(device_id.clone(), ok);
+                    skipped.insert(device_id.clone(), dup);
+                }
+                Ok(json!({ "created": created, "skipped": skipped }))
+            }
+        }
+    }
+
+    async fn rollback(&self, stage: &RecipeStage, output: Option<&Value>, ctx: &StageContext) -> Result<RollbackOutcome, String> {
+        match stage {
+            RecipeStage::ImportFolder { .. } => Ok(RollbackOutcome::Skipped("导入的号码保留在号码库中".to_string())),
+            RecipeStage::AllocateNumbers { .. } => {
+                let batches = string_map(output, "batches");
+                if batches.is_empty() {
+                    return Ok(RollbackOutcome::Skipped("没有生成批次".to_string()));
+                }
+                let facade = self.facade();
+                let mut released = BTreeMap::new();
+                for (device_id, batch_id) in &batches {
+                    let count = facade
+                        .revert_allocation_batch(batch_id)
+                        .map_err(|e| format!("释放批次 {} 失败: {}", batch_id, e))?;
+                    released.insert(device_id.clone(), count);
+                }
+                Ok(RollbackOutcome::Done(json!({ "releasedBatches": batches, "released": released })))
+            }
+            RecipeStage::GenerateVcf { .. } => {
+                let files = string_map(output, "files");
+                if files.is_empty() {
+                    return Ok(RollbackOutcome::Skipped("没有生成 VCF 文件".to_string()));
+                }
+                let mut removed = Vec::new();
+                for path in files.values() {
+                    match std::fs::remove_file(path) {
+                        Ok(()) => removed.push(path.clone()),
+                        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                        Err(e) => return Err(format!("删除 {} 失败: {}", path, e)),
+                    }
+                }
+                // 默认目录按执行 ID 建立，删空后一并清理
+                if let Some(dir) = dirs::data_dir() {
+                    let _ = std::fs::remove_dir(dir.join("employee-gui").join("recipe_vcf").join(&ctx.run_id));
+                }
+                Ok(RollbackOutcome::Done(json!({ "removedFiles": removed })))
+            }
+            RecipeStage::RunImportScript { .. } => {
+                let imported = count_map(output, "imported");
+                if imported.is_empty() {
+                    return Ok(RollbackOutcome::Skipped("没有设备完成导入".to_string()));
+                }
+                let files = string_map(ctx.output_of("generate_vcf"), "files");
+                let mut cleaned = Vec::new();
+                let mut failed = BTreeMap::new();
+                for device_id in imported.keys() {
+                    let local_path = files.get(device_id).map(String::as_str).unwrap_or_default();
+                    let leftover = remove_pushed_files(device_id, local_path);
+                    if leftover.is_empty() {
+                        cleaned.push(device_id.clone());
+                    } else {
+                        failed.insert(device_id.clone(), leftover);
+                    }
+                }
+                if !failed.is_empty() {
+                    return Err(format!("部分设备文件删除失败: {}", failed.keys().cloned().collect::<Vec<_>>().join(", ")));
+                }
+                // 已写入通讯录的联系人无法可靠区分，只清理推送的文件
+                Ok(RollbackOutcome::Done(json!({ "cleanedDevices": cleaned, "contactsKept": imported })))
+            }
+            RecipeStage::StartFollowCampaign { .. } => {
+                Ok(RollbackOutcome::Skipped("已创建的关注任务请在活动中暂停或取消".to_string()))
+            }
+        }
+    }
+
+    fn on_run_update(&self, run: &RecipeRun) {
+        event_outbox::emit(&self.app, RECIPE_RUN_EVENT, &run.id, run);
+    }
+}
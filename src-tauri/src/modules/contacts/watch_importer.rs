@@ -0,0 +1,98 @@
+// src-tauri/src/modules/contacts/watch_importer.rs
+// module: contacts | layer: tauri-plugin | role: 监听来源自动导入
+// summary: 用文件夹导入的同一套读取 / 入库流程导入监听目录中的文件，按来源的导入会话记录逐文件结果（来源标记 + 跳过未变化文件），并向前端发出自动导入汇总事件
+
+use chrono::Utc;
+use std::fs;
+use std::path::Path;
+use tauri::AppHandle;
+
+use super::import_folder_file;
+use crate::services::contact_storage::repositories::folder_import_sessions_repo::FolderImportFileDto;
+use crate::services::contact_storage::repository_facade::ContactStorageFacade;
+use crate::services::contact_watch::{AutoImportStatus, AutoImportSummary, ContactWatchImporter, WatchSource, CONTACT_AUTO_IMPORT_EVENT};
+use crate::services::event_outbox;
+
+pub struct ContactWatchFileImporter {
+    app: AppHandle,
+}
+
+impl ContactWatchFileImporter {
+    pub fn new(app: AppHandle) -> Self {
+        Self { app }
+    }
+}
+
+impl ContactWatchImporter for ContactWatchFileImporter {
+    fn import(&self, source: &WatchSource, path: &Path) -> Result<AutoImportSummary, String> {
+        let facade = ContactStorageFacade::new(&self.app);
+        let session_id = facade.open_folder_import_session(&source.session_key(), false)?;
+
+        let file_path = path.to_string_lossy().to_string();
+        let metadata = fs::metadata(path).ok();
+        let file_size = metadata.as_ref().map_or(0, |m| m.len() as i64);
+        let modified_at = metadata
+            .and_then(|m| m.modified().ok())
+            .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+            .map_or(0, |d| d.as_secs() as i64);
+
+        let mut summary = AutoImportSummary {
+            source_id: source.id.clone(),
+            source_name: source.name.clone(),
+            file_path: file_path.clone(),
+            status: AutoImportStatus::Unchanged,
+            total_numbers: 0,
+            inserted: 0,
+            duplicates: 0,
+            error: None,
+            finished_at: Utc::now(),
+        };
+        if facade.is_folder_file_completed(session_id, &file_path, file_size, modified_at)? {
+            facade.finish_folder_import_session(session_id, "completed")?;
+            return Ok(summary);
+        }
+
+        let ext = path.extension().map(|e| e.to_string_lossy().to_lowercase()).unwrap_or_default();
+        let mut record = FolderImportFileDto {
+            file_path: file_path.clone(),
+            file_size,
+            modified_at,
+            status: "completed".to_string(),
+            total_numbers: 0,
+            inserted: 0,
+            duplicates: 0,
+            error_message: None,
+            updated_at: String::new(),
+        };
+        match import_folder_file(&facade, path, &ext) {
+            Ok(result) => {
+                record.total_numbers = result.total_numbers;
+                record.inserted = result.inserted;
+                record.duplicates = result.duplicates;
+                summary.status = AutoImportStatus::Imported;
+            }
+            Err(e) => {
+                record.status = "failed".to_string();
+                record.error_message = Some(e.clone());
+                summary.status = AutoImportStatus::Failed;
+                summary.error = Some(e);
+            }
+        }
+        facade.record_folder_import_file(session_id, &record)?;
+        facade.finish_folder_import_session(session_id, if summary.status == AutoImportStatus::Failed { "partial" } else { "completed" })?;
+
+        summary.total_numbers = record.total_numbers;
+        summary.inserted = record.inserted;
+        summary.duplicates = record.duplicates;
+        summary.finished_at = Utc::now();
+        tracing::info!(
+            "📥 自动导入 [{}] {}: {:?}, 新增 {} 个号码",
+            source.name, file_path, summary.status, summary.inserted
+        );
+        Ok(summary)
+    }
+
+    fn on_imported(&self, summary: &AutoImportSummary) {
+        event_outbox::emit(&self.app, CONTACT_AUTO_IMPORT_EVENT, &summary.source_id, summary);
+    }
+}
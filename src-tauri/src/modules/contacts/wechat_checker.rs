@@ -0,0 +1,54 @@
+// src-tauri/src/modules/contacts/wechat_checker.rs
+// module: contacts | layer: tauri-plugin | role: 微信注册核验执行
+// summary: 基于联系人库实现核验结果读写，在并发控制下后台运行单设备核验并推送进度事件
+
+use tauri::AppHandle;
+
+use crate::services::concurrency::{self, WorkRequest};
+use crate::services::contact_storage::repositories::contact_numbers::wechat_check::{WechatCheckCandidate, WechatStatus};
+use crate::services::contact_storage::repository_facade::ContactStorageFacade;
+use crate::services::event_outbox;
+use crate::services::wechat_verification::{
+    self, AdbWechatDriver, VerificationProgress, WechatCheckStore, WechatVerificationOptions, WECHAT_VERIFICATION_EVENT,
+};
+
+pub struct ContactWechatCheckStore {
+    app: AppHandle,
+}
+
+impl ContactWechatCheckStore {
+    pub fn new(app: AppHandle) -> Self {
+        Self { app }
+    }
+}
+
+impl WechatCheckStore for ContactWechatCheckStore {
+    fn candidates(&self, device_id: &str, limit: i64, recheck_after_days: Option<i64>) -> Result<Vec<WechatCheckCandidate>, String> {
+        ContactStorageFacade::new(&self.app).list_wechat_check_candidates(device_id, limit, recheck_after_days)
+    }
+
+    fn record(&self, id: i64, status: WechatStatus, device_id: &str, note: Option<&str>) -> Result<(), String> {
+        ContactStorageFacade::new(&self.app).record_wechat_check(id, status, device_id, note).map(|_| ())
+    }
+
+    fn checks_since(&self, device_id: &str, hours: i64) -> Result<i64, String> {
+        ContactStorageFacade::new(&self.app).count_wechat_checks_since(device_id, hours)
+    }
+}
+
+/// 登记并在后台启动核验，立即返回初始进度
+pub fn spawn_verification(app: AppHandle, device_id: String, options: WechatVerificationOptions) -> Result<VerificationProgress, String> {
+    let stop = wechat_verification::begin(&device_id, &options)?;
+    let initial = wechat_verification::status().into_iter().find(|p| p.device_id == device_id);
+    tauri::async_runtime::spawn(async move {
+        let driver = AdbWechatDriver::new(&device_id);
+        let store = ContactWechatCheckStore::new(app.clone());
+        let emit = |progress: &VerificationProgress| {
+            event_outbox::emit(&app, WECHAT_VERIFICATION_EVENT, &progress.device_id, progress);
+        };
+        let request = WorkRequest::device(&device_id, "wechat_verification", "微信注册核验");
+        let run = wechat_verification::run_verification(&driver, &store, &device_id, &options, &stop, &emit);
+        concurrency::run(request, run).await;
+    });
+    initial.ok_or_else(|| "核验任务登记失败".to_string())
+}
@@ -1,38 +1,34 @@
 use tauri::{plugin::{Builder, TauriPlugin}, Runtime, Manager, State};
-use std::sync::Mutex;
-use crate::services::employee_service::{Employee, EmployeeService};
+use crate::core::app_services::SharedAppServices;
+use crate::services::employee_service::Employee;
 
 #[tauri::command]
-async fn list(service: State<'_, Mutex<EmployeeService>>) -> Result<Vec<Employee>, String> {
-    let service = service.lock().map_err(|e| e.to_string())?;
-    service.get_all().map_err(|e| e.to_string())
+async fn list(services: State<'_, SharedAppServices>) -> Result<Vec<Employee>, String> {
+    crate::core::plugin_isolation::guard("employees", async move { services.with_employees(|service| service.get_all().map_err(|e| e.to_string())).await }).await
 }
 
 #[tauri::command]
-async fn add(employee: Employee, service: State<'_, Mutex<EmployeeService>>) -> Result<Employee, String> {
-    let mut service = service.lock().map_err(|e| e.to_string())?;
-    service.create(employee).map_err(|e| e.to_string())
+async fn add(employee: Employee, services: State<'_, SharedAppServices>) -> Result<Employee, String> {
+    crate::core::plugin_isolation::guard("employees", async move { services.with_employees(move |service| service.create(employee).map_err(|e| e.to_string())).await }).await
 }
 
 #[tauri::command]
-async fn update(employee: Employee, service: State<'_, Mutex<EmployeeService>>) -> Result<Employee, String> {
-    let mut service = service.lock().map_err(|e| e.to_string())?;
-    service.update(employee).map_err(|e| e.to_string())
+async fn update(employee: Employee, services: State<'_, SharedAppServices>) -> Result<Employee, String> {
+    crate::core::plugin_isolation::guard("employees", async move { services.with_employees(move |service| service.update(employee).map_err(|e| e.to_string())).await }).await
 }
 
 #[tauri::command]
-async fn delete(id: i32, service: State<'_, Mutex<EmployeeService>>) -> Result<(), String> {
-    let mut service = service.lock().map_err(|e| e.to_string())?;
-    service.delete(id).map_err(|e| e.to_string())
+async fn delete(id: i32, services: State<'_, SharedAppServices>) -> Result<(), String> {
+    crate::core::plugin_isolation::guard("employees", async move { services.with_employees(move |service| service.delete(id).map_err(|e| e.to_string())).await }).await
 }
 
 pub fn init<R: Runtime>() -> TauriPlugin<R> {
     Builder::new("employees")
-        .invoke_handler(tauri::generate_handler![
+        .invoke_handler(crate::core::plugin_isolation::isolate("employees", tauri::generate_handler![
             list,
             add,
             update,
             delete
-        ])
+        ]))
         .build()
 }
@@ -1,12 +1,14 @@
-use tauri::{plugin::{Builder, TauriPlugin}, Wry, AppHandle};
+use tauri::{plugin::{Builder, TauriPlugin}, Wry, AppHandle, Manager};
 use serde_json::Value;
 use crate::automation::types::{ContextEnvelope, SingleStepSpecV3, ChainSpecV3, StaticSpecV3, TaskV3};
 use crate::commands::automation_commands::{
     execute_single_step_test_v3 as execute_single_step_test_v3_impl,
     execute_chain_test_v3 as execute_chain_test_v3_impl,
     execute_static_strategy_test_v3 as execute_static_strategy_test_v3_impl,
-    execute_task_v3 as execute_task_v3_impl
+    execute_task_v3 as execute_task_v3_impl,
+    cancellation_registry,
 };
+use crate::automation::pipeline::cancellation::{ActiveTaskInfo, V3CancellationRegistry};
 
 #[tauri::command]
 async fn execute_single_step_test_v3(
@@ -14,7 +16,7 @@ async fn execute_single_step_test_v3(
     envelope: ContextEnvelope,
     step: SingleStepSpecV3,
 ) -> Result<Value, String> {
-    execute_single_step_test_v3_impl(app, envelope, step).await
+    crate::core::plugin_isolation::guard("execution_v3", async move { execute_single_step_test_v3_impl(app, envelope, step).await }).await
 }
 
 #[tauri::command]
@@ -23,7 +25,7 @@ async fn execute_chain_test_v3(
     envelope: ContextEnvelope,
     spec: Value,
 ) -> Result<Value, String> {
-    execute_chain_test_v3_impl(app, envelope, spec).await
+    crate::core::plugin_isolation::guard("execution_v3", async move { execute_chain_test_v3_impl(app, envelope, spec).await }).await
 }
 
 #[tauri::command]
@@ -32,7 +34,7 @@ async fn execute_static_strategy_test_v3(
     envelope: ContextEnvelope,
     spec: StaticSpecV3,
 ) -> Result<Value, String> {
-    execute_static_strategy_test_v3_impl(app, envelope, spec).await
+    crate::core::plugin_isolation::guard("execution_v3", async move { execute_static_strategy_test_v3_impl(app, envelope, spec).await }).await
 }
 
 #[tauri::command]
@@ -41,24 +43,44 @@ async fn execute_task_v3(
     envelope: ContextEnvelope,
     task: TaskV3,
 ) -> Result<Value, String> {
-    execute_task_v3_impl(app, envelope, task).await
+    crate::core::plugin_isolation::guard("execution_v3", async move { execute_task_v3_impl(app, envelope, task).await }).await
 }
 
+/// 取消进行中的 V3 任务（级联取消其链与步骤），返回是否找到该任务
 #[tauri::command]
-async fn cancel_execution_v3(analysis_id: String) -> Result<(), String> {
-    // TODO: Implement cancellation logic
-    tracing::warn!("cancel_execution_v3 not implemented yet for {}", analysis_id);
-    Ok(())
+async fn cancel_execution_v3(app: AppHandle, task_id: String) -> Result<bool, String> {
+    crate::core::plugin_isolation::guard("execution_v3", cancel_execution_v3_impl(app, task_id)).await
+}
+
+async fn cancel_execution_v3_impl(app: AppHandle, task_id: String) -> Result<bool, String> {
+    let cancelled = cancellation_registry(&app).cancel(&task_id);
+    if cancelled {
+        tracing::info!("🛑 [V3] 已请求取消任务: {}", task_id);
+    } else {
+        tracing::warn!("⚠️ [V3] 取消请求未找到进行中的任务: {}", task_id);
+    }
+    Ok(cancelled)
+}
+
+/// 进行中的 V3 任务及其当前链 / 步骤
+#[tauri::command]
+async fn list_active_executions_v3(app: AppHandle) -> Result<Vec<ActiveTaskInfo>, String> {
+    crate::core::plugin_isolation::guard("execution_v3", async move { Ok(cancellation_registry(&app).active()) }).await
 }
 
 pub fn init() -> TauriPlugin<Wry> {
     Builder::new("execution_v3")
-        .invoke_handler(tauri::generate_handler![
+        .setup(|app, _api| {
+            app.manage(V3CancellationRegistry::default());
+            Ok(())
+        })
+        .invoke_handler(crate::core::plugin_isolation::isolate("execution_v3", tauri::generate_handler![
             execute_single_step_test_v3,
             execute_chain_test_v3,
             execute_static_strategy_test_v3,
             execute_task_v3,
-            cancel_execution_v3 // ✅ Register cancel command
-        ])
+            cancel_execution_v3,
+            list_active_executions_v3
+        ]))
         .build()
 }
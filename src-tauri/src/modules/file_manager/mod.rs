@@ -1,23 +1,33 @@
 // src-tauri/src/modules/file_manager/mod.rs
 // module: file_manager | layer: api | role: File System Plugin
-// summary: 文件系统插件，提供文件读写、删除、打开等功能
+// summary: 文件系统插件，提供文件读写、删除、打开等功能，以及设备端文件的列目录、推送/拉取（带进度）、删除与校验
 
 use tauri::{
     plugin::{Builder, TauriPlugin},
-    Runtime,
+    AppHandle, Runtime,
 };
 use std::path::Path;
 use base64::Engine as _;
 
+use crate::services::device_files::{self, RemoteEntry, TransferDirection, TransferResult};
+use crate::services::device_windows;
+
+/// 设备文件传输进度事件
+const DEVICE_TRANSFER_PROGRESS_EVENT: &str = "file_manager://device_transfer_progress";
+
 /// 读取文本文件内容
 #[tauri::command]
 async fn read_text(path: String) -> Result<String, String> {
-    std::fs::read_to_string(&path).map_err(|e| format!("读取文件失败: {}", e))
+    crate::core::plugin_isolation::guard("file_manager", async move { std::fs::read_to_string(&path).map_err(|e| format!("读取文件失败: {}", e)) }).await
 }
 
 /// 写入文本文件内容
 #[tauri::command]
 async fn write_text(path: String, content: String) -> Result<String, String> {
+    crate::core::plugin_isolation::guard("file_manager", write_text_impl(path, content)).await
+}
+
+async fn write_text_impl(path: String, content: String) -> Result<String, String> {
     // 确保父目录存在
     if let Some(parent) = Path::new(&path).parent() {
         std::fs::create_dir_all(parent).ok();
@@ -31,6 +41,10 @@ async fn write_text(path: String, content: String) -> Result<String, String> {
 /// 追加文本到文件（用于日志）
 #[tauri::command]
 async fn append_text(path: String, content: String) -> Result<String, String> {
+    crate::core::plugin_isolation::guard("file_manager", append_text_impl(path, content)).await
+}
+
+async fn append_text_impl(path: String, content: String) -> Result<String, String> {
     use std::io::Write;
     
     // 确保父目录存在
@@ -56,6 +70,10 @@ async fn append_text(path: String, content: String) -> Result<String, String> {
 /// 删除文件
 #[tauri::command]
 async fn delete(path: String) -> Result<(), String> {
+    crate::core::plugin_isolation::guard("file_manager", delete_impl(path)).await
+}
+
+async fn delete_impl(path: String) -> Result<(), String> {
     match std::fs::remove_file(&path) {
         Ok(_) => Ok(()),
         Err(e) => {
@@ -71,6 +89,10 @@ async fn delete(path: String) -> Result<(), String> {
 /// 读取文件并转换为 Data URL
 #[tauri::command]
 async fn read_as_data_url(path: String) -> Result<String, String> {
+    crate::core::plugin_isolation::guard("file_manager", read_as_data_url_impl(path)).await
+}
+
+async fn read_as_data_url_impl(path: String) -> Result<String, String> {
     #[cfg(debug_assertions)]
     tracing::debug!("🖼️ [Plugin:file] 读取图片文件: {}", path);
     
@@ -104,6 +126,10 @@ async fn read_as_data_url(path: String) -> Result<String, String> {
 /// 在文件管理器中显示
 #[tauri::command]
 async fn reveal(path: String) -> Result<(), String> {
+    crate::core::plugin_isolation::guard("file_manager", reveal_impl(path)).await
+}
+
+async fn reveal_impl(path: String) -> Result<(), String> {
     #[cfg(target_os = "windows")]
     {
         use std::process::Command;
@@ -168,6 +194,10 @@ async fn reveal(path: String) -> Result<(), String> {
 /// 清除 ADB 密钥
 #[tauri::command]
 async fn clear_adb_keys() -> Result<(), String> {
+    crate::core::plugin_isolation::guard("file_manager", clear_adb_keys_impl()).await
+}
+
+async fn clear_adb_keys_impl() -> Result<(), String> {
     let home = std::env::var("USERPROFILE")
         .or_else(|_| std::env::var("HOME"))
         .map(std::path::PathBuf::from)
@@ -192,6 +222,10 @@ async fn clear_adb_keys() -> Result<(), String> {
 /// 清空日志文件
 #[tauri::command]
 async fn clear_log_files() -> Result<ClearLogsResult, String> {
+    crate::core::plugin_isolation::guard("file_manager", clear_log_files_impl()).await
+}
+
+async fn clear_log_files_impl() -> Result<ClearLogsResult, String> {
     let mut result = ClearLogsResult {
         backend_files_deleted: 0,
         frontend_files_deleted: 0,
@@ -259,10 +293,137 @@ struct ClearLogsResult {
     errors: Vec<String>,
 }
 
+/// 列出设备目录
+#[tauri::command]
+async fn list_device_dir(device_id: String, path: String) -> Result<Vec<RemoteEntry>, String> {
+    crate::core::plugin_isolation::guard("file_manager", list_device_dir_impl(device_id, path)).await
+}
+
+async fn list_device_dir_impl(device_id: String, path: String) -> Result<Vec<RemoteEntry>, String> {
+    tokio::task::spawn_blocking(move || device_files::list_dir(&device_id, &path))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// 推送本地文件到设备（进度通过 file_manager://device_transfer_progress 推送）
+#[tauri::command]
+async fn push_device_file<R: Runtime>(
+    app: AppHandle<R>,
+    device_id: String,
+    local_path: String,
+    remote_path: String,
+    transfer_id: Option<String>,
+) -> Result<TransferResult, String> {
+    crate::core::plugin_isolation::guard("file_manager", push_device_file_impl(app, device_id, local_path, remote_path, transfer_id)).await
+}
+
+async fn push_device_file_impl<R: Runtime>(
+    app: AppHandle<R>,
+    device_id: String,
+    local_path: String,
+    remote_path: String,
+    transfer_id: Option<String>,
+) -> Result<TransferResult, String> {
+    let transfer_id = transfer_id.unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+    tokio::task::spawn_blocking(move || {
+        device_files::push_file(&device_id, Path::new(&local_path), &remote_path, |transferred, total| {
+            emit_transfer_progress(&app, &device_id, &transfer_id, TransferDirection::Push, transferred, total);
+        })
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// 从设备拉取文件到本地
+#[tauri::command]
+async fn pull_device_file<R: Runtime>(
+    app: AppHandle<R>,
+    device_id: String,
+    remote_path: String,
+    local_path: String,
+    transfer_id: Option<String>,
+) -> Result<TransferResult, String> {
+    crate::core::plugin_isolation::guard("file_manager", pull_device_file_impl(app, device_id, remote_path, local_path, transfer_id)).await
+}
+
+async fn pull_device_file_impl<R: Runtime>(
+    app: AppHandle<R>,
+    device_id: String,
+    remote_path: String,
+    local_path: String,
+    transfer_id: Option<String>,
+) -> Result<TransferResult, String> {
+    let transfer_id = transfer_id.unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+    tokio::task::spawn_blocking(move || {
+        device_files::pull_file(&device_id, &remote_path, Path::new(&local_path), |transferred, total| {
+            emit_transfer_progress(&app, &device_id, &transfer_id, TransferDirection::Pull, transferred, total);
+        })
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+fn emit_transfer_progress<R: Runtime>(
+    app: &AppHandle<R>,
+    device_id: &str,
+    transfer_id: &str,
+    direction: TransferDirection,
+    transferred: u64,
+    total: u64,
+) {
+    let _ = device_windows::emit_for_device(
+        app,
+        device_id,
+        DEVICE_TRANSFER_PROGRESS_EVENT,
+        serde_json::json!({
+            "transfer_id": transfer_id,
+            "direction": direction,
+            "transferred": transferred,
+            "total": total,
+        }),
+    );
+}
+
+/// 删除设备文件（仅限 /sdcard、/storage、/data/local/tmp 下）
+#[tauri::command]
+async fn delete_device_path(device_id: String, path: String, recursive: Option<bool>) -> Result<(), String> {
+    crate::core::plugin_isolation::guard("file_manager", delete_device_path_impl(device_id, path, recursive)).await
+}
+
+async fn delete_device_path_impl(device_id: String, path: String, recursive: Option<bool>) -> Result<(), String> {
+    tokio::task::spawn_blocking(move || device_files::delete_path(&device_id, &path, recursive.unwrap_or(false)))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// 计算设备文件 MD5（设备不支持时返回 null）
+#[tauri::command]
+async fn device_file_checksum(device_id: String, path: String) -> Result<Option<String>, String> {
+    crate::core::plugin_isolation::guard("file_manager", device_file_checksum_impl(device_id, path)).await
+}
+
+async fn device_file_checksum_impl(device_id: String, path: String) -> Result<Option<String>, String> {
+    tokio::task::spawn_blocking(move || device_files::remote_md5(&device_id, &path))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// 清理 uiautomator 遗留的 dump 文件
+#[tauri::command]
+async fn cleanup_device_dump_files(device_id: String) -> Result<Vec<String>, String> {
+    crate::core::plugin_isolation::guard("file_manager", cleanup_device_dump_files_impl(device_id)).await
+}
+
+async fn cleanup_device_dump_files_impl(device_id: String) -> Result<Vec<String>, String> {
+    tokio::task::spawn_blocking(move || device_files::cleanup_uiautomator_artifacts(&device_id))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
 /// 初始化插件
 pub fn init<R: Runtime>() -> TauriPlugin<R> {
     Builder::new("file_manager")
-        .invoke_handler(tauri::generate_handler![
+        .invoke_handler(crate::core::plugin_isolation::isolate("file_manager", tauri::generate_handler![
             read_text,
             write_text,
             append_text,
@@ -270,7 +431,13 @@ pub fn init<R: Runtime>() -> TauriPlugin<R> {
             read_as_data_url,
             reveal,
             clear_adb_keys,
-            clear_log_files
-        ])
+            clear_log_files,
+            list_device_dir,
+            push_device_file,
+            pull_device_file,
+            delete_device_path,
+            device_file_checksum,
+            cleanup_device_dump_files
+        ]))
         .build()
 }
@@ -9,10 +9,21 @@ use tauri::{
 use std::path::Path;
 use tokio::fs;
 use image::{ImageFormat, imageops::FilterType, GenericImageView};
+use tracing::warn;
+
+use crate::services::artifact_store::gallery::{self, GalleryPage, GalleryQuery};
+use crate::services::artifact_store::ArtifactStore;
+
+/// 图库缩略图默认宽度
+const DEFAULT_GALLERY_THUMB_WIDTH: u32 = 240;
 
 /// 加载图片（优化版）
 #[tauri::command]
 async fn load(path: String) -> Result<Vec<u8>, String> {
+    crate::core::plugin_isolation::guard("image_optimization", load_impl(path)).await
+}
+
+async fn load_impl(path: String) -> Result<Vec<u8>, String> {
     // 验证路径安全性
     let path_obj = Path::new(&path);
     if !path_obj.exists() {
@@ -42,17 +53,10 @@ async fn load(path: String) -> Result<Vec<u8>, String> {
     }
 }
 
-/// 生成缩略图
-#[tauri::command]  
-async fn generate_thumbnail(
-    source_path: String, 
-    target_path: String,
-    max_width: u32
-) -> Result<String, String> {
-    println!("🔧 [Plugin:image] 开始生成缩略图: {} -> {} (width: {})", source_path, target_path, max_width);
-    
+/// 按最大宽度等比缩放并保存为 WebP，返回 (原宽, 原高, 新高)
+fn write_thumbnail(source_path: &str, target_path: &str, max_width: u32) -> Result<(u32, u32, u32), String> {
     // 打开原图
-    let img = image::open(&source_path)
+    let img = image::open(source_path)
         .map_err(|e| format!("无法打开原图: {}", e))?;
     
     // 计算缩略图尺寸
@@ -64,16 +68,83 @@ async fn generate_thumbnail(
     let thumbnail = img.resize(max_width, new_height, FilterType::Lanczos3);
     
     // 保存缩略图
-    thumbnail.save_with_format(&target_path, ImageFormat::WebP)
+    thumbnail.save_with_format(target_path, ImageFormat::WebP)
         .map_err(|e| format!("保存缩略图失败: {}", e))?;
+    Ok((width, height, new_height))
+}
+
+/// 生成缩略图
+#[tauri::command]  
+async fn generate_thumbnail(
+    source_path: String, 
+    target_path: String,
+    max_width: u32
+) -> Result<String, String> {
+    crate::core::plugin_isolation::guard("image_optimization", generate_thumbnail_impl(source_path, target_path, max_width)).await
+}
+
+async fn generate_thumbnail_impl(
+    source_path: String, 
+    target_path: String,
+    max_width: u32
+) -> Result<String, String> {
+    println!("🔧 [Plugin:image] 开始生成缩略图: {} -> {} (width: {})", source_path, target_path, max_width);
+    
+    let (width, height, new_height) = write_thumbnail(&source_path, &target_path, max_width)?;
     
     println!("✅ [Plugin:image] 缩略图生成完成: {} ({}x{} -> {}x{})", target_path, width, height, max_width, new_height);
     Ok(target_path)
 }
 
+/// 截图图库：跨运行浏览截图制品（过滤 / 去重 / 分页），并为当前页生成缩略图
+///
+/// 缩略图路径可直接交给 `load` 读取
+#[tauri::command]
+async fn list_screenshot_gallery(
+    query: Option<GalleryQuery>,
+    thumbnail_width: Option<u32>,
+) -> Result<GalleryPage, String> {
+    crate::core::plugin_isolation::guard("image_optimization", list_screenshot_gallery_impl(query, thumbnail_width)).await
+}
+
+async fn list_screenshot_gallery_impl(
+    query: Option<GalleryQuery>,
+    thumbnail_width: Option<u32>,
+) -> Result<GalleryPage, String> {
+    let query = query.unwrap_or_default();
+    let max_width = thumbnail_width.unwrap_or(DEFAULT_GALLERY_THUMB_WIDTH).clamp(32, 1080);
+    tokio::task::spawn_blocking(move || {
+        let mut page = gallery::query(ArtifactStore::global(), &query)?;
+        let thumbs: Vec<_> = {
+            let store = ArtifactStore::global().lock().map_err(|e| e.to_string())?;
+            page.items.iter().map(|i| store.thumbnail_path(&i.artifact.token)).collect()
+        };
+        // 缩略图在锁外生成，已存在的直接复用
+        for (item, thumb) in page.items.iter_mut().zip(thumbs) {
+            if !thumb.exists() {
+                if let Some(parent) = thumb.parent() {
+                    let _ = std::fs::create_dir_all(parent);
+                }
+                if let Err(e) = write_thumbnail(&item.local_path, &thumb.to_string_lossy(), max_width) {
+                    warn!("⚠️ [Plugin:image] 截图缩略图生成失败: {} - {}", item.artifact.token, e);
+                    continue;
+                }
+            }
+            item.thumbnail_path = Some(thumb.to_string_lossy().to_string());
+        }
+        Ok(page)
+    })
+    .await
+    .map_err(|e| format!("图库查询任务失败: {}", e))?
+}
+
 /// 批量预加载图片
 #[tauri::command]
 async fn preload_batch(image_paths: Vec<String>) -> Result<Vec<String>, String> {
+    crate::core::plugin_isolation::guard("image_optimization", preload_batch_impl(image_paths)).await
+}
+
+async fn preload_batch_impl(image_paths: Vec<String>) -> Result<Vec<String>, String> {
     let mut successful_preloads = Vec::new();
     let mut tasks = Vec::new();
     
@@ -110,10 +181,11 @@ async fn preload_batch(image_paths: Vec<String>) -> Result<Vec<String>, String>
 /// 初始化插件
 pub fn init<R: Runtime>() -> TauriPlugin<R> {
     Builder::new("image_optimization")
-        .invoke_handler(tauri::generate_handler![
+        .invoke_handler(crate::core::plugin_isolation::isolate("image_optimization", tauri::generate_handler![
             load,
             generate_thumbnail,
-            preload_batch
-        ])
+            preload_batch,
+            list_screenshot_gallery
+        ]))
         .build()
 }
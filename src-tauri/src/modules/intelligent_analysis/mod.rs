@@ -10,7 +10,16 @@ use crate::commands::intelligent_analysis::{
     AnalysisJobConfig, AnalysisJobResponse, BindAnalysisResultRequest, BindAnalysisResultResponse,
     StrategyCandidate, ANALYSIS_SERVICE, STEP_STRATEGY_STORE
 };
-use crate::commands::run_step_v2::{RunStepRequestV2, StepResponseV2, run_step_v2 as run_step_v2_impl};
+use crate::commands::run_step_v2::run_step_v2 as run_step_v2_impl;
+use crate::types::dto::execution::{RunStepRequestDto, StepResponseDto};
+use crate::types::{AppError, AppResult, ErrorCode};
+use crate::commands::run_step_v2::matching_config::{self, MatchingConfigStatus};
+use crate::commands::run_step_v2::plan_contract::{self, PlanValidationReport};
+use crate::automation::matching::decision_journal::{
+    explain_last_match as explain_last_match_impl, ExplanationNode, MAX_RECORDED_DECISIONS
+};
+use crate::services::selector_precompute;
+use crate::services::interaction_heatmap::{HeatmapStore, ScreenHeatmap, ScreenSummary};
 use crate::commands::structure_recommend::{
     self, RecommendInput, UiRecommendation, FlexibleRecommendInput, ResolveFromSnapshotInput, ResolvedFourNodes
 };
@@ -25,21 +34,37 @@ use crate::commands::execute_structure_match::{
 async fn start_intelligent_analysis(
     app_handle: AppHandle,
     config: AnalysisJobConfig,
-) -> Result<AnalysisJobResponse, String> {
-    ANALYSIS_SERVICE.start_analysis(app_handle, config).await
+) -> AppResult<AnalysisJobResponse> {
+    crate::core::plugin_isolation::guard("intelligent_analysis", start_intelligent_analysis_impl(app_handle, config)).await
+}
+
+async fn start_intelligent_analysis_impl(
+    app_handle: AppHandle,
+    config: AnalysisJobConfig,
+) -> AppResult<AnalysisJobResponse> {
+    crate::services::licensing::require_feature(crate::services::licensing::LicenseFeature::AiAnalysis)
+        .map_err(|e| AppError::new(ErrorCode::LicenseRequired, e))?;
+    crate::services::telemetry::record_feature("intelligent_analysis.start");
+    Ok(ANALYSIS_SERVICE.start_analysis(app_handle, config).await?)
 }
 
 /// 取消智能分析
 #[tauri::command]
-async fn cancel_intelligent_analysis(job_id: String) -> Result<(), String> {
-    ANALYSIS_SERVICE.cancel_analysis(&job_id)
+async fn cancel_intelligent_analysis(job_id: String) -> AppResult<()> {
+    crate::core::plugin_isolation::guard("intelligent_analysis", async move { Ok(ANALYSIS_SERVICE.cancel_analysis(&job_id)?) }).await
 }
 
 /// 绑定分析结果到步骤卡
 #[tauri::command]
 async fn bind_analysis_result_to_step(
     request: BindAnalysisResultRequest,
-) -> Result<BindAnalysisResultResponse, String> {
+) -> AppResult<BindAnalysisResultResponse> {
+    crate::core::plugin_isolation::guard("intelligent_analysis", bind_analysis_result_to_step_impl(request)).await
+}
+
+async fn bind_analysis_result_to_step_impl(
+    request: BindAnalysisResultRequest,
+) -> AppResult<BindAnalysisResultResponse> {
     let BindAnalysisResultRequest {
         step_id,
         analysis_result,
@@ -58,7 +83,7 @@ async fn bind_analysis_result_to_step(
     let strategy = match selected_strategy {
         Some(s) => s,
         None => {
-            return Err(format!(
+            return Err(AppError::not_found(format!(
                 "未找到策略 key={} (available: {:?})",
                 selected_strategy_key,
                 analysis_result
@@ -66,13 +91,13 @@ async fn bind_analysis_result_to_step(
                     .iter()
                     .map(|s| s.key.as_str())
                     .collect::<Vec<_>>()
-            ));
+            )));
         }
     };
     
     // 2. 检查是否已存在策略
     let mut store = STEP_STRATEGY_STORE.lock().map_err(|e| {
-        format!("锁定步骤策略存储失败: {}", e)
+        AppError::internal("锁定步骤策略存储失败").with_context(e.to_string())
     })?;
     
     let has_existing = store.contains_key(&step_id);
@@ -116,9 +141,13 @@ async fn bind_analysis_result_to_step(
 
 /// 获取步骤绑定的策略 (用于测试和查询)
 #[tauri::command]
-async fn get_step_strategy(step_id: String) -> Result<Option<StrategyCandidate>, String> {
+async fn get_step_strategy(step_id: String) -> AppResult<Option<StrategyCandidate>> {
+    crate::core::plugin_isolation::guard("intelligent_analysis", get_step_strategy_impl(step_id)).await
+}
+
+async fn get_step_strategy_impl(step_id: String) -> AppResult<Option<StrategyCandidate>> {
     let store = STEP_STRATEGY_STORE.lock().map_err(|e| {
-        format!("锁定步骤策略存储失败: {}", e)
+        AppError::internal("锁定步骤策略存储失败").with_context(e.to_string())
     })?;
     
     Ok(store.get(&step_id).map(|(strategy, _)| strategy.clone()))
@@ -126,9 +155,13 @@ async fn get_step_strategy(step_id: String) -> Result<Option<StrategyCandidate>,
 
 /// 清除步骤策略 (用于测试)
 #[tauri::command]
-async fn clear_step_strategy(step_id: String) -> Result<bool, String> {
+async fn clear_step_strategy(step_id: String) -> AppResult<bool> {
+    crate::core::plugin_isolation::guard("intelligent_analysis", clear_step_strategy_impl(step_id)).await
+}
+
+async fn clear_step_strategy_impl(step_id: String) -> AppResult<bool> {
     let mut store = STEP_STRATEGY_STORE.lock().map_err(|e| {
-        format!("锁定步骤策略存储失败: {}", e)
+        AppError::internal("锁定步骤策略存储失败").with_context(e.to_string())
     })?;
     
     Ok(store.remove(&step_id).is_some())
@@ -136,53 +169,144 @@ async fn clear_step_strategy(step_id: String) -> Result<bool, String> {
 
 /// 运行单步 V2 (Legacy)
 #[tauri::command]
-async fn run_step_v2(app_handle: AppHandle, request: RunStepRequestV2) -> Result<StepResponseV2, String> {
-    run_step_v2_impl(app_handle, request).await
+async fn run_step_v2(app_handle: AppHandle, request: RunStepRequestDto) -> AppResult<StepResponseDto> {
+    crate::core::plugin_isolation::guard("intelligent_analysis", async move { run_step_v2_impl(app_handle, request.into()).await.map(StepResponseDto::from) }).await
+}
+
+/// 校验决策链 Plan JSON（结构 + 语义规则），问题以 JSON Pointer 定位，供 Plan 构建器逐项标红
+#[tauri::command]
+async fn validate_plan_json(plan_json: String) -> AppResult<PlanValidationReport> {
+    crate::core::plugin_isolation::guard("intelligent_analysis", async move { Ok(plan_contract::validate_plan_json(&plan_json)) }).await
+}
+
+/// 解释步骤最近一次的匹配决策（各候选的评分明细树）
+#[tauri::command]
+async fn explain_last_match(step_id: String) -> AppResult<ExplanationNode> {
+    crate::core::plugin_isolation::guard("intelligent_analysis", explain_last_match_impl(step_id)).await
+}
+
+async fn explain_last_match_impl(step_id: String) -> AppResult<ExplanationNode> {
+    explain_last_match_impl(&step_id)
+        .ok_or_else(|| AppError::not_found(format!("步骤 {} 没有最近的匹配决策记录（仅保留最近 {} 次）", step_id, MAX_RECORDED_DECISIONS)))
 }
 
 // Wrappers for structure_recommend and execute_structure_match
 
 #[tauri::command]
-async fn recommend_structure_mode(app_handle: AppHandle, input: RecommendInput) -> Result<UiRecommendation, String> {
-    structure_recommend::recommend_structure_mode(app_handle, input).await.map_err(|e| e.to_string())
+async fn recommend_structure_mode(app_handle: AppHandle, input: RecommendInput) -> AppResult<UiRecommendation> {
+    crate::core::plugin_isolation::guard("intelligent_analysis", async move { Ok(structure_recommend::recommend_structure_mode(app_handle, input).await?) }).await
+}
+
+#[tauri::command]
+async fn recommend_structure_mode_v2(app_handle: AppHandle, input: FlexibleRecommendInput) -> AppResult<UiRecommendation> {
+    crate::core::plugin_isolation::guard("intelligent_analysis", async move { Ok(structure_recommend::recommend_structure_mode_v2(app_handle, input).await?) }).await
+}
+
+#[tauri::command]
+async fn dry_run_structure_match(app_handle: AppHandle, input: RecommendInput, mode: String) -> AppResult<Vec<usize>> {
+    crate::core::plugin_isolation::guard("intelligent_analysis", async move { Ok(structure_recommend::dry_run_structure_match(app_handle, input, mode).await?) }).await
+}
+
+#[tauri::command]
+async fn resolve_from_stepcard_snapshot(input: ResolveFromSnapshotInput) -> AppResult<ResolvedFourNodes> {
+    crate::core::plugin_isolation::guard("intelligent_analysis", async move { Ok(structure_recommend::resolve_from_stepcard_snapshot(input).await?) }).await
+}
+
+#[tauri::command]
+async fn execute_structure_match_step(app_handle: AppHandle, input: ExecuteMatchInput) -> AppResult<ExecutionResult> {
+    crate::core::plugin_isolation::guard("intelligent_analysis", async move { Ok(execute_structure_match::execute_structure_match_step(app_handle, input).await?) }).await
+}
+
+/// 查看当前生效的匹配配置（评分权重、奖惩项、安全阈值）及来源
+#[tauri::command]
+async fn get_matching_config() -> AppResult<MatchingConfigStatus> {
+    crate::core::plugin_isolation::guard("intelligent_analysis", async move { Ok(matching_config::status()) }).await
+}
+
+/// 重新读取匹配配置文件；校验失败时保留当前配置并返回错误
+#[tauri::command]
+async fn reload_matching_config() -> AppResult<MatchingConfigStatus> {
+    crate::core::plugin_isolation::guard("intelligent_analysis", async move { matching_config::reload().map_err(|e| AppError::invalid_argument("匹配配置校验失败，已保留当前配置").with_context(e)) }).await
+}
+
+/// 空闲预计算的命中率与缓存状态
+#[tauri::command]
+async fn get_selector_precompute_stats() -> AppResult<selector_precompute::PrecomputeStats> {
+    crate::core::plugin_isolation::guard("intelligent_analysis", async move { Ok(selector_precompute::stats()) }).await
 }
 
+/// 使预计算的候选失效：指定快照 ID 时只删除绑定该快照的条目，否则清空；返回删除数量
 #[tauri::command]
-async fn recommend_structure_mode_v2(app_handle: AppHandle, input: FlexibleRecommendInput) -> Result<UiRecommendation, String> {
-    structure_recommend::recommend_structure_mode_v2(app_handle, input).await.map_err(|e| e.to_string())
+async fn invalidate_precomputed_candidates(snapshot_id: Option<String>) -> AppResult<usize> {
+    crate::core::plugin_isolation::guard("intelligent_analysis", invalidate_precomputed_candidates_impl(snapshot_id)).await
+}
+
+async fn invalidate_precomputed_candidates_impl(snapshot_id: Option<String>) -> AppResult<usize> {
+    Ok(match snapshot_id {
+        Some(id) => selector_precompute::invalidate_snapshot(&id),
+        None => selector_precompute::clear(),
+    })
+}
+
+fn lock_heatmap() -> AppResult<std::sync::MutexGuard<'static, HeatmapStore>> {
+    HeatmapStore::global()
+        .lock()
+        .map_err(|e| AppError::internal("锁定交互热力统计失败").with_context(e.to_string()))
 }
 
+/// 界面元素交互热力图：各元素的操作次数、占比与失败率
 #[tauri::command]
-async fn dry_run_structure_match(app_handle: AppHandle, input: RecommendInput, mode: String) -> Result<Vec<usize>, String> {
-    structure_recommend::dry_run_structure_match(app_handle, input, mode).await
+async fn get_screen_heatmap(screen_fingerprint: String) -> AppResult<ScreenHeatmap> {
+    crate::core::plugin_isolation::guard("intelligent_analysis", get_screen_heatmap_impl(screen_fingerprint)).await
+}
+
+async fn get_screen_heatmap_impl(screen_fingerprint: String) -> AppResult<ScreenHeatmap> {
+    lock_heatmap()?
+        .heatmap(&screen_fingerprint)
+        .ok_or_else(|| AppError::not_found(format!("界面 {} 暂无交互记录", screen_fingerprint)))
 }
 
+/// 有交互记录的界面列表（按交互次数降序），可按包名过滤
 #[tauri::command]
-async fn resolve_from_stepcard_snapshot(input: ResolveFromSnapshotInput) -> Result<ResolvedFourNodes, String> {
-    structure_recommend::resolve_from_stepcard_snapshot(input).await.map_err(|e| e.to_string())
+async fn list_heatmap_screens(package: Option<String>) -> AppResult<Vec<ScreenSummary>> {
+    crate::core::plugin_isolation::guard("intelligent_analysis", async move { Ok(lock_heatmap()?.screens(package.as_deref())) }).await
 }
 
+/// 清除热力统计：指定界面指纹时只清除该界面；返回清除的界面数
 #[tauri::command]
-async fn execute_structure_match_step(app_handle: AppHandle, input: ExecuteMatchInput) -> Result<ExecutionResult, String> {
-    execute_structure_match::execute_structure_match_step(app_handle, input).await
+async fn clear_screen_heatmap(screen_fingerprint: Option<String>) -> AppResult<usize> {
+    crate::core::plugin_isolation::guard("intelligent_analysis", async move { Ok(lock_heatmap()?.clear(screen_fingerprint.as_deref())) }).await
 }
 
 // ==================== 🔌 Plugin Initialization ====================
 
 pub fn init() -> TauriPlugin<Wry> {
     Builder::new("intelligent_analysis")
-        .invoke_handler(tauri::generate_handler![
+        .setup(|app, _api| {
+            tauri::async_runtime::spawn(selector_precompute::run_worker(app.clone()));
+            Ok(())
+        })
+        .invoke_handler(crate::core::plugin_isolation::isolate("intelligent_analysis", tauri::generate_handler![
             start_intelligent_analysis,
             cancel_intelligent_analysis,
             bind_analysis_result_to_step,
             get_step_strategy,
             clear_step_strategy,
             run_step_v2,
+            validate_plan_json,
+            explain_last_match,
             recommend_structure_mode,
             recommend_structure_mode_v2,
             dry_run_structure_match,
             resolve_from_stepcard_snapshot,
-            execute_structure_match_step
-        ])
+            execute_structure_match_step,
+            get_matching_config,
+            reload_matching_config,
+            get_selector_precompute_stats,
+            invalidate_precomputed_candidates,
+            get_screen_heatmap,
+            list_heatmap_screens,
+            clear_screen_heatmap
+        ]))
         .build()
 }
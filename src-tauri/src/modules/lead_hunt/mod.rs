@@ -6,13 +6,27 @@ use crate::commands::commands_lead_hunt::*;
 
 pub fn init() -> TauriPlugin<Wry> {
     Builder::<Wry>::new("lead_hunt")
-        .invoke_handler(tauri::generate_handler![
+        .invoke_handler(crate::core::plugin_isolation::isolate("lead_hunt", tauri::generate_handler![
             lh_save_comments,
             lh_list_comments,
             lh_import_comments,
             lh_create_replay_plan,
             lh_run_replay_plan,
-            lh_analyze_comments
-        ])
+            lh_analyze_comments,
+            preview_next_targets,
+            rescore_target_queue,
+            pin_target_to_top,
+            set_task_lead_score,
+            pause_campaign,
+            resume_campaign,
+            get_campaign_state,
+            list_campaign_timeline,
+            campaign_should_yield,
+            list_protected_accounts,
+            add_protected_account,
+            remove_protected_account,
+            import_protected_accounts,
+            list_protected_skips
+        ]))
         .build()
 }
@@ -20,3 +20,4 @@ pub mod ui_dump;  // ✅ UI Dump 多模式系统
 pub mod agent;    // ✅ AI Agent 插件（内嵌 AI 代理）
 pub mod agent_runtime; // ✅ Agent 自主运行时（真正的 AI Agent）
 pub mod cloud_sync;    // ✅ 云同步模块（设备ID、配置同步）
+pub mod scheduler;     // ✅ 脚本定时执行插件
@@ -0,0 +1,118 @@
+// src-tauri/src/modules/prospecting/media.rs
+// module: prospecting | layer: commands | role: reply-media-commands
+// summary: 回复媒体命令 - 素材导入 / 审核 / 删除，回复时推送素材到设备并经图库选择器附加，记录每条回复发送的素材
+
+use chrono::Utc;
+use serde_json::Value;
+use tauri::State;
+
+use super::ProspectingState;
+use crate::services::media_assets::gallery_picker::{self, GalleryFlow};
+use crate::services::media_assets::{self, MediaAsset, MediaAssetStore, MediaSendRecord};
+use crate::services::prospecting::{ReplyPlan, ReplyStepType};
+use crate::types::AppResult;
+
+#[tauri::command]
+pub async fn list_media_assets() -> AppResult<Vec<MediaAsset>> {
+    crate::core::plugin_isolation::guard("prospecting", async move { Ok(MediaAssetStore::global().lock().map_err(|e| e.to_string())?.list()) }).await
+}
+
+/// 导入本地图片为素材（需审核后才能发送）
+#[tauri::command]
+pub async fn import_media_asset(path: String, name: Option<String>) -> AppResult<MediaAsset> {
+    crate::core::plugin_isolation::guard("prospecting", import_media_asset_impl(path, name)).await
+}
+
+async fn import_media_asset_impl(path: String, name: Option<String>) -> AppResult<MediaAsset> {
+    let mut store = MediaAssetStore::global().lock().map_err(|e| e.to_string())?;
+    Ok(store.import(std::path::Path::new(&path), name.as_deref())?)
+}
+
+#[tauri::command]
+pub async fn approve_media_asset(id: String, approved: Option<bool>, approver: Option<String>) -> AppResult<MediaAsset> {
+    crate::core::plugin_isolation::guard("prospecting", approve_media_asset_impl(id, approved, approver)).await
+}
+
+async fn approve_media_asset_impl(id: String, approved: Option<bool>, approver: Option<String>) -> AppResult<MediaAsset> {
+    let mut store = MediaAssetStore::global().lock().map_err(|e| e.to_string())?;
+    Ok(store.set_approved(&id, approved.unwrap_or(true), approver.as_deref())?)
+}
+
+#[tauri::command]
+pub async fn delete_media_asset(id: String) -> AppResult<bool> {
+    crate::core::plugin_isolation::guard("prospecting", async move { Ok(MediaAssetStore::global().lock().map_err(|e| e.to_string())?.delete(&id)?) }).await
+}
+
+/// 素材发送记录（可按回复计划过滤）
+#[tauri::command]
+pub async fn list_media_sends(reply_plan_id: Option<String>, limit: Option<usize>) -> AppResult<Vec<MediaSendRecord>> {
+    crate::core::plugin_isolation::guard("prospecting", list_media_sends_impl(reply_plan_id, limit)).await
+}
+
+async fn list_media_sends_impl(reply_plan_id: Option<String>, limit: Option<usize>) -> AppResult<Vec<MediaSendRecord>> {
+    let store = MediaAssetStore::global().lock().map_err(|e| e.to_string())?;
+    Ok(store.sends(reply_plan_id.as_deref(), limit.unwrap_or(200)))
+}
+
+/// 计划要附带的素材：显式传入 > 计划字段 > attach_media 步骤参数
+fn plan_asset_id(plan: &ReplyPlan) -> Option<String> {
+    plan.media_asset_id.clone().or_else(|| {
+        plan.steps
+            .iter()
+            .filter(|s| s.step_type == ReplyStepType::AttachMedia)
+            .find_map(|s| s.params.get("assetId").and_then(Value::as_str).map(str::to_string))
+    })
+}
+
+/// 回复执行的附图步骤：推送素材到设备 → 图库选择器附加 → 记录发送的素材
+///
+/// 调用时设备应停在目标评论的回复输入框
+#[tauri::command]
+pub async fn attach_reply_media(
+    state: State<'_, ProspectingState>,
+    device_id: String,
+    plan_id: String,
+    asset_id: Option<String>,
+) -> AppResult<MediaSendRecord> {
+    crate::core::plugin_isolation::guard("prospecting", attach_reply_media_impl(state, device_id, plan_id, asset_id)).await
+}
+
+async fn attach_reply_media_impl(
+    state: State<'_, ProspectingState>,
+    device_id: String,
+    plan_id: String,
+    asset_id: Option<String>,
+) -> AppResult<MediaSendRecord> {
+    let mut plan = state
+        .with_service(|service| service.get_reply_plans_by_ids(std::slice::from_ref(&plan_id)))
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .next()
+        .ok_or_else(|| format!("回复计划不存在: {}", plan_id))?;
+    let asset_id = asset_id.or_else(|| plan_asset_id(&plan)).ok_or("回复计划未指定媒体素材")?;
+    let (asset, local_path) = MediaAssetStore::global().lock().map_err(|e| e.to_string())?.sendable(&asset_id)?;
+
+    let remote_path = {
+        let (device_id, asset) = (device_id.clone(), asset.clone());
+        tokio::task::spawn_blocking(move || media_assets::push_to_device(&device_id, &asset, &local_path))
+            .await
+            .map_err(|e| format!("推送任务失败: {}", e))??
+    };
+    let platform = serde_json::to_value(&plan.platform).ok().and_then(|v| v.as_str().map(str::to_string)).unwrap_or_default();
+    gallery_picker::attach_latest_image(&device_id, &GalleryFlow::for_platform(&platform)).await?;
+
+    let record = MediaSendRecord {
+        asset_id: asset.id.clone(),
+        sha256: asset.sha256.clone(),
+        reply_plan_id: plan.id.clone(),
+        device_id,
+        remote_path,
+        sent_at: Utc::now(),
+    };
+    MediaAssetStore::global().lock().map_err(|e| e.to_string())?.record_send(record.clone())?;
+
+    plan.media_asset_id = Some(asset.id);
+    plan.updated_at = Utc::now().timestamp_millis();
+    state.with_service(|service| service.save_reply_plan(&plan)).map_err(|e| e.to_string())?;
+    Ok(record)
+}
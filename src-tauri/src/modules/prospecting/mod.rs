@@ -1,45 +1,86 @@
 use tauri::{
     plugin::{Builder, TauriPlugin},
-    Runtime, Manager, State, AppHandle
+    Runtime, Manager, State, AppHandle, Emitter, Wry
 };
-use std::sync::Arc;
 use parking_lot::Mutex;
 use std::path::PathBuf;
 use anyhow::Result;
 use serde_json::Value;
 
+use crate::ai::batch_embed::embed_in_batches;
+use crate::ai::router::AIRouter;
+use crate::core::plugin_bootstrap::{self, LazyService};
+use crate::core::app_services::SharedAppServices;
+use crate::services::concurrency::{self, WorkRequest};
 use crate::services::prospecting::{
     ProspectingService,
     Comment, RawComment, CommentFilter, AnalysisResult, ReplyPlan, Statistics,
+    SemanticMatch, CommentCluster, EmbeddingIndexReport,
 };
+use crate::services::prospecting::prospecting_export::{lead_columns, lead_row};
+use crate::types::{AppError, AppResult, ErrorCode};
+use crate::types::dto::prospecting::{AnalysisResultDto, CommentDto, RawCommentDto, ReplyPlanDto, StatisticsDto};
+use crate::services::report_export::{self, ExportOptions, ExportOutcome};
+use crate::services::marketing_storage::commands as marketing;
+use crate::services::marketing_storage::models::{AuditLogPayload, CommentPayload, TaskPayload, WatchTargetPayload};
+use crate::services::prospecting::prospecting_calendar::{CalendarEntry, ReplySchedule, ScheduledReplies};
+use crate::services::prospecting::prospecting_rules::{RuleApplyReport, RuleSet, RuleTestResult};
+use crate::services::prospecting::prospecting_import_readers::{read_table, RawTable};
+use crate::services::prospecting::prospecting_ingestion::{
+    self as ingestion, ImportPreview, ImportProfile, ImportReport, IMPORT_PROFILES_FILE_NAME,
+};
+
+mod media;
+use media::*;
+
+/// 单次向量化的评论数上限
+const DEFAULT_EMBED_LIMIT: usize = 2000;
+/// 语义检索默认返回条数
+const DEFAULT_SEARCH_TOP_K: usize = 20;
+/// 导入进度事件
+const IMPORT_PROGRESS_EVENT: &str = "prospecting://import_progress";
 
 pub struct ProspectingState {
-    service: Arc<Mutex<Option<ProspectingService>>>,
+    /// 懒加载服务：首次访问时才打开数据库，耗时记入插件状态
+    service: LazyService<ProspectingService>,
+    /// 懒加载使用的数据目录（setup 时记录，首次访问时才创建服务）
+    data_dir: Mutex<Option<PathBuf>>,
 }
 
 impl ProspectingState {
     pub fn new() -> Self {
         Self {
-            service: Arc::new(Mutex::new(None)),
+            service: LazyService::new("prospecting"),
+            data_dir: Mutex::new(None),
         }
     }
+
+    pub fn set_data_dir(&self, data_dir: PathBuf) {
+        *self.data_dir.lock() = Some(data_dir);
+    }
     
+    /// 显式初始化（前端 init_storage）；服务已就绪时直接返回
     pub fn init_service(&self, data_dir: PathBuf) -> Result<()> {
-        let service = ProspectingService::new(data_dir)?;
-        let mut guard = self.service.lock();
-        *guard = Some(service);
-        Ok(())
+        self.data_dir.lock().get_or_insert(data_dir);
+        self.service().map(|_| ())
+    }
+
+    fn service(&self) -> Result<&ProspectingService> {
+        self.service.get_or_try_init(|| {
+            let data_dir = self
+                .data_dir
+                .lock()
+                .clone()
+                .ok_or_else(|| anyhow::anyhow!("Prospecting service not initialized"))?;
+            ProspectingService::new(data_dir)
+        })
     }
     
     pub fn with_service<F, R>(&self, f: F) -> Result<R>
     where
         F: FnOnce(&ProspectingService) -> Result<R>,
     {
-        let guard = self.service.lock();
-        match guard.as_ref() {
-            Some(service) => f(service),
-            None => Err(anyhow::anyhow!("Prospecting service not initialized")),
-        }
+        f(self.service()?)
     }
 }
 
@@ -47,20 +88,35 @@ impl ProspectingState {
 async fn init_storage<R: Runtime>(
     app: AppHandle<R>,
     state: State<'_, ProspectingState>,
-) -> Result<(), String> {
+) -> AppResult<()> {
+    crate::core::plugin_isolation::guard("prospecting", init_storage_impl(app, state)).await
+}
+
+async fn init_storage_impl<R: Runtime>(
+    app: AppHandle<R>,
+    state: State<'_, ProspectingState>,
+) -> AppResult<()> {
     let data_dir = app.path()
         .app_data_dir()
         .map_err(|e| format!("Failed to get app data dir: {}", e))?;
     
     state.init_service(data_dir)
-        .map_err(|e| format!("Failed to initialize service: {}", e))
+        .map_err(|e| AppError::new(ErrorCode::StorageError, "Failed to initialize service").with_context(e.to_string()))
 }
 
 #[tauri::command]
 async fn save_comment(
     state: State<'_, ProspectingState>,
-    comment: RawComment,
-) -> Result<(), String> {
+    comment: RawCommentDto,
+) -> AppResult<()> {
+    crate::core::plugin_isolation::guard("prospecting", save_comment_impl(state, comment)).await
+}
+
+async fn save_comment_impl(
+    state: State<'_, ProspectingState>,
+    comment: RawCommentDto,
+) -> AppResult<()> {
+    let comment = RawComment::from(comment);
     state.with_service(|service| {
         service.save_comment(&comment)
     }).map_err(|e| e.to_string())?;
@@ -71,27 +127,82 @@ async fn save_comment(
 async fn get_comments(
     state: State<'_, ProspectingState>,
     filter: CommentFilter,
-) -> Result<Vec<Comment>, String> {
+) -> AppResult<Vec<CommentDto>> {
+    crate::core::plugin_isolation::guard("prospecting", get_comments_impl(state, filter)).await
+}
+
+async fn get_comments_impl(
+    state: State<'_, ProspectingState>,
+    filter: CommentFilter,
+) -> AppResult<Vec<CommentDto>> {
     state.with_service(|service| {
         service.get_comments(&filter)
-    }).map_err(|e| e.to_string())
+    }).map(|comments| comments.into_iter().map(Into::into).collect()).map_err(AppError::from)
+}
+
+/// 按筛选条件导出线索（csv / xlsx / json），进度通过统一导出事件上报
+#[tauri::command]
+async fn export_leads<R: Runtime>(
+    app: AppHandle<R>,
+    state: State<'_, ProspectingState>,
+    filter: CommentFilter,
+    format: Option<String>,
+    locale: Option<String>,
+    output_path: Option<String>,
+) -> AppResult<ExportOutcome> {
+    crate::core::plugin_isolation::guard("prospecting", export_leads_impl(app, state, filter, format, locale, output_path)).await
+}
+
+async fn export_leads_impl<R: Runtime>(
+    app: AppHandle<R>,
+    state: State<'_, ProspectingState>,
+    filter: CommentFilter,
+    format: Option<String>,
+    locale: Option<String>,
+    output_path: Option<String>,
+) -> AppResult<ExportOutcome> {
+    let options = ExportOptions::from_args(format.as_deref(), locale.as_deref(), output_path)?;
+    let comments = state.with_service(|service| service.get_comments(&filter)).map_err(|e| e.to_string())?;
+    tokio::task::spawn_blocking(move || {
+        let rows: Vec<Value> = comments.iter().map(lead_row).collect();
+        let columns = lead_columns(options.locale);
+        report_export::export_table("leads", options, columns, &rows, report_export::emit_progress(&app))
+    })
+    .await
+    .map_err(|e| AppError::internal(e.to_string()))?
+    .map_err(AppError::from)
 }
 
 #[tauri::command]
 async fn get_comments_by_ids(
     state: State<'_, ProspectingState>,
     ids: Vec<String>,
-) -> Result<Vec<Comment>, String> {
+) -> AppResult<Vec<CommentDto>> {
+    crate::core::plugin_isolation::guard("prospecting", get_comments_by_ids_impl(state, ids)).await
+}
+
+async fn get_comments_by_ids_impl(
+    state: State<'_, ProspectingState>,
+    ids: Vec<String>,
+) -> AppResult<Vec<CommentDto>> {
     state.with_service(|service| {
         service.get_comments_by_ids(&ids)
-    }).map_err(|e| e.to_string())
+    }).map(|comments| comments.into_iter().map(Into::into).collect()).map_err(AppError::from)
 }
 
 #[tauri::command]
 async fn save_analysis(
     state: State<'_, ProspectingState>,
-    analysis: AnalysisResult,
-) -> Result<(), String> {
+    analysis: AnalysisResultDto,
+) -> AppResult<()> {
+    crate::core::plugin_isolation::guard("prospecting", save_analysis_impl(state, analysis)).await
+}
+
+async fn save_analysis_impl(
+    state: State<'_, ProspectingState>,
+    analysis: AnalysisResultDto,
+) -> AppResult<()> {
+    let analysis = AnalysisResult::from(analysis);
     state.with_service(|service| {
         service.save_analysis(&analysis)
     }).map_err(|e| e.to_string())?;
@@ -101,8 +212,16 @@ async fn save_analysis(
 #[tauri::command]
 async fn save_reply_plan(
     state: State<'_, ProspectingState>,
-    plan: ReplyPlan,
-) -> Result<(), String> {
+    plan: ReplyPlanDto,
+) -> AppResult<()> {
+    crate::core::plugin_isolation::guard("prospecting", save_reply_plan_impl(state, plan)).await
+}
+
+async fn save_reply_plan_impl(
+    state: State<'_, ProspectingState>,
+    plan: ReplyPlanDto,
+) -> AppResult<()> {
+    let plan = ReplyPlan::from(plan);
     state.with_service(|service| {
         service.save_reply_plan(&plan)
     }).map_err(|e| e.to_string())?;
@@ -113,37 +232,439 @@ async fn save_reply_plan(
 async fn get_reply_plans(
     state: State<'_, ProspectingState>,
     comment_ids: Vec<String>,
-) -> Result<Vec<ReplyPlan>, String> {
+) -> AppResult<Vec<ReplyPlanDto>> {
+    crate::core::plugin_isolation::guard("prospecting", get_reply_plans_impl(state, comment_ids)).await
+}
+
+async fn get_reply_plans_impl(
+    state: State<'_, ProspectingState>,
+    comment_ids: Vec<String>,
+) -> AppResult<Vec<ReplyPlanDto>> {
     state.with_service(|service| {
         service.get_reply_plans(&comment_ids)
-    }).map_err(|e| e.to_string())
+    }).map(|plans| plans.into_iter().map(Into::into).collect()).map_err(AppError::from)
 }
 
 #[tauri::command]
 async fn get_reply_plans_by_ids(
     state: State<'_, ProspectingState>,
     ids: Vec<String>,
-) -> Result<Vec<ReplyPlan>, String> {
+) -> AppResult<Vec<ReplyPlanDto>> {
+    crate::core::plugin_isolation::guard("prospecting", get_reply_plans_by_ids_impl(state, ids)).await
+}
+
+async fn get_reply_plans_by_ids_impl(
+    state: State<'_, ProspectingState>,
+    ids: Vec<String>,
+) -> AppResult<Vec<ReplyPlanDto>> {
     state.with_service(|service| {
         service.get_reply_plans_by_ids(&ids)
-    }).map_err(|e| e.to_string())
+    }).map(|plans| plans.into_iter().map(Into::into).collect()).map_err(AppError::from)
+}
+
+/// 为回复计划排期（指定时间，或 `nextCalendarSlot` 按活动的下一个内容档期）
+#[tauri::command]
+async fn schedule_reply_plans(
+    state: State<'_, ProspectingState>,
+    plan_ids: Vec<String>,
+    schedule: ReplySchedule,
+) -> AppResult<ScheduledReplies> {
+    crate::core::plugin_isolation::guard("prospecting", schedule_reply_plans_impl(state, plan_ids, schedule)).await
+}
+
+async fn schedule_reply_plans_impl(
+    state: State<'_, ProspectingState>,
+    plan_ids: Vec<String>,
+    schedule: ReplySchedule,
+) -> AppResult<ScheduledReplies> {
+    state.with_service(|service| {
+        service.schedule_reply_plans(&plan_ids, &schedule)
+    }).map_err(AppError::from)
+}
+
+/// 已到计划时间（或未排期）的待执行回复计划
+#[tauri::command]
+async fn get_due_reply_plans(
+    state: State<'_, ProspectingState>,
+    limit: Option<usize>,
+) -> AppResult<Vec<ReplyPlanDto>> {
+    crate::core::plugin_isolation::guard("prospecting", get_due_reply_plans_impl(state, limit)).await
+}
+
+async fn get_due_reply_plans_impl(
+    state: State<'_, ProspectingState>,
+    limit: Option<usize>,
+) -> AppResult<Vec<ReplyPlanDto>> {
+    state.with_service(|service| {
+        service.get_due_reply_plans(chrono::Utc::now().timestamp_millis(), limit.unwrap_or(100))
+    }).map(|plans| plans.into_iter().map(Into::into).collect()).map_err(AppError::from)
+}
+
+/// 内容日历档期（`from` / `to` 为 YYYY-MM-DD）
+#[tauri::command]
+async fn list_content_calendar(
+    state: State<'_, ProspectingState>,
+    from: Option<String>,
+    to: Option<String>,
+    campaign_id: Option<String>,
+) -> AppResult<Vec<CalendarEntry>> {
+    crate::core::plugin_isolation::guard("prospecting", list_content_calendar_impl(state, from, to, campaign_id)).await
+}
+
+async fn list_content_calendar_impl(
+    state: State<'_, ProspectingState>,
+    from: Option<String>,
+    to: Option<String>,
+    campaign_id: Option<String>,
+) -> AppResult<Vec<CalendarEntry>> {
+    state.with_service(|service| {
+        service.list_calendar_entries(from.as_deref(), to.as_deref(), campaign_id.as_deref())
+    }).map_err(AppError::from)
+}
+
+#[tauri::command]
+async fn save_content_calendar_entry(
+    state: State<'_, ProspectingState>,
+    entry: CalendarEntry,
+) -> AppResult<CalendarEntry> {
+    crate::core::plugin_isolation::guard("prospecting", save_content_calendar_entry_impl(state, entry)).await
+}
+
+async fn save_content_calendar_entry_impl(
+    state: State<'_, ProspectingState>,
+    entry: CalendarEntry,
+) -> AppResult<CalendarEntry> {
+    state.with_service(|service| {
+        service.save_calendar_entry(entry)
+    }).map_err(AppError::from)
+}
+
+#[tauri::command]
+async fn delete_content_calendar_entry(
+    state: State<'_, ProspectingState>,
+    id: String,
+) -> AppResult<bool> {
+    crate::core::plugin_isolation::guard("prospecting", delete_content_calendar_entry_impl(state, id)).await
+}
+
+async fn delete_content_calendar_entry_impl(
+    state: State<'_, ProspectingState>,
+    id: String,
+) -> AppResult<bool> {
+    state.with_service(|service| {
+        service.delete_calendar_entry(&id)
+    }).map_err(AppError::from)
 }
 
 #[tauri::command]
 async fn execute_real_reply_plan(
     _state: State<'_, ProspectingState>,
     plan_id: String,
-) -> Result<bool, String> {
-    Ok(true)
+) -> AppResult<bool> {
+    crate::core::plugin_isolation::guard("prospecting", async move { Ok(true) }).await
 }
 
 #[tauri::command]
 async fn get_statistics(
     state: State<'_, ProspectingState>,
-) -> Result<Statistics, String> {
+) -> AppResult<StatisticsDto> {
+    crate::core::plugin_isolation::guard("prospecting", get_statistics_impl(state)).await
+}
+
+async fn get_statistics_impl(
+    state: State<'_, ProspectingState>,
+) -> AppResult<StatisticsDto> {
     state.with_service(|service| {
         service.get_statistics()
-    }).map_err(|e| e.to_string())
+    }).map(StatisticsDto::from).map_err(AppError::from)
+}
+
+/// 批量向量化尚未入索引（或内容已变化）的评论
+#[tauri::command]
+async fn index_comment_embeddings(
+    services: State<'_, SharedAppServices>,
+    state: State<'_, ProspectingState>,
+    limit: Option<usize>,
+) -> AppResult<EmbeddingIndexReport> {
+    crate::core::plugin_isolation::guard("prospecting", index_comment_embeddings_impl(services, state, limit)).await
+}
+
+async fn index_comment_embeddings_impl(
+    services: State<'_, SharedAppServices>,
+    state: State<'_, ProspectingState>,
+    limit: Option<usize>,
+) -> AppResult<EmbeddingIndexReport> {
+    let settings = services.ai.settings_with_keys()?;
+    let model = settings.default_embed_model.clone();
+
+    let pending = state.with_service(|service| {
+        service.get_comments_pending_embedding(&model, limit.unwrap_or(DEFAULT_EMBED_LIMIT))
+    })?;
+    let texts: Vec<String> = pending.iter().map(|(_, content)| content.clone()).collect();
+
+    let router = AIRouter::new(settings.clone());
+    let outcome = embed_in_batches(&texts, settings.embed_batch_size, settings.max_retries, |batch| {
+        let request = WorkRequest::ai("prospecting_embed", format!("{} 条评论", batch.len()));
+        concurrency::run(request, router.embed(&model, batch))
+    }).await;
+
+    let mut report = EmbeddingIndexReport {
+        model: model.clone(),
+        pending: pending.len(),
+        requests: outcome.requests,
+        ..Default::default()
+    };
+    let mut items = Vec::with_capacity(outcome.succeeded());
+    for ((id, content), vector) in pending.into_iter().zip(outcome.vectors) {
+        match vector {
+            Some(vector) => items.push((id, content, vector)),
+            None => report.failed_ids.push(id),
+        }
+    }
+    report.embedded = items.len();
+
+    report.indexed_total = state.with_service(|service| {
+        service.save_embeddings(&model, &items)?;
+        service.embedding_count(&model)
+    })?;
+    Ok(report)
+}
+
+/// 向量化查询并在评论向量索引中检索；`embed` 由调用方提供（命令中经并发控制器调用 AI）
+async fn search_comments_by_embedding<F, Fut>(
+    state: &ProspectingState,
+    model: &str,
+    query: String,
+    top_k: usize,
+    embed: F,
+) -> AppResult<Vec<SemanticMatch>>
+where
+    F: FnOnce(Vec<String>) -> Fut,
+    Fut: std::future::Future<Output = Result<Vec<Vec<f32>>>>,
+{
+    if query.trim().is_empty() {
+        return Ok(Vec::new());
+    }
+    let query_vector = embed(vec![query])
+        .await?
+        .into_iter()
+        .next()
+        .ok_or("查询向量化失败")?;
+
+    Ok(state.with_service(|service| service.semantic_search(model, &query_vector, top_k))?)
+}
+
+/// 语义检索评论（需先执行 index_comment_embeddings）
+#[tauri::command]
+async fn semantic_search_comments(
+    services: State<'_, SharedAppServices>,
+    state: State<'_, ProspectingState>,
+    query: String,
+    top_k: Option<usize>,
+) -> AppResult<Vec<SemanticMatch>> {
+    crate::core::plugin_isolation::guard("prospecting", semantic_search_comments_impl(services, state, query, top_k)).await
+}
+
+async fn semantic_search_comments_impl(
+    services: State<'_, SharedAppServices>,
+    state: State<'_, ProspectingState>,
+    query: String,
+    top_k: Option<usize>,
+) -> AppResult<Vec<SemanticMatch>> {
+    let settings = services.ai.settings_with_keys()?;
+    let model = settings.default_embed_model.clone();
+    let router = AIRouter::new(settings);
+
+    search_comments_by_embedding(&state, &model, query, top_k.unwrap_or(DEFAULT_SEARCH_TOP_K), |input| {
+        concurrency::run(WorkRequest::ai("prospecting_search", "查询向量化"), router.embed(&model, input))
+    })
+    .await
+}
+
+/// 按语义把已向量化的评论聚成 k 类
+#[tauri::command]
+async fn cluster_comments(
+    services: State<'_, SharedAppServices>,
+    state: State<'_, ProspectingState>,
+    k: usize,
+) -> AppResult<Vec<CommentCluster>> {
+    crate::core::plugin_isolation::guard("prospecting", cluster_comments_impl(services, state, k)).await
+}
+
+async fn cluster_comments_impl(
+    services: State<'_, SharedAppServices>,
+    state: State<'_, ProspectingState>,
+    k: usize,
+) -> AppResult<Vec<CommentCluster>> {
+    let model = services.ai.settings.read().default_embed_model.clone();
+
+    state.with_service(|service| {
+        service.cluster_comments(&model, k)
+    }).map_err(AppError::from)
+}
+
+/// 获取关键词规则集
+#[tauri::command]
+async fn get_keyword_rules(
+    state: State<'_, ProspectingState>,
+) -> AppResult<RuleSet> {
+    crate::core::plugin_isolation::guard("prospecting", get_keyword_rules_impl(state)).await
+}
+
+async fn get_keyword_rules_impl(
+    state: State<'_, ProspectingState>,
+) -> AppResult<RuleSet> {
+    state.with_service(|service| {
+        Ok(service.get_rule_set())
+    }).map_err(AppError::from)
+}
+
+/// 保存关键词规则集（任一规则无效时拒绝保存）
+#[tauri::command]
+async fn save_keyword_rules(
+    state: State<'_, ProspectingState>,
+    rule_set: RuleSet,
+) -> AppResult<()> {
+    crate::core::plugin_isolation::guard("prospecting", save_keyword_rules_impl(state, rule_set)).await
+}
+
+async fn save_keyword_rules_impl(
+    state: State<'_, ProspectingState>,
+    rule_set: RuleSet,
+) -> AppResult<()> {
+    state.with_service(|service| {
+        service.save_rule_set(rule_set)
+    }).map_err(AppError::from)
+}
+
+/// 规则测试台：用样例文本试跑规则（可传入未保存的规则集）
+#[tauri::command]
+async fn test_keyword_rules(
+    state: State<'_, ProspectingState>,
+    samples: Vec<String>,
+    rule_set: Option<RuleSet>,
+) -> AppResult<Vec<RuleTestResult>> {
+    crate::core::plugin_isolation::guard("prospecting", test_keyword_rules_impl(state, samples, rule_set)).await
+}
+
+async fn test_keyword_rules_impl(
+    state: State<'_, ProspectingState>,
+    samples: Vec<String>,
+    rule_set: Option<RuleSet>,
+) -> AppResult<Vec<RuleTestResult>> {
+    state.with_service(|service| {
+        service.test_rules(rule_set.as_ref(), &samples)
+    }).map_err(AppError::from)
+}
+
+/// 对评论应用关键词规则；`comment_ids` 为空时处理全部评论
+#[tauri::command]
+async fn apply_keyword_rules(
+    state: State<'_, ProspectingState>,
+    comment_ids: Option<Vec<String>>,
+) -> AppResult<RuleApplyReport> {
+    crate::core::plugin_isolation::guard("prospecting", apply_keyword_rules_impl(state, comment_ids)).await
+}
+
+async fn apply_keyword_rules_impl(
+    state: State<'_, ProspectingState>,
+    comment_ids: Option<Vec<String>>,
+) -> AppResult<RuleApplyReport> {
+    state.with_service(|service| {
+        service.apply_rules(comment_ids.as_deref())
+    }).map_err(AppError::from)
+}
+
+fn import_profiles_path<R: Runtime>(app: &AppHandle<R>) -> Result<PathBuf, String> {
+    let data_dir = app.path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data dir: {}", e))?;
+    std::fs::create_dir_all(&data_dir).map_err(|e| e.to_string())?;
+    Ok(data_dir.join(IMPORT_PROFILES_FILE_NAME))
+}
+
+/// 在阻塞线程中读取导入文件
+async fn read_import_table(path: String, profile: &ImportProfile) -> Result<RawTable, String> {
+    let options = profile.read.clone();
+    tokio::task::spawn_blocking(move || read_table(std::path::Path::new(&path), &options))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())
+}
+
+/// 已保存的导入配置
+#[tauri::command]
+async fn list_import_profiles<R: Runtime>(app: AppHandle<R>) -> AppResult<Vec<ImportProfile>> {
+    crate::core::plugin_isolation::guard("prospecting", async move { ingestion::load_profiles(&import_profiles_path(&app)?).map_err(AppError::from) }).await
+}
+
+/// 保存导入配置（同名覆盖），返回全部配置
+#[tauri::command]
+async fn save_import_profile<R: Runtime>(
+    app: AppHandle<R>,
+    profile: ImportProfile,
+) -> AppResult<Vec<ImportProfile>> {
+    crate::core::plugin_isolation::guard("prospecting", async move { ingestion::upsert_profile(&import_profiles_path(&app)?, profile).map_err(AppError::from) }).await
+}
+
+/// 删除导入配置
+#[tauri::command]
+async fn delete_import_profile<R: Runtime>(app: AppHandle<R>, name: String) -> AppResult<bool> {
+    crate::core::plugin_isolation::guard("prospecting", async move { ingestion::delete_profile(&import_profiles_path(&app)?, &name).map_err(AppError::from) }).await
+}
+
+/// 导入前预览：表头、识别的编码、映射结果样例与行级错误
+#[tauri::command]
+async fn preview_comment_import(
+    path: String,
+    profile: Option<ImportProfile>,
+) -> AppResult<ImportPreview> {
+    crate::core::plugin_isolation::guard("prospecting", preview_comment_import_impl(path, profile)).await
+}
+
+async fn preview_comment_import_impl(
+    path: String,
+    profile: Option<ImportProfile>,
+) -> AppResult<ImportPreview> {
+    let profile = profile.unwrap_or_default();
+    let table = read_import_table(path, &profile).await?;
+    Ok(ingestion::preview(&table, &profile))
+}
+
+/// 从导出文件导入评论（分块写入，推送进度事件）
+#[tauri::command]
+async fn import_comments_from_file<R: Runtime>(
+    app: AppHandle<R>,
+    state: State<'_, ProspectingState>,
+    path: String,
+    profile: Option<ImportProfile>,
+) -> AppResult<ImportReport> {
+    crate::core::plugin_isolation::guard("prospecting", import_comments_from_file_impl(app, state, path, profile)).await
+}
+
+async fn import_comments_from_file_impl<R: Runtime>(
+    app: AppHandle<R>,
+    state: State<'_, ProspectingState>,
+    path: String,
+    profile: Option<ImportProfile>,
+) -> AppResult<ImportReport> {
+    let profile = profile.unwrap_or_default();
+    let table = read_import_table(path, &profile).await?;
+
+    let report = ingestion::import_rows(
+        &table,
+        &profile,
+        |chunk| state.with_service(|service| service.import_comments(chunk)),
+        |processed, total| {
+            let _ = app.emit(IMPORT_PROGRESS_EVENT, serde_json::json!({ "processed": processed, "total": total }));
+        },
+    ).map_err(|e| e.to_string())?;
+
+    tracing::info!(
+        "📥 评论导入完成: 共 {} 行，新增 {}，重复 {}，跳过 {}",
+        report.total_rows, report.imported, report.duplicates, report.skipped
+    );
+    Ok(report)
 }
 
 #[tauri::command]
@@ -151,8 +672,8 @@ async fn assign_tasks_to_device(
     _device_id: String,
     _task_ids: Vec<String>,
     _assigned_at: String,
-) -> Result<(), String> {
-    Ok(())
+) -> AppResult<()> {
+    crate::core::plugin_isolation::guard("prospecting", async move { Ok(()) }).await
 }
 
 #[tauri::command]
@@ -161,8 +682,8 @@ async fn update_task_status(
     _status: String,
     _error_message: Option<String>,
     _updated_at: String,
-) -> Result<(), String> {
-    Ok(())
+) -> AppResult<()> {
+    crate::core::plugin_isolation::guard("prospecting", async move { Ok(()) }).await
 }
 
 #[tauri::command]
@@ -170,21 +691,21 @@ async fn cancel_task(
     _task_id: String,
     _reason: String,
     _cancelled_at: String,
-) -> Result<(), String> {
-    Ok(())
+) -> AppResult<()> {
+    crate::core::plugin_isolation::guard("prospecting", async move { Ok(()) }).await
 }
 
 #[tauri::command]
 async fn get_rate_control_stats(
     _device_id: String,
     _since: String,
-) -> Result<Value, String> {
-    Ok(serde_json::json!({}))
+) -> AppResult<Value> {
+    crate::core::plugin_isolation::guard("prospecting", async move { Ok(serde_json::json!({})) }).await
 }
 
 #[tauri::command]
-async fn get_device_id() -> Result<String, String> {
-    Ok("mock_device_id".to_string())
+async fn get_device_id() -> AppResult<String> {
+    crate::core::plugin_isolation::guard("prospecting", async move { Ok("mock_device_id".to_string()) }).await
 }
 
 #[tauri::command]
@@ -194,15 +715,15 @@ async fn get_cross_device_operations(
     _target_user_id: Option<String>,
     _content: Option<String>,
     _exclude_device_id: String,
-) -> Result<Vec<Value>, String> {
-    Ok(vec![])
+) -> AppResult<Vec<Value>> {
+    crate::core::plugin_isolation::guard("prospecting", async move { Ok(vec![]) }).await
 }
 
 #[tauri::command]
 async fn record_operation(
     _operation: Value,
-) -> Result<(), String> {
-    Ok(())
+) -> AppResult<()> {
+    crate::core::plugin_isolation::guard("prospecting", async move { Ok(()) }).await
 }
 
 #[tauri::command]
@@ -210,155 +731,184 @@ async fn get_operation_history(
     _platform: String,
     _task_type: String,
     _limit: i32,
-) -> Result<Vec<Value>, String> {
-    Ok(vec![])
+) -> AppResult<Vec<Value>> {
+    crate::core::plugin_isolation::guard("prospecting", async move { Ok(vec![]) }).await
 }
 
 #[tauri::command]
 async fn calculate_content_hash(
     _content: String,
-) -> Result<String, String> {
-    Ok("mock_hash".to_string())
+) -> AppResult<String> {
+    crate::core::plugin_isolation::guard("prospecting", async move { Ok("mock_hash".to_string()) }).await
 }
 
 #[tauri::command]
 async fn calculate_content_similarity(
     _content1: String,
     _content_hash2: String,
-) -> Result<f64, String> {
-    Ok(0.0)
+) -> AppResult<f64> {
+    crate::core::plugin_isolation::guard("prospecting", async move { Ok(0.0) }).await
 }
 
 #[tauri::command]
 async fn sync_operations_to_cloud(
     _operations: Vec<Value>,
-) -> Result<(), String> {
-    Ok(())
+) -> AppResult<()> {
+    crate::core::plugin_isolation::guard("prospecting", async move { Ok(()) }).await
 }
 
 #[tauri::command]
 async fn sync_operations_from_cloud(
     _device_id: String,
     _since_duration: i32,
-) -> Result<Vec<Value>, String> {
-    Ok(vec![])
+) -> AppResult<Vec<Value>> {
+    crate::core::plugin_isolation::guard("prospecting", async move { Ok(vec![]) }).await
 }
 
 #[tauri::command]
 async fn cleanup_expired_operations(
     _cutoff_time: String,
-) -> Result<i32, String> {
-    Ok(0)
+) -> AppResult<i32> {
+    crate::core::plugin_isolation::guard("prospecting", async move { Ok(0) }).await
+}
+
+/// 精准获客写命令走 marketing_storage（带幂等键），SQLite 写入放到阻塞线程池
+async fn run_marketing_write<T, F>(f: F) -> AppResult<T>
+where
+    T: Send + 'static,
+    F: FnOnce() -> Result<T, String> + Send + 'static,
+{
+    tokio::task::spawn_blocking(f)
+        .await
+        .map_err(|e| AppError::internal(format!("写入任务执行失败: {}", e)))?
+        .map_err(AppError::from)
 }
 
 #[tauri::command]
-async fn bulk_upsert_watch_targets(_payloads: Vec<Value>) -> Result<(), String> {
-    Ok(())
+async fn bulk_upsert_watch_targets(
+    app_handle: AppHandle,
+    payloads: Vec<WatchTargetPayload>,
+    idempotency_key: Option<String>,
+) -> AppResult<usize> {
+    crate::core::plugin_isolation::guard("prospecting", async move { run_marketing_write(move || marketing::bulk_upsert_watch_targets(app_handle, payloads, idempotency_key)).await }).await
 }
 
 #[tauri::command]
-async fn list_watch_targets(_limit: Option<i32>, _offset: Option<i32>) -> Result<Vec<Value>, String> {
-    Ok(vec![])
+async fn list_watch_targets(_limit: Option<i32>, _offset: Option<i32>) -> AppResult<Vec<Value>> {
+    crate::core::plugin_isolation::guard("prospecting", async move { Ok(vec![]) }).await
 }
 
 #[tauri::command]
-async fn get_watch_target_by_dedup_key(_dedup_key: String) -> Result<Option<Value>, String> {
-    Ok(None)
+async fn get_watch_target_by_dedup_key(_dedup_key: String) -> AppResult<Option<Value>> {
+    crate::core::plugin_isolation::guard("prospecting", async move { Ok(None) }).await
 }
 
 #[tauri::command]
-async fn insert_task(_task: Value) -> Result<String, String> {
-    Ok("stub_task_id".to_string())
+async fn insert_task(app_handle: AppHandle, task: TaskPayload, idempotency_key: Option<String>) -> AppResult<String> {
+    crate::core::plugin_isolation::guard("prospecting", async move { run_marketing_write(move || marketing::insert_task(app_handle, task, idempotency_key)).await }).await
 }
 
 #[tauri::command]
-async fn list_tasks(_status: Option<String>, _limit: Option<i32>) -> Result<Vec<Value>, String> {
-    Ok(vec![])
+async fn list_tasks(_status: Option<String>, _limit: Option<i32>) -> AppResult<Vec<Value>> {
+    crate::core::plugin_isolation::guard("prospecting", async move { Ok(vec![]) }).await
 }
 
 #[tauri::command]
-async fn get_task_progress(_task_id: String) -> Result<Value, String> {
-    Ok(serde_json::json!({}))
+async fn get_task_progress(_task_id: String) -> AppResult<Value> {
+    crate::core::plugin_isolation::guard("prospecting", async move { Ok(serde_json::json!({})) }).await
 }
 
 #[tauri::command]
-async fn submit_acquisition_task(_data: Value) -> Result<Value, String> {
-    Ok(serde_json::json!({}))
+async fn submit_acquisition_task(_data: Value) -> AppResult<Value> {
+    crate::core::plugin_isolation::guard("prospecting", async move { Ok(serde_json::json!({})) }).await
 }
 
 #[tauri::command]
-async fn pause_task(_task_id: i64) -> Result<bool, String> {
-    Ok(true)
+async fn pause_task(_task_id: i64) -> AppResult<bool> {
+    crate::core::plugin_isolation::guard("prospecting", async move { Ok(true) }).await
 }
 
 #[tauri::command]
-async fn resume_task(_task_id: i64) -> Result<bool, String> {
-    Ok(true)
+async fn resume_task(_task_id: i64) -> AppResult<bool> {
+    crate::core::plugin_isolation::guard("prospecting", async move { Ok(true) }).await
 }
 
 #[tauri::command]
-async fn stop_task(_task_id: i64) -> Result<bool, String> {
-    Ok(true)
+async fn stop_task(_task_id: i64) -> AppResult<bool> {
+    crate::core::plugin_isolation::guard("prospecting", async move { Ok(true) }).await
 }
 
 #[tauri::command]
-async fn check_and_reserve_dedup(_key: String) -> Result<bool, String> {
-    Ok(true)
+async fn check_and_reserve_dedup(_key: String) -> AppResult<bool> {
+    crate::core::plugin_isolation::guard("prospecting", async move { Ok(true) }).await
 }
 
 #[tauri::command]
-async fn insert_comment(_comment: Value) -> Result<String, String> {
-    Ok("stub_id".to_string())
+async fn insert_comment(
+    app_handle: AppHandle,
+    comment: CommentPayload,
+    idempotency_key: Option<String>,
+) -> AppResult<String> {
+    crate::core::plugin_isolation::guard("prospecting", async move { run_marketing_write(move || marketing::insert_comment(app_handle, comment, idempotency_key)).await }).await
 }
 
 #[tauri::command]
-async fn list_comments(_filter: Value) -> Result<Vec<Value>, String> {
-    Ok(vec![])
+async fn list_comments(_filter: Value) -> AppResult<Vec<Value>> {
+    crate::core::plugin_isolation::guard("prospecting", async move { Ok(vec![]) }).await
 }
 
 #[tauri::command]
-async fn insert_daily_report(_report: Value) -> Result<(), String> {
-    Ok(())
+async fn insert_daily_report(_report: Value) -> AppResult<()> {
+    crate::core::plugin_isolation::guard("prospecting", async move { Ok(()) }).await
 }
 
 #[tauri::command]
-async fn insert_audit_log(_log: Value) -> Result<(), String> {
-    Ok(())
+async fn insert_audit_log(
+    app_handle: AppHandle,
+    log: AuditLogPayload,
+    idempotency_key: Option<String>,
+) -> AppResult<String> {
+    crate::core::plugin_isolation::guard("prospecting", async move { run_marketing_write(move || marketing::insert_audit_log(app_handle, log, idempotency_key)).await }).await
 }
 
 #[tauri::command]
-async fn get_comment_by_id(_id: String) -> Result<Option<Value>, String> {
-    Ok(None)
+async fn cleanup_expired_idempotency_keys(app_handle: AppHandle) -> AppResult<usize> {
+    crate::core::plugin_isolation::guard("prospecting", async move { run_marketing_write(move || marketing::cleanup_expired_idempotency_keys(app_handle)).await }).await
 }
 
 #[tauri::command]
-async fn get_watch_target_by_id(_id: String) -> Result<Option<Value>, String> {
-    Ok(None)
+async fn get_comment_by_id(_id: String) -> AppResult<Option<Value>> {
+    crate::core::plugin_isolation::guard("prospecting", async move { Ok(None) }).await
 }
 
 #[tauri::command]
-async fn init_precise_acquisition_storage() -> Result<(), String> {
-    Ok(())
+async fn get_watch_target_by_id(_id: String) -> AppResult<Option<Value>> {
+    crate::core::plugin_isolation::guard("prospecting", async move { Ok(None) }).await
+}
+
+#[tauri::command]
+async fn init_precise_acquisition_storage() -> AppResult<()> {
+    crate::core::plugin_isolation::guard("prospecting", async move { Ok(()) }).await
 }
 
 #[tauri::command]
-async fn get_reply_templates() -> Result<Vec<Value>, String> {
-    Ok(vec![])
+async fn get_reply_templates() -> AppResult<Vec<Value>> {
+    crate::core::plugin_isolation::guard("prospecting", async move { Ok(vec![]) }).await
 }
 
 #[tauri::command]
-async fn add_reply_template(_template: Value) -> Result<String, String> {
-    Ok("stub_id".to_string())
+async fn add_reply_template(_template: Value) -> AppResult<String> {
+    crate::core::plugin_isolation::guard("prospecting", async move { Ok("stub_id".to_string()) }).await
 }
 
 #[tauri::command]
-async fn get_precise_acquisition_stats() -> Result<Value, String> {
-    Ok(serde_json::json!({}))
+async fn get_precise_acquisition_stats() -> AppResult<Value> {
+    crate::core::plugin_isolation::guard("prospecting", async move { Ok(serde_json::json!({})) }).await
 }
 
 #[tauri::command]
-async fn generate_daily_report(_date: String) -> Result<Value, String> {
-    Ok(serde_json::json!({}))
+async fn generate_daily_report(_date: String) -> AppResult<Value> {
+    crate::core::plugin_isolation::guard("prospecting", async move { Ok(serde_json::json!({})) }).await
 }
 
 #[tauri::command]
@@ -370,7 +920,19 @@ async fn get_collected_comments(
     _region: Option<String>,
     _min_like_count: Option<i32>,
     _time_range: Option<Value>,
-) -> Result<Value, String> {
+) -> AppResult<Value> {
+    crate::core::plugin_isolation::guard("prospecting", get_collected_comments_impl(_limit, _offset, _platform, _source_target_id, _region, _min_like_count, _time_range)).await
+}
+
+async fn get_collected_comments_impl(
+    _limit: Option<i32>,
+    _offset: Option<i32>,
+    _platform: Option<String>,
+    _source_target_id: Option<String>,
+    _region: Option<String>,
+    _min_like_count: Option<i32>,
+    _time_range: Option<Value>,
+) -> AppResult<Value> {
     Ok(serde_json::json!({
         "comments": [],
         "total": 0
@@ -383,7 +945,16 @@ async fn schedule_auto_collection(
     _interval_hours: i32,
     _max_comments_per_target: i32,
     _respect_rate_limits: bool,
-) -> Result<Value, String> {
+) -> AppResult<Value> {
+    crate::core::plugin_isolation::guard("prospecting", schedule_auto_collection_impl(_targets, _interval_hours, _max_comments_per_target, _respect_rate_limits)).await
+}
+
+async fn schedule_auto_collection_impl(
+    _targets: Vec<String>,
+    _interval_hours: i32,
+    _max_comments_per_target: i32,
+    _respect_rate_limits: bool,
+) -> AppResult<Value> {
     Ok(serde_json::json!({
         "scheduled": true,
         "next_run_time": "2024-01-01T00:00:00Z",
@@ -398,8 +969,8 @@ async fn save_collection_result(
     _comments: Vec<Value>,
     _total_count: i32,
     _collected_at: String,
-) -> Result<(), String> {
-    Ok(())
+) -> AppResult<()> {
+    crate::core::plugin_isolation::guard("prospecting", async move { Ok(()) }).await
 }
 
 #[tauri::command]
@@ -408,32 +979,63 @@ async fn log_collection_error(
     _platform: String,
     _error: String,
     _timestamp: String,
-) -> Result<(), String> {
-    Ok(())
+) -> AppResult<()> {
+    crate::core::plugin_isolation::guard("prospecting", async move { Ok(()) }).await
 }
 
 #[tauri::command]
-async fn save_safety_config(_config: Value) -> Result<(), String> {
-    Ok(())
+async fn save_safety_config(_config: Value) -> AppResult<()> {
+    crate::core::plugin_isolation::guard("prospecting", async move { Ok(()) }).await
 }
 
-pub fn init<R: Runtime>() -> TauriPlugin<R> {
-    Builder::<R>::new("prospecting")
+pub fn init() -> TauriPlugin<Wry> {
+    Builder::<Wry>::new("prospecting")
         .setup(|app, _api| {
-            app.manage(ProspectingState::new());
+            plugin_bootstrap::registry().track_setup("prospecting", || {
+                let state = ProspectingState::new();
+                if let Ok(data_dir) = app.path().app_data_dir() {
+                    state.set_data_dir(data_dir);
+                }
+                app.manage(state);
+                Ok::<_, String>(())
+            })?;
             Ok(())
         })
-        .invoke_handler(tauri::generate_handler![
+        .invoke_handler(crate::core::plugin_isolation::isolate("prospecting", tauri::generate_handler![
             init_storage,
             save_comment,
             get_comments,
+            export_leads,
             get_comments_by_ids,
             save_analysis,
             save_reply_plan,
             get_reply_plans,
             get_reply_plans_by_ids,
+            schedule_reply_plans,
+            get_due_reply_plans,
+            list_content_calendar,
+            save_content_calendar_entry,
+            delete_content_calendar_entry,
             execute_real_reply_plan,
+            list_media_assets,
+            import_media_asset,
+            approve_media_asset,
+            delete_media_asset,
+            list_media_sends,
+            attach_reply_media,
             get_statistics,
+            index_comment_embeddings,
+            semantic_search_comments,
+            cluster_comments,
+            get_keyword_rules,
+            save_keyword_rules,
+            test_keyword_rules,
+            apply_keyword_rules,
+            list_import_profiles,
+            save_import_profile,
+            delete_import_profile,
+            preview_comment_import,
+            import_comments_from_file,
             assign_tasks_to_device,
             update_task_status,
             cancel_task,
@@ -462,6 +1064,7 @@ pub fn init<R: Runtime>() -> TauriPlugin<R> {
             list_comments,
             insert_daily_report,
             insert_audit_log,
+            cleanup_expired_idempotency_keys,
             get_comment_by_id,
             get_watch_target_by_id,
             init_precise_acquisition_storage,
@@ -474,6 +1077,62 @@ pub fn init<R: Runtime>() -> TauriPlugin<R> {
             save_collection_result,
             log_collection_error,
             save_safety_config
-        ])
+        ]))
         .build()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::prospecting::SocialPlatform;
+
+    fn raw_comment(id: &str, content: &str) -> RawComment {
+        RawComment {
+            id: id.to_string(),
+            platform: SocialPlatform::Douyin,
+            video_url: None,
+            author: "测试用户".to_string(),
+            content: content.to_string(),
+            timestamp: None,
+            avatar_url: None,
+            like_count: None,
+            metadata: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn semantic_search_embeds_the_query_and_ranks_indexed_comments() {
+        let dir = tempfile::tempdir().unwrap();
+        let state = ProspectingState::new();
+        state.init_service(dir.path().to_path_buf()).unwrap();
+        state
+            .with_service(|service| {
+                service.import_comments(&[raw_comment("c1", "求购链接"), raw_comment("c2", "路过看看")])?;
+                service.save_embeddings(
+                    "test-embed",
+                    &[
+                        ("c1".to_string(), "求购链接".to_string(), vec![1.0, 0.0]),
+                        ("c2".to_string(), "路过看看".to_string(), vec![0.0, 1.0]),
+                    ],
+                )
+            })
+            .unwrap();
+
+        let matches = search_comments_by_embedding(&state, "test-embed", "怎么购买".to_string(), 1, |input| async move {
+            assert_eq!(input, vec!["怎么购买".to_string()]);
+            Ok(vec![vec![0.9, 0.1]])
+        })
+        .await
+        .unwrap();
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].comment.raw.id, "c1");
+
+        // 空查询不调用 AI
+        let empty = search_comments_by_embedding(&state, "test-embed", "  ".to_string(), 5, |_| async {
+            Err(anyhow::anyhow!("空查询不应向量化"))
+        })
+        .await
+        .unwrap();
+        assert!(empty.is_empty());
+    }
+}
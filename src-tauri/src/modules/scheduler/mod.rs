@@ -0,0 +1,86 @@
+// src-tauri/src/modules/scheduler/mod.rs
+// module: scheduler | layer: api | role: Scheduler Plugin
+// summary: 脚本定时执行插件，按设备登记智能脚本的 cron 计划并查看运行记录
+
+use chrono::{DateTime, Local, Utc};
+use tauri::{
+    plugin::{Builder, TauriPlugin},
+    AppHandle, Runtime, State,
+};
+
+use crate::services::script_manager::ScriptManagerState;
+use crate::services::script_scheduler::{
+    self, cron::CronExpr, ScheduleRun, ScheduleStore, ScheduleUpdate, ScriptSchedule,
+};
+
+const DEFAULT_RUN_LIMIT: usize = 50;
+const MAX_CRON_PREVIEW: usize = 20;
+
+/// 新建定时计划：脚本需已保存在脚本库中
+#[tauri::command]
+fn create_script_schedule(
+    scripts: State<'_, ScriptManagerState>,
+    script_id: String,
+    device_id: String,
+    cron: String,
+    enabled: Option<bool>,
+) -> Result<ScriptSchedule, String> {
+    scripts.0.lock().load_script(&script_id).map_err(|e| format!("加载脚本失败: {}", e))?;
+    let store = ScheduleStore::global().lock().map_err(|e| e.to_string())?;
+    store.create(&script_id, &device_id, &cron, enabled.unwrap_or(true), Utc::now())
+}
+
+#[tauri::command]
+fn update_script_schedule(schedule_id: String, update: ScheduleUpdate) -> Result<ScriptSchedule, String> {
+    let store = ScheduleStore::global().lock().map_err(|e| e.to_string())?;
+    store.update(&schedule_id, update, Utc::now())
+}
+
+/// 删除计划及其运行记录
+#[tauri::command]
+fn delete_script_schedule(schedule_id: String) -> Result<bool, String> {
+    ScheduleStore::global().lock().map_err(|e| e.to_string())?.delete(&schedule_id)
+}
+
+#[tauri::command]
+fn list_script_schedules() -> Result<Vec<ScriptSchedule>, String> {
+    ScheduleStore::global().lock().map_err(|e| e.to_string())?.list()
+}
+
+/// 运行记录（新的在前），可按计划过滤
+#[tauri::command]
+fn list_schedule_runs(schedule_id: Option<String>, limit: Option<usize>) -> Result<Vec<ScheduleRun>, String> {
+    let store = ScheduleStore::global().lock().map_err(|e| e.to_string())?;
+    store.runs(schedule_id.as_deref(), limit.unwrap_or(DEFAULT_RUN_LIMIT))
+}
+
+/// 立即执行一次计划，不改变下次触发时间
+#[tauri::command]
+fn run_script_schedule_now<R: Runtime>(app: AppHandle<R>, schedule_id: String) -> Result<ScheduleRun, String> {
+    script_scheduler::run_now(&app, &schedule_id)
+}
+
+/// 校验 cron 表达式并预览接下来的触发时间（本地时间）
+#[tauri::command]
+fn preview_cron(cron: String, count: Option<usize>) -> Result<Vec<DateTime<Local>>, String> {
+    let expr = CronExpr::parse(&cron)?;
+    Ok(expr.upcoming(Local::now(), count.unwrap_or(5).clamp(1, MAX_CRON_PREVIEW)))
+}
+
+pub fn init<R: Runtime>() -> TauriPlugin<R> {
+    Builder::new("scheduler")
+        .setup(|app, _api| {
+            tauri::async_runtime::spawn(script_scheduler::run_scheduler(app.clone()));
+            Ok(())
+        })
+        .invoke_handler(crate::core::plugin_isolation::isolate("scheduler", tauri::generate_handler![
+            create_script_schedule,
+            update_script_schedule,
+            delete_script_schedule,
+            list_script_schedules,
+            list_schedule_runs,
+            run_script_schedule_now,
+            preview_cron
+        ]))
+        .build()
+}
@@ -0,0 +1,73 @@
+// src-tauri/src/modules/script_manager/debug.rs
+// module: script_manager | layer: commands | role: debug-commands
+// summary: 脚本调试执行命令 - 断点 / 单步运行，暂停时继续、单步、中止与现场检查
+
+use tauri::{AppHandle, Emitter, Runtime};
+use tracing::info;
+
+use crate::services::execution::debug_session::{get_session, DebugCommand, DebugInspection, DebugOptions, DebugSession};
+use crate::services::execution::model::{SmartExecutionResult, SmartExecutorConfig, SmartScriptStep};
+use crate::services::smart_script_executor::SmartScriptExecutor;
+
+/// 暂停事件，载荷为暂停位置的检查结果（含 run_id）
+pub const DEBUG_PAUSED_EVENT: &str = "script-debug://paused";
+
+/// 以调试模式执行整套脚本；每次暂停发出 `script-debug://paused`，运行结束后返回结果
+#[tauri::command]
+pub async fn execute_smart_automation_script_debug<R: Runtime>(
+    app: AppHandle<R>,
+    device_id: String,
+    steps: Vec<SmartScriptStep>,
+    config: Option<SmartExecutorConfig>,
+    debug: DebugOptions,
+) -> Result<SmartExecutionResult, String> {
+    crate::core::plugin_isolation::guard("script_manager", execute_smart_automation_script_debug_impl(app, device_id, steps, config, debug)).await
+}
+
+async fn execute_smart_automation_script_debug_impl<R: Runtime>(
+    app: AppHandle<R>,
+    device_id: String,
+    steps: Vec<SmartScriptStep>,
+    config: Option<SmartExecutorConfig>,
+    debug: DebugOptions,
+) -> Result<SmartExecutionResult, String> {
+    info!(
+        "🐞 调试执行智能脚本: 设备 {}, {} 个步骤, 断点 {:?}, 逐步暂停 {}",
+        device_id,
+        steps.len(),
+        debug.breakpoints,
+        debug.pause_every_step
+    );
+    let on_pause = Box::new(move |inspection: &DebugInspection| {
+        let _ = app.emit(DEBUG_PAUSED_EVENT, inspection);
+    });
+    let session = DebugSession::start(&device_id, debug, Some(on_pause));
+    let executor = SmartScriptExecutor::new(device_id);
+    let result = executor.execute_smart_script_debug(steps, config, session.clone()).await;
+    session.finish();
+    result.map_err(|e| format!("调试执行失败: {}", e))
+}
+
+/// 继续运行到下一个断点
+#[tauri::command]
+pub async fn debug_continue(run_id: String) -> Result<(), String> {
+    crate::core::plugin_isolation::guard("script_manager", async move { get_session(&run_id)?.command(DebugCommand::Continue) }).await
+}
+
+/// 执行当前步骤后再次暂停
+#[tauri::command]
+pub async fn debug_step_over(run_id: String) -> Result<(), String> {
+    crate::core::plugin_isolation::guard("script_manager", async move { get_session(&run_id)?.command(DebugCommand::StepOver) }).await
+}
+
+/// 中止调试运行（暂停中或运行中均可）
+#[tauri::command]
+pub async fn debug_abort(run_id: String) -> Result<(), String> {
+    crate::core::plugin_isolation::guard("script_manager", async move { get_session(&run_id)?.command(DebugCommand::Abort) }).await
+}
+
+/// 查看暂停位置：变量、最近一次 dump 句柄与上一步匹配详情
+#[tauri::command]
+pub async fn debug_inspect(run_id: String) -> Result<DebugInspection, String> {
+    crate::core::plugin_isolation::guard("script_manager", async move { Ok(get_session(&run_id)?.inspect()) }).await
+}
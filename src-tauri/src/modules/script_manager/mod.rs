@@ -6,13 +6,29 @@ use crate::services::script_manager::*;
 use crate::services::commands::*;
 use crate::services::script_manager::ScriptManagerState;
 
+mod debug;
+pub(crate) mod prevalidate;
+mod recorder;
+mod reference_graph;
+mod sandbox;
+mod search;
+use debug::*;
+use prevalidate::*;
+use recorder::*;
+use reference_graph::*;
+use sandbox::*;
+use search::*;
+
 pub fn init<R: Runtime>() -> TauriPlugin<R> {
     Builder::new("script_manager")
         .setup(|app, _api| {
-            app.manage(ScriptManagerState::new());
+            crate::core::plugin_bootstrap::registry().track_setup("script_manager", || {
+                app.manage(ScriptManagerState::new());
+                Ok::<_, String>(())
+            })?;
             Ok(())
         })
-        .invoke_handler(tauri::generate_handler![
+        .invoke_handler(crate::core::plugin_isolation::isolate("script_manager", tauri::generate_handler![
             save_smart_script,
             load_smart_script,
             delete_smart_script,
@@ -23,7 +39,28 @@ pub fn init<R: Runtime>() -> TauriPlugin<R> {
             create_script_from_template,
             execute_single_step_test,
             execute_smart_automation_script,
-            execute_smart_automation_script_multi
-        ])
+            execute_smart_automation_script_multi,
+            execute_smart_automation_script_parallel,
+            execute_smart_automation_script_debug,
+            debug_continue,
+            debug_step_over,
+            debug_abort,
+            debug_inspect,
+            list_sandbox_profiles,
+            save_sandbox_profile,
+            delete_sandbox_profile,
+            bind_sandbox_profile,
+            set_device_sandbox_groups,
+            list_active_sandboxes,
+            check_sandbox_action,
+            search_scripts,
+            export_reference_graph,
+            get_reference_blast_radius,
+            prevalidate_script,
+            start_recording,
+            stop_recording,
+            get_recording,
+            export_recording_as_script
+        ]))
         .build()
 }
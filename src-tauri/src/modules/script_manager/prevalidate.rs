@@ -0,0 +1,450 @@
+// src-tauri/src/modules/script_manager/prevalidate.rs
+// module: script_manager | layer: commands | role: prevalidate
+// summary: 脚本执行前的批量预校验 - 不连设备，把每个步骤的选择器回放到它绑定的缓存快照上，标记快照缺失、选择器不再唯一 / 找不到、策略未注册的步骤
+
+use regex::Regex;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::{BTreeSet, HashMap};
+use tauri::State;
+
+use super::reference_graph::collect_snapshot_refs;
+use crate::engine::strategy_plugin::STRATEGY_REGISTRY;
+use crate::engine::ui_tree::{Attr, NodeRef, UiTree};
+use crate::services::execution::matching::matching_strategies::{extract_matching_context, is_registered_strategy};
+use crate::services::execution::model::SmartScriptStep;
+use crate::services::script_manager::ScriptManagerState;
+
+/// 允许命中多个元素的选择模式（smartSelection.mode）
+const MULTI_SELECTION_MODES: &[&str] = &["first", "last", "random", "all"];
+
+/// 步骤就绪状态
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum StepReadiness {
+    Ready,
+    /// 存在需要处理的问题
+    Flagged,
+    /// 已禁用，或动作不需要定位元素
+    Skipped,
+    /// 选择器只能在真机上判断（如纯位置 XPath）
+    Unverifiable,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum PrevalidationIssue {
+    /// 没有绑定快照，或绑定的快照已不在缓存中
+    #[serde(rename_all = "camelCase")]
+    SnapshotMissing { snapshot_id: Option<String> },
+    /// 匹配策略未注册（运行时会被静默回退）
+    UnknownStrategy { strategy: String },
+    InvalidSelector { reason: String },
+    /// 快照中找不到选择器对应的元素
+    SelectorNotFound,
+    /// 快照中命中多个元素，而步骤要求唯一
+    #[serde(rename_all = "camelCase")]
+    SelectorAmbiguous { match_count: usize },
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StepPrevalidation {
+    pub step_id: String,
+    pub step_name: String,
+    pub order: i32,
+    pub readiness: StepReadiness,
+    pub strategy: Option<String>,
+    pub snapshot_id: Option<String>,
+    /// 快照中命中的元素数（未回放时为 None）
+    pub match_count: Option<usize>,
+    pub issues: Vec<PrevalidationIssue>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScriptPrevalidationReport {
+    pub script_id: String,
+    pub script_name: String,
+    pub total_steps: usize,
+    pub ready_steps: usize,
+    pub flagged_steps: usize,
+    pub skipped_steps: usize,
+    pub unverifiable_steps: usize,
+    pub steps: Vec<StepPrevalidation>,
+    pub validated_at: String,
+}
+
+/// 单个属性约束
+enum Constraint {
+    Equals(Attr, String),
+    Contains(Attr, String),
+    NotContains(Attr, String),
+    Matches(Attr, Regex),
+    NotMatches(Attr, Regex),
+}
+
+impl Constraint {
+    fn check(&self, node: &NodeRef<'_>) -> bool {
+        let value = |attr: &Attr| node.attr(*attr).unwrap_or_default();
+        match self {
+            Constraint::Equals(attr, expected) => value(attr) == expected,
+            Constraint::Contains(attr, word) => value(attr).contains(word.as_str()),
+            Constraint::NotContains(attr, word) => !value(attr).contains(word.as_str()),
+            Constraint::Matches(attr, re) => re.is_match(value(attr)),
+            Constraint::NotMatches(attr, re) => !re.is_match(value(attr)),
+        }
+    }
+}
+
+/// 可回放到快照上的选择器
+#[derive(Default)]
+struct OfflineSelector {
+    constraints: Vec<Constraint>,
+    /// XPath 中的子孙文本过滤 `[.//*[@text='…']]`
+    descendant_text: Option<String>,
+    /// 前端临时 ID `element_N`
+    element_index: Option<usize>,
+    /// 含有只能在真机上判断的部分
+    unverifiable: bool,
+}
+
+impl OfflineSelector {
+    fn is_empty(&self) -> bool {
+        self.constraints.is_empty() && self.descendant_text.is_none() && self.element_index.is_none() && !self.unverifiable
+    }
+
+    fn matches(&self, node: &NodeRef<'_>) -> bool {
+        self.element_index.map_or(true, |index| node.id() == index)
+            && self.constraints.iter().all(|c| c.check(node))
+            && self.descendant_text.as_deref().map_or(true, |text| has_descendant_text(node, text))
+    }
+
+    fn count(&self, tree: &UiTree) -> usize {
+        tree.nodes().filter(|node| self.matches(node)).count()
+    }
+}
+
+fn has_descendant_text(node: &NodeRef<'_>, text: &str) -> bool {
+    node.children().any(|child| child.text() == text || child.content_desc() == text || has_descendant_text(&child, text))
+}
+
+fn attr_for_field(field: &str) -> Option<Attr> {
+    Some(match field {
+        "resource-id" | "resource_id" | "resourceId" => Attr::ResourceId,
+        "text" => Attr::Text,
+        "content-desc" | "content_desc" | "contentDesc" => Attr::ContentDesc,
+        "class" | "class_name" | "className" => Attr::Class,
+        "package" => Attr::Package,
+        _ => return None,
+    })
+}
+
+fn compile(pattern: &str) -> Result<Regex, String> {
+    Regex::new(pattern).map_err(|e| format!("正则 {} 无效: {}", pattern, e))
+}
+
+/// 解析 XPath 中可离线判断的部分：末级类名、`@attr='v'`、`contains(@attr,'v')`、子孙文本过滤与 `element_N`
+fn apply_xpath(selector: &mut OfflineSelector, xpath: &str) {
+    let xpath = xpath.trim();
+    if let Some(index) = xpath.trim_start_matches("//").strip_prefix("element_").and_then(|n| n.parse().ok()) {
+        selector.element_index = Some(index);
+        return;
+    }
+    selector.descendant_text = crate::automation::matching::xpath::extract_child_text_filter_from_xpath(xpath);
+    let child_filter = Regex::new(r"\[\.//[^\]]*\]\]").expect("valid regex");
+    let stripped = child_filter.replace_all(xpath, "");
+
+    let before = selector.constraints.len();
+    let equals = Regex::new(r#"@([\w-]+)\s*=\s*(?:'([^']*)'|"([^"]*)")"#).expect("valid regex");
+    let contains = Regex::new(r#"contains\(\s*@([\w-]+)\s*,\s*(?:'([^']*)'|"([^"]*)")\s*\)"#).expect("valid regex");
+    for (re, is_contains) in [(&contains, true), (&equals, false)] {
+        for caps in re.captures_iter(&stripped) {
+            let Some(attr) = attr_for_field(&caps[1]) else { continue };
+            let value = caps.get(2).or_else(|| caps.get(3)).map_or(String::new(), |m| m.as_str().to_string());
+            selector.constraints.push(if is_contains { Constraint::Contains(attr, value) } else { Constraint::Equals(attr, value) });
+        }
+    }
+    // 末级节点的类名（`node` / `hierarchy` 是 dump 的通用标签，不代表类名）
+    if let Some(last) = Regex::new(r"(?:^|/)([A-Za-z_][\w.$]*)(?:\[[^\]]*\])*$").expect("valid regex").captures(&stripped) {
+        if !matches!(&last[1], "node" | "hierarchy") {
+            selector.constraints.push(Constraint::Equals(Attr::Class, last[1].to_string()));
+        }
+    }
+    let positional = Regex::new(r"\[\d+\]").expect("valid regex").is_match(&stripped);
+    if positional || (selector.constraints.len() == before && selector.descendant_text.is_none()) {
+        selector.unverifiable = true;
+    }
+}
+
+/// 从步骤参数构建离线选择器：优先 `matching`，否则读取扁平的 resource_id / text / content_desc / xpath
+fn build_selector(params: &Value) -> Result<OfflineSelector, String> {
+    let mut selector = OfflineSelector::default();
+    let map: HashMap<String, Value> = params.as_object().map(|o| o.clone().into_iter().collect()).unwrap_or_default();
+
+    if let Some(context) = extract_matching_context(&map) {
+        let fields: Vec<String> =
+            if context.fields.is_empty() { context.values.keys().cloned().collect() } else { context.fields.clone() };
+        for field in &fields {
+            let Some(value) = context.values.get(field).filter(|v| !v.is_empty()) else { continue };
+            if field == "xpath" {
+                apply_xpath(&mut selector, value);
+                continue;
+            }
+            let Some(attr) = attr_for_field(field) else { continue };
+            selector.constraints.push(match context.match_mode.get(field).map(String::as_str) {
+                Some("contains") => Constraint::Contains(attr, value.clone()),
+                Some("regex") => Constraint::Matches(attr, compile(value)?),
+                _ => Constraint::Equals(attr, value.clone()),
+            });
+        }
+        let lists = [
+            (&context.includes, false, false),
+            (&context.excludes, true, false),
+            (&context.regex_includes, false, true),
+            (&context.regex_excludes, true, true),
+        ];
+        for (list, negate, regex) in lists {
+            for (field, words) in list {
+                let Some(attr) = attr_for_field(field) else { continue };
+                for word in words.iter().filter(|w| !w.is_empty()) {
+                    selector.constraints.push(match (negate, regex) {
+                        (false, false) => Constraint::Contains(attr, word.clone()),
+                        (true, false) => Constraint::NotContains(attr, word.clone()),
+                        (false, true) => Constraint::Matches(attr, compile(word)?),
+                        (true, true) => Constraint::NotMatches(attr, compile(word)?),
+                    });
+                }
+            }
+        }
+        return Ok(selector);
+    }
+
+    let flat = |keys: &[&str]| keys.iter().find_map(|key| params.get(*key)).and_then(Value::as_str).filter(|s| !s.trim().is_empty());
+    for (keys, attr) in [
+        (&["resource_id", "resourceId"][..], Attr::ResourceId),
+        (&["text"][..], Attr::Text),
+        (&["content_desc", "contentDesc"][..], Attr::ContentDesc),
+    ] {
+        if let Some(value) = flat(keys) {
+            selector.constraints.push(Constraint::Equals(attr, value.to_string()));
+        }
+    }
+    if let Some(xpath) = flat(&["xpath"]) {
+        apply_xpath(&mut selector, xpath);
+    }
+    Ok(selector)
+}
+
+/// 步骤使用的策略名：`matching.strategy`，其次参数中的 `strategy`
+fn step_strategy(params: &Value) -> Option<String> {
+    params
+        .get("matching")
+        .and_then(|m| m.get("strategy"))
+        .or_else(|| params.get("strategy"))
+        .and_then(Value::as_str)
+        .filter(|s| !s.trim().is_empty())
+        .map(str::to_string)
+}
+
+/// 绑定的快照：引用的快照 ID（取第一个），以及参数中内嵌的 XML
+pub(crate) fn bound_snapshot(params: &Value) -> (Option<String>, Option<String>) {
+    let mut refs = BTreeSet::new();
+    collect_snapshot_refs(params, &mut refs);
+    let embedded = params
+        .get("xmlSnapshot")
+        .and_then(|s| s.get("xmlContent"))
+        .and_then(Value::as_str)
+        .filter(|s| !s.trim().is_empty())
+        .map(str::to_string);
+    (refs.into_iter().next(), embedded)
+}
+
+/// 逐步预校验；`load_snapshot` 按快照 ID 取 XML，`is_known_strategy` 判断策略是否注册
+pub fn prevalidate_steps(
+    steps: &[SmartScriptStep],
+    load_snapshot: impl Fn(&str) -> Option<String>,
+    is_known_strategy: impl Fn(&str) -> bool,
+) -> Vec<StepPrevalidation> {
+    // 多个步骤常共用同一份快照，只解析一次
+    let mut trees: HashMap<String, Option<UiTree>> = HashMap::new();
+    let mut ordered: Vec<&SmartScriptStep> = steps.iter().collect();
+    ordered.sort_by_key(|step| step.order);
+
+    ordered
+        .into_iter()
+        .map(|step| {
+            let params = &step.parameters;
+            let strategy = step_strategy(params);
+            let (snapshot_id, embedded) = bound_snapshot(params);
+            let mut result = StepPrevalidation {
+                step_id: step.id.clone(),
+                step_name: step.name.clone(),
+                order: step.order,
+                readiness: StepReadiness::Ready,
+                strategy: strategy.clone(),
+                snapshot_id: snapshot_id.clone(),
+                match_count: None,
+                issues: Vec::new(),
+            };
+            if !step.enabled {
+                result.readiness = StepReadiness::Skipped;
+                return result;
+            }
+
+            if let Some(strategy) = strategy.filter(|s| !is_known_strategy(s)) {
+                result.issues.push(PrevalidationIssue::UnknownStrategy { strategy });
+            }
+
+            let selector = match build_selector(params) {
+                Ok(selector) => selector,
+                Err(reason) => {
+                    result.issues.push(PrevalidationIssue::InvalidSelector { reason });
+                    result.readiness = StepReadiness::Flagged;
+                    return result;
+                }
+            };
+            if selector.is_empty() {
+                if result.issues.is_empty() {
+                    result.readiness = StepReadiness::Skipped;
+                } else {
+                    result.readiness = StepReadiness::Flagged;
+                }
+                return result;
+            }
+
+            let key = snapshot_id.clone().unwrap_or_else(|| format!("embedded:{}", step.id));
+            let tree = trees
+                .entry(key)
+                .or_insert_with(|| {
+                    snapshot_id
+                        .as_deref()
+                        .and_then(&load_snapshot)
+                        .or(embedded)
+                        .map(|xml| UiTree::parse_lenient(&xml))
+                        .filter(|tree| !tree.is_empty())
+                })
+                .as_ref();
+
+            match tree {
+                None => result.issues.push(PrevalidationIssue::SnapshotMissing { snapshot_id }),
+                Some(tree) => {
+                    let count = selector.count(tree);
+                    result.match_count = Some(count);
+                    let multi = params
+                        .get("smartSelection")
+                        .and_then(|s| s.get("mode"))
+                        .and_then(Value::as_str)
+                        .is_some_and(|mode| MULTI_SELECTION_MODES.contains(&mode));
+                    if selector.unverifiable {
+                        // 位置型 XPath 只能确认快照存在
+                    } else if count == 0 {
+                        result.issues.push(PrevalidationIssue::SelectorNotFound);
+                    } else if count > 1 && !multi {
+                        result.issues.push(PrevalidationIssue::SelectorAmbiguous { match_count: count });
+                    }
+                }
+            }
+
+            result.readiness = if !result.issues.is_empty() {
+                StepReadiness::Flagged
+            } else if selector.unverifiable {
+                StepReadiness::Unverifiable
+            } else {
+                StepReadiness::Ready
+            };
+            result
+        })
+        .collect()
+}
+
+fn is_known_strategy(strategy: &str) -> bool {
+    is_registered_strategy(strategy) || STRATEGY_REGISTRY.lock().map(|registry| registry.get(strategy).is_some()).unwrap_or(false)
+}
+
+/// 预校验脚本：逐步把选择器回放到绑定的缓存快照上（不需要设备），返回每步就绪报告
+#[tauri::command]
+pub async fn prevalidate_script(
+    state: State<'_, ScriptManagerState>,
+    script_id: String,
+) -> Result<ScriptPrevalidationReport, String> {
+    crate::core::plugin_isolation::guard("script_manager", prevalidate_script_impl(state, script_id)).await
+}
+
+async fn prevalidate_script_impl(
+    state: State<'_, ScriptManagerState>,
+    script_id: String,
+) -> Result<ScriptPrevalidationReport, String> {
+    let script = state.0.lock().load_script(&script_id).map_err(|e| format!("加载脚本失败: {}", e))?;
+    let steps = prevalidate_steps(
+        &script.steps,
+        |id| crate::domain::analysis_cache::api::get_dom(&id.to_string()).map(|dom| dom.xml_content),
+        is_known_strategy,
+    );
+    let count = |readiness: StepReadiness| steps.iter().filter(|s| s.readiness == readiness).count();
+    Ok(ScriptPrevalidationReport {
+        script_id: script.id.clone(),
+        script_name: script.name.clone(),
+        total_steps: steps.len(),
+        ready_steps: count(StepReadiness::Ready),
+        flagged_steps: count(StepReadiness::Flagged),
+        skipped_steps: count(StepReadiness::Skipped),
+        unverifiable_steps: count(StepReadiness::Unverifiable),
+        steps,
+        validated_at: chrono::Utc::now().to_rfc3339(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::execution::model::SmartActionType;
+    use serde_json::json;
+
+    const XML: &str = r#"<hierarchy rotation="0"><node index="0" class="android.widget.FrameLayout" bounds="[0,0][1080,1920]"><node index="0" text="关注" resource-id="com.app:id/follow" class="android.widget.Button" clickable="true" bounds="[10,10][200,100]"/><node index="1" text="关注" resource-id="com.app:id/follow" class="android.widget.Button" clickable="true" bounds="[10,200][200,300]"/><node index="2" text="" resource-id="com.app:id/card" class="android.widget.LinearLayout" bounds="[0,400][1080,600]"><node index="0" text="小明" class="android.widget.TextView" bounds="[10,410][300,450]"/></node></node></hierarchy>"#;
+
+    fn step(id: &str, order: i32, parameters: Value) -> SmartScriptStep {
+        SmartScriptStep {
+            id: id.to_string(),
+            step_type: SmartActionType::Tap,
+            name: id.to_string(),
+            description: String::new(),
+            parameters,
+            enabled: true,
+            order,
+        }
+    }
+
+    #[test]
+    fn test_prevalidate_flags_missing_ambiguous_and_unknown() {
+        let snap = json!({ "xmlCacheId": "ui_dump_a.xml" });
+        let steps = vec![
+            step("ambiguous", 1, json!({ "xmlSnapshot": snap, "matching": { "strategy": "standard", "fields": ["resource-id"], "values": { "resource-id": "com.app:id/follow" } } })),
+            step("first_ok", 2, json!({ "xmlSnapshot": snap, "smartSelection": { "mode": "first" }, "matching": { "strategy": "standard", "values": { "text": "关注" } } })),
+            step("xpath_child", 3, json!({ "xmlSnapshot": snap, "xpath": "//android.widget.LinearLayout[@resource-id='com.app:id/card'][.//*[@text='小明']]" })),
+            step("not_found", 4, json!({ "xmlSnapshot": snap, "matching": { "strategy": "standard", "values": { "text": "私信" } } })),
+            step("missing", 5, json!({ "xmlSnapshot": { "xmlCacheId": "ui_dump_gone.xml" }, "text": "关注" })),
+            step("unknown", 6, json!({ "xmlSnapshot": snap, "matching": { "strategy": "teleport", "values": { "resource-id": "com.app:id/card" } } })),
+            step("wait", 0, json!({ "duration": 500 })),
+            step("positional", 7, json!({ "xmlSnapshot": snap, "xpath": "/hierarchy/node[1]/node[2]" })),
+        ];
+        let load = |id: &str| (id == "ui_dump_a.xml").then(|| XML.to_string());
+        let report = prevalidate_steps(&steps, load, is_registered_strategy);
+        let by_id: HashMap<&str, &StepPrevalidation> = report.iter().map(|s| (s.step_id.as_str(), s)).collect();
+
+        assert_eq!(report[0].step_id, "wait", "按 order 排序");
+        assert_eq!(by_id["wait"].readiness, StepReadiness::Skipped);
+        assert_eq!(by_id["ambiguous"].issues, vec![PrevalidationIssue::SelectorAmbiguous { match_count: 2 }]);
+        assert_eq!(by_id["first_ok"].readiness, StepReadiness::Ready);
+        assert_eq!(by_id["xpath_child"].readiness, StepReadiness::Ready);
+        assert_eq!(by_id["xpath_child"].match_count, Some(1));
+        assert_eq!(by_id["not_found"].issues, vec![PrevalidationIssue::SelectorNotFound]);
+        assert_eq!(by_id["missing"].issues, vec![PrevalidationIssue::SnapshotMissing { snapshot_id: Some("ui_dump_gone.xml".into()) }]);
+        assert_eq!(by_id["unknown"].issues, vec![PrevalidationIssue::UnknownStrategy { strategy: "teleport".into() }]);
+        assert_eq!(by_id["unknown"].match_count, Some(1));
+        assert_eq!(by_id["positional"].readiness, StepReadiness::Unverifiable);
+
+        let json = serde_json::to_value(by_id["ambiguous"]).unwrap();
+        assert_eq!(json["issues"][0], json!({ "kind": "selectorAmbiguous", "matchCount": 2 }));
+    }
+}
@@ -0,0 +1,49 @@
+// src-tauri/src/modules/script_manager/recorder.rs
+// module: script_manager | layer: commands | role: script-recorder
+// summary: 录制模式命令 - 开始 / 停止录制设备上的手动操作，查看录到的步骤，并导出为已保存的智能脚本
+
+use tauri::{AppHandle, Runtime, State};
+use tracing::info;
+
+use crate::services::script_manager::{ScriptManagerState, SmartScript};
+use crate::services::script_recorder::{self, RecordingStatus};
+
+/// 开始录制；录到的步骤通过 `script-recorder://step` 事件实时推送
+#[tauri::command]
+pub async fn start_recording<R: Runtime>(app: AppHandle<R>, device_id: String) -> Result<RecordingStatus, String> {
+    crate::core::plugin_isolation::guard("script_manager", async move { script_recorder::start_recording(app, device_id).await }).await
+}
+
+/// 停止录制并返回录到的步骤
+#[tauri::command]
+pub async fn stop_recording(recording_id: String) -> Result<RecordingStatus, String> {
+    crate::core::plugin_isolation::guard("script_manager", async move { script_recorder::stop_recording(&recording_id) }).await
+}
+
+#[tauri::command]
+pub async fn get_recording(recording_id: String) -> Result<RecordingStatus, String> {
+    crate::core::plugin_isolation::guard("script_manager", async move { script_recorder::get_recording(&recording_id) }).await
+}
+
+/// 把录制导出为脚本并保存
+#[tauri::command]
+pub async fn export_recording_as_script(
+    state: State<'_, ScriptManagerState>,
+    recording_id: String,
+    name: Option<String>,
+    description: Option<String>,
+) -> Result<SmartScript, String> {
+    crate::core::plugin_isolation::guard("script_manager", export_recording_as_script_impl(state, recording_id, name, description)).await
+}
+
+async fn export_recording_as_script_impl(
+    state: State<'_, ScriptManagerState>,
+    recording_id: String,
+    name: Option<String>,
+    description: Option<String>,
+) -> Result<SmartScript, String> {
+    let script = script_recorder::build_script(&recording_id, name, description)?;
+    state.0.lock().save_script(&script).map_err(|e| format!("保存录制脚本失败: {}", e))?;
+    info!("💾 录制 {} 已导出为脚本 {}（{} 个步骤）", recording_id, script.id, script.steps.len());
+    Ok(script)
+}
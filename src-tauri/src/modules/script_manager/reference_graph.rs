@@ -0,0 +1,340 @@
+// src-tauri/src/modules/script_manager/reference_graph.rs
+// module: script_manager | layer: commands | role: reference-graph
+// summary: 引用关系图导出 - 脚本 → 步骤 → 快照 → XML 缓存文件、版本 → 快照 / 父版本、分支 → 头版本，导出 JSON / DOT，删除缓存前查看影响范围
+
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, BTreeSet, HashMap, VecDeque};
+use tauri::State;
+
+use crate::domain::analysis_cache::lifecycle::get_all_snapshot_refs;
+use crate::domain::analysis_cache::version_control::XmlVersion;
+use crate::domain::analysis_cache::version_storage::VERSION_STORAGE;
+use crate::services::script_manager::{ScriptManagerState, SmartScript};
+
+/// 步骤参数中表示快照引用的字段
+const SNAPSHOT_KEYS: &[&str] = &["xmlCacheId", "xml_cache_id", "snapshotId", "snapshot_id"];
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum GraphNodeKind {
+    Script,
+    Step,
+    Snapshot,
+    /// debug_xml 目录下的 XML 缓存文件
+    CacheFile,
+    Version,
+    Branch,
+}
+
+/// 边方向为「依赖方 → 被依赖方」
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum GraphEdgeKind {
+    /// 脚本包含步骤
+    Contains,
+    /// 步骤参数引用快照
+    UsesSnapshot,
+    /// 快照内容来自缓存文件
+    StoredIn,
+    /// 版本对应的快照
+    VersionOf,
+    /// 版本的父版本
+    Parent,
+    /// 分支的头版本
+    Head,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct GraphNode {
+    /// `<kind>:<原始 ID>`，如 `snapshot:ui_dump_e0d9_20251203_123223.xml`
+    pub id: String,
+    pub kind: GraphNodeKind,
+    pub label: String,
+    #[serde(skip_serializing_if = "Value::is_null")]
+    pub attrs: Value,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct GraphEdge {
+    pub from: String,
+    pub to: String,
+    pub kind: GraphEdgeKind,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ReferenceGraph {
+    pub nodes: Vec<GraphNode>,
+    pub edges: Vec<GraphEdge>,
+}
+
+fn node_id(kind: GraphNodeKind, raw: &str) -> String {
+    let prefix = match kind {
+        GraphNodeKind::Script => "script",
+        GraphNodeKind::Step => "step",
+        GraphNodeKind::Snapshot => "snapshot",
+        GraphNodeKind::CacheFile => "cache",
+        GraphNodeKind::Version => "version",
+        GraphNodeKind::Branch => "branch",
+    };
+    format!("{}:{}", prefix, raw)
+}
+
+impl ReferenceGraph {
+    /// 添加节点（同 ID 只保留第一次），返回节点 ID
+    fn node(&mut self, index: &mut HashMap<String, usize>, kind: GraphNodeKind, raw: &str, label: &str, attrs: Value) -> String {
+        let id = node_id(kind, raw);
+        if !index.contains_key(&id) {
+            index.insert(id.clone(), self.nodes.len());
+            self.nodes.push(GraphNode { id: id.clone(), kind, label: label.to_string(), attrs });
+        }
+        id
+    }
+
+    fn edge(&mut self, from: &str, to: &str, kind: GraphEdgeKind) {
+        let edge = GraphEdge { from: from.to_string(), to: to.to_string(), kind };
+        if !self.edges.contains(&edge) {
+            self.edges.push(edge);
+        }
+    }
+
+    /// 依赖某节点的全部节点（沿反向边传递），即删除它会波及的范围
+    pub fn dependents(&self, id: &str) -> Vec<GraphNode> {
+        let mut reverse: HashMap<&str, Vec<&str>> = HashMap::new();
+        for edge in &self.edges {
+            reverse.entry(edge.to.as_str()).or_default().push(edge.from.as_str());
+        }
+        let mut seen = BTreeSet::new();
+        let mut queue = VecDeque::from([id]);
+        while let Some(current) = queue.pop_front() {
+            for &from in reverse.get(current).into_iter().flatten() {
+                if from != id && seen.insert(from) {
+                    queue.push_back(from);
+                }
+            }
+        }
+        let mut found: Vec<GraphNode> = self.nodes.iter().filter(|n| seen.contains(n.id.as_str())).cloned().collect();
+        found.sort_by(|a, b| (a.kind, &a.id).cmp(&(b.kind, &b.id)));
+        found
+    }
+
+    /// Graphviz DOT 文本
+    pub fn to_dot(&self) -> String {
+        let quote = |s: &str| format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""));
+        let mut out = String::from("digraph references {\n  rankdir=LR;\n  node [fontname=\"sans-serif\"];\n");
+        for node in &self.nodes {
+            let shape = match node.kind {
+                GraphNodeKind::Script => "folder",
+                GraphNodeKind::Step => "box",
+                GraphNodeKind::Snapshot => "ellipse",
+                GraphNodeKind::CacheFile => "note",
+                GraphNodeKind::Version => "circle",
+                GraphNodeKind::Branch => "cds",
+            };
+            out.push_str(&format!("  {} [label={}, shape={}];\n", quote(&node.id), quote(&node.label), shape));
+        }
+        for edge in &self.edges {
+            let label = serde_json::to_value(edge.kind).ok().and_then(|v| v.as_str().map(str::to_string)).unwrap_or_default();
+            out.push_str(&format!("  {} -> {} [label={}];\n", quote(&edge.from), quote(&edge.to), quote(&label)));
+        }
+        out.push_str("}\n");
+        out
+    }
+}
+
+/// 递归收集参数中引用的快照 ID
+pub(super) fn collect_snapshot_refs(value: &Value, refs: &mut BTreeSet<String>) {
+    match value {
+        Value::Object(map) => {
+            for (key, child) in map {
+                match child {
+                    Value::String(s) if SNAPSHOT_KEYS.contains(&key.as_str()) && !s.trim().is_empty() => {
+                        refs.insert(s.trim().to_string());
+                    }
+                    _ => collect_snapshot_refs(child, refs),
+                }
+            }
+        }
+        Value::Array(items) => items.iter().for_each(|child| collect_snapshot_refs(child, refs)),
+        _ => {}
+    }
+}
+
+/// 由脚本、缓存文件名、快照引用计数、版本历史构建引用图
+fn build_graph(
+    scripts: &[SmartScript],
+    cache_files: &[String],
+    pinned: &HashMap<String, usize>,
+    branches: &[(String, Vec<XmlVersion>)],
+) -> ReferenceGraph {
+    let mut graph = ReferenceGraph::default();
+    let mut index = HashMap::new();
+    let cache_set: BTreeSet<&str> = cache_files.iter().map(String::as_str).collect();
+    let snapshot = |graph: &mut ReferenceGraph, index: &mut HashMap<String, usize>, raw: &str| {
+        let attrs = pinned.get(raw).map_or(Value::Null, |count| json!({ "refCount": count }));
+        let id = graph.node(index, GraphNodeKind::Snapshot, raw, raw, attrs);
+        if cache_set.contains(raw) {
+            let file = graph.node(index, GraphNodeKind::CacheFile, raw, raw, Value::Null);
+            graph.edge(&id, &file, GraphEdgeKind::StoredIn);
+        }
+        id
+    };
+
+    for script in scripts {
+        let script_id = graph.node(&mut index, GraphNodeKind::Script, &script.id, &script.name, json!({ "version": script.version }));
+        for step in &script.steps {
+            let step_id = graph.node(&mut index, GraphNodeKind::Step, &step.id, &step.name, Value::Null);
+            graph.edge(&script_id, &step_id, GraphEdgeKind::Contains);
+            let mut refs = BTreeSet::new();
+            collect_snapshot_refs(&step.parameters, &mut refs);
+            for raw in refs {
+                let snap = snapshot(&mut graph, &mut index, &raw);
+                graph.edge(&step_id, &snap, GraphEdgeKind::UsesSnapshot);
+            }
+        }
+    }
+
+    for (branch, history) in branches {
+        let branch_id = graph.node(&mut index, GraphNodeKind::Branch, branch, branch, Value::Null);
+        for (i, version) in history.iter().enumerate() {
+            let attrs = json!({ "timestamp": version.timestamp, "type": version.version_type });
+            let version_id = graph.node(&mut index, GraphNodeKind::Version, &version.id, &version.display_name(), attrs);
+            if i == 0 {
+                graph.edge(&branch_id, &version_id, GraphEdgeKind::Head);
+            }
+            let snap = snapshot(&mut graph, &mut index, &version.snapshot_id);
+            graph.edge(&version_id, &snap, GraphEdgeKind::VersionOf);
+            if let Some(parent) = &version.parent_id {
+                let parent_id = node_id(GraphNodeKind::Version, parent);
+                graph.edge(&version_id, &parent_id, GraphEdgeKind::Parent);
+            }
+        }
+    }
+
+    // 只被引用计数固定、没有脚本引用的快照也列出来
+    let pinned_sorted: BTreeMap<_, _> = pinned.iter().collect();
+    for raw in pinned_sorted.keys() {
+        snapshot(&mut graph, &mut index, raw);
+    }
+    // 没有任何引用的缓存文件（可安全删除）
+    for file in cache_files {
+        graph.node(&mut index, GraphNodeKind::CacheFile, file, file, Value::Null);
+    }
+    // 父版本可能不在任何分支历史中，去掉悬空边
+    graph.edges.retain(|e| index.contains_key(&e.to));
+    graph
+}
+
+async fn collect_graph(state: &State<'_, ScriptManagerState>) -> Result<ReferenceGraph, String> {
+    let scripts = state.0.lock().list_scripts().map_err(|e| format!("列出脚本失败: {}", e))?;
+    let cache_files: Vec<String> = std::fs::read_dir(crate::modules::xml_cache::get_debug_xml_dir())
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter_map(|e| e.file_name().to_str().map(str::to_string))
+                .filter(|name| name.ends_with(".xml"))
+                .collect()
+        })
+        .unwrap_or_default();
+    let branches = {
+        let storage = VERSION_STORAGE.read().await;
+        let mut branches = Vec::new();
+        for branch in storage.list_branches() {
+            let history = storage.get_branch_history(&branch.name, None).await.map_err(|e| e.to_string())?;
+            branches.push((branch.name, history));
+        }
+        branches
+    };
+    Ok(build_graph(&scripts, &cache_files, &get_all_snapshot_refs(), &branches))
+}
+
+/// 导出引用关系图；`format` 为 `json`（默认）或 `dot`，给出 `output_path` 时同时写入文件
+#[tauri::command]
+pub async fn export_reference_graph(
+    state: State<'_, ScriptManagerState>,
+    format: Option<String>,
+    output_path: Option<String>,
+) -> Result<String, String> {
+    crate::core::plugin_isolation::guard("script_manager", export_reference_graph_impl(state, format, output_path)).await
+}
+
+async fn export_reference_graph_impl(
+    state: State<'_, ScriptManagerState>,
+    format: Option<String>,
+    output_path: Option<String>,
+) -> Result<String, String> {
+    let graph = collect_graph(&state).await?;
+    let content = match format.as_deref().unwrap_or("json") {
+        "json" => serde_json::to_string_pretty(&graph).map_err(|e| e.to_string())?,
+        "dot" => graph.to_dot(),
+        other => return Err(format!("不支持的导出格式: {}（支持 json / dot）", other)),
+    };
+    if let Some(path) = output_path.filter(|p| !p.trim().is_empty()) {
+        std::fs::write(&path, &content).map_err(|e| format!("写入导出文件失败: {}", e))?;
+    }
+    Ok(content)
+}
+
+/// 删除某个节点（如 `cache:ui_dump_xxx.xml`）会波及的脚本 / 步骤 / 版本
+#[tauri::command]
+pub async fn get_reference_blast_radius(
+    state: State<'_, ScriptManagerState>,
+    node_id: String,
+) -> Result<Vec<GraphNode>, String> {
+    crate::core::plugin_isolation::guard("script_manager", get_reference_blast_radius_impl(state, node_id)).await
+}
+
+async fn get_reference_blast_radius_impl(
+    state: State<'_, ScriptManagerState>,
+    node_id: String,
+) -> Result<Vec<GraphNode>, String> {
+    let graph = collect_graph(&state).await?;
+    if !graph.nodes.iter().any(|n| n.id == node_id) {
+        return Err(format!("引用图中不存在节点: {}", node_id));
+    }
+    Ok(graph.dependents(&node_id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::execution::model::{SmartActionType, SmartScriptStep};
+
+    fn step(id: &str, parameters: Value) -> SmartScriptStep {
+        SmartScriptStep {
+            id: id.to_string(),
+            step_type: SmartActionType::Tap,
+            name: format!("步骤 {}", id),
+            description: String::new(),
+            parameters,
+            enabled: true,
+            order: 0,
+        }
+    }
+
+    #[test]
+    fn test_graph_edges_dot_and_blast_radius() {
+        let cache = "ui_dump_e0d9_20251203_123223.xml".to_string();
+        let script = SmartScript {
+            id: "script_1".to_string(),
+            name: "关注流程".to_string(),
+            steps: vec![
+                step("s0", json!({ "xmlSnapshot": { "xmlCacheId": cache, "xmlContent": "<hierarchy/>" } })),
+                step("s1", json!({ "text": "关注" })),
+            ],
+            ..Default::default()
+        };
+        let pinned = HashMap::from([("snap_orphan".to_string(), 2)]);
+        let graph = build_graph(&[script], &[cache.clone(), "ui_dump_unused.xml".to_string()], &pinned, &[]);
+
+        let cache_id = format!("cache:{}", cache);
+        let radius: Vec<String> = graph.dependents(&cache_id).into_iter().map(|n| n.id).collect();
+        assert_eq!(radius, vec!["script:script_1".to_string(), "step:s0".to_string(), format!("snapshot:{}", cache)]);
+        assert!(graph.dependents("cache:ui_dump_unused.xml").is_empty());
+        assert!(graph.nodes.iter().any(|n| n.id == "snapshot:snap_orphan" && n.attrs["refCount"] == 2));
+
+        let dot = graph.to_dot();
+        assert!(dot.starts_with("digraph references {"));
+        assert!(dot.contains("\"step:s0\" -> \"snapshot:ui_dump_e0d9_20251203_123223.xml\" [label=\"uses_snapshot\"];"));
+    }
+}
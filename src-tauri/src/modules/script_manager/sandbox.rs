@@ -0,0 +1,69 @@
+// src-tauri/src/modules/script_manager/sandbox.rs
+// module: script_manager | layer: commands | role: sandbox-commands
+// summary: 执行沙箱配置命令 - 配置增删、活动/脚本绑定与设备分组；进出沙箱由脚本 / 活动 / Agent 执行器自动完成
+
+use crate::core::application::sandbox_service::{ActiveSandbox, SANDBOX};
+use crate::core::domain::sandbox::SandboxProfile;
+
+/// 列出所有沙箱配置（含内置）
+#[tauri::command]
+pub async fn list_sandbox_profiles() -> Result<Vec<SandboxProfile>, String> {
+    crate::core::plugin_isolation::guard("script_manager", async move { Ok(SANDBOX.list_profiles()) }).await
+}
+
+/// 新建或更新沙箱配置
+#[tauri::command]
+pub async fn save_sandbox_profile(profile: SandboxProfile) -> Result<(), String> {
+    crate::core::plugin_isolation::guard("script_manager", async move { SANDBOX.save_profile(profile).map_err(|e| e.to_string()) }).await
+}
+
+/// 删除自定义沙箱配置（仍被绑定或正在生效时拒绝）
+#[tauri::command]
+pub async fn delete_sandbox_profile(profile_id: String) -> Result<bool, String> {
+    crate::core::plugin_isolation::guard("script_manager", async move { SANDBOX.delete_profile(&profile_id).map_err(|e| e.to_string()) }).await
+}
+
+/// 绑定沙箱配置到活动或脚本；`owner` 形如 `campaign:<id>` / `script:<id>`，`profile_id` 为空表示解绑
+#[tauri::command]
+pub async fn bind_sandbox_profile(owner: String, profile_id: Option<String>) -> Result<(), String> {
+    crate::core::plugin_isolation::guard("script_manager", bind_sandbox_profile_impl(owner, profile_id)).await
+}
+
+async fn bind_sandbox_profile_impl(owner: String, profile_id: Option<String>) -> Result<(), String> {
+    if !owner.starts_with("campaign:") && !owner.starts_with("script:") {
+        return Err(format!("无效的归属: {}（应为 campaign:<id> 或 script:<id>）", owner));
+    }
+    SANDBOX.bind(&owner, profile_id.as_deref()).map_err(|e| e.to_string())
+}
+
+/// 设置设备所属分组（空列表表示清除）
+#[tauri::command]
+pub async fn set_device_sandbox_groups(device_id: String, groups: Vec<String>) -> Result<(), String> {
+    crate::core::plugin_isolation::guard("script_manager", async move { SANDBOX.set_device_groups(&device_id, groups).map_err(|e| e.to_string()) }).await
+}
+
+/// 当前生效的沙箱作用域
+#[tauri::command]
+pub async fn list_active_sandboxes() -> Result<Vec<ActiveSandbox>, String> {
+    crate::core::plugin_isolation::guard("script_manager", async move { Ok(SANDBOX.active_scopes()) }).await
+}
+
+/// 预检某个动作是否会被沙箱拦截
+#[tauri::command]
+pub async fn check_sandbox_action(
+    action: String,
+    device_id: Option<String>,
+    package: Option<String>,
+) -> Result<(), String> {
+    crate::core::plugin_isolation::guard("script_manager", check_sandbox_action_impl(action, device_id, package)).await
+}
+
+async fn check_sandbox_action_impl(
+    action: String,
+    device_id: Option<String>,
+    package: Option<String>,
+) -> Result<(), String> {
+    SANDBOX
+        .check(&action, device_id.as_deref(), package.as_deref())
+        .map_err(|e| e.to_string())
+}
@@ -0,0 +1,205 @@
+// src-tauri/src/modules/script_manager/search.rs
+// module: script_manager | layer: commands | role: script-search
+// summary: 全项目字符串搜索 - 在已保存脚本（步骤、选择器、备注）和智能分析绑定的步骤策略中查找引用，重命名选择器前用来确认影响范围
+
+use serde::Serialize;
+use serde_json::Value;
+use tauri::State;
+
+use crate::commands::intelligent_analysis::{StrategyCandidate, STEP_STRATEGY_STORE};
+use crate::services::script_manager::{ScriptManagerState, SmartScript};
+
+/// 命中值超过该长度时只返回命中位置附近的片段
+const SNIPPET_MAX_CHARS: usize = 120;
+
+/// 命中来源
+#[derive(Debug, Clone, Copy, Serialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ScriptSearchSource {
+    /// 脚本 JSON 本身
+    Script,
+    /// 智能分析绑定到步骤上的策略
+    BoundStrategy,
+}
+
+/// 一条搜索命中
+#[derive(Debug, Clone, Serialize)]
+pub struct ScriptSearchMatch {
+    pub source: ScriptSearchSource,
+    /// 绑定策略所属步骤不在任何脚本中时为空
+    pub script_id: Option<String>,
+    pub script_name: Option<String>,
+    /// 命中位于某个步骤内时的步骤下标
+    pub step_index: Option<usize>,
+    pub step_id: Option<String>,
+    /// 命中字段路径，如 `steps[3].parameters.resource_id`；绑定策略为 `strategy.xpath` 形式
+    pub field_path: String,
+    pub value: String,
+}
+
+/// 在脚本和绑定策略中搜索字符串（不区分大小写）
+#[tauri::command]
+pub async fn search_scripts(
+    state: State<'_, ScriptManagerState>,
+    query: String,
+) -> Result<Vec<ScriptSearchMatch>, String> {
+    crate::core::plugin_isolation::guard("script_manager", search_scripts_impl(state, query)).await
+}
+
+async fn search_scripts_impl(
+    state: State<'_, ScriptManagerState>,
+    query: String,
+) -> Result<Vec<ScriptSearchMatch>, String> {
+    if query.trim().is_empty() {
+        return Err("搜索内容不能为空".to_string());
+    }
+    let scripts = state.0.lock().list_scripts().map_err(|e| format!("列出脚本失败: {}", e))?;
+    let strategies: Vec<(String, StrategyCandidate)> = STEP_STRATEGY_STORE
+        .lock()
+        .map_err(|e| format!("锁定步骤策略存储失败: {}", e))?
+        .iter()
+        .map(|(step_id, (strategy, _))| (step_id.clone(), strategy.clone()))
+        .collect();
+    Ok(search_in(&scripts, &strategies, query.trim()))
+}
+
+fn search_in(
+    scripts: &[SmartScript],
+    strategies: &[(String, StrategyCandidate)],
+    query: &str,
+) -> Vec<ScriptSearchMatch> {
+    let needle = query.to_lowercase();
+    let mut matches = Vec::new();
+
+    for script in scripts {
+        let Ok(doc) = serde_json::to_value(script) else { continue };
+        let mut hits = Vec::new();
+        collect_hits(&doc, String::new(), &needle, &mut hits);
+        for (field_path, value) in hits {
+            let step_index = step_index_of(&field_path);
+            matches.push(ScriptSearchMatch {
+                source: ScriptSearchSource::Script,
+                script_id: Some(script.id.clone()),
+                script_name: Some(script.name.clone()),
+                step_index,
+                step_id: step_index.and_then(|i| script.steps.get(i)).map(|s| s.id.clone()),
+                field_path,
+                value: snippet(&value, &needle),
+            });
+        }
+    }
+
+    for (step_id, strategy) in strategies {
+        let Ok(doc) = serde_json::to_value(strategy) else { continue };
+        let mut hits = Vec::new();
+        collect_hits(&doc, "strategy".to_string(), &needle, &mut hits);
+        if hits.is_empty() {
+            continue;
+        }
+        let owner = scripts
+            .iter()
+            .find_map(|s| s.steps.iter().position(|step| &step.id == step_id).map(|i| (s, i)));
+        for (field_path, value) in hits {
+            matches.push(ScriptSearchMatch {
+                source: ScriptSearchSource::BoundStrategy,
+                script_id: owner.map(|(s, _)| s.id.clone()),
+                script_name: owner.map(|(s, _)| s.name.clone()),
+                step_index: owner.map(|(_, i)| i),
+                step_id: Some(step_id.clone()),
+                field_path,
+                value: snippet(&value, &needle),
+            });
+        }
+    }
+    matches
+}
+
+/// 递归收集字符串 / 数字叶子中包含关键字的字段
+fn collect_hits(value: &Value, path: String, needle: &str, hits: &mut Vec<(String, String)>) {
+    match value {
+        Value::Object(map) => {
+            for (key, child) in map {
+                let child_path = if path.is_empty() { key.clone() } else { format!("{}.{}", path, key) };
+                collect_hits(child, child_path, needle, hits);
+            }
+        }
+        Value::Array(items) => {
+            for (i, child) in items.iter().enumerate() {
+                collect_hits(child, format!("{}[{}]", path, i), needle, hits);
+            }
+        }
+        Value::String(s) if s.to_lowercase().contains(needle) => hits.push((path, s.clone())),
+        Value::Number(n) if n.to_string().contains(needle) => hits.push((path, n.to_string())),
+        _ => {}
+    }
+}
+
+/// 从 `steps[3].xxx` 形式的路径中取步骤下标
+fn step_index_of(field_path: &str) -> Option<usize> {
+    let rest = field_path.strip_prefix("steps[")?;
+    rest[..rest.find(']')?].parse().ok()
+}
+
+fn snippet(value: &str, needle: &str) -> String {
+    let chars: Vec<char> = value.chars().collect();
+    if chars.len() <= SNIPPET_MAX_CHARS {
+        return value.to_string();
+    }
+    // 按字符定位命中处，避免在多字节字符中间截断
+    let lower: Vec<char> = value.to_lowercase().chars().collect();
+    let needle: Vec<char> = needle.chars().collect();
+    let at = lower.windows(needle.len().max(1)).position(|w| w == needle.as_slice()).unwrap_or(0);
+    let start = at.saturating_sub(SNIPPET_MAX_CHARS / 2).min(chars.len() - SNIPPET_MAX_CHARS);
+    let body: String = chars[start..start + SNIPPET_MAX_CHARS].iter().collect();
+    format!(
+        "{}{}{}",
+        if start > 0 { "…" } else { "" },
+        body,
+        if start + SNIPPET_MAX_CHARS < chars.len() { "…" } else { "" }
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::execution::model::{SmartActionType, SmartScriptStep};
+    use serde_json::json;
+
+    fn step(id: &str, parameters: Value) -> SmartScriptStep {
+        SmartScriptStep {
+            id: id.to_string(),
+            step_type: SmartActionType::Tap,
+            name: "点击".to_string(),
+            description: String::new(),
+            parameters,
+            enabled: true,
+            order: 0,
+        }
+    }
+
+    #[test]
+    fn test_search_reports_step_index_and_field_path() {
+        let script = SmartScript {
+            id: "script_1".to_string(),
+            steps: vec![
+                step("s0", json!({ "text": "关注" })),
+                step("s1", json!({ "resource_id": "com.xingin.xhs:id/follow_btn", "comment": "关注按钮" })),
+            ],
+            ..Default::default()
+        };
+        let strategy: StrategyCandidate = serde_json::from_value(json!({
+            "key": "k", "name": "n", "confidence": 0.9, "description": "", "variant": "self_anchor", "enabled": true, "is_recommended": true,
+            "xpath": "//*[@resource-id='com.xingin.xhs:id/FOLLOW_BTN']"
+        }))
+        .unwrap();
+
+        let matches = search_in(&[script], &[("s1".to_string(), strategy)], "id/follow_btn");
+        assert_eq!(matches.len(), 2);
+        assert_eq!(matches[0].field_path, "steps[1].parameters.resource_id");
+        assert_eq!(matches[0].step_index, Some(1));
+        assert_eq!(matches[0].step_id.as_deref(), Some("s1"));
+        assert_eq!(matches[1].source, ScriptSearchSource::BoundStrategy);
+        assert_eq!(matches[1].field_path, "strategy.xpath");
+        assert_eq!(matches[1].script_id.as_deref(), Some("script_1"));
+    }
+}
@@ -11,7 +11,7 @@ use tauri::{
 use tracing::{info, debug};
 use crate::types::smart_selection::*;
 use crate::services::adb::AdbService;
-use std::sync::Mutex;
+use crate::core::app_services::SharedAppServices;
 use crate::commands::intelligent_analysis::{STEP_STRATEGY_STORE, StrategyCandidate};
 
 /// 执行智能选择命令（已迁移到V3，保留API兼容）
@@ -19,7 +19,15 @@ use crate::commands::intelligent_analysis::{STEP_STRATEGY_STORE, StrategyCandida
 async fn execute(
     device_id: String,
     _protocol: SmartSelectionProtocol,
-    _adb_service: State<'_, Mutex<AdbService>>,
+    _services: State<'_, SharedAppServices>,
+) -> Result<SmartSelectionResult, String> {
+    crate::core::plugin_isolation::guard("smart_selection", execute_impl(device_id, _protocol, _services)).await
+}
+
+async fn execute_impl(
+    device_id: String,
+    _protocol: SmartSelectionProtocol,
+    _services: State<'_, SharedAppServices>,
 ) -> Result<SmartSelectionResult, String> {
     info!("🎯 [Legacy API兼容] 开始执行智能选择，设备: {}", device_id);
     info!("⚠️ 此API已废弃，建议使用 execute_chain_test_v3");
@@ -35,6 +43,12 @@ async fn execute(
 #[tauri::command]
 async fn validate(
     protocol: SmartSelectionProtocol,
+) -> Result<ValidationResult, String> {
+    crate::core::plugin_isolation::guard("smart_selection", validate_impl(protocol)).await
+}
+
+async fn validate_impl(
+    protocol: SmartSelectionProtocol,
 ) -> Result<ValidationResult, String> {
     debug!("🔍 验证智能选择协议");
     
@@ -86,6 +100,10 @@ async fn validate(
 /// 获取智能选择统计信息
 #[tauri::command]
 async fn get_stats() -> Result<SmartSelectionStats, String> {
+    crate::core::plugin_isolation::guard("smart_selection", get_stats_impl()).await
+}
+
+async fn get_stats_impl() -> Result<SmartSelectionStats, String> {
     info!("📊 获取智能选择统计信息");
     
     Ok(SmartSelectionStats {
@@ -105,7 +123,14 @@ async fn get_stats() -> Result<SmartSelectionStats, String> {
 #[tauri::command]
 async fn test_connectivity(
     device_id: String,
-    _adb_service: State<'_, Mutex<AdbService>>,
+    _services: State<'_, SharedAppServices>,
+) -> Result<ConnectivityTestResult, String> {
+    crate::core::plugin_isolation::guard("smart_selection", test_connectivity_impl(device_id, _services)).await
+}
+
+async fn test_connectivity_impl(
+    device_id: String,
+    _services: State<'_, SharedAppServices>,
 ) -> Result<ConnectivityTestResult, String> {
     info!("🔗 测试智能选择系统连通性，设备: {}", device_id);
     
@@ -191,7 +216,15 @@ async fn test_connectivity(
 async fn preview(
     device_id: String,
     protocol: SmartSelectionProtocol,
-    _adb_service: State<'_, Mutex<AdbService>>,
+    _services: State<'_, SharedAppServices>,
+) -> Result<CandidatePreviewResult, String> {
+    crate::core::plugin_isolation::guard("smart_selection", preview_impl(device_id, protocol, _services)).await
+}
+
+async fn preview_impl(
+    device_id: String,
+    protocol: SmartSelectionProtocol,
+    _services: State<'_, SharedAppServices>,
 ) -> Result<CandidatePreviewResult, String> {
     info!("👁️ 预览智能选择候选元素，设备: {}", device_id);
     
@@ -236,6 +269,15 @@ async fn save_config(
     selection_mode: String,
     batch_config: Option<serde_json::Value>,
     structural_signatures: Option<serde_json::Value>,
+) -> Result<bool, String> {
+    crate::core::plugin_isolation::guard("smart_selection", save_config_impl(step_id, selection_mode, batch_config, structural_signatures)).await
+}
+
+async fn save_config_impl(
+    step_id: String,
+    selection_mode: String,
+    batch_config: Option<serde_json::Value>,
+    structural_signatures: Option<serde_json::Value>,
 ) -> Result<bool, String> {
     info!(
         "📥 [save_config] 收到保存请求: step_id={}, mode={}, batch_config={:?}, structural_signatures={:?}",
@@ -298,14 +340,14 @@ async fn save_config(
 // 导出插件初始化函数
 pub fn init<R: Runtime>() -> TauriPlugin<R> {
     Builder::new("smart_selection")
-        .invoke_handler(tauri::generate_handler![
+        .invoke_handler(crate::core::plugin_isolation::isolate("smart_selection", tauri::generate_handler![
             execute,
             validate,
             get_stats,
             test_connectivity,
             preview,
             save_config
-        ])
+        ]))
         .build()
 }
 
@@ -4,11 +4,30 @@
 
 use tauri::{
     plugin::{Builder, TauriPlugin},
-    Runtime,
+    AppHandle, Manager, Runtime, Window,
 };
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use crate::services::adb::AdbService;
+use crate::core::plugin_bootstrap::{self, PluginStatus, PluginStatusReport};
+use crate::core::plugin_isolation;
+use crate::core::application::read_only_mode::{ReadOnlyStatus, READ_ONLY};
+use crate::core::application::command_audit::{AuditEntry, AuditQuery, COMMAND_AUDIT};
+use crate::core::shared::i18n::{self, Locale};
+use crate::services::licensing::{self, LicenseManager, LicenseStatus};
+use crate::services::trend_rollup::{self, Granularity, Metric, TimeRange, TimeSeries};
+use crate::services::trend_rollup::anomaly::{self, Anomaly, AnomalyConfig, AnomalyDetector};
+use crate::services::telemetry::{self, Telemetry, TelemetryBatch, TelemetryConfig, UploadOutcome};
+use crate::services::support_session::{self, SupportSessionOptions, SupportSessionStatus};
+use crate::services::quick_actions::{
+    self, QuickAction, QuickActionBinding, QuickActionContext, QuickActionOutcome, QuickActionStore, TauriQuickCommandRunner,
+};
+use crate::services::contact_storage::repositories::common::database::contacts_db_path;
+use crate::services::concurrency::{ConcurrencyController, ConcurrencyLimits, ConcurrencyStatus};
+use crate::services::event_outbox::{EventOutbox, EventReplay};
+use crate::infrastructure::event_bindings::{self, EventSchema};
+use crate::services::demo_dataset::{self, DatasetSource, DemoDatasetReport, CONTACTS_PLAN, PROSPECTING_PLAN};
+use crate::services::prospecting::prospecting_repository::PROSPECTING_DB_FILE;
 use crate::commands::click_normalizer_test::{
     self, ClickNormalizeRequest, ClickNormalizeResponse, AnalyzeResponse
 };
@@ -35,6 +54,10 @@ pub struct SystemHealthCheck {
 /// 后端健康检查 - 轻量级 ping 命令
 #[tauri::command]
 async fn ping() -> Result<PingResponse, String> {
+    crate::core::plugin_isolation::guard("system_diagnostic", ping_impl()).await
+}
+
+async fn ping_impl() -> Result<PingResponse, String> {
     use std::time::{SystemTime, UNIX_EPOCH};
 
     let timestamp = SystemTime::now()
@@ -52,6 +75,10 @@ async fn ping() -> Result<PingResponse, String> {
 /// 分析系统完整健康检查
 #[tauri::command]
 async fn health_check() -> Result<SystemHealthCheck, String> {
+    crate::core::plugin_isolation::guard("system_diagnostic", health_check_impl()).await
+}
+
+async fn health_check_impl() -> Result<SystemHealthCheck, String> {
     tracing::info!("🔍 [Plugin:diagnostic] 开始系统健康检查");
     
     // 检查ADB连接
@@ -81,36 +108,36 @@ async fn health_check() -> Result<SystemHealthCheck, String> {
 /// 获取 ADB 路径
 #[tauri::command]
 async fn get_adb_path() -> Result<String, String> {
-    crate::services::diagnostic_service::get_adb_path_cmd().await
+    crate::core::plugin_isolation::guard("system_diagnostic", async move { crate::services::diagnostic_service::get_adb_path_cmd().await }).await
 }
 
 /// 获取环境信息
 #[tauri::command]
 async fn get_env_info() -> Result<Value, String> {
-    crate::services::diagnostic_service::get_environment_info().await
+    crate::core::plugin_isolation::guard("system_diagnostic", async move { crate::services::diagnostic_service::get_environment_info().await }).await
 }
 
 /// 测试设备响应性
 #[tauri::command]
 async fn test_device(device_id: String) -> Result<Value, String> {
-    crate::services::diagnostic_service::test_device_responsiveness(device_id).await
+    crate::core::plugin_isolation::guard("system_diagnostic", async move { crate::services::diagnostic_service::test_device_responsiveness(device_id).await }).await
 }
 
 /// 运行完整诊断
 #[tauri::command]
 async fn run_diagnostic() -> Result<Value, String> {
-    crate::services::diagnostic_service::run_full_diagnostic().await
+    crate::core::plugin_isolation::guard("system_diagnostic", async move { crate::services::diagnostic_service::run_full_diagnostic().await }).await
 }
 
 // Wrappers for click_normalizer_test
 #[tauri::command]
-async fn test_click_normalization(request: ClickNormalizeRequest) -> ClickNormalizeResponse {
-    click_normalizer_test::test_click_normalization(request).await
+async fn test_click_normalization(request: ClickNormalizeRequest) -> Result<ClickNormalizeResponse, String> {
+    crate::core::plugin_isolation::guard("system_diagnostic", async move { Ok(click_normalizer_test::test_click_normalization(request).await) }).await
 }
 
 #[tauri::command]
-async fn analyze_xml_structure(xml_content: String) -> AnalyzeResponse {
-    click_normalizer_test::analyze_xml_structure(xml_content).await
+async fn analyze_xml_structure(xml_content: String) -> Result<AnalyzeResponse, String> {
+    crate::core::plugin_isolation::guard("system_diagnostic", async move { Ok(click_normalizer_test::analyze_xml_structure(xml_content).await) }).await
 }
 
 // ==================== 辅助函数 ====================
@@ -132,17 +159,327 @@ async fn check_device_availability() -> bool {
 
 #[tauri::command]
 async fn clear_logs() -> Result<(), String> {
-    Ok(())
+    crate::core::plugin_isolation::guard("system_diagnostic", async move { Ok(()) }).await
 }
 
 #[tauri::command]
 async fn add_log_entry(_entry: Value) -> Result<(), String> {
-    Ok(())
+    crate::core::plugin_isolation::guard("system_diagnostic", async move { Ok(()) }).await
+}
+
+/// 获取插件启动状态（依赖关系、懒加载状态、启动耗时）
+#[tauri::command]
+async fn get_plugin_status() -> Result<PluginStatusReport, String> {
+    crate::core::plugin_isolation::guard("system_diagnostic", async move { Ok(plugin_bootstrap::registry().report()) }).await
+}
+
+/// 运行端到端回归场景（隐藏命令，需启用 scenario-tests 特性）
+#[tauri::command]
+async fn run_scenario(name: String) -> Result<Value, String> {
+    crate::core::plugin_isolation::guard("system_diagnostic", run_scenario_impl(name)).await
+}
+
+async fn run_scenario_impl(name: String) -> Result<Value, String> {
+    #[cfg(feature = "scenario-tests")]
+    {
+        let report = crate::services::scenario_runner::run_scenario(&name).await?;
+        serde_json::to_value(report).map_err(|e| e.to_string())
+    }
+    #[cfg(not(feature = "scenario-tests"))]
+    {
+        Err(format!("场景回归未启用（需要 scenario-tests 特性）: {}", name))
+    }
+}
+
+/// 获取报表/导出语言
+#[tauri::command]
+async fn get_locale() -> Result<Locale, String> {
+    crate::core::plugin_isolation::guard("system_diagnostic", async move { Ok(i18n::current_locale()) }).await
+}
+
+/// 设置报表/导出语言（zh-CN / en-US）
+#[tauri::command]
+async fn set_locale(locale: String) -> Result<Locale, String> {
+    crate::core::plugin_isolation::guard("system_diagnostic", set_locale_impl(locale)).await
+}
+
+async fn set_locale_impl(locale: String) -> Result<Locale, String> {
+    let parsed = Locale::parse(&locale)
+        .ok_or_else(|| i18n::t_args(i18n::current_locale(), "locale.unsupported", &[("locale", locale.as_str())]))?;
+    i18n::set_locale(parsed)?;
+    Ok(parsed)
+}
+
+/// 获取授权状态（有效期 / 席位 / 已启用功能 / 宽限期）
+#[tauri::command]
+async fn get_license_status() -> Result<LicenseStatus, String> {
+    crate::core::plugin_isolation::guard("system_diagnostic", async move { Ok(licensing::license_status()) }).await
+}
+
+/// 激活授权码
+#[tauri::command]
+async fn activate_license(license_key: String) -> Result<LicenseStatus, String> {
+    crate::core::plugin_isolation::guard("system_diagnostic", async move { LicenseManager::global().lock().map_err(|e| e.to_string())?.activate(&license_key) }).await
+}
+
+/// 移除本机授权
+#[tauri::command]
+async fn deactivate_license() -> Result<LicenseStatus, String> {
+    crate::core::plugin_isolation::guard("system_diagnostic", async move { Ok(LicenseManager::global().lock().map_err(|e| e.to_string())?.deactivate()) }).await
+}
+
+/// 查看待上报的使用统计（即实际会发送的完整内容）
+#[tauri::command]
+async fn get_pending_telemetry() -> Result<TelemetryBatch, String> {
+    crate::core::plugin_isolation::guard("system_diagnostic", async move { Ok(Telemetry::global().lock().map_err(|e| e.to_string())?.pending_batch()) }).await
+}
+
+#[tauri::command]
+async fn get_telemetry_config() -> Result<TelemetryConfig, String> {
+    crate::core::plugin_isolation::guard("system_diagnostic", async move { Ok(Telemetry::global().lock().map_err(|e| e.to_string())?.config().clone()) }).await
+}
+
+/// 开启 / 关闭使用统计、切换离线模式、设置上报地址
+#[tauri::command]
+async fn set_telemetry_config(config: TelemetryConfig) -> Result<TelemetryConfig, String> {
+    crate::core::plugin_isolation::guard("system_diagnostic", set_telemetry_config_impl(config)).await
+}
+
+async fn set_telemetry_config_impl(config: TelemetryConfig) -> Result<TelemetryConfig, String> {
+    let mut telemetry = Telemetry::global().lock().map_err(|e| e.to_string())?;
+    telemetry.set_config(config);
+    Ok(telemetry.config().clone())
+}
+
+/// 立即上报待发送的使用统计
+#[tauri::command]
+async fn upload_telemetry_now() -> Result<UploadOutcome, String> {
+    crate::core::plugin_isolation::guard("system_diagnostic", async move { telemetry::upload_pending().await }).await
+}
+
+/// 开启支持会话：日志过滤、脱敏后推送到支持端点（未配置时写入加密日志包），返回会话码
+#[tauri::command]
+async fn start_support_session(options: SupportSessionOptions) -> Result<SupportSessionStatus, String> {
+    crate::core::plugin_isolation::guard("system_diagnostic", async move { support_session::start(options) }).await
+}
+
+/// 立即停止支持会话，不再发送任何日志
+#[tauri::command]
+async fn stop_support_session() -> Result<SupportSessionStatus, String> {
+    crate::core::plugin_isolation::guard("system_diagnostic", async move { support_session::stop() }).await
+}
+
+/// 当前支持会话状态（没有进行中的会话时为最近结束的一次）
+#[tauri::command]
+async fn get_support_session_status() -> Result<Option<SupportSessionStatus>, String> {
+    crate::core::plugin_isolation::guard("system_diagnostic", async move { Ok(support_session::status()) }).await
+}
+
+/// 获取只读观察模式状态
+#[tauri::command]
+async fn get_read_only_mode() -> Result<ReadOnlyStatus, String> {
+    crate::core::plugin_isolation::guard("system_diagnostic", async move { Ok(READ_ONLY.status()) }).await
+}
+
+/// 开启 / 关闭只读观察模式（演示、审计时只允许观察与查询）
+#[tauri::command]
+async fn set_read_only_mode(enabled: bool) -> Result<ReadOnlyStatus, String> {
+    crate::core::plugin_isolation::guard("system_diagnostic", async move { READ_ONLY.set_enabled(enabled).map_err(|e| e.to_string()) }).await
+}
+
+/// 仪表盘时间序列（导入数 / 动作数 / 成功率 / 设备在线时长）
+#[tauri::command]
+async fn get_timeseries(
+    metric: Metric,
+    range: Option<TimeRange>,
+    granularity: Option<Granularity>,
+) -> Result<TimeSeries, String> {
+    crate::core::plugin_isolation::guard("system_diagnostic", async move { trend_rollup::get_timeseries(metric, &range.unwrap_or_default(), granularity.unwrap_or_default()) }).await
+}
+
+/// 获取指标异常检测配置
+#[tauri::command]
+async fn get_anomaly_config() -> Result<AnomalyConfig, String> {
+    crate::core::plugin_isolation::guard("system_diagnostic", async move { Ok(AnomalyDetector::global().lock().map_err(|e| e.to_string())?.config().clone()) }).await
+}
+
+/// 保存指标异常检测配置（各指标灵敏度）
+#[tauri::command]
+async fn set_anomaly_config(config: AnomalyConfig) -> Result<AnomalyConfig, String> {
+    crate::core::plugin_isolation::guard("system_diagnostic", set_anomaly_config_impl(config)).await
+}
+
+async fn set_anomaly_config_impl(config: AnomalyConfig) -> Result<AnomalyConfig, String> {
+    let mut detector = AnomalyDetector::global().lock().map_err(|e| e.to_string())?;
+    detector.set_config(config)?;
+    Ok(detector.config().clone())
+}
+
+/// 最近检测到的指标异常
+#[tauri::command]
+async fn get_recent_anomalies() -> Result<Vec<Anomaly>, String> {
+    crate::core::plugin_isolation::guard("system_diagnostic", async move { Ok(AnomalyDetector::global().lock().map_err(|e| e.to_string())?.recent()) }).await
+}
+
+/// 立即执行一次异常检测，新异常同时以通知事件发出
+#[tauri::command]
+async fn run_anomaly_check_now<R: Runtime>(app: AppHandle<R>) -> Result<Vec<Anomaly>, String> {
+    crate::core::plugin_isolation::guard("system_diagnostic", async move { anomaly::check_now(&app) }).await
+}
+
+/// 查询命令审计记录（按命令名、时间区间、分发结果筛选，时间倒序）
+#[tauri::command]
+async fn query_command_audit(query: Option<AuditQuery>) -> Result<Vec<AuditEntry>, String> {
+    crate::core::plugin_isolation::guard("system_diagnostic", async move { COMMAND_AUDIT.query(&query.unwrap_or_default()) }).await
+}
+
+/// 快捷动作列表（内置 + 该用户的自定义动作）
+#[tauri::command]
+async fn list_quick_actions(user_id: Option<String>) -> Result<Vec<QuickAction>, String> {
+    crate::core::plugin_isolation::guard("system_diagnostic", async move { Ok(QuickActionStore::global().lock().map_err(|e| e.to_string())?.actions(user_id.as_deref())) }).await
+}
+
+/// 新增或更新自定义快捷动作
+#[tauri::command]
+async fn save_quick_action(user_id: Option<String>, action: QuickAction) -> Result<QuickAction, String> {
+    crate::core::plugin_isolation::guard("system_diagnostic", async move { QuickActionStore::global().lock().map_err(|e| e.to_string())?.save_action(user_id.as_deref(), action) }).await
+}
+
+/// 删除自定义快捷动作（仍有快捷键绑定时拒绝）
+#[tauri::command]
+async fn delete_quick_action(user_id: Option<String>, action_id: String) -> Result<bool, String> {
+    crate::core::plugin_isolation::guard("system_diagnostic", async move { QuickActionStore::global().lock().map_err(|e| e.to_string())?.delete_action(user_id.as_deref(), &action_id) }).await
+}
+
+/// 该用户的快捷键绑定
+#[tauri::command]
+async fn list_quick_action_bindings(user_id: Option<String>) -> Result<Vec<QuickActionBinding>, String> {
+    crate::core::plugin_isolation::guard("system_diagnostic", async move { Ok(QuickActionStore::global().lock().map_err(|e| e.to_string())?.bindings(user_id.as_deref())) }).await
+}
+
+/// 绑定快捷键到动作（同一组合键覆盖旧绑定）
+#[tauri::command]
+async fn bind_quick_action(user_id: Option<String>, hotkey: String, action_id: String) -> Result<QuickActionBinding, String> {
+    crate::core::plugin_isolation::guard("system_diagnostic", async move { QuickActionStore::global().lock().map_err(|e| e.to_string())?.bind(user_id.as_deref(), &hotkey, &action_id) }).await
+}
+
+/// 解除快捷键绑定
+#[tauri::command]
+async fn unbind_quick_action(user_id: Option<String>, hotkey: String) -> Result<bool, String> {
+    crate::core::plugin_isolation::guard("system_diagnostic", async move { QuickActionStore::global().lock().map_err(|e| e.to_string())?.unbind(user_id.as_deref(), &hotkey) }).await
+}
+
+/// 按动作 ID 在后端执行快捷动作的全部步骤
+#[tauri::command]
+async fn dispatch_quick_action<R: Runtime>(
+    app: AppHandle<R>,
+    user_id: Option<String>,
+    action_id: String,
+    context: Option<QuickActionContext>,
+) -> Result<QuickActionOutcome, String> {
+    crate::core::plugin_isolation::guard("system_diagnostic", dispatch_quick_action_impl(app, user_id, action_id, context)).await
+}
+
+async fn dispatch_quick_action_impl<R: Runtime>(
+    app: AppHandle<R>,
+    user_id: Option<String>,
+    action_id: String,
+    context: Option<QuickActionContext>,
+) -> Result<QuickActionOutcome, String> {
+    let action = QuickActionStore::global()
+        .lock()
+        .map_err(|e| e.to_string())?
+        .find_action(user_id.as_deref(), &action_id)
+        .ok_or_else(|| format!("快捷动作不存在: {}", action_id))?;
+    tracing::info!("⚡ [Plugin:diagnostic] 执行快捷动作: {}", action.id);
+    Ok(quick_actions::dispatch(&action, &context.unwrap_or_default(), &TauriQuickCommandRunner::new(app)).await)
+}
+
+/// 导出脱敏后的联系人 / 精准获客库（供支持与开发排查使用的演示数据集）
+#[tauri::command]
+async fn export_demo_dataset<R: Runtime>(
+    app: AppHandle<R>,
+    output_path: String,
+    salt: Option<String>,
+) -> Result<DemoDatasetReport, String> {
+    crate::core::plugin_isolation::guard("system_diagnostic", export_demo_dataset_impl(app, output_path, salt)).await
+}
+
+async fn export_demo_dataset_impl<R: Runtime>(
+    app: AppHandle<R>,
+    output_path: String,
+    salt: Option<String>,
+) -> Result<DemoDatasetReport, String> {
+    let data_dir = app.path().app_data_dir().map_err(|e| e.to_string())?;
+    let sources = vec![
+        DatasetSource { name: "contacts.db", path: contacts_db_path(&app), plan: CONTACTS_PLAN },
+        DatasetSource { name: "prospecting.db", path: data_dir.join(PROSPECTING_DB_FILE), plan: PROSPECTING_PLAN },
+    ];
+    tracing::info!("📦 [Plugin:diagnostic] 导出演示数据集: {}", output_path);
+    tokio::task::spawn_blocking(move || demo_dataset::export_demo_dataset(&sources, std::path::Path::new(&output_path), salt.as_deref()))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// 并发控制实时状态（运行中 / 排队中的设备与 AI 工作）
+#[tauri::command]
+async fn get_concurrency_status() -> Result<ConcurrencyStatus, String> {
+    crate::core::plugin_isolation::guard("system_diagnostic", async move { Ok(ConcurrencyController::global().status()) }).await
+}
+
+/// 修改并发上限（0 表示不限），立即按新上限放行排队工作
+#[tauri::command]
+async fn set_concurrency_limits(limits: ConcurrencyLimits) -> Result<ConcurrencyStatus, String> {
+    crate::core::plugin_isolation::guard("system_diagnostic", async move { ConcurrencyController::global().set_limits(limits) }).await
+}
+
+/// 前端重连后补发 `cursor` 之后的任务状态事件；`correlation_ids` 可限定任务
+#[tauri::command]
+async fn replay_events_since<R: Runtime>(
+    window: Window<R>,
+    cursor: u64,
+    correlation_ids: Option<Vec<String>>,
+    limit: Option<usize>,
+) -> Result<EventReplay, String> {
+    crate::core::plugin_isolation::guard("system_diagnostic", replay_events_since_impl(window, cursor, correlation_ids, limit)).await
+}
+
+async fn replay_events_since_impl<R: Runtime>(
+    window: Window<R>,
+    cursor: u64,
+    correlation_ids: Option<Vec<String>>,
+    limit: Option<usize>,
+) -> Result<EventReplay, String> {
+    let mut outbox = EventOutbox::global().lock().map_err(|e| e.to_string())?;
+    Ok(outbox.replay_since(window.label(), cursor, correlation_ids.as_deref(), limit.unwrap_or(500), chrono::Utc::now()))
+}
+
+/// 确认已处理到 `cursor` 的事件，返回被清理的条数
+#[tauri::command]
+async fn ack_events<R: Runtime>(window: Window<R>, cursor: u64) -> Result<usize, String> {
+    crate::core::plugin_isolation::guard("system_diagnostic", ack_events_impl(window, cursor)).await
+}
+
+async fn ack_events_impl<R: Runtime>(window: Window<R>, cursor: u64) -> Result<usize, String> {
+    let mut outbox = EventOutbox::global().lock().map_err(|e| e.to_string())?;
+    Ok(outbox.acknowledge(window.label(), cursor, chrono::Utc::now()))
+}
+
+/// 查询前端事件负载的 TypeScript 定义（与 gen_ts_bindings 生成的 bindings 同源）
+#[tauri::command]
+async fn get_event_schema(event_name: String) -> Result<EventSchema, String> {
+    crate::core::plugin_isolation::guard("system_diagnostic", async move { event_bindings::get_event_schema(&event_name) }).await
+}
+
+/// 重新初始化降级插件的状态（命令 panic 后调用）
+#[tauri::command]
+async fn restart_module(name: String) -> Result<PluginStatus, String> {
+    crate::core::plugin_isolation::guard("system_diagnostic", async move { plugin_isolation::restart_module(&name) }).await
 }
 
 pub fn init<R: Runtime>() -> TauriPlugin<R> {
     Builder::new("system_diagnostic")
-        .invoke_handler(tauri::generate_handler![
+        .invoke_handler(crate::core::plugin_isolation::isolate("system_diagnostic", tauri::generate_handler![
             ping,
             health_check,
             get_adb_path,
@@ -152,7 +489,52 @@ pub fn init<R: Runtime>() -> TauriPlugin<R> {
             test_click_normalization,
             analyze_xml_structure,
             clear_logs,
-            add_log_entry
-        ])
+            add_log_entry,
+            get_plugin_status,
+            run_scenario,
+            get_locale,
+            set_locale,
+            get_license_status,
+            activate_license,
+            deactivate_license,
+            get_pending_telemetry,
+            get_telemetry_config,
+            set_telemetry_config,
+            upload_telemetry_now,
+            start_support_session,
+            stop_support_session,
+            get_support_session_status,
+            restart_module,
+            get_read_only_mode,
+            set_read_only_mode,
+            get_timeseries,
+            get_anomaly_config,
+            set_anomaly_config,
+            get_recent_anomalies,
+            run_anomaly_check_now,
+            query_command_audit,
+            list_quick_actions,
+            save_quick_action,
+            delete_quick_action,
+            list_quick_action_bindings,
+            bind_quick_action,
+            unbind_quick_action,
+            dispatch_quick_action,
+            export_demo_dataset,
+            get_concurrency_status,
+            set_concurrency_limits,
+            replay_events_since,
+            ack_events,
+            get_event_schema
+        ]))
+        .setup(|_app, _api| {
+            plugin_isolation::register_restart("system_diagnostic", || {
+                LicenseManager::global().clear_poison();
+                Telemetry::global().clear_poison();
+                QuickActionStore::global().clear_poison();
+                Ok(())
+            });
+            Ok(())
+        })
         .build()
 }
@@ -19,6 +19,7 @@ use tauri::{
 use tokio::sync::RwLock;
 use tracing::{error, info, warn};
 
+use crate::types::{AppError, AppResult, ErrorCode};
 use crate::utils::adb_utils::get_adb_path;
 
 use ui_dump_config::{ConfigSummary, UiDumpConfigManager};
@@ -60,30 +61,46 @@ impl UiDumpState {
 // Tauri 命令
 // ============================================================================
 
+fn save_failed(e: anyhow::Error) -> AppError {
+    AppError::new(ErrorCode::StorageError, "保存 UI Dump 配置失败").with_context(e.to_string())
+}
+
 /// 获取当前模式配置
 #[tauri::command]
-async fn get_mode(state: State<'_, UiDumpState>) -> Result<DumpMode, String> {
+async fn get_mode(state: State<'_, UiDumpState>) -> AppResult<DumpMode> {
+    crate::core::plugin_isolation::guard("ui_dump", get_mode_impl(state)).await
+}
+
+async fn get_mode_impl(state: State<'_, UiDumpState>) -> AppResult<DumpMode> {
     let manager = state.config_manager.read().await;
     Ok(manager.get_preferred_mode())
 }
 
 /// 设置首选模式
 #[tauri::command]
-async fn set_mode(mode: DumpMode, state: State<'_, UiDumpState>) -> Result<(), String> {
+async fn set_mode(mode: DumpMode, state: State<'_, UiDumpState>) -> AppResult<()> {
+    crate::core::plugin_isolation::guard("ui_dump", set_mode_impl(mode, state)).await
+}
+
+async fn set_mode_impl(mode: DumpMode, state: State<'_, UiDumpState>) -> AppResult<()> {
     let mut manager = state.config_manager.write().await;
     manager.set_preferred_mode(mode);
-    manager.save().await.map_err(|e| e.to_string())?;
+    manager.save().await.map_err(save_failed)?;
     info!("✅ UI Dump 模式已设置为: {:?}", mode);
     Ok(())
 }
 
 /// 执行 UI Dump
 #[tauri::command]
-async fn dump(device_id: String, state: State<'_, UiDumpState>) -> Result<DumpResult, String> {
+async fn dump(device_id: String, state: State<'_, UiDumpState>) -> AppResult<DumpResult> {
+    crate::core::plugin_isolation::guard("ui_dump", dump_impl(device_id, state)).await
+}
+
+async fn dump_impl(device_id: String, state: State<'_, UiDumpState>) -> AppResult<DumpResult> {
     state.provider
         .dump(&device_id)
         .await
-        .map_err(|e| e.to_string())
+        .map_err(|e| AppError::ui_dump_failed(&device_id, e))
 }
 
 /// 执行 UI Dump 并保存到文件
@@ -98,14 +115,23 @@ async fn dump_and_save(
     save_dir: Option<String>,
     take_screenshot: Option<bool>,
     state: State<'_, UiDumpState>,
-) -> Result<DumpAndSaveResult, String> {
+) -> AppResult<DumpAndSaveResult> {
+    crate::core::plugin_isolation::guard("ui_dump", dump_and_save_impl(device_id, save_dir, take_screenshot, state)).await
+}
+
+async fn dump_and_save_impl(
+    device_id: String,
+    save_dir: Option<String>,
+    take_screenshot: Option<bool>,
+    state: State<'_, UiDumpState>,
+) -> AppResult<DumpAndSaveResult> {
     let save_path = save_dir.map(std::path::PathBuf::from);
     let screenshot = take_screenshot.unwrap_or(false);
     
     state.provider
         .dump_and_save(&device_id, save_path, screenshot)
         .await
-        .map_err(|e| e.to_string())
+        .map_err(|e| AppError::ui_dump_failed(&device_id, e))
 }
 
 /// 测试指定模式
@@ -114,55 +140,83 @@ async fn test_mode(
     device_id: String,
     mode: DumpMode,
     state: State<'_, UiDumpState>,
-) -> Result<DumpResult, String> {
+) -> AppResult<DumpResult> {
+    crate::core::plugin_isolation::guard("ui_dump", test_mode_impl(device_id, mode, state)).await
+}
+
+async fn test_mode_impl(
+    device_id: String,
+    mode: DumpMode,
+    state: State<'_, UiDumpState>,
+) -> AppResult<DumpResult> {
     state.provider
         .test_mode(&device_id, mode)
         .await
-        .map_err(|e| e.to_string())
+        .map_err(|e| AppError::ui_dump_failed(&device_id, e))
 }
 
 /// 获取诊断日志
 #[tauri::command]
-async fn get_diagnostics(state: State<'_, UiDumpState>) -> Result<Vec<DiagnosticEntry>, String> {
-    Ok(state.provider.get_diagnostics().await)
+async fn get_diagnostics(state: State<'_, UiDumpState>) -> AppResult<Vec<DiagnosticEntry>> {
+    crate::core::plugin_isolation::guard("ui_dump", async move { Ok(state.provider.get_diagnostics().await) }).await
 }
 
 /// 获取诊断摘要
 #[tauri::command]
-async fn get_diagnostic_summary(state: State<'_, UiDumpState>) -> Result<DiagnosticSummary, String> {
+async fn get_diagnostic_summary(state: State<'_, UiDumpState>) -> AppResult<DiagnosticSummary> {
+    crate::core::plugin_isolation::guard("ui_dump", get_diagnostic_summary_impl(state)).await
+}
+
+async fn get_diagnostic_summary_impl(state: State<'_, UiDumpState>) -> AppResult<DiagnosticSummary> {
     let diagnostics = state.diagnostics.read().await;
     Ok(diagnostics.generate_summary())
 }
 
 /// 清空诊断日志
 #[tauri::command]
-async fn clear_diagnostics(state: State<'_, UiDumpState>) -> Result<(), String> {
+async fn clear_diagnostics(state: State<'_, UiDumpState>) -> AppResult<()> {
+    crate::core::plugin_isolation::guard("ui_dump", clear_diagnostics_impl(state)).await
+}
+
+async fn clear_diagnostics_impl(state: State<'_, UiDumpState>) -> AppResult<()> {
     state.provider.clear_diagnostics().await;
     Ok(())
 }
 
 /// 获取配置摘要
 #[tauri::command]
-async fn get_config(state: State<'_, UiDumpState>) -> Result<ConfigSummary, String> {
+async fn get_config(state: State<'_, UiDumpState>) -> AppResult<ConfigSummary> {
+    crate::core::plugin_isolation::guard("ui_dump", get_config_impl(state)).await
+}
+
+async fn get_config_impl(state: State<'_, UiDumpState>) -> AppResult<ConfigSummary> {
     let manager = state.config_manager.read().await;
     Ok(ConfigSummary::from(manager.get_config()))
 }
 
 /// 设置 ExecOut 超时时间
 #[tauri::command]
-async fn set_exec_out_timeout(timeout_ms: u64, state: State<'_, UiDumpState>) -> Result<(), String> {
+async fn set_exec_out_timeout(timeout_ms: u64, state: State<'_, UiDumpState>) -> AppResult<()> {
+    crate::core::plugin_isolation::guard("ui_dump", set_exec_out_timeout_impl(timeout_ms, state)).await
+}
+
+async fn set_exec_out_timeout_impl(timeout_ms: u64, state: State<'_, UiDumpState>) -> AppResult<()> {
     let mut manager = state.config_manager.write().await;
     manager.set_exec_out_timeout(timeout_ms);
-    manager.save().await.map_err(|e| e.to_string())?;
+    manager.save().await.map_err(save_failed)?;
     Ok(())
 }
 
 /// 设置 DumpPull 超时时间
 #[tauri::command]
-async fn set_dump_pull_timeout(timeout_ms: u64, state: State<'_, UiDumpState>) -> Result<(), String> {
+async fn set_dump_pull_timeout(timeout_ms: u64, state: State<'_, UiDumpState>) -> AppResult<()> {
+    crate::core::plugin_isolation::guard("ui_dump", set_dump_pull_timeout_impl(timeout_ms, state)).await
+}
+
+async fn set_dump_pull_timeout_impl(timeout_ms: u64, state: State<'_, UiDumpState>) -> AppResult<()> {
     let mut manager = state.config_manager.write().await;
     manager.set_dump_pull_timeout(timeout_ms);
-    manager.save().await.map_err(|e| e.to_string())?;
+    manager.save().await.map_err(save_failed)?;
     Ok(())
 }
 
@@ -171,29 +225,44 @@ async fn set_dump_pull_timeout(timeout_ms: u64, state: State<'_, UiDumpState>) -
 async fn clear_device_compat(
     device_id: Option<String>,
     state: State<'_, UiDumpState>,
-) -> Result<(), String> {
+) -> AppResult<()> {
+    crate::core::plugin_isolation::guard("ui_dump", clear_device_compat_impl(device_id, state)).await
+}
+
+async fn clear_device_compat_impl(
+    device_id: Option<String>,
+    state: State<'_, UiDumpState>,
+) -> AppResult<()> {
     let mut manager = state.config_manager.write().await;
     if let Some(id) = device_id {
         manager.clear_device_compat(&id);
     } else {
         manager.clear_device_compat_cache();
     }
-    manager.save().await.map_err(|e| e.to_string())?;
+    manager.save().await.map_err(save_failed)?;
     Ok(())
 }
 
 /// 重置配置为默认值
 #[tauri::command]
-async fn reset_config(state: State<'_, UiDumpState>) -> Result<(), String> {
+async fn reset_config(state: State<'_, UiDumpState>) -> AppResult<()> {
+    crate::core::plugin_isolation::guard("ui_dump", reset_config_impl(state)).await
+}
+
+async fn reset_config_impl(state: State<'_, UiDumpState>) -> AppResult<()> {
     let mut manager = state.config_manager.write().await;
     manager.reset_to_default();
-    manager.save().await.map_err(|e| e.to_string())?;
+    manager.save().await.map_err(save_failed)?;
     Ok(())
 }
 
 /// 获取所有可用模式列表
 #[tauri::command]
-async fn list_modes() -> Result<Vec<ModeInfo>, String> {
+async fn list_modes() -> AppResult<Vec<ModeInfo>> {
+    crate::core::plugin_isolation::guard("ui_dump", list_modes_impl()).await
+}
+
+async fn list_modes_impl() -> AppResult<Vec<ModeInfo>> {
     Ok(vec![
         ModeInfo {
             mode: DumpMode::Auto,
@@ -224,7 +293,11 @@ async fn list_modes() -> Result<Vec<ModeInfo>, String> {
 
 /// 检查 Android App 连接状态（简单版）
 #[tauri::command]
-async fn check_android_app_status(device_id: String) -> Result<AndroidAppStatus, String> {
+async fn check_android_app_status(device_id: String) -> AppResult<AndroidAppStatus> {
+    crate::core::plugin_isolation::guard("ui_dump", check_android_app_status_impl(device_id)).await
+}
+
+async fn check_android_app_status_impl(device_id: String) -> AppResult<AndroidAppStatus> {
     use tokio::net::TcpStream;
     use tokio::time::timeout;
     use std::time::Duration;
@@ -280,7 +353,11 @@ async fn check_android_app_status(device_id: String) -> Result<AndroidAppStatus,
 /// 
 /// 执行多个步骤的诊断，返回每个步骤的详细状态
 #[tauri::command]
-async fn diagnose_android_app(device_id: String) -> Result<AndroidAppDiagnosis, String> {
+async fn diagnose_android_app(device_id: String) -> AppResult<AndroidAppDiagnosis> {
+    crate::core::plugin_isolation::guard("ui_dump", diagnose_android_app_impl(device_id)).await
+}
+
+async fn diagnose_android_app_impl(device_id: String) -> AppResult<AndroidAppDiagnosis> {
     use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
     use tokio::net::TcpStream;
     use tokio::time::timeout;
@@ -606,7 +683,7 @@ pub struct ModeInfo {
 /// - Linux: `~/.config/<app>/dump_config.json`
 pub fn init<R: Runtime>() -> TauriPlugin<R> {
     Builder::new("ui_dump")
-        .invoke_handler(tauri::generate_handler![
+        .invoke_handler(crate::core::plugin_isolation::isolate("ui_dump", tauri::generate_handler![
             get_mode,
             set_mode,
             dump,
@@ -623,7 +700,7 @@ pub fn init<R: Runtime>() -> TauriPlugin<R> {
             list_modes,
             check_android_app_status,
             diagnose_android_app,
-        ])
+        ]))
         .setup(|app, _api| {
             // 获取应用数据目录
             let app_data_dir = match app.path().app_data_dir() {
@@ -24,6 +24,13 @@ use crate::services::universal_ui_page_analyzer::{
 async fn analyze_page<R: Runtime>(
     app_handle: tauri::AppHandle<R>,
     device_id: String,
+) -> Result<UniversalPageCaptureResult, String> {
+    crate::core::plugin_isolation::guard("universal_ui", analyze_page_impl(app_handle, device_id)).await
+}
+
+async fn analyze_page_impl<R: Runtime>(
+    app_handle: tauri::AppHandle<R>,
+    device_id: String,
 ) -> Result<UniversalPageCaptureResult, String> {
     info!("🔌 [Plugin:universal_ui] 调用 analyze_page，设备: {}", device_id);
     
@@ -35,6 +42,12 @@ async fn analyze_page<R: Runtime>(
 #[tauri::command]
 async fn extract_elements(
     xml_content: String,
+) -> Result<Vec<UIElement>, String> {
+    crate::core::plugin_isolation::guard("universal_ui", extract_elements_impl(xml_content)).await
+}
+
+async fn extract_elements_impl(
+    xml_content: String,
 ) -> Result<Vec<UIElement>, String> {
     info!("🔌 [Plugin:universal_ui] 调用 extract_elements，XML长度: {}", xml_content.len());
     
@@ -49,6 +62,15 @@ async fn acknowledge_event(
     _event_type: String,
     _acknowledged_at: Option<i64>,
     _additional_data: Option<serde_json::Value>,
+) -> Result<(), String> {
+    crate::core::plugin_isolation::guard("universal_ui", acknowledge_event_impl(_event_id, _event_type, _acknowledged_at, _additional_data)).await
+}
+
+async fn acknowledge_event_impl(
+    _event_id: String,
+    _event_type: String,
+    _acknowledged_at: Option<i64>,
+    _additional_data: Option<serde_json::Value>,
 ) -> Result<(), String> {
     // Stub implementation
     Ok(())
@@ -58,6 +80,12 @@ async fn acknowledge_event(
 #[tauri::command]
 async fn classify_elements(
     elements: Vec<UIElement>,
+) -> Result<HashMap<String, Vec<UIElement>>, String> {
+    crate::core::plugin_isolation::guard("universal_ui", classify_elements_impl(elements)).await
+}
+
+async fn classify_elements_impl(
+    elements: Vec<UIElement>,
 ) -> Result<HashMap<String, Vec<UIElement>>, String> {
     info!("🔌 [Plugin:universal_ui] 调用 classify_elements，元素数: {}", elements.len());
     
@@ -69,6 +97,12 @@ async fn classify_elements(
 #[tauri::command]
 async fn deduplicate(
     elements: Vec<UIElement>,
+) -> Result<Vec<UIElement>, String> {
+    crate::core::plugin_isolation::guard("universal_ui", deduplicate_impl(elements)).await
+}
+
+async fn deduplicate_impl(
+    elements: Vec<UIElement>,
 ) -> Result<Vec<UIElement>, String> {
     info!("🔌 [Plugin:universal_ui] 调用 deduplicate，元素数: {}", elements.len());
     
@@ -81,6 +115,13 @@ async fn deduplicate(
 async fn identify_page(
     xml_content: String,
     app_package: String,
+) -> Result<String, String> {
+    crate::core::plugin_isolation::guard("universal_ui", identify_page_impl(xml_content, app_package)).await
+}
+
+async fn identify_page_impl(
+    xml_content: String,
+    app_package: String,
 ) -> Result<String, String> {
     info!("🔌 [Plugin:universal_ui] 调用 identify_page，包名: {}", app_package);
     
@@ -90,36 +131,40 @@ async fn identify_page(
 
 #[tauri::command]
 async fn save_page_analysis(_analysis: serde_json::Value) -> Result<String, String> {
-    Ok("stub_id".to_string())
+    crate::core::plugin_isolation::guard("universal_ui", async move { Ok("stub_id".to_string()) }).await
 }
 
 #[tauri::command]
 async fn get_page_analysis_by_id(_analysis_id: String) -> Result<serde_json::Value, String> {
-    Ok(serde_json::json!({}))
+    crate::core::plugin_isolation::guard("universal_ui", async move { Ok(serde_json::json!({})) }).await
 }
 
 #[tauri::command]
 async fn get_page_analyses_by_device(_device_id: String, _limit: Option<u32>) -> Result<Vec<serde_json::Value>, String> {
-    Ok(vec![])
+    crate::core::plugin_isolation::guard("universal_ui", async move { Ok(vec![]) }).await
 }
 
 #[tauri::command]
 async fn get_page_analyses_by_app(_app_package: String, _limit: Option<u32>) -> Result<Vec<serde_json::Value>, String> {
-    Ok(vec![])
+    crate::core::plugin_isolation::guard("universal_ui", async move { Ok(vec![]) }).await
 }
 
 #[tauri::command]
 async fn get_page_analyses_by_type(_page_type: String, _limit: Option<u32>) -> Result<Vec<serde_json::Value>, String> {
-    Ok(vec![])
+    crate::core::plugin_isolation::guard("universal_ui", async move { Ok(vec![]) }).await
 }
 
 #[tauri::command]
 async fn cleanup_old_page_analyses(_older_than_days: i32) -> Result<usize, String> {
-    Ok(0)
+    crate::core::plugin_isolation::guard("universal_ui", async move { Ok(0) }).await
 }
 
 #[tauri::command]
 async fn get_page_analysis_statistics() -> Result<serde_json::Value, String> {
+    crate::core::plugin_isolation::guard("universal_ui", get_page_analysis_statistics_impl()).await
+}
+
+async fn get_page_analysis_statistics_impl() -> Result<serde_json::Value, String> {
     Ok(serde_json::json!({
         "total_count": 0,
         "total_size_bytes": 0,
@@ -130,12 +175,12 @@ async fn get_page_analysis_statistics() -> Result<serde_json::Value, String> {
 
 #[tauri::command]
 async fn delete_page_analysis(_analysis_id: String) -> Result<(), String> {
-    Ok(())
+    crate::core::plugin_isolation::guard("universal_ui", async move { Ok(()) }).await
 }
 
 #[tauri::command]
 async fn delete_page_analyses_by_device(_device_id: String) -> Result<usize, String> {
-    Ok(0)
+    crate::core::plugin_isolation::guard("universal_ui", async move { Ok(0) }).await
 }
 
 // ==================== 插件初始化 ====================
@@ -152,7 +197,7 @@ pub fn init<R: Runtime>() -> TauriPlugin<R> {
     info!("🔌 初始化 Universal UI 插件");
     
     Builder::new("universal_ui")
-        .invoke_handler(tauri::generate_handler![
+        .invoke_handler(crate::core::plugin_isolation::isolate("universal_ui", tauri::generate_handler![
             analyze_page,
             extract_elements,
             acknowledge_event,
@@ -168,6 +213,6 @@ pub fn init<R: Runtime>() -> TauriPlugin<R> {
             get_page_analysis_statistics,
             delete_page_analysis,
             delete_page_analyses_by_device
-        ])
+        ]))
         .build()
 }
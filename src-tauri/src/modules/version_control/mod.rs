@@ -7,6 +7,8 @@ use crate::domain::analysis_cache::version_commands::{
     self, BranchRequest, ComputeDiffRequest, CreateVersionRequest, InitVersionControlRequest,
     RebuildVersionRequest, VersionQueryRequest,
 };
+use crate::domain::analysis_cache::screen_changelog::ScreenChangelog;
+use crate::domain::analysis_cache::selector_stability::{SelectorStabilityReport, StabilitySelector};
 use crate::domain::analysis_cache::version_control::{
     Branch, IntegrityReport, StorageStats, XmlDelta, XmlVersion,
 };
@@ -15,58 +17,72 @@ use crate::domain::analysis_cache::version_control::{
 
 #[tauri::command]
 async fn init_version_control(request: InitVersionControlRequest) -> Result<String, String> {
-    version_commands::init_version_control(request).await
+    crate::core::plugin_isolation::guard("version_control", async move { version_commands::init_version_control(request).await }).await
 }
 
 #[tauri::command]
 async fn create_version(request: CreateVersionRequest) -> Result<String, String> {
-    version_commands::create_version(request).await
+    crate::core::plugin_isolation::guard("version_control", async move { version_commands::create_version(request).await }).await
 }
 
 #[tauri::command]
 async fn query_versions(request: VersionQueryRequest) -> Result<Vec<XmlVersion>, String> {
-    version_commands::query_versions(request).await
+    crate::core::plugin_isolation::guard("version_control", async move { version_commands::query_versions(request).await }).await
 }
 
 #[tauri::command]
 async fn create_branch(request: BranchRequest) -> Result<Branch, String> {
-    version_commands::create_branch(request).await
+    crate::core::plugin_isolation::guard("version_control", async move { version_commands::create_branch(request).await }).await
 }
 
 #[tauri::command]
 async fn list_branches() -> Result<Vec<Branch>, String> {
-    version_commands::list_branches().await
+    crate::core::plugin_isolation::guard("version_control", async move { version_commands::list_branches().await }).await
 }
 
 #[tauri::command]
 async fn compute_xml_diff(request: ComputeDiffRequest) -> Result<XmlDelta, String> {
-    version_commands::compute_xml_diff(request).await
+    crate::core::plugin_isolation::guard("version_control", async move { version_commands::compute_xml_diff(request).await }).await
 }
 
 #[tauri::command]
 async fn rebuild_version(request: RebuildVersionRequest) -> Result<String, String> {
-    version_commands::rebuild_version(request).await
+    crate::core::plugin_isolation::guard("version_control", async move { version_commands::rebuild_version(request).await }).await
 }
 
 #[tauri::command]
 async fn get_version_storage_stats() -> Result<StorageStats, String> {
-    version_commands::get_version_storage_stats().await
+    crate::core::plugin_isolation::guard("version_control", async move { version_commands::get_version_storage_stats().await }).await
 }
 
 #[tauri::command]
 async fn check_version_integrity() -> Result<IntegrityReport, String> {
-    version_commands::check_version_integrity().await
+    crate::core::plugin_isolation::guard("version_control", async move { version_commands::check_version_integrity().await }).await
 }
 
 #[tauri::command]
 async fn delete_version(version_id: String) -> Result<String, String> {
-    version_commands::delete_version(version_id).await
+    crate::core::plugin_isolation::guard("version_control", async move { version_commands::delete_version(version_id).await }).await
+}
+
+#[tauri::command]
+async fn analyze_selector_stability(
+    snapshot_series_id: String,
+    selector: StabilitySelector,
+    depth: Option<usize>,
+) -> Result<SelectorStabilityReport, String> {
+    crate::core::plugin_isolation::guard("version_control", async move { version_commands::analyze_selector_stability(snapshot_series_id, selector, depth).await }).await
+}
+
+#[tauri::command]
+async fn compare_screen_versions(version_a: String, version_b: String) -> Result<ScreenChangelog, String> {
+    crate::core::plugin_isolation::guard("version_control", async move { version_commands::compare_screen_versions(version_a, version_b).await }).await
 }
 
 // 2. Plugin Initialization
 pub fn init<R: Runtime>() -> TauriPlugin<R> {
     Builder::new("version_control")
-        .invoke_handler(tauri::generate_handler![
+        .invoke_handler(crate::core::plugin_isolation::isolate("version_control", tauri::generate_handler![
             init_version_control,
             create_version,
             query_versions,
@@ -76,7 +92,9 @@ pub fn init<R: Runtime>() -> TauriPlugin<R> {
             rebuild_version,
             get_version_storage_stats,
             check_version_integrity,
-            delete_version
-        ])
+            delete_version,
+            analyze_selector_stability,
+            compare_screen_versions
+        ]))
         .build()
 }
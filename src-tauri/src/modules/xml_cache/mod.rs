@@ -77,6 +77,10 @@ pub struct XmlCacheFileMetadata {
 
 #[tauri::command]
 async fn list_xml_cache_files() -> Result<Vec<String>, String> {
+    crate::core::plugin_isolation::guard("xml_cache", list_xml_cache_files_impl()).await
+}
+
+async fn list_xml_cache_files_impl() -> Result<Vec<String>, String> {
     use std::fs;
     let debug_dir = get_debug_xml_dir();
     if !debug_dir.exists() { return Ok(vec![]); }
@@ -100,6 +104,10 @@ async fn list_xml_cache_files() -> Result<Vec<String>, String> {
 /// 预期性能：42个文件 < 50ms（仅文件系统操作）
 #[tauri::command]
 async fn list_xml_cache_files_quick() -> Result<Vec<XmlCacheFileQuickMetadata>, String> {
+    crate::core::plugin_isolation::guard("xml_cache", list_xml_cache_files_quick_impl()).await
+}
+
+async fn list_xml_cache_files_quick_impl() -> Result<Vec<XmlCacheFileQuickMetadata>, String> {
     use std::fs;
     use std::time::Instant;
     use regex::Regex;
@@ -190,6 +198,10 @@ async fn list_xml_cache_files_quick() -> Result<Vec<XmlCacheFileQuickMetadata>,
 /// 优化后：一次调用返回所有文件的完整元数据
 #[tauri::command]
 async fn list_xml_cache_files_with_metadata() -> Result<Vec<XmlCacheFileMetadata>, String> {
+    crate::core::plugin_isolation::guard("xml_cache", list_xml_cache_files_with_metadata_impl()).await
+}
+
+async fn list_xml_cache_files_with_metadata_impl() -> Result<Vec<XmlCacheFileMetadata>, String> {
     use std::fs;
     use std::time::Instant;
     use regex::Regex;
@@ -401,6 +413,10 @@ fn identify_page_type_fast(app_package: &str, xml_content: &str) -> String {
 
 #[tauri::command]
 async fn read_xml_cache_file(file_name: String) -> Result<String, String> {
+    crate::core::plugin_isolation::guard("xml_cache", read_xml_cache_file_impl(file_name)).await
+}
+
+async fn read_xml_cache_file_impl(file_name: String) -> Result<String, String> {
     use std::fs;
     let debug_dir = get_debug_xml_dir();
     let file_path = debug_dir.join(&file_name);
@@ -434,6 +450,10 @@ pub struct XmlContentAnalysis {
 
 #[tauri::command]
 async fn analyze_xml_cache_file(file_name: String) -> Result<XmlContentAnalysis, String> {
+    crate::core::plugin_isolation::guard("xml_cache", analyze_xml_cache_file_impl(file_name)).await
+}
+
+async fn analyze_xml_cache_file_impl(file_name: String) -> Result<XmlContentAnalysis, String> {
     use std::fs;
     use std::time::Instant;
     
@@ -467,6 +487,10 @@ async fn analyze_xml_cache_file(file_name: String) -> Result<XmlContentAnalysis,
 
 #[tauri::command]
 async fn get_xml_file_size(file_name: String) -> Result<u64, String> {
+    crate::core::plugin_isolation::guard("xml_cache", get_xml_file_size_impl(file_name)).await
+}
+
+async fn get_xml_file_size_impl(file_name: String) -> Result<u64, String> {
     use std::fs;
     let debug_dir = get_debug_xml_dir();
     let file_path = debug_dir.join(&file_name);
@@ -476,6 +500,10 @@ async fn get_xml_file_size(file_name: String) -> Result<u64, String> {
 
 #[tauri::command]
 async fn get_xml_file_absolute_path(file_name: String) -> Result<String, String> {
+    crate::core::plugin_isolation::guard("xml_cache", get_xml_file_absolute_path_impl(file_name)).await
+}
+
+async fn get_xml_file_absolute_path_impl(file_name: String) -> Result<String, String> {
     use std::fs;
     // 原有逻辑：优先使用父目录的 debug_xml
     let primary_debug_dir = get_debug_xml_dir();
@@ -514,6 +542,13 @@ async fn get_xml_file_absolute_path(file_name: String) -> Result<String, String>
 async fn delete_xml_cache_artifacts(
     xml_file_name: String,
     screenshot_file_name: Option<String>,
+) -> Result<(), String> {
+    crate::core::plugin_isolation::guard("xml_cache", delete_xml_cache_artifacts_impl(xml_file_name, screenshot_file_name)).await
+}
+
+async fn delete_xml_cache_artifacts_impl(
+    xml_file_name: String,
+    screenshot_file_name: Option<String>,
 ) -> Result<(), String> {
     use std::fs;
 
@@ -556,6 +591,14 @@ async fn parse_cached_xml_to_elements(
     xml_content: Option<String>,
     file_path: Option<String>,
     enable_filtering: Option<bool>, // 新增参数：是否启用过滤
+) -> Result<serde_json::Value, String> {
+    crate::core::plugin_isolation::guard("xml_cache", parse_cached_xml_to_elements_impl(xml_content, file_path, enable_filtering)).await
+}
+
+async fn parse_cached_xml_to_elements_impl(
+    xml_content: Option<String>,
+    file_path: Option<String>,
+    enable_filtering: Option<bool>, // 新增参数：是否启用过滤
 ) -> Result<serde_json::Value, String> {
     use crate::services::universal_ui_page_analyzer::UniversalUIPageAnalyzer;
     use tracing::{info, error};
@@ -617,7 +660,7 @@ async fn parse_cached_xml_to_elements(
     }
 }
 
-fn get_debug_xml_dir() -> std::path::PathBuf {
+pub(crate) fn get_debug_xml_dir() -> std::path::PathBuf {
     // 🔧 修复：强制使用项目根目录的绝对路径，避免运行时路径混乱
     let absolute_project_root = std::path::PathBuf::from("D:\\rust\\active-projects\\小红书\\employeeGUI");
     let debug_xml_path = absolute_project_root.join("debug_xml");
@@ -634,6 +677,10 @@ fn get_debug_xml_dir() -> std::path::PathBuf {
 /// 🔧 调试命令：检查XML缓存路径问题
 #[tauri::command]
 async fn debug_xml_cache_paths() -> Result<serde_json::Value, String> {
+    crate::core::plugin_isolation::guard("xml_cache", debug_xml_cache_paths_impl()).await
+}
+
+async fn debug_xml_cache_paths_impl() -> Result<serde_json::Value, String> {
     use std::fs;
     use serde_json::json;
     
@@ -684,6 +731,15 @@ async fn link_step_snapshot(
     step_id: String,
     snapshot_id: SnapshotId,
     description: Option<String>
+) -> Result<usize, String> {
+    crate::core::plugin_isolation::guard("xml_cache", link_step_snapshot_impl(step_id, snapshot_id, description)).await
+}
+
+#[allow(unused_variables)]
+async fn link_step_snapshot_impl(
+    step_id: String,
+    snapshot_id: SnapshotId,
+    description: Option<String>
 ) -> Result<usize, String> {
     let _ = description;
     debug!(
@@ -720,6 +776,14 @@ async fn unlink_step_snapshot(
     step_id: String,
     snapshot_id: SnapshotId,
     force_remove: Option<bool>
+) -> Result<Option<usize>, String> {
+    crate::core::plugin_isolation::guard("xml_cache", unlink_step_snapshot_impl(step_id, snapshot_id, force_remove)).await
+}
+
+async fn unlink_step_snapshot_impl(
+    step_id: String,
+    snapshot_id: SnapshotId,
+    force_remove: Option<bool>
 ) -> Result<Option<usize>, String> {
     let force = force_remove.unwrap_or(false);
     
@@ -756,6 +820,10 @@ async fn unlink_step_snapshot(
 /// 获取指定快照的引用信息
 #[tauri::command]
 async fn get_snapshot_reference_info(snapshot_id: SnapshotId) -> Result<Option<SnapshotRefInfo>, String> {
+    crate::core::plugin_isolation::guard("xml_cache", get_snapshot_reference_info_impl(snapshot_id)).await
+}
+
+async fn get_snapshot_reference_info_impl(snapshot_id: SnapshotId) -> Result<Option<SnapshotRefInfo>, String> {
     debug!(snapshot_id = %snapshot_id, "Getting snapshot reference info");
     
     let info = get_snapshot_ref_info(&snapshot_id);
@@ -776,6 +844,10 @@ async fn get_snapshot_reference_info(snapshot_id: SnapshotId) -> Result<Option<S
 /// 获取所有快照的引用计数统计
 #[tauri::command]
 async fn get_all_snapshot_references() -> Result<HashMap<SnapshotId, usize>, String> {
+    crate::core::plugin_isolation::guard("xml_cache", get_all_snapshot_references_impl()).await
+}
+
+async fn get_all_snapshot_references_impl() -> Result<HashMap<SnapshotId, usize>, String> {
     debug!("Getting all snapshot references");
     
     let refs = get_all_snapshot_refs();
@@ -800,6 +872,10 @@ pub struct CacheSystemStatus {
 
 #[tauri::command]
 async fn get_cache_system_status() -> Result<CacheSystemStatus, String> {
+    crate::core::plugin_isolation::guard("xml_cache", get_cache_system_status_impl()).await
+}
+
+async fn get_cache_system_status_impl() -> Result<CacheSystemStatus, String> {
     debug!("Getting cache system status");
     
     let consistency_issues = validate_cache_consistency()
@@ -831,6 +907,10 @@ async fn get_cache_system_status() -> Result<CacheSystemStatus, String> {
 /// 验证缓存一致性
 #[tauri::command]
 async fn validate_cache_consistency_cmd() -> Result<Vec<String>, String> {
+    crate::core::plugin_isolation::guard("xml_cache", validate_cache_consistency_cmd_impl()).await
+}
+
+async fn validate_cache_consistency_cmd_impl() -> Result<Vec<String>, String> {
     debug!("Validating cache consistency");
     
     match validate_cache_consistency() {
@@ -855,6 +935,10 @@ async fn validate_cache_consistency_cmd() -> Result<Vec<String>, String> {
 /// 强制清理所有缓存（调试用）
 #[tauri::command]
 async fn force_clear_all_caches_cmd() -> Result<(), String> {
+    crate::core::plugin_isolation::guard("xml_cache", force_clear_all_caches_cmd_impl()).await
+}
+
+async fn force_clear_all_caches_cmd_impl() -> Result<(), String> {
     warn!("Force clearing all caches - this is a debug operation");
     
     match force_clear_all_caches() {
@@ -872,6 +956,10 @@ async fn force_clear_all_caches_cmd() -> Result<(), String> {
 /// 清理过期缓存
 #[tauri::command]
 async fn cleanup_cache_cmd(max_age_hours: u32) -> Result<usize, String> {
+    crate::core::plugin_isolation::guard("xml_cache", cleanup_cache_cmd_impl(max_age_hours)).await
+}
+
+async fn cleanup_cache_cmd_impl(max_age_hours: u32) -> Result<usize, String> {
     // TODO: 实现基于时间的缓存清理
     tracing::info!("缓存清理: 最大年龄{}小时", max_age_hours);
     Ok(0)
@@ -881,7 +969,11 @@ async fn cleanup_cache_cmd(max_age_hours: u32) -> Result<usize, String> {
 
 /// 注册XML快照，返回SnapshotId
 #[tauri::command]
-async fn register_snapshot_cmd(xml_content: String) -> String {
+async fn register_snapshot_cmd(xml_content: String) -> Result<String, String> {
+    crate::core::plugin_isolation::guard("xml_cache", async move { Ok(register_snapshot_cmd_impl(xml_content).await) }).await
+}
+
+async fn register_snapshot_cmd_impl(xml_content: String) -> String {
     let snapshot_id = register_snapshot(&xml_content);
     tracing::info!("前端注册XML快照: snapshot_id={}", snapshot_id);
     snapshot_id
@@ -892,6 +984,13 @@ async fn register_snapshot_cmd(xml_content: String) -> String {
 async fn get_subtree_metrics_cmd(
     snapshot_id: String,
     abs_xpath: String,
+) -> Result<SubtreeMetricsDto, String> {
+    crate::core::plugin_isolation::guard("xml_cache", get_subtree_metrics_cmd_impl(snapshot_id, abs_xpath)).await
+}
+
+async fn get_subtree_metrics_cmd_impl(
+    snapshot_id: String,
+    abs_xpath: String,
 ) -> Result<SubtreeMetricsDto, String> {
     match get_or_compute_subtree(&snapshot_id, &abs_xpath) {
         Ok(metrics) => {
@@ -911,8 +1010,8 @@ async fn get_subtree_metrics_cmd(
 async fn try_get_subtree_metrics_cmd(
     snapshot_id: String,
     abs_xpath: String,
-) -> Option<SubtreeMetricsDto> {
-    try_get_subtree(&snapshot_id, &abs_xpath).map(|m| m.into())
+) -> Result<Option<SubtreeMetricsDto>, String> {
+    crate::core::plugin_isolation::guard("xml_cache", async move { Ok(try_get_subtree(&snapshot_id, &abs_xpath).map(|m| m.into())) }).await
 }
 
 /// 批量获取多个元素的子树指标
@@ -920,6 +1019,13 @@ async fn try_get_subtree_metrics_cmd(
 async fn batch_get_subtree_metrics_cmd(
     snapshot_id: String,
     xpath_list: Vec<String>,
+) -> Result<Vec<SubtreeMetricsDto>, String> {
+    crate::core::plugin_isolation::guard("xml_cache", batch_get_subtree_metrics_cmd_impl(snapshot_id, xpath_list)).await
+}
+
+async fn batch_get_subtree_metrics_cmd_impl(
+    snapshot_id: String,
+    xpath_list: Vec<String>,
 ) -> Result<Vec<SubtreeMetricsDto>, String> {
     let mut results = Vec::new();
     
@@ -946,7 +1052,11 @@ pub struct CacheStats {
 
 /// 获取缓存统计信息
 #[tauri::command]
-async fn get_cache_stats_cmd() -> CacheStats {
+async fn get_cache_stats_cmd() -> Result<CacheStats, String> {
+    crate::core::plugin_isolation::guard("xml_cache", async move { Ok(get_cache_stats_cmd_impl().await) }).await
+}
+
+async fn get_cache_stats_cmd_impl() -> CacheStats {
     use crate::domain::analysis_cache::{DOM_CACHE, SUBTREE_CACHE};
     
     let stats = CacheStats {
@@ -961,11 +1071,84 @@ async fn get_cache_stats_cmd() -> CacheStats {
     stats
 }
 
+/// XML 索引缓存（内存 + 持久化）命中统计
+#[tauri::command]
+async fn get_xml_index_cache_stats() -> Result<crate::engine::xml_index_store::XmlIndexCacheStats, String> {
+    crate::core::plugin_isolation::guard("xml_cache", async move { Ok(crate::engine::xml_index_store::stats()) }).await
+}
+
+/// 清空 XML 索引缓存（内存与持久化），返回删除的持久化条目数
+#[tauri::command]
+async fn clear_xml_index_cache() -> Result<usize, String> {
+    crate::core::plugin_isolation::guard("xml_cache", clear_xml_index_cache_impl()).await
+}
+
+async fn clear_xml_index_cache_impl() -> Result<usize, String> {
+    crate::engine::XmlIndexer::clear_cache();
+    tokio::task::spawn_blocking(crate::engine::xml_index_store::clear).await.map_err(|e| e.to_string())?
+}
+
+// ==================== 🧬 App Profiles ====================
+
+/// 参与画像推断的最近 dump 数
+const PROFILE_INFERENCE_MAX_DUMPS: usize = 500;
+
+/// 扫描缓存的 dump，按应用推断元素结构画像并保存
+#[tauri::command]
+async fn infer_app_profiles() -> Result<crate::services::app_profiles::InferenceSummary, String> {
+    crate::core::plugin_isolation::guard("xml_cache", infer_app_profiles_impl()).await
+}
+
+async fn infer_app_profiles_impl() -> Result<crate::services::app_profiles::InferenceSummary, String> {
+    tokio::task::spawn_blocking(|| {
+        let debug_dir = get_debug_xml_dir();
+        let mut files: Vec<std::path::PathBuf> = std::fs::read_dir(&debug_dir)
+            .map(|entries| entries.flatten().map(|e| e.path()).collect())
+            .unwrap_or_default();
+        files.retain(|p| p.file_name().and_then(|f| f.to_str()).is_some_and(|n| n.starts_with("ui_dump_") && n.ends_with(".xml")));
+        // 文件名带时间戳，倒序即最新在前
+        files.sort();
+        files.reverse();
+        files.truncate(PROFILE_INFERENCE_MAX_DUMPS);
+
+        let contents: Vec<String> = files.iter().filter_map(|p| std::fs::read_to_string(p).ok()).collect();
+        let summary = crate::services::app_profiles::infer_and_store(contents.iter().map(String::as_str))?;
+        info!("🧬 应用画像推断完成: {} 个 dump, {} 个应用", summary.dumps_scanned, summary.packages.len());
+        Ok(summary)
+    })
+    .await
+    .map_err(|e| format!("应用画像推断任务失败: {}", e))?
+}
+
+/// 已保存的应用画像
+#[tauri::command]
+async fn list_app_profiles() -> Result<Vec<crate::services::app_profiles::AppElementProfile>, String> {
+    crate::core::plugin_isolation::guard("xml_cache", async move { Ok(crate::services::app_profiles::AppProfileStore::global().lock().map_err(|e| e.to_string())?.list()) }).await
+}
+
+/// 按应用画像给元素生成选择器建议（没有画像时使用通用评分）
+#[tauri::command]
+async fn suggest_app_selectors(
+    package: String,
+    element: crate::services::app_profiles::ElementQuery,
+) -> Result<Vec<crate::services::app_profiles::SelectorSuggestion>, String> {
+    crate::core::plugin_isolation::guard("xml_cache", suggest_app_selectors_impl(package, element)).await
+}
+
+async fn suggest_app_selectors_impl(
+    package: String,
+    element: crate::services::app_profiles::ElementQuery,
+) -> Result<Vec<crate::services::app_profiles::SelectorSuggestion>, String> {
+    use crate::services::app_profiles::{suggest_selectors, AppProfileStore};
+    let store = AppProfileStore::global().lock().map_err(|e| e.to_string())?;
+    Ok(suggest_selectors(store.get(&package), &element))
+}
+
 // ==================== 🔌 Plugin Initialization ====================
 
 pub fn init<R: Runtime>() -> TauriPlugin<R> {
     Builder::new("xml_cache")
-        .invoke_handler(tauri::generate_handler![
+        .invoke_handler(crate::core::plugin_isolation::isolate("xml_cache", tauri::generate_handler![
             // XML Cache
             list_xml_cache_files,
             list_xml_cache_files_quick, // 🚀 快速元数据（仅文件系统信息，<50ms）
@@ -1002,7 +1185,14 @@ pub fn init<R: Runtime>() -> TauriPlugin<R> {
             try_get_subtree_metrics_cmd,
             batch_get_subtree_metrics_cmd,
             cleanup_cache_cmd,
-            get_cache_stats_cmd
-        ])
+            get_cache_stats_cmd,
+            get_xml_index_cache_stats,
+            clear_xml_index_cache,
+
+            // App Profiles
+            infer_app_profiles,
+            list_app_profiles,
+            suggest_app_selectors
+        ]))
         .build()
 }
@@ -17,7 +17,7 @@ pub struct ScreenshotService;
 
 impl ScreenshotService {
     /// 直接通过 `adb exec-out screencap -p` 获取PNG二进制
-    fn capture_png_bytes(device_id: &str) -> Result<Vec<u8>, String> {
+    pub fn capture_png_bytes(device_id: &str) -> Result<Vec<u8>, String> {
         let output = execute_adb_command(&["-s", device_id, "exec-out", "screencap", "-p"]) 
             .map_err(|e| format!("执行截图命令失败: {e}"))?;
 
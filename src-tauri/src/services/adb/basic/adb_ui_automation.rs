@@ -10,6 +10,7 @@ impl AdbService {
     /// 优先使用 ExecOut 快速模式（跳过文件 I/O），失败后回退到传统 DumpPull 方式。
     /// 用于智能元素查找、UI分析等自动化操作
     pub async fn dump_ui_hierarchy(&self, device_id: &str) -> Result<String, Box<dyn std::error::Error>> {
+        let _timeline = crate::services::execution::timeline::phase(crate::services::execution::timeline::Phase::Dump);
         // ========== 1. 优先尝试 ExecOut 快速模式 ==========
         debug!("🚀 尝试 ExecOut 快速模式...");
         let exec_out = ExecOutExecutor::new(3000); // 3秒超时
@@ -1,52 +1,51 @@
 // src/services/adb/commands/adb_file.rs
 // module: adb | layer: commands | role: 文件操作命令
-// summary: ADB文件传输命令（push/pull），提供设备验证
+// summary: ADB文件推送命令，提供设备验证、哈希校验、失败重试与断点续传
 
+use std::path::Path;
 use tracing::{info, error};
 use crate::services::adb::get_device_session;
-use tokio::process::Command;
-#[cfg(windows)]
-// use std::os::windows::process::CommandExt;
+use crate::services::device_files::{self, PushOptions, PushProgress};
 
 /// 安全的ADB Push命令
-/// 
-/// 将本地文件推送到设备
-#[tauri::command]
-#[allow(non_snake_case)]
+///
+/// 将本地文件推送到设备，推送后比对设备端 sha256sum / md5sum，
+/// 不一致时按 `options` 退避重试；`on_progress` 回调已发送 / 已校验字节数
 pub async fn safe_adb_push(
-    deviceId: String,
-    localPath: String,
-    remotePath: String,
+    device_id: String,
+    local_path: String,
+    remote_path: String,
+    options: Option<PushOptions>,
+    on_progress: impl FnMut(&PushProgress) + Send + 'static,
 ) -> Result<String, String> {
-    info!("📂 开始推送文件: {} -> {} (设备: {})", localPath, remotePath, deviceId);
+    info!("📂 开始推送文件: {} -> {} (设备: {})", local_path, remote_path, device_id);
 
     // 获取设备会话（自动验证设备在线）
-    let session = get_device_session(&deviceId).await
+    get_device_session(&device_id).await
         .map_err(|e| format!("无法获取设备会话: {}", e))?;
-    
-    let adb_path = session.get_adb_path().await;
 
-    let mut cmd = Command::new(adb_path);
-    cmd.args(&["-s", &deviceId, "push", &localPath, &remotePath]);
-    
-    #[cfg(windows)]
-    cmd.creation_flags(0x08000000); // CREATE_NO_WINDOW
+    let options = options.unwrap_or_default();
+    let result = tokio::task::spawn_blocking(move || {
+        device_files::push_file_verified(&device_id, Path::new(&local_path), &remote_path, &options, on_progress)
+    })
+    .await
+    .map_err(|e| format!("执行ADB push任务失败: {}", e))?;
 
-    match cmd.output().await {
-        Ok(output) => {
-            if output.status.success() {
-                let stdout = String::from_utf8_lossy(&output.stdout).to_string();
-                info!("✅ 文件推送成功: {}", stdout);
-                Ok(stdout)
-            } else {
-                let stderr = String::from_utf8_lossy(&output.stderr).to_string();
-                error!("❌ 文件推送失败: {}", stderr);
-                Err(format!("文件推送失败: {}", stderr))
-            }
+    match result {
+        Ok(pushed) => {
+            let method = pushed
+                .hash_tool
+                .map_or_else(|| "size".to_string(), |tool| format!("{:?}", tool).to_lowercase());
+            let message = format!(
+                "{}: {} bytes pushed, verified by {} (attempts: {}, resumed: {} bytes)",
+                pushed.remote_path, pushed.bytes, method, pushed.attempts, pushed.resumed_bytes
+            );
+            info!("✅ 文件推送成功: {}", message);
+            Ok(message)
         }
         Err(e) => {
-            error!("💥 执行ADB push命令失败: {}", e);
-            Err(format!("执行ADB push命令失败: {}", e))
+            error!("❌ 文件推送失败: {}", e);
+            Err(format!("文件推送失败: {}", e))
         }
     }
 }
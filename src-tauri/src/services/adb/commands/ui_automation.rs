@@ -22,6 +22,7 @@ pub struct QuickUiResult {
 #[command]
 pub async fn adb_dump_ui_xml(device_id: String) -> Result<String, String> {
     let start_time = std::time::Instant::now();
+    let _timeline = crate::services::execution::timeline::phase(crate::services::execution::timeline::Phase::Dump);
     info!("🔍 快速抓取UI XML: device={}", device_id);
 
     // 获取设备会话
@@ -5,15 +5,18 @@
 // - session/    会话层（长连接复用、性能优化）
 // - tracking/   追踪层（实时设备监控）
 // - commands/   命令层（Tauri 命令封装）
+// - transport   调用通道抽象（真实 adb / 测试假设备）
 
 pub mod basic;
 pub mod session;
 pub mod tracking;
 pub mod commands;
+pub mod transport;
 
 // 重新导出常用接口，保持向后兼容
 pub use basic::AdbService;
 pub use basic::initialize_adb_system;
+pub use transport::{AdbTransport, CommandAdbTransport};
 pub use session::{AdbShellSession, get_device_session};
 pub use tracking::{
     start_device_tracking, stop_device_tracking, get_tracked_devices
@@ -255,6 +255,11 @@ impl AdbDeviceTracker {
                         *last = devices.clone();
                         drop(last);
 
+                        // 在线设备全集发布到业务事件总线（在线时长统计等）
+                        crate::core::shared::event_bus::publish(crate::core::shared::event_bus::AppEvent::DevicesOnline {
+                            device_ids: devices.iter().filter(|d| d.status == "device").map(|d| d.id.clone()).collect(),
+                        });
+
                         // 发送事件
                         let event = DeviceChangeEvent {
                             event_type,
@@ -0,0 +1,41 @@
+// src-tauri/src/services/adb/transport.rs
+// module: adb | layer: infrastructure | role: adb-transport
+// summary: ADB 调用通道抽象 - 真实实现执行 adb 可执行文件，回归场景可替换为内存假设备
+
+use std::process::Output;
+
+use anyhow::Result;
+
+use crate::utils::adb_utils::{execute_command_hidden, get_adb_path};
+
+/// ADB 调用通道：参数与 adb 命令行一致（如 `["-s", serial, "shell", ...]`）
+pub trait AdbTransport: Send + Sync {
+    fn execute(&self, args: &[&str]) -> Result<Output>;
+}
+
+/// 通过 adb 可执行文件执行（Windows 下隐藏窗口）
+#[derive(Debug, Clone)]
+pub struct CommandAdbTransport {
+    adb_path: String,
+}
+
+impl CommandAdbTransport {
+    pub fn new(adb_path: impl Into<String>) -> Self {
+        Self { adb_path: adb_path.into() }
+    }
+
+    /// 使用全局检测到的 adb 路径
+    pub fn system() -> Self {
+        Self::new(get_adb_path())
+    }
+
+    pub fn adb_path(&self) -> &str {
+        &self.adb_path
+    }
+}
+
+impl AdbTransport for CommandAdbTransport {
+    fn execute(&self, args: &[&str]) -> Result<Output> {
+        execute_command_hidden(&self.adb_path, args)
+    }
+}
@@ -0,0 +1,226 @@
+// src-tauri/src/services/app_profiles/inference.rs
+// module: app_profiles | layer: services | role: dump 结构推断
+// summary: 扫描缓存的 UI dump，按包名统计 resource-id 前缀、混淆 id、文本/描述覆盖率、自定义属性、重复列表容器与稳定元素类型，生成应用画像
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use chrono::Utc;
+
+use super::{AppElementProfile, ContainerPattern, CountShare, ElementType, PROFILE_VERSION};
+
+/// uiautomator 标准属性，其余视为应用 / 采集端附加的自定义属性
+const STANDARD_ATTRIBUTES: &[&str] = &[
+    "index", "text", "resource-id", "class", "package", "content-desc", "checkable", "checked", "clickable",
+    "enabled", "focusable", "focused", "scrollable", "long-clickable", "password", "selected", "bounds",
+    "rotation", "visible-to-user", "drawing-order", "hint", "display-id",
+];
+/// 同一父节点下结构相同的子节点达到该数量才算列表容器
+const MIN_REPEATED_ITEMS: usize = 3;
+const MAX_PREFIXES: usize = 10;
+const MAX_CONTAINERS: usize = 10;
+const MAX_ELEMENT_TYPES: usize = 50;
+
+/// 单个包的累计统计
+#[derive(Default)]
+struct Accumulator {
+    dumps: usize,
+    nodes: usize,
+    clickable: usize,
+    clickable_with_id: usize,
+    clickable_with_obfuscated_id: usize,
+    clickable_with_text: usize,
+    clickable_with_desc: usize,
+    clickable_with_child_text: usize,
+    ids: usize,
+    id_prefixes: HashMap<String, usize>,
+    custom_attributes: HashMap<String, HashSet<usize>>,
+    containers: HashMap<(String, String), (usize, HashSet<usize>)>,
+    elements: HashMap<(String, String), (usize, usize, HashSet<usize>)>,
+}
+
+pub(crate) fn short_class(class: &str) -> &str {
+    class.rsplit('.').next().unwrap_or(class)
+}
+
+/// `com.xingin.xhs:id/note_title` → `com.xingin.xhs:id/note_`；没有分段的名字只保留命名空间
+pub fn id_prefix(resource_id: &str) -> String {
+    match resource_id.split_once(":id/") {
+        Some((namespace, name)) => match name.split_once('_') {
+            Some((head, _)) if !head.is_empty() => format!("{}:id/{}_", namespace, head),
+            _ => format!("{}:id/", namespace),
+        },
+        None => resource_id.to_string(),
+    }
+}
+
+/// 混淆过的 id 名（如 `a1`、`bq`、`x3f`）：很短且没有下划线分段，跨版本不稳定
+pub fn is_obfuscated_id(resource_id: &str) -> bool {
+    let name = resource_id.rsplit('/').next().unwrap_or(resource_id);
+    name.len() <= 3 && !name.contains('_') && name.chars().all(|c| c.is_ascii_alphanumeric())
+}
+
+fn non_empty<'a>(node: &roxmltree::Node<'a, 'a>, attr: &str) -> Option<&'a str> {
+    node.attribute(attr).map(str::trim).filter(|v| !v.is_empty())
+}
+
+/// 节点的浅层结构签名：类名 + 直接子节点类名序列
+fn shallow_signature(node: &roxmltree::Node) -> String {
+    let children: Vec<&str> = node
+        .children()
+        .filter(|c| c.has_tag_name("node"))
+        .map(|c| short_class(c.attribute("class").unwrap_or("")))
+        .collect();
+    format!("{}[{}]", short_class(node.attribute("class").unwrap_or("")), children.join(","))
+}
+
+impl Accumulator {
+    fn add_dump(&mut self, dump_index: usize, doc: &roxmltree::Document) {
+        self.dumps += 1;
+        for node in doc.descendants().filter(|n| n.has_tag_name("node")) {
+            self.nodes += 1;
+            let resource_id = non_empty(&node, "resource-id");
+            let text = non_empty(&node, "text");
+            let desc = non_empty(&node, "content-desc");
+            let class = node.attribute("class").unwrap_or("").to_string();
+
+            for attr in node.attributes() {
+                if !STANDARD_ATTRIBUTES.contains(&attr.name()) {
+                    self.custom_attributes.entry(attr.name().to_string()).or_default().insert(dump_index);
+                }
+            }
+
+            if let Some(id) = resource_id {
+                self.ids += 1;
+                *self.id_prefixes.entry(id_prefix(id)).or_default() += 1;
+                let entry = self.elements.entry((id.to_string(), class.clone())).or_default();
+                entry.0 += 1;
+                if text.is_some() {
+                    entry.1 += 1;
+                }
+                entry.2.insert(dump_index);
+            }
+
+            if node.attribute("clickable") == Some("true") {
+                self.clickable += 1;
+                if let Some(id) = resource_id {
+                    self.clickable_with_id += 1;
+                    if is_obfuscated_id(id) {
+                        self.clickable_with_obfuscated_id += 1;
+                    }
+                }
+                if text.is_some() {
+                    self.clickable_with_text += 1;
+                } else if node.descendants().skip(1).any(|d| non_empty(&d, "text").is_some()) {
+                    self.clickable_with_child_text += 1;
+                }
+                if desc.is_some() {
+                    self.clickable_with_desc += 1;
+                }
+            }
+
+            let mut item_signatures: HashMap<String, usize> = HashMap::new();
+            for child in node.children().filter(|c| c.has_tag_name("node")) {
+                *item_signatures.entry(shallow_signature(&child)).or_default() += 1;
+            }
+            if let Some((item, _)) = item_signatures.into_iter().filter(|(_, n)| *n >= MIN_REPEATED_ITEMS).max_by_key(|(_, n)| *n) {
+                let container = resource_id.map(str::to_string).unwrap_or_else(|| short_class(&class).to_string());
+                let entry = self.containers.entry((container, item)).or_default();
+                entry.0 += 1;
+                entry.1.insert(dump_index);
+            }
+        }
+    }
+
+    fn into_profile(self, package: String) -> AppElementProfile {
+        let ratio = |n: usize, d: usize| if d == 0 { 0.0 } else { ((n as f64 / d as f64) * 1000.0).round() / 1000.0 };
+
+        let mut id_prefixes: Vec<CountShare> = self
+            .id_prefixes
+            .into_iter()
+            .map(|(value, count)| CountShare { value, count, share: ratio(count, self.ids) })
+            .collect();
+        id_prefixes.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.value.cmp(&b.value)));
+        id_prefixes.truncate(MAX_PREFIXES);
+
+        let mut custom_attributes: Vec<CountShare> = self
+            .custom_attributes
+            .into_iter()
+            .map(|(value, dumps)| CountShare { value, count: dumps.len(), share: ratio(dumps.len(), self.dumps) })
+            .collect();
+        custom_attributes.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.value.cmp(&b.value)));
+
+        let mut containers: Vec<ContainerPattern> = self
+            .containers
+            .into_iter()
+            .map(|((container, item_signature), (occurrences, dumps))| ContainerPattern {
+                container,
+                item_signature,
+                occurrences,
+                dumps_seen: dumps.len(),
+            })
+            .collect();
+        containers.sort_by(|a, b| b.dumps_seen.cmp(&a.dumps_seen).then_with(|| b.occurrences.cmp(&a.occurrences)));
+        containers.truncate(MAX_CONTAINERS);
+
+        // 只在一次 dump 中出现的 id 多半是一次性内容，不算稳定元素类型
+        let min_dumps = if self.dumps > 1 { 2 } else { 1 };
+        let mut element_types: Vec<ElementType> = self
+            .elements
+            .into_iter()
+            .filter(|(_, (_, _, dumps))| dumps.len() >= min_dumps)
+            .map(|((resource_id, class), (occurrences, with_text, dumps))| ElementType {
+                obfuscated: is_obfuscated_id(&resource_id),
+                resource_id,
+                class,
+                occurrences,
+                dumps_seen: dumps.len(),
+                text_share: ratio(with_text, occurrences),
+            })
+            .collect();
+        element_types.sort_by(|a, b| {
+            b.dumps_seen.cmp(&a.dumps_seen).then_with(|| b.occurrences.cmp(&a.occurrences)).then_with(|| a.resource_id.cmp(&b.resource_id))
+        });
+        element_types.truncate(MAX_ELEMENT_TYPES);
+
+        let mut profile = AppElementProfile {
+            version: PROFILE_VERSION,
+            package,
+            dumps_analyzed: self.dumps,
+            node_count: self.nodes,
+            clickable_count: self.clickable,
+            id_coverage: ratio(self.clickable_with_id, self.clickable),
+            obfuscated_id_share: ratio(self.clickable_with_obfuscated_id, self.clickable_with_id),
+            text_coverage: ratio(self.clickable_with_text, self.clickable),
+            desc_coverage: ratio(self.clickable_with_desc, self.clickable),
+            child_text_share: ratio(self.clickable_with_child_text, self.clickable),
+            id_prefixes,
+            custom_attributes,
+            containers,
+            element_types,
+            strategy_scores: BTreeMap::new(),
+            inferred_at: Utc::now(),
+        };
+        profile.strategy_scores = profile.derive_strategy_scores();
+        profile
+    }
+}
+
+/// 按包名推断画像；解析失败或没有包名的 dump 计入 `skipped`
+pub fn infer_profiles<'a>(dumps: impl IntoIterator<Item = &'a str>) -> (Vec<AppElementProfile>, usize) {
+    let mut accumulators: BTreeMap<String, Accumulator> = BTreeMap::new();
+    let mut skipped = 0;
+    for (index, xml) in dumps.into_iter().enumerate() {
+        let Ok(doc) = roxmltree::Document::parse(xml) else {
+            skipped += 1;
+            continue;
+        };
+        let package = doc.descendants().filter(|n| n.has_tag_name("node")).find_map(|n| non_empty(&n, "package"));
+        let Some(package) = package else {
+            skipped += 1;
+            continue;
+        };
+        accumulators.entry(package.to_string()).or_default().add_dump(index, &doc);
+    }
+    let profiles = accumulators.into_iter().map(|(package, acc)| acc.into_profile(package)).collect();
+    (profiles, skipped)
+}
@@ -0,0 +1,375 @@
+// src-tauri/src/services/app_profiles/mod.rs
+// module: app_profiles | layer: services | role: 应用元素画像
+// summary: 从缓存 dump 推断的按应用元素结构画像（id 前缀、列表容器、稳定元素类型）- 持久化、策略插件预排序与选择器建议
+
+mod inference;
+
+pub use inference::{id_prefix, infer_profiles, is_obfuscated_id};
+use inference::short_class;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::{Mutex, OnceLock};
+
+const PROFILE_FILE_NAME: &str = "app_profiles.json";
+pub(crate) const PROFILE_VERSION: u32 = 1;
+/// 画像至少基于这么多次 dump 才参与策略排序
+const MIN_DUMPS_FOR_RANKING: usize = 3;
+
+static STORE: OnceLock<Mutex<AppProfileStore>> = OnceLock::new();
+
+/// 取值及其计数 / 占比
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CountShare {
+    pub value: String,
+    pub count: usize,
+    pub share: f64,
+}
+
+/// 重复出现的列表容器：容器（resource-id 或短类名）+ 列表项的浅层结构签名
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContainerPattern {
+    pub container: String,
+    pub item_signature: String,
+    pub occurrences: usize,
+    pub dumps_seen: usize,
+}
+
+/// 跨 dump 稳定出现的元素类型（resource-id + 类名）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ElementType {
+    pub resource_id: String,
+    pub class: String,
+    pub occurrences: usize,
+    pub dumps_seen: usize,
+    /// 带文本的比例（列表项标题等动态文本接近 1）
+    pub text_share: f64,
+    pub obfuscated: bool,
+}
+
+/// 单个应用的元素结构画像
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppElementProfile {
+    pub version: u32,
+    pub package: String,
+    pub dumps_analyzed: usize,
+    pub node_count: usize,
+    pub clickable_count: usize,
+    /// 可点击节点中带 resource-id 的比例
+    pub id_coverage: f64,
+    /// 可点击节点的 resource-id 中混淆 id 的比例
+    pub obfuscated_id_share: f64,
+    pub text_coverage: f64,
+    pub desc_coverage: f64,
+    /// 可点击节点自身无文本、文本在子节点上的比例
+    pub child_text_share: f64,
+    pub id_prefixes: Vec<CountShare>,
+    /// 非 uiautomator 标准属性（count 为出现过的 dump 数）
+    pub custom_attributes: Vec<CountShare>,
+    pub containers: Vec<ContainerPattern>,
+    pub element_types: Vec<ElementType>,
+    /// 策略执行器名（与 StrategyRegistry 一致）→ 0..1 适配度
+    pub strategy_scores: BTreeMap<String, f64>,
+    pub inferred_at: DateTime<Utc>,
+}
+
+impl AppElementProfile {
+    /// 按统计特征估算各策略在该应用上的适配度
+    pub(crate) fn derive_strategy_scores(&self) -> BTreeMap<String, f64> {
+        let stable_id = self.id_coverage * (1.0 - self.obfuscated_id_share);
+        let container_strength = (self.containers.len() as f64 / 3.0).min(1.0);
+        let round = |v: f64| (v.clamp(0.0, 1.0) * 1000.0).round() / 1000.0;
+        [
+            ("self_id", stable_id),
+            ("self_desc", self.desc_coverage),
+            ("child_to_parent", self.child_text_share),
+            ("region_text_to_parent", self.child_text_share * (0.6 + 0.4 * container_strength)),
+            ("region_local_index_with_check", 0.8 * container_strength),
+            ("neighbor_relative", 0.5 * self.text_coverage),
+            ("global_index_with_strong_checks", 0.2),
+            ("bounds_tap", 0.05),
+        ]
+        .into_iter()
+        .map(|(name, score)| (name.to_string(), round(score)))
+        .collect()
+    }
+
+    /// 策略适配度；样本不足或未知策略返回 None
+    pub fn strategy_score(&self, strategy: &str) -> Option<f64> {
+        if self.dumps_analyzed < MIN_DUMPS_FOR_RANKING {
+            return None;
+        }
+        self.strategy_scores.get(&strategy.replace('-', "_")).copied()
+    }
+
+    pub fn element_type(&self, resource_id: &str) -> Option<&ElementType> {
+        self.element_types.iter().find(|t| t.resource_id == resource_id)
+    }
+}
+
+/// 推断结果摘要
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InferenceSummary {
+    pub dumps_scanned: usize,
+    pub dumps_skipped: usize,
+    pub packages: Vec<String>,
+}
+
+/// 画像文件（包名 → 画像）
+pub struct AppProfileStore {
+    path: PathBuf,
+    profiles: BTreeMap<String, AppElementProfile>,
+}
+
+impl AppProfileStore {
+    pub fn global() -> &'static Mutex<AppProfileStore> {
+        STORE.get_or_init(|| {
+            let path = dirs::data_dir().unwrap_or_else(|| PathBuf::from(".")).join("employee-gui").join(PROFILE_FILE_NAME);
+            Mutex::new(Self::open(path))
+        })
+    }
+
+    pub fn open(path: PathBuf) -> Self {
+        let profiles = std::fs::read_to_string(&path)
+            .ok()
+            .and_then(|content| serde_json::from_str::<BTreeMap<String, AppElementProfile>>(&content).ok())
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|(_, p)| p.version == PROFILE_VERSION)
+            .collect();
+        Self { path, profiles }
+    }
+
+    pub fn get(&self, package: &str) -> Option<&AppElementProfile> {
+        self.profiles.get(package)
+    }
+
+    pub fn list(&self) -> Vec<AppElementProfile> {
+        self.profiles.values().cloned().collect()
+    }
+
+    /// 用新推断的画像替换同包名的旧画像（未出现在本次 dump 中的应用保留）
+    pub fn upsert_all(&mut self, profiles: Vec<AppElementProfile>) -> Result<(), String> {
+        for profile in profiles {
+            self.profiles.insert(profile.package.clone(), profile);
+        }
+        if let Some(dir) = self.path.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+        }
+        let content = serde_json::to_string_pretty(&self.profiles).map_err(|e| e.to_string())?;
+        std::fs::write(&self.path, content).map_err(|e| format!("保存应用画像失败: {}", e))
+    }
+}
+
+/// 推断并保存画像
+pub fn infer_and_store<'a>(dumps: impl IntoIterator<Item = &'a str>) -> Result<InferenceSummary, String> {
+    let mut dumps_scanned = 0;
+    let (profiles, dumps_skipped) = infer_profiles(dumps.into_iter().inspect(|_| dumps_scanned += 1));
+    let packages = profiles.iter().map(|p| p.package.clone()).collect();
+    AppProfileStore::global().lock().map_err(|e| e.to_string())?.upsert_all(profiles)?;
+    Ok(InferenceSummary { dumps_scanned, dumps_skipped, packages })
+}
+
+/// 策略插件预排序：保持 `items` 原有的强弱顺序为主，画像适配度作为加权；没有画像时原样返回
+pub fn rank_by_profile<T>(package: Option<&str>, items: Vec<T>, strategy_of: impl Fn(&T) -> &str) -> Vec<T> {
+    let Some(profile) = package
+        .filter(|p| !p.is_empty())
+        .and_then(|p| AppProfileStore::global().lock().ok().and_then(|store| store.get(p).cloned()))
+    else {
+        return items;
+    };
+    rank_with(&profile, items, strategy_of)
+}
+
+fn rank_with<T>(profile: &AppElementProfile, items: Vec<T>, strategy_of: impl Fn(&T) -> &str) -> Vec<T> {
+    let total = items.len().max(1) as f64;
+    let mut scored: Vec<(f64, T)> = items
+        .into_iter()
+        .enumerate()
+        .map(|(i, item)| {
+            let order_score = 1.0 - i as f64 / total;
+            let fit = profile.strategy_score(strategy_of(&item)).unwrap_or(0.5);
+            (0.5 * order_score + 0.5 * fit, item)
+        })
+        .collect();
+    scored.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap_or(std::cmp::Ordering::Equal));
+    scored.into_iter().map(|(_, item)| item).collect()
+}
+
+/// 需要建议选择器的元素
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ElementQuery {
+    pub resource_id: Option<String>,
+    pub text: Option<String>,
+    pub content_desc: Option<String>,
+    pub class: Option<String>,
+}
+
+/// 一条选择器建议
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SelectorSuggestion {
+    /// 对应的策略执行器名
+    pub strategy: String,
+    pub xpath: String,
+    pub score: f64,
+    pub reason: String,
+}
+
+fn xpath_literal(value: &str) -> String {
+    if !value.contains('\'') {
+        format!("'{}'", value)
+    } else if !value.contains('"') {
+        format!("\"{}\"", value)
+    } else {
+        let parts: Vec<String> = value.split('\'').map(|p| format!("'{}'", p)).collect();
+        format!("concat({})", parts.join(", \"'\", "))
+    }
+}
+
+/// 按应用画像给元素的候选选择器打分（无画像时使用通用经验值），分数从高到低
+pub fn suggest_selectors(profile: Option<&AppElementProfile>, element: &ElementQuery) -> Vec<SelectorSuggestion> {
+    let mut suggestions = Vec::new();
+    let non_empty = |v: &Option<String>| v.as_deref().map(str::trim).filter(|v| !v.is_empty()).map(str::to_string);
+
+    if let Some(id) = non_empty(&element.resource_id) {
+        let (score, reason) = match profile.and_then(|p| p.element_type(&id).map(|t| (p, t))) {
+            Some((_, t)) if t.obfuscated => (0.35, "该应用的这个 id 是混淆名，版本更新后容易变化".to_string()),
+            Some((p, t)) if t.text_share >= 0.9 && p.containers.iter().any(|c| c.item_signature.contains(short_class(&t.class))) => {
+                (0.55, format!("列表项内重复出现的 id（{} 次 dump），需配合文本或区域限定", t.dumps_seen))
+            }
+            Some((_, t)) => (0.9, format!("稳定元素类型，在 {} 次 dump 中出现", t.dumps_seen)),
+            None if is_obfuscated_id(&id) => (0.35, "疑似混淆 id".to_string()),
+            None => match profile {
+                Some(p) if p.id_prefixes.iter().any(|prefix| id.starts_with(&prefix.value)) => {
+                    (0.75, "符合该应用常见的 id 前缀".to_string())
+                }
+                Some(_) => (0.6, "该应用画像中未见过这个 id".to_string()),
+                None => (0.7, "resource-id 通常最稳定".to_string()),
+            },
+        };
+        suggestions.push(SelectorSuggestion {
+            strategy: "self_id".to_string(),
+            xpath: format!("//*[@resource-id={}]", xpath_literal(&id)),
+            score,
+            reason,
+        });
+    }
+
+    if let Some(desc) = non_empty(&element.content_desc) {
+        let fit = profile.and_then(|p| p.strategy_score("self_desc")).unwrap_or(0.5);
+        suggestions.push(SelectorSuggestion {
+            strategy: "self_desc".to_string(),
+            xpath: format!("//*[@content-desc={}]", xpath_literal(&desc)),
+            score: 0.4 + 0.4 * fit,
+            reason: format!("该应用可点击元素的描述覆盖率 {:.0}%", fit * 100.0),
+        });
+    }
+
+    if let Some(text) = non_empty(&element.text) {
+        let fit = profile.and_then(|p| p.strategy_score("child_to_parent")).unwrap_or(0.5);
+        suggestions.push(SelectorSuggestion {
+            strategy: "child_to_parent".to_string(),
+            xpath: format!("//*[@text={}]/ancestor-or-self::*[@clickable='true'][1]", xpath_literal(&text)),
+            score: 0.35 + 0.4 * fit,
+            reason: format!("该应用 {:.0}% 的可点击元素文本在子节点上", fit * 100.0),
+        });
+    }
+
+    if let (Some(class), Some(p)) = (non_empty(&element.class), profile) {
+        if let Some(container) = p.containers.iter().find(|c| c.item_signature.contains(short_class(&class))) {
+            suggestions.push(SelectorSuggestion {
+                strategy: "region_local_index_with_check".to_string(),
+                xpath: if container.container.contains(":id/") {
+                    format!("//*[@resource-id={}]/*", xpath_literal(&container.container))
+                } else {
+                    format!("//*[contains(@class, {})]/*", xpath_literal(&container.container))
+                },
+                score: p.strategy_score("region_local_index_with_check").unwrap_or(0.4),
+                reason: format!("元素位于重复列表容器 {}（{} 次 dump）", container.container, container.dumps_seen),
+            });
+        }
+    }
+
+    suggestions.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
+    suggestions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dump(title: &str) -> String {
+        format!(
+            r#"<hierarchy rotation="0">
+  <node class="android.widget.FrameLayout" package="com.example.feed" clickable="false" bounds="[0,0][1080,2400]" app-layer="feed">
+    <node resource-id="com.example.feed:id/feed_list" class="androidx.recyclerview.widget.RecyclerView" package="com.example.feed" clickable="false">
+      <node class="android.widget.LinearLayout" package="com.example.feed" clickable="true"><node resource-id="com.example.feed:id/feed_title" class="android.widget.TextView" text="{t}1" clickable="false"/></node>
+      <node class="android.widget.LinearLayout" package="com.example.feed" clickable="true"><node resource-id="com.example.feed:id/feed_title" class="android.widget.TextView" text="{t}2" clickable="false"/></node>
+      <node class="android.widget.LinearLayout" package="com.example.feed" clickable="true"><node resource-id="com.example.feed:id/feed_title" class="android.widget.TextView" text="{t}3" clickable="false"/></node>
+    </node>
+    <node resource-id="com.example.feed:id/tab_home" class="android.widget.Button" package="com.example.feed" clickable="true" content-desc="首页"/>
+    <node resource-id="com.example.feed:id/a1" class="android.widget.ImageView" package="com.example.feed" clickable="true"/>
+  </node>
+</hierarchy>"#,
+            t = title
+        )
+    }
+
+    #[test]
+    fn infers_profile_ranks_strategies_and_suggests_selectors() {
+        assert_eq!(id_prefix("com.example.feed:id/feed_title"), "com.example.feed:id/feed_");
+        assert_eq!(id_prefix("android:id/content"), "android:id/");
+        assert!(is_obfuscated_id("com.example.feed:id/a1") && !is_obfuscated_id("com.example.feed:id/tab_home"));
+
+        let dumps: Vec<String> = ["早安", "午后", "晚间"].iter().map(|t| dump(t)).collect();
+        let (profiles, skipped) = infer_profiles(dumps.iter().map(String::as_str).chain(["<broken", "<hierarchy/>"]));
+        assert_eq!((profiles.len(), skipped), (1, 2));
+        let profile = &profiles[0];
+        assert_eq!((profile.package.as_str(), profile.dumps_analyzed, profile.clickable_count), ("com.example.feed", 3, 15));
+        assert_eq!(profile.id_prefixes[0].value, "com.example.feed:id/feed_");
+        assert_eq!(profile.custom_attributes[0].value, "app-layer");
+        assert_eq!(profile.containers.len(), 1);
+        assert_eq!(profile.containers[0].container, "com.example.feed:id/feed_list");
+        assert_eq!(profile.containers[0].dumps_seen, 3);
+        assert_eq!(profile.child_text_share, 0.6);
+        assert!(profile.element_type("com.example.feed:id/a1").unwrap().obfuscated);
+
+        // 计划顺序为主，子节点文本占优的应用把 child-to-parent 提到 self_desc 之前
+        let ranked = rank_with(profile, vec!["global_index_with_strong_checks", "self_desc", "child-to-parent"], |s| *s);
+        assert_eq!(ranked, vec!["global_index_with_strong_checks", "child-to-parent", "self_desc"]);
+
+        let title = ElementQuery {
+            resource_id: Some("com.example.feed:id/feed_title".into()),
+            text: Some("早安1".into()),
+            class: Some("android.widget.LinearLayout".into()),
+            ..Default::default()
+        };
+        let suggestions = suggest_selectors(Some(profile), &title);
+        let strategies: Vec<&str> = suggestions.iter().map(|s| s.strategy.as_str()).collect();
+        assert_eq!(strategies, vec!["child_to_parent", "self_id", "region_local_index_with_check"]);
+        assert_eq!(suggestions[2].xpath, "//*[@resource-id='com.example.feed:id/feed_list']/*");
+
+        let tab = ElementQuery { resource_id: Some("com.example.feed:id/tab_home".into()), ..Default::default() };
+        assert_eq!(suggest_selectors(Some(profile), &tab)[0].score, 0.9);
+        let obfuscated = ElementQuery { resource_id: Some("com.example.feed:id/a1".into()), ..Default::default() };
+        assert_eq!(suggest_selectors(Some(profile), &obfuscated)[0].score, 0.35);
+        assert_eq!(xpath_literal("it's"), "\"it's\"");
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(PROFILE_FILE_NAME);
+        AppProfileStore::open(path.clone()).upsert_all(profiles.clone()).unwrap();
+        let reopened = AppProfileStore::open(path);
+        let stored = reopened.get("com.example.feed").unwrap();
+        assert_eq!(stored.containers, profile.containers);
+        assert_eq!(stored.element_types.len(), profile.element_types.len());
+    }
+}
@@ -0,0 +1,248 @@
+// src-tauri/src/services/artifact_store/gallery.rs
+// module: artifact_store | layer: services | role: 截图图库查询
+// summary: 跨运行浏览截图制品 - 按设备 / 运行 / 步骤 / 时间过滤，感知哈希（dHash）折叠近似相同的画面，分页返回
+
+use chrono::{DateTime, Duration as ChronoDuration, Utc};
+use image::imageops::FilterType;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use tracing::warn;
+
+use super::{Artifact, ArtifactStore};
+
+/// 截图制品类型
+pub const SCREENSHOT_KIND: &str = "screenshot";
+/// 元信息里缓存感知哈希的字段
+const PHASH_META_KEY: &str = "phash";
+/// 运行 / 失败截图在图库中的保留天数（失败记录目录里的原件不受影响）
+pub const RUN_SCREENSHOT_TTL_DAYS: i64 = 7;
+/// 默认视为同一画面的最大汉明距离（64 位哈希）
+const DEFAULT_MAX_DISTANCE: u32 = 5;
+const DEFAULT_PAGE_SIZE: usize = 50;
+const MAX_PAGE_SIZE: usize = 200;
+
+/// 图库查询条件（字段均可省略）
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct GalleryQuery {
+    pub device_id: Option<String>,
+    pub run_id: Option<String>,
+    pub step_id: Option<String>,
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+    /// 是否折叠近似相同的画面（省略时折叠）
+    pub dedup: Option<bool>,
+    pub max_distance: Option<u32>,
+    pub offset: usize,
+    pub limit: Option<usize>,
+}
+
+/// 图库中的一张截图（折叠后的代表帧为最新的一张）
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GalleryItem {
+    pub artifact: Artifact,
+    pub local_path: String,
+    /// 缩略图路径，由 image_optimization 插件生成
+    pub thumbnail_path: Option<String>,
+    pub phash: Option<String>,
+    /// 被折叠进这一张的近似画面令牌（从新到旧）
+    pub duplicate_tokens: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GalleryPage {
+    pub items: Vec<GalleryItem>,
+    /// 过滤 + 折叠后的总数
+    pub total: usize,
+    /// 折叠前命中过滤条件的截图数
+    pub matched: usize,
+    pub offset: usize,
+    pub limit: usize,
+}
+
+/// dHash：缩放到 9x8 灰度，逐行比较相邻像素明暗
+pub fn dhash(img: &image::DynamicImage) -> u64 {
+    let small = img.resize_exact(9, 8, FilterType::Triangle).to_luma8();
+    let mut hash = 0u64;
+    for y in 0..8 {
+        for x in 0..8 {
+            hash <<= 1;
+            if small.get_pixel(x, y)[0] > small.get_pixel(x + 1, y)[0] {
+                hash |= 1;
+            }
+        }
+    }
+    hash
+}
+
+pub fn hamming(a: u64, b: u64) -> u32 {
+    (a ^ b).count_ones()
+}
+
+fn meta_str<'a>(artifact: &'a Artifact, key: &str) -> Option<&'a str> {
+    artifact.meta.get(key).and_then(Value::as_str)
+}
+
+impl GalleryQuery {
+    fn matches(&self, artifact: &Artifact) -> bool {
+        let field = |want: &Option<String>, key: &str| want.as_deref().is_none_or(|w| meta_str(artifact, key) == Some(w));
+        field(&self.device_id, "deviceId")
+            && field(&self.run_id, "runId")
+            && field(&self.step_id, "stepId")
+            && self.from.is_none_or(|from| artifact.created_at >= from)
+            && self.to.is_none_or(|to| artifact.created_at <= to)
+    }
+}
+
+/// 存入一张截图制品：感知哈希在加锁前算好并写进元信息，查询时无需再解码
+///
+/// `meta` 应带 `deviceId` / `runId` / `stepId`，供图库过滤；返回制品及其文件路径
+pub fn index_screenshot(
+    store: &Mutex<ArtifactStore>,
+    png: &[u8],
+    ttl: ChronoDuration,
+    mut meta: Value,
+) -> Result<(Artifact, PathBuf), String> {
+    match image::load_from_memory(png) {
+        Ok(img) => {
+            if !meta.is_object() {
+                meta = Value::Object(Default::default());
+            }
+            meta[PHASH_META_KEY] = Value::String(format!("{:016x}", dhash(&img)));
+        }
+        Err(e) => warn!("⚠️ 截图无法解码，入库时不计算感知哈希: {}", e),
+    }
+    let mut store = store.lock().map_err(|e| e.to_string())?;
+    let artifact = store.put(SCREENSHOT_KIND, png, "png", "image/png", ttl, meta)?;
+    let (_, path) = store.get(&artifact.token)?;
+    Ok((artifact, path))
+}
+
+/// 查询截图图库；旧制品缺少感知哈希时在锁外解码计算，再写回元信息
+pub fn query(store: &Mutex<ArtifactStore>, query: &GalleryQuery) -> Result<GalleryPage, String> {
+    let matched: Vec<_> = store
+        .lock()
+        .map_err(|e| e.to_string())?
+        .list(SCREENSHOT_KIND)
+        .into_iter()
+        .filter(|(a, _)| query.matches(a))
+        .collect();
+    let dedup = query.dedup.unwrap_or(true);
+
+    let mut hashed = Vec::with_capacity(matched.len());
+    let mut updates = Vec::new();
+    for (artifact, path) in matched {
+        let cached = meta_str(&artifact, PHASH_META_KEY).and_then(|h| u64::from_str_radix(h, 16).ok());
+        let hash = match cached {
+            Some(hash) => Some(hash),
+            None if dedup => match image::open(&path) {
+                Ok(img) => {
+                    let hash = dhash(&img);
+                    updates.push((artifact.token.clone(), PHASH_META_KEY, Value::String(format!("{:016x}", hash))));
+                    Some(hash)
+                }
+                Err(e) => {
+                    warn!("⚠️ 截图无法解码，跳过去重 token={}: {}", artifact.token, e);
+                    None
+                }
+            },
+            None => None,
+        };
+        hashed.push((artifact, path, hash));
+    }
+    if !updates.is_empty() {
+        store.lock().map_err(|e| e.to_string())?.set_meta(&updates)?;
+    }
+
+    // 列表已按时间从新到旧，每组保留最新一张作为代表帧
+    let max_distance = query.max_distance.unwrap_or(DEFAULT_MAX_DISTANCE);
+    let matched_count = hashed.len();
+    let mut items: Vec<(GalleryItem, Option<u64>)> = Vec::new();
+    for (artifact, path, hash) in hashed {
+        if dedup {
+            if let Some(hash) = hash {
+                if let Some((rep, _)) =
+                    items.iter_mut().find(|(_, h)| h.is_some_and(|h| hamming(h, hash) <= max_distance))
+                {
+                    rep.duplicate_tokens.push(artifact.token);
+                    continue;
+                }
+            }
+        }
+        items.push((
+            GalleryItem {
+                local_path: path.to_string_lossy().to_string(),
+                thumbnail_path: None,
+                phash: hash.map(|h| format!("{:016x}", h)),
+                duplicate_tokens: Vec::new(),
+                artifact,
+            },
+            hash,
+        ));
+    }
+
+    let total = items.len();
+    let limit = query.limit.unwrap_or(DEFAULT_PAGE_SIZE).clamp(1, MAX_PAGE_SIZE);
+    let items = items.into_iter().skip(query.offset).take(limit).map(|(item, _)| item).collect();
+    Ok(GalleryPage { items, total, matched: matched_count, offset: query.offset, limit })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration as ChronoDuration;
+    use std::io::Cursor;
+
+    fn png(f: impl Fn(u32, u32) -> u8) -> Vec<u8> {
+        let img = image::GrayImage::from_fn(64, 64, |x, y| image::Luma([f(x, y)]));
+        let mut bytes = Cursor::new(Vec::new());
+        image::DynamicImage::ImageLuma8(img).write_to(&mut bytes, image::ImageOutputFormat::Png).unwrap();
+        bytes.into_inner()
+    }
+
+    #[test]
+    fn test_filter_dedup_and_paginate() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = Mutex::new(ArtifactStore::open(dir.path().to_path_buf()));
+        let ttl = ChronoDuration::minutes(30);
+        let gradient = png(|x, _| (x * 4) as u8);
+        let gradient_noise = png(|x, y| if (x, y) == (3, 3) { 255 } else { (x * 4) as u8 });
+        let reversed = png(|x, _| 255 - (x * 4) as u8);
+        let meta = |device: &str, run: &str| serde_json::json!({ "deviceId": device, "runId": run });
+        {
+            let mut raw = store.lock().unwrap();
+            raw.put(SCREENSHOT_KIND, &gradient, "png", "image/png", ttl, meta("d1", "r1")).unwrap();
+            raw.put(SCREENSHOT_KIND, &reversed, "png", "image/png", ttl, meta("d1", "r1")).unwrap();
+            raw.put("log", b"x", "txt", "text/plain", ttl, meta("d1", "r1")).unwrap();
+        }
+        // 经 index_screenshot 入库的截图自带感知哈希
+        let (indexed, path) = index_screenshot(&store, &gradient_noise, ttl, meta("d1", "r2")).unwrap();
+        assert!(indexed.meta.get(PHASH_META_KEY).is_some());
+        assert!(path.exists());
+        index_screenshot(&store, &gradient, ttl, meta("d2", "r3")).unwrap();
+
+        let page = query(&store, &GalleryQuery::default()).unwrap();
+        assert_eq!((page.matched, page.total), (4, 2));
+        assert_eq!(page.items.iter().map(|i| i.duplicate_tokens.len()).sum::<usize>(), 2);
+
+        let d1 = GalleryQuery { device_id: Some("d1".into()), ..Default::default() };
+        assert_eq!(query(&store, &d1).unwrap().total, 2);
+        let raw = GalleryQuery { device_id: Some("d1".into()), dedup: Some(false), ..Default::default() };
+        assert_eq!(query(&store, &raw).unwrap().total, 3);
+        let run = GalleryQuery { run_id: Some("r2".into()), ..Default::default() };
+        assert_eq!(query(&store, &run).unwrap().total, 1);
+        let future = GalleryQuery { from: Some(Utc::now() + ChronoDuration::minutes(1)), ..Default::default() };
+        assert_eq!(query(&store, &future).unwrap().total, 0);
+
+        let second = query(&store, &GalleryQuery { offset: 1, limit: Some(1), ..Default::default() }).unwrap();
+        assert_eq!((second.items.len(), second.total), (1, 2));
+
+        // 感知哈希已缓存进索引
+        let reopened = ArtifactStore::open(dir.path().to_path_buf());
+        assert!(reopened.list(SCREENSHOT_KIND).iter().all(|(a, _)| a.meta.get(PHASH_META_KEY).is_some()));
+    }
+}
@@ -0,0 +1,223 @@
+// src-tauri/src/services/artifact_store/mod.rs
+// module: artifact_store | layer: services | role: 短期制品存储
+// summary: 截图等制品按短令牌存放在数据目录，到期自动清理；令牌可贴进工单，持有令牌即可取回文件
+
+pub mod gallery;
+
+use chrono::{DateTime, Duration as ChronoDuration, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::path::PathBuf;
+use std::sync::{Mutex, OnceLock};
+use tracing::{info, warn};
+
+/// 制品目录名（数据目录下）
+const ARTIFACT_DIR_NAME: &str = "artifacts";
+const INDEX_FILE_NAME: &str = "index.json";
+/// 缩略图子目录（按令牌命名，随制品一起清理）
+const THUMB_DIR_NAME: &str = "thumbs";
+/// 令牌长度（十六进制字符）
+const TOKEN_LEN: usize = 12;
+/// 后台清理过期制品的间隔
+const PURGE_INTERVAL: std::time::Duration = std::time::Duration::from_secs(10 * 60);
+
+static STORE: OnceLock<Mutex<ArtifactStore>> = OnceLock::new();
+
+/// 一个制品的元信息
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Artifact {
+    pub token: String,
+    /// 如 `screenshot`
+    pub kind: String,
+    pub file_name: String,
+    pub content_type: String,
+    pub size: u64,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+    /// 附加信息（设备 ID 等）
+    #[serde(default)]
+    pub meta: Value,
+}
+
+pub struct ArtifactStore {
+    dir: PathBuf,
+    artifacts: Vec<Artifact>,
+}
+
+impl ArtifactStore {
+    pub fn global() -> &'static Mutex<ArtifactStore> {
+        STORE.get_or_init(|| {
+            let dir = dirs::data_dir().unwrap_or_else(|| PathBuf::from(".")).join("employee-gui").join(ARTIFACT_DIR_NAME);
+            Mutex::new(Self::open(dir))
+        })
+    }
+
+    pub fn open(dir: PathBuf) -> Self {
+        let artifacts = std::fs::read_to_string(dir.join(INDEX_FILE_NAME))
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+        Self { dir, artifacts }
+    }
+
+    fn save_index(&self) -> Result<(), String> {
+        std::fs::create_dir_all(&self.dir).map_err(|e| format!("创建制品目录失败: {}", e))?;
+        let content = serde_json::to_string_pretty(&self.artifacts).map_err(|e| e.to_string())?;
+        std::fs::write(self.dir.join(INDEX_FILE_NAME), content).map_err(|e| format!("写入制品索引失败: {}", e))
+    }
+
+    /// 存入制品，返回带令牌的元信息
+    pub fn put(
+        &mut self,
+        kind: &str,
+        bytes: &[u8],
+        extension: &str,
+        content_type: &str,
+        ttl: ChronoDuration,
+        meta: Value,
+    ) -> Result<Artifact, String> {
+        let now = Utc::now();
+        self.purge_expired(now);
+        let token: String = uuid::Uuid::new_v4().simple().to_string().chars().take(TOKEN_LEN).collect();
+        let file_name = format!("{}.{}", token, extension);
+        std::fs::create_dir_all(&self.dir).map_err(|e| format!("创建制品目录失败: {}", e))?;
+        std::fs::write(self.dir.join(&file_name), bytes).map_err(|e| format!("写入制品失败: {}", e))?;
+        let artifact = Artifact {
+            token,
+            kind: kind.to_string(),
+            file_name,
+            content_type: content_type.to_string(),
+            size: bytes.len() as u64,
+            created_at: now,
+            expires_at: now + ttl,
+            meta,
+        };
+        self.artifacts.push(artifact.clone());
+        self.save_index()?;
+        Ok(artifact)
+    }
+
+    /// 按令牌取制品及其文件路径；过期或不存在时报错
+    pub fn get(&mut self, token: &str) -> Result<(Artifact, PathBuf), String> {
+        self.purge_expired(Utc::now());
+        let artifact = self
+            .artifacts
+            .iter()
+            .find(|a| a.token == token.trim())
+            .cloned()
+            .ok_or_else(|| format!("制品不存在或已过期: {}", token))?;
+        let path = self.dir.join(&artifact.file_name);
+        Ok((artifact, path))
+    }
+
+    /// 某类制品（未过期）及其文件路径，按创建时间从新到旧
+    pub fn list(&self, kind: &str) -> Vec<(Artifact, PathBuf)> {
+        let now = Utc::now();
+        let mut items: Vec<_> = self
+            .artifacts
+            .iter()
+            .filter(|a| a.kind == kind && a.expires_at > now)
+            .map(|a| (a.clone(), self.dir.join(&a.file_name)))
+            .collect();
+        items.sort_by(|a, b| b.0.created_at.cmp(&a.0.created_at));
+        items
+    }
+
+    /// 批量写入制品元信息字段（如缓存的感知哈希），只落盘一次
+    pub fn set_meta(&mut self, updates: &[(String, &str, Value)]) -> Result<(), String> {
+        if updates.is_empty() {
+            return Ok(());
+        }
+        for (token, key, value) in updates {
+            if let Some(artifact) = self.artifacts.iter_mut().find(|a| &a.token == token) {
+                if !artifact.meta.is_object() {
+                    artifact.meta = Value::Object(Default::default());
+                }
+                artifact.meta[*key] = value.clone();
+            }
+        }
+        self.save_index()
+    }
+
+    /// 按令牌删除制品（连同缩略图），只落盘一次；返回实际删除数
+    pub fn remove(&mut self, tokens: &[String]) -> Result<usize, String> {
+        if tokens.is_empty() {
+            return Ok(0);
+        }
+        let (removed, kept): (Vec<Artifact>, Vec<Artifact>) =
+            std::mem::take(&mut self.artifacts).into_iter().partition(|a| tokens.contains(&a.token));
+        self.artifacts = kept;
+        for artifact in &removed {
+            let _ = std::fs::remove_file(self.dir.join(&artifact.file_name));
+            let _ = std::fs::remove_file(self.thumbnail_path(&artifact.token));
+        }
+        if !removed.is_empty() {
+            self.save_index()?;
+        }
+        Ok(removed.len())
+    }
+
+    /// 制品缩略图路径（不保证已生成）
+    pub fn thumbnail_path(&self, token: &str) -> PathBuf {
+        self.dir.join(THUMB_DIR_NAME).join(format!("{}.webp", token))
+    }
+
+    /// 删除已过期的制品，返回删除数
+    pub fn purge_expired(&mut self, now: DateTime<Utc>) -> usize {
+        let (expired, alive): (Vec<Artifact>, Vec<Artifact>) =
+            std::mem::take(&mut self.artifacts).into_iter().partition(|a| a.expires_at <= now);
+        self.artifacts = alive;
+        for artifact in &expired {
+            let _ = std::fs::remove_file(self.dir.join(&artifact.file_name));
+            let _ = std::fs::remove_file(self.thumbnail_path(&artifact.token));
+        }
+        if !expired.is_empty() {
+            if let Err(e) = self.save_index() {
+                warn!("⚠️ 清理过期制品后保存索引失败: {}", e);
+            }
+        }
+        expired.len()
+    }
+}
+
+/// 后台清理（应用启动时 spawn）：启动时先清一次，之后按固定间隔清理，不依赖有人存取制品
+pub async fn run_purger() {
+    loop {
+        let purged = tokio::task::spawn_blocking(|| {
+            ArtifactStore::global().lock().map(|mut store| store.purge_expired(Utc::now())).unwrap_or(0)
+        })
+        .await
+        .unwrap_or(0);
+        if purged > 0 {
+            info!("🧹 已清理过期制品 {} 个", purged);
+        }
+        tokio::time::sleep(PURGE_INTERVAL).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_put_get_and_expire() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut store = ArtifactStore::open(dir.path().to_path_buf());
+        let kept = store
+            .put("screenshot", b"png", "png", "image/png", ChronoDuration::minutes(30), serde_json::json!({ "deviceId": "d1" }))
+            .unwrap();
+        let expired = store.put("screenshot", b"old", "png", "image/png", ChronoDuration::seconds(-1), Value::Null).unwrap();
+        assert_eq!(kept.token.len(), TOKEN_LEN);
+
+        let (artifact, path) = store.get(&kept.token).unwrap();
+        assert_eq!(artifact.meta["deviceId"], "d1");
+        assert_eq!(std::fs::read(path).unwrap(), b"png");
+        assert!(store.get(&expired.token).is_err());
+        assert!(!dir.path().join(&expired.file_name).exists());
+
+        // 索引落盘，重新打开后仍可取回
+        let mut reopened = ArtifactStore::open(dir.path().to_path_buf());
+        assert!(reopened.get(&kept.token).is_ok());
+    }
+}
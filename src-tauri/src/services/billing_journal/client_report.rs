@@ -0,0 +1,417 @@
+// src-tauri/src/services/billing_journal/client_report.rs
+// module: billing_journal | layer: services | role: 客户聚合报表
+// summary: 面向客户的只含聚合数据的报表 - 按动作 / 活动 / 日 / 周 / 月分组计数，不足最小分组人数的组隐藏、计数按档位取整，拒绝行级字段，输出可套模板的 JSON 并带生成工作区水印
+
+use chrono::{DateTime, Datelike, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, BTreeSet};
+
+use super::{action_label, BillingEntry, BillingJournal};
+use crate::core::shared::i18n::{self, Locale};
+use crate::services::report_export::{Column, ExportOptions, ExportOutcome, ExportProgress, ReportExporter};
+
+/// 输出模板标识（前端 / PDF 渲染按此选择模板）
+pub const REPORT_TEMPLATE: &str = "client_aggregate_v1";
+/// 默认最小分组人数（去重后的对象数）
+const DEFAULT_MIN_GROUP_SIZE: usize = 10;
+/// 默认计数取整档位
+const DEFAULT_BUCKET: u64 = 5;
+/// 行级字段：不得作为分组维度出现在客户报表中
+const ROW_LEVEL_FIELDS: &[&str] = &["target", "account", "account_id", "device", "device_id", "timestamp"];
+
+/// 分组维度（只允许聚合维度）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReportDimension {
+    Action,
+    Campaign,
+    Day,
+    Week,
+    Month,
+}
+
+impl ReportDimension {
+    pub fn parse(name: &str) -> Result<Self, String> {
+        match name.trim() {
+            "action" => Ok(Self::Action),
+            "campaign" | "campaign_id" => Ok(Self::Campaign),
+            "day" => Ok(Self::Day),
+            "week" => Ok(Self::Week),
+            "month" => Ok(Self::Month),
+            other if ROW_LEVEL_FIELDS.contains(&other) => {
+                Err(format!("客户报表只允许聚合数据，拒绝按行级字段「{}」分组", other))
+            }
+            other => Err(format!("不支持的分组维度: {}（支持 action / campaign / day / week / month）", other)),
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Self::Action => "动作",
+            Self::Campaign => "活动",
+            Self::Day => "日期",
+            Self::Week => "周",
+            Self::Month => "月份",
+        }
+    }
+
+    fn key(self, entry: &BillingEntry, locale: Locale) -> String {
+        let date = entry.timestamp.get(..10).and_then(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d").ok());
+        match self {
+            Self::Action => action_label(locale, &entry.action),
+            Self::Campaign if entry.campaign_id.is_empty() => "未归属活动".to_string(),
+            Self::Campaign => entry.campaign_id.clone(),
+            Self::Day => date.map(|d| d.to_string()).unwrap_or_default(),
+            Self::Week => date.map(|d| format!("{}-W{:02}", d.iso_week().year(), d.iso_week().week())).unwrap_or_default(),
+            Self::Month => date.map(|d| d.format("%Y-%m").to_string()).unwrap_or_default(),
+        }
+    }
+}
+
+/// 转化率：分子动作总量 / 分母动作总量
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RateSpec {
+    pub label: String,
+    pub numerator: String,
+    pub denominator: String,
+}
+
+/// 报表请求
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClientReportRequest {
+    pub client_id: String,
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    /// 分组维度名（action / campaign / day / week / month）
+    #[serde(default)]
+    pub group_by: Vec<String>,
+    #[serde(default)]
+    pub rates: Vec<RateSpec>,
+    pub min_group_size: Option<usize>,
+    pub bucket: Option<u64>,
+    pub title: Option<String>,
+    pub locale: Option<String>,
+}
+
+/// 报表中的一张表
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReportSection {
+    pub heading: String,
+    pub columns: Vec<String>,
+    pub rows: Vec<Vec<String>>,
+    pub note: Option<String>,
+}
+
+/// 隐私处理说明（随报表一起展示给客户）
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PrivacyNote {
+    pub min_group_size: usize,
+    pub bucket: u64,
+    /// 因人数不足被隐藏的分组数
+    pub suppressed_groups: usize,
+    /// 恒为 false：报表不含任何行级数据
+    pub row_level_data: bool,
+}
+
+/// 生成工作区水印
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Watermark {
+    pub workspace: String,
+    pub machine_id: String,
+    pub generated_at: DateTime<Utc>,
+    /// 报表内容摘要，用于核对导出件未被篡改
+    pub digest: String,
+    pub text: String,
+}
+
+/// 客户报表（PDF / HTML 模板直接渲染）
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClientReport {
+    pub template: String,
+    pub title: String,
+    pub client_id: String,
+    pub period_from: NaiveDate,
+    pub period_to: NaiveDate,
+    pub sections: Vec<ReportSection>,
+    pub privacy: PrivacyNote,
+    pub watermark: Watermark,
+}
+
+/// 生成报表的工作区身份
+#[derive(Debug, Clone)]
+pub struct Workspace {
+    pub name: String,
+    pub machine_id: String,
+}
+
+/// 一个分组的累计：数量与去重对象
+#[derive(Default)]
+struct Group {
+    quantity: u64,
+    targets: BTreeSet<String>,
+    untargeted: usize,
+}
+
+impl Group {
+    fn add(&mut self, entry: &BillingEntry) {
+        self.quantity += entry.quantity;
+        if entry.target.is_empty() {
+            self.untargeted += 1;
+        } else {
+            self.targets.insert(entry.target.clone());
+        }
+    }
+
+    /// 分组人数：去重对象数（无对象的记录逐条计）
+    fn size(&self) -> usize {
+        self.targets.len() + self.untargeted
+    }
+}
+
+/// 四舍五入到档位；非零但不足半档时显示为 `<档位`
+fn bucketed(value: u64, bucket: u64) -> String {
+    let rounded = (value + bucket / 2) / bucket * bucket;
+    if value > 0 && rounded == 0 {
+        format!("<{}", bucket)
+    } else {
+        rounded.to_string()
+    }
+}
+
+/// 由计费记录生成聚合报表（纯函数，记录不会出现在输出中）
+pub fn build_report(
+    entries: &[BillingEntry],
+    request: &ClientReportRequest,
+    workspace: &Workspace,
+    now: DateTime<Utc>,
+) -> Result<ClientReport, String> {
+    let dimensions = request.group_by.iter().map(|d| ReportDimension::parse(d)).collect::<Result<Vec<_>, _>>()?;
+    let locale = i18n::resolve_locale(request.locale.as_deref());
+    let min_group = request.min_group_size.unwrap_or(DEFAULT_MIN_GROUP_SIZE).max(2);
+    let bucket = request.bucket.unwrap_or(DEFAULT_BUCKET).max(1);
+    let mut suppressed = 0;
+
+    let mut by_action: BTreeMap<String, Group> = BTreeMap::new();
+    for entry in entries {
+        by_action.entry(entry.action.clone()).or_default().add(entry);
+    }
+    let mut overview_rows = Vec::new();
+    for (action, group) in &by_action {
+        if group.size() < min_group {
+            suppressed += 1;
+            continue;
+        }
+        overview_rows.push(vec![action_label(locale, action), bucketed(group.quantity, bucket)]);
+    }
+    let mut sections = vec![ReportSection {
+        heading: "总览".to_string(),
+        columns: vec!["动作".to_string(), "数量".to_string()],
+        rows: overview_rows,
+        note: None,
+    }];
+
+    if !dimensions.is_empty() {
+        let mut groups: BTreeMap<Vec<String>, Group> = BTreeMap::new();
+        for entry in entries {
+            let key = dimensions.iter().map(|d| d.key(entry, locale)).collect();
+            groups.entry(key).or_default().add(entry);
+        }
+        let mut rows = Vec::new();
+        let mut hidden = 0;
+        for (key, group) in groups {
+            if group.size() < min_group {
+                hidden += 1;
+                continue;
+            }
+            let mut row = key;
+            row.push(bucketed(group.quantity, bucket));
+            rows.push(row);
+        }
+        suppressed += hidden;
+        let mut columns: Vec<String> = dimensions.iter().map(|d| d.label().to_string()).collect();
+        columns.push("数量".to_string());
+        sections.push(ReportSection {
+            heading: "分组统计".to_string(),
+            columns,
+            rows,
+            note: (hidden > 0).then(|| format!("{} 个分组人数不足 {}，已隐藏", hidden, min_group)),
+        });
+    }
+
+    if !request.rates.is_empty() {
+        let rows = request
+            .rates
+            .iter()
+            .map(|rate| {
+                let numerator = by_action.get(&rate.numerator).map_or(0, |g| g.quantity);
+                let denominator = by_action.get(&rate.denominator);
+                let value = match denominator {
+                    Some(d) if d.size() >= min_group && d.quantity > 0 => {
+                        format!("{}%", (numerator as f64 * 100.0 / d.quantity as f64).round())
+                    }
+                    _ => "—".to_string(),
+                };
+                vec![rate.label.clone(), value]
+            })
+            .collect();
+        sections.push(ReportSection {
+            heading: "转化率".to_string(),
+            columns: vec!["指标".to_string(), "比率".to_string()],
+            rows,
+            note: Some(format!("分母人数不足 {} 时不计算", min_group)),
+        });
+    }
+
+    let title = request.title.clone().unwrap_or_else(|| format!("{} 运营报告", request.client_id));
+    let digest_source = serde_json::to_string(&(&request.client_id, request.from, request.to, &sections, &workspace.name))
+        .map_err(|e| e.to_string())?;
+    let digest: String = hex::encode(Sha256::digest(digest_source.as_bytes())).chars().take(16).collect();
+    let machine_id: String = workspace.machine_id.chars().take(8).collect();
+    let watermark = Watermark {
+        text: format!("{} · {} · {} · #{}", workspace.name, machine_id, now.format("%Y-%m-%d %H:%M UTC"), digest),
+        workspace: workspace.name.clone(),
+        machine_id,
+        generated_at: now,
+        digest,
+    };
+
+    Ok(ClientReport {
+        template: REPORT_TEMPLATE.to_string(),
+        title,
+        client_id: request.client_id.clone(),
+        period_from: request.from,
+        period_to: request.to,
+        sections,
+        privacy: PrivacyNote { min_group_size: min_group, bucket, suppressed_groups: suppressed, row_level_data: false },
+        watermark,
+    })
+}
+
+/// 从全局计费日志生成客户报表，水印取当前授权的被授权方与本机 ID
+pub fn generate(request: &ClientReportRequest) -> Result<ClientReport, String> {
+    let entries = BillingJournal::global()
+        .lock()
+        .map_err(|e| e.to_string())?
+        .entries_between(&request.client_id, request.from, request.to)?;
+    let status = crate::services::licensing::license_status();
+    let workspace = Workspace {
+        name: status.license.map(|l| l.licensee).unwrap_or_else(|| "未授权工作区".to_string()),
+        machine_id: status.machine_id,
+    };
+    build_report(&entries, request, &workspace, Utc::now())
+}
+
+/// 导出客户报表：每个分节一张表，末尾附说明表（报表周期、分节备注、隐私处理与水印）
+pub fn export_report<F: FnMut(&ExportProgress)>(
+    report: &ClientReport,
+    options: ExportOptions,
+    on_progress: F,
+) -> Result<ExportOutcome, String> {
+    let locale = options.locale;
+    let note = |label_key: &str, value: String| serde_json::json!({ "item": i18n::t_in(locale, label_key), "value": value });
+    let mut notes = vec![
+        note("export.note.title", report.title.clone()),
+        note("export.note.period", format!("{} ~ {}", report.period_from, report.period_to)),
+    ];
+    notes.extend(
+        report
+            .sections
+            .iter()
+            .filter_map(|s| s.note.as_ref().map(|text| serde_json::json!({ "item": s.heading, "value": text }))),
+    );
+    notes.push(note("export.note.min_group_size", report.privacy.min_group_size.to_string()));
+    notes.push(note("export.note.bucket", report.privacy.bucket.to_string()));
+    notes.push(note("export.note.suppressed_groups", report.privacy.suppressed_groups.to_string()));
+    notes.push(note("export.note.watermark", report.watermark.text.clone()));
+    notes.push(note("export.note.digest", report.watermark.digest.clone()));
+    let total = report.sections.iter().map(|s| s.rows.len()).sum::<usize>() + notes.len();
+    let mut exporter = ReportExporter::create("client_report", options, Some(total as u64), on_progress)?;
+
+    for section in &report.sections {
+        let columns = section
+            .columns
+            .iter()
+            .enumerate()
+            .map(|(i, label)| Column::new(&i.to_string(), label.clone()))
+            .collect();
+        exporter.start_table(Some(&section.heading), columns)?;
+        for row in &section.rows {
+            let cells: serde_json::Map<String, serde_json::Value> =
+                row.iter().enumerate().map(|(i, cell)| (i.to_string(), cell.clone().into())).collect();
+            exporter.write_row(&serde_json::Value::Object(cells))?;
+        }
+    }
+
+    exporter.start_table(
+        Some(&i18n::t_in(locale, "export.note.heading")),
+        vec![
+            Column::localized("item", "export.note.item", locale),
+            Column::localized("value", "export.note.value", locale),
+        ],
+    )?;
+    exporter.write_rows(&notes)?;
+    exporter.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(day: u32, action: &str, campaign: &str, target: &str) -> BillingEntry {
+        BillingEntry {
+            timestamp: format!("2026-10-{:02}T10:00:00+08:00", day),
+            client_id: "acme".into(),
+            action: action.into(),
+            account_id: "acc_1".into(),
+            campaign_id: campaign.into(),
+            device_id: "d1".into(),
+            quantity: 1,
+            target: target.into(),
+        }
+    }
+
+    fn request(group_by: &[&str]) -> ClientReportRequest {
+        ClientReportRequest {
+            client_id: "acme".into(),
+            from: NaiveDate::from_ymd_opt(2026, 10, 1).unwrap(),
+            to: NaiveDate::from_ymd_opt(2026, 10, 31).unwrap(),
+            group_by: group_by.iter().map(|s| s.to_string()).collect(),
+            rates: vec![RateSpec { label: "回复率".into(), numerator: "reply".into(), denominator: "follow".into() }],
+            min_group_size: Some(3),
+            bucket: Some(5),
+            title: None,
+            locale: None,
+        }
+    }
+
+    #[test]
+    fn aggregates_with_suppression_and_refuses_row_level() {
+        let mut entries: Vec<_> = (0..12).map(|i| entry(1, "follow", "c1", &format!("user_{}", i))).collect();
+        entries.extend((0..4).map(|i| entry(2, "reply", "c1", &format!("user_{}", i))));
+        // 只有两个对象的活动，应被隐藏
+        entries.extend((0..2).map(|i| entry(3, "follow", "c2", &format!("vip_{}", i))));
+        let workspace = Workspace { name: "星河代理".into(), machine_id: "abcdef123456".into() };
+
+        let report = build_report(&entries, &request(&["campaign", "action"]), &workspace, Utc::now()).unwrap();
+        assert_eq!(report.sections[0].rows.len(), 2);
+        let grouped = &report.sections[1];
+        assert_eq!(grouped.rows.len(), 2);
+        assert!(grouped.rows.iter().all(|r| r[0] == "c1"));
+        assert_eq!(report.privacy.suppressed_groups, 1);
+        // 14 个关注中 4 个回复 → 29%
+        assert_eq!(report.sections[2].rows[0][1], "29%");
+        assert!(report.watermark.text.starts_with("星河代理 · abcdef12 · "));
+
+        let json = serde_json::to_string(&report).unwrap();
+        assert!(!json.contains("user_") && !json.contains("vip_") && !json.contains("acc_1"));
+
+        assert!(build_report(&entries, &request(&["target"]), &workspace, Utc::now()).unwrap_err().contains("行级"));
+        assert_eq!((bucketed(2, 5), bucketed(13, 5), bucketed(0, 5)), ("<5".to_string(), "15".to_string(), "0".to_string()));
+    }
+}
@@ -0,0 +1,433 @@
+// src-tauri/src/services/billing_journal/mod.rs
+// module: billing_journal | layer: services | role: 计费动作日志
+// summary: 将可计费动作（关注/回复/导入）按客户写入按天滚动的 CSV，并生成客户月度汇总
+
+pub mod client_report;
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fs::OpenOptions;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use tracing::{info, warn};
+
+use crate::core::shared::i18n::{self, Locale};
+use crate::services::report_export::{self, CellFormat, Column, ExportFormat};
+
+/// 配置文件名
+const CONFIG_FILE_NAME: &str = "billing_journal.json";
+
+static JOURNAL: OnceLock<Mutex<BillingJournal>> = OnceLock::new();
+
+/// 计费日志配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BillingJournalConfig {
+    /// 默认关闭：需在计费设置中显式开启后才写入客户账单
+    pub enabled: bool,
+    /// 输出目录（为空时使用数据目录下的 billing/）
+    #[serde(default)]
+    pub output_dir: Option<String>,
+    /// 需要记账的动作
+    pub billable_actions: Vec<String>,
+    /// 账号 → 客户
+    #[serde(default)]
+    pub account_clients: HashMap<String, String>,
+    /// 未映射账号归属的客户
+    pub default_client: String,
+}
+
+impl Default for BillingJournalConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            output_dir: None,
+            billable_actions: vec!["follow".into(), "reply".into(), "import".into()],
+            account_clients: HashMap::new(),
+            default_client: "default".into(),
+        }
+    }
+}
+
+/// 一条计费记录（即 CSV 的一行）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BillingEntry {
+    /// RFC3339 本地时间
+    pub timestamp: String,
+    pub client_id: String,
+    pub action: String,
+    pub account_id: String,
+    pub campaign_id: String,
+    pub device_id: String,
+    pub quantity: u64,
+    /// 动作对象（目标用户 / 导入文件等）
+    pub target: String,
+}
+
+/// 待记账的动作
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BillableAction {
+    pub action: String,
+    #[serde(default)]
+    pub account_id: Option<String>,
+    #[serde(default)]
+    pub campaign_id: Option<String>,
+    #[serde(default)]
+    pub device_id: Option<String>,
+    /// 显式指定客户（否则按账号映射）
+    #[serde(default)]
+    pub client_id: Option<String>,
+    #[serde(default)]
+    pub target: Option<String>,
+    #[serde(default)]
+    pub quantity: Option<u64>,
+}
+
+/// 客户月度汇总
+#[derive(Debug, Clone, Serialize)]
+pub struct ClientMonthlySummary {
+    pub client_id: String,
+    /// YYYY-MM
+    pub month: String,
+    /// 动作 → 数量
+    pub totals: BTreeMap<String, u64>,
+    pub total: u64,
+    /// 有记录的天数
+    pub active_days: usize,
+}
+
+/// 计费日志
+pub struct BillingJournal {
+    config: BillingJournalConfig,
+    config_path: Option<PathBuf>,
+    default_output_dir: PathBuf,
+}
+
+impl BillingJournal {
+    pub fn global() -> &'static Mutex<BillingJournal> {
+        JOURNAL.get_or_init(|| Mutex::new(Self::load_default()))
+    }
+
+    /// 指定输出目录的实例（配置不落盘）
+    pub fn with_output_dir(output_dir: PathBuf, config: BillingJournalConfig) -> Self {
+        Self { config, config_path: None, default_output_dir: output_dir }
+    }
+
+    fn load_default() -> Self {
+        let base = dirs::data_dir()
+            .unwrap_or_else(|| PathBuf::from("."))
+            .join("employee-gui");
+        let config_path = base.join(CONFIG_FILE_NAME);
+        let config = std::fs::read_to_string(&config_path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+        Self { config, config_path: Some(config_path), default_output_dir: base.join("billing") }
+    }
+
+    pub fn config(&self) -> &BillingJournalConfig {
+        &self.config
+    }
+
+    pub fn save_config(&mut self, config: BillingJournalConfig) -> Result<(), String> {
+        if let Some(path) = &self.config_path {
+            if let Some(dir) = path.parent() {
+                std::fs::create_dir_all(dir).map_err(|e| format!("创建配置目录失败: {}", e))?;
+            }
+            let content = serde_json::to_string_pretty(&config).map_err(|e| e.to_string())?;
+            std::fs::write(path, content).map_err(|e| format!("保存计费日志配置失败: {}", e))?;
+        }
+        info!("💾 计费日志配置已更新: enabled={}, actions={:?}", config.enabled, config.billable_actions);
+        self.config = config;
+        Ok(())
+    }
+
+    fn output_dir(&self) -> PathBuf {
+        self.config
+            .output_dir
+            .as_ref()
+            .filter(|d| !d.is_empty())
+            .map(PathBuf::from)
+            .unwrap_or_else(|| self.default_output_dir.clone())
+    }
+
+    fn resolve_client(&self, action: &BillableAction) -> String {
+        action
+            .client_id
+            .clone()
+            .filter(|c| !c.is_empty())
+            .or_else(|| {
+                action
+                    .account_id
+                    .as_ref()
+                    .and_then(|a| self.config.account_clients.get(a).cloned())
+            })
+            .unwrap_or_else(|| self.config.default_client.clone())
+    }
+
+    /// 记录一次动作；非计费动作或已关闭时返回 `Ok(None)`
+    pub fn record(&self, action: &BillableAction) -> Result<Option<BillingEntry>, String> {
+        if !self.config.enabled || !self.config.billable_actions.iter().any(|a| a == &action.action) {
+            return Ok(None);
+        }
+
+        let now = chrono::Local::now();
+        let entry = BillingEntry {
+            timestamp: now.to_rfc3339(),
+            client_id: self.resolve_client(action),
+            action: action.action.clone(),
+            account_id: action.account_id.clone().unwrap_or_default(),
+            campaign_id: action.campaign_id.clone().unwrap_or_default(),
+            device_id: action.device_id.clone().unwrap_or_default(),
+            quantity: action.quantity.unwrap_or(1),
+            target: action.target.clone().unwrap_or_default(),
+        };
+
+        let path = self.daily_file(&entry.client_id, &now.format("%Y-%m-%d").to_string());
+        append_entry(&path, &entry)?;
+        Ok(Some(entry))
+    }
+
+    /// 按天滚动的文件：<output>/<client>/<client>_<YYYY-MM-DD>.csv
+    fn daily_file(&self, client_id: &str, date: &str) -> PathBuf {
+        let client = sanitize(client_id);
+        self.output_dir().join(&client).join(format!("{}_{}.csv", client, date))
+    }
+
+    /// 某客户在日期区间（含两端）内的全部记录，仅供服务端聚合使用
+    pub fn entries_between(
+        &self,
+        client_id: &str,
+        from: chrono::NaiveDate,
+        to: chrono::NaiveDate,
+    ) -> Result<Vec<BillingEntry>, String> {
+        if from > to {
+            return Err(format!("起始日期 {} 晚于结束日期 {}", from, to));
+        }
+        let mut entries = Vec::new();
+        for date in from.iter_days().take_while(|d| *d <= to) {
+            let path = self.daily_file(client_id, &date.format("%Y-%m-%d").to_string());
+            if path.exists() {
+                entries.extend(read_entries(&path)?);
+            }
+        }
+        Ok(entries)
+    }
+
+    /// 生成月度汇总；`client_id` 为空时汇总所有客户
+    pub fn monthly_summary(&self, month: &str, client_id: Option<&str>) -> Result<Vec<ClientMonthlySummary>, String> {
+        if chrono::NaiveDate::parse_from_str(&format!("{}-01", month), "%Y-%m-%d").is_err() {
+            return Err(format!("无效的月份: {}（应为 YYYY-MM）", month));
+        }
+
+        let root = self.output_dir();
+        let clients: Vec<String> = match client_id {
+            Some(client) => vec![sanitize(client)],
+            None => match std::fs::read_dir(&root) {
+                Ok(entries) => entries
+                    .filter_map(|e| e.ok())
+                    .filter(|e| e.path().is_dir())
+                    .map(|e| e.file_name().to_string_lossy().to_string())
+                    .collect(),
+                Err(_) => Vec::new(),
+            },
+        };
+
+        let mut summaries = Vec::new();
+        for client in clients {
+            let prefix = format!("{}_{}-", client, month);
+            let Ok(entries) = std::fs::read_dir(root.join(&client)) else {
+                continue;
+            };
+
+            let mut totals: BTreeMap<String, u64> = BTreeMap::new();
+            let mut active_days = 0;
+            for file in entries.filter_map(|e| e.ok()) {
+                let name = file.file_name().to_string_lossy().to_string();
+                if !name.starts_with(&prefix) || !name.ends_with(".csv") {
+                    continue;
+                }
+                active_days += 1;
+                for entry in read_entries(&file.path())? {
+                    *totals.entry(entry.action).or_default() += entry.quantity;
+                }
+            }
+
+            if active_days > 0 {
+                summaries.push(ClientMonthlySummary {
+                    client_id: client,
+                    month: month.to_string(),
+                    total: totals.values().sum(),
+                    totals,
+                    active_days,
+                });
+            }
+        }
+        summaries.sort_by(|a, b| a.client_id.cmp(&b.client_id));
+        Ok(summaries)
+    }
+}
+
+/// 月度汇总的导出列（动作列按字母序，表头与动作名称按语言本地化）
+pub fn summary_columns(summaries: &[ClientMonthlySummary], locale: Locale) -> Vec<Column> {
+    let mut actions: Vec<&String> = summaries.iter().flat_map(|s| s.totals.keys()).collect();
+    actions.sort();
+    actions.dedup();
+
+    let mut columns = vec![
+        Column::localized("client", "billing.report.client", locale),
+        Column::localized("month", "billing.report.month", locale),
+    ];
+    columns.extend(
+        actions
+            .iter()
+            .map(|action| Column::new(&format!("action:{}", action), action_label(locale, action)).format(CellFormat::Integer)),
+    );
+    columns.push(Column::localized("total", "billing.report.total", locale).format(CellFormat::Integer));
+    columns.push(Column::localized("active_days", "billing.report.active_days", locale).format(CellFormat::Integer));
+    columns
+}
+
+/// 月度汇总转为导出行，键与 [`summary_columns`] 对应；未出现的动作记 0
+pub fn summary_rows(summaries: &[ClientMonthlySummary]) -> Vec<serde_json::Value> {
+    let actions: Vec<&String> = summaries.iter().flat_map(|s| s.totals.keys()).collect();
+    summaries
+        .iter()
+        .map(|summary| {
+            let mut row = serde_json::Map::new();
+            row.insert("client".into(), summary.client_id.clone().into());
+            row.insert("month".into(), summary.month.clone().into());
+            for action in &actions {
+                row.insert(format!("action:{}", action), summary.totals.get(*action).copied().unwrap_or(0).into());
+            }
+            row.insert("total".into(), summary.total.into());
+            row.insert("active_days".into(), summary.active_days.into());
+            serde_json::Value::Object(row)
+        })
+        .collect()
+}
+
+/// 将月度汇总渲染为客户可读的 CSV
+pub fn render_summary_csv(summaries: &[ClientMonthlySummary], locale: Locale) -> Result<String, String> {
+    report_export::render_to_string(
+        &summary_columns(summaries, locale),
+        &summary_rows(summaries),
+        ExportFormat::Csv,
+        locale,
+    )
+}
+
+/// 动作显示名；消息包中没有的自定义动作保留原名
+fn action_label(locale: Locale, action: &str) -> String {
+    let key = format!("billing.action.{}", action);
+    let label = i18n::t_in(locale, &key);
+    if label == key { action.to_string() } else { label }
+}
+
+/// 客户 ID 作为目录/文件名时去掉路径分隔符等字符
+fn sanitize(client_id: &str) -> String {
+    client_id
+        .chars()
+        .map(|c| if c.is_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+        .collect()
+}
+
+fn append_entry(path: &Path, entry: &BillingEntry) -> Result<(), String> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| format!("创建计费目录失败: {}", e))?;
+    }
+    let is_new = !path.exists();
+    let file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .map_err(|e| format!("打开计费文件失败: {}", e))?;
+    let mut writer = csv::WriterBuilder::new().has_headers(is_new).from_writer(file);
+    writer.serialize(entry).map_err(|e| format!("写入计费记录失败: {}", e))?;
+    writer.flush().map_err(|e| format!("写入计费记录失败: {}", e))
+}
+
+fn read_entries(path: &Path) -> Result<Vec<BillingEntry>, String> {
+    let mut reader = csv::Reader::from_path(path).map_err(|e| format!("读取计费文件失败: {}", e))?;
+    let mut entries = Vec::new();
+    for row in reader.deserialize() {
+        match row {
+            Ok(entry) => entries.push(entry),
+            Err(e) => warn!("⚠️ 跳过损坏的计费记录 {:?}: {}", path, e),
+        }
+    }
+    Ok(entries)
+}
+
+/// 记录一次动作到全局计费日志（失败只告警，不影响业务动作）
+pub fn record_billable(action: BillableAction) {
+    let journal = match BillingJournal::global().lock() {
+        Ok(journal) => journal,
+        Err(e) => {
+            warn!("⚠️ 计费日志锁失败: {}", e);
+            return;
+        }
+    };
+    if let Err(e) = journal.record(&action) {
+        warn!("⚠️ 计费记录写入失败 ({}): {}", action.action, e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn journal(dir: &Path) -> BillingJournal {
+        let mut config = BillingJournalConfig::default();
+        config.enabled = true;
+        config.account_clients.insert("acc_1".into(), "client/a".into());
+        BillingJournal::with_output_dir(dir.to_path_buf(), config)
+    }
+
+    #[test]
+    fn records_billable_actions_per_client_and_summarizes_month() {
+        let dir = tempfile::tempdir().unwrap();
+        let journal = journal(dir.path());
+
+        let follow = BillableAction { action: "follow".into(), account_id: Some("acc_1".into()), ..Default::default() };
+        let entry = journal.record(&follow).unwrap().unwrap();
+        assert_eq!(entry.client_id, "client/a");
+        journal.record(&follow).unwrap();
+        journal
+            .record(&BillableAction { action: "import".into(), quantity: Some(120), ..Default::default() })
+            .unwrap();
+        assert!(journal.record(&BillableAction { action: "like".into(), ..Default::default() }).unwrap().is_none());
+
+        let month = chrono::Local::now().format("%Y-%m").to_string();
+        let summaries = journal.monthly_summary(&month, None).unwrap();
+        assert_eq!(summaries.len(), 2);
+        let client_a = summaries.iter().find(|s| s.client_id == "client_a").unwrap();
+        assert_eq!(client_a.totals["follow"], 2);
+        assert_eq!(client_a.active_days, 1);
+        let default = summaries.iter().find(|s| s.client_id == "default").unwrap();
+        assert_eq!(default.total, 120);
+    }
+
+    #[test]
+    fn disabled_journal_and_bad_month_are_handled() {
+        let dir = tempfile::tempdir().unwrap();
+        let journal = BillingJournal::with_output_dir(dir.path().to_path_buf(), BillingJournalConfig::default());
+        assert!(journal.record(&BillableAction { action: "follow".into(), ..Default::default() }).unwrap().is_none());
+        assert!(journal.monthly_summary("2024-13", None).is_err());
+        assert!(journal.monthly_summary("2024-05", Some("nobody")).unwrap().is_empty());
+    }
+
+    #[test]
+    fn renders_localized_summary_csv() {
+        let summary = ClientMonthlySummary {
+            client_id: "acme".into(),
+            month: "2024-05".into(),
+            totals: BTreeMap::from([("follow".to_string(), 3), ("custom".to_string(), 1)]),
+            total: 4,
+            active_days: 2,
+        };
+        let csv = render_summary_csv(&[summary], Locale::EnUs).unwrap();
+        let mut lines = csv.lines();
+        assert_eq!(lines.next(), Some("Client,Month,custom,Follows,Total,Active Days"));
+        assert_eq!(lines.next(), Some("acme,2024-05,1,3,4,2"));
+    }
+}
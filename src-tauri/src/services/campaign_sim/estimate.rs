@@ -0,0 +1,377 @@
+// src-tauri/src/services/campaign_sim/estimate.rs
+// module: campaign_sim | layer: services | role: 脚本执行成本估算
+// summary: 启动前按步骤耗时统计与各设备生效的频控预算，推演脚本在设备分组上的逐设备 ETA 与受限动作消耗，超出允许时间窗时给出警告
+
+use chrono::{Duration, NaiveDate, NaiveDateTime, Timelike};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+use super::{
+    at, in_window, next_window_start, offset_of, parse_time, validate_working_hours, StepDurationStats, FALLBACK_STEP_MS, MAX_SIMULATED_DAYS,
+};
+use crate::core::application::sandbox_service::SANDBOX;
+use crate::services::device_config_sync::{self, RateLimits};
+use crate::services::execution::model::{SmartActionType, SmartScriptStep};
+use crate::services::script_manager::SmartScript;
+
+/// 推演的动作总数上限（次数 × 展开后的步骤数）
+const MAX_SIMULATED_ACTIONS: u64 = 2_000_000;
+/// 循环未设置次数时执行器的默认值
+const DEFAULT_LOOP_COUNT: u64 = 3;
+/// 等待步骤未设置时长时执行器的默认值
+const DEFAULT_WAIT_MS: u64 = 500;
+
+/// 允许执行的时间窗与执行量
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ExecutionWindow {
+    /// 开始时间（RFC3339 或本地时间，默认现在）
+    pub start_at: Option<String>,
+    /// 必须完成的时间
+    pub deadline: Option<String>,
+    /// 工作时段 [开始小时, 结束小时)，可跨零点
+    pub working_hours: Option<(u32, u32)>,
+    /// 每台设备执行脚本的次数（默认 1）
+    pub runs_per_device: Option<u32>,
+}
+
+/// 单设备估算
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeviceEstimate {
+    pub device_id: String,
+    pub rate_limits: RateLimits,
+    pub runs: u32,
+    /// 消耗的受频控动作数
+    pub budgeted_actions: u64,
+    pub busy_hours: f64,
+    pub rate_limit_wait_hours: f64,
+    pub off_hours_wait_hours: f64,
+    pub eta: String,
+    pub fits_window: bool,
+}
+
+/// 估算结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExecutionEstimate {
+    pub script_id: String,
+    pub script_name: String,
+    pub device_group: String,
+    /// 单次执行展开循环后的步骤数
+    pub steps_per_run: u64,
+    /// 单次执行消耗的受频控动作数
+    pub actions_per_run: u64,
+    pub started_at: String,
+    /// 最后一台设备完成的时间
+    pub eta: String,
+    pub duration_hours: f64,
+    pub deadline: Option<String>,
+    pub fits_window: bool,
+    pub devices: Vec<DeviceEstimate>,
+    /// 每种步骤的耗时来源说明
+    pub step_sources: BTreeMap<String, String>,
+    pub warnings: Vec<String>,
+}
+
+/// 展开后的一步：执行次数（循环倍数）、平均耗时、是否计入频控预算
+struct StepCost {
+    weight: u64,
+    mean_ms: i64,
+    budgeted: bool,
+}
+
+/// 计入频控的交互动作（等待、识别、控制流等不计）
+fn consumes_budget(step_type: &SmartActionType) -> bool {
+    matches!(
+        step_type,
+        SmartActionType::Tap
+            | SmartActionType::Input
+            | SmartActionType::Swipe
+            | SmartActionType::KeyEvent
+            | SmartActionType::LongPress
+            | SmartActionType::SmartTap
+            | SmartActionType::SmartScroll
+            | SmartActionType::SmartNavigation
+            | SmartActionType::ContactImportToDevice
+            | SmartActionType::AiLaunchApp
+            | SmartActionType::AiTapRelative
+            | SmartActionType::AiCustomCommand
+    )
+}
+
+fn action_key(step_type: &SmartActionType) -> String {
+    serde_json::to_value(step_type).ok().and_then(|v| v.as_str().map(String::from)).unwrap_or_else(|| "unknown".to_string())
+}
+
+/// 按 order 展开启用的步骤，循环体按次数加权；无限循环按一次计并给出警告
+fn step_costs(
+    steps: &[SmartScriptStep],
+    stats: &StepDurationStats,
+    sources: &mut BTreeMap<String, String>,
+    warnings: &mut Vec<String>,
+) -> Vec<StepCost> {
+    let mut ordered: Vec<&SmartScriptStep> = steps.iter().filter(|s| s.enabled).collect();
+    ordered.sort_by_key(|s| s.order);
+    let mut multipliers: Vec<u64> = Vec::new();
+    let mut costs = Vec::new();
+    for step in ordered {
+        match step.step_type {
+            SmartActionType::LoopStart => {
+                let infinite = step.parameters.get("is_infinite_loop").and_then(|v| v.as_bool()).unwrap_or(false);
+                if infinite {
+                    warnings.push(format!("步骤「{}」是无限循环，估算按执行一次计算", step.name));
+                }
+                let count = step.parameters.get("loop_count").and_then(|v| v.as_u64()).unwrap_or(DEFAULT_LOOP_COUNT);
+                multipliers.push(if infinite { 1 } else { count.max(1) });
+                continue;
+            }
+            SmartActionType::LoopEnd => {
+                multipliers.pop();
+                continue;
+            }
+            _ => {}
+        }
+        let key = action_key(&step.step_type);
+        let (mean_ms, source) = if matches!(step.step_type, SmartActionType::Wait) {
+            let ms = step.parameters.get("duration_ms").or_else(|| step.parameters.get("wait_ms")).and_then(|v| v.as_u64());
+            (ms.unwrap_or(DEFAULT_WAIT_MS) as f64, "wait parameter".to_string())
+        } else {
+            match stats.get(&key) {
+                Some(stat) => (stat.mean_ms(), format!("recorded (n={})", stat.samples)),
+                None => (FALLBACK_STEP_MS as f64, "fallback".to_string()),
+            }
+        };
+        sources.insert(key, source);
+        costs.push(StepCost {
+            weight: multipliers.iter().product::<u64>().max(1),
+            mean_ms: mean_ms.round() as i64,
+            budgeted: consumes_budget(&step.step_type),
+        });
+    }
+    costs
+}
+
+struct DeviceRun {
+    clock_ms: i64,
+    busy_ms: i64,
+    limit_wait_ms: i64,
+    off_hours_wait_ms: i64,
+    actions: u64,
+}
+
+/// 单设备推演：按平均耗时顺序执行，受频控动作前检查最短间隔、每小时与每日上限，工作时段外顺延
+fn simulate_device(
+    costs: &[StepCost],
+    runs: u32,
+    limits: &RateLimits,
+    start: NaiveDateTime,
+    working_hours: Option<(u32, u32)>,
+) -> Result<DeviceRun, String> {
+    let horizon_ms = Duration::days(MAX_SIMULATED_DAYS).num_milliseconds();
+    let min_interval_ms = limits.min_interval_secs.map_or(0, |s| s as i64 * 1_000);
+    let mut hourly: HashMap<(NaiveDate, u32), u32> = HashMap::new();
+    let mut daily: HashMap<NaiveDate, u32> = HashMap::new();
+    let mut last_action_ms: Option<i64> = None;
+    let mut run = DeviceRun { clock_ms: 0, busy_ms: 0, limit_wait_ms: 0, off_hours_wait_ms: 0, actions: 0 };
+
+    for _ in 0..runs {
+        for cost in costs {
+            for _ in 0..cost.weight {
+                loop {
+                    if run.clock_ms > horizon_ms {
+                        return Err(format!("推演超过 {} 天仍未完成，请检查频控与工作时段配置", MAX_SIMULATED_DAYS));
+                    }
+                    let now = at(start, run.clock_ms);
+                    if let Some(window) = working_hours.filter(|w| !in_window(now.hour(), *w)) {
+                        let resume = offset_of(start, next_window_start(now, window));
+                        run.off_hours_wait_ms += resume - run.clock_ms;
+                        run.clock_ms = resume;
+                        continue;
+                    }
+                    if !cost.budgeted {
+                        break;
+                    }
+                    let date = now.date();
+                    let resume = if let Some(ready) = last_action_ms.map(|t| t + min_interval_ms).filter(|t| *t > run.clock_ms) {
+                        ready
+                    } else if limits.max_actions_per_day.is_some_and(|cap| daily.get(&date).copied().unwrap_or(0) >= cap) {
+                        offset_of(start, date.succ_opt().and_then(|d| d.and_hms_opt(0, 0, 0)).ok_or("日期计算溢出")?)
+                    } else if limits.max_actions_per_hour.is_some_and(|cap| hourly.get(&(date, now.hour())).copied().unwrap_or(0) >= cap) {
+                        offset_of(start, date.and_hms_opt(now.hour(), 0, 0).map(|h| h + Duration::hours(1)).ok_or("日期计算溢出")?)
+                    } else {
+                        break;
+                    };
+                    run.limit_wait_ms += resume - run.clock_ms;
+                    run.clock_ms = resume;
+                }
+                if cost.budgeted {
+                    let now = at(start, run.clock_ms);
+                    *hourly.entry((now.date(), now.hour())).or_default() += 1;
+                    *daily.entry(now.date()).or_default() += 1;
+                    last_action_ms = Some(run.clock_ms);
+                    run.actions += 1;
+                }
+                run.clock_ms += cost.mean_ms;
+                run.busy_ms += cost.mean_ms;
+            }
+        }
+    }
+    Ok(run)
+}
+
+/// 用给定的统计与设备频控估算（不读取全局状态）
+pub fn estimate_with(
+    script: &SmartScript,
+    device_group: &str,
+    devices: &[(String, RateLimits)],
+    window: &ExecutionWindow,
+    stats: &StepDurationStats,
+) -> Result<ExecutionEstimate, String> {
+    if devices.is_empty() {
+        return Err(format!("设备分组 {} 中没有设备", device_group));
+    }
+    validate_working_hours(window.working_hours)?;
+    let runs = window.runs_per_device.unwrap_or(1);
+    if runs == 0 {
+        return Err("每台设备的执行次数必须大于 0".to_string());
+    }
+    let start = parse_time(window.start_at.as_deref(), "开始时间")?;
+    let deadline = match window.deadline.as_deref().map(str::trim).filter(|s| !s.is_empty()) {
+        Some(raw) => Some(parse_time(Some(raw), "截止时间")?),
+        None => None,
+    };
+    if deadline.is_some_and(|d| d <= start) {
+        return Err("截止时间必须晚于开始时间".to_string());
+    }
+
+    let mut step_sources = BTreeMap::new();
+    let mut warnings = Vec::new();
+    let costs = step_costs(&script.steps, stats, &mut step_sources, &mut warnings);
+    if costs.is_empty() {
+        return Err("脚本没有可执行的步骤".to_string());
+    }
+    let steps_per_run: u64 = costs.iter().map(|c| c.weight).sum();
+    let actions_per_run: u64 = costs.iter().filter(|c| c.budgeted).map(|c| c.weight).sum();
+    if steps_per_run.saturating_mul(runs as u64) > MAX_SIMULATED_ACTIONS {
+        return Err(format!("估算规模过大（每台设备 {} 步），请减少执行次数或循环次数", steps_per_run.saturating_mul(runs as u64)));
+    }
+
+    let fmt = |time: NaiveDateTime| time.format("%Y-%m-%dT%H:%M:%S").to_string();
+    let hours = |ms: i64| ms as f64 / 3_600_000.0;
+    let mut estimates = Vec::with_capacity(devices.len());
+    let mut makespan_ms = 0;
+    for (device_id, limits) in devices {
+        let run = simulate_device(&costs, runs, limits, start, window.working_hours)?;
+        let eta = at(start, run.clock_ms);
+        let fits_window = deadline.map_or(true, |d| eta <= d);
+        if !fits_window {
+            warnings.push(format!("设备 {} 预计 {} 完成，超出截止时间", device_id, fmt(eta)));
+        }
+        if limits.max_actions_per_day.is_some_and(|cap| (cap as u64) < actions_per_run) {
+            warnings.push(format!("设备 {} 每日动作上限不足以在一天内完成一次脚本", device_id));
+        }
+        makespan_ms = makespan_ms.max(run.clock_ms);
+        estimates.push(DeviceEstimate {
+            device_id: device_id.clone(),
+            rate_limits: limits.clone(),
+            runs,
+            budgeted_actions: run.actions,
+            busy_hours: hours(run.busy_ms),
+            rate_limit_wait_hours: hours(run.limit_wait_ms),
+            off_hours_wait_hours: hours(run.off_hours_wait_ms),
+            eta: fmt(eta),
+            fits_window,
+        });
+    }
+
+    Ok(ExecutionEstimate {
+        script_id: script.id.clone(),
+        script_name: script.name.clone(),
+        device_group: device_group.to_string(),
+        steps_per_run,
+        actions_per_run,
+        started_at: fmt(start),
+        eta: fmt(at(start, makespan_ms)),
+        duration_hours: hours(makespan_ms),
+        deadline: deadline.map(fmt),
+        fits_window: estimates.iter().all(|d| d.fits_window),
+        devices: estimates,
+        step_sources,
+        warnings,
+    })
+}
+
+/// 用全局步骤耗时统计、沙箱设备分组与各设备生效的频控估算
+pub fn estimate_execution(script: &SmartScript, device_group: &str, window: &ExecutionWindow) -> Result<ExecutionEstimate, String> {
+    let devices: Vec<(String, RateLimits)> = SANDBOX
+        .devices_in_group(device_group)
+        .into_iter()
+        .map(|device_id| {
+            let limits = device_config_sync::effective_settings(&device_id).rate_limits;
+            (device_id, limits)
+        })
+        .collect();
+    let stats = StepDurationStats::global().lock().map_err(|e| e.to_string())?;
+    estimate_with(script, device_group, &devices, window, &stats)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::{json, Value};
+
+    fn step(order: i32, step_type: SmartActionType, parameters: Value) -> SmartScriptStep {
+        SmartScriptStep {
+            id: format!("s{}", order),
+            step_type,
+            name: format!("step {}", order),
+            description: String::new(),
+            parameters,
+            enabled: true,
+            order,
+        }
+    }
+
+    #[test]
+    fn test_estimate_respects_budgets_and_deadline() {
+        let mut stats = StepDurationStats::in_memory();
+        stats.record("tap", 1_000, true);
+        let script = SmartScript {
+            id: "follow".into(),
+            name: "关注".into(),
+            steps: vec![
+                step(1, SmartActionType::LoopStart, json!({ "loop_count": 4 })),
+                step(2, SmartActionType::Tap, json!({})),
+                step(3, SmartActionType::Wait, json!({ "duration_ms": 2_000 })),
+                step(4, SmartActionType::LoopEnd, json!({})),
+                step(5, SmartActionType::Tap, json!({})),
+            ],
+            ..Default::default()
+        };
+        let unlimited = ("d1".to_string(), RateLimits::default());
+        let capped = ("d2".to_string(), RateLimits { max_actions_per_hour: Some(3), ..Default::default() });
+        let window = ExecutionWindow {
+            start_at: Some("2025-01-01T10:00:00".into()),
+            deadline: Some("2025-01-01T10:30:00".into()),
+            ..Default::default()
+        };
+
+        let estimate = estimate_with(&script, "client_a", &[unlimited, capped], &window, &stats).unwrap();
+        assert_eq!((estimate.steps_per_run, estimate.actions_per_run), (9, 5));
+        assert_eq!(estimate.step_sources["tap"], "recorded (n=1)");
+        // 不限速：5 次点击 × 1s + 4 次等待 × 2s = 13s
+        assert_eq!(estimate.devices[0].eta, "2025-01-01T10:00:13");
+        assert!(estimate.devices[0].fits_window);
+        // 每小时 3 次：第 4 次点击等到 11:00
+        assert!(estimate.devices[1].eta.starts_with("2025-01-01T11:00"));
+        assert!(!estimate.fits_window);
+        assert_eq!(estimate.warnings.len(), 1);
+
+        let interval = ("d3".to_string(), RateLimits { min_interval_secs: Some(10), ..Default::default() });
+        let estimate = estimate_with(&script, "client_a", &[interval], &ExecutionWindow { deadline: None, ..window.clone() }, &stats).unwrap();
+        // 相邻点击至少间隔 10s：第 5 次点击在 40s 开始
+        assert_eq!(estimate.devices[0].eta, "2025-01-01T10:00:41");
+        assert!(estimate_with(&script, "empty", &[], &window, &stats).is_err());
+    }
+}
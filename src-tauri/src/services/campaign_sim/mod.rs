@@ -0,0 +1,369 @@
+// src-tauri/src/services/campaign_sim/mod.rs
+// module: campaign_sim | layer: services | role: 活动模拟器
+// summary: 不连接设备，按记录的步骤耗时统计与频控配置离散推演活动计划，给出 ETA、设备利用率预测与每日动作量
+
+pub mod estimate;
+pub mod stats;
+
+use chrono::{Duration, NaiveDate, NaiveDateTime, Timelike};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+pub use estimate::{estimate_execution, ExecutionEstimate, ExecutionWindow};
+pub use stats::{record_step_duration, DurationStat, StepDurationStats};
+
+/// 无统计也无计划默认值时的步骤耗时
+const FALLBACK_STEP_MS: u64 = 3_000;
+/// 模拟时长上限（防止频控配置过严时推演不结束）
+const MAX_SIMULATED_DAYS: i64 = 3_650;
+const MAX_TARGETS: u32 = 1_000_000;
+
+/// 计划中每个目标要执行的一步
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlanStep {
+    /// 动作名（与耗时统计的键一致，如 tap / follow / reply）
+    pub action: String,
+    /// 没有记录统计时使用的耗时
+    #[serde(default)]
+    pub default_duration_ms: Option<u64>,
+    /// 覆盖统计得到的成功率
+    #[serde(default)]
+    pub success_rate: Option<f64>,
+}
+
+/// 单设备频控
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RateLimit {
+    pub action: String,
+    #[serde(default)]
+    pub per_hour: Option<u32>,
+    #[serde(default)]
+    pub per_day: Option<u32>,
+}
+
+/// 活动计划
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CampaignPlan {
+    pub name: String,
+    pub target_count: u32,
+    pub devices: Vec<String>,
+    pub steps: Vec<PlanStep>,
+    #[serde(default)]
+    pub rate_limits: Vec<RateLimit>,
+    /// 同一设备相邻两个目标之间的间隔
+    #[serde(default)]
+    pub target_interval_ms: u64,
+    /// 工作时段 [开始小时, 结束小时)，可跨零点
+    #[serde(default)]
+    pub working_hours: Option<(u32, u32)>,
+    /// 开始时间（RFC3339 或本地时间，默认现在）
+    #[serde(default)]
+    pub start_at: Option<String>,
+    /// 随机种子（相同种子结果可复现）
+    #[serde(default)]
+    pub seed: Option<u64>,
+}
+
+impl CampaignPlan {
+    fn validate(&self) -> Result<(), String> {
+        if self.devices.is_empty() {
+            return Err("活动计划至少需要一台设备".into());
+        }
+        if self.steps.is_empty() {
+            return Err("活动计划至少需要一个步骤".into());
+        }
+        if self.target_count > MAX_TARGETS {
+            return Err(format!("目标数超过上限 {}", MAX_TARGETS));
+        }
+        if let Some(limit) = self.rate_limits.iter().find(|l| l.per_hour == Some(0) || l.per_day == Some(0)) {
+            return Err(format!("动作 {} 的频控上限不能为 0", limit.action));
+        }
+        validate_working_hours(self.working_hours)
+    }
+
+    fn start_time(&self) -> Result<NaiveDateTime, String> {
+        parse_time(self.start_at.as_deref(), "开始时间")
+    }
+}
+
+/// 解析 RFC3339 或本地时间，为空时取现在；`label` 用于错误提示
+fn parse_time(raw: Option<&str>, label: &str) -> Result<NaiveDateTime, String> {
+    match raw.map(str::trim).filter(|s| !s.is_empty()) {
+        None => Ok(chrono::Local::now().naive_local()),
+        Some(raw) => chrono::DateTime::parse_from_rfc3339(raw)
+            .map(|dt| dt.naive_local())
+            .or_else(|_| NaiveDateTime::parse_from_str(raw, "%Y-%m-%dT%H:%M:%S"))
+            .map_err(|e| format!("{}无效 {}: {}", label, raw, e)),
+    }
+}
+
+fn validate_working_hours(working_hours: Option<(u32, u32)>) -> Result<(), String> {
+    match working_hours {
+        Some((start, end)) if start >= 24 || end >= 24 || start == end => Err(format!("工作时段无效: {}-{}", start, end)),
+        _ => Ok(()),
+    }
+}
+
+/// 单设备预测
+#[derive(Debug, Clone, Serialize)]
+pub struct DeviceForecast {
+    pub device_id: String,
+    pub targets: u32,
+    pub busy_hours: f64,
+    /// 忙碌时间 / 活动总时长
+    pub utilization: f64,
+}
+
+/// 某一天的动作量
+#[derive(Debug, Clone, Serialize)]
+pub struct DailyActions {
+    pub date: String,
+    pub actions: BTreeMap<String, u32>,
+}
+
+/// 模拟结果
+#[derive(Debug, Clone, Serialize)]
+pub struct SimulationReport {
+    pub plan_name: String,
+    pub targets: u32,
+    pub completed: u32,
+    pub failed: u32,
+    pub started_at: String,
+    pub eta: String,
+    pub duration_hours: f64,
+    /// 因频控等待的设备时长（小时，各设备累加）
+    pub rate_limit_wait_hours: f64,
+    /// 因工作时段等待的设备时长
+    pub off_hours_wait_hours: f64,
+    /// 频控等待最多的动作
+    pub bottleneck_action: Option<String>,
+    pub devices: Vec<DeviceForecast>,
+    pub daily_actions: Vec<DailyActions>,
+    /// 每个动作的耗时来源说明
+    pub step_sources: BTreeMap<String, String>,
+}
+
+/// 一个步骤的采样参数
+struct StepModel {
+    action: String,
+    mean_ms: f64,
+    stddev_ms: f64,
+    success_rate: f64,
+}
+
+impl StepModel {
+    fn build(step: &PlanStep, stats: &StepDurationStats) -> (Self, String) {
+        let (mean_ms, stddev_ms, recorded_rate, source) = match stats.get(&step.action) {
+            Some(stat) => (stat.mean_ms(), stat.stddev_ms(), stat.success_rate(), format!("recorded (n={})", stat.samples)),
+            None => match step.default_duration_ms {
+                Some(ms) => (ms as f64, 0.0, 1.0, "plan default".to_string()),
+                None => (FALLBACK_STEP_MS as f64, 0.0, 1.0, "fallback".to_string()),
+            },
+        };
+        let success_rate = step.success_rate.unwrap_or(recorded_rate).clamp(0.0, 1.0);
+        (Self { action: step.action.clone(), mean_ms, stddev_ms, success_rate }, source)
+    }
+
+    /// 近似正态采样（三个均匀分布之和），下限为均值的 20%
+    fn sample_ms(&self, rng: &mut StdRng) -> i64 {
+        let z: f64 = (0..3).map(|_| rng.gen_range(-1.0f64..1.0)).sum();
+        (self.mean_ms + self.stddev_ms * z).max(self.mean_ms * 0.2).round() as i64
+    }
+}
+
+struct DeviceState {
+    clock_ms: i64,
+    busy_ms: i64,
+    targets: u32,
+    /// (动作, 日期, 小时) → 次数
+    hourly: HashMap<(String, NaiveDate, u32), u32>,
+    daily: HashMap<(String, NaiveDate), u32>,
+}
+
+fn at(start: NaiveDateTime, clock_ms: i64) -> NaiveDateTime {
+    start + Duration::milliseconds(clock_ms)
+}
+
+fn offset_of(start: NaiveDateTime, time: NaiveDateTime) -> i64 {
+    (time - start).num_milliseconds()
+}
+
+fn in_window(hour: u32, (start, end): (u32, u32)) -> bool {
+    if start < end { (start..end).contains(&hour) } else { hour >= start || hour < end }
+}
+
+fn next_window_start(now: NaiveDateTime, (start, _): (u32, u32)) -> NaiveDateTime {
+    let today = now.date().and_hms_opt(start, 0, 0).unwrap_or(now);
+    if today > now { today } else { today + Duration::days(1) }
+}
+
+/// 用全局耗时统计模拟
+pub fn simulate_campaign(plan: &CampaignPlan) -> Result<SimulationReport, String> {
+    let stats = StepDurationStats::global().lock().map_err(|e| e.to_string())?;
+    simulate_with_stats(plan, &stats)
+}
+
+/// 离散推演：每个目标分配给最早空闲的设备，逐步检查工作时段与频控，按统计采样耗时与成败
+pub fn simulate_with_stats(plan: &CampaignPlan, stats: &StepDurationStats) -> Result<SimulationReport, String> {
+    plan.validate()?;
+    let start = plan.start_time()?;
+    let mut rng = StdRng::seed_from_u64(plan.seed.unwrap_or(42));
+
+    let mut step_sources = BTreeMap::new();
+    let models: Vec<StepModel> = plan
+        .steps
+        .iter()
+        .map(|step| {
+            let (model, source) = StepModel::build(step, stats);
+            step_sources.insert(step.action.clone(), source);
+            model
+        })
+        .collect();
+    let limits: HashMap<&str, &RateLimit> = plan.rate_limits.iter().map(|l| (l.action.as_str(), l)).collect();
+
+    let mut devices: Vec<DeviceState> = plan
+        .devices
+        .iter()
+        .map(|_| DeviceState { clock_ms: 0, busy_ms: 0, targets: 0, hourly: HashMap::new(), daily: HashMap::new() })
+        .collect();
+    let mut daily_actions: BTreeMap<NaiveDate, BTreeMap<String, u32>> = BTreeMap::new();
+    let mut limit_wait_ms: BTreeMap<String, i64> = BTreeMap::new();
+    let mut off_hours_wait_ms = 0i64;
+    let (mut completed, mut failed) = (0u32, 0u32);
+    let horizon_ms = Duration::days(MAX_SIMULATED_DAYS).num_milliseconds();
+
+    for _ in 0..plan.target_count {
+        let device = devices.iter_mut().min_by_key(|d| d.clock_ms).expect("devices validated non-empty");
+        device.targets += 1;
+        let mut target_ok = true;
+
+        for model in &models {
+            // 推进到允许执行的时刻
+            loop {
+                if device.clock_ms > horizon_ms {
+                    return Err(format!("模拟超过 {} 天仍未完成，请检查频控与工作时段配置", MAX_SIMULATED_DAYS));
+                }
+                let now = at(start, device.clock_ms);
+                if let Some(window) = plan.working_hours.filter(|w| !in_window(now.hour(), *w)) {
+                    let resume = offset_of(start, next_window_start(now, window));
+                    off_hours_wait_ms += resume - device.clock_ms;
+                    device.clock_ms = resume;
+                    continue;
+                }
+                let Some(limit) = limits.get(model.action.as_str()) else { break };
+                let date = now.date();
+                let day_count = device.daily.get(&(model.action.clone(), date)).copied().unwrap_or(0);
+                let hour_count = device.hourly.get(&(model.action.clone(), date, now.hour())).copied().unwrap_or(0);
+                let resume = if limit.per_day.is_some_and(|cap| day_count >= cap) {
+                    date.succ_opt().and_then(|d| d.and_hms_opt(0, 0, 0))
+                } else if limit.per_hour.is_some_and(|cap| hour_count >= cap) {
+                    date.and_hms_opt(now.hour(), 0, 0).map(|h| h + Duration::hours(1))
+                } else {
+                    break;
+                };
+                let resume = offset_of(start, resume.ok_or("日期计算溢出")?);
+                *limit_wait_ms.entry(model.action.clone()).or_default() += resume - device.clock_ms;
+                device.clock_ms = resume;
+            }
+
+            let now = at(start, device.clock_ms);
+            *device.hourly.entry((model.action.clone(), now.date(), now.hour())).or_default() += 1;
+            *device.daily.entry((model.action.clone(), now.date())).or_default() += 1;
+            *daily_actions.entry(now.date()).or_default().entry(model.action.clone()).or_default() += 1;
+
+            let duration = model.sample_ms(&mut rng);
+            device.clock_ms += duration;
+            device.busy_ms += duration;
+            if !rng.gen_bool(model.success_rate) {
+                target_ok = false;
+                break;
+            }
+        }
+
+        if target_ok { completed += 1 } else { failed += 1 }
+        device.clock_ms += plan.target_interval_ms as i64;
+    }
+
+    let makespan_ms = devices.iter().map(|d| d.clock_ms).max().unwrap_or(0);
+    let hours = |ms: i64| ms as f64 / 3_600_000.0;
+    Ok(SimulationReport {
+        plan_name: plan.name.clone(),
+        targets: plan.target_count,
+        completed,
+        failed,
+        started_at: start.format("%Y-%m-%dT%H:%M:%S").to_string(),
+        eta: at(start, makespan_ms).format("%Y-%m-%dT%H:%M:%S").to_string(),
+        duration_hours: hours(makespan_ms),
+        rate_limit_wait_hours: hours(limit_wait_ms.values().sum()),
+        off_hours_wait_hours: hours(off_hours_wait_ms),
+        bottleneck_action: limit_wait_ms.iter().filter(|(_, ms)| **ms > 0).max_by_key(|(_, ms)| **ms).map(|(a, _)| a.clone()),
+        devices: plan
+            .devices
+            .iter()
+            .zip(&devices)
+            .map(|(id, d)| DeviceForecast {
+                device_id: id.clone(),
+                targets: d.targets,
+                busy_hours: hours(d.busy_ms),
+                utilization: if makespan_ms > 0 { d.busy_ms as f64 / makespan_ms as f64 } else { 0.0 },
+            })
+            .collect(),
+        daily_actions: daily_actions
+            .into_iter()
+            .map(|(date, actions)| DailyActions { date: date.format("%Y-%m-%d").to_string(), actions })
+            .collect(),
+        step_sources,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn plan() -> CampaignPlan {
+        CampaignPlan {
+            name: "follow-campaign".into(),
+            target_count: 10,
+            devices: vec!["d1".into(), "d2".into()],
+            steps: vec![
+                PlanStep { action: "follow".into(), default_duration_ms: None, success_rate: None },
+                PlanStep { action: "open_profile".into(), default_duration_ms: Some(500), success_rate: None },
+            ],
+            rate_limits: vec![RateLimit { action: "follow".into(), per_hour: Some(3), per_day: None }],
+            target_interval_ms: 0,
+            working_hours: None,
+            start_at: Some("2025-01-01T10:00:00".into()),
+            seed: Some(7),
+        }
+    }
+
+    #[test]
+    fn respects_hourly_caps_and_reports_daily_counts() {
+        let mut stats = StepDurationStats::in_memory();
+        for _ in 0..5 {
+            stats.record("follow", 1_000, true);
+        }
+
+        let report = simulate_with_stats(&plan(), &stats).unwrap();
+        assert_eq!((report.completed, report.failed), (10, 0));
+        // 每台设备 5 个目标，每小时最多 3 次关注 → 第 4 个目标等到 11:00
+        assert!(report.eta.starts_with("2025-01-01T11:00"));
+        assert_eq!(report.bottleneck_action.as_deref(), Some("follow"));
+        assert_eq!(report.daily_actions.len(), 1);
+        assert_eq!(report.daily_actions[0].actions["follow"], 10);
+        assert_eq!(report.step_sources["follow"], "recorded (n=5)");
+        assert_eq!(report.step_sources["open_profile"], "plan default");
+        assert!(report.devices.iter().all(|d| d.targets == 5 && d.utilization < 0.01));
+
+        // 工作时段外开始 → 推迟到次日 9 点
+        let night = CampaignPlan { working_hours: Some((9, 18)), start_at: Some("2025-01-01T20:00:00".into()), ..plan() };
+        let report = simulate_with_stats(&night, &stats).unwrap();
+        assert!(report.eta.starts_with("2025-01-02T10:00"));
+        assert!(report.off_hours_wait_hours > 12.0);
+
+        let zero_cap = CampaignPlan { rate_limits: vec![RateLimit { action: "follow".into(), per_hour: Some(0), per_day: None }], ..plan() };
+        assert!(simulate_with_stats(&zero_cap, &stats).is_err());
+    }
+}
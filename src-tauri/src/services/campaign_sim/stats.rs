@@ -0,0 +1,126 @@
+// src-tauri/src/services/campaign_sim/stats.rs
+// module: campaign_sim | layer: services | role: 步骤耗时统计
+// summary: 按动作累计真实执行的耗时与成功率（均值 / 标准差 / 极值），落盘供活动模拟器采样
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::{Mutex, OnceLock};
+use tracing::warn;
+
+const STATS_FILE_NAME: &str = "step_duration_stats.json";
+/// 每累计多少条样本落盘一次
+const FLUSH_EVERY: u64 = 20;
+
+static STATS: OnceLock<Mutex<StepDurationStats>> = OnceLock::new();
+
+/// 单个动作的耗时统计
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DurationStat {
+    pub samples: u64,
+    pub successes: u64,
+    pub total_ms: f64,
+    pub total_sq_ms: f64,
+    pub min_ms: u64,
+    pub max_ms: u64,
+}
+
+impl DurationStat {
+    pub fn record(&mut self, duration_ms: u64, success: bool) {
+        if self.samples == 0 || duration_ms < self.min_ms {
+            self.min_ms = duration_ms;
+        }
+        self.max_ms = self.max_ms.max(duration_ms);
+        self.samples += 1;
+        self.successes += success as u64;
+        self.total_ms += duration_ms as f64;
+        self.total_sq_ms += (duration_ms as f64).powi(2);
+    }
+
+    pub fn mean_ms(&self) -> f64 {
+        if self.samples == 0 { 0.0 } else { self.total_ms / self.samples as f64 }
+    }
+
+    pub fn stddev_ms(&self) -> f64 {
+        if self.samples < 2 {
+            return 0.0;
+        }
+        let mean = self.mean_ms();
+        (self.total_sq_ms / self.samples as f64 - mean * mean).max(0.0).sqrt()
+    }
+
+    pub fn success_rate(&self) -> f64 {
+        if self.samples == 0 { 1.0 } else { self.successes as f64 / self.samples as f64 }
+    }
+}
+
+/// 动作 → 耗时统计
+#[derive(Debug, Default)]
+pub struct StepDurationStats {
+    actions: BTreeMap<String, DurationStat>,
+    data_dir: Option<PathBuf>,
+    unflushed: u64,
+}
+
+impl StepDurationStats {
+    pub fn global() -> &'static Mutex<StepDurationStats> {
+        STATS.get_or_init(|| {
+            let base = dirs::data_dir().unwrap_or_else(|| PathBuf::from(".")).join("employee-gui");
+            Mutex::new(Self::load(base))
+        })
+    }
+
+    pub fn load(data_dir: PathBuf) -> Self {
+        let actions = std::fs::read_to_string(data_dir.join(STATS_FILE_NAME))
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+        Self { actions, data_dir: Some(data_dir), unflushed: 0 }
+    }
+
+    /// 不落盘的实例
+    pub fn in_memory() -> Self {
+        Self::default()
+    }
+
+    pub fn record(&mut self, action: &str, duration_ms: u64, success: bool) {
+        self.actions.entry(action.to_string()).or_default().record(duration_ms, success);
+        self.unflushed += 1;
+        if self.unflushed >= FLUSH_EVERY {
+            self.flush();
+        }
+    }
+
+    pub fn get(&self, action: &str) -> Option<&DurationStat> {
+        self.actions.get(action).filter(|s| s.samples > 0)
+    }
+
+    pub fn snapshot(&self) -> BTreeMap<String, DurationStat> {
+        self.actions.clone()
+    }
+
+    pub fn clear(&mut self) {
+        self.actions.clear();
+        self.unflushed = 0;
+        self.flush();
+    }
+
+    pub fn flush(&mut self) {
+        self.unflushed = 0;
+        let Some(dir) = &self.data_dir else { return };
+        let result = std::fs::create_dir_all(dir)
+            .map_err(|e| e.to_string())
+            .and_then(|_| serde_json::to_string_pretty(&self.actions).map_err(|e| e.to_string()))
+            .and_then(|content| std::fs::write(dir.join(STATS_FILE_NAME), content).map_err(|e| e.to_string()));
+        if let Err(e) = result {
+            warn!("⚠️ 步骤耗时统计保存失败: {}", e);
+        }
+    }
+}
+
+/// 记录一次真实执行的步骤耗时（执行链路调用）
+pub fn record_step_duration(action: &str, duration_ms: u64, success: bool) {
+    if let Ok(mut stats) = StepDurationStats::global().lock() {
+        stats.record(action, duration_ms, success);
+    }
+}
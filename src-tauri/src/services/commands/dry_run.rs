@@ -0,0 +1,398 @@
+// src-tauri/src/services/commands/dry_run.rs
+// module: services | layer: application | role: 智能脚本试运行
+// summary: 抓取真机界面后逐步回放选择器，给出候选元素、置信度与安全闸门判定，不注入任何点击 / 滑动 / 输入
+
+use regex::Regex;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::time::Instant;
+use tracing::info;
+
+use crate::commands::run_step_v2::matching_config::{self, FieldWeights, MatchingConfig};
+use crate::commands::run_step_v2::{Bounds, MatchCandidate};
+use crate::engine::gating::SafetyGatekeeper;
+use crate::engine::ui_tree::{Attr, NodeRef, UiTree};
+use crate::services::adb::AdbService;
+use crate::services::execution::matching::matching_strategies::extract_matching_context;
+use crate::services::execution::model::{
+    DryRunCandidate, DryRunGateVerdict, DryRunStepReport, DryRunStepStatus, SmartExecutionResult, SmartScriptStep,
+};
+
+/// 每步报告中保留的候选数
+const MAX_CANDIDATES: usize = 5;
+/// 允许命中多个元素的选择模式（smartSelection.mode）
+const MULTI_SELECTION_MODES: &[&str] = &["first", "last", "random", "all"];
+
+enum FieldMode {
+    /// 运行时的默认比较：双向包含
+    Loose,
+    Equals,
+    Contains,
+    Regex(Regex),
+    /// XPath 只比较末级类名是否出现在路径中（与 V2 运行时一致）
+    XPathClass,
+}
+
+struct FieldTarget {
+    name: &'static str,
+    attr: Attr,
+    value: String,
+    mode: FieldMode,
+    weights: FieldWeights,
+}
+
+impl FieldTarget {
+    fn matches(&self, actual: &str) -> bool {
+        let target = self.value.as_str();
+        match &self.mode {
+            FieldMode::Loose => actual.contains(target) || target.contains(actual),
+            FieldMode::Equals => actual == target,
+            FieldMode::Contains => actual.contains(target),
+            FieldMode::Regex(re) => re.is_match(actual),
+            FieldMode::XPathClass => target.contains(actual),
+        }
+    }
+}
+
+/// includes / excludes 过滤条件
+struct Filter {
+    attr: Attr,
+    word: String,
+    regex: Option<Regex>,
+    negate: bool,
+}
+
+impl Filter {
+    fn accepts(&self, node: &NodeRef<'_>) -> bool {
+        let actual = node.attr(self.attr).unwrap_or_default();
+        let hit = match &self.regex {
+            Some(re) => re.is_match(actual),
+            None => actual.contains(self.word.as_str()),
+        };
+        hit != self.negate
+    }
+}
+
+struct StepSelector {
+    targets: Vec<FieldTarget>,
+    filters: Vec<Filter>,
+    multi: bool,
+    min_confidence: f64,
+    forbid_containers: bool,
+}
+
+fn field_spec<'a>(field: &str, config: &'a MatchingConfig) -> Option<(&'static str, Attr, &'a FieldWeights)> {
+    let weights = &config.weights;
+    Some(match field {
+        "resource-id" | "resource_id" | "resourceId" => ("resource_id", Attr::ResourceId, &weights.resource_id),
+        "text" => ("text", Attr::Text, &weights.text),
+        "content-desc" | "content_desc" | "contentDesc" => ("content_desc", Attr::ContentDesc, &weights.content_desc),
+        "class" | "class_name" | "className" => ("class_name", Attr::Class, &weights.class_name),
+        "xpath" => ("xpath", Attr::Class, &weights.xpath),
+        _ => return None,
+    })
+}
+
+fn compile(pattern: &str) -> Result<Regex, String> {
+    Regex::new(pattern).map_err(|e| format!("正则 {} 无效: {}", pattern, e))
+}
+
+/// 从步骤参数构建选择器：优先 `matching`，否则读取扁平的 resource_id / text / content_desc / class_name / xpath
+fn build_selector(params: &Value, config: &MatchingConfig) -> Result<StepSelector, String> {
+    let mut selector = StepSelector {
+        targets: Vec::new(),
+        filters: Vec::new(),
+        multi: false,
+        min_confidence: params
+            .get("min_confidence")
+            .and_then(Value::as_f64)
+            .unwrap_or(config.safety.min_confidence as f64),
+        forbid_containers: params.get("forbid_fullscreen_or_container").and_then(Value::as_bool).unwrap_or(true),
+    };
+    let mut push = |field: &str, value: &str, mode: Option<&str>| -> Result<(), String> {
+        let Some((name, attr, weights)) = field_spec(field, config) else { return Ok(()) };
+        let mode = match (name, mode) {
+            ("xpath", _) => FieldMode::XPathClass,
+            (_, Some("equals")) => FieldMode::Equals,
+            (_, Some("contains")) => FieldMode::Contains,
+            (_, Some("regex")) => FieldMode::Regex(compile(value)?),
+            _ => FieldMode::Loose,
+        };
+        selector.targets.push(FieldTarget { name, attr, value: value.to_string(), mode, weights: *weights });
+        Ok(())
+    };
+
+    let map: HashMap<String, Value> = params.as_object().map(|o| o.clone().into_iter().collect()).unwrap_or_default();
+    let mut selection_mode = params.get("smartSelection").and_then(|s| s.get("mode")).and_then(Value::as_str).map(str::to_string);
+    if let Some(context) = extract_matching_context(&map) {
+        let fields: Vec<String> =
+            if context.fields.is_empty() { context.values.keys().cloned().collect() } else { context.fields.clone() };
+        for field in &fields {
+            if let Some(value) = context.values.get(field).filter(|v| !v.trim().is_empty()) {
+                push(field, value, context.match_mode.get(field).map(String::as_str))?;
+            }
+        }
+        let lists = [
+            (&context.includes, false, false),
+            (&context.excludes, true, false),
+            (&context.regex_includes, false, true),
+            (&context.regex_excludes, true, true),
+        ];
+        for (list, negate, regex) in lists {
+            for (field, words) in list {
+                let Some((_, attr, _)) = field_spec(field, config) else { continue };
+                for word in words.iter().filter(|w| !w.is_empty()) {
+                    let regex = if regex { Some(compile(word)?) } else { None };
+                    selector.filters.push(Filter { attr, word: word.clone(), regex, negate });
+                }
+            }
+        }
+        selection_mode = selection_mode.or(context.selection_mode);
+    } else {
+        for (keys, field) in [
+            (&["resource_id", "resourceId"][..], "resource_id"),
+            (&["text"][..], "text"),
+            (&["content_desc", "contentDesc"][..], "content_desc"),
+            (&["class_name", "className"][..], "class_name"),
+            (&["xpath"][..], "xpath"),
+        ] {
+            let value = keys.iter().find_map(|key| params.get(*key)).and_then(Value::as_str).filter(|s| !s.trim().is_empty());
+            if let Some(value) = value {
+                push(field, value, None)?;
+            }
+        }
+    }
+    selector.multi = selection_mode.is_some_and(|mode| MULTI_SELECTION_MODES.contains(&mode.as_str()));
+    Ok(selector)
+}
+
+/// 按三态权重给节点打分；没有任何字段命中时不算候选
+fn score_node(node: &NodeRef<'_>, selector: &StepSelector) -> Option<DryRunCandidate> {
+    if !selector.filters.iter().all(|f| f.accepts(node)) {
+        return None;
+    }
+    let mut score = 0.0f32;
+    let mut total = 0.0f32;
+    let mut matched_fields = Vec::new();
+    for target in &selector.targets {
+        total += target.weights.matched;
+        match node.attr(target.attr).filter(|v| !v.is_empty()) {
+            Some(actual) if target.matches(actual) => {
+                score += target.weights.matched;
+                matched_fields.push(target.name.to_string());
+            }
+            Some(_) => score += target.weights.mismatched,
+            None => score += target.weights.lost,
+        }
+    }
+    if matched_fields.is_empty() || total <= 0.0 {
+        return None;
+    }
+    let owned = |attr: Attr| node.attr(attr).filter(|v| !v.is_empty()).map(str::to_string);
+    Some(DryRunCandidate {
+        confidence: ((score / total) as f64).clamp(0.0, 1.0),
+        bounds: node.bounds(),
+        text: owned(Attr::Text),
+        resource_id: owned(Attr::ResourceId),
+        content_desc: owned(Attr::ContentDesc),
+        class_name: owned(Attr::Class),
+        matched_fields,
+    })
+}
+
+fn as_match_candidate(index: usize, candidate: &DryRunCandidate) -> MatchCandidate {
+    let (left, top, right, bottom) = candidate.bounds.unwrap_or_default();
+    MatchCandidate {
+        id: format!("candidate_{}", index),
+        score: candidate.confidence,
+        confidence: candidate.confidence,
+        bounds: Bounds { left, top, right, bottom },
+        text: candidate.text.clone(),
+        class_name: candidate.class_name.clone(),
+        package_name: None,
+    }
+}
+
+/// 对最佳候选执行与真实运行相同的三道安全闸门
+fn evaluate_gates(candidates: &[DryRunCandidate], selector: &StepSelector) -> Vec<DryRunGateVerdict> {
+    let Some(top) = candidates.first() else {
+        return Vec::new();
+    };
+    let ranked: Vec<MatchCandidate> = candidates.iter().enumerate().map(|(i, c)| as_match_candidate(i, c)).collect();
+    let confident = top.confidence >= selector.min_confidence;
+    let unique = selector.multi || SafetyGatekeeper::validate_uniqueness(&ranked, selector.min_confidence as f32);
+    let safe_target = SafetyGatekeeper::validate_container_safety(&ranked[0], selector.forbid_containers);
+    let high_quality = candidates.iter().filter(|c| c.confidence >= selector.min_confidence).count();
+    vec![
+        DryRunGateVerdict {
+            gate: "confidence".to_string(),
+            passed: confident,
+            detail: format!("最佳置信度 {:.2}，阈值 {:.2}", top.confidence, selector.min_confidence),
+        },
+        DryRunGateVerdict {
+            gate: "uniqueness".to_string(),
+            passed: unique,
+            detail: if selector.multi {
+                "选择模式允许命中多个元素".to_string()
+            } else {
+                format!("共 {} 个候选，{} 个达到阈值", candidates.len(), high_quality)
+            },
+        },
+        DryRunGateVerdict {
+            gate: "container".to_string(),
+            passed: safe_target,
+            detail: if safe_target {
+                "目标不是整屏或容器节点".to_string()
+            } else {
+                format!("目标为整屏或容器节点（{}）", top.class_name.as_deref().unwrap_or("unknown"))
+            },
+        },
+    ]
+}
+
+/// 在同一份界面 dump 上逐步回放（不会触发任何设备操作，因此后续步骤看到的仍是当前页面）
+pub fn dry_run_steps(steps: &[SmartScriptStep], ui_xml: &str, config: &MatchingConfig) -> Vec<DryRunStepReport> {
+    let tree = UiTree::parse_lenient(ui_xml);
+    let mut ordered: Vec<&SmartScriptStep> = steps.iter().collect();
+    ordered.sort_by_key(|step| step.order);
+
+    ordered
+        .into_iter()
+        .map(|step| {
+            let mut report = DryRunStepReport {
+                step_id: step.id.clone(),
+                step_name: step.name.clone(),
+                order: step.order,
+                step_type: step.step_type.clone(),
+                status: DryRunStepStatus::WouldExecute,
+                candidates: Vec::new(),
+                gates: Vec::new(),
+                message: String::new(),
+            };
+            if !step.enabled {
+                report.status = DryRunStepStatus::Disabled;
+                report.message = "步骤已禁用".to_string();
+                return report;
+            }
+            let selector = match build_selector(&step.parameters, config) {
+                Ok(selector) => selector,
+                Err(reason) => {
+                    report.status = DryRunStepStatus::Blocked;
+                    report.gates.push(DryRunGateVerdict { gate: "selector".to_string(), passed: false, detail: reason.clone() });
+                    report.message = format!("选择器无效: {}", reason);
+                    return report;
+                }
+            };
+            if selector.targets.is_empty() {
+                report.status = DryRunStepStatus::NoTarget;
+                report.message = "步骤不需要定位元素".to_string();
+                return report;
+            }
+
+            let mut candidates: Vec<DryRunCandidate> = tree.nodes().filter_map(|node| score_node(&node, &selector)).collect();
+            candidates.sort_by(|a, b| b.confidence.total_cmp(&a.confidence));
+            report.gates = evaluate_gates(&candidates, &selector);
+            let total = candidates.len();
+            candidates.truncate(MAX_CANDIDATES);
+            report.candidates = candidates;
+
+            if total == 0 {
+                report.status = DryRunStepStatus::NotFound;
+                report.message = "当前界面中找不到目标元素".to_string();
+            } else if let Some(failed) = report.gates.iter().find(|g| !g.passed) {
+                report.status = DryRunStepStatus::Blocked;
+                report.message = format!("被 {} 闸门拦截: {}", failed.gate, failed.detail);
+            } else {
+                report.message = format!("将作用于最佳候选（共 {} 个候选）", total);
+            }
+            report
+        })
+        .collect()
+}
+
+/// 试运行整套脚本：抓取一次真机界面，返回每步的候选与闸门判定
+pub async fn dry_run_script(device_id: &str, steps: &[SmartScriptStep]) -> Result<SmartExecutionResult, String> {
+    let started = Instant::now();
+    let ui_xml = AdbService::new()
+        .dump_ui_hierarchy(device_id)
+        .await
+        .map_err(|e| format!("获取界面失败: {}", e))?;
+    let reports = dry_run_steps(steps, &ui_xml, &matching_config::current());
+
+    let count = |status: DryRunStepStatus| reports.iter().filter(|r| r.status == status).count() as u32;
+    let would_execute = count(DryRunStepStatus::WouldExecute);
+    let failed = count(DryRunStepStatus::Blocked) + count(DryRunStepStatus::NotFound);
+    let skipped = count(DryRunStepStatus::NoTarget) + count(DryRunStepStatus::Disabled);
+    let logs = reports.iter().map(|r| format!("🧪 [{}] {}: {:?} - {}", r.order, r.step_name, r.status, r.message)).collect();
+    let message = format!("试运行完成: {} 步可执行, {} 步被拦截或未找到, {} 步无需定位", would_execute, failed, skipped);
+    info!("🧪 设备 {} {}", device_id, message);
+
+    Ok(SmartExecutionResult {
+        success: failed == 0,
+        total_steps: reports.len() as u32,
+        executed_steps: 0,
+        failed_steps: failed,
+        skipped_steps: skipped,
+        duration_ms: started.elapsed().as_millis() as u64,
+        logs,
+        final_page_state: None,
+        extracted_data: HashMap::new(),
+        message,
+        run_id: None,
+        dry_run: Some(reports),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::execution::model::SmartActionType;
+    use serde_json::json;
+
+    const XML: &str = r#"<hierarchy rotation="0"><node index="0" class="android.widget.FrameLayout" bounds="[0,0][1080,2400]"><node index="0" text="关注" resource-id="com.app:id/follow" class="android.widget.Button" clickable="true" bounds="[10,10][200,100]"/><node index="1" text="关注" resource-id="com.app:id/follow" class="android.widget.Button" clickable="true" bounds="[10,200][200,300]"/><node index="2" text="发送" resource-id="com.app:id/send" class="android.widget.Button" clickable="true" bounds="[10,400][200,500]"/><node index="3" text="" resource-id="com.app:id/list" class="android.widget.LinearLayout" bounds="[0,600][1080,1600]"/></node></hierarchy>"#;
+
+    fn step(id: &str, order: i32, step_type: SmartActionType, parameters: Value) -> SmartScriptStep {
+        SmartScriptStep {
+            id: id.to_string(),
+            step_type,
+            name: id.to_string(),
+            description: String::new(),
+            parameters,
+            enabled: true,
+            order,
+        }
+    }
+
+    #[test]
+    fn test_dry_run_reports_candidates_and_gate_verdicts() {
+        let steps = vec![
+            step("send", 1, SmartActionType::Tap, json!({ "matching": { "strategy": "standard", "values": { "resource-id": "com.app:id/send", "text": "发送" } } })),
+            step("follow", 2, SmartActionType::Tap, json!({ "resource_id": "com.app:id/follow" })),
+            step("follow_first", 3, SmartActionType::Tap, json!({ "resource_id": "com.app:id/follow", "smartSelection": { "mode": "first" } })),
+            step("list", 4, SmartActionType::Tap, json!({ "resource_id": "com.app:id/list" })),
+            step("missing", 5, SmartActionType::Tap, json!({ "text": "私信" })),
+            step("wait", 6, SmartActionType::Wait, json!({ "duration": 1000 })),
+        ];
+        let reports = dry_run_steps(&steps, XML, &MatchingConfig::default());
+        let by_id = |id: &str| reports.iter().find(|r| r.step_id == id).unwrap();
+
+        let send = by_id("send");
+        assert_eq!(send.status, DryRunStepStatus::WouldExecute);
+        assert_eq!(send.candidates[0].resource_id.as_deref(), Some("com.app:id/send"));
+        assert!((send.candidates[0].confidence - 1.0).abs() < 1e-6);
+        assert!(send.gates.iter().all(|g| g.passed));
+
+        let follow = by_id("follow");
+        assert_eq!(follow.status, DryRunStepStatus::Blocked);
+        assert_eq!(follow.candidates.len(), 2);
+        assert!(!follow.gates.iter().find(|g| g.gate == "uniqueness").unwrap().passed);
+        assert_eq!(by_id("follow_first").status, DryRunStepStatus::WouldExecute);
+
+        let list = by_id("list");
+        assert_eq!(list.status, DryRunStepStatus::Blocked);
+        assert!(!list.gates.iter().find(|g| g.gate == "container").unwrap().passed);
+
+        assert_eq!(by_id("missing").status, DryRunStepStatus::NotFound);
+        assert_eq!(by_id("wait").status, DryRunStepStatus::NoTarget);
+    }
+}
@@ -1,4 +1,7 @@
 use std::collections::HashMap;
+use std::sync::Arc;
+
+use tauri::{AppHandle, Runtime};
 
 use crate::services::smart_script_executor::SmartScriptExecutor;
 use crate::services::execution::model::{
@@ -7,7 +10,8 @@ use crate::services::execution::model::{
     SmartScriptStep,
     SingleStepTestResult,
 };
-use tracing::{error, info};
+use crate::automation::pipeline::multi_device::{self, DeviceProgress, MultiDeviceReport};
+use tracing::{error, info, warn};
 
 // 🆕 导出智能自动链测试命令
 mod chain_test;
@@ -15,11 +19,21 @@ mod chain_test;
 // 🆕 导出静态策略测试命令
 mod static_test;
 
+// 🆕 试运行：只匹配不注入
+mod dry_run;
+
 /// 执行单步智能脚本测试。
 #[tauri::command]
 pub async fn execute_single_step_test(
     device_id: String,
     step: SmartScriptStep,
+) -> Result<SingleStepTestResult, String> {
+    crate::core::plugin_isolation::guard("script_manager", execute_single_step_test_impl(device_id, step)).await
+}
+
+async fn execute_single_step_test_impl(
+    device_id: String,
+    step: SmartScriptStep,
 ) -> Result<SingleStepTestResult, String> {
     info!("🧪 收到单步测试请求: {} (设备: {})", step.name, device_id);
     info!("📋 步骤类型: {:?}", step.step_type);
@@ -40,14 +54,31 @@ pub async fn execute_single_step_test(
 }
 
 /// 执行整套智能脚本。
+///
+/// `dry_run` 为 true 时只抓取真机界面逐步匹配，返回每步的候选、置信度与安全闸门判定，不做任何点击 / 滑动。
 #[tauri::command]
 pub async fn execute_smart_automation_script(
     device_id: String,
     steps: Vec<SmartScriptStep>,
     config: Option<SmartExecutorConfig>,
+    dry_run: Option<bool>,
+) -> Result<SmartExecutionResult, String> {
+    crate::core::plugin_isolation::guard("script_manager", execute_smart_automation_script_impl(device_id, steps, config, dry_run)).await
+}
+
+async fn execute_smart_automation_script_impl(
+    device_id: String,
+    steps: Vec<SmartScriptStep>,
+    config: Option<SmartExecutorConfig>,
+    dry_run: Option<bool>,
 ) -> Result<SmartExecutionResult, String> {
     info!("🚀 收到智能脚本批量执行请求: 设备 {}, {} 个步骤", device_id, steps.len());
 
+    if dry_run.unwrap_or(false) {
+        info!("🧪 试运行模式：只匹配不执行");
+        return dry_run::dry_run_script(&device_id, &steps).await;
+    }
+
     if std::env::var("USE_NEW_BACKEND").ok().as_deref() == Some("1") {
         info!("🧪 开启新后端灰度 (USE_NEW_BACKEND=1)，进入 v2 管线...");
         let adb_path = crate::utils::adb_utils::get_adb_path();
@@ -64,6 +95,8 @@ pub async fn execute_smart_automation_script(
                     final_page_state: None,
                     extracted_data: HashMap::new(),
                     message: "v2 pipeline 执行成功".to_string(),
+                    run_id: None,
+                    dry_run: None,
                 };
                 return Ok(result);
             }
@@ -91,44 +124,86 @@ pub async fn execute_smart_automation_script(
     }
 }
 
-/// 在多台设备上执行整套智能脚本（顺序执行聚合结果）。
+/// 在多台设备上执行整套智能脚本（并行执行，返回「设备 → 结果」映射）。
 #[tauri::command]
-pub async fn execute_smart_automation_script_multi(
+pub async fn execute_smart_automation_script_multi<R: Runtime>(
+    app: AppHandle<R>,
     device_ids: Vec<String>,
     steps: Vec<SmartScriptStep>,
     config: Option<SmartExecutorConfig>,
+    max_concurrency: Option<usize>,
 ) -> Result<HashMap<String, SmartExecutionResult>, String> {
-    info!("🚀 收到多设备智能脚本批量执行请求: 设备数={}, 步骤数={}", device_ids.len(), steps.len());
+    crate::core::plugin_isolation::guard("script_manager", execute_smart_automation_script_multi_impl(app, device_ids, steps, config, max_concurrency)).await
+}
 
-    let mut results: HashMap<String, SmartExecutionResult> = HashMap::new();
+async fn execute_smart_automation_script_multi_impl<R: Runtime>(
+    app: AppHandle<R>,
+    device_ids: Vec<String>,
+    steps: Vec<SmartScriptStep>,
+    config: Option<SmartExecutorConfig>,
+    max_concurrency: Option<usize>,
+) -> Result<HashMap<String, SmartExecutionResult>, String> {
+    let total_steps = steps.len();
+    let report = execute_smart_automation_script_parallel(app, device_ids, steps, config, max_concurrency).await?;
+    Ok(report.into_results(total_steps))
+}
 
-    for device_id in device_ids {
-        info!("➡️ 开始执行设备: {}", device_id);
-        let executor = SmartScriptExecutor::new(device_id.clone());
-        match executor.execute_smart_script(steps.clone(), config.clone()).await {
-            Ok(result) => {
-                info!("✅ 设备 {} 执行完成: 耗时={}ms, 成功={}", device_id, result.duration_ms, result.success);
-                results.insert(device_id, result);
-            }
-            Err(e) => {
-                error!("❌ 设备 {} 执行失败: {}", device_id, e);
-                // 失败时也生成一个失败结果，保证返回结构完整
-                let fail = SmartExecutionResult {
-                    success: false,
-                    total_steps: steps.len() as u32,
-                    executed_steps: 0,
-                    failed_steps: steps.len() as u32,
-                    skipped_steps: 0,
-                    duration_ms: 0,
-                    logs: vec![format!("设备执行失败: {}", e)],
-                    final_page_state: None,
-                    extracted_data: HashMap::new(),
-                    message: format!("设备执行失败: {}", e),
-                };
-                results.insert(device_id, fail);
+/// 把整套智能脚本并行分发到多台设备；每台设备推送 `device:{serial}:progress` 事件，返回逐设备汇总报告。
+#[tauri::command]
+pub async fn execute_smart_automation_script_parallel<R: Runtime>(
+    app: AppHandle<R>,
+    device_ids: Vec<String>,
+    steps: Vec<SmartScriptStep>,
+    config: Option<SmartExecutorConfig>,
+    max_concurrency: Option<usize>,
+) -> Result<MultiDeviceReport, String> {
+    crate::core::plugin_isolation::guard("script_manager", execute_smart_automation_script_parallel_impl(app, device_ids, steps, config, max_concurrency)).await
+}
+
+async fn execute_smart_automation_script_parallel_impl<R: Runtime>(
+    app: AppHandle<R>,
+    device_ids: Vec<String>,
+    steps: Vec<SmartScriptStep>,
+    config: Option<SmartExecutorConfig>,
+    max_concurrency: Option<usize>,
+) -> Result<MultiDeviceReport, String> {
+    info!(
+        "🚀 收到多设备智能脚本并行执行请求: 设备数={}, 步骤数={}, 并发上限={:?}",
+        device_ids.len(),
+        steps.len(),
+        max_concurrency
+    );
+    crate::services::licensing::require_device_count(device_ids.len())?;
+    crate::services::telemetry::record_feature("script.multi_device");
+
+    let batch_id = uuid::Uuid::new_v4().to_string();
+    let sink = Arc::new(move |progress: DeviceProgress| {
+        let event = multi_device::progress_event_name(&progress.device_id);
+        let device_id = progress.device_id.clone();
+        if let Err(e) = crate::services::device_windows::emit_for_device(&app, &device_id, &event, progress) {
+            warn!("⚠️ 推送设备进度失败: {} - {}", device_id, e);
+        }
+    });
+    let total_steps = steps.len();
+    let report = multi_device::execute_parallel(&batch_id, device_ids, total_steps, max_concurrency, sink, move |device_id| {
+        let steps = steps.clone();
+        let config = config.clone();
+        async move {
+            info!("➡️ 开始执行设备: {}", device_id);
+            let executor = SmartScriptExecutor::new(device_id.clone());
+            let outcome = executor.execute_smart_script(steps, config).await.map_err(|e| e.to_string());
+            match &outcome {
+                Ok(result) => info!("✅ 设备 {} 执行完成: 耗时={}ms, 成功={}", device_id, result.duration_ms, result.success),
+                Err(e) => error!("❌ 设备 {} 执行失败: {}", device_id, e),
             }
+            outcome
         }
-    }
+    })
+    .await;
 
-    Ok(results)
+    info!(
+        "🏁 多设备执行结束: batch={}, 成功 {}/{}, 耗时 {}ms",
+        report.batch_id, report.succeeded, report.total_devices, report.duration_ms
+    );
+    Ok(report)
 }
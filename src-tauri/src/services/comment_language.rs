@@ -0,0 +1,128 @@
+// src-tauri/src/services/comment_language.rs
+// module: comment-language | layer: services | role: 评论语言识别
+// summary: 按汉字 / 拉丁单词占比粗分中文、英文、中英混合，评论入库时打标，回复模板按语言路由
+
+use rusqlite::types::{FromSql, FromSqlResult, ToSql, ToSqlOutput, ValueRef};
+use serde::{Deserialize, Serialize};
+
+/// 汉字按约 2 字一词折算，与英文单词数比较
+const CJK_CHARS_PER_WORD: f64 = 2.0;
+/// 中文占比不低于该值判为中文
+const ZH_SHARE_MIN: f64 = 0.6;
+/// 中文占比不高于该值判为英文
+const EN_SHARE_MAX: f64 = 0.15;
+
+/// 评论语言
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum CommentLanguage {
+    Zh,
+    En,
+    /// 中英混合
+    Mixed,
+    /// 纯表情 / 数字 / 链接等无法判断
+    #[default]
+    #[serde(other)]
+    Unknown,
+}
+
+impl CommentLanguage {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CommentLanguage::Zh => "zh",
+            CommentLanguage::En => "en",
+            CommentLanguage::Mixed => "mixed",
+            CommentLanguage::Unknown => "unknown",
+        }
+    }
+
+    pub fn parse(s: &str) -> Self {
+        match s.trim().to_lowercase().as_str() {
+            "zh" | "zh-cn" | "chinese" => CommentLanguage::Zh,
+            "en" | "english" => CommentLanguage::En,
+            "mixed" => CommentLanguage::Mixed,
+            _ => CommentLanguage::Unknown,
+        }
+    }
+
+    /// 选回复模板时可接受的模板语言（按优先级）；未标语言的通用模板总是最后兜底
+    pub fn template_preference(&self) -> &'static [CommentLanguage] {
+        match self {
+            CommentLanguage::Zh => &[CommentLanguage::Zh, CommentLanguage::Mixed],
+            CommentLanguage::En => &[CommentLanguage::En],
+            CommentLanguage::Mixed => &[CommentLanguage::Mixed, CommentLanguage::Zh, CommentLanguage::En],
+            CommentLanguage::Unknown => &[CommentLanguage::Zh],
+        }
+    }
+}
+
+impl ToSql for CommentLanguage {
+    fn to_sql(&self) -> rusqlite::Result<ToSqlOutput<'_>> {
+        Ok(ToSqlOutput::from(self.as_str()))
+    }
+}
+
+impl FromSql for CommentLanguage {
+    fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+        value.as_str().map(CommentLanguage::parse)
+    }
+}
+
+fn is_cjk(c: char) -> bool {
+    matches!(c, '\u{4e00}'..='\u{9fff}' | '\u{3400}'..='\u{4dbf}' | '\u{f900}'..='\u{faff}')
+}
+
+/// 识别一段评论文本的语言（忽略链接、@提及、数字与表情）
+pub fn detect_language(text: &str) -> CommentLanguage {
+    let mut cjk = 0usize;
+    let mut latin_words = 0usize;
+    for token in text.split_whitespace() {
+        if token.starts_with("http://") || token.starts_with("https://") || token.starts_with('@') {
+            continue;
+        }
+        let mut in_word = false;
+        for c in token.chars() {
+            if is_cjk(c) {
+                cjk += 1;
+            }
+            let is_letter = c.is_ascii_alphabetic();
+            if is_letter && !in_word {
+                latin_words += 1;
+            }
+            in_word = is_letter;
+        }
+    }
+
+    match (cjk, latin_words) {
+        (0, 0) => CommentLanguage::Unknown,
+        (_, 0) => CommentLanguage::Zh,
+        (0, _) => CommentLanguage::En,
+        _ => {
+            let zh_words = cjk as f64 / CJK_CHARS_PER_WORD;
+            let share = zh_words / (zh_words + latin_words as f64);
+            if share >= ZH_SHARE_MIN {
+                CommentLanguage::Zh
+            } else if share <= EN_SHARE_MAX {
+                CommentLanguage::En
+            } else {
+                CommentLanguage::Mixed
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_language() {
+        assert_eq!(detect_language("这个多少钱？怎么买"), CommentLanguage::Zh);
+        assert_eq!(detect_language("这个iPhone多少钱"), CommentLanguage::Zh);
+        assert_eq!(detect_language("How much is this? DM me"), CommentLanguage::En);
+        assert_eq!(detect_language("Nice video 好看"), CommentLanguage::Mixed);
+        assert_eq!(detect_language("I really love this product, 赞"), CommentLanguage::En);
+        assert_eq!(detect_language("😀👍 666 https://v.douyin.com/abc @小明"), CommentLanguage::Unknown);
+        assert_eq!(CommentLanguage::parse("EN"), CommentLanguage::En);
+    }
+}
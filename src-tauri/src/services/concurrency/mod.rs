@@ -0,0 +1,368 @@
+// src-tauri/src/services/concurrency/mod.rs
+// module: concurrency | layer: services | role: 执行并发控制
+// summary: 调度器、配方队列、Agent、手动运行等各子系统开始设备 / AI 工作前统一申请许可；按全局、单设备、类别（AI 密集 / 设备密集）上限放行，超出的排队并按来源轮转公平放行，提供实时状态视图
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::future::Future;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, MutexGuard, OnceLock};
+use tokio::sync::oneshot;
+use tracing::{debug, info};
+
+const LIMITS_FILE_NAME: &str = "concurrency_limits.json";
+
+static CONTROLLER: OnceLock<ConcurrencyController> = OnceLock::new();
+
+tokio::task_local! {
+    /// 当前任务已持有许可的设备（嵌套申请同一设备时直接放行，避免自锁）
+    static HELD_DEVICES: Vec<String>;
+}
+
+/// 工作类别
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WorkCategory {
+    /// 占用设备的工作（脚本、导入、Agent 目标）
+    Device,
+    /// 调用大模型的工作
+    Ai,
+}
+
+/// 并发上限；0 表示不限
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConcurrencyLimits {
+    pub global: usize,
+    pub per_device: usize,
+    #[serde(default)]
+    pub per_category: HashMap<WorkCategory, usize>,
+}
+
+impl Default for ConcurrencyLimits {
+    fn default() -> Self {
+        Self {
+            global: 8,
+            per_device: 1,
+            per_category: HashMap::from([(WorkCategory::Device, 6), (WorkCategory::Ai, 2)]),
+        }
+    }
+}
+
+impl ConcurrencyLimits {
+    fn category(&self, category: WorkCategory) -> usize {
+        self.per_category.get(&category).copied().unwrap_or(0)
+    }
+}
+
+/// 一次工作申请
+#[derive(Debug, Clone)]
+pub struct WorkRequest {
+    pub category: WorkCategory,
+    pub device_id: Option<String>,
+    /// 发起的子系统（script / agent / recipe / ai ...），排队时按来源轮转
+    pub source: String,
+    pub label: String,
+}
+
+impl WorkRequest {
+    pub fn device(device_id: &str, source: &str, label: impl Into<String>) -> Self {
+        Self { category: WorkCategory::Device, device_id: Some(device_id.to_string()), source: source.to_string(), label: label.into() }
+    }
+
+    pub fn ai(source: &str, label: impl Into<String>) -> Self {
+        Self { category: WorkCategory::Ai, device_id: None, source: source.to_string(), label: label.into() }
+    }
+}
+
+/// 运行中 / 排队中的工作
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkInfo {
+    pub id: u64,
+    pub category: WorkCategory,
+    pub device_id: Option<String>,
+    pub source: String,
+    pub label: String,
+    /// 在其他许可内部发起的嵌套申请，不占全局名额
+    pub nested: bool,
+    pub queued_at: DateTime<Utc>,
+    pub started_at: Option<DateTime<Utc>>,
+}
+
+/// 实时状态
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConcurrencyStatus {
+    pub limits: ConcurrencyLimits,
+    pub running: Vec<WorkInfo>,
+    /// 排队中的工作（先到在前）
+    pub queued: Vec<WorkInfo>,
+    pub running_by_category: HashMap<WorkCategory, usize>,
+    pub running_by_device: HashMap<String, usize>,
+}
+
+struct Waiter {
+    info: WorkInfo,
+    tx: oneshot::Sender<()>,
+}
+
+struct State {
+    limits: ConcurrencyLimits,
+    path: Option<PathBuf>,
+    next_id: u64,
+    running: Vec<WorkInfo>,
+    queue: Vec<Waiter>,
+}
+
+impl State {
+    fn global_full(&self) -> bool {
+        self.limits.global > 0 && self.running.iter().filter(|w| !w.nested).count() >= self.limits.global
+    }
+
+    /// 单设备 / 类别名额是否允许
+    fn resources_free(&self, info: &WorkInfo) -> bool {
+        let category_limit = self.limits.category(info.category);
+        if category_limit > 0 && self.running.iter().filter(|w| w.category == info.category).count() >= category_limit {
+            return false;
+        }
+        match &info.device_id {
+            Some(device) if self.limits.per_device > 0 => {
+                self.running.iter().filter(|w| w.device_id.as_ref() == Some(device)).count() < self.limits.per_device
+            }
+            _ => true,
+        }
+    }
+
+    /// 放行可以开始的排队工作
+    ///
+    /// 只被自身设备 / 类别名额挡住的工作可以被后来者越过；全局名额满时停止放行（嵌套申请除外）。
+    /// 多个可放行者中优先选运行中数量最少的来源，同等时先到先得，避免某个子系统独占名额。
+    fn pump(&mut self) {
+        loop {
+            let global_full = self.global_full();
+            let candidate = self
+                .queue
+                .iter()
+                .enumerate()
+                .filter(|(_, w)| (w.info.nested || !global_full) && self.resources_free(&w.info))
+                .min_by_key(|(index, w)| (self.running.iter().filter(|r| r.source == w.info.source).count(), *index))
+                .map(|(index, _)| index);
+            let Some(index) = candidate else { break };
+            let mut waiter = self.queue.remove(index);
+            waiter.info.started_at = Some(Utc::now());
+            let info = waiter.info.clone();
+            // 申请方已放弃（future 被丢弃）时不占名额
+            if waiter.tx.send(()).is_ok() {
+                debug!("🚦 放行 {} [{}] {}", info.id, info.source, info.label);
+                self.running.push(info);
+            }
+        }
+    }
+
+    fn remove(&mut self, id: u64) {
+        self.queue.retain(|w| w.info.id != id);
+        let before = self.running.len();
+        self.running.retain(|w| w.id != id);
+        if self.running.len() != before {
+            self.pump();
+        }
+    }
+
+    fn status(&self) -> ConcurrencyStatus {
+        let mut running_by_category = HashMap::new();
+        let mut running_by_device = HashMap::new();
+        for work in &self.running {
+            *running_by_category.entry(work.category).or_insert(0) += 1;
+            if let Some(device) = &work.device_id {
+                *running_by_device.entry(device.clone()).or_insert(0) += 1;
+            }
+        }
+        ConcurrencyStatus {
+            limits: self.limits.clone(),
+            running: self.running.clone(),
+            queued: self.queue.iter().map(|w| w.info.clone()).collect(),
+            running_by_category,
+            running_by_device,
+        }
+    }
+}
+
+/// 并发控制器
+#[derive(Clone)]
+pub struct ConcurrencyController {
+    state: Arc<Mutex<State>>,
+}
+
+impl ConcurrencyController {
+    pub fn new(limits: ConcurrencyLimits) -> Self {
+        Self::with_path(limits, None)
+    }
+
+    fn with_path(limits: ConcurrencyLimits, path: Option<PathBuf>) -> Self {
+        let state = State { limits, path, next_id: 1, running: Vec::new(), queue: Vec::new() };
+        Self { state: Arc::new(Mutex::new(state)) }
+    }
+
+    /// 全局实例（上限持久化在应用数据目录）
+    pub fn global() -> &'static ConcurrencyController {
+        CONTROLLER.get_or_init(|| {
+            let path = dirs::data_dir().unwrap_or_else(|| PathBuf::from(".")).join("employee-gui").join(LIMITS_FILE_NAME);
+            let limits = std::fs::read_to_string(&path).ok().and_then(|content| serde_json::from_str(&content).ok()).unwrap_or_default();
+            Self::with_path(limits, Some(path))
+        })
+    }
+
+    fn lock(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// 申请许可；名额不足时排队等待。等待中丢弃返回的 future 即撤销排队
+    pub async fn acquire(&self, request: WorkRequest) -> ConcurrencyPermit {
+        self.acquire_inner(request, false).await
+    }
+
+    async fn acquire_inner(&self, request: WorkRequest, nested: bool) -> ConcurrencyPermit {
+        let (id, rx) = {
+            let mut state = self.lock();
+            let id = state.next_id;
+            state.next_id += 1;
+            let (tx, rx) = oneshot::channel();
+            let info = WorkInfo {
+                id,
+                category: request.category,
+                device_id: request.device_id,
+                source: request.source,
+                label: request.label,
+                nested,
+                queued_at: Utc::now(),
+                started_at: None,
+            };
+            state.queue.push(Waiter { info, tx });
+            state.pump();
+            (id, rx)
+        };
+        // 放行前后被取消都要归还名额，由许可的 Drop 统一处理
+        let permit = ConcurrencyPermit { controller: self.clone(), id };
+        let _ = rx.await;
+        permit
+    }
+
+    pub fn status(&self) -> ConcurrencyStatus {
+        self.lock().status()
+    }
+
+    /// 修改上限并立即按新上限放行排队工作
+    pub fn set_limits(&self, limits: ConcurrencyLimits) -> Result<ConcurrencyStatus, String> {
+        let mut state = self.lock();
+        if let Some(path) = &state.path {
+            if let Some(dir) = path.parent() {
+                std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+            }
+            let content = serde_json::to_string_pretty(&limits).map_err(|e| e.to_string())?;
+            std::fs::write(path, content).map_err(|e| format!("保存并发上限失败: {}", e))?;
+        }
+        info!("🚦 并发上限已更新: 全局 {} / 单设备 {}", limits.global, limits.per_device);
+        state.limits = limits;
+        state.pump();
+        Ok(state.status())
+    }
+}
+
+/// 工作许可，丢弃时归还名额
+pub struct ConcurrencyPermit {
+    controller: ConcurrencyController,
+    id: u64,
+}
+
+impl Drop for ConcurrencyPermit {
+    fn drop(&mut self) {
+        self.controller.lock().remove(self.id);
+    }
+}
+
+/// 在许可内执行 `fut`（使用全局控制器）
+///
+/// 当前任务已持有同一设备的许可时直接执行；在其他许可内部发起的申请不占全局名额，避免嵌套等待造成死锁。
+pub async fn run<F: Future>(request: WorkRequest, fut: F) -> F::Output {
+    ConcurrencyController::global().run(request, fut).await
+}
+
+impl ConcurrencyController {
+    pub async fn run<F: Future>(&self, request: WorkRequest, fut: F) -> F::Output {
+        let held = HELD_DEVICES.try_with(|held| held.clone()).ok();
+        if let (Some(held), Some(device)) = (&held, &request.device_id) {
+            if held.contains(device) {
+                return fut.await;
+            }
+        }
+        let mut devices = held.clone().unwrap_or_default();
+        devices.extend(request.device_id.clone());
+        let _permit = self.acquire_inner(request, held.is_some()).await;
+        HELD_DEVICES.scope(devices, fut).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn settle() {
+        for _ in 0..5 {
+            tokio::task::yield_now().await;
+        }
+    }
+
+    #[tokio::test]
+    async fn test_limits_fair_order_and_cancellation() {
+        let limits = ConcurrencyLimits { global: 2, per_device: 1, per_category: HashMap::from([(WorkCategory::Ai, 1)]) };
+        let controller = ConcurrencyController::new(limits);
+
+        let agent_a = controller.acquire(WorkRequest::device("A", "agent", "goal")).await;
+        let queued_a = tokio::spawn({
+            let c = controller.clone();
+            async move { c.acquire(WorkRequest::device("A", "manual", "script")).await }
+        });
+        settle().await;
+        // A 被占用时 B 可以越过排队的 A 先开始
+        let manual_b = controller.acquire(WorkRequest::device("B", "manual", "script")).await;
+        let ai = tokio::spawn({
+            let c = controller.clone();
+            async move { c.acquire(WorkRequest::ai("ai", "chat")).await }
+        });
+        let cancelled = tokio::spawn({
+            let c = controller.clone();
+            async move { c.acquire(WorkRequest::device("C", "recipe", "import")).await }
+        });
+        settle().await;
+        let status = controller.status();
+        assert_eq!(status.running.len(), 2);
+        assert_eq!(status.queued.iter().map(|w| w.source.as_str()).collect::<Vec<_>>(), vec!["manual", "ai", "recipe"]);
+
+        cancelled.abort();
+        let _ = cancelled.await;
+        assert_eq!(controller.status().queued.len(), 2);
+
+        // A 释放后：manual 已有 1 个在跑，ai 来源为 0，优先放行 ai
+        drop(agent_a);
+        let status = controller.status();
+        assert_eq!(status.running.iter().map(|w| w.source.as_str()).collect::<Vec<_>>(), vec!["manual", "ai"]);
+        let ai = ai.await.unwrap();
+
+        drop(manual_b);
+        let queued_a = queued_a.await.unwrap();
+        assert_eq!(controller.status().running_by_device.get("A"), Some(&1));
+
+        // 同一任务内嵌套申请同一设备直接放行
+        drop(ai);
+        drop(queued_a);
+        let nested = controller
+            .run(WorkRequest::device("A", "script", "outer"), async {
+                controller.run(WorkRequest::device("A", "script", "inner"), async { controller.status().running.len() }).await
+            })
+            .await;
+        assert_eq!(nested, 1);
+        assert!(controller.status().running.is_empty());
+    }
+}
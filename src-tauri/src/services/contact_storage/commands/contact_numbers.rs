@@ -6,6 +6,7 @@ use tauri::{command, AppHandle};
 use super::super::repository_facade::ContactStorageFacade;
 use super::super::models::{self, ContactStatus, ImportRecordStatus};
 use super::super::parser::extract_numbers_from_text; // 使用 parser 模块的实现
+use super::super::parser::readers::parse_contact_file;
 use std::path::Path;
 use std::fs;
 use std::str::FromStr;
@@ -16,16 +17,10 @@ pub async fn import_contact_numbers_from_file(
     app_handle: AppHandle,
     file_path: String,
 ) -> Result<models::ImportNumbersResult, String> {
-    
-    
-    if !Path::new(&file_path).exists() {
-        return Err(format!("文件不存在: {}", file_path));
-    }
-
-    let content = fs::read_to_string(&file_path).map_err(|e| format!("读取文件失败: {}", e))?;
-    let total_lines = content.lines().count() as i64;
-    let parse_result = extract_numbers_from_text(&content);
-    let numbers = parse_result.contacts; // 提取联系人列表
+    // TXT / CSV / Excel / vCard 按扩展名识别，表格文件按表头推断列映射
+    let parsed = parse_contact_file(Path::new(&file_path), None)?;
+    let total_lines = parsed.total_lines as i64;
+    let numbers = parsed.result.contacts; // 提取联系人列表
 
     // 提取文件名（用于记录）
     let file_name = Path::new(&file_path)
@@ -35,7 +30,13 @@ pub async fn import_contact_numbers_from_file(
         .to_string();
 
     let facade = ContactStorageFacade::new(&app_handle);
-    let (inserted, duplicates, errors) = facade.insert_numbers(&numbers, &file_path)?;
+    let (inserted, duplicates, mut errors) = facade.insert_numbers(&numbers, &file_path)?;
+    // 表格文件中的行业列与 contacts 插件的 import_file 一样写入号码标签
+    if !parsed.industries.is_empty() {
+        if let Err(e) = facade.apply_import_industries(&parsed.industries, &file_path) {
+            errors.push(format!("写入行业标签失败: {}", e));
+        }
+    }
     
     // 无论导入结果如何都记录到 txt_import_records 表（包括空文件和全部重复）
     let status_str = if errors.is_empty() { 
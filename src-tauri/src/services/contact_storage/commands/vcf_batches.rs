@@ -94,13 +94,14 @@ pub async fn get_recent_vcf_batches_cmd(
 pub async fn create_vcf_batch_with_numbers_cmd(
     app_handle: AppHandle,
     batch_name: String,
-    source_type: String,
-    generation_method: String,
+    _source_type: String,
+    _generation_method: String,
     _description: Option<String>,
     number_ids: Vec<i64>,
+    device_id: Option<String>,
 ) -> Result<models::VcfBatchCreationResult, String> {
     let facade = ContactStorageFacade::new(&app_handle);
-    facade.create_vcf_batch_with_numbers(&batch_name, number_ids.len() as i64, &source_type, &generation_method)
+    facade.create_vcf_batch_with_numbers(&batch_name, &number_ids, device_id.as_deref())
 }
 
 /// 获取 VCF 批次统计信息
@@ -1,7 +1,17 @@
 use rusqlite::{Connection, Result as SqliteResult};
+use std::collections::BTreeMap;
 use tauri::AppHandle;
 
 use super::super::repositories::contact_numbers_repo::ContactNumberRepository;
+use super::super::repositories::contact_numbers::allocation_planner::{
+    self, AllocationExecutionResult, AllocationPlan, AllocationPlanRequest,
+};
+use super::super::repositories::contact_numbers::global_dedup::{self, DedupProgress, DedupReport, DedupStrategy, DuplicateNumberGroup};
+use super::super::repositories::contact_numbers::destructive_preview::{
+    self, DestructiveExecution, DestructiveOperation, DestructivePreview, NumberSelection, PreviewRegistry,
+};
+use super::super::repositories::contact_numbers::industry_quota::{self, IndustryQuotaRule, QuotaViolation};
+use super::super::repositories::contact_numbers::wechat_check::{self, WechatCheckCandidate, WechatStatus};
 use super::super::models::{ContactNumberDto, ContactNumberList, AllocationResultDto, ContactStatus};
 use super::common::db_connector::with_db_connection;
 
@@ -31,6 +41,17 @@ impl ContactNumbersFacade {
         })
     }
 
+    /// 为导入的号码补充行业标签
+    pub fn apply_import_industries(
+        app_handle: &AppHandle,
+        industries: &std::collections::HashMap<String, String>,
+        source_file: &str,
+    ) -> Result<i64, String> {
+        Self::with_db_connection(app_handle, |conn| {
+            ContactNumberRepository::apply_import_industries(conn, industries, source_file)
+        })
+    }
+
     /// 获取联系人号码统计信息
     pub fn get_contact_number_stats(app_handle: &AppHandle) -> Result<serde_json::Value, String> {
         with_db_connection(app_handle, |conn| {
@@ -74,6 +95,57 @@ impl ContactNumbersFacade {
         })
     }
 
+    /// 全库按规范化号码去重合并
+    pub fn run_global_dedup(
+        app_handle: &AppHandle,
+        strategy: DedupStrategy,
+        dry_run: bool,
+        chunk_size: usize,
+        on_progress: impl FnMut(&DedupProgress),
+    ) -> Result<DedupReport, String> {
+        Self::with_db_connection(app_handle, |conn| {
+            global_dedup::run_global_dedup(conn, strategy, dry_run, chunk_size, on_progress)
+        })
+    }
+
+    /// 查找规范化后相同的号码分组（只读）
+    pub fn find_duplicate_numbers(app_handle: &AppHandle, cross_batch_only: bool) -> Result<Vec<DuplicateNumberGroup>, String> {
+        Self::with_db_connection(app_handle, |conn| {
+            global_dedup::find_duplicate_numbers(conn, cross_batch_only, global_dedup::DEFAULT_CHUNK_SIZE)
+        })
+    }
+
+    /// 预览破坏性操作并签发令牌
+    pub fn preview_destructive_operation(
+        app_handle: &AppHandle,
+        operation: DestructiveOperation,
+        selection: NumberSelection,
+        sample_size: usize,
+    ) -> Result<DestructivePreview, String> {
+        let mut registry = PreviewRegistry::global().lock().map_err(|e| format!("预览令牌表锁定失败: {}", e))?;
+        Self::with_db_connection(app_handle, |conn| {
+            destructive_preview::preview(conn, &mut registry, operation, selection, sample_size)
+        })
+    }
+
+    /// 按预览令牌执行破坏性操作
+    pub fn execute_destructive_operation(app_handle: &AppHandle, token: &str) -> Result<DestructiveExecution, String> {
+        let mut registry = PreviewRegistry::global().lock().map_err(|e| format!("预览令牌表锁定失败: {}", e))?;
+        Self::with_db_connection(app_handle, |conn| Ok(destructive_preview::execute(conn, &mut registry, token)))?
+    }
+
+    /// 合并指定的重复组（未指定时合并全部）
+    pub fn merge_duplicates(
+        app_handle: &AppHandle,
+        normalized_phones: Option<&[String]>,
+        strategy: DedupStrategy,
+        dry_run: bool,
+    ) -> Result<DedupReport, String> {
+        Self::with_db_connection(app_handle, |conn| {
+            global_dedup::merge_duplicates(conn, normalized_phones, strategy, dry_run, global_dedup::DEFAULT_CHUNK_SIZE, |_| {})
+        })
+    }
+
     /// 获取所有联系人号码ID
     pub fn list_all_contact_number_ids(
         app_handle: &AppHandle,
@@ -165,6 +237,82 @@ impl ContactNumbersFacade {
         })
     }
 
+    /// 生成多设备号码分配方案（只读）
+    pub fn plan_allocation(app_handle: &AppHandle, request: &AllocationPlanRequest) -> Result<AllocationPlan, String> {
+        Self::with_db_connection(app_handle, |conn| allocation_planner::build_plan(conn, request))
+    }
+
+    /// 执行审阅后的分配方案
+    pub fn execute_allocation_plan(
+        app_handle: &AppHandle,
+        plan: &AllocationPlan,
+    ) -> Result<AllocationExecutionResult, String> {
+        Self::with_db_connection(app_handle, |conn| allocation_planner::execute_plan(conn, plan))
+    }
+
+    /// 撤销分配方案生成的批次（释放号码、删除配额占用与批次）
+    pub fn revert_allocation_batch(app_handle: &AppHandle, batch_id: &str) -> Result<i64, String> {
+        Self::with_db_connection(app_handle, |conn| allocation_planner::revert_batch(conn, batch_id))
+    }
+
+    /// 列出行业配额规则
+    pub fn list_industry_quota_rules(app_handle: &AppHandle) -> Result<Vec<IndustryQuotaRule>, String> {
+        Self::with_db_connection(app_handle, industry_quota::list_rules)
+    }
+
+    /// 新建或更新行业配额规则
+    pub fn save_industry_quota_rule(app_handle: &AppHandle, rule: &IndustryQuotaRule) -> Result<IndustryQuotaRule, String> {
+        Self::with_db_connection(app_handle, |conn| industry_quota::save_rule(conn, rule))
+    }
+
+    /// 删除行业配额规则
+    pub fn delete_industry_quota_rule(app_handle: &AppHandle, id: i64) -> Result<bool, String> {
+        Self::with_db_connection(app_handle, |conn| industry_quota::delete_rule(conn, id))
+    }
+
+    /// 预检：把这些号码分给设备会突破哪些配额规则
+    pub fn check_industry_quota(
+        app_handle: &AppHandle,
+        device_id: &str,
+        number_ids: &[i64],
+    ) -> Result<Vec<QuotaViolation>, String> {
+        Self::with_db_connection(app_handle, |conn| {
+            let by_industry = industry_quota::industry_counts(conn, number_ids)?;
+            industry_quota::check_quota(conn, device_id, &by_industry)
+        })
+    }
+
+    /// 待做微信注册核验的号码
+    pub fn list_wechat_check_candidates(
+        app_handle: &AppHandle,
+        device_id: &str,
+        limit: i64,
+        recheck_after_days: Option<i64>,
+    ) -> Result<Vec<WechatCheckCandidate>, String> {
+        Self::with_db_connection(app_handle, |conn| wechat_check::list_candidates(conn, device_id, limit, recheck_after_days))
+    }
+
+    /// 写入微信注册核验结论
+    pub fn record_wechat_check(
+        app_handle: &AppHandle,
+        id: i64,
+        status: WechatStatus,
+        device_id: &str,
+        note: Option<&str>,
+    ) -> Result<bool, String> {
+        Self::with_db_connection(app_handle, |conn| wechat_check::record_result(conn, id, status, device_id, note))
+    }
+
+    /// 设备最近若干小时内的核验次数
+    pub fn count_wechat_checks_since(app_handle: &AppHandle, device_id: &str, hours: i64) -> Result<i64, String> {
+        Self::with_db_connection(app_handle, |conn| wechat_check::count_checks_since(conn, device_id, hours))
+    }
+
+    /// 微信核验结论汇总
+    pub fn summarize_wechat_checks(app_handle: &AppHandle, device_id: Option<&str>) -> Result<BTreeMap<String, i64>, String> {
+        Self::with_db_connection(app_handle, |conn| wechat_check::summarize(conn, device_id))
+    }
+
     /// 获取号码
     pub fn fetch_numbers(app_handle: &AppHandle, count: i64) -> Result<Vec<ContactNumberDto>, String> {
         Self::with_db_connection(app_handle, |conn| {
@@ -3,6 +3,7 @@ use tauri::AppHandle;
 use std::path::Path;
 
 use super::super::repositories::txt_import_records_repo;
+use super::super::repositories::folder_import_sessions_repo::{self, FolderImportFileDto, FolderImportSessionDto};
 use super::super::models::{TxtImportRecordDto, TxtImportRecordList, ImportRecordStatus};
 use super::common::db_connector::with_db_connection;
 
@@ -173,4 +174,37 @@ impl TxtImportFacade {
             Ok(json_stats)
         })
     }
-}
\ No newline at end of file
+
+    /// 打开（或续用）文件夹导入会话
+    pub fn open_folder_import_session(app_handle: &AppHandle, folder_path: &str, restart: bool) -> Result<i64, String> {
+        Self::with_db_connection(app_handle, |conn| folder_import_sessions_repo::open_session(conn, folder_path, restart))
+    }
+
+    /// 文件是否已在会话中导入完成且未变化
+    pub fn is_folder_file_completed(
+        app_handle: &AppHandle,
+        session_id: i64,
+        file_path: &str,
+        file_size: i64,
+        modified_at: i64,
+    ) -> Result<bool, String> {
+        Self::with_db_connection(app_handle, |conn| {
+            folder_import_sessions_repo::is_file_completed(conn, session_id, file_path, file_size, modified_at)
+        })
+    }
+
+    /// 记录文件夹中单个文件的导入结果
+    pub fn record_folder_import_file(app_handle: &AppHandle, session_id: i64, file: &FolderImportFileDto) -> Result<(), String> {
+        Self::with_db_connection(app_handle, |conn| folder_import_sessions_repo::record_file(conn, session_id, file))
+    }
+
+    /// 结束文件夹导入会话
+    pub fn finish_folder_import_session(app_handle: &AppHandle, session_id: i64, status: &str) -> Result<(), String> {
+        Self::with_db_connection(app_handle, |conn| folder_import_sessions_repo::finish_session(conn, session_id, status))
+    }
+
+    /// 查询文件夹导入会话
+    pub fn get_folder_import_session(app_handle: &AppHandle, folder_path: &str) -> Result<Option<FolderImportSessionDto>, String> {
+        Self::with_db_connection(app_handle, |conn| folder_import_sessions_repo::get_session(conn, folder_path))
+    }
+}
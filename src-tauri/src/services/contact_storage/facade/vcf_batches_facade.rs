@@ -3,6 +3,9 @@ use tauri::AppHandle;
 
 use super::super::repositories::vcf_batches_repo::VcfBatchRepository;
 use super::super::models::{VcfBatchDto, VcfBatchList, VcfBatchStatsDto, VcfBatchCreationResult};
+use super::super::repositories::contact_numbers::industry_quota;
+use super::super::repositories::contact_numbers::vcf_chunk_export::{self, VcfChunkExportResult};
+use crate::services::vcf::VcfExportOptions;
 use super::common::db_connector::with_db_connection;
 
 /// VCF 批次管理门面
@@ -106,29 +109,55 @@ impl VcfBatchesFacade {
 
     // ==================== 扩展方法（满足repository_facade调用需求） ====================
 
-    /// 创建带号码的VCF批次
+    /// 创建带号码的VCF批次；指定设备时先校验行业配额，通过后记入配额台账
     pub fn create_vcf_batch_with_numbers(
         app_handle: &AppHandle,
         batch_name: &str,
-        number_count: i64,
-        _industry: &str,
-        _device_id: &str,
+        number_ids: &[i64],
+        device_id: Option<&str>,
     ) -> Result<VcfBatchCreationResult, String> {
         Self::with_db_connection(app_handle, |conn| {
+            let tx = conn.unchecked_transaction()?;
+            let quota_usage = match device_id {
+                Some(device_id) => {
+                    let by_industry = industry_quota::industry_counts(&tx, number_ids)?;
+                    industry_quota::enforce_quota(&tx, device_id, &by_industry)?;
+                    Some((device_id, by_industry))
+                }
+                None => None,
+            };
+
             // 创建基础批次
-            VcfBatchRepository::create_vcf_batch(conn, batch_name, "", None, None)?;
+            VcfBatchRepository::create_vcf_batch(&tx, batch_name, "", None, None)?;
             
             // 获取刚创建的批次
-            let batch = VcfBatchRepository::get_vcf_batch(conn, batch_name)?
+            let batch = VcfBatchRepository::get_vcf_batch(&tx, batch_name)?
                 .ok_or_else(|| rusqlite::Error::InvalidColumnName("Failed to retrieve created batch".to_string()))?;
+
+            if let Some((device_id, by_industry)) = quota_usage {
+                industry_quota::record_usage(&tx, device_id, &batch.batch_id, &by_industry)?;
+            }
+            tx.commit()?;
             
             Ok(VcfBatchCreationResult {
                 batch,
-                associated_numbers: number_count
+                associated_numbers: number_ids.len() as i64
             })
         })
     }
 
+    /// 按选中顺序导出号码为 VCF（可分片 / 套用名称模板 / 指定 vCard 版本），每个分片文件记一条批次
+    pub fn export_numbers_to_vcf(
+        app_handle: &AppHandle,
+        number_ids: &[i64],
+        output_path: &str,
+        options: &VcfExportOptions,
+    ) -> Result<VcfChunkExportResult, String> {
+        Self::with_db_connection(app_handle, |conn| {
+            Ok(vcf_chunk_export::export_numbers(conn, number_ids, output_path, options))
+        })?
+    }
+
     /// 按名称搜索VCF批次
     pub fn search_vcf_batches_by_name(
         app_handle: &AppHandle,
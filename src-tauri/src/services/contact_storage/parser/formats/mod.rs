@@ -5,6 +5,8 @@
 pub mod csv_format;
 pub mod plain_format;
 pub mod mixed_text_format;
+pub mod vcard_format;
+pub mod table_format;
 
 use super::types::ParsedContact;
 
@@ -0,0 +1,100 @@
+/// 表格（CSV / Excel）解析策略
+///
+/// 按前端给出的列映射（号码列 / 姓名列 / 行业列）从表格行中提取联系人
+
+use serde::{Deserialize, Serialize};
+
+use super::super::{validators, normalizers};
+
+/// 列映射：列名取自表头（`preview_xlsx_columns` 返回的 headers）
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ColumnMapping {
+    /// 工作表名，默认第一个
+    pub sheet: Option<String>,
+    /// CSV 分隔符，默认逗号（.tsv 默认制表符）
+    #[serde(default)]
+    pub delimiter: Option<char>,
+    pub phone_column: String,
+    pub name_column: Option<String>,
+    pub industry_column: Option<String>,
+}
+
+/// 表格行解析出的联系人
+#[derive(Debug, Clone, PartialEq)]
+pub struct TableContact {
+    pub phone: String,
+    pub name: String,
+    pub industry: Option<String>,
+}
+
+fn column_index(headers: &[String], column: &str) -> Option<usize> {
+    let wanted = column.trim();
+    headers.iter().position(|h| h.trim() == wanted)
+}
+
+/// 解析表格行，返回（联系人, 无效行数）；映射的列不存在时报错
+pub fn parse_table_rows(
+    headers: &[String],
+    rows: &[Vec<String>],
+    mapping: &ColumnMapping,
+) -> Result<(Vec<TableContact>, usize), String> {
+    let locate = |column: &str| column_index(headers, column).ok_or_else(|| format!("表头中找不到列: {}", column));
+    let phone_idx = locate(&mapping.phone_column)?;
+    let name_idx = mapping.name_column.as_deref().filter(|c| !c.trim().is_empty()).map(locate).transpose()?;
+    let industry_idx = mapping.industry_column.as_deref().filter(|c| !c.trim().is_empty()).map(locate).transpose()?;
+
+    let cell = |row: &Vec<String>, idx: usize| row.get(idx).map(|s| s.trim().to_string()).unwrap_or_default();
+    let mut contacts = Vec::new();
+    let mut invalid = 0;
+    for row in rows {
+        let raw = cell(row, phone_idx);
+        if raw.is_empty() && row.iter().all(|c| c.trim().is_empty()) {
+            continue; // 空行不计为无效
+        }
+        // Excel 常把号码存成数字，读出来可能带 ".0"
+        let digits = normalizers::clean_phone_number(raw.strip_suffix(".0").unwrap_or(&raw));
+        if !validators::is_valid_phone_number(&digits) {
+            invalid += 1;
+            continue;
+        }
+        contacts.push(TableContact {
+            phone: normalizers::normalize_phone_number(&digits),
+            name: name_idx.map(|i| cell(row, i)).unwrap_or_default(),
+            industry: industry_idx.map(|i| cell(row, i)).filter(|s| !s.is_empty()),
+        });
+    }
+    Ok((contacts, invalid))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_table_rows_with_mapping() {
+        let headers = vec!["姓名".to_string(), "手机".to_string(), "行业".to_string()];
+        let rows = vec![
+            vec!["张经理".into(), "13912345678.0".into(), "餐饮".into()],
+            vec!["李总".into(), "+86 158-0015-8001".into(), "".into()],
+            vec!["王五".into(), "12345".into(), "教育".into()],
+            vec!["".into(), "".into(), "".into()],
+        ];
+        let mapping = ColumnMapping {
+            phone_column: "手机".into(),
+            name_column: Some("姓名".into()),
+            industry_column: Some("行业".into()),
+            ..Default::default()
+        };
+
+        let (contacts, invalid) = parse_table_rows(&headers, &rows, &mapping).unwrap();
+        assert_eq!(invalid, 1);
+        assert_eq!(contacts.len(), 2);
+        assert_eq!(contacts[0].industry.as_deref(), Some("餐饮"));
+        assert_eq!(contacts[1].phone, "15800158001");
+        assert_eq!(contacts[1].industry, None);
+
+        let missing = ColumnMapping { phone_column: "电话".into(), ..Default::default() };
+        assert!(parse_table_rows(&headers, &rows, &missing).is_err());
+    }
+}
@@ -0,0 +1,130 @@
+/// vCard 格式解析策略
+///
+/// 把已有的 .vcf 文件反向解析为联系人号码（兼容 2.1 / 3.0 / 4.0 的常见写法）
+
+use super::super::{validators, normalizers};
+use super::super::types::ParsedContact;
+
+/// 展开折行：以空格或制表符开头的行是上一行的续行；
+/// 2.1 版 QUOTED-PRINTABLE 以 `=` 结尾的行同样与下一行拼接
+fn unfold_lines(content: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    let mut soft_break = false;
+    for raw in content.lines() {
+        let line = raw.trim_end_matches('\r');
+        match lines.last_mut() {
+            Some(last) if soft_break => last.push_str(line.trim_start()),
+            Some(last) if line.starts_with(' ') || line.starts_with('\t') => last.push_str(&line[1..]),
+            _ => lines.push(line.to_string()),
+        }
+        soft_break = lines.last().is_some_and(|l| l.to_ascii_uppercase().contains("QUOTED-PRINTABLE") && l.ends_with('='));
+        if soft_break {
+            if let Some(last) = lines.last_mut() {
+                last.pop();
+            }
+        }
+    }
+    lines
+}
+
+/// 解码 QUOTED-PRINTABLE（`=E5=BC=A0` → 张）
+fn decode_quoted_printable(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'=' && i + 2 < bytes.len() {
+            let hex = |b: u8| (b as char).to_digit(16);
+            if let (Some(hi), Some(lo)) = (hex(bytes[i + 1]), hex(bytes[i + 2])) {
+                out.push((hi * 16 + lo) as u8);
+                i += 3;
+                continue;
+            }
+        }
+        out.push(bytes[i]);
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+/// 拆出 `属性名;参数:值`，返回（大写属性名, 大写参数串, 值）
+fn split_property(line: &str) -> Option<(String, String, String)> {
+    let colon = line.find(':')?;
+    let (head, value) = (&line[..colon], &line[colon + 1..]);
+    let mut parts = head.splitn(2, ';');
+    // 去掉分组前缀（item1.TEL）
+    let name = parts.next()?.rsplit('.').next()?.to_ascii_uppercase();
+    let params = parts.next().unwrap_or("").to_ascii_uppercase();
+    let value = if params.contains("QUOTED-PRINTABLE") { decode_quoted_printable(value) } else { value.to_string() };
+    Some((name, params, value.trim().to_string()))
+}
+
+/// 结构化姓名 `N:姓;名;中间名;前缀;后缀` → `姓名`
+fn name_from_structured(value: &str) -> String {
+    let parts: Vec<&str> = value.split(';').map(str::trim).collect();
+    format!("{}{}", parts.first().unwrap_or(&""), parts.get(1).unwrap_or(&""))
+}
+
+/// 解析 vCard 文本，每个有效的 TEL 生成一条联系人（同一张名片的多个号码共用姓名）
+pub fn parse_vcard_text(content: &str) -> Vec<ParsedContact> {
+    let mut contacts = Vec::new();
+    let mut in_card = false;
+    let mut full_name = String::new();
+    let mut structured_name = String::new();
+    let mut phones: Vec<String> = Vec::new();
+
+    for line in unfold_lines(content) {
+        let Some((name, _params, value)) = split_property(&line) else { continue };
+        match name.as_str() {
+            "BEGIN" if value.eq_ignore_ascii_case("VCARD") => {
+                in_card = true;
+                full_name.clear();
+                structured_name.clear();
+                phones.clear();
+            }
+            "END" if value.eq_ignore_ascii_case("VCARD") && in_card => {
+                in_card = false;
+                let display = if full_name.is_empty() { structured_name.clone() } else { full_name.clone() };
+                for phone in phones.drain(..) {
+                    contacts.push((phone, display.clone()));
+                }
+            }
+            "FN" if in_card => full_name = value,
+            "N" if in_card => structured_name = name_from_structured(&value),
+            "TEL" if in_card => {
+                let digits = normalizers::clean_phone_number(value.trim_start_matches("tel:"));
+                if validators::is_valid_phone_number(&digits) {
+                    let phone = normalizers::normalize_phone_number(&digits);
+                    if !phones.contains(&phone) {
+                        phones.push(phone);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    contacts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_vcard_versions() {
+        let content = "BEGIN:VCARD\r\nVERSION:3.0\r\nFN:张经理\r\nTEL;TYPE=CELL:+86 139-1234-5678\r\nTEL;TYPE=WORK:13912345678\r\nitem1.TEL:15800158001\r\nEND:VCARD\r\n\
+BEGIN:VCARD\nVERSION:2.1\nN;CHARSET=UTF-8;ENCODING=QUOTED-PRINTABLE:=E6=9D=8E;=E6=80=BB;;;\nTEL;CELL:1370013\n 8000\nEND:VCARD\n\
+BEGIN:VCARD\nVERSION:4.0\nFN:无效\nTEL;VALUE=uri:tel:12345\nEND:VCARD\n";
+
+        let result = parse_vcard_text(content);
+        assert_eq!(
+            result,
+            vec![
+                ("13912345678".to_string(), "张经理".to_string()),
+                ("15800158001".to_string(), "张经理".to_string()),
+                ("13700138000".to_string(), "李总".to_string()),
+            ]
+        );
+    }
+}
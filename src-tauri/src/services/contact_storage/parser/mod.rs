@@ -7,7 +7,9 @@ pub mod validators;
 pub mod normalizers;
 pub mod deduplicator;
 pub mod formats;
+pub mod readers;
 
+use std::collections::HashMap;
 use types::{ParseStats, ParseResult};
 use deduplicator::deduplicate_by_phone;
 use formats::ContactFormatParser;
@@ -60,6 +62,53 @@ pub fn extract_numbers_from_text(content: &str) -> ParseResult {
     ParseResult { contacts, stats }
 }
 
+/// 从 vCard 文本中提取联系人号码（反向解析已有的 .vcf 文件）
+pub fn extract_numbers_from_vcard(content: &str) -> ParseResult {
+    let contacts = formats::vcard_format::parse_vcard_text(content);
+    let before_dedup = contacts.len();
+    let contacts = deduplicate_by_phone(contacts);
+    let after_dedup = contacts.len();
+
+    let stats = ParseStats {
+        total_lines: before_dedup,
+        parsed_count: after_dedup,
+        invalid_count: 0,
+        duplicate_count: before_dedup.saturating_sub(after_dedup),
+    };
+
+    ParseResult { contacts, stats }
+}
+
+/// 按列映射从表格（CSV / Excel）中提取联系人号码，同时返回 号码 → 行业 的映射
+pub fn extract_numbers_from_table(
+    headers: &[String],
+    rows: &[Vec<String>],
+    mapping: &formats::table_format::ColumnMapping,
+) -> Result<(ParseResult, HashMap<String, String>), String> {
+    let (table_contacts, invalid_count) = formats::table_format::parse_table_rows(headers, rows, mapping)?;
+    let before_dedup = table_contacts.len();
+
+    let mut industries = HashMap::new();
+    let mut contacts = Vec::with_capacity(table_contacts.len());
+    for contact in table_contacts {
+        if let Some(industry) = contact.industry {
+            industries.entry(contact.phone.clone()).or_insert(industry);
+        }
+        contacts.push((contact.phone, contact.name));
+    }
+    let contacts = deduplicate_by_phone(contacts);
+    let after_dedup = contacts.len();
+
+    let stats = ParseStats {
+        total_lines: before_dedup + invalid_count,
+        parsed_count: after_dedup,
+        invalid_count,
+        duplicate_count: before_dedup.saturating_sub(after_dedup),
+    };
+
+    Ok((ParseResult { contacts, stats }, industries))
+}
+
 /// 贪婪解析：尝试所有策略并合并结果
 /// 
 /// 适用于格式混合的复杂文本
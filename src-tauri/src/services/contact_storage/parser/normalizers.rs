@@ -2,11 +2,16 @@
 /// 
 /// 提供号码清理、格式化、标准化功能
 
-/// 规范化手机号码（去除国家码，保留11位）
+/// 规范化手机号码（去除国家码 / 国际前缀 / 长途前缀，保留11位）
 /// 
-/// **处理规则：**
+/// **处理规则（依次执行）：**
+/// - 全角数字转半角，去除空格、破折号、括号等分隔符
+/// - `0086` 开头的15位 → 去掉国际前缀
 /// - 13位且以86开头 → 去掉国家码
-/// - 其他情况 → 提取纯数字
+/// - 12位且以01开头（手机号前误加长途前缀0）→ 去掉0
+/// - 其他情况 → 保留纯数字
+/// 
+/// 入库、去重、核验统一使用该规则，`+86 139-1234-5678`、`0086 13912345678`、`１３９１２３４５６７８` 都得到同一号码
 /// 
 /// # Examples
 /// 
@@ -17,21 +22,36 @@
 /// assert_eq!(normalize_phone_number("139-1234-5678"), "13912345678");
 /// ```
 pub fn normalize_phone_number(phone: &str) -> String {
-    let digits: String = phone.chars().filter(|c| c.is_ascii_digit()).collect();
+    let digits = clean_phone_number(phone);
     
+    // 0086 国际前缀
+    if digits.len() == 15 && digits.starts_with("0086") {
+        return digits[4..].to_string();
+    }
     // 如果是13位且以86开头，去掉国家码
     if digits.len() == 13 && digits.starts_with("86") {
         return digits[2..].to_string();
     }
+    // 手机号前多出的长途前缀 0
+    if digits.len() == 12 && digits.starts_with("01") {
+        return digits[1..].to_string();
+    }
     
     digits
 }
 
 /// 清理号码：去除所有分隔符和空格
 /// 
-/// 保留数字，移除：空格、破折号、括号、点号等
+/// 保留数字（全角数字转为半角），移除：空格、破折号、括号、点号等
 pub fn clean_phone_number(phone: &str) -> String {
-    phone.chars().filter(|c| c.is_ascii_digit()).collect()
+    phone
+        .chars()
+        .filter_map(|c| match c {
+            '0'..='9' => Some(c),
+            '０'..='９' => char::from_u32(c as u32 - '０' as u32 + '0' as u32),
+            _ => None,
+        })
+        .collect()
 }
 
 /// 从混合文本中提取纯号码
@@ -74,6 +94,11 @@ mod tests {
         assert_eq!(normalize_phone_number("8613912345678"), "13912345678");
         assert_eq!(normalize_phone_number("139-1234-5678"), "13912345678");
         assert_eq!(normalize_phone_number("(139) 1234-5678"), "13912345678");
+        assert_eq!(normalize_phone_number("0086 139 1234 5678"), "13912345678");
+        assert_eq!(normalize_phone_number("013912345678"), "13912345678");
+        assert_eq!(normalize_phone_number("１３９ １２３４ ５６７８"), "13912345678");
+        // 固话 / 短号不做改写
+        assert_eq!(normalize_phone_number("01012345678"), "01012345678");
     }
     
     #[test]
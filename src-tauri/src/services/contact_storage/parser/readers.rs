@@ -0,0 +1,247 @@
+/// 号码文件读取
+///
+/// 按扩展名识别 TXT / CSV / Excel(xlsx) / vCard，表格类文件按列映射解析（未给映射时按表头自动推断），
+/// 导入与预览共用同一套解析逻辑
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+
+use super::formats::table_format::{self, ColumnMapping};
+use super::types::ParseResult;
+use super::{extract_numbers_from_table, extract_numbers_from_text, extract_numbers_from_vcard, normalizers, validators};
+use crate::services::prospecting::prospecting_import_readers::{read_table, ImportFormat, RawTable, ReadOptions};
+
+/// 推断号码列时抽样的行数
+const SAMPLE_ROWS: usize = 20;
+
+const PHONE_HEADERS: &[&str] = &["手机", "电话", "号码", "phone", "mobile", "tel"];
+const NAME_HEADERS: &[&str] = &["姓名", "名称", "联系人", "客户", "name"];
+const INDUSTRY_HEADERS: &[&str] = &["行业", "industry"];
+
+/// 号码文件格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ContactFileFormat {
+    Txt,
+    Csv,
+    Xlsx,
+    Vcf,
+}
+
+impl ContactFileFormat {
+    /// 按扩展名识别，未知扩展名按 TXT 智能解析
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|e| e.to_str()).map(str::to_lowercase).as_deref() {
+            Some("csv") | Some("tsv") => Self::Csv,
+            Some("xlsx") | Some("xlsm") => Self::Xlsx,
+            Some("vcf") => Self::Vcf,
+            _ => Self::Txt,
+        }
+    }
+
+    pub fn is_table(self) -> bool {
+        matches!(self, Self::Csv | Self::Xlsx)
+    }
+}
+
+/// 预览中的一行
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContactPreviewRow {
+    pub phone: String,
+    pub name: String,
+    pub industry: Option<String>,
+}
+
+/// 文件预览：前 N 条解析结果 + 全文件统计，表格文件附带表头与实际使用的列映射
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContactFilePreview {
+    pub format: ContactFileFormat,
+    pub headers: Vec<String>,
+    pub mapping: Option<ColumnMapping>,
+    /// 映射是否由表头自动推断
+    pub mapping_inferred: bool,
+    pub rows: Vec<ContactPreviewRow>,
+    pub total_lines: usize,
+    pub parsed_count: usize,
+    pub invalid_count: usize,
+    pub duplicate_count: usize,
+    /// CSV 实际使用的编码
+    pub encoding: Option<String>,
+}
+
+/// 解析后的号码文件
+pub struct ParsedContactFile {
+    pub format: ContactFileFormat,
+    /// 原始行数（TXT 为行数，vCard 为名片数，表格为数据行数）
+    pub total_lines: usize,
+    pub result: ParseResult,
+    /// 号码 → 行业（仅表格文件的行业列）
+    pub industries: HashMap<String, String>,
+    pub table: Option<(RawTable, ColumnMapping, bool)>,
+}
+
+fn is_phone_cell(cell: &str) -> bool {
+    let cell = cell.trim();
+    validators::is_valid_phone_number(&normalizers::clean_phone_number(cell.strip_suffix(".0").unwrap_or(cell)))
+}
+
+/// 读取表格；首行本身就是号码数据（无表头的 CSV）时生成「列1、列2…」表头
+pub fn read_contact_table(path: &Path, format: ContactFileFormat, mapping: Option<&ColumnMapping>) -> Result<RawTable, String> {
+    let import_format = match format {
+        ContactFileFormat::Csv => ImportFormat::Csv,
+        ContactFileFormat::Xlsx => ImportFormat::Xlsx,
+        _ => return Err(format!("{:?} 不是表格文件", format)),
+    };
+    let options = ReadOptions {
+        format: Some(import_format),
+        sheet: mapping.and_then(|m| m.sheet.clone()),
+        delimiter: mapping.and_then(|m| m.delimiter),
+        ..Default::default()
+    };
+    let mut table = read_table(path, &options).map_err(|e| format!("读取表格失败: {}", e))?;
+    if table.headers.iter().any(|h| is_phone_cell(h)) {
+        let first = std::mem::take(&mut table.headers);
+        table.headers = (1..=first.len()).map(|i| format!("列{}", i)).collect();
+        table.rows.insert(0, first);
+    }
+    Ok(table)
+}
+
+fn header_matching(headers: &[String], keywords: &[&str], exclude: Option<&str>) -> Option<String> {
+    headers
+        .iter()
+        .filter(|h| Some(h.as_str()) != exclude)
+        .find(|h| {
+            let lower = h.to_lowercase();
+            keywords.iter().any(|k| lower.contains(k))
+        })
+        .cloned()
+}
+
+/// 按表头关键字推断列映射；号码列找不到关键字时取抽样中有效号码最多的列
+pub fn infer_mapping(table: &RawTable) -> Option<ColumnMapping> {
+    let phone_column = header_matching(&table.headers, PHONE_HEADERS, None).or_else(|| {
+        let sample = &table.rows[..table.rows.len().min(SAMPLE_ROWS)];
+        (0..table.headers.len())
+            .map(|i| (i, sample.iter().filter(|row| row.get(i).is_some_and(|c| is_phone_cell(c))).count()))
+            .filter(|(_, hits)| *hits > 0)
+            .max_by_key(|(i, hits)| (*hits, std::cmp::Reverse(*i)))
+            .map(|(i, _)| table.headers[i].clone())
+    })?;
+    let name_column = header_matching(&table.headers, NAME_HEADERS, Some(phone_column.as_str())).or_else(|| {
+        // 无表头的「姓名,号码」文件：号码列之外的第一列视为姓名
+        table.headers.iter().find(|h| h.starts_with('列') && **h != phone_column).cloned()
+    });
+    let industry_column = header_matching(&table.headers, INDUSTRY_HEADERS, Some(phone_column.as_str()));
+    Some(ColumnMapping { sheet: None, delimiter: None, phone_column, name_column, industry_column })
+}
+
+/// 解析号码文件；表格文件使用给定映射，未给时自动推断
+pub fn parse_contact_file(path: &Path, mapping: Option<ColumnMapping>) -> Result<ParsedContactFile, String> {
+    if !path.exists() {
+        return Err(format!("文件不存在: {}", path.display()));
+    }
+    let format = ContactFileFormat::from_path(path);
+    if format.is_table() {
+        let table = read_contact_table(path, format, mapping.as_ref())?;
+        let (mapping, inferred) = match mapping.filter(|m| !m.phone_column.trim().is_empty()) {
+            Some(mapping) => (mapping, false),
+            None => (infer_mapping(&table).ok_or("无法识别号码列，请手动指定列映射")?, true),
+        };
+        let (result, industries) = extract_numbers_from_table(&table.headers, &table.rows, &mapping)?;
+        return Ok(ParsedContactFile { format, total_lines: table.rows.len(), result, industries, table: Some((table, mapping, inferred)) });
+    }
+
+    let bytes = std::fs::read(path).map_err(|e| format!("读取文件失败: {}", e))?;
+    let content = String::from_utf8_lossy(&bytes);
+    let (total_lines, result) = match format {
+        ContactFileFormat::Vcf => (content.matches("BEGIN:VCARD").count(), extract_numbers_from_vcard(&content)),
+        _ => (content.lines().count(), extract_numbers_from_text(&content)),
+    };
+    Ok(ParsedContactFile { format, total_lines, result, industries: HashMap::new(), table: None })
+}
+
+/// 预览号码文件：返回前 `limit` 条解析结果，不写入数据库
+pub fn preview_contact_file(path: &Path, mapping: Option<ColumnMapping>, limit: usize) -> Result<ContactFilePreview, String> {
+    let parsed = parse_contact_file(path, mapping)?;
+    let rows = match &parsed.table {
+        // 表格按原始行顺序预览，保留行业列
+        Some((table, mapping, _)) => {
+            let (contacts, _) = table_format::parse_table_rows(&table.headers, &table.rows, mapping)?;
+            contacts
+                .into_iter()
+                .take(limit)
+                .map(|c| ContactPreviewRow { phone: c.phone, name: c.name, industry: c.industry })
+                .collect()
+        }
+        None => parsed
+            .result
+            .contacts
+            .iter()
+            .take(limit)
+            .map(|(phone, name)| ContactPreviewRow { phone: phone.clone(), name: name.clone(), industry: None })
+            .collect(),
+    };
+    let stats = &parsed.result.stats;
+    let (headers, mapping, mapping_inferred, encoding) = match parsed.table {
+        Some((table, mapping, inferred)) => (table.headers, Some(mapping), inferred, table.encoding),
+        None => (Vec::new(), None, false, None),
+    };
+    Ok(ContactFilePreview {
+        format: parsed.format,
+        headers,
+        mapping,
+        mapping_inferred,
+        rows,
+        total_lines: parsed.total_lines,
+        parsed_count: stats.parsed_count,
+        invalid_count: stats.invalid_count,
+        duplicate_count: stats.duplicate_count,
+        encoding,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_temp(dir: &tempfile::TempDir, name: &str, content: &[u8]) -> std::path::PathBuf {
+        let path = dir.path().join(name);
+        std::fs::write(&path, content).unwrap();
+        path
+    }
+
+    #[test]
+    fn previews_csv_with_inferred_and_explicit_mapping() {
+        let csv = "客户名称,联系电话,所属行业\n张经理,13912345678,餐饮\n李总,158-0015-8001,\n王五,12345,教育\n赵六,13912345678,零售\n";
+        let dir = tempfile::tempdir().unwrap();
+        let path = write_temp(&dir, "contacts.csv", csv.as_bytes());
+
+        let preview = preview_contact_file(&path, None, 2).unwrap();
+        assert_eq!(preview.format, ContactFileFormat::Csv);
+        assert!(preview.mapping_inferred);
+        let mapping = preview.mapping.clone().unwrap();
+        assert_eq!(mapping.phone_column, "联系电话");
+        assert_eq!(mapping.name_column.as_deref(), Some("客户名称"));
+        assert_eq!(mapping.industry_column.as_deref(), Some("所属行业"));
+        assert_eq!(preview.rows.len(), 2);
+        assert_eq!(preview.rows[0].industry.as_deref(), Some("餐饮"));
+        assert_eq!(preview.rows[1].phone, "15800158001");
+        assert_eq!((preview.total_lines, preview.parsed_count, preview.invalid_count, preview.duplicate_count), (4, 2, 1, 1));
+
+        let explicit = ColumnMapping { phone_column: "联系电话".into(), ..Default::default() };
+        let preview = preview_contact_file(&path, Some(explicit), 10).unwrap();
+        assert!(!preview.mapping_inferred);
+        assert_eq!(preview.rows[0].name, "");
+
+        // 无表头的「姓名,号码」CSV
+        let path = write_temp(&dir, "plain.csv", "张三,13912345678\n李四,13823456789\n".as_bytes());
+        let preview = preview_contact_file(&path, None, 10).unwrap();
+        assert_eq!(preview.headers, vec!["列1", "列2"]);
+        assert_eq!(preview.rows[0], ContactPreviewRow { phone: "13912345678".into(), name: "张三".into(), industry: None });
+        assert_eq!(preview.parsed_count, 2);
+    }
+}
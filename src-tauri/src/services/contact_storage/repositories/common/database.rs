@@ -1,16 +1,15 @@
 use rusqlite::{Connection, Result as SqliteResult};
-use tauri::{AppHandle, Manager};
+use tauri::{AppHandle, Manager, Runtime};
 use super::schema;
 use crate::infrastructure::database as shared_db;
 
 /// 公共数据库连接管理
 /// 提供统一的数据库连接获取和错误处理
 
-/// 获取数据库连接
-pub fn get_connection(app_handle: &AppHandle) -> SqliteResult<Connection> {
-    // 使用 Tauri 推荐的方式获取应用数据目录
-    // 开发环境：项目根目录/src-tauri/data/
-    // 生产环境：系统应用数据目录
+/// 联系人数据库文件路径
+///
+/// 开发环境：项目根目录/src-tauri/data/；生产环境：系统应用数据目录
+pub fn contacts_db_path<R: Runtime>(app_handle: &AppHandle<R>) -> std::path::PathBuf {
     let db_dir = if cfg!(debug_assertions) {
         // 开发环境：使用项目根目录的 src-tauri/data/
         let manifest_dir = std::env::var("CARGO_MANIFEST_DIR")
@@ -26,7 +25,12 @@ pub fn get_connection(app_handle: &AppHandle) -> SqliteResult<Connection> {
     
     std::fs::create_dir_all(&db_dir).expect("failed to create data dir");
     
-    let db_path = db_dir.join("employees.db");
+    db_dir.join("employees.db")
+}
+
+/// 获取数据库连接
+pub fn get_connection(app_handle: &AppHandle) -> SqliteResult<Connection> {
+    let db_path = contacts_db_path(app_handle);
     
     tracing::debug!("尝试连接数据库: {:?}", db_path);
     
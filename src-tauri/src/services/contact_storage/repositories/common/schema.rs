@@ -35,10 +35,19 @@ pub fn init_contact_storage_tables(conn: &Connection) -> SqliteResult<()> {
     
     // 创建TXT文件导入记录表
     create_txt_import_records_table(conn)?;
+
+    // 创建文件夹导入会话表（断点续导）
+    crate::services::contact_storage::repositories::folder_import_sessions_repo::create_folder_import_tables(conn)?;
+
+    // 创建行业配额规则与用量台账表
+    crate::services::contact_storage::repositories::contact_numbers::industry_quota::create_quota_tables(conn)?;
     
     // 执行数据库迁移
     migrate_contact_numbers_table(conn)?;
 
+    // 微信注册核验结果列（需在 phone 列重建之后执行）
+    crate::services::contact_storage::repositories::contact_numbers::wechat_check::ensure_wechat_check_columns(conn)?;
+
     tracing::info!("✅ 数据库表初始化完成");
     Ok(())
 }
@@ -0,0 +1,406 @@
+/// 号码分配规划：把可用号码按设备容量拆分到多台设备
+///
+/// 规划只读不写，返回可审阅的方案；操作员确认后把（可能调整过的）方案交回执行，
+/// 执行时在单个事务内重新校验号码状态 / 容量 / 历史导入 / 行业配额，任何冲突都整体回滚。
+use rusqlite::{params, Connection, Result as SqlResult};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use super::industry_quota;
+
+/// (设备, 行业) → 剩余配额
+pub type QuotaLimits = HashMap<(String, String), i64>;
+
+/// 设备分配约束
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeviceAllocationSpec {
+    pub device_id: String,
+    /// 本次最多分配的号码数
+    pub capacity: i64,
+    /// 行业偏好；为空表示不限行业
+    #[serde(default)]
+    pub industries: Vec<String>,
+}
+
+/// 规划请求
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AllocationPlanRequest {
+    pub devices: Vec<DeviceAllocationSpec>,
+    /// 只在这些号码中分配；为空则使用全部可用号码
+    #[serde(default)]
+    pub number_ids: Option<Vec<i64>>,
+    /// 本次最多分配的总数
+    #[serde(default)]
+    pub limit: Option<i64>,
+    /// 严格行业约束：有行业偏好的设备只接收对应行业的号码
+    #[serde(default)]
+    pub strict_affinity: bool,
+}
+
+/// 单台设备的分配结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlannedDeviceAllocation {
+    pub device_id: String,
+    pub capacity: i64,
+    pub number_ids: Vec<i64>,
+    /// 行业 → 数量（未分类记为空字符串）
+    pub by_industry: BTreeMap<String, i64>,
+}
+
+/// 可审阅的分配方案
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AllocationPlan {
+    pub plan_id: String,
+    pub created_at: String,
+    pub allocations: Vec<PlannedDeviceAllocation>,
+    pub candidate_count: i64,
+    pub unallocated_count: i64,
+    /// 因曾导入到对应设备而避开的（号码, 设备）组合数
+    pub avoided_reimports: i64,
+    /// 本次规划中配额已用尽的设备 / 行业说明
+    #[serde(default)]
+    pub quota_limited: Vec<String>,
+}
+
+/// 方案执行结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AllocationExecutionResult {
+    pub plan_id: String,
+    /// 设备 → 新建的批次ID
+    pub batches: BTreeMap<String, String>,
+    pub assigned: i64,
+}
+
+/// 参与规划的候选号码
+#[derive(Debug, Clone)]
+pub struct AllocationCandidate {
+    pub id: i64,
+    pub industry: Option<String>,
+    /// 该号码曾导入过的设备
+    pub imported_devices: HashSet<String>,
+}
+
+fn affinity(device: &DeviceAllocationSpec, industry: Option<&str>) -> bool {
+    industry.is_some_and(|i| device.industries.iter().any(|d| d == i))
+}
+
+/// 纯规划算法：
+/// 1. 先处理能命中某台设备行业偏好的号码，只在命中的设备之间分配；
+/// 2. 其余号码（及偏好设备已满的号码）分配给不限行业的设备，非严格模式下再退回到任意设备；
+/// 每一步都选当前已分配数最少的设备（同数时选剩余容量大的），从而在约束下尽量均衡。
+/// 有行业配额的 (设备, 行业) 组合分配数不超过 `quotas` 中的剩余量。
+pub fn plan_allocation(
+    devices: &[DeviceAllocationSpec],
+    candidates: &[AllocationCandidate],
+    limit: Option<i64>,
+    strict_affinity: bool,
+    quotas: &QuotaLimits,
+) -> (Vec<PlannedDeviceAllocation>, i64) {
+    let mut assigned: Vec<Vec<&AllocationCandidate>> = vec![Vec::new(); devices.len()];
+    let mut quota_used: HashMap<(usize, String), i64> = HashMap::new();
+    let mut remaining_total = limit.unwrap_or(i64::MAX);
+
+    let (preferred, others): (Vec<&AllocationCandidate>, Vec<&AllocationCandidate>) = candidates
+        .iter()
+        .partition(|c| devices.iter().any(|d| affinity(d, c.industry.as_deref())));
+
+    let mut place = |candidate: &AllocationCandidate, eligible: &dyn Fn(&DeviceAllocationSpec) -> bool| -> bool {
+        let mut best: Option<usize> = None;
+        for (idx, device) in devices.iter().enumerate() {
+            if !eligible(device) || assigned[idx].len() as i64 >= device.capacity {
+                continue;
+            }
+            if candidate.imported_devices.contains(&device.device_id) {
+                continue;
+            }
+            if let Some(industry) = candidate.industry.as_deref() {
+                let left = quotas.get(&(device.device_id.clone(), industry.to_string()));
+                if left.is_some_and(|left| quota_used.get(&(idx, industry.to_string())).copied().unwrap_or(0) >= *left) {
+                    continue;
+                }
+            }
+            let better = match best {
+                None => true,
+                Some(b) => {
+                    let (count, best_count) = (assigned[idx].len(), assigned[b].len());
+                    count < best_count
+                        || (count == best_count
+                            && device.capacity - count as i64 > devices[b].capacity - best_count as i64)
+                }
+            };
+            if better {
+                best = Some(idx);
+            }
+        }
+        match best {
+            Some(idx) => {
+                if let Some(industry) = candidate.industry.clone() {
+                    *quota_used.entry((idx, industry)).or_insert(0) += 1;
+                }
+                assigned[idx].push(candidate);
+                true
+            }
+            None => false,
+        }
+    };
+
+    let mut unallocated = 0;
+    for candidate in preferred {
+        if remaining_total <= 0 {
+            unallocated += 1;
+            continue;
+        }
+        let industry = candidate.industry.as_deref();
+        let placed = place(candidate, &|d| affinity(d, industry))
+            || place(candidate, &|d| d.industries.is_empty())
+            || (!strict_affinity && place(candidate, &|_| true));
+        if placed {
+            remaining_total -= 1;
+        } else {
+            unallocated += 1;
+        }
+    }
+    for candidate in others {
+        if remaining_total <= 0 {
+            unallocated += 1;
+            continue;
+        }
+        // 优先给不限行业的设备，避免占用有偏好设备的容量
+        let placed = place(candidate, &|d| d.industries.is_empty()) || (!strict_affinity && place(candidate, &|_| true));
+        if placed {
+            remaining_total -= 1;
+        } else {
+            unallocated += 1;
+        }
+    }
+
+    let allocations = devices
+        .iter()
+        .zip(assigned)
+        .map(|(device, numbers)| {
+            let mut by_industry = BTreeMap::new();
+            for n in &numbers {
+                *by_industry.entry(n.industry.clone().unwrap_or_default()).or_insert(0) += 1;
+            }
+            PlannedDeviceAllocation {
+                device_id: device.device_id.clone(),
+                capacity: device.capacity,
+                number_ids: numbers.iter().map(|n| n.id).collect(),
+                by_industry,
+            }
+        })
+        .collect();
+    (allocations, unallocated)
+}
+
+/// 读取可用号码及其历史导入设备（同号码在其他来源文件中的导入记录同样计入）
+fn load_candidates(conn: &Connection, number_ids: Option<&[i64]>) -> SqlResult<Vec<AllocationCandidate>> {
+    let mut stmt = conn.prepare(
+        "SELECT c.id, c.industry,
+                (SELECT GROUP_CONCAT(DISTINCT h.imported_device_id) FROM contact_numbers h
+                 WHERE h.phone = c.phone AND h.imported_device_id IS NOT NULL AND h.imported_device_id != '')
+         FROM contact_numbers c
+         WHERE c.status = 'available'
+         ORDER BY c.id",
+    )?;
+    let wanted: Option<HashSet<i64>> = number_ids.map(|ids| ids.iter().copied().collect());
+    let rows = stmt.query_map([], |row| {
+        let devices: Option<String> = row.get(2)?;
+        Ok(AllocationCandidate {
+            id: row.get(0)?,
+            industry: row.get::<_, Option<String>>(1)?.filter(|s| !s.trim().is_empty()),
+            imported_devices: devices
+                .map(|d| d.split(',').map(|s| s.to_string()).collect())
+                .unwrap_or_default(),
+        })
+    })?;
+    let mut candidates = Vec::new();
+    for row in rows {
+        let candidate = row?;
+        if wanted.as_ref().map_or(true, |w| w.contains(&candidate.id)) {
+            candidates.push(candidate);
+        }
+    }
+    Ok(candidates)
+}
+
+/// 生成分配方案（只读）
+pub fn build_plan(conn: &Connection, request: &AllocationPlanRequest) -> SqlResult<AllocationPlan> {
+    let candidates = load_candidates(conn, request.number_ids.as_deref())?;
+    let device_ids: Vec<String> = request.devices.iter().map(|d| d.device_id.clone()).collect();
+    let industries: Vec<String> =
+        candidates.iter().filter_map(|c| c.industry.clone()).collect::<HashSet<_>>().into_iter().collect();
+    let quotas = industry_quota::remaining_quotas(conn, &device_ids, &industries)?;
+    let (allocations, unallocated_count) =
+        plan_allocation(&request.devices, &candidates, request.limit, request.strict_affinity, &quotas);
+    let mut quota_limited: Vec<String> = allocations
+        .iter()
+        .flat_map(|a| {
+            let quotas = &quotas;
+            a.by_industry.iter().filter_map(move |(industry, count)| {
+                let left = *quotas.get(&(a.device_id.clone(), industry.clone()))?;
+                (*count >= left).then(|| format!("设备 {} 的 {} 行业配额已用尽（本次可分配 {}）", a.device_id, industry, left))
+            })
+        })
+        .collect();
+    quota_limited.sort();
+    let avoided_reimports = candidates
+        .iter()
+        .map(|c| request.devices.iter().filter(|d| c.imported_devices.contains(&d.device_id)).count() as i64)
+        .sum();
+    Ok(AllocationPlan {
+        plan_id: uuid::Uuid::new_v4().to_string(),
+        created_at: chrono::Utc::now().to_rfc3339(),
+        allocations,
+        candidate_count: candidates.len() as i64,
+        unallocated_count,
+        avoided_reimports,
+        quota_limited,
+    })
+}
+
+fn conflict(message: String) -> rusqlite::Error {
+    rusqlite::Error::ToSqlConversionFailure(message.into())
+}
+
+/// 执行（审阅后的）方案：单事务内校验并为每台设备建立批次、标记号码为已分配
+pub fn execute_plan(conn: &Connection, plan: &AllocationPlan) -> SqlResult<AllocationExecutionResult> {
+    let mut seen = HashSet::new();
+    for allocation in &plan.allocations {
+        if allocation.number_ids.len() as i64 > allocation.capacity {
+            return Err(conflict(format!("设备 {} 分配数超过容量 {}", allocation.device_id, allocation.capacity)));
+        }
+        if let Some(dup) = allocation.number_ids.iter().find(|id| !seen.insert(**id)) {
+            return Err(conflict(format!("号码 {} 被分配给了多台设备", dup)));
+        }
+    }
+
+    let all_ids: Vec<i64> = seen.into_iter().collect();
+    let current: HashMap<i64, AllocationCandidate> =
+        load_candidates(conn, Some(&all_ids))?.into_iter().map(|c| (c.id, c)).collect();
+
+    let short_id: String = plan.plan_id.chars().take(8).collect();
+    let tx = conn.unchecked_transaction()?;
+    let now = chrono::Utc::now().format("%Y-%m-%d %H:%M:%S").to_string();
+    let mut batches = BTreeMap::new();
+    let mut assigned = 0;
+    for allocation in plan.allocations.iter().filter(|a| !a.number_ids.is_empty()) {
+        for id in &allocation.number_ids {
+            match current.get(id) {
+                None => return Err(conflict(format!("号码 {} 已不是可用状态", id))),
+                Some(c) if c.imported_devices.contains(&allocation.device_id) => {
+                    return Err(conflict(format!("号码 {} 曾导入过设备 {}", id, allocation.device_id)))
+                }
+                Some(_) => {}
+            }
+        }
+        let mut by_industry = BTreeMap::new();
+        for industry in allocation.number_ids.iter().filter_map(|id| current.get(id)?.industry.clone()) {
+            *by_industry.entry(industry).or_insert(0) += 1;
+        }
+        industry_quota::enforce_quota(&tx, &allocation.device_id, &by_industry)?;
+
+        let batch_id = format!("alloc_{}_{}", short_id, allocation.device_id);
+        let industry = (allocation.by_industry.len() == 1)
+            .then(|| allocation.by_industry.keys().next().cloned())
+            .flatten()
+            .filter(|i| !i.is_empty());
+        tx.execute(
+            "INSERT INTO vcf_batches (batch_id, batch_name, source_type, contact_count, status, industry, description, created_at)
+             VALUES (?1, ?2, 'allocation_plan', ?3, 'pending', ?4, ?5, ?6)",
+            params![
+                batch_id,
+                format!("分配方案 {} - {}", short_id, allocation.device_id),
+                allocation.number_ids.len() as i64,
+                industry,
+                format!("device={}", allocation.device_id),
+                now,
+            ],
+        )?;
+
+        let mut stmt = tx.prepare(
+            "UPDATE contact_numbers SET status = 'assigned', assigned_batch_id = ?1, assigned_at = ?2
+             WHERE id = ?3 AND status = 'available'",
+        )?;
+        for id in &allocation.number_ids {
+            if stmt.execute(params![batch_id, now, id])? == 0 {
+                return Err(conflict(format!("号码 {} 在执行期间被占用", id)));
+            }
+        }
+        industry_quota::record_usage(&tx, &allocation.device_id, &batch_id, &by_industry)?;
+        assigned += allocation.number_ids.len() as i64;
+        batches.insert(allocation.device_id.clone(), batch_id);
+    }
+    tx.commit()?;
+
+    Ok(AllocationExecutionResult { plan_id: plan.plan_id.clone(), batches, assigned })
+}
+
+/// 撤销方案生成的批次：号码回到可用、删除配额占用记录与批次，返回释放的号码数
+///
+/// 只释放仍处于已分配状态的号码，已导入 / 已使用的号码保持不变。
+pub fn revert_batch(conn: &Connection, batch_id: &str) -> SqlResult<i64> {
+    let tx = conn.unchecked_transaction()?;
+    let released = tx.execute(
+        "UPDATE contact_numbers SET status = 'available', assigned_batch_id = NULL, assigned_at = NULL
+         WHERE assigned_batch_id = ?1 AND status = 'assigned'",
+        params![batch_id],
+    )?;
+    tx.execute("DELETE FROM industry_quota_usage WHERE batch_id = ?1", params![batch_id])?;
+    tx.execute("DELETE FROM vcf_batches WHERE batch_id = ?1", params![batch_id])?;
+    tx.commit()?;
+    Ok(released as i64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candidate(id: i64, industry: Option<&str>, imported: &[&str]) -> AllocationCandidate {
+        AllocationCandidate {
+            id,
+            industry: industry.map(String::from),
+            imported_devices: imported.iter().map(|s| s.to_string()).collect(),
+        }
+    }
+
+    fn device(id: &str, capacity: i64, industries: &[&str]) -> DeviceAllocationSpec {
+        DeviceAllocationSpec { device_id: id.into(), capacity, industries: industries.iter().map(|s| s.to_string()).collect() }
+    }
+
+    #[test]
+    fn balances_respects_affinity_capacity_and_history() {
+        let devices = vec![device("a", 3, &[]), device("b", 3, &[]), device("food", 2, &["餐饮"])];
+        let candidates = vec![
+            candidate(1, Some("餐饮"), &[]),
+            candidate(2, Some("餐饮"), &["food"]),
+            candidate(3, None, &["a"]),
+            candidate(4, None, &[]),
+            candidate(5, None, &[]),
+            candidate(6, Some("教育"), &[]),
+        ];
+
+        let (plan, unallocated) = plan_allocation(&devices, &candidates, None, true, &QuotaLimits::new());
+        let ids = |d: usize| plan[d].number_ids.clone();
+        assert_eq!(ids(2), vec![1]);
+        assert!(!ids(0).contains(&3));
+        assert_eq!(ids(0).len() + ids(1).len(), 5);
+        assert!((ids(0).len() as i64 - ids(1).len() as i64).abs() <= 1);
+        assert_eq!(unallocated, 0);
+
+        let (limited, unallocated) = plan_allocation(&devices, &candidates, Some(2), true, &QuotaLimits::new());
+        assert_eq!(limited.iter().map(|a| a.number_ids.len()).sum::<usize>(), 2);
+        assert_eq!(unallocated, 4);
+
+        // 餐饮行业在 food 上已无配额：偏好设备被跳过，严格模式下落到不限行业的设备
+        let quotas = QuotaLimits::from([(("food".to_string(), "餐饮".to_string()), 0)]);
+        let (capped, _) = plan_allocation(&devices, &candidates, None, true, &quotas);
+        assert!(capped[2].number_ids.is_empty());
+        assert!(capped[0].number_ids.contains(&1) || capped[1].number_ids.contains(&1));
+    }
+}
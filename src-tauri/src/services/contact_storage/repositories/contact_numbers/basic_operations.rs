@@ -1,5 +1,6 @@
 use rusqlite::{Connection, Result as SqlResult, params};
 use super::super::super::models::*;
+use super::super::super::parser::normalizers::normalize_phone_number;
 use std::path::Path;
 
 /// 基础CRUD操作：插入、查询、获取单个号码等
@@ -17,7 +18,7 @@ fn extract_file_name(file_path: &str) -> String {
         .unwrap_or_else(|| String::from("未知来源"))
 }
 
-/// 插入联系人号码到数据库
+/// 插入联系人号码到数据库（号码先规范化，格式不同的同一号码在同一文件内按重复计）
 pub fn insert_numbers(
     conn: &Connection,
     numbers: &[(String, String)],
@@ -30,7 +31,12 @@ pub fn insert_numbers(
     let mut duplicate_count = 0;
     let mut errors = Vec::new();
     
-    for (phone, name) in numbers {
+    for (raw_phone, name) in numbers {
+        let phone = normalize_phone_number(raw_phone);
+        if phone.is_empty() {
+            errors.push(format!("号码 {} 不含数字，已跳过", raw_phone));
+            continue;
+        }
         match conn.execute(
             "INSERT INTO contact_numbers (phone, name, source_file, created_at) VALUES (?1, ?2, ?3, datetime('now'))",
             params![phone, name, &file_name],
@@ -46,6 +52,24 @@ pub fn insert_numbers(
     Ok((inserted_count, duplicate_count, errors))
 }
 
+/// 为刚导入的号码补充行业标签（来自 Excel 行业列），已有标签的号码不覆盖
+pub fn apply_import_industries(
+    conn: &Connection,
+    industries: &std::collections::HashMap<String, String>,
+    source_file: &str,
+) -> SqlResult<i64> {
+    let file_name = extract_file_name(source_file);
+    let mut stmt = conn.prepare(
+        "UPDATE contact_numbers SET industry = ?1
+         WHERE phone = ?2 AND source_file = ?3 AND (industry IS NULL OR industry = '')",
+    )?;
+    let mut updated = 0;
+    for (phone, industry) in industries {
+        updated += stmt.execute(params![industry, normalize_phone_number(phone), &file_name])? as i64;
+    }
+    Ok(updated)
+}
+
 /// 简单列出联系人号码
 pub fn list_numbers(
     conn: &Connection,
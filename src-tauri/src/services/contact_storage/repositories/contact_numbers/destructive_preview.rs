@@ -0,0 +1,388 @@
+/// 破坏性号码操作的预览与确认执行
+///
+/// 删除 / 标记未导入 / 批量改行业之前先生成预览：受影响行数、样例行、关联的 VCF 批次与导入会话，
+/// 并签发一次性预览令牌。执行时必须带回令牌；若受影响的数据在预览后发生变化（被其他操作改动 / 新增 / 删除），
+/// 执行会被拒绝并要求重新预览，保证「看到的就是将要改的」。
+use rusqlite::{params_from_iter, Connection, OptionalExtension, Result as SqlResult};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use crate::services::contact_storage::models::{ContactNumberDto, ContactStatus};
+
+/// 预览令牌有效期
+pub const PREVIEW_TTL: Duration = Duration::from_secs(10 * 60);
+/// 默认样例行数
+pub const DEFAULT_SAMPLE_SIZE: usize = 20;
+/// 单条 SQL 的最大占位符数量
+const ID_CHUNK: usize = 500;
+
+static REGISTRY: OnceLock<Mutex<PreviewRegistry>> = OnceLock::new();
+
+/// 破坏性操作
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum DestructiveOperation {
+    Delete,
+    MarkNotImported,
+    RetagIndustry { industry: String },
+}
+
+/// 操作对象
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "by", rename_all = "snake_case")]
+pub enum NumberSelection {
+    Ids { ids: Vec<i64> },
+    IdRange { start_id: i64, end_id: i64 },
+    /// 与号码列表的筛选条件一致
+    Filter {
+        search: Option<String>,
+        industry: Option<String>,
+        status: Option<ContactStatus>,
+    },
+    VcfBatch { batch_id: String },
+}
+
+/// 受影响号码关联的 VCF 批次
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchReference {
+    pub batch_id: String,
+    pub batch_name: Option<String>,
+    pub batch_status: Option<String>,
+    pub affected: usize,
+}
+
+/// 受影响号码关联的导入会话
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionReference {
+    pub session_id: i64,
+    pub device_id: Option<String>,
+    pub session_status: Option<String>,
+    pub affected: usize,
+}
+
+/// 预览结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DestructivePreview {
+    pub token: String,
+    pub operation: DestructiveOperation,
+    /// 选中的号码数
+    pub matched: usize,
+    /// 实际会被改动的号码数（例如改行业时已是目标行业的不计入）
+    pub affected: usize,
+    /// 受影响号码按状态分布
+    pub by_status: BTreeMap<String, usize>,
+    pub samples: Vec<ContactNumberDto>,
+    pub batches: Vec<BatchReference>,
+    pub sessions: Vec<SessionReference>,
+    pub expires_in_secs: u64,
+}
+
+/// 执行结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DestructiveExecution {
+    pub operation: DestructiveOperation,
+    pub affected: usize,
+}
+
+struct PendingPreview {
+    operation: DestructiveOperation,
+    selection: NumberSelection,
+    fingerprint: String,
+    created_at: Instant,
+}
+
+/// 已签发的预览令牌（一次性）
+#[derive(Default)]
+pub struct PreviewRegistry {
+    pending: HashMap<String, PendingPreview>,
+}
+
+impl PreviewRegistry {
+    pub fn global() -> &'static Mutex<PreviewRegistry> {
+        REGISTRY.get_or_init(|| Mutex::new(PreviewRegistry::default()))
+    }
+
+    fn purge_expired(&mut self) {
+        self.pending.retain(|_, p| p.created_at.elapsed() < PREVIEW_TTL);
+    }
+}
+
+fn row_to_dto(row: &rusqlite::Row) -> SqlResult<ContactNumberDto> {
+    Ok(ContactNumberDto {
+        id: row.get(0)?,
+        phone: row.get(1)?,
+        name: row.get(2)?,
+        source_file: row.get(3)?,
+        created_at: row.get(4)?,
+        industry: row.get(5)?,
+        status: row.get(6)?,
+        assigned_at: row.get(7)?,
+        assigned_batch_id: row.get(8)?,
+        imported_session_id: row.get(9)?,
+        imported_device_id: row.get(10)?,
+    })
+}
+
+const SELECT_COLUMNS: &str = "SELECT id, phone, name, source_file, created_at, industry, status, assigned_at, assigned_batch_id,
+                                     imported_session_id, imported_device_id FROM contact_numbers";
+
+/// 读取选中的号码（按 id 排序）
+fn load_selection(conn: &Connection, selection: &NumberSelection) -> SqlResult<Vec<ContactNumberDto>> {
+    let mut rows = Vec::new();
+    match selection {
+        NumberSelection::Ids { ids } => {
+            for chunk in ids.chunks(ID_CHUNK) {
+                let sql = format!("{} WHERE id IN ({})", SELECT_COLUMNS, vec!["?"; chunk.len()].join(","));
+                let mut stmt = conn.prepare(&sql)?;
+                rows.extend(stmt.query_map(params_from_iter(chunk.iter()), row_to_dto)?.collect::<SqlResult<Vec<_>>>()?);
+            }
+            rows.sort_by_key(|r| r.id);
+            rows.dedup_by_key(|r| r.id);
+        }
+        NumberSelection::IdRange { start_id, end_id } => {
+            let mut stmt = conn.prepare(&format!("{} WHERE id >= ?1 AND id <= ?2 ORDER BY id", SELECT_COLUMNS))?;
+            rows = stmt.query_map([start_id, end_id], row_to_dto)?.collect::<SqlResult<_>>()?;
+        }
+        NumberSelection::Filter { search, industry, status } => {
+            let mut conditions = Vec::new();
+            let mut values: Vec<String> = Vec::new();
+            if let Some(keyword) = search.as_deref().filter(|s| !s.is_empty()) {
+                conditions.push("phone LIKE ?");
+                values.push(format!("%{}%", keyword));
+            }
+            if let Some(industry) = industry {
+                conditions.push("industry = ?");
+                values.push(industry.clone());
+            }
+            if let Some(status) = status {
+                conditions.push("status = ?");
+                values.push(status.to_string());
+            }
+            let where_clause = if conditions.is_empty() { String::new() } else { format!("WHERE {}", conditions.join(" AND ")) };
+            let mut stmt = conn.prepare(&format!("{} {} ORDER BY id", SELECT_COLUMNS, where_clause))?;
+            rows = stmt.query_map(params_from_iter(values.iter()), row_to_dto)?.collect::<SqlResult<_>>()?;
+        }
+        NumberSelection::VcfBatch { batch_id } => {
+            let mut stmt = conn.prepare(&format!("{} WHERE assigned_batch_id = ?1 ORDER BY id", SELECT_COLUMNS))?;
+            rows = stmt.query_map([batch_id], row_to_dto)?.collect::<SqlResult<_>>()?;
+        }
+    }
+    Ok(rows)
+}
+
+/// 该操作是否真的会改动这条号码
+fn is_affected(operation: &DestructiveOperation, row: &ContactNumberDto) -> bool {
+    match operation {
+        DestructiveOperation::Delete => true,
+        DestructiveOperation::MarkNotImported => {
+            row.status.as_ref().is_some_and(|s| *s != ContactStatus::Available)
+                || row.assigned_batch_id.is_some()
+                || row.imported_session_id.is_some()
+                || row.imported_device_id.is_some()
+        }
+        DestructiveOperation::RetagIndustry { industry } => row.industry.as_deref() != Some(industry.as_str()),
+    }
+}
+
+/// 受影响行的指纹：任一行的状态 / 行业 / 分配信息变化、行被删除或新行进入选择范围都会改变指纹
+fn fingerprint(rows: &[&ContactNumberDto]) -> String {
+    let mut ctx = md5::Context::new();
+    for row in rows {
+        ctx.consume(
+            format!(
+                "{}|{:?}|{:?}|{:?}|{:?};",
+                row.id, row.status, row.industry, row.assigned_batch_id, row.imported_session_id
+            )
+            .as_bytes(),
+        );
+    }
+    format!("{:x}", ctx.compute())
+}
+
+fn batch_references(conn: &Connection, rows: &[&ContactNumberDto]) -> SqlResult<Vec<BatchReference>> {
+    let mut counts: BTreeMap<&str, usize> = BTreeMap::new();
+    for row in rows {
+        if let Some(batch_id) = row.assigned_batch_id.as_deref() {
+            *counts.entry(batch_id).or_default() += 1;
+        }
+    }
+    let mut stmt = conn.prepare("SELECT batch_name, status FROM vcf_batches WHERE batch_id = ?1")?;
+    counts
+        .into_iter()
+        .map(|(batch_id, affected)| {
+            let (batch_name, batch_status) = stmt
+                .query_row([batch_id], |r| Ok((r.get(0)?, r.get(1)?)))
+                .optional()?
+                .unwrap_or((None, None));
+            Ok(BatchReference { batch_id: batch_id.to_string(), batch_name, batch_status, affected })
+        })
+        .collect()
+}
+
+fn session_references(conn: &Connection, rows: &[&ContactNumberDto]) -> SqlResult<Vec<SessionReference>> {
+    let mut counts: BTreeMap<i64, usize> = BTreeMap::new();
+    for row in rows {
+        if let Some(session_id) = row.imported_session_id {
+            *counts.entry(session_id).or_default() += 1;
+        }
+    }
+    let mut stmt = conn.prepare("SELECT device_id, status FROM import_sessions WHERE id = ?1")?;
+    counts
+        .into_iter()
+        .map(|(session_id, affected)| {
+            let (device_id, session_status) = stmt
+                .query_row([session_id], |r| Ok((r.get(0)?, r.get(1)?)))
+                .optional()?
+                .unwrap_or((None, None));
+            Ok(SessionReference { session_id, device_id, session_status, affected })
+        })
+        .collect()
+}
+
+/// 生成预览并签发令牌（只读）
+pub fn preview(
+    conn: &Connection,
+    registry: &mut PreviewRegistry,
+    operation: DestructiveOperation,
+    selection: NumberSelection,
+    sample_size: usize,
+) -> SqlResult<DestructivePreview> {
+    let rows = load_selection(conn, &selection)?;
+    let affected: Vec<&ContactNumberDto> = rows.iter().filter(|r| is_affected(&operation, r)).collect();
+
+    let mut by_status = BTreeMap::new();
+    for row in &affected {
+        let status = row.status.as_ref().map(ContactStatus::to_string).unwrap_or_else(|| "available".to_string());
+        *by_status.entry(status).or_default() += 1;
+    }
+    let token = uuid::Uuid::new_v4().to_string();
+    let preview = DestructivePreview {
+        token: token.clone(),
+        operation: operation.clone(),
+        matched: rows.len(),
+        affected: affected.len(),
+        by_status,
+        samples: affected.iter().take(sample_size).map(|r| (*r).clone()).collect(),
+        batches: batch_references(conn, &affected)?,
+        sessions: session_references(conn, &affected)?,
+        expires_in_secs: PREVIEW_TTL.as_secs(),
+    };
+
+    registry.purge_expired();
+    registry.pending.insert(
+        token,
+        PendingPreview { operation, selection, fingerprint: fingerprint(&affected), created_at: Instant::now() },
+    );
+    Ok(preview)
+}
+
+fn execute_on_ids(conn: &Connection, operation: &DestructiveOperation, ids: &[i64]) -> SqlResult<usize> {
+    let mut affected = 0;
+    for chunk in ids.chunks(ID_CHUNK) {
+        let placeholders = vec!["?"; chunk.len()].join(",");
+        affected += match operation {
+            DestructiveOperation::Delete => {
+                conn.execute(&format!("DELETE FROM contact_numbers WHERE id IN ({})", placeholders), params_from_iter(chunk.iter()))?
+            }
+            DestructiveOperation::MarkNotImported => conn.execute(
+                &format!(
+                    "UPDATE contact_numbers
+                     SET status = 'available', assigned_batch_id = NULL, imported_device_id = NULL, imported_session_id = NULL,
+                         imported_at = NULL, assigned_at = NULL
+                     WHERE id IN ({})",
+                    placeholders
+                ),
+                params_from_iter(chunk.iter()),
+            )?,
+            DestructiveOperation::RetagIndustry { industry } => {
+                let mut values: Vec<&dyn rusqlite::ToSql> = vec![industry as &dyn rusqlite::ToSql];
+                values.extend(chunk.iter().map(|id| id as &dyn rusqlite::ToSql));
+                conn.execute(&format!("UPDATE contact_numbers SET industry = ? WHERE id IN ({})", placeholders), values.as_slice())?
+            }
+        };
+    }
+    Ok(affected)
+}
+
+/// 按预览令牌执行；令牌无效 / 过期或数据已变化时返回错误（令牌在任何情况下都只能使用一次）
+pub fn execute(conn: &Connection, registry: &mut PreviewRegistry, token: &str) -> Result<DestructiveExecution, String> {
+    registry.purge_expired();
+    let pending = registry.pending.remove(token).ok_or("预览令牌无效或已过期，请重新预览")?;
+
+    let tx = conn.unchecked_transaction().map_err(|e| e.to_string())?;
+    let rows = load_selection(&tx, &pending.selection).map_err(|e| e.to_string())?;
+    let affected: Vec<&ContactNumberDto> = rows.iter().filter(|r| is_affected(&pending.operation, r)).collect();
+    if fingerprint(&affected) != pending.fingerprint {
+        return Err("预览后数据已发生变化，请重新预览确认".to_string());
+    }
+    let ids: Vec<i64> = affected.iter().map(|r| r.id).collect();
+    let count = execute_on_ids(&tx, &pending.operation, &ids).map_err(|e| e.to_string())?;
+    tx.commit().map_err(|e| e.to_string())?;
+    Ok(DestructiveExecution { operation: pending.operation, affected: count })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::contact_storage::repositories::common::schema::init_contact_storage_tables;
+
+    fn seed(conn: &Connection) {
+        init_contact_storage_tables(conn).unwrap();
+        conn.execute("INSERT INTO vcf_batches (batch_id, batch_name, status) VALUES ('b1', '一月批次', 'completed')", []).unwrap();
+        conn.execute(
+            "INSERT INTO import_sessions (session_id, device_id, batch_id, target_app, status) VALUES ('s1', 'dev-1', 'b1', 'contacts', 'success')",
+            [],
+        )
+        .unwrap();
+        for (phone, industry, batch, session) in [
+            ("13900000001", Some("餐饮"), Some("b1"), Some(1)),
+            ("13900000002", Some("餐饮"), Some("b1"), None),
+            ("13900000003", None, None, None),
+            ("15800000004", Some("教育"), None, None),
+        ] {
+            conn.execute(
+                "INSERT INTO contact_numbers (phone, source_file, industry, assigned_batch_id, imported_session_id, status)
+                 VALUES (?1, 'a.txt', ?2, ?3, ?4, CASE WHEN ?4 IS NOT NULL THEN 'imported' WHEN ?3 IS NOT NULL THEN 'assigned' ELSE 'available' END)",
+                rusqlite::params![phone, industry, batch, session],
+            )
+            .unwrap();
+        }
+    }
+
+    #[test]
+    fn preview_reports_impact_and_execute_requires_unchanged_data() {
+        let conn = Connection::open_in_memory().unwrap();
+        seed(&conn);
+        let mut registry = PreviewRegistry::default();
+        let filter = NumberSelection::Filter { search: Some("139".into()), industry: None, status: None };
+
+        let retag = preview(&conn, &mut registry, DestructiveOperation::RetagIndustry { industry: "餐饮".into() }, filter.clone(), 1).unwrap();
+        assert_eq!((retag.matched, retag.affected, retag.samples.len()), (3, 1, 1));
+
+        let delete = preview(&conn, &mut registry, DestructiveOperation::Delete, filter.clone(), 10).unwrap();
+        assert_eq!(delete.affected, 3);
+        assert_eq!(delete.by_status.get("imported"), Some(&1));
+        assert_eq!((delete.batches[0].batch_name.as_deref(), delete.batches[0].affected), (Some("一月批次"), 2));
+        assert_eq!((delete.sessions[0].device_id.as_deref(), delete.sessions[0].affected), (Some("dev-1"), 1));
+
+        // 预览后新号码进入筛选范围 → 拒绝执行，令牌作废
+        conn.execute("INSERT INTO contact_numbers (phone, source_file) VALUES ('13900000009', 'b.txt')", []).unwrap();
+        assert!(execute(&conn, &mut registry, &delete.token).unwrap_err().contains("重新预览"));
+        assert!(execute(&conn, &mut registry, &delete.token).unwrap_err().contains("无效"));
+
+        let mark = preview(&conn, &mut registry, DestructiveOperation::MarkNotImported, NumberSelection::VcfBatch { batch_id: "b1".into() }, 10).unwrap();
+        assert_eq!(mark.affected, 2);
+        assert_eq!(execute(&conn, &mut registry, &mark.token).unwrap().affected, 2);
+        let assigned: i64 = conn.query_row("SELECT COUNT(*) FROM contact_numbers WHERE assigned_batch_id IS NOT NULL", [], |r| r.get(0)).unwrap();
+        assert_eq!(assigned, 0);
+
+        let retag = preview(&conn, &mut registry, DestructiveOperation::RetagIndustry { industry: "零售".into() }, NumberSelection::Ids { ids: vec![3, 4, 99] }, 10).unwrap();
+        assert_eq!(execute(&conn, &mut registry, &retag.token).unwrap().affected, 2);
+    }
+}
@@ -0,0 +1,482 @@
+/// 全库号码去重：清理历史导入遗留的重复号码
+///
+/// 旧版导入没有统一规范化号码（`+86 139-1234-5678` 与 `13912345678` 被当作不同号码），
+/// 且 UNIQUE(phone, source_file) 只在单文件内去重。这里按规范化号码分组扫描整张
+/// contact_numbers 表，按策略保留一条并把其余记录的标签 / 分配 / 导入信息合并进来。
+use rusqlite::{params, Connection, OptionalExtension, Result as SqlResult};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use ts_rs::TS;
+
+use crate::services::contact_storage::parser::normalizers::normalize_phone_number;
+
+/// 默认每批扫描 / 合并的行数
+pub const DEFAULT_CHUNK_SIZE: usize = 2000;
+
+/// 重复组保留策略
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum DedupStrategy {
+    /// 保留最早导入的记录
+    #[default]
+    KeepEarliest,
+    /// 优先保留已打行业标签 / 已分配 / 已导入的记录，同等条件下保留最早的
+    KeepTagged,
+    /// 保留元数据最完整的记录（姓名、行业、分配 / 导入信息），同等条件下保留最早的
+    KeepRichest,
+}
+
+/// 进度事件负载
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct DedupProgress {
+    /// scanning | merging | done
+    pub phase: String,
+    pub processed: usize,
+    pub total: usize,
+    pub groups_found: usize,
+}
+
+/// 单个重复组的合并明细
+#[derive(Debug, Clone, Serialize)]
+pub struct DedupMergeGroup {
+    pub normalized_phone: String,
+    pub kept_id: i64,
+    pub kept_source_file: String,
+    pub removed_ids: Vec<i64>,
+    pub removed_source_files: Vec<String>,
+    /// 从被删除记录合并到保留记录的字段
+    pub merged_fields: Vec<String>,
+}
+
+/// 合并报告
+#[derive(Debug, Clone, Serialize)]
+pub struct DedupReport {
+    pub strategy: DedupStrategy,
+    pub dry_run: bool,
+    pub scanned: usize,
+    pub duplicate_groups: usize,
+    pub removed: usize,
+    pub started_at: String,
+    pub finished_at: String,
+    pub groups: Vec<DedupMergeGroup>,
+}
+
+#[derive(Debug, Clone)]
+struct NumberRow {
+    id: i64,
+    phone: String,
+    name: String,
+    source_file: String,
+    created_at: String,
+    status: String,
+    industry: Option<String>,
+    assigned_batch_id: Option<String>,
+    assigned_at: Option<String>,
+    imported_device_id: Option<String>,
+    imported_session_id: Option<i64>,
+    imported_at: Option<String>,
+}
+
+impl NumberRow {
+    fn status_rank(&self) -> u8 {
+        match self.status.as_str() {
+            "imported" => 2,
+            "assigned" => 1,
+            _ => 0,
+        }
+    }
+
+    fn is_tagged(&self) -> bool {
+        self.industry.as_deref().is_some_and(|s| !s.trim().is_empty())
+    }
+
+    /// 元数据完整度：已填写的字段数 + 状态等级
+    fn richness(&self) -> u8 {
+        let filled = |v: Option<&str>| u8::from(v.is_some_and(|s| !s.trim().is_empty()));
+        filled(Some(self.name.as_str()))
+            + u8::from(self.is_tagged())
+            + filled(self.assigned_batch_id.as_deref())
+            + filled(self.imported_device_id.as_deref())
+            + self.status_rank()
+    }
+}
+
+/// 选出保留记录的下标
+fn pick_keeper(rows: &[NumberRow], strategy: DedupStrategy) -> usize {
+    let earliest = |a: &NumberRow, b: &NumberRow| (a.created_at.as_str(), a.id).cmp(&(b.created_at.as_str(), b.id));
+    (0..rows.len())
+        .min_by(|&a, &b| {
+            let (ra, rb) = (&rows[a], &rows[b]);
+            match strategy {
+                DedupStrategy::KeepEarliest => earliest(ra, rb),
+                DedupStrategy::KeepTagged => rb
+                    .is_tagged()
+                    .cmp(&ra.is_tagged())
+                    .then(rb.status_rank().cmp(&ra.status_rank()))
+                    .then_with(|| earliest(ra, rb)),
+                DedupStrategy::KeepRichest => rb.richness().cmp(&ra.richness()).then_with(|| earliest(ra, rb)),
+            }
+        })
+        .unwrap_or(0)
+}
+
+/// 把重复记录的信息合并进保留记录，返回合并后的记录与被合并的字段名
+fn merge_into(keeper: &NumberRow, others: &[&NumberRow]) -> (NumberRow, Vec<String>) {
+    let mut merged = keeper.clone();
+    let mut fields = Vec::new();
+
+    if merged.name.trim().is_empty() {
+        if let Some(o) = others.iter().find(|o| !o.name.trim().is_empty()) {
+            merged.name = o.name.clone();
+            fields.push("name".to_string());
+        }
+    }
+    if !merged.is_tagged() {
+        if let Some(o) = others.iter().find(|o| o.is_tagged()) {
+            merged.industry = o.industry.clone();
+            fields.push("industry".to_string());
+        }
+    }
+    // 状态只升不降，且分配 / 导入信息随状态一起整体带过来，避免拼出不一致的记录
+    if let Some(o) = others.iter().filter(|o| o.status_rank() > merged.status_rank()).max_by_key(|o| o.status_rank()) {
+        merged.status = o.status.clone();
+        merged.assigned_batch_id = o.assigned_batch_id.clone();
+        merged.assigned_at = o.assigned_at.clone();
+        merged.imported_device_id = o.imported_device_id.clone();
+        merged.imported_session_id = o.imported_session_id;
+        merged.imported_at = o.imported_at.clone();
+        fields.push("status".to_string());
+    }
+    (merged, fields)
+}
+
+fn read_chunk(conn: &Connection, after_id: i64, limit: usize) -> SqlResult<Vec<NumberRow>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, phone, name, source_file, created_at, status, industry, assigned_batch_id, assigned_at,
+                imported_device_id, imported_session_id, imported_at
+         FROM contact_numbers WHERE id > ?1 ORDER BY id LIMIT ?2",
+    )?;
+    let rows = stmt.query_map(params![after_id, limit as i64], |row| {
+        Ok(NumberRow {
+            id: row.get(0)?,
+            phone: row.get(1)?,
+            name: row.get::<_, Option<String>>(2)?.unwrap_or_default(),
+            source_file: row.get(3)?,
+            created_at: row.get::<_, Option<String>>(4)?.unwrap_or_default(),
+            status: row.get::<_, Option<String>>(5)?.unwrap_or_else(|| "available".to_string()),
+            industry: row.get(6)?,
+            assigned_batch_id: row.get(7)?,
+            assigned_at: row.get(8)?,
+            imported_device_id: row.get(9)?,
+            imported_session_id: row.get(10)?,
+            imported_at: row.get(11)?,
+        })
+    })?;
+    rows.collect()
+}
+
+fn apply_group(conn: &Connection, merged: &NumberRow, normalized: &str, removed_ids: &[i64]) -> SqlResult<()> {
+    let placeholders = vec!["?"; removed_ids.len()].join(",");
+    let sql = format!("DELETE FROM contact_numbers WHERE id IN ({})", placeholders);
+    conn.execute(&sql, rusqlite::params_from_iter(removed_ids.iter()))?;
+
+    // 被删除的记录已让出 (phone, source_file)，保留记录的号码可以安全地改写为规范化形式
+    let phone_taken: Option<i64> = conn
+        .query_row(
+            "SELECT id FROM contact_numbers WHERE phone = ?1 AND source_file = ?2 AND id != ?3",
+            params![normalized, merged.source_file, merged.id],
+            |row| row.get(0),
+        )
+        .optional()?;
+    let phone = if phone_taken.is_none() && !normalized.is_empty() { normalized } else { merged.phone.as_str() };
+
+    conn.execute(
+        "UPDATE contact_numbers SET phone = ?1, name = ?2, status = ?3, industry = ?4, assigned_batch_id = ?5,
+                assigned_at = ?6, imported_device_id = ?7, imported_session_id = ?8, imported_at = ?9
+         WHERE id = ?10",
+        params![
+            phone,
+            merged.name,
+            merged.status,
+            merged.industry,
+            merged.assigned_batch_id,
+            merged.assigned_at,
+            merged.imported_device_id,
+            merged.imported_session_id,
+            merged.imported_at,
+            merged.id,
+        ],
+    )?;
+    Ok(())
+}
+
+/// 分批按 id 扫描全表，按规范化号码分组，返回 (已扫描行数, 重复组)；重复组按最小 id 排序
+fn scan_duplicates(
+    conn: &Connection,
+    chunk_size: usize,
+    on_progress: &mut impl FnMut(&DedupProgress),
+) -> SqlResult<(usize, Vec<(String, Vec<NumberRow>)>)> {
+    let total: i64 = conn.query_row("SELECT COUNT(*) FROM contact_numbers", [], |row| row.get(0))?;
+    let total = total as usize;
+
+    let mut by_phone: HashMap<String, Vec<NumberRow>> = HashMap::new();
+    let mut scanned = 0;
+    let mut last_id = 0;
+    loop {
+        let chunk = read_chunk(conn, last_id, chunk_size)?;
+        let Some(last) = chunk.last() else { break };
+        last_id = last.id;
+        scanned += chunk.len();
+        for row in chunk {
+            by_phone.entry(normalize_phone_number(&row.phone)).or_default().push(row);
+        }
+        let groups_found = by_phone.values().filter(|rows| rows.len() > 1).count();
+        on_progress(&DedupProgress { phase: "scanning".into(), processed: scanned, total, groups_found });
+    }
+
+    let mut duplicates: Vec<(String, Vec<NumberRow>)> =
+        by_phone.into_iter().filter(|(phone, rows)| rows.len() > 1 && !phone.is_empty()).collect();
+    duplicates.sort_by_key(|(_, rows)| rows.iter().map(|r| r.id).min().unwrap_or_default());
+    Ok((scanned, duplicates))
+}
+
+/// 按策略合并重复组：每批按行数切分，单批一个事务，中途失败时已提交的批次保持有效
+fn merge_groups(
+    conn: &Connection,
+    duplicates: Vec<(String, Vec<NumberRow>)>,
+    strategy: DedupStrategy,
+    dry_run: bool,
+    chunk_size: usize,
+    on_progress: &mut impl FnMut(&DedupProgress),
+) -> SqlResult<(usize, Vec<DedupMergeGroup>)> {
+    let group_count = duplicates.len();
+    let mut groups = Vec::with_capacity(group_count);
+    let mut removed = 0;
+    let mut processed = 0;
+    let mut batch_rows = 0;
+    let mut tx = if dry_run { None } else { Some(conn.unchecked_transaction()?) };
+    for (normalized, rows) in duplicates {
+        let keeper_idx = pick_keeper(&rows, strategy);
+        let keeper = &rows[keeper_idx];
+        let others: Vec<&NumberRow> = rows.iter().enumerate().filter(|(i, _)| *i != keeper_idx).map(|(_, r)| r).collect();
+        let (merged, merged_fields) = merge_into(keeper, &others);
+        let removed_ids: Vec<i64> = others.iter().map(|r| r.id).collect();
+
+        if let Some(tx) = &tx {
+            apply_group(tx, &merged, &normalized, &removed_ids)?;
+        }
+        removed += removed_ids.len();
+        batch_rows += rows.len();
+        processed += 1;
+        groups.push(DedupMergeGroup {
+            normalized_phone: normalized,
+            kept_id: keeper.id,
+            kept_source_file: keeper.source_file.clone(),
+            removed_ids,
+            removed_source_files: others.iter().map(|r| r.source_file.clone()).collect(),
+            merged_fields,
+        });
+
+        if batch_rows >= chunk_size {
+            if let Some(done) = tx.take() {
+                done.commit()?;
+                tx = Some(conn.unchecked_transaction()?);
+            }
+            batch_rows = 0;
+            on_progress(&DedupProgress { phase: "merging".into(), processed, total: group_count, groups_found: group_count });
+        }
+    }
+    if let Some(done) = tx.take() {
+        done.commit()?;
+    }
+    on_progress(&DedupProgress { phase: "done".into(), processed, total: group_count, groups_found: group_count });
+    Ok((removed, groups))
+}
+
+/// 扫描全表并合并规范化后相同的号码
+///
+/// - 分批按 id 扫描，合并阶段每批重复组一个事务，中途失败时已提交的批次保持有效
+/// - `dry_run` 只生成报告不改动数据
+/// - `on_progress` 在每批扫描 / 合并后回调
+pub fn run_global_dedup(
+    conn: &Connection,
+    strategy: DedupStrategy,
+    dry_run: bool,
+    chunk_size: usize,
+    on_progress: impl FnMut(&DedupProgress),
+) -> SqlResult<DedupReport> {
+    merge_duplicates(conn, None, strategy, dry_run, chunk_size, on_progress)
+}
+
+/// 合并指定的重复组（`normalized_phones` 为空时合并全部），报告格式与全库去重一致
+pub fn merge_duplicates(
+    conn: &Connection,
+    normalized_phones: Option<&[String]>,
+    strategy: DedupStrategy,
+    dry_run: bool,
+    chunk_size: usize,
+    mut on_progress: impl FnMut(&DedupProgress),
+) -> SqlResult<DedupReport> {
+    let chunk_size = chunk_size.max(1);
+    let started_at = chrono::Utc::now().to_rfc3339();
+    let (scanned, mut duplicates) = scan_duplicates(conn, chunk_size, &mut on_progress)?;
+    if let Some(wanted) = normalized_phones {
+        let wanted: HashSet<String> = wanted.iter().map(|p| normalize_phone_number(p)).collect();
+        duplicates.retain(|(phone, _)| wanted.contains(phone));
+    }
+    let duplicate_groups = duplicates.len();
+    let (removed, groups) = merge_groups(conn, duplicates, strategy, dry_run, chunk_size, &mut on_progress)?;
+
+    Ok(DedupReport {
+        strategy,
+        dry_run,
+        scanned,
+        duplicate_groups,
+        removed,
+        started_at,
+        finished_at: chrono::Utc::now().to_rfc3339(),
+        groups,
+    })
+}
+
+/// 重复组中的一条记录
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DuplicateNumberEntry {
+    pub id: i64,
+    /// 库中原样保存的号码（旧数据可能带 +86 / 空格 / 破折号）
+    pub phone: String,
+    pub name: String,
+    pub source_file: String,
+    pub assigned_batch_id: Option<String>,
+    pub status: String,
+    pub industry: Option<String>,
+    pub created_at: String,
+    pub richness: u8,
+}
+
+/// 规范化后相同的一组号码
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DuplicateNumberGroup {
+    pub normalized_phone: String,
+    /// 组内记录来自不同导入文件或分配批次
+    pub cross_batch: bool,
+    /// 组内出现的不同原始写法数
+    pub distinct_formats: usize,
+    /// 按 KeepRichest 策略会保留的记录
+    pub suggested_keep_id: i64,
+    pub entries: Vec<DuplicateNumberEntry>,
+}
+
+/// 查找规范化后相同的号码分组（只读）；`cross_batch_only` 时只返回跨文件 / 跨批次的组
+pub fn find_duplicate_numbers(conn: &Connection, cross_batch_only: bool, chunk_size: usize) -> SqlResult<Vec<DuplicateNumberGroup>> {
+    let (_, duplicates) = scan_duplicates(conn, chunk_size.max(1), &mut |_: &DedupProgress| {})?;
+    Ok(duplicates
+        .into_iter()
+        .map(|(normalized_phone, rows)| {
+            let sources: HashSet<(&str, Option<&str>)> =
+                rows.iter().map(|r| (r.source_file.as_str(), r.assigned_batch_id.as_deref())).collect();
+            let formats: HashSet<&str> = rows.iter().map(|r| r.phone.as_str()).collect();
+            DuplicateNumberGroup {
+                cross_batch: sources.len() > 1,
+                distinct_formats: formats.len(),
+                suggested_keep_id: rows[pick_keeper(&rows, DedupStrategy::KeepRichest)].id,
+                entries: rows
+                    .iter()
+                    .map(|r| DuplicateNumberEntry {
+                        id: r.id,
+                        phone: r.phone.clone(),
+                        name: r.name.clone(),
+                        source_file: r.source_file.clone(),
+                        assigned_batch_id: r.assigned_batch_id.clone(),
+                        status: r.status.clone(),
+                        industry: r.industry.clone(),
+                        created_at: r.created_at.clone(),
+                        richness: r.richness(),
+                    })
+                    .collect(),
+                normalized_phone,
+            }
+        })
+        .filter(|group| group.cross_batch || !cross_batch_only)
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::contact_storage::repositories::common::schema::init_contact_storage_tables;
+
+    fn seed(conn: &Connection) {
+        init_contact_storage_tables(conn).unwrap();
+        for (phone, file, created, industry, status) in [
+            ("13912345678", "a.txt", "2024-01-01", None, "available"),
+            ("+86 139-1234-5678", "b.txt", "2024-02-01", Some("餐饮"), "imported"),
+            ("8613912345678", "c.txt", "2024-03-01", None, "available"),
+            ("15800158001", "a.txt", "2024-01-01", None, "available"),
+        ] {
+            conn.execute(
+                "INSERT INTO contact_numbers (phone, name, source_file, created_at, industry, status, imported_device_id)
+                 VALUES (?1, '', ?2, ?3, ?4, ?5, CASE WHEN ?5 = 'imported' THEN 'dev-1' END)",
+                params![phone, file, created, industry, status],
+            )
+            .unwrap();
+        }
+    }
+
+    #[test]
+    fn merges_normalized_collisions_with_strategy_and_dry_run() {
+        let conn = Connection::open_in_memory().unwrap();
+        seed(&conn);
+        let count = |conn: &Connection| conn.query_row("SELECT COUNT(*) FROM contact_numbers", [], |r| r.get::<_, i64>(0)).unwrap();
+
+        let preview = run_global_dedup(&conn, DedupStrategy::KeepEarliest, true, 2, |_| {}).unwrap();
+        assert_eq!((preview.scanned, preview.duplicate_groups, preview.removed), (4, 1, 2));
+        assert_eq!(count(&conn), 4);
+
+        let tagged = run_global_dedup(&conn, DedupStrategy::KeepTagged, true, 2, |_| {}).unwrap();
+        assert_eq!(tagged.groups[0].kept_source_file, "b.txt");
+
+        let mut phases = Vec::new();
+        let report = run_global_dedup(&conn, DedupStrategy::KeepEarliest, false, 2, |p| phases.push(p.phase.clone())).unwrap();
+        assert_eq!(report.groups[0].kept_source_file, "a.txt");
+        assert_eq!(report.groups[0].merged_fields, vec!["industry", "status"]);
+        assert_eq!(phases.last().map(String::as_str), Some("done"));
+        assert_eq!(count(&conn), 2);
+
+        let (industry, status, device): (Option<String>, String, Option<String>) = conn
+            .query_row(
+                "SELECT industry, status, imported_device_id FROM contact_numbers WHERE phone = '13912345678'",
+                [],
+                |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?)),
+            )
+            .unwrap();
+        assert_eq!((industry.as_deref(), status.as_str(), device.as_deref()), (Some("餐饮"), "imported", Some("dev-1")));
+    }
+
+    #[test]
+    fn finds_cross_batch_groups_and_merges_richest() {
+        let conn = Connection::open_in_memory().unwrap();
+        seed(&conn);
+        conn.execute("INSERT INTO contact_numbers (phone, name, source_file) VALUES ('0086 158 0015 8001', '', 'a.txt')", []).unwrap();
+        conn.execute("UPDATE contact_numbers SET name = '王经理' WHERE phone = '8613912345678'", []).unwrap();
+
+        let groups = find_duplicate_numbers(&conn, false, 2).unwrap();
+        assert_eq!(groups.len(), 2);
+        let mobile = &groups[0];
+        assert_eq!((mobile.normalized_phone.as_str(), mobile.cross_batch, mobile.distinct_formats), ("13912345678", true, 3));
+        // b.txt: 行业 + 已导入 + 设备；c.txt 只有姓名
+        assert_eq!(mobile.suggested_keep_id, 2);
+        assert_eq!(find_duplicate_numbers(&conn, true, 2).unwrap().len(), 1);
+
+        let report = merge_duplicates(&conn, Some(&["+86 13912345678".to_string()][..]), DedupStrategy::KeepRichest, false, 10, |_| {}).unwrap();
+        assert_eq!((report.duplicate_groups, report.removed, report.groups[0].kept_id), (1, 2, 2));
+        assert_eq!(report.groups[0].merged_fields, vec!["name"]);
+        let (phone, name): (String, String) =
+            conn.query_row("SELECT phone, name FROM contact_numbers WHERE id = 2", [], |r| Ok((r.get(0)?, r.get(1)?))).unwrap();
+        assert_eq!((phone.as_str(), name.as_str()), ("13912345678", "王经理"));
+        // 未指定的组保持不变
+        assert_eq!(find_duplicate_numbers(&conn, false, 2).unwrap().len(), 1);
+    }
+}
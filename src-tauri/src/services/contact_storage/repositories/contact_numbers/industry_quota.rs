@@ -0,0 +1,316 @@
+/// 行业配额：限制某行业号码在单台设备上（可选时间窗口内）的分配上限
+///
+/// 规则存放在 industry_quota_rules；每次为设备建立批次时把各行业数量记入
+/// industry_quota_usage 台账，校验时按规则的设备 / 时间窗口汇总台账用量。
+/// 建批次和执行分配方案前都会校验，超限时报错并列出拦截的规则。
+use rusqlite::{params, Connection, OptionalExtension, Result as SqlResult};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+/// 配额规则
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct IndustryQuotaRule {
+    /// 为空表示新建
+    #[serde(default)]
+    pub id: Option<i64>,
+    pub industry: String,
+    /// 为空表示对每台设备分别生效
+    #[serde(default)]
+    pub device_id: Option<String>,
+    pub max_numbers: i64,
+    /// 时间窗口（小时）；为空表示累计不限时
+    #[serde(default)]
+    pub window_hours: Option<i64>,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    #[serde(default)]
+    pub note: Option<String>,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+/// 一条被突破的规则
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct QuotaViolation {
+    pub rule_id: i64,
+    pub industry: String,
+    pub device_id: String,
+    pub max_numbers: i64,
+    pub window_hours: Option<i64>,
+    /// 窗口内已用数量
+    pub used: i64,
+    /// 本次申请数量
+    pub requested: i64,
+}
+
+impl QuotaViolation {
+    pub fn describe(&self) -> String {
+        let window = self.window_hours.map_or("累计".to_string(), |h| format!("{} 小时内", h));
+        format!(
+            "规则#{} [{}] 设备 {} {}最多 {} 个，已用 {}，本次 {}",
+            self.rule_id, self.industry, self.device_id, window, self.max_numbers, self.used, self.requested
+        )
+    }
+}
+
+pub fn create_quota_tables(conn: &Connection) -> SqlResult<()> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS industry_quota_rules (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            industry TEXT NOT NULL,
+            device_id TEXT,              -- 为空表示每台设备分别生效
+            max_numbers INTEGER NOT NULL,
+            window_hours INTEGER,        -- 为空表示累计
+            enabled INTEGER NOT NULL DEFAULT 1,
+            note TEXT,
+            created_at TEXT NOT NULL DEFAULT (datetime('now'))
+        )",
+        [],
+    )?;
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS industry_quota_usage (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            device_id TEXT NOT NULL,
+            industry TEXT NOT NULL,
+            count INTEGER NOT NULL,
+            batch_id TEXT,
+            created_at TEXT NOT NULL DEFAULT (datetime('now'))
+        )",
+        [],
+    )?;
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_industry_quota_usage_device ON industry_quota_usage(device_id, industry, created_at)",
+        [],
+    )?;
+    Ok(())
+}
+
+fn invalid(message: String) -> rusqlite::Error {
+    rusqlite::Error::ToSqlConversionFailure(message.into())
+}
+
+pub fn list_rules(conn: &Connection) -> SqlResult<Vec<IndustryQuotaRule>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, industry, device_id, max_numbers, window_hours, enabled, note
+         FROM industry_quota_rules ORDER BY industry, id",
+    )?;
+    let rows = stmt.query_map([], |row| {
+        Ok(IndustryQuotaRule {
+            id: row.get(0)?,
+            industry: row.get(1)?,
+            device_id: row.get(2)?,
+            max_numbers: row.get(3)?,
+            window_hours: row.get(4)?,
+            enabled: row.get::<_, i64>(5)? != 0,
+            note: row.get(6)?,
+        })
+    })?;
+    rows.collect()
+}
+
+/// 新建或更新规则，返回保存后的规则
+pub fn save_rule(conn: &Connection, rule: &IndustryQuotaRule) -> SqlResult<IndustryQuotaRule> {
+    let industry = rule.industry.trim();
+    if industry.is_empty() {
+        return Err(invalid("配额规则必须指定行业".to_string()));
+    }
+    if rule.max_numbers < 0 {
+        return Err(invalid("配额上限不能为负数".to_string()));
+    }
+    if rule.window_hours.is_some_and(|h| h <= 0) {
+        return Err(invalid("时间窗口必须大于 0 小时".to_string()));
+    }
+    let device_id = rule.device_id.as_deref().map(str::trim).filter(|d| !d.is_empty());
+    let id = match rule.id {
+        Some(id) => {
+            let updated = conn.execute(
+                "UPDATE industry_quota_rules
+                 SET industry = ?1, device_id = ?2, max_numbers = ?3, window_hours = ?4, enabled = ?5, note = ?6
+                 WHERE id = ?7",
+                params![industry, device_id, rule.max_numbers, rule.window_hours, rule.enabled, rule.note, id],
+            )?;
+            if updated == 0 {
+                return Err(invalid(format!("配额规则不存在: {}", id)));
+            }
+            id
+        }
+        None => {
+            conn.execute(
+                "INSERT INTO industry_quota_rules (industry, device_id, max_numbers, window_hours, enabled, note)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                params![industry, device_id, rule.max_numbers, rule.window_hours, rule.enabled, rule.note],
+            )?;
+            conn.last_insert_rowid()
+        }
+    };
+    Ok(IndustryQuotaRule {
+        id: Some(id),
+        industry: industry.to_string(),
+        device_id: device_id.map(String::from),
+        ..rule.clone()
+    })
+}
+
+pub fn delete_rule(conn: &Connection, id: i64) -> SqlResult<bool> {
+    Ok(conn.execute("DELETE FROM industry_quota_rules WHERE id = ?1", params![id])? > 0)
+}
+
+/// 号码 ID → 按行业计数（未分类号码不受配额约束，不计入）
+pub fn industry_counts(conn: &Connection, number_ids: &[i64]) -> SqlResult<BTreeMap<String, i64>> {
+    let mut stmt = conn.prepare("SELECT industry FROM contact_numbers WHERE id = ?1")?;
+    let mut counts = BTreeMap::new();
+    for id in number_ids {
+        let industry: Option<String> = stmt.query_row(params![id], |row| row.get(0)).optional()?.flatten();
+        if let Some(industry) = industry.map(|i| i.trim().to_string()).filter(|i| !i.is_empty()) {
+            *counts.entry(industry).or_insert(0) += 1;
+        }
+    }
+    Ok(counts)
+}
+
+fn used_in_window(conn: &Connection, device_id: &str, industry: &str, window_hours: Option<i64>) -> SqlResult<i64> {
+    match window_hours {
+        Some(hours) => conn.query_row(
+            "SELECT COALESCE(SUM(count), 0) FROM industry_quota_usage
+             WHERE device_id = ?1 AND industry = ?2 AND created_at >= datetime('now', ?3)",
+            params![device_id, industry, format!("-{} hours", hours)],
+            |row| row.get(0),
+        ),
+        None => conn.query_row(
+            "SELECT COALESCE(SUM(count), 0) FROM industry_quota_usage WHERE device_id = ?1 AND industry = ?2",
+            params![device_id, industry],
+            |row| row.get(0),
+        ),
+    }
+}
+
+fn applicable_rules<'a>(
+    rules: &'a [IndustryQuotaRule],
+    device_id: &'a str,
+    industry: &'a str,
+) -> impl Iterator<Item = &'a IndustryQuotaRule> {
+    rules.iter().filter(move |r| {
+        r.enabled && r.industry == industry && r.device_id.as_deref().map_or(true, |d| d == device_id)
+    })
+}
+
+/// 按设备 + 行业计算剩余配额（取所有适用规则中最小的剩余量）；没有适用规则的组合不出现在结果中
+pub fn remaining_quotas(
+    conn: &Connection,
+    device_ids: &[String],
+    industries: &[String],
+) -> SqlResult<HashMap<(String, String), i64>> {
+    let rules = list_rules(conn)?;
+    let mut remaining = HashMap::new();
+    for device_id in device_ids {
+        for industry in industries {
+            for rule in applicable_rules(&rules, device_id, industry) {
+                let left = (rule.max_numbers - used_in_window(conn, device_id, industry, rule.window_hours)?).max(0);
+                remaining
+                    .entry((device_id.clone(), industry.clone()))
+                    .and_modify(|v: &mut i64| *v = (*v).min(left))
+                    .or_insert(left);
+            }
+        }
+    }
+    Ok(remaining)
+}
+
+/// 校验为设备新增这些号码是否突破配额，返回所有被突破的规则
+pub fn check_quota(conn: &Connection, device_id: &str, by_industry: &BTreeMap<String, i64>) -> SqlResult<Vec<QuotaViolation>> {
+    let rules = list_rules(conn)?;
+    let mut violations = Vec::new();
+    for (industry, &requested) in by_industry.iter().filter(|(i, n)| !i.is_empty() && **n > 0) {
+        for rule in applicable_rules(&rules, device_id, industry) {
+            let used = used_in_window(conn, device_id, industry, rule.window_hours)?;
+            if used + requested > rule.max_numbers {
+                violations.push(QuotaViolation {
+                    rule_id: rule.id.unwrap_or_default(),
+                    industry: industry.clone(),
+                    device_id: device_id.to_string(),
+                    max_numbers: rule.max_numbers,
+                    window_hours: rule.window_hours,
+                    used,
+                    requested,
+                });
+            }
+        }
+    }
+    Ok(violations)
+}
+
+/// 校验配额，突破时返回列出全部拦截规则的错误
+pub fn enforce_quota(conn: &Connection, device_id: &str, by_industry: &BTreeMap<String, i64>) -> SqlResult<()> {
+    let violations = check_quota(conn, device_id, by_industry)?;
+    if violations.is_empty() {
+        return Ok(());
+    }
+    let details: Vec<String> = violations.iter().map(QuotaViolation::describe).collect();
+    Err(invalid(format!("超出行业配额，批次被拦截: {}", details.join("; "))))
+}
+
+/// 记录设备各行业用量（建批次成功后调用）
+pub fn record_usage(
+    conn: &Connection,
+    device_id: &str,
+    batch_id: &str,
+    by_industry: &BTreeMap<String, i64>,
+) -> SqlResult<()> {
+    let mut stmt = conn.prepare(
+        "INSERT INTO industry_quota_usage (device_id, industry, count, batch_id) VALUES (?1, ?2, ?3, ?4)",
+    )?;
+    for (industry, count) in by_industry.iter().filter(|(i, n)| !i.is_empty() && **n > 0) {
+        stmt.execute(params![device_id, industry, count, batch_id])?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(industry: &str, device: Option<&str>, max: i64, window: Option<i64>) -> IndustryQuotaRule {
+        IndustryQuotaRule {
+            id: None,
+            industry: industry.into(),
+            device_id: device.map(String::from),
+            max_numbers: max,
+            window_hours: window,
+            enabled: true,
+            note: None,
+        }
+    }
+
+    #[test]
+    fn enforces_per_device_window_rules() {
+        let conn = Connection::open_in_memory().unwrap();
+        create_quota_tables(&conn).unwrap();
+        let global = save_rule(&conn, &rule("教育", None, 500, None)).unwrap();
+        let daily = save_rule(&conn, &rule("教育", Some("dev-a"), 100, Some(24))).unwrap();
+        save_rule(&conn, &rule("餐饮", None, 10, None)).unwrap();
+
+        let request = BTreeMap::from([("教育".to_string(), 80)]);
+        assert!(check_quota(&conn, "dev-a", &request).unwrap().is_empty());
+        record_usage(&conn, "dev-a", "b1", &request).unwrap();
+
+        let violations = check_quota(&conn, "dev-a", &request).unwrap();
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].rule_id, daily.id.unwrap());
+        assert_eq!(violations[0].used, 80);
+        let err = enforce_quota(&conn, "dev-a", &request).unwrap_err().to_string();
+        assert!(err.contains(&format!("规则#{}", daily.id.unwrap())));
+
+        // 设备级规则不影响其他设备，全局规则仍生效
+        assert!(check_quota(&conn, "dev-b", &request).unwrap().is_empty());
+        let too_many = BTreeMap::from([("教育".to_string(), 501)]);
+        assert_eq!(check_quota(&conn, "dev-b", &too_many).unwrap()[0].rule_id, global.id.unwrap());
+
+        let remaining = remaining_quotas(&conn, &["dev-a".into(), "dev-b".into()], &["教育".into()]).unwrap();
+        assert_eq!(remaining[&("dev-a".to_string(), "教育".to_string())], 20);
+        assert_eq!(remaining[&("dev-b".to_string(), "教育".to_string())], 500);
+    }
+}
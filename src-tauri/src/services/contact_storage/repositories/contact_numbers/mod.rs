@@ -20,4 +20,22 @@ pub mod advanced_queries;
 // 文件相关查询
 pub mod file_queries;
 
+// 全库去重与合并
+pub mod global_dedup;
+
+// 破坏性操作的预览与令牌确认执行
+pub mod destructive_preview;
+
+// 分片导出 VCF 并按分片记录批次
+pub mod vcf_chunk_export;
+
+// 多设备号码分配规划
+pub mod allocation_planner;
+
+// 行业配额规则与用量台账
+pub mod industry_quota;
+
+// 微信注册核验结果
+pub mod wechat_check;
+
 // 对外统一接口（保持向后兼容）
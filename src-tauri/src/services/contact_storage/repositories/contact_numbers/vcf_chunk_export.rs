@@ -0,0 +1,236 @@
+/// 号码分片导出 VCF：按选中顺序取号码，生成的每个分片文件各记一条 vcf_batches 批次
+///
+/// 文件生成本身在 services::vcf::vcf_export 中完成；这里负责取数、号码 → Contact 映射，
+/// 以及在单个事务内写入分片批次并把号码挂到对应批次上，便于按文件追溯导入情况。
+use rusqlite::{params, params_from_iter, Connection, Result as SqlResult};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use super::super::super::models::ContactNumberDto;
+use crate::services::vcf::{generate_vcf_files, Contact, VcardVersion, VcfChunkFile, VcfExportOptions};
+
+/// 单个分片对应的批次记录
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VcfChunkBatchRecord {
+    pub batch_id: String,
+    pub batch_name: String,
+    pub file_path: String,
+    pub contact_count: i64,
+    /// 分片内号码行业一致时记录该行业
+    pub industry: Option<String>,
+    pub first_sequence: usize,
+    pub last_sequence: usize,
+}
+
+/// 一次分片导出的结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VcfChunkExportResult {
+    pub export_id: String,
+    pub version: VcardVersion,
+    pub total_contacts: i64,
+    /// 请求中不存在的号码 ID
+    pub missing_ids: Vec<i64>,
+    pub chunks: Vec<VcfChunkBatchRecord>,
+}
+
+/// 按传入顺序获取号码（重复 ID 只取一次），同时返回不存在的 ID
+pub fn fetch_numbers_in_order(conn: &Connection, ids: &[i64]) -> SqlResult<(Vec<ContactNumberDto>, Vec<i64>)> {
+    let mut found: HashMap<i64, ContactNumberDto> = HashMap::with_capacity(ids.len());
+    for chunk in ids.chunks(500) {
+        let placeholders = vec!["?"; chunk.len()].join(",");
+        let mut stmt = conn.prepare(&format!(
+            "SELECT id, phone, name, source_file, created_at, industry, status, assigned_at, assigned_batch_id, imported_session_id, imported_device_id
+             FROM contact_numbers WHERE id IN ({})",
+            placeholders
+        ))?;
+        let rows = stmt.query_map(params_from_iter(chunk.iter()), |row| {
+            Ok(ContactNumberDto {
+                id: row.get(0)?,
+                phone: row.get(1)?,
+                name: row.get(2)?,
+                source_file: row.get(3)?,
+                created_at: row.get(4)?,
+                industry: row.get(5)?,
+                status: row.get(6)?,
+                assigned_at: row.get(7)?,
+                assigned_batch_id: row.get(8)?,
+                imported_session_id: row.get(9)?,
+                imported_device_id: row.get(10)?,
+            })
+        })?;
+        for row in rows {
+            let dto = row?;
+            found.insert(dto.id, dto);
+        }
+    }
+
+    let mut numbers = Vec::with_capacity(found.len());
+    let mut missing = Vec::new();
+    for id in ids {
+        match found.remove(id) {
+            Some(dto) => numbers.push(dto),
+            None if !numbers.iter().any(|n: &ContactNumberDto| n.id == *id) => missing.push(*id),
+            None => {}
+        }
+    }
+    Ok((numbers, missing))
+}
+
+/// 号码 → VCF 联系人：Contact.id 为号码 ID，行业写入 occupation（供 `{industry}` 模板使用）
+pub fn to_vcf_contact(number: &ContactNumberDto) -> Contact {
+    Contact {
+        id: number.id.to_string(),
+        name: if number.name.trim().is_empty() { number.phone.clone() } else { number.name.clone() },
+        phone: number.phone.clone(),
+        email: String::new(),
+        address: String::new(),
+        occupation: number.industry.clone().unwrap_or_default(),
+    }
+}
+
+/// 为每个分片写入一条已生成（generated）的批次，并把分片内号码挂到该批次；
+/// 可用号码转为 assigned，已导入等其它状态保持不变
+pub fn record_chunk_batches(
+    conn: &Connection,
+    export_id: &str,
+    version: VcardVersion,
+    files: &[VcfChunkFile],
+    numbers: &[ContactNumberDto],
+) -> SqlResult<Vec<VcfChunkBatchRecord>> {
+    let industries: HashMap<String, Option<&str>> =
+        numbers.iter().map(|n| (n.id.to_string(), n.industry.as_deref().filter(|i| !i.is_empty()))).collect();
+    let short_id: String = export_id.chars().filter(|c| c.is_ascii_alphanumeric()).take(8).collect();
+    let now = chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
+    let total = files.len();
+
+    let tx = conn.unchecked_transaction()?;
+    let mut records = Vec::with_capacity(total);
+    for file in files {
+        let batch_id = format!("vcf_{}_{:03}", short_id, file.index);
+        let batch_name = std::path::Path::new(&file.file_path)
+            .file_stem()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_else(|| batch_id.clone());
+        let mut chunk_industries = file.contact_ids.iter().map(|id| industries.get(id).copied().flatten());
+        let first = chunk_industries.next().flatten();
+        let industry = first.filter(|f| chunk_industries.all(|i| i == Some(*f))).map(String::from);
+
+        tx.execute(
+            "INSERT INTO vcf_batches (batch_id, batch_name, created_at, vcf_file_path, source_type, contact_count, status, industry, description, notes)
+             VALUES (?1, ?2, ?3, ?4, 'chunked_export', ?5, 'generated', ?6, ?7, ?8)",
+            params![
+                batch_id,
+                batch_name,
+                now,
+                file.file_path,
+                file.contact_count as i64,
+                industry,
+                format!("分片 {}/{} · vCard {}", file.index, total, version.as_str()),
+                format!("export={}", export_id),
+            ],
+        )?;
+
+        let mut stmt = tx.prepare(
+            "UPDATE contact_numbers
+             SET assigned_batch_id = ?1, assigned_at = ?2,
+                 status = CASE WHEN status = 'available' THEN 'assigned' ELSE status END
+             WHERE id = ?3",
+        )?;
+        for id in file.contact_ids.iter().filter_map(|id| id.parse::<i64>().ok()) {
+            stmt.execute(params![batch_id, now, id])?;
+        }
+
+        records.push(VcfChunkBatchRecord {
+            batch_id,
+            batch_name,
+            file_path: file.file_path.clone(),
+            contact_count: file.contact_count as i64,
+            industry,
+            first_sequence: file.first_sequence,
+            last_sequence: file.last_sequence,
+        });
+    }
+    tx.commit()?;
+    Ok(records)
+}
+
+/// 完整的分片导出流程：按选中顺序取号码 → 生成 VCF 文件 → 记录分片批次
+pub fn export_numbers(
+    conn: &Connection,
+    number_ids: &[i64],
+    output_path: &str,
+    options: &VcfExportOptions,
+) -> Result<VcfChunkExportResult, String> {
+    let (numbers, missing_ids) = fetch_numbers_in_order(conn, number_ids).map_err(|e| e.to_string())?;
+    if numbers.is_empty() {
+        return Err("没有可导出的号码".to_string());
+    }
+
+    let contacts: Vec<_> = numbers.iter().map(to_vcf_contact).collect();
+    let files = generate_vcf_files(&contacts, output_path, options).map_err(|e| format!("生成VCF失败: {}", e))?;
+
+    let export_id = uuid::Uuid::new_v4().to_string();
+    let chunks = record_chunk_batches(conn, &export_id, options.version, &files, &numbers).map_err(|e| e.to_string())?;
+
+    Ok(VcfChunkExportResult {
+        export_id,
+        version: options.version,
+        total_contacts: numbers.len() as i64,
+        missing_ids,
+        chunks,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::contact_storage::repositories::common::schema::init_contact_storage_tables;
+
+    #[test]
+    fn records_one_batch_per_chunk_in_selection_order() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_contact_storage_tables(&conn).unwrap();
+        for (phone, industry, status) in [
+            ("13900000001", Some("餐饮"), "available"),
+            ("13900000002", Some("餐饮"), "imported"),
+            ("13900000003", Some("教育"), "available"),
+        ] {
+            conn.execute(
+                "INSERT INTO contact_numbers (phone, source_file, industry, status) VALUES (?1, 'a.txt', ?2, ?3)",
+                params![phone, industry, status],
+            )
+            .unwrap();
+        }
+
+        let (numbers, missing) = fetch_numbers_in_order(&conn, &[3, 1, 99, 2, 1]).unwrap();
+        assert_eq!(numbers.iter().map(|n| n.id).collect::<Vec<_>>(), vec![3, 1, 2]);
+        assert_eq!(missing, vec![99]);
+        assert_eq!(to_vcf_contact(&numbers[0]).occupation, "教育");
+
+        let chunk = |index: usize, ids: &[&str]| VcfChunkFile {
+            index,
+            file_path: format!("/tmp/out_{:03}.vcf", index),
+            contact_count: ids.len(),
+            first_sequence: index,
+            last_sequence: index,
+            contact_ids: ids.iter().map(|s| s.to_string()).collect(),
+        };
+        let files = vec![chunk(1, &["3"]), chunk(2, &["1", "2"])];
+        let records = record_chunk_batches(&conn, "abcd-1234-ef", VcardVersion::V30, &files, &numbers).unwrap();
+
+        assert_eq!(records[0].batch_id, "vcf_abcd1234_001");
+        assert_eq!(records[0].industry.as_deref(), Some("教育"));
+        assert_eq!(records[1].batch_name, "out_002");
+        assert_eq!(records[1].industry.as_deref(), Some("餐饮"));
+        let (status, batch): (String, String) = conn
+            .query_row("SELECT status, assigned_batch_id FROM contact_numbers WHERE id = 2", [], |r| Ok((r.get(0)?, r.get(1)?)))
+            .unwrap();
+        assert_eq!((status.as_str(), batch.as_str()), ("imported", "vcf_abcd1234_002"));
+        let description: String = conn
+            .query_row("SELECT description FROM vcf_batches WHERE batch_id = 'vcf_abcd1234_002'", [], |r| r.get(0))
+            .unwrap();
+        assert_eq!(description, "分片 2/2 · vCard 3.0");
+    }
+}
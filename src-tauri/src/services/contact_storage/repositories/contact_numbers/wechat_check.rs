@@ -0,0 +1,150 @@
+/// 微信注册核验结果
+///
+/// 已导入设备的号码经「添加朋友」搜索核验是否注册微信，结果写回 contact_numbers 的
+/// wechat_status / wechat_checked_at / wechat_check_device / wechat_check_note 列。
+/// 每台设备的核验频率按已写入的核验时间统计，应用重启后限额依然有效。
+use rusqlite::{params, Connection, Result as SqlResult};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// 单个号码的核验结论
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WechatStatus {
+    /// 搜到了用户资料页
+    Registered,
+    /// 「该用户不存在」
+    NotRegistered,
+    /// 「被搜帐号状态异常，无法显示」
+    Abnormal,
+}
+
+impl WechatStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WechatStatus::Registered => "registered",
+            WechatStatus::NotRegistered => "not_registered",
+            WechatStatus::Abnormal => "abnormal",
+        }
+    }
+}
+
+/// 待核验号码
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct WechatCheckCandidate {
+    pub id: i64,
+    pub phone: String,
+    pub name: String,
+}
+
+/// 迁移：为 contact_numbers 添加核验结果列
+pub fn ensure_wechat_check_columns(conn: &Connection) -> SqlResult<()> {
+    let mut stmt = conn.prepare("PRAGMA table_info(contact_numbers)")?;
+    let columns: Vec<String> = stmt.query_map([], |row| row.get(1))?.collect::<SqlResult<_>>()?;
+    for (column, ddl) in [
+        ("wechat_status", "ALTER TABLE contact_numbers ADD COLUMN wechat_status TEXT"),
+        ("wechat_checked_at", "ALTER TABLE contact_numbers ADD COLUMN wechat_checked_at TEXT"),
+        ("wechat_check_device", "ALTER TABLE contact_numbers ADD COLUMN wechat_check_device TEXT"),
+        ("wechat_check_note", "ALTER TABLE contact_numbers ADD COLUMN wechat_check_note TEXT"),
+    ] {
+        if !columns.iter().any(|c| c == column) {
+            tracing::info!("📦 添加 {} 列到 contact_numbers 表", column);
+            conn.execute(ddl, [])?;
+        }
+    }
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_contact_numbers_wechat_check ON contact_numbers(wechat_check_device, wechat_checked_at)",
+        [],
+    )?;
+    Ok(())
+}
+
+/// 已导入到该设备、尚未核验（或核验已超过 `recheck_after_days` 天）的号码，按导入先后排列
+pub fn list_candidates(
+    conn: &Connection,
+    device_id: &str,
+    limit: i64,
+    recheck_after_days: Option<i64>,
+) -> SqlResult<Vec<WechatCheckCandidate>> {
+    let recheck = recheck_after_days.map(|days| format!("-{} days", days.max(0)));
+    let mut stmt = conn.prepare(
+        "SELECT id, phone, name FROM contact_numbers
+         WHERE status = 'imported' AND imported_device_id = ?1
+           AND (wechat_status IS NULL OR (?2 IS NOT NULL AND wechat_checked_at < datetime('now', ?2)))
+         ORDER BY imported_at, id
+         LIMIT ?3",
+    )?;
+    let rows = stmt.query_map(params![device_id, recheck, limit], |row| {
+        Ok(WechatCheckCandidate { id: row.get(0)?, phone: row.get(1)?, name: row.get(2)? })
+    })?;
+    rows.collect()
+}
+
+/// 写入核验结论
+pub fn record_result(conn: &Connection, id: i64, status: WechatStatus, device_id: &str, note: Option<&str>) -> SqlResult<bool> {
+    let changed = conn.execute(
+        "UPDATE contact_numbers
+         SET wechat_status = ?1, wechat_checked_at = datetime('now'), wechat_check_device = ?2, wechat_check_note = ?3
+         WHERE id = ?4",
+        params![status.as_str(), device_id, note, id],
+    )?;
+    Ok(changed > 0)
+}
+
+/// 该设备最近 `hours` 小时内完成的核验次数
+pub fn count_checks_since(conn: &Connection, device_id: &str, hours: i64) -> SqlResult<i64> {
+    conn.query_row(
+        "SELECT COUNT(*) FROM contact_numbers WHERE wechat_check_device = ?1 AND wechat_checked_at >= datetime('now', ?2)",
+        params![device_id, format!("-{} hours", hours)],
+        |row| row.get(0),
+    )
+}
+
+/// 按核验结论汇总（未核验记为 pending），可限定导入设备
+pub fn summarize(conn: &Connection, device_id: Option<&str>) -> SqlResult<BTreeMap<String, i64>> {
+    let mut stmt = conn.prepare(
+        "SELECT COALESCE(wechat_status, 'pending'), COUNT(*) FROM contact_numbers
+         WHERE status = 'imported' AND (?1 IS NULL OR imported_device_id = ?1)
+         GROUP BY 1",
+    )?;
+    let rows = stmt.query_map(params![device_id], |row| Ok((row.get(0)?, row.get(1)?)))?;
+    rows.collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::contact_storage::repositories::common::schema::init_contact_storage_tables;
+
+    #[test]
+    fn lists_records_and_counts_checks() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_contact_storage_tables(&conn).unwrap();
+        conn.execute_batch(
+            "INSERT INTO contact_numbers (phone, name, source_file, status, imported_device_id, imported_at) VALUES
+                 ('13800000001', '甲', 'a.txt', 'imported', 'dev-a', '2024-01-01 10:00:00'),
+                 ('13800000002', '乙', 'a.txt', 'imported', 'dev-a', '2024-01-01 09:00:00'),
+                 ('13800000003', '丙', 'a.txt', 'imported', 'dev-b', '2024-01-01 09:00:00'),
+                 ('13800000004', '丁', 'a.txt', 'available', NULL, NULL);",
+        )
+        .unwrap();
+
+        let pending = list_candidates(&conn, "dev-a", 10, None).unwrap();
+        assert_eq!(pending.iter().map(|c| c.phone.as_str()).collect::<Vec<_>>(), vec!["13800000002", "13800000001"]);
+
+        assert!(record_result(&conn, pending[0].id, WechatStatus::Registered, "dev-a", None).unwrap());
+        assert!(record_result(&conn, pending[1].id, WechatStatus::NotRegistered, "dev-a", Some("该用户不存在")).unwrap());
+        assert!(list_candidates(&conn, "dev-a", 10, None).unwrap().is_empty());
+        // 刚核验过的号码不会被重新核验
+        assert!(list_candidates(&conn, "dev-a", 10, Some(30)).unwrap().is_empty());
+
+        assert_eq!(count_checks_since(&conn, "dev-a", 1).unwrap(), 2);
+        assert_eq!(count_checks_since(&conn, "dev-b", 24).unwrap(), 0);
+
+        let summary = summarize(&conn, None).unwrap();
+        assert_eq!(summary.get("registered"), Some(&1));
+        assert_eq!(summary.get("not_registered"), Some(&1));
+        assert_eq!(summary.get("pending"), Some(&1));
+    }
+}
@@ -42,6 +42,16 @@ impl ContactNumberRepository {
         }
     }
 
+    /// 为导入的号码补充行业标签
+    /// 委托给 basic_operations 子模块
+    pub fn apply_import_industries(
+        conn: &Connection,
+        industries: &std::collections::HashMap<String, String>,
+        source_file: &str,
+    ) -> SqliteResult<i64> {
+        basic_operations::apply_import_industries(conn, industries, source_file)
+    }
+
     /// 分页查询联系人号码  
     /// 委托给 basic_operations 子模块
     pub fn list_numbers(
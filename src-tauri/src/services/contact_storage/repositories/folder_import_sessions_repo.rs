@@ -0,0 +1,224 @@
+/// 文件夹导入会话仓储层实现
+///
+/// 按文件夹记录每个文件的导入结果，重跑时跳过已完成且未变化（大小 / 修改时间一致）的文件，
+/// 失败的文件在下次运行时重试。
+
+use rusqlite::{params, Connection, OptionalExtension, Result as SqliteResult};
+use serde::{Deserialize, Serialize};
+
+/// 单个文件的导入状态
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct FolderImportFileDto {
+    pub file_path: String,
+    pub file_size: i64,
+    pub modified_at: i64,
+    /// completed | failed
+    pub status: String,
+    pub total_numbers: i64,
+    pub inserted: i64,
+    pub duplicates: i64,
+    pub error_message: Option<String>,
+    pub updated_at: String,
+}
+
+/// 文件夹导入会话
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct FolderImportSessionDto {
+    pub id: i64,
+    pub folder_path: String,
+    /// running | completed | partial
+    pub status: String,
+    pub started_at: String,
+    pub finished_at: Option<String>,
+    pub files: Vec<FolderImportFileDto>,
+}
+
+pub fn create_folder_import_tables(conn: &Connection) -> SqliteResult<()> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS folder_import_sessions (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            folder_path TEXT NOT NULL UNIQUE,
+            status TEXT NOT NULL DEFAULT 'running',  -- running, completed, partial
+            started_at TEXT NOT NULL DEFAULT (datetime('now')),
+            finished_at TEXT
+        )",
+        [],
+    )?;
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS folder_import_files (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            session_id INTEGER NOT NULL,  -- 关联 folder_import_sessions.id
+            file_path TEXT NOT NULL,
+            file_size INTEGER NOT NULL DEFAULT 0,
+            modified_at INTEGER NOT NULL DEFAULT 0,
+            status TEXT NOT NULL,  -- completed, failed
+            total_numbers INTEGER NOT NULL DEFAULT 0,
+            inserted INTEGER NOT NULL DEFAULT 0,
+            duplicates INTEGER NOT NULL DEFAULT 0,
+            error_message TEXT,
+            updated_at TEXT NOT NULL DEFAULT (datetime('now')),
+            UNIQUE(session_id, file_path)
+        )",
+        [],
+    )?;
+    Ok(())
+}
+
+/// 打开（或续用）文件夹的导入会话；`restart` 时清空已记录的文件进度
+pub fn open_session(conn: &Connection, folder_path: &str, restart: bool) -> SqliteResult<i64> {
+    conn.execute(
+        "INSERT INTO folder_import_sessions (folder_path, status, started_at)
+         VALUES (?1, 'running', datetime('now'))
+         ON CONFLICT(folder_path) DO UPDATE SET status = 'running', started_at = datetime('now'), finished_at = NULL",
+        params![folder_path],
+    )?;
+    let session_id: i64 = conn.query_row(
+        "SELECT id FROM folder_import_sessions WHERE folder_path = ?1",
+        params![folder_path],
+        |row| row.get(0),
+    )?;
+    if restart {
+        conn.execute("DELETE FROM folder_import_files WHERE session_id = ?1", params![session_id])?;
+    }
+    Ok(session_id)
+}
+
+/// 文件是否已在本会话中成功导入且之后未被修改
+pub fn is_file_completed(
+    conn: &Connection,
+    session_id: i64,
+    file_path: &str,
+    file_size: i64,
+    modified_at: i64,
+) -> SqliteResult<bool> {
+    let found: Option<i64> = conn
+        .query_row(
+            "SELECT 1 FROM folder_import_files
+             WHERE session_id = ?1 AND file_path = ?2 AND status = 'completed' AND file_size = ?3 AND modified_at = ?4",
+            params![session_id, file_path, file_size, modified_at],
+            |row| row.get(0),
+        )
+        .optional()?;
+    Ok(found.is_some())
+}
+
+/// 记录单个文件的导入结果
+pub fn record_file(conn: &Connection, session_id: i64, file: &FolderImportFileDto) -> SqliteResult<()> {
+    conn.execute(
+        "INSERT INTO folder_import_files
+         (session_id, file_path, file_size, modified_at, status, total_numbers, inserted, duplicates, error_message, updated_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, datetime('now'))
+         ON CONFLICT(session_id, file_path) DO UPDATE SET
+           file_size = excluded.file_size,
+           modified_at = excluded.modified_at,
+           status = excluded.status,
+           total_numbers = excluded.total_numbers,
+           inserted = excluded.inserted,
+           duplicates = excluded.duplicates,
+           error_message = excluded.error_message,
+           updated_at = datetime('now')",
+        params![
+            session_id,
+            file.file_path,
+            file.file_size,
+            file.modified_at,
+            file.status,
+            file.total_numbers,
+            file.inserted,
+            file.duplicates,
+            file.error_message,
+        ],
+    )?;
+    Ok(())
+}
+
+pub fn finish_session(conn: &Connection, session_id: i64, status: &str) -> SqliteResult<()> {
+    conn.execute(
+        "UPDATE folder_import_sessions SET status = ?1, finished_at = datetime('now') WHERE id = ?2",
+        params![status, session_id],
+    )?;
+    Ok(())
+}
+
+/// 查询文件夹的导入会话及逐文件进度
+pub fn get_session(conn: &Connection, folder_path: &str) -> SqliteResult<Option<FolderImportSessionDto>> {
+    let session = conn
+        .query_row(
+            "SELECT id, folder_path, status, started_at, finished_at FROM folder_import_sessions WHERE folder_path = ?1",
+            params![folder_path],
+            |row| {
+                Ok(FolderImportSessionDto {
+                    id: row.get(0)?,
+                    folder_path: row.get(1)?,
+                    status: row.get(2)?,
+                    started_at: row.get(3)?,
+                    finished_at: row.get(4)?,
+                    files: Vec::new(),
+                })
+            },
+        )
+        .optional()?;
+    let Some(mut session) = session else {
+        return Ok(None);
+    };
+
+    let mut stmt = conn.prepare(
+        "SELECT file_path, file_size, modified_at, status, total_numbers, inserted, duplicates, error_message, updated_at
+         FROM folder_import_files WHERE session_id = ?1 ORDER BY file_path",
+    )?;
+    let rows = stmt.query_map(params![session.id], |row| {
+        Ok(FolderImportFileDto {
+            file_path: row.get(0)?,
+            file_size: row.get(1)?,
+            modified_at: row.get(2)?,
+            status: row.get(3)?,
+            total_numbers: row.get(4)?,
+            inserted: row.get(5)?,
+            duplicates: row.get(6)?,
+            error_message: row.get(7)?,
+            updated_at: row.get(8)?,
+        })
+    })?;
+    session.files = rows.collect::<SqliteResult<Vec<_>>>()?;
+    Ok(Some(session))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(path: &str, status: &str, size: i64) -> FolderImportFileDto {
+        FolderImportFileDto {
+            file_path: path.into(),
+            file_size: size,
+            modified_at: 100,
+            status: status.into(),
+            total_numbers: 3,
+            inserted: 3,
+            duplicates: 0,
+            error_message: (status == "failed").then(|| "读取失败".to_string()),
+            updated_at: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_resume_skips_only_unchanged_completed_files() {
+        let conn = Connection::open_in_memory().unwrap();
+        create_folder_import_tables(&conn).unwrap();
+
+        let session = open_session(&conn, "/data/in", false).unwrap();
+        record_file(&conn, session, &file("/data/in/a.txt", "completed", 10)).unwrap();
+        record_file(&conn, session, &file("/data/in/b.txt", "failed", 10)).unwrap();
+        finish_session(&conn, session, "partial").unwrap();
+
+        // 续跑沿用同一会话
+        assert_eq!(open_session(&conn, "/data/in", false).unwrap(), session);
+        assert!(is_file_completed(&conn, session, "/data/in/a.txt", 10, 100).unwrap());
+        assert!(!is_file_completed(&conn, session, "/data/in/a.txt", 11, 100).unwrap());
+        assert!(!is_file_completed(&conn, session, "/data/in/b.txt", 10, 100).unwrap());
+        assert_eq!(get_session(&conn, "/data/in").unwrap().unwrap().files.len(), 2);
+
+        open_session(&conn, "/data/in", true).unwrap();
+        assert!(!is_file_completed(&conn, session, "/data/in/a.txt", 10, 100).unwrap());
+    }
+}
@@ -1,5 +1,6 @@
 pub mod common;
 pub mod txt_import_records_repo;
+pub mod folder_import_sessions_repo;
 pub mod contact_numbers;       // 新的模块化结构  
 pub mod vcf_batches;          // VCF批次模块化架构
 
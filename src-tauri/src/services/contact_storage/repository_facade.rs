@@ -9,6 +9,19 @@ use super::facade::{
     DatabaseFacade,
 };
 
+use super::repositories::contact_numbers::allocation_planner::{
+    AllocationExecutionResult, AllocationPlan, AllocationPlanRequest,
+};
+use super::repositories::contact_numbers::destructive_preview::{
+    DestructiveExecution, DestructiveOperation, DestructivePreview, NumberSelection,
+};
+use super::repositories::contact_numbers::global_dedup::{DedupProgress, DedupReport, DedupStrategy, DuplicateNumberGroup};
+use super::repositories::contact_numbers::industry_quota::{IndustryQuotaRule, QuotaViolation};
+use super::repositories::contact_numbers::vcf_chunk_export::VcfChunkExportResult;
+use super::repositories::contact_numbers::wechat_check::{WechatCheckCandidate, WechatStatus};
+use super::repositories::folder_import_sessions_repo::{FolderImportFileDto, FolderImportSessionDto};
+use crate::services::vcf::VcfExportOptions;
+
 // 引入模型类
 use super::models::{
     AllocationResultDto, ContactNumberDto, VcfBatchDto, VcfBatchList, 
@@ -98,6 +111,15 @@ impl ContactStorageFacade {
         ContactNumbersFacade::insert_numbers(&self.app_handle, numbers, source_file)
     }
 
+    /// 为导入的号码补充行业标签（Excel 行业列）
+    pub fn apply_import_industries(
+        &self,
+        industries: &std::collections::HashMap<String, String>,
+        source_file: &str,
+    ) -> Result<i64, String> {
+        ContactNumbersFacade::apply_import_industries(&self.app_handle, industries, source_file)
+    }
+
     /// 获取联系人号码统计
     pub fn get_contact_number_stats(&self) -> Result<serde_json::Value, String> {
         ContactNumbersFacade::get_contact_number_stats(&self.app_handle)
@@ -139,6 +161,47 @@ impl ContactStorageFacade {
         ContactNumbersFacade::delete_numbers_by_ids(&self.app_handle, number_ids)
     }
 
+    /// 全库按规范化号码去重合并（清理历史导入遗留的重复号码）
+    pub fn run_global_dedup(
+        &self,
+        strategy: DedupStrategy,
+        dry_run: bool,
+        chunk_size: usize,
+        on_progress: impl FnMut(&DedupProgress),
+    ) -> Result<DedupReport, String> {
+        ContactNumbersFacade::run_global_dedup(&self.app_handle, strategy, dry_run, chunk_size, on_progress)
+    }
+
+    /// 查找格式不同但规范化后相同的号码分组，`cross_batch_only` 时只返回跨文件 / 跨批次的组
+    pub fn find_duplicate_numbers(&self, cross_batch_only: bool) -> Result<Vec<DuplicateNumberGroup>, String> {
+        ContactNumbersFacade::find_duplicate_numbers(&self.app_handle, cross_batch_only)
+    }
+
+    /// 预览删除 / 标记未导入 / 改行业的影响范围，返回一次性执行令牌
+    pub fn preview_destructive_operation(
+        &self,
+        operation: DestructiveOperation,
+        selection: NumberSelection,
+        sample_size: usize,
+    ) -> Result<DestructivePreview, String> {
+        ContactNumbersFacade::preview_destructive_operation(&self.app_handle, operation, selection, sample_size)
+    }
+
+    /// 凭预览令牌执行；预览后数据有变化时拒绝执行
+    pub fn execute_destructive_operation(&self, token: &str) -> Result<DestructiveExecution, String> {
+        ContactNumbersFacade::execute_destructive_operation(&self.app_handle, token)
+    }
+
+    /// 合并指定的重复组，保留元数据更完整的记录
+    pub fn merge_duplicates(
+        &self,
+        normalized_phones: Option<&[String]>,
+        strategy: DedupStrategy,
+        dry_run: bool,
+    ) -> Result<DedupReport, String> {
+        ContactNumbersFacade::merge_duplicates(&self.app_handle, normalized_phones, strategy, dry_run)
+    }
+
     /// 获取满足筛选条件的所有号码ID
     pub fn list_all_contact_number_ids(
         &self,
@@ -193,6 +256,61 @@ impl ContactStorageFacade {
         ContactNumbersFacade::allocate_numbers_to_device(&self.app_handle, device_id, count, industry_filter, "")
     }
 
+    /// 生成多设备号码分配方案（均衡数量、行业偏好、避开曾导入到该设备的号码）
+    pub fn plan_allocation(&self, request: &AllocationPlanRequest) -> Result<AllocationPlan, String> {
+        ContactNumbersFacade::plan_allocation(&self.app_handle, request)
+    }
+
+    /// 执行审阅后的分配方案（单事务，冲突时整体回滚）
+    pub fn execute_allocation_plan(&self, plan: &AllocationPlan) -> Result<AllocationExecutionResult, String> {
+        ContactNumbersFacade::execute_allocation_plan(&self.app_handle, plan)
+    }
+
+    /// 撤销分配方案生成的批次，返回释放回可用的号码数
+    pub fn revert_allocation_batch(&self, batch_id: &str) -> Result<i64, String> {
+        ContactNumbersFacade::revert_allocation_batch(&self.app_handle, batch_id)
+    }
+
+    /// 列出行业配额规则
+    pub fn list_industry_quota_rules(&self) -> Result<Vec<IndustryQuotaRule>, String> {
+        ContactNumbersFacade::list_industry_quota_rules(&self.app_handle)
+    }
+
+    /// 新建或更新行业配额规则
+    pub fn save_industry_quota_rule(&self, rule: &IndustryQuotaRule) -> Result<IndustryQuotaRule, String> {
+        ContactNumbersFacade::save_industry_quota_rule(&self.app_handle, rule)
+    }
+
+    /// 删除行业配额规则
+    pub fn delete_industry_quota_rule(&self, id: i64) -> Result<bool, String> {
+        ContactNumbersFacade::delete_industry_quota_rule(&self.app_handle, id)
+    }
+
+    /// 预检行业配额
+    pub fn check_industry_quota(&self, device_id: &str, number_ids: &[i64]) -> Result<Vec<QuotaViolation>, String> {
+        ContactNumbersFacade::check_industry_quota(&self.app_handle, device_id, number_ids)
+    }
+
+    /// 待做微信注册核验的号码
+    pub fn list_wechat_check_candidates(&self, device_id: &str, limit: i64, recheck_after_days: Option<i64>) -> Result<Vec<WechatCheckCandidate>, String> {
+        ContactNumbersFacade::list_wechat_check_candidates(&self.app_handle, device_id, limit, recheck_after_days)
+    }
+
+    /// 写入微信注册核验结论
+    pub fn record_wechat_check(&self, id: i64, status: WechatStatus, device_id: &str, note: Option<&str>) -> Result<bool, String> {
+        ContactNumbersFacade::record_wechat_check(&self.app_handle, id, status, device_id, note)
+    }
+
+    /// 设备最近若干小时内的核验次数
+    pub fn count_wechat_checks_since(&self, device_id: &str, hours: i64) -> Result<i64, String> {
+        ContactNumbersFacade::count_wechat_checks_since(&self.app_handle, device_id, hours)
+    }
+
+    /// 微信核验结论汇总
+    pub fn summarize_wechat_checks(&self, device_id: Option<&str>) -> Result<std::collections::BTreeMap<String, i64>, String> {
+        ContactNumbersFacade::summarize_wechat_checks(&self.app_handle, device_id)
+    }
+
     /// 获取号码
     pub fn fetch_numbers(&self, count: i64) -> Result<Vec<ContactNumberDto>, String> {
         ContactNumbersFacade::fetch_numbers(&self.app_handle, count)
@@ -366,6 +484,31 @@ impl ContactStorageFacade {
         TxtImportFacade::find_txt_import_record_by_path(&self.app_handle, file_path)
     }
 
+    /// 打开（或续用）文件夹导入会话
+    pub fn open_folder_import_session(&self, folder_path: &str, restart: bool) -> Result<i64, String> {
+        TxtImportFacade::open_folder_import_session(&self.app_handle, folder_path, restart)
+    }
+
+    /// 文件是否已在会话中导入完成且未变化
+    pub fn is_folder_file_completed(&self, session_id: i64, file_path: &str, file_size: i64, modified_at: i64) -> Result<bool, String> {
+        TxtImportFacade::is_folder_file_completed(&self.app_handle, session_id, file_path, file_size, modified_at)
+    }
+
+    /// 记录文件夹中单个文件的导入结果
+    pub fn record_folder_import_file(&self, session_id: i64, file: &FolderImportFileDto) -> Result<(), String> {
+        TxtImportFacade::record_folder_import_file(&self.app_handle, session_id, file)
+    }
+
+    /// 结束文件夹导入会话
+    pub fn finish_folder_import_session(&self, session_id: i64, status: &str) -> Result<(), String> {
+        TxtImportFacade::finish_folder_import_session(&self.app_handle, session_id, status)
+    }
+
+    /// 查询文件夹导入会话
+    pub fn get_folder_import_session(&self, folder_path: &str) -> Result<Option<FolderImportSessionDto>, String> {
+        TxtImportFacade::get_folder_import_session(&self.app_handle, folder_path)
+    }
+
     /// 更新TXT导入统计
     pub fn update_txt_import_stats(
         &self,
@@ -433,8 +576,13 @@ impl ContactStorageFacade {
 
     // VCF 批次相关缺失方法
     /// 创建VCF批次（包含号码）
-    pub fn create_vcf_batch_with_numbers(&self, batch_name: &str, number_count: i64, industry: &str, device_id: &str) -> Result<VcfBatchCreationResult, String> {
-        VcfBatchesFacade::create_vcf_batch_with_numbers(&self.app_handle, batch_name, number_count, industry, device_id)
+    pub fn create_vcf_batch_with_numbers(&self, batch_name: &str, number_ids: &[i64], device_id: Option<&str>) -> Result<VcfBatchCreationResult, String> {
+        VcfBatchesFacade::create_vcf_batch_with_numbers(&self.app_handle, batch_name, number_ids, device_id)
+    }
+
+    /// 分片导出号码为VCF并按分片记录批次
+    pub fn export_numbers_to_vcf(&self, number_ids: &[i64], output_path: &str, options: &VcfExportOptions) -> Result<VcfChunkExportResult, String> {
+        VcfBatchesFacade::export_numbers_to_vcf(&self.app_handle, number_ids, output_path, options)
     }
 
     /// 搜索VCF批次（按名称）
@@ -1,11 +1,54 @@
 // src-tauri/src/services/contact_verification.rs
 // module: contact-verification | layer: services | role: 快速号码验证服务
-// summary: 实现智能采样验证，提供高效的联系人导入验证功能
+// summary: 一次拉取设备通讯录号码后本地比对（失败时退回分块 IN 查询），逐号返回验证结果并上报进度
 
-use anyhow::Result;
 use serde::{Deserialize, Serialize};
-use std::process::Command;
+use std::collections::{HashMap, HashSet};
+use std::time::Instant;
 use tracing::{info, warn};
+use ts_rs::TS;
+
+use crate::services::contact_storage::parser::normalizers::normalize_phone_number;
+use crate::services::adb::transport::{AdbTransport, CommandAdbTransport};
+
+/// 分块查询时每条 `IN (...)` 的号码数（受 adb shell 命令行长度限制）
+pub const IN_QUERY_CHUNK_SIZE: usize = 100;
+/// 本地比对阶段每处理多少个号码上报一次进度
+const DIFF_PROGRESS_STEP: usize = 500;
+const PHONE_MIME_WHERE: &str = "mimetype='vnd.android.cursor.item/phone_v2'";
+
+/// 单个号码的验证状态
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum NumberVerificationStatus {
+    /// 设备通讯录中存在
+    Found,
+    /// 设备通讯录中不存在
+    Missing,
+    /// 输入不是有效号码，未参与比对
+    Invalid,
+}
+
+/// 单个号码的验证结果
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct NumberVerification {
+    pub phone: String,
+    pub normalized: String,
+    pub status: NumberVerificationStatus,
+    /// 设备上匹配到的原始号码写法（如 `138 1234 5678`）
+    pub device_value: Option<String>,
+}
+
+/// 进度事件负载
+#[derive(Debug, Clone, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+pub struct VerificationProgress {
+    /// pulling | querying | diffing | done
+    pub phase: String,
+    pub processed: usize,
+    pub total: usize,
+}
 
 /// 验证结果
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -13,185 +56,226 @@ use tracing::{info, warn};
 pub struct VerificationResult {
     pub success: bool,
     pub total_expected: i32,
+    /// 实际参与比对的号码数（全量比对后等于有效号码数）
     pub sampled_count: i32,
     pub found_count: i32,
     pub success_rate: f64,
     pub estimated_imported: i32,
+    /// device_snapshot_diff | chunked_in_query | empty_input
     pub method: String,
+    /// 已在设备上找到的号码
     pub verified_phones: Vec<String>,
+    #[serde(default)]
+    pub results: Vec<NumberVerification>,
+    #[serde(default)]
+    pub duration_ms: u64,
 }
 
-/// 🚀 快速验证联系人（Tauri命令）
-// #[tauri::command] - Moved to plugin:contacts
-pub async fn verify_contacts_fast(
+/// 🚀 批量验证号码是否已导入设备通讯录
+///
+/// 先一次性拉取设备上全部电话号码并在本地比对；拉取失败时退回按块 `IN (...)` 查询。
+pub async fn verify_contacts_fast<F>(
     device_id: String,
     phone_numbers: Vec<String>,
-) -> Result<VerificationResult, String> {
-    info!("🔍 开始快速验证 {} 个号码", phone_numbers.len());
-    
-    if phone_numbers.is_empty() {
-        return Ok(VerificationResult {
-            success: false,
-            total_expected: 0,
-            sampled_count: 0,
-            found_count: 0,
-            success_rate: 0.0,
-            estimated_imported: 0,
-            method: "empty_input".to_string(),
-            verified_phones: vec![],
-        });
+    on_progress: F,
+) -> Result<VerificationResult, String>
+where
+    F: FnMut(VerificationProgress) + Send + 'static,
+{
+    info!("🔍 开始批量验证 {} 个号码", phone_numbers.len());
+    tokio::task::spawn_blocking(move || {
+        verify_numbers_with(&CommandAdbTransport::system(), &device_id, &phone_numbers, on_progress)
+    })
+    .await
+    .map_err(|e| format!("号码验证任务异常: {}", e))?
+}
+
+/// 通过指定 ADB 通道同步验证（阻塞调用；场景回归使用假设备）
+pub fn verify_numbers_with<F>(
+    transport: &dyn AdbTransport,
+    device_id: &str,
+    phone_numbers: &[String],
+    mut on_progress: F,
+) -> Result<VerificationResult, String>
+where
+    F: FnMut(VerificationProgress),
+{
+    let started = Instant::now();
+    let total = phone_numbers.len();
+    if total == 0 {
+        return Ok(build_result(0, Vec::new(), "empty_input", 0));
     }
-    
-    // 步骤1: 智能选择样本
-    let samples = select_verification_samples(&phone_numbers);
-    info!("📊 从 {} 个中选择 {} 个样本进行验证", phone_numbers.len(), samples.len());
-    
-    // 步骤2: 快速检查每个样本
-    let mut found_count = 0;
-    for phone in &samples {
-        match check_contact_exists_fast(&device_id, phone).await {
-            Ok(true) => {
-                found_count += 1;
-                info!("✅ 找到号码: {}", phone);
-            }
-            Ok(false) => {
-                info!("❌ 未找到号码: {}", phone);
-            }
-            Err(e) => {
-                warn!("⚠️ 检查号码失败 {}: {}", phone, e);
+
+    let wanted: Vec<String> = phone_numbers.iter().map(|p| normalize_phone_number(p)).collect();
+    on_progress(VerificationProgress { phase: "pulling".to_string(), processed: 0, total });
+
+    let (device_numbers, method) = match pull_device_numbers(transport, device_id) {
+        Ok(values) => {
+            info!("📥 设备 {} 通讯录共 {} 个号码，开始本地比对", device_id, values.len());
+            (values, "device_snapshot_diff")
+        }
+        Err(e) => {
+            warn!("⚠️ 拉取设备通讯录失败，改用分块查询: {}", e);
+            let valid: Vec<String> = unique_valid(&wanted);
+            let mut values = Vec::new();
+            let mut processed = 0;
+            for chunk in valid.chunks(IN_QUERY_CHUNK_SIZE) {
+                values.extend(query_numbers_chunk(transport, device_id, chunk)?);
+                processed += chunk.len();
+                on_progress(VerificationProgress { phase: "querying".to_string(), processed, total: valid.len() });
             }
+            (values, "chunked_in_query")
         }
-    }
-    
-    // 步骤3: 计算结果
-    let success_rate = found_count as f64 / samples.len() as f64;
-    let estimated_imported = (phone_numbers.len() as f64 * success_rate) as i32;
-    
-    let method = match found_count {
-        n if n == samples.len() => "fast_sample_all_success".to_string(),
-        0 => "fast_sample_all_failed".to_string(),
-        _ => "fast_sample_partial".to_string(),
     };
-    
+
+    let device_index = index_device_numbers(&device_numbers);
+    let mut results = Vec::with_capacity(total);
+    for (i, (phone, normalized)) in phone_numbers.iter().zip(wanted).enumerate() {
+        results.push(classify(phone, normalized, &device_index));
+        if (i + 1) % DIFF_PROGRESS_STEP == 0 {
+            on_progress(VerificationProgress { phase: "diffing".to_string(), processed: i + 1, total });
+        }
+    }
+    on_progress(VerificationProgress { phase: "done".to_string(), processed: total, total });
+
+    let result = build_result(total, results, method, started.elapsed().as_millis() as u64);
     info!(
-        "📊 验证完成: {}/{} 成功, 成功率: {:.1}%, 推断导入: {} 个",
-        found_count, samples.len(), success_rate * 100.0, estimated_imported
+        "📊 验证完成: {}/{} 已在设备上, 方法 {}, 耗时 {} ms",
+        result.found_count, result.sampled_count, result.method, result.duration_ms
     );
-    
-    Ok(VerificationResult {
-        success: found_count > 0,
-        total_expected: phone_numbers.len() as i32,
-        sampled_count: samples.len() as i32,
-        found_count: found_count as i32,
-        success_rate,
-        estimated_imported,
-        method,
-        verified_phones: samples,
-    })
+    Ok(result)
 }
 
-/// 🎯 智能选择验证样本
-fn select_verification_samples(phones: &[String]) -> Vec<String> {
-    if phones.len() <= 5 {
-        // 少于5个，全部验证
-        return phones.to_vec();
+fn is_valid_number(normalized: &str) -> bool {
+    normalized.len() >= 5
+}
+
+fn unique_valid(normalized: &[String]) -> Vec<String> {
+    let mut seen = HashSet::new();
+    normalized.iter().filter(|n| is_valid_number(n) && seen.insert(n.as_str())).cloned().collect()
+}
+
+/// 规范化号码 → 设备上的原始写法（保留第一次出现的）
+fn index_device_numbers(values: &[String]) -> HashMap<String, String> {
+    let mut index = HashMap::with_capacity(values.len());
+    for value in values {
+        index.entry(normalize_phone_number(value)).or_insert_with(|| value.clone());
     }
-    
-    // 字典序排序（130开头 > 135开头 > 138开头...）
-    let mut sorted = phones.to_vec();
-    sorted.sort();
-    
-    // 取前5个（最容易在联系人列表首页找到）
-    sorted.into_iter().take(5).collect()
-}
-
-/// 🚀 快速检查号码是否存在
-async fn check_contact_exists_fast(device_id: &str, phone: &str) -> Result<bool> {
-    // 规范化号码（去除空格、横线等）
-    let normalized = phone.replace(&[' ', '-', '(', ')', '+'][..], "");
-    
-    // 构建ADB查询命令
-    let adb_path = get_adb_path()?;
-    
-    let query = format!(
-        "content query --uri content://com.android.contacts/data \
-         --projection data1 \
-         --where \"mimetype='vnd.android.cursor.item/phone_v2' AND data1 LIKE '%{}%'\"",
-        normalized
-    );
-    
-    // 执行ADB命令
-    let output = Command::new(adb_path)
-        .args(&["-s", device_id, "shell", &query])
-        .output()
-        .map_err(|e| anyhow::anyhow!("执行ADB命令失败: {}", e))?;
-    
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let stderr = String::from_utf8_lossy(&output.stderr);
-    
-    // 检查是否找到号码
-    let found = stdout.contains("data1=") || stdout.contains(&normalized);
-    
-    if !stderr.is_empty() && !stderr.contains("Row:") {
-        warn!("ADB查询警告: {}", stderr);
+    index
+}
+
+fn classify(phone: &str, normalized: String, device_index: &HashMap<String, String>) -> NumberVerification {
+    let (status, device_value) = if !is_valid_number(&normalized) {
+        (NumberVerificationStatus::Invalid, None)
+    } else if let Some(value) = device_index.get(&normalized) {
+        (NumberVerificationStatus::Found, Some(value.clone()))
+    } else {
+        (NumberVerificationStatus::Missing, None)
+    };
+    NumberVerification { phone: phone.to_string(), normalized, status, device_value }
+}
+
+fn build_result(total: usize, results: Vec<NumberVerification>, method: &str, duration_ms: u64) -> VerificationResult {
+    let checked = results.iter().filter(|r| r.status != NumberVerificationStatus::Invalid).count();
+    let verified_phones: Vec<String> = results
+        .iter()
+        .filter(|r| r.status == NumberVerificationStatus::Found)
+        .map(|r| r.phone.clone())
+        .collect();
+    let found = verified_phones.len();
+    VerificationResult {
+        success: found > 0,
+        total_expected: total as i32,
+        sampled_count: checked as i32,
+        found_count: found as i32,
+        success_rate: if checked == 0 { 0.0 } else { found as f64 / checked as f64 },
+        estimated_imported: found as i32,
+        method: method.to_string(),
+        verified_phones,
+        results,
+        duration_ms,
     }
-    
-    Ok(found)
-}
-
-/// 获取ADB路径
-fn get_adb_path() -> Result<String> {
-    // 尝试多个可能的路径
-    let possible_paths = vec![
-        "D:\\开发\\marketing-automation-desktop\\platform-tools\\adb.exe",
-        "platform-tools/adb.exe",
-        "adb.exe",
-        "adb",
+}
+
+/// 一次性拉取设备通讯录中的全部电话号码
+fn pull_device_numbers(transport: &dyn AdbTransport, device_id: &str) -> Result<Vec<String>, String> {
+    run_phone_query(transport, device_id, PHONE_MIME_WHERE)
+}
+
+/// 分块查询：同时匹配去掉空格 / 横线后的纯号码和带 +86 的写法
+fn query_numbers_chunk(transport: &dyn AdbTransport, device_id: &str, chunk: &[String]) -> Result<Vec<String>, String> {
+    let list = chunk
+        .iter()
+        .flat_map(|n| [format!("'{}'", n), format!("'+86{}'", n)])
+        .collect::<Vec<_>>()
+        .join(",");
+    let clause = format!("{} AND REPLACE(REPLACE(data1,' ',''),'-','') IN ({})", PHONE_MIME_WHERE, list);
+    run_phone_query(transport, device_id, &clause)
+}
+
+fn run_phone_query(transport: &dyn AdbTransport, device_id: &str, where_clause: &str) -> Result<Vec<String>, String> {
+    let quoted_where = format!("\"{}\"", where_clause);
+    let args = [
+        "-s",
+        device_id,
+        "shell",
+        "content",
+        "query",
+        "--uri",
+        "content://com.android.contacts/data",
+        "--projection",
+        "data1",
+        "--where",
+        quoted_where.as_str(),
     ];
-    
-    for path in possible_paths {
-        if std::path::Path::new(path).exists() {
-            return Ok(path.to_string());
-        }
+    let output = transport.execute(&args).map_err(|e| format!("执行ADB命令失败: {}", e))?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    if !output.status.success() || stdout.contains("Error while accessing provider") {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("content query 失败: {}{}", stderr.trim(), stdout.trim()));
     }
-    
-    // 如果都不存在，尝试从PATH中找
-    Ok("adb".to_string())
+    Ok(parse_data1_rows(&stdout))
+}
+
+/// 解析 `Row: 0 data1=138 1234 5678` 形式的输出
+fn parse_data1_rows(output: &str) -> Vec<String> {
+    output
+        .lines()
+        .filter(|line| line.trim_start().starts_with("Row"))
+        .filter_map(|line| line.find("data1=").map(|idx| line[idx + "data1=".len()..].trim().to_string()))
+        .filter(|value| !value.is_empty() && value != "NULL")
+        .collect()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    
-    #[test]
-    fn test_select_verification_samples() {
-        let phones = vec![
-            "13912345678".to_string(),
-            "13012345678".to_string(),
-            "15912345678".to_string(),
-            "13512345678".to_string(),
-            "13812345678".to_string(),
-            "18612345678".to_string(),
-        ];
-        
-        let samples = select_verification_samples(&phones);
-        
-        assert_eq!(samples.len(), 5);
-        // 应该按字典序排列
-        assert_eq!(samples[0], "13012345678");
-        assert_eq!(samples[1], "13512345678");
-    }
-    
+
     #[test]
-    fn test_select_verification_samples_small() {
-        let phones = vec![
-            "13912345678".to_string(),
-            "13012345678".to_string(),
-        ];
-        
-        let samples = select_verification_samples(&phones);
-        
-        // 少于5个，应该全部返回
-        assert_eq!(samples.len(), 2);
+    fn test_parse_and_diff_device_snapshot() {
+        let output = "Row: 0 data1=138 1234 5678\nRow: 1 data1=+86 139-1234-5678\nRow: 2 data1=NULL\n";
+        let device = parse_data1_rows(output);
+        assert_eq!(device, vec!["138 1234 5678", "+86 139-1234-5678"]);
+
+        let index = index_device_numbers(&device);
+        let phones = ["13812345678", "8613912345678", "13012345678", "abc"];
+        let results: Vec<NumberVerification> =
+            phones.iter().map(|p| classify(p, normalize_phone_number(p), &index)).collect();
+        let statuses: Vec<_> = results.iter().map(|r| r.status).collect();
+        assert_eq!(
+            statuses,
+            vec![
+                NumberVerificationStatus::Found,
+                NumberVerificationStatus::Found,
+                NumberVerificationStatus::Missing,
+                NumberVerificationStatus::Invalid,
+            ]
+        );
+        assert_eq!(results[0].device_value.as_deref(), Some("138 1234 5678"));
+
+        let result = build_result(phones.len(), results, "device_snapshot_diff", 0);
+        assert_eq!(result.sampled_count, 3);
+        assert_eq!(result.found_count, 2);
+        assert_eq!(result.verified_phones, vec!["13812345678", "8613912345678"]);
     }
 }
@@ -0,0 +1,368 @@
+// src-tauri/src/services/contact_watch/mod.rs
+// module: contact_watch | layer: services | role: 联系人来源文件夹监听
+// summary: 配置需要监听的目录（持久化为 JSON），新增 / 修改的 TXT、vCard 文件去抖后自动导入并按来源打标；支持整体与单个来源的暂停 / 恢复，导入动作由插件层实现的 ContactWatchImporter 提供
+
+mod watcher;
+
+pub use watcher::{reload, run};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+const STORE_FILE_NAME: &str = "contact_watch_sources.json";
+/// 前端订阅的自动导入事件
+pub const CONTACT_AUTO_IMPORT_EVENT: &str = "contacts://auto-import";
+/// 文件最后一次变化后静默多久才导入（写入中的文件会持续产生事件）
+pub const DEFAULT_DEBOUNCE: Duration = Duration::from_secs(2);
+/// 文件夹监听支持的扩展名（Excel 需要列映射，不自动导入）
+pub const SUPPORTED_EXTENSIONS: [&str; 2] = ["txt", "vcf"];
+
+static STORE: OnceLock<Mutex<ContactWatchStore>> = OnceLock::new();
+static STATS: OnceLock<Mutex<HashMap<String, WatchSourceStats>>> = OnceLock::new();
+
+/// 监听来源
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WatchSource {
+    #[serde(default)]
+    pub id: String,
+    pub name: String,
+    pub directory: PathBuf,
+    /// 是否包含子目录
+    #[serde(default)]
+    pub recursive: bool,
+    #[serde(default = "default_extensions")]
+    pub extensions: Vec<String>,
+    #[serde(default)]
+    pub paused: bool,
+}
+
+fn default_extensions() -> Vec<String> {
+    SUPPORTED_EXTENSIONS.iter().map(|e| e.to_string()).collect()
+}
+
+impl WatchSource {
+    /// 导入记录使用的会话键：同一来源的文件归入同一个文件夹导入会话，既是来源标记也用于跳过未变化的文件
+    pub fn session_key(&self) -> String {
+        format!("watch:{}", self.id)
+    }
+
+    /// 文件是否属于该来源（目录层级与扩展名）
+    pub fn covers(&self, directory: &Path, path: &Path) -> bool {
+        let in_scope = if self.recursive { path.starts_with(directory) } else { path.parent() == Some(directory) };
+        let ext = path.extension().map(|e| e.to_string_lossy().to_lowercase()).unwrap_or_default();
+        in_scope && self.extensions.iter().any(|e| *e == ext)
+    }
+
+    fn validated(mut self) -> Result<Self, String> {
+        self.name = self.name.trim().to_string();
+        if self.name.is_empty() {
+            return Err("来源名称不能为空".to_string());
+        }
+        if !self.directory.is_absolute() {
+            return Err(format!("监听目录必须是绝对路径: {}", self.directory.display()));
+        }
+        if !self.directory.is_dir() {
+            return Err(format!("监听目录不存在或不是目录: {}", self.directory.display()));
+        }
+        self.extensions = self.extensions.iter().map(|e| e.trim().trim_start_matches('.').to_lowercase()).collect();
+        self.extensions.sort();
+        self.extensions.dedup();
+        if self.extensions.is_empty() {
+            self.extensions = default_extensions();
+        }
+        if let Some(ext) = self.extensions.iter().find(|e| !SUPPORTED_EXTENSIONS.contains(&e.as_str())) {
+            return Err(format!("不支持自动导入的扩展名: {}", ext));
+        }
+        Ok(self)
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct StoreFile {
+    /// 整体暂停
+    #[serde(default)]
+    paused: bool,
+    #[serde(default)]
+    sources: Vec<WatchSource>,
+}
+
+/// 监听来源存储
+pub struct ContactWatchStore {
+    path: PathBuf,
+    data: StoreFile,
+}
+
+impl ContactWatchStore {
+    pub fn global() -> &'static Mutex<ContactWatchStore> {
+        STORE.get_or_init(|| {
+            let path = dirs::data_dir().unwrap_or_else(|| PathBuf::from(".")).join("employee-gui").join(STORE_FILE_NAME);
+            Mutex::new(Self::open(path))
+        })
+    }
+
+    pub fn open(path: PathBuf) -> Self {
+        let data = std::fs::read_to_string(&path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+        Self { path, data }
+    }
+
+    fn save(&self) -> Result<(), String> {
+        if let Some(dir) = self.path.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+        }
+        let content = serde_json::to_string_pretty(&self.data).map_err(|e| e.to_string())?;
+        std::fs::write(&self.path, content).map_err(|e| format!("保存监听来源失败: {}", e))
+    }
+
+    pub fn sources(&self) -> Vec<WatchSource> {
+        self.data.sources.clone()
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.data.paused
+    }
+
+    /// 需要监听的来源（未整体暂停且来源本身未暂停）
+    pub fn active_sources(&self) -> Vec<WatchSource> {
+        if self.data.paused {
+            return Vec::new();
+        }
+        self.data.sources.iter().filter(|s| !s.paused).cloned().collect()
+    }
+
+    /// 新增或更新来源；`id` 为空时新增
+    pub fn save_source(&mut self, source: WatchSource) -> Result<WatchSource, String> {
+        let mut source = source.validated()?;
+        if source.id.is_empty() {
+            source.id = uuid::Uuid::new_v4().to_string();
+        }
+        if let Some(other) = self.data.sources.iter().find(|s| s.id != source.id && s.directory == source.directory) {
+            return Err(format!("该目录已被来源「{}」监听", other.name));
+        }
+        match self.data.sources.iter_mut().find(|s| s.id == source.id) {
+            Some(existing) => *existing = source.clone(),
+            None => self.data.sources.push(source.clone()),
+        }
+        self.save()?;
+        Ok(source)
+    }
+
+    /// 删除来源，返回是否存在
+    pub fn remove_source(&mut self, source_id: &str) -> Result<bool, String> {
+        let before = self.data.sources.len();
+        self.data.sources.retain(|s| s.id != source_id);
+        if self.data.sources.len() == before {
+            return Ok(false);
+        }
+        self.save()?;
+        Ok(true)
+    }
+
+    /// 暂停 / 恢复；`source_id` 为空时作用于整体
+    pub fn set_paused(&mut self, source_id: Option<&str>, paused: bool) -> Result<(), String> {
+        match source_id {
+            None => self.data.paused = paused,
+            Some(id) => {
+                let source = self.data.sources.iter_mut().find(|s| s.id == id).ok_or_else(|| format!("监听来源不存在: {}", id))?;
+                source.paused = paused;
+            }
+        }
+        self.save()
+    }
+}
+
+/// 单个文件的自动导入结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum AutoImportStatus {
+    Imported,
+    /// 已导入且之后未修改
+    Unchanged,
+    Failed,
+}
+
+/// `contacts://auto-import` 事件负载
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AutoImportSummary {
+    pub source_id: String,
+    pub source_name: String,
+    pub file_path: String,
+    pub status: AutoImportStatus,
+    pub total_numbers: i64,
+    pub inserted: i64,
+    pub duplicates: i64,
+    pub error: Option<String>,
+    pub finished_at: DateTime<Utc>,
+}
+
+/// 单个来源的运行统计（进程内）
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WatchSourceStats {
+    pub imported_files: u64,
+    pub failed_files: u64,
+    pub inserted_numbers: i64,
+    pub last_import_at: Option<DateTime<Utc>>,
+    /// 最近一次导入或建立监听的错误
+    pub last_error: Option<String>,
+}
+
+fn with_stats<T>(f: impl FnOnce(&mut HashMap<String, WatchSourceStats>) -> T) -> T {
+    let mut stats = STATS.get_or_init(|| Mutex::new(HashMap::new())).lock().unwrap_or_else(|e| e.into_inner());
+    f(&mut stats)
+}
+
+fn record_summary(summary: &AutoImportSummary) {
+    with_stats(|stats| {
+        let entry = stats.entry(summary.source_id.clone()).or_default();
+        match summary.status {
+            AutoImportStatus::Imported => {
+                entry.imported_files += 1;
+                entry.inserted_numbers += summary.inserted;
+                entry.last_import_at = Some(summary.finished_at);
+                entry.last_error = None;
+            }
+            AutoImportStatus::Failed => {
+                entry.failed_files += 1;
+                entry.last_error = summary.error.clone();
+            }
+            AutoImportStatus::Unchanged => {}
+        }
+    });
+}
+
+fn record_watch_error(source_id: &str, error: String) {
+    with_stats(|stats| stats.entry(source_id.to_string()).or_default().last_error = Some(error));
+}
+
+/// 来源及其运行状态
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WatchSourceStatus {
+    pub source: WatchSource,
+    /// 当前是否在监听（整体与来源均未暂停）
+    pub active: bool,
+    pub stats: WatchSourceStats,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContactWatchStatus {
+    pub paused: bool,
+    pub sources: Vec<WatchSourceStatus>,
+}
+
+pub fn status() -> Result<ContactWatchStatus, String> {
+    let store = ContactWatchStore::global().lock().map_err(|e| e.to_string())?;
+    let sources = store
+        .sources()
+        .into_iter()
+        .map(|source| WatchSourceStatus {
+            active: !store.is_paused() && !source.paused,
+            stats: with_stats(|stats| stats.get(&source.id).cloned().unwrap_or_default()),
+            source,
+        })
+        .collect();
+    Ok(ContactWatchStatus { paused: store.is_paused(), sources })
+}
+
+/// 自动导入执行器（由插件层实现，持有数据库等运行时资源）；在阻塞线程中调用
+pub trait ContactWatchImporter: Send + Sync {
+    fn import(&self, source: &WatchSource, path: &Path) -> Result<AutoImportSummary, String>;
+
+    /// 文件导入完成或失败后通知，未变化而跳过的文件不通知（默认忽略）
+    fn on_imported(&self, _summary: &AutoImportSummary) {}
+}
+
+/// 按文件去抖：最后一次事件后静默满窗口期才交付
+pub struct Debouncer {
+    window: Duration,
+    pending: HashMap<(String, PathBuf), Instant>,
+}
+
+impl Debouncer {
+    pub fn new(window: Duration) -> Self {
+        Self { window, pending: HashMap::new() }
+    }
+
+    pub fn touch(&mut self, source_id: &str, path: PathBuf, now: Instant) {
+        self.pending.insert((source_id.to_string(), path), now);
+    }
+
+    /// 丢弃不再监听的来源的待处理文件
+    pub fn retain_sources(&mut self, source_ids: &[String]) {
+        self.pending.retain(|(id, _), _| source_ids.contains(id));
+    }
+
+    /// 取出已静默满窗口期的文件（按路径排序）
+    pub fn drain_ready(&mut self, now: Instant) -> Vec<(String, PathBuf)> {
+        let mut ready: Vec<(String, PathBuf)> = self
+            .pending
+            .iter()
+            .filter(|(_, last)| now.duration_since(**last) >= self.window)
+            .map(|(key, _)| key.clone())
+            .collect();
+        for key in &ready {
+            self.pending.remove(key);
+        }
+        ready.sort_by(|a, b| a.1.cmp(&b.1));
+        ready
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sources_scope_pause_and_debounce() {
+        let dir = tempfile::tempdir().unwrap();
+        let inbox = dir.path().join("inbox");
+        std::fs::create_dir_all(inbox.join("sub")).unwrap();
+
+        let mut store = ContactWatchStore::open(dir.path().join(STORE_FILE_NAME));
+        let draft = WatchSource {
+            id: String::new(),
+            name: " 共享目录 ".into(),
+            directory: inbox.clone(),
+            recursive: false,
+            extensions: vec![".TXT".into()],
+            paused: false,
+        };
+        assert!(store.save_source(WatchSource { extensions: vec!["xlsx".into()], ..draft.clone() }).is_err());
+        let source = store.save_source(draft.clone()).unwrap();
+        assert_eq!((source.name.as_str(), source.extensions.clone()), ("共享目录", vec!["txt".to_string()]));
+        assert!(store.save_source(draft).is_err(), "同一目录不能重复监听");
+
+        assert!(source.covers(&inbox, &inbox.join("a.TXT")));
+        assert!(!source.covers(&inbox, &inbox.join("a.vcf")));
+        assert!(!source.covers(&inbox, &inbox.join("sub/a.txt")));
+        assert!(WatchSource { recursive: true, ..source.clone() }.covers(&inbox, &inbox.join("sub/a.txt")));
+
+        store.set_paused(Some(&source.id), true).unwrap();
+        assert!(store.active_sources().is_empty());
+        store.set_paused(Some(&source.id), false).unwrap();
+        store.set_paused(None, true).unwrap();
+        let reopened = ContactWatchStore::open(dir.path().join(STORE_FILE_NAME));
+        assert!(reopened.is_paused() && reopened.active_sources().is_empty());
+        assert_eq!(reopened.sources(), vec![source.clone()]);
+
+        let start = Instant::now();
+        let mut debouncer = Debouncer::new(Duration::from_secs(2));
+        debouncer.touch(&source.id, inbox.join("b.txt"), start);
+        debouncer.touch(&source.id, inbox.join("a.txt"), start);
+        debouncer.touch(&source.id, inbox.join("b.txt"), start + Duration::from_secs(1));
+        let ready = debouncer.drain_ready(start + Duration::from_secs(2));
+        assert_eq!(ready, vec![(source.id.clone(), inbox.join("a.txt"))], "仍在写入的文件继续等待");
+        assert_eq!(debouncer.drain_ready(start + Duration::from_secs(3)).len(), 1);
+        assert!(debouncer.drain_ready(start + Duration::from_secs(10)).is_empty());
+    }
+}
@@ -0,0 +1,164 @@
+// src-tauri/src/services/contact_watch/watcher.rs
+// module: contact_watch | layer: services | role: 文件夹监听循环
+// summary: 用 notify 监听活跃来源的目录，创建 / 修改事件经去抖后逐个交给 ContactWatchImporter 导入；来源变更或恢复时重建监听并补扫目录中的现有文件
+
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, OnceLock};
+use std::time::{Duration, Instant};
+use tokio::sync::{mpsc, Notify};
+use tracing::{info, warn};
+
+use super::{
+    record_summary, record_watch_error, AutoImportStatus, ContactWatchImporter, ContactWatchStore, Debouncer, WatchSource, DEFAULT_DEBOUNCE,
+};
+
+/// 无事件时检查去抖队列的间隔
+const TICK: Duration = Duration::from_millis(500);
+
+static RELOAD: OnceLock<Notify> = OnceLock::new();
+
+fn reload_signal() -> &'static Notify {
+    RELOAD.get_or_init(Notify::new)
+}
+
+/// 来源或暂停状态变化后调用，监听循环据此重建监听并补扫
+pub fn reload() {
+    reload_signal().notify_one();
+}
+
+/// 正在监听的来源（目录已规范化，与 notify 事件中的路径一致）
+struct ActiveSource {
+    source: WatchSource,
+    directory: PathBuf,
+}
+
+/// 为活跃来源建立监听；失败的来源记录错误后跳过
+fn build_watcher(tx: mpsc::UnboundedSender<notify::Result<Event>>) -> (Option<RecommendedWatcher>, Vec<ActiveSource>) {
+    let sources = match ContactWatchStore::global().lock() {
+        Ok(store) => store.active_sources(),
+        Err(e) => {
+            warn!("⚠️ 读取监听来源失败: {}", e);
+            return (None, Vec::new());
+        }
+    };
+    if sources.is_empty() {
+        return (None, Vec::new());
+    }
+    let mut watcher = match notify::recommended_watcher(move |res| {
+        let _ = tx.send(res);
+    }) {
+        Ok(watcher) => watcher,
+        Err(e) => {
+            warn!("⚠️ 创建文件夹监听失败: {}", e);
+            return (None, Vec::new());
+        }
+    };
+
+    let mut active = Vec::new();
+    for source in sources {
+        let directory = source.directory.canonicalize().unwrap_or_else(|_| source.directory.clone());
+        let mode = if source.recursive { RecursiveMode::Recursive } else { RecursiveMode::NonRecursive };
+        match watcher.watch(&directory, mode) {
+            Ok(()) => {
+                info!("👀 开始监听联系人来源「{}」: {}", source.name, directory.display());
+                active.push(ActiveSource { source, directory });
+            }
+            Err(e) => {
+                warn!("⚠️ 监听目录失败 {}: {}", directory.display(), e);
+                record_watch_error(&source.id, format!("监听目录失败: {}", e));
+            }
+        }
+    }
+    (Some(watcher), active)
+}
+
+/// 目录中已有的文件（启动或恢复时补扫；已导入且未修改的文件由导入器跳过）
+fn existing_files(active: &ActiveSource) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    let mut dirs = vec![active.directory.clone()];
+    while let Some(dir) = dirs.pop() {
+        let Ok(entries) = std::fs::read_dir(&dir) else { continue };
+        for path in entries.flatten().map(|entry| entry.path()) {
+            if path.is_dir() {
+                if active.source.recursive {
+                    dirs.push(path);
+                }
+            } else if active.source.covers(&active.directory, &path) {
+                files.push(path);
+            }
+        }
+    }
+    files
+}
+
+fn owner<'a>(active: &'a [ActiveSource], path: &Path) -> Option<&'a ActiveSource> {
+    active.iter().find(|a| a.source.covers(&a.directory, path))
+}
+
+/// 监听循环（应用启动时派生）
+pub async fn run(importer: Arc<dyn ContactWatchImporter>) {
+    let (tx, mut rx) = mpsc::unbounded_channel::<notify::Result<Event>>();
+    let mut debouncer = Debouncer::new(DEFAULT_DEBOUNCE);
+    let mut _watcher: Option<RecommendedWatcher> = None;
+    let mut active: Vec<ActiveSource> = Vec::new();
+    let mut needs_reload = true;
+
+    loop {
+        if needs_reload {
+            needs_reload = false;
+            // 先释放旧监听，避免同一目录重复注册
+            _watcher = None;
+            (_watcher, active) = build_watcher(tx.clone());
+            debouncer.retain_sources(&active.iter().map(|a| a.source.id.clone()).collect::<Vec<_>>());
+            let now = Instant::now();
+            for a in &active {
+                for path in existing_files(a) {
+                    debouncer.touch(&a.source.id, path, now);
+                }
+            }
+        }
+
+        tokio::select! {
+            Some(res) = rx.recv() => match res {
+                Ok(event) if matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_)) => {
+                    let now = Instant::now();
+                    for path in event.paths {
+                        if let Some(a) = owner(&active, &path) {
+                            debouncer.touch(&a.source.id, path, now);
+                        }
+                    }
+                }
+                Ok(_) => {}
+                Err(e) => warn!("⚠️ 文件夹监听事件错误: {}", e),
+            },
+            _ = reload_signal().notified() => needs_reload = true,
+            _ = tokio::time::sleep(TICK) => {}
+        }
+
+        for (source_id, path) in debouncer.drain_ready(Instant::now()) {
+            let Some(source) = active.iter().find(|a| a.source.id == source_id).map(|a| a.source.clone()) else { continue };
+            if !path.is_file() {
+                continue;
+            }
+            let importer = importer.clone();
+            let result = tokio::task::spawn_blocking(move || {
+                let summary = importer.import(&source, &path);
+                match &summary {
+                    Ok(s) if s.status != AutoImportStatus::Unchanged => importer.on_imported(s),
+                    _ => {}
+                }
+                summary
+            })
+            .await;
+            match result {
+                Ok(Ok(summary)) => record_summary(&summary),
+                Ok(Err(e)) => {
+                    warn!("⚠️ 自动导入失败: {}", e);
+                    record_watch_error(&source_id, e);
+                }
+                Err(e) => warn!("⚠️ 自动导入任务异常: {}", e),
+            }
+        }
+    }
+}
@@ -0,0 +1,489 @@
+// src-tauri/src/services/demo_dataset/mod.rs
+// module: demo_dataset | layer: services | role: 演示数据集脱敏导出
+// summary: 克隆联系人 / 精准获客数据库，按脱敏计划把号码替换为确定性的假号码、姓名打乱、评论与备注按长度遮盖，计划外的表清空，保留行数 / 行业 / 状态等统计维度，打包成可分享的 zip
+
+use chrono::{DateTime, Utc};
+use rusqlite::types::Value;
+use rusqlite::{params_from_iter, Connection, ErrorCode, OptionalExtension};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use tracing::{info, warn};
+use zip::write::FileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+/// 生成唯一值冲突（如 UNIQUE(phone, source_file)）时的最大重试次数，仍冲突则删除该行
+const MAX_ATTEMPTS: u32 = 8;
+const MANIFEST_FILE: &str = "manifest.json";
+
+/// 列的脱敏方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldRule {
+    /// 保留前缀（国家码 / 号段）与长度，末 8 位数字按哈希替换
+    Phone,
+    /// 逐字替换为同类字符（汉字 / 字母 / 数字），长度不变
+    Name,
+    /// 字母、数字、汉字遮盖为 `*`，保留标点与空白（长度不变）
+    Text,
+    /// 替换为 `redacted/<哈希>.<扩展名>`
+    Path,
+    Url,
+    Fixed(&'static str),
+    Null,
+}
+
+/// 一张表的脱敏规则；列为空表示整表原样保留（不含敏感信息）
+pub struct TableRule {
+    pub table: &'static str,
+    pub columns: &'static [(&'static str, FieldRule)],
+}
+
+/// 联系人库（employees.db）：计划外的表（员工信息等）清空
+pub const CONTACTS_PLAN: &[TableRule] = &[
+    TableRule {
+        table: "contact_numbers",
+        columns: &[("phone", FieldRule::Phone), ("phone_number", FieldRule::Phone), ("name", FieldRule::Name), ("source_file", FieldRule::Path), ("wechat_check_note", FieldRule::Text)],
+    },
+    TableRule { table: "vcf_batch_numbers", columns: &[("phone_number", FieldRule::Phone)] },
+    TableRule {
+        table: "vcf_batches",
+        columns: &[("vcf_file_path", FieldRule::Path), ("description", FieldRule::Text), ("notes", FieldRule::Text)],
+    },
+    TableRule {
+        table: "import_sessions",
+        columns: &[("error_message", FieldRule::Text), ("description", FieldRule::Text), ("notes", FieldRule::Text)],
+    },
+    TableRule { table: "import_session_events", columns: &[("error_message", FieldRule::Text)] },
+    TableRule {
+        table: "txt_import_records",
+        columns: &[("file_path", FieldRule::Path), ("file_name", FieldRule::Path), ("error_message", FieldRule::Text), ("notes", FieldRule::Text)],
+    },
+    TableRule { table: "folder_import_sessions", columns: &[("folder_path", FieldRule::Path)] },
+    TableRule { table: "folder_import_files", columns: &[("file_path", FieldRule::Path), ("error_message", FieldRule::Text)] },
+    TableRule { table: "industry_quota_rules", columns: &[] },
+    TableRule { table: "industry_quota_usage", columns: &[] },
+];
+
+/// 精准获客库（prospecting.db）：评论向量由原文生成，不在计划内，随其他计划外的表清空
+pub const PROSPECTING_PLAN: &[TableRule] = &[
+    TableRule {
+        table: "comments",
+        columns: &[
+            ("author", FieldRule::Name),
+            ("content", FieldRule::Text),
+            ("video_url", FieldRule::Url),
+            ("avatar_url", FieldRule::Null),
+            ("metadata", FieldRule::Null),
+        ],
+    },
+    TableRule { table: "analysis_results", columns: &[("entities", FieldRule::Fixed("[]")), ("suggested_reply", FieldRule::Text)] },
+    TableRule {
+        table: "reply_plans",
+        columns: &[
+            ("video_url", FieldRule::Url),
+            ("target_author", FieldRule::Name),
+            ("target_comment", FieldRule::Text),
+            ("reply_content", FieldRule::Text),
+            ("steps", FieldRule::Fixed("[]")),
+        ],
+    },
+    TableRule { table: "reply_records", columns: &[("actual_reply", FieldRule::Text)] },
+    TableRule { table: "rule_labels", columns: &[] },
+    TableRule { table: "content_calendar_entries", columns: &[("note", FieldRule::Text)] },
+    TableRule { table: "content_calendar_campaigns", columns: &[] },
+];
+
+/// 替换姓名中汉字用的常见字
+const NAME_CHARS: &[char] = &[
+    '王', '李', '张', '刘', '陈', '杨', '黄', '赵', '吴', '周', '徐', '孙', '马', '朱', '胡', '郭', '何', '高', '林', '罗', '郑', '梁',
+    '谢', '宋', '唐', '许', '韩', '冯', '邓', '曹', '彭', '曾', '田', '董', '潘', '袁', '蔡', '蒋', '余', '杜', '叶', '程', '魏', '苏',
+    '明', '华', '伟', '芳', '娜', '敏', '静', '丽', '强', '磊', '军', '洋', '勇', '艳', '杰', '涛', '超', '霞', '平', '刚', '桂', '英',
+];
+
+/// 确定性脱敏器：同一 salt 下相同输入得到相同输出（跨表一致，便于关联统计）
+pub struct Anonymizer {
+    salt: String,
+}
+
+impl Anonymizer {
+    pub fn new(salt: impl Into<String>) -> Self {
+        Self { salt: salt.into() }
+    }
+
+    /// 按需延长的哈希字节流
+    fn stream(&self, kind: &str, value: &str, attempt: u32) -> impl Iterator<Item = u8> + '_ {
+        let seed = format!("{}\u{1f}{}\u{1f}{}\u{1f}{}", self.salt, kind, value, attempt);
+        (0u32..).flat_map(move |block| {
+            let mut hasher = Sha256::new();
+            hasher.update(seed.as_bytes());
+            hasher.update(block.to_le_bytes());
+            hasher.finalize().to_vec()
+        })
+    }
+
+    fn hex(&self, kind: &str, value: &str, attempt: u32, len: usize) -> String {
+        self.stream(kind, value, attempt).take(len.div_ceil(2)).map(|b| format!("{:02x}", b)).collect::<String>()[..len].to_string()
+    }
+
+    pub fn phone(&self, value: &str, attempt: u32) -> String {
+        let digits = value.chars().filter(|c| c.is_ascii_digit()).count();
+        let keep = digits.saturating_sub(8).max(1);
+        let mut bytes = self.stream("phone", value, attempt);
+        let mut seen = 0;
+        value
+            .chars()
+            .map(|c| {
+                if !c.is_ascii_digit() {
+                    return c;
+                }
+                seen += 1;
+                if seen <= keep {
+                    c
+                } else {
+                    char::from(b'0' + bytes.next().unwrap_or(0) % 10)
+                }
+            })
+            .collect()
+    }
+
+    pub fn name(&self, value: &str, attempt: u32) -> String {
+        let mut bytes = self.stream("name", value, attempt);
+        value
+            .chars()
+            .map(|c| {
+                let b = bytes.next().unwrap_or(0);
+                match c {
+                    '\u{4e00}'..='\u{9fff}' => NAME_CHARS[b as usize % NAME_CHARS.len()],
+                    'a'..='z' => char::from(b'a' + b % 26),
+                    'A'..='Z' => char::from(b'A' + b % 26),
+                    '0'..='9' => char::from(b'0' + b % 10),
+                    c if c.is_whitespace() || c.is_ascii_punctuation() => c,
+                    _ => '*',
+                }
+            })
+            .collect()
+    }
+
+    pub fn text(value: &str) -> String {
+        value.chars().map(|c| if c.is_alphanumeric() { '*' } else { c }).collect()
+    }
+
+    pub fn path(&self, value: &str, attempt: u32) -> String {
+        let hash = self.hex("path", value, attempt, 12);
+        let ext = Path::new(value).extension().map(|e| format!(".{}", e.to_string_lossy())).unwrap_or_default();
+        if value.contains(['/', '\\', ':']) {
+            format!("redacted/{}{}", hash, ext)
+        } else {
+            format!("{}{}", hash, ext)
+        }
+    }
+
+    pub fn url(&self, value: &str, attempt: u32) -> String {
+        format!("https://example.com/redacted/{}", self.hex("url", value, attempt, 12))
+    }
+
+    /// 按规则脱敏；空值保持为空
+    pub fn apply(&self, rule: FieldRule, value: Option<&str>, attempt: u32) -> Option<String> {
+        match (rule, value) {
+            (FieldRule::Null, _) => None,
+            (FieldRule::Fixed(fixed), _) => Some(fixed.to_string()),
+            (_, None) => None,
+            (_, Some("")) => Some(String::new()),
+            (FieldRule::Phone, Some(v)) => Some(self.phone(v, attempt)),
+            (FieldRule::Name, Some(v)) => Some(self.name(v, attempt)),
+            (FieldRule::Text, Some(v)) => Some(Self::text(v)),
+            (FieldRule::Path, Some(v)) => Some(self.path(v, attempt)),
+            (FieldRule::Url, Some(v)) => Some(self.url(v, attempt)),
+        }
+    }
+}
+
+/// 单表处理结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TableReport {
+    pub table: String,
+    pub rows: usize,
+    pub anonymized_columns: Vec<String>,
+    /// 计划外的表：已清空（保留结构）
+    pub cleared: bool,
+    /// 脱敏后仍与唯一约束冲突而删除的行
+    pub dropped_rows: usize,
+}
+
+/// 单个数据库处理结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DatabaseReport {
+    /// 归档内的文件名
+    pub name: String,
+    pub tables: Vec<TableReport>,
+}
+
+/// 演示数据集导出结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DemoDatasetReport {
+    pub archive_path: String,
+    pub created_at: DateTime<Utc>,
+    pub size_bytes: u64,
+    pub databases: Vec<DatabaseReport>,
+    /// 源文件不存在而跳过的数据库
+    pub skipped: Vec<String>,
+}
+
+/// 待导出的数据库
+pub struct DatasetSource {
+    /// 归档内的文件名
+    pub name: &'static str,
+    pub path: PathBuf,
+    pub plan: &'static [TableRule],
+}
+
+fn db_err(e: rusqlite::Error) -> String {
+    format!("数据库操作失败: {}", e)
+}
+
+fn quote_ident(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
+
+fn user_tables(conn: &Connection) -> Result<Vec<String>, String> {
+    let mut stmt = conn
+        .prepare("SELECT name FROM sqlite_master WHERE type = 'table' AND name NOT LIKE 'sqlite_%' ORDER BY name")
+        .map_err(db_err)?;
+    let rows = stmt.query_map([], |row| row.get(0)).map_err(db_err)?;
+    rows.collect::<rusqlite::Result<_>>().map_err(db_err)
+}
+
+fn table_columns(conn: &Connection, table: &str) -> Result<Vec<String>, String> {
+    let mut stmt = conn.prepare("SELECT name FROM pragma_table_info(?1)").map_err(db_err)?;
+    let rows = stmt.query_map([table], |row| row.get(0)).map_err(db_err)?;
+    rows.collect::<rusqlite::Result<_>>().map_err(db_err)
+}
+
+fn count_rows(conn: &Connection, table: &str) -> Result<usize, String> {
+    conn.query_row(&format!("SELECT COUNT(*) FROM {}", quote_ident(table)), [], |row| row.get::<_, i64>(0))
+        .map(|n| n as usize)
+        .map_err(db_err)
+}
+
+fn anonymize_table(conn: &Connection, table: &str, columns: &[(&str, FieldRule)], anonymizer: &Anonymizer) -> Result<TableReport, String> {
+    let names: Vec<&str> = columns.iter().map(|(c, _)| *c).collect();
+    let select = format!(
+        "SELECT rowid, {} FROM {}",
+        names.iter().map(|c| format!("CAST({} AS TEXT)", quote_ident(c))).collect::<Vec<_>>().join(", "),
+        quote_ident(table)
+    );
+    let rows: Vec<(i64, Vec<Option<String>>)> = {
+        let mut stmt = conn.prepare(&select).map_err(db_err)?;
+        let rows = stmt
+            .query_map([], |row| {
+                let values = (1..=names.len()).map(|i| row.get::<_, Option<String>>(i)).collect::<rusqlite::Result<_>>()?;
+                Ok((row.get(0)?, values))
+            })
+            .map_err(db_err)?;
+        rows.collect::<rusqlite::Result<_>>().map_err(db_err)?
+    };
+
+    let update = format!(
+        "UPDATE {} SET {} WHERE rowid = ?",
+        quote_ident(table),
+        names.iter().map(|c| format!("{} = ?", quote_ident(c))).collect::<Vec<_>>().join(", ")
+    );
+    let delete = format!("DELETE FROM {} WHERE rowid = ?", quote_ident(table));
+    let mut dropped_rows = 0;
+    for (rowid, values) in &rows {
+        let mut attempt = 0;
+        loop {
+            let new_values: Vec<Option<String>> =
+                columns.iter().zip(values).map(|((_, rule), value)| anonymizer.apply(*rule, value.as_deref(), attempt)).collect();
+            let params = new_values
+                .into_iter()
+                .map(|v| v.map(Value::Text).unwrap_or(Value::Null))
+                .chain(std::iter::once(Value::Integer(*rowid)));
+            match conn.execute(&update, params_from_iter(params)) {
+                Ok(_) => break,
+                Err(rusqlite::Error::SqliteFailure(e, _)) if e.code == ErrorCode::ConstraintViolation && attempt + 1 < MAX_ATTEMPTS => attempt += 1,
+                Err(rusqlite::Error::SqliteFailure(e, _)) if e.code == ErrorCode::ConstraintViolation => {
+                    conn.execute(&delete, [rowid]).map_err(db_err)?;
+                    dropped_rows += 1;
+                    break;
+                }
+                Err(e) => return Err(db_err(e)),
+            }
+        }
+    }
+
+    Ok(TableReport {
+        table: table.to_string(),
+        rows: rows.len() - dropped_rows,
+        anonymized_columns: names.iter().map(|c| c.to_string()).collect(),
+        cleared: false,
+        dropped_rows,
+    })
+}
+
+/// 在（已克隆的）数据库上原地执行脱敏计划；计划外的表清空
+pub fn anonymize_connection(conn: &Connection, plan: &[TableRule], anonymizer: &Anonymizer) -> Result<Vec<TableReport>, String> {
+    conn.execute_batch("PRAGMA foreign_keys = OFF; BEGIN").map_err(db_err)?;
+    let result: Result<Vec<TableReport>, String> = (|| {
+        let mut reports = Vec::new();
+        for table in user_tables(conn)? {
+            let Some(rule) = plan.iter().find(|r| r.table == table) else {
+                conn.execute(&format!("DELETE FROM {}", quote_ident(&table)), []).map_err(db_err)?;
+                reports.push(TableReport { table, rows: 0, anonymized_columns: Vec::new(), cleared: true, dropped_rows: 0 });
+                continue;
+            };
+            let existing = table_columns(conn, &table)?;
+            let columns: Vec<(&str, FieldRule)> = rule.columns.iter().copied().filter(|(c, _)| existing.iter().any(|e| e == c)).collect();
+            if columns.is_empty() {
+                let rows = count_rows(conn, &table)?;
+                reports.push(TableReport { table, rows, anonymized_columns: Vec::new(), cleared: false, dropped_rows: 0 });
+            } else {
+                reports.push(anonymize_table(conn, &table, &columns, anonymizer)?);
+            }
+        }
+        Ok(reports)
+    })();
+    match result {
+        Ok(reports) => {
+            conn.execute_batch("COMMIT").map_err(db_err)?;
+            Ok(reports)
+        }
+        Err(e) => {
+            let _ = conn.execute_batch("ROLLBACK");
+            Err(e)
+        }
+    }
+}
+
+/// 克隆一个数据库到 `target` 并脱敏（VACUUM 回收已删除数据所在的页，避免原文残留在空闲页中）
+fn anonymize_copy(source: &DatasetSource, target: &Path, anonymizer: &Anonymizer) -> Result<DatabaseReport, String> {
+    let src = Connection::open_with_flags(&source.path, rusqlite::OpenFlags::SQLITE_OPEN_READ_ONLY).map_err(db_err)?;
+    src.execute("VACUUM INTO ?1", [target.to_string_lossy()]).map_err(db_err)?;
+    drop(src);
+
+    let conn = Connection::open(target).map_err(db_err)?;
+    let tables = anonymize_connection(&conn, source.plan, anonymizer)?;
+    conn.query_row("PRAGMA journal_mode = DELETE", [], |row| row.get::<_, String>(0)).optional().map_err(db_err)?;
+    conn.execute_batch("VACUUM").map_err(db_err)?;
+    Ok(DatabaseReport { name: source.name.to_string(), tables })
+}
+
+fn zip_err(e: impl std::fmt::Display) -> String {
+    format!("写入归档失败: {}", e)
+}
+
+/// 导出演示数据集：逐库克隆脱敏后与清单一起打包到 `output`（先写临时文件再替换）
+///
+/// 未指定 `salt` 时随机生成且不写入清单，同一份归档内号码映射一致、不同归档之间无法关联。
+pub fn export_demo_dataset(sources: &[DatasetSource], output: &Path, salt: Option<&str>) -> Result<DemoDatasetReport, String> {
+    let salt = salt.map(str::to_string).unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+    let anonymizer = Anonymizer::new(salt);
+    let work_dir = std::env::temp_dir().join(format!("demo-dataset-{}", uuid::Uuid::new_v4()));
+    fs::create_dir_all(&work_dir).map_err(|e| format!("创建临时目录失败: {}", e))?;
+
+    let result: Result<DemoDatasetReport, String> = (|| {
+        let mut databases = Vec::new();
+        let mut skipped = Vec::new();
+        let mut files = Vec::new();
+        for source in sources {
+            if !source.path.is_file() {
+                warn!("⚠️ 演示数据集: 跳过不存在的数据库 {}", source.path.display());
+                skipped.push(source.name.to_string());
+                continue;
+            }
+            let target = work_dir.join(source.name);
+            databases.push(anonymize_copy(source, &target, &anonymizer)?);
+            files.push(target);
+        }
+        if databases.is_empty() {
+            return Err("没有可导出的数据库".to_string());
+        }
+
+        let mut report = DemoDatasetReport {
+            archive_path: output.to_string_lossy().to_string(),
+            created_at: Utc::now(),
+            size_bytes: 0,
+            databases,
+            skipped,
+        };
+        if let Some(parent) = output.parent() {
+            fs::create_dir_all(parent).map_err(zip_err)?;
+        }
+        let partial = output.with_extension("zip.partial");
+        {
+            let mut zip = ZipWriter::new(File::create(&partial).map_err(zip_err)?);
+            let options = FileOptions::default().compression_method(CompressionMethod::Deflated);
+            for file in &files {
+                let name = file.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+                zip.start_file(name, options).map_err(zip_err)?;
+                std::io::copy(&mut File::open(file).map_err(zip_err)?, &mut zip).map_err(zip_err)?;
+            }
+            zip.start_file(MANIFEST_FILE, options).map_err(zip_err)?;
+            zip.write_all(&serde_json::to_vec_pretty(&report).map_err(zip_err)?).map_err(zip_err)?;
+            zip.finish().map_err(zip_err)?;
+        }
+        fs::rename(&partial, output).map_err(zip_err)?;
+        report.size_bytes = fs::metadata(output).map(|m| m.len()).unwrap_or(0);
+        Ok(report)
+    })();
+
+    let _ = fs::remove_dir_all(&work_dir);
+    if let Ok(report) = &result {
+        info!("📦 演示数据集已导出: {} ({} 字节)", report.archive_path, report.size_bytes);
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_anonymize_keeps_shape_and_clears_unplanned_tables() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE contact_numbers (id INTEGER PRIMARY KEY, phone TEXT NOT NULL, name TEXT NOT NULL DEFAULT '',
+                 source_file TEXT NOT NULL, industry TEXT, UNIQUE(phone, source_file));
+             CREATE TABLE vcf_batch_numbers (id INTEGER PRIMARY KEY, phone_number TEXT NOT NULL);
+             CREATE TABLE employees (id INTEGER PRIMARY KEY, name TEXT);
+             INSERT INTO contact_numbers (phone, name, source_file, industry) VALUES
+                 ('13812345678', '张三丰', 'C:\\leads\\客户.txt', '餐饮'),
+                 ('+86 139-0000-1111', 'Tom Lee', 'C:\\leads\\客户.txt', '教育');
+             INSERT INTO vcf_batch_numbers (phone_number) VALUES ('13812345678');
+             INSERT INTO employees (name) VALUES ('李四');",
+        )
+        .unwrap();
+
+        let reports = anonymize_connection(&conn, CONTACTS_PLAN, &Anonymizer::new("test-salt")).unwrap();
+        let employees = reports.iter().find(|r| r.table == "employees").unwrap();
+        assert!(employees.cleared);
+        assert_eq!(count_rows(&conn, "employees").unwrap(), 0);
+
+        let rows: Vec<(String, String, String, String)> = conn
+            .prepare("SELECT phone, name, source_file, industry FROM contact_numbers ORDER BY id")
+            .unwrap()
+            .query_map([], |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?, r.get(3)?)))
+            .unwrap()
+            .collect::<rusqlite::Result<_>>()
+            .unwrap();
+        let (phone, name, source_file, industry) = &rows[0];
+        assert_ne!(phone, "13812345678");
+        assert!(phone.starts_with("138") && phone.len() == 11);
+        assert_eq!(name.chars().count(), 3);
+        assert_ne!(name, "张三丰");
+        assert!(source_file.starts_with("redacted/") && source_file.ends_with(".txt"));
+        assert_eq!(industry, "餐饮");
+        assert!(rows[1].0.starts_with("+86 139-") && rows[1].0.len() == "+86 139-0000-1111".len());
+        assert_eq!(rows[1].1.len(), "Tom Lee".len());
+
+        // 同一 salt 下相同号码跨表映射一致
+        let batch_phone: String = conn.query_row("SELECT phone_number FROM vcf_batch_numbers", [], |r| r.get(0)).unwrap();
+        assert_eq!(&batch_phone, phone);
+        assert_eq!(Anonymizer::text("想了解价格，加V: abc123"), "*****，**: ******");
+    }
+}
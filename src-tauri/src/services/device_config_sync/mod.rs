@@ -0,0 +1,473 @@
+// src-tauri/src/services/device_config_sync/mod.rs
+// module: device_config_sync | layer: services | role: 设备配置双向同步
+// summary: 设备上线时从 lead-server 拉取设备配置（频率限制 / 功能开关 / 营销活动分配），按优先级规则与本地覆盖合并生效；推送本地覆盖前逐字段检测冲突
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, HashSet};
+use std::path::PathBuf;
+use std::sync::{Mutex, OnceLock};
+use tokio::sync::broadcast::error::RecvError;
+use tracing::{info, warn};
+
+use crate::core::shared::event_bus::{self, AppEvent};
+use crate::infra::lead_server_client::{DeviceConfigUpdate, LeadServerClient, RemoteDeviceConfig};
+
+/// 状态文件名
+const STATE_FILE_NAME: &str = "device_config_sync.json";
+/// 服务端 config_json 中存放桌面端设置的键（服务端按顶层键合并）
+pub const CONFIG_JSON_KEY: &str = "desktopSettings";
+
+static STORE: OnceLock<Mutex<ConfigSyncStore>> = OnceLock::new();
+
+/// 频率限制（未设置表示不限制）
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RateLimits {
+    #[serde(default)]
+    pub max_actions_per_hour: Option<u32>,
+    #[serde(default)]
+    pub max_actions_per_day: Option<u32>,
+    /// 两次动作之间的最短间隔（秒）
+    #[serde(default)]
+    pub min_interval_secs: Option<u32>,
+}
+
+/// 同步的设备设置；服务端与本地覆盖层使用同一结构，未设置的字段不参与合并
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeviceSettings {
+    #[serde(default)]
+    pub rate_limits: RateLimits,
+    /// 功能名 → 是否启用
+    #[serde(default)]
+    pub features: BTreeMap<String, bool>,
+    /// 分配给该设备的营销活动
+    #[serde(default)]
+    pub campaign_ids: Option<Vec<String>>,
+}
+
+impl DeviceSettings {
+    /// 从服务端设备配置中取出桌面端设置
+    pub fn from_remote(config: &RemoteDeviceConfig) -> Self {
+        config
+            .config_json
+            .get(CONFIG_JSON_KEY)
+            .and_then(|value| serde_json::from_value(value.clone()).ok())
+            .unwrap_or_default()
+    }
+
+    /// 展开为 `字段路径 → 值`，用于逐字段比较
+    fn fields(&self) -> BTreeMap<String, Value> {
+        let mut out = BTreeMap::new();
+        let limits = [
+            ("maxActionsPerHour", self.rate_limits.max_actions_per_hour),
+            ("maxActionsPerDay", self.rate_limits.max_actions_per_day),
+            ("minIntervalSecs", self.rate_limits.min_interval_secs),
+        ];
+        for (name, value) in limits {
+            if let Some(value) = value {
+                out.insert(format!("rateLimits.{}", name), json!(value));
+            }
+        }
+        for (name, enabled) in &self.features {
+            out.insert(format!("features.{}", name), json!(enabled));
+        }
+        if let Some(campaigns) = &self.campaign_ids {
+            out.insert("campaignIds".to_string(), json!(campaigns));
+        }
+        out
+    }
+
+    /// 在 `base` 上叠加本结构中已设置的字段（推送时：服务端当前值 + 本地覆盖）
+    pub fn overlay_on(&self, base: &DeviceSettings) -> DeviceSettings {
+        let mut features = base.features.clone();
+        features.extend(self.features.iter().map(|(k, v)| (k.clone(), *v)));
+        DeviceSettings {
+            rate_limits: RateLimits {
+                max_actions_per_hour: self.rate_limits.max_actions_per_hour.or(base.rate_limits.max_actions_per_hour),
+                max_actions_per_day: self.rate_limits.max_actions_per_day.or(base.rate_limits.max_actions_per_day),
+                min_interval_secs: self.rate_limits.min_interval_secs.or(base.rate_limits.min_interval_secs),
+            },
+            features,
+            campaign_ids: self.campaign_ids.clone().or_else(|| base.campaign_ids.clone()),
+        }
+    }
+}
+
+/// 服务端与本地覆盖同时设置某字段时取谁
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Precedence {
+    /// 服务端优先（统一管控）
+    Server,
+    /// 本地覆盖优先
+    Local,
+    /// 取更严格的值：频率上限取小、最短间隔取大、功能开关任一方关闭即关闭；营销活动分配按服务端优先处理
+    #[default]
+    Strictest,
+}
+
+/// 各类设置的优先级规则
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncPolicy {
+    #[serde(default)]
+    pub rate_limits: Precedence,
+    #[serde(default)]
+    pub features: Precedence,
+    #[serde(default = "default_campaign_precedence")]
+    pub campaigns: Precedence,
+    /// 设备上线时自动拉取
+    #[serde(default = "default_pull_on_connect")]
+    pub pull_on_connect: bool,
+}
+
+fn default_campaign_precedence() -> Precedence {
+    Precedence::Server
+}
+
+fn default_pull_on_connect() -> bool {
+    true
+}
+
+impl Default for SyncPolicy {
+    fn default() -> Self {
+        Self {
+            rate_limits: Precedence::Strictest,
+            features: Precedence::Strictest,
+            campaigns: default_campaign_precedence(),
+            pull_on_connect: true,
+        }
+    }
+}
+
+fn pick_limit(server: Option<u32>, local: Option<u32>, precedence: Precedence, stricter: fn(u32, u32) -> u32) -> Option<u32> {
+    match (server, local) {
+        (Some(s), Some(l)) => Some(match precedence {
+            Precedence::Server => s,
+            Precedence::Local => l,
+            Precedence::Strictest => stricter(s, l),
+        }),
+        (s, l) => s.or(l),
+    }
+}
+
+/// 按优先级规则合并服务端设置与本地覆盖，得到生效设置
+pub fn resolve(server: Option<&DeviceSettings>, local: &DeviceSettings, policy: &SyncPolicy) -> DeviceSettings {
+    let empty = DeviceSettings::default();
+    let server = server.unwrap_or(&empty);
+    let limits = policy.rate_limits;
+
+    let mut features = server.features.clone();
+    for (name, &local_enabled) in &local.features {
+        let enabled = match (server.features.get(name), policy.features) {
+            (None, _) | (Some(_), Precedence::Local) => local_enabled,
+            (Some(&server_enabled), Precedence::Server) => server_enabled,
+            (Some(&server_enabled), Precedence::Strictest) => server_enabled && local_enabled,
+        };
+        features.insert(name.clone(), enabled);
+    }
+
+    let campaign_ids = match policy.campaigns {
+        Precedence::Local => local.campaign_ids.clone().or_else(|| server.campaign_ids.clone()),
+        Precedence::Server | Precedence::Strictest => server.campaign_ids.clone().or_else(|| local.campaign_ids.clone()),
+    };
+
+    DeviceSettings {
+        rate_limits: RateLimits {
+            max_actions_per_hour: pick_limit(
+                server.rate_limits.max_actions_per_hour,
+                local.rate_limits.max_actions_per_hour,
+                limits,
+                u32::min,
+            ),
+            max_actions_per_day: pick_limit(
+                server.rate_limits.max_actions_per_day,
+                local.rate_limits.max_actions_per_day,
+                limits,
+                u32::min,
+            ),
+            min_interval_secs: pick_limit(
+                server.rate_limits.min_interval_secs,
+                local.rate_limits.min_interval_secs,
+                limits,
+                u32::max,
+            ),
+        },
+        features,
+        campaign_ids,
+    }
+}
+
+/// 一个字段的推送冲突：上次拉取后服务端改过，且与本地覆盖不一致
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigConflict {
+    pub field: String,
+    /// 上次拉取时的服务端值
+    pub base: Option<Value>,
+    pub remote: Option<Value>,
+    pub local: Value,
+}
+
+/// 逐字段检测冲突（只检查本地覆盖中设置了的字段）
+pub fn detect_conflicts(base: Option<&DeviceSettings>, remote: &DeviceSettings, local: &DeviceSettings) -> Vec<ConfigConflict> {
+    let base = base.map(DeviceSettings::fields).unwrap_or_default();
+    let remote = remote.fields();
+    local
+        .fields()
+        .into_iter()
+        .filter_map(|(field, local_value)| {
+            let remote_value = remote.get(&field);
+            let base_value = base.get(&field);
+            (remote_value != base_value && remote_value != Some(&local_value)).then(|| ConfigConflict {
+                base: base_value.cloned(),
+                remote: remote_value.cloned(),
+                local: local_value,
+                field,
+            })
+        })
+        .collect()
+}
+
+/// 单台设备的同步记录
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeviceConfigRecord {
+    /// 本地覆盖层
+    #[serde(default)]
+    pub overrides: DeviceSettings,
+    /// 上次拉取到的服务端设置（也是冲突检测的基线）
+    #[serde(default)]
+    pub server: Option<DeviceSettings>,
+    #[serde(default)]
+    pub server_updated_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub last_pulled_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub last_pushed_at: Option<DateTime<Utc>>,
+    /// 推送因服务器不可达进入了离线队列
+    #[serde(default)]
+    pub push_queued: bool,
+}
+
+/// 前端展示用的设备设置
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeviceSettingsView {
+    pub device_id: String,
+    pub effective: DeviceSettings,
+    #[serde(flatten)]
+    pub record: DeviceConfigRecord,
+}
+
+/// 推送结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PushReport {
+    pub pushed: bool,
+    pub queued: bool,
+    pub conflicts: Vec<ConfigConflict>,
+    pub settings: DeviceSettingsView,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct ConfigSyncState {
+    #[serde(default)]
+    policy: SyncPolicy,
+    #[serde(default)]
+    devices: BTreeMap<String, DeviceConfigRecord>,
+}
+
+/// 同步状态存储（优先级规则 + 各设备记录）
+pub struct ConfigSyncStore {
+    path: PathBuf,
+    state: ConfigSyncState,
+}
+
+impl ConfigSyncStore {
+    pub fn global() -> &'static Mutex<ConfigSyncStore> {
+        STORE.get_or_init(|| {
+            let path = dirs::data_dir().unwrap_or_else(|| PathBuf::from(".")).join("employee-gui").join(STATE_FILE_NAME);
+            Mutex::new(Self::open(path))
+        })
+    }
+
+    pub fn open(path: PathBuf) -> Self {
+        let state = std::fs::read_to_string(&path).ok().and_then(|content| serde_json::from_str(&content).ok()).unwrap_or_default();
+        Self { path, state }
+    }
+
+    fn save(&self) -> Result<(), String> {
+        if let Some(dir) = self.path.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+        }
+        let content = serde_json::to_string_pretty(&self.state).map_err(|e| e.to_string())?;
+        std::fs::write(&self.path, content).map_err(|e| format!("保存设备配置同步状态失败: {}", e))
+    }
+
+    pub fn policy(&self) -> &SyncPolicy {
+        &self.state.policy
+    }
+
+    pub fn set_policy(&mut self, policy: SyncPolicy) -> Result<(), String> {
+        self.state.policy = policy;
+        self.save()
+    }
+
+    pub fn record(&self, device_id: &str) -> DeviceConfigRecord {
+        self.state.devices.get(device_id).cloned().unwrap_or_default()
+    }
+
+    pub fn view(&self, device_id: &str) -> DeviceSettingsView {
+        let record = self.record(device_id);
+        DeviceSettingsView {
+            device_id: device_id.to_string(),
+            effective: resolve(record.server.as_ref(), &record.overrides, &self.state.policy),
+            record,
+        }
+    }
+
+    pub fn set_overrides(&mut self, device_id: &str, overrides: DeviceSettings) -> Result<DeviceSettingsView, String> {
+        self.state.devices.entry(device_id.to_string()).or_default().overrides = overrides;
+        self.save()?;
+        Ok(self.view(device_id))
+    }
+
+    /// 记录拉取结果；服务端没有该设备时基线为空
+    pub fn apply_pull(&mut self, device_id: &str, remote: Option<&RemoteDeviceConfig>) -> Result<DeviceSettingsView, String> {
+        let record = self.state.devices.entry(device_id.to_string()).or_default();
+        record.server = remote.map(DeviceSettings::from_remote);
+        record.server_updated_at = remote.map(|config| config.updated_at);
+        record.last_pulled_at = Some(Utc::now());
+        self.save()?;
+        Ok(self.view(device_id))
+    }
+
+    fn mark_pushed(&mut self, device_id: &str, queued: bool) -> Result<(), String> {
+        let record = self.state.devices.entry(device_id.to_string()).or_default();
+        record.push_queued = queued;
+        if !queued {
+            record.last_pushed_at = Some(Utc::now());
+        }
+        self.save()
+    }
+}
+
+fn store() -> std::sync::MutexGuard<'static, ConfigSyncStore> {
+    ConfigSyncStore::global().lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// 当前生效设置（供限速 / 功能开关 / 活动分配等调用方读取）
+pub fn effective_settings(device_id: &str) -> DeviceSettings {
+    store().view(device_id).effective
+}
+
+/// 从服务端拉取设备配置并按优先级规则生效
+pub async fn pull(device_id: &str) -> Result<DeviceSettingsView, String> {
+    let remote = LeadServerClient::global().get_device_config(device_id).await?;
+    store().apply_pull(device_id, remote.as_ref())
+}
+
+/// 推送本地覆盖；服务端在上次拉取后改过同一字段且值不同则视为冲突，`force` 为 false 时不推送
+pub async fn push(device_id: &str, force: bool) -> Result<PushReport, String> {
+    let client = LeadServerClient::global();
+    let remote = client.get_device_config(device_id).await?;
+    let record = store().record(device_id);
+    let remote_settings = remote.as_ref().map(DeviceSettings::from_remote).unwrap_or_default();
+
+    let unchanged = remote.as_ref().map(|config| config.updated_at) == record.server_updated_at;
+    let conflicts =
+        if unchanged { Vec::new() } else { detect_conflicts(record.server.as_ref(), &remote_settings, &record.overrides) };
+    if !conflicts.is_empty() && !force {
+        info!("⚠️ 设备 {} 配置推送存在 {} 处冲突，等待处理", device_id, conflicts.len());
+        let settings = store().apply_pull(device_id, remote.as_ref())?;
+        return Ok(PushReport { pushed: false, queued: false, conflicts, settings });
+    }
+
+    let merged = record.overrides.overlay_on(&remote_settings);
+    let config_json = serde_json::to_value(&merged).map_err(|e| e.to_string())?;
+    let update = DeviceConfigUpdate { config_json: Some(json!({ CONFIG_JSON_KEY: config_json })), ..Default::default() };
+    let delivery = client.push_device_config(device_id, update).await?;
+    store().mark_pushed(device_id, delivery.queued)?;
+
+    if delivery.delivered {
+        let settings = pull(device_id).await?;
+        return Ok(PushReport { pushed: true, queued: false, conflicts, settings });
+    }
+    let settings = store().view(device_id);
+    Ok(PushReport { pushed: false, queued: delivery.queued, conflicts, settings })
+}
+
+/// 后台任务：订阅设备上线事件，新上线的设备自动拉取配置（应用启动时 spawn）
+pub async fn run() {
+    let mut events = event_bus::subscribe();
+    let mut online: HashSet<String> = HashSet::new();
+    loop {
+        let device_ids = match events.recv().await {
+            Ok(AppEvent::DevicesOnline { device_ids }) => device_ids,
+            Ok(_) => continue,
+            Err(RecvError::Lagged(_)) => continue,
+            Err(RecvError::Closed) => break,
+        };
+        let current: HashSet<String> = device_ids.into_iter().collect();
+        let pull_on_connect = store().policy().pull_on_connect;
+        if pull_on_connect {
+            for device_id in current.difference(&online) {
+                match pull(device_id).await {
+                    Ok(view) => info!("🔄 设备 {} 已同步服务端配置 (服务端更新于 {:?})", device_id, view.record.server_updated_at),
+                    Err(e) => warn!("⚠️ 设备 {} 拉取服务端配置失败: {}", device_id, e),
+                }
+            }
+        }
+        online = current;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings(per_hour: Option<u32>, interval: Option<u32>, features: &[(&str, bool)], campaigns: Option<&[&str]>) -> DeviceSettings {
+        DeviceSettings {
+            rate_limits: RateLimits { max_actions_per_hour: per_hour, max_actions_per_day: None, min_interval_secs: interval },
+            features: features.iter().map(|(k, v)| (k.to_string(), *v)).collect(),
+            campaign_ids: campaigns.map(|c| c.iter().map(|s| s.to_string()).collect()),
+        }
+    }
+
+    #[test]
+    fn resolves_by_precedence_and_detects_field_conflicts() {
+        let server = settings(Some(30), Some(20), &[("auto_reply", true), ("follow", false)], Some(&["c1"]));
+        let local = settings(Some(50), Some(10), &[("follow", true), ("dm", true)], Some(&["c2"]));
+
+        let strict = resolve(Some(&server), &local, &SyncPolicy::default());
+        assert_eq!(strict, settings(Some(30), Some(20), &[("auto_reply", true), ("dm", true), ("follow", false)], Some(&["c1"])));
+
+        let policy = SyncPolicy {
+            rate_limits: Precedence::Local,
+            features: Precedence::Local,
+            campaigns: Precedence::Local,
+            pull_on_connect: true,
+        };
+        assert_eq!(resolve(Some(&server), &local, &policy), settings(Some(50), Some(10), &[("auto_reply", true), ("dm", true), ("follow", true)], Some(&["c2"])));
+        assert_eq!(resolve(None, &local, &SyncPolicy::default()), local);
+
+        // 服务端在拉取后把每小时上限改为 20（本地覆盖 50 → 冲突），开启了 dm（与本地一致 → 不冲突）
+        let remote = settings(Some(20), Some(20), &[("auto_reply", true), ("dm", true), ("follow", false)], Some(&["c1"]));
+        let conflicts = detect_conflicts(Some(&server), &remote, &local);
+        assert_eq!(conflicts.iter().map(|c| c.field.as_str()).collect::<Vec<_>>(), vec!["rateLimits.maxActionsPerHour"]);
+        assert_eq!((conflicts[0].base.clone(), conflicts[0].local.clone()), (Some(json!(30)), json!(50)));
+
+        let merged = local.overlay_on(&remote);
+        assert_eq!(merged, settings(Some(50), Some(10), &[("auto_reply", true), ("dm", true), ("follow", true)], Some(&["c2"])));
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(STATE_FILE_NAME);
+        let mut store = ConfigSyncStore::open(path.clone());
+        store.set_overrides("d1", local.clone()).unwrap();
+        assert_eq!(ConfigSyncStore::open(path).view("d1").effective, local);
+    }
+}
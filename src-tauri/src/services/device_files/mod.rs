@@ -0,0 +1,344 @@
+// src-tauri/src/services/device_files/mod.rs
+// module: device_files | layer: services | role: 设备端文件操作
+// summary: 经 adb 列目录、分块推送/拉取文件（exec-in / exec-out 流式传输并回调进度）、带哈希校验与重试续传的推送、删除、计算远端 MD5，以及清理 uiautomator 遗留的临时文件
+
+use serde::{Deserialize, Serialize};
+use std::io::{Read, Write};
+use std::path::Path;
+use std::process::{Command, Stdio};
+use tracing::{info, warn};
+
+use crate::utils::adb_utils::{execute_adb_command, get_adb_path};
+
+mod verified_push;
+pub use verified_push::{backoff_delay, detect_hash_tool, push_file_verified, HashTool, PushOptions, PushPhase, PushProgress, VerifiedPush};
+
+/// 传输分块大小
+pub const TRANSFER_CHUNK_SIZE: usize = 256 * 1024;
+
+/// 允许删除的设备目录前缀（避免误删系统目录）
+const DELETABLE_ROOTS: &[&str] = &["/sdcard/", "/storage/", "/data/local/tmp/"];
+
+/// uiautomator 及本应用 dump 遗留的临时文件
+const UIAUTOMATOR_ARTIFACTS: &[&str] = &[
+    "/sdcard/window_dump.xml",
+    "/sdcard/ui_dump.xml",
+    "/data/local/tmp/window_dump*.xml",
+    "/data/local/tmp/ui_dump*.xml",
+];
+
+/// 设备目录项
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RemoteEntry {
+    pub name: String,
+    pub path: String,
+    pub size: u64,
+    /// 修改时间（Unix 秒）
+    pub modified: i64,
+    /// file / dir / link / other
+    pub kind: String,
+}
+
+/// 传输方向
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TransferDirection {
+    Push,
+    Pull,
+}
+
+/// 传输结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransferResult {
+    pub direction: TransferDirection,
+    pub device_id: String,
+    pub remote_path: String,
+    pub local_path: String,
+    pub bytes: u64,
+    pub local_md5: String,
+    /// 设备不支持 md5sum 时为空
+    pub remote_md5: Option<String>,
+    pub verified: bool,
+}
+
+/// 单引号包裹，供设备端 sh 解析
+pub fn shell_quote(s: &str) -> String {
+    format!("'{}'", s.replace('\'', r"'\''"))
+}
+
+fn adb_shell(device_id: &str, command: &str) -> Result<String, String> {
+    let output = execute_adb_command(&["-s", device_id, "shell", command]).map_err(|e| format!("adb 执行失败: {}", e))?;
+    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("设备命令失败: {}", if stderr.trim().is_empty() { stdout.trim() } else { stderr.trim() }));
+    }
+    Ok(stdout)
+}
+
+fn adb_command() -> Command {
+    let mut cmd = Command::new(get_adb_path());
+    #[cfg(windows)]
+    {
+        use std::os::windows::process::CommandExt;
+        cmd.creation_flags(0x08000000);
+    }
+    cmd
+}
+
+fn validate_remote_path(path: &str) -> Result<(), String> {
+    if !path.starts_with('/') || path.contains('\0') || path.contains('\n') {
+        return Err(format!("无效的设备路径: {:?}", path));
+    }
+    Ok(())
+}
+
+/// 解析 `stat -c '%s|%Y|%F|%n'` 输出
+pub fn parse_stat_lines(output: &str) -> Vec<RemoteEntry> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut parts = line.trim_end_matches('\r').splitn(4, '|');
+            let size = parts.next()?.trim().parse().ok()?;
+            let modified = parts.next()?.trim().parse().ok()?;
+            let kind = match parts.next()?.trim() {
+                "regular file" | "regular empty file" => "file",
+                "directory" => "dir",
+                "symbolic link" => "link",
+                _ => "other",
+            };
+            let path = parts.next()?.to_string();
+            let name = path.rsplit('/').next().unwrap_or(&path).to_string();
+            Some(RemoteEntry { name, path, size, modified, kind: kind.to_string() })
+        })
+        .collect()
+}
+
+/// 列出设备目录（目录在前，按名称排序）
+pub fn list_dir(device_id: &str, dir: &str) -> Result<Vec<RemoteEntry>, String> {
+    validate_remote_path(dir)?;
+    let dir = if dir.len() > 1 { dir.trim_end_matches('/') } else { dir };
+    let command = format!(
+        "find {} -mindepth 1 -maxdepth 1 -exec stat -c '%s|%Y|%F|%n' {{}} +",
+        shell_quote(dir)
+    );
+    let mut entries = parse_stat_lines(&adb_shell(device_id, &command)?);
+    entries.sort_by(|a, b| (a.kind != "dir").cmp(&(b.kind != "dir")).then_with(|| a.name.cmp(&b.name)));
+    Ok(entries)
+}
+
+/// 远端文件大小
+pub fn remote_size(device_id: &str, path: &str) -> Result<u64, String> {
+    validate_remote_path(path)?;
+    let output = adb_shell(device_id, &format!("stat -c %s {}", shell_quote(path)))?;
+    output.trim().parse().map_err(|_| format!("无法读取设备文件大小: {}", output.trim()))
+}
+
+/// 远端 MD5（设备无 md5sum 时返回 `Ok(None)`）
+pub fn remote_md5(device_id: &str, path: &str) -> Result<Option<String>, String> {
+    validate_remote_path(path)?;
+    let output = adb_shell(device_id, &format!("md5sum {} 2>/dev/null || echo unsupported", shell_quote(path)))?;
+    Ok(parse_md5sum(&output))
+}
+
+fn parse_md5sum(output: &str) -> Option<String> {
+    let hash = output.split_whitespace().next()?.to_ascii_lowercase();
+    (hash.len() == 32 && hash.chars().all(|c| c.is_ascii_hexdigit())).then_some(hash)
+}
+
+/// 从 reader 分块复制到 writer，同时计算 MD5 并回调进度
+pub fn copy_chunked<R: Read, W: Write>(
+    mut reader: R,
+    mut writer: W,
+    total: u64,
+    mut on_progress: impl FnMut(u64, u64),
+) -> std::io::Result<(u64, String)> {
+    let mut buffer = vec![0u8; TRANSFER_CHUNK_SIZE];
+    let mut context = md5::Context::new();
+    let mut transferred = 0u64;
+    loop {
+        let n = reader.read(&mut buffer)?;
+        if n == 0 {
+            break;
+        }
+        writer.write_all(&buffer[..n])?;
+        context.consume(&buffer[..n]);
+        transferred += n as u64;
+        on_progress(transferred, total);
+    }
+    writer.flush()?;
+    Ok((transferred, format!("{:x}", context.compute())))
+}
+
+fn finish(result: TransferResult) -> TransferResult {
+    match &result.remote_md5 {
+        Some(_) if !result.verified => warn!(
+            "⚠️ 文件校验不一致 ({:?}): {} ↔ {}",
+            result.direction, result.local_path, result.remote_path
+        ),
+        _ => info!(
+            "📁 文件传输完成 ({:?}): {} ↔ {} ({} 字节)",
+            result.direction, result.local_path, result.remote_path, result.bytes
+        ),
+    }
+    result
+}
+
+/// 拉取设备文件（exec-out cat 流式读取）
+pub fn pull_file(
+    device_id: &str,
+    remote_path: &str,
+    local_path: &Path,
+    on_progress: impl FnMut(u64, u64),
+) -> Result<TransferResult, String> {
+    let total = remote_size(device_id, remote_path)?;
+    if let Some(dir) = local_path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| format!("创建本地目录失败: {}", e))?;
+    }
+    let file = std::fs::File::create(local_path).map_err(|e| format!("创建本地文件失败: {}", e))?;
+
+    let mut child = adb_command()
+        .args(["-s", device_id, "exec-out", &format!("cat {}", shell_quote(remote_path))])
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("启动 adb 失败: {}", e))?;
+    let stdout = child.stdout.take().ok_or("无法读取 adb 输出")?;
+    let copied = copy_chunked(stdout, std::io::BufWriter::new(file), total, on_progress);
+    let status = child.wait().map_err(|e| e.to_string())?;
+    let (bytes, local_md5) = copied.map_err(|e| format!("拉取文件失败: {}", e))?;
+    if !status.success() || bytes != total {
+        let _ = std::fs::remove_file(local_path);
+        return Err(format!("拉取文件不完整: 期望 {} 字节，收到 {} 字节", total, bytes));
+    }
+
+    let remote_md5 = remote_md5(device_id, remote_path)?;
+    Ok(finish(TransferResult {
+        direction: TransferDirection::Pull,
+        device_id: device_id.to_string(),
+        remote_path: remote_path.to_string(),
+        local_path: local_path.to_string_lossy().to_string(),
+        bytes,
+        verified: remote_md5.as_deref().map_or(true, |h| h == local_md5),
+        local_md5,
+        remote_md5,
+    }))
+}
+
+/// 推送文件到设备（exec-in 写入 stdin），完成后比对 MD5
+pub fn push_file(
+    device_id: &str,
+    local_path: &Path,
+    remote_path: &str,
+    on_progress: impl FnMut(u64, u64),
+) -> Result<TransferResult, String> {
+    validate_remote_path(remote_path)?;
+    let file = std::fs::File::open(local_path).map_err(|e| format!("打开本地文件失败: {}", e))?;
+    let total = file.metadata().map(|m| m.len()).unwrap_or(0);
+    if let Some((dir, _)) = remote_path.rsplit_once('/').filter(|(dir, _)| !dir.is_empty()) {
+        adb_shell(device_id, &format!("mkdir -p {}", shell_quote(dir)))?;
+    }
+
+    let mut child = adb_command()
+        .args(["-s", device_id, "exec-in", &format!("cat > {}", shell_quote(remote_path))])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("启动 adb 失败: {}", e))?;
+    let stdin = child.stdin.take().ok_or("无法写入 adb 输入")?;
+    // stdin 在 copy_chunked 结束时随所有权释放，通知设备端 EOF
+    let copied = copy_chunked(std::io::BufReader::new(file), stdin, total, on_progress);
+    let output = child.wait_with_output().map_err(|e| e.to_string())?;
+    let (bytes, local_md5) = copied.map_err(|e| format!("推送文件失败: {}", e))?;
+    if !output.status.success() {
+        return Err(format!("推送文件失败: {}", String::from_utf8_lossy(&output.stderr).trim()));
+    }
+
+    let remote_md5 = remote_md5(device_id, remote_path)?;
+    Ok(finish(TransferResult {
+        direction: TransferDirection::Push,
+        device_id: device_id.to_string(),
+        remote_path: remote_path.to_string(),
+        local_path: local_path.to_string_lossy().to_string(),
+        bytes,
+        verified: remote_md5.as_deref().map_or(true, |h| h == local_md5),
+        local_md5,
+        remote_md5,
+    }))
+}
+
+/// 检查路径是否位于允许删除的目录下
+pub fn check_deletable(path: &str) -> Result<(), String> {
+    validate_remote_path(path)?;
+    if path.split('/').any(|segment| segment == "..") {
+        return Err(format!("路径不能包含 ..: {}", path));
+    }
+    let normalized = format!("{}/", path.trim_end_matches('/'));
+    if DELETABLE_ROOTS.iter().any(|root| normalized.starts_with(root) && normalized.len() > root.len()) {
+        Ok(())
+    } else {
+        Err(format!("只允许删除 {:?} 下的文件: {}", DELETABLE_ROOTS, path))
+    }
+}
+
+/// 删除设备文件或目录
+pub fn delete_path(device_id: &str, path: &str, recursive: bool) -> Result<(), String> {
+    check_deletable(path)?;
+    let flags = if recursive { "-rf" } else { "-f" };
+    adb_shell(device_id, &format!("rm {} {}", flags, shell_quote(path)))?;
+    info!("🗑️ 已删除设备文件 {}: {}", device_id, path);
+    Ok(())
+}
+
+/// 清理 uiautomator 遗留的 dump 文件，返回实际删除的路径
+pub fn cleanup_uiautomator_artifacts(device_id: &str) -> Result<Vec<String>, String> {
+    // 通配符需要由设备端 sh 展开，因此这里不加引号
+    let command = format!(
+        "for f in {}; do [ -f \"$f\" ] && rm -f \"$f\" && echo \"$f\"; done; true",
+        UIAUTOMATOR_ARTIFACTS.join(" ")
+    );
+    let removed: Vec<String> = adb_shell(device_id, &command)?
+        .lines()
+        .map(|l| l.trim().to_string())
+        .filter(|l| !l.is_empty())
+        .collect();
+    info!("🧹 设备 {} 清理 uiautomator 临时文件 {} 个", device_id, removed.len());
+    Ok(removed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_listing_and_guards_deletes() {
+        let output = "4096|1700000000|directory|/sdcard/Download\r\n\
+                      120|1700000100|regular file|/sdcard/a|b.vcf\n\
+                      0|1700000200|regular empty file|/sdcard/empty\n\
+                      garbage line\n";
+        let entries = parse_stat_lines(output);
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[0].kind, "dir");
+        assert_eq!(entries[1].name, "a|b.vcf");
+        assert_eq!(entries[2].size, 0);
+
+        assert!(check_deletable("/sdcard/Download/x.vcf").is_ok());
+        assert!(check_deletable("/data/local/tmp/window_dump.xml").is_ok());
+        assert!(check_deletable("/sdcard").is_err());
+        assert!(check_deletable("/sdcard/").is_err());
+        assert!(check_deletable("/sdcard/../system").is_err());
+        assert!(check_deletable("/system/app").is_err());
+
+        assert_eq!(shell_quote("it's"), r"'it'\''s'");
+        assert_eq!(parse_md5sum("D41D8CD98F00B204E9800998ECF8427E  /sdcard/x\n").as_deref(), Some("d41d8cd98f00b204e9800998ecf8427e"));
+        assert_eq!(parse_md5sum("unsupported"), None);
+
+        let mut seen = Vec::new();
+        let data = vec![7u8; TRANSFER_CHUNK_SIZE + 10];
+        let (bytes, hash) = copy_chunked(&data[..], Vec::new(), data.len() as u64, |done, _| seen.push(done)).unwrap();
+        assert_eq!(bytes, data.len() as u64);
+        assert_eq!(seen.last(), Some(&bytes));
+        assert_eq!(hash, format!("{:x}", md5::compute(&data)));
+    }
+}
@@ -0,0 +1,342 @@
+// src-tauri/src/services/device_files/verified_push.rs
+// module: device_files | layer: services | role: 带校验的推送
+// summary: 推送前探测设备端 sha256sum / md5sum，推送后比对整文件哈希；不一致时指数退避重试，残留的部分文件前缀校验通过则续传，并回调已校验字节进度
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+use std::process::Stdio;
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+use tracing::{info, warn};
+
+use super::{adb_command, adb_shell, copy_chunked, shell_quote, validate_remote_path, TRANSFER_CHUNK_SIZE};
+
+/// 空输入的哈希，用于探测设备端哈希工具是否可用
+const EMPTY_SHA256: &str = "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855";
+const EMPTY_MD5: &str = "d41d8cd98f00b204e9800998ecf8427e";
+
+/// 设备端可用的哈希工具（按设备缓存探测结果，None 表示都不可用）
+static HASH_TOOLS: OnceLock<Mutex<HashMap<String, Option<HashTool>>>> = OnceLock::new();
+
+/// 设备端校验所用的哈希算法
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HashTool {
+    Sha256,
+    Md5,
+}
+
+impl HashTool {
+    fn command(self) -> &'static str {
+        match self {
+            HashTool::Sha256 => "sha256sum",
+            HashTool::Md5 => "md5sum",
+        }
+    }
+
+    fn hex_len(self) -> usize {
+        match self {
+            HashTool::Sha256 => 64,
+            HashTool::Md5 => 32,
+        }
+    }
+}
+
+/// 推送重试策略
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PushOptions {
+    /// 总尝试次数（含首次）
+    pub max_attempts: u32,
+    pub initial_backoff_ms: u64,
+    pub max_backoff_ms: u64,
+    /// 设备上残留部分文件且前缀校验一致时从断点续传
+    pub resume: bool,
+}
+
+impl Default for PushOptions {
+    fn default() -> Self {
+        Self { max_attempts: 3, initial_backoff_ms: 500, max_backoff_ms: 8000, resume: true }
+    }
+}
+
+/// 推送阶段
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PushPhase {
+    Resuming,
+    Transferring,
+    Verifying,
+    Retrying,
+    Verified,
+}
+
+/// 推送进度（`verified_bytes` 为已通过哈希校验的字节数）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PushProgress {
+    pub device_id: String,
+    pub remote_path: String,
+    pub attempt: u32,
+    pub phase: PushPhase,
+    pub sent_bytes: u64,
+    pub verified_bytes: u64,
+    pub total_bytes: u64,
+}
+
+/// 带校验推送的结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VerifiedPush {
+    pub device_id: String,
+    pub remote_path: String,
+    pub local_path: String,
+    pub bytes: u64,
+    pub local_sha256: String,
+    /// 设备端整文件哈希（算法见 `hash_tool`）；设备无哈希工具时为空，只比对大小
+    pub remote_hash: Option<String>,
+    pub hash_tool: Option<HashTool>,
+    pub attempts: u32,
+    /// 最后一次尝试中续传跳过的字节数
+    pub resumed_bytes: u64,
+}
+
+/// 第 `retry` 次重试前的等待时间（从 1 开始，指数增长并封顶）
+pub fn backoff_delay(retry: u32, options: &PushOptions) -> Duration {
+    let factor = 1u64 << retry.saturating_sub(1).min(20);
+    Duration::from_millis(options.initial_backoff_ms.saturating_mul(factor).min(options.max_backoff_ms))
+}
+
+fn parse_digest(output: &str, tool: HashTool) -> Option<String> {
+    let hash = output.split_whitespace().next()?.to_ascii_lowercase();
+    (hash.len() == tool.hex_len() && hash.chars().all(|c| c.is_ascii_hexdigit())).then_some(hash)
+}
+
+/// 解析探测输出：优先 sha256sum，其次 md5sum
+fn parse_hash_probe(output: &str) -> Option<HashTool> {
+    let output = output.to_ascii_lowercase();
+    if output.contains(EMPTY_SHA256) {
+        Some(HashTool::Sha256)
+    } else if output.contains(EMPTY_MD5) {
+        Some(HashTool::Md5)
+    } else {
+        None
+    }
+}
+
+/// 探测设备端可用的哈希工具（结果按设备缓存）
+pub fn detect_hash_tool(device_id: &str) -> Result<Option<HashTool>, String> {
+    let cache = HASH_TOOLS.get_or_init(|| Mutex::new(HashMap::new()));
+    if let Some(tool) = cache.lock().map_err(|e| e.to_string())?.get(device_id) {
+        return Ok(*tool);
+    }
+    let output = adb_shell(device_id, "sha256sum </dev/null 2>/dev/null; md5sum </dev/null 2>/dev/null; true")?;
+    let tool = parse_hash_probe(&output);
+    if tool.is_none() {
+        warn!("⚠️ 设备 {} 不支持 sha256sum / md5sum，推送只能比对文件大小", device_id);
+    }
+    cache.lock().map_err(|e| e.to_string())?.insert(device_id.to_string(), tool);
+    Ok(tool)
+}
+
+/// 本地文件（前 `limit` 字节）的哈希
+fn local_digest(path: &Path, tool: HashTool, limit: Option<u64>) -> Result<String, String> {
+    let file = std::fs::File::open(path).map_err(|e| format!("打开本地文件失败: {}", e))?;
+    let mut reader = file.take(limit.unwrap_or(u64::MAX));
+    let mut buffer = vec![0u8; TRANSFER_CHUNK_SIZE];
+    let mut sha256 = Sha256::new();
+    let mut md5 = md5::Context::new();
+    loop {
+        let n = reader.read(&mut buffer).map_err(|e| format!("读取本地文件失败: {}", e))?;
+        if n == 0 {
+            break;
+        }
+        match tool {
+            HashTool::Sha256 => sha256.update(&buffer[..n]),
+            HashTool::Md5 => md5.consume(&buffer[..n]),
+        }
+    }
+    Ok(match tool {
+        HashTool::Sha256 => hex::encode(sha256.finalize()),
+        HashTool::Md5 => format!("{:x}", md5.compute()),
+    })
+}
+
+/// 设备端文件（前 `limit` 字节）的哈希
+fn remote_digest(device_id: &str, path: &str, tool: HashTool, limit: Option<u64>) -> Result<Option<String>, String> {
+    let command = match limit {
+        Some(limit) => format!("head -c {} {} | {}", limit, shell_quote(path), tool.command()),
+        None => format!("{} {}", tool.command(), shell_quote(path)),
+    };
+    Ok(parse_digest(&adb_shell(device_id, &format!("{} 2>/dev/null", command))?, tool))
+}
+
+/// 设备端文件大小，不存在时为 None
+fn existing_size(device_id: &str, path: &str) -> Result<Option<u64>, String> {
+    let quoted = shell_quote(path);
+    let output = adb_shell(device_id, &format!("[ -f {0} ] && stat -c %s {0}; true", quoted))?;
+    Ok(output.trim().parse().ok())
+}
+
+/// 从 `offset` 起把本地文件写入设备（offset > 0 时追加）
+fn stream_from(
+    device_id: &str,
+    local_path: &Path,
+    remote_path: &str,
+    offset: u64,
+    total: u64,
+    mut on_sent: impl FnMut(u64),
+) -> Result<(), String> {
+    let mut file = std::fs::File::open(local_path).map_err(|e| format!("打开本地文件失败: {}", e))?;
+    file.seek(SeekFrom::Start(offset)).map_err(|e| format!("定位本地文件失败: {}", e))?;
+    let redirect = if offset > 0 { ">>" } else { ">" };
+    let mut child = adb_command()
+        .args(["-s", device_id, "exec-in", &format!("cat {} {}", redirect, shell_quote(remote_path))])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("启动 adb 失败: {}", e))?;
+    let stdin = child.stdin.take().ok_or("无法写入 adb 输入")?;
+    let copied = copy_chunked(std::io::BufReader::new(file), stdin, total - offset, |sent, _| on_sent(offset + sent));
+    let output = child.wait_with_output().map_err(|e| e.to_string())?;
+    let (bytes, _) = copied.map_err(|e| format!("推送文件失败: {}", e))?;
+    if !output.status.success() {
+        return Err(format!("推送文件失败: {}", String::from_utf8_lossy(&output.stderr).trim()));
+    }
+    if offset + bytes != total {
+        return Err(format!("推送文件不完整: 期望 {} 字节，发送 {} 字节", total, offset + bytes));
+    }
+    Ok(())
+}
+
+/// 推送文件并校验设备端哈希；校验不一致时删除远端文件后退避重试，传输中断时保留部分文件以便续传
+pub fn push_file_verified(
+    device_id: &str,
+    local_path: &Path,
+    remote_path: &str,
+    options: &PushOptions,
+    mut on_progress: impl FnMut(&PushProgress),
+) -> Result<VerifiedPush, String> {
+    validate_remote_path(remote_path)?;
+    let total = std::fs::metadata(local_path).map_err(|e| format!("读取本地文件失败: {}", e))?.len();
+    let tool = detect_hash_tool(device_id)?;
+    let local_sha256 = local_digest(local_path, HashTool::Sha256, None)?;
+    let expected = match tool {
+        Some(HashTool::Md5) => Some(local_digest(local_path, HashTool::Md5, None)?),
+        Some(HashTool::Sha256) => Some(local_sha256.clone()),
+        None => None,
+    };
+    if let Some((dir, _)) = remote_path.rsplit_once('/').filter(|(dir, _)| !dir.is_empty()) {
+        adb_shell(device_id, &format!("mkdir -p {}", shell_quote(dir)))?;
+    }
+
+    let max_attempts = options.max_attempts.max(1);
+    let mut last_error = String::new();
+    for attempt in 1..=max_attempts {
+        let mut report = |phase: PushPhase, sent_bytes: u64, verified_bytes: u64| {
+            on_progress(&PushProgress {
+                device_id: device_id.to_string(),
+                remote_path: remote_path.to_string(),
+                attempt,
+                phase,
+                sent_bytes,
+                verified_bytes,
+                total_bytes: total,
+            })
+        };
+        if attempt > 1 {
+            report(PushPhase::Retrying, 0, 0);
+            std::thread::sleep(backoff_delay(attempt - 1, options));
+        }
+
+        // 续传：远端已有文件的前缀与本地一致时跳过这部分
+        let mut offset = 0;
+        if let (true, Some(tool)) = (options.resume, tool) {
+            match existing_size(device_id, remote_path) {
+                Ok(Some(size)) if size > 0 && size <= total => {
+                    report(PushPhase::Resuming, size, 0);
+                    if let Ok(Some(remote)) = remote_digest(device_id, remote_path, tool, Some(size)) {
+                        if remote == local_digest(local_path, tool, Some(size))? {
+                            offset = size;
+                        }
+                    }
+                }
+                Ok(_) => {}
+                Err(e) => warn!("⚠️ 读取远端文件大小失败，从头推送: {}", e),
+            }
+        }
+
+        if offset < total {
+            let sent = stream_from(device_id, local_path, remote_path, offset, total, |sent| {
+                report(PushPhase::Transferring, sent, offset)
+            });
+            if let Err(e) = sent {
+                warn!("⚠️ 推送中断 (第 {}/{} 次): {}", attempt, max_attempts, e);
+                last_error = e;
+                continue;
+            }
+        }
+
+        report(PushPhase::Verifying, total, offset);
+        let (remote_hash, verified) = match tool {
+            Some(tool) => {
+                let remote = remote_digest(device_id, remote_path, tool, None)?;
+                let verified = remote.is_some() && remote == expected;
+                (remote, verified)
+            }
+            None => (None, existing_size(device_id, remote_path)? == Some(total)),
+        };
+        if verified {
+            report(PushPhase::Verified, total, total);
+            info!("✅ 推送校验通过: {} -> {} ({} 字节, 第 {} 次, 续传 {} 字节)", local_path.display(), remote_path, total, attempt, offset);
+            return Ok(VerifiedPush {
+                device_id: device_id.to_string(),
+                remote_path: remote_path.to_string(),
+                local_path: local_path.to_string_lossy().to_string(),
+                bytes: total,
+                local_sha256,
+                remote_hash,
+                hash_tool: tool,
+                attempts: attempt,
+                resumed_bytes: offset,
+            });
+        }
+
+        warn!("⚠️ 推送校验不一致 (第 {}/{} 次): 期望 {:?}，设备端 {:?}", attempt, max_attempts, expected, remote_hash);
+        last_error = format!("校验不一致: 期望 {}，设备端 {}", expected.as_deref().unwrap_or("-"), remote_hash.as_deref().unwrap_or("-"));
+        // 内容已损坏，不能作为续传前缀
+        adb_shell(device_id, &format!("rm -f {}", shell_quote(remote_path)))?;
+    }
+    Err(format!("推送失败（已尝试 {} 次）: {}", max_attempts, last_error))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backoff_probe_and_local_digests() {
+        let options = PushOptions { initial_backoff_ms: 500, max_backoff_ms: 3000, ..Default::default() };
+        let delays: Vec<u64> = (1..=5).map(|r| backoff_delay(r, &options).as_millis() as u64).collect();
+        assert_eq!(delays, vec![500, 1000, 2000, 3000, 3000]);
+        assert_eq!(backoff_delay(64, &PushOptions::default()), Duration::from_millis(8000));
+
+        assert_eq!(parse_hash_probe(&format!("{}  -\n{}  -\n", EMPTY_SHA256, EMPTY_MD5)), Some(HashTool::Sha256));
+        assert_eq!(parse_hash_probe(&format!("{}  -\r\n", EMPTY_MD5.to_uppercase())), Some(HashTool::Md5));
+        assert_eq!(parse_hash_probe("sh: sha256sum: not found"), None);
+        assert_eq!(parse_digest(&format!("{}  /sdcard/a.vcf", EMPTY_SHA256), HashTool::Md5), None);
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("contacts.vcf");
+        std::fs::write(&path, b"BEGIN:VCARD\nEND:VCARD\n").unwrap();
+        assert_eq!(local_digest(&path, HashTool::Sha256, Some(0)).unwrap(), EMPTY_SHA256);
+        assert_eq!(local_digest(&path, HashTool::Md5, Some(0)).unwrap(), EMPTY_MD5);
+        assert_eq!(local_digest(&path, HashTool::Md5, None).unwrap(), format!("{:x}", md5::compute(b"BEGIN:VCARD\nEND:VCARD\n")));
+        assert_eq!(
+            local_digest(&path, HashTool::Sha256, Some(6)).unwrap(),
+            hex::encode(Sha256::digest(b"BEGIN:"))
+        );
+    }
+}
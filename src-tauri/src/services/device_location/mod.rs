@@ -0,0 +1,395 @@
+// src-tauri/src/services/device_location/mod.rs
+// module: device_location | layer: services | role: 设备定位控制
+// summary: 按设备类型选择定位下发方式（模拟器控制台 / 雷电 ldconsole / 模拟定位应用），通过 dumpsys location 回读校验，并维护按营销活动的定位预设
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::process::Command;
+use std::sync::{Mutex, OnceLock};
+use tracing::{info, warn};
+
+use crate::utils::adb_utils::execute_adb_command;
+
+/// 配置文件名
+const CONFIG_FILE_NAME: &str = "device_locations.json";
+/// 默认模拟定位应用（io.appium.settings 提供 LocationService）
+const DEFAULT_MOCK_PACKAGE: &str = "io.appium.settings";
+/// 校验容差（度，约 100 米）
+const VERIFY_TOLERANCE_DEG: f64 = 0.001;
+/// 模拟器控制台端口起点（emulator-5554 → 雷电索引 0）
+const EMULATOR_BASE_PORT: u32 = 5554;
+
+static MANAGER: OnceLock<Mutex<LocationManager>> = OnceLock::new();
+
+/// 定位下发方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LocationMethod {
+    /// 按设备自动选择
+    #[default]
+    Auto,
+    /// `adb emu geo fix`（Android SDK 模拟器）
+    EmulatorConsole,
+    /// `ldconsole locate`（雷电模拟器）
+    LdConsole,
+    /// 真机：通过模拟定位应用
+    MockProvider,
+}
+
+/// 定位预设
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LocationPreset {
+    pub id: String,
+    pub name: String,
+    pub latitude: f64,
+    pub longitude: f64,
+    #[serde(default)]
+    pub method: LocationMethod,
+}
+
+/// 定位配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LocationConfig {
+    #[serde(default)]
+    pub presets: Vec<LocationPreset>,
+    /// 营销活动 ID → 预设 ID
+    #[serde(default)]
+    pub campaign_presets: HashMap<String, String>,
+    /// 雷电 ldconsole.exe 路径（为空时 Auto 不会选择 LdConsole）
+    #[serde(default)]
+    pub ldconsole_path: Option<String>,
+    #[serde(default = "default_mock_package")]
+    pub mock_provider_package: String,
+}
+
+fn default_mock_package() -> String {
+    DEFAULT_MOCK_PACKAGE.to_string()
+}
+
+impl Default for LocationConfig {
+    fn default() -> Self {
+        Self {
+            presets: Vec::new(),
+            campaign_presets: HashMap::new(),
+            ldconsole_path: None,
+            mock_provider_package: default_mock_package(),
+        }
+    }
+}
+
+/// 定位下发与校验结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LocationStatus {
+    pub device_id: String,
+    pub method: LocationMethod,
+    pub requested: (f64, f64),
+    /// 设备回读的最近定位（lat, lng）
+    pub effective: Option<(f64, f64)>,
+    pub provider: Option<String>,
+    pub verified: bool,
+}
+
+/// 命令通道抽象（adb 与 ldconsole；便于无设备测试）
+pub trait LocationTransport {
+    fn adb(&self, args: &[&str]) -> Result<String, String>;
+    fn ldconsole(&self, ldconsole_path: &str, args: &[&str]) -> Result<String, String>;
+}
+
+/// 实际执行 adb / ldconsole
+pub struct SystemTransport;
+
+impl LocationTransport for SystemTransport {
+    fn adb(&self, args: &[&str]) -> Result<String, String> {
+        let output = execute_adb_command(args).map_err(|e| format!("adb 执行失败: {}", e))?;
+        if !output.status.success() {
+            return Err(format!("adb 命令失败: {}", String::from_utf8_lossy(&output.stderr).trim()));
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    }
+
+    fn ldconsole(&self, ldconsole_path: &str, args: &[&str]) -> Result<String, String> {
+        let mut cmd = Command::new(ldconsole_path);
+        cmd.args(args);
+        #[cfg(windows)]
+        {
+            use std::os::windows::process::CommandExt;
+            cmd.creation_flags(0x08000000);
+        }
+        let output = cmd.output().map_err(|e| format!("ldconsole 执行失败: {}", e))?;
+        if !output.status.success() {
+            return Err(format!("ldconsole 命令失败: {}", String::from_utf8_lossy(&output.stderr).trim()));
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    }
+}
+
+/// 坐标合法性检查
+pub fn validate_coordinates(latitude: f64, longitude: f64) -> Result<(), String> {
+    if !(-90.0..=90.0).contains(&latitude) || !(-180.0..=180.0).contains(&longitude) {
+        return Err(format!("坐标超出范围: lat={}, lng={}", latitude, longitude));
+    }
+    Ok(())
+}
+
+/// `emulator-5556` → 控制台端口 5556
+fn emulator_port(device_id: &str) -> Option<u32> {
+    device_id.strip_prefix("emulator-")?.parse().ok()
+}
+
+/// 雷电实例索引（端口 5554 起每实例 +2）
+fn ldplayer_index(device_id: &str) -> Option<u32> {
+    let port = emulator_port(device_id)?;
+    (port >= EMULATOR_BASE_PORT && (port - EMULATOR_BASE_PORT) % 2 == 0).then(|| (port - EMULATOR_BASE_PORT) / 2)
+}
+
+/// 解析 Auto 为具体方式
+pub fn resolve_method(method: LocationMethod, device_id: &str, config: &LocationConfig) -> LocationMethod {
+    if method != LocationMethod::Auto {
+        return method;
+    }
+    let has_ldconsole = config.ldconsole_path.as_deref().is_some_and(|p| !p.is_empty());
+    match emulator_port(device_id) {
+        Some(_) if has_ldconsole && ldplayer_index(device_id).is_some() => LocationMethod::LdConsole,
+        Some(_) => LocationMethod::EmulatorConsole,
+        None => LocationMethod::MockProvider,
+    }
+}
+
+/// 从 `dumpsys location` 输出中取第一条 `Location[provider lat,lng ...]`
+pub fn parse_dumpsys_location(output: &str) -> Option<(String, f64, f64)> {
+    for (start, _) in output.match_indices("Location[") {
+        let rest = &output[start + "Location[".len()..];
+        let mut parts = rest.split_whitespace();
+        let Some(provider) = parts.next() else { continue };
+        let Some(coords) = parts.next() else { continue };
+        let mut latlng = coords.trim_end_matches(']').split(',');
+        let (Some(lat), Some(lng)) = (latlng.next(), latlng.next()) else { continue };
+        if let (Ok(lat), Ok(lng)) = (lat.parse::<f64>(), lng.parse::<f64>()) {
+            return Some((provider.to_string(), lat, lng));
+        }
+    }
+    None
+}
+
+/// 定位管理器
+pub struct LocationManager {
+    config: LocationConfig,
+    config_path: Option<PathBuf>,
+}
+
+impl LocationManager {
+    pub fn global() -> &'static Mutex<LocationManager> {
+        MANAGER.get_or_init(|| {
+            let config_path = dirs::data_dir()
+                .unwrap_or_else(|| PathBuf::from("."))
+                .join("employee-gui")
+                .join(CONFIG_FILE_NAME);
+            let config = std::fs::read_to_string(&config_path)
+                .ok()
+                .and_then(|content| serde_json::from_str(&content).ok())
+                .unwrap_or_default();
+            Mutex::new(Self { config, config_path: Some(config_path) })
+        })
+    }
+
+    /// 不落盘的实例
+    pub fn in_memory(config: LocationConfig) -> Self {
+        Self { config, config_path: None }
+    }
+
+    pub fn config(&self) -> &LocationConfig {
+        &self.config
+    }
+
+    pub fn save_config(&mut self, config: LocationConfig) -> Result<(), String> {
+        for preset in &config.presets {
+            validate_coordinates(preset.latitude, preset.longitude)
+                .map_err(|e| format!("预设 {}: {}", preset.id, e))?;
+        }
+        if let Some((campaign, preset)) = config.campaign_presets.iter()
+            .find(|(_, p)| !config.presets.iter().any(|preset| &preset.id == *p))
+        {
+            return Err(format!("活动 {} 引用了不存在的定位预设: {}", campaign, preset));
+        }
+        if let Some(path) = &self.config_path {
+            if let Some(dir) = path.parent() {
+                std::fs::create_dir_all(dir).map_err(|e| format!("创建配置目录失败: {}", e))?;
+            }
+            let content = serde_json::to_string_pretty(&config).map_err(|e| e.to_string())?;
+            std::fs::write(path, content).map_err(|e| format!("保存定位配置失败: {}", e))?;
+        }
+        info!("💾 定位配置已更新: {} 个预设，{} 个活动已绑定", config.presets.len(), config.campaign_presets.len());
+        self.config = config;
+        Ok(())
+    }
+
+    pub fn preset_for_campaign(&self, campaign_id: &str) -> Option<&LocationPreset> {
+        let preset_id = self.config.campaign_presets.get(campaign_id)?;
+        self.config.presets.iter().find(|p| &p.id == preset_id)
+    }
+
+    /// 下发定位并回读校验
+    pub fn set_location(
+        &self,
+        transport: &dyn LocationTransport,
+        device_id: &str,
+        latitude: f64,
+        longitude: f64,
+        method: LocationMethod,
+    ) -> Result<LocationStatus, String> {
+        validate_coordinates(latitude, longitude)?;
+        let method = resolve_method(method, device_id, &self.config);
+        let lat = latitude.to_string();
+        let lng = longitude.to_string();
+        match method {
+            LocationMethod::EmulatorConsole | LocationMethod::Auto => {
+                // geo fix 参数顺序为 经度 纬度
+                transport.adb(&["-s", device_id, "emu", "geo", "fix", &lng, &lat])?;
+            }
+            LocationMethod::LdConsole => {
+                let path = self.config.ldconsole_path.as_deref().filter(|p| !p.is_empty())
+                    .ok_or("未配置 ldconsole 路径")?;
+                let index = ldplayer_index(device_id)
+                    .ok_or_else(|| format!("无法从设备 ID 推断雷电实例索引: {}", device_id))?;
+                let lli = format!("{},{}", lng, lat);
+                transport.ldconsole(path, &["locate", "--index", &index.to_string(), "--LLI", &lli])?;
+            }
+            LocationMethod::MockProvider => {
+                let package = self.config.mock_provider_package.as_str();
+                transport.adb(&["-s", device_id, "shell", "appops", "set", package, "android:mock_location", "allow"])?;
+                let component = format!("{}/.LocationService", package);
+                transport.adb(&[
+                    "-s", device_id, "shell", "am", "start-foreground-service", "--user", "0",
+                    "-n", &component, "--es", "latitude", &lat, "--es", "longitude", &lng,
+                ])?;
+            }
+        }
+
+        let mut status = self.verify_location(transport, device_id, latitude, longitude)?;
+        status.method = method;
+        if status.verified {
+            info!("📍 设备 {} 定位已设置为 ({}, {})，方式 {:?}", device_id, latitude, longitude, method);
+        } else {
+            warn!("⚠️ 设备 {} 定位校验未通过: 期望 ({}, {})，实际 {:?}", device_id, latitude, longitude, status.effective);
+        }
+        Ok(status)
+    }
+
+    /// 读取设备最近定位并与期望坐标比对
+    pub fn verify_location(
+        &self,
+        transport: &dyn LocationTransport,
+        device_id: &str,
+        latitude: f64,
+        longitude: f64,
+    ) -> Result<LocationStatus, String> {
+        let output = transport.adb(&["-s", device_id, "shell", "dumpsys", "location"])?;
+        let parsed = parse_dumpsys_location(&output);
+        let verified = parsed.as_ref().is_some_and(|(_, lat, lng)| {
+            (lat - latitude).abs() <= VERIFY_TOLERANCE_DEG && (lng - longitude).abs() <= VERIFY_TOLERANCE_DEG
+        });
+        Ok(LocationStatus {
+            device_id: device_id.to_string(),
+            method: resolve_method(LocationMethod::Auto, device_id, &self.config),
+            requested: (latitude, longitude),
+            effective: parsed.as_ref().map(|(_, lat, lng)| (*lat, *lng)),
+            provider: parsed.map(|(provider, _, _)| provider),
+            verified,
+        })
+    }
+
+    /// 脚本启动前应用活动绑定的定位预设；活动未绑定时返回 `Ok(None)`
+    pub fn apply_campaign_preset(
+        &self,
+        transport: &dyn LocationTransport,
+        campaign_id: &str,
+        device_id: &str,
+    ) -> Result<Option<LocationStatus>, String> {
+        let Some(preset) = self.preset_for_campaign(campaign_id) else {
+            return Ok(None);
+        };
+        let status = self.set_location(transport, device_id, preset.latitude, preset.longitude, preset.method)?;
+        if !status.verified {
+            return Err(format!(
+                "活动 {} 定位预设 {} 未生效: 设备回读 {:?}",
+                campaign_id, preset.name, status.effective
+            ));
+        }
+        Ok(Some(status))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+
+    /// 记录命令并把最近一次写入的坐标作为 dumpsys 输出
+    #[derive(Default)]
+    struct FakeTransport {
+        commands: RefCell<Vec<String>>,
+        location: RefCell<Option<(String, String)>>,
+    }
+
+    impl LocationTransport for FakeTransport {
+        fn adb(&self, args: &[&str]) -> Result<String, String> {
+            self.commands.borrow_mut().push(args.join(" "));
+            match args {
+                [.., "emu", "geo", "fix", lng, lat] => *self.location.borrow_mut() = Some((lat.to_string(), lng.to_string())),
+                [.., "--es", "latitude", lat, "--es", "longitude", lng] => {
+                    *self.location.borrow_mut() = Some((lat.to_string(), lng.to_string()))
+                }
+                [.., "dumpsys", "location"] => {
+                    return Ok(match &*self.location.borrow() {
+                        Some((lat, lng)) => format!("  last location=Location[gps {},{} hAcc=5 et=+1s]\n", lat, lng),
+                        None => "Location Manager State:\n".to_string(),
+                    });
+                }
+                _ => {}
+            }
+            Ok(String::new())
+        }
+
+        fn ldconsole(&self, _path: &str, args: &[&str]) -> Result<String, String> {
+            self.commands.borrow_mut().push(format!("ldconsole {}", args.join(" ")));
+            Ok(String::new())
+        }
+    }
+
+    #[test]
+    fn applies_campaign_presets_with_device_specific_methods() {
+        let transport = FakeTransport::default();
+        let mut manager = LocationManager::in_memory(LocationConfig::default());
+        let preset = LocationPreset {
+            id: "sh".into(),
+            name: "上海".into(),
+            latitude: 31.2304,
+            longitude: 121.4737,
+            method: LocationMethod::Auto,
+        };
+        let mut config = LocationConfig { presets: vec![preset], ..Default::default() };
+        config.campaign_presets.insert("c1".into(), "missing".into());
+        assert!(manager.save_config(config.clone()).is_err());
+        config.campaign_presets.insert("c1".into(), "sh".into());
+        manager.save_config(config).unwrap();
+
+        let status = manager.apply_campaign_preset(&transport, "c1", "emulator-5554").unwrap().unwrap();
+        assert_eq!(status.method, LocationMethod::EmulatorConsole);
+        assert!(status.verified);
+        assert!(transport.commands.borrow()[0].ends_with("emu geo fix 121.4737 31.2304"));
+
+        let status = manager.set_location(&transport, "R58M123", 22.5431, 114.0579, LocationMethod::Auto).unwrap();
+        assert_eq!(status.method, LocationMethod::MockProvider);
+        assert_eq!(status.provider.as_deref(), Some("gps"));
+        assert!(manager.apply_campaign_preset(&transport, "other", "R58M123").unwrap().is_none());
+
+        // 假通道的 ldconsole 不改变回读坐标，校验应失败
+        manager.config.ldconsole_path = Some("ldconsole.exe".into());
+        let status = manager.set_location(&transport, "emulator-5556", 39.9, 116.4, LocationMethod::Auto).unwrap();
+        assert_eq!(status.method, LocationMethod::LdConsole);
+        assert!(transport.commands.borrow().iter().any(|c| c == "ldconsole locate --index 1 --LLI 116.4,39.9"));
+        assert!(!status.verified);
+        assert!(validate_coordinates(91.0, 0.0).is_err());
+    }
+}
@@ -0,0 +1,299 @@
+// src-tauri/src/services/device_status_indicator/mod.rs
+// module: device_status_indicator | layer: services | role: 设备端运行状态提示
+// summary: 脚本运行期间在手机上显示「自动化运行中 – step N/M」（shell 通知或设备端代理悬浮层，代理不可用时回退通知），按间隔节流刷新，运行结束 / 被中止 / 强制停止时自动清除
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use tracing::{debug, warn};
+
+use crate::infra::adb::agent_input_injector::AgentInputInjector;
+use crate::services::device_files::shell_quote;
+
+/// shell 通知的 tag（同一设备只保留一条，重复 post 即更新）
+const NOTIFICATION_TAG: &str = "automation-status";
+/// `cmd notification post` 发出的通知归属 shell 包
+const SHELL_PACKAGE: &str = "com.android.shell";
+const DEFAULT_TITLE: &str = "营销自动化";
+
+/// 提示方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IndicatorStyle {
+    /// 通知栏常驻通知（`cmd notification`，无需额外 APK）
+    #[default]
+    Notification,
+    /// 设备端代理绘制的悬浮文字；代理不可用时回退通知
+    Overlay,
+}
+
+/// 单次运行的状态提示配置（随 SmartExecutorConfig 下发）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StatusIndicatorConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default)]
+    pub style: IndicatorStyle,
+    /// 两次刷新的最小间隔（秒），避免每步都调用 adb
+    #[serde(default = "default_min_interval_secs")]
+    pub min_interval_secs: u64,
+    /// 通知标题 / 悬浮文字前缀，默认「营销自动化」
+    #[serde(default)]
+    pub label: Option<String>,
+}
+
+fn default_min_interval_secs() -> u64 {
+    5
+}
+
+impl Default for StatusIndicatorConfig {
+    fn default() -> Self {
+        Self { enabled: false, style: IndicatorStyle::default(), min_interval_secs: default_min_interval_secs(), label: None }
+    }
+}
+
+/// 提示文字，如「自动化运行中 – step 12/50」
+pub fn format_status(current: usize, total: usize) -> String {
+    format!("自动化运行中 – step {}/{}", current, total)
+}
+
+/// 实际显示 / 清除提示的后端
+#[async_trait]
+pub trait IndicatorBackend: Send + Sync {
+    async fn show(&self, device_id: &str, title: &str, text: &str) -> Result<(), String>;
+    async fn clear(&self, device_id: &str) -> Result<(), String>;
+}
+
+/// 经 adb 的默认后端
+pub struct AdbIndicatorBackend {
+    adb_path: String,
+    style: IndicatorStyle,
+    agent: AgentInputInjector,
+}
+
+impl AdbIndicatorBackend {
+    pub fn new(adb_path: String, style: IndicatorStyle) -> Self {
+        let agent = AgentInputInjector::from_env(adb_path.clone());
+        Self { adb_path, style, agent }
+    }
+
+    async fn shell(&self, device_id: &str, command: &str) -> Result<(), String> {
+        let output = tokio::process::Command::new(&self.adb_path)
+            .args(["-s", device_id, "shell", command])
+            .output()
+            .await
+            .map_err(|e| format!("adb 执行失败: {}", e))?;
+        if !output.status.success() {
+            return Err(format!("设备命令失败: {}", String::from_utf8_lossy(&output.stderr).trim()));
+        }
+        Ok(())
+    }
+
+    async fn post_notification(&self, device_id: &str, title: &str, text: &str) -> Result<(), String> {
+        let command = format!(
+            "cmd notification post -S bigtext -t {} {} {}",
+            shell_quote(title),
+            NOTIFICATION_TAG,
+            shell_quote(text)
+        );
+        self.shell(device_id, &command).await
+    }
+
+    /// `cmd notification` 没有撤销子命令，改用 INotificationManager.cancelAllNotifications 清除 shell 包的通知
+    async fn cancel_notification(&self, device_id: &str) -> Result<(), String> {
+        self.shell(device_id, &format!("service call notification 1 s16 {} i32 0", SHELL_PACKAGE)).await
+    }
+}
+
+#[async_trait]
+impl IndicatorBackend for AdbIndicatorBackend {
+    async fn show(&self, device_id: &str, title: &str, text: &str) -> Result<(), String> {
+        if self.style == IndicatorStyle::Overlay && self.agent.is_available(device_id) {
+            match self.agent.show_overlay(device_id, &format!("{} · {}", title, text)).await {
+                Ok(()) => return Ok(()),
+                Err(e) => debug!("悬浮状态显示失败，改用通知: {}", e),
+            }
+        }
+        self.post_notification(device_id, title, text).await
+    }
+
+    async fn clear(&self, device_id: &str) -> Result<(), String> {
+        if self.style == IndicatorStyle::Overlay && self.agent.is_available(device_id) {
+            let _ = self.agent.clear_overlay(device_id).await;
+        }
+        // 悬浮层可能中途回退过通知，两者都清
+        self.cancel_notification(device_id).await
+    }
+}
+
+/// 正在显示提示的设备（强制停止时据此统一清除）
+struct ActiveIndicator {
+    stopped: Arc<AtomicBool>,
+    backend: Arc<dyn IndicatorBackend>,
+}
+
+static ACTIVE: OnceLock<Mutex<HashMap<String, ActiveIndicator>>> = OnceLock::new();
+
+fn active() -> std::sync::MutexGuard<'static, HashMap<String, ActiveIndicator>> {
+    ACTIVE.get_or_init(Default::default).lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// 单次运行的状态提示
+pub struct StatusIndicator {
+    device_id: String,
+    title: String,
+    min_interval: Duration,
+    backend: Arc<dyn IndicatorBackend>,
+    stopped: Arc<AtomicBool>,
+    last_shown: Option<Instant>,
+    shown: bool,
+    finished: bool,
+}
+
+impl StatusIndicator {
+    /// 配置启用时创建（经 adb 显示）；未配置或未启用返回 None
+    pub fn start(device_id: &str, adb_path: &str, config: Option<&StatusIndicatorConfig>) -> Option<Self> {
+        let config = config.filter(|c| c.enabled)?;
+        let backend = Arc::new(AdbIndicatorBackend::new(adb_path.to_string(), config.style));
+        Some(Self::with_backend(device_id, config, backend))
+    }
+
+    pub fn with_backend(device_id: &str, config: &StatusIndicatorConfig, backend: Arc<dyn IndicatorBackend>) -> Self {
+        let stopped = Arc::new(AtomicBool::new(false));
+        active().insert(device_id.to_string(), ActiveIndicator { stopped: stopped.clone(), backend: backend.clone() });
+        Self {
+            device_id: device_id.to_string(),
+            title: config.label.clone().filter(|l| !l.trim().is_empty()).unwrap_or_else(|| DEFAULT_TITLE.to_string()),
+            min_interval: Duration::from_secs(config.min_interval_secs),
+            backend,
+            stopped,
+            last_shown: None,
+            shown: false,
+            finished: false,
+        }
+    }
+
+    /// 进入第 `current` 步（从 1 开始）时调用；首步与最后一步总是刷新，其余按间隔节流
+    pub async fn progress(&mut self, current: usize, total: usize) {
+        if self.finished || self.stopped.load(Ordering::SeqCst) {
+            return;
+        }
+        let now = Instant::now();
+        let due = match self.last_shown {
+            None => true,
+            Some(last) => current >= total || now.duration_since(last) >= self.min_interval,
+        };
+        if !due {
+            return;
+        }
+        self.last_shown = Some(now);
+        match self.backend.show(&self.device_id, &self.title, &format_status(current, total)).await {
+            Ok(()) => self.shown = true,
+            Err(e) => warn!("⚠️ 设备状态提示显示失败 {}: {}", self.device_id, e),
+        }
+    }
+
+    /// 运行结束时清除提示
+    pub async fn finish(mut self) {
+        self.finished = true;
+        self.release();
+        if self.shown && !self.stopped.load(Ordering::SeqCst) {
+            if let Err(e) = self.backend.clear(&self.device_id).await {
+                warn!("⚠️ 设备状态提示清除失败 {}: {}", self.device_id, e);
+            }
+        }
+    }
+
+    fn release(&self) {
+        let mut active = active();
+        if active.get(&self.device_id).is_some_and(|a| Arc::ptr_eq(&a.stopped, &self.stopped)) {
+            active.remove(&self.device_id);
+        }
+    }
+}
+
+impl Drop for StatusIndicator {
+    /// 运行被中途丢弃（任务取消）时仍尽力清除
+    fn drop(&mut self) {
+        if self.finished {
+            return;
+        }
+        self.release();
+        if !self.shown || self.stopped.load(Ordering::SeqCst) {
+            return;
+        }
+        if let Ok(handle) = tokio::runtime::Handle::try_current() {
+            let backend = self.backend.clone();
+            let device_id = self.device_id.clone();
+            handle.spawn(async move {
+                let _ = backend.clear(&device_id).await;
+            });
+        }
+    }
+}
+
+/// 强制停止时清除所有设备上的提示，之后这些运行不再刷新
+pub async fn clear_all_indicators() -> usize {
+    let entries: Vec<(String, ActiveIndicator)> = active().drain().collect();
+    let count = entries.len();
+    for (device_id, entry) in entries {
+        entry.stopped.store(true, Ordering::SeqCst);
+        if let Err(e) = entry.backend.clear(&device_id).await {
+            warn!("⚠️ 设备状态提示清除失败 {}: {}", device_id, e);
+        }
+    }
+    count
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Default)]
+    struct RecordingBackend {
+        calls: Mutex<Vec<String>>,
+    }
+
+    #[async_trait]
+    impl IndicatorBackend for RecordingBackend {
+        async fn show(&self, _device_id: &str, title: &str, text: &str) -> Result<(), String> {
+            self.calls.lock().unwrap().push(format!("show {} | {}", title, text));
+            Ok(())
+        }
+
+        async fn clear(&self, _device_id: &str) -> Result<(), String> {
+            self.calls.lock().unwrap().push("clear".to_string());
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_indicator_throttles_and_clears() {
+        let backend = Arc::new(RecordingBackend::default());
+        let config = StatusIndicatorConfig { enabled: true, min_interval_secs: 3600, ..Default::default() };
+        let mut indicator = StatusIndicator::with_backend("indicator-test-1", &config, backend.clone());
+        for step in 1..=50 {
+            indicator.progress(step, 50).await;
+        }
+        indicator.finish().await;
+        assert_eq!(
+            *backend.calls.lock().unwrap(),
+            vec!["show 营销自动化 | 自动化运行中 – step 1/50", "show 营销自动化 | 自动化运行中 – step 50/50", "clear"]
+        );
+
+        // 强制停止后清除一次，运行不再刷新，结束时也不重复清除
+        let backend = Arc::new(RecordingBackend::default());
+        let config = StatusIndicatorConfig { enabled: true, min_interval_secs: 0, label: Some("引流".into()), ..Default::default() };
+        let mut indicator = StatusIndicator::with_backend("indicator-test-2", &config, backend.clone());
+        indicator.progress(1, 3).await;
+        assert!(clear_all_indicators().await >= 1);
+        indicator.progress(2, 3).await;
+        indicator.finish().await;
+        assert_eq!(*backend.calls.lock().unwrap(), vec!["show 引流 | 自动化运行中 – step 1/3", "clear"]);
+        assert!(StatusIndicator::start("indicator-test-3", "adb", Some(&StatusIndicatorConfig::default())).is_none());
+    }
+}
@@ -0,0 +1,177 @@
+// src-tauri/src/services/device_windows/mod.rs
+// module: device_windows | layer: services | role: 设备独立窗口
+// summary: 每台设备一个控制窗口（镜像 / 日志 / 任务状态）- 窗口登记表、按设备过滤的事件路由、窗口关闭时清理登记
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::sync::{Mutex, OnceLock};
+use tauri::{AppHandle, Emitter, EventTarget, Manager, Runtime, WebviewUrl, WebviewWindowBuilder};
+use tracing::{info, warn};
+
+/// 设备窗口标签前缀（capabilities 中以 `device-*` 授权）
+pub const DEVICE_WINDOW_PREFIX: &str = "device-";
+/// 设备窗口关闭后通知其余窗口
+pub const DEVICE_WINDOW_CLOSED_EVENT: &str = "device-window://closed";
+
+static REGISTRY: OnceLock<Mutex<DeviceWindowRegistry>> = OnceLock::new();
+
+/// 已打开的设备窗口
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DeviceWindow {
+    pub label: String,
+    pub device_id: String,
+    pub opened_at: DateTime<Utc>,
+}
+
+/// 窗口标签 → 设备窗口
+#[derive(Debug, Default)]
+pub struct DeviceWindowRegistry {
+    windows: HashMap<String, DeviceWindow>,
+}
+
+impl DeviceWindowRegistry {
+    pub fn global() -> &'static Mutex<DeviceWindowRegistry> {
+        REGISTRY.get_or_init(|| Mutex::new(DeviceWindowRegistry::default()))
+    }
+
+    pub fn register(&mut self, window: DeviceWindow) {
+        self.windows.insert(window.label.clone(), window);
+    }
+
+    pub fn unregister(&mut self, label: &str) -> Option<DeviceWindow> {
+        self.windows.remove(label)
+    }
+
+    pub fn get(&self, label: &str) -> Option<&DeviceWindow> {
+        self.windows.get(label)
+    }
+
+    /// 按打开时间排序
+    pub fn list(&self) -> Vec<DeviceWindow> {
+        let mut windows: Vec<_> = self.windows.values().cloned().collect();
+        windows.sort_by(|a, b| a.opened_at.cmp(&b.opened_at).then_with(|| a.label.cmp(&b.label)));
+        windows
+    }
+
+    /// 不应收到 `device_id` 事件的窗口：绑定了其他设备的设备窗口
+    pub fn excluded_for(&self, device_id: &str) -> HashSet<String> {
+        self.windows.values().filter(|w| w.device_id != device_id).map(|w| w.label.clone()).collect()
+    }
+}
+
+/// 设备 ID 对应的窗口标签（标签只允许字母数字与 `-/:_`）
+pub fn window_label(device_id: &str) -> String {
+    let safe: String = device_id
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || matches!(c, '-' | ':' | '_') { c } else { '_' })
+        .collect();
+    format!("{}{}", DEVICE_WINDOW_PREFIX, safe)
+}
+
+fn encode_query(value: &str) -> String {
+    value
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => (b as char).to_string(),
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+fn target_label(target: &EventTarget) -> Option<&str> {
+    match target {
+        EventTarget::AnyLabel { label }
+        | EventTarget::Window { label }
+        | EventTarget::Webview { label }
+        | EventTarget::WebviewWindow { label } => Some(label),
+        _ => None,
+    }
+}
+
+/// 发送设备相关事件：主窗口与该设备的窗口都能收到，其他设备的窗口收不到
+pub fn emit_for_device<R: Runtime, S: Serialize + Clone>(
+    app: &AppHandle<R>,
+    device_id: &str,
+    event: &str,
+    payload: S,
+) -> tauri::Result<()> {
+    let excluded = DeviceWindowRegistry::global().lock().map(|r| r.excluded_for(device_id)).unwrap_or_default();
+    if excluded.is_empty() {
+        return app.emit(event, payload);
+    }
+    app.emit_filter(event, payload, |target| target_label(target).is_none_or(|label| !excluded.contains(label)))
+}
+
+/// 打开（或聚焦已打开的）设备窗口
+pub fn open<R: Runtime>(app: &AppHandle<R>, device_id: &str) -> Result<DeviceWindow, String> {
+    let label = window_label(device_id);
+    if let Some(existing) = app.get_webview_window(&label) {
+        let _ = existing.unminimize();
+        existing.set_focus().map_err(|e| format!("聚焦设备窗口失败: {}", e))?;
+        let registered = DeviceWindowRegistry::global().lock().map_err(|e| e.to_string())?.get(&label).cloned();
+        if let Some(window) = registered {
+            return Ok(window);
+        }
+    } else {
+        let url = format!("index.html?deviceWindow=1&deviceId={}", encode_query(device_id));
+        WebviewWindowBuilder::new(app, &label, WebviewUrl::App(url.into()))
+            .title(format!("设备控制台 - {}", device_id))
+            .inner_size(960.0, 720.0)
+            .min_inner_size(480.0, 360.0)
+            .build()
+            .map_err(|e| format!("创建设备窗口失败: {}", e))?;
+        info!("🪟 已打开设备窗口: {} ({})", label, device_id);
+    }
+
+    let window = DeviceWindow { label, device_id: device_id.to_string(), opened_at: Utc::now() };
+    DeviceWindowRegistry::global().lock().map_err(|e| e.to_string())?.register(window.clone());
+    Ok(window)
+}
+
+/// 窗口销毁时调用：移除登记并通知其余窗口
+pub fn on_window_destroyed<R: Runtime>(app: &AppHandle<R>, label: &str) {
+    if !label.starts_with(DEVICE_WINDOW_PREFIX) {
+        return;
+    }
+    let removed = match DeviceWindowRegistry::global().lock() {
+        Ok(mut registry) => registry.unregister(label),
+        Err(e) => {
+            warn!("⚠️ 设备窗口登记表不可用: {}", e);
+            return;
+        }
+    };
+    if let Some(window) = removed {
+        info!("🪟 设备窗口已关闭: {} ({})", window.label, window.device_id);
+        let _ = app.emit(DEVICE_WINDOW_CLOSED_EVENT, &window);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn labels_registry_and_routing() {
+        assert_eq!(window_label("emulator-5554"), "device-emulator-5554");
+        assert_eq!(window_label("192.168.1.8:5555"), "device-192_168_1_8:5555");
+        assert_eq!(encode_query("192.168.1.8:5555"), "192.168.1.8%3A5555");
+
+        let mut registry = DeviceWindowRegistry::default();
+        let window = |device: &str| DeviceWindow { label: window_label(device), device_id: device.to_string(), opened_at: Utc::now() };
+        registry.register(window("emulator-5554"));
+        registry.register(window("emulator-5556"));
+        registry.register(window("emulator-5556"));
+        assert_eq!(registry.list().len(), 2);
+
+        let excluded = registry.excluded_for("emulator-5554");
+        assert_eq!(excluded, HashSet::from(["device-emulator-5556".to_string()]));
+        assert_eq!(registry.excluded_for("R58M123").len(), 2);
+
+        assert!(registry.unregister("device-emulator-5556").is_some());
+        assert!(registry.unregister("device-emulator-5556").is_none());
+        assert!(registry.excluded_for("emulator-5554").is_empty());
+        assert_eq!(target_label(&EventTarget::WebviewWindow { label: "main".into() }), Some("main"));
+        assert_eq!(target_label(&EventTarget::App), None);
+    }
+}
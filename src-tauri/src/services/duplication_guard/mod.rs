@@ -1,6 +1,11 @@
 use serde::{Deserialize, Serialize};
 use std::sync::{Mutex, OnceLock};
 
+use crate::services::protected_accounts;
+
+/// 命中受保护账号名单时返回的规则 ID
+pub const PROTECTED_ACCOUNT_RULE_ID: &str = "protected_account";
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DuplicationRule {
     pub id: String,
@@ -16,6 +21,8 @@ pub struct DuplicationCheckRequest {
     pub target_id: String,
     pub action: String, // follow | reply | like | share
     pub device_id: String,
+    #[serde(default)]
+    pub platform: Option<String>, // 目标所在平台，用于匹配受保护账号名单
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -32,6 +39,10 @@ pub struct ActionRecord {
     pub action: String,
     pub device_id: String,
     pub timestamp: i64, // epoch millis
+    #[serde(default)]
+    pub account_id: Option<String>,  // 执行账号（计费归属）
+    #[serde(default)]
+    pub campaign_id: Option<String>, // 所属活动
 }
 
 static STORE: OnceLock<Mutex<DuplicationStore>> = OnceLock::new();
@@ -133,6 +144,15 @@ fn humanize_hours(h: u64) -> String {
 
 #[tauri::command]
 pub fn check_duplication_action_cmd(req: DuplicationCheckRequest) -> DuplicationCheckResult {
+    // 受保护账号优先于任何查重规则
+    if let Some(hit) = protected_accounts::guard(req.platform.as_deref(), &req.target_id, &req.action, "duplication_guard") {
+        return DuplicationCheckResult {
+            result: "blocked".into(),
+            reason: format!("目标为受保护账号，禁止自动触达（{}）", hit.reason),
+            confidence: 100,
+            rule_id: Some(PROTECTED_ACCOUNT_RULE_ID.into()),
+        };
+    }
     let store = DuplicationStore::global().lock().unwrap();
     store.check(&req)
 }
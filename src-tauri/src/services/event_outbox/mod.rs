@@ -0,0 +1,319 @@
+// src-tauri/src/services/event_outbox/mod.rs
+// module: event_outbox | layer: services | role: 前端事件发件箱
+// summary: 任务状态事件追加写入发件箱并带游标推送，前端重连后按游标补发，确认后自动清理
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::{Mutex, OnceLock};
+use tauri::{AppHandle, Emitter, Runtime};
+use tracing::warn;
+
+const STORE_FILE_NAME: &str = "event_outbox.jsonl";
+/// 同一关联 ID 只保留最近的事件，任务状态事件后发的覆盖先发的
+const MAX_PER_CORRELATION: usize = 50;
+const MAX_TOTAL: usize = 5000;
+const MAX_AGE_HOURS: i64 = 24;
+/// 超过该时长未补发 / 确认的窗口不再阻止清理
+const CLIENT_IDLE_MINUTES: i64 = 10;
+/// 追加行数超过保留条数的倍数时重写文件
+const COMPACT_FACTOR: usize = 2;
+
+/// 每条入箱事件在原事件之外额外推送到该通道，前端据此记录游标
+pub const OUTBOX_EVENT: &str = "outbox://event";
+
+static OUTBOX: OnceLock<Mutex<EventOutbox>> = OnceLock::new();
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct OutboxEntry {
+    pub cursor: u64,
+    pub event: String,
+    pub correlation_id: String,
+    pub payload: Value,
+    pub emitted_at: DateTime<Utc>,
+}
+
+/// 一次补发的结果
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct EventReplay {
+    pub events: Vec<OutboxEntry>,
+    /// 下次补发应传入的游标
+    pub latest_cursor: u64,
+    /// 游标之后有事件因容量或过期被清理，前端应整体刷新任务状态
+    pub truncated: bool,
+    /// 本次之后仍有更多事件（受 limit 限制）
+    pub has_more: bool,
+}
+
+#[derive(Debug, Clone)]
+struct ClientAck {
+    cursor: u64,
+    seen_at: DateTime<Utc>,
+}
+
+pub struct EventOutbox {
+    path: Option<PathBuf>,
+    entries: VecDeque<OutboxEntry>,
+    last_cursor: u64,
+    /// 未经确认就被清理的最大游标
+    dropped_through: u64,
+    /// 窗口标签 -> 已确认游标
+    clients: HashMap<String, ClientAck>,
+    appended_lines: usize,
+}
+
+impl EventOutbox {
+    pub fn global() -> &'static Mutex<EventOutbox> {
+        OUTBOX.get_or_init(|| {
+            let path = dirs::data_dir().unwrap_or_else(|| PathBuf::from(".")).join("employee-gui").join(STORE_FILE_NAME);
+            Mutex::new(Self::open(path))
+        })
+    }
+
+    /// 读取上次运行遗留的事件，损坏的行直接跳过
+    pub fn open(path: PathBuf) -> Self {
+        let entries: VecDeque<OutboxEntry> = std::fs::read_to_string(&path)
+            .map(|content| content.lines().filter_map(|line| serde_json::from_str(line).ok()).collect())
+            .unwrap_or_default();
+        let last_cursor = entries.iter().map(|e| e.cursor).max().unwrap_or(0);
+        let mut outbox = Self {
+            path: Some(path),
+            appended_lines: entries.len(),
+            entries,
+            last_cursor,
+            dropped_through: 0,
+            clients: HashMap::new(),
+        };
+        outbox.prune(Utc::now());
+        outbox
+    }
+
+    #[cfg(test)]
+    fn in_memory() -> Self {
+        Self {
+            path: None,
+            entries: VecDeque::new(),
+            last_cursor: 0,
+            dropped_through: 0,
+            clients: HashMap::new(),
+            appended_lines: 0,
+        }
+    }
+
+    /// 追加一条事件并返回其游标
+    ///
+    /// 游标取毫秒时间戳（单调递增），重启后仍大于前端保存的旧游标
+    pub fn append(&mut self, event: &str, correlation_id: &str, payload: Value, now: DateTime<Utc>) -> OutboxEntry {
+        let cursor = (now.timestamp_millis().max(0) as u64).max(self.last_cursor + 1);
+        self.last_cursor = cursor;
+        let entry = OutboxEntry {
+            cursor,
+            event: event.to_string(),
+            correlation_id: correlation_id.to_string(),
+            payload,
+            emitted_at: now,
+        };
+        self.entries.push_back(entry.clone());
+        self.append_line(&entry);
+        let per_correlation = self.entries.iter().filter(|e| e.correlation_id == entry.correlation_id).count();
+        if per_correlation > MAX_PER_CORRELATION || self.entries.len() > MAX_TOTAL {
+            self.prune(now);
+        }
+        entry
+    }
+
+    /// `cursor` 之后的事件（按游标升序），可限定关联 ID
+    pub fn replay_since(
+        &mut self,
+        client: &str,
+        cursor: u64,
+        correlation_ids: Option<&[String]>,
+        limit: usize,
+        now: DateTime<Utc>,
+    ) -> EventReplay {
+        self.touch(client, now);
+        let mut matching = self
+            .entries
+            .iter()
+            .filter(|e| e.cursor > cursor)
+            .filter(|e| correlation_ids.map_or(true, |ids| ids.iter().any(|id| id == &e.correlation_id)));
+        let events: Vec<OutboxEntry> = matching.by_ref().take(limit.max(1)).cloned().collect();
+        let has_more = matching.next().is_some();
+        let latest_cursor = if has_more {
+            events.last().map_or(cursor, |e| e.cursor)
+        } else {
+            self.last_cursor.max(cursor)
+        };
+        EventReplay { events, latest_cursor, truncated: cursor < self.dropped_through, has_more }
+    }
+
+    /// 窗口确认已处理到 `cursor`；所有活跃窗口都确认过的事件随即清理，返回清理条数
+    pub fn acknowledge(&mut self, client: &str, cursor: u64, now: DateTime<Utc>) -> usize {
+        let ack = self.clients.entry(client.to_string()).or_insert(ClientAck { cursor: 0, seen_at: now });
+        ack.cursor = ack.cursor.max(cursor.min(self.last_cursor));
+        ack.seen_at = now;
+        self.prune(now)
+    }
+
+    fn touch(&mut self, client: &str, now: DateTime<Utc>) {
+        self.clients
+            .entry(client.to_string())
+            .and_modify(|ack| ack.seen_at = now)
+            .or_insert(ClientAck { cursor: 0, seen_at: now });
+    }
+
+    /// 清理已确认、过期及超出容量的事件，返回清理条数
+    fn prune(&mut self, now: DateTime<Utc>) -> usize {
+        let before = self.entries.len();
+        self.clients.retain(|_, ack| now - ack.seen_at <= Duration::minutes(CLIENT_IDLE_MINUTES));
+        let acked_through = self.clients.values().map(|ack| ack.cursor).min().unwrap_or(0);
+        self.entries.retain(|e| e.cursor > acked_through);
+
+        let mut dropped_through = self.dropped_through;
+        let expire_before = now - Duration::hours(MAX_AGE_HOURS);
+        let mut kept_per_correlation: BTreeMap<String, usize> = BTreeMap::new();
+        let mut keep = vec![true; self.entries.len()];
+        for (index, entry) in self.entries.iter().enumerate().rev() {
+            let kept = kept_per_correlation.entry(entry.correlation_id.clone()).or_default();
+            if entry.emitted_at < expire_before || *kept >= MAX_PER_CORRELATION {
+                keep[index] = false;
+                dropped_through = dropped_through.max(entry.cursor);
+            } else {
+                *kept += 1;
+            }
+        }
+        let mut flags = keep.into_iter();
+        self.entries.retain(|_| flags.next().unwrap_or(true));
+        while self.entries.len() > MAX_TOTAL {
+            if let Some(entry) = self.entries.pop_front() {
+                dropped_through = dropped_through.max(entry.cursor);
+            }
+        }
+        self.dropped_through = dropped_through;
+
+        let removed = before - self.entries.len();
+        if removed > 0 || self.appended_lines > self.entries.len().max(1) * COMPACT_FACTOR {
+            self.rewrite();
+        }
+        removed
+    }
+
+    fn append_line(&mut self, entry: &OutboxEntry) {
+        let Some(path) = &self.path else {
+            return;
+        };
+        let result = (|| -> std::io::Result<()> {
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            let line = serde_json::to_string(entry).map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+            let mut file = std::fs::OpenOptions::new().create(true).append(true).open(path)?;
+            writeln!(file, "{}", line)
+        })();
+        match result {
+            Ok(()) => self.appended_lines += 1,
+            Err(e) => warn!("⚠️ 写入事件发件箱失败: {}", e),
+        }
+    }
+
+    fn rewrite(&mut self) {
+        let Some(path) = &self.path else {
+            return;
+        };
+        let content: String = self
+            .entries
+            .iter()
+            .filter_map(|e| serde_json::to_string(e).ok())
+            .map(|line| line + "\n")
+            .collect();
+        match std::fs::write(path, content) {
+            Ok(()) => self.appended_lines = self.entries.len(),
+            Err(e) => warn!("⚠️ 重写事件发件箱失败: {}", e),
+        }
+    }
+}
+
+/// 任务状态事件：写入发件箱后照常推送原事件，并在 [`OUTBOX_EVENT`] 通道推送带游标的副本
+pub fn emit<R: Runtime, T: Serialize>(app: &AppHandle<R>, event: &str, correlation_id: &str, payload: &T) {
+    let payload = match serde_json::to_value(payload) {
+        Ok(value) => value,
+        Err(e) => {
+            warn!("⚠️ 事件 {} 序列化失败: {}", event, e);
+            return;
+        }
+    };
+    let entry = match EventOutbox::global().lock() {
+        Ok(mut outbox) => Some(outbox.append(event, correlation_id, payload.clone(), Utc::now())),
+        Err(e) => {
+            warn!("⚠️ 事件发件箱不可用: {}", e);
+            None
+        }
+    };
+    if let Err(e) = app.emit(event, &payload) {
+        warn!("⚠️ 发送事件 {} 失败: {}", event, e);
+    }
+    if let Some(entry) = entry {
+        let _ = app.emit(OUTBOX_EVENT, &entry);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn replays_since_cursor_and_prunes_after_all_windows_ack() {
+        let mut outbox = EventOutbox::in_memory();
+        let t0 = Utc::now();
+        let first = outbox.append("recipe-run", "run-1", json!({ "status": "running" }), t0);
+        let second = outbox.append("recipe-run", "run-1", json!({ "status": "done" }), t0);
+        let other = outbox.append("auto-import", "source-a", json!({ "inserted": 3 }), t0);
+        assert!(second.cursor > first.cursor && other.cursor > second.cursor);
+
+        // 两个窗口重连补发
+        let replay = outbox.replay_since("main", first.cursor, None, 10, t0);
+        assert_eq!(replay.events, vec![second.clone(), other.clone()]);
+        assert_eq!(replay.latest_cursor, other.cursor);
+        assert!(!replay.truncated && !replay.has_more);
+        let only_run = outbox.replay_since("device-a", 0, Some(&["run-1".to_string()]), 1, t0);
+        assert_eq!(only_run.events, vec![first.clone()]);
+        assert!(only_run.has_more);
+        assert_eq!(only_run.latest_cursor, first.cursor);
+
+        // 只有一个窗口确认时不清理
+        assert_eq!(outbox.acknowledge("main", other.cursor, t0), 0);
+        assert_eq!(outbox.acknowledge("device-a", second.cursor, t0), 2);
+        assert_eq!(outbox.entries.len(), 1);
+
+        // 闲置窗口不再阻止清理
+        let later = t0 + Duration::minutes(CLIENT_IDLE_MINUTES + 1);
+        assert_eq!(outbox.acknowledge("main", other.cursor, later), 1);
+        assert_eq!(outbox.entries.len(), 0);
+    }
+
+    #[test]
+    fn caps_events_per_correlation_and_flags_truncation() {
+        let mut outbox = EventOutbox::in_memory();
+        let t0 = Utc::now();
+        let first = outbox.append("wechat", "dev-a", json!(0), t0);
+        for i in 1..=MAX_PER_CORRELATION {
+            outbox.append("wechat", "dev-a", json!(i), t0);
+        }
+        assert_eq!(outbox.entries.len(), MAX_PER_CORRELATION);
+        let replay = outbox.replay_since("main", 0, None, MAX_TOTAL, t0);
+        assert!(replay.truncated);
+        assert!(replay.events.iter().all(|e| e.cursor > first.cursor));
+        assert!(!outbox.replay_since("main", first.cursor, None, 10, t0).truncated);
+
+        // 过期事件被清理
+        let expired = t0 + Duration::hours(MAX_AGE_HOURS + 1);
+        outbox.acknowledge("main", 0, expired);
+        assert_eq!(outbox.entries.len(), 0);
+    }
+}
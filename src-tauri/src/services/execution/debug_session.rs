@@ -0,0 +1,311 @@
+//! debug_session.rs - 脚本调试执行（断点 / 单步）
+//!
+//! 调试运行在每个步骤执行前调用 `before_step`：命中断点或处于单步模式时暂停，等待前端
+//! `debug_continue` / `debug_step_over` / `debug_abort`。暂停期间 `inspect` 返回当前变量、
+//! 最近一次 dump 的文件句柄以及上一步的匹配决策。会话在运行结束时注销。
+
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, OnceLock};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tokio::sync::Notify;
+use tracing::warn;
+
+use crate::automation::matching::decision_journal::{last_decision, MatchDecision};
+use crate::services::execution::model::SmartScriptStep;
+use crate::services::execution::UiBridge;
+
+static SESSIONS: OnceLock<Mutex<HashMap<String, Arc<DebugSession>>>> = OnceLock::new();
+
+fn sessions() -> &'static Mutex<HashMap<String, Arc<DebugSession>>> {
+    SESSIONS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// 按运行 ID 查找调试会话
+pub fn get_session(run_id: &str) -> Result<Arc<DebugSession>, String> {
+    sessions()
+        .lock()
+        .map_err(|e| format!("锁定调试会话失败: {}", e))?
+        .get(run_id)
+        .cloned()
+        .ok_or_else(|| format!("未找到调试会话: {}（运行可能已结束）", run_id))
+}
+
+/// 调试选项
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DebugOptions {
+    /// 断点步骤 ID，执行到该步骤前暂停
+    #[serde(default)]
+    pub breakpoints: Vec<String>,
+    /// 每个步骤执行前都暂停
+    #[serde(default)]
+    pub pause_every_step: bool,
+}
+
+/// 暂停时前端下发的指令
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DebugCommand {
+    /// 运行到下一个断点
+    Continue,
+    /// 执行当前步骤后再次暂停
+    StepOver,
+    /// 停止运行，不再执行后续步骤
+    Abort,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DebugStatus {
+    Running,
+    Paused,
+    Aborting,
+}
+
+/// 暂停位置：即将执行的步骤
+#[derive(Debug, Clone, Serialize)]
+pub struct PausedPosition {
+    pub step_index: usize,
+    pub step_id: String,
+    pub step_name: String,
+    /// `breakpoint` / `step`
+    pub reason: &'static str,
+}
+
+/// 最近一次 dump：暂停时落盘，`path` 可直接交给前端读取
+#[derive(Debug, Clone, Serialize)]
+pub struct DumpHandle {
+    pub path: String,
+    pub length: usize,
+    /// 暂停时距该 dump 的毫秒数
+    pub age_ms: u64,
+}
+
+/// 暂停位置的检查结果
+#[derive(Debug, Clone, Serialize)]
+pub struct DebugInspection {
+    pub run_id: String,
+    pub device_id: String,
+    pub status: DebugStatus,
+    pub paused_at: Option<PausedPosition>,
+    pub variables: HashMap<String, Value>,
+    pub last_dump: Option<DumpHandle>,
+    /// 上一个已执行步骤及其匹配决策
+    pub last_step_id: Option<String>,
+    pub match_details: Option<MatchDecision>,
+}
+
+struct DebugState {
+    status: DebugStatus,
+    paused_at: Option<PausedPosition>,
+    pending: Option<DebugCommand>,
+    stepping: bool,
+    variables: HashMap<String, Value>,
+    last_step_id: Option<String>,
+    last_dump: Option<DumpHandle>,
+}
+
+type PauseListener = Box<dyn Fn(&DebugInspection) + Send + Sync>;
+
+/// 一次调试运行
+pub struct DebugSession {
+    run_id: String,
+    device_id: String,
+    breakpoints: HashSet<String>,
+    pause_every_step: bool,
+    state: Mutex<DebugState>,
+    resume: Notify,
+    on_pause: Option<PauseListener>,
+}
+
+impl DebugSession {
+    /// 创建并注册会话；`on_pause` 在每次暂停时收到检查结果
+    pub fn start(device_id: &str, options: DebugOptions, on_pause: Option<PauseListener>) -> Arc<Self> {
+        let session = Arc::new(Self {
+            run_id: uuid::Uuid::new_v4().to_string(),
+            device_id: device_id.to_string(),
+            breakpoints: options.breakpoints.into_iter().collect(),
+            pause_every_step: options.pause_every_step,
+            state: Mutex::new(DebugState {
+                status: DebugStatus::Running,
+                paused_at: None,
+                pending: None,
+                stepping: options.pause_every_step,
+                variables: HashMap::new(),
+                last_step_id: None,
+                last_dump: None,
+            }),
+            resume: Notify::new(),
+            on_pause,
+        });
+        if let Ok(mut all) = sessions().lock() {
+            all.insert(session.run_id.clone(), Arc::clone(&session));
+        }
+        session
+    }
+
+    pub fn run_id(&self) -> &str {
+        &self.run_id
+    }
+
+    /// 运行结束后注销
+    pub fn finish(&self) {
+        if let Ok(mut all) = sessions().lock() {
+            all.remove(&self.run_id);
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, DebugState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// 步骤执行前调用；需要暂停时一直等到收到指令
+    pub async fn before_step(
+        &self,
+        index: usize,
+        step: &SmartScriptStep,
+        variables: HashMap<String, Value>,
+    ) -> DebugCommand {
+        {
+            let mut state = self.lock();
+            state.variables = variables;
+            if state.pending == Some(DebugCommand::Abort) {
+                return DebugCommand::Abort;
+            }
+            let reason = if self.breakpoints.contains(&step.id) {
+                "breakpoint"
+            } else if state.stepping {
+                "step"
+            } else {
+                return DebugCommand::Continue;
+            };
+            state.status = DebugStatus::Paused;
+            state.paused_at = Some(PausedPosition {
+                step_index: index,
+                step_id: step.id.clone(),
+                step_name: step.name.clone(),
+                reason,
+            });
+            state.last_dump = self.save_last_dump();
+        }
+        if let Some(listener) = &self.on_pause {
+            listener(&self.inspect());
+        }
+
+        loop {
+            let notified = self.resume.notified();
+            {
+                let mut state = self.lock();
+                if let Some(command) = state.pending {
+                    match command {
+                        DebugCommand::Continue => state.stepping = self.pause_every_step,
+                        DebugCommand::StepOver => state.stepping = true,
+                        // 保留 Abort，之后的 before_step 也直接返回
+                        DebugCommand::Abort => {}
+                    }
+                    if command != DebugCommand::Abort {
+                        state.pending = None;
+                        state.status = DebugStatus::Running;
+                    }
+                    state.paused_at = None;
+                    return command;
+                }
+            }
+            notified.await;
+        }
+    }
+
+    /// 步骤执行后调用，记录用于查询匹配详情的步骤 ID
+    pub fn after_step(&self, step_id: &str) {
+        self.lock().last_step_id = Some(step_id.to_string());
+    }
+
+    /// 下发指令；继续 / 单步只在暂停时有效，中止随时有效
+    pub fn command(&self, command: DebugCommand) -> Result<(), String> {
+        let mut state = self.lock();
+        if command == DebugCommand::Abort {
+            state.status = DebugStatus::Aborting;
+        } else if state.status != DebugStatus::Paused {
+            return Err(format!("调试会话 {} 当前未暂停", self.run_id));
+        }
+        state.pending = Some(command);
+        drop(state);
+        self.resume.notify_one();
+        Ok(())
+    }
+
+    pub fn inspect(&self) -> DebugInspection {
+        let state = self.lock();
+        DebugInspection {
+            run_id: self.run_id.clone(),
+            device_id: self.device_id.clone(),
+            status: state.status,
+            paused_at: state.paused_at.clone(),
+            variables: state.variables.clone(),
+            last_dump: state.last_dump.clone(),
+            last_step_id: state.last_step_id.clone(),
+            match_details: state.last_step_id.as_deref().and_then(last_decision),
+        }
+    }
+
+    fn save_last_dump(&self) -> Option<DumpHandle> {
+        let (xml, age_ms) = UiBridge::last_cached_dump()?;
+        let dir = dirs::data_dir().unwrap_or_else(std::env::temp_dir).join("employee-gui").join("debug_dumps");
+        let path: PathBuf = dir.join(format!("{}.xml", self.run_id));
+        if let Err(e) = std::fs::create_dir_all(&dir).and_then(|_| std::fs::write(&path, &xml)) {
+            warn!("⚠️ 调试 dump 落盘失败: {}", e);
+            return None;
+        }
+        Some(DumpHandle { path: path.to_string_lossy().to_string(), length: xml.len(), age_ms })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::execution::model::SmartActionType;
+
+    fn step(id: &str) -> SmartScriptStep {
+        SmartScriptStep {
+            id: id.to_string(),
+            step_type: SmartActionType::Tap,
+            name: id.to_string(),
+            description: String::new(),
+            parameters: Value::Null,
+            enabled: true,
+            order: 0,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_breakpoint_step_over_and_abort() {
+        let options = DebugOptions { breakpoints: vec!["b".to_string()], pause_every_step: false };
+        let session = DebugSession::start("device", options, None);
+        assert!(get_session(session.run_id()).is_ok());
+
+        // 未命中断点直接继续；运行中不能单步
+        assert_eq!(session.before_step(0, &step("a"), HashMap::new()).await, DebugCommand::Continue);
+        assert!(session.command(DebugCommand::StepOver).is_err());
+
+        let paused = {
+            let session = Arc::clone(&session);
+            tokio::spawn(async move { session.before_step(1, &step("b"), HashMap::new()).await })
+        };
+        while session.inspect().status != DebugStatus::Paused {
+            tokio::task::yield_now().await;
+        }
+        assert_eq!(session.inspect().paused_at.unwrap().reason, "breakpoint");
+        session.command(DebugCommand::StepOver).unwrap();
+        assert_eq!(paused.await.unwrap(), DebugCommand::StepOver);
+
+        // 单步模式下下一步也暂停，中止后后续步骤都直接返回 Abort
+        session.command(DebugCommand::Abort).unwrap();
+        assert_eq!(session.before_step(2, &step("c"), HashMap::new()).await, DebugCommand::Abort);
+        assert_eq!(session.before_step(3, &step("d"), HashMap::new()).await, DebugCommand::Abort);
+
+        session.finish();
+        assert!(get_session(session.run_id()).is_err());
+    }
+}
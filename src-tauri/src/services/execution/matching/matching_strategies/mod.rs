@@ -36,6 +36,35 @@ pub use matching_anchor_relation::AnchorByRelationStrategyProcessor; // 🎯 关
 use std::collections::HashMap;
 use serde_json::Value;
 
+/// 工厂中显式注册的策略名；其余名称会被回退到 enhanced（脚本预校验据此标记未注册策略）
+pub const REGISTERED_STRATEGIES: &[&str] = &[
+    "anchor_by_child_text",
+    "anchor_by_sibling_text",
+    "anchor_by_parent_text",
+    "anchor_by_child_or_parent_text",
+    "anchor_by_relation",
+    "xpath-direct",
+    "xpath-first-index",
+    "xpath-all-elements",
+    "enhanced",
+    "standard",
+    "absolute",
+    "custom",
+    "hidden-element-parent",
+    "strict",
+    "relaxed",
+    "positionless",
+    "intelligent",
+    "a11y",
+    "bounds_near",
+    "xpath_fuzzy",
+];
+
+/// 策略名是否在工厂中注册
+pub fn is_registered_strategy(strategy: &str) -> bool {
+    REGISTERED_STRATEGIES.contains(&strategy)
+}
+
 /// 策略工厂 - 根据策略名称创建对应的处理器
 pub fn create_strategy_processor(strategy: &str) -> Box<dyn StrategyProcessor + Send + Sync> {
     match strategy {
@@ -6,6 +6,7 @@ pub mod matching_strategies;
 mod unified;
 mod enhanced_element_matcher; // 🆕 增强型元素匹配器
 pub mod smart_xpath_generator; // 🆕 智能 XPath 生成器
+pub mod xpath_eval; // 🆕 XPath 求值器（函数 / 轴 / 位置谓词）
 mod candidate_evaluator; // 🆕 多候选元素评估器
 
 pub use legacy_regex::{
@@ -29,6 +30,7 @@ pub use smart_xpath_generator::{
     XPathCandidate,
     ElementAttributes,
 };
+pub use xpath_eval::{XPathExpr, XPathMatchedNode, XPathValidation};
 
 // 导出多候选评估器
 pub use candidate_evaluator::{
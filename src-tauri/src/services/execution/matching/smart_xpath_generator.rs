@@ -6,6 +6,8 @@
 use std::collections::HashMap;
 use tracing::debug;
 
+use super::xpath_eval::{self, xpath_literal, XPathExpr, XPathValidation};
+
 /// XPath 生成策略
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum XPathStrategy {
@@ -35,6 +37,8 @@ pub struct XPathCandidate {
 }
 
 /// 元素属性映射
+///
+/// 除 dump 属性外还可携带上下文键：`parent-resource-id`（父节点）、`ancestor-resource-id`（任意祖先容器），用于生成轴向候选
 pub type ElementAttributes = HashMap<String, String>;
 
 /// 智能 XPath 生成器
@@ -101,6 +105,9 @@ impl SmartXPathGenerator {
         // 5. Composite 策略（组合多属性）
         candidates.extend(self.generate_composite_candidates(attributes));
 
+        // 5.5 RelativePosition 策略（同级位置 / 祖先容器轴）
+        candidates.extend(self.generate_relative_position_candidates(attributes));
+
         // 6. Fallback 策略（基于 bounds）
         if let Some(bounds) = attributes.get("bounds") {
             candidates.extend(self.generate_fallback_candidates(bounds, attributes));
@@ -125,20 +132,33 @@ impl SmartXPathGenerator {
     fn generate_resource_id_candidates(&self, resource_id: &str) -> Vec<XPathCandidate> {
         let base_confidence = self.strategy_success_rates.get(&XPathStrategy::ResourceId).copied().unwrap_or(0.9);
         
-        vec![
+        let mut candidates = vec![
             XPathCandidate {
-                xpath: format!("//*[@resource-id='{}']", resource_id),
+                xpath: format!("//*[@resource-id={}]", xpath_literal(resource_id)),
                 strategy: XPathStrategy::ResourceId,
                 confidence: base_confidence * 0.7, // 降低单纯 resource-id 的置信度
                 description: format!("基于 resource-id 的精确匹配: {}", resource_id),
             },
             XPathCandidate {
-                xpath: format!("(//*[@resource-id='{}'])[1]", resource_id),
+                xpath: format!("(//*[@resource-id={}])[1]", xpath_literal(resource_id)),
                 strategy: XPathStrategy::ResourceId,
                 confidence: base_confidence * 0.65,
                 description: format!("基于 resource-id 的首个元素匹配: {}", resource_id),
             },
-        ]
+        ];
+
+        // 只匹配 `:id/` 之后的部分，应用分身 / 换包名后仍然有效
+        if let Some(pos) = resource_id.find(":id/") {
+            let local_id = &resource_id[pos..];
+            candidates.push(XPathCandidate {
+                xpath: format!("//*[contains(@resource-id, {})]", xpath_literal(local_id)),
+                strategy: XPathStrategy::ResourceId,
+                confidence: base_confidence * 0.6,
+                description: format!("基于 resource-id 后缀的包名无关匹配: {}", local_id),
+            });
+        }
+
+        candidates
     }
 
     /// 基于 content-desc 生成候选项
@@ -147,7 +167,7 @@ impl SmartXPathGenerator {
         
         let mut candidates = vec![
             XPathCandidate {
-                xpath: format!("//*[@content-desc='{}']", content_desc),
+                xpath: format!("//*[@content-desc={}]", xpath_literal(content_desc)),
                 strategy: XPathStrategy::ContentDesc,
                 confidence: base_confidence,
                 description: format!("基于 content-desc 的精确匹配: {}", content_desc),
@@ -157,7 +177,7 @@ impl SmartXPathGenerator {
         // 如果 content-desc 包含部分匹配的可能性
         if content_desc.len() > 3 {
             candidates.push(XPathCandidate {
-                xpath: format!("//*[contains(@content-desc, '{}')]", content_desc),
+                xpath: format!("//*[contains(@content-desc, {})]", xpath_literal(content_desc)),
                 strategy: XPathStrategy::ContentDesc,
                 confidence: base_confidence * 0.8,
                 description: format!("基于 content-desc 的部分匹配: {}", content_desc),
@@ -173,23 +193,35 @@ impl SmartXPathGenerator {
         
         let mut candidates = vec![
             XPathCandidate {
-                xpath: format!("//*[@text='{}']", text),
+                xpath: format!("//*[@text={}]", xpath_literal(text)),
                 strategy: XPathStrategy::Text,
                 confidence: base_confidence,
                 description: format!("基于 text 属性的精确匹配: {}", text),
             },
             XPathCandidate {
-                xpath: format!("//*[normalize-space(text())='{}']", text.trim()),
+                xpath: format!("//*[normalize-space(@text)={}]", xpath_literal(text.trim())),
                 strategy: XPathStrategy::Text,
                 confidence: base_confidence * 0.95,
                 description: format!("基于标准化文本内容的匹配: {}", text.trim()),
             },
         ];
 
+        // 前缀匹配（长文本尾部常带计数 / 时间等易变内容）
+        let normalized: String = text.split_whitespace().collect::<Vec<_>>().join(" ");
+        if normalized.chars().count() > 8 {
+            let prefix: String = normalized.chars().take(6).collect();
+            candidates.push(XPathCandidate {
+                xpath: format!("//*[starts-with(normalize-space(@text), {})]", xpath_literal(&prefix)),
+                strategy: XPathStrategy::Text,
+                confidence: base_confidence * 0.75,
+                description: format!("基于标准化文本前缀的匹配: {}", prefix),
+            });
+        }
+
         // 部分匹配（针对长文本）
         if text.len() > 5 {
             candidates.push(XPathCandidate {
-                xpath: format!("//*[contains(text(), '{}')]", text),
+                xpath: format!("//*[contains(@text, {})]", xpath_literal(text)),
                 strategy: XPathStrategy::Text,
                 confidence: base_confidence * 0.7,
                 description: format!("基于文本的部分匹配: {}", text),
@@ -205,7 +237,7 @@ impl SmartXPathGenerator {
         
         let mut candidates = vec![
             XPathCandidate {
-                xpath: format!("//*[@class='{}']", class_name),
+                xpath: format!("//*[@class={}]", xpath_literal(class_name)),
                 strategy: XPathStrategy::ClassHierarchy,
                 confidence: base_confidence * 0.6, // 类名匹配通常不够精确
                 description: format!("基于类名的匹配: {}", class_name),
@@ -216,7 +248,7 @@ impl SmartXPathGenerator {
         if let Some(index) = attributes.get("index") {
             if let Ok(idx) = index.parse::<usize>() {
                 candidates.push(XPathCandidate {
-                    xpath: format!("(//*[@class='{}'])[{}]", class_name, idx + 1),
+                    xpath: format!("(//*[@class={}])[{}]", xpath_literal(class_name), idx + 1),
                     strategy: XPathStrategy::ClassHierarchy,
                     confidence: base_confidence * 0.8,
                     description: format!("基于类名和索引的匹配: {} [{}]", class_name, idx + 1),
@@ -240,7 +272,7 @@ impl SmartXPathGenerator {
         if let (Some(resource_id), Some(text)) = (attributes.get("resource-id"), attributes.get("text")) {
             if !resource_id.is_empty() && !text.is_empty() {
                 candidates.push(XPathCandidate {
-                    xpath: format!("//*[@resource-id='{}'][.//*[@text='{}']]", resource_id, text),
+                    xpath: format!("//*[@resource-id={}][.//*[@text={}]]", xpath_literal(resource_id), xpath_literal(text)),
                     strategy: XPathStrategy::Composite,
                     confidence: base_confidence * 1.1, // 高于基准置信度
                     description: format!("组合匹配(高优先级): resource-id='{}' + 子元素text='{}'", resource_id, text),
@@ -252,7 +284,7 @@ impl SmartXPathGenerator {
         if let (Some(resource_id), Some(content_desc)) = (attributes.get("resource-id"), attributes.get("content-desc")) {
             if !resource_id.is_empty() && !content_desc.is_empty() {
                 candidates.push(XPathCandidate {
-                    xpath: format!("//*[@resource-id='{}'][.//*[@content-desc='{}']]", resource_id, content_desc),
+                    xpath: format!("//*[@resource-id={}][.//*[@content-desc={}]]", xpath_literal(resource_id), xpath_literal(content_desc)),
                     strategy: XPathStrategy::Composite,
                     confidence: base_confidence * 1.05,
                     description: format!("组合匹配(高优先级): resource-id='{}' + 子元素content-desc='{}'", resource_id, content_desc),
@@ -264,7 +296,7 @@ impl SmartXPathGenerator {
         if let (Some(resource_id), Some(class_name)) = (attributes.get("resource-id"), attributes.get("class")) {
             if !resource_id.is_empty() && !class_name.is_empty() {
                 candidates.push(XPathCandidate {
-                    xpath: format!("//*[@resource-id='{}' and @class='{}']", resource_id, class_name),
+                    xpath: format!("//*[@resource-id={} and @class={}]", xpath_literal(resource_id), xpath_literal(class_name)),
                     strategy: XPathStrategy::Composite,
                     confidence: base_confidence,
                     description: format!("组合匹配: resource-id='{}' + class='{}'", resource_id, class_name),
@@ -276,7 +308,7 @@ impl SmartXPathGenerator {
         if let (Some(text), Some(class_name)) = (attributes.get("text"), attributes.get("class")) {
             if !text.is_empty() && !class_name.is_empty() {
                 candidates.push(XPathCandidate {
-                    xpath: format!("//*[@text='{}' and @class='{}']", text, class_name),
+                    xpath: format!("//*[@text={} and @class={}]", xpath_literal(text), xpath_literal(class_name)),
                     strategy: XPathStrategy::Composite,
                     confidence: base_confidence * 0.9,
                     description: format!("组合匹配: text='{}' + class='{}'", text, class_name),
@@ -288,7 +320,7 @@ impl SmartXPathGenerator {
         if let (Some(content_desc), Some(class_name)) = (attributes.get("content-desc"), attributes.get("class")) {
             if !content_desc.is_empty() && !class_name.is_empty() {
                 candidates.push(XPathCandidate {
-                    xpath: format!("//*[@content-desc='{}' and @class='{}']", content_desc, class_name),
+                    xpath: format!("//*[@content-desc={} and @class={}]", xpath_literal(content_desc), xpath_literal(class_name)),
                     strategy: XPathStrategy::Composite,
                     confidence: base_confidence * 0.95,
                     description: format!("组合匹配: content-desc='{}' + class='{}'", content_desc, class_name),
@@ -299,6 +331,56 @@ impl SmartXPathGenerator {
         candidates
     }
 
+    /// 基于同级位置与祖先容器生成候选项
+    ///
+    /// - 同级位置：`//*[n][@class='X']`，n 来自 dump 的 index（位置谓词写在前面，按父节点内的位置计数）
+    /// - 祖先轴：`//*[...][ancestor::*[@resource-id='容器']]`
+    /// - 后代轴：`//*[@resource-id='父节点']/descendant::*[...]`
+    fn generate_relative_position_candidates(&self, attributes: &ElementAttributes) -> Vec<XPathCandidate> {
+        let base_confidence = self.strategy_success_rates.get(&XPathStrategy::RelativePosition).copied().unwrap_or(0.7);
+        let non_empty = |key: &str| attributes.get(key).map(|v| v.as_str()).filter(|v| !v.is_empty());
+        let mut candidates = Vec::new();
+
+        // 元素自身的判定条件（优先 resource-id，其次 text / content-desc）
+        let self_predicate = non_empty("resource-id")
+            .map(|rid| format!("@resource-id={}", xpath_literal(rid)))
+            .or_else(|| non_empty("text").map(|t| format!("normalize-space(@text)={}", xpath_literal(t.trim()))))
+            .or_else(|| non_empty("content-desc").map(|d| format!("@content-desc={}", xpath_literal(d))));
+
+        if let (Some(class_name), Some(index)) = (non_empty("class"), non_empty("index").and_then(|i| i.parse::<usize>().ok())) {
+            let xpath = match non_empty("parent-resource-id") {
+                Some(parent) => format!("//*[@resource-id={}]/*[{}][@class={}]", xpath_literal(parent), index + 1, xpath_literal(class_name)),
+                None => format!("//*[{}][@class={}]", index + 1, xpath_literal(class_name)),
+            };
+            candidates.push(XPathCandidate {
+                xpath,
+                strategy: XPathStrategy::RelativePosition,
+                confidence: base_confidence * if non_empty("parent-resource-id").is_some() { 0.9 } else { 0.6 },
+                description: format!("基于同级位置的匹配: {} 第 {} 个子元素", class_name, index + 1),
+            });
+        }
+
+        if let (Some(ancestor), Some(predicate)) = (non_empty("ancestor-resource-id"), self_predicate.as_deref()) {
+            candidates.push(XPathCandidate {
+                xpath: format!("//*[{}][ancestor::*[@resource-id={}]]", predicate, xpath_literal(ancestor)),
+                strategy: XPathStrategy::RelativePosition,
+                confidence: base_confidence * 1.1,
+                description: format!("基于祖先容器的匹配: {} 内的 {}", ancestor, predicate),
+            });
+        }
+
+        if let (Some(parent), Some(predicate)) = (non_empty("parent-resource-id"), self_predicate.as_deref()) {
+            candidates.push(XPathCandidate {
+                xpath: format!("//*[@resource-id={}]/descendant::*[{}][1]", xpath_literal(parent), predicate),
+                strategy: XPathStrategy::RelativePosition,
+                confidence: base_confidence,
+                description: format!("基于父节点后代轴的匹配: {} 下首个 {}", parent, predicate),
+            });
+        }
+
+        candidates
+    }
+
     /// 基于 bounds 的 fallback 候选项
     fn generate_fallback_candidates(&self, bounds: &str, attributes: &ElementAttributes) -> Vec<XPathCandidate> {
         let base_confidence = self.strategy_success_rates.get(&XPathStrategy::Fallback).copied().unwrap_or(0.6);
@@ -307,7 +389,7 @@ impl SmartXPathGenerator {
         // 直接基于 bounds 的匹配（最后的手段）
         if !bounds.is_empty() && bounds != "[0,0][0,0]" {
             candidates.push(XPathCandidate {
-                xpath: format!("//*[@bounds='{}']", bounds),
+                xpath: format!("//*[@bounds={}]", xpath_literal(bounds)),
                 strategy: XPathStrategy::Fallback,
                 confidence: base_confidence * 0.5, // 很低的置信度，因为 bounds 经常变化
                 description: format!("Fallback: 基于 bounds 的匹配: {}", bounds),
@@ -317,7 +399,7 @@ impl SmartXPathGenerator {
             if let Some(class_name) = attributes.get("class") {
                 if !class_name.is_empty() {
                     candidates.push(XPathCandidate {
-                        xpath: format!("//*[@class='{}' and @bounds='{}']", class_name, bounds),
+                        xpath: format!("//*[@class={} and @bounds={}]", xpath_literal(class_name), xpath_literal(bounds)),
                         strategy: XPathStrategy::Fallback,
                         confidence: base_confidence * 0.7,
                         description: format!("Fallback: class + bounds 组合匹配"),
@@ -346,7 +428,7 @@ impl SmartXPathGenerator {
         debug!("更新策略 {:?} 成功率: {:.3} -> {:.3}", &strategy, current_rate, new_rate);
     }
 
-    /// 验证 XPath 语法正确性
+    /// 验证 XPath 语法正确性（能被求值器完整解析，含函数 / 轴 / 位置谓词）
     pub fn validate_xpath(&self, xpath: &str) -> bool {
         // 基本的 XPath 语法检查
         if xpath.is_empty() {
@@ -358,7 +440,16 @@ impl SmartXPathGenerator {
         let has_balanced_brackets = self.check_balanced_brackets(xpath);
         let has_valid_chars = !xpath.contains('\n') && !xpath.contains('\r');
 
-        has_valid_start && has_balanced_brackets && has_valid_chars
+        has_valid_start && has_balanced_brackets && has_valid_chars && XPathExpr::parse(xpath).is_ok()
+    }
+
+    /// 在缓存的 XML 上求值 XPath：语法正确且至少命中一个节点才算有效
+    pub fn validate_xpath_against_xml(&self, xpath: &str, xml_content: &str) -> XPathValidation {
+        if !self.validate_xpath(xpath) {
+            let error = XPathExpr::parse(xpath).err().unwrap_or_else(|| "XPath 格式不合法".to_string());
+            return XPathValidation { valid: false, match_count: 0, matches: Vec::new(), error: Some(error) };
+        }
+        xpath_eval::evaluate_on_xml(xpath, xml_content)
     }
 
     /// 检查括号平衡
@@ -425,6 +516,58 @@ mod tests {
         assert!(!generator.validate_xpath("//*[@text='unclosed"));
     }
 
+    #[test]
+    fn test_rich_predicates_validate_against_xml() {
+        let xml = r#"<hierarchy><node index="0" class="android.widget.LinearLayout" resource-id="com.app:id/tabs" bounds="[0,0][600,100]">
+            <node index="0" class="android.widget.TextView" resource-id="com.app:id/tab_title" text="首页推荐内容列表页" bounds="[0,0][300,100]"/>
+            <node index="1" class="android.widget.TextView" resource-id="com.app:id/tab_title" text="消息" bounds="[300,0][600,100]"/>
+        </node></hierarchy>"#;
+        let generator = SmartXPathGenerator::new();
+        let mut attributes = HashMap::new();
+        attributes.insert("resource-id".to_string(), "com.clone.app:id/tab_title".to_string());
+        attributes.insert("class".to_string(), "android.widget.TextView".to_string());
+        attributes.insert("text".to_string(), " 首页推荐内容列表页 ".to_string());
+        attributes.insert("index".to_string(), "0".to_string());
+        attributes.insert("parent-resource-id".to_string(), "com.app:id/tabs".to_string());
+        attributes.insert("ancestor-resource-id".to_string(), "com.app:id/tabs".to_string());
+
+        let candidates = generator.generate_candidates(&attributes);
+        let find = |needle: &str| candidates.iter().find(|c| c.xpath.contains(needle)).unwrap().xpath.clone();
+
+        // 包名不同，仅靠 `:id/` 后缀命中两个标签
+        assert_eq!(generator.validate_xpath_against_xml(&find("contains(@resource-id"), xml).match_count, 2);
+        let prefix = generator.validate_xpath_against_xml(&find("starts-with("), xml);
+        assert_eq!((prefix.match_count, prefix.matches[0].text.as_str()), (1, "首页推荐内容列表页"));
+        let positional = generator.validate_xpath_against_xml(&find("/*[1][@class="), xml);
+        assert_eq!(positional.matches[0].index_path, vec![0, 0]);
+        assert!(candidates.iter().any(|c| c.strategy == XPathStrategy::RelativePosition && c.xpath.contains("ancestor::*")));
+
+        let report = generator.validate_xpath_against_xml("//*[@text='消息']/preceding-sibling::*[1]", xml);
+        assert_eq!(report.matches[0].text, "首页推荐内容列表页");
+        let broken = generator.validate_xpath_against_xml("//*[contains(@text]", xml);
+        assert!(!broken.valid && broken.error.is_some());
+    }
+
+    #[test]
+    fn test_quoted_values_are_escaped_in_every_candidate() {
+        let xml = r#"<hierarchy><node index="0" class="android.widget.Button" resource-id="com.app:id/ok" text="Don't stop" content-desc="it's &quot;ok&quot;" bounds="[0,0][300,100]"/></hierarchy>"#;
+        let generator = SmartXPathGenerator::new();
+        let mut attributes = HashMap::new();
+        attributes.insert("resource-id".to_string(), "com.app:id/ok".to_string());
+        attributes.insert("class".to_string(), "android.widget.Button".to_string());
+        attributes.insert("text".to_string(), "Don't stop".to_string());
+        attributes.insert("content-desc".to_string(), "it's \"ok\"".to_string());
+        attributes.insert("bounds".to_string(), "[0,0][300,100]".to_string());
+
+        let candidates = generator.generate_candidates(&attributes);
+        for candidate in &candidates {
+            let report = generator.validate_xpath_against_xml(&candidate.xpath, xml);
+            assert!(report.valid, "{} 无法解析: {:?}", candidate.xpath, report.error);
+            assert_eq!(report.match_count, 1, "{} 应命中唯一节点", candidate.xpath);
+        }
+        assert!(candidates.iter().any(|c| c.xpath.contains("normalize-space(@text)=")));
+    }
+
     #[test]
     fn test_composite_generation() {
         let generator = SmartXPathGenerator::new();
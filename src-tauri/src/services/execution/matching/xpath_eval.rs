@@ -0,0 +1,861 @@
+//! xpath_eval.rs - XPath 表达式解析与求值
+//!
+//! 模块: 执行引擎匹配系统 | 层级: 工具层 | 角色: XPath 求值器
+//! summary: 在 UiTree 上求值 XPath 子集（轴、位置谓词、contains/starts-with/normalize-space 等函数），供 SmartXPathGenerator 校验候选
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::engine::ui_tree::{Attr, Flag, NodeRef, UiTree};
+
+/// 解析后的 XPath
+#[derive(Debug, Clone)]
+pub struct XPathExpr {
+    root: Expr,
+}
+
+/// 命中节点摘要
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct XPathMatchedNode {
+    /// 文档顺序下标（与 element_N 一致）
+    pub node_index: usize,
+    pub index_path: Vec<usize>,
+    pub class: Option<String>,
+    pub resource_id: Option<String>,
+    pub text: String,
+    pub content_desc: String,
+    pub bounds: Option<String>,
+}
+
+/// XPath 在一份 XML 上的校验结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct XPathValidation {
+    /// 语法正确且至少命中一个节点
+    pub valid: bool,
+    pub match_count: usize,
+    /// 最多返回前 20 个命中
+    pub matches: Vec<XPathMatchedNode>,
+    pub error: Option<String>,
+}
+
+const MAX_REPORTED_MATCHES: usize = 20;
+
+impl XPathExpr {
+    pub fn parse(xpath: &str) -> Result<XPathExpr, String> {
+        let tokens = tokenize(xpath)?;
+        let mut parser = Parser { tokens, pos: 0 };
+        let root = parser.parse_expr()?;
+        if let Some(tok) = parser.peek() {
+            return Err(format!("XPath 多余的内容: {:?}", tok));
+        }
+        Ok(XPathExpr { root })
+    }
+
+    /// 求值并返回命中节点（文档顺序）；表达式结果不是节点集时报错
+    pub fn select<'a>(&self, tree: &'a UiTree) -> Result<Vec<NodeRef<'a>>, String> {
+        let eval = Evaluator { tree };
+        let ctx = Context { node: Ctx::Doc, pos: 1, size: 1 };
+        match eval.eval(&self.root, &ctx) {
+            Value::Nodes(nodes) => Ok(nodes
+                .into_iter()
+                .filter_map(|n| match n {
+                    Ctx::Node(id) => tree.node(id),
+                    _ => None,
+                })
+                .collect()),
+            _ => Err("XPath 结果不是节点集".to_string()),
+        }
+    }
+}
+
+/// 解析 XML 并求值，返回可直接交给前端的校验结果
+pub fn evaluate_on_xml(xpath: &str, xml: &str) -> XPathValidation {
+    let failed = |error: String| XPathValidation { valid: false, match_count: 0, matches: Vec::new(), error: Some(error) };
+    let expr = match XPathExpr::parse(xpath) {
+        Ok(expr) => expr,
+        Err(e) => return failed(e),
+    };
+    let tree = match UiTree::parse(xml) {
+        Ok(tree) => tree,
+        Err(e) => return failed(format!("XML 解析失败: {}", e)),
+    };
+    match expr.select(&tree) {
+        Ok(nodes) => XPathValidation {
+            valid: !nodes.is_empty(),
+            match_count: nodes.len(),
+            matches: nodes
+                .iter()
+                .take(MAX_REPORTED_MATCHES)
+                .map(|n| XPathMatchedNode {
+                    node_index: n.id(),
+                    index_path: n.index_path(),
+                    class: n.class_name().map(String::from),
+                    resource_id: n.resource_id().map(String::from),
+                    text: n.text().to_string(),
+                    content_desc: n.content_desc().to_string(),
+                    bounds: n.attr(Attr::Bounds).map(String::from),
+                })
+                .collect(),
+            error: None,
+        },
+        Err(e) => failed(e),
+    }
+}
+
+/// 把任意字符串写成 XPath 字面量（同时含单双引号时用 concat）
+pub fn xpath_literal(value: &str) -> String {
+    if !value.contains('\'') {
+        format!("'{}'", value)
+    } else if !value.contains('"') {
+        format!("\"{}\"", value)
+    } else {
+        let parts: Vec<String> = value.split('\'').map(|p| format!("'{}'", p)).collect();
+        format!("concat({})", parts.join(", \"'\", "))
+    }
+}
+
+// ==================== 词法 ====================
+
+#[derive(Debug, Clone, PartialEq)]
+enum Tok {
+    Slash,
+    DoubleSlash,
+    LParen,
+    RParen,
+    LBracket,
+    RBracket,
+    At,
+    Comma,
+    Pipe,
+    Dot,
+    DotDot,
+    ColonColon,
+    Star,
+    Minus,
+    Op(&'static str),
+    Literal(String),
+    Number(f64),
+    Name(String),
+}
+
+fn tokenize(input: &str) -> Result<Vec<Tok>, String> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+        let (tok, len) = match c {
+            c if c.is_whitespace() => {
+                i += 1;
+                continue;
+            }
+            '/' if next == Some('/') => (Tok::DoubleSlash, 2),
+            '/' => (Tok::Slash, 1),
+            '(' => (Tok::LParen, 1),
+            ')' => (Tok::RParen, 1),
+            '[' => (Tok::LBracket, 1),
+            ']' => (Tok::RBracket, 1),
+            '@' => (Tok::At, 1),
+            ',' => (Tok::Comma, 1),
+            '|' => (Tok::Pipe, 1),
+            '*' => (Tok::Star, 1),
+            '-' => (Tok::Minus, 1),
+            ':' if next == Some(':') => (Tok::ColonColon, 2),
+            '=' => (Tok::Op("="), 1),
+            '!' if next == Some('=') => (Tok::Op("!="), 2),
+            '<' if next == Some('=') => (Tok::Op("<="), 2),
+            '<' => (Tok::Op("<"), 1),
+            '>' if next == Some('=') => (Tok::Op(">="), 2),
+            '>' => (Tok::Op(">"), 1),
+            '.' if next == Some('.') => (Tok::DotDot, 2),
+            '.' if !next.is_some_and(|n| n.is_ascii_digit()) => (Tok::Dot, 1),
+            '\'' | '"' => {
+                let end = chars[i + 1..]
+                    .iter()
+                    .position(|&ch| ch == c)
+                    .ok_or_else(|| format!("XPath 字符串未闭合（位置 {}）", i))?;
+                (Tok::Literal(chars[i + 1..i + 1 + end].iter().collect()), end + 2)
+            }
+            c if c.is_ascii_digit() || c == '.' => {
+                let len = chars[i..].iter().take_while(|ch| ch.is_ascii_digit() || **ch == '.').count();
+                let raw: String = chars[i..i + len].iter().collect();
+                let num = raw.parse::<f64>().map_err(|_| format!("XPath 数字非法: {}", raw))?;
+                (Tok::Number(num), len)
+            }
+            c if c.is_alphabetic() || c == '_' => {
+                let len = chars[i..]
+                    .iter()
+                    .take_while(|ch| ch.is_alphanumeric() || matches!(ch, '_' | '-' | '.' | '$'))
+                    .count();
+                (Tok::Name(chars[i..i + len].iter().collect()), len)
+            }
+            other => return Err(format!("XPath 非法字符 '{}'（位置 {}）", other, i)),
+        };
+        tokens.push(tok);
+        i += len;
+    }
+    Ok(tokens)
+}
+
+// ==================== 语法 ====================
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Axis {
+    Child,
+    Descendant,
+    DescendantOrSelf,
+    Parent,
+    Ancestor,
+    AncestorOrSelf,
+    SelfAxis,
+    FollowingSibling,
+    PrecedingSibling,
+    Attribute,
+}
+
+impl Axis {
+    fn from_name(name: &str) -> Option<Axis> {
+        Some(match name {
+            "child" => Axis::Child,
+            "descendant" => Axis::Descendant,
+            "descendant-or-self" => Axis::DescendantOrSelf,
+            "parent" => Axis::Parent,
+            "ancestor" => Axis::Ancestor,
+            "ancestor-or-self" => Axis::AncestorOrSelf,
+            "self" => Axis::SelfAxis,
+            "following-sibling" => Axis::FollowingSibling,
+            "preceding-sibling" => Axis::PrecedingSibling,
+            "attribute" => Axis::Attribute,
+            _ => return None,
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum NodeTest {
+    /// `*`
+    Any,
+    /// `node()`
+    Node,
+    /// `text()`
+    Text,
+    /// 元素名：`node` / `hierarchy` / 完整类名 / 类名短名（Button）；属性轴上为属性名
+    Name(String),
+}
+
+#[derive(Debug, Clone)]
+struct Step {
+    axis: Axis,
+    test: NodeTest,
+    predicates: Vec<Expr>,
+}
+
+#[derive(Debug, Clone)]
+enum Expr {
+    Or(Box<Expr>, Box<Expr>),
+    And(Box<Expr>, Box<Expr>),
+    Cmp(&'static str, Box<Expr>, Box<Expr>),
+    Neg(Box<Expr>),
+    Union(Box<Expr>, Box<Expr>),
+    Literal(String),
+    Number(f64),
+    Call(String, Vec<Expr>),
+    Path { absolute: bool, steps: Vec<Step> },
+    /// `(expr)[pred]/rest`
+    Filter { primary: Box<Expr>, predicates: Vec<Expr>, rest: Vec<Step> },
+}
+
+struct Parser {
+    tokens: Vec<Tok>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Tok> {
+        self.tokens.get(self.pos)
+    }
+
+    fn peek_at(&self, offset: usize) -> Option<&Tok> {
+        self.tokens.get(self.pos + offset)
+    }
+
+    fn next(&mut self) -> Option<Tok> {
+        let tok = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        tok
+    }
+
+    fn expect(&mut self, tok: Tok) -> Result<(), String> {
+        match self.next() {
+            Some(t) if t == tok => Ok(()),
+            other => Err(format!("XPath 语法错误: 期望 {:?}，实际 {:?}", tok, other)),
+        }
+    }
+
+    fn is_keyword(&self, word: &str) -> bool {
+        matches!(self.peek(), Some(Tok::Name(n)) if n == word)
+    }
+
+    fn parse_expr(&mut self) -> Result<Expr, String> {
+        let mut left = self.parse_and()?;
+        while self.is_keyword("or") {
+            self.pos += 1;
+            left = Expr::Or(Box::new(left), Box::new(self.parse_and()?));
+        }
+        Ok(left)
+    }
+
+    fn parse_and(&mut self) -> Result<Expr, String> {
+        let mut left = self.parse_cmp()?;
+        while self.is_keyword("and") {
+            self.pos += 1;
+            left = Expr::And(Box::new(left), Box::new(self.parse_cmp()?));
+        }
+        Ok(left)
+    }
+
+    fn parse_cmp(&mut self) -> Result<Expr, String> {
+        let mut left = self.parse_unary()?;
+        while let Some(Tok::Op(op)) = self.peek() {
+            let op = *op;
+            self.pos += 1;
+            left = Expr::Cmp(op, Box::new(left), Box::new(self.parse_unary()?));
+        }
+        Ok(left)
+    }
+
+    fn parse_unary(&mut self) -> Result<Expr, String> {
+        if self.peek() == Some(&Tok::Minus) {
+            self.pos += 1;
+            return Ok(Expr::Neg(Box::new(self.parse_unary()?)));
+        }
+        let mut left = self.parse_path_expr()?;
+        while self.peek() == Some(&Tok::Pipe) {
+            self.pos += 1;
+            left = Expr::Union(Box::new(left), Box::new(self.parse_path_expr()?));
+        }
+        Ok(left)
+    }
+
+    fn starts_location_path(&self) -> bool {
+        match self.peek() {
+            Some(Tok::Slash | Tok::DoubleSlash | Tok::Dot | Tok::DotDot | Tok::At | Tok::Star) => true,
+            Some(Tok::Name(name)) => match self.peek_at(1) {
+                Some(Tok::LParen) => name == "node" || name == "text",
+                _ => true,
+            },
+            _ => false,
+        }
+    }
+
+    fn parse_path_expr(&mut self) -> Result<Expr, String> {
+        if self.starts_location_path() {
+            return self.parse_location_path();
+        }
+        let primary = match self.next() {
+            Some(Tok::Literal(s)) => Expr::Literal(s),
+            Some(Tok::Number(n)) => Expr::Number(n),
+            Some(Tok::LParen) => {
+                let inner = self.parse_expr()?;
+                self.expect(Tok::RParen)?;
+                inner
+            }
+            Some(Tok::Name(name)) => {
+                self.expect(Tok::LParen)?;
+                let mut args = Vec::new();
+                if self.peek() != Some(&Tok::RParen) {
+                    args.push(self.parse_expr()?);
+                    while self.peek() == Some(&Tok::Comma) {
+                        self.pos += 1;
+                        args.push(self.parse_expr()?);
+                    }
+                }
+                self.expect(Tok::RParen)?;
+                Expr::Call(name, args)
+            }
+            other => return Err(format!("XPath 语法错误: 意外的 {:?}", other)),
+        };
+
+        let predicates = self.parse_predicates()?;
+        let mut rest = Vec::new();
+        if matches!(self.peek(), Some(Tok::Slash | Tok::DoubleSlash)) {
+            self.parse_relative_steps(&mut rest, true)?;
+        }
+        if predicates.is_empty() && rest.is_empty() {
+            Ok(primary)
+        } else {
+            Ok(Expr::Filter { primary: Box::new(primary), predicates, rest })
+        }
+    }
+
+    fn parse_location_path(&mut self) -> Result<Expr, String> {
+        let mut steps = Vec::new();
+        let absolute = matches!(self.peek(), Some(Tok::Slash | Tok::DoubleSlash));
+        if self.peek() == Some(&Tok::Slash) {
+            self.pos += 1;
+            // 单独的 `/` 表示文档根
+            if !self.starts_location_path() || matches!(self.peek(), Some(Tok::Slash | Tok::DoubleSlash)) {
+                return Ok(Expr::Path { absolute, steps });
+            }
+            steps.push(self.parse_step()?);
+        } else if self.peek() != Some(&Tok::DoubleSlash) {
+            steps.push(self.parse_step()?);
+        }
+        self.parse_relative_steps(&mut steps, false)?;
+        Ok(Expr::Path { absolute, steps })
+    }
+
+    /// 解析 `/step` 与 `//step` 序列；`require_one` 时至少要有一个分隔符
+    fn parse_relative_steps(&mut self, steps: &mut Vec<Step>, require_one: bool) -> Result<(), String> {
+        let mut seen = false;
+        loop {
+            match self.peek() {
+                Some(Tok::Slash) => self.pos += 1,
+                Some(Tok::DoubleSlash) => {
+                    self.pos += 1;
+                    steps.push(Step { axis: Axis::DescendantOrSelf, test: NodeTest::Node, predicates: Vec::new() });
+                }
+                _ if require_one && !seen => return Err("XPath 语法错误: 缺少路径分隔符".to_string()),
+                _ => return Ok(()),
+            }
+            seen = true;
+            steps.push(self.parse_step()?);
+        }
+    }
+
+    fn parse_step(&mut self) -> Result<Step, String> {
+        let plain = |axis, test| Step { axis, test, predicates: Vec::new() };
+        match self.peek() {
+            Some(Tok::Dot) => {
+                self.pos += 1;
+                return Ok(plain(Axis::SelfAxis, NodeTest::Node));
+            }
+            Some(Tok::DotDot) => {
+                self.pos += 1;
+                return Ok(plain(Axis::Parent, NodeTest::Node));
+            }
+            _ => {}
+        }
+
+        let mut axis = Axis::Child;
+        if self.peek() == Some(&Tok::At) {
+            self.pos += 1;
+            axis = Axis::Attribute;
+        } else if let (Some(Tok::Name(name)), Some(Tok::ColonColon)) = (self.peek(), self.peek_at(1)) {
+            axis = Axis::from_name(name).ok_or_else(|| format!("XPath 不支持的轴: {}", name))?;
+            self.pos += 2;
+        }
+
+        let test = match self.next() {
+            Some(Tok::Star) => NodeTest::Any,
+            Some(Tok::Name(name)) if self.peek() == Some(&Tok::LParen) => {
+                self.pos += 1;
+                self.expect(Tok::RParen)?;
+                match name.as_str() {
+                    "node" => NodeTest::Node,
+                    "text" => NodeTest::Text,
+                    other => return Err(format!("XPath 不支持的节点测试: {}()", other)),
+                }
+            }
+            Some(Tok::Name(name)) => NodeTest::Name(name),
+            other => return Err(format!("XPath 语法错误: 期望节点测试，实际 {:?}", other)),
+        };
+        let predicates = self.parse_predicates()?;
+        Ok(Step { axis, test, predicates })
+    }
+
+    fn parse_predicates(&mut self) -> Result<Vec<Expr>, String> {
+        let mut predicates = Vec::new();
+        while self.peek() == Some(&Tok::LBracket) {
+            self.pos += 1;
+            predicates.push(self.parse_expr()?);
+            self.expect(Tok::RBracket)?;
+        }
+        Ok(predicates)
+    }
+}
+
+// ==================== 求值 ====================
+
+/// 求值时的节点：文档根、`<hierarchy>`、普通节点；派生顺序即文档顺序
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Ctx {
+    Doc,
+    Root,
+    Node(usize),
+}
+
+#[derive(Debug, Clone)]
+enum Value {
+    Nodes(Vec<Ctx>),
+    /// 属性值 / text() 结果
+    Strs(Vec<String>),
+    Str(String),
+    Num(f64),
+    Bool(bool),
+}
+
+struct Context {
+    node: Ctx,
+    pos: usize,
+    size: usize,
+}
+
+struct Evaluator<'a> {
+    tree: &'a UiTree,
+}
+
+impl<'a> Evaluator<'a> {
+    fn node_ref(&self, ctx: Ctx) -> Option<NodeRef<'a>> {
+        match ctx {
+            Ctx::Node(id) => self.tree.node(id),
+            _ => None,
+        }
+    }
+
+    fn children(&self, ctx: Ctx) -> Vec<Ctx> {
+        match ctx {
+            Ctx::Doc => vec![Ctx::Root],
+            Ctx::Root => self.tree.roots().map(|n| Ctx::Node(n.id())).collect(),
+            Ctx::Node(_) => self.node_ref(ctx).map(|n| n.children().map(|c| Ctx::Node(c.id())).collect()).unwrap_or_default(),
+        }
+    }
+
+    fn parent(&self, ctx: Ctx) -> Option<Ctx> {
+        match ctx {
+            Ctx::Doc => None,
+            Ctx::Root => Some(Ctx::Doc),
+            Ctx::Node(_) => Some(self.node_ref(ctx)?.parent().map(|p| Ctx::Node(p.id())).unwrap_or(Ctx::Root)),
+        }
+    }
+
+    fn descendants(&self, ctx: Ctx, out: &mut Vec<Ctx>) {
+        for child in self.children(ctx) {
+            out.push(child);
+            self.descendants(child, out);
+        }
+    }
+
+    /// 按轴方向排列：反向轴（ancestor / preceding-sibling）由近到远，位置谓词据此编号
+    fn axis_nodes(&self, ctx: Ctx, axis: Axis) -> Vec<Ctx> {
+        match axis {
+            Axis::Child => self.children(ctx),
+            Axis::Descendant | Axis::DescendantOrSelf => {
+                let mut out = if axis == Axis::DescendantOrSelf { vec![ctx] } else { Vec::new() };
+                self.descendants(ctx, &mut out);
+                out
+            }
+            Axis::Parent => self.parent(ctx).into_iter().collect(),
+            Axis::Ancestor | Axis::AncestorOrSelf => {
+                let mut out = if axis == Axis::AncestorOrSelf { vec![ctx] } else { Vec::new() };
+                let mut current = self.parent(ctx);
+                while let Some(node) = current {
+                    out.push(node);
+                    current = self.parent(node);
+                }
+                out
+            }
+            Axis::SelfAxis => vec![ctx],
+            Axis::FollowingSibling | Axis::PrecedingSibling => {
+                let siblings = self.parent(ctx).map(|p| self.children(p)).unwrap_or_default();
+                let at = siblings.iter().position(|s| *s == ctx).unwrap_or(0);
+                if axis == Axis::FollowingSibling {
+                    siblings[at + 1..].to_vec()
+                } else {
+                    siblings[..at].iter().rev().copied().collect()
+                }
+            }
+            Axis::Attribute => Vec::new(),
+        }
+    }
+
+    fn matches_test(&self, ctx: Ctx, test: &NodeTest) -> bool {
+        match (ctx, test) {
+            (_, NodeTest::Node) => true,
+            (Ctx::Doc, _) | (_, NodeTest::Text) => false,
+            (_, NodeTest::Any) => true,
+            (Ctx::Root, NodeTest::Name(name)) => name == "hierarchy",
+            (Ctx::Node(_), NodeTest::Name(name)) => {
+                name == "node"
+                    || self.node_ref(ctx).and_then(|n| n.class_name()).is_some_and(|class| {
+                        class == name || class.rsplit('.').next() == Some(name.as_str())
+                    })
+            }
+        }
+    }
+
+    fn attribute(&self, ctx: Ctx, name: &str) -> Option<String> {
+        let node = self.node_ref(ctx)?;
+        let attr = match name {
+            "text" => Attr::Text,
+            "resource-id" => Attr::ResourceId,
+            "class" => Attr::Class,
+            "package" => Attr::Package,
+            "content-desc" => Attr::ContentDesc,
+            "bounds" => Attr::Bounds,
+            "index" => Attr::Index,
+            _ => {
+                let flag = match name {
+                    "clickable" => Flag::Clickable,
+                    "long-clickable" => Flag::LongClickable,
+                    "enabled" => Flag::Enabled,
+                    "focusable" => Flag::Focusable,
+                    "focused" => Flag::Focused,
+                    "scrollable" => Flag::Scrollable,
+                    "checkable" => Flag::Checkable,
+                    "checked" => Flag::Checked,
+                    "selected" => Flag::Selected,
+                    "password" => Flag::Password,
+                    _ => return None,
+                };
+                return Some(node.flag(flag).to_string());
+            }
+        };
+        node.attr(attr).map(String::from)
+    }
+
+    /// 节点的字符串值：uiautomator 节点取 text 属性
+    fn string_value(&self, ctx: Ctx) -> String {
+        self.node_ref(ctx).map(|n| n.text().to_string()).unwrap_or_default()
+    }
+
+    fn apply_predicates(&self, mut nodes: Vec<Ctx>, predicates: &[Expr]) -> Vec<Ctx> {
+        for predicate in predicates {
+            let size = nodes.len();
+            nodes = nodes
+                .into_iter()
+                .enumerate()
+                .filter(|(i, node)| {
+                    let ctx = Context { node: *node, pos: i + 1, size };
+                    match self.eval(predicate, &ctx) {
+                        Value::Num(n) => n == (i + 1) as f64,
+                        value => self.to_bool(&value),
+                    }
+                })
+                .map(|(_, node)| node)
+                .collect();
+        }
+        nodes
+    }
+
+    fn eval_steps(&self, start: Vec<Ctx>, steps: &[Step]) -> Value {
+        let mut current = start;
+        for (i, step) in steps.iter().enumerate() {
+            let last = i + 1 == steps.len();
+            if step.axis == Axis::Attribute || step.test == NodeTest::Text {
+                if !last {
+                    return Value::Nodes(Vec::new());
+                }
+                let values = current
+                    .iter()
+                    .filter_map(|ctx| match (&step.axis, &step.test) {
+                        (Axis::Attribute, NodeTest::Name(name)) => self.attribute(*ctx, name),
+                        (Axis::Attribute, _) => None,
+                        _ => Some(self.string_value(*ctx)).filter(|t| !t.is_empty()),
+                    })
+                    .collect();
+                return Value::Strs(values);
+            }
+
+            let mut next = Vec::new();
+            for ctx in &current {
+                let candidates: Vec<Ctx> = self
+                    .axis_nodes(*ctx, step.axis)
+                    .into_iter()
+                    .filter(|n| self.matches_test(*n, &step.test))
+                    .collect();
+                next.extend(self.apply_predicates(candidates, &step.predicates));
+            }
+            next.sort();
+            next.dedup();
+            current = next;
+        }
+        Value::Nodes(current)
+    }
+
+    fn eval(&self, expr: &Expr, ctx: &Context) -> Value {
+        match expr {
+            Expr::Literal(s) => Value::Str(s.clone()),
+            Expr::Number(n) => Value::Num(*n),
+            Expr::Neg(inner) => Value::Num(-self.to_num(&self.eval(inner, ctx))),
+            Expr::Or(a, b) => Value::Bool(self.to_bool(&self.eval(a, ctx)) || self.to_bool(&self.eval(b, ctx))),
+            Expr::And(a, b) => Value::Bool(self.to_bool(&self.eval(a, ctx)) && self.to_bool(&self.eval(b, ctx))),
+            Expr::Cmp(op, a, b) => Value::Bool(self.compare(op, &self.eval(a, ctx), &self.eval(b, ctx))),
+            Expr::Union(a, b) => match (self.eval(a, ctx), self.eval(b, ctx)) {
+                (Value::Nodes(mut x), Value::Nodes(y)) => {
+                    x.extend(y);
+                    x.sort();
+                    x.dedup();
+                    Value::Nodes(x)
+                }
+                _ => Value::Nodes(Vec::new()),
+            },
+            Expr::Path { absolute, steps } => {
+                let start = if *absolute { Ctx::Doc } else { ctx.node };
+                self.eval_steps(vec![start], steps)
+            }
+            Expr::Filter { primary, predicates, rest } => match self.eval(primary, ctx) {
+                Value::Nodes(nodes) => {
+                    let filtered = self.apply_predicates(nodes, predicates);
+                    if rest.is_empty() {
+                        Value::Nodes(filtered)
+                    } else {
+                        self.eval_steps(filtered, rest)
+                    }
+                }
+                _ => Value::Nodes(Vec::new()),
+            },
+            Expr::Call(name, args) => self.call(name, args, ctx),
+        }
+    }
+
+    fn call(&self, name: &str, args: &[Expr], ctx: &Context) -> Value {
+        let arg = |i: usize| args.get(i).map(|a| self.eval(a, ctx));
+        let str_arg = |i: usize| arg(i).map(|v| self.to_string(&v)).unwrap_or_else(|| self.string_value(ctx.node));
+        match name {
+            "contains" => Value::Bool(str_arg(0).contains(&str_arg(1))),
+            "starts-with" => Value::Bool(str_arg(0).starts_with(&str_arg(1))),
+            "ends-with" => Value::Bool(str_arg(0).ends_with(&str_arg(1))),
+            "matches" => Value::Bool(Regex::new(&str_arg(1)).is_ok_and(|re| re.is_match(&str_arg(0)))),
+            "normalize-space" => Value::Str(str_arg(0).split_whitespace().collect::<Vec<_>>().join(" ")),
+            "string" => Value::Str(str_arg(0)),
+            "string-length" => Value::Num(str_arg(0).chars().count() as f64),
+            "lower-case" => Value::Str(str_arg(0).to_lowercase()),
+            "upper-case" => Value::Str(str_arg(0).to_uppercase()),
+            "concat" => Value::Str(args.iter().map(|a| self.to_string(&self.eval(a, ctx))).collect()),
+            "not" => Value::Bool(!arg(0).is_some_and(|v| self.to_bool(&v))),
+            "true" => Value::Bool(true),
+            "false" => Value::Bool(false),
+            "boolean" => Value::Bool(arg(0).is_some_and(|v| self.to_bool(&v))),
+            "number" => Value::Num(arg(0).map_or(f64::NAN, |v| self.to_num(&v))),
+            "position" => Value::Num(ctx.pos as f64),
+            "last" => Value::Num(ctx.size as f64),
+            "count" => Value::Num(match arg(0) {
+                Some(Value::Nodes(n)) => n.len() as f64,
+                Some(Value::Strs(s)) => s.len() as f64,
+                _ => 0.0,
+            }),
+            _ => Value::Bool(false),
+        }
+    }
+
+    fn to_bool(&self, value: &Value) -> bool {
+        match value {
+            Value::Nodes(n) => !n.is_empty(),
+            Value::Strs(s) => !s.is_empty(),
+            Value::Str(s) => !s.is_empty(),
+            Value::Num(n) => *n != 0.0 && !n.is_nan(),
+            Value::Bool(b) => *b,
+        }
+    }
+
+    fn to_string(&self, value: &Value) -> String {
+        match value {
+            Value::Nodes(n) => n.first().map(|c| self.string_value(*c)).unwrap_or_default(),
+            Value::Strs(s) => s.first().cloned().unwrap_or_default(),
+            Value::Str(s) => s.clone(),
+            Value::Num(n) if n.fract() == 0.0 && n.is_finite() => format!("{}", *n as i64),
+            Value::Num(n) => n.to_string(),
+            Value::Bool(b) => b.to_string(),
+        }
+    }
+
+    fn to_num(&self, value: &Value) -> f64 {
+        match value {
+            Value::Num(n) => *n,
+            Value::Bool(b) => *b as u8 as f64,
+            other => self.to_string(other).trim().parse().unwrap_or(f64::NAN),
+        }
+    }
+
+    /// 节点集 / 属性集与其它值比较时，任一成员满足即为真
+    fn compare(&self, op: &str, a: &Value, b: &Value) -> bool {
+        let set = |v: &Value| match v {
+            Value::Nodes(n) => Some(n.iter().map(|c| self.string_value(*c)).collect::<Vec<_>>()),
+            Value::Strs(s) => Some(s.clone()),
+            _ => None,
+        };
+        match (set(a), set(b)) {
+            (Some(xs), Some(ys)) => xs.iter().any(|x| ys.iter().any(|y| self.compare_atoms(op, &Value::Str(x.clone()), &Value::Str(y.clone())))),
+            (Some(xs), None) => xs.iter().any(|x| self.compare_atoms(op, &Value::Str(x.clone()), b)),
+            (None, Some(ys)) => ys.iter().any(|y| self.compare_atoms(op, a, &Value::Str(y.clone()))),
+            (None, None) => self.compare_atoms(op, a, b),
+        }
+    }
+
+    fn compare_atoms(&self, op: &str, a: &Value, b: &Value) -> bool {
+        match op {
+            "=" | "!=" => {
+                let equal = match (a, b) {
+                    (Value::Bool(_), _) | (_, Value::Bool(_)) => self.to_bool(a) == self.to_bool(b),
+                    (Value::Num(_), _) | (_, Value::Num(_)) => self.to_num(a) == self.to_num(b),
+                    _ => self.to_string(a) == self.to_string(b),
+                };
+                equal == (op == "=")
+            }
+            _ => {
+                let (x, y) = (self.to_num(a), self.to_num(b));
+                match op {
+                    "<" => x < y,
+                    "<=" => x <= y,
+                    ">" => x > y,
+                    _ => x >= y,
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const XML: &str = r#"<hierarchy rotation="0">
+  <node index="0" class="android.widget.FrameLayout" resource-id="com.app:id/root" bounds="[0,0][1080,2400]">
+    <node index="0" class="android.widget.LinearLayout" resource-id="com.app:id/tabs" bounds="[0,2200][1080,2400]">
+      <node index="0" class="android.widget.FrameLayout" resource-id="com.app:id/tab" clickable="true" bounds="[0,2200][360,2400]">
+        <node index="0" class="android.widget.TextView" text="  首页 " bounds="[100,2300][260,2380]"/>
+      </node>
+      <node index="1" class="android.widget.FrameLayout" resource-id="com.app:id/tab" clickable="true" bounds="[360,2200][720,2400]">
+        <node index="0" class="android.widget.TextView" text="消息" bounds="[460,2300][620,2380]"/>
+      </node>
+      <node index="2" class="android.widget.FrameLayout" resource-id="com.app:id/tab" clickable="true" bounds="[720,2200][1080,2400]">
+        <node index="0" class="android.widget.TextView" text="我的主页" bounds="[820,2300][980,2380]"/>
+      </node>
+    </node>
+  </node>
+</hierarchy>"#;
+
+    fn ids(xpath: &str) -> Vec<usize> {
+        let tree = UiTree::parse(XML).unwrap();
+        XPathExpr::parse(xpath).unwrap().select(&tree).unwrap().iter().map(|n| n.id()).collect()
+    }
+
+    #[test]
+    fn evaluates_functions_axes_and_positions() {
+        assert_eq!(ids("//*[@resource-id='com.app:id/tab']"), vec![2, 4, 6]);
+        assert_eq!(ids("//*[@resource-id='com.app:id/tab'][2]"), vec![4]);
+        assert_eq!(ids("(//*[@resource-id='com.app:id/tab'])[last()]"), vec![6]);
+        assert_eq!(ids("//*[contains(@resource-id, ':id/tab') and position()=1]"), vec![1, 2]);
+        assert_eq!(ids("//*[starts-with(@text, '我的')]"), vec![7]);
+        assert_eq!(ids("//*[normalize-space(text())='首页']"), vec![3]);
+        assert_eq!(ids("//TextView[ancestor::*[@resource-id='com.app:id/tabs']][2]"), Vec::<usize>::new());
+        assert_eq!(ids("//*[@resource-id='com.app:id/tabs']/descendant::TextView[2]"), vec![5]);
+        assert_eq!(ids("//*[@text='消息']/ancestor::*[@clickable='true'][1]"), vec![4]);
+        assert_eq!(ids("//*[@resource-id='com.app:id/tab'][.//*[@text='消息']]/following-sibling::*"), vec![6]);
+        assert_eq!(ids("/hierarchy/node/node/node[3]/.."), vec![1]);
+        assert_eq!(ids("//*[@text=concat('消', \"息\")] | //*[string-length(@text) > 3]"), vec![3, 5, 7]);
+
+        assert!(XPathExpr::parse("//*[@text='unclosed").is_err());
+        assert!(XPathExpr::parse("//*[foo::node()]").is_err());
+        assert_eq!(xpath_literal("it's"), "\"it's\"");
+
+        let report = evaluate_on_xml("//*[@text='消息']", XML);
+        assert!(report.valid);
+        assert_eq!(report.matches[0].index_path, vec![0, 0, 1, 0]);
+        assert!(!evaluate_on_xml("//*[@text='不存在']", XML).valid);
+    }
+}
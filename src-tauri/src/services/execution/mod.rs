@@ -13,6 +13,8 @@ pub mod orchestrator; // 智能脚本批量执行编排器
 pub mod actions; // 智能脚本动作分发器
 pub mod ui_bridge; // UI 操作桥接层
 pub mod loop_handler; // 循环处理器
+pub mod timeline; // 执行时间线（阶段耗时，chrome://tracing 导出）
+pub mod debug_session; // 调试执行（断点 / 单步）
 
 pub use model::*;
 pub use retry::*;
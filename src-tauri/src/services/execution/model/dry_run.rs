@@ -0,0 +1,55 @@
+//! dry_run.rs - 智能脚本试运行（只匹配不注入）的结果模型
+
+use serde::{Deserialize, Serialize};
+
+use super::smart::SmartActionType;
+
+/// 试运行中单个步骤的结论
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DryRunStepStatus {
+    /// 找到目标且全部安全闸门通过，真实执行时会点击 / 输入
+    WouldExecute,
+    /// 找到候选但被安全闸门拦截
+    Blocked,
+    /// 当前界面中找不到目标
+    NotFound,
+    /// 步骤不需要定位元素（等待、按键、坐标操作等）
+    NoTarget,
+    Disabled,
+}
+
+/// 候选元素及其置信度（0..1）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DryRunCandidate {
+    pub confidence: f64,
+    pub bounds: Option<(i32, i32, i32, i32)>,
+    pub text: Option<String>,
+    pub resource_id: Option<String>,
+    pub content_desc: Option<String>,
+    pub class_name: Option<String>,
+    /// 命中的选择器字段
+    pub matched_fields: Vec<String>,
+}
+
+/// 单个安全闸门的判定
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DryRunGateVerdict {
+    /// confidence | uniqueness | container
+    pub gate: String,
+    pub passed: bool,
+    pub detail: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DryRunStepReport {
+    pub step_id: String,
+    pub step_name: String,
+    pub order: i32,
+    pub step_type: SmartActionType,
+    pub status: DryRunStepStatus,
+    /// 按置信度降序的候选（最多保留前几个）
+    pub candidates: Vec<DryRunCandidate>,
+    pub gates: Vec<DryRunGateVerdict>,
+    pub message: String,
+}
@@ -3,6 +3,7 @@
 mod step;
 mod context;
 mod smart;
+mod dry_run;
 
 pub use step::{ExecStep, ExecStepKind, ExecStepId, ExecStepMeta};
 pub use context::ExecutionContext;
@@ -13,3 +14,4 @@ pub use smart::{
 	SmartExecutionResult,
 	SingleStepTestResult,
 };
+pub use dry_run::{DryRunCandidate, DryRunGateVerdict, DryRunStepReport, DryRunStepStatus};
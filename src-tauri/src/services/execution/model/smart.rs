@@ -4,6 +4,8 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
+use crate::services::device_status_indicator::StatusIndicatorConfig;
+
 /// 智能脚本步骤的操作类型（与前端保持兼容）。
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -155,6 +157,12 @@ pub struct SmartExecutionResult {
     pub final_page_state: Option<String>,
     pub extracted_data: HashMap<String, serde_json::Value>,
     pub message: String,
+    /// 执行时间线 ID（可用 export_execution_timeline 导出）
+    #[serde(default)]
+    pub run_id: Option<String>,
+    /// 试运行（dry_run）时每个步骤的匹配与安全闸门报告
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dry_run: Option<Vec<super::DryRunStepReport>>,
 }
 
 /// 执行时的配置项（兼容旧接口）。
@@ -164,4 +172,7 @@ pub struct SmartExecutorConfig {
     pub auto_verification_enabled: bool,
     pub smart_recovery_enabled: bool,
     pub detailed_logging: bool,
+    /// 运行期间在设备上显示状态提示（缺省不显示）
+    #[serde(default)]
+    pub status_indicator: Option<StatusIndicatorConfig>,
 }
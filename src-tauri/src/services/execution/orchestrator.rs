@@ -6,7 +6,13 @@ use tracing::{error, info, warn};
 
 use crate::application::device_metrics::{DeviceMetrics, DeviceMetricsProvider};
 use crate::application::normalizer::normalize_step_json;
+use crate::automation::pipeline::multi_device;
 use crate::infra::device::metrics_provider::RealDeviceMetricsProvider;
+use crate::services::concurrency::{self, WorkRequest};
+use crate::services::execution::debug_session::{DebugCommand, DebugSession};
+use crate::services::execution::timeline::{self, Phase};
+use crate::services::campaign_sim::record_step_duration;
+use crate::services::device_status_indicator::StatusIndicator;
 use crate::services::execution::model::{
     SmartActionType, SmartExecutionResult, SmartExecutorConfig, SmartScriptStep,
 };
@@ -17,6 +23,7 @@ use serde_json;
 pub struct SmartScriptOrchestrator<'a> {
     executor: &'a SmartScriptExecutor,
     preprocessor: Arc<Mutex<ScriptPreprocessor>>,
+    debug: Option<Arc<DebugSession>>,
 }
 
 impl<'a> SmartScriptOrchestrator<'a> {
@@ -27,19 +34,45 @@ impl<'a> SmartScriptOrchestrator<'a> {
         Self {
             executor,
             preprocessor,
+            debug: None,
         }
     }
 
+    /// 以调试模式执行：步骤执行前按断点 / 单步暂停，运行 ID 使用调试会话 ID
+    pub fn with_debug(mut self, session: Arc<DebugSession>) -> Self {
+        self.debug = Some(session);
+        self
+    }
+
+    /// 执行脚本；每次执行分配一个 run_id，各步骤阶段耗时记入执行时间线
     pub async fn execute(
         &self,
         steps: Vec<SmartScriptStep>,
         config: Option<SmartExecutorConfig>,
+    ) -> Result<SmartExecutionResult> {
+        let run_id = match &self.debug {
+            Some(session) => session.run_id().to_string(),
+            None => uuid::Uuid::new_v4().to_string(),
+        };
+        let label = format!("smart_script {}", self.executor.device_id());
+        let source = if self.debug.is_some() { "debug" } else { "script" };
+        let request = WorkRequest::device(self.executor.device_id(), source, label.clone());
+        let mut result = concurrency::run(request, timeline::scope_run(&run_id, &label, self.execute_steps(steps, config))).await?;
+        result.run_id = Some(run_id);
+        Ok(result)
+    }
+
+    async fn execute_steps(
+        &self,
+        steps: Vec<SmartScriptStep>,
+        config: Option<SmartExecutorConfig>,
     ) -> Result<SmartExecutionResult> {
         let start_time = std::time::Instant::now();
         let mut logs = Vec::new();
         let mut executed_steps = 0u32;
         let mut failed_steps = 0u32;
-        let skipped_steps = 0u32;
+        let mut skipped_steps = 0u32;
+        let mut aborted = false;
         let mut extracted_data = HashMap::new();
 
         let device_id = self.executor.device_id();
@@ -50,6 +83,7 @@ impl<'a> SmartScriptOrchestrator<'a> {
             auto_verification_enabled: true,
             smart_recovery_enabled: true,
             detailed_logging: true,
+            status_indicator: None,
         });
 
         let provider = RealDeviceMetricsProvider::new(adb_path.to_string());
@@ -145,13 +179,30 @@ impl<'a> SmartScriptOrchestrator<'a> {
                     final_page_state: None,
                     extracted_data: HashMap::new(),
                     message: format!("控制流预处理失败: {}", e),
+                    run_id: None,
+                    dry_run: None,
                 });
             }
         };
 
         logs.push(format!("📋 已启用的步骤: {} 个", processed_steps.len()));
 
+        let mut indicator = StatusIndicator::start(device_id, adb_path, config.status_indicator.as_ref());
+
         for (index, step) in processed_steps.iter().enumerate() {
+            if let Some(debug) = &self.debug {
+                if debug.before_step(index, step, self.debug_variables(&extracted_data)).await == DebugCommand::Abort {
+                    aborted = true;
+                    skipped_steps = (processed_steps.len() - index) as u32;
+                    logs.push(format!("⏹️ 调试已中止，停在步骤 {}: {}", index + 1, step.name));
+                    break;
+                }
+            }
+
+            if let Some(indicator) = indicator.as_mut() {
+                indicator.progress(index + 1, processed_steps.len()).await;
+            }
+
             let step_start = std::time::Instant::now();
             let params = serde_json::from_value::<HashMap<String, serde_json::Value>>(step.parameters.clone());
             let detailed_info = match params {
@@ -177,7 +228,19 @@ impl<'a> SmartScriptOrchestrator<'a> {
             info!("{}", detailed_info);
             logs.push(detailed_info);
 
-            match self.executor.execute_single_step(step.clone()).await {
+            multi_device::report_step_started(index, &step.id, &step.name);
+            let mut step_span = timeline::span(Phase::Step, &step.id);
+            let outcome = self.executor.execute_single_step(step.clone()).await;
+            let action = serde_json::to_value(&step.step_type)
+                .ok()
+                .and_then(|v| v.as_str().map(String::from))
+                .unwrap_or_else(|| "unknown".to_string());
+            record_step_duration(&action, step_start.elapsed().as_millis() as u64, matches!(&outcome, Ok(r) if r.success));
+            match &outcome {
+                Ok(r) => multi_device::report_step_finished(index, &step.id, &step.name, r.success, Some(r.message.clone())),
+                Err(e) => multi_device::report_step_finished(index, &step.id, &step.name, false, Some(e.to_string())),
+            }
+            match outcome {
                 Ok(result) => {
                     if result.success {
                         executed_steps += 1;
@@ -191,6 +254,7 @@ impl<'a> SmartScriptOrchestrator<'a> {
                             extracted_data.insert(format!("{}_{}", step.id, key), value);
                         }
                     } else {
+                        step_span.fail();
                         failed_steps += 1;
                         logs.push(format!(
                             "❌ 步骤失败: {} - {}",
@@ -205,6 +269,7 @@ impl<'a> SmartScriptOrchestrator<'a> {
                     logs.extend(result.logs);
                 }
                 Err(e) => {
+                    step_span.fail();
                     failed_steps += 1;
                     let error_msg = format!("❌ 步骤执行异常: {} - {}", step.name, e);
                     logs.push(error_msg);
@@ -217,15 +282,29 @@ impl<'a> SmartScriptOrchestrator<'a> {
                 }
             }
 
+            drop(step_span);
+            if let Some(debug) = &self.debug {
+                debug.after_step(&step.id);
+            }
+
             if index < processed_steps.len() - 1 {
                 tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
             }
         }
 
+        if let Some(indicator) = indicator {
+            indicator.finish().await;
+        }
+
         let total_duration = start_time.elapsed().as_millis() as u64;
-        let success = failed_steps == 0 && executed_steps > 0;
+        let success = !aborted && failed_steps == 0 && executed_steps > 0;
 
-        let message = if success {
+        let message = if aborted {
+            format!(
+                "智能脚本调试已中止，{} 个成功，{} 个失败，{} 个未执行",
+                executed_steps, failed_steps, skipped_steps
+            )
+        } else if success {
             format!(
                 "智能脚本执行成功！共执行 {} 个步骤，耗时 {}ms",
                 executed_steps, total_duration
@@ -251,6 +330,20 @@ impl<'a> SmartScriptOrchestrator<'a> {
             final_page_state: None,
             extracted_data,
             message,
+            run_id: None,
+            dry_run: None,
         })
     }
+
+    /// 调试暂停时展示的变量：执行上下文变量 + 已提取的数据
+    fn debug_variables(&self, extracted_data: &HashMap<String, serde_json::Value>) -> HashMap<String, serde_json::Value> {
+        let mut variables = extracted_data.clone();
+        let env = self.executor.ui_bridge().execution_environment();
+        if let Ok(ctx) = env.context.lock() {
+            for (key, value) in ctx.variables.inner() {
+                variables.entry(key.clone()).or_insert_with(|| value.clone());
+            }
+        }
+        variables
+    }
 }
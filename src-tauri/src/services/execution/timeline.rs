@@ -0,0 +1,293 @@
+//! timeline.rs - 执行时间线（按运行记录每个步骤各阶段耗时）
+//!
+//! 运行入口用 `scope_run` 包裹执行 future，阶段代码用 `span(Phase, step_id)` 打点，
+//! 守卫析构时记录耗时；不在运行范围内时打点为空操作。底层代码（dump / 注入器）不知道
+//! 步骤 ID 时用 `phase(Phase)`，归入当前正在执行的步骤。导出为 chrome://tracing 兼容 JSON。
+
+use std::collections::{BTreeMap, VecDeque};
+use std::future::Future;
+use std::sync::{Mutex, OnceLock};
+use std::time::Instant;
+
+use serde::Serialize;
+use serde_json::{json, Value};
+
+/// 最多保留的运行数（按开始顺序淘汰）
+pub const MAX_TIMELINE_RUNS: usize = 20;
+/// 单次运行最多记录的阶段事件数
+const MAX_EVENTS_PER_RUN: usize = 50_000;
+
+tokio::task_local! {
+    static CURRENT_RUN: String;
+}
+
+static TIMELINES: OnceLock<Mutex<VecDeque<Timeline>>> = OnceLock::new();
+
+fn timelines() -> &'static Mutex<VecDeque<Timeline>> {
+    TIMELINES.get_or_init(|| Mutex::new(VecDeque::with_capacity(MAX_TIMELINE_RUNS)))
+}
+
+/// 执行阶段
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Phase {
+    /// 整个步骤
+    Step,
+    /// UI dump
+    Dump,
+    /// 元素匹配
+    Match,
+    /// 执行网关 / 沙箱检查
+    Gate,
+    /// 设备动作
+    Action,
+    /// 后置校验
+    Verify,
+}
+
+impl Phase {
+    pub const ALL: [Phase; 6] = [Phase::Step, Phase::Dump, Phase::Match, Phase::Gate, Phase::Action, Phase::Verify];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Phase::Step => "step",
+            Phase::Dump => "dump",
+            Phase::Match => "match",
+            Phase::Gate => "gate",
+            Phase::Action => "action",
+            Phase::Verify => "verify",
+        }
+    }
+
+    /// chrome://tracing 中的行号（每个阶段一行，形成甘特图）
+    fn lane(self) -> u32 {
+        Phase::ALL.iter().position(|p| *p == self).unwrap_or(0) as u32 + 1
+    }
+}
+
+/// 一条阶段记录（相对运行开始的微秒）
+#[derive(Debug, Clone, Serialize)]
+pub struct PhaseEvent {
+    pub phase: Phase,
+    pub step_id: String,
+    pub start_us: u64,
+    pub duration_us: u64,
+    pub ok: bool,
+}
+
+/// 一次运行的时间线
+#[derive(Debug, Clone)]
+struct Timeline {
+    run_id: String,
+    label: String,
+    started: Instant,
+    started_at: String,
+    events: Vec<PhaseEvent>,
+    dropped: usize,
+    /// 最近开始的步骤（运行内步骤顺序执行）
+    current_step: String,
+}
+
+/// 运行概览
+#[derive(Debug, Clone, Serialize)]
+pub struct TimelineSummary {
+    pub run_id: String,
+    pub label: String,
+    pub started_at: String,
+    pub steps: usize,
+    pub events: usize,
+    /// 阶段 → 累计耗时（毫秒）
+    pub phase_totals_ms: BTreeMap<String, f64>,
+}
+
+impl Timeline {
+    fn summary(&self) -> TimelineSummary {
+        let mut phase_totals_ms = BTreeMap::new();
+        for event in &self.events {
+            *phase_totals_ms.entry(event.phase.name().to_string()).or_insert(0.0) += event.duration_us as f64 / 1000.0;
+        }
+        TimelineSummary {
+            run_id: self.run_id.clone(),
+            label: self.label.clone(),
+            started_at: self.started_at.clone(),
+            steps: self.events.iter().filter(|e| e.phase == Phase::Step).count(),
+            events: self.events.len(),
+            phase_totals_ms,
+        }
+    }
+}
+
+/// 开始（或继续）一次运行；同一 run_id 重复调用时沿用已有时间线
+pub fn start_run(run_id: &str, label: &str) {
+    let Ok(mut runs) = timelines().lock() else { return };
+    if runs.iter().any(|t| t.run_id == run_id) {
+        return;
+    }
+    if runs.len() >= MAX_TIMELINE_RUNS {
+        runs.pop_front();
+    }
+    runs.push_back(Timeline {
+        run_id: run_id.to_string(),
+        label: label.to_string(),
+        started: Instant::now(),
+        started_at: chrono::Local::now().to_rfc3339(),
+        events: Vec::new(),
+        dropped: 0,
+        current_step: String::new(),
+    });
+}
+
+/// 在运行范围内执行 future，期间的 `span` 打点都归入该运行
+pub async fn scope_run<F: Future>(run_id: &str, label: &str, fut: F) -> F::Output {
+    start_run(run_id, label);
+    CURRENT_RUN.scope(run_id.to_string(), fut).await
+}
+
+/// 当前任务所属的运行
+pub fn current_run() -> Option<String> {
+    CURRENT_RUN.try_with(|run| run.clone()).ok()
+}
+
+/// 阶段打点守卫：析构时记录耗时
+#[must_use = "守卫析构时才记录耗时"]
+pub struct PhaseSpan {
+    run_id: Option<String>,
+    phase: Phase,
+    step_id: String,
+    started: Instant,
+    ok: bool,
+}
+
+impl PhaseSpan {
+    /// 标记该阶段失败（导出时写入 args.ok=false）
+    pub fn fail(&mut self) {
+        self.ok = false;
+    }
+}
+
+impl Drop for PhaseSpan {
+    fn drop(&mut self) {
+        let Some(run_id) = self.run_id.take() else { return };
+        let ended = Instant::now();
+        let Ok(mut runs) = timelines().lock() else { return };
+        let Some(timeline) = runs.iter_mut().find(|t| t.run_id == run_id) else { return };
+        if timeline.events.len() >= MAX_EVENTS_PER_RUN {
+            timeline.dropped += 1;
+            return;
+        }
+        timeline.events.push(PhaseEvent {
+            phase: self.phase,
+            step_id: std::mem::take(&mut self.step_id),
+            start_us: self.started.saturating_duration_since(timeline.started).as_micros() as u64,
+            duration_us: ended.saturating_duration_since(self.started).as_micros() as u64,
+            ok: self.ok,
+        });
+    }
+}
+
+/// 开始一个阶段打点（不在运行范围内时不记录）
+pub fn span(phase: Phase, step_id: &str) -> PhaseSpan {
+    let mut span = PhaseSpan { run_id: current_run(), phase, step_id: String::new(), started: Instant::now(), ok: true };
+    let Some(run_id) = &span.run_id else { return span };
+    if let Ok(mut runs) = timelines().lock() {
+        if let Some(timeline) = runs.iter_mut().find(|t| &t.run_id == run_id) {
+            if phase == Phase::Step {
+                timeline.current_step = step_id.to_string();
+            }
+            span.step_id = if step_id.is_empty() { timeline.current_step.clone() } else { step_id.to_string() };
+        }
+    }
+    span
+}
+
+/// 归入当前步骤的阶段打点（供不知道步骤 ID 的底层代码使用）
+pub fn phase(phase: Phase) -> PhaseSpan {
+    span(phase, "")
+}
+
+/// 最近的运行概览（新的在前）
+pub fn list_runs() -> Vec<TimelineSummary> {
+    match timelines().lock() {
+        Ok(runs) => runs.iter().rev().map(Timeline::summary).collect(),
+        Err(_) => Vec::new(),
+    }
+}
+
+/// 导出 chrome://tracing（Trace Event Format）兼容的 JSON
+pub fn export_chrome_trace(run_id: &str) -> Option<Value> {
+    let runs = timelines().lock().ok()?;
+    let timeline = runs.iter().find(|t| t.run_id == run_id)?;
+
+    let mut events: Vec<Value> = vec![json!({
+        "name": "process_name", "ph": "M", "pid": 1, "tid": 0,
+        "args": { "name": format!("run {} {}", timeline.label, timeline.run_id) }
+    })];
+    events.extend(Phase::ALL.iter().map(|phase| {
+        json!({
+            "name": "thread_name", "ph": "M", "pid": 1, "tid": phase.lane(),
+            "args": { "name": phase.name() }
+        })
+    }));
+    events.extend(timeline.events.iter().map(|event| {
+        json!({
+            "name": if event.phase == Phase::Step { event.step_id.clone() } else { format!("{} {}", event.phase.name(), event.step_id) },
+            "cat": event.phase.name(),
+            "ph": "X",
+            "ts": event.start_us,
+            "dur": event.duration_us,
+            "pid": 1,
+            "tid": event.phase.lane(),
+            "args": { "step_id": event.step_id, "ok": event.ok },
+        })
+    }));
+
+    let summary = timeline.summary();
+    Some(json!({
+        "traceEvents": events,
+        "displayTimeUnit": "ms",
+        "otherData": {
+            "run_id": summary.run_id,
+            "label": summary.label,
+            "started_at": summary.started_at,
+            "steps": summary.steps,
+            "phase_totals_ms": summary.phase_totals_ms,
+            "dropped_events": timeline.dropped,
+        },
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn records_phase_spans_within_run_scope_and_exports_chrome_trace() {
+        // 运行范围外的打点不记录
+        drop(span(Phase::Dump, "outside"));
+
+        scope_run("timeline-test-run", "unit", async {
+            for step in ["s1", "s2"] {
+                let _step = span(Phase::Step, step);
+                drop(phase(Phase::Dump));
+                let mut action = span(Phase::Action, step);
+                if step == "s2" {
+                    action.fail();
+                }
+            }
+        })
+        .await;
+
+        let trace = export_chrome_trace("timeline-test-run").unwrap();
+        let events = trace["traceEvents"].as_array().unwrap();
+        let spans: Vec<&Value> = events.iter().filter(|e| e["ph"] == "X").collect();
+        assert_eq!(spans.len(), 6);
+        assert!(spans.iter().any(|e| e["cat"] == "dump" && e["args"]["step_id"] == "s2"));
+        assert!(spans.iter().all(|e| e["args"]["step_id"] != "outside"));
+        let failed = spans.iter().find(|e| e["args"]["ok"] == false).unwrap();
+        assert_eq!(failed["cat"], "action");
+        assert_eq!(failed["tid"], Phase::Action.lane());
+        assert_eq!(trace["otherData"]["steps"], 2);
+        assert!(list_runs().iter().any(|r| r.run_id == "timeline-test-run"));
+        assert!(export_chrome_trace("missing-run").is_none());
+    }
+}
@@ -54,6 +54,12 @@ impl UiBridge {
         Arc::clone(&self.exec_env)
     }
 
+    /// 最近一次 dump 的 XML 及距今毫秒数（供调试暂停时查看）
+    pub fn last_cached_dump() -> Option<(String, u64)> {
+        let cache = XML_CACHE.read().ok()?;
+        cache.as_ref().map(|c| (c.content.clone(), c.timestamp.elapsed().as_millis() as u64))
+    }
+
     /// 统一获取 UI 快照（XML + 可选截图）。
     /// 当前实现：委托给 `ExecutionEnvironment::capture_snapshot`。
     pub async fn capture_snapshot(&self) -> anyhow::Result<Option<String>> {
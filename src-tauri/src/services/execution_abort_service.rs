@@ -293,7 +293,13 @@ pub async fn force_stop_all_adb_operations() -> Result<String, String> {
         }
     }
 
-    // 3. 清理所有执行状态
+    // 3. 清除设备上的运行状态提示
+    let cleared = crate::services::device_status_indicator::clear_all_indicators().await;
+    if cleared > 0 {
+        info!("🧹 [强制停止] 已清除 {} 台设备的运行状态提示", cleared);
+    }
+
+    // 4. 清理所有执行状态
     {
         let mut manager = EXECUTION_MANAGER.lock().map_err(|e| {
             error!("❌ [强制停止] 清理时获取执行管理器锁失败: {}", e);
@@ -0,0 +1,420 @@
+// src-tauri/src/services/failure_annotation/mod.rs
+// module: failure_annotation | layer: services | role: 失败截图标注
+// summary: 步骤失败时在截图上绘制期望元素的最后已知边界、实际匹配候选边界与点击坐标，并把原图、标注图与 trace.json 存入同一失败记录目录；参数缺少边界时按选择器在 UI 树中定位期望元素
+
+use image::{DynamicImage, ImageFormat, Rgba, RgbaImage};
+use serde::Serialize;
+use serde_json::Value;
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+use tracing::{info, warn};
+
+use crate::automation::matching::decision_journal::{last_decision, MatchDecision};
+use crate::engine::ui_tree::UiTree;
+use crate::services::artifact_store::{gallery, ArtifactStore};
+
+/// 失败记录目录名（数据目录下）
+const TRACE_DIR_NAME: &str = "failure_traces";
+/// 期望元素（绿色）
+const EXPECTED_COLOR: Rgba<u8> = Rgba([0, 200, 83, 255]);
+/// 实际选中的候选（红色）
+const MATCHED_COLOR: Rgba<u8> = Rgba([229, 57, 53, 255]);
+/// 其他候选（琥珀色）
+const CANDIDATE_COLOR: Rgba<u8> = Rgba([255, 179, 0, 255]);
+/// 点击坐标（蓝色）
+const TAP_COLOR: Rgba<u8> = Rgba([30, 136, 229, 255]);
+/// 最多绘制的非选中候选数
+const MAX_EXTRA_CANDIDATES: usize = 5;
+
+/// 屏幕矩形（像素）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct Rect {
+    pub left: i32,
+    pub top: i32,
+    pub right: i32,
+    pub bottom: i32,
+}
+
+impl Rect {
+    /// 从 `[l,t][r,b]` 或 `[l,t,r,b]` 文本中解析
+    pub fn parse(text: &str) -> Option<Rect> {
+        let numbers: Vec<i32> = text
+            .split(|c: char| !(c.is_ascii_digit() || c == '-'))
+            .filter(|s| !s.is_empty())
+            .map(|s| s.parse().ok())
+            .collect::<Option<Vec<_>>>()?;
+        match numbers[..] {
+            [left, top, right, bottom] if right > left && bottom > top => Some(Rect { left, top, right, bottom }),
+            _ => None,
+        }
+    }
+
+    /// 步骤参数中的 bounds 可能是字符串、`[l,t,r,b]` 数组或 `{left,top,right,bottom}` 对象
+    pub fn from_value(value: &Value) -> Option<Rect> {
+        match value {
+            Value::String(s) => Rect::parse(s),
+            Value::Array(items) => {
+                let n: Vec<i32> = items.iter().map(|v| v.as_i64().map(|n| n as i32)).collect::<Option<_>>()?;
+                Rect::parse(&format!("{:?}", n))
+            }
+            Value::Object(map) => {
+                let get = |k: &str| map.get(k).and_then(Value::as_i64).map(|n| n as i32);
+                Rect::parse(&format!("[{},{},{},{}]", get("left")?, get("top")?, get("right")?, get("bottom")?))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// 需要绘制到截图上的标注
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct FailureAnnotation {
+    /// 期望元素的最后已知边界
+    pub expected: Option<Rect>,
+    /// 实际匹配到的候选边界
+    pub matched: Option<Rect>,
+    /// 其他候选边界
+    pub candidates: Vec<Rect>,
+    /// 点击坐标
+    pub tap: Option<(i32, i32)>,
+}
+
+impl FailureAnnotation {
+    /// 由步骤参数与最近一次匹配决策组装标注
+    pub fn from_step(params: &Value, decision: Option<&MatchDecision>, tap: Option<(i32, i32)>) -> Self {
+        let expected = ["bounds", "original_bounds", "originalBounds"]
+            .iter()
+            .find_map(|key| params.get(*key).and_then(Rect::from_value))
+            .or_else(|| params.get("params").and_then(|p| p.get("bounds")).and_then(Rect::from_value));
+
+        let mut annotation = Self { expected, tap, ..Default::default() };
+        if let Some(decision) = decision {
+            for (index, candidate) in decision.candidates.iter().enumerate() {
+                let Some(rect) = Rect::parse(&candidate.bounds) else { continue };
+                if decision.selected == Some(index) {
+                    annotation.matched = Some(rect);
+                } else if annotation.candidates.len() < MAX_EXTRA_CANDIDATES {
+                    annotation.candidates.push(rect);
+                }
+            }
+        }
+        annotation
+    }
+
+    /// 参数中没有边界时，按 resource-id / text / content-desc 在 dump 中查找期望元素
+    pub fn locate_expected(&mut self, params: &Value, tree: &UiTree) {
+        if self.expected.is_some() {
+            return;
+        }
+        let selector = |keys: &[&str]| {
+            keys.iter()
+                .find_map(|key| params.get(*key).or_else(|| params.get("params").and_then(|p| p.get(*key))))
+                .and_then(Value::as_str)
+                .filter(|s| !s.is_empty())
+        };
+        let resource_id = selector(&["resource_id", "resourceId"]);
+        let text = selector(&["text", "targetText"]);
+        let content_desc = selector(&["content_desc", "contentDesc"]);
+        if resource_id.is_none() && text.is_none() && content_desc.is_none() {
+            return;
+        }
+        self.expected = tree
+            .nodes()
+            .find(|node| {
+                resource_id.map_or(true, |id| node.resource_id() == Some(id))
+                    && text.map_or(true, |t| node.text() == t)
+                    && content_desc.map_or(true, |d| node.content_desc() == d)
+            })
+            .and_then(|node| node.bounds())
+            .map(|(left, top, right, bottom)| Rect { left, top, right, bottom })
+            .filter(|r| r.right > r.left && r.bottom > r.top);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.expected.is_none() && self.matched.is_none() && self.candidates.is_empty() && self.tap.is_none()
+    }
+}
+
+/// 线宽随截图尺寸缩放（1080p 约 6 像素）
+fn stroke_width(image: &RgbaImage) -> i32 {
+    (image.width().min(image.height()) as i32 / 180).max(2)
+}
+
+fn fill_rect(image: &mut RgbaImage, x0: i32, y0: i32, x1: i32, y1: i32, color: Rgba<u8>) {
+    let (w, h) = (image.width() as i32, image.height() as i32);
+    for y in y0.max(0)..y1.min(h) {
+        for x in x0.max(0)..x1.min(w) {
+            image.put_pixel(x as u32, y as u32, color);
+        }
+    }
+}
+
+fn draw_rect_outline(image: &mut RgbaImage, rect: Rect, width: i32, color: Rgba<u8>) {
+    fill_rect(image, rect.left, rect.top, rect.right, rect.top + width, color);
+    fill_rect(image, rect.left, rect.bottom - width, rect.right, rect.bottom, color);
+    fill_rect(image, rect.left, rect.top, rect.left + width, rect.bottom, color);
+    fill_rect(image, rect.right - width, rect.top, rect.right, rect.bottom, color);
+}
+
+/// 点击位置：十字准星 + 圆环
+fn draw_tap(image: &mut RgbaImage, (cx, cy): (i32, i32), width: i32, color: Rgba<u8>) {
+    let arm = width * 6;
+    let half = width / 2;
+    fill_rect(image, cx - arm, cy - half, cx + arm + 1, cy + half + 1, color);
+    fill_rect(image, cx - half, cy - arm, cx + half + 1, cy + arm + 1, color);
+
+    let (w, h) = (image.width() as i32, image.height() as i32);
+    let radius = arm as f32 * 0.7;
+    for y in (cy - arm).max(0)..(cy + arm + 1).min(h) {
+        for x in (cx - arm).max(0)..(cx + arm + 1).min(w) {
+            let d = (((x - cx).pow(2) + (y - cy).pow(2)) as f32).sqrt();
+            if (d - radius).abs() <= half.max(1) as f32 {
+                image.put_pixel(x as u32, y as u32, color);
+            }
+        }
+    }
+}
+
+/// 在图像上绘制标注（先画次要候选，选中与期望覆盖其上）
+pub fn annotate_image(image: &mut RgbaImage, annotation: &FailureAnnotation) {
+    let width = stroke_width(image);
+    for rect in &annotation.candidates {
+        draw_rect_outline(image, *rect, (width / 2).max(1), CANDIDATE_COLOR);
+    }
+    if let Some(rect) = annotation.expected {
+        draw_rect_outline(image, rect, width, EXPECTED_COLOR);
+    }
+    if let Some(rect) = annotation.matched {
+        draw_rect_outline(image, rect, width, MATCHED_COLOR);
+    }
+    if let Some(tap) = annotation.tap {
+        draw_tap(image, tap, width, TAP_COLOR);
+    }
+}
+
+/// 对 PNG 截图绘制标注并重新编码为 PNG
+pub fn annotate_png(png: &[u8], annotation: &FailureAnnotation) -> Result<Vec<u8>, String> {
+    let mut image = image::load_from_memory_with_format(png, ImageFormat::Png)
+        .map_err(|e| format!("解析截图失败: {}", e))?
+        .to_rgba8();
+    annotate_image(&mut image, annotation);
+    let mut out = Cursor::new(Vec::new());
+    DynamicImage::ImageRgba8(image)
+        .write_to(&mut out, ImageFormat::Png)
+        .map_err(|e| format!("编码标注图失败: {}", e))?;
+    Ok(out.into_inner())
+}
+
+/// trace.json 内容
+#[derive(Debug, Clone, Serialize)]
+pub struct FailureTrace {
+    pub step_id: String,
+    pub device_id: String,
+    /// RFC3339 本地时间
+    pub recorded_at: String,
+    pub error: String,
+    pub params: Value,
+    pub annotation: FailureAnnotation,
+    /// 图例：颜色 → 含义
+    pub legend: Vec<(String, String)>,
+    pub decision: Option<MatchDecision>,
+    pub screenshot: Option<String>,
+    pub annotated_screenshot: Option<String>,
+}
+
+fn legend() -> Vec<(String, String)> {
+    [
+        ("green", "期望元素最后已知边界"),
+        ("red", "实际选中的候选"),
+        ("amber", "其他候选"),
+        ("blue", "点击坐标"),
+    ]
+    .iter()
+    .map(|(color, meaning)| (color.to_string(), meaning.to_string()))
+    .collect()
+}
+
+/// 失败记录根目录
+pub fn default_trace_root() -> PathBuf {
+    dirs::data_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("employee-gui")
+        .join(TRACE_DIR_NAME)
+}
+
+fn sanitize(s: &str) -> String {
+    s.chars().map(|c| if c.is_alphanumeric() || c == '-' || c == '_' { c } else { '_' }).collect()
+}
+
+/// 写入失败记录目录：trace.json + screenshot.png + annotated.png，返回目录路径
+#[allow(clippy::too_many_arguments)]
+pub fn write_failure_report(
+    root: &Path,
+    step_id: &str,
+    device_id: &str,
+    error: &str,
+    params: &Value,
+    tap: Option<(i32, i32)>,
+    screenshot: Option<&[u8]>,
+    ui_xml: Option<&str>,
+) -> Result<PathBuf, String> {
+    let now = chrono::Local::now();
+    let dir = root.join(format!("{}_{}", now.format("%Y%m%d_%H%M%S%3f"), sanitize(step_id)));
+    std::fs::create_dir_all(&dir).map_err(|e| format!("创建失败记录目录失败: {}", e))?;
+
+    let decision = last_decision(step_id);
+    let mut annotation = FailureAnnotation::from_step(params, decision.as_ref(), tap);
+    if let Some(xml) = ui_xml {
+        annotation.locate_expected(params, &UiTree::parse_lenient(xml));
+    }
+    let mut trace = FailureTrace {
+        step_id: step_id.to_string(),
+        device_id: device_id.to_string(),
+        recorded_at: now.to_rfc3339(),
+        error: error.to_string(),
+        params: params.clone(),
+        annotation,
+        legend: legend(),
+        decision,
+        screenshot: None,
+        annotated_screenshot: None,
+    };
+
+    if let Some(png) = screenshot {
+        std::fs::write(dir.join("screenshot.png"), png).map_err(|e| format!("写入截图失败: {}", e))?;
+        trace.screenshot = Some("screenshot.png".into());
+        match annotate_png(png, &trace.annotation) {
+            Ok(annotated) => {
+                std::fs::write(dir.join("annotated.png"), annotated).map_err(|e| format!("写入标注图失败: {}", e))?;
+                trace.annotated_screenshot = Some("annotated.png".into());
+            }
+            Err(e) => warn!("⚠️ 截图标注失败 ({}): {}", step_id, e),
+        }
+    }
+
+    let content = serde_json::to_string_pretty(&trace).map_err(|e| e.to_string())?;
+    std::fs::write(dir.join("trace.json"), content).map_err(|e| format!("写入 trace 失败: {}", e))?;
+    info!("🧾 步骤 {} 失败记录已保存: {}", step_id, dir.display());
+    Ok(dir)
+}
+
+/// 截图并写入失败记录（尽力而为：截图失败时仍保存 trace）；`ui_xml` 为失败前最后一次 dump
+pub async fn capture_failure_report(
+    device_id: &str,
+    step_id: &str,
+    error: &str,
+    params: &Value,
+    tap: Option<(i32, i32)>,
+    ui_xml: Option<&str>,
+) -> Option<PathBuf> {
+    let (device_id, step_id, error, params) =
+        (device_id.to_string(), step_id.to_string(), error.to_string(), params.clone());
+    let ui_xml = ui_xml.map(str::to_string);
+    // 运行 ID 是任务局部变量，进入 spawn_blocking 前取出
+    let run_id = crate::services::execution::timeline::current_run();
+    let result = tokio::task::spawn_blocking(move || {
+        let screenshot = crate::screenshot_service::ScreenshotService::capture_png_bytes(&device_id)
+            .map_err(|e| warn!("⚠️ 失败截图获取失败 ({}): {}", device_id, e))
+            .ok();
+        let dir = write_failure_report(
+            &default_trace_root(),
+            &step_id,
+            &device_id,
+            &error,
+            &params,
+            tap,
+            screenshot.as_deref(),
+            ui_xml.as_deref(),
+        )?;
+        if let Some(png) = screenshot.as_deref() {
+            index_failure_screenshot(png, &device_id, run_id.as_deref(), &step_id, &dir);
+        }
+        Ok::<_, String>(dir)
+    })
+    .await;
+    match result {
+        Ok(Ok(dir)) => Some(dir),
+        Ok(Err(e)) => {
+            warn!("⚠️ 失败记录保存失败: {}", e);
+            None
+        }
+        Err(e) => {
+            warn!("⚠️ 失败记录任务异常: {}", e);
+            None
+        }
+    }
+}
+
+/// 失败截图同时登记为截图制品，截图图库可按运行 / 步骤找到它（尽力而为）
+fn index_failure_screenshot(png: &[u8], device_id: &str, run_id: Option<&str>, step_id: &str, dir: &Path) {
+    let meta = serde_json::json!({
+        "deviceId": device_id,
+        "runId": run_id,
+        "stepId": step_id,
+        "source": "failure",
+        "traceDir": dir.to_string_lossy(),
+    });
+    let ttl = chrono::Duration::days(gallery::RUN_SCREENSHOT_TTL_DAYS);
+    if let Err(e) = gallery::index_screenshot(ArtifactStore::global(), png, ttl, meta) {
+        warn!("⚠️ 失败截图登记制品失败 ({}): {}", step_id, e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::automation::matching::decision_journal::CandidateRecord;
+
+    fn candidate(rank: usize, bounds: &str) -> CandidateRecord {
+        CandidateRecord {
+            rank,
+            score: 1.0 / rank as f32,
+            text: String::new(),
+            content_desc: String::new(),
+            resource_id: None,
+            class_name: None,
+            bounds: bounds.to_string(),
+            components: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn builds_annotation_and_draws_onto_png() {
+        let params = serde_json::json!({ "bounds": "[10,10][60,40]" });
+        let decision = MatchDecision::new("s", vec![candidate(1, "[0,50,90,90]"), candidate(2, "[5,5,20,20]")], Some(0));
+        let annotation = FailureAnnotation::from_step(&params, Some(&decision), Some((45, 70)));
+        assert_eq!(annotation.expected, Some(Rect { left: 10, top: 10, right: 60, bottom: 40 }));
+        assert_eq!(annotation.matched, Some(Rect { left: 0, top: 50, right: 90, bottom: 90 }));
+        assert_eq!(annotation.candidates.len(), 1);
+        assert_eq!(Rect::from_value(&serde_json::json!([1, 2, 3, 4])), Some(Rect { left: 1, top: 2, right: 3, bottom: 4 }));
+        assert_eq!(Rect::parse("[0,0][0,0]"), None);
+
+        let mut blank = Cursor::new(Vec::new());
+        DynamicImage::ImageRgba8(RgbaImage::from_pixel(100, 100, Rgba([0, 0, 0, 255])))
+            .write_to(&mut blank, ImageFormat::Png)
+            .unwrap();
+        let annotated = annotate_png(blank.get_ref(), &annotation).unwrap();
+        let image = image::load_from_memory(&annotated).unwrap().to_rgba8();
+        assert_eq!(*image.get_pixel(10, 10), EXPECTED_COLOR);
+        assert_eq!(*image.get_pixel(0, 89), MATCHED_COLOR);
+        assert_eq!(*image.get_pixel(45, 70), TAP_COLOR);
+        assert_eq!(*image.get_pixel(30, 25), Rgba([0, 0, 0, 255]));
+
+        let dir = tempfile::tempdir().unwrap();
+        let report =
+            write_failure_report(dir.path(), "step/1", "dev", "boom", &params, None, Some(blank.get_ref().as_slice()), None).unwrap();
+        assert!(report.join("annotated.png").exists());
+        assert!(std::fs::read_to_string(report.join("trace.json")).unwrap().contains("\"error\": \"boom\""));
+    }
+
+    #[test]
+    fn locates_expected_element_in_dump_when_bounds_missing() {
+        let xml = r#"<hierarchy><node class="A" bounds="[0,0][100,100]"><node resource-id="com.app:id/follow" text="关注" bounds="[10,20][60,40]" /></node></hierarchy>"#;
+        let tree = UiTree::parse(xml).unwrap();
+        let params = serde_json::json!({ "params": { "resourceId": "com.app:id/follow" } });
+        let mut annotation = FailureAnnotation::from_step(&params, None, None);
+        assert!(annotation.expected.is_none());
+        annotation.locate_expected(&params, &tree);
+        assert_eq!(annotation.expected, Some(Rect { left: 10, top: 20, right: 60, bottom: 40 }));
+    }
+}
@@ -0,0 +1,400 @@
+// src-tauri/src/services/interaction_heatmap/mod.rs
+// module: interaction_heatmap | layer: services | role: 元素交互热力统计
+// summary: 按界面指纹累计各元素被步骤操作的次数与失败率（只记录 resource-id / 类名 / 层级路径与边界，不保存文本内容），落盘供热力图查询
+
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::PathBuf;
+use std::sync::{Mutex, OnceLock};
+use tracing::warn;
+
+use crate::engine::ui_tree::{Flag, NodeRef, UiTree};
+
+const HEATMAP_FILE_NAME: &str = "interaction_heatmap.json";
+/// 每累计多少次交互落盘一次
+const FLUSH_EVERY: u64 = 20;
+/// 最多保留的界面数（按最近出现时间淘汰）
+const MAX_SCREENS: usize = 300;
+/// 单个界面最多保留的元素数（按操作次数淘汰）
+const MAX_ELEMENTS_PER_SCREEN: usize = 200;
+/// 无 resource-id 的界面按浅层结构生成指纹的深度
+const SKELETON_DEPTH: usize = 3;
+
+static STORE: OnceLock<Mutex<HeatmapStore>> = OnceLock::new();
+
+/// 界面指纹：包名 + 界面上 resource-id 集合（无 id 时为浅层类名结构）的哈希；文本、坐标变化不影响指纹
+pub fn screen_fingerprint(tree: &UiTree) -> String {
+    let package = tree.nodes().find_map(|n| n.package()).unwrap_or("unknown");
+    let ids: BTreeSet<&str> = tree.nodes().filter_map(|n| n.resource_id()).collect();
+    let mut ctx = md5::Context::new();
+    ctx.consume(package.as_bytes());
+    if ids.is_empty() {
+        for node in tree.nodes().filter(|n| n.depth() < SKELETON_DEPTH) {
+            ctx.consume(format!("|{}:{}", node.depth(), node.class_name().unwrap_or("")).as_bytes());
+        }
+    } else {
+        for id in ids {
+            ctx.consume(b"|");
+            ctx.consume(id.as_bytes());
+        }
+    }
+    format!("{}#{}", package, &format!("{:x}", ctx.compute())[..12])
+}
+
+fn short_class(class: &str) -> &str {
+    class.rsplit('.').next().unwrap_or(class)
+}
+
+/// 匿名化的元素标识：有 resource-id 时按 id，否则按类名 + 层级路径
+fn element_key(node: NodeRef<'_>) -> (String, String) {
+    match node.resource_id() {
+        Some(id) => (format!("id:{}", id), id.rsplit(":id/").next().unwrap_or(id).to_string()),
+        None => {
+            let class = short_class(node.class_name().unwrap_or("node")).to_string();
+            let path = node.index_path().iter().map(usize::to_string).collect::<Vec<_>>().join(".");
+            (format!("path:{}@{}", class, path), format!("{}@{}", class, path))
+        }
+    }
+}
+
+/// 操作落点：成功时为点击坐标，失败时按步骤选择器在界面中查找
+#[derive(Debug, Clone)]
+pub enum InteractionTarget<'a> {
+    Point(i32, i32),
+    Selector(&'a Value),
+}
+
+fn selector_param<'a>(params: &'a Value, keys: &[&str]) -> Option<&'a str> {
+    keys.iter()
+        .find_map(|key| params.get(*key).or_else(|| params.get("params").and_then(|p| p.get(*key))))
+        .and_then(Value::as_str)
+        .filter(|s| !s.is_empty())
+}
+
+fn resolve_target<'t>(tree: &'t UiTree, target: &InteractionTarget<'_>) -> Option<NodeRef<'t>> {
+    match target {
+        InteractionTarget::Point(x, y) => {
+            let hit = tree.node_at(*x, *y)?;
+            let mut current = Some(hit);
+            while let Some(node) = current {
+                if node.flag(Flag::Clickable) || node.flag(Flag::LongClickable) {
+                    return Some(node);
+                }
+                current = node.parent();
+            }
+            Some(hit)
+        }
+        InteractionTarget::Selector(params) => {
+            let resource_id = selector_param(params, &["resource_id", "resourceId"]);
+            let text = selector_param(params, &["text", "targetText"]);
+            let content_desc = selector_param(params, &["content_desc", "contentDesc"]);
+            if resource_id.is_none() && text.is_none() && content_desc.is_none() {
+                return None;
+            }
+            tree.nodes().find(|node| {
+                resource_id.is_none_or(|id| node.resource_id() == Some(id))
+                    && text.is_none_or(|t| node.text() == t)
+                    && content_desc.is_none_or(|d| node.content_desc() == d)
+            })
+        }
+    }
+}
+
+/// 选择器在界面中找不到元素时的标识（只保留 resource-id，其余条件取哈希）
+fn selector_key(params: &Value) -> (String, String) {
+    if let Some(id) = selector_param(params, &["resource_id", "resourceId"]) {
+        return (format!("id:{}", id), id.rsplit(":id/").next().unwrap_or(id).to_string());
+    }
+    let raw = ["text", "targetText", "content_desc", "contentDesc", "xpath"]
+        .iter()
+        .filter_map(|key| selector_param(params, &[key]))
+        .collect::<Vec<_>>()
+        .join("|");
+    let hash = format!("{:x}", md5::compute(raw.as_bytes()));
+    (format!("selector:{}", &hash[..12]), format!("未定位元素 {}", &hash[..6]))
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ElementStats {
+    pub label: String,
+    pub class_name: Option<String>,
+    /// 最近一次出现的边界
+    pub bounds: Option<(i32, i32, i32, i32)>,
+    pub interactions: u64,
+    pub failures: u64,
+    /// 失败错误码 → 次数
+    pub failure_codes: BTreeMap<String, u64>,
+    /// 动作 → 次数
+    pub actions: BTreeMap<String, u64>,
+    pub last_seen: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScreenStats {
+    pub package: String,
+    pub interactions: u64,
+    pub last_seen: String,
+    pub elements: BTreeMap<String, ElementStats>,
+}
+
+/// 热力图中的一个元素
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ElementHeat {
+    pub key: String,
+    pub label: String,
+    pub class_name: Option<String>,
+    pub bounds: Option<(i32, i32, i32, i32)>,
+    pub interactions: u64,
+    pub failures: u64,
+    pub failure_rate: f64,
+    /// 占该界面全部交互的比例
+    pub usage_share: f64,
+    pub failure_codes: BTreeMap<String, u64>,
+    pub actions: BTreeMap<String, u64>,
+    pub last_seen: String,
+}
+
+/// 单个界面的热力图（元素按交互次数降序）
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScreenHeatmap {
+    pub fingerprint: String,
+    pub package: String,
+    pub interactions: u64,
+    pub failures: u64,
+    pub last_seen: String,
+    pub elements: Vec<ElementHeat>,
+}
+
+/// 界面列表项
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScreenSummary {
+    pub fingerprint: String,
+    pub package: String,
+    pub interactions: u64,
+    pub failures: u64,
+    pub elements: usize,
+    pub last_seen: String,
+}
+
+fn ratio(part: u64, total: u64) -> f64 {
+    if total == 0 { 0.0 } else { part as f64 / total as f64 }
+}
+
+#[derive(Debug, Default)]
+pub struct HeatmapStore {
+    screens: BTreeMap<String, ScreenStats>,
+    data_dir: Option<PathBuf>,
+    unflushed: u64,
+}
+
+impl HeatmapStore {
+    pub fn global() -> &'static Mutex<HeatmapStore> {
+        STORE.get_or_init(|| {
+            let base = dirs::data_dir().unwrap_or_else(|| PathBuf::from(".")).join("employee-gui");
+            Mutex::new(Self::load(base))
+        })
+    }
+
+    pub fn load(data_dir: PathBuf) -> Self {
+        let screens = std::fs::read_to_string(data_dir.join(HEATMAP_FILE_NAME))
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+        Self { screens, data_dir: Some(data_dir), unflushed: 0 }
+    }
+
+    /// 不落盘的实例
+    pub fn in_memory() -> Self {
+        Self::default()
+    }
+
+    /// 记录一次交互；`failure_code` 为空表示成功。返回界面指纹
+    pub fn record(&mut self, xml: &str, target: InteractionTarget<'_>, action: &str, failure_code: Option<&str>) -> String {
+        let tree = UiTree::parse_lenient(xml);
+        let fingerprint = screen_fingerprint(&tree);
+        let node = resolve_target(&tree, &target);
+        let (key, label) = match (node, &target) {
+            (Some(node), _) => element_key(node),
+            (None, InteractionTarget::Selector(params)) => selector_key(params),
+            (None, InteractionTarget::Point(x, y)) => (format!("point:{}x{}", x / 50 * 50, y / 50 * 50), format!("({}, {}) 附近", x, y)),
+        };
+        let now = Utc::now().to_rfc3339();
+
+        let screen = self.screens.entry(fingerprint.clone()).or_default();
+        screen.package = fingerprint.split('#').next().unwrap_or_default().to_string();
+        screen.interactions += 1;
+        screen.last_seen = now.clone();
+        let element = screen.elements.entry(key).or_default();
+        element.label = label;
+        if let Some(node) = node {
+            element.class_name = node.class_name().map(str::to_string);
+            element.bounds = node.bounds();
+        }
+        element.interactions += 1;
+        *element.actions.entry(action.to_string()).or_default() += 1;
+        if let Some(code) = failure_code {
+            element.failures += 1;
+            *element.failure_codes.entry(code.to_string()).or_default() += 1;
+        }
+        element.last_seen = now;
+        if screen.elements.len() > MAX_ELEMENTS_PER_SCREEN {
+            if let Some(coldest) = screen.elements.iter().min_by_key(|(_, e)| (e.interactions, e.last_seen.clone())).map(|(k, _)| k.clone()) {
+                screen.elements.remove(&coldest);
+            }
+        }
+        if self.screens.len() > MAX_SCREENS {
+            if let Some(oldest) = self.screens.iter().min_by_key(|(_, s)| s.last_seen.clone()).map(|(k, _)| k.clone()) {
+                self.screens.remove(&oldest);
+            }
+        }
+
+        self.unflushed += 1;
+        if self.unflushed >= FLUSH_EVERY {
+            self.flush();
+        }
+        fingerprint
+    }
+
+    pub fn heatmap(&self, fingerprint: &str) -> Option<ScreenHeatmap> {
+        let screen = self.screens.get(fingerprint)?;
+        let mut elements: Vec<ElementHeat> = screen
+            .elements
+            .iter()
+            .map(|(key, e)| ElementHeat {
+                key: key.clone(),
+                label: e.label.clone(),
+                class_name: e.class_name.clone(),
+                bounds: e.bounds,
+                interactions: e.interactions,
+                failures: e.failures,
+                failure_rate: ratio(e.failures, e.interactions),
+                usage_share: ratio(e.interactions, screen.interactions),
+                failure_codes: e.failure_codes.clone(),
+                actions: e.actions.clone(),
+                last_seen: e.last_seen.clone(),
+            })
+            .collect();
+        elements.sort_by(|a, b| b.interactions.cmp(&a.interactions).then_with(|| a.key.cmp(&b.key)));
+        Some(ScreenHeatmap {
+            fingerprint: fingerprint.to_string(),
+            package: screen.package.clone(),
+            interactions: screen.interactions,
+            failures: elements.iter().map(|e| e.failures).sum(),
+            last_seen: screen.last_seen.clone(),
+            elements,
+        })
+    }
+
+    /// 全部界面，按交互次数降序；可按包名过滤
+    pub fn screens(&self, package: Option<&str>) -> Vec<ScreenSummary> {
+        let mut screens: Vec<ScreenSummary> = self
+            .screens
+            .iter()
+            .filter(|(_, s)| package.is_none_or(|p| s.package == p))
+            .map(|(fingerprint, s)| ScreenSummary {
+                fingerprint: fingerprint.clone(),
+                package: s.package.clone(),
+                interactions: s.interactions,
+                failures: s.elements.values().map(|e| e.failures).sum(),
+                elements: s.elements.len(),
+                last_seen: s.last_seen.clone(),
+            })
+            .collect();
+        screens.sort_by(|a, b| b.interactions.cmp(&a.interactions));
+        screens
+    }
+
+    pub fn clear(&mut self, fingerprint: Option<&str>) -> usize {
+        let removed = match fingerprint {
+            Some(fp) => self.screens.remove(fp).map_or(0, |_| 1),
+            None => std::mem::take(&mut self.screens).len(),
+        };
+        self.flush();
+        removed
+    }
+
+    pub fn flush(&mut self) {
+        self.unflushed = 0;
+        let Some(dir) = &self.data_dir else { return };
+        let result = std::fs::create_dir_all(dir)
+            .map_err(|e| e.to_string())
+            .and_then(|_| serde_json::to_string(&self.screens).map_err(|e| e.to_string()))
+            .and_then(|content| std::fs::write(dir.join(HEATMAP_FILE_NAME), content).map_err(|e| e.to_string()));
+        if let Err(e) = result {
+            warn!("⚠️ 交互热力统计保存失败: {}", e);
+        }
+    }
+}
+
+/// 记录一次步骤交互（执行链路调用）
+pub fn record_interaction(xml: &str, target: InteractionTarget<'_>, action: &str, failure_code: Option<&str>) {
+    if xml.is_empty() {
+        return;
+    }
+    if let Ok(mut store) = HeatmapStore::global().lock() {
+        store.record(xml, target, action, failure_code);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn screen(title: &str) -> String {
+        format!(
+            r#"<hierarchy rotation="0">
+  <node index="0" text="" class="android.widget.FrameLayout" package="com.app" bounds="[0,0][1080,1920]" clickable="false">
+    <node index="0" text="{title}" resource-id="com.app:id/title" class="android.widget.TextView" package="com.app" bounds="[0,0][1080,200]" clickable="false" />
+    <node index="1" text="" resource-id="com.app:id/follow" class="android.widget.FrameLayout" package="com.app" bounds="[40,300][540,400]" clickable="true">
+      <node index="0" text="关注" class="android.widget.TextView" package="com.app" bounds="[60,320][200,380]" clickable="false" />
+    </node>
+    <node index="2" text="私信" class="android.widget.Button" package="com.app" bounds="[600,300][1000,400]" clickable="true" />
+  </node>
+</hierarchy>"#
+        )
+    }
+
+    #[test]
+    fn aggregates_usage_and_failures_per_element_without_text() {
+        let mut store = HeatmapStore::in_memory();
+        // 文本变化不影响指纹
+        let fp = store.record(&screen("用户A"), InteractionTarget::Point(100, 350), "tap", None);
+        assert_eq!(store.record(&screen("用户B"), InteractionTarget::Point(300, 390), "tap", None), fp);
+        assert!(fp.starts_with("com.app#"));
+
+        let params = serde_json::json!({ "resource_id": "com.app:id/follow" });
+        store.record(&screen("用户C"), InteractionTarget::Selector(&params), "tap", Some("NO_MATCH"));
+        store.record(&screen("用户C"), InteractionTarget::Point(800, 350), "long_press", None);
+        let missing = serde_json::json!({ "text": "不存在" });
+        store.record(&screen("用户C"), InteractionTarget::Selector(&missing), "tap", Some("NO_MATCH"));
+
+        let heatmap = store.heatmap(&fp).unwrap();
+        assert_eq!((heatmap.interactions, heatmap.failures), (5, 2));
+        let follow = &heatmap.elements[0];
+        assert_eq!((follow.key.as_str(), follow.label.as_str()), ("id:com.app:id/follow", "follow"));
+        assert_eq!((follow.interactions, follow.failures), (3, 1));
+        assert!((follow.failure_rate - 1.0 / 3.0).abs() < 1e-9);
+        assert!((follow.usage_share - 0.6).abs() < 1e-9);
+        assert_eq!(follow.failure_codes["NO_MATCH"], 1);
+
+        let button = heatmap.elements.iter().find(|e| e.key.starts_with("path:")).unwrap();
+        assert_eq!(button.label, "Button@0.2");
+        assert_eq!(button.actions["long_press"], 1);
+        let unresolved = heatmap.elements.iter().find(|e| e.key.starts_with("selector:")).unwrap();
+        assert_eq!(unresolved.failures, 1);
+        // 不保存元素文本
+        let json = serde_json::to_string(&store.screens).unwrap();
+        assert!(!json.contains("私信") && !json.contains("用户") && !json.contains("不存在"));
+
+        assert_eq!(store.screens(Some("com.app")).len(), 1);
+        assert_eq!(store.clear(Some(&fp)), 1);
+        assert!(store.heatmap(&fp).is_none());
+    }
+}
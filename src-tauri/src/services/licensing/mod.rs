@@ -0,0 +1,437 @@
+// src-tauri/src/services/licensing/mod.rs
+// module: licensing | layer: services | role: 授权与激活
+// summary: 离线校验签名授权码（有效期 / 席位 / 功能），过期后按宽限期策略降级，为 Agent、AI 分析、多设备编排等高级功能提供统一的功能门禁
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
+use chrono::{DateTime, Duration, Utc};
+use ed25519_dalek::{Signature, Verifier, VerifyingKey};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::{Mutex, OnceLock};
+use tracing::{info, warn};
+
+const LICENSE_FILE_NAME: &str = "license.key";
+const CLOCK_FILE_NAME: &str = "license_clock.json";
+/// 授权码校验公钥（hex 编码的 Ed25519 公钥，构建时通过环境变量注入，无默认值）
+///
+/// 客户端只内置公钥；私钥只在签发工具 `license_signer` 中使用，不进入应用。
+const LICENSE_PUBLIC_KEY: Option<&str> = option_env!("LICENSE_PUBLIC_KEY");
+
+static MANAGER: OnceLock<Mutex<LicenseManager>> = OnceLock::new();
+
+/// 受授权控制的高级功能
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LicenseFeature {
+    /// AI Agent（对话 / 自主运行 / 副驾驶）
+    Agent,
+    /// AI 智能分析
+    AiAnalysis,
+    /// 多设备编排
+    MultiDevice,
+}
+
+impl LicenseFeature {
+    pub const ALL: [LicenseFeature; 3] = [LicenseFeature::Agent, LicenseFeature::AiAnalysis, LicenseFeature::MultiDevice];
+
+    fn label(&self) -> &'static str {
+        match self {
+            LicenseFeature::Agent => "AI Agent",
+            LicenseFeature::AiAnalysis => "AI 智能分析",
+            LicenseFeature::MultiDevice => "多设备编排",
+        }
+    }
+}
+
+/// 授权内容（授权码签名部分）
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct License {
+    pub license_id: String,
+    /// 被授权方（代理商 / 团队名）
+    pub licensee: String,
+    /// 席位数（可激活的机器数）
+    pub seats: u32,
+    pub features: Vec<LicenseFeature>,
+    pub issued_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+    /// 绑定的机器 ID（即已激活的席位），数量不能超过席位数；为空的授权无效
+    #[serde(default)]
+    pub machine_ids: Vec<String>,
+    /// 多设备编排时单机可同时驱动的设备数上限
+    #[serde(default)]
+    pub max_devices: Option<u32>,
+}
+
+/// 过期宽限策略
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GracePolicy {
+    /// 过期后仍保留全部功能的天数
+    pub grace_days: i64,
+    /// 到期前多少天开始提醒续费
+    pub warn_before_days: i64,
+}
+
+impl Default for GracePolicy {
+    fn default() -> Self {
+        Self { grace_days: 7, warn_before_days: 14 }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LicenseState {
+    /// 未激活
+    Unlicensed,
+    /// 开发构建且未激活：放行全部功能
+    Development,
+    Active,
+    /// 已过期但在宽限期内
+    Grace,
+    Expired,
+    /// 签名错误 / 格式错误 / 本机未授权
+    Invalid,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LicenseStatus {
+    pub state: LicenseState,
+    pub license: Option<License>,
+    pub enabled_features: Vec<LicenseFeature>,
+    pub machine_id: String,
+    pub days_remaining: Option<i64>,
+    pub grace_days_remaining: Option<i64>,
+    /// 即将到期 / 宽限期中时提醒续费
+    pub renewal_warning: bool,
+    pub message: String,
+}
+
+impl LicenseStatus {
+    pub fn allows(&self, feature: LicenseFeature) -> bool {
+        self.enabled_features.contains(&feature)
+    }
+}
+
+/// 授权码签名部分：`base64url(JSON)`（签发工具对其签名）
+pub fn license_payload(license: &License) -> Result<String, String> {
+    Ok(URL_SAFE_NO_PAD.encode(serde_json::to_vec(license).map_err(|e| e.to_string())?))
+}
+
+/// 解析 hex 编码的 Ed25519 公钥
+pub fn parse_public_key(hex_key: &str) -> Result<VerifyingKey, String> {
+    let bytes: [u8; 32] = hex::decode(hex_key.trim())
+        .ok()
+        .and_then(|b| b.try_into().ok())
+        .ok_or("授权公钥格式错误")?;
+    VerifyingKey::from_bytes(&bytes).map_err(|e| format!("授权公钥无效: {}", e))
+}
+
+/// 构建时内置的授权公钥
+fn embedded_public_key() -> Result<VerifyingKey, String> {
+    parse_public_key(LICENSE_PUBLIC_KEY.ok_or("本构建未内置授权公钥，无法校验授权码")?)
+}
+
+/// 校验授权码签名并解析授权内容；授权码格式为 `base64url(JSON).hex(Ed25519 签名)`
+pub fn verify_license_key(key: &str, public_key: &VerifyingKey) -> Result<License, String> {
+    let (payload, signature) = key.trim().split_once('.').ok_or("授权码格式错误")?;
+    let signature: [u8; 64] = hex::decode(signature)
+        .ok()
+        .and_then(|b| b.try_into().ok())
+        .ok_or("授权码签名格式错误")?;
+    public_key
+        .verify(payload.as_bytes(), &Signature::from_bytes(&signature))
+        .map_err(|_| "授权码签名无效".to_string())?;
+    let json = URL_SAFE_NO_PAD.decode(payload).map_err(|_| "授权码内容格式错误".to_string())?;
+    serde_json::from_slice(&json).map_err(|e| format!("授权码内容解析失败: {}", e))
+}
+
+/// 根据授权码校验结果、本机 ID 与当前时间计算授权状态
+pub fn evaluate(
+    license: Option<Result<License, String>>,
+    machine_id: &str,
+    now: DateTime<Utc>,
+    policy: &GracePolicy,
+    development: bool,
+) -> LicenseStatus {
+    let mut status = LicenseStatus {
+        state: LicenseState::Unlicensed,
+        license: None,
+        enabled_features: Vec::new(),
+        machine_id: machine_id.to_string(),
+        days_remaining: None,
+        grace_days_remaining: None,
+        renewal_warning: false,
+        message: String::new(),
+    };
+
+    let license = match license {
+        None if development => {
+            status.state = LicenseState::Development;
+            status.enabled_features = LicenseFeature::ALL.to_vec();
+            status.message = "开发构建，未激活授权时放行全部功能".to_string();
+            return status;
+        }
+        None => {
+            status.message = "未激活授权，高级功能不可用".to_string();
+            return status;
+        }
+        Some(Err(e)) => {
+            status.state = LicenseState::Invalid;
+            status.message = e;
+            return status;
+        }
+        Some(Ok(license)) => license,
+    };
+
+    // 席位必须绑定到机器：未绑定的授权码可以在任意多台机器上激活，席位数形同虚设
+    if license.machine_ids.is_empty() {
+        status.state = LicenseState::Invalid;
+        status.message = "授权未绑定机器，无法校验席位".to_string();
+    } else if license.machine_ids.len() > license.seats as usize {
+        status.state = LicenseState::Invalid;
+        status.message = format!("授权绑定机器数 {} 超过席位数 {}", license.machine_ids.len(), license.seats);
+    } else if !license.machine_ids.iter().any(|id| id == machine_id) {
+        status.state = LicenseState::Invalid;
+        status.message = format!("本机 ({}) 不在授权席位内", machine_id);
+    }
+    if status.state == LicenseState::Invalid {
+        status.license = Some(license);
+        return status;
+    }
+
+    let remaining = license.expires_at - now;
+    let grace_end = license.expires_at + Duration::days(policy.grace_days);
+    status.days_remaining = Some(remaining.num_days());
+    if remaining > Duration::zero() {
+        status.state = LicenseState::Active;
+        status.renewal_warning = remaining < Duration::days(policy.warn_before_days);
+        status.message = format!("授权有效，剩余 {} 天", remaining.num_days());
+    } else if now < grace_end {
+        status.state = LicenseState::Grace;
+        status.renewal_warning = true;
+        status.grace_days_remaining = Some((grace_end - now).num_days());
+        status.message = format!("授权已过期，宽限期剩余 {} 天，请尽快续费", (grace_end - now).num_days());
+    } else {
+        status.state = LicenseState::Expired;
+        status.renewal_warning = true;
+        status.message = "授权已过期，高级功能已停用".to_string();
+    }
+    if matches!(status.state, LicenseState::Active | LicenseState::Grace) {
+        status.enabled_features = license.features.clone();
+    }
+    status.license = Some(license);
+    status
+}
+
+/// 记录见过的最晚时间，防止离线时回拨系统时钟绕过有效期
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct LicenseClock {
+    last_seen: Option<DateTime<Utc>>,
+}
+
+/// 本机授权管理
+#[derive(Debug)]
+pub struct LicenseManager {
+    data_dir: Option<PathBuf>,
+    key: Option<String>,
+    /// 读取失败时保留错误：不能用占位 ID 去匹配席位
+    machine_id: Result<String, String>,
+    clock: LicenseClock,
+    policy: GracePolicy,
+}
+
+impl LicenseManager {
+    pub fn global() -> &'static Mutex<LicenseManager> {
+        MANAGER.get_or_init(|| {
+            let base = dirs::data_dir().unwrap_or_else(|| PathBuf::from(".")).join("employee-gui");
+            let machine_id = crate::modules::cloud_sync::get_device_id().map_err(|e| {
+                warn!("⚠️ 获取本机 ID 失败: {}", e);
+                format!("无法读取本机 ID，授权无法校验: {}", e)
+            });
+            Mutex::new(Self::load(base, machine_id))
+        })
+    }
+
+    pub fn load(data_dir: PathBuf, machine_id: Result<String, String>) -> Self {
+        let key = std::fs::read_to_string(data_dir.join(LICENSE_FILE_NAME))
+            .ok()
+            .map(|k| k.trim().to_string())
+            .filter(|k| !k.is_empty());
+        let clock = std::fs::read_to_string(data_dir.join(CLOCK_FILE_NAME))
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+        Self { data_dir: Some(data_dir), key, machine_id, clock, policy: GracePolicy::default() }
+    }
+
+    /// 不落盘的实例
+    pub fn in_memory(machine_id: &str) -> Self {
+        Self {
+            data_dir: None,
+            key: None,
+            machine_id: Ok(machine_id.to_string()),
+            clock: LicenseClock::default(),
+            policy: GracePolicy::default(),
+        }
+    }
+
+    /// 当前时间；系统时钟早于记录过的最晚时间时以后者为准
+    fn trusted_now(&mut self) -> DateTime<Utc> {
+        let now = Utc::now();
+        match self.clock.last_seen {
+            Some(last) if last > now => last,
+            _ => {
+                let advanced = self.clock.last_seen.is_none_or(|last| now - last > Duration::hours(1));
+                self.clock.last_seen = Some(now);
+                if advanced {
+                    self.save(CLOCK_FILE_NAME, &serde_json::to_string(&self.clock).unwrap_or_default());
+                }
+                now
+            }
+        }
+    }
+
+    pub fn status(&mut self) -> LicenseStatus {
+        let now = self.trusted_now();
+        let license = self.key.as_deref().map(|key| {
+            // 本机 ID 不可读时已激活的授权一律视为无效
+            self.machine_id.as_ref().map_err(Clone::clone)?;
+            embedded_public_key().and_then(|public_key| verify_license_key(key, &public_key))
+        });
+        let machine_id = self.machine_id.as_deref().unwrap_or_default();
+        evaluate(license, machine_id, now, &self.policy, cfg!(debug_assertions))
+    }
+
+    /// 激活授权码；本机 ID 不可读、签名或本机席位校验失败时不保存
+    pub fn activate(&mut self, key: &str) -> Result<LicenseStatus, String> {
+        let machine_id = self.machine_id.clone()?;
+        let license = verify_license_key(key, &embedded_public_key()?)?;
+        let status = evaluate(Some(Ok(license)), &machine_id, self.trusted_now(), &self.policy, false);
+        if status.state == LicenseState::Invalid {
+            return Err(status.message);
+        }
+        self.key = Some(key.trim().to_string());
+        self.save(LICENSE_FILE_NAME, key.trim());
+        info!("🔑 授权已激活: {}", status.message);
+        Ok(status)
+    }
+
+    pub fn deactivate(&mut self) -> LicenseStatus {
+        self.key = None;
+        if let Some(dir) = &self.data_dir {
+            let _ = std::fs::remove_file(dir.join(LICENSE_FILE_NAME));
+        }
+        self.status()
+    }
+
+    fn save(&self, file_name: &str, content: &str) {
+        let Some(dir) = &self.data_dir else { return };
+        let result = std::fs::create_dir_all(dir).and_then(|_| std::fs::write(dir.join(file_name), content));
+        if let Err(e) = result {
+            warn!("⚠️ 授权信息保存失败: {}", e);
+        }
+    }
+}
+
+pub fn license_status() -> LicenseStatus {
+    match LicenseManager::global().lock() {
+        Ok(mut manager) => manager.status(),
+        Err(e) => e.into_inner().status(),
+    }
+}
+
+/// 功能门禁：未授权时返回可直接提示给用户的错误
+pub fn require_feature(feature: LicenseFeature) -> Result<(), String> {
+    let status = license_status();
+    if status.allows(feature) {
+        return Ok(());
+    }
+    Err(format!("「{}」需要有效授权：{}", feature.label(), status.message))
+}
+
+/// 多设备编排门禁：超过一台设备时校验功能与设备数上限
+pub fn require_device_count(count: usize) -> Result<(), String> {
+    if count <= 1 {
+        return Ok(());
+    }
+    require_feature(LicenseFeature::MultiDevice)?;
+    let max = license_status().license.and_then(|l| l.max_devices);
+    match max {
+        Some(max) if count > max as usize => Err(format!("授权最多同时驱动 {} 台设备，当前 {} 台", max, count)),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ed25519_dalek::{Signer, SigningKey};
+
+    fn sign(license: &License, signing_key: &SigningKey) -> String {
+        let payload = license_payload(license).unwrap();
+        format!("{}.{}", payload, hex::encode(signing_key.sign(payload.as_bytes()).to_bytes()))
+    }
+
+    fn license(expires_in_days: i64, machine_ids: Vec<&str>) -> License {
+        let now = Utc::now();
+        License {
+            license_id: "L-1".into(),
+            licensee: "agency".into(),
+            seats: 2,
+            features: vec![LicenseFeature::Agent, LicenseFeature::MultiDevice],
+            issued_at: now - Duration::days(30),
+            expires_at: now + Duration::days(expires_in_days),
+            machine_ids: machine_ids.into_iter().map(String::from).collect(),
+            max_devices: Some(5),
+        }
+    }
+
+    #[test]
+    fn verifies_signature_and_applies_grace_policy() {
+        let signing_key = SigningKey::from_bytes(&[7u8; 32]);
+        let public_key = parse_public_key(&hex::encode(signing_key.verifying_key().to_bytes())).unwrap();
+        let key = sign(&license(30, vec!["m1"]), &signing_key);
+        assert_eq!(verify_license_key(&key, &public_key).unwrap().licensee, "agency");
+        let other = SigningKey::from_bytes(&[9u8; 32]).verifying_key();
+        assert!(verify_license_key(&key, &other).is_err());
+        let tampered = format!("{}x.{}", key.split('.').next().unwrap(), key.split('.').nth(1).unwrap());
+        assert!(verify_license_key(&tampered, &public_key).is_err());
+
+        let policy = GracePolicy::default();
+        let now = Utc::now();
+        let active = evaluate(Some(Ok(license(30, vec!["m1"]))), "m1", now, &policy, false);
+        assert_eq!(active.state, LicenseState::Active);
+        assert!(active.allows(LicenseFeature::Agent) && !active.allows(LicenseFeature::AiAnalysis));
+
+        let grace = evaluate(Some(Ok(license(-3, vec!["m1"]))), "m1", now, &policy, false);
+        assert_eq!(grace.state, LicenseState::Grace);
+        assert!(grace.allows(LicenseFeature::Agent) && grace.renewal_warning);
+
+        let expired = evaluate(Some(Ok(license(-10, vec!["m1"]))), "m1", now, &policy, false);
+        assert_eq!(expired.state, LicenseState::Expired);
+        assert!(expired.enabled_features.is_empty());
+
+        let other_machine = evaluate(Some(Ok(license(30, vec!["m2"]))), "m1", now, &policy, false);
+        assert_eq!(other_machine.state, LicenseState::Invalid);
+        let over_seats = evaluate(Some(Ok(license(30, vec!["m1", "m2", "m3"]))), "m1", now, &policy, false);
+        assert_eq!(over_seats.state, LicenseState::Invalid);
+        let unbound = evaluate(Some(Ok(license(30, vec![]))), "m1", now, &policy, false);
+        assert_eq!(unbound.state, LicenseState::Invalid);
+        assert!(unbound.enabled_features.is_empty());
+
+        assert_eq!(evaluate(None, "m1", now, &policy, false).state, LicenseState::Unlicensed);
+        assert!(evaluate(None, "m1", now, &policy, true).allows(LicenseFeature::AiAnalysis));
+    }
+
+    #[test]
+    fn unreadable_machine_id_invalidates_license_instead_of_matching_placeholder() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join(LICENSE_FILE_NAME), "payload.signature").unwrap();
+        let mut manager = LicenseManager::load(dir.path().to_path_buf(), Err("无法读取本机 ID".to_string()));
+
+        let status = manager.status();
+        assert_eq!(status.state, LicenseState::Invalid);
+        assert!(status.message.contains("本机 ID"));
+        assert!(status.enabled_features.is_empty());
+        assert!(manager.activate("payload.signature").unwrap_err().contains("本机 ID"));
+    }
+}
@@ -0,0 +1,293 @@
+// src-tauri/src/services/list_tracking/mod.rs
+// module: list_tracking | layer: services | role: 信息流列表追踪
+// summary: 为可见列表项生成与位置无关的指纹，按采集会话维护已见集合，区分新项与重渲染项，估算滚动进度并判定到底，供评论采集与滚动查找复用
+
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::sync::{Mutex, OnceLock};
+
+/// 连续多少页没有新项视为到底
+pub const DEFAULT_MAX_STALE_PAGES: u32 = 2;
+
+/// 一个可见列表项
+#[derive(Debug, Clone, Serialize)]
+pub struct ListItem {
+    /// 指纹（文本 + 描述 + 类名，不含坐标，滚动后重渲染仍一致）
+    pub fingerprint: String,
+    pub bounds: String,
+    /// 贴住容器上 / 下边缘（dump 的 bounds 已按可见区域裁切，贴边即可能只露出一部分）
+    pub clipped_top: bool,
+    pub clipped_bottom: bool,
+}
+
+fn parse_bounds(bounds: &str) -> Option<(i32, i32, i32, i32)> {
+    let nums: Vec<i32> = bounds
+        .split(|c: char| !c.is_ascii_digit() && c != '-')
+        .filter(|s| !s.is_empty())
+        .filter_map(|s| s.parse().ok())
+        .collect();
+    match nums[..] {
+        [l, t, r, b] => Some((l, t, r, b)),
+        _ => None,
+    }
+}
+
+fn item_fingerprint(node: roxmltree::Node) -> Option<String> {
+    let mut ctx = md5::Context::new();
+    let mut has_content = false;
+    ctx.consume(node.attribute("class").unwrap_or("").as_bytes());
+    for child in node.descendants().filter(|n| n.is_element()) {
+        for attr in ["text", "content-desc"] {
+            let value = child.attribute(attr).unwrap_or("").trim();
+            if !value.is_empty() {
+                has_content = true;
+                ctx.consume(b"|");
+                ctx.consume(value.as_bytes());
+            }
+        }
+    }
+    // 没有任何文本的项多为占位 / 加载骨架，不参与追踪
+    has_content.then(|| format!("{:x}", ctx.compute())[..16].to_string())
+}
+
+/// 提取可见列表项：指定 resource-id 的容器，缺省取面积最大的可滚动节点；项为容器的直接子节点
+pub fn visible_items(xml: &str, container_id: Option<&str>) -> Result<Vec<ListItem>, String> {
+    let doc = roxmltree::Document::parse(xml).map_err(|e| format!("解析UI Dump失败: {}", e))?;
+    let area = |n: &roxmltree::Node| {
+        n.attribute("bounds").and_then(parse_bounds).map(|(l, t, r, b)| (r - l) as i64 * (b - t) as i64).unwrap_or(0)
+    };
+    let container = match container_id {
+        Some(id) => doc.descendants().find(|n| n.attribute("resource-id") == Some(id)),
+        None => doc.descendants().filter(|n| n.attribute("scrollable") == Some("true")).max_by_key(area),
+    };
+    let Some(container) = container else {
+        return Err(match container_id {
+            Some(id) => format!("未找到列表容器: {}", id),
+            None => "页面上没有可滚动的列表容器".to_string(),
+        });
+    };
+    let frame = container.attribute("bounds").and_then(parse_bounds);
+
+    Ok(container
+        .children()
+        .filter(|n| n.is_element())
+        .filter_map(|node| {
+            let fingerprint = item_fingerprint(node)?;
+            let bounds = node.attribute("bounds").unwrap_or("").to_string();
+            let (clipped_top, clipped_bottom) = match (frame, parse_bounds(&bounds)) {
+                (Some((_, ft, _, fb)), Some((_, t, _, b))) => (t <= ft, b >= fb),
+                _ => (false, false),
+            };
+            Some(ListItem { fingerprint, bounds, clipped_top, clipped_bottom })
+        })
+        .collect())
+}
+
+/// 一页观察结果
+#[derive(Debug, Clone, Serialize)]
+pub struct PageObservation {
+    pub page: u32,
+    pub visible: usize,
+    /// 新出现的项在本页中的下标
+    pub new_indices: Vec<usize>,
+    /// 已见过（重渲染）的项数
+    pub repeated: usize,
+    /// 是否判定列表到底（连续无新项，或滚动后页面完全未变）
+    pub end_reached: bool,
+}
+
+/// 滚动进度估算
+#[derive(Debug, Clone, Serialize)]
+pub struct ScrollProgress {
+    pub pages: u32,
+    pub seen: usize,
+    /// 平均每页新增项数
+    pub avg_new_per_page: f64,
+    /// 最近一页新增项数（逐页下降通常意味着接近底部）
+    pub last_new: usize,
+    pub expected_total: Option<usize>,
+    /// 已知总数时为 seen/expected；未知总数时只有到底后为 1.0
+    pub estimated_fraction: Option<f64>,
+    pub end_reached: bool,
+}
+
+/// 单个采集会话的已见集合
+#[derive(Debug, Clone)]
+pub struct ListTracker {
+    seen: HashSet<String>,
+    pages: u32,
+    stale_pages: u32,
+    max_stale_pages: u32,
+    last_page: Vec<String>,
+    last_new: usize,
+    expected_total: Option<usize>,
+    end_reached: bool,
+}
+
+impl ListTracker {
+    pub fn new(expected_total: Option<usize>, max_stale_pages: u32) -> Self {
+        Self {
+            seen: HashSet::new(),
+            pages: 0,
+            stale_pages: 0,
+            max_stale_pages: max_stale_pages.max(1),
+            last_page: Vec::new(),
+            last_new: 0,
+            expected_total,
+            end_reached: false,
+        }
+    }
+
+    /// 记录一页指纹；同页内重复的指纹也只算一次
+    pub fn observe(&mut self, fingerprints: &[String]) -> PageObservation {
+        self.pages += 1;
+        let new_indices: Vec<usize> = fingerprints
+            .iter()
+            .enumerate()
+            .filter(|(_, fp)| self.seen.insert((*fp).clone()))
+            .map(|(i, _)| i)
+            .collect();
+
+        let unchanged = self.pages > 1 && !fingerprints.is_empty() && fingerprints == self.last_page.as_slice();
+        self.stale_pages = if new_indices.is_empty() { self.stale_pages + 1 } else { 0 };
+        self.end_reached = unchanged
+            || self.stale_pages >= self.max_stale_pages
+            || self.expected_total.is_some_and(|total| self.seen.len() >= total);
+        self.last_page = fingerprints.to_vec();
+        self.last_new = new_indices.len();
+
+        PageObservation {
+            page: self.pages,
+            visible: fingerprints.len(),
+            repeated: fingerprints.len() - new_indices.len(),
+            new_indices,
+            end_reached: self.end_reached,
+        }
+    }
+
+    /// 从 dump 中观察一页；贴底的项下一页完整出现时再计，贴顶的项上一页已完整见过（首页除外）
+    pub fn observe_xml(&mut self, xml: &str, container_id: Option<&str>) -> Result<PageObservation, String> {
+        let first_page = self.pages == 0;
+        let fingerprints: Vec<String> = visible_items(xml, container_id)?
+            .into_iter()
+            .filter(|item| !item.clipped_bottom && (first_page || !item.clipped_top))
+            .map(|item| item.fingerprint)
+            .collect();
+        Ok(self.observe(&fingerprints))
+    }
+
+    pub fn has_seen(&self, fingerprint: &str) -> bool {
+        self.seen.contains(fingerprint)
+    }
+
+    pub fn end_reached(&self) -> bool {
+        self.end_reached
+    }
+
+    pub fn progress(&self) -> ScrollProgress {
+        let seen = self.seen.len();
+        let avg_new_per_page = if self.pages == 0 { 0.0 } else { seen as f64 / self.pages as f64 };
+        let estimated_fraction = match self.expected_total {
+            Some(total) if total > 0 => Some((seen as f64 / total as f64).min(1.0)),
+            _ if self.end_reached => Some(1.0),
+            _ => None,
+        };
+        ScrollProgress {
+            pages: self.pages,
+            seen,
+            avg_new_per_page,
+            last_new: self.last_new,
+            expected_total: self.expected_total,
+            estimated_fraction,
+            end_reached: self.end_reached,
+        }
+    }
+}
+
+static SESSIONS: OnceLock<Mutex<HashMap<String, ListTracker>>> = OnceLock::new();
+
+fn sessions() -> &'static Mutex<HashMap<String, ListTracker>> {
+    SESSIONS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// 开始（或重置）一个采集会话
+pub fn start_session(session_id: &str, expected_total: Option<usize>, max_stale_pages: Option<u32>) -> Result<(), String> {
+    let tracker = ListTracker::new(expected_total, max_stale_pages.unwrap_or(DEFAULT_MAX_STALE_PAGES));
+    sessions().lock().map_err(|e| e.to_string())?.insert(session_id.to_string(), tracker);
+    Ok(())
+}
+
+/// 在会话中观察一页 dump
+pub fn observe_session_page(session_id: &str, xml: &str, container_id: Option<&str>) -> Result<PageObservation, String> {
+    let mut sessions = sessions().lock().map_err(|e| e.to_string())?;
+    let tracker = sessions.get_mut(session_id).ok_or_else(|| format!("列表追踪会话不存在: {}", session_id))?;
+    tracker.observe_xml(xml, container_id)
+}
+
+/// 丢弃全部会话（插件重新初始化时调用，锁中毒也一并恢复）
+pub fn clear_sessions() {
+    let sessions = sessions();
+    sessions.clear_poison();
+    if let Ok(mut sessions) = sessions.lock() {
+        sessions.clear();
+    }
+}
+
+/// 结束会话并返回最终进度
+pub fn end_session(session_id: &str) -> Result<ScrollProgress, String> {
+    let tracker = sessions()
+        .lock()
+        .map_err(|e| e.to_string())?
+        .remove(session_id)
+        .ok_or_else(|| format!("列表追踪会话不存在: {}", session_id))?;
+    Ok(tracker.progress())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn page(items: &[(&str, &str)]) -> String {
+        let nodes: String = items
+            .iter()
+            .map(|(text, bounds)| format!(r#"<node class="LinearLayout" bounds="{}"><node text="{}"/></node>"#, bounds, text))
+            .collect();
+        format!(
+            r#"<hierarchy><node class="RecyclerView" scrollable="true" bounds="[0,200][1080,2000]">{}</node></hierarchy>"#,
+            nodes
+        )
+    }
+
+    #[test]
+    fn tracks_new_items_across_scrolls_and_detects_end() {
+        let mut tracker = ListTracker::new(None, 2);
+
+        let first = tracker
+            .observe_xml(&page(&[("a", "[0,300][1080,800]"), ("b", "[0,800][1080,1400]"), ("c", "[0,1400][1080,2000]")]), None)
+            .unwrap();
+        // c 贴底，本页不计入
+        assert_eq!((first.visible, first.new_indices.len()), (2, 2));
+
+        let second = tracker
+            .observe_xml(&page(&[("b", "[0,250][1080,700]"), ("c", "[0,700][1080,1300]"), ("d", "[0,1300][1080,1900]")]), None)
+            .unwrap();
+        assert_eq!(second.new_indices, vec![1, 2]);
+        assert_eq!(second.repeated, 1);
+        assert!(!second.end_reached);
+
+        // 滚动无效，页面完全一致 → 到底
+        let third = tracker
+            .observe_xml(&page(&[("b", "[0,250][1080,700]"), ("c", "[0,700][1080,1300]"), ("d", "[0,1300][1080,1900]")]), None)
+            .unwrap();
+        assert!(third.new_indices.is_empty());
+        assert!(third.end_reached);
+
+        let progress = tracker.progress();
+        assert_eq!((progress.pages, progress.seen), (3, 4));
+        assert_eq!(progress.estimated_fraction, Some(1.0));
+
+        let mut known_total = ListTracker::new(Some(8), 2);
+        known_total.observe(&["x".to_string(), "y".to_string()]);
+        assert_eq!(known_total.progress().estimated_fraction, Some(0.25));
+    }
+}
@@ -93,8 +93,8 @@ impl LogCollector {
 
         // 实时发送到前端
         if let Some(app_handle) = &self.app_handle {
-            use crate::infrastructure::events::emit_and_trace;
-            let _ = emit_and_trace(app_handle, "log-entry", &log_entry);
+            use crate::infrastructure::events::emit_and_trace_for_device;
+            let _ = emit_and_trace_for_device(app_handle, log_entry.device_id.as_deref(), "log-entry", &log_entry);
         }
         
         // 🐛 修复：移除 tracing 输出，避免日志重复
@@ -147,8 +147,8 @@ impl LogCollector {
 
         // 实时发送到前端  
         if let Some(app_handle) = &self.app_handle {
-            use crate::infrastructure::events::emit_and_trace;
-            let _ = emit_and_trace(app_handle, "adb-command-log", &adb_log);
+            use crate::infrastructure::events::emit_and_trace_for_device;
+            let _ = emit_and_trace_for_device(app_handle, adb_log.device_id.as_deref(), "adb-command-log", &adb_log);
         }
 
         // 也作为普通日志记录
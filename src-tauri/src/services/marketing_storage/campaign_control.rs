@@ -0,0 +1,411 @@
+//! Campaign pause / resume protocol.
+//!
+//! 暂停不再只是停止派发：请求暂停后活动进入 DRAINING，`lock_next_ready_task` 不再租出
+//! 该活动的任务，执行器做完手头的原子动作后照常 `mark_task_result`；在途任务清空（或
+//! 超过排空期限，剩余任务退回 READY 且不计重试次数）时写入检查点并进入 PAUSED：
+//! 记录下一个要执行的任务、各状态计数、频控退避剩余秒数，并释放任务上的账号锁。
+//! 恢复时按检查点重新套上频控退避，队列从检查点位置继续。每一步都写入活动时间线。
+
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// 活动暂停状态变化事件
+pub const CAMPAIGN_STATE_EVENT: &str = "campaign://state";
+
+/// 默认排空期限（与任务默认租约一致）
+pub const DEFAULT_DRAIN_SECONDS: i64 = 120;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum CampaignRunStatus {
+    Running,
+    /// 已请求暂停，等待在途任务完成
+    Draining,
+    Paused,
+}
+
+impl CampaignRunStatus {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Running => "RUNNING",
+            Self::Draining => "DRAINING",
+            Self::Paused => "PAUSED",
+        }
+    }
+
+    fn parse(value: &str) -> Self {
+        match value {
+            "DRAINING" => Self::Draining,
+            "PAUSED" => Self::Paused,
+            _ => Self::Running,
+        }
+    }
+}
+
+/// 暂停检查点
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CampaignCheckpoint {
+    /// 恢复后第一个会被租出的任务
+    pub next_task_id: Option<String>,
+    pub last_completed_task_id: Option<String>,
+    pub done: i64,
+    pub failed: i64,
+    pub remaining: i64,
+    /// 频控退避：任务 → 暂停时剩余的秒数
+    pub backoff_seconds: BTreeMap<String, i64>,
+    /// 排空超时被退回 READY 的任务
+    pub interrupted: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CampaignControlState {
+    pub campaign_id: String,
+    pub status: CampaignRunStatus,
+    pub pause_requested_at: Option<String>,
+    pub drain_deadline: Option<String>,
+    pub paused_at: Option<String>,
+    pub checkpoint: Option<CampaignCheckpoint>,
+    /// 当前在途（EXECUTING）的任务数
+    pub in_flight: i64,
+    pub updated_at: String,
+}
+
+/// 活动时间线上的一条记录
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CampaignTimelineEntry {
+    pub id: i64,
+    pub campaign_id: String,
+    /// PAUSE_REQUESTED / PAUSED / DRAIN_TIMEOUT / RESUMED / PAUSE_CANCELLED
+    pub kind: String,
+    pub at: String,
+    pub detail: Option<serde_json::Value>,
+}
+
+fn in_flight(conn: &Connection, campaign_id: &str) -> rusqlite::Result<i64> {
+    conn.query_row(
+        "SELECT COUNT(*) FROM tasks WHERE campaign_id = ?1 AND status = 'EXECUTING'",
+        params![campaign_id],
+        |row| row.get(0),
+    )
+}
+
+fn append_timeline(conn: &Connection, campaign_id: &str, kind: &str, detail: serde_json::Value) -> rusqlite::Result<()> {
+    conn.execute(
+        "INSERT INTO campaign_timeline (campaign_id, kind, at, detail) VALUES (?1, ?2, datetime('now'), ?3)",
+        params![campaign_id, kind, detail.to_string()],
+    )?;
+    Ok(())
+}
+
+/// 活动当前的暂停状态；从未暂停过的活动视为 RUNNING
+pub fn get_state(conn: &Connection, campaign_id: &str) -> rusqlite::Result<CampaignControlState> {
+    let row = conn
+        .query_row(
+            "SELECT status, pause_requested_at, drain_deadline, paused_at, checkpoint, updated_at FROM campaign_state WHERE campaign_id = ?1",
+            params![campaign_id],
+            |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, Option<String>>(1)?,
+                    row.get::<_, Option<String>>(2)?,
+                    row.get::<_, Option<String>>(3)?,
+                    row.get::<_, Option<String>>(4)?,
+                    row.get::<_, String>(5)?,
+                ))
+            },
+        )
+        .optional()?;
+    let in_flight = in_flight(conn, campaign_id)?;
+    Ok(match row {
+        Some((status, pause_requested_at, drain_deadline, paused_at, checkpoint, updated_at)) => CampaignControlState {
+            campaign_id: campaign_id.to_string(),
+            status: CampaignRunStatus::parse(&status),
+            pause_requested_at,
+            drain_deadline,
+            paused_at,
+            checkpoint: checkpoint.and_then(|c| serde_json::from_str(&c).ok()),
+            in_flight,
+            updated_at,
+        },
+        None => CampaignControlState {
+            campaign_id: campaign_id.to_string(),
+            status: CampaignRunStatus::Running,
+            pause_requested_at: None,
+            drain_deadline: None,
+            paused_at: None,
+            checkpoint: None,
+            in_flight,
+            updated_at: String::new(),
+        },
+    })
+}
+
+/// 执行器在原子动作之间调用：任务所属活动正在暂停时应尽快收尾并上报结果
+pub fn should_yield(conn: &Connection, task_id: &str) -> rusqlite::Result<bool> {
+    conn.query_row(
+        "SELECT EXISTS(SELECT 1 FROM tasks t JOIN campaign_state s ON s.campaign_id = t.campaign_id
+                       WHERE t.id = ?1 AND s.status != 'RUNNING')",
+        params![task_id],
+        |row| row.get(0),
+    )
+}
+
+/// 请求暂停：停止派发并开始排空；没有在途任务时立即完成暂停
+pub fn request_pause(conn: &mut Connection, campaign_id: &str, drain_seconds: i64, reason: Option<&str>) -> rusqlite::Result<CampaignControlState> {
+    let current = get_state(conn, campaign_id)?;
+    if current.status != CampaignRunStatus::Running {
+        return Ok(current);
+    }
+    let drain_seconds = if drain_seconds <= 0 { DEFAULT_DRAIN_SECONDS } else { drain_seconds };
+    let tx = conn.transaction()?;
+    tx.execute(
+        r#"INSERT INTO campaign_state (campaign_id, status, pause_requested_at, drain_deadline, paused_at, checkpoint, updated_at)
+           VALUES (?1, 'DRAINING', datetime('now'), datetime('now', printf('+%d seconds', ?2)), NULL, NULL, datetime('now'))
+           ON CONFLICT(campaign_id) DO UPDATE SET status = 'DRAINING', pause_requested_at = excluded.pause_requested_at,
+             drain_deadline = excluded.drain_deadline, paused_at = NULL, updated_at = excluded.updated_at"#,
+        params![campaign_id, drain_seconds],
+    )?;
+    let in_flight = in_flight(&tx, campaign_id)?;
+    append_timeline(&tx, campaign_id, "PAUSE_REQUESTED", serde_json::json!({ "inFlight": in_flight, "drainSeconds": drain_seconds, "reason": reason }))?;
+    tx.commit()?;
+    try_complete_pause(conn, campaign_id, false)
+}
+
+/// 排空完成（或 `force` / 超过排空期限）时写检查点并进入 PAUSED；否则保持 DRAINING
+pub fn try_complete_pause(conn: &mut Connection, campaign_id: &str, force: bool) -> rusqlite::Result<CampaignControlState> {
+    let state = get_state(conn, campaign_id)?;
+    if state.status != CampaignRunStatus::Draining {
+        return Ok(state);
+    }
+    let expired: bool = conn.query_row(
+        "SELECT drain_deadline IS NOT NULL AND drain_deadline <= datetime('now') FROM campaign_state WHERE campaign_id = ?1",
+        params![campaign_id],
+        |row| row.get(0),
+    )?;
+    if state.in_flight > 0 && !force && !expired {
+        return Ok(state);
+    }
+
+    let tx = conn.transaction()?;
+    // 排空超时：未上报的任务退回队列，本次不计重试
+    let interrupted: Vec<String> = {
+        let mut stmt = tx.prepare("SELECT id FROM tasks WHERE campaign_id = ?1 AND status = 'EXECUTING' ORDER BY id")?;
+        let rows = stmt.query_map(params![campaign_id], |row| row.get(0))?;
+        rows.collect::<rusqlite::Result<_>>()?
+    };
+    tx.execute(
+        "UPDATE tasks SET status = 'READY', attempts = MAX(attempts - 1, 0), lock_owner = NULL, lease_until = NULL
+         WHERE campaign_id = ?1 AND status = 'EXECUTING'",
+        params![campaign_id],
+    )?;
+    // 频控退避：记录剩余秒数，恢复时再套上（暂停期间不消耗退避时间）
+    let backoff_seconds: BTreeMap<String, i64> = {
+        let mut stmt = tx.prepare(
+            "SELECT id, CAST(strftime('%s', lease_until) - strftime('%s', 'now') AS INTEGER) FROM tasks
+             WHERE campaign_id = ?1 AND status IN ('NEW', 'READY') AND lease_until > datetime('now')",
+        )?;
+        let rows = stmt.query_map(params![campaign_id], |row| Ok((row.get(0)?, row.get(1)?)))?;
+        rows.collect::<rusqlite::Result<_>>()?
+    };
+    // 释放账号锁与租约
+    tx.execute(
+        "UPDATE tasks SET lock_owner = NULL, lease_until = NULL WHERE campaign_id = ?1 AND status IN ('NEW', 'READY')",
+        params![campaign_id],
+    )?;
+
+    let count = |status: &str| -> rusqlite::Result<i64> {
+        tx.query_row("SELECT COUNT(*) FROM tasks WHERE campaign_id = ?1 AND status = ?2", params![campaign_id, status], |row| row.get(0))
+    };
+    let (done, failed) = (count("DONE")?, count("FAILED")?);
+    let remaining: i64 = tx.query_row(
+        "SELECT COUNT(*) FROM tasks WHERE campaign_id = ?1 AND status IN ('NEW', 'READY')",
+        params![campaign_id],
+        |row| row.get(0),
+    )?;
+    let next_task_id: Option<String> = tx
+        .query_row(
+            r#"SELECT t.id FROM tasks t LEFT JOIN task_queue q ON q.task_id = t.id
+               WHERE t.campaign_id = ?1 AND t.status = 'READY'
+               ORDER BY q.pinned_at IS NULL, q.pinned_at ASC, COALESCE(q.score, 0) DESC, t.priority ASC, t.created_at ASC LIMIT 1"#,
+            params![campaign_id],
+            |row| row.get(0),
+        )
+        .optional()?;
+    let last_completed_task_id: Option<String> = tx
+        .query_row(
+            "SELECT id FROM tasks WHERE campaign_id = ?1 AND status IN ('DONE', 'FAILED') ORDER BY executed_at DESC LIMIT 1",
+            params![campaign_id],
+            |row| row.get(0),
+        )
+        .optional()?;
+    let checkpoint = CampaignCheckpoint { next_task_id, last_completed_task_id, done, failed, remaining, backoff_seconds, interrupted };
+
+    tx.execute(
+        "UPDATE campaign_state SET status = 'PAUSED', paused_at = datetime('now'), checkpoint = ?2, updated_at = datetime('now') WHERE campaign_id = ?1",
+        params![campaign_id, serde_json::to_string(&checkpoint).unwrap_or_default()],
+    )?;
+    if !checkpoint.interrupted.is_empty() {
+        append_timeline(&tx, campaign_id, "DRAIN_TIMEOUT", serde_json::json!({ "requeued": checkpoint.interrupted }))?;
+    }
+    append_timeline(&tx, campaign_id, "PAUSED", serde_json::to_value(&checkpoint).unwrap_or_default())?;
+    tx.commit()?;
+    get_state(conn, campaign_id)
+}
+
+/// 处理所有超过排空期限仍在 DRAINING 的活动，返回完成暂停的活动
+pub fn enforce_drain_deadlines(conn: &mut Connection) -> rusqlite::Result<Vec<CampaignControlState>> {
+    let expired: Vec<String> = {
+        let mut stmt = conn.prepare("SELECT campaign_id FROM campaign_state WHERE status = 'DRAINING' AND drain_deadline <= datetime('now')")?;
+        let rows = stmt.query_map([], |row| row.get(0))?;
+        rows.collect::<rusqlite::Result<_>>()?
+    };
+    expired.iter().map(|id| try_complete_pause(conn, id, true)).collect()
+}
+
+/// 任务上报结果后调用：所属活动在排空中时检查是否可以完成暂停
+pub fn on_task_settled(conn: &mut Connection, task_id: &str) -> rusqlite::Result<Option<CampaignControlState>> {
+    let campaign_id: Option<String> = conn
+        .query_row(
+            "SELECT t.campaign_id FROM tasks t JOIN campaign_state s ON s.campaign_id = t.campaign_id
+             WHERE t.id = ?1 AND s.status = 'DRAINING'",
+            params![task_id],
+            |row| row.get(0),
+        )
+        .optional()?;
+    match campaign_id {
+        Some(id) => try_complete_pause(conn, &id, false).map(Some),
+        None => Ok(None),
+    }
+}
+
+/// 恢复活动：排空中则取消暂停；已暂停则按检查点恢复频控退避后继续派发
+pub fn resume(conn: &mut Connection, campaign_id: &str) -> rusqlite::Result<CampaignControlState> {
+    let state = get_state(conn, campaign_id)?;
+    let tx = conn.transaction()?;
+    match state.status {
+        CampaignRunStatus::Running => return Ok(state),
+        CampaignRunStatus::Draining => {
+            append_timeline(&tx, campaign_id, "PAUSE_CANCELLED", serde_json::json!({ "inFlight": state.in_flight }))?;
+        }
+        CampaignRunStatus::Paused => {
+            let checkpoint = state.checkpoint.clone().unwrap_or_default();
+            for (task_id, seconds) in &checkpoint.backoff_seconds {
+                tx.execute(
+                    "UPDATE tasks SET lease_until = datetime('now', printf('+%d seconds', ?2)) WHERE id = ?1 AND status IN ('NEW', 'READY')",
+                    params![task_id, seconds],
+                )?;
+            }
+            let paused_seconds: Option<i64> = tx.query_row(
+                "SELECT CAST(strftime('%s', 'now') - strftime('%s', paused_at) AS INTEGER) FROM campaign_state WHERE campaign_id = ?1",
+                params![campaign_id],
+                |row| row.get(0),
+            )?;
+            append_timeline(
+                &tx,
+                campaign_id,
+                "RESUMED",
+                serde_json::json!({
+                    "pausedSeconds": paused_seconds,
+                    "nextTaskId": checkpoint.next_task_id,
+                    "restoredBackoffs": checkpoint.backoff_seconds.len(),
+                }),
+            )?;
+        }
+    }
+    tx.execute(
+        "UPDATE campaign_state SET status = 'RUNNING', drain_deadline = NULL, updated_at = datetime('now') WHERE campaign_id = ?1",
+        params![campaign_id],
+    )?;
+    tx.commit()?;
+    get_state(conn, campaign_id)
+}
+
+/// 活动时间线（新的在前）
+pub fn timeline(conn: &Connection, campaign_id: &str, limit: usize) -> rusqlite::Result<Vec<CampaignTimelineEntry>> {
+    let mut stmt = conn.prepare("SELECT id, campaign_id, kind, at, detail FROM campaign_timeline WHERE campaign_id = ?1 ORDER BY id DESC LIMIT ?2")?;
+    let rows = stmt.query_map(params![campaign_id, limit as i64], |row| {
+        Ok(CampaignTimelineEntry {
+            id: row.get(0)?,
+            campaign_id: row.get(1)?,
+            kind: row.get(2)?,
+            at: row.get(3)?,
+            detail: row.get::<_, Option<String>>(4)?.and_then(|d| serde_json::from_str(&d).ok()),
+        })
+    })?;
+    rows.collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::marketing_storage::models::{ExecutorMode, TaskPayload, TaskResultCode, TaskType};
+    use crate::services::marketing_storage::repositories as repo;
+
+    fn task(conn: &Connection, user: &str, campaign: &str, priority: i32) -> String {
+        let id = repo::insert_task(
+            conn,
+            &TaskPayload {
+                task_type: TaskType::Follow,
+                comment_id: None,
+                target_user_id: Some(user.to_string()),
+                assign_account_id: "acc".into(),
+                executor_mode: ExecutorMode::Api,
+                dedup_key: format!("follow:{}", user),
+                priority: Some(priority),
+                deadline_at: None,
+                campaign_id: Some(campaign.to_string()),
+            },
+        )
+        .unwrap();
+        conn.execute("UPDATE tasks SET status = 'READY' WHERE id = ?1", params![id]).unwrap();
+        id
+    }
+
+    #[test]
+    fn drains_in_flight_then_checkpoints_and_resumes_backoff() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        repo::ensure_marketing_tables(&conn).unwrap();
+        let (a, b, c) = (task(&conn, "u1", "c1", 1), task(&conn, "u2", "c1", 2), task(&conn, "u3", "c1", 3));
+        let other = task(&conn, "u4", "c2", 4);
+
+        // a 在途；b 命中频控进入退避
+        let leased = repo::lock_next_ready_task(&mut conn, "acc", 60).unwrap().unwrap();
+        assert_eq!(leased.id, a);
+        conn.execute("UPDATE tasks SET lease_until = datetime('now', '+300 seconds'), result_code = 'RATE_LIMITED' WHERE id = ?1", params![b])
+            .unwrap();
+
+        let draining = request_pause(&mut conn, "c1", 60, Some("manual")).unwrap();
+        assert_eq!((draining.status, draining.in_flight), (CampaignRunStatus::Draining, 1));
+        assert!(should_yield(&conn, &a).unwrap());
+        // 排空期间不再租出本活动的任务，其他活动照常
+        assert_eq!(repo::lock_next_ready_task(&mut conn, "acc", 60).unwrap().map(|t| t.id), Some(other));
+
+        repo::mark_task_result(&mut conn, &a, Some(TaskResultCode::Ok), None).unwrap();
+        let paused = on_task_settled(&mut conn, &a).unwrap().unwrap();
+        assert_eq!(paused.status, CampaignRunStatus::Paused);
+        let checkpoint = paused.checkpoint.unwrap();
+        assert_eq!((checkpoint.done, checkpoint.remaining), (1, 2));
+        assert_eq!(checkpoint.last_completed_task_id.as_deref(), Some(a.as_str()));
+        assert_eq!(checkpoint.next_task_id.as_deref(), Some(b.as_str()));
+        assert!((295..=300).contains(&checkpoint.backoff_seconds[&b]));
+        let lease: Option<String> = conn.query_row("SELECT lease_until FROM tasks WHERE id = ?1", params![b], |r| r.get(0)).unwrap();
+        assert_eq!(lease, None);
+
+        // 恢复后退避重新生效，队列从未退避的任务继续
+        assert_eq!(resume(&mut conn, "c1").unwrap().status, CampaignRunStatus::Running);
+        assert_eq!(repo::lock_next_ready_task(&mut conn, "acc", 60).unwrap().map(|t| t.id), Some(c));
+        let kinds: Vec<String> = timeline(&conn, "c1", 10).unwrap().into_iter().map(|e| e.kind).collect();
+        assert_eq!(kinds, vec!["RESUMED", "PAUSED", "PAUSE_REQUESTED"]);
+
+        // 排空超时：强制暂停时在途任务退回队列
+        request_pause(&mut conn, "c1", 60, None).unwrap();
+        let forced = try_complete_pause(&mut conn, "c1", true).unwrap();
+        assert_eq!(forced.checkpoint.unwrap().interrupted, vec![c.clone()]);
+        let status: String = conn.query_row("SELECT status FROM tasks WHERE id = ?1", params![c], |r| r.get(0)).unwrap();
+        assert_eq!(status, "READY");
+    }
+}
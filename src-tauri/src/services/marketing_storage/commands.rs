@@ -8,15 +8,23 @@ use super::models::{
     TaskPayload, TaskRow, TaskStatus, TaskType, TaskResultCode,
     AuditLogPayload,
     ReplyTemplatePayload, ReplyTemplateRow,
-    MarketingPlatform, TargetType,
+    MarketingPlatform, TargetType, CommentLanguage,
 };
 use super::facade::MarketingStorageFacade;
+use crate::services::report_export::{ExportOptions, ExportOutcome};
+use super::idempotency::with_idempotency;
 
 // ==================== 候选池相关命令 ====================
 
 #[tauri::command]
-pub fn bulk_upsert_watch_targets(app_handle: tauri::AppHandle, payloads: Vec<WatchTargetPayload>) -> Result<usize, String> {
-    MarketingStorageFacade::bulk_upsert_watch_targets(&app_handle, payloads)
+pub fn bulk_upsert_watch_targets(
+    app_handle: tauri::AppHandle,
+    payloads: Vec<WatchTargetPayload>,
+    idempotency_key: Option<String>,
+) -> Result<usize, String> {
+    with_idempotency(&app_handle, idempotency_key.as_deref(), "bulk_upsert_watch_targets", || {
+        MarketingStorageFacade::bulk_upsert_watch_targets(&app_handle, payloads)
+    })
 }
 
 #[tauri::command]
@@ -58,23 +66,32 @@ pub fn update_watch_target(
     region: Option<String>,
     notes: Option<String>,
     last_fetch_at: Option<String>,
+    idempotency_key: Option<String>,
 ) -> Result<(), String> {
-    MarketingStorageFacade::update_watch_target(
-        &app_handle,
-        &id,
-        title.as_deref(),
-        industry_tags.as_deref(),
-        region.as_deref(),
-        notes.as_deref(),
-        last_fetch_at.as_deref(),
-    )
+    with_idempotency(&app_handle, idempotency_key.as_deref(), "update_watch_target", || {
+        MarketingStorageFacade::update_watch_target(
+            &app_handle,
+            &id,
+            title.as_deref(),
+            industry_tags.as_deref(),
+            region.as_deref(),
+            notes.as_deref(),
+            last_fetch_at.as_deref(),
+        )
+    })
 }
 
 // ==================== 评论相关命令 ====================
 
 #[tauri::command]
-pub fn insert_comment(app_handle: tauri::AppHandle, comment: CommentPayload) -> Result<String, String> {
-    MarketingStorageFacade::insert_comment(&app_handle, comment)
+pub fn insert_comment(
+    app_handle: tauri::AppHandle,
+    comment: CommentPayload,
+    idempotency_key: Option<String>,
+) -> Result<String, String> {
+    with_idempotency(&app_handle, idempotency_key.as_deref(), "insert_comment", || {
+        MarketingStorageFacade::insert_comment(&app_handle, comment)
+    })
 }
 
 #[tauri::command]
@@ -85,16 +102,24 @@ pub fn list_comments(
     platform: Option<String>,
     source_target_id: Option<String>,
     region: Option<String>,
+    language: Option<String>,
 ) -> Result<Vec<CommentRow>, String> {
     let platform_enum = platform.map(MarketingPlatform::from);
-    MarketingStorageFacade::list_comments(&app_handle, limit, offset, platform_enum, source_target_id, region)
+    let language = language.as_deref().map(CommentLanguage::parse);
+    MarketingStorageFacade::list_comments(&app_handle, limit, offset, platform_enum, source_target_id, region, language)
 }
 
 // ==================== 任务相关命令 ====================
 
 #[tauri::command]
-pub fn insert_task(app_handle: tauri::AppHandle, task: TaskPayload) -> Result<String, String> {
-    MarketingStorageFacade::insert_task(&app_handle, task)
+pub fn insert_task(
+    app_handle: tauri::AppHandle,
+    task: TaskPayload,
+    idempotency_key: Option<String>,
+) -> Result<String, String> {
+    with_idempotency(&app_handle, idempotency_key.as_deref(), "insert_task", || {
+        MarketingStorageFacade::insert_task(&app_handle, task)
+    })
 }
 
 #[tauri::command]
@@ -104,8 +129,11 @@ pub fn update_task_status(
     status: TaskStatus,
     result_code: Option<TaskResultCode>,
     error_message: Option<String>,
+    idempotency_key: Option<String>,
 ) -> Result<(), String> {
-    MarketingStorageFacade::update_task_status(&app_handle, &task_id, status, result_code, error_message.as_deref())
+    with_idempotency(&app_handle, idempotency_key.as_deref(), "update_task_status", || {
+        MarketingStorageFacade::update_task_status(&app_handle, &task_id, status, result_code, error_message.as_deref())
+    })
 }
 
 #[tauri::command]
@@ -135,16 +163,25 @@ pub fn mark_task_result(
     task_id: String,
     result_code: Option<TaskResultCode>,
     error_message: Option<String>,
+    idempotency_key: Option<String>,
 ) -> Result<(), String> {
-    MarketingStorageFacade::mark_task_result(&app_handle, &task_id, result_code, error_message.as_deref())
+    with_idempotency(&app_handle, idempotency_key.as_deref(), "mark_task_result", || {
+        MarketingStorageFacade::mark_task_result(&app_handle, &task_id, result_code, error_message.as_deref())
+    })
 }
 
 
 // ==================== 审计日志相关命令 ====================
 
 #[tauri::command]
-pub fn insert_audit_log(app_handle: tauri::AppHandle, log: AuditLogPayload) -> Result<String, String> {
-    MarketingStorageFacade::insert_audit_log(&app_handle, log)
+pub fn insert_audit_log(
+    app_handle: tauri::AppHandle,
+    log: AuditLogPayload,
+    idempotency_key: Option<String>,
+) -> Result<String, String> {
+    with_idempotency(&app_handle, idempotency_key.as_deref(), "insert_audit_log", || {
+        MarketingStorageFacade::insert_audit_log(&app_handle, log)
+    })
 }
 
 // ==================== 去重索引（带TTL） ====================
@@ -180,8 +217,26 @@ pub fn export_audit_logs(
     start_time: Option<String>,
     end_time: Option<String>,
     format: Option<String>,
+    locale: Option<String>,
 ) -> Result<String, String> {
-    MarketingStorageFacade::export_audit_logs(&app_handle, start_time.as_deref(), end_time.as_deref(), format.as_deref())
+    MarketingStorageFacade::export_audit_logs(&app_handle, start_time.as_deref(), end_time.as_deref(), format.as_deref(), locale.as_deref())
+}
+
+#[tauri::command]
+pub async fn export_audit_logs_to_file(
+    app_handle: tauri::AppHandle,
+    start_time: Option<String>,
+    end_time: Option<String>,
+    format: Option<String>,
+    locale: Option<String>,
+    output_path: Option<String>,
+) -> Result<ExportOutcome, String> {
+    let options = ExportOptions::from_args(format.as_deref(), locale.as_deref(), output_path)?;
+    tokio::task::spawn_blocking(move || {
+        MarketingStorageFacade::export_audit_logs_to_file(&app_handle, start_time.as_deref(), end_time.as_deref(), options)
+    })
+    .await
+    .map_err(|e| e.to_string())?
 }
 
 #[tauri::command]
@@ -192,12 +247,21 @@ pub fn cleanup_expired_audit_logs(
     MarketingStorageFacade::cleanup_expired_audit_logs(&app_handle, retention_days)
 }
 
+#[tauri::command]
+pub fn cleanup_expired_idempotency_keys(app_handle: tauri::AppHandle) -> Result<usize, String> {
+    let conn = super::repositories::get_connection(&app_handle).map_err(|e| e.to_string())?;
+    super::repositories::cleanup_expired_idempotency_keys(&conn).map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 pub fn batch_store_audit_logs(
     app_handle: tauri::AppHandle,
     logs: Vec<AuditLogPayload>,
+    idempotency_key: Option<String>,
 ) -> Result<i64, String> {
-    MarketingStorageFacade::batch_store_audit_logs(&app_handle, logs)
+    with_idempotency(&app_handle, idempotency_key.as_deref(), "batch_store_audit_logs", || {
+        MarketingStorageFacade::batch_store_audit_logs(&app_handle, logs)
+    })
 }
 
 // ==================== 回复模板相关命令 ====================
@@ -206,8 +270,11 @@ pub fn batch_store_audit_logs(
 pub fn insert_reply_template(
     app_handle: tauri::AppHandle,
     payload: ReplyTemplatePayload,
+    idempotency_key: Option<String>,
 ) -> Result<String, String> {
-    MarketingStorageFacade::insert_reply_template(&app_handle, payload)
+    with_idempotency(&app_handle, idempotency_key.as_deref(), "insert_reply_template", || {
+        MarketingStorageFacade::insert_reply_template(&app_handle, payload)
+    })
 }
 
 #[tauri::command]
@@ -217,8 +284,10 @@ pub fn list_reply_templates(
     offset: Option<i64>,
     channel: Option<String>,
     enabled: Option<bool>,
+    language: Option<String>,
 ) -> Result<Vec<ReplyTemplateRow>, String> {
-    MarketingStorageFacade::list_reply_templates(&app_handle, limit, offset, channel, enabled)
+    let language = language.as_deref().map(CommentLanguage::parse);
+    MarketingStorageFacade::list_reply_templates(&app_handle, limit, offset, channel, enabled, language)
 }
 
 /// Alias for list_reply_templates (frontend expects get_reply_templates with no params)
@@ -226,7 +295,7 @@ pub fn list_reply_templates(
 pub fn get_reply_templates(
     app_handle: tauri::AppHandle,
 ) -> Result<Vec<ReplyTemplateRow>, String> {
-    list_reply_templates(app_handle, None, None, None, None)
+    list_reply_templates(app_handle, None, None, None, None, None)
 }
 
 #[tauri::command]
@@ -239,16 +308,39 @@ pub fn update_reply_template(
     variables: Option<String>,
     category: Option<String>,
     enabled: Option<bool>,
+    language: Option<String>,
+    idempotency_key: Option<String>,
 ) -> Result<(), String> {
-    MarketingStorageFacade::update_reply_template(
+    with_idempotency(&app_handle, idempotency_key.as_deref(), "update_reply_template", || {
+        MarketingStorageFacade::update_reply_template(
+            &app_handle,
+            &id,
+            template_name.as_deref(),
+            channel.as_deref(),
+            text.as_deref(),
+            variables.as_deref(),
+            category.as_deref(),
+            enabled,
+            language.as_deref().map(CommentLanguage::parse),
+        )
+    })
+}
+
+/// 按评论语言选回复模板：给 `comment_id` 时取入库识别的语言，也可直接给 `language`
+#[tauri::command]
+pub fn select_reply_template(
+    app_handle: tauri::AppHandle,
+    comment_id: Option<String>,
+    language: Option<String>,
+    channel: Option<String>,
+    category: Option<String>,
+) -> Result<Option<ReplyTemplateRow>, String> {
+    MarketingStorageFacade::select_reply_template(
         &app_handle,
-        &id,
-        template_name.as_deref(),
+        comment_id.as_deref(),
+        language.as_deref().map(CommentLanguage::parse),
         channel.as_deref(),
-        text.as_deref(),
-        variables.as_deref(),
         category.as_deref(),
-        enabled,
     )
 }
 
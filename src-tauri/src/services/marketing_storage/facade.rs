@@ -9,9 +9,14 @@ use super::models::{
     TaskPayload, TaskRow, ListTasksQuery, TaskStatus, TaskType, TaskResultCode,
     AuditLogPayload,
     ReplyTemplatePayload, ReplyTemplateRow, ListReplyTemplatesQuery,
-    MarketingPlatform, TargetType,
+    MarketingPlatform, TargetType, CommentLanguage,
 };
 use super::repositories as repo;
+use super::task_queue::{self, QueueWeights, QueuedTask};
+use super::campaign_control::{self, CampaignControlState, CampaignTimelineEntry, CAMPAIGN_STATE_EVENT};
+use crate::services::protected_accounts;
+use crate::services::warmup;
+use crate::services::report_export::{self, ExportOptions, ExportOutcome};
 
 pub struct MarketingStorageFacade;
 
@@ -75,15 +80,20 @@ impl MarketingStorageFacade {
         platform: Option<MarketingPlatform>,
         source_target_id: Option<String>,
         region: Option<String>,
+        language: Option<CommentLanguage>,
     ) -> Result<Vec<CommentRow>, String> {
         let conn = repo::get_connection(app_handle).map_err(|e| e.to_string())?;
-        let q = ListCommentsQuery { limit, offset, platform, source_target_id, region };
+        let q = ListCommentsQuery { limit, offset, platform, source_target_id, region, language };
         repo::list_comments(&conn, &q).map_err(|e| e.to_string())
     }
 
     // ==================== 任务相关 ====================
 
     pub fn insert_task(app_handle: &AppHandle, task: TaskPayload) -> Result<String, String> {
+        // 活动任务只分配给已完成养号的账号
+        if task.campaign_id.is_some() {
+            warmup::ensure_ready_for_campaign(&task.assign_account_id)?;
+        }
         let conn = repo::get_connection(app_handle).map_err(|e| e.to_string())?;
         repo::insert_task(&conn, &task).map_err(|e| e.to_string())
     }
@@ -119,7 +129,14 @@ impl MarketingStorageFacade {
     ) -> Result<Option<TaskRow>, String> {
         let mut conn = repo::get_connection(app_handle).map_err(|e| e.to_string())?;
         let lease = lease_seconds.unwrap_or(120);
-        repo::lock_next_ready_task(&mut conn, account_id, lease).map_err(|e| e.to_string())
+        // 超过排空期限的暂停先落地，避免其任务在期限后被重新租出
+        for state in campaign_control::enforce_drain_deadlines(&mut conn).map_err(|e| e.to_string())? {
+            emit_campaign_state(app_handle, &state);
+        }
+        repo::lock_next_ready_task_guarded(&mut conn, account_id, lease, |task_type, platform, target| {
+            protected_accounts::guard(platform, target, task_type, "task_queue").map(|hit| hit.reason)
+        })
+        .map_err(|e| e.to_string())
     }
 
     pub fn mark_task_result(
@@ -129,7 +146,93 @@ impl MarketingStorageFacade {
         error_message: Option<&str>,
     ) -> Result<(), String> {
         let mut conn = repo::get_connection(app_handle).map_err(|e| e.to_string())?;
-        repo::mark_task_result(&mut conn, task_id, result_code, error_message).map_err(|e| e.to_string())
+        repo::mark_task_result(&mut conn, task_id, result_code, error_message).map_err(|e| e.to_string())?;
+        // 所属活动在排空中：最后一个在途任务上报后完成暂停
+        if let Some(state) = campaign_control::on_task_settled(&mut conn, task_id).map_err(|e| e.to_string())? {
+            if state.status != campaign_control::CampaignRunStatus::Draining {
+                emit_campaign_state(app_handle, &state);
+            }
+        }
+        Ok(())
+    }
+
+    // ==================== 活动暂停 / 恢复 ====================
+
+    pub fn pause_campaign(
+        app_handle: &AppHandle,
+        campaign_id: &str,
+        drain_seconds: Option<i64>,
+        reason: Option<&str>,
+    ) -> Result<CampaignControlState, String> {
+        let mut conn = repo::get_connection(app_handle).map_err(|e| e.to_string())?;
+        let drain = drain_seconds.unwrap_or(campaign_control::DEFAULT_DRAIN_SECONDS);
+        let state = campaign_control::request_pause(&mut conn, campaign_id, drain, reason).map_err(|e| e.to_string())?;
+        emit_campaign_state(app_handle, &state);
+        Ok(state)
+    }
+
+    /// 排空期限到达后调用：仍在 DRAINING 时把剩余在途任务退回队列并完成暂停
+    pub fn enforce_campaign_drain(app_handle: &AppHandle, campaign_id: &str) -> Result<CampaignControlState, String> {
+        let mut conn = repo::get_connection(app_handle).map_err(|e| e.to_string())?;
+        let before = campaign_control::get_state(&conn, campaign_id).map_err(|e| e.to_string())?;
+        let state = campaign_control::try_complete_pause(&mut conn, campaign_id, false).map_err(|e| e.to_string())?;
+        if before.status != state.status {
+            emit_campaign_state(app_handle, &state);
+        }
+        Ok(state)
+    }
+
+    pub fn resume_campaign(app_handle: &AppHandle, campaign_id: &str) -> Result<CampaignControlState, String> {
+        let mut conn = repo::get_connection(app_handle).map_err(|e| e.to_string())?;
+        let state = campaign_control::resume(&mut conn, campaign_id).map_err(|e| e.to_string())?;
+        emit_campaign_state(app_handle, &state);
+        Ok(state)
+    }
+
+    pub fn get_campaign_state(app_handle: &AppHandle, campaign_id: &str) -> Result<CampaignControlState, String> {
+        let conn = repo::get_connection(app_handle).map_err(|e| e.to_string())?;
+        campaign_control::get_state(&conn, campaign_id).map_err(|e| e.to_string())
+    }
+
+    pub fn list_campaign_timeline(app_handle: &AppHandle, campaign_id: &str, limit: usize) -> Result<Vec<CampaignTimelineEntry>, String> {
+        let conn = repo::get_connection(app_handle).map_err(|e| e.to_string())?;
+        campaign_control::timeline(&conn, campaign_id, limit).map_err(|e| e.to_string())
+    }
+
+    pub fn campaign_should_yield(app_handle: &AppHandle, task_id: &str) -> Result<bool, String> {
+        let conn = repo::get_connection(app_handle).map_err(|e| e.to_string())?;
+        campaign_control::should_yield(&conn, task_id).map_err(|e| e.to_string())
+    }
+
+    // ==================== 优先队列相关 ====================
+
+    pub fn preview_next_targets(
+        app_handle: &AppHandle,
+        campaign_id: Option<&str>,
+        n: usize,
+        weights: Option<QueueWeights>,
+    ) -> Result<Vec<QueuedTask>, String> {
+        let conn = repo::get_connection(app_handle).map_err(|e| e.to_string())?;
+        task_queue::preview_next(&conn, campaign_id, n, &weights.unwrap_or_default()).map_err(|e| e.to_string())
+    }
+
+    pub fn rescore_target_queue(
+        app_handle: &AppHandle,
+        campaign_id: Option<&str>,
+        weights: Option<QueueWeights>,
+    ) -> Result<usize, String> {
+        let conn = repo::get_connection(app_handle).map_err(|e| e.to_string())?;
+        task_queue::rescore(&conn, campaign_id, &weights.unwrap_or_default()).map_err(|e| e.to_string())
+    }
+
+    pub fn pin_target_to_top(app_handle: &AppHandle, task_id: &str, pinned: bool) -> Result<(), String> {
+        let conn = repo::get_connection(app_handle).map_err(|e| e.to_string())?;
+        task_queue::pin(&conn, task_id, pinned).map_err(|e| e.to_string())
+    }
+
+    pub fn set_task_lead_score(app_handle: &AppHandle, task_id: &str, lead_score: Option<f64>) -> Result<(), String> {
+        let conn = repo::get_connection(app_handle).map_err(|e| e.to_string())?;
+        task_queue::set_lead_score(&conn, task_id, lead_score).map_err(|e| e.to_string())
     }
 
     // ==================== 审计日志相关 ====================
@@ -167,9 +270,24 @@ impl MarketingStorageFacade {
         start_time: Option<&str>,
         end_time: Option<&str>,
         format: Option<&str>,
+        locale: Option<&str>,
     ) -> Result<String, String> {
         let conn = repo::get_connection(app_handle).map_err(|e| e.to_string())?;
-        repo::export_audit_logs(&conn, start_time, end_time, format.unwrap_or("csv")).map_err(|e| e.to_string())
+        let locale = crate::core::shared::i18n::resolve_locale(locale);
+        repo::export_audit_logs(&conn, start_time, end_time, format.unwrap_or("csv"), locale).map_err(|e| e.to_string())
+    }
+
+    /// 审计日志导出为文件（csv / xlsx / json），进度通过统一导出事件上报
+    pub fn export_audit_logs_to_file(
+        app_handle: &AppHandle,
+        start_time: Option<&str>,
+        end_time: Option<&str>,
+        options: ExportOptions,
+    ) -> Result<ExportOutcome, String> {
+        let conn = repo::get_connection(app_handle).map_err(|e| e.to_string())?;
+        let logs = repo::query_audit_logs(&conn, start_time, end_time, None, i64::MAX, 0).map_err(|e| e.to_string())?;
+        let columns = repo::audit_log_columns(options.locale);
+        report_export::export_table("audit_logs", options, columns, &logs, report_export::emit_progress(app_handle))
     }
 
     pub fn cleanup_expired_audit_logs(
@@ -204,9 +322,10 @@ impl MarketingStorageFacade {
         offset: Option<i64>,
         channel: Option<String>,
         enabled: Option<bool>,
+        language: Option<CommentLanguage>,
     ) -> Result<Vec<ReplyTemplateRow>, String> {
         let conn = repo::get_connection(app_handle).map_err(|e| e.to_string())?;
-        let q = ListReplyTemplatesQuery { limit, offset, channel, enabled };
+        let q = ListReplyTemplatesQuery { limit, offset, channel, enabled, language };
         repo::list_reply_templates(&conn, &q).map_err(|e| e.to_string())
     }
 
@@ -219,6 +338,7 @@ impl MarketingStorageFacade {
         variables: Option<&str>,
         category: Option<&str>,
         enabled: Option<bool>,
+        language: Option<CommentLanguage>,
     ) -> Result<(), String> {
         let conn = repo::get_connection(app_handle).map_err(|e| e.to_string())?;
         repo::update_reply_template(
@@ -230,9 +350,29 @@ impl MarketingStorageFacade {
             variables,
             category,
             enabled,
+            language,
         ).map_err(|e| e.to_string())
     }
 
+    /// 按评论语言（或直接给定的语言）选回复模板
+    pub fn select_reply_template(
+        app_handle: &AppHandle,
+        comment_id: Option<&str>,
+        language: Option<CommentLanguage>,
+        channel: Option<&str>,
+        category: Option<&str>,
+    ) -> Result<Option<ReplyTemplateRow>, String> {
+        let conn = repo::get_connection(app_handle).map_err(|e| e.to_string())?;
+        let language = match (language, comment_id) {
+            (Some(language), _) => language,
+            (None, Some(id)) => repo::get_comment_language(&conn, id)
+                .map_err(|e| e.to_string())?
+                .ok_or_else(|| format!("评论不存在: {}", id))?,
+            (None, None) => return Err("需要指定 comment_id 或 language".to_string()),
+        };
+        repo::select_reply_template(&conn, language, channel, category).map_err(|e| e.to_string())
+    }
+
     // ==================== 统计相关 ====================
 
     pub fn get_precise_acquisition_stats(
@@ -242,3 +382,7 @@ impl MarketingStorageFacade {
         repo::get_precise_acquisition_stats(&conn).map_err(|e| e.to_string())
     }
 }
+
+fn emit_campaign_state(app_handle: &AppHandle, state: &CampaignControlState) {
+    crate::services::event_outbox::emit(app_handle, CAMPAIGN_STATE_EVENT, &state.campaign_id, state);
+}
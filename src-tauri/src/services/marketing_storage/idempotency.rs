@@ -0,0 +1,129 @@
+//! Idempotency layer for mutating marketing commands.
+//!
+//! 前端超时重试会重复创建任务/评论。调用方携带 `idempotency_key` 时，
+//! 先在 `idempotency_keys` 表中预占 key（pending），再执行写入，成功后回填 JSON 结果（带 TTL）。
+//! 相同 key + 命令的重复调用直接返回首次结果；首次调用仍在执行时返回 `IDEMPOTENCY_IN_PROGRESS`。
+
+use rusqlite::Connection;
+use serde::{de::DeserializeOwned, Serialize};
+use tauri::AppHandle;
+
+use super::repositories::{self as repo, IdempotencyReservation};
+
+/// 幂等结果默认保留时长（小时）
+pub const DEFAULT_IDEMPOTENCY_TTL_HOURS: i64 = 24;
+/// pending 预占的租期（分钟），超时后视为执行方已崩溃
+pub const IDEMPOTENCY_LEASE_MINUTES: i64 = 10;
+
+/// 带幂等保护地执行写操作（自动获取数据库连接）
+pub fn with_idempotency<T, F>(
+    app_handle: &AppHandle,
+    idempotency_key: Option<&str>,
+    command: &str,
+    op: F,
+) -> Result<T, String>
+where
+    T: Serialize + DeserializeOwned,
+    F: FnOnce() -> Result<T, String>,
+{
+    match normalize_key(idempotency_key) {
+        None => op(),
+        Some(key) => {
+            let conn = repo::get_connection(app_handle).map_err(|e| e.to_string())?;
+            run_idempotent(&conn, key, command, op)
+        }
+    }
+}
+
+/// 在给定连接上执行幂等逻辑；只有成功结果会被缓存，失败会释放预占以便重试
+pub fn run_idempotent<T, F>(conn: &Connection, key: &str, command: &str, op: F) -> Result<T, String>
+where
+    T: Serialize + DeserializeOwned,
+    F: FnOnce() -> Result<T, String>,
+{
+    match repo::reserve_idempotency_key(conn, key, command, IDEMPOTENCY_LEASE_MINUTES).map_err(|e| e.to_string())? {
+        IdempotencyReservation::Completed(cached) => {
+            tracing::info!("♻️ 幂等命中: command={} key={}", command, key);
+            return serde_json::from_str(&cached).map_err(|e| format!("幂等结果反序列化失败: {}", e));
+        }
+        IdempotencyReservation::InProgress => {
+            return Err(format!("IDEMPOTENCY_IN_PROGRESS: 相同请求正在处理中 command={} key={}", command, key));
+        }
+        IdempotencyReservation::Reserved => {}
+    }
+
+    let result = match op() {
+        Ok(result) => result,
+        Err(e) => {
+            repo::release_idempotency_key(conn, key, command).map_err(|e| e.to_string())?;
+            return Err(e);
+        }
+    };
+    let json = serde_json::to_string(&result).map_err(|e| format!("幂等结果序列化失败: {}", e))?;
+    repo::complete_idempotency_key(conn, key, command, &json, DEFAULT_IDEMPOTENCY_TTL_HOURS)
+        .map_err(|e| e.to_string())?;
+    Ok(result)
+}
+
+fn normalize_key(key: Option<&str>) -> Option<&str> {
+    key.map(str::trim).filter(|k| !k.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn conn() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        repo::ensure_marketing_tables(&conn).unwrap();
+        conn
+    }
+
+    #[test]
+    fn repeated_key_returns_first_result() {
+        let conn = conn();
+        let mut calls = 0;
+        let first: String = run_idempotent(&conn, "k1", "insert_task", || { calls += 1; Ok("tsk_a".to_string()) }).unwrap();
+        let second: String = run_idempotent(&conn, "k1", "insert_task", || { calls += 1; Ok("tsk_b".to_string()) }).unwrap();
+        assert_eq!(first, "tsk_a");
+        assert_eq!(second, "tsk_a");
+        assert_eq!(calls, 1);
+    }
+
+    #[test]
+    fn failures_are_not_cached_and_keys_are_scoped_per_command() {
+        let conn = conn();
+        let failed: Result<String, String> = run_idempotent(&conn, "k2", "insert_comment", || Err("boom".into()));
+        assert!(failed.is_err());
+        let retried: String = run_idempotent(&conn, "k2", "insert_comment", || Ok("cmt_1".into())).unwrap();
+        assert_eq!(retried, "cmt_1");
+
+        let other: String = run_idempotent(&conn, "k2", "insert_task", || Ok("tsk_1".into())).unwrap();
+        assert_eq!(other, "tsk_1");
+    }
+
+    #[test]
+    fn key_is_reserved_before_op_runs() {
+        let conn = conn();
+        // 首次调用执行期间，同 key 的重入调用被拒绝而不是再写一次
+        let first: String = run_idempotent(&conn, "k3", "insert_task", || {
+            let nested: Result<String, String> = run_idempotent(&conn, "k3", "insert_task", || Ok("tsk_dup".into()));
+            assert!(nested.unwrap_err().starts_with("IDEMPOTENCY_IN_PROGRESS"));
+            Ok("tsk_first".into())
+        })
+        .unwrap();
+        assert_eq!(first, "tsk_first");
+    }
+
+    #[test]
+    fn expired_keys_are_pruned_on_read() {
+        let conn = conn();
+        let _: String = run_idempotent(&conn, "old", "insert_task", || Ok("tsk_old".into())).unwrap();
+        conn.execute("UPDATE idempotency_keys SET expire_at = datetime('now', '-1 hour')", []).unwrap();
+
+        let fresh: String = run_idempotent(&conn, "new", "insert_task", || Ok("tsk_new".into())).unwrap();
+        assert_eq!(fresh, "tsk_new");
+        let remaining: i64 = conn.query_row("SELECT COUNT(*) FROM idempotency_keys", [], |r| r.get(0)).unwrap();
+        assert_eq!(remaining, 1);
+    }
+}
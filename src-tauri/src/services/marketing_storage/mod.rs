@@ -5,6 +5,9 @@
 pub mod models;
 pub mod repositories;
 pub mod facade;
+pub mod idempotency;
+pub mod task_queue;
+pub mod campaign_control;
 pub mod commands;
 
 // Re-export commands for easy import in main.rs
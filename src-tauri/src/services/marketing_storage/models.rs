@@ -2,6 +2,8 @@ use serde::{Deserialize, Serialize};
 use rusqlite::types::{FromSql, FromSqlResult, ValueRef, ToSql, ToSqlOutput};
 use rusqlite::Result;
 
+pub use crate::services::comment_language::CommentLanguage;
+
 // ==================== 候选池相关模型 ====================
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -54,6 +56,8 @@ pub struct CommentPayload {
     pub publish_time: String,   // 评论发布时间 ISO8601
     pub region: Option<String>, // 地域（若可识别）
     pub source_target_id: String, // 溯源到 watch_targets.id
+    #[serde(default)]
+    pub language: Option<CommentLanguage>, // 为空时入库按内容自动识别
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -68,6 +72,8 @@ pub struct CommentRow {
     pub region: Option<String>,
     pub source_target_id: String,
     pub inserted_at: String,    // 入库时间
+    #[serde(default)]
+    pub language: CommentLanguage,
 }
 
 // ==================== 任务相关模型 ====================
@@ -82,6 +88,8 @@ pub struct TaskPayload {
     pub dedup_key: String,      // 查重键
     pub priority: Option<i32>,  // P0-P3默认 2
     pub deadline_at: Option<String>, // ISO8601
+    #[serde(default)]
+    pub campaign_id: Option<String>, // 所属活动（优先队列按活动预览）
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -300,6 +308,8 @@ pub struct TaskRow {
     pub deadline_at: Option<String>,
     pub lock_owner: Option<String>,
     pub lease_until: Option<String>,
+    #[serde(default)]
+    pub campaign_id: Option<String>,
 }
 
 // ==================== 话术模板相关模型 ====================
@@ -312,6 +322,8 @@ pub struct ReplyTemplatePayload {
     pub variables: Option<String>, // 变量名（semicolon separated）
     pub category: Option<String>,  // 模板类别
     pub enabled: bool,          // 启用状态
+    #[serde(default)]
+    pub language: Option<CommentLanguage>, // 适用语言；为空表示通用
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -325,6 +337,8 @@ pub struct ReplyTemplateRow {
     pub enabled: bool,
     pub created_at: String,
     pub updated_at: String,
+    #[serde(default)]
+    pub language: Option<CommentLanguage>,
 }
 
 // ==================== 审计日志相关模型 ====================
@@ -378,6 +392,8 @@ pub struct ListCommentsQuery {
     pub platform: Option<MarketingPlatform>,
     pub source_target_id: Option<String>,
     pub region: Option<String>,
+    #[serde(default)]
+    pub language: Option<CommentLanguage>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -395,6 +411,8 @@ pub struct ListReplyTemplatesQuery {
     pub offset: Option<i64>,
     pub channel: Option<String>,
     pub enabled: Option<bool>,
+    #[serde(default)]
+    pub language: Option<CommentLanguage>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
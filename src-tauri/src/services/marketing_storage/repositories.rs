@@ -6,6 +6,8 @@ use rusqlite::{params, Connection, OptionalExtension, Row};
 use tauri::AppHandle;
 use uuid::Uuid;
 
+use crate::core::shared::i18n::{self, Locale};
+
 use super::models::{
     WatchTargetPayload, WatchTargetRow, ListWatchTargetsQuery,
     CommentPayload, CommentRow, ListCommentsQuery,
@@ -13,6 +15,8 @@ use super::models::{
     AuditLogPayload,
     ReplyTemplatePayload, ReplyTemplateRow, ListReplyTemplatesQuery,
 };
+use crate::services::comment_language::{detect_language, CommentLanguage};
+use crate::services::report_export::{self, CellFormat, Column, ExportFormat};
 
 // ==================== SQL 表创建脚本 ====================
 
@@ -66,11 +70,43 @@ CREATE TABLE IF NOT EXISTS tasks (
   deadline_at TEXT,                                 -- 截止时间
   lock_owner TEXT,                                  -- 锁定者（多机协作）
   lease_until TEXT,                                 -- 租约到期时间
+  campaign_id TEXT,                                 -- 所属活动
   created_at TEXT NOT NULL DEFAULT (datetime('now')),
   executed_at TEXT,                                 -- 实际执行时间
   FOREIGN KEY (comment_id) REFERENCES comments(id)
 );
 
+-- 任务优先队列评分（定期重算，可手动置顶）
+CREATE TABLE IF NOT EXISTS task_queue (
+  task_id TEXT PRIMARY KEY,
+  lead_score REAL,                                  -- 外部线索分（0-1），为空时按评论热度估算
+  score REAL NOT NULL DEFAULT 0,                    -- 综合分
+  breakdown TEXT,                                   -- 各项得分 JSON
+  pinned_at TEXT,                                   -- 手动置顶时间
+  scored_at TEXT,                                   -- 最近评分时间
+  FOREIGN KEY (task_id) REFERENCES tasks(id)
+);
+
+-- 活动暂停状态（RUNNING / DRAINING / PAUSED，检查点为 JSON）
+CREATE TABLE IF NOT EXISTS campaign_state (
+  campaign_id TEXT PRIMARY KEY,
+  status TEXT NOT NULL DEFAULT 'RUNNING',
+  pause_requested_at TEXT,
+  drain_deadline TEXT,                              -- 排空期限，超时后在途任务退回队列
+  paused_at TEXT,
+  checkpoint TEXT,
+  updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+-- 活动时间线（暂停 / 恢复标记）
+CREATE TABLE IF NOT EXISTS campaign_timeline (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  campaign_id TEXT NOT NULL,
+  kind TEXT NOT NULL,
+  at TEXT NOT NULL DEFAULT (datetime('now')),
+  detail TEXT
+);
+
 -- 话术模板表
 CREATE TABLE IF NOT EXISTS reply_templates (
   id TEXT PRIMARY KEY,
@@ -116,31 +152,53 @@ CREATE TABLE IF NOT EXISTS dedup_index (
   PRIMARY KEY (key, scope)
 );
 
+-- 幂等键（前端超时重试时返回首次结果）；state: pending 执行中 / completed 已有结果
+CREATE TABLE IF NOT EXISTS idempotency_keys (
+  key TEXT NOT NULL,
+  command TEXT NOT NULL,
+  state TEXT NOT NULL DEFAULT 'completed',
+  response_json TEXT NOT NULL DEFAULT '',
+  created_at TEXT NOT NULL DEFAULT (datetime('now')),
+  expire_at TEXT NOT NULL,
+  PRIMARY KEY (key, command)
+);
+
 -- 索引
 CREATE INDEX IF NOT EXISTS idx_watch_targets_platform ON watch_targets(platform);
 CREATE INDEX IF NOT EXISTS idx_watch_targets_type ON watch_targets(target_type);
 CREATE INDEX IF NOT EXISTS idx_comments_platform ON comments(platform);
 CREATE INDEX IF NOT EXISTS idx_comments_source_target ON comments(source_target_id);
 CREATE INDEX IF NOT EXISTS idx_tasks_status ON tasks(status);
+CREATE INDEX IF NOT EXISTS idx_campaign_timeline_campaign ON campaign_timeline(campaign_id);
 CREATE INDEX IF NOT EXISTS idx_tasks_type ON tasks(task_type);
 CREATE INDEX IF NOT EXISTS idx_tasks_account ON tasks(assign_account_id);
 CREATE INDEX IF NOT EXISTS idx_templates_channel ON reply_templates(channel);
 CREATE INDEX IF NOT EXISTS idx_audit_action ON audit_logs(action);
 CREATE INDEX IF NOT EXISTS idx_audit_task ON audit_logs(task_id);
 CREATE INDEX IF NOT EXISTS idx_reports_date ON daily_reports(date);
+CREATE INDEX IF NOT EXISTS idx_idempotency_expire ON idempotency_keys(expire_at);
 "#;
 
 pub fn get_connection(app: &AppHandle) -> rusqlite::Result<Connection> {
     // 使用新的统一数据库连接获取方式
     let conn = crate::services::contact_storage::repositories::common::database::get_connection(app)?;
     
-    // 确保 marketing_storage 相关的表存在
+    ensure_marketing_tables(&conn)?;
+    
+    Ok(conn)
+}
+
+/// 确保 marketing_storage 相关的表存在并完成模式迁移
+pub fn ensure_marketing_tables(conn: &Connection) -> rusqlite::Result<()> {
     conn.execute_batch(CREATE_TABLES_SQL)?;
     
     // 执行 marketing storage 特定的模式迁移
-    apply_task_schema_migrations(&conn)?;
-    
-    Ok(conn)
+    apply_task_schema_migrations(conn)?;
+    apply_language_schema_migrations(conn)?;
+    if !column_exists(conn, "idempotency_keys", "state")? {
+        let _ = conn.execute("ALTER TABLE idempotency_keys ADD COLUMN state TEXT NOT NULL DEFAULT 'completed'", []);
+    }
+    Ok(())
 }
 
 fn column_exists(conn: &Connection, table: &str, column: &str) -> rusqlite::Result<bool> {
@@ -174,10 +232,36 @@ fn apply_task_schema_migrations(conn: &Connection) -> rusqlite::Result<()> {
     if !column_exists(conn, "tasks", "lease_until")? {
         let _ = conn.execute("ALTER TABLE tasks ADD COLUMN lease_until TEXT", []);
     }
+    // campaign_id TEXT NULL
+    if !column_exists(conn, "tasks", "campaign_id")? {
+        let _ = conn.execute("ALTER TABLE tasks ADD COLUMN campaign_id TEXT", []);
+    }
+    let _ = conn.execute("CREATE INDEX IF NOT EXISTS idx_tasks_campaign ON tasks(campaign_id)", []);
     Ok(())
 }
 
-fn map_task_row(row: &Row) -> rusqlite::Result<TaskRow> {
+/// 评论 / 回复模板的语言列；存量评论按内容补识别
+fn apply_language_schema_migrations(conn: &Connection) -> rusqlite::Result<()> {
+    if !column_exists(conn, "reply_templates", "language")? {
+        let _ = conn.execute("ALTER TABLE reply_templates ADD COLUMN language TEXT", []);
+    }
+    if !column_exists(conn, "comments", "language")? {
+        conn.execute("ALTER TABLE comments ADD COLUMN language TEXT", [])?;
+        let pending: Vec<(String, String)> = {
+            let mut stmt = conn.prepare("SELECT id, content FROM comments WHERE language IS NULL")?;
+            let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+            rows.collect::<rusqlite::Result<_>>()?
+        };
+        let mut update = conn.prepare("UPDATE comments SET language = ?1 WHERE id = ?2")?;
+        for (id, content) in &pending {
+            update.execute(params![detect_language(content), id])?;
+        }
+    }
+    let _ = conn.execute("CREATE INDEX IF NOT EXISTS idx_comments_language ON comments(language)", []);
+    Ok(())
+}
+
+pub(super) fn map_task_row(row: &Row) -> rusqlite::Result<TaskRow> {
     Ok(TaskRow {
         id: row.get(0)?,
         task_type: row.get(1)?,
@@ -196,6 +280,7 @@ fn map_task_row(row: &Row) -> rusqlite::Result<TaskRow> {
         deadline_at: row.get(14)?,
         lock_owner: row.get(15)?,
         lease_until: row.get(16)?,
+        campaign_id: row.get(17)?,
     })
 }
 
@@ -370,9 +455,10 @@ pub fn update_watch_target(
 pub fn insert_comment(conn: &Connection, comment: &CommentPayload) -> rusqlite::Result<String> {
     let id = format!("cmt_{}", Uuid::new_v4().to_string().replace("-", "")[..16].to_lowercase());
     let sql = r#"
-INSERT INTO comments (id, platform, video_id, author_id, content, like_count, publish_time, region, source_target_id, inserted_at)
-VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, datetime('now'))
+INSERT INTO comments (id, platform, video_id, author_id, content, like_count, publish_time, region, source_target_id, language, inserted_at)
+VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, datetime('now'))
 "#;
+    let language = comment.language.unwrap_or_else(|| detect_language(&comment.content));
     conn.execute(sql, params![
         id,
         comment.platform,
@@ -383,17 +469,19 @@ VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, datetime('now'))
         comment.publish_time,
         comment.region,
         comment.source_target_id,
+        language,
     ])?;
     Ok(id)
 }
 
 pub fn list_comments(conn: &Connection, query: &ListCommentsQuery) -> rusqlite::Result<Vec<CommentRow>> {
-    let mut sql = String::from("SELECT id, platform, video_id, author_id, content, like_count, publish_time, region, source_target_id, inserted_at FROM comments WHERE 1=1");
+    let mut sql = String::from("SELECT id, platform, video_id, author_id, content, like_count, publish_time, region, source_target_id, inserted_at, language FROM comments WHERE 1=1");
     let mut params: Vec<Box<dyn rusqlite::ToSql>> = vec![];
     
     if let Some(platform) = &query.platform { sql.push_str(" AND platform = ?"); params.push(Box::new(platform.clone())); }
     if let Some(source_target_id) = &query.source_target_id { sql.push_str(" AND source_target_id = ?"); params.push(Box::new(source_target_id.clone())); }
     if let Some(region) = &query.region { sql.push_str(" AND region = ?"); params.push(Box::new(region.clone())); }
+    if let Some(language) = query.language { sql.push_str(" AND language = ?"); params.push(Box::new(language)); }
     
     sql.push_str(" ORDER BY inserted_at DESC");
     if let Some(limit) = query.limit { sql.push_str(" LIMIT "); sql.push_str(&limit.to_string()); }
@@ -412,6 +500,7 @@ pub fn list_comments(conn: &Connection, query: &ListCommentsQuery) -> rusqlite::
             region: row.get(7)?,
             source_target_id: row.get(8)?,
             inserted_at: row.get(9)?,
+            language: row.get::<_, Option<CommentLanguage>>(10)?.unwrap_or_default(),
         })
     })?;
     let mut out = Vec::new();
@@ -419,14 +508,23 @@ pub fn list_comments(conn: &Connection, query: &ListCommentsQuery) -> rusqlite::
     Ok(out)
 }
 
+/// 评论入库时识别的语言
+pub fn get_comment_language(conn: &Connection, comment_id: &str) -> rusqlite::Result<Option<CommentLanguage>> {
+    conn.query_row("SELECT language FROM comments WHERE id = ?1", params![comment_id], |row| {
+        row.get::<_, Option<CommentLanguage>>(0)
+    })
+    .optional()
+    .map(|found| found.map(|lang| lang.unwrap_or_default()))
+}
+
 // ==================== 任务操作函数 ====================
 
 pub fn insert_task(conn: &Connection, task: &TaskPayload) -> rusqlite::Result<String> {
     let id = format!("tsk_{}", Uuid::new_v4().to_string().replace("-", "")[..16].to_lowercase());
     let priority = task.priority.unwrap_or(2);
     let sql = r#"
-INSERT INTO tasks (id, task_type, comment_id, target_user_id, assign_account_id, status, executor_mode, priority, dedup_key, deadline_at, created_at)
-VALUES (?1, ?2, ?3, ?4, ?5, 'NEW', ?6, ?7, ?8, ?9, datetime('now'))
+INSERT INTO tasks (id, task_type, comment_id, target_user_id, assign_account_id, status, executor_mode, priority, dedup_key, deadline_at, campaign_id, created_at)
+VALUES (?1, ?2, ?3, ?4, ?5, 'NEW', ?6, ?7, ?8, ?9, ?10, datetime('now'))
 "#;
     conn.execute(sql, params![
         id,
@@ -438,6 +536,7 @@ VALUES (?1, ?2, ?3, ?4, ?5, 'NEW', ?6, ?7, ?8, ?9, datetime('now'))
         priority,
         task.dedup_key,
         task.deadline_at,
+        task.campaign_id,
     ])?;
     Ok(id)
 }
@@ -461,7 +560,7 @@ pub fn update_task_status(conn: &Connection, task_id: &str, status: TaskStatus,
 }
 
 pub fn list_tasks(conn: &Connection, query: &ListTasksQuery) -> rusqlite::Result<Vec<TaskRow>> {
-    let mut sql = String::from("SELECT id, task_type, comment_id, target_user_id, assign_account_id, status, executor_mode, result_code, error_message, dedup_key, created_at, executed_at, priority, attempts, deadline_at, lock_owner, lease_until FROM tasks WHERE 1=1");
+    let mut sql = String::from("SELECT id, task_type, comment_id, target_user_id, assign_account_id, status, executor_mode, result_code, error_message, dedup_key, created_at, executed_at, priority, attempts, deadline_at, lock_owner, lease_until, campaign_id FROM tasks WHERE 1=1");
     let mut params: Vec<Box<dyn rusqlite::ToSql>> = vec![];
     
     if let Some(status) = &query.status { sql.push_str(" AND status = ?"); params.push(Box::new(status.clone())); }
@@ -480,34 +579,71 @@ pub fn list_tasks(conn: &Connection, query: &ListTasksQuery) -> rusqlite::Result
 }
 
 pub fn lock_next_ready_task(conn: &mut Connection, account_id: &str, lease_seconds: i64) -> rusqlite::Result<Option<TaskRow>> {
+    lock_next_ready_task_guarded(conn, account_id, lease_seconds, |_, _, _| None)
+}
+
+/// 出队前检查目标是否受保护：`is_protected(task_type, platform, target_user_id)` 返回原因时，
+/// 该任务直接置为 FAILED / BLOCKED 并写一条 `PROTECTED_SKIP` 审计，继续取下一个
+pub fn lock_next_ready_task_guarded<F>(
+    conn: &mut Connection,
+    account_id: &str,
+    lease_seconds: i64,
+    is_protected: F,
+) -> rusqlite::Result<Option<TaskRow>>
+where
+    F: Fn(&str, Option<&str>, &str) -> Option<String>,
+{
     let lease = if lease_seconds <= 0 { 120 } else { lease_seconds };
     let tx = conn.transaction()?;
+    // 置顶优先，其次按优先队列综合分（未评分的任务保持原 FIFO 顺序）
     let select_sql = r#"
-SELECT id FROM tasks
-WHERE status = 'READY'
-  AND (lease_until IS NULL OR lease_until <= datetime('now'))
-  AND (deadline_at IS NULL OR deadline_at > datetime('now'))
-ORDER BY priority ASC, created_at ASC
+SELECT t.id, t.task_type, t.target_user_id, c.platform FROM tasks t
+LEFT JOIN task_queue q ON q.task_id = t.id
+LEFT JOIN comments c ON c.id = t.comment_id
+WHERE t.status = 'READY'
+  AND (t.lease_until IS NULL OR t.lease_until <= datetime('now'))
+  AND (t.deadline_at IS NULL OR t.deadline_at > datetime('now'))
+  AND (t.campaign_id IS NULL OR t.campaign_id NOT IN (SELECT campaign_id FROM campaign_state WHERE status != 'RUNNING'))
+ORDER BY q.pinned_at IS NULL, q.pinned_at ASC, COALESCE(q.score, 0) DESC, t.priority ASC, t.created_at ASC
 LIMIT 1
 "#;
-    let task_id: Option<String> = tx
-        .query_row(select_sql, [], |row| row.get(0))
-        .optional()?;
+    loop {
+        let candidate: Option<(String, String, Option<String>, Option<String>)> = tx
+            .query_row(select_sql, [], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)))
+            .optional()?;
+        let Some((id, task_type, target_user_id, platform)) = candidate else {
+            tx.commit()?;
+            return Ok(None);
+        };
+
+        let protected_reason = target_user_id
+            .as_deref()
+            .and_then(|target| is_protected(&task_type, platform.as_deref(), target).map(|reason| (target, reason)));
+        if let Some((target, reason)) = protected_reason {
+            tx.execute(
+                "UPDATE tasks SET status = 'FAILED', result_code = 'BLOCKED', error_message = ?, executed_at = datetime('now'), lock_owner = NULL, lease_until = NULL WHERE id = ?",
+                params![format!("受保护账号，已跳过: {}", reason), id],
+            )?;
+            insert_audit_log(&tx, &AuditLogPayload {
+                action: "PROTECTED_SKIP".to_string(),
+                task_id: Some(id.clone()),
+                account_id: Some(account_id.to_string()),
+                operator: "system".to_string(),
+                payload_hash: Some(format!("{}:{}", platform.as_deref().unwrap_or("*"), target)),
+            })?;
+            continue;
+        }
 
-    if let Some(id) = task_id {
         tx.execute(
             "UPDATE tasks SET status = 'EXECUTING', lock_owner = ?, lease_until = datetime('now', printf('+%d seconds', ?)), attempts = attempts + 1 WHERE id = ?",
             params![account_id, lease, id],
         )?;
         let task = {
-            let mut stmt = tx.prepare("SELECT id, task_type, comment_id, target_user_id, assign_account_id, status, executor_mode, result_code, error_message, dedup_key, created_at, executed_at, priority, attempts, deadline_at, lock_owner, lease_until FROM tasks WHERE id = ?")?;
+            let mut stmt = tx.prepare("SELECT id, task_type, comment_id, target_user_id, assign_account_id, status, executor_mode, result_code, error_message, dedup_key, created_at, executed_at, priority, attempts, deadline_at, lock_owner, lease_until, campaign_id FROM tasks WHERE id = ?")?;
             stmt.query_row(params![id.clone()], |row| map_task_row(row))?
         };
         tx.commit()?;
-        Ok(Some(task))
-    } else {
-        tx.commit()?;
-        Ok(None)
+        return Ok(Some(task));
     }
 }
 
@@ -621,6 +757,87 @@ ON CONFLICT(key, scope) DO UPDATE SET
     Ok(true)
 }
 
+// ==================== 幂等键操作函数 ====================
+
+/// 幂等键预占结果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IdempotencyReservation {
+    /// 本次调用占到了 key，应执行写操作
+    Reserved,
+    /// 已有首次结果（JSON）
+    Completed(String),
+    /// 另一次调用正在执行
+    InProgress,
+}
+
+/// 在事务内预占幂等键：先清理过期记录，再插入 pending 行；
+/// pending 行在 `lease_minutes` 后过期，避免进程崩溃后 key 永久卡住
+pub fn reserve_idempotency_key(
+    conn: &Connection,
+    key: &str,
+    command: &str,
+    lease_minutes: i64,
+) -> rusqlite::Result<IdempotencyReservation> {
+    let tx = conn.unchecked_transaction()?;
+    tx.execute("DELETE FROM idempotency_keys WHERE expire_at <= datetime('now')", [])?;
+    let inserted = tx.execute(
+        r#"
+INSERT OR IGNORE INTO idempotency_keys (key, command, state, response_json, created_at, expire_at)
+VALUES (?1, ?2, 'pending', '', datetime('now'), datetime('now', printf('+%d minutes', ?3)))
+"#,
+        params![key, command, lease_minutes],
+    )?;
+    let reservation = if inserted == 1 {
+        IdempotencyReservation::Reserved
+    } else {
+        let (state, response_json): (String, String) = tx.query_row(
+            "SELECT state, response_json FROM idempotency_keys WHERE key = ?1 AND command = ?2",
+            params![key, command],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )?;
+        if state == "completed" {
+            IdempotencyReservation::Completed(response_json)
+        } else {
+            IdempotencyReservation::InProgress
+        }
+    };
+    tx.commit()?;
+    Ok(reservation)
+}
+
+/// 写入首次结果并按 TTL 保留
+pub fn complete_idempotency_key(
+    conn: &Connection,
+    key: &str,
+    command: &str,
+    response_json: &str,
+    ttl_hours: i64,
+) -> rusqlite::Result<()> {
+    conn.execute(
+        r#"
+UPDATE idempotency_keys
+SET state = 'completed', response_json = ?3, expire_at = datetime('now', printf('+%d hours', ?4))
+WHERE key = ?1 AND command = ?2
+"#,
+        params![key, command, response_json, ttl_hours],
+    )?;
+    Ok(())
+}
+
+/// 写操作失败时释放预占，允许重试
+pub fn release_idempotency_key(conn: &Connection, key: &str, command: &str) -> rusqlite::Result<()> {
+    conn.execute(
+        "DELETE FROM idempotency_keys WHERE key = ?1 AND command = ?2 AND state = 'pending'",
+        params![key, command],
+    )?;
+    Ok(())
+}
+
+/// 清理过期幂等键
+pub fn cleanup_expired_idempotency_keys(conn: &Connection) -> rusqlite::Result<usize> {
+    conn.execute("DELETE FROM idempotency_keys WHERE expire_at <= datetime('now')", [])
+}
+
 // ==================== 审计日志操作函数 ====================
 
 pub fn insert_audit_log(conn: &Connection, log: &AuditLogPayload) -> rusqlite::Result<String> {
@@ -698,38 +915,37 @@ pub fn export_audit_logs(
     start_time: Option<&str>,
     end_time: Option<&str>,
     format: &str,
+    locale: Locale,
 ) -> rusqlite::Result<String> {
+    let unsupported = || {
+        rusqlite::Error::InvalidColumnName(i18n::t_args(
+            locale,
+            "audit.export.unsupported_format",
+            &[("format", format)],
+        ))
+    };
+    // 字符串接口只支持 csv / json，xlsx 需走文件导出
+    let format = match ExportFormat::parse(format) {
+        Some(f @ (ExportFormat::Csv | ExportFormat::Json)) => f,
+        _ => return Err(unsupported()),
+    };
     let logs = query_audit_logs(conn, start_time, end_time, None, i64::MAX, 0)?;
+    report_export::render_to_string(&audit_log_columns(locale), &logs, format, locale).map_err(|e| {
+        rusqlite::Error::ToSqlConversionFailure(Box::new(std::io::Error::new(std::io::ErrorKind::Other, e)))
+    })
+}
 
-    match format.to_lowercase().as_str() {
-        "csv" => {
-            let mut output = String::new();
-            output.push_str("ID,Action,Task ID,Account ID,Operator,Payload Hash,Timestamp\n");
-            
-            for log in logs {
-                output.push_str(&format!(
-                    "{},{},{},{},{},{},{}\n",
-                    log["id"].as_str().unwrap_or(""),
-                    log["action"].as_str().unwrap_or(""),
-                    log["task_id"].as_str().unwrap_or(""),
-                    log["account_id"].as_str().unwrap_or(""),
-                    log["operator"].as_str().unwrap_or(""),
-                    log["payload_hash"].as_str().unwrap_or(""),
-                    log["ts"].as_str().unwrap_or("")
-                ));
-            }
-            Ok(output)
-        }
-        "json" => {
-            serde_json::to_string_pretty(&logs).map_err(|_e| {
-                rusqlite::Error::ToSqlConversionFailure(Box::new(std::io::Error::new(
-                    std::io::ErrorKind::Other,
-                    "Failed to serialize JSON"
-                )))
-            })
-        }
-        _ => Err(rusqlite::Error::InvalidColumnName(format!("Unsupported export format: {}", format)))
-    }
+/// 审计日志导出列（字符串导出与文件导出共用）
+pub fn audit_log_columns(locale: Locale) -> Vec<Column> {
+    vec![
+        Column::localized("id", "audit.export.id", locale),
+        Column::localized("action", "audit.export.action", locale),
+        Column::localized("task_id", "audit.export.task_id", locale),
+        Column::localized("account_id", "audit.export.account_id", locale),
+        Column::localized("operator", "audit.export.operator", locale),
+        Column::localized("payload_hash", "audit.export.payload_hash", locale),
+        Column::localized("ts", "audit.export.timestamp", locale).format(CellFormat::DateTime),
+    ]
 }
 
 /// 清理过期审计日志
@@ -778,8 +994,8 @@ pub fn batch_store_audit_logs(
 pub fn insert_reply_template(conn: &Connection, payload: &ReplyTemplatePayload) -> rusqlite::Result<String> {
     let id = format!("tpl_{}", Uuid::new_v4().to_string().replace("-", "")[..16].to_lowercase());
     let sql = r#"
-INSERT INTO reply_templates (id, template_name, channel, text, variables, category, enabled, created_at, updated_at)
-VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, datetime('now'), datetime('now'))
+INSERT INTO reply_templates (id, template_name, channel, text, variables, category, enabled, language, created_at, updated_at)
+VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, datetime('now'), datetime('now'))
 "#;
     conn.execute(sql, params![
         id,
@@ -789,12 +1005,13 @@ VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, datetime('now'), datetime('now'))
         payload.variables,
         payload.category,
         payload.enabled,
+        payload.language,
     ])?;
     Ok(id)
 }
 
 pub fn list_reply_templates(conn: &Connection, query: &ListReplyTemplatesQuery) -> rusqlite::Result<Vec<ReplyTemplateRow>> {
-    let mut sql = String::from("SELECT id, template_name, channel, text, variables, category, enabled, created_at, updated_at FROM reply_templates WHERE 1=1");
+    let mut sql = String::from("SELECT id, template_name, channel, text, variables, category, enabled, created_at, updated_at, language FROM reply_templates WHERE 1=1");
     let mut params: Vec<Box<dyn rusqlite::ToSql>> = vec![];
     
     if let Some(channel) = &query.channel {
@@ -805,6 +1022,10 @@ pub fn list_reply_templates(conn: &Connection, query: &ListReplyTemplatesQuery)
         sql.push_str(" AND enabled = ?");
         params.push(Box::new(enabled));
     }
+    if let Some(language) = query.language {
+        sql.push_str(" AND language = ?");
+        params.push(Box::new(language));
+    }
     
     sql.push_str(" ORDER BY created_at DESC");
     if let Some(limit) = query.limit {
@@ -828,6 +1049,7 @@ pub fn list_reply_templates(conn: &Connection, query: &ListReplyTemplatesQuery)
             enabled: row.get(6)?,
             created_at: row.get(7)?,
             updated_at: row.get(8)?,
+            language: row.get(9)?,
         })
     })?;
     let mut out = Vec::new();
@@ -846,6 +1068,7 @@ pub fn update_reply_template(
     variables: Option<&str>,
     category: Option<&str>,
     enabled: Option<bool>,
+    language: Option<CommentLanguage>,
 ) -> rusqlite::Result<()> {
     let mut sql = String::from("UPDATE reply_templates SET updated_at = datetime('now')");
     let mut params: Vec<Box<dyn rusqlite::ToSql>> = vec![];
@@ -874,6 +1097,10 @@ pub fn update_reply_template(
         sql.push_str(", enabled = ?");
         params.push(Box::new(en));
     }
+    if let Some(lang) = language {
+        sql.push_str(", language = ?");
+        params.push(Box::new(lang));
+    }
     
     sql.push_str(" WHERE id = ?");
     params.push(Box::new(id.to_string()));
@@ -882,6 +1109,34 @@ pub fn update_reply_template(
     Ok(())
 }
 
+/// 按评论语言选回复模板：依次尝试 `template_preference` 中的语言，最后退回未标语言的通用模板；
+/// 渠道匹配 `channel` 或 `all`，指定类别时只在该类别内选，同级取最近更新的
+pub fn select_reply_template(
+    conn: &Connection,
+    language: CommentLanguage,
+    channel: Option<&str>,
+    category: Option<&str>,
+) -> rusqlite::Result<Option<ReplyTemplateRow>> {
+    let candidates = list_reply_templates(
+        conn,
+        &ListReplyTemplatesQuery { limit: None, offset: None, channel: None, enabled: Some(true), language: None },
+    )?;
+    let eligible: Vec<&ReplyTemplateRow> = candidates
+        .iter()
+        .filter(|t| channel.map_or(true, |c| t.channel == c || t.channel == "all"))
+        .filter(|t| category.map_or(true, |c| t.category.as_deref() == Some(c)))
+        .collect();
+    let rank = |t: &ReplyTemplateRow| match t.language {
+        None => Some(usize::MAX),
+        Some(lang) => language.template_preference().iter().position(|l| *l == lang),
+    };
+    Ok(eligible
+        .into_iter()
+        .filter_map(|t| rank(t).map(|r| (r, t)))
+        .min_by(|(ra, a), (rb, b)| ra.cmp(rb).then_with(|| b.updated_at.cmp(&a.updated_at)))
+        .map(|(_, t)| t.clone()))
+}
+
 // ==================== 统计查询函数 ====================
 
 pub fn get_precise_acquisition_stats(conn: &Connection) -> rusqlite::Result<serde_json::Value> {
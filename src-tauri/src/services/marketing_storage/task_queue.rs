@@ -0,0 +1,291 @@
+//! Priority queue over pending marketing tasks.
+//!
+//! 任务默认按 FIFO 处理。这里为待执行任务（NEW / READY）计算综合分：
+//! 线索分（外部写入或按评论点赞估算）、时效（评论发布时间半衰）、历史互动
+//! （同一作者此前成功执行过的任务数），结果写入 `task_queue` 表；
+//! `lock_next_ready_task` 按「置顶 → 综合分 → priority → 创建时间」取任务。
+//! 预览时若评分过期会自动重算，实现定期重评分。
+
+use chrono::{NaiveDateTime, Utc};
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+
+use super::models::TaskRow;
+use super::repositories as repo;
+
+/// 评分权重
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct QueueWeights {
+    pub lead: f64,
+    pub recency: f64,
+    pub engagement: f64,
+    /// 时效分减半所需小时数
+    pub recency_half_life_hours: f64,
+    /// 评分超过该分钟数视为过期，预览时自动重算
+    pub rescore_after_minutes: i64,
+}
+
+impl Default for QueueWeights {
+    fn default() -> Self {
+        Self { lead: 0.5, recency: 0.3, engagement: 0.2, recency_half_life_hours: 24.0, rescore_after_minutes: 10 }
+    }
+}
+
+/// 各项得分（均为 0-1）
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct ScoreBreakdown {
+    pub lead: f64,
+    pub recency: f64,
+    pub engagement: f64,
+    pub total: f64,
+}
+
+/// 评分输入
+#[derive(Debug, Clone, Default)]
+pub struct ScoreInput {
+    pub lead_score: Option<f64>,
+    pub like_count: Option<i32>,
+    /// 评论发布时间（缺失时用任务创建时间）
+    pub published_at: Option<String>,
+    pub created_at: String,
+    pub past_engagements: u32,
+}
+
+/// 队列中的任务
+#[derive(Debug, Clone, Serialize)]
+pub struct QueuedTask {
+    pub task: TaskRow,
+    pub score: f64,
+    pub pinned: bool,
+    pub breakdown: Option<ScoreBreakdown>,
+    pub scored_at: Option<String>,
+}
+
+fn parse_time(raw: &str) -> Option<NaiveDateTime> {
+    chrono::DateTime::parse_from_rfc3339(raw)
+        .map(|dt| dt.naive_utc())
+        .or_else(|_| NaiveDateTime::parse_from_str(raw, "%Y-%m-%d %H:%M:%S"))
+        .ok()
+}
+
+/// 计算综合分
+pub fn score(input: &ScoreInput, weights: &QueueWeights, now: NaiveDateTime) -> ScoreBreakdown {
+    let lead = match input.lead_score {
+        Some(score) => score.clamp(0.0, 1.0),
+        // 无外部线索分时，评论越热越可能是活跃用户
+        None => 1.0 - (-(input.like_count.unwrap_or(0).max(0) as f64) / 50.0).exp(),
+    };
+    let recency = input
+        .published_at
+        .as_deref()
+        .and_then(parse_time)
+        .or_else(|| parse_time(&input.created_at))
+        .map(|at| {
+            let age_hours = (now - at).num_minutes().max(0) as f64 / 60.0;
+            0.5f64.powf(age_hours / weights.recency_half_life_hours.max(0.1))
+        })
+        .unwrap_or(0.0);
+    let engagement = (input.past_engagements.min(3) as f64) / 3.0;
+    let total = weights.lead * lead + weights.recency * recency + weights.engagement * engagement;
+    ScoreBreakdown { lead, recency, engagement, total }
+}
+
+fn now_sql() -> String {
+    Utc::now().naive_utc().format("%Y-%m-%d %H:%M:%S").to_string()
+}
+
+/// 重算活动（为空时全部）中待执行任务的评分，返回评分条数
+pub fn rescore(conn: &Connection, campaign_id: Option<&str>, weights: &QueueWeights) -> rusqlite::Result<usize> {
+    let sql = r#"
+SELECT t.id, q.lead_score, c.like_count, c.publish_time, t.created_at,
+       (SELECT COUNT(*) FROM tasks p LEFT JOIN comments pc ON pc.id = p.comment_id
+         WHERE p.id != t.id AND p.status = 'DONE' AND p.result_code = 'OK'
+           AND COALESCE(pc.author_id, p.target_user_id) = COALESCE(c.author_id, t.target_user_id)) AS engaged
+FROM tasks t
+LEFT JOIN comments c ON c.id = t.comment_id
+LEFT JOIN task_queue q ON q.task_id = t.id
+WHERE t.status IN ('NEW', 'READY') AND (?1 IS NULL OR t.campaign_id = ?1)
+"#;
+    let now = Utc::now().naive_utc();
+    let scored_at = now_sql();
+    let mut stmt = conn.prepare(sql)?;
+    let rows = stmt.query_map(params![campaign_id], |row| {
+        Ok((
+            row.get::<_, String>(0)?,
+            ScoreInput {
+                lead_score: row.get(1)?,
+                like_count: row.get(2)?,
+                published_at: row.get(3)?,
+                created_at: row.get(4)?,
+                past_engagements: row.get::<_, i64>(5)?.max(0) as u32,
+            },
+        ))
+    })?;
+
+    let mut count = 0;
+    for row in rows {
+        let (task_id, input) = row?;
+        let breakdown = score(&input, weights, now);
+        let breakdown_json = serde_json::to_string(&breakdown).unwrap_or_default();
+        conn.execute(
+            r#"INSERT INTO task_queue (task_id, score, breakdown, scored_at) VALUES (?1, ?2, ?3, ?4)
+               ON CONFLICT(task_id) DO UPDATE SET score = excluded.score, breakdown = excluded.breakdown, scored_at = excluded.scored_at"#,
+            params![task_id, breakdown.total, breakdown_json, scored_at],
+        )?;
+        count += 1;
+    }
+    Ok(count)
+}
+
+/// 是否存在未评分或评分过期的待执行任务
+fn needs_rescore(conn: &Connection, campaign_id: Option<&str>, weights: &QueueWeights) -> rusqlite::Result<bool> {
+    let stale: i64 = conn.query_row(
+        r#"SELECT COUNT(*) FROM tasks t LEFT JOIN task_queue q ON q.task_id = t.id
+           WHERE t.status IN ('NEW', 'READY') AND (?1 IS NULL OR t.campaign_id = ?1)
+             AND (q.scored_at IS NULL OR q.scored_at < datetime('now', printf('-%d minutes', ?2)))"#,
+        params![campaign_id, weights.rescore_after_minutes],
+        |row| row.get(0),
+    )?;
+    Ok(stale > 0)
+}
+
+/// 预览接下来会被处理的任务（评分过期时先重算）
+pub fn preview_next(
+    conn: &Connection,
+    campaign_id: Option<&str>,
+    limit: usize,
+    weights: &QueueWeights,
+) -> rusqlite::Result<Vec<QueuedTask>> {
+    if needs_rescore(conn, campaign_id, weights)? {
+        rescore(conn, campaign_id, weights)?;
+    }
+    let sql = r#"
+SELECT t.id, t.task_type, t.comment_id, t.target_user_id, t.assign_account_id, t.status, t.executor_mode, t.result_code,
+       t.error_message, t.dedup_key, t.created_at, t.executed_at, t.priority, t.attempts, t.deadline_at, t.lock_owner,
+       t.lease_until, t.campaign_id, q.score, q.pinned_at, q.breakdown, q.scored_at
+FROM tasks t
+LEFT JOIN task_queue q ON q.task_id = t.id
+WHERE t.status IN ('NEW', 'READY') AND (?1 IS NULL OR t.campaign_id = ?1)
+  AND (t.deadline_at IS NULL OR t.deadline_at > datetime('now'))
+ORDER BY q.pinned_at IS NULL, q.pinned_at ASC, COALESCE(q.score, 0) DESC, t.priority ASC, t.created_at ASC
+LIMIT ?2
+"#;
+    let mut stmt = conn.prepare(sql)?;
+    let rows = stmt.query_map(params![campaign_id, limit as i64], |row| {
+        let breakdown: Option<String> = row.get(20)?;
+        Ok(QueuedTask {
+            task: repo::map_task_row(row)?,
+            score: row.get::<_, Option<f64>>(18)?.unwrap_or(0.0),
+            pinned: row.get::<_, Option<String>>(19)?.is_some(),
+            breakdown: breakdown.and_then(|b| serde_json::from_str(&b).ok()),
+            scored_at: row.get(21)?,
+        })
+    })?;
+    rows.collect()
+}
+
+/// 手动置顶 / 取消置顶
+pub fn pin(conn: &Connection, task_id: &str, pinned: bool) -> rusqlite::Result<()> {
+    let pinned_at = pinned.then(now_sql);
+    conn.execute(
+        r#"INSERT INTO task_queue (task_id, pinned_at) VALUES (?1, ?2)
+           ON CONFLICT(task_id) DO UPDATE SET pinned_at = excluded.pinned_at"#,
+        params![task_id, pinned_at],
+    )?;
+    Ok(())
+}
+
+/// 写入外部线索分（0-1，为空时恢复按评论热度估算）；下次预览时重算
+pub fn set_lead_score(conn: &Connection, task_id: &str, lead_score: Option<f64>) -> rusqlite::Result<()> {
+    conn.execute(
+        r#"INSERT INTO task_queue (task_id, lead_score) VALUES (?1, ?2)
+           ON CONFLICT(task_id) DO UPDATE SET lead_score = excluded.lead_score, scored_at = NULL"#,
+        params![task_id, lead_score.map(|s| s.clamp(0.0, 1.0))],
+    )?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::marketing_storage::models::{ExecutorMode, TaskPayload, TaskType};
+
+    fn conn() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        repo::ensure_marketing_tables(&conn).unwrap();
+        conn
+    }
+
+    fn task(conn: &Connection, user: &str, campaign: &str) -> String {
+        let id = repo::insert_task(
+            conn,
+            &TaskPayload {
+                task_type: TaskType::Follow,
+                comment_id: None,
+                target_user_id: Some(user.to_string()),
+                assign_account_id: "acc".into(),
+                executor_mode: ExecutorMode::Api,
+                dedup_key: format!("follow:{}", user),
+                priority: None,
+                deadline_at: None,
+                campaign_id: Some(campaign.to_string()),
+            },
+        )
+        .unwrap();
+        conn.execute("UPDATE tasks SET status = 'READY' WHERE id = ?1", params![id]).unwrap();
+        id
+    }
+
+    #[test]
+    fn orders_by_pin_then_score_and_locks_in_queue_order() {
+        let mut conn = conn();
+        let first = task(&conn, "u1", "c1");
+        let hot = task(&conn, "u2", "c1");
+        let pinned = task(&conn, "u3", "c1");
+        task(&conn, "u4", "other");
+
+        set_lead_score(&conn, &hot, Some(1.0)).unwrap();
+        pin(&conn, &pinned, true).unwrap();
+
+        let weights = QueueWeights::default();
+        let preview = preview_next(&conn, Some("c1"), 10, &weights).unwrap();
+        let ids: Vec<&str> = preview.iter().map(|q| q.task.id.as_str()).collect();
+        assert_eq!(ids, vec![pinned.as_str(), hot.as_str(), first.as_str()]);
+        assert!(preview[0].pinned);
+        assert_eq!(preview[1].breakdown.as_ref().unwrap().lead, 1.0);
+
+        let locked = repo::lock_next_ready_task(&mut conn, "acc", 60).unwrap().unwrap();
+        assert_eq!(locked.id, pinned);
+        assert_eq!(locked.campaign_id.as_deref(), Some("c1"));
+
+        pin(&conn, &pinned, false).unwrap();
+        let now = Utc::now().naive_utc();
+        let stale = ScoreInput { created_at: "2000-01-01 00:00:00".into(), ..Default::default() };
+        assert!(score(&stale, &weights, now).recency < 0.001);
+    }
+
+    #[test]
+    fn guarded_lock_skips_protected_targets_with_audit() {
+        let mut conn = conn();
+        let vip = task(&conn, "vip_user", "c1");
+        let normal = task(&conn, "u2", "c1");
+        pin(&conn, &vip, true).unwrap();
+
+        let locked = repo::lock_next_ready_task_guarded(&mut conn, "acc", 60, |_, _, target| {
+            (target == "vip_user").then(|| "VIP".to_string())
+        })
+        .unwrap()
+        .unwrap();
+        assert_eq!(locked.id, normal);
+
+        let (status, result_code): (String, String) = conn
+            .query_row("SELECT status, result_code FROM tasks WHERE id = ?1", params![vip], |row| Ok((row.get(0)?, row.get(1)?)))
+            .unwrap();
+        assert_eq!((status.as_str(), result_code.as_str()), ("FAILED", "BLOCKED"));
+        let audits: i64 = conn
+            .query_row("SELECT COUNT(*) FROM audit_logs WHERE action = 'PROTECTED_SKIP' AND task_id = ?1", params![vip], |row| row.get(0))
+            .unwrap();
+        assert_eq!(audits, 1);
+    }
+}
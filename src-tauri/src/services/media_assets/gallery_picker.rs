@@ -0,0 +1,149 @@
+// src-tauri/src/services/media_assets/gallery_picker.rs
+// module: media_assets | layer: services | role: 图库选择器自动化
+// summary: 在回复输入框里点开图片入口 → 选相册第一张（刚推送的素材）→ 确认，按平台配置入口与确认按钮文案
+
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tracing::info;
+
+use crate::automation::analysis::hierarchy::parse_bounds;
+use crate::services::adb::AdbService;
+
+/// 每次点击后等待界面切换的时间
+const SETTLE_DELAY: Duration = Duration::from_millis(1200);
+/// 顶部区域（标题栏 / 相册切换）占屏高比例，缩略图候选不取该区域
+const TOP_BAR_RATIO: f64 = 0.08;
+/// 缩略图最小边长（像素）
+const MIN_THUMB_SIDE: i32 = 100;
+
+/// 某平台的图库选择流程
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GalleryFlow {
+    /// 回复框里图片入口的文字或描述
+    pub attach_labels: Vec<String>,
+    /// 选中图片后的确认按钮文字
+    pub confirm_labels: Vec<String>,
+}
+
+impl GalleryFlow {
+    /// 各平台默认流程（platform 与 SocialPlatform 的序列化值一致）
+    pub fn for_platform(platform: &str) -> Self {
+        let (attach, confirm): (&[&str], &[&str]) = match platform {
+            "xhs" => (&["图片", "相册", "添加图片"], &["完成", "确定", "发送"]),
+            "weibo" => (&["图片", "添加图片", "相册"], &["下一步", "完成", "确定"]),
+            "kuaishou" => (&["图片", "相册", "发送图片"], &["发送", "完成", "确定"]),
+            _ => (&["图片", "相册", "发送图片", "表情和图片"], &["发送", "完成", "确定"]),
+        };
+        Self {
+            attach_labels: attach.iter().map(|s| s.to_string()).collect(),
+            confirm_labels: confirm.iter().map(|s| s.to_string()).collect(),
+        }
+    }
+}
+
+fn center(bounds: (i32, i32, i32, i32)) -> (i32, i32) {
+    ((bounds.0 + bounds.2) / 2, (bounds.1 + bounds.3) / 2)
+}
+
+/// 找文字或描述与任一标签相同（其次包含）的可点击节点，按标签顺序优先
+pub fn find_labeled(xml: &str, labels: &[String]) -> Result<Option<(i32, i32)>, String> {
+    let doc = roxmltree::Document::parse(xml).map_err(|e| format!("解析UI Dump失败: {}", e))?;
+    let nodes: Vec<_> = doc
+        .descendants()
+        .filter(|n| n.has_tag_name("node") && n.attribute("enabled") != Some("false"))
+        .collect();
+    let label_of = |n: &roxmltree::Node, exact: bool, label: &str| {
+        ["text", "content-desc"].iter().any(|attr| {
+            n.attribute(*attr)
+                .is_some_and(|v| if exact { v.trim() == label } else { !v.is_empty() && v.contains(label) })
+        })
+    };
+    for exact in [true, false] {
+        for label in labels {
+            if let Some(bounds) = nodes
+                .iter()
+                .filter(|n| label_of(n, exact, label))
+                .find_map(|n| n.attribute("bounds").and_then(parse_bounds))
+            {
+                return Ok(Some(center(bounds)));
+            }
+        }
+    }
+    Ok(None)
+}
+
+/// 找图库网格里的第一张缩略图（最靠上、其次最靠左的近似正方形可点击节点）
+pub fn find_first_thumbnail(xml: &str) -> Result<Option<(i32, i32)>, String> {
+    let doc = roxmltree::Document::parse(xml).map_err(|e| format!("解析UI Dump失败: {}", e))?;
+    let screen_bottom = doc
+        .descendants()
+        .filter_map(|n| n.attribute("bounds").and_then(parse_bounds))
+        .map(|b| b.3)
+        .max()
+        .unwrap_or(0);
+    let top_limit = (screen_bottom as f64 * TOP_BAR_RATIO) as i32;
+
+    let thumb = doc
+        .descendants()
+        .filter(|n| n.has_tag_name("node"))
+        .filter(|n| n.attribute("clickable") == Some("true") || n.attribute("checkable") == Some("true"))
+        .filter(|n| {
+            let class = n.attribute("class").unwrap_or("");
+            let id = n.attribute("resource-id").unwrap_or("").to_lowercase();
+            class.contains("ImageView") || ["thumb", "image", "photo", "media", "cover"].iter().any(|k| id.contains(k))
+        })
+        .filter_map(|n| n.attribute("bounds").and_then(parse_bounds))
+        .filter(|&(l, t, r, b)| {
+            let (w, h) = (r - l, b - t);
+            t >= top_limit && w >= MIN_THUMB_SIDE && h >= MIN_THUMB_SIDE && (w - h).abs() * 5 <= w
+        })
+        .min_by_key(|&(l, t, _, _)| (t, l));
+    Ok(thumb.map(center))
+}
+
+async fn dump(device_id: &str) -> Result<String, String> {
+    AdbService::new()
+        .dump_ui_hierarchy(device_id)
+        .await
+        .map_err(|e| format!("Failed to dump hierarchy: {}", e))
+}
+
+async fn tap(device_id: &str, (x, y): (i32, i32)) -> Result<(), String> {
+    AdbService::new().tap_screen(device_id, x, y).await.map_err(|e| format!("点击失败: {}", e))?;
+    tokio::time::sleep(SETTLE_DELAY).await;
+    Ok(())
+}
+
+/// 在当前回复输入界面附加相册里最新的一张图片（调用前应已推送素材并触发媒体扫描）
+pub async fn attach_latest_image(device_id: &str, flow: &GalleryFlow) -> Result<(), String> {
+    let attach = find_labeled(&dump(device_id).await?, &flow.attach_labels)?
+        .ok_or_else(|| format!("未找到图片入口（{}）", flow.attach_labels.join("/")))?;
+    tap(device_id, attach).await?;
+
+    let thumb = find_first_thumbnail(&dump(device_id).await?)?.ok_or("图库中未找到可选择的图片")?;
+    tap(device_id, thumb).await?;
+
+    // 部分平台点选即返回输入框，没有确认按钮
+    if let Some(confirm) = find_labeled(&dump(device_id).await?, &flow.confirm_labels)? {
+        tap(device_id, confirm).await?;
+    }
+    info!("🖼️ 已在设备 {} 上附加图片", device_id);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GALLERY: &str = r#"<?xml version='1.0' encoding='UTF-8' standalone='yes' ?><hierarchy rotation="0"><node class="FrameLayout" bounds="[0,0][1080,2400]"><node text="相册" clickable="true" bounds="[0,0][300,150]"/><node class="RecyclerView" bounds="[0,150][1080,2200]"><node class="ImageView" resource-id="app:id/thumb" clickable="true" bounds="[360,200][720,560]"/><node class="ImageView" resource-id="app:id/thumb" clickable="true" bounds="[0,200][360,560]"/><node class="ImageView" resource-id="app:id/banner" clickable="true" bounds="[0,100][1080,190]"/></node><node text="完成(1)" clickable="true" bounds="[800,2200][1080,2400]"/></node></hierarchy>"#;
+
+    #[test]
+    fn picks_first_thumbnail_and_labels() {
+        assert_eq!(find_first_thumbnail(GALLERY).unwrap(), Some((180, 380)));
+        let flow = GalleryFlow::for_platform("xhs");
+        // 精确匹配失败时按包含匹配
+        assert_eq!(find_labeled(GALLERY, &flow.confirm_labels).unwrap(), Some((940, 2300)));
+        assert_eq!(find_labeled(GALLERY, &["相册".to_string()]).unwrap(), Some((150, 75)));
+        assert_eq!(find_labeled(GALLERY, &["视频".to_string()]).unwrap(), None);
+    }
+}
@@ -0,0 +1,265 @@
+// src-tauri/src/services/media_assets/mod.rs
+// module: media_assets | layer: services | role: 回复媒体素材库
+// summary: 审核过的图片素材按 SHA-256 校验存放在数据目录，发送前推送到设备相册并触发媒体扫描，逐条记录每次回复发送了哪个素材
+
+pub mod gallery_picker;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use tracing::{info, warn};
+
+use crate::services::device_files::{self, shell_quote};
+use crate::utils::adb_utils::execute_adb_command;
+
+const MEDIA_DIR_NAME: &str = "media_assets";
+const INDEX_FILE_NAME: &str = "index.json";
+/// 设备端素材目录（相册可见）
+pub const DEVICE_MEDIA_DIR: &str = "/sdcard/DCIM/ReplyMedia";
+/// 允许作为回复附件的扩展名
+const ALLOWED_EXTENSIONS: &[&str] = &["jpg", "jpeg", "png", "gif", "webp"];
+/// 素材大小上限
+const MAX_ASSET_BYTES: u64 = 20 * 1024 * 1024;
+/// 发送记录最多保留条数
+const MAX_SEND_RECORDS: usize = 10_000;
+
+static STORE: OnceLock<Mutex<MediaAssetStore>> = OnceLock::new();
+
+/// 媒体素材
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct MediaAsset {
+    pub id: String,
+    pub name: String,
+    /// 数据目录内的文件名（`<sha256 前 16 位>.<ext>`）
+    pub file_name: String,
+    pub mime_type: String,
+    pub size: u64,
+    pub sha256: String,
+    /// 只有审核通过的素材可以发送
+    pub approved: bool,
+    pub approved_by: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// 一次回复附带素材的发送记录
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MediaSendRecord {
+    pub asset_id: String,
+    pub sha256: String,
+    pub reply_plan_id: String,
+    pub device_id: String,
+    pub remote_path: String,
+    pub sent_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct MediaIndex {
+    #[serde(default)]
+    assets: Vec<MediaAsset>,
+    #[serde(default)]
+    sends: Vec<MediaSendRecord>,
+}
+
+pub struct MediaAssetStore {
+    dir: PathBuf,
+    index: MediaIndex,
+}
+
+fn sha256_file(path: &Path) -> Result<String, String> {
+    let bytes = std::fs::read(path).map_err(|e| format!("读取素材失败: {}", e))?;
+    Ok(hex::encode(Sha256::digest(&bytes)))
+}
+
+fn mime_for(extension: &str) -> &'static str {
+    match extension {
+        "png" => "image/png",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        _ => "image/jpeg",
+    }
+}
+
+impl MediaAssetStore {
+    pub fn global() -> &'static Mutex<MediaAssetStore> {
+        STORE.get_or_init(|| {
+            let dir = dirs::data_dir().unwrap_or_else(|| PathBuf::from(".")).join("employee-gui").join(MEDIA_DIR_NAME);
+            Mutex::new(Self::open(dir))
+        })
+    }
+
+    pub fn open(dir: PathBuf) -> Self {
+        let index = std::fs::read_to_string(dir.join(INDEX_FILE_NAME))
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+        Self { dir, index }
+    }
+
+    fn save_index(&self) -> Result<(), String> {
+        std::fs::create_dir_all(&self.dir).map_err(|e| format!("创建素材目录失败: {}", e))?;
+        let content = serde_json::to_string_pretty(&self.index).map_err(|e| e.to_string())?;
+        std::fs::write(self.dir.join(INDEX_FILE_NAME), content).map_err(|e| format!("写入素材索引失败: {}", e))
+    }
+
+    pub fn list(&self) -> Vec<MediaAsset> {
+        self.index.assets.clone()
+    }
+
+    fn find(&self, id: &str) -> Result<&MediaAsset, String> {
+        self.index.assets.iter().find(|a| a.id == id).ok_or_else(|| format!("素材不存在: {}", id))
+    }
+
+    /// 导入本地图片（待审核）；内容相同的素材只保留一份
+    pub fn import(&mut self, local_path: &Path, name: Option<&str>) -> Result<MediaAsset, String> {
+        let extension = local_path
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(str::to_lowercase)
+            .filter(|e| ALLOWED_EXTENSIONS.contains(&e.as_str()))
+            .ok_or_else(|| format!("不支持的素材格式（仅支持 {}）", ALLOWED_EXTENSIONS.join("/")))?;
+        let size = std::fs::metadata(local_path).map_err(|e| format!("读取素材失败: {}", e))?.len();
+        if size == 0 || size > MAX_ASSET_BYTES {
+            return Err(format!("素材大小需在 1B ~ {}MB 之间", MAX_ASSET_BYTES / 1024 / 1024));
+        }
+        let sha256 = sha256_file(local_path)?;
+        if let Some(existing) = self.index.assets.iter().find(|a| a.sha256 == sha256) {
+            return Ok(existing.clone());
+        }
+
+        std::fs::create_dir_all(&self.dir).map_err(|e| format!("创建素材目录失败: {}", e))?;
+        let file_name = format!("{}.{}", &sha256[..16], extension);
+        std::fs::copy(local_path, self.dir.join(&file_name)).map_err(|e| format!("复制素材失败: {}", e))?;
+        let asset = MediaAsset {
+            id: format!("media_{}", &sha256[..12]),
+            name: name
+                .map(str::to_string)
+                .unwrap_or_else(|| local_path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default()),
+            file_name,
+            mime_type: mime_for(&extension).to_string(),
+            size,
+            sha256,
+            approved: false,
+            approved_by: None,
+            created_at: Utc::now(),
+        };
+        self.index.assets.push(asset.clone());
+        self.save_index()?;
+        Ok(asset)
+    }
+
+    /// 审核通过 / 撤销审核
+    pub fn set_approved(&mut self, id: &str, approved: bool, approver: Option<&str>) -> Result<MediaAsset, String> {
+        let asset = self.index.assets.iter_mut().find(|a| a.id == id).ok_or_else(|| format!("素材不存在: {}", id))?;
+        asset.approved = approved;
+        asset.approved_by = if approved { approver.map(str::to_string) } else { None };
+        let asset = asset.clone();
+        self.save_index()?;
+        Ok(asset)
+    }
+
+    pub fn delete(&mut self, id: &str) -> Result<bool, String> {
+        let Some(pos) = self.index.assets.iter().position(|a| a.id == id) else {
+            return Ok(false);
+        };
+        let asset = self.index.assets.remove(pos);
+        let _ = std::fs::remove_file(self.dir.join(&asset.file_name));
+        self.save_index()?;
+        Ok(true)
+    }
+
+    /// 取可发送的素材及本地路径：必须已审核，且文件校验和与入库时一致
+    pub fn sendable(&self, id: &str) -> Result<(MediaAsset, PathBuf), String> {
+        let asset = self.find(id)?;
+        if !asset.approved {
+            return Err(format!("素材未通过审核: {}", asset.name));
+        }
+        let path = self.dir.join(&asset.file_name);
+        let actual = sha256_file(&path)?;
+        if actual != asset.sha256 {
+            return Err(format!("素材文件校验失败（已被修改或损坏）: {}", asset.name));
+        }
+        Ok((asset.clone(), path))
+    }
+
+    pub fn record_send(&mut self, record: MediaSendRecord) -> Result<(), String> {
+        self.index.sends.push(record);
+        if self.index.sends.len() > MAX_SEND_RECORDS {
+            let excess = self.index.sends.len() - MAX_SEND_RECORDS;
+            self.index.sends.drain(0..excess);
+        }
+        self.save_index()
+    }
+
+    /// 发送记录（新的在前），可按回复计划过滤
+    pub fn sends(&self, reply_plan_id: Option<&str>, limit: usize) -> Vec<MediaSendRecord> {
+        self.index
+            .sends
+            .iter()
+            .rev()
+            .filter(|s| reply_plan_id.is_none_or(|id| s.reply_plan_id == id))
+            .take(limit)
+            .cloned()
+            .collect()
+    }
+}
+
+/// 推送素材到设备相册目录并触发媒体扫描，返回设备端路径
+pub fn push_to_device(device_id: &str, asset: &MediaAsset, local_path: &Path) -> Result<String, String> {
+    let remote_path = format!("{}/{}", DEVICE_MEDIA_DIR, asset.file_name);
+    let transfer = device_files::push_file(device_id, local_path, &remote_path, |_, _| {})?;
+    if !transfer.verified {
+        return Err(format!("素材推送后校验不一致: {}", remote_path));
+    }
+    // 让相册立即看到新文件（否则图库选择器里可能找不到）
+    let scan = format!(
+        "am broadcast -a android.intent.action.MEDIA_SCANNER_SCAN_FILE -d {}",
+        shell_quote(&format!("file://{}", remote_path))
+    );
+    if let Err(e) = execute_adb_command(&["-s", device_id, "shell", &scan]) {
+        warn!("⚠️ 触发媒体扫描失败（相册可能延迟显示）: {}", e);
+    }
+    info!("🖼️ 素材已推送到设备 {}: {}", device_id, remote_path);
+    Ok(remote_path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_import_approve_and_checksum_guard() {
+        let dir = tempfile::tempdir().unwrap();
+        let src = dir.path().join("banner.PNG");
+        std::fs::write(&src, b"fake-png").unwrap();
+        let mut store = MediaAssetStore::open(dir.path().join("store"));
+
+        let asset = store.import(&src, None).unwrap();
+        assert_eq!((asset.name.as_str(), asset.mime_type.as_str()), ("banner.PNG", "image/png"));
+        assert_eq!(store.import(&src, Some("again")).unwrap().id, asset.id);
+        assert!(store.sendable(&asset.id).is_err());
+
+        store.set_approved(&asset.id, true, Some("ops")).unwrap();
+        let (_, path) = store.sendable(&asset.id).unwrap();
+        std::fs::write(&path, b"tampered").unwrap();
+        assert!(store.sendable(&asset.id).unwrap_err().contains("校验失败"));
+
+        assert!(store.import(&dir.path().join("a.txt"), None).is_err());
+        store
+            .record_send(MediaSendRecord {
+                asset_id: asset.id.clone(),
+                sha256: asset.sha256.clone(),
+                reply_plan_id: "plan_1".into(),
+                device_id: "d1".into(),
+                remote_path: format!("{}/{}", DEVICE_MEDIA_DIR, asset.file_name),
+                sent_at: Utc::now(),
+            })
+            .unwrap();
+        let reopened = MediaAssetStore::open(dir.path().join("store"));
+        assert_eq!(reopened.sends(Some("plan_1"), 10).len(), 1);
+        assert!(reopened.sends(Some("plan_2"), 10).is_empty());
+    }
+}
@@ -21,10 +21,48 @@ pub mod commands; // Tauri 命令封装
 // pub mod contact_service; // 已删除：合并至 contact_storage
 pub mod contact_storage; // 新增：联系人号码存储（TXT导入到SQLite）
 pub mod contact_verification; // 新增：快速号码验证服务
+pub mod contact_watch; // 新增：联系人来源文件夹监听（去抖 + 自动导入 + 暂停 / 恢复）
+pub mod comment_language; // 新增：评论语言识别（中文 / 英文 / 混合）
 pub mod crash_debugger;
 pub mod device_contact_metrics;
 pub mod diagnostic_service; // 新增：系统诊断服务
 pub mod duplication_guard; // 新增：查重防护服务（内存态）
+pub mod billing_journal; // 新增：计费动作日志（按客户按天 CSV）
+pub mod network_profile; // 新增：设备网络配置档（按设备代理）
+pub mod device_location; // 新增：设备定位控制（模拟定位 + 活动预设）
+pub mod device_files; // 新增：设备端文件操作（列目录/推送/拉取/校验）
+pub mod concurrency; // 新增：执行并发控制（全局 / 单设备 / 类别上限与公平排队）
+pub mod demo_dataset; // 新增：演示数据集脱敏导出（联系人 / 精准获客库）
+pub mod event_outbox; // 新增：前端事件发件箱（断线期间的任务状态事件按游标补发）
+pub mod device_status_indicator; // 新增：自动化运行时的设备端状态提示（通知 / 代理悬浮层）
+pub mod failure_annotation; // 新增：失败截图标注与失败记录
+pub mod screen_stability; // 新增：屏幕稳定检测（wait_for_idle）
+pub mod interaction_heatmap; // 新增：按界面指纹的元素交互热力统计
+pub mod scoped_dump; // 新增：容器范围 UI dump
+pub mod list_tracking; // 新增：信息流列表追踪（去重 / 滚动进度 / 到底判定）
+pub mod campaign_sim; // 新增：活动模拟器（ETA / 设备利用率 / 每日动作量）
+pub mod licensing; // 新增：授权校验与高级功能门禁
+pub mod telemetry; // 新增：匿名使用统计（需用户开启）
+pub mod trend_rollup; // 新增：历史趋势按天汇总（仪表盘时间序列）
+pub mod artifact_store; // 新增：短期制品存储（令牌取回，到期清理）
+pub mod screen_share; // 新增：设备截图分享（制品 + webhook 上传）
+pub mod protected_accounts; // 新增：受保护账号名单（永不自动触达）
+pub mod media_assets; // 新增：回复媒体素材库（审核 + 校验 + 推送到设备相册）
+pub mod wechat_verification; // 新增：已导入号码的微信注册核验（添加朋友搜索 + 限速 + 风控中止）
+pub mod warmup; // 新增：新号养号计划（动作曲线 + 执行器 + 就绪度门禁）
+pub mod self_test; // 新增：模拟器自检（金丝雀脚本 + 耗时基线 + 环境漂移）
+pub mod smoke_test; // 新增：目标应用冒烟测试（声明式定义 + 定时执行 + 回归历史）
+pub mod script_scheduler; // 新增：智能脚本定时执行（cron 计划 + SQLite 持久化）
+pub mod selector_precompute; // 新增：空闲时预计算步骤选择器候选
+pub mod support_session; // 新增：支持会话（日志脱敏推送 / 加密日志包）
+pub mod script_recorder; // 新增：脚本录制（getevent 手势 → 智能脚本步骤）
+pub mod device_windows; // 新增：设备独立控制窗口（登记表 + 按设备路由事件）
+pub mod app_profiles; // 新增：应用元素画像（dump 结构推断 + 策略预排序 + 选择器建议）
+pub mod device_config_sync; // 新增：设备配置与 lead-server 双向同步（优先级合并 + 冲突检测）
+pub mod realtime_uplink; // 新增：实时看板上行通道（WebSocket + 服务端订阅过滤）
+pub mod report_export; // 新增：统一报表导出（列定义 + 格式规则 + CSV / xlsx / JSON 流式写入）
+pub mod recipes; // 新增：自动化配方（导入 → 分配 → VCF → 导入脚本 → 关注活动，阶段检查点续跑）
+pub mod quick_actions; // 新增：快捷动作（按用户的快捷键绑定 + 后端按序执行多命令宏）
 pub mod employee_service;
 pub mod execution; // 新增：执行分层骨架 (模型/重试/快照)
 // pub mod huawei_enhanced_importer; // 已删除：合并至 vcf 模块
@@ -35,6 +73,8 @@ pub mod scrcpy_manager;
 pub mod script_execution; // 新增：脚本执行模块（控制流处理系统）
 // ✅ 已删除：script_executor (535行) - 基础执行器已被 SmartScriptExecutor 完全替代
 pub mod script_manager; // 新增：智能脚本管理服务
+#[cfg(any(test, feature = "scenario-tests"))]
+pub mod scenario_runner; // 端到端场景回归（FakeDevice + 内存数据库）
 pub mod smart_app; // 新增：智能应用服务
 pub mod smart_app_manager;
 // pub mod smart_app_service; // 已删除：应用管理服务（迁移至 commands/apps.rs）
@@ -0,0 +1,490 @@
+// src-tauri/src/services/network_profile/mod.rs
+// module: network_profile | layer: services | role: 设备网络配置档
+// summary: 按设备分配 HTTP 代理配置档，经 adb `settings put global http_proxy` 下发并回读校验，运行结束后恢复干净设置，并记录每次营销活动运行时生效的配置档
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{Mutex, OnceLock};
+use tracing::{info, warn};
+
+use crate::utils::adb_utils::execute_adb_command;
+
+/// 配置文件名
+const CONFIG_FILE_NAME: &str = "network_profiles.json";
+/// 运行记录文件名
+const RUNS_FILE_NAME: &str = "network_profile_runs.json";
+/// 最多保留的运行记录条数
+const MAX_RUN_RECORDS: usize = 2000;
+/// 清除代理时写入的值（Android 约定 `:0` 表示无代理）
+const CLEAR_PROXY_VALUE: &str = ":0";
+
+static MANAGER: OnceLock<Mutex<NetworkProfileManager>> = OnceLock::new();
+
+/// 一个网络配置档（HTTP 代理）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NetworkProfile {
+    pub id: String,
+    pub name: String,
+    pub host: String,
+    pub port: u16,
+    /// 不走代理的主机列表（逗号分隔，写入 global_http_proxy_exclusion_list）
+    #[serde(default)]
+    pub bypass: Option<String>,
+    /// 备注地区（仅展示用）
+    #[serde(default)]
+    pub region: Option<String>,
+}
+
+impl NetworkProfile {
+    /// 写入 `http_proxy` 的值
+    pub fn proxy_value(&self) -> String {
+        format!("{}:{}", self.host.trim(), self.port)
+    }
+
+    /// 规范化后的排除列表（去掉空项与首尾空白，逗号连接）；为空时返回 None
+    pub fn bypass_value(&self) -> Option<String> {
+        let entries: Vec<&str> = self
+            .bypass
+            .as_deref()
+            .unwrap_or_default()
+            .split(',')
+            .map(str::trim)
+            .filter(|e| !e.is_empty())
+            .collect();
+        (!entries.is_empty()).then(|| entries.join(","))
+    }
+
+    /// 主机、端口、排除列表都会拼进 adb shell 命令，只接受主机名 / IPv4 / 通配符主机
+    fn validate(&self) -> Result<(), String> {
+        if self.id.trim().is_empty() {
+            return Err("配置档 ID 不能为空".into());
+        }
+        if !is_valid_host(self.host.trim(), false) {
+            return Err(format!("配置档 {} 的代理主机无效: {:?}", self.id, self.host));
+        }
+        if self.port == 0 {
+            return Err(format!("配置档 {} 的代理端口无效", self.id));
+        }
+        if let Some(bypass) = self.bypass_value() {
+            if let Some(entry) = bypass.split(',').find(|e| !is_valid_host(e, true)) {
+                return Err(format!("配置档 {} 的排除列表包含无效主机: {:?}", self.id, entry));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// 主机名（RFC 1123 标签）或 IPv4；`allow_wildcard` 时允许 `*` 作为整个标签（如 `*.example.com`、`192.168.*`）
+fn is_valid_host(host: &str, allow_wildcard: bool) -> bool {
+    if host.is_empty() || host.len() > 253 {
+        return false;
+    }
+    host.split('.').all(|label| {
+        (allow_wildcard && label == "*")
+            || (!label.is_empty()
+                && label.len() <= 63
+                && !label.starts_with('-')
+                && !label.ends_with('-')
+                && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-'))
+    })
+}
+
+/// 配置档与设备分配
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NetworkProfileConfig {
+    #[serde(default)]
+    pub profiles: Vec<NetworkProfile>,
+    /// 设备 ID → 配置档 ID
+    #[serde(default)]
+    pub assignments: HashMap<String, String>,
+    /// 运行结束后是否自动恢复干净网络设置
+    #[serde(default = "default_restore_after_run")]
+    pub restore_after_run: bool,
+}
+
+fn default_restore_after_run() -> bool {
+    true
+}
+
+impl Default for NetworkProfileConfig {
+    fn default() -> Self {
+        Self { profiles: Vec::new(), assignments: HashMap::new(), restore_after_run: true }
+    }
+}
+
+/// 设备当前代理状态
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeviceProxyStatus {
+    pub device_id: String,
+    /// 设备上读回的代理（None 表示未设置代理）
+    pub actual: Option<String>,
+    /// 期望的代理（None 表示期望无代理）
+    pub expected: Option<String>,
+    pub matches: bool,
+}
+
+/// 一次营销活动运行的网络记录
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NetworkRunRecord {
+    pub run_id: String,
+    pub campaign_id: String,
+    pub device_id: String,
+    /// 未分配配置档时为空（直连）
+    pub profile_id: Option<String>,
+    pub profile_name: Option<String>,
+    pub proxy: Option<String>,
+    /// 开始时代理校验是否通过
+    pub verified: bool,
+    /// RFC3339 本地时间
+    pub started_at: String,
+    #[serde(default)]
+    pub ended_at: Option<String>,
+    /// 结束时是否已恢复干净设置
+    #[serde(default)]
+    pub restored: bool,
+}
+
+/// 设备 shell 抽象（便于在无设备环境下测试）
+pub trait DeviceShell {
+    fn shell(&self, device_id: &str, command: &[&str]) -> Result<String, String>;
+}
+
+/// 通过 adb 执行 shell 命令
+pub struct AdbDeviceShell;
+
+impl DeviceShell for AdbDeviceShell {
+    fn shell(&self, device_id: &str, command: &[&str]) -> Result<String, String> {
+        let mut args = vec!["-s", device_id, "shell"];
+        args.extend_from_slice(command);
+        let output = execute_adb_command(&args).map_err(|e| format!("adb 执行失败: {}", e))?;
+        if !output.status.success() {
+            return Err(format!(
+                "adb 命令失败 ({}): {}",
+                command.join(" "),
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    }
+}
+
+/// 读取设备当前代理；`null`、空值与 `:0` 都视为未设置
+pub fn read_proxy(shell: &dyn DeviceShell, device_id: &str) -> Result<Option<String>, String> {
+    let value = shell.shell(device_id, &["settings", "get", "global", "http_proxy"])?;
+    let value = value.trim();
+    if value.is_empty() || value == "null" || value == CLEAR_PROXY_VALUE {
+        return Ok(None);
+    }
+    Ok(Some(value.to_string()))
+}
+
+/// 校验设备代理是否与期望一致
+pub fn verify_proxy(shell: &dyn DeviceShell, device_id: &str, expected: Option<&str>) -> Result<DeviceProxyStatus, String> {
+    let actual = read_proxy(shell, device_id)?;
+    Ok(DeviceProxyStatus {
+        device_id: device_id.to_string(),
+        matches: actual.as_deref() == expected,
+        actual,
+        expected: expected.map(str::to_string),
+    })
+}
+
+/// 下发配置档并回读校验
+pub fn apply_profile(shell: &dyn DeviceShell, device_id: &str, profile: &NetworkProfile) -> Result<DeviceProxyStatus, String> {
+    profile.validate()?;
+    let proxy = profile.proxy_value();
+    shell.shell(device_id, &["settings", "put", "global", "http_proxy", &proxy])?;
+    match profile.bypass_value() {
+        Some(bypass) => {
+            shell.shell(device_id, &["settings", "put", "global", "global_http_proxy_exclusion_list", &bypass])?;
+        }
+        None => {
+            shell.shell(device_id, &["settings", "delete", "global", "global_http_proxy_exclusion_list"])?;
+        }
+    }
+    let status = verify_proxy(shell, device_id, Some(&proxy))?;
+    if status.matches {
+        info!("🌐 设备 {} 已应用网络配置档 {} ({})", device_id, profile.id, proxy);
+    } else {
+        warn!("⚠️ 设备 {} 代理校验不一致: 期望 {}，实际 {:?}", device_id, proxy, status.actual);
+    }
+    Ok(status)
+}
+
+/// 清除代理相关设置，恢复直连
+pub fn restore_clean(shell: &dyn DeviceShell, device_id: &str) -> Result<DeviceProxyStatus, String> {
+    shell.shell(device_id, &["settings", "put", "global", "http_proxy", CLEAR_PROXY_VALUE])?;
+    for key in ["global_http_proxy_host", "global_http_proxy_port", "global_http_proxy_exclusion_list"] {
+        // 旧系统上这些键可能不存在，删除失败不影响结果
+        if let Err(e) = shell.shell(device_id, &["settings", "delete", "global", key]) {
+            warn!("⚠️ 删除 {} 失败 ({}): {}", key, device_id, e);
+        }
+    }
+    let status = verify_proxy(shell, device_id, None)?;
+    if !status.matches {
+        warn!("⚠️ 设备 {} 代理未能清除: {:?}", device_id, status.actual);
+    }
+    Ok(status)
+}
+
+/// 网络配置档管理器
+pub struct NetworkProfileManager {
+    config: NetworkProfileConfig,
+    runs: Vec<NetworkRunRecord>,
+    data_dir: Option<PathBuf>,
+}
+
+impl NetworkProfileManager {
+    pub fn global() -> &'static Mutex<NetworkProfileManager> {
+        MANAGER.get_or_init(|| {
+            let base = dirs::data_dir()
+                .unwrap_or_else(|| PathBuf::from("."))
+                .join("employee-gui");
+            Mutex::new(Self::load(base))
+        })
+    }
+
+    /// 从目录加载（文件缺失或损坏时使用默认值）
+    pub fn load(data_dir: PathBuf) -> Self {
+        let config = read_json(&data_dir.join(CONFIG_FILE_NAME)).unwrap_or_default();
+        let runs = read_json(&data_dir.join(RUNS_FILE_NAME)).unwrap_or_default();
+        Self { config, runs, data_dir: Some(data_dir) }
+    }
+
+    /// 不落盘的实例
+    pub fn in_memory(config: NetworkProfileConfig) -> Self {
+        Self { config, runs: Vec::new(), data_dir: None }
+    }
+
+    pub fn config(&self) -> &NetworkProfileConfig {
+        &self.config
+    }
+
+    pub fn save_config(&mut self, config: NetworkProfileConfig) -> Result<(), String> {
+        let mut seen = std::collections::HashSet::new();
+        for profile in &config.profiles {
+            profile.validate()?;
+            if !seen.insert(profile.id.as_str()) {
+                return Err(format!("配置档 ID 重复: {}", profile.id));
+            }
+        }
+        if let Some((device, profile_id)) = config.assignments.iter().find(|(_, p)| !seen.contains(p.as_str())) {
+            return Err(format!("设备 {} 分配了不存在的配置档: {}", device, profile_id));
+        }
+        self.write_file(CONFIG_FILE_NAME, &config)?;
+        info!("💾 网络配置档已更新: {} 个配置档，{} 台设备已分配", config.profiles.len(), config.assignments.len());
+        self.config = config;
+        Ok(())
+    }
+
+    /// 设置或取消设备的配置档分配
+    pub fn assign(&mut self, device_id: &str, profile_id: Option<&str>) -> Result<(), String> {
+        let mut config = self.config.clone();
+        match profile_id {
+            Some(id) => {
+                config.assignments.insert(device_id.to_string(), id.to_string());
+            }
+            None => {
+                config.assignments.remove(device_id);
+            }
+        }
+        self.save_config(config)
+    }
+
+    pub fn profile(&self, profile_id: &str) -> Option<&NetworkProfile> {
+        self.config.profiles.iter().find(|p| p.id == profile_id)
+    }
+
+    pub fn profile_for_device(&self, device_id: &str) -> Option<&NetworkProfile> {
+        self.config.assignments.get(device_id).and_then(|id| self.profile(id))
+    }
+
+    /// 运行开始：按分配下发配置档（未分配则确保直连），并记录本次运行
+    pub fn begin_run(&mut self, shell: &dyn DeviceShell, campaign_id: &str, device_id: &str) -> Result<NetworkRunRecord, String> {
+        let profile = self.profile_for_device(device_id).cloned();
+        let status = match &profile {
+            Some(profile) => apply_profile(shell, device_id, profile)?,
+            None => restore_clean(shell, device_id)?,
+        };
+        if !status.matches {
+            return Err(format!(
+                "设备 {} 代理校验失败: 期望 {:?}，实际 {:?}",
+                device_id, status.expected, status.actual
+            ));
+        }
+
+        let record = NetworkRunRecord {
+            run_id: uuid::Uuid::new_v4().to_string(),
+            campaign_id: campaign_id.to_string(),
+            device_id: device_id.to_string(),
+            profile_id: profile.as_ref().map(|p| p.id.clone()),
+            profile_name: profile.as_ref().map(|p| p.name.clone()),
+            proxy: status.actual,
+            verified: true,
+            started_at: chrono::Local::now().to_rfc3339(),
+            ended_at: None,
+            restored: false,
+        };
+        self.runs.push(record.clone());
+        if self.runs.len() > MAX_RUN_RECORDS {
+            let excess = self.runs.len() - MAX_RUN_RECORDS;
+            self.runs.drain(..excess);
+        }
+        self.persist_runs();
+        Ok(record)
+    }
+
+    /// 运行结束：按配置恢复干净设置并补全记录
+    pub fn end_run(&mut self, shell: &dyn DeviceShell, run_id: &str) -> Result<NetworkRunRecord, String> {
+        let index = self.runs.iter().position(|r| r.run_id == run_id)
+            .ok_or_else(|| format!("运行记录不存在: {}", run_id))?;
+        if self.runs[index].ended_at.is_some() {
+            return Ok(self.runs[index].clone());
+        }
+
+        let device_id = self.runs[index].device_id.clone();
+        let restored = if self.config.restore_after_run {
+            match restore_clean(shell, &device_id) {
+                Ok(status) => status.matches,
+                Err(e) => {
+                    warn!("⚠️ 设备 {} 恢复网络设置失败: {}", device_id, e);
+                    false
+                }
+            }
+        } else {
+            false
+        };
+
+        let record = &mut self.runs[index];
+        record.ended_at = Some(chrono::Local::now().to_rfc3339());
+        record.restored = restored;
+        let record = record.clone();
+        self.persist_runs();
+        Ok(record)
+    }
+
+    /// 运行记录（按开始时间倒序），可按活动过滤
+    pub fn runs(&self, campaign_id: Option<&str>) -> Vec<NetworkRunRecord> {
+        self.runs.iter()
+            .rev()
+            .filter(|r| campaign_id.map_or(true, |c| r.campaign_id == c))
+            .cloned()
+            .collect()
+    }
+
+    fn persist_runs(&self) {
+        if let Err(e) = self.write_file(RUNS_FILE_NAME, &self.runs) {
+            warn!("⚠️ 网络运行记录保存失败: {}", e);
+        }
+    }
+
+    fn write_file<T: Serialize>(&self, name: &str, value: &T) -> Result<(), String> {
+        let Some(dir) = &self.data_dir else {
+            return Ok(());
+        };
+        std::fs::create_dir_all(dir).map_err(|e| format!("创建配置目录失败: {}", e))?;
+        let content = serde_json::to_string_pretty(value).map_err(|e| e.to_string())?;
+        std::fs::write(dir.join(name), content).map_err(|e| format!("保存 {} 失败: {}", name, e))
+    }
+}
+
+fn read_json<T: serde::de::DeserializeOwned>(path: &std::path::Path) -> Option<T> {
+    let content = std::fs::read_to_string(path).ok()?;
+    match serde_json::from_str(&content) {
+        Ok(value) => Some(value),
+        Err(e) => {
+            warn!("⚠️ 解析 {:?} 失败，使用默认值: {}", path, e);
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+
+    /// 模拟 `settings` 命令的设备
+    #[derive(Default)]
+    struct FakeSettings {
+        values: RefCell<HashMap<String, String>>,
+    }
+
+    impl DeviceShell for FakeSettings {
+        fn shell(&self, _device_id: &str, command: &[&str]) -> Result<String, String> {
+            let mut values = self.values.borrow_mut();
+            match command {
+                ["settings", "put", "global", key, value] => {
+                    values.insert(key.to_string(), value.to_string());
+                    Ok(String::new())
+                }
+                ["settings", "get", "global", key] => Ok(values.get(*key).cloned().unwrap_or_else(|| "null".into())),
+                ["settings", "delete", "global", key] => {
+                    values.remove(*key);
+                    Ok(String::new())
+                }
+                _ => Err(format!("unexpected command: {:?}", command)),
+            }
+        }
+    }
+
+    fn profile(id: &str, port: u16) -> NetworkProfile {
+        NetworkProfile {
+            id: id.into(),
+            name: format!("{} proxy", id),
+            host: "10.0.0.2".into(),
+            port,
+            bypass: Some("localhost".into()),
+            region: None,
+        }
+    }
+
+    #[test]
+    fn applies_verifies_and_restores_per_device_profiles() {
+        let shell = FakeSettings::default();
+        let mut manager = NetworkProfileManager::in_memory(NetworkProfileConfig {
+            profiles: vec![profile("gz", 8080)],
+            ..Default::default()
+        });
+        manager.assign("dev1", Some("gz")).unwrap();
+        assert!(manager.assign("dev1", Some("missing")).is_err());
+
+        let run = manager.begin_run(&shell, "campaign-1", "dev1").unwrap();
+        assert_eq!(run.profile_id.as_deref(), Some("gz"));
+        assert_eq!(run.proxy.as_deref(), Some("10.0.0.2:8080"));
+        assert_eq!(shell.values.borrow()["global_http_proxy_exclusion_list"], "localhost");
+
+        let ended = manager.end_run(&shell, &run.run_id).unwrap();
+        assert!(ended.restored && ended.ended_at.is_some());
+        assert_eq!(read_proxy(&shell, "dev1").unwrap(), None);
+        assert!(!shell.values.borrow().contains_key("global_http_proxy_exclusion_list"));
+
+        // 未分配的设备按直连记录
+        let direct = manager.begin_run(&shell, "campaign-2", "dev2").unwrap();
+        assert_eq!(direct.profile_id, None);
+        assert_eq!(manager.runs(Some("campaign-1")).len(), 1);
+        assert_eq!(manager.runs(None)[0].campaign_id, "campaign-2");
+    }
+
+    #[test]
+    fn rejects_hosts_and_bypass_lists_that_could_inject_shell() {
+        let mut ok = profile("ok", 8080);
+        ok.host = "proxy-1.example.com".into();
+        ok.bypass = Some(" localhost, *.example.com ,192.168.*,".into());
+        assert!(ok.validate().is_ok());
+        assert_eq!(ok.bypass_value().as_deref(), Some("localhost,*.example.com,192.168.*"));
+
+        for host in ["10.0.0.2;reboot", "a b", "host:80", "-bad.com", "$(id)", ""] {
+            let mut p = profile("h", 8080);
+            p.host = host.into();
+            assert!(p.validate().is_err(), "host {:?} should be rejected", host);
+        }
+        for bypass in ["localhost;reboot", "a.com b.com", "*.com|id", "`id`", "ex*ample.com"] {
+            let mut p = profile("b", 8080);
+            p.bypass = Some(bypass.into());
+            assert!(p.validate().is_err(), "bypass {:?} should be rejected", bypass);
+        }
+        assert!(profile("p", 0).validate().is_err());
+    }
+}
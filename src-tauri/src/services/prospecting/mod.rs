@@ -2,6 +2,12 @@
 pub mod prospecting_types;
 pub mod prospecting_repository;
 pub mod prospecting_service;
+pub mod prospecting_rules;
+pub mod prospecting_import_readers;
+pub mod prospecting_ingestion;
+pub mod prospecting_vector_index;
+pub mod prospecting_calendar;
+pub mod prospecting_export;
 
 pub use prospecting_types::*;
 pub use prospecting_service::ProspectingService;
\ No newline at end of file
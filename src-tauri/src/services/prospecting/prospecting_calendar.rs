@@ -0,0 +1,146 @@
+// src-tauri/src/services/prospecting/prospecting_calendar.rs
+// module: services/prospecting | layer: domain | role: 内容日历
+// summary: 内容发布日历（命名档期 + 日期 + 关联活动），回复计划可按"活动的下一个档期"排期，与内容发布日对齐
+
+use chrono::{Local, NaiveDate, NaiveTime, TimeZone};
+use serde::{Deserialize, Serialize};
+
+/// 档期未指定时间时的默认发送时间
+pub const DEFAULT_SLOT_TIME: &str = "09:00";
+
+/// 内容日历档期
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CalendarEntry {
+    /// 为空时新建
+    #[serde(default)]
+    pub id: String,
+    pub name: String,
+    /// 档期日期 YYYY-MM-DD（本地时区）
+    #[serde(rename = "slotDate")]
+    pub slot_date: String,
+    /// 档期时间 HH:MM，为空时按 09:00
+    #[serde(default, rename = "slotTime")]
+    pub slot_time: Option<String>,
+    /// 关联的活动 ID
+    #[serde(default, rename = "campaignIds")]
+    pub campaign_ids: Vec<String>,
+    #[serde(default)]
+    pub note: Option<String>,
+    #[serde(default, rename = "createdAt")]
+    pub created_at: i64,
+    #[serde(default, rename = "updatedAt")]
+    pub updated_at: i64,
+}
+
+impl CalendarEntry {
+    /// 校验日期 / 时间格式与名称
+    pub fn validate(&self) -> Result<(), String> {
+        if self.name.trim().is_empty() {
+            return Err("档期名称不能为空".to_string());
+        }
+        parse_date(&self.slot_date)?;
+        if let Some(time) = self.slot_time.as_deref() {
+            parse_time(time)?;
+        }
+        Ok(())
+    }
+
+    /// 档期开始时间（unix 毫秒，本地时区）
+    pub fn slot_timestamp(&self) -> Result<i64, String> {
+        let date = parse_date(&self.slot_date)?;
+        let time = parse_time(self.slot_time.as_deref().unwrap_or(DEFAULT_SLOT_TIME))?;
+        Local
+            .from_local_datetime(&date.and_time(time))
+            .earliest()
+            .map(|dt| dt.timestamp_millis())
+            .ok_or_else(|| format!("档期时间在本地时区不存在: {} {:?}", self.slot_date, self.slot_time))
+    }
+}
+
+fn parse_date(s: &str) -> Result<NaiveDate, String> {
+    NaiveDate::parse_from_str(s.trim(), "%Y-%m-%d").map_err(|_| format!("档期日期格式应为 YYYY-MM-DD: {}", s))
+}
+
+fn parse_time(s: &str) -> Result<NaiveTime, String> {
+    NaiveTime::parse_from_str(s.trim(), "%H:%M").map_err(|_| format!("档期时间格式应为 HH:MM: {}", s))
+}
+
+/// 回复计划排期方式
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "mode", rename_all = "camelCase")]
+pub enum ReplySchedule {
+    /// 指定时间（unix 毫秒）
+    At { at: i64 },
+    /// 活动在日历上的下一个档期（不早于 `after`，默认当前时间）
+    NextCalendarSlot {
+        #[serde(rename = "campaignId")]
+        campaign_id: String,
+        #[serde(default)]
+        after: Option<i64>,
+    },
+}
+
+/// 排期结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScheduledReplies {
+    #[serde(rename = "scheduledAt")]
+    pub scheduled_at: i64,
+    /// 按日历排期时命中的档期
+    #[serde(rename = "calendarEntry")]
+    pub calendar_entry: Option<CalendarEntry>,
+    #[serde(rename = "planIds")]
+    pub plan_ids: Vec<String>,
+}
+
+/// 找出活动在 `now` 之后（含）的第一个档期及其时间
+pub fn next_slot_for_campaign<'a>(
+    entries: &'a [CalendarEntry],
+    campaign_id: &str,
+    now: i64,
+) -> Option<(&'a CalendarEntry, i64)> {
+    entries
+        .iter()
+        .filter(|e| e.campaign_ids.iter().any(|c| c == campaign_id))
+        .filter_map(|e| e.slot_timestamp().ok().map(|ts| (e, ts)))
+        .filter(|(_, ts)| *ts >= now)
+        .min_by_key(|(_, ts)| *ts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(name: &str, date: &str, time: Option<&str>, campaigns: &[&str]) -> CalendarEntry {
+        CalendarEntry {
+            id: name.to_string(),
+            name: name.to_string(),
+            slot_date: date.to_string(),
+            slot_time: time.map(str::to_string),
+            campaign_ids: campaigns.iter().map(|c| c.to_string()).collect(),
+            note: None,
+            created_at: 0,
+            updated_at: 0,
+        }
+    }
+
+    #[test]
+    fn test_next_slot_for_campaign() {
+        let entries = vec![
+            entry("past", "2020-01-01", None, &["c1"]),
+            entry("later", "2030-03-02", Some("20:00"), &["c1"]),
+            entry("sooner", "2030-03-01", None, &["c1", "c2"]),
+            entry("other", "2030-01-01", None, &["c3"]),
+        ];
+        let now = entry("now", "2025-06-01", None, &[]).slot_timestamp().unwrap();
+
+        let (hit, ts) = next_slot_for_campaign(&entries, "c1", now).unwrap();
+        assert_eq!(hit.name, "sooner");
+        assert_eq!(ts, entries[2].slot_timestamp().unwrap());
+        assert!(next_slot_for_campaign(&entries, "missing", now).is_none());
+
+        assert!(entry("bad", "2030/03/01", None, &[]).validate().is_err());
+        assert!(entry("bad", "2030-03-01", Some("25:00"), &[]).validate().is_err());
+        let schedule: ReplySchedule = serde_json::from_str(r#"{"mode":"nextCalendarSlot","campaignId":"c1"}"#).unwrap();
+        assert!(matches!(schedule, ReplySchedule::NextCalendarSlot { campaign_id, after: None } if campaign_id == "c1"));
+    }
+}
@@ -0,0 +1,59 @@
+// src-tauri/src/services/prospecting/prospecting_export.rs
+// module: services/prospecting | layer: domain | role: 线索导出列定义
+// summary: 把评论 + AI 分析结果展开为线索导出行（平台 / 作者 / 内容 / 意图 / 置信度 / 实体 / 回复状态），列定义交由统一报表导出器
+
+use serde_json::{json, Value};
+
+use crate::core::shared::i18n::Locale;
+use crate::services::report_export::{CellFormat, Column};
+
+use super::Comment;
+
+/// 线索导出列
+pub fn lead_columns(locale: Locale) -> Vec<Column> {
+    vec![
+        Column::localized("id", "export.lead.id", locale),
+        Column::localized("platform", "export.lead.platform", locale),
+        Column::localized("author", "export.lead.author", locale),
+        Column::localized("content", "export.lead.content", locale),
+        Column::localized("videoUrl", "export.lead.video_url", locale),
+        Column::localized("timestamp", "export.lead.timestamp", locale).format(CellFormat::DateTime),
+        Column::localized("intent", "export.lead.intent", locale),
+        Column::localized("confidence", "export.lead.confidence", locale).format(CellFormat::Percent),
+        Column::localized("product", "export.lead.product", locale),
+        Column::localized("contact", "export.lead.contact", locale),
+        Column::localized("location", "export.lead.location", locale),
+        Column::localized("tags", "export.lead.tags", locale),
+        Column::localized("language", "export.lead.language", locale),
+        Column::localized("isReplied", "export.lead.replied", locale).format(CellFormat::Boolean),
+        Column::localized("repliedAt", "export.lead.replied_at", locale).format(CellFormat::DateTime),
+    ]
+}
+
+/// 枚举按序列化名称输出（与前端展示一致）
+fn enum_text<T: serde::Serialize>(value: &T) -> Value {
+    serde_json::to_value(value).unwrap_or(Value::Null)
+}
+
+/// 评论展开为导出行，键与 [`lead_columns`] 对应
+pub fn lead_row(comment: &Comment) -> Value {
+    let raw = &comment.raw;
+    let analysis = comment.analysis.as_ref();
+    json!({
+        "id": raw.id,
+        "platform": enum_text(&raw.platform),
+        "author": raw.author,
+        "content": raw.content,
+        "videoUrl": raw.video_url,
+        "timestamp": raw.timestamp,
+        "intent": analysis.map(|a| enum_text(&a.intent)),
+        "confidence": analysis.map(|a| a.confidence),
+        "product": analysis.and_then(|a| a.entities.product.clone()),
+        "contact": analysis.and_then(|a| a.entities.contact.clone()),
+        "location": analysis.and_then(|a| a.entities.location.clone()),
+        "tags": analysis.map(|a| a.tags.clone()).unwrap_or_default(),
+        "language": enum_text(&comment.language),
+        "isReplied": comment.is_replied.unwrap_or(false),
+        "repliedAt": comment.replied_at,
+    })
+}
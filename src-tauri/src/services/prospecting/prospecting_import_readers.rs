@@ -0,0 +1,362 @@
+// src-tauri/src/services/prospecting/prospecting_import_readers.rs
+// module: services/prospecting | layer: infrastructure | role: 导入文件读取
+// summary: 把第三方工具导出的 CSV / JSON / Excel(xlsx) 评论文件读成统一的表格（表头 + 行），CSV 自动识别 UTF-8 / GBK 编码
+
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::io::Read;
+use std::path::Path;
+
+/// 导入文件格式
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ImportFormat {
+    Csv,
+    Json,
+    Xlsx,
+}
+
+impl ImportFormat {
+    /// 按扩展名识别
+    pub fn from_path(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()?.to_lowercase().as_str() {
+            "csv" | "txt" | "tsv" => Some(Self::Csv),
+            "json" | "jsonl" => Some(Self::Json),
+            "xlsx" | "xlsm" => Some(Self::Xlsx),
+            _ => None,
+        }
+    }
+}
+
+/// 文本编码（仅 CSV 需要）
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum TextEncoding {
+    /// 自动识别：BOM / 合法 UTF-8 按 UTF-8，否则按 GBK
+    #[default]
+    Auto,
+    Utf8,
+    Gbk,
+}
+
+/// 读取选项
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ReadOptions {
+    pub format: Option<ImportFormat>,
+    #[serde(default)]
+    pub encoding: TextEncoding,
+    /// CSV 分隔符，默认逗号（.tsv 默认制表符）
+    pub delimiter: Option<char>,
+    /// Excel 工作表名，默认第一个
+    pub sheet: Option<String>,
+    /// JSON 中评论数组所在字段（如 "data.comments"），默认自动查找
+    #[serde(rename = "jsonPath")]
+    pub json_path: Option<String>,
+}
+
+/// 统一的表格数据
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RawTable {
+    pub headers: Vec<String>,
+    pub rows: Vec<Vec<String>>,
+    /// 实际使用的编码（"utf-8" / "gbk"，非文本格式为空）
+    pub encoding: Option<String>,
+}
+
+/// 读取导入文件
+pub fn read_table(path: &Path, options: &ReadOptions) -> Result<RawTable> {
+    let format = options.format
+        .or_else(|| ImportFormat::from_path(path))
+        .ok_or_else(|| anyhow!("无法识别文件格式，请指定 csv / json / xlsx"))?;
+    let bytes = std::fs::read(path).with_context(|| format!("读取文件失败: {}", path.display()))?;
+
+    match format {
+        ImportFormat::Csv => {
+            let (text, encoding) = decode_text(&bytes, options.encoding)?;
+            let is_tsv = path.extension().and_then(|e| e.to_str()).is_some_and(|e| e.eq_ignore_ascii_case("tsv"));
+            let delimiter = options.delimiter.unwrap_or(if is_tsv { '\t' } else { ',' });
+            let mut table = read_csv(&text, delimiter)?;
+            table.encoding = Some(encoding.to_string());
+            Ok(table)
+        }
+        ImportFormat::Json => {
+            let (text, encoding) = decode_text(&bytes, options.encoding)?;
+            let mut table = read_json(&text, options.json_path.as_deref())?;
+            table.encoding = Some(encoding.to_string());
+            Ok(table)
+        }
+        ImportFormat::Xlsx => read_xlsx(&bytes, options.sheet.as_deref()),
+    }
+}
+
+/// 解码文本，返回（文本, 编码名）
+pub fn decode_text(bytes: &[u8], encoding: TextEncoding) -> Result<(String, &'static str)> {
+    let without_bom = bytes.strip_prefix(b"\xEF\xBB\xBF");
+    match encoding {
+        TextEncoding::Utf8 => Ok((String::from_utf8_lossy(without_bom.unwrap_or(bytes)).into_owned(), "utf-8")),
+        TextEncoding::Gbk => Ok((decode_gbk(bytes), "gbk")),
+        TextEncoding::Auto => {
+            if let Some(rest) = without_bom {
+                return Ok((String::from_utf8_lossy(rest).into_owned(), "utf-8"));
+            }
+            match std::str::from_utf8(bytes) {
+                Ok(text) => Ok((text.to_string(), "utf-8")),
+                Err(_) => Ok((decode_gbk(bytes), "gbk")),
+            }
+        }
+    }
+}
+
+fn decode_gbk(bytes: &[u8]) -> String {
+    // GB18030 兼容 GBK，Excel "另存为 CSV" 在中文系统下即为此编码
+    let (text, _, _) = encoding_rs::GB18030.decode(bytes);
+    text.into_owned()
+}
+
+fn read_csv(text: &str, delimiter: char) -> Result<RawTable> {
+    let delimiter = u8::try_from(delimiter).map_err(|_| anyhow!("分隔符必须是单字节字符"))?;
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(delimiter)
+        .flexible(true)
+        .from_reader(text.as_bytes());
+
+    let headers = reader.headers()?.iter().map(|h| h.trim().to_string()).collect();
+    let mut rows = Vec::new();
+    for record in reader.records() {
+        let record = record?;
+        if record.iter().all(|cell| cell.trim().is_empty()) {
+            continue;
+        }
+        rows.push(record.iter().map(|cell| cell.to_string()).collect());
+    }
+    Ok(RawTable { headers, rows, encoding: None })
+}
+
+fn read_json(text: &str, json_path: Option<&str>) -> Result<RawTable> {
+    let trimmed = text.trim_start();
+    // JSON Lines：每行一个对象
+    let items: Vec<Value> = if !trimmed.starts_with('[') && trimmed.lines().filter(|l| !l.trim().is_empty()).count() > 1
+        && serde_json::from_str::<Value>(trimmed).is_err()
+    {
+        trimmed.lines()
+            .filter(|l| !l.trim().is_empty())
+            .map(serde_json::from_str)
+            .collect::<std::result::Result<_, _>>()
+            .context("JSON Lines 解析失败")?
+    } else {
+        let root: Value = serde_json::from_str(trimmed).context("JSON 解析失败")?;
+        find_json_array(&root, json_path)?.clone()
+    };
+
+    let mut headers: Vec<String> = Vec::new();
+    for item in &items {
+        if let Some(object) = item.as_object() {
+            for key in object.keys() {
+                if !headers.contains(key) {
+                    headers.push(key.clone());
+                }
+            }
+        }
+    }
+    let rows = items.iter()
+        .filter_map(|item| item.as_object())
+        .map(|object| headers.iter().map(|h| object.get(h).map(json_cell).unwrap_or_default()).collect())
+        .collect();
+    Ok(RawTable { headers, rows, encoding: None })
+}
+
+fn find_json_array<'a>(root: &'a Value, json_path: Option<&str>) -> Result<&'a Vec<Value>> {
+    if let Some(path) = json_path.filter(|p| !p.is_empty()) {
+        let mut current = root;
+        for segment in path.split('.') {
+            current = current.get(segment).ok_or_else(|| anyhow!("JSON 中找不到字段 `{}`", path))?;
+        }
+        return current.as_array().ok_or_else(|| anyhow!("`{}` 不是数组", path));
+    }
+    if let Some(array) = root.as_array() {
+        return Ok(array);
+    }
+    // 常见导出结构：{"data": [...]} / {"data": {"comments": [...]}}
+    for key in ["comments", "data", "list", "items", "records"] {
+        match root.get(key) {
+            Some(Value::Array(array)) => return Ok(array),
+            Some(nested @ Value::Object(_)) => {
+                if let Ok(array) = find_json_array(nested, None) {
+                    return Ok(array);
+                }
+            }
+            _ => {}
+        }
+    }
+    Err(anyhow!("JSON 中没有找到评论数组，请指定 jsonPath"))
+}
+
+fn json_cell(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+// ========== xlsx（zip + XML）==========
+
+fn read_zip_entry(archive: &mut zip::ZipArchive<std::io::Cursor<&[u8]>>, name: &str) -> Result<Option<String>> {
+    let mut file = match archive.by_name(name) {
+        Ok(file) => file,
+        Err(zip::result::ZipError::FileNotFound) => return Ok(None),
+        Err(e) => return Err(e.into()),
+    };
+    let mut text = String::new();
+    file.read_to_string(&mut text)?;
+    Ok(Some(text))
+}
+
+fn read_xlsx(bytes: &[u8], sheet: Option<&str>) -> Result<RawTable> {
+    let mut archive = zip::ZipArchive::new(std::io::Cursor::new(bytes)).context("不是有效的 xlsx 文件")?;
+
+    let workbook = read_zip_entry(&mut archive, "xl/workbook.xml")?.ok_or_else(|| anyhow!("xlsx 缺少 workbook.xml"))?;
+    let rels = read_zip_entry(&mut archive, "xl/_rels/workbook.xml.rels")?.unwrap_or_default();
+    let sheet_path = resolve_sheet_path(&workbook, &rels, sheet)?;
+
+    let shared_strings = match read_zip_entry(&mut archive, "xl/sharedStrings.xml")? {
+        Some(xml) => parse_shared_strings(&xml)?,
+        None => Vec::new(),
+    };
+    let sheet_xml = read_zip_entry(&mut archive, &sheet_path)?
+        .ok_or_else(|| anyhow!("xlsx 缺少工作表 {}", sheet_path))?;
+    let mut grid = parse_sheet(&sheet_xml, &shared_strings)?;
+
+    if grid.is_empty() {
+        return Ok(RawTable::default());
+    }
+    let headers = grid.remove(0).into_iter().map(|h| h.trim().to_string()).collect::<Vec<_>>();
+    let rows = grid.into_iter()
+        .filter(|row| row.iter().any(|cell| !cell.trim().is_empty()))
+        .map(|mut row| {
+            row.resize(headers.len().max(row.len()), String::new());
+            row
+        })
+        .collect();
+    Ok(RawTable { headers, rows, encoding: None })
+}
+
+fn resolve_sheet_path(workbook: &str, rels: &str, sheet: Option<&str>) -> Result<String> {
+    let doc = roxmltree::Document::parse(workbook)?;
+    let sheets: Vec<(String, String)> = doc.descendants()
+        .filter(|n| n.has_tag_name("sheet"))
+        .map(|n| {
+            let rel_id = n.attributes()
+                .find(|a| a.name() == "id")
+                .map(|a| a.value().to_string())
+                .unwrap_or_default();
+            (n.attribute("name").unwrap_or_default().to_string(), rel_id)
+        })
+        .collect();
+    let (_, rel_id) = match sheet {
+        Some(name) => sheets.iter().find(|(n, _)| n == name).ok_or_else(|| anyhow!("找不到工作表「{}」", name))?,
+        None => sheets.first().ok_or_else(|| anyhow!("xlsx 没有工作表"))?,
+    };
+
+    let targets: HashMap<String, String> = if rels.is_empty() {
+        HashMap::new()
+    } else {
+        roxmltree::Document::parse(rels)?
+            .descendants()
+            .filter(|n| n.has_tag_name("Relationship"))
+            .filter_map(|n| Some((n.attribute("Id")?.to_string(), n.attribute("Target")?.to_string())))
+            .collect()
+    };
+    Ok(match targets.get(rel_id) {
+        Some(target) if target.starts_with('/') => target.trim_start_matches('/').to_string(),
+        Some(target) => format!("xl/{}", target),
+        None => "xl/worksheets/sheet1.xml".to_string(),
+    })
+}
+
+fn parse_shared_strings(xml: &str) -> Result<Vec<String>> {
+    let doc = roxmltree::Document::parse(xml)?;
+    Ok(doc.root_element()
+        .children()
+        .filter(|n| n.has_tag_name("si"))
+        .map(collect_text)
+        .collect())
+}
+
+/// 拼接节点下所有 `<t>` 文本（富文本单元格由多个 run 组成）
+fn collect_text(node: roxmltree::Node) -> String {
+    node.descendants()
+        .filter(|n| n.has_tag_name("t"))
+        .filter_map(|n| n.text())
+        .collect()
+}
+
+/// "AB12" → 列下标 27
+fn column_index(cell_ref: &str) -> Option<usize> {
+    let letters: String = cell_ref.chars().take_while(|c| c.is_ascii_alphabetic()).collect();
+    if letters.is_empty() {
+        return None;
+    }
+    Some(letters.to_ascii_uppercase().bytes().fold(0usize, |acc, b| acc * 26 + (b - b'A' + 1) as usize) - 1)
+}
+
+fn parse_sheet(xml: &str, shared_strings: &[String]) -> Result<Vec<Vec<String>>> {
+    let doc = roxmltree::Document::parse(xml)?;
+    let mut grid = Vec::new();
+    for row in doc.descendants().filter(|n| n.has_tag_name("row")) {
+        let mut cells: Vec<String> = Vec::new();
+        for (position, cell) in row.children().filter(|n| n.has_tag_name("c")).enumerate() {
+            let col = cell.attribute("r").and_then(column_index).unwrap_or(position);
+            let raw = cell.children().find(|n| n.has_tag_name("v")).and_then(|v| v.text()).unwrap_or_default();
+            let value = match cell.attribute("t") {
+                Some("s") => raw.parse::<usize>().ok().and_then(|i| shared_strings.get(i)).cloned().unwrap_or_default(),
+                Some("inlineStr") => cell.children().find(|n| n.has_tag_name("is")).map(collect_text).unwrap_or_default(),
+                Some("b") => if raw == "1" { "TRUE".to_string() } else { "FALSE".to_string() },
+                _ => raw.to_string(),
+            };
+            if cells.len() <= col {
+                cells.resize(col + 1, String::new());
+            }
+            cells[col] = value;
+        }
+        grid.push(cells);
+    }
+    Ok(grid)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_gbk_and_reads_csv() {
+        let (gbk_bytes, _, _) = encoding_rs::GBK.encode("作者,评论\n张三,多少钱\n");
+        let (text, encoding) = decode_text(&gbk_bytes, TextEncoding::Auto).unwrap();
+        assert_eq!(encoding, "gbk");
+        let table = read_csv(&text, ',').unwrap();
+        assert_eq!(table.headers, vec!["作者", "评论"]);
+        assert_eq!(table.rows, vec![vec!["张三".to_string(), "多少钱".to_string()]]);
+
+        let (_, encoding) = decode_text("\u{feff}a,b".as_bytes(), TextEncoding::Auto).unwrap();
+        assert_eq!(encoding, "utf-8");
+    }
+
+    #[test]
+    fn reads_nested_json_and_sheet_cells() {
+        let table = read_json(r#"{"data": {"comments": [{"nick": "a", "text": "hi", "likes": 3}, {"nick": "b"}]}}"#, None).unwrap();
+        assert_eq!(table.headers, vec!["nick", "text", "likes"]);
+        assert_eq!(table.rows[0], vec!["a", "hi", "3"]);
+        assert_eq!(table.rows[1], vec!["b", "", ""]);
+
+        let sheet = r#"<worksheet><sheetData>
+            <row r="1"><c r="A1" t="s"><v>0</v></c><c r="C1" t="inlineStr"><is><t>点赞</t></is></c></row>
+            <row r="2"><c r="A2" t="s"><v>1</v></c><c r="C2"><v>12</v></c></row>
+        </sheetData></worksheet>"#;
+        let grid = parse_sheet(sheet, &["评论".to_string(), "在哪买".to_string()]).unwrap();
+        assert_eq!(grid[0], vec!["评论", "", "点赞"]);
+        assert_eq!(grid[1], vec!["在哪买", "", "12"]);
+        assert_eq!(column_index("AB12"), Some(27));
+    }
+}
@@ -0,0 +1,468 @@
+// src-tauri/src/services/prospecting/prospecting_ingestion.rs
+// module: services/prospecting | layer: application | role: 评论导入
+// summary: 导入配置（按格式保存列映射）、导入前预览、逐行校验（带行号的错误）、把表格行映射为 RawComment 并分块写入
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+
+use super::prospecting_import_readers::{RawTable, ReadOptions};
+use super::prospecting_types::{RawComment, SocialPlatform};
+
+/// 导入配置文件名（位于应用数据目录）
+pub const IMPORT_PROFILES_FILE_NAME: &str = "import_profiles.json";
+/// 每次写库的行数
+pub const IMPORT_CHUNK_SIZE: usize = 500;
+/// 预览返回的样例行数
+const PREVIEW_ROWS: usize = 20;
+/// 报告中保留的错误条数上限
+const MAX_REPORTED_ERRORS: usize = 200;
+
+/// 列映射：字段 → 源文件列名
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ColumnMapping {
+    pub id: Option<String>,
+    pub content: Option<String>,
+    pub author: Option<String>,
+    pub platform: Option<String>,
+    #[serde(rename = "videoUrl")]
+    pub video_url: Option<String>,
+    pub timestamp: Option<String>,
+    #[serde(rename = "avatarUrl")]
+    pub avatar_url: Option<String>,
+    #[serde(rename = "likeCount")]
+    pub like_count: Option<String>,
+}
+
+impl ColumnMapping {
+    /// 按常见表头猜测映射（导出工具多为中文表头）
+    pub fn guess(headers: &[String]) -> Self {
+        let find = |candidates: &[&str]| -> Option<String> {
+            headers.iter()
+                .find(|h| {
+                    let lower = h.to_lowercase();
+                    candidates.iter().any(|c| lower == *c)
+                })
+                .or_else(|| headers.iter().find(|h| {
+                    let lower = h.to_lowercase();
+                    candidates.iter().any(|c| lower.contains(c))
+                }))
+                .cloned()
+        };
+        Self {
+            id: find(&["id", "comment_id", "cid", "评论id"]),
+            content: find(&["content", "text", "comment", "评论内容", "评论", "内容"]),
+            author: find(&["author", "nickname", "nick", "user", "用户昵称", "昵称", "作者", "用户"]),
+            platform: find(&["platform", "平台"]),
+            video_url: find(&["video_url", "url", "视频链接", "作品链接", "链接"]),
+            timestamp: find(&["timestamp", "time", "create_time", "评论时间", "时间"]),
+            avatar_url: find(&["avatar", "avatar_url", "头像"]),
+            like_count: find(&["like_count", "likes", "digg_count", "点赞数", "点赞"]),
+        }
+    }
+}
+
+/// 可保存的导入配置
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ImportProfile {
+    pub name: String,
+    #[serde(default)]
+    pub read: ReadOptions,
+    #[serde(default)]
+    pub mapping: ColumnMapping,
+    /// 未映射平台列或无法识别时使用
+    #[serde(rename = "defaultPlatform")]
+    pub default_platform: Option<SocialPlatform>,
+    /// 时间列的 chrono 格式（默认识别秒/毫秒时间戳与常见日期格式）
+    #[serde(rename = "timestampFormat")]
+    pub timestamp_format: Option<String>,
+}
+
+/// 加载已保存的导入配置
+pub fn load_profiles(path: &Path) -> Result<Vec<ImportProfile>> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    Ok(serde_json::from_slice(&std::fs::read(path)?)?)
+}
+
+/// 保存（按名称替换）导入配置
+pub fn upsert_profile(path: &Path, profile: ImportProfile) -> Result<Vec<ImportProfile>> {
+    if profile.name.trim().is_empty() {
+        anyhow::bail!("配置名称不能为空");
+    }
+    let mut profiles = load_profiles(path)?;
+    match profiles.iter_mut().find(|p| p.name == profile.name) {
+        Some(existing) => *existing = profile,
+        None => profiles.push(profile),
+    }
+    std::fs::write(path, serde_json::to_vec_pretty(&profiles)?)?;
+    Ok(profiles)
+}
+
+/// 删除导入配置，返回是否存在
+pub fn delete_profile(path: &Path, name: &str) -> Result<bool> {
+    let mut profiles = load_profiles(path)?;
+    let before = profiles.len();
+    profiles.retain(|p| p.name != name);
+    if profiles.len() == before {
+        return Ok(false);
+    }
+    std::fs::write(path, serde_json::to_vec_pretty(&profiles)?)?;
+    Ok(true)
+}
+
+/// 行级校验错误（行号从 1 开始，不含表头）
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RowError {
+    pub row: usize,
+    pub column: Option<String>,
+    pub message: String,
+}
+
+/// 导入预览
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportPreview {
+    pub headers: Vec<String>,
+    pub encoding: Option<String>,
+    #[serde(rename = "totalRows")]
+    pub total_rows: usize,
+    #[serde(rename = "validRows")]
+    pub valid_rows: usize,
+    /// 实际使用的映射（配置未指定的列按表头猜测）
+    pub mapping: ColumnMapping,
+    /// 前几行映射结果
+    pub samples: Vec<RawComment>,
+    pub errors: Vec<RowError>,
+}
+
+/// 导入结果
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ImportReport {
+    #[serde(rename = "totalRows")]
+    pub total_rows: usize,
+    pub imported: usize,
+    /// 已存在（按 ID 去重）而未写入的行数
+    pub duplicates: usize,
+    /// 校验失败跳过的行数
+    pub skipped: usize,
+    pub chunks: usize,
+    pub errors: Vec<RowError>,
+}
+
+impl ImportReport {
+    pub fn push_error(&mut self, error: RowError) {
+        if self.errors.len() < MAX_REPORTED_ERRORS {
+            self.errors.push(error);
+        }
+    }
+}
+
+/// 表格到评论的映射器
+pub struct RowMapper<'a> {
+    profile: &'a ImportProfile,
+    mapping: ColumnMapping,
+    columns: HashMap<&'a str, usize>,
+}
+
+impl<'a> RowMapper<'a> {
+    pub fn new(profile: &'a ImportProfile, table: &'a RawTable) -> Self {
+        let guessed = ColumnMapping::guess(&table.headers);
+        let pick = |configured: &Option<String>, guessed: Option<String>| configured.clone().or(guessed);
+        let m = &profile.mapping;
+        let mapping = ColumnMapping {
+            id: pick(&m.id, guessed.id),
+            content: pick(&m.content, guessed.content),
+            author: pick(&m.author, guessed.author),
+            platform: pick(&m.platform, guessed.platform),
+            video_url: pick(&m.video_url, guessed.video_url),
+            timestamp: pick(&m.timestamp, guessed.timestamp),
+            avatar_url: pick(&m.avatar_url, guessed.avatar_url),
+            like_count: pick(&m.like_count, guessed.like_count),
+        };
+        let columns = table.headers.iter().enumerate().map(|(i, h)| (h.as_str(), i)).collect();
+        Self { profile, mapping, columns }
+    }
+
+    pub fn mapping(&self) -> &ColumnMapping {
+        &self.mapping
+    }
+
+    /// 校验映射本身（必填列是否存在）
+    pub fn check_mapping(&self) -> Vec<RowError> {
+        let mut errors = Vec::new();
+        match &self.mapping.content {
+            None => errors.push(RowError { row: 0, column: None, message: "未找到评论内容列，请配置 content 映射".into() }),
+            Some(column) if !self.columns.contains_key(column.as_str()) => errors.push(RowError {
+                row: 0,
+                column: Some(column.clone()),
+                message: "文件中不存在该列".into(),
+            }),
+            _ => {}
+        }
+        errors
+    }
+
+    fn cell<'r>(&self, row: &'r [String], column: &Option<String>) -> Option<&'r str> {
+        let index = *self.columns.get(column.as_deref()?)?;
+        row.get(index).map(|s| s.trim()).filter(|s| !s.is_empty())
+    }
+
+    /// 映射一行；`row_no` 从 1 开始
+    pub fn map_row(&self, row_no: usize, row: &[String]) -> std::result::Result<RawComment, Vec<RowError>> {
+        let mut errors = Vec::new();
+        let error = |column: &Option<String>, message: String| RowError { row: row_no, column: column.clone(), message };
+
+        let content = self.cell(row, &self.mapping.content).map(str::to_string);
+        if content.is_none() {
+            errors.push(error(&self.mapping.content, "评论内容为空".into()));
+        }
+
+        let platform = match self.cell(row, &self.mapping.platform) {
+            Some(value) => match parse_platform(value) {
+                Some(platform) => Some(platform),
+                None => {
+                    errors.push(error(&self.mapping.platform, format!("无法识别的平台: {}", value)));
+                    None
+                }
+            },
+            None => {
+                if self.profile.default_platform.is_none() {
+                    errors.push(error(&self.mapping.platform, "平台为空，请在配置中指定默认平台".into()));
+                }
+                self.profile.default_platform.clone()
+            }
+        };
+
+        let timestamp = match self.cell(row, &self.mapping.timestamp) {
+            Some(value) => match parse_timestamp(value, self.profile.timestamp_format.as_deref()) {
+                Some(ts) => Some(ts),
+                None => {
+                    errors.push(error(&self.mapping.timestamp, format!("无法解析的时间: {}", value)));
+                    None
+                }
+            },
+            None => None,
+        };
+
+        let like_count = match self.cell(row, &self.mapping.like_count) {
+            Some(value) => match parse_count(value) {
+                Some(count) => Some(count),
+                None => {
+                    errors.push(error(&self.mapping.like_count, format!("无法解析的点赞数: {}", value)));
+                    None
+                }
+            },
+            None => None,
+        };
+
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+        let (Some(content), Some(platform)) = (content, platform) else {
+            return Err(errors);
+        };
+        let author = self.cell(row, &self.mapping.author).unwrap_or("匿名").to_string();
+        let video_url = self.cell(row, &self.mapping.video_url).map(str::to_string);
+        // 无 ID 列时用内容生成稳定 ID，重复导入同一文件不会产生重复评论
+        let id = self.cell(row, &self.mapping.id).map(str::to_string).unwrap_or_else(|| {
+            let key = format!("{:?}|{}|{}|{}", platform, video_url.as_deref().unwrap_or_default(), author, content);
+            format!("import_{:x}", md5::compute(key.as_bytes()))
+        });
+
+        Ok(RawComment {
+            id,
+            platform,
+            video_url,
+            author,
+            content,
+            timestamp,
+            avatar_url: self.cell(row, &self.mapping.avatar_url).map(str::to_string),
+            like_count,
+            metadata: None,
+        })
+    }
+}
+
+/// 生成导入预览
+pub fn preview(table: &RawTable, profile: &ImportProfile) -> ImportPreview {
+    let mapper = RowMapper::new(profile, table);
+    let mut errors = mapper.check_mapping();
+    let mut samples = Vec::new();
+    let mut valid_rows = 0;
+
+    if errors.is_empty() {
+        for (i, row) in table.rows.iter().enumerate() {
+            match mapper.map_row(i + 1, row) {
+                Ok(comment) => {
+                    valid_rows += 1;
+                    if samples.len() < PREVIEW_ROWS {
+                        samples.push(comment);
+                    }
+                }
+                Err(row_errors) => {
+                    if errors.len() < MAX_REPORTED_ERRORS {
+                        errors.extend(row_errors);
+                    }
+                }
+            }
+        }
+    }
+
+    ImportPreview {
+        headers: table.headers.clone(),
+        encoding: table.encoding.clone(),
+        total_rows: table.rows.len(),
+        valid_rows,
+        mapping: mapper.mapping().clone(),
+        samples,
+        errors,
+    }
+}
+
+/// 按块映射并写入；`save_chunk` 返回新写入条数，`on_progress(已处理行数, 总行数)`
+pub fn import_rows(
+    table: &RawTable,
+    profile: &ImportProfile,
+    mut save_chunk: impl FnMut(&[RawComment]) -> Result<usize>,
+    mut on_progress: impl FnMut(usize, usize),
+) -> Result<ImportReport> {
+    let mapper = RowMapper::new(profile, table);
+    let mapping_errors = mapper.check_mapping();
+    if let Some(error) = mapping_errors.first() {
+        anyhow::bail!("{}", error.message);
+    }
+
+    let mut report = ImportReport { total_rows: table.rows.len(), ..Default::default() };
+    for (chunk_index, chunk) in table.rows.chunks(IMPORT_CHUNK_SIZE).enumerate() {
+        let offset = chunk_index * IMPORT_CHUNK_SIZE;
+        let mut comments = Vec::with_capacity(chunk.len());
+        for (i, row) in chunk.iter().enumerate() {
+            match mapper.map_row(offset + i + 1, row) {
+                Ok(comment) => comments.push(comment),
+                Err(row_errors) => {
+                    report.skipped += 1;
+                    row_errors.into_iter().for_each(|e| report.push_error(e));
+                }
+            }
+        }
+        if !comments.is_empty() {
+            let inserted = save_chunk(&comments)?;
+            report.imported += inserted;
+            report.duplicates += comments.len().saturating_sub(inserted);
+            report.chunks += 1;
+        }
+        on_progress(offset + chunk.len(), table.rows.len());
+    }
+    Ok(report)
+}
+
+/// 平台名称（兼容中文与域名写法）
+pub fn parse_platform(value: &str) -> Option<SocialPlatform> {
+    let lower = value.trim().to_lowercase();
+    match lower.as_str() {
+        "douyin" | "抖音" | "dy" | "tiktok" => Some(SocialPlatform::Douyin),
+        "xhs" | "xiaohongshu" | "小红书" | "red" => Some(SocialPlatform::Xhs),
+        "weibo" | "微博" | "wb" => Some(SocialPlatform::Weibo),
+        "kuaishou" | "快手" | "ks" => Some(SocialPlatform::Kuaishou),
+        _ if lower.contains("douyin.com") => Some(SocialPlatform::Douyin),
+        _ if lower.contains("xiaohongshu.com") => Some(SocialPlatform::Xhs),
+        _ if lower.contains("weibo.com") => Some(SocialPlatform::Weibo),
+        _ if lower.contains("kuaishou.com") => Some(SocialPlatform::Kuaishou),
+        _ => None,
+    }
+}
+
+/// 时间解析为秒级时间戳：秒 / 毫秒时间戳、自定义格式、常见日期格式（按本地时区）
+pub fn parse_timestamp(value: &str, format: Option<&str>) -> Option<i64> {
+    use chrono::{Local, NaiveDate, NaiveDateTime, TimeZone};
+
+    let value = value.trim();
+    if let Ok(number) = value.parse::<f64>() {
+        let secs = if number > 1e11 { number / 1000.0 } else { number };
+        return Some(secs as i64);
+    }
+
+    let to_local = |naive: NaiveDateTime| Local.from_local_datetime(&naive).earliest().map(|dt| dt.timestamp());
+    if let Some(fmt) = format {
+        return NaiveDateTime::parse_from_str(value, fmt).ok().and_then(to_local)
+            .or_else(|| NaiveDate::parse_from_str(value, fmt).ok().and_then(|d| d.and_hms_opt(0, 0, 0)).and_then(to_local));
+    }
+    if let Ok(dt) = chrono::DateTime::parse_from_rfc3339(value) {
+        return Some(dt.timestamp());
+    }
+    const DATETIME_FORMATS: [&str; 4] = ["%Y-%m-%d %H:%M:%S", "%Y/%m/%d %H:%M:%S", "%Y-%m-%d %H:%M", "%Y/%m/%d %H:%M"];
+    const DATE_FORMATS: [&str; 3] = ["%Y-%m-%d", "%Y/%m/%d", "%Y年%m月%d日"];
+    DATETIME_FORMATS.iter()
+        .find_map(|fmt| NaiveDateTime::parse_from_str(value, fmt).ok())
+        .or_else(|| DATE_FORMATS.iter()
+            .find_map(|fmt| NaiveDate::parse_from_str(value, fmt).ok())
+            .and_then(|d| d.and_hms_opt(0, 0, 0)))
+        .and_then(to_local)
+}
+
+/// 计数解析：兼容 "1,234"、"1.2万"、"3w"、"1.5k"
+pub fn parse_count(value: &str) -> Option<i32> {
+    let cleaned = value.trim().replace(',', "");
+    let (number, multiplier) = if let Some(n) = cleaned.strip_suffix(|c| c == '万' || c == 'w' || c == 'W') {
+        (n, 10_000.0)
+    } else if let Some(n) = cleaned.strip_suffix(|c| c == 'k' || c == 'K') {
+        (n, 1_000.0)
+    } else {
+        (cleaned.as_str(), 1.0)
+    };
+    let parsed = number.trim().parse::<f64>().ok()?;
+    (parsed >= 0.0).then(|| (parsed * multiplier).round().min(i32::MAX as f64) as i32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn table(headers: &[&str], rows: &[&[&str]]) -> RawTable {
+        RawTable {
+            headers: headers.iter().map(|s| s.to_string()).collect(),
+            rows: rows.iter().map(|r| r.iter().map(|s| s.to_string()).collect()).collect(),
+            encoding: Some("utf-8".into()),
+        }
+    }
+
+    #[test]
+    fn previews_with_guessed_mapping_and_row_errors() {
+        let table = table(
+            &["用户昵称", "评论内容", "平台", "点赞数", "评论时间"],
+            &[
+                &["张三", "多少钱", "抖音", "1.2万", "2024-05-01 12:00:00"],
+                &["李四", "", "小红书", "3", ""],
+                &["王五", "在哪买", "未知平台", "abc", ""],
+            ],
+        );
+        let preview = preview(&table, &ImportProfile::default());
+        assert_eq!(preview.mapping.content.as_deref(), Some("评论内容"));
+        assert_eq!(preview.valid_rows, 1);
+        assert_eq!(preview.samples[0].like_count, Some(12_000));
+        assert_eq!(preview.errors.len(), 3);
+        assert_eq!(preview.errors[0], RowError { row: 2, column: Some("评论内容".into()), message: "评论内容为空".into() });
+        assert_eq!(preview.errors[2].row, 3);
+    }
+
+    #[test]
+    fn imports_in_chunks_with_stable_ids() {
+        let rows: Vec<Vec<String>> = (0..IMPORT_CHUNK_SIZE + 3).map(|i| vec![format!("评论{}", i)]).collect();
+        let table = RawTable { headers: vec!["content".into()], rows, encoding: None };
+        let profile = ImportProfile { default_platform: Some(SocialPlatform::Douyin), ..Default::default() };
+
+        let mut saved = Vec::new();
+        let report = import_rows(&table, &profile, |chunk| {
+            saved.extend(chunk.iter().map(|c| c.id.clone()));
+            Ok(chunk.len())
+        }, |_, _| {}).unwrap();
+        assert_eq!((report.imported, report.chunks, report.skipped), (IMPORT_CHUNK_SIZE + 3, 2, 0));
+        assert!(saved[0].starts_with("import_"));
+
+        let again = RowMapper::new(&profile, &table).map_row(1, &table.rows[0]).unwrap();
+        assert_eq!(again.id, saved[0]);
+        assert_eq!(parse_timestamp("1714536000000", None), Some(1_714_536_000));
+    }
+}
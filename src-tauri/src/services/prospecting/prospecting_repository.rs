@@ -5,6 +5,13 @@ use std::path::PathBuf;
 
 use crate::infrastructure::database::get_connection;
 use super::prospecting_types::*;
+use super::prospecting_rules::RuleMatch;
+use super::prospecting_vector_index::{decode_vector, encode_vector};
+use super::prospecting_calendar::{CalendarEntry, DEFAULT_SLOT_TIME};
+use crate::services::comment_language::{detect_language, CommentLanguage};
+
+/// 精准获客数据库文件名（位于应用数据目录）
+pub const PROSPECTING_DB_FILE: &str = "prospecting.db";
 
 /// 精准获客数据存储仓储
 pub struct ProspectingRepository {
@@ -14,7 +21,7 @@ pub struct ProspectingRepository {
 impl ProspectingRepository {
     /// 创建新的仓储实例
     pub fn new(data_dir: PathBuf) -> Result<Self> {
-        let db_path = data_dir.join("prospecting.db");
+        let db_path = data_dir.join(PROSPECTING_DB_FILE);
         let repo = Self { db_path };
         repo.init_database()?;
         Ok(repo)
@@ -100,6 +107,97 @@ impl ProspectingRepository {
             [],
         )?;
 
+        // 创建规则标注表（与 AI 分析结果分开保存）
+        conn.execute(
+            r#"
+            CREATE TABLE IF NOT EXISTS rule_labels (
+                comment_id TEXT PRIMARY KEY,
+                rule_id TEXT NOT NULL,
+                rule_name TEXT NOT NULL,
+                intent TEXT NOT NULL,
+                priority INTEGER NOT NULL,
+                matched_terms TEXT NOT NULL,
+                labeled_at INTEGER NOT NULL,
+                FOREIGN KEY (comment_id) REFERENCES comments (id) ON DELETE CASCADE
+            )
+            "#,
+            [],
+        )?;
+
+        // 评论语言列（入库时识别），存量评论按内容补识别
+        let has_language: bool = conn
+            .prepare("SELECT 1 FROM pragma_table_info('comments') WHERE name = 'language'")?
+            .exists([])?;
+        if !has_language {
+            conn.execute("ALTER TABLE comments ADD COLUMN language TEXT", [])?;
+            let pending: Vec<(String, String)> = {
+                let mut stmt = conn.prepare("SELECT id, content FROM comments")?;
+                let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+                rows.collect::<rusqlite::Result<_>>()?
+            };
+            let mut update = conn.prepare("UPDATE comments SET language = ?1 WHERE id = ?2")?;
+            for (id, content) in &pending {
+                update.execute(params![detect_language(content), id])?;
+            }
+        }
+
+        // 回复计划排期 / 媒体列（追加在末尾，与 SELECT * 的列序一致）
+        for (column, ddl) in [
+            ("campaign_id", "ALTER TABLE reply_plans ADD COLUMN campaign_id TEXT"),
+            ("scheduled_at", "ALTER TABLE reply_plans ADD COLUMN scheduled_at INTEGER"),
+            ("media_asset_id", "ALTER TABLE reply_plans ADD COLUMN media_asset_id TEXT"),
+        ] {
+            let exists: bool = conn
+                .prepare("SELECT 1 FROM pragma_table_info('reply_plans') WHERE name = ?1")?
+                .exists(params![column])?;
+            if !exists {
+                conn.execute(ddl, [])?;
+            }
+        }
+
+        // 内容日历档期及其关联活动
+        conn.execute(
+            r#"
+            CREATE TABLE IF NOT EXISTS content_calendar_entries (
+                id TEXT PRIMARY KEY,
+                name TEXT NOT NULL,
+                slot_date TEXT NOT NULL,
+                slot_time TEXT,
+                note TEXT,
+                created_at INTEGER NOT NULL,
+                updated_at INTEGER NOT NULL
+            )
+            "#,
+            [],
+        )?;
+        conn.execute(
+            r#"
+            CREATE TABLE IF NOT EXISTS content_calendar_campaigns (
+                entry_id TEXT NOT NULL,
+                campaign_id TEXT NOT NULL,
+                PRIMARY KEY (entry_id, campaign_id),
+                FOREIGN KEY (entry_id) REFERENCES content_calendar_entries (id) ON DELETE CASCADE
+            )
+            "#,
+            [],
+        )?;
+
+                // 创建评论向量表（向量以小端 f32 BLOB 存储，按模型区分）
+        conn.execute(
+            r#"
+            CREATE TABLE IF NOT EXISTS comment_embeddings (
+                comment_id TEXT PRIMARY KEY,
+                model TEXT NOT NULL,
+                dims INTEGER NOT NULL,
+                vector BLOB NOT NULL,
+                content_hash TEXT NOT NULL,
+                updated_at INTEGER NOT NULL,
+                FOREIGN KEY (comment_id) REFERENCES comments (id) ON DELETE CASCADE
+            )
+            "#,
+            [],
+        )?;
+
         Ok(())
     }
 
@@ -114,8 +212,8 @@ impl ProspectingRepository {
         conn.execute(
             r#"
             INSERT OR REPLACE INTO comments 
-            (id, platform, video_url, author, content, timestamp, avatar_url, like_count, metadata)
-            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)
+            (id, platform, video_url, author, content, timestamp, avatar_url, like_count, metadata, language)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)
             "#,
             params![
                 comment.id,
@@ -127,12 +225,48 @@ impl ProspectingRepository {
                 comment.avatar_url,
                 comment.like_count,
                 metadata_json,
+                detect_language(&comment.content),
             ],
         )?;
 
         Ok(())
     }
 
+    /// 批量导入评论（单事务，已存在的 ID 跳过以保留分析结果），返回新写入条数
+    pub fn import_comments(&self, comments: &[RawComment]) -> Result<usize> {
+        let mut conn = get_connection(&self.db_path)?;
+        let tx = conn.transaction()?;
+        let mut inserted = 0;
+        {
+            let mut stmt = tx.prepare(
+                r#"
+                INSERT OR IGNORE INTO comments
+                (id, platform, video_url, author, content, timestamp, avatar_url, like_count, metadata, language)
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)
+                "#,
+            )?;
+            for comment in comments {
+                let metadata_json = comment.metadata.as_ref()
+                    .map(|m| serde_json::to_string(m).unwrap_or_default())
+                    .unwrap_or_default();
+                inserted += stmt.execute(params![
+                    comment.id,
+                    &comment.platform,
+                    comment.video_url,
+                    comment.author,
+                    comment.content,
+                    comment.timestamp,
+                    comment.avatar_url,
+                    comment.like_count,
+                    metadata_json,
+                    detect_language(&comment.content),
+                ])?;
+            }
+        }
+        tx.commit()?;
+        Ok(inserted)
+    }
+
     /// 保存分析结果
     pub fn save_analysis(&self, analysis: &AnalysisResult) -> Result<()> {
         let conn = get_connection(&self.db_path)?;
@@ -166,7 +300,7 @@ impl ProspectingRepository {
                 c.id, c.platform, c.video_url, c.author, c.content, c.timestamp, 
                 c.avatar_url, c.like_count, c.metadata,
                 a.intent, a.confidence, a.entities, a.suggested_reply, a.tags, a.analyzed_at,
-                r.replied_at, r.actual_reply
+                r.replied_at, r.actual_reply, c.language
             FROM comments c
             LEFT JOIN analysis_results a ON c.id = a.comment_id
             LEFT JOIN reply_records r ON c.id = r.comment_id
@@ -185,6 +319,11 @@ impl ProspectingRepository {
             params.push(Box::new(intent.clone()));
         }
         
+        if let Some(language) = filter.language {
+            sql.push_str(" AND c.language = ?");
+            params.push(Box::new(language));
+        }
+
         if let Some(has_analysis) = filter.has_analysis {
             if has_analysis {
                 sql.push_str(" AND a.comment_id IS NOT NULL");
@@ -244,6 +383,7 @@ impl ProspectingRepository {
                 is_replied,
                 replied_at,
                 actual_reply,
+                language: row.get::<_, Option<CommentLanguage>>(17)?.unwrap_or_default(),
             })
         })?;
 
@@ -275,7 +415,7 @@ impl ProspectingRepository {
                 c.id, c.platform, c.video_url, c.author, c.content, c.timestamp, 
                 c.avatar_url, c.like_count, c.metadata,
                 a.intent, a.confidence, a.entities, a.suggested_reply, a.tags, a.analyzed_at,
-                r.replied_at, r.actual_reply
+                r.replied_at, r.actual_reply, c.language
             FROM comments c
             LEFT JOIN analysis_results a ON c.id = a.comment_id
             LEFT JOIN reply_records r ON c.id = r.comment_id
@@ -334,6 +474,7 @@ impl ProspectingRepository {
                 is_replied,
                 replied_at,
                 actual_reply,
+                language: row.get::<_, Option<CommentLanguage>>(17)?.unwrap_or_default(),
             })
         })?;
 
@@ -354,8 +495,8 @@ impl ProspectingRepository {
             INSERT OR REPLACE INTO reply_plans 
             (id, comment_id, platform, video_url, target_author, target_comment, 
              reply_content, steps, status, created_at, updated_at, executed_at, 
-             completed_at, error, is_simulation)
-            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15)
+             completed_at, error, is_simulation, campaign_id, scheduled_at, media_asset_id)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18)
             "#,
             params![
                 plan.id,
@@ -373,6 +514,9 @@ impl ProspectingRepository {
                 plan.completed_at,
                 plan.error,
                 plan.is_simulation,
+                plan.campaign_id,
+                plan.scheduled_at,
+                plan.media_asset_id,
             ],
         )?;
 
@@ -481,28 +625,7 @@ impl ProspectingRepository {
         let mut stmt = conn.prepare(&sql)?;
         let params: Vec<&dyn rusqlite::ToSql> = comment_ids.iter().map(|s| s as &dyn rusqlite::ToSql).collect();
         
-        let plan_iter = stmt.query_map(&params[..], |row| {
-            let steps_json: String = row.get(7)?;
-            let steps: Vec<ReplyStep> = serde_json::from_str(&steps_json).unwrap_or_default();
-            
-            Ok(ReplyPlan {
-                id: row.get(0)?,
-                comment_id: row.get(1)?,
-                platform: row.get(2)?,
-                video_url: row.get(3)?,
-                target_author: row.get(4)?,
-                target_comment: row.get(5)?,
-                reply_content: row.get(6)?,
-                steps,
-                status: row.get(8)?,
-                created_at: row.get(9)?,
-                updated_at: row.get(10)?,
-                executed_at: row.get(11)?,
-                completed_at: row.get(12)?,
-                error: row.get(13)?,
-                is_simulation: row.get(14)?,
-            })
-        })?;
+        let plan_iter = stmt.query_map(&params[..], map_reply_plan_row)?;
 
         plan_iter.collect::<Result<Vec<_>, _>>().map_err(|e| anyhow::anyhow!(e))
     }
@@ -520,29 +643,251 @@ impl ProspectingRepository {
         let mut stmt = conn.prepare(&sql)?;
         let params: Vec<&dyn rusqlite::ToSql> = ids.iter().map(|s| s as &dyn rusqlite::ToSql).collect();
         
-        let plan_iter = stmt.query_map(&params[..], |row| {
-            let steps_json: String = row.get(7)?;
-            let steps: Vec<ReplyStep> = serde_json::from_str(&steps_json).unwrap_or_default();
-            
-            Ok(ReplyPlan {
+        let plan_iter = stmt.query_map(&params[..], map_reply_plan_row)?;
+
+        plan_iter.collect::<Result<Vec<_>, _>>().map_err(|e| anyhow::anyhow!(e))
+    }
+
+    /// 获取需要（重新）向量化的评论：没有该模型的向量，或内容已变化
+    pub fn get_comments_pending_embedding(&self, model: &str, limit: usize) -> Result<Vec<(String, String)>> {
+        let conn = get_connection(&self.db_path)?;
+        let mut stmt = conn.prepare(
+            r#"
+            SELECT c.id, c.content, e.content_hash
+            FROM comments c
+            LEFT JOIN comment_embeddings e ON c.id = e.comment_id AND e.model = ?1
+            ORDER BY c.created_at DESC
+            "#,
+        )?;
+        let rows = stmt.query_map(params![model], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?, row.get::<_, Option<String>>(2)?))
+        })?;
+
+        let mut pending = Vec::new();
+        for row in rows {
+            let (id, content, hash) = row?;
+            if content.trim().is_empty() || hash.as_deref() == Some(content_hash(&content).as_str()) {
+                continue;
+            }
+            pending.push((id, content));
+            if pending.len() >= limit {
+                break;
+            }
+        }
+        Ok(pending)
+    }
+
+    /// 批量保存评论向量（单事务）
+    pub fn save_embeddings(&self, model: &str, items: &[(String, String, Vec<f32>)]) -> Result<()> {
+        let mut conn = get_connection(&self.db_path)?;
+        let tx = conn.transaction()?;
+        let now = chrono::Utc::now().timestamp();
+        {
+            let mut stmt = tx.prepare(
+                r#"
+                INSERT OR REPLACE INTO comment_embeddings
+                (comment_id, model, dims, vector, content_hash, updated_at)
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+                "#,
+            )?;
+            for (comment_id, content, vector) in items {
+                stmt.execute(params![
+                    comment_id,
+                    model,
+                    vector.len() as i64,
+                    encode_vector(vector),
+                    content_hash(content),
+                    now,
+                ])?;
+            }
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// 加载指定模型的全部向量
+    pub fn load_embeddings(&self, model: &str) -> Result<Vec<(String, Vec<f32>)>> {
+        let conn = get_connection(&self.db_path)?;
+        let mut stmt = conn.prepare("SELECT comment_id, vector FROM comment_embeddings WHERE model = ?1")?;
+        let rows = stmt.query_map(params![model], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, Vec<u8>>(1)?))
+        })?;
+
+        let mut embeddings = Vec::new();
+        for row in rows {
+            let (id, bytes) = row?;
+            if let Some(vector) = decode_vector(&bytes) {
+                embeddings.push((id, vector));
+            }
+        }
+        Ok(embeddings)
+    }
+
+    /// 写入回复计划的排期（单事务），返回更新条数
+    pub fn set_reply_plan_schedule(&self, plan_ids: &[String], campaign_id: Option<&str>, scheduled_at: i64) -> Result<usize> {
+        let mut conn = get_connection(&self.db_path)?;
+        let tx = conn.transaction()?;
+        let now = chrono::Utc::now().timestamp_millis();
+        let mut updated = 0;
+        {
+            let mut stmt = tx.prepare(
+                "UPDATE reply_plans SET scheduled_at = ?1, campaign_id = COALESCE(?2, campaign_id), updated_at = ?3 WHERE id = ?4",
+            )?;
+            for id in plan_ids {
+                updated += stmt.execute(params![scheduled_at, campaign_id, now, id])?;
+            }
+        }
+        tx.commit()?;
+        Ok(updated)
+    }
+
+    /// 到期（或未排期）的待执行回复计划，按计划时间排序
+    pub fn get_due_reply_plans(&self, now: i64, limit: usize) -> Result<Vec<ReplyPlan>> {
+        let conn = get_connection(&self.db_path)?;
+        let mut stmt = conn.prepare(
+            "SELECT * FROM reply_plans WHERE status = ?1 AND (scheduled_at IS NULL OR scheduled_at <= ?2)
+             ORDER BY COALESCE(scheduled_at, created_at) LIMIT ?3",
+        )?;
+        let plans = stmt.query_map(params![&ReplyPlanStatus::Pending, now, limit as i64], map_reply_plan_row)?;
+        plans.collect::<Result<Vec<_>, _>>().map_err(|e| anyhow::anyhow!(e))
+    }
+
+    /// 查询内容日历档期；`from` / `to` 为 YYYY-MM-DD（含）
+    pub fn list_calendar_entries(
+        &self,
+        from: Option<&str>,
+        to: Option<&str>,
+        campaign_id: Option<&str>,
+    ) -> Result<Vec<CalendarEntry>> {
+        let conn = get_connection(&self.db_path)?;
+        let mut stmt = conn.prepare(
+            r#"
+            SELECT e.id, e.name, e.slot_date, e.slot_time, e.note, e.created_at, e.updated_at,
+                   (SELECT group_concat(campaign_id, char(31)) FROM content_calendar_campaigns WHERE entry_id = e.id)
+            FROM content_calendar_entries e
+            WHERE (?1 IS NULL OR e.slot_date >= ?1)
+              AND (?2 IS NULL OR e.slot_date <= ?2)
+              AND (?3 IS NULL OR EXISTS (SELECT 1 FROM content_calendar_campaigns c WHERE c.entry_id = e.id AND c.campaign_id = ?3))
+            ORDER BY e.slot_date, COALESCE(e.slot_time, ?4)
+            "#,
+        )?;
+        let rows = stmt.query_map(params![from, to, campaign_id, DEFAULT_SLOT_TIME], |row| {
+            let campaigns: Option<String> = row.get(7)?;
+            Ok(CalendarEntry {
                 id: row.get(0)?,
-                comment_id: row.get(1)?,
-                platform: row.get(2)?,
-                video_url: row.get(3)?,
-                target_author: row.get(4)?,
-                target_comment: row.get(5)?,
-                reply_content: row.get(6)?,
-                steps,
-                status: row.get(8)?,
-                created_at: row.get(9)?,
-                updated_at: row.get(10)?,
-                executed_at: row.get(11)?,
-                completed_at: row.get(12)?,
-                error: row.get(13)?,
-                is_simulation: row.get(14)?,
+                name: row.get(1)?,
+                slot_date: row.get(2)?,
+                slot_time: row.get(3)?,
+                note: row.get(4)?,
+                created_at: row.get(5)?,
+                updated_at: row.get(6)?,
+                campaign_ids: campaigns
+                    .map(|c| c.split('\u{1f}').map(str::to_string).collect())
+                    .unwrap_or_default(),
             })
         })?;
+        rows.collect::<Result<Vec<_>, _>>().map_err(|e| anyhow::anyhow!(e))
+    }
 
-        plan_iter.collect::<Result<Vec<_>, _>>().map_err(|e| anyhow::anyhow!(e))
+    /// 新建或更新档期（连同关联活动整体替换）
+    pub fn save_calendar_entry(&self, entry: &CalendarEntry) -> Result<()> {
+        let mut conn = get_connection(&self.db_path)?;
+        let tx = conn.transaction()?;
+        tx.execute(
+            r#"
+            INSERT INTO content_calendar_entries (id, name, slot_date, slot_time, note, created_at, updated_at)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+            ON CONFLICT(id) DO UPDATE SET
+              name = excluded.name, slot_date = excluded.slot_date, slot_time = excluded.slot_time,
+              note = excluded.note, updated_at = excluded.updated_at
+            "#,
+            params![
+                entry.id,
+                entry.name.trim(),
+                entry.slot_date.trim(),
+                entry.slot_time.as_deref().map(str::trim),
+                entry.note,
+                entry.created_at,
+                entry.updated_at,
+            ],
+        )?;
+        tx.execute("DELETE FROM content_calendar_campaigns WHERE entry_id = ?1", params![entry.id])?;
+        {
+            let mut stmt = tx.prepare("INSERT OR IGNORE INTO content_calendar_campaigns (entry_id, campaign_id) VALUES (?1, ?2)")?;
+            for campaign_id in entry.campaign_ids.iter().map(|c| c.trim()).filter(|c| !c.is_empty()) {
+                stmt.execute(params![entry.id, campaign_id])?;
+            }
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// 删除档期，返回是否存在
+    pub fn delete_calendar_entry(&self, id: &str) -> Result<bool> {
+        let conn = get_connection(&self.db_path)?;
+        conn.execute("DELETE FROM content_calendar_campaigns WHERE entry_id = ?1", params![id])?;
+        let deleted = conn.execute("DELETE FROM content_calendar_entries WHERE id = ?1", params![id])?;
+        Ok(deleted > 0)
+    }
+
+    /// 保存规则标注
+    pub fn save_rule_label(&self, comment_id: &str, label: &RuleMatch, labeled_at: i64) -> Result<()> {
+        let conn = get_connection(&self.db_path)?;
+        conn.execute(
+            r#"
+            INSERT OR REPLACE INTO rule_labels
+            (comment_id, rule_id, rule_name, intent, priority, matched_terms, labeled_at)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+            "#,
+            params![
+                comment_id,
+                label.rule_id,
+                label.rule_name,
+                &label.intent,
+                label.priority,
+                serde_json::to_string(&label.matched_terms)?,
+                labeled_at,
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// 删除规则标注（规则不再命中时）
+    pub fn delete_rule_label(&self, comment_id: &str) -> Result<()> {
+        let conn = get_connection(&self.db_path)?;
+        conn.execute("DELETE FROM rule_labels WHERE comment_id = ?1", params![comment_id])?;
+        Ok(())
     }
-}
\ No newline at end of file
+}
+
+/// `SELECT * FROM reply_plans` 的行映射
+fn map_reply_plan_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<ReplyPlan> {
+    let steps_json: String = row.get(7)?;
+    let steps: Vec<ReplyStep> = serde_json::from_str(&steps_json).unwrap_or_default();
+
+    Ok(ReplyPlan {
+        id: row.get(0)?,
+        comment_id: row.get(1)?,
+        platform: row.get(2)?,
+        video_url: row.get(3)?,
+        target_author: row.get(4)?,
+        target_comment: row.get(5)?,
+        reply_content: row.get(6)?,
+        steps,
+        status: row.get(8)?,
+        created_at: row.get(9)?,
+        updated_at: row.get(10)?,
+        executed_at: row.get(11)?,
+        completed_at: row.get(12)?,
+        error: row.get(13)?,
+        is_simulation: row.get(14)?,
+        campaign_id: row.get(15)?,
+        scheduled_at: row.get(16)?,
+        media_asset_id: row.get(17)?,
+    })
+}
+
+/// 评论内容摘要（用于判断向量是否过期）
+fn content_hash(content: &str) -> String {
+    format!("{:x}", md5::compute(content.as_bytes()))
+}
@@ -0,0 +1,377 @@
+// src-tauri/src/services/prospecting/prospecting_rules.rs
+// module: services/prospecting | layer: domain | role: 关键词规则引擎
+// summary: 用户自定义关键词/正则规则（支持 全部/任一/非 组合）映射到意图与优先级，本地评估评论，并按配置与 AI 标注合并
+
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+use super::prospecting_types::{AnalysisResult, Entities, IntentType};
+
+/// 规则配置文件名（位于应用数据目录）
+pub const RULES_FILE_NAME: &str = "prospecting_rules.json";
+/// 规则命中时写入分析结果的置信度
+const RULE_CONFIDENCE: f64 = 1.0;
+/// 组合条件的最大嵌套深度
+const MAX_CONDITION_DEPTH: usize = 8;
+
+/// 规则条件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum RuleCondition {
+    /// 包含关键词
+    Keyword {
+        value: String,
+        #[serde(default, rename = "caseSensitive")]
+        case_sensitive: bool,
+    },
+    /// 匹配正则
+    Regex {
+        pattern: String,
+        #[serde(default, rename = "caseSensitive")]
+        case_sensitive: bool,
+    },
+    /// 全部满足
+    All { conditions: Vec<RuleCondition> },
+    /// 任一满足
+    Any { conditions: Vec<RuleCondition> },
+    /// 不满足
+    Not { condition: Box<RuleCondition> },
+}
+
+/// 一条关键词规则
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KeywordRule {
+    pub id: String,
+    pub name: String,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    pub condition: RuleCondition,
+    pub intent: IntentType,
+    /// 数值越大越优先；同优先级按规则顺序
+    #[serde(default)]
+    pub priority: i32,
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+/// 规则与 AI 标注冲突时的处理方式
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum ConflictPolicy {
+    /// 规则结果覆盖 AI 结果
+    #[default]
+    RulesOverride,
+    /// 已有 AI 结果时保留 AI，规则只补充未分析的评论
+    AiOverrides,
+    /// 两者都保存：AI 结果不动，规则结果单独记录
+    KeepBoth,
+}
+
+/// 规则集（持久化单元）
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct RuleSet {
+    pub rules: Vec<KeywordRule>,
+    #[serde(default, rename = "conflictPolicy")]
+    pub conflict_policy: ConflictPolicy,
+}
+
+impl RuleSet {
+    /// 从文件加载；文件不存在时返回空规则集
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        Ok(serde_json::from_slice(&std::fs::read(path)?)?)
+    }
+
+    pub fn save(&self, path: &Path) -> anyhow::Result<()> {
+        std::fs::write(path, serde_json::to_vec_pretty(self)?)?;
+        Ok(())
+    }
+}
+
+/// 单条规则命中
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RuleMatch {
+    #[serde(rename = "ruleId")]
+    pub rule_id: String,
+    #[serde(rename = "ruleName")]
+    pub rule_name: String,
+    pub intent: IntentType,
+    pub priority: i32,
+    pub tags: Vec<String>,
+    /// 命中的关键词 / 正则片段
+    #[serde(rename = "matchedTerms")]
+    pub matched_terms: Vec<String>,
+}
+
+/// 分析结果是否由规则写入
+pub fn is_rule_analysis(analysis: &AnalysisResult) -> bool {
+    analysis.tags.iter().any(|t| t.starts_with("rule:"))
+}
+
+impl RuleMatch {
+    /// 转为分析结果（规则命中视为确定结果）
+    pub fn to_analysis(&self, comment_id: &str, analyzed_at: i64) -> AnalysisResult {
+        let mut tags = self.tags.clone();
+        tags.push(format!("rule:{}", self.rule_name));
+        AnalysisResult {
+            comment_id: comment_id.to_string(),
+            intent: self.intent.clone(),
+            confidence: RULE_CONFIDENCE,
+            entities: Entities::default(),
+            suggested_reply: String::new(),
+            tags,
+            analyzed_at,
+        }
+    }
+}
+
+/// 规则测试台单条结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RuleTestResult {
+    pub text: String,
+    /// 全部命中（按优先级降序）
+    pub matches: Vec<RuleMatch>,
+    /// 最终采用的规则
+    pub winner: Option<RuleMatch>,
+}
+
+/// 批量应用规则的统计
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct RuleApplyReport {
+    pub evaluated: usize,
+    pub matched: usize,
+    /// 规则结果写入分析表的条数
+    pub written: usize,
+    /// 因策略保留 AI 结果的条数
+    #[serde(rename = "keptAi")]
+    pub kept_ai: usize,
+}
+
+/// 对已有 AI 结果应用冲突策略后的写入决定
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Resolution {
+    /// 写入规则结果到分析表
+    WriteRule,
+    /// 保留 AI 结果，仅记录规则标注
+    KeepAi,
+}
+
+/// 按策略决定规则命中是否写入分析结果
+pub fn resolve_conflict(policy: ConflictPolicy, has_ai_analysis: bool) -> Resolution {
+    match (policy, has_ai_analysis) {
+        (_, false) => Resolution::WriteRule,
+        (ConflictPolicy::RulesOverride, true) => Resolution::WriteRule,
+        (ConflictPolicy::AiOverrides | ConflictPolicy::KeepBoth, true) => Resolution::KeepAi,
+    }
+}
+
+#[derive(Debug)]
+enum CompiledCondition {
+    Keyword { needle: String, case_sensitive: bool },
+    Regex(Regex),
+    All(Vec<CompiledCondition>),
+    Any(Vec<CompiledCondition>),
+    Not(Box<CompiledCondition>),
+}
+
+impl CompiledCondition {
+    fn compile(condition: &RuleCondition, depth: usize) -> Result<Self, String> {
+        if depth > MAX_CONDITION_DEPTH {
+            return Err(format!("条件嵌套超过 {} 层", MAX_CONDITION_DEPTH));
+        }
+        let compile_all = |conditions: &[RuleCondition]| -> Result<Vec<Self>, String> {
+            if conditions.is_empty() {
+                return Err("组合条件不能为空".to_string());
+            }
+            conditions.iter().map(|c| Self::compile(c, depth + 1)).collect()
+        };
+        Ok(match condition {
+            RuleCondition::Keyword { value, case_sensitive } => {
+                if value.trim().is_empty() {
+                    return Err("关键词不能为空".to_string());
+                }
+                let needle = if *case_sensitive { value.clone() } else { value.to_lowercase() };
+                Self::Keyword { needle, case_sensitive: *case_sensitive }
+            }
+            RuleCondition::Regex { pattern, case_sensitive } => RegexBuilder::new(pattern)
+                .case_insensitive(!case_sensitive)
+                .size_limit(1 << 20)
+                .build()
+                .map(Self::Regex)
+                .map_err(|e| format!("正则无效 `{}`: {}", pattern, e))?,
+            RuleCondition::All { conditions } => Self::All(compile_all(conditions)?),
+            RuleCondition::Any { conditions } => Self::Any(compile_all(conditions)?),
+            RuleCondition::Not { condition } => Self::Not(Box::new(Self::compile(condition, depth + 1)?)),
+        })
+    }
+
+    /// 评估条件，命中的片段写入 `terms`（"非"分支内的命中不计入）
+    fn matches(&self, text: &str, lowered: &str, terms: &mut Vec<String>) -> bool {
+        match self {
+            Self::Keyword { needle, case_sensitive } => {
+                let haystack = if *case_sensitive { text } else { lowered };
+                let hit = haystack.contains(needle.as_str());
+                if hit {
+                    terms.push(needle.clone());
+                }
+                hit
+            }
+            Self::Regex(regex) => match regex.find(text) {
+                Some(m) => {
+                    terms.push(m.as_str().to_string());
+                    true
+                }
+                None => false,
+            },
+            Self::All(conditions) => {
+                let mut collected = Vec::new();
+                let hit = conditions.iter().all(|c| c.matches(text, lowered, &mut collected));
+                if hit {
+                    terms.extend(collected);
+                }
+                hit
+            }
+            Self::Any(conditions) => {
+                // 不短路，收集所有命中的片段便于测试台展示
+                let hits: Vec<bool> = conditions.iter().map(|c| c.matches(text, lowered, terms)).collect();
+                hits.into_iter().any(|h| h)
+            }
+            Self::Not(condition) => !condition.matches(text, lowered, &mut Vec::new()),
+        }
+    }
+}
+
+/// 编译后的规则引擎
+#[derive(Debug)]
+pub struct RuleEngine {
+    rules: Vec<(KeywordRule, CompiledCondition)>,
+    policy: ConflictPolicy,
+}
+
+impl RuleEngine {
+    /// 编译规则集；任一启用规则无效时返回带规则名的错误
+    pub fn compile(rule_set: &RuleSet) -> Result<Self, String> {
+        let mut rules = Vec::new();
+        for rule in rule_set.rules.iter().filter(|r| r.enabled) {
+            let compiled = CompiledCondition::compile(&rule.condition, 0)
+                .map_err(|e| format!("规则「{}」: {}", rule.name, e))?;
+            rules.push((rule.clone(), compiled));
+        }
+        // 稳定排序：同优先级保持用户定义的顺序
+        rules.sort_by(|a, b| b.0.priority.cmp(&a.0.priority));
+        Ok(Self { rules, policy: rule_set.conflict_policy })
+    }
+
+    pub fn policy(&self) -> ConflictPolicy {
+        self.policy
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// 全部命中的规则（按优先级降序）
+    pub fn evaluate(&self, text: &str) -> Vec<RuleMatch> {
+        let lowered = text.to_lowercase();
+        self.rules.iter()
+            .filter_map(|(rule, condition)| {
+                let mut terms = Vec::new();
+                condition.matches(text, &lowered, &mut terms).then(|| RuleMatch {
+                    rule_id: rule.id.clone(),
+                    rule_name: rule.name.clone(),
+                    intent: rule.intent.clone(),
+                    priority: rule.priority,
+                    tags: rule.tags.clone(),
+                    matched_terms: terms,
+                })
+            })
+            .collect()
+    }
+
+    /// 最高优先级的命中
+    pub fn best_match(&self, text: &str) -> Option<RuleMatch> {
+        self.evaluate(text).into_iter().next()
+    }
+
+    /// 测试台：对样例文本逐条评估
+    pub fn test(&self, samples: &[String]) -> Vec<RuleTestResult> {
+        samples.iter()
+            .map(|text| {
+                let matches = self.evaluate(text);
+                RuleTestResult {
+                    text: text.clone(),
+                    winner: matches.first().cloned(),
+                    matches,
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keyword(value: &str) -> RuleCondition {
+        RuleCondition::Keyword { value: value.to_string(), case_sensitive: false }
+    }
+
+    fn rule(id: &str, priority: i32, intent: IntentType, condition: RuleCondition) -> KeywordRule {
+        KeywordRule {
+            id: id.to_string(),
+            name: id.to_string(),
+            enabled: true,
+            condition,
+            intent,
+            priority,
+            tags: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn combinators_and_priorities_pick_winner() {
+        let rule_set = RuleSet {
+            rules: vec![
+                rule("price", 1, IntentType::Inquiry, RuleCondition::Any {
+                    conditions: vec![keyword("多少钱"), RuleCondition::Regex { pattern: r"价[格钱]".into(), case_sensitive: false }],
+                }),
+                rule("buy", 5, IntentType::Purchase, RuleCondition::All {
+                    conditions: vec![
+                        keyword("买"),
+                        RuleCondition::Not { condition: Box::new(keyword("不买")) },
+                    ],
+                }),
+            ],
+            conflict_policy: ConflictPolicy::KeepBoth,
+        };
+        let engine = RuleEngine::compile(&rule_set).unwrap();
+
+        let results = engine.test(&["想买，多少钱？".to_string(), "不买，问下价格".to_string(), "路过".to_string()]);
+        assert_eq!(results[0].winner.as_ref().unwrap().rule_id, "buy");
+        assert_eq!(results[0].matches.len(), 2);
+        assert_eq!(results[1].winner.as_ref().unwrap().rule_id, "price");
+        assert_eq!(results[1].winner.as_ref().unwrap().matched_terms, vec!["价格".to_string()]);
+        assert!(results[2].winner.is_none());
+
+        assert_eq!(resolve_conflict(engine.policy(), true), Resolution::KeepAi);
+        assert_eq!(resolve_conflict(ConflictPolicy::RulesOverride, true), Resolution::WriteRule);
+        assert_eq!(resolve_conflict(ConflictPolicy::AiOverrides, false), Resolution::WriteRule);
+    }
+
+    #[test]
+    fn reports_invalid_rules_by_name() {
+        let rule_set = RuleSet {
+            rules: vec![rule("坏规则", 0, IntentType::Invalid, RuleCondition::Regex { pattern: "(".into(), case_sensitive: false })],
+            conflict_policy: ConflictPolicy::default(),
+        };
+        let error = RuleEngine::compile(&rule_set).unwrap_err();
+        assert!(error.starts_with("规则「坏规则」: 正则无效"));
+    }
+}
@@ -1,20 +1,47 @@
 use anyhow::Result;
+use parking_lot::RwLock;
+use std::collections::HashMap;
 use std::path::PathBuf;
 use std::sync::Arc;
 
+use super::prospecting_calendar::{next_slot_for_campaign, CalendarEntry, ReplySchedule, ScheduledReplies};
 use super::prospecting_repository::ProspectingRepository;
+use super::prospecting_rules::*;
 use super::prospecting_types::*;
+use super::prospecting_vector_index::VectorIndex;
 
 /// 精准获客服务
 pub struct ProspectingService {
     repo: Arc<ProspectingRepository>,
+    /// 向量索引缓存（按向量模型），首次检索时从数据库加载
+    vector_index: RwLock<Option<(String, VectorIndex)>>,
+    /// 关键词规则（原始定义 + 编译结果）
+    rules: RwLock<(RuleSet, RuleEngine)>,
+    rules_path: PathBuf,
 }
 
 impl ProspectingService {
     /// 创建新的服务实例
     pub fn new(data_dir: PathBuf) -> Result<Self> {
+        let rules_path = data_dir.join(RULES_FILE_NAME);
         let repo = Arc::new(ProspectingRepository::new(data_dir)?);
-        Ok(Self { repo })
+
+        // 规则文件损坏时不阻塞服务启动，按空规则运行
+        let rule_set = RuleSet::load(&rules_path).unwrap_or_else(|e| {
+            tracing::warn!("⚠️ 关键词规则加载失败，按空规则运行: {}", e);
+            RuleSet::default()
+        });
+        let engine = RuleEngine::compile(&rule_set).unwrap_or_else(|e| {
+            tracing::warn!("⚠️ 关键词规则编译失败，按空规则运行: {}", e);
+            RuleEngine::compile(&RuleSet::default()).expect("empty rule set compiles")
+        });
+
+        Ok(Self {
+            repo,
+            vector_index: RwLock::new(None),
+            rules: RwLock::new((rule_set, engine)),
+            rules_path,
+        })
     }
 
     /// 初始化数据库
@@ -27,8 +54,19 @@ impl ProspectingService {
         self.repo.save_comment(comment)
     }
 
-    /// 保存分析结果
+    /// 批量导入评论，返回新写入条数
+    pub fn import_comments(&self, comments: &[RawComment]) -> Result<usize> {
+        self.repo.import_comments(comments)
+    }
+
+    /// 保存分析结果（AI）；策略为"规则覆盖"时不覆盖规则写入的结果
     pub fn save_analysis(&self, analysis: &AnalysisResult) -> Result<()> {
+        if self.rules.read().1.policy() == ConflictPolicy::RulesOverride {
+            let existing = self.repo.get_comments_by_ids(std::slice::from_ref(&analysis.comment_id))?;
+            if existing.iter().any(|c| c.analysis.as_ref().is_some_and(is_rule_analysis)) {
+                return Ok(());
+            }
+        }
         self.repo.save_analysis(analysis)
     }
 
@@ -61,4 +99,188 @@ impl ProspectingService {
     pub fn get_reply_plans_by_ids(&self, ids: &[String]) -> Result<Vec<ReplyPlan>> {
         self.repo.get_reply_plans_by_ids(ids)
     }
+
+    /// 内容日历档期列表
+    pub fn list_calendar_entries(&self, from: Option<&str>, to: Option<&str>, campaign_id: Option<&str>) -> Result<Vec<CalendarEntry>> {
+        self.repo.list_calendar_entries(from, to, campaign_id)
+    }
+
+    /// 保存档期（`id` 为空时新建），返回保存后的档期
+    pub fn save_calendar_entry(&self, mut entry: CalendarEntry) -> Result<CalendarEntry> {
+        entry.validate().map_err(|e| anyhow::anyhow!(e))?;
+        let now = chrono::Utc::now().timestamp_millis();
+        if entry.id.trim().is_empty() {
+            entry.id = format!("cal_{}", uuid::Uuid::new_v4().simple());
+            entry.created_at = now;
+        }
+        entry.updated_at = now;
+        self.repo.save_calendar_entry(&entry)?;
+        Ok(entry)
+    }
+
+    pub fn delete_calendar_entry(&self, id: &str) -> Result<bool> {
+        self.repo.delete_calendar_entry(id)
+    }
+
+    /// 为回复计划排期：指定时间，或活动在内容日历上的下一个档期
+    pub fn schedule_reply_plans(&self, plan_ids: &[String], schedule: &ReplySchedule) -> Result<ScheduledReplies> {
+        let (scheduled_at, calendar_entry, campaign_id) = match schedule {
+            ReplySchedule::At { at } => (*at, None, None),
+            ReplySchedule::NextCalendarSlot { campaign_id, after } => {
+                let now = after.unwrap_or_else(|| chrono::Utc::now().timestamp_millis());
+                let entries = self.repo.list_calendar_entries(None, None, Some(campaign_id))?;
+                let (entry, ts) = next_slot_for_campaign(&entries, campaign_id, now)
+                    .ok_or_else(|| anyhow::anyhow!("活动 {} 在内容日历上没有后续档期", campaign_id))?;
+                (ts, Some(entry.clone()), Some(campaign_id.as_str()))
+            }
+        };
+        self.repo.set_reply_plan_schedule(plan_ids, campaign_id, scheduled_at)?;
+        Ok(ScheduledReplies { scheduled_at, calendar_entry, plan_ids: plan_ids.to_vec() })
+    }
+
+    /// 到期的待执行回复计划
+    pub fn get_due_reply_plans(&self, now: i64, limit: usize) -> Result<Vec<ReplyPlan>> {
+        self.repo.get_due_reply_plans(now, limit)
+    }
+
+    /// 获取需要（重新）向量化的评论 (id, content)
+    pub fn get_comments_pending_embedding(&self, model: &str, limit: usize) -> Result<Vec<(String, String)>> {
+        self.repo.get_comments_pending_embedding(model, limit)
+    }
+
+    /// 保存评论向量，并同步更新已加载的索引
+    pub fn save_embeddings(&self, model: &str, items: &[(String, String, Vec<f32>)]) -> Result<()> {
+        self.repo.save_embeddings(model, items)?;
+        let mut cache = self.vector_index.write();
+        if let Some((cached_model, index)) = cache.as_mut() {
+            if cached_model == model {
+                for (id, _, vector) in items {
+                    if index.upsert(id, vector).is_err() {
+                        // 维度变化（换了同名模型），下次检索时重新加载
+                        *cache = None;
+                        break;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn with_vector_index<T>(&self, model: &str, f: impl FnOnce(&VectorIndex) -> Result<T>) -> Result<T> {
+        {
+            let cache = self.vector_index.read();
+            if let Some((cached_model, index)) = cache.as_ref() {
+                if cached_model == model {
+                    return f(index);
+                }
+            }
+        }
+        let mut index = VectorIndex::new(0);
+        for (id, vector) in self.repo.load_embeddings(model)? {
+            // 跳过维度不一致的旧数据
+            let _ = index.upsert(&id, &vector);
+        }
+        let result = f(&index);
+        *self.vector_index.write() = Some((model.to_string(), index));
+        result
+    }
+
+    /// 索引中指定模型的向量数
+    pub fn embedding_count(&self, model: &str) -> Result<usize> {
+        self.with_vector_index(model, |index| Ok(index.len()))
+    }
+
+    /// 语义检索：返回与查询向量最相似的评论
+    pub fn semantic_search(&self, model: &str, query: &[f32], top_k: usize) -> Result<Vec<SemanticMatch>> {
+        let hits = self.with_vector_index(model, |index| index.search(query, top_k))?;
+        let ids: Vec<String> = hits.iter().map(|(id, _)| id.clone()).collect();
+        let mut comments: HashMap<String, Comment> = self.repo.get_comments_by_ids(&ids)?
+            .into_iter()
+            .map(|c| (c.raw.id.clone(), c))
+            .collect();
+        Ok(hits.into_iter()
+            .filter_map(|(id, score)| comments.remove(&id).map(|comment| SemanticMatch { comment, score }))
+            .collect())
+    }
+
+    /// 评论聚类（K-Means），按簇大小降序
+    pub fn cluster_comments(&self, model: &str, k: usize) -> Result<Vec<CommentCluster>> {
+        let clusters = self.with_vector_index(model, |index| {
+            Ok(index.kmeans(k).into_iter()
+                .map(|c| {
+                    let ids: Vec<String> = c.members.iter().map(|&i| index.ids()[i].clone()).collect();
+                    (ids, index.ids()[c.representative].clone(), c.cohesion)
+                })
+                .collect::<Vec<_>>())
+        })?;
+
+        let representative_ids: Vec<String> = clusters.iter().map(|(_, rep, _)| rep.clone()).collect();
+        let mut representatives: HashMap<String, Comment> = self.repo.get_comments_by_ids(&representative_ids)?
+            .into_iter()
+            .map(|c| (c.raw.id.clone(), c))
+            .collect();
+        Ok(clusters.into_iter()
+            .enumerate()
+            .map(|(cluster_id, (comment_ids, rep, cohesion))| CommentCluster {
+                cluster_id,
+                size: comment_ids.len(),
+                comment_ids,
+                representative: representatives.remove(&rep),
+                cohesion,
+            })
+            .collect())
+    }
+
+    /// 当前关键词规则集
+    pub fn get_rule_set(&self) -> RuleSet {
+        self.rules.read().0.clone()
+    }
+
+    /// 校验并保存关键词规则集
+    pub fn save_rule_set(&self, rule_set: RuleSet) -> Result<()> {
+        let engine = RuleEngine::compile(&rule_set).map_err(anyhow::Error::msg)?;
+        rule_set.save(&self.rules_path)?;
+        *self.rules.write() = (rule_set, engine);
+        Ok(())
+    }
+
+    /// 规则测试台：`rule_set` 为空时使用已保存的规则
+    pub fn test_rules(&self, rule_set: Option<&RuleSet>, samples: &[String]) -> Result<Vec<RuleTestResult>> {
+        match rule_set {
+            Some(rule_set) => Ok(RuleEngine::compile(rule_set).map_err(anyhow::Error::msg)?.test(samples)),
+            None => Ok(self.rules.read().1.test(samples)),
+        }
+    }
+
+    /// 对评论应用关键词规则：记录规则标注，并按冲突策略决定是否写入分析结果
+    pub fn apply_rules(&self, comment_ids: Option<&[String]>) -> Result<RuleApplyReport> {
+        let comments = match comment_ids {
+            Some(ids) => self.repo.get_comments_by_ids(ids)?,
+            None => self.repo.get_comments(&CommentFilter::default())?,
+        };
+        let rules = self.rules.read();
+        let engine = &rules.1;
+        let now = chrono::Utc::now().timestamp();
+        let mut report = RuleApplyReport::default();
+
+        for comment in &comments {
+            report.evaluated += 1;
+            let Some(winner) = engine.best_match(&comment.raw.content) else {
+                self.repo.delete_rule_label(&comment.raw.id)?;
+                continue;
+            };
+            report.matched += 1;
+            self.repo.save_rule_label(&comment.raw.id, &winner, now)?;
+
+            let has_ai = comment.analysis.as_ref().is_some_and(|a| !is_rule_analysis(a));
+            match resolve_conflict(engine.policy(), has_ai) {
+                Resolution::WriteRule => {
+                    self.repo.save_analysis(&winner.to_analysis(&comment.raw.id, now))?;
+                    report.written += 1;
+                }
+                Resolution::KeepAi => report.kept_ai += 1,
+            }
+        }
+        Ok(report)
+    }
 }
@@ -4,6 +4,8 @@ use rusqlite::Result;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
+pub use crate::services::comment_language::CommentLanguage;
+
 /// 社交媒体平台
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
@@ -118,6 +120,9 @@ pub struct Comment {
     pub replied_at: Option<i64>,
     #[serde(rename = "actualReply")]
     pub actual_reply: Option<String>,
+    /// 入库时识别的语言
+    #[serde(default)]
+    pub language: CommentLanguage,
 }
 
 /// 回复计划状态
@@ -152,6 +157,8 @@ pub enum ReplyStepType {
     NavigateToVideo,
     FindComment,
     InputReply,
+    /// 通过图库选择器附加媒体素材（params.assetId）
+    AttachMedia,
     SendReply,
     #[serde(other)]
     Unknown,
@@ -172,7 +179,7 @@ pub enum ReplyStepStatus {
 pub struct ReplyStep {
     pub id: String,
     #[serde(rename = "type")]
-    pub step_type: ReplyStepType, // open_app, navigate_to_video, find_comment, input_reply, send_reply
+    pub step_type: ReplyStepType, // open_app, navigate_to_video, find_comment, input_reply, attach_media, send_reply
     pub description: String,
     pub params: HashMap<String, serde_json::Value>,
     pub status: ReplyStepStatus, // pending, executing, completed, failed
@@ -208,6 +215,15 @@ pub struct ReplyPlan {
     pub error: Option<String>,
     #[serde(rename = "isSimulation")]
     pub is_simulation: bool,
+    /// 所属活动（按内容日历排期时使用）
+    #[serde(default, rename = "campaignId")]
+    pub campaign_id: Option<String>,
+    /// 计划发送时间（unix 毫秒，与 createdAt 一致）；为空表示立即可执行
+    #[serde(default, rename = "scheduledAt")]
+    pub scheduled_at: Option<i64>,
+    /// 随回复发送的媒体素材（发送后记录实际使用的素材）
+    #[serde(default, rename = "mediaAssetId")]
+    pub media_asset_id: Option<String>,
 }
 
 /// 筛选条件
@@ -217,6 +233,8 @@ pub struct CommentFilter {
     pub intent: Option<IntentType>,
     #[serde(rename = "hasAnalysis")]
     pub has_analysis: Option<bool>,
+    #[serde(default)]
+    pub language: Option<CommentLanguage>,
 }
 
 /// 统计信息
@@ -250,4 +268,41 @@ pub struct ReplyExecutionResult {
     #[serde(rename = "completedSteps")]
     pub completed_steps: usize,
     pub error: Option<String>,
-}
\ No newline at end of file
+}
+/// 语义检索命中
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SemanticMatch {
+    pub comment: Comment,
+    /// 余弦相似度（-1 ~ 1）
+    pub score: f32,
+}
+
+/// 评论聚类结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommentCluster {
+    #[serde(rename = "clusterId")]
+    pub cluster_id: usize,
+    pub size: usize,
+    #[serde(rename = "commentIds")]
+    pub comment_ids: Vec<String>,
+    /// 最接近簇中心的评论
+    pub representative: Option<Comment>,
+    /// 成员与簇中心的平均相似度
+    pub cohesion: f32,
+}
+
+/// 批量向量化报告
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct EmbeddingIndexReport {
+    pub model: String,
+    /// 本次待向量化的评论数
+    pub pending: usize,
+    pub embedded: usize,
+    #[serde(rename = "failedIds")]
+    pub failed_ids: Vec<String>,
+    /// 实际发出的请求数（含重试）
+    pub requests: usize,
+    /// 索引中该模型的向量总数
+    #[serde(rename = "indexedTotal")]
+    pub indexed_total: usize,
+}
@@ -0,0 +1,254 @@
+// src-tauri/src/services/prospecting/prospecting_vector_index.rs
+// module: services/prospecting | layer: infrastructure | role: 评论向量索引
+// summary: 内存平铺向量索引 - 向量预先归一化连续存放，按 8 路展开的点积求余弦相似度（便于编译器向量化），支持 Top-K 检索与 K-Means 聚类
+
+use anyhow::{anyhow, Result};
+use std::collections::HashMap;
+
+/// K-Means 最大迭代次数
+const KMEANS_MAX_ITERATIONS: usize = 50;
+
+/// 平铺向量索引（几万条评论规模下全量扫描足够快）
+#[derive(Debug, Clone, Default)]
+pub struct VectorIndex {
+    dims: usize,
+    ids: Vec<String>,
+    positions: HashMap<String, usize>,
+    /// 归一化后的向量，按 `dims` 连续存放
+    data: Vec<f32>,
+}
+
+impl VectorIndex {
+    pub fn new(dims: usize) -> Self {
+        Self { dims, ..Default::default() }
+    }
+
+    pub fn dims(&self) -> usize {
+        self.dims
+    }
+
+    pub fn len(&self) -> usize {
+        self.ids.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ids.is_empty()
+    }
+
+    pub fn ids(&self) -> &[String] {
+        &self.ids
+    }
+
+    /// 插入或替换向量
+    pub fn upsert(&mut self, id: &str, vector: &[f32]) -> Result<()> {
+        if self.dims == 0 {
+            self.dims = vector.len();
+        }
+        if vector.len() != self.dims {
+            return Err(anyhow!("向量维度不一致: 索引 {}，输入 {}", self.dims, vector.len()));
+        }
+        let normalized = normalize(vector);
+        match self.positions.get(id) {
+            Some(&row) => self.data[row * self.dims..(row + 1) * self.dims].copy_from_slice(&normalized),
+            None => {
+                self.positions.insert(id.to_string(), self.ids.len());
+                self.ids.push(id.to_string());
+                self.data.extend_from_slice(&normalized);
+            }
+        }
+        Ok(())
+    }
+
+    fn row(&self, i: usize) -> &[f32] {
+        &self.data[i * self.dims..(i + 1) * self.dims]
+    }
+
+    /// 余弦相似度 Top-K（按相似度降序）
+    pub fn search(&self, query: &[f32], top_k: usize) -> Result<Vec<(String, f32)>> {
+        if self.is_empty() || top_k == 0 {
+            return Ok(Vec::new());
+        }
+        if query.len() != self.dims {
+            return Err(anyhow!("查询向量维度不一致: 索引 {}，查询 {}", self.dims, query.len()));
+        }
+        let query = normalize(query);
+        let mut scored: Vec<(usize, f32)> = (0..self.len()).map(|i| (i, dot(self.row(i), &query))).collect();
+        let top_k = top_k.min(scored.len());
+        if top_k < scored.len() {
+            scored.select_nth_unstable_by(top_k - 1, |a, b| b.1.total_cmp(&a.1));
+            scored.truncate(top_k);
+        }
+        scored.sort_by(|a, b| b.1.total_cmp(&a.1));
+        Ok(scored.into_iter().map(|(i, score)| (self.ids[i].clone(), score)).collect())
+    }
+
+    /// K-Means 聚类（球面 K-Means，最远点初始化保证结果可复现）
+    ///
+    /// 返回每个簇的成员下标，按簇大小降序；`k` 会被限制在 [1, len]。
+    pub fn kmeans(&self, k: usize) -> Vec<Cluster> {
+        let n = self.len();
+        if n == 0 {
+            return Vec::new();
+        }
+        let k = k.clamp(1, n);
+
+        // 最远点初始化：从第一条开始，每次选与已有中心最不相似的一条
+        let mut centroids: Vec<Vec<f32>> = vec![self.row(0).to_vec()];
+        let mut best_sim: Vec<f32> = (0..n).map(|i| dot(self.row(i), &centroids[0])).collect();
+        while centroids.len() < k {
+            let (next, _) = best_sim.iter().enumerate().min_by(|a, b| a.1.total_cmp(b.1)).unwrap();
+            centroids.push(self.row(next).to_vec());
+            let latest = centroids.last().unwrap();
+            for (i, sim) in best_sim.iter_mut().enumerate() {
+                *sim = sim.max(dot(self.row(i), latest));
+            }
+        }
+
+        let mut assignment = vec![0usize; n];
+        for iteration in 0..KMEANS_MAX_ITERATIONS {
+            let mut changed = iteration == 0;
+            for (i, slot) in assignment.iter_mut().enumerate() {
+                let nearest = nearest_centroid(self.row(i), &centroids);
+                if *slot != nearest {
+                    *slot = nearest;
+                    changed = true;
+                }
+            }
+            if !changed {
+                break;
+            }
+            let mut sums = vec![vec![0.0f32; self.dims]; k];
+            for (i, &c) in assignment.iter().enumerate() {
+                for (acc, x) in sums[c].iter_mut().zip(self.row(i)) {
+                    *acc += x;
+                }
+            }
+            for (centroid, sum) in centroids.iter_mut().zip(sums) {
+                // 空簇保留原中心
+                if sum.iter().any(|x| *x != 0.0) {
+                    *centroid = normalize(&sum);
+                }
+            }
+        }
+
+        let mut clusters: Vec<Cluster> = centroids.iter()
+            .map(|_| Cluster { members: Vec::new(), representative: 0, cohesion: 0.0 })
+            .collect();
+        for (i, &c) in assignment.iter().enumerate() {
+            clusters[c].members.push(i);
+        }
+        for (cluster, centroid) in clusters.iter_mut().zip(&centroids) {
+            let sims: Vec<(usize, f32)> = cluster.members.iter().map(|&i| (i, dot(self.row(i), centroid))).collect();
+            if let Some(&(best, _)) = sims.iter().max_by(|a, b| a.1.total_cmp(&b.1)) {
+                cluster.representative = best;
+                cluster.cohesion = sims.iter().map(|(_, s)| s).sum::<f32>() / sims.len() as f32;
+            }
+        }
+        clusters.retain(|c| !c.members.is_empty());
+        clusters.sort_by(|a, b| b.members.len().cmp(&a.members.len()));
+        clusters
+    }
+}
+
+/// 聚类结果（下标指向索引中的行）
+#[derive(Debug, Clone)]
+pub struct Cluster {
+    pub members: Vec<usize>,
+    /// 最接近簇中心的成员
+    pub representative: usize,
+    /// 成员与中心的平均相似度（越高越紧凑）
+    pub cohesion: f32,
+}
+
+fn nearest_centroid(row: &[f32], centroids: &[Vec<f32>]) -> usize {
+    centroids.iter()
+        .map(|c| dot(row, c))
+        .enumerate()
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(i, _)| i)
+        .unwrap_or(0)
+}
+
+/// 点积：8 路独立累加，去掉循环依赖后编译器可生成 SIMD 指令
+pub fn dot(a: &[f32], b: &[f32]) -> f32 {
+    let mut lanes = [0.0f32; 8];
+    let chunks_a = a.chunks_exact(8);
+    let chunks_b = b.chunks_exact(8);
+    let tail: f32 = chunks_a.remainder().iter().zip(chunks_b.remainder()).map(|(x, y)| x * y).sum();
+    for (ca, cb) in chunks_a.zip(chunks_b) {
+        for ((acc, x), y) in lanes.iter_mut().zip(ca).zip(cb) {
+            *acc += x * y;
+        }
+    }
+    lanes.iter().sum::<f32>() + tail
+}
+
+fn normalize(v: &[f32]) -> Vec<f32> {
+    let norm = dot(v, v).sqrt();
+    if norm == 0.0 {
+        return v.to_vec();
+    }
+    v.iter().map(|x| x / norm).collect()
+}
+
+/// 向量编码为小端字节（SQLite BLOB）
+pub fn encode_vector(v: &[f32]) -> Vec<u8> {
+    v.iter().flat_map(|x| x.to_le_bytes()).collect()
+}
+
+/// 从小端字节解码；长度不是 4 的倍数时返回 None
+pub fn decode_vector(bytes: &[u8]) -> Option<Vec<f32>> {
+    if bytes.len() % 4 != 0 {
+        return None;
+    }
+    Some(bytes.chunks_exact(4).map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]])).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn index_of(vectors: &[(&str, Vec<f32>)]) -> VectorIndex {
+        let mut index = VectorIndex::new(0);
+        for (id, v) in vectors {
+            index.upsert(id, v).unwrap();
+        }
+        index
+    }
+
+    #[test]
+    fn searches_by_cosine_similarity() {
+        let mut index = index_of(&[
+            ("price", vec![1.0, 0.1, 0.0]),
+            ("address", vec![0.0, 1.0, 0.1]),
+            ("price2", vec![2.0, 0.0, 0.1]),
+        ]);
+        let hits = index.search(&[1.0, 0.0, 0.0], 2).unwrap();
+        assert_eq!(hits.iter().map(|(id, _)| id.as_str()).collect::<Vec<_>>(), vec!["price2", "price"]);
+        assert!(index.search(&[1.0, 0.0], 1).is_err());
+
+        index.upsert("price", &[0.0, 0.0, 1.0]).unwrap();
+        assert_eq!(index.len(), 3);
+        assert_eq!(decode_vector(&encode_vector(&[1.5, -2.0])), Some(vec![1.5, -2.0]));
+
+        let long: Vec<f32> = (0..19).map(|i| i as f32).collect();
+        assert_eq!(dot(&long, &long), long.iter().map(|x| x * x).sum::<f32>());
+    }
+
+    #[test]
+    fn clusters_separate_groups() {
+        let index = index_of(&[
+            ("a1", vec![1.0, 0.0]),
+            ("a2", vec![0.9, 0.1]),
+            ("a3", vec![0.95, 0.05]),
+            ("b1", vec![0.0, 1.0]),
+            ("b2", vec![0.1, 0.9]),
+        ]);
+        let clusters = index.kmeans(2);
+        assert_eq!(clusters.len(), 2);
+        assert_eq!(clusters[0].members, vec![0, 1, 2]);
+        assert_eq!(clusters[1].members, vec![3, 4]);
+        assert!(clusters[0].cohesion > 0.9);
+        assert_eq!(index.kmeans(10).len(), 5);
+    }
+}
@@ -0,0 +1,263 @@
+// src-tauri/src/services/protected_accounts/mod.rs
+// module: protected_accounts | layer: services | role: 受保护账号名单
+// summary: 客户自家员工、VIP 等账号永不自动触达；回复 / 关注任务出队与查重防护在任何外发动作前查名单，命中即跳过并留审计
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::{Mutex, OnceLock};
+use tracing::warn;
+
+const STORE_FILE_NAME: &str = "protected_accounts.json";
+/// 跳过记录最多保留条数
+const MAX_SKIP_RECORDS: usize = 5_000;
+/// 不限平台的名单条目
+pub const ANY_PLATFORM: &str = "*";
+
+static STORE: OnceLock<Mutex<ProtectedAccountStore>> = OnceLock::new();
+
+/// 名单条目；`account` 为平台用户 ID 或 @handle
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ProtectedAccount {
+    /// 平台（douyin / oceanengine / ...），`*` 表示所有平台
+    pub platform: String,
+    pub account: String,
+    pub reason: String,
+    pub added_by: String,
+    pub added_at: DateTime<Utc>,
+}
+
+/// 一次因命中名单而跳过的外发动作
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProtectedSkipRecord {
+    pub platform: Option<String>,
+    pub target: String,
+    /// 跳过的动作（reply / follow / ...）
+    pub action: String,
+    /// 拦截来源：task_queue | duplication_guard
+    pub source: String,
+    pub reason: String,
+    pub skipped_at: DateTime<Utc>,
+}
+
+/// 批量导入结果
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ProtectedImportReport {
+    pub added: usize,
+    pub updated: usize,
+    /// 无法解析的行（原文）
+    pub invalid_lines: Vec<String>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct StoreFile {
+    #[serde(default)]
+    accounts: Vec<ProtectedAccount>,
+    #[serde(default)]
+    skips: Vec<ProtectedSkipRecord>,
+}
+
+pub struct ProtectedAccountStore {
+    path: PathBuf,
+    data: StoreFile,
+}
+
+/// 统一大小写、去掉 handle 前的 `@`
+fn normalize_account(account: &str) -> String {
+    account.trim().trim_start_matches('@').trim().to_lowercase()
+}
+
+fn normalize_platform(platform: &str) -> String {
+    let p = platform.trim().to_lowercase();
+    if p.is_empty() { ANY_PLATFORM.to_string() } else { p }
+}
+
+impl ProtectedAccountStore {
+    pub fn global() -> &'static Mutex<ProtectedAccountStore> {
+        STORE.get_or_init(|| {
+            let path = dirs::data_dir().unwrap_or_else(|| PathBuf::from(".")).join("employee-gui").join(STORE_FILE_NAME);
+            Mutex::new(Self::open(path))
+        })
+    }
+
+    pub fn open(path: PathBuf) -> Self {
+        let data = std::fs::read_to_string(&path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+        Self { path, data }
+    }
+
+    fn save(&self) -> Result<(), String> {
+        if let Some(dir) = self.path.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+        }
+        let content = serde_json::to_string_pretty(&self.data).map_err(|e| e.to_string())?;
+        std::fs::write(&self.path, content).map_err(|e| format!("保存受保护账号名单失败: {}", e))
+    }
+
+    pub fn list(&self) -> Vec<ProtectedAccount> {
+        self.data.accounts.clone()
+    }
+
+    /// 新增或更新条目，返回是否为新增
+    fn upsert(&mut self, platform: &str, account: &str, reason: &str, added_by: &str) -> Result<bool, String> {
+        let platform = normalize_platform(platform);
+        let key = normalize_account(account);
+        if key.is_empty() {
+            return Err("账号不能为空".to_string());
+        }
+        if let Some(existing) = self.data.accounts.iter_mut().find(|a| a.platform == platform && a.account == key) {
+            existing.reason = reason.trim().to_string();
+            existing.added_by = added_by.to_string();
+            return Ok(false);
+        }
+        self.data.accounts.push(ProtectedAccount {
+            platform,
+            account: key,
+            reason: reason.trim().to_string(),
+            added_by: added_by.to_string(),
+            added_at: Utc::now(),
+        });
+        Ok(true)
+    }
+
+    pub fn add(&mut self, platform: &str, account: &str, reason: &str, added_by: &str) -> Result<ProtectedAccount, String> {
+        self.upsert(platform, account, reason, added_by)?;
+        self.save()?;
+        let (platform, key) = (normalize_platform(platform), normalize_account(account));
+        Ok(self.data.accounts.iter().find(|a| a.platform == platform && a.account == key).cloned().unwrap())
+    }
+
+    /// 删除条目，返回是否存在
+    pub fn remove(&mut self, platform: &str, account: &str) -> Result<bool, String> {
+        let (platform, key) = (normalize_platform(platform), normalize_account(account));
+        let before = self.data.accounts.len();
+        self.data.accounts.retain(|a| !(a.platform == platform && a.account == key));
+        if self.data.accounts.len() == before {
+            return Ok(false);
+        }
+        self.save()?;
+        Ok(true)
+    }
+
+    /// 批量导入，每行 `平台,账号[,原因]`；空行与 `#` 开头的注释行忽略
+    pub fn bulk_import(&mut self, text: &str, added_by: &str) -> Result<ProtectedImportReport, String> {
+        let mut report = ProtectedImportReport::default();
+        for line in text.lines() {
+            let trimmed = line.trim();
+            if trimmed.is_empty() || trimmed.starts_with('#') {
+                continue;
+            }
+            let mut parts = trimmed.splitn(3, [',', '\t']).map(str::trim);
+            let (platform, account) = (parts.next().unwrap_or(""), parts.next().unwrap_or(""));
+            let reason = parts.next().unwrap_or("");
+            if platform.is_empty() || normalize_account(account).is_empty() {
+                report.invalid_lines.push(line.to_string());
+                continue;
+            }
+            if self.upsert(platform, account, reason, added_by)? {
+                report.added += 1;
+            } else {
+                report.updated += 1;
+            }
+        }
+        if report.added + report.updated > 0 {
+            self.save()?;
+        }
+        Ok(report)
+    }
+
+    /// 查找命中的条目；`platform` 未知时按账号匹配任意平台的条目
+    pub fn find(&self, platform: Option<&str>, target: &str) -> Option<&ProtectedAccount> {
+        let key = normalize_account(target);
+        if key.is_empty() {
+            return None;
+        }
+        let platform = platform.map(normalize_platform);
+        self.data.accounts.iter().find(|a| {
+            a.account == key
+                && match platform.as_deref() {
+                    None | Some(ANY_PLATFORM) => true,
+                    Some(p) => a.platform == ANY_PLATFORM || a.platform == p,
+                }
+        })
+    }
+
+    /// 记录一次跳过
+    pub fn record_skip(&mut self, record: ProtectedSkipRecord) {
+        self.data.skips.push(record);
+        if self.data.skips.len() > MAX_SKIP_RECORDS {
+            let excess = self.data.skips.len() - MAX_SKIP_RECORDS;
+            self.data.skips.drain(0..excess);
+        }
+        if let Err(e) = self.save() {
+            warn!("⚠️ 写入受保护账号跳过记录失败: {}", e);
+        }
+    }
+
+    /// 最近的跳过记录（新的在前）
+    pub fn recent_skips(&self, limit: usize) -> Vec<ProtectedSkipRecord> {
+        self.data.skips.iter().rev().take(limit).cloned().collect()
+    }
+}
+
+/// 外发动作前的检查：命中名单时记录跳过并返回条目
+pub fn guard(platform: Option<&str>, target: &str, action: &str, source: &str) -> Option<ProtectedAccount> {
+    let mut store = match ProtectedAccountStore::global().lock() {
+        Ok(store) => store,
+        Err(e) => {
+            warn!("⚠️ 受保护账号名单不可用: {}", e);
+            return None;
+        }
+    };
+    let hit = store.find(platform, target).cloned()?;
+    warn!("🛡️ 跳过受保护账号: platform={:?} target={} action={} source={}", platform, target, action, source);
+    store.record_skip(ProtectedSkipRecord {
+        platform: platform.map(str::to_string),
+        target: target.to_string(),
+        action: action.to_string(),
+        source: source.to_string(),
+        reason: hit.reason.clone(),
+        skipped_at: Utc::now(),
+    });
+    Some(hit)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_import_match_and_skip_log() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(STORE_FILE_NAME);
+        let mut store = ProtectedAccountStore::open(path.clone());
+
+        let report = store
+            .bulk_import("# 平台,账号,原因\ndouyin,@Boss_Wang,客户老板\n*,staff01\n\nbroken-line\ndouyin,boss_wang,VIP", "ops")
+            .unwrap();
+        assert_eq!((report.added, report.updated), (2, 1));
+        assert_eq!(report.invalid_lines, vec!["broken-line".to_string()]);
+
+        assert_eq!(store.find(Some("douyin"), "boss_wang").unwrap().reason, "VIP");
+        assert!(store.find(Some("oceanengine"), "boss_wang").is_none());
+        assert!(store.find(None, "BOSS_WANG").is_some());
+        assert!(store.find(Some("oceanengine"), "@staff01").is_some());
+
+        store.record_skip(ProtectedSkipRecord {
+            platform: Some("douyin".into()),
+            target: "boss_wang".into(),
+            action: "reply".into(),
+            source: "task_queue".into(),
+            reason: "VIP".into(),
+            skipped_at: Utc::now(),
+        });
+
+        let reopened = ProtectedAccountStore::open(path);
+        assert_eq!(reopened.list().len(), 2);
+        assert_eq!(reopened.recent_skips(10).len(), 1);
+        assert!(store.remove("douyin", "Boss_Wang").unwrap());
+        assert!(store.find(Some("douyin"), "boss_wang").is_none());
+    }
+}
@@ -0,0 +1,471 @@
+// src-tauri/src/services/quick_actions/mod.rs
+// module: quick_actions | layer: services | role: 快捷动作注册表与分发
+// summary: 动作 ID → 固定参数的命令序列（内置 + 用户自定义），按用户持久化快捷键绑定；分发时在后端按序执行，独占动作互不穿插
+
+mod runner;
+
+pub use runner::TauriQuickCommandRunner;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::{Mutex, OnceLock};
+use std::time::Instant;
+
+const STORE_FILE_NAME: &str = "quick_actions.json";
+/// 未指定用户时使用的档案
+pub const DEFAULT_USER: &str = "default";
+/// 快捷动作只能发出该前缀的前端事件
+pub const QUICK_ACTION_EVENT_PREFIX: &str = "quick-action://";
+/// 单步等待上限
+const MAX_WAIT_MS: u64 = 60_000;
+
+pub const DUMP_AND_INSPECT_ACTION: &str = "dump-and-inspect";
+pub const EMERGENCY_STOP_ACTION: &str = "emergency-stop";
+
+static STORE: OnceLock<Mutex<QuickActionStore>> = OnceLock::new();
+/// 独占动作的分发锁，保证多命令宏不被其他宏穿插
+static DISPATCH_LOCK: OnceLock<tokio::sync::Mutex<()>> = OnceLock::new();
+
+/// 快捷动作中的单条命令（参数在注册时固定）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "command", rename_all = "camelCase")]
+pub enum QuickCommand {
+    /// dump 当前设备界面并保存
+    #[serde(rename_all = "camelCase")]
+    DumpScreen {
+        #[serde(default)]
+        take_screenshot: bool,
+    },
+    /// 向前端发事件（如打开检查器），事件体附带上一步的输出
+    EmitEvent {
+        event: String,
+        #[serde(default)]
+        payload: Value,
+    },
+    StopAgent,
+    StopCopilot,
+    /// 中止进行中的脚本执行；`force` 时同时强制终止设备上的 ADB 操作
+    CancelExecutions {
+        #[serde(default)]
+        force: bool,
+    },
+    Wait { ms: u64 },
+}
+
+impl QuickCommand {
+    pub fn name(&self) -> &'static str {
+        match self {
+            QuickCommand::DumpScreen { .. } => "dumpScreen",
+            QuickCommand::EmitEvent { .. } => "emitEvent",
+            QuickCommand::StopAgent => "stopAgent",
+            QuickCommand::StopCopilot => "stopCopilot",
+            QuickCommand::CancelExecutions { .. } => "cancelExecutions",
+            QuickCommand::Wait { .. } => "wait",
+        }
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        match self {
+            QuickCommand::EmitEvent { event, .. } if !event.starts_with(QUICK_ACTION_EVENT_PREFIX) => {
+                Err(format!("事件名必须以 {} 开头: {}", QUICK_ACTION_EVENT_PREFIX, event))
+            }
+            QuickCommand::Wait { ms } if *ms > MAX_WAIT_MS => Err(format!("等待时间不能超过 {} 毫秒", MAX_WAIT_MS)),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// 快捷动作
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QuickAction {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    pub steps: Vec<QuickCommand>,
+    /// 某步失败后继续执行后续步骤（默认遇错即停）
+    #[serde(default)]
+    pub continue_on_error: bool,
+    /// 独占执行：等待正在运行的其他独占动作结束；紧急停止等抢占型动作应关闭
+    #[serde(default = "default_exclusive")]
+    pub exclusive: bool,
+    #[serde(default)]
+    pub builtin: bool,
+}
+
+fn default_exclusive() -> bool {
+    true
+}
+
+/// 内置快捷动作
+pub fn builtin_actions() -> Vec<QuickAction> {
+    vec![
+        QuickAction {
+            id: DUMP_AND_INSPECT_ACTION.to_string(),
+            name: "抓取当前界面并打开检查器".to_string(),
+            description: "dump 当前设备界面（含截图）后通知前端打开元素检查器".to_string(),
+            steps: vec![
+                QuickCommand::DumpScreen { take_screenshot: true },
+                QuickCommand::EmitEvent { event: format!("{}open-inspector", QUICK_ACTION_EVENT_PREFIX), payload: Value::Null },
+            ],
+            continue_on_error: false,
+            exclusive: true,
+            builtin: true,
+        },
+        QuickAction {
+            id: EMERGENCY_STOP_ACTION.to_string(),
+            name: "紧急停止".to_string(),
+            description: "停止 Agent 与副驾驶，并强制中止所有脚本执行".to_string(),
+            steps: vec![
+                QuickCommand::StopAgent,
+                QuickCommand::StopCopilot,
+                QuickCommand::CancelExecutions { force: true },
+            ],
+            continue_on_error: true,
+            exclusive: false,
+            builtin: true,
+        },
+    ]
+}
+
+/// 快捷键绑定；`hotkey` 为规范化后的组合键，如 `Ctrl+Shift+D`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QuickActionBinding {
+    pub hotkey: String,
+    pub action_id: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct UserQuickActions {
+    #[serde(default)]
+    actions: Vec<QuickAction>,
+    #[serde(default)]
+    bindings: Vec<QuickActionBinding>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct StoreFile {
+    #[serde(default)]
+    users: BTreeMap<String, UserQuickActions>,
+}
+
+fn normalize_user(user_id: Option<&str>) -> String {
+    user_id.map(str::trim).filter(|u| !u.is_empty()).unwrap_or(DEFAULT_USER).to_string()
+}
+
+/// 规范化组合键：修饰键按 Ctrl / Alt / Shift / Meta 排序，主键大写；无修饰键时只允许 F1–F24 与 Escape
+pub fn normalize_hotkey(hotkey: &str) -> Result<String, String> {
+    const MODIFIERS: [&str; 4] = ["Ctrl", "Alt", "Shift", "Meta"];
+    let mut modifiers = [false; 4];
+    let mut key: Option<String> = None;
+    for token in hotkey.split('+').map(str::trim) {
+        let modifier = match token.to_lowercase().as_str() {
+            "ctrl" | "control" => Some(0),
+            "alt" | "option" => Some(1),
+            "shift" => Some(2),
+            "meta" | "cmd" | "command" | "super" | "win" => Some(3),
+            _ => None,
+        };
+        match modifier {
+            Some(index) => modifiers[index] = true,
+            None if token.is_empty() => return Err(format!("快捷键格式无效: {}", hotkey)),
+            None if key.is_some() => return Err(format!("快捷键只能包含一个主键: {}", hotkey)),
+            None => {
+                let mut chars = token.chars();
+                let first = chars.next().unwrap_or_default().to_uppercase().to_string();
+                key = Some(first + &chars.as_str().to_lowercase());
+            }
+        }
+    }
+    let key = key.ok_or_else(|| format!("快捷键缺少主键: {}", hotkey))?;
+    let is_function_key = key.strip_prefix('F').and_then(|n| n.parse::<u8>().ok()).is_some_and(|n| (1..=24).contains(&n));
+    if !modifiers.iter().any(|m| *m) && !is_function_key && key != "Escape" {
+        return Err(format!("快捷键需要至少一个修饰键: {}", hotkey));
+    }
+    let mut parts: Vec<&str> = MODIFIERS.iter().zip(modifiers).filter(|(_, on)| *on).map(|(name, _)| *name).collect();
+    parts.push(&key);
+    Ok(parts.join("+"))
+}
+
+fn validate_action(action: &QuickAction) -> Result<(), String> {
+    let id_ok = !action.id.is_empty()
+        && action.id.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || matches!(c, '-' | '_' | '.'));
+    if !id_ok {
+        return Err(format!("动作 ID 只能包含小写字母、数字和 -_. : {}", action.id));
+    }
+    if builtin_actions().iter().any(|b| b.id == action.id) {
+        return Err(format!("不能覆盖内置动作: {}", action.id));
+    }
+    if action.name.trim().is_empty() {
+        return Err("动作名称不能为空".to_string());
+    }
+    if action.steps.is_empty() {
+        return Err("动作至少需要一个步骤".to_string());
+    }
+    action.steps.iter().try_for_each(QuickCommand::validate)
+}
+
+/// 按用户保存的自定义动作与快捷键绑定
+pub struct QuickActionStore {
+    path: PathBuf,
+    data: StoreFile,
+}
+
+impl QuickActionStore {
+    pub fn global() -> &'static Mutex<QuickActionStore> {
+        STORE.get_or_init(|| {
+            let path = dirs::data_dir().unwrap_or_else(|| PathBuf::from(".")).join("employee-gui").join(STORE_FILE_NAME);
+            Mutex::new(Self::open(path))
+        })
+    }
+
+    pub fn open(path: PathBuf) -> Self {
+        let data = std::fs::read_to_string(&path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+        Self { path, data }
+    }
+
+    fn save(&self) -> Result<(), String> {
+        if let Some(dir) = self.path.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+        }
+        let content = serde_json::to_string_pretty(&self.data).map_err(|e| e.to_string())?;
+        std::fs::write(&self.path, content).map_err(|e| format!("保存快捷动作失败: {}", e))
+    }
+
+    fn user(&self, user_id: Option<&str>) -> Option<&UserQuickActions> {
+        self.data.users.get(&normalize_user(user_id))
+    }
+
+    fn user_mut(&mut self, user_id: Option<&str>) -> &mut UserQuickActions {
+        self.data.users.entry(normalize_user(user_id)).or_default()
+    }
+
+    /// 内置动作 + 该用户的自定义动作
+    pub fn actions(&self, user_id: Option<&str>) -> Vec<QuickAction> {
+        let mut actions = builtin_actions();
+        actions.extend(self.user(user_id).map(|u| u.actions.clone()).unwrap_or_default());
+        actions
+    }
+
+    pub fn find_action(&self, user_id: Option<&str>, action_id: &str) -> Option<QuickAction> {
+        self.actions(user_id).into_iter().find(|a| a.id == action_id)
+    }
+
+    /// 新增或更新自定义动作
+    pub fn save_action(&mut self, user_id: Option<&str>, mut action: QuickAction) -> Result<QuickAction, String> {
+        action.id = action.id.trim().to_string();
+        action.builtin = false;
+        validate_action(&action)?;
+        let actions = &mut self.user_mut(user_id).actions;
+        match actions.iter_mut().find(|a| a.id == action.id) {
+            Some(existing) => *existing = action.clone(),
+            None => actions.push(action.clone()),
+        }
+        self.save()?;
+        Ok(action)
+    }
+
+    /// 删除自定义动作，返回是否存在；仍被快捷键绑定时拒绝删除
+    pub fn delete_action(&mut self, user_id: Option<&str>, action_id: &str) -> Result<bool, String> {
+        let user = self.user_mut(user_id);
+        if let Some(binding) = user.bindings.iter().find(|b| b.action_id == action_id) {
+            return Err(format!("动作仍绑定在快捷键 {} 上，请先解除绑定", binding.hotkey));
+        }
+        let before = user.actions.len();
+        user.actions.retain(|a| a.id != action_id);
+        if user.actions.len() == before {
+            return Ok(false);
+        }
+        self.save()?;
+        Ok(true)
+    }
+
+    pub fn bindings(&self, user_id: Option<&str>) -> Vec<QuickActionBinding> {
+        self.user(user_id).map(|u| u.bindings.clone()).unwrap_or_default()
+    }
+
+    /// 绑定快捷键；同一组合键已有绑定时覆盖
+    pub fn bind(&mut self, user_id: Option<&str>, hotkey: &str, action_id: &str) -> Result<QuickActionBinding, String> {
+        let hotkey = normalize_hotkey(hotkey)?;
+        if self.find_action(user_id, action_id).is_none() {
+            return Err(format!("快捷动作不存在: {}", action_id));
+        }
+        let binding = QuickActionBinding { hotkey, action_id: action_id.to_string() };
+        let bindings = &mut self.user_mut(user_id).bindings;
+        match bindings.iter_mut().find(|b| b.hotkey == binding.hotkey) {
+            Some(existing) => *existing = binding.clone(),
+            None => bindings.push(binding.clone()),
+        }
+        self.save()?;
+        Ok(binding)
+    }
+
+    /// 解除快捷键绑定，返回是否存在
+    pub fn unbind(&mut self, user_id: Option<&str>, hotkey: &str) -> Result<bool, String> {
+        let hotkey = normalize_hotkey(hotkey)?;
+        let bindings = &mut self.user_mut(user_id).bindings;
+        let before = bindings.len();
+        bindings.retain(|b| b.hotkey != hotkey);
+        if bindings.len() == before {
+            return Ok(false);
+        }
+        self.save()?;
+        Ok(true)
+    }
+}
+
+/// 分发时由前端提供的上下文
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct QuickActionContext {
+    /// 当前选中的设备；dump 等设备相关命令需要
+    pub device_id: Option<String>,
+}
+
+/// 执行单条命令（等待由分发器自身处理）
+#[async_trait]
+pub trait QuickCommandRunner: Send + Sync {
+    async fn run(&self, command: &QuickCommand, ctx: &QuickActionContext, previous: Option<&Value>) -> Result<Value, String>;
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QuickStepOutcome {
+    pub index: usize,
+    pub command: String,
+    pub success: bool,
+    pub output: Option<Value>,
+    pub error: Option<String>,
+    pub duration_ms: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QuickActionOutcome {
+    pub action_id: String,
+    pub success: bool,
+    pub steps: Vec<QuickStepOutcome>,
+    /// 因前面步骤失败而未执行的步骤数
+    pub skipped_steps: usize,
+    pub duration_ms: u64,
+}
+
+/// 按序执行动作的全部步骤；独占动作在分发锁内执行，默认遇错即停
+pub async fn dispatch(action: &QuickAction, ctx: &QuickActionContext, runner: &dyn QuickCommandRunner) -> QuickActionOutcome {
+    let _guard = match action.exclusive {
+        true => Some(DISPATCH_LOCK.get_or_init(|| tokio::sync::Mutex::new(())).lock().await),
+        false => None,
+    };
+    let started = Instant::now();
+    let mut steps = Vec::with_capacity(action.steps.len());
+    let mut previous: Option<Value> = None;
+    for (index, command) in action.steps.iter().enumerate() {
+        let step_started = Instant::now();
+        let result = match command {
+            QuickCommand::Wait { ms } => {
+                tokio::time::sleep(std::time::Duration::from_millis((*ms).min(MAX_WAIT_MS))).await;
+                Ok(Value::Null)
+            }
+            _ => runner.run(command, ctx, previous.as_ref()).await,
+        };
+        let (output, error) = match result {
+            Ok(output) => (Some(output), None),
+            Err(error) => (None, Some(error)),
+        };
+        let failed = error.is_some();
+        if let Some(output) = &output {
+            previous = Some(output.clone());
+        }
+        steps.push(QuickStepOutcome {
+            index,
+            command: command.name().to_string(),
+            success: !failed,
+            output,
+            error,
+            duration_ms: step_started.elapsed().as_millis() as u64,
+        });
+        if failed && !action.continue_on_error {
+            break;
+        }
+    }
+    QuickActionOutcome {
+        action_id: action.id.clone(),
+        success: steps.iter().all(|s| s.success),
+        skipped_steps: action.steps.len() - steps.len(),
+        steps,
+        duration_ms: started.elapsed().as_millis() as u64,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    struct FakeRunner;
+
+    #[async_trait]
+    impl QuickCommandRunner for FakeRunner {
+        async fn run(&self, command: &QuickCommand, ctx: &QuickActionContext, previous: Option<&Value>) -> Result<Value, String> {
+            match command {
+                QuickCommand::DumpScreen { .. } => {
+                    ctx.device_id.as_ref().map(|d| json!({ "device": d })).ok_or_else(|| "no device".to_string())
+                }
+                QuickCommand::EmitEvent { .. } => Ok(json!({ "previous": previous })),
+                _ => Ok(Value::Null),
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_bindings_per_user_and_dispatch() {
+        assert_eq!(normalize_hotkey(" shift + ctrl + d ").unwrap(), "Ctrl+Shift+D");
+        assert_eq!(normalize_hotkey("f5").unwrap(), "F5");
+        assert!(normalize_hotkey("d").is_err());
+        assert!(normalize_hotkey("Ctrl+A+B").is_err());
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(STORE_FILE_NAME);
+        let mut store = QuickActionStore::open(path.clone());
+        let custom = QuickAction {
+            id: "wait-then-stop".into(),
+            name: "等待后停止".into(),
+            description: String::new(),
+            steps: vec![QuickCommand::Wait { ms: 1 }, QuickCommand::StopAgent],
+            continue_on_error: false,
+            exclusive: true,
+            builtin: false,
+        };
+        store.save_action(Some("alice"), custom).unwrap();
+        store.bind(Some("alice"), "ctrl+shift+d", DUMP_AND_INSPECT_ACTION).unwrap();
+        store.bind(Some("alice"), "Ctrl+Shift+D", "wait-then-stop").unwrap();
+        assert!(store.bind(Some("bob"), "Ctrl+K", "wait-then-stop").is_err(), "自定义动作按用户隔离");
+        assert!(store.delete_action(Some("alice"), "wait-then-stop").is_err());
+        assert!(store.save_action(None, QuickAction { id: EMERGENCY_STOP_ACTION.into(), ..builtin_actions()[1].clone() }).is_err());
+
+        let reopened = QuickActionStore::open(path);
+        assert_eq!(reopened.bindings(Some("alice")), vec![QuickActionBinding { hotkey: "Ctrl+Shift+D".into(), action_id: "wait-then-stop".into() }]);
+        assert!(reopened.bindings(None).is_empty());
+
+        let inspect = reopened.find_action(Some("alice"), DUMP_AND_INSPECT_ACTION).unwrap();
+        let ctx = QuickActionContext { device_id: Some("emulator-5554".into()) };
+        let outcome = dispatch(&inspect, &ctx, &FakeRunner).await;
+        assert!(outcome.success);
+        assert_eq!(outcome.steps[1].output, Some(json!({ "previous": { "device": "emulator-5554" } })));
+
+        let failed = dispatch(&inspect, &QuickActionContext::default(), &FakeRunner).await;
+        assert!(!failed.success);
+        assert_eq!((failed.steps.len(), failed.skipped_steps), (1, 1));
+
+        let json = serde_json::to_value(&builtin_actions()[0].steps[0]).unwrap();
+        assert_eq!(json, json!({ "command": "dumpScreen", "takeScreenshot": true }));
+    }
+}
@@ -0,0 +1,55 @@
+// src-tauri/src/services/quick_actions/runner.rs
+// module: quick_actions | layer: services | role: 快捷动作命令执行
+// summary: 通过 AppHandle 取各插件的托管状态执行快捷动作命令：UI Dump、前端事件、停止 Agent / 副驾驶、中止脚本执行
+
+use super::{QuickActionContext, QuickCommand, QuickCommandRunner};
+use crate::modules::agent_runtime::AgentRuntimeState;
+use crate::modules::ui_dump::UiDumpState;
+use crate::services::execution_abort_service::{cancel_current_operation, force_stop_all_adb_operations};
+use async_trait::async_trait;
+use serde_json::{json, Value};
+use tauri::{AppHandle, Emitter, Manager, Runtime};
+
+pub struct TauriQuickCommandRunner<R: Runtime> {
+    app: AppHandle<R>,
+}
+
+impl<R: Runtime> TauriQuickCommandRunner<R> {
+    pub fn new(app: AppHandle<R>) -> Self {
+        Self { app }
+    }
+
+    fn agent_runtime(&self) -> Result<tauri::State<'_, AgentRuntimeState>, String> {
+        self.app.try_state::<AgentRuntimeState>().ok_or_else(|| "Agent 运行时插件未初始化".to_string())
+    }
+}
+
+#[async_trait]
+impl<R: Runtime> QuickCommandRunner for TauriQuickCommandRunner<R> {
+    async fn run(&self, command: &QuickCommand, ctx: &QuickActionContext, previous: Option<&Value>) -> Result<Value, String> {
+        match command {
+            QuickCommand::DumpScreen { take_screenshot } => {
+                let device_id = ctx.device_id.as_deref().ok_or_else(|| "该快捷动作需要先选择设备".to_string())?;
+                let state = self.app.try_state::<UiDumpState>().ok_or_else(|| "UI Dump 插件未初始化".to_string())?;
+                let result = state.provider.dump_and_save(device_id, None, *take_screenshot).await.map_err(|e| e.to_string())?;
+                serde_json::to_value(result).map_err(|e| e.to_string())
+            }
+            QuickCommand::EmitEvent { event, payload } => {
+                let body = json!({ "payload": payload, "previous": previous, "deviceId": ctx.device_id });
+                self.app.emit(event, body).map_err(|e| e.to_string())?;
+                Ok(Value::Null)
+            }
+            QuickCommand::StopAgent => {
+                self.agent_runtime()?.stop_agent().await;
+                Ok(Value::Null)
+            }
+            QuickCommand::StopCopilot => {
+                self.agent_runtime()?.stop_copilot();
+                Ok(Value::Null)
+            }
+            QuickCommand::CancelExecutions { force: true } => force_stop_all_adb_operations().await.map(Value::String),
+            QuickCommand::CancelExecutions { force: false } => cancel_current_operation().await.map(Value::String),
+            QuickCommand::Wait { .. } => Ok(Value::Null),
+        }
+    }
+}
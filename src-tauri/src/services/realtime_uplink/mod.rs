@@ -0,0 +1,485 @@
+// src-tauri/src/services/realtime_uplink/mod.rs
+// module: realtime_uplink | layer: services | role: 实时看板上行通道
+// summary: 通过 WebSocket 把事件总线中的业务事件（任务进度 / 动作计数 / 设备状态）推送给 lead-server 看板 - 令牌认证、心跳、断线退避重连，按服务端下发的订阅条件过滤
+
+use chrono::{DateTime, Utc};
+use futures::{SinkExt, StreamExt};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::PathBuf;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use tokio::sync::broadcast::{self, error::RecvError};
+use tokio::sync::Notify;
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+use tokio_tungstenite::tungstenite::http::header::AUTHORIZATION;
+use tokio_tungstenite::tungstenite::Message;
+use tracing::{info, warn};
+
+use crate::core::shared::event_bus::{self, AppEvent};
+use crate::infra::lead_server_client::LeadServerClient;
+
+/// 配置文件名
+const CONFIG_FILE_NAME: &str = "realtime_uplink.json";
+/// 服务端桌面端通道路径
+const UPLINK_PATH: &str = "/api/realtime/desktop";
+/// 连续多少个心跳周期没有收到应答视为连接失效
+const MISSED_HEARTBEATS: u32 = 3;
+
+static CONFIG: OnceLock<Mutex<UplinkConfigStore>> = OnceLock::new();
+static STATUS: OnceLock<Mutex<UplinkStatus>> = OnceLock::new();
+static CHANGED: OnceLock<Notify> = OnceLock::new();
+
+/// 上行主题
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Topic {
+    JobProgress,
+    ActionCount,
+    DeviceStatus,
+}
+
+impl Topic {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Topic::JobProgress => "job_progress",
+            Topic::ActionCount => "action_count",
+            Topic::DeviceStatus => "device_status",
+        }
+    }
+}
+
+/// 通道配置
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UplinkConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default = "default_heartbeat_secs")]
+    pub heartbeat_secs: u64,
+    /// 动作计数的汇总上报间隔
+    #[serde(default = "default_action_flush_secs")]
+    pub action_flush_secs: u64,
+    #[serde(default = "default_reconnect_initial_ms")]
+    pub reconnect_initial_ms: u64,
+    #[serde(default = "default_reconnect_max_ms")]
+    pub reconnect_max_ms: u64,
+}
+
+fn default_heartbeat_secs() -> u64 {
+    20
+}
+
+fn default_action_flush_secs() -> u64 {
+    5
+}
+
+fn default_reconnect_initial_ms() -> u64 {
+    1000
+}
+
+fn default_reconnect_max_ms() -> u64 {
+    60_000
+}
+
+impl Default for UplinkConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            heartbeat_secs: default_heartbeat_secs(),
+            action_flush_secs: default_action_flush_secs(),
+            reconnect_initial_ms: default_reconnect_initial_ms(),
+            reconnect_max_ms: default_reconnect_max_ms(),
+        }
+    }
+}
+
+impl UplinkConfig {
+    /// 第 `attempt` 次重连前的等待时间（1 起，指数增长）
+    pub fn reconnect_delay(&self, attempt: u32) -> Duration {
+        let factor = 1u64 << attempt.saturating_sub(1).min(16);
+        Duration::from_millis(self.reconnect_initial_ms.saturating_mul(factor).min(self.reconnect_max_ms))
+    }
+}
+
+struct UplinkConfigStore {
+    path: PathBuf,
+    config: UplinkConfig,
+}
+
+fn config_store() -> &'static Mutex<UplinkConfigStore> {
+    CONFIG.get_or_init(|| {
+        let path = dirs::data_dir().unwrap_or_else(|| PathBuf::from(".")).join("employee-gui").join(CONFIG_FILE_NAME);
+        let config = std::fs::read_to_string(&path).ok().and_then(|content| serde_json::from_str(&content).ok()).unwrap_or_default();
+        Mutex::new(UplinkConfigStore { path, config })
+    })
+}
+
+fn changed() -> &'static Notify {
+    CHANGED.get_or_init(Notify::new)
+}
+
+pub fn config() -> UplinkConfig {
+    config_store().lock().map(|store| store.config.clone()).unwrap_or_default()
+}
+
+/// 保存配置并唤醒通道（开启 / 关闭立即生效）
+pub fn set_config(config: UplinkConfig) -> Result<(), String> {
+    let mut store = config_store().lock().map_err(|e| e.to_string())?;
+    if let Some(dir) = store.path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    }
+    let content = serde_json::to_string_pretty(&config).map_err(|e| e.to_string())?;
+    std::fs::write(&store.path, content).map_err(|e| format!("保存实时通道配置失败: {}", e))?;
+    store.config = config;
+    changed().notify_one();
+    Ok(())
+}
+
+/// 服务端下发的订阅条件（看板订阅的并集）
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubscriptionFilter {
+    #[serde(default)]
+    pub topics: BTreeSet<String>,
+    /// None 表示全部设备
+    #[serde(default)]
+    pub device_ids: Option<BTreeSet<String>>,
+}
+
+impl SubscriptionFilter {
+    pub fn matches(&self, topic: Topic, device_id: Option<&str>) -> bool {
+        if !self.topics.contains(topic.as_str()) {
+            return false;
+        }
+        match (&self.device_ids, device_id) {
+            (None, _) | (Some(_), None) => true,
+            (Some(ids), Some(id)) => ids.contains(id),
+        }
+    }
+}
+
+/// 通道状态
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UplinkStatus {
+    pub enabled: bool,
+    pub connected: bool,
+    pub connected_at: Option<DateTime<Utc>>,
+    pub last_heartbeat_ack_at: Option<DateTime<Utc>>,
+    pub reconnect_attempts: u32,
+    pub events_sent: u64,
+    /// 未被任何看板订阅而未发送的事件
+    pub events_filtered: u64,
+    pub filter: SubscriptionFilter,
+    pub last_error: Option<String>,
+}
+
+fn update_status(f: impl FnOnce(&mut UplinkStatus)) {
+    if let Ok(mut status) = STATUS.get_or_init(|| Mutex::new(UplinkStatus::default())).lock() {
+        f(&mut status);
+    }
+}
+
+pub fn status() -> UplinkStatus {
+    let mut status = STATUS.get_or_init(|| Mutex::new(UplinkStatus::default())).lock().map(|s| s.clone()).unwrap_or_default();
+    status.enabled = config().enabled;
+    status
+}
+
+/// 桌面端 → 服务端
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+enum UplinkMessage<'a> {
+    #[serde(rename_all = "camelCase")]
+    Hello { client_id: &'a str, device_ids: &'a [String] },
+    #[serde(rename_all = "camelCase")]
+    Event { topic: &'static str, device_id: Option<&'a str>, payload: &'a Value, ts: i64 },
+    Heartbeat { ts: i64 },
+}
+
+/// 服务端 → 桌面端
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+enum ServerCommand {
+    Subscribe(SubscriptionFilter),
+    HeartbeatAck {
+        #[allow(dead_code)]
+        ts: i64,
+    },
+    #[serde(other)]
+    Unknown,
+}
+
+/// 待上行的事件
+#[derive(Debug, Clone, PartialEq)]
+pub struct UplinkEvent {
+    pub topic: Topic,
+    pub device_id: Option<String>,
+    pub payload: Value,
+}
+
+/// 事件总线 → 上行事件；动作逐条累计，按汇总间隔输出计数
+#[derive(Debug, Default)]
+pub struct EventMapper {
+    /// (设备, 动作) → (总数, 成功数)，上次汇总之后的增量
+    action_counts: BTreeMap<(String, String), (u64, u64)>,
+    online: Vec<String>,
+}
+
+impl EventMapper {
+    pub fn online(&self) -> &[String] {
+        &self.online
+    }
+
+    pub fn map(&mut self, event: AppEvent) -> Vec<UplinkEvent> {
+        let status = |device_id: &str, status: &str, extra: Value| {
+            let mut payload = json!({ "status": status });
+            if let (Some(payload), Value::Object(extra)) = (payload.as_object_mut(), extra) {
+                payload.extend(extra);
+            }
+            UplinkEvent { topic: Topic::DeviceStatus, device_id: Some(device_id.to_string()), payload }
+        };
+        match event {
+            AppEvent::JobProgress { job_id, kind, device_id, completed, total, finished } => vec![UplinkEvent {
+                topic: Topic::JobProgress,
+                device_id,
+                payload: json!({ "jobId": job_id, "kind": kind, "completed": completed, "total": total, "finished": finished }),
+            }],
+            AppEvent::ActionExecuted { action, device_id, success } => {
+                let entry = self.action_counts.entry((device_id, action)).or_default();
+                entry.0 += 1;
+                if success {
+                    entry.1 += 1;
+                }
+                Vec::new()
+            }
+            AppEvent::DevicesOnline { device_ids } => {
+                let mut events: Vec<UplinkEvent> = device_ids
+                    .iter()
+                    .filter(|id| !self.online.contains(id))
+                    .map(|id| status(id, "online", Value::Null))
+                    .collect();
+                events.extend(self.online.iter().filter(|id| !device_ids.contains(id)).map(|id| status(id, "offline", Value::Null)));
+                self.online = device_ids;
+                events
+            }
+            AppEvent::AccountRestricted { account_id, device_id, reason } => {
+                vec![status(&device_id, "restricted", json!({ "accountId": account_id, "reason": reason }))]
+            }
+            AppEvent::ContactsImported { .. } => Vec::new(),
+        }
+    }
+
+    /// 取出汇总间隔内的动作计数
+    pub fn drain_action_counts(&mut self) -> Vec<UplinkEvent> {
+        std::mem::take(&mut self.action_counts)
+            .into_iter()
+            .map(|((device_id, action), (total, success))| UplinkEvent {
+                topic: Topic::ActionCount,
+                device_id: Some(device_id),
+                payload: json!({ "action": action, "total": total, "success": success }),
+            })
+            .collect()
+    }
+
+    /// 当前在线设备快照（看板刚订阅设备状态时补发）
+    pub fn snapshot(&self) -> Vec<UplinkEvent> {
+        self.online
+            .iter()
+            .map(|id| UplinkEvent { topic: Topic::DeviceStatus, device_id: Some(id.clone()), payload: json!({ "status": "online" }) })
+            .collect()
+    }
+}
+
+/// `http://host:port` → `ws://host:port/api/realtime/desktop`
+pub fn websocket_url(base_url: &str) -> Result<String, String> {
+    let base = base_url.trim_end_matches('/');
+    let rest = if let Some(rest) = base.strip_prefix("https://") {
+        format!("wss://{}", rest)
+    } else if let Some(rest) = base.strip_prefix("http://") {
+        format!("ws://{}", rest)
+    } else {
+        return Err(format!("无法识别的服务器地址: {}", base_url));
+    };
+    Ok(format!("{}{}", rest, UPLINK_PATH))
+}
+
+type WsSink = futures::stream::SplitSink<
+    tokio_tungstenite::WebSocketStream<tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>>,
+    Message,
+>;
+
+async fn send(sink: &mut WsSink, message: &UplinkMessage<'_>) -> Result<(), String> {
+    let text = serde_json::to_string(message).map_err(|e| e.to_string())?;
+    sink.send(Message::Text(text)).await.map_err(|e| format!("发送失败: {}", e))
+}
+
+async fn send_events(sink: &mut WsSink, filter: &SubscriptionFilter, events: Vec<UplinkEvent>) -> Result<(), String> {
+    let ts = Utc::now().timestamp_millis();
+    for event in events {
+        if !filter.matches(event.topic, event.device_id.as_deref()) {
+            update_status(|s| s.events_filtered += 1);
+            continue;
+        }
+        let message = UplinkMessage::Event { topic: event.topic.as_str(), device_id: event.device_id.as_deref(), payload: &event.payload, ts };
+        send(sink, &message).await?;
+        update_status(|s| s.events_sent += 1);
+    }
+    Ok(())
+}
+
+/// 一次连接的生命周期；返回 Ok 表示主动关闭（通道被关闭或事件总线结束）
+async fn session(config: &UplinkConfig, events: &mut broadcast::Receiver<AppEvent>, mapper: &mut EventMapper) -> Result<(), String> {
+    let client = LeadServerClient::global();
+    let token = client.access_token().await.ok_or("未登录 lead-server，无法建立实时通道")?;
+    let mut request = websocket_url(client.base_url())?.into_client_request().map_err(|e| e.to_string())?;
+    let bearer = format!("Bearer {}", token).parse().map_err(|_| "登录令牌格式无效".to_string())?;
+    request.headers_mut().insert(AUTHORIZATION, bearer);
+    let (socket, _) = tokio_tungstenite::connect_async(request).await.map_err(|e| format!("连接实时通道失败: {}", e))?;
+    let (mut sink, mut stream) = socket.split();
+
+    let client_id = crate::modules::cloud_sync::get_device_id().map_err(|e| e.to_string())?;
+    send(&mut sink, &UplinkMessage::Hello { client_id: &client_id, device_ids: mapper.online() }).await?;
+    info!("📡 实时看板通道已连接: {}", client.base_url());
+    update_status(|s| {
+        s.connected = true;
+        s.connected_at = Some(Utc::now());
+        s.reconnect_attempts = 0;
+        s.last_error = None;
+    });
+
+    let heartbeat_period = Duration::from_secs(config.heartbeat_secs.max(1));
+    let mut heartbeat = tokio::time::interval(heartbeat_period);
+    let mut flush = tokio::time::interval(Duration::from_secs(config.action_flush_secs.max(1)));
+    let mut filter = SubscriptionFilter::default();
+    let mut last_ack = Instant::now();
+    loop {
+        tokio::select! {
+            received = events.recv() => match received {
+                Ok(event) => {
+                    let mapped = mapper.map(event);
+                    send_events(&mut sink, &filter, mapped).await?;
+                }
+                Err(RecvError::Lagged(skipped)) => warn!("⚠️ 实时通道处理滞后，丢弃 {} 条事件", skipped),
+                Err(RecvError::Closed) => return Ok(()),
+            },
+            message = stream.next() => match message {
+                Some(Ok(Message::Text(text))) => match serde_json::from_str::<ServerCommand>(&text) {
+                    Ok(ServerCommand::Subscribe(next)) => {
+                        let newly_watching = next.topics.contains(Topic::DeviceStatus.as_str())
+                            && !filter.topics.contains(Topic::DeviceStatus.as_str());
+                        filter = next;
+                        update_status(|s| s.filter = filter.clone());
+                        if newly_watching {
+                            send_events(&mut sink, &filter, mapper.snapshot()).await?;
+                        }
+                    }
+                    Ok(ServerCommand::HeartbeatAck { .. }) => {
+                        last_ack = Instant::now();
+                        update_status(|s| s.last_heartbeat_ack_at = Some(Utc::now()));
+                    }
+                    Ok(ServerCommand::Unknown) => {}
+                    Err(e) => warn!("⚠️ 无法解析实时通道消息: {}", e),
+                },
+                Some(Ok(Message::Close(_))) | None => return Err("服务器关闭了实时通道".to_string()),
+                Some(Ok(_)) => {}
+                Some(Err(e)) => return Err(format!("实时通道读取失败: {}", e)),
+            },
+            _ = heartbeat.tick() => {
+                if last_ack.elapsed() > heartbeat_period * MISSED_HEARTBEATS {
+                    return Err("心跳超时".to_string());
+                }
+                send(&mut sink, &UplinkMessage::Heartbeat { ts: Utc::now().timestamp_millis() }).await?;
+            }
+            _ = flush.tick() => {
+                let counts = mapper.drain_action_counts();
+                send_events(&mut sink, &filter, counts).await?;
+            }
+            _ = changed().notified() => {
+                if !self::config().enabled {
+                    let _ = sink.send(Message::Close(None)).await;
+                    return Ok(());
+                }
+            }
+        }
+    }
+}
+
+/// 后台任务：按配置维持实时通道，断线后指数退避重连（应用启动时 spawn）
+pub async fn run() {
+    let mut events = event_bus::subscribe();
+    let mut mapper = EventMapper::default();
+    let mut attempt = 0u32;
+    loop {
+        let config = config();
+        if !config.enabled {
+            // 未开启时继续跟踪在线设备，开启后 hello 与快照才准确
+            tokio::select! {
+                received = events.recv() => match received {
+                    Ok(event) => {
+                        mapper.map(event);
+                        mapper.drain_action_counts();
+                    }
+                    Err(RecvError::Lagged(_)) => {}
+                    Err(RecvError::Closed) => break,
+                },
+                _ = changed().notified() => {}
+            }
+            continue;
+        }
+
+        let result = session(&config, &mut events, &mut mapper).await;
+        update_status(|s| s.connected = false);
+        match result {
+            Ok(()) => {
+                attempt = 0;
+                continue;
+            }
+            Err(e) => {
+                attempt += 1;
+                warn!("⚠️ 实时看板通道断开（第 {} 次）: {}", attempt, e);
+                update_status(|s| {
+                    s.reconnect_attempts = attempt;
+                    s.last_error = Some(e);
+                });
+            }
+        }
+        tokio::select! {
+            _ = tokio::time::sleep(config.reconnect_delay(attempt)) => {}
+            _ = changed().notified() => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn maps_bus_events_and_applies_server_filter() {
+        let mut mapper = EventMapper::default();
+        let online = mapper.map(AppEvent::DevicesOnline { device_ids: vec!["d1".into(), "d2".into()] });
+        assert_eq!(online.len(), 2);
+        let changed = mapper.map(AppEvent::DevicesOnline { device_ids: vec!["d2".into()] });
+        assert_eq!(changed, vec![UplinkEvent { topic: Topic::DeviceStatus, device_id: Some("d1".into()), payload: json!({ "status": "offline" }) }]);
+
+        for success in [true, false, true] {
+            assert!(mapper.map(AppEvent::ActionExecuted { action: "follow".into(), device_id: "d2".into(), success }).is_empty());
+        }
+        let counts = mapper.drain_action_counts();
+        assert_eq!(counts[0].payload, json!({ "action": "follow", "total": 3, "success": 2 }));
+        assert!(mapper.drain_action_counts().is_empty());
+
+        let filter = SubscriptionFilter { topics: ["action_count".to_string()].into(), device_ids: Some(["d1".to_string()].into()) };
+        assert!(!filter.matches(Topic::ActionCount, Some("d2")));
+        assert!(!filter.matches(Topic::JobProgress, Some("d1")));
+        let command: ServerCommand = serde_json::from_str(r#"{"type":"subscribe","topics":["action_count"],"deviceIds":["d1"]}"#).unwrap();
+        assert!(matches!(command, ServerCommand::Subscribe(f) if f == filter));
+
+        assert_eq!(websocket_url("https://leads.example.com/").unwrap(), "wss://leads.example.com/api/realtime/desktop");
+        let config = UplinkConfig::default();
+        assert_eq!((config.reconnect_delay(1), config.reconnect_delay(10)), (Duration::from_secs(1), Duration::from_secs(60)));
+    }
+}
@@ -0,0 +1,810 @@
+// src-tauri/src/services/recipes/mod.rs
+// module: recipes | layer: services | role: 自动化配方
+// summary: 把 文件夹导入 → 按设备组分配 → 生成 VCF → 执行导入脚本 → 启动关注活动 串成可保存的配方，阶段参数校验、按周定时，执行记录逐阶段落盘检查点以便续跑，失败 / 取消时可逆序执行各阶段的补偿回滚
+
+pub mod runner;
+
+use chrono::{DateTime, Datelike, Local, NaiveDate, NaiveTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::PathBuf;
+use std::sync::{Mutex, OnceLock};
+
+use crate::services::contact_storage::repositories::contact_numbers::allocation_planner::DeviceAllocationSpec;
+
+pub use runner::{enqueue, rollback_run, run_scheduler, run_worker, RollbackOutcome, StageContext, StageExecutor, RECIPE_RUN_EVENT};
+
+const STATE_FILE_NAME: &str = "recipes.json";
+/// 保留的执行记录条数（不含排队 / 运行中的记录）
+const MAX_FINISHED_RUNS: usize = 100;
+
+static STORE: OnceLock<Mutex<RecipeStore>> = OnceLock::new();
+
+/// 配方阶段（按顺序执行，后面的阶段读取前面阶段的输出）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum RecipeStage {
+    /// 导入文件夹下的 TXT / vCard（沿用文件夹续导会话）
+    ImportFolder {
+        folder_path: String,
+        #[serde(default)]
+        restart: bool,
+    },
+    /// 按设备组的容量 / 行业偏好分配可用号码，每台设备一个批次
+    AllocateNumbers {
+        devices: Vec<DeviceAllocationSpec>,
+        #[serde(default)]
+        limit: Option<i64>,
+        #[serde(default)]
+        strict_affinity: bool,
+    },
+    /// 为分配出的每个批次生成 VCF 文件
+    GenerateVcf {
+        /// 为空时写到应用数据目录
+        #[serde(default)]
+        output_dir: Option<String>,
+    },
+    /// 在各设备上执行多品牌 VCF 导入脚本
+    RunImportScript {
+        /// 只执行这些设备；为空表示全部
+        #[serde(default)]
+        devices: Vec<String>,
+    },
+    /// 为导入的号码创建关注任务（设备 → 执行账号）
+    StartFollowCampaign {
+        campaign_id: String,
+        accounts: BTreeMap<String, String>,
+        #[serde(default)]
+        priority: Option<i32>,
+    },
+}
+
+impl RecipeStage {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            RecipeStage::ImportFolder { .. } => "import_folder",
+            RecipeStage::AllocateNumbers { .. } => "allocate_numbers",
+            RecipeStage::GenerateVcf { .. } => "generate_vcf",
+            RecipeStage::RunImportScript { .. } => "run_import_script",
+            RecipeStage::StartFollowCampaign { .. } => "start_follow_campaign",
+        }
+    }
+}
+
+/// 按周定时：每逢 `weekdays`（1 = 周一 … 7 = 周日）的 `at` 之后执行一次
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RecipeSchedule {
+    pub weekdays: Vec<u32>,
+    /// HH:MM（本地时间）
+    pub at: String,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl RecipeSchedule {
+    fn time(&self) -> Option<NaiveTime> {
+        NaiveTime::parse_from_str(self.at.trim(), "%H:%M").ok()
+    }
+
+    /// 当前时刻是否到点（同一天只触发一次）
+    pub fn due(&self, now: DateTime<Local>, last_run: Option<NaiveDate>) -> bool {
+        let Some(at) = self.time() else { return false };
+        self.enabled
+            && self.weekdays.contains(&now.weekday().number_from_monday())
+            && now.time() >= at
+            && last_run != Some(now.date_naive())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Recipe {
+    /// 为空时保存会生成
+    #[serde(default)]
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    pub stages: Vec<RecipeStage>,
+    #[serde(default)]
+    pub schedule: Option<RecipeSchedule>,
+    /// 失败 / 取消后自动逆序回滚已执行的阶段
+    #[serde(default)]
+    pub rollback_on_failure: bool,
+    #[serde(default)]
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+/// 校验问题；`stage` 为空表示配方级问题
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RecipeIssue {
+    pub stage: Option<usize>,
+    pub message: String,
+}
+
+fn issue(stage: Option<usize>, message: impl Into<String>) -> RecipeIssue {
+    RecipeIssue { stage, message: message.into() }
+}
+
+/// 校验阶段参数与前后依赖
+pub fn validate(recipe: &Recipe) -> Vec<RecipeIssue> {
+    let mut issues = Vec::new();
+    if recipe.name.trim().is_empty() {
+        issues.push(issue(None, "配方名称不能为空"));
+    }
+    if recipe.stages.is_empty() {
+        issues.push(issue(None, "至少需要一个阶段"));
+    }
+    if let Some(schedule) = &recipe.schedule {
+        if schedule.time().is_none() {
+            issues.push(issue(None, format!("定时时间格式应为 HH:MM: {}", schedule.at)));
+        }
+        if schedule.weekdays.is_empty() || schedule.weekdays.iter().any(|d| !(1..=7).contains(d)) {
+            issues.push(issue(None, "定时星期应为 1-7 且不能为空"));
+        }
+    }
+
+    let mut allocated_devices: Option<HashSet<&str>> = None;
+    let mut has_vcf = false;
+    for (index, stage) in recipe.stages.iter().enumerate() {
+        let at = Some(index);
+        match stage {
+            RecipeStage::ImportFolder { folder_path, .. } => {
+                if folder_path.trim().is_empty() {
+                    issues.push(issue(at, "导入文件夹路径不能为空"));
+                }
+            }
+            RecipeStage::AllocateNumbers { devices, limit, .. } => {
+                if devices.is_empty() {
+                    issues.push(issue(at, "设备组至少需要一台设备"));
+                }
+                let mut seen = HashSet::new();
+                for device in devices {
+                    if device.capacity <= 0 {
+                        issues.push(issue(at, format!("设备 {} 的容量必须大于 0", device.device_id)));
+                    }
+                    if !seen.insert(device.device_id.as_str()) {
+                        issues.push(issue(at, format!("设备 {} 重复", device.device_id)));
+                    }
+                }
+                if limit.is_some_and(|l| l <= 0) {
+                    issues.push(issue(at, "分配总数上限必须大于 0"));
+                }
+                allocated_devices = Some(seen);
+            }
+            RecipeStage::GenerateVcf { .. } => {
+                if allocated_devices.is_none() {
+                    issues.push(issue(at, "生成 VCF 前需要先有分配号码阶段"));
+                }
+                has_vcf = true;
+            }
+            RecipeStage::RunImportScript { devices } => {
+                if !has_vcf {
+                    issues.push(issue(at, "执行导入脚本前需要先有生成 VCF 阶段"));
+                }
+                if let Some(allocated) = &allocated_devices {
+                    for device in devices.iter().filter(|d| !allocated.contains(d.as_str())) {
+                        issues.push(issue(at, format!("设备 {} 不在分配的设备组中", device)));
+                    }
+                }
+            }
+            RecipeStage::StartFollowCampaign { campaign_id, accounts, .. } => {
+                if campaign_id.trim().is_empty() {
+                    issues.push(issue(at, "关注活动 ID 不能为空"));
+                }
+                match &allocated_devices {
+                    None => issues.push(issue(at, "启动关注活动前需要先有分配号码阶段")),
+                    Some(allocated) => {
+                        let mut missing: Vec<&&str> = allocated.iter().filter(|d| !accounts.contains_key(**d)).collect();
+                        missing.sort();
+                        for device in missing {
+                            issues.push(issue(at, format!("设备 {} 未指定执行账号", device)));
+                        }
+                    }
+                }
+            }
+        }
+    }
+    issues
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RunStatus {
+    Queued,
+    Running,
+    Completed,
+    Failed,
+    Cancelled,
+    RollingBack,
+    RolledBack,
+}
+
+impl RunStatus {
+    pub fn is_finished(self) -> bool {
+        matches!(self, RunStatus::Completed | RunStatus::Failed | RunStatus::Cancelled | RunStatus::RolledBack)
+    }
+
+    /// 失败 / 取消（含回滚未完成）的执行可以回滚
+    pub fn can_roll_back(self) -> bool {
+        matches!(self, RunStatus::Failed | RunStatus::Cancelled)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RollbackStepStatus {
+    /// 已执行补偿动作
+    RolledBack,
+    /// 该阶段没有回滚处理或无需回滚
+    Skipped,
+    Failed,
+}
+
+/// 单个阶段的回滚结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RollbackStep {
+    pub stage_index: usize,
+    pub kind: String,
+    pub status: RollbackStepStatus,
+    #[serde(default)]
+    pub detail: Option<Value>,
+    #[serde(default)]
+    pub message: Option<String>,
+}
+
+/// 回滚报告（按执行的逆序排列）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RollbackReport {
+    pub steps: Vec<RollbackStep>,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: Option<DateTime<Utc>>,
+}
+
+impl RollbackReport {
+    pub fn succeeded(&self) -> bool {
+        self.steps.iter().all(|s| s.status != RollbackStepStatus::Failed)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StageStatus {
+    Pending,
+    Running,
+    Completed,
+    Failed,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RunTrigger {
+    Manual,
+    Scheduled,
+}
+
+/// 阶段检查点：完成后的输出供后续阶段与续跑使用；失败时保留已完成部分
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StageCheckpoint {
+    pub kind: String,
+    pub status: StageStatus,
+    #[serde(default)]
+    pub output: Option<Value>,
+    #[serde(default)]
+    pub error: Option<String>,
+    #[serde(default)]
+    pub attempts: u32,
+    pub started_at: Option<DateTime<Utc>>,
+    pub finished_at: Option<DateTime<Utc>>,
+}
+
+/// 一次配方执行；`stages` 为入队时的配方快照，编辑配方不影响续跑
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecipeRun {
+    pub id: String,
+    pub recipe_id: String,
+    pub recipe_name: String,
+    pub trigger: RunTrigger,
+    pub status: RunStatus,
+    pub stages: Vec<RecipeStage>,
+    pub checkpoints: Vec<StageCheckpoint>,
+    #[serde(default)]
+    pub cancel_requested: bool,
+    #[serde(default)]
+    pub rollback_on_failure: bool,
+    /// 最近一次回滚报告
+    #[serde(default)]
+    pub rollback: Option<RollbackReport>,
+    #[serde(default)]
+    pub error: Option<String>,
+    pub queued_at: DateTime<Utc>,
+    pub finished_at: Option<DateTime<Utc>>,
+}
+
+impl RecipeRun {
+    fn new(recipe: &Recipe, trigger: RunTrigger) -> Self {
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            recipe_id: recipe.id.clone(),
+            recipe_name: recipe.name.clone(),
+            trigger,
+            status: RunStatus::Queued,
+            stages: recipe.stages.clone(),
+            checkpoints: recipe
+                .stages
+                .iter()
+                .map(|stage| StageCheckpoint {
+                    kind: stage.kind().to_string(),
+                    status: StageStatus::Pending,
+                    output: None,
+                    error: None,
+                    attempts: 0,
+                    started_at: None,
+                    finished_at: None,
+                })
+                .collect(),
+            cancel_requested: false,
+            rollback_on_failure: recipe.rollback_on_failure,
+            rollback: None,
+            error: None,
+            queued_at: Utc::now(),
+            finished_at: None,
+        }
+    }
+
+    /// 第一个未完成的阶段
+    pub fn next_stage(&self) -> Option<usize> {
+        self.checkpoints.iter().position(|c| c.status != StageStatus::Completed)
+    }
+
+    pub fn completed_stages(&self) -> usize {
+        self.checkpoints.iter().filter(|c| c.status == StageStatus::Completed).count()
+    }
+
+    /// 需要回滚的阶段（已完成或失败过的），按逆序
+    pub fn rollback_candidates(&self) -> Vec<usize> {
+        (0..self.checkpoints.len())
+            .rev()
+            .filter(|&i| matches!(self.checkpoints[i].status, StageStatus::Completed | StageStatus::Failed))
+            .collect()
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct RecipeState {
+    recipes: Vec<Recipe>,
+    runs: Vec<RecipeRun>,
+    /// 配方 → 最近一次定时触发的日期
+    last_scheduled: HashMap<String, NaiveDate>,
+}
+
+pub struct RecipeStore {
+    path: PathBuf,
+    state: RecipeState,
+}
+
+impl RecipeStore {
+    pub fn global() -> &'static Mutex<RecipeStore> {
+        STORE.get_or_init(|| {
+            let path = dirs::data_dir().unwrap_or_else(|| PathBuf::from(".")).join("employee-gui").join(STATE_FILE_NAME);
+            Mutex::new(Self::open(path))
+        })
+    }
+
+    /// 加载时把上次退出前仍在运行的记录标为失败，可手动续跑
+    pub fn open(path: PathBuf) -> Self {
+        let mut state: RecipeState =
+            std::fs::read_to_string(&path).ok().and_then(|content| serde_json::from_str(&content).ok()).unwrap_or_default();
+        for run in state.runs.iter_mut().filter(|r| matches!(r.status, RunStatus::Running | RunStatus::RollingBack)) {
+            run.status = RunStatus::Failed;
+            run.error = Some("应用退出时中断".to_string());
+            for checkpoint in run.checkpoints.iter_mut().filter(|c| c.status == StageStatus::Running) {
+                checkpoint.status = StageStatus::Failed;
+                checkpoint.error = Some("应用退出时中断".to_string());
+            }
+        }
+        Self { path, state }
+    }
+
+    fn save(&self) -> Result<(), String> {
+        if let Some(dir) = self.path.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+        }
+        let content = serde_json::to_string_pretty(&self.state).map_err(|e| e.to_string())?;
+        std::fs::write(&self.path, content).map_err(|e| format!("保存配方失败: {}", e))
+    }
+
+    pub fn list(&self) -> Vec<Recipe> {
+        self.state.recipes.clone()
+    }
+
+    pub fn get(&self, id: &str) -> Option<&Recipe> {
+        self.state.recipes.iter().find(|r| r.id == id)
+    }
+
+    /// 新建或更新；校验不通过时拒绝保存
+    pub fn save_recipe(&mut self, mut recipe: Recipe) -> Result<Recipe, String> {
+        let issues = validate(&recipe);
+        if !issues.is_empty() {
+            let messages: Vec<String> = issues
+                .iter()
+                .map(|i| match i.stage {
+                    Some(stage) => format!("阶段 {}: {}", stage + 1, i.message),
+                    None => i.message.clone(),
+                })
+                .collect();
+            return Err(format!("配方校验失败: {}", messages.join("；")));
+        }
+        if recipe.id.is_empty() {
+            recipe.id = uuid::Uuid::new_v4().to_string();
+        }
+        recipe.updated_at = Some(Utc::now());
+        match self.state.recipes.iter_mut().find(|r| r.id == recipe.id) {
+            Some(existing) => *existing = recipe.clone(),
+            None => self.state.recipes.push(recipe.clone()),
+        }
+        self.save()?;
+        Ok(recipe)
+    }
+
+    pub fn delete_recipe(&mut self, id: &str) -> Result<bool, String> {
+        let before = self.state.recipes.len();
+        self.state.recipes.retain(|r| r.id != id);
+        self.state.last_scheduled.remove(id);
+        let removed = self.state.recipes.len() != before;
+        if removed {
+            self.save()?;
+        }
+        Ok(removed)
+    }
+
+    pub fn runs(&self, recipe_id: Option<&str>) -> Vec<RecipeRun> {
+        self.state.runs.iter().filter(|r| recipe_id.map_or(true, |id| r.recipe_id == id)).cloned().collect()
+    }
+
+    pub fn run(&self, run_id: &str) -> Option<&RecipeRun> {
+        self.state.runs.iter().find(|r| r.id == run_id)
+    }
+
+    fn run_mut(&mut self, run_id: &str) -> Result<&mut RecipeRun, String> {
+        self.state.runs.iter_mut().find(|r| r.id == run_id).ok_or_else(|| format!("执行记录不存在: {}", run_id))
+    }
+
+    /// 入队一次执行；同一配方已有排队 / 运行中的记录时拒绝
+    pub fn queue_run(&mut self, recipe_id: &str, trigger: RunTrigger) -> Result<RecipeRun, String> {
+        let recipe = self.get(recipe_id).cloned().ok_or_else(|| format!("配方不存在: {}", recipe_id))?;
+        if self.state.runs.iter().any(|r| r.recipe_id == recipe_id && !r.status.is_finished()) {
+            return Err(format!("配方 {} 已在队列中", recipe.name));
+        }
+        let run = RecipeRun::new(&recipe, trigger);
+        self.state.runs.push(run.clone());
+        self.prune_runs();
+        self.save()?;
+        Ok(run)
+    }
+
+    /// 失败 / 取消的执行从第一个未完成阶段重新排队；已完成阶段的检查点保留
+    pub fn resume_run(&mut self, run_id: &str) -> Result<RecipeRun, String> {
+        let recipe_id = self.run_mut(run_id)?.recipe_id.clone();
+        if self.state.runs.iter().any(|r| r.recipe_id == recipe_id && r.id != run_id && !r.status.is_finished()) {
+            return Err("该配方已有其他执行在队列中".to_string());
+        }
+        let run = self.run_mut(run_id)?;
+        if !matches!(run.status, RunStatus::Failed | RunStatus::Cancelled | RunStatus::RolledBack) {
+            return Err("只有失败、已取消或已回滚的执行可以续跑".to_string());
+        }
+        run.status = RunStatus::Queued;
+        run.cancel_requested = false;
+        run.error = None;
+        run.finished_at = None;
+        let run = run.clone();
+        self.save()?;
+        Ok(run)
+    }
+
+    /// 排队中的直接取消；运行中的在当前阶段结束后停止
+    pub fn cancel_run(&mut self, run_id: &str) -> Result<RecipeRun, String> {
+        let run = self.run_mut(run_id)?;
+        match run.status {
+            RunStatus::Queued => {
+                run.status = RunStatus::Cancelled;
+                run.finished_at = Some(Utc::now());
+            }
+            RunStatus::Running => run.cancel_requested = true,
+            _ => return Err("执行已结束".to_string()),
+        }
+        let run = run.clone();
+        self.save()?;
+        Ok(run)
+    }
+
+    /// 取出最早排队的执行并标为运行中
+    fn start_next(&mut self) -> Result<Option<RecipeRun>, String> {
+        let Some(run) = self.state.runs.iter_mut().find(|r| r.status == RunStatus::Queued) else {
+            return Ok(None);
+        };
+        run.status = RunStatus::Running;
+        let run = run.clone();
+        self.save()?;
+        Ok(Some(run))
+    }
+
+    fn begin_stage(&mut self, run_id: &str, index: usize) -> Result<RecipeRun, String> {
+        let run = self.run_mut(run_id)?;
+        let checkpoint = run.checkpoints.get_mut(index).ok_or("阶段不存在")?;
+        checkpoint.status = StageStatus::Running;
+        checkpoint.error = None;
+        checkpoint.attempts += 1;
+        checkpoint.started_at = Some(Utc::now());
+        checkpoint.finished_at = None;
+        let run = run.clone();
+        self.save()?;
+        Ok(run)
+    }
+
+    /// 记录阶段结果（失败时保留部分输出，续跑时交回执行器跳过已完成部分）
+    fn finish_stage(&mut self, run_id: &str, index: usize, result: Result<Value, StageFailure>) -> Result<RecipeRun, String> {
+        let run = self.run_mut(run_id)?;
+        let checkpoint = run.checkpoints.get_mut(index).ok_or("阶段不存在")?;
+        checkpoint.finished_at = Some(Utc::now());
+        match result {
+            Ok(output) => {
+                checkpoint.status = StageStatus::Completed;
+                checkpoint.output = Some(output);
+            }
+            Err(failure) => {
+                checkpoint.status = StageStatus::Failed;
+                checkpoint.error = Some(failure.message.clone());
+                if failure.partial.is_some() {
+                    checkpoint.output = failure.partial;
+                }
+                run.status = RunStatus::Failed;
+                run.error = Some(failure.message);
+                run.finished_at = Some(Utc::now());
+            }
+        }
+        let run = run.clone();
+        self.save()?;
+        Ok(run)
+    }
+
+    /// 全部阶段完成 / 取消请求生效时结束执行
+    fn settle_run(&mut self, run_id: &str) -> Result<RecipeRun, String> {
+        let run = self.run_mut(run_id)?;
+        if run.status == RunStatus::Running {
+            if run.next_stage().is_none() {
+                run.status = RunStatus::Completed;
+            } else if run.cancel_requested {
+                run.status = RunStatus::Cancelled;
+            }
+            if run.status.is_finished() {
+                run.finished_at = Some(Utc::now());
+            }
+        }
+        let run = run.clone();
+        self.save()?;
+        Ok(run)
+    }
+
+    /// 标记开始回滚；只允许失败 / 取消的执行
+    fn begin_rollback(&mut self, run_id: &str) -> Result<RecipeRun, String> {
+        let run = self.run_mut(run_id)?;
+        if !run.status.can_roll_back() {
+            return Err("只有失败或已取消的执行可以回滚".to_string());
+        }
+        run.status = RunStatus::RollingBack;
+        run.rollback = Some(RollbackReport { steps: Vec::new(), started_at: Utc::now(), finished_at: None });
+        let run = run.clone();
+        self.save()?;
+        Ok(run)
+    }
+
+    fn record_rollback_step(&mut self, run_id: &str, step: RollbackStep) -> Result<RecipeRun, String> {
+        let run = self.run_mut(run_id)?;
+        // 已补偿的阶段回到待执行，续跑时重新执行
+        if step.status == RollbackStepStatus::RolledBack {
+            if let Some(checkpoint) = run.checkpoints.get_mut(step.stage_index) {
+                checkpoint.status = StageStatus::Pending;
+                checkpoint.output = None;
+                checkpoint.error = None;
+            }
+        }
+        if let Some(report) = run.rollback.as_mut() {
+            report.steps.push(step);
+        }
+        let run = run.clone();
+        self.save()?;
+        Ok(run)
+    }
+
+    /// 全部阶段回滚成功 → 已回滚；有失败 → 回到失败状态，可再次回滚
+    fn finish_rollback(&mut self, run_id: &str) -> Result<RecipeRun, String> {
+        let run = self.run_mut(run_id)?;
+        let succeeded = run.rollback.as_ref().map_or(true, RollbackReport::succeeded);
+        if let Some(report) = run.rollback.as_mut() {
+            report.finished_at = Some(Utc::now());
+        }
+        run.status = if succeeded { RunStatus::RolledBack } else { RunStatus::Failed };
+        if !succeeded {
+            run.error = Some("回滚未全部完成".to_string());
+        }
+        run.finished_at = Some(Utc::now());
+        let run = run.clone();
+        self.save()?;
+        Ok(run)
+    }
+
+    fn prune_runs(&mut self) {
+        let finished = self.state.runs.iter().filter(|r| r.status.is_finished()).count();
+        let mut excess = finished.saturating_sub(MAX_FINISHED_RUNS);
+        self.state.runs.retain(|r| {
+            if excess > 0 && r.status.is_finished() {
+                excess -= 1;
+                return false;
+            }
+            true
+        });
+    }
+
+    /// 到点的定时配方（同时记录触发日期）
+    fn take_due(&mut self, now: DateTime<Local>) -> Result<Vec<String>, String> {
+        let due: Vec<String> = self
+            .state
+            .recipes
+            .iter()
+            .filter(|r| r.schedule.as_ref().is_some_and(|s| s.due(now, self.state.last_scheduled.get(&r.id).copied())))
+            .map(|r| r.id.clone())
+            .collect();
+        if !due.is_empty() {
+            for id in &due {
+                self.state.last_scheduled.insert(id.clone(), now.date_naive());
+            }
+            self.save()?;
+        }
+        Ok(due)
+    }
+}
+
+/// 阶段失败：错误信息 + 已完成部分（如已导入的设备）
+#[derive(Debug, Clone)]
+pub struct StageFailure {
+    pub message: String,
+    pub partial: Option<Value>,
+}
+
+impl From<String> for StageFailure {
+    fn from(message: String) -> Self {
+        Self { message, partial: None }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn spec(device: &str) -> DeviceAllocationSpec {
+        DeviceAllocationSpec { device_id: device.into(), capacity: 100, industries: Vec::new() }
+    }
+
+    fn weekly_recipe() -> Recipe {
+        Recipe {
+            id: String::new(),
+            name: "周一导入".into(),
+            description: None,
+            stages: vec![
+                RecipeStage::ImportFolder { folder_path: "/data/numbers".into(), restart: false },
+                RecipeStage::AllocateNumbers { devices: vec![spec("d1"), spec("d2")], limit: None, strict_affinity: false },
+                RecipeStage::GenerateVcf { output_dir: None },
+                RecipeStage::RunImportScript { devices: Vec::new() },
+                RecipeStage::StartFollowCampaign {
+                    campaign_id: "c1".into(),
+                    accounts: BTreeMap::from([("d1".into(), "acc1".into()), ("d2".into(), "acc2".into())]),
+                    priority: None,
+                },
+            ],
+            schedule: Some(RecipeSchedule { weekdays: vec![1], at: "09:00".into(), enabled: true }),
+            rollback_on_failure: false,
+            updated_at: None,
+        }
+    }
+
+    #[test]
+    fn validates_stage_parameters_and_dependencies() {
+        assert!(validate(&weekly_recipe()).is_empty());
+
+        let mut recipe = weekly_recipe();
+        recipe.stages.remove(1);
+        let issues = validate(&recipe);
+        assert!(issues.iter().any(|i| i.stage == Some(1) && i.message.contains("分配号码")));
+        assert!(issues.iter().any(|i| i.stage == Some(3) && i.message.contains("分配号码")));
+
+        let mut recipe = weekly_recipe();
+        if let RecipeStage::StartFollowCampaign { accounts, .. } = &mut recipe.stages[4] {
+            accounts.remove("d2");
+        }
+        assert_eq!(validate(&recipe), vec![issue(Some(4), "设备 d2 未指定执行账号")]);
+    }
+
+    #[test]
+    fn weekly_schedule_fires_once_per_day() {
+        let schedule = RecipeSchedule { weekdays: vec![1], at: "09:00".into(), enabled: true };
+        // 2026-10-12 是周一
+        let monday = Local.with_ymd_and_hms(2026, 10, 12, 9, 30, 0).unwrap();
+        assert!(schedule.due(monday, None));
+        assert!(!schedule.due(monday, Some(monday.date_naive())));
+        assert!(!schedule.due(Local.with_ymd_and_hms(2026, 10, 12, 8, 0, 0).unwrap(), None));
+        assert!(!schedule.due(Local.with_ymd_and_hms(2026, 10, 13, 9, 30, 0).unwrap(), None));
+    }
+
+    #[test]
+    fn checkpoints_allow_resuming_from_failed_stage() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut store = RecipeStore::open(dir.path().join(STATE_FILE_NAME));
+        let recipe = store.save_recipe(weekly_recipe()).unwrap();
+        let run = store.queue_run(&recipe.id, RunTrigger::Manual).unwrap();
+        assert!(store.queue_run(&recipe.id, RunTrigger::Manual).is_err());
+
+        let started = store.start_next().unwrap().unwrap();
+        assert_eq!(started.id, run.id);
+        store.begin_stage(&run.id, 0).unwrap();
+        store.finish_stage(&run.id, 0, Ok(serde_json::json!({ "inserted": 3 }))).unwrap();
+        store.begin_stage(&run.id, 1).unwrap();
+        let failed = store.finish_stage(&run.id, 1, Err(StageFailure::from("设备离线".to_string()))).unwrap();
+        assert_eq!((failed.status, failed.next_stage()), (RunStatus::Failed, Some(1)));
+
+        // 重新打开后续跑：已完成阶段保留，从失败阶段继续
+        let mut store = RecipeStore::open(dir.path().join(STATE_FILE_NAME));
+        let resumed = store.resume_run(&run.id).unwrap();
+        assert_eq!(resumed.status, RunStatus::Queued);
+        assert_eq!(resumed.completed_stages(), 1);
+        assert_eq!(resumed.checkpoints[0].output, Some(serde_json::json!({ "inserted": 3 })));
+    }
+
+    #[test]
+    fn rollback_resets_compensated_stages_in_reverse_order() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut store = RecipeStore::open(dir.path().join(STATE_FILE_NAME));
+        let recipe = store.save_recipe(weekly_recipe()).unwrap();
+        let run = store.queue_run(&recipe.id, RunTrigger::Manual).unwrap();
+        store.start_next().unwrap();
+        for index in 0..2 {
+            store.begin_stage(&run.id, index).unwrap();
+            store.finish_stage(&run.id, index, Ok(serde_json::json!({}))).unwrap();
+        }
+        store.begin_stage(&run.id, 2).unwrap();
+        let failed = store.finish_stage(&run.id, 2, Err(StageFailure::from("VCF 失败".to_string()))).unwrap();
+        assert_eq!(failed.rollback_candidates(), vec![2, 1, 0]);
+
+        store.begin_rollback(&run.id).unwrap();
+        assert!(store.begin_rollback(&run.id).is_err());
+        let step = |stage_index: usize, status| RollbackStep {
+            stage_index,
+            kind: String::new(),
+            status,
+            detail: None,
+            message: None,
+        };
+        store.record_rollback_step(&run.id, step(2, RollbackStepStatus::RolledBack)).unwrap();
+        store.record_rollback_step(&run.id, step(1, RollbackStepStatus::RolledBack)).unwrap();
+        store.record_rollback_step(&run.id, step(0, RollbackStepStatus::Skipped)).unwrap();
+        let rolled_back = store.finish_rollback(&run.id).unwrap();
+
+        assert_eq!(rolled_back.status, RunStatus::RolledBack);
+        // 文件夹导入没有回滚处理，续跑时从分配阶段重新开始
+        assert_eq!(rolled_back.next_stage(), Some(1));
+        assert_eq!(rolled_back.rollback.as_ref().unwrap().steps.len(), 3);
+        assert!(store.resume_run(&run.id).is_ok());
+    }
+}
@@ -0,0 +1,226 @@
+// src-tauri/src/services/recipes/runner.rs
+// module: recipes | layer: services | role: 配方执行队列
+// summary: 单工作协程按入队顺序执行配方，每个阶段前后落盘检查点并发布 JobProgress；阶段的具体动作由插件层实现的 StageExecutor 提供，定时器按周把到点的配方入队；失败 / 取消后可逆序调用各阶段的回滚处理并生成回滚报告
+
+use async_trait::async_trait;
+use chrono::Local;
+use serde_json::Value;
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+use tokio::sync::Notify;
+use tracing::{info, warn};
+
+use super::{RecipeRun, RecipeStage, RecipeStore, RollbackStep, RollbackStepStatus, RunStatus, RunTrigger, StageFailure};
+use crate::core::shared::event_bus::{self, AppEvent};
+
+/// 前端订阅的执行状态事件
+pub const RECIPE_RUN_EVENT: &str = "recipes://run";
+/// 定时检查间隔
+const SCHEDULER_TICK: Duration = Duration::from_secs(60);
+
+static WAKE: OnceLock<Notify> = OnceLock::new();
+
+fn wake() -> &'static Notify {
+    WAKE.get_or_init(Notify::new)
+}
+
+/// 阶段执行上下文
+#[derive(Debug, Clone)]
+pub struct StageContext {
+    pub run_id: String,
+    pub stage_index: usize,
+    /// 前面各阶段的输出（按阶段顺序）
+    pub outputs: Vec<(String, Option<Value>)>,
+    /// 本阶段上次失败时保留的部分输出，用于跳过已完成部分
+    pub partial: Option<Value>,
+}
+
+impl StageContext {
+    /// 前面最近一个指定类型阶段的输出
+    pub fn output_of(&self, kind: &str) -> Option<&Value> {
+        self.outputs.iter().rev().find(|(k, _)| k == kind).and_then(|(_, output)| output.as_ref())
+    }
+}
+
+/// 阶段回滚结果
+#[derive(Debug, Clone)]
+pub enum RollbackOutcome {
+    /// 已执行补偿动作，附带明细
+    Done(Value),
+    /// 无需 / 无法回滚，附带原因
+    Skipped(String),
+}
+
+/// 阶段执行器（由插件层实现，持有 AppHandle 等运行时资源）
+#[async_trait]
+pub trait StageExecutor: Send + Sync {
+    async fn execute(&self, stage: &RecipeStage, ctx: &StageContext) -> Result<Value, StageFailure>;
+
+    /// 撤销阶段已产生的效果；`output` 为该阶段的输出（失败阶段为部分输出）
+    async fn rollback(&self, _stage: &RecipeStage, _output: Option<&Value>, _ctx: &StageContext) -> Result<RollbackOutcome, String> {
+        Ok(RollbackOutcome::Skipped("该阶段没有回滚处理".to_string()))
+    }
+
+    /// 执行状态变化通知（默认忽略）
+    fn on_run_update(&self, _run: &RecipeRun) {}
+}
+
+fn with_store<T>(f: impl FnOnce(&mut RecipeStore) -> Result<T, String>) -> Result<T, String> {
+    let mut store = RecipeStore::global().lock().map_err(|e| e.to_string())?;
+    f(&mut store)
+}
+
+/// 一键执行：入队并唤醒工作协程
+pub fn enqueue(recipe_id: &str, trigger: RunTrigger) -> Result<RecipeRun, String> {
+    let run = with_store(|s| s.queue_run(recipe_id, trigger))?;
+    wake().notify_one();
+    Ok(run)
+}
+
+/// 续跑失败 / 取消的执行
+pub fn resume(run_id: &str) -> Result<RecipeRun, String> {
+    let run = with_store(|s| s.resume_run(run_id))?;
+    wake().notify_one();
+    Ok(run)
+}
+
+pub fn cancel(run_id: &str) -> Result<RecipeRun, String> {
+    with_store(|s| s.cancel_run(run_id))
+}
+
+fn report(executor: &dyn StageExecutor, run: &RecipeRun) {
+    event_bus::publish(AppEvent::JobProgress {
+        job_id: run.id.clone(),
+        kind: "recipe".into(),
+        device_id: None,
+        completed: run.completed_stages() as u64,
+        total: run.stages.len() as u64,
+        finished: run.status.is_finished(),
+    });
+    executor.on_run_update(run);
+}
+
+/// 从第一个未完成阶段执行到结束 / 失败 / 取消
+async fn execute_run(executor: &dyn StageExecutor, mut run: RecipeRun) -> Result<RecipeRun, String> {
+    info!("🧾 开始执行配方 {} ({})", run.recipe_name, run.id);
+    report(executor, &run);
+    while let Some(index) = run.next_stage() {
+        let cancel_requested = with_store(|s| Ok(s.run(&run.id).is_some_and(|r| r.cancel_requested)))?;
+        if cancel_requested {
+            break;
+        }
+        let previous_partial = run.checkpoints[index].output.clone();
+        run = with_store(|s| s.begin_stage(&run.id, index))?;
+        report(executor, &run);
+
+        let ctx = StageContext {
+            run_id: run.id.clone(),
+            stage_index: index,
+            outputs: run.checkpoints[..index].iter().map(|c| (c.kind.clone(), c.output.clone())).collect(),
+            partial: previous_partial,
+        };
+        let result = executor.execute(&run.stages[index], &ctx).await;
+        if let Err(failure) = &result {
+            warn!("⚠️ 配方 {} 阶段 {} ({}) 失败: {}", run.recipe_name, index + 1, run.checkpoints[index].kind, failure.message);
+        }
+        run = with_store(|s| s.finish_stage(&run.id, index, result))?;
+        report(executor, &run);
+        if run.status == RunStatus::Failed {
+            break;
+        }
+    }
+    if run.status != RunStatus::Failed {
+        run = with_store(|s| s.settle_run(&run.id))?;
+        report(executor, &run);
+    }
+    info!("🧾 配方 {} 执行结束: {:?}", run.recipe_name, run.status);
+    if run.rollback_on_failure && run.status.can_roll_back() {
+        return rollback_run(executor, &run.id).await;
+    }
+    Ok(run)
+}
+
+/// 逆序回滚失败 / 取消的执行：每个已执行阶段调用回滚处理，结果逐条写入回滚报告
+pub async fn rollback_run(executor: &dyn StageExecutor, run_id: &str) -> Result<RecipeRun, String> {
+    let mut run = with_store(|s| s.begin_rollback(run_id))?;
+    report(executor, &run);
+    info!("↩️ 开始回滚配方 {} ({})", run.recipe_name, run.id);
+    for index in run.rollback_candidates() {
+        let ctx = StageContext {
+            run_id: run.id.clone(),
+            stage_index: index,
+            outputs: run.checkpoints[..index].iter().map(|c| (c.kind.clone(), c.output.clone())).collect(),
+            partial: None,
+        };
+        let checkpoint = &run.checkpoints[index];
+        let step = match executor.rollback(&run.stages[index], checkpoint.output.as_ref(), &ctx).await {
+            Ok(RollbackOutcome::Done(detail)) => RollbackStep {
+                stage_index: index,
+                kind: checkpoint.kind.clone(),
+                status: RollbackStepStatus::RolledBack,
+                detail: Some(detail),
+                message: None,
+            },
+            Ok(RollbackOutcome::Skipped(reason)) => RollbackStep {
+                stage_index: index,
+                kind: checkpoint.kind.clone(),
+                status: RollbackStepStatus::Skipped,
+                detail: None,
+                message: Some(reason),
+            },
+            Err(e) => {
+                warn!("⚠️ 配方 {} 阶段 {} ({}) 回滚失败: {}", run.recipe_name, index + 1, checkpoint.kind, e);
+                RollbackStep {
+                    stage_index: index,
+                    kind: checkpoint.kind.clone(),
+                    status: RollbackStepStatus::Failed,
+                    detail: None,
+                    message: Some(e),
+                }
+            }
+        };
+        run = with_store(|s| s.record_rollback_step(&run.id, step))?;
+        report(executor, &run);
+    }
+    let run = with_store(|s| s.finish_rollback(&run.id))?;
+    report(executor, &run);
+    info!("↩️ 配方 {} 回滚结束: {:?}", run.recipe_name, run.status);
+    Ok(run)
+}
+
+/// 执行队列工作协程：逐个取出排队的执行
+pub async fn run_worker(executor: Arc<dyn StageExecutor>) {
+    loop {
+        match with_store(|s| s.start_next()) {
+            Ok(Some(run)) => {
+                if let Err(e) = execute_run(executor.as_ref(), run).await {
+                    warn!("⚠️ 配方执行记录更新失败: {}", e);
+                }
+            }
+            Ok(None) => wake().notified().await,
+            Err(e) => {
+                warn!("⚠️ 读取配方队列失败: {}", e);
+                tokio::time::sleep(SCHEDULER_TICK).await;
+            }
+        }
+    }
+}
+
+/// 定时器：到点的配方按计划入队
+pub async fn run_scheduler() {
+    loop {
+        tokio::time::sleep(SCHEDULER_TICK).await;
+        let due = match with_store(|s| s.take_due(Local::now())) {
+            Ok(due) => due,
+            Err(e) => {
+                warn!("⚠️ 检查定时配方失败: {}", e);
+                continue;
+            }
+        };
+        for recipe_id in due {
+            if let Err(e) = enqueue(&recipe_id, RunTrigger::Scheduled) {
+                warn!("⚠️ 定时配方 {} 入队失败: {}", recipe_id, e);
+            }
+        }
+    }
+}
@@ -0,0 +1,137 @@
+// src-tauri/src/services/report_export/format.rs
+// module: report_export | layer: services | role: 单元格格式规则
+// summary: 按列格式把 JSON 值转换为单元格（整数 / 小数 / 百分比 / 时间 / 布尔 / 文本），统一时间格式与布尔文案，并防止 CSV 公式注入
+
+use chrono::{DateTime, Local, NaiveDateTime, TimeZone};
+use serde_json::Value;
+
+use crate::core::shared::i18n::{self, Locale};
+
+/// 统一输出的时间格式
+pub const DATETIME_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
+/// 列格式
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum CellFormat {
+    #[default]
+    Text,
+    Integer,
+    /// 保留指定位数的小数
+    Decimal(u8),
+    /// 0~1 的比例显示为百分比（一位小数）
+    Percent,
+    /// 时间戳（秒 / 毫秒）或时间字符串，统一为 `YYYY-MM-DD HH:MM:SS`
+    DateTime,
+    /// 是 / 否
+    Boolean,
+}
+
+/// 格式化后的单元格
+#[derive(Debug, Clone, PartialEq)]
+pub enum CellValue {
+    Empty,
+    Text(String),
+    /// 数值与其显示文本（CSV 写显示文本，xlsx 写数值）
+    Number(f64, String),
+}
+
+impl CellValue {
+    pub fn display(&self) -> &str {
+        match self {
+            CellValue::Empty => "",
+            CellValue::Text(text) | CellValue::Number(_, text) => text,
+        }
+    }
+}
+
+fn as_f64(value: &Value) -> Option<f64> {
+    match value {
+        Value::Number(n) => n.as_f64(),
+        Value::String(s) => s.trim().parse().ok(),
+        Value::Bool(b) => Some(if *b { 1.0 } else { 0.0 }),
+        _ => None,
+    }
+}
+
+fn as_text(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        Value::Array(items) => items.iter().map(as_text).filter(|s| !s.is_empty()).collect::<Vec<_>>().join(", "),
+        other => other.to_string(),
+    }
+}
+
+/// 时间：数字按秒 / 毫秒解析并转为本地时间；带时区的字符串转本地时间；不带时区的字符串原样规范化
+pub fn format_datetime(value: &Value) -> Option<String> {
+    match value {
+        Value::Number(n) => {
+            let raw = n.as_i64()?;
+            let millis = if raw.abs() >= 100_000_000_000 { raw } else { raw * 1000 };
+            Local.timestamp_millis_opt(millis).single().map(|t| t.format(DATETIME_FORMAT).to_string())
+        }
+        Value::String(s) => {
+            let s = s.trim();
+            if let Ok(t) = DateTime::parse_from_rfc3339(s) {
+                return Some(t.with_timezone(&Local).format(DATETIME_FORMAT).to_string());
+            }
+            let normalized = s.replace('T', " ");
+            let without_fraction = normalized.split('.').next().unwrap_or(&normalized);
+            match NaiveDateTime::parse_from_str(without_fraction, DATETIME_FORMAT) {
+                Ok(t) => Some(t.format(DATETIME_FORMAT).to_string()),
+                Err(_) => Some(s.to_string()),
+            }
+        }
+        _ => None,
+    }
+}
+
+/// 以 `= + - @` 等开头的文本会被表格软件当作公式；号码 / 负数这类纯数字内容除外
+fn guard_formula(text: String) -> String {
+    let mut chars = text.chars();
+    let risky = match chars.next() {
+        Some('=') | Some('@') | Some('\t') | Some('\r') => true,
+        Some('+') | Some('-') => !chars.as_str().chars().all(|c| c.is_ascii_digit() || c == ' ' || c == '-' || c == '.'),
+        _ => false,
+    };
+    if risky {
+        format!("'{}", text)
+    } else {
+        text
+    }
+}
+
+/// 按列格式转换单元格
+pub fn format_cell(value: &Value, format: CellFormat, locale: Locale) -> CellValue {
+    if value.is_null() || value.as_str().is_some_and(|s| s.is_empty()) {
+        return CellValue::Empty;
+    }
+    let number = |n: f64, decimals: usize| CellValue::Number(n, format!("{:.*}", decimals, n));
+    match format {
+        CellFormat::Integer => match as_f64(value) {
+            Some(n) => number(n.round(), 0),
+            None => CellValue::Text(guard_formula(as_text(value))),
+        },
+        CellFormat::Decimal(places) => match as_f64(value) {
+            Some(n) => {
+                let factor = 10f64.powi(places as i32);
+                number((n * factor).round() / factor, places as usize)
+            }
+            None => CellValue::Text(guard_formula(as_text(value))),
+        },
+        CellFormat::Percent => match as_f64(value) {
+            Some(n) => CellValue::Text(format!("{:.1}%", n * 100.0)),
+            None => CellValue::Text(guard_formula(as_text(value))),
+        },
+        CellFormat::DateTime => format_datetime(value).map(CellValue::Text).unwrap_or(CellValue::Empty),
+        CellFormat::Boolean => {
+            let truthy = match value {
+                Value::Bool(b) => *b,
+                Value::Number(n) => n.as_f64().is_some_and(|n| n != 0.0),
+                Value::String(s) => matches!(s.trim().to_ascii_lowercase().as_str(), "true" | "1" | "yes"),
+                _ => false,
+            };
+            CellValue::Text(i18n::t_in(locale, if truthy { "export.bool.yes" } else { "export.bool.no" }))
+        }
+        CellFormat::Text => CellValue::Text(guard_formula(as_text(value))),
+    }
+}
@@ -0,0 +1,460 @@
+// src-tauri/src/services/report_export/mod.rs
+// module: report_export | layer: services | role: 统一报表导出器
+// summary: 各模块共用的 CSV / Excel / JSON 导出 - 列定义 + 格式规则 + 本地化表头，逐行流式写入临时文件后原子替换，统一进度事件与输出路径规则
+
+pub mod format;
+mod xlsx;
+
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tauri::{AppHandle, Runtime};
+
+use crate::core::shared::i18n::{self, Locale};
+
+pub use format::{format_cell, CellFormat, CellValue};
+
+/// 前端订阅的导出进度事件
+pub const EXPORT_PROGRESS_EVENT: &str = "report-export://progress";
+
+/// 每写入多少行上报一次进度
+const PROGRESS_EVERY_ROWS: u64 = 500;
+
+/// 默认导出目录名（位于文档目录下）
+const DEFAULT_EXPORT_DIR: &str = "employee-gui-exports";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    Csv,
+    Xlsx,
+    Json,
+}
+
+impl ExportFormat {
+    pub fn parse(code: &str) -> Option<ExportFormat> {
+        match code.trim().to_ascii_lowercase().as_str() {
+            "csv" => Some(ExportFormat::Csv),
+            "xlsx" | "excel" => Some(ExportFormat::Xlsx),
+            "json" => Some(ExportFormat::Json),
+            _ => None,
+        }
+    }
+
+    /// 解析失败时返回本地化的"不支持的格式"错误
+    pub fn parse_or_err(code: Option<&str>, locale: Locale) -> Result<ExportFormat, String> {
+        let code = code.unwrap_or("csv");
+        Self::parse(code).ok_or_else(|| i18n::t_args(locale, "export.unsupported_format", &[("format", code)]))
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Xlsx => "xlsx",
+            ExportFormat::Json => "json",
+        }
+    }
+}
+
+/// 列定义：取值键 + 表头 + 格式
+#[derive(Debug, Clone)]
+pub struct Column {
+    pub key: String,
+    pub label: String,
+    pub format: CellFormat,
+}
+
+impl Column {
+    pub fn new(key: &str, label: impl Into<String>) -> Self {
+        Self { key: key.to_string(), label: label.into(), format: CellFormat::Text }
+    }
+
+    /// 表头取自 i18n 词条
+    pub fn localized(key: &str, label_key: &str, locale: Locale) -> Self {
+        Self::new(key, i18n::t_in(locale, label_key))
+    }
+
+    pub fn format(mut self, format: CellFormat) -> Self {
+        self.format = format;
+        self
+    }
+}
+
+/// 导出请求的公共选项
+#[derive(Debug, Clone)]
+pub struct ExportOptions {
+    pub format: ExportFormat,
+    pub locale: Locale,
+    /// 文件或目录；为空时写入默认导出目录
+    pub output_path: Option<String>,
+}
+
+impl ExportOptions {
+    /// 按命令参数构造（格式缺省为 csv）
+    pub fn from_args(format: Option<&str>, locale: Option<&str>, output_path: Option<String>) -> Result<Self, String> {
+        let locale = i18n::resolve_locale(locale);
+        Ok(Self { format: ExportFormat::parse_or_err(format, locale)?, locale, output_path })
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportProgress {
+    pub export_id: String,
+    pub kind: String,
+    pub rows_written: u64,
+    pub total_rows: Option<u64>,
+    pub finished: bool,
+    pub output_path: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportOutcome {
+    pub export_id: String,
+    pub kind: String,
+    pub format: ExportFormat,
+    pub output_path: String,
+    pub rows_written: u64,
+    pub bytes: u64,
+}
+
+/// 输出路径规则：未指定 → 默认目录；指定目录（已存在或以分隔符结尾）→ 目录下自动命名；缺扩展名 → 补齐
+pub fn resolve_output_path(requested: Option<&str>, kind: &str, format: ExportFormat) -> PathBuf {
+    let file_name = || format!("{}_{}.{}", kind, chrono::Local::now().format("%Y%m%d_%H%M%S"), format.extension());
+    let requested = requested.map(str::trim).filter(|p| !p.is_empty());
+    match requested {
+        None => default_export_dir().join(file_name()),
+        Some(raw) => {
+            let path = PathBuf::from(raw);
+            if path.is_dir() || raw.ends_with('/') || raw.ends_with('\\') {
+                path.join(file_name())
+            } else if path.extension().is_none() {
+                path.with_extension(format.extension())
+            } else {
+                path
+            }
+        }
+    }
+}
+
+fn default_export_dir() -> PathBuf {
+    dirs::document_dir()
+        .or_else(dirs::data_dir)
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join(DEFAULT_EXPORT_DIR)
+}
+
+fn io_err(e: impl std::fmt::Display) -> String {
+    format!("写入导出文件失败: {}", e)
+}
+
+fn cells(columns: &[Column], row: &Value, locale: Locale) -> Vec<CellValue> {
+    columns.iter().map(|c| format_cell(row.get(&c.key).unwrap_or(&Value::Null), c.format, locale)).collect()
+}
+
+enum Sink {
+    Csv(csv::Writer<BufWriter<File>>),
+    Xlsx(xlsx::XlsxWriter<BufWriter<File>>),
+    Json { out: BufWriter<File>, first: bool },
+}
+
+/// 流式导出器：`start_table` 声明列，`write_row` 逐行写入，`finish` 落盘并返回结果
+pub struct ReportExporter<F: FnMut(&ExportProgress)> {
+    export_id: String,
+    kind: String,
+    options: ExportOptions,
+    final_path: PathBuf,
+    partial_path: PathBuf,
+    sink: Option<Sink>,
+    columns: Vec<Column>,
+    table_title: Option<String>,
+    tables: usize,
+    rows_written: u64,
+    total_rows: Option<u64>,
+    on_progress: F,
+}
+
+impl<F: FnMut(&ExportProgress)> ReportExporter<F> {
+    pub fn create(kind: &str, options: ExportOptions, total_rows: Option<u64>, on_progress: F) -> Result<Self, String> {
+        let final_path = resolve_output_path(options.output_path.as_deref(), kind, options.format);
+        if let Some(parent) = final_path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            fs::create_dir_all(parent).map_err(|e| format!("创建导出目录失败: {}", e))?;
+        }
+        let mut partial = final_path.clone().into_os_string();
+        partial.push(".partial");
+        let partial_path = PathBuf::from(partial);
+        let file = BufWriter::new(File::create(&partial_path).map_err(io_err)?);
+        let sink = match options.format {
+            ExportFormat::Csv => {
+                let mut out = file;
+                // BOM 让 Excel 以 UTF-8 打开中文内容
+                out.write_all("\u{feff}".as_bytes()).map_err(io_err)?;
+                Sink::Csv(csv::WriterBuilder::new().flexible(true).from_writer(out))
+            }
+            ExportFormat::Xlsx => Sink::Xlsx(xlsx::XlsxWriter::new(file)),
+            ExportFormat::Json => {
+                let mut out = file;
+                out.write_all(b"[").map_err(io_err)?;
+                Sink::Json { out, first: true }
+            }
+        };
+        Ok(Self {
+            export_id: uuid::Uuid::new_v4().to_string(),
+            kind: kind.to_string(),
+            options,
+            final_path,
+            partial_path,
+            sink: Some(sink),
+            columns: Vec::new(),
+            table_title: None,
+            tables: 0,
+            rows_written: 0,
+            total_rows,
+            on_progress,
+        })
+    }
+
+    pub fn export_id(&self) -> &str {
+        &self.export_id
+    }
+
+    pub fn locale(&self) -> Locale {
+        self.options.locale
+    }
+
+    fn sink(&mut self) -> Result<&mut Sink, String> {
+        self.sink.as_mut().ok_or_else(|| "导出已结束".to_string())
+    }
+
+    /// 开始一张表（CSV 中以空行 + 标题行分隔，xlsx 中为独立工作表，JSON 行带 `table` 字段）
+    pub fn start_table(&mut self, title: Option<&str>, columns: Vec<Column>) -> Result<(), String> {
+        let first_table = self.tables == 0;
+        let labels: Vec<String> = columns.iter().map(|c| c.label.clone()).collect();
+        match self.sink()? {
+            Sink::Csv(writer) => {
+                if !first_table {
+                    // 单个空字段会被写成 `""`，空行直接写入底层
+                    writer.flush().map_err(io_err)?;
+                    writer.get_mut().write_all(b"\n").map_err(io_err)?;
+                }
+                if let Some(title) = title {
+                    writer.write_record([title]).map_err(io_err)?;
+                }
+                writer.write_record(&labels).map_err(io_err)?;
+            }
+            Sink::Xlsx(writer) => {
+                writer.start_sheet(title)?;
+                let header: Vec<CellValue> = labels.into_iter().map(CellValue::Text).collect();
+                writer.write_row(&header)?;
+            }
+            Sink::Json { .. } => {}
+        }
+        self.columns = columns;
+        self.table_title = title.map(str::to_string);
+        self.tables += 1;
+        Ok(())
+    }
+
+    pub fn write_row(&mut self, row: &Value) -> Result<(), String> {
+        let locale = self.options.locale;
+        let formatted = cells(&self.columns, row, locale);
+        let title = self.table_title.clone();
+        match self.sink()? {
+            Sink::Csv(writer) => writer.write_record(formatted.iter().map(CellValue::display)).map_err(io_err)?,
+            Sink::Xlsx(writer) => writer.write_row(&formatted)?,
+            Sink::Json { out, first } => {
+                let mut object = row.clone();
+                if let (Some(title), Value::Object(map)) = (title, &mut object) {
+                    map.insert("table".to_string(), Value::String(title));
+                }
+                if !*first {
+                    out.write_all(b",").map_err(io_err)?;
+                }
+                *first = false;
+                serde_json::to_writer(&mut *out, &object).map_err(io_err)?;
+            }
+        }
+        self.rows_written += 1;
+        if self.rows_written % PROGRESS_EVERY_ROWS == 0 {
+            self.report(false);
+        }
+        Ok(())
+    }
+
+    pub fn write_rows<'a>(&mut self, rows: impl IntoIterator<Item = &'a Value>) -> Result<(), String> {
+        rows.into_iter().try_for_each(|row| self.write_row(row))
+    }
+
+    fn report(&mut self, finished: bool) {
+        let progress = ExportProgress {
+            export_id: self.export_id.clone(),
+            kind: self.kind.clone(),
+            rows_written: self.rows_written,
+            total_rows: self.total_rows,
+            finished,
+            output_path: self.final_path.to_string_lossy().to_string(),
+        };
+        (self.on_progress)(&progress);
+    }
+
+    pub fn finish(mut self) -> Result<ExportOutcome, String> {
+        let sink = self.sink.take().ok_or_else(|| "导出已结束".to_string())?;
+        let mut out = match sink {
+            Sink::Csv(writer) => writer.into_inner().map_err(|e| io_err(e.error()))?,
+            Sink::Xlsx(writer) => writer.finish()?,
+            Sink::Json { mut out, .. } => {
+                out.write_all(b"]").map_err(io_err)?;
+                out
+            }
+        };
+        out.flush().map_err(io_err)?;
+        drop(out);
+        fs::rename(&self.partial_path, &self.final_path).map_err(io_err)?;
+        let bytes = fs::metadata(&self.final_path).map(|m| m.len()).unwrap_or(0);
+        self.report(true);
+        Ok(ExportOutcome {
+            export_id: self.export_id.clone(),
+            kind: self.kind.clone(),
+            format: self.options.format,
+            output_path: self.final_path.to_string_lossy().to_string(),
+            rows_written: self.rows_written,
+            bytes,
+        })
+    }
+}
+
+impl<F: FnMut(&ExportProgress)> Drop for ReportExporter<F> {
+    /// 中途失败时清理临时文件
+    fn drop(&mut self) {
+        if self.sink.take().is_some() {
+            let _ = fs::remove_file(&self.partial_path);
+        }
+    }
+}
+
+/// 把进度转发为前端事件
+pub fn emit_progress<R: Runtime>(app: &AppHandle<R>) -> impl FnMut(&ExportProgress) {
+    let app = app.clone();
+    move |progress: &ExportProgress| {
+        crate::services::event_outbox::emit(&app, EXPORT_PROGRESS_EVENT, &progress.export_id, progress);
+    }
+}
+
+/// 单表导出的便捷入口
+pub fn export_table<F: FnMut(&ExportProgress)>(
+    kind: &str,
+    options: ExportOptions,
+    columns: Vec<Column>,
+    rows: &[Value],
+    on_progress: F,
+) -> Result<ExportOutcome, String> {
+    let mut exporter = ReportExporter::create(kind, options, Some(rows.len() as u64), on_progress)?;
+    exporter.start_table(None, columns)?;
+    exporter.write_rows(rows)?;
+    exporter.finish()
+}
+
+/// 内存渲染（CSV 不带 BOM / JSON），供直接返回字符串的旧接口使用
+pub fn render_to_string(columns: &[Column], rows: &[Value], format: ExportFormat, locale: Locale) -> Result<String, String> {
+    match format {
+        ExportFormat::Csv => {
+            let mut writer = csv::Writer::from_writer(Vec::new());
+            writer.write_record(columns.iter().map(|c| c.label.as_str())).map_err(|e| e.to_string())?;
+            for row in rows {
+                let formatted = cells(columns, row, locale);
+                writer.write_record(formatted.iter().map(CellValue::display)).map_err(|e| e.to_string())?;
+            }
+            let bytes = writer.into_inner().map_err(|e| e.to_string())?;
+            String::from_utf8(bytes).map_err(|e| e.to_string())
+        }
+        ExportFormat::Json => serde_json::to_string_pretty(rows).map_err(|e| e.to_string()),
+        ExportFormat::Xlsx => Err(i18n::t_args(locale, "export.unsupported_format", &[("format", "xlsx")])),
+    }
+}
+
+/// 文件是否为 zip（xlsx）容器，供调用方校验
+pub fn is_zip_file(path: &Path) -> bool {
+    fs::read(path).map(|bytes| bytes.starts_with(b"PK\x03\x04")).unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn columns() -> Vec<Column> {
+        vec![
+            Column::new("name", "Name"),
+            Column::new("count", "Count").format(CellFormat::Integer),
+            Column::new("rate", "Rate").format(CellFormat::Percent),
+            Column::new("ok", "OK").format(CellFormat::Boolean),
+        ]
+    }
+
+    #[test]
+    fn formats_cells_by_column_rules() {
+        let locale = Locale::EnUs;
+        assert_eq!(format_cell(&json!(3.6), CellFormat::Integer, locale).display(), "4");
+        assert_eq!(format_cell(&json!("1.234"), CellFormat::Decimal(2), locale).display(), "1.23");
+        assert_eq!(format_cell(&json!(0.125), CellFormat::Percent, locale).display(), "12.5%");
+        assert_eq!(format_cell(&json!("=cmd()"), CellFormat::Text, locale).display(), "'=cmd()");
+        assert_eq!(format_cell(&json!("+8613800000000"), CellFormat::Text, locale).display(), "+8613800000000");
+        assert_eq!(format_cell(&json!("2024-05-01T08:09:10.123"), CellFormat::DateTime, locale).display(), "2024-05-01 08:09:10");
+        assert_eq!(format_cell(&Value::Null, CellFormat::Integer, locale), CellValue::Empty);
+    }
+
+    #[test]
+    fn renders_csv_in_memory_with_escaping() {
+        let rows = vec![json!({"name": "a,b", "count": 2, "rate": 0.5, "ok": true})];
+        let csv = render_to_string(&columns(), &rows, ExportFormat::Csv, Locale::EnUs).unwrap();
+        assert_eq!(csv, "Name,Count,Rate,OK\n\"a,b\",2,50.0%,Yes\n");
+    }
+
+    #[test]
+    fn streams_multi_table_csv_and_xlsx_to_disk() {
+        let dir = tempfile::tempdir().unwrap();
+        let rows = vec![json!({"name": "x", "count": 1}), json!({"name": "y", "count": 2})];
+
+        for format in [ExportFormat::Csv, ExportFormat::Xlsx] {
+            let options = ExportOptions {
+                format,
+                locale: Locale::EnUs,
+                output_path: Some(format!("{}/", dir.path().display())),
+            };
+            let mut finished = false;
+            let mut exporter = ReportExporter::create("demo", options, Some(4), |p: &ExportProgress| finished |= p.finished).unwrap();
+            exporter.start_table(Some("First"), columns()).unwrap();
+            exporter.write_rows(&rows).unwrap();
+            exporter.start_table(Some("Second"), columns()).unwrap();
+            exporter.write_rows(&rows).unwrap();
+            let outcome = exporter.finish().unwrap();
+
+            assert!(finished);
+            assert_eq!(outcome.rows_written, 4);
+            let path = PathBuf::from(&outcome.output_path);
+            assert_eq!(path.extension().unwrap(), format.extension());
+            assert!(!Path::new(&format!("{}.partial", outcome.output_path)).exists());
+            match format {
+                ExportFormat::Csv => {
+                    let text = fs::read_to_string(&path).unwrap();
+                    assert!(text.starts_with('\u{feff}'));
+                    assert!(text.contains("\n\nSecond\nName,Count,Rate,OK\nx,1,,\n"));
+                }
+                _ => assert!(is_zip_file(&path)),
+            }
+        }
+    }
+
+    #[test]
+    fn resolves_output_paths() {
+        let path = resolve_output_path(Some("/tmp/report"), "audit", ExportFormat::Xlsx);
+        assert_eq!(path, PathBuf::from("/tmp/report.xlsx"));
+        let path = resolve_output_path(None, "audit", ExportFormat::Csv);
+        assert!(path.to_string_lossy().contains(DEFAULT_EXPORT_DIR));
+        assert_eq!(xlsx::column_name(27), "AB");
+    }
+}
@@ -0,0 +1,178 @@
+// src-tauri/src/services/report_export/xlsx.rs
+// module: report_export | layer: services | role: xlsx 流式写入
+// summary: 不依赖第三方表格库的最小 xlsx 写入器 - 每张表一个工作表，行直接写入 zip 条目（内联字符串 + 数值单元格），结束时补齐工作簿清单
+
+use std::io::{Seek, Write};
+
+use zip::write::FileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+use super::format::CellValue;
+
+/// Excel 工作表名长度上限
+const MAX_SHEET_NAME_CHARS: usize = 31;
+
+pub struct XlsxWriter<W: Write + Seek> {
+    zip: ZipWriter<W>,
+    sheets: Vec<String>,
+    in_sheet: bool,
+    next_row: u32,
+}
+
+fn options() -> FileOptions {
+    FileOptions::default().compression_method(CompressionMethod::Deflated)
+}
+
+/// XML 转义，并去掉 XML 1.0 不允许的控制字符
+fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\t' | '\n' | '\r' => out.push(c),
+            c if (c as u32) < 0x20 => {}
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// 0 → A，25 → Z，26 → AA
+pub fn column_name(mut index: usize) -> String {
+    let mut name = Vec::new();
+    loop {
+        name.push(b'A' + (index % 26) as u8);
+        if index < 26 {
+            break;
+        }
+        index = index / 26 - 1;
+    }
+    name.reverse();
+    String::from_utf8(name).unwrap_or_default()
+}
+
+/// 工作表名：去掉非法字符、截断并保证唯一
+fn sheet_name(title: &str, existing: &[String]) -> String {
+    let cleaned: String = title
+        .chars()
+        .filter(|c| !matches!(c, '[' | ']' | ':' | '*' | '?' | '/' | '\\'))
+        .take(MAX_SHEET_NAME_CHARS)
+        .collect();
+    let base = if cleaned.trim().is_empty() { format!("Sheet{}", existing.len() + 1) } else { cleaned };
+    let mut name = base.clone();
+    let mut n = 2;
+    while existing.iter().any(|s| s.eq_ignore_ascii_case(&name)) {
+        let suffix = format!(" ({})", n);
+        let keep = MAX_SHEET_NAME_CHARS.saturating_sub(suffix.chars().count());
+        name = format!("{}{}", base.chars().take(keep).collect::<String>(), suffix);
+        n += 1;
+    }
+    name
+}
+
+fn io_err(e: impl std::fmt::Display) -> String {
+    format!("写入 xlsx 失败: {}", e)
+}
+
+impl<W: Write + Seek> XlsxWriter<W> {
+    pub fn new(inner: W) -> Self {
+        Self { zip: ZipWriter::new(inner), sheets: Vec::new(), in_sheet: false, next_row: 1 }
+    }
+
+    fn end_sheet(&mut self) -> Result<(), String> {
+        if self.in_sheet {
+            self.zip.write_all(b"</sheetData></worksheet>").map_err(io_err)?;
+            self.in_sheet = false;
+        }
+        Ok(())
+    }
+
+    pub fn start_sheet(&mut self, title: Option<&str>) -> Result<(), String> {
+        self.end_sheet()?;
+        let name = sheet_name(title.unwrap_or(""), &self.sheets);
+        self.sheets.push(name);
+        self.zip.start_file(format!("xl/worksheets/sheet{}.xml", self.sheets.len()), options()).map_err(io_err)?;
+        self.zip
+            .write_all(
+                br#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?><worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main"><sheetData>"#,
+            )
+            .map_err(io_err)?;
+        self.in_sheet = true;
+        self.next_row = 1;
+        Ok(())
+    }
+
+    pub fn write_row(&mut self, cells: &[CellValue]) -> Result<(), String> {
+        if !self.in_sheet {
+            self.start_sheet(None)?;
+        }
+        let row = self.next_row;
+        let mut xml = format!(r#"<row r="{}">"#, row);
+        for (index, cell) in cells.iter().enumerate() {
+            let reference = format!("{}{}", column_name(index), row);
+            match cell {
+                CellValue::Empty => {}
+                CellValue::Number(value, _) if value.is_finite() => {
+                    xml.push_str(&format!(r#"<c r="{}"><v>{}</v></c>"#, reference, value));
+                }
+                other => xml.push_str(&format!(
+                    r#"<c r="{}" t="inlineStr"><is><t xml:space="preserve">{}</t></is></c>"#,
+                    reference,
+                    escape(other.display())
+                )),
+            }
+        }
+        xml.push_str("</row>");
+        self.zip.write_all(xml.as_bytes()).map_err(io_err)?;
+        self.next_row += 1;
+        Ok(())
+    }
+
+    /// 写入工作簿清单并结束 zip
+    pub fn finish(mut self) -> Result<W, String> {
+        if self.sheets.is_empty() {
+            self.start_sheet(None)?;
+        }
+        self.end_sheet()?;
+
+        let mut content_types = String::from(
+            r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?><Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types"><Default Extension="rels" ContentType="application/vnd.openxmlformats-package.relationships+xml"/><Default Extension="xml" ContentType="application/xml"/><Override PartName="/xl/workbook.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.sheet.main+xml"/>"#,
+        );
+        let mut workbook = String::from(
+            r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?><workbook xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships"><sheets>"#,
+        );
+        let mut workbook_rels = String::from(
+            r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?><Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">"#,
+        );
+        for (i, name) in self.sheets.iter().enumerate() {
+            let n = i + 1;
+            content_types.push_str(&format!(
+                r#"<Override PartName="/xl/worksheets/sheet{}.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.worksheet+xml"/>"#,
+                n
+            ));
+            workbook.push_str(&format!(r#"<sheet name="{}" sheetId="{}" r:id="rId{}"/>"#, escape(name), n, n));
+            workbook_rels.push_str(&format!(
+                r#"<Relationship Id="rId{}" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet" Target="worksheets/sheet{}.xml"/>"#,
+                n, n
+            ));
+        }
+        content_types.push_str("</Types>");
+        workbook.push_str("</sheets></workbook>");
+        workbook_rels.push_str("</Relationships>");
+        let root_rels = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?><Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/officeDocument" Target="xl/workbook.xml"/></Relationships>"#;
+
+        for (path, content) in [
+            ("[Content_Types].xml", content_types.as_str()),
+            ("_rels/.rels", root_rels),
+            ("xl/workbook.xml", workbook.as_str()),
+            ("xl/_rels/workbook.xml.rels", workbook_rels.as_str()),
+        ] {
+            self.zip.start_file(path, options()).map_err(io_err)?;
+            self.zip.write_all(content.as_bytes()).map_err(io_err)?;
+        }
+        self.zip.finish().map_err(io_err)
+    }
+}
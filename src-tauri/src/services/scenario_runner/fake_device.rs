@@ -0,0 +1,151 @@
+// src-tauri/src/services/scenario_runner/fake_device.rs
+// module: services/scenario_runner | layer: infrastructure | role: fake-device-transport
+// summary: 内存假设备 - 以 AdbTransport 形式应答 push / am start / content query，支持注入失败号码
+
+use std::collections::{HashMap, HashSet};
+use std::process::{ExitStatus, Output};
+
+use anyhow::{anyhow, Result};
+use parking_lot::Mutex;
+
+use crate::services::adb::transport::AdbTransport;
+use crate::services::contact_storage::parser::normalizers::normalize_phone_number;
+
+/// 内存假设备
+///
+/// 只应答真实导入 / 验证链路会发出的 adb 命令：
+/// - `push <local> <remote>`：读取本地文件保存到内存
+/// - `shell mkdir -p <dir>`：直接成功
+/// - `shell am start -a VIEW -d file://<remote> -t text/x-vcard`：解析 VCF，每张名片的首个 TEL 写入通讯录
+/// - `shell content query ... --projection data1`：按 `Row: N data1=...` 输出通讯录号码
+///
+/// 其余命令返回错误，避免场景悄悄走到未模拟的分支。
+#[derive(Debug, Default)]
+pub struct FakeDevice {
+    serial: String,
+    /// 以规范化号码记录的导入失败号码（模拟联系人应用拒绝写入）
+    failing_numbers: HashSet<String>,
+    state: Mutex<FakeDeviceState>,
+}
+
+#[derive(Debug, Default)]
+struct FakeDeviceState {
+    files: HashMap<String, String>,
+    /// 按导入顺序保存设备上的原始号码写法
+    contacts: Vec<String>,
+}
+
+impl FakeDevice {
+    pub fn new(serial: impl Into<String>) -> Self {
+        Self {
+            serial: serial.into(),
+            ..Default::default()
+        }
+    }
+
+    /// 注入导入失败的号码
+    pub fn fail_on(mut self, numbers: &[&str]) -> Self {
+        self.failing_numbers.extend(numbers.iter().map(|n| normalize_phone_number(n)));
+        self
+    }
+
+    pub fn serial(&self) -> &str {
+        &self.serial
+    }
+
+    pub fn file_exists(&self, remote_path: &str) -> bool {
+        self.state.lock().files.contains_key(remote_path)
+    }
+
+    pub fn has_contact(&self, phone: &str) -> bool {
+        let wanted = normalize_phone_number(phone);
+        self.state.lock().contacts.iter().any(|c| normalize_phone_number(c) == wanted)
+    }
+
+    pub fn contact_count(&self) -> usize {
+        self.state.lock().contacts.len()
+    }
+
+    fn push(&self, local: &str, remote: &str) -> Result<Output> {
+        let content = std::fs::read_to_string(local).map_err(|e| anyhow!("读取本地文件失败 {}: {}", local, e))?;
+        self.state.lock().files.insert(remote.to_string(), content);
+        Ok(output(format!("{}: 1 file pushed, 0 skipped.", local), ""))
+    }
+
+    fn start_view_intent(&self, args: &[&str]) -> Result<Output> {
+        let data = flag_value(args, "-d").ok_or_else(|| anyhow!("am start 缺少 -d"))?;
+        if flag_value(args, "-t") != Some("text/x-vcard") {
+            return Ok(output("", "Error: Activity not started, unable to resolve Intent"));
+        }
+        let remote = data.trim_start_matches("file://");
+
+        let mut state = self.state.lock();
+        let Some(content) = state.files.get(remote).cloned() else {
+            return Ok(output("", format!("Error: file not found: {}", remote)));
+        };
+        for phone in content.split("BEGIN:VCARD").filter_map(first_tel) {
+            let normalized = normalize_phone_number(&phone);
+            if self.failing_numbers.contains(&normalized) {
+                continue;
+            }
+            if !state.contacts.iter().any(|c| normalize_phone_number(c) == normalized) {
+                state.contacts.push(phone);
+            }
+        }
+        Ok(output(format!("Starting: Intent {{ act=android.intent.action.VIEW dat={} typ=text/x-vcard }}", data), ""))
+    }
+
+    fn query_data1(&self) -> Result<Output> {
+        let state = self.state.lock();
+        let rows: String = state
+            .contacts
+            .iter()
+            .enumerate()
+            .map(|(i, phone)| format!("Row: {} data1={}\n", i, phone))
+            .collect();
+        Ok(output(rows, ""))
+    }
+}
+
+impl AdbTransport for FakeDevice {
+    fn execute(&self, args: &[&str]) -> Result<Output> {
+        let args = match args {
+            ["-s", serial, rest @ ..] if *serial == self.serial => rest,
+            ["-s", serial, ..] => return Err(anyhow!("device '{}' not found", serial)),
+            rest => rest,
+        };
+        match args {
+            ["push", local, remote] => self.push(local, remote),
+            ["shell", "mkdir", ..] => Ok(output("", "")),
+            ["shell", "am", "start", rest @ ..] if flag_value(rest, "-a") == Some("android.intent.action.VIEW") => {
+                self.start_view_intent(rest)
+            }
+            ["shell", "content", "query", rest @ ..] if flag_value(rest, "--projection") == Some("data1") => {
+                self.query_data1()
+            }
+            _ => Err(anyhow!("FakeDevice 未模拟的命令: {}", args.join(" "))),
+        }
+    }
+}
+
+fn output(stdout: impl Into<String>, stderr: impl Into<String>) -> Output {
+    Output {
+        status: ExitStatus::default(),
+        stdout: stdout.into().into_bytes(),
+        stderr: stderr.into().into_bytes(),
+    }
+}
+
+fn flag_value<'a>(args: &[&'a str], flag: &str) -> Option<&'a str> {
+    args.iter().position(|a| *a == flag).and_then(|i| args.get(i + 1)).copied()
+}
+
+/// 取单张名片中首个 `TEL...:` 行的号码
+fn first_tel(card: &str) -> Option<String> {
+    card.lines()
+        .map(str::trim)
+        .find(|line| line.to_ascii_uppercase().starts_with("TEL"))
+        .and_then(|line| line.split_once(':'))
+        .map(|(_, phone)| phone.trim().to_string())
+        .filter(|phone| !phone.is_empty())
+}
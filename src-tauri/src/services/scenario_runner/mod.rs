@@ -0,0 +1,310 @@
+// src-tauri/src/services/scenario_runner/mod.rs
+// module: services/scenario_runner | layer: application | role: e2e-scenario-runner
+// summary: 端到端场景回归 - 导入号码 → 生成VCF批次 → 执行导入脚本 → 对账，逐阶段断言数据库状态
+//
+// 仅在 `scenario-tests` 特性或 cargo test 下编译，使用内存 SQLite + FakeDevice（AdbTransport），不接触真实设备。
+
+pub mod fake_device;
+
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use rusqlite::{params, Connection};
+use serde::Serialize;
+
+use crate::services::contact_storage::models::ImportSessionStatus;
+use crate::services::contact_storage::repositories::common::schema::init_contact_storage_tables;
+use crate::services::contact_storage::repositories::contact_numbers::{status_management, vcf_chunk_export};
+use crate::services::contact_storage::repositories::contact_numbers_repo::ContactNumberRepository;
+use crate::services::contact_storage::repositories::import_sessions_repo::ImportSessionRepository;
+use crate::services::contact_verification::verify_numbers_with;
+use crate::services::vcf::{MultiBrandVcfImporter, VcfExportOptions};
+use fake_device::FakeDevice;
+
+/// 单条断言结果
+#[derive(Debug, Clone, Serialize)]
+pub struct AssertionResult {
+    pub description: String,
+    pub expected: String,
+    pub actual: String,
+    pub passed: bool,
+}
+
+/// 单阶段报告
+#[derive(Debug, Clone, Serialize)]
+pub struct PhaseReport {
+    pub phase: String,
+    pub duration_ms: u64,
+    pub passed: bool,
+    pub assertions: Vec<AssertionResult>,
+    pub error: Option<String>,
+}
+
+/// 场景报告
+#[derive(Debug, Clone, Serialize)]
+pub struct ScenarioReport {
+    pub name: String,
+    pub passed: bool,
+    pub phases: Vec<PhaseReport>,
+}
+
+/// 场景定义
+struct ScenarioSpec {
+    name: &'static str,
+    numbers: usize,
+    failing: &'static [&'static str],
+}
+
+const SCENARIOS: &[ScenarioSpec] = &[
+    ScenarioSpec { name: "import_vcf_reconcile", numbers: 20, failing: &[] },
+    ScenarioSpec { name: "partial_import_failure", numbers: 12, failing: &["13800000003", "13800000007"] },
+];
+
+const PHASES: [&str; 4] = ["import_numbers", "create_vcf_batch", "run_import_script", "verify_reconciliation"];
+
+/// 列出内置场景
+pub fn list_scenarios() -> Vec<&'static str> {
+    SCENARIOS.iter().map(|s| s.name).collect()
+}
+
+/// 按名称运行内置场景
+///
+/// 各阶段调用真实的仓储 / VCF 导出 / 导入器 / 验证服务，只有 ADB 通道替换为 FakeDevice。
+pub async fn run_scenario(name: &str) -> Result<ScenarioReport, String> {
+    let spec = SCENARIOS
+        .iter()
+        .find(|s| s.name == name)
+        .ok_or_else(|| format!("未知场景: {}（可用: {}）", name, list_scenarios().join(", ")))?;
+
+    let conn = Connection::open_in_memory().map_err(|e| format!("创建内存数据库失败: {}", e))?;
+    init_contact_storage_tables(&conn).map_err(|e| format!("初始化表结构失败: {}", e))?;
+
+    let work_dir = std::env::temp_dir().join(format!("scenario_{}_{}", spec.name, uuid::Uuid::new_v4().simple()));
+    let device = Arc::new(FakeDevice::new("fake-device-01").fail_on(spec.failing));
+    let mut ctx = ScenarioContext {
+        conn,
+        device,
+        spec,
+        work_dir,
+        number_ids: Vec::new(),
+        batch_id: None,
+        vcf_path: None,
+        session_id: None,
+    };
+
+    let mut phases = Vec::new();
+    for phase in PHASES {
+        let started = Instant::now();
+        let mut assertions = Vec::new();
+        let result = match phase {
+            "import_numbers" => ctx.phase_import(&mut assertions),
+            "create_vcf_batch" => ctx.phase_create_batch(&mut assertions),
+            "run_import_script" => ctx.phase_run_import(&mut assertions).await,
+            _ => ctx.phase_reconcile(&mut assertions),
+        };
+        let passed = result.is_ok() && assertions.iter().all(|a| a.passed);
+        phases.push(PhaseReport {
+            phase: phase.to_string(),
+            duration_ms: started.elapsed().as_millis() as u64,
+            passed,
+            assertions,
+            error: result.err(),
+        });
+        // 前置阶段失败时后续阶段没有意义
+        if !passed {
+            break;
+        }
+    }
+    let _ = std::fs::remove_dir_all(&ctx.work_dir);
+
+    let passed = phases.len() == PHASES.len() && phases.iter().all(|p| p.passed);
+    Ok(ScenarioReport { name: spec.name.to_string(), passed, phases })
+}
+
+struct ScenarioContext {
+    conn: Connection,
+    device: Arc<FakeDevice>,
+    spec: &'static ScenarioSpec,
+    /// 本次场景生成 VCF 的临时目录
+    work_dir: PathBuf,
+    number_ids: Vec<i64>,
+    batch_id: Option<String>,
+    vcf_path: Option<String>,
+    session_id: Option<i64>,
+}
+
+impl ScenarioContext {
+    fn phone(i: usize) -> String {
+        format!("138{:08}", i)
+    }
+
+    fn count(&self, sql: &str, args: impl rusqlite::Params) -> Result<i64, String> {
+        self.conn
+            .query_row(sql, args, |row| row.get(0))
+            .map_err(|e| format!("查询失败: {}", e))
+    }
+
+    fn count_status(&self, status: &str) -> Result<i64, String> {
+        self.count("SELECT COUNT(*) FROM contact_numbers WHERE status = ?1", params![status])
+    }
+
+    /// 阶段一：导入号码（含一个重复号码，验证去重）
+    fn phase_import(&mut self, out: &mut Vec<AssertionResult>) -> Result<(), String> {
+        let mut numbers: Vec<(String, String)> = (0..self.spec.numbers)
+            .map(|i| (Self::phone(i), format!("联系人{}", i)))
+            .collect();
+        numbers.push((Self::phone(0), "重复".to_string()));
+
+        let (inserted, duplicates, errors) =
+            ContactNumberRepository::insert_numbers(&self.conn, &numbers, "scenario_numbers.txt");
+
+        out.push(check("插入数量", self.spec.numbers as i64, inserted));
+        out.push(check("重复数量", 1, duplicates));
+        out.push(check("插入错误数", 0, errors.len() as i64));
+        out.push(check("available 状态号码数", self.spec.numbers as i64, self.count_status("available")?));
+
+        let mut stmt = self.conn.prepare("SELECT id FROM contact_numbers ORDER BY id").map_err(|e| e.to_string())?;
+        let ids = stmt.query_map([], |row| row.get::<_, i64>(0)).map_err(|e| e.to_string())?;
+        self.number_ids = ids.collect::<Result<_, _>>().map_err(|e| e.to_string())?;
+        Ok(())
+    }
+
+    /// 阶段二：按选中顺序导出 VCF，生成批次并分配号码
+    fn phase_create_batch(&mut self, out: &mut Vec<AssertionResult>) -> Result<(), String> {
+        let output_path = self.work_dir.join("scenario.vcf");
+        let export = vcf_chunk_export::export_numbers(
+            &self.conn,
+            &self.number_ids,
+            &output_path.to_string_lossy(),
+            &VcfExportOptions::default(),
+        )?;
+        let total = self.spec.numbers as i64;
+
+        out.push(check("导出号码数", total, export.total_contacts));
+        out.push(check("分片数", 1, export.chunks.len() as i64));
+        out.push(check("assigned 状态号码数", total, self.count_status("assigned")?));
+        out.push(check("available 状态号码数", 0, self.count_status("available")?));
+
+        let chunk = export.chunks.into_iter().next().ok_or("导出结果缺少分片")?;
+        out.push(check(
+            "批次关联号码数",
+            total,
+            self.count("SELECT COUNT(*) FROM contact_numbers WHERE assigned_batch_id = ?1", params![chunk.batch_id])?,
+        ));
+        self.batch_id = Some(chunk.batch_id);
+        self.vcf_path = Some(chunk.file_path);
+        Ok(())
+    }
+
+    /// 阶段三：创建导入会话，通过 VCF 导入器把文件推送到假设备并发起导入
+    async fn phase_run_import(&mut self, out: &mut Vec<AssertionResult>) -> Result<(), String> {
+        let batch_id = self.batch_id.clone().ok_or("缺少批次")?;
+        let vcf_path = self.vcf_path.clone().ok_or("缺少 VCF 文件")?;
+
+        let session_id = ImportSessionRepository::create_import_session(&self.conn, &batch_id, self.device.serial(), None)
+            .map_err(|e| e.to_string())?;
+        self.session_id = Some(session_id);
+
+        let importer = MultiBrandVcfImporter::with_transport(self.device.serial().to_string(), self.device.clone())
+            .with_intent_settle_delay(Duration::ZERO);
+        let result = importer.import_via_intent(&vcf_path).await.map_err(|e| e.to_string())?;
+
+        let remote_path = format!("/sdcard/Download/{}", file_name(&vcf_path));
+        out.push(check("导入命令已发送", true, result.success));
+        out.push(check("VCF 已推送", true, self.device.file_exists(&remote_path)));
+        out.push(check("VCF 联系人数", self.spec.numbers, result.total_contacts));
+        out.push(check(
+            "设备通讯录数量",
+            self.spec.numbers - self.spec.failing.len(),
+            self.device.contact_count(),
+        ));
+        Ok(())
+    }
+
+    /// 阶段四：对账 - 验证设备通讯录，未导入的号码回收为 available，其余标记 imported
+    fn phase_reconcile(&mut self, out: &mut Vec<AssertionResult>) -> Result<(), String> {
+        let session_id = self.session_id.ok_or("缺少导入会话")?;
+        let numbers = vcf_chunk_export::fetch_numbers_in_order(&self.conn, &self.number_ids)
+            .map_err(|e| e.to_string())?
+            .0;
+        let phones: Vec<String> = numbers.iter().map(|n| n.phone.clone()).collect();
+
+        let verification = verify_numbers_with(self.device.as_ref(), self.device.serial(), &phones, |_| {})?;
+        let missing_ids: Vec<i64> = numbers
+            .iter()
+            .filter(|n| !verification.verified_phones.contains(&n.phone))
+            .map(|n| n.id)
+            .collect();
+        let expected_failed = self.spec.failing.len() as i64;
+        out.push(check("验证方式", "device_snapshot_diff".to_string(), verification.method.clone()));
+        out.push(check("设备缺失号码数", expected_failed, missing_ids.len() as i64));
+
+        let released = status_management::mark_numbers_as_not_imported_by_ids(&self.conn, &missing_ids)
+            .map_err(|e| e.to_string())?;
+        let (start, end) = match (self.number_ids.iter().min(), self.number_ids.iter().max()) {
+            (Some(start), Some(end)) => (*start, *end),
+            _ => return Err("缺少号码".to_string()),
+        };
+        let imported = status_management::mark_numbers_imported(&self.conn, start, end, self.device.serial())
+            .map_err(|e| e.to_string())?;
+        let status = if imported > 0 { ImportSessionStatus::Success } else { ImportSessionStatus::Failed };
+        ImportSessionRepository::finish_import_session(&self.conn, session_id, status, imported, released, None)
+            .map_err(|e| e.to_string())?;
+
+        out.push(check("回收号码数", expected_failed, released));
+        out.push(check("imported 状态号码数", self.spec.numbers as i64 - expected_failed, self.count_status("imported")?));
+        out.push(check("残留 assigned 号码数", 0, self.count_status("assigned")?));
+        let missing_on_device = numbers
+            .iter()
+            .filter(|n| !missing_ids.contains(&n.id) && !self.device.has_contact(&n.phone))
+            .count();
+        out.push(check("数据库已导入但设备缺失", 0, missing_on_device as i64));
+        out.push(check(
+            "会话成功 / 失败数",
+            (imported, released),
+            self.conn
+                .query_row(
+                    "SELECT success_count, failed_count FROM import_sessions WHERE id = ?1",
+                    params![session_id],
+                    |r| Ok((r.get::<_, i64>(0)?, r.get::<_, i64>(1)?)),
+                )
+                .map_err(|e| e.to_string())?,
+        ));
+        Ok(())
+    }
+}
+
+fn file_name(path: &str) -> String {
+    std::path::Path::new(path)
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+fn check<T: PartialEq + std::fmt::Debug>(description: &str, expected: T, actual: T) -> AssertionResult {
+    AssertionResult {
+        description: description.to_string(),
+        passed: expected == actual,
+        expected: format!("{:?}", expected),
+        actual: format!("{:?}", actual),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn all_builtin_scenarios_pass() {
+        for name in list_scenarios() {
+            let report = run_scenario(name).await.expect("场景应能运行");
+            assert!(report.passed, "场景 {} 失败: {:#?}", name, report.phases);
+        }
+    }
+
+    #[tokio::test]
+    async fn unknown_scenario_is_rejected() {
+        assert!(run_scenario("does_not_exist").await.is_err());
+    }
+}
@@ -0,0 +1,146 @@
+// src-tauri/src/services/scoped_dump/mod.rs
+// module: scoped_dump | layer: services | role: 容器范围 UI dump
+// summary: dump 后按容器选择器只保留其子树（uiautomator 不支持按节点过滤，统一在本地裁剪），输出仍是完整的 hierarchy 文档，坐标保持绝对值
+
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use crate::services::adb::AdbService;
+
+/// 容器选择器；多个条件同时满足，命中多个时取第 `index` 个（文档顺序）
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DumpScope {
+    pub resource_id: Option<String>,
+    pub class_name: Option<String>,
+    pub text: Option<String>,
+    pub content_desc: Option<String>,
+    pub index: usize,
+    /// 容器不存在时报错（默认回退到整屏 dump）
+    pub required: bool,
+}
+
+impl DumpScope {
+    /// 从步骤参数读取 `dump_scope`：字符串视为 resource-id，对象按字段解析
+    pub fn from_step(step: &serde_json::Value) -> Option<Self> {
+        let scope = match step.get("dump_scope")? {
+            serde_json::Value::String(id) => Self { resource_id: Some(id.clone()), ..Default::default() },
+            value @ serde_json::Value::Object(_) => serde_json::from_value(value.clone()).ok()?,
+            _ => return None,
+        };
+        (!scope.is_empty()).then_some(scope)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.resource_id.is_none() && self.class_name.is_none() && self.text.is_none() && self.content_desc.is_none()
+    }
+
+    fn matches(&self, node: &roxmltree::Node) -> bool {
+        let eq = |attr: &str, expected: &Option<String>| expected.as_deref().is_none_or(|v| node.attribute(attr) == Some(v));
+        node.is_element()
+            && eq("resource-id", &self.resource_id)
+            && eq("class", &self.class_name)
+            && eq("text", &self.text)
+            && eq("content-desc", &self.content_desc)
+    }
+
+    /// 日志用描述
+    pub fn describe(&self) -> String {
+        [
+            ("resource-id", &self.resource_id),
+            ("class", &self.class_name),
+            ("text", &self.text),
+            ("content-desc", &self.content_desc),
+        ]
+        .iter()
+        .filter_map(|(k, v)| v.as_ref().map(|v| format!("{}={}", k, v)))
+        .collect::<Vec<_>>()
+        .join(",")
+    }
+}
+
+/// 裁剪结果
+#[derive(Debug, Clone, Serialize)]
+pub struct ScopedDump {
+    pub xml: String,
+    /// 是否找到容器（false 时 xml 为整屏 dump）
+    pub scoped: bool,
+    pub node_count: usize,
+    pub full_node_count: usize,
+}
+
+/// 把整屏 dump 裁剪到容器子树；容器不存在时返回 None
+pub fn scope_xml(full_xml: &str, scope: &DumpScope) -> Result<Option<ScopedDump>, String> {
+    let doc = roxmltree::Document::parse(full_xml).map_err(|e| format!("解析UI Dump失败: {}", e))?;
+    let full_node_count = doc.descendants().filter(|n| n.has_tag_name("node")).count();
+    let Some(container) = doc.descendants().filter(|n| scope.matches(n)).nth(scope.index) else {
+        return Ok(None);
+    };
+
+    let rotation = doc.root_element().attribute("rotation").unwrap_or("0");
+    let xml = format!(
+        "<?xml version='1.0' encoding='UTF-8' standalone='yes' ?><hierarchy rotation=\"{}\">{}</hierarchy>",
+        rotation,
+        &full_xml[container.range()]
+    );
+    Ok(Some(ScopedDump {
+        xml,
+        scoped: true,
+        node_count: container.descendants().filter(|n| n.has_tag_name("node")).count(),
+        full_node_count,
+    }))
+}
+
+/// 按范围裁剪；容器缺失时按 `required` 报错或回退整屏
+pub fn apply_scope(full_xml: String, scope: &DumpScope) -> Result<ScopedDump, String> {
+    match scope_xml(&full_xml, scope)? {
+        Some(scoped) => {
+            info!("✂️ 范围 dump: [{}] {}/{} 节点", scope.describe(), scoped.node_count, scoped.full_node_count);
+            Ok(scoped)
+        }
+        None if scope.required => Err(format!("未找到 dump 范围容器: {}", scope.describe())),
+        None => {
+            warn!("⚠️ 未找到 dump 范围容器 [{}]，使用整屏 dump", scope.describe());
+            let node_count = full_xml.matches("<node").count();
+            Ok(ScopedDump { xml: full_xml, scoped: false, node_count, full_node_count: node_count })
+        }
+    }
+}
+
+/// dump 设备 UI 并裁剪到容器子树
+pub async fn dump_scoped(device_id: &str, scope: &DumpScope) -> Result<ScopedDump, String> {
+    let full_xml = AdbService::new()
+        .dump_ui_hierarchy(device_id)
+        .await
+        .map_err(|e| format!("Failed to dump hierarchy: {}", e))?;
+    apply_scope(full_xml, scope)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PAGE: &str = r#"<?xml version='1.0' encoding='UTF-8' standalone='yes' ?><hierarchy rotation="0"><node class="FrameLayout" bounds="[0,0][1080,2400]"><node resource-id="app:id/feed" class="RecyclerView" bounds="[0,0][1080,2200]"><node text="post" bounds="[0,0][1080,500]"/></node><node resource-id="app:id/tabs" class="LinearLayout" bounds="[0,2200][1080,2400]"><node text="首页" bounds="[0,2200][270,2400]"/><node text="消息" bounds="[540,2200][810,2400]"/></node></node></hierarchy>"#;
+
+    #[test]
+    fn scopes_to_container_subtree_or_falls_back() {
+        let scope = DumpScope::from_step(&serde_json::json!({ "dump_scope": "app:id/tabs" })).unwrap();
+        let scoped = apply_scope(PAGE.to_string(), &scope).unwrap();
+        assert!(scoped.scoped);
+        assert_eq!((scoped.node_count, scoped.full_node_count), (3, 6));
+        assert!(scoped.xml.starts_with("<?xml"));
+        assert!(scoped.xml.contains(r#"text="消息" bounds="[540,2200][810,2400]""#));
+        assert!(!scoped.xml.contains("post"));
+        roxmltree::Document::parse(&scoped.xml).unwrap();
+
+        let by_text = DumpScope { text: Some("消息".into()), ..Default::default() };
+        assert_eq!(scope_xml(PAGE, &by_text).unwrap().unwrap().node_count, 1);
+
+        let missing = DumpScope { resource_id: Some("app:id/none".into()), ..Default::default() };
+        let fallback = apply_scope(PAGE.to_string(), &missing).unwrap();
+        assert!(!fallback.scoped);
+        assert_eq!(fallback.xml, PAGE);
+        assert!(apply_scope(PAGE.to_string(), &DumpScope { required: true, ..missing }).is_err());
+        assert!(DumpScope::from_step(&serde_json::json!({ "dump_scope": {} })).is_none());
+    }
+}
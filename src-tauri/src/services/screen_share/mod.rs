@@ -0,0 +1,205 @@
+// src-tauri/src/services/screen_share/mod.rs
+// module: screen_share | layer: services | role: 截图分享
+// summary: 支持人员排查用 - 截取设备当前屏幕存入短期制品，按配置上传到 webhook 地址，返回可贴进工单的引用
+
+use chrono::{DateTime, Duration as ChronoDuration, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::time::Duration;
+use tracing::{info, warn};
+
+use crate::screenshot_service::ScreenshotService;
+use crate::services::artifact_store::{gallery, ArtifactStore};
+
+const CONFIG_FILE_NAME: &str = "screen_share_config.json";
+/// 返回给前端的令牌占位；保存时原样传回表示沿用已保存的令牌
+pub const MASKED_TOKEN: &str = "********";
+
+/// 截图分享配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScreenShareConfig {
+    /// 上传地址（webhook）；为空时只存本地制品
+    pub upload_url: Option<String>,
+    /// 上传时附带的 `Authorization: Bearer` 令牌
+    pub auth_token: Option<String>,
+    /// 制品保留分钟数
+    pub ttl_minutes: i64,
+    pub timeout_secs: u64,
+}
+
+impl Default for ScreenShareConfig {
+    fn default() -> Self {
+        Self { upload_url: None, auth_token: None, ttl_minutes: 60, timeout_secs: 20 }
+    }
+}
+
+impl ScreenShareConfig {
+    /// 令牌替换为占位符后的副本（返回给前端用）
+    pub fn masked(mut self) -> Self {
+        if self.auth_token.as_deref().is_some_and(|t| !t.is_empty()) {
+            self.auth_token = Some(MASKED_TOKEN.to_string());
+        }
+        self
+    }
+
+    /// 令牌仍是占位符时沿用 `saved` 中的令牌
+    fn with_saved_token(mut self, saved: &ScreenShareConfig) -> Self {
+        if self.auth_token.as_deref() == Some(MASKED_TOKEN) {
+            self.auth_token = saved.auth_token.clone();
+        }
+        self
+    }
+}
+
+fn config_path() -> PathBuf {
+    dirs::data_dir().unwrap_or_else(|| PathBuf::from(".")).join("employee-gui").join(CONFIG_FILE_NAME)
+}
+
+pub fn load_config() -> ScreenShareConfig {
+    std::fs::read_to_string(config_path())
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+pub fn save_config(config: &ScreenShareConfig) -> Result<(), String> {
+    if config.ttl_minutes <= 0 {
+        return Err("保留时间必须大于 0 分钟".to_string());
+    }
+    if let Some(url) = config.upload_url.as_deref().filter(|u| !u.trim().is_empty()) {
+        if !(url.starts_with("https://") || url.starts_with("http://")) {
+            return Err(format!("上传地址必须是 http(s) 地址: {}", url));
+        }
+    }
+    let config = config.clone().with_saved_token(&load_config());
+    let path = config_path();
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    }
+    let content = serde_json::to_string_pretty(&config).map_err(|e| e.to_string())?;
+    std::fs::write(path, content).map_err(|e| format!("保存截图分享配置失败: {}", e))
+}
+
+/// 分享结果：`reference` 为远程地址（已上传）或 `artifact://<token>`
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ShareReference {
+    pub token: String,
+    pub device_id: String,
+    pub captured_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+    pub local_path: String,
+    pub remote_url: Option<String>,
+    /// 上传失败时的原因（本地制品仍然可用）
+    pub upload_error: Option<String>,
+    pub reference: String,
+}
+
+/// 截图 → 存入制品 → 按需上传；`upload` 为空时配置了上传地址就上传
+///
+/// `run_id` / `step_id` 写入制品元信息，供截图图库按运行 / 步骤过滤
+pub async fn capture_and_share(
+    device_id: &str,
+    upload: Option<bool>,
+    run_id: Option<&str>,
+    step_id: Option<&str>,
+) -> Result<ShareReference, String> {
+    let config = load_config();
+    let device = device_id.to_string();
+    let png = tokio::task::spawn_blocking(move || ScreenshotService::capture_png_bytes(&device))
+        .await
+        .map_err(|e| format!("截图任务失败: {}", e))??;
+
+    let (artifact, path) = gallery::index_screenshot(
+        ArtifactStore::global(),
+        &png,
+        ChronoDuration::minutes(config.ttl_minutes.max(1)),
+        serde_json::json!({ "deviceId": device_id, "runId": run_id, "stepId": step_id }),
+    )?;
+    info!("📸 截图已存为制品 token={} device={} size={}", artifact.token, device_id, artifact.size);
+
+    let upload_url = config.upload_url.clone().filter(|u| !u.trim().is_empty());
+    let (remote_url, upload_error) = match (upload_url, upload) {
+        (None, Some(true)) => (None, Some("未配置上传地址".to_string())),
+        (Some(url), None | Some(true)) => match upload_png(&url, &config, &artifact.token, device_id, png).await {
+            Ok(remote) => (Some(remote), None),
+            Err(e) => {
+                warn!("⚠️ 截图上传失败 token={}: {}", artifact.token, e);
+                (None, Some(e))
+            }
+        },
+        _ => (None, None),
+    };
+
+    Ok(ShareReference {
+        reference: remote_url.clone().unwrap_or_else(|| format!("artifact://{}", artifact.token)),
+        token: artifact.token,
+        device_id: device_id.to_string(),
+        captured_at: artifact.created_at,
+        expires_at: artifact.expires_at,
+        local_path: path.to_string_lossy().to_string(),
+        remote_url,
+        upload_error,
+    })
+}
+
+/// 以 PNG 原始内容 POST 到上传地址；响应 JSON 中的 `url` 作为远程引用，否则用上传地址 + 令牌
+async fn upload_png(
+    url: &str,
+    config: &ScreenShareConfig,
+    token: &str,
+    device_id: &str,
+    png: Vec<u8>,
+) -> Result<String, String> {
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(config.timeout_secs.max(1)))
+        .build()
+        .map_err(|e| e.to_string())?;
+    let mut request = client
+        .post(url)
+        .header("Content-Type", "image/png")
+        .header("X-Artifact-Token", token)
+        .header("X-Device-Id", device_id)
+        .body(png);
+    if let Some(auth) = config.auth_token.as_deref().filter(|t| !t.is_empty()) {
+        request = request.bearer_auth(auth);
+    }
+    let response = request.send().await.and_then(|r| r.error_for_status()).map_err(|e| e.to_string())?;
+    let body = response.text().await.unwrap_or_default();
+    Ok(remote_reference(&body).unwrap_or_else(|| format!("{}#{}", url, token)))
+}
+
+/// 从上传响应中取远程地址：JSON 的 `url` / `link` 字段，或纯文本 http(s) 地址
+fn remote_reference(body: &str) -> Option<String> {
+    let body = body.trim();
+    if let Ok(value) = serde_json::from_str::<serde_json::Value>(body) {
+        return ["url", "link"].iter().find_map(|k| value.get(*k).and_then(|v| v.as_str()).map(String::from));
+    }
+    (body.starts_with("http://") || body.starts_with("https://")).then(|| body.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_remote_reference_from_response() {
+        assert_eq!(remote_reference(r#"{"url":"https://s.example/a1"}"#).as_deref(), Some("https://s.example/a1"));
+        assert_eq!(remote_reference(" https://s.example/b2\n").as_deref(), Some("https://s.example/b2"));
+        assert_eq!(remote_reference(r#"{"ok":true}"#), None);
+        assert_eq!(remote_reference("ok"), None);
+    }
+
+    #[test]
+    fn test_token_is_masked_and_placeholder_keeps_saved_token() {
+        let saved = ScreenShareConfig { auth_token: Some("secret-token".into()), ..Default::default() };
+        let shown = saved.clone().masked();
+        assert_eq!(shown.auth_token.as_deref(), Some(MASKED_TOKEN));
+        assert_eq!(ScreenShareConfig::default().masked().auth_token, None);
+
+        assert_eq!(shown.with_saved_token(&saved).auth_token.as_deref(), Some("secret-token"));
+        let replaced = ScreenShareConfig { auth_token: Some("new".into()), ..Default::default() };
+        assert_eq!(replaced.with_saved_token(&saved).auth_token.as_deref(), Some("new"));
+    }
+}
@@ -0,0 +1,230 @@
+// src-tauri/src/services/screen_stability/mod.rs
+// module: screen_stability | layer: services | role: 屏幕稳定检测原语
+// summary: 轮询廉价的 UI 签名（窗口焦点 + 子树 dump 哈希），屏幕在 quiet_ms 内无变化即视为稳定；供脚本、Agent 观察阶段与动作后校验复用
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+use tracing::{debug, info, warn};
+
+use crate::services::adb::AdbService;
+
+/// 默认静默时长（毫秒）
+pub const DEFAULT_QUIET_MS: u64 = 800;
+/// 默认超时（毫秒）
+pub const DEFAULT_TIMEOUT_MS: u64 = 8_000;
+/// 默认轮询间隔（毫秒）
+const DEFAULT_POLL_MS: u64 = 250;
+
+/// 签名采样来源（真机实现走 ADB，测试用假实现）
+#[async_trait]
+pub trait ScreenProbe: Send + Sync {
+    /// 当前焦点窗口标识（dumpsys 的 mCurrentFocus 行）
+    async fn window_token(&self, device_id: &str) -> Result<String, String>;
+    /// 当前 UI dump XML
+    async fn ui_dump(&self, device_id: &str) -> Result<String, String>;
+}
+
+/// ADB 实现
+pub struct AdbScreenProbe;
+
+#[async_trait]
+impl ScreenProbe for AdbScreenProbe {
+    async fn window_token(&self, device_id: &str) -> Result<String, String> {
+        AdbService::new()
+            .get_current_activity(device_id)
+            .await
+            .map(|raw| raw.trim().to_string())
+            .map_err(|e| e.to_string())
+    }
+
+    async fn ui_dump(&self, device_id: &str) -> Result<String, String> {
+        AdbService::new().dump_ui_hierarchy(device_id).await.map_err(|e| e.to_string())
+    }
+}
+
+/// 稳定检测参数
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct IdleOptions {
+    /// 连续无变化多久视为稳定
+    pub quiet_ms: u64,
+    /// 最长等待
+    pub timeout_ms: u64,
+    /// 轮询间隔
+    pub poll_ms: u64,
+    /// 只比较该 resource-id 节点下的子树（为空比较整棵树）
+    pub subtree: Option<String>,
+}
+
+impl Default for IdleOptions {
+    fn default() -> Self {
+        Self { quiet_ms: DEFAULT_QUIET_MS, timeout_ms: DEFAULT_TIMEOUT_MS, poll_ms: DEFAULT_POLL_MS, subtree: None }
+    }
+}
+
+impl IdleOptions {
+    /// 从步骤参数读取：`wait_for_idle: true` 或 `wait_for_idle: { quiet_ms, timeout_ms, poll_ms, subtree }`
+    pub fn from_step(step: &serde_json::Value) -> Option<Self> {
+        match step.get("wait_for_idle")? {
+            serde_json::Value::Bool(true) => Some(Self::default()),
+            value @ serde_json::Value::Object(_) => serde_json::from_value(value.clone()).ok(),
+            _ => None,
+        }
+    }
+}
+
+/// 稳定检测结果
+#[derive(Debug, Clone, Serialize)]
+pub struct IdleReport {
+    /// 是否在超时前达到稳定
+    pub idle: bool,
+    pub waited_ms: u64,
+    pub polls: u32,
+    /// 观察到的签名变化次数
+    pub changes: u32,
+    /// 最后一次签名（窗口 + 子树哈希）
+    pub signature: Option<String>,
+    /// 最后一次采样错误
+    pub last_error: Option<String>,
+}
+
+/// UI 子树签名：只取结构与可见内容属性，忽略 dump 中的无关差异
+pub fn ui_signature(xml: &str, subtree: Option<&str>) -> String {
+    let Ok(doc) = roxmltree::Document::parse(xml) else {
+        return format!("{:x}", md5::compute(xml.as_bytes()));
+    };
+    let root = subtree
+        .and_then(|id| doc.descendants().find(|n| n.attribute("resource-id") == Some(id)))
+        .unwrap_or_else(|| doc.root_element());
+
+    let mut ctx = md5::Context::new();
+    for node in root.descendants().filter(|n| n.is_element()) {
+        for attr in ["class", "resource-id", "text", "content-desc", "bounds", "checked", "selected"] {
+            ctx.consume(node.attribute(attr).unwrap_or("").as_bytes());
+            ctx.consume(b"|");
+        }
+        ctx.consume(b"\n");
+    }
+    format!("{:x}", ctx.compute())
+}
+
+/// 等待屏幕稳定（见 `wait_for_idle_with`）
+pub async fn wait_for_idle(device_id: &str, options: &IdleOptions) -> IdleReport {
+    wait_for_idle_with(&AdbScreenProbe, device_id, options).await
+}
+
+/// 轮询签名直到连续 quiet_ms 无变化或超时；窗口焦点变化时本轮不再 dump
+pub async fn wait_for_idle_with(probe: &dyn ScreenProbe, device_id: &str, options: &IdleOptions) -> IdleReport {
+    let started = Instant::now();
+    let quiet = Duration::from_millis(options.quiet_ms);
+    let timeout = Duration::from_millis(options.timeout_ms.max(options.quiet_ms));
+    let poll = Duration::from_millis(options.poll_ms.max(1));
+
+    let mut report =
+        IdleReport { idle: false, waited_ms: 0, polls: 0, changes: 0, signature: None, last_error: None };
+    let mut last_window: Option<String> = None;
+    let mut stable_since = Instant::now();
+
+    loop {
+        report.polls += 1;
+        let signature = match probe.window_token(device_id).await {
+            Ok(window) if last_window.as_ref().is_some_and(|w| *w != window) => {
+                last_window = Some(window.clone());
+                Err(format!("窗口切换: {}", window))
+            }
+            Ok(window) => {
+                last_window = Some(window.clone());
+                match probe.ui_dump(device_id).await {
+                    Ok(xml) => Ok(format!("{}#{}", window, ui_signature(&xml, options.subtree.as_deref()))),
+                    Err(e) => {
+                        report.last_error = Some(e.clone());
+                        Err(e)
+                    }
+                }
+            }
+            Err(e) => {
+                report.last_error = Some(e.clone());
+                Err(e)
+            }
+        };
+
+        match signature {
+            Ok(signature) if report.signature.as_deref() == Some(signature.as_str()) => {
+                if stable_since.elapsed() >= quiet {
+                    report.idle = true;
+                }
+            }
+            Ok(signature) => {
+                if report.signature.is_some() {
+                    report.changes += 1;
+                }
+                report.signature = Some(signature);
+                stable_since = Instant::now();
+            }
+            Err(reason) => {
+                debug!("屏幕签名变化或采样失败: {}", reason);
+                report.signature = None;
+                report.changes += 1;
+                stable_since = Instant::now();
+            }
+        }
+
+        report.waited_ms = started.elapsed().as_millis() as u64;
+        if report.idle {
+            info!("🧘 屏幕已稳定: device={} waited={}ms polls={}", device_id, report.waited_ms, report.polls);
+            return report;
+        }
+        if started.elapsed() >= timeout {
+            warn!("⏱️ 等待屏幕稳定超时: device={} waited={}ms changes={}", device_id, report.waited_ms, report.changes);
+            return report;
+        }
+        tokio::time::sleep(poll).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    /// 按顺序返回预设 dump，用完后保持最后一个
+    struct ScriptedProbe {
+        dumps: Mutex<Vec<&'static str>>,
+    }
+
+    #[async_trait]
+    impl ScreenProbe for ScriptedProbe {
+        async fn window_token(&self, _device_id: &str) -> Result<String, String> {
+            Ok("mCurrentFocus=Window{1 u0 com.demo/.Main}".to_string())
+        }
+
+        async fn ui_dump(&self, _device_id: &str) -> Result<String, String> {
+            let mut dumps = self.dumps.lock().unwrap();
+            Ok(if dumps.len() > 1 { dumps.remove(0) } else { dumps[0] }.to_string())
+        }
+    }
+
+    const LOADING: &str = r#"<hierarchy><node class="android.widget.ProgressBar" bounds="[0,0][10,10]"/></hierarchy>"#;
+    const LOADED: &str = r#"<hierarchy><node resource-id="com.demo:id/list" class="ListView" bounds="[0,0][100,100]"><node text="a"/></node><node text="clock 10:01"/></hierarchy>"#;
+    const LOADED_CLOCK: &str = r#"<hierarchy><node resource-id="com.demo:id/list" class="ListView" bounds="[0,0][100,100]"><node text="a"/></node><node text="clock 10:02"/></hierarchy>"#;
+
+    #[tokio::test]
+    async fn waits_until_signature_stops_changing() {
+        let options = IdleOptions { quiet_ms: 20, timeout_ms: 2_000, poll_ms: 5, subtree: None };
+        let probe = ScriptedProbe { dumps: Mutex::new(vec![LOADING, LOADING, LOADED]) };
+        let report = wait_for_idle_with(&probe, "dev", &options).await;
+        assert!(report.idle);
+        assert_eq!(report.changes, 1);
+
+        // 子树外的变化（时钟）不影响子树签名
+        assert_ne!(ui_signature(LOADED, None), ui_signature(LOADED_CLOCK, None));
+        assert_eq!(ui_signature(LOADED, Some("com.demo:id/list")), ui_signature(LOADED_CLOCK, Some("com.demo:id/list")));
+
+        let flicker = IdleOptions { quiet_ms: 50, timeout_ms: 50, poll_ms: 5, subtree: None };
+        let probe = ScriptedProbe { dumps: Mutex::new([LOADED, LOADED_CLOCK].repeat(100)) };
+        let report = wait_for_idle_with(&probe, "dev", &flicker).await;
+        assert!(!report.idle);
+        assert!(report.changes > 0);
+    }
+}
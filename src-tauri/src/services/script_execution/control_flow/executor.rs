@@ -511,6 +511,8 @@ impl From<ExecutionResult> for SmartExecutionResult {
             } else {
                 format!("执行失败: {} 个步骤失败", result.execution_stats.step_stats.failed_steps)
             },
+            run_id: None,
+            dry_run: None,
         }
     }
 }
\ No newline at end of file
@@ -60,6 +60,7 @@ impl Default for SmartScript {
                 auto_verification_enabled: true,
                 smart_recovery_enabled: true,
                 detailed_logging: true,
+                status_indicator: None,
             },
             metadata: HashMap::new(),
         }
@@ -305,6 +306,13 @@ impl ScriptManagerService {
 pub async fn save_smart_script(
     state: State<'_, ScriptManagerState>,
     script: SmartScript
+) -> Result<SmartScript, String> {
+    crate::core::plugin_isolation::guard("script_manager", save_smart_script_impl(state, script)).await
+}
+
+async fn save_smart_script_impl(
+    state: State<'_, ScriptManagerState>,
+    script: SmartScript
 ) -> Result<SmartScript, String> {
     let service = state.0.lock();
     let mut updated_script = script;
@@ -320,6 +328,13 @@ pub async fn save_smart_script(
 pub async fn load_smart_script(
     state: State<'_, ScriptManagerState>,
     script_id: String
+) -> Result<SmartScript, String> {
+    crate::core::plugin_isolation::guard("script_manager", load_smart_script_impl(state, script_id)).await
+}
+
+async fn load_smart_script_impl(
+    state: State<'_, ScriptManagerState>,
+    script_id: String
 ) -> Result<SmartScript, String> {
     let service = state.0.lock();
     service.load_script(&script_id)
@@ -330,6 +345,13 @@ pub async fn load_smart_script(
 pub async fn delete_smart_script(
     state: State<'_, ScriptManagerState>,
     script_id: String
+) -> Result<(), String> {
+    crate::core::plugin_isolation::guard("script_manager", delete_smart_script_impl(state, script_id)).await
+}
+
+async fn delete_smart_script_impl(
+    state: State<'_, ScriptManagerState>,
+    script_id: String
 ) -> Result<(), String> {
     let service = state.0.lock();
     service.delete_script(&script_id)
@@ -339,6 +361,12 @@ pub async fn delete_smart_script(
 #[command]
 pub async fn list_smart_scripts(
     state: State<'_, ScriptManagerState>
+) -> Result<Vec<SmartScript>, String> {
+    crate::core::plugin_isolation::guard("script_manager", list_smart_scripts_impl(state)).await
+}
+
+async fn list_smart_scripts_impl(
+    state: State<'_, ScriptManagerState>
 ) -> Result<Vec<SmartScript>, String> {
     let service = state.0.lock();
     service.list_scripts()
@@ -349,6 +377,13 @@ pub async fn list_smart_scripts(
 pub async fn import_smart_script(
     state: State<'_, ScriptManagerState>,
     file_path: String
+) -> Result<SmartScript, String> {
+    crate::core::plugin_isolation::guard("script_manager", import_smart_script_impl(state, file_path)).await
+}
+
+async fn import_smart_script_impl(
+    state: State<'_, ScriptManagerState>,
+    file_path: String
 ) -> Result<SmartScript, String> {
     let service = state.0.lock();
     service.import_script(&file_path)
@@ -360,6 +395,14 @@ pub async fn export_smart_script(
     state: State<'_, ScriptManagerState>,
     script_id: String, 
     output_path: String
+) -> Result<(), String> {
+    crate::core::plugin_isolation::guard("script_manager", export_smart_script_impl(state, script_id, output_path)).await
+}
+
+async fn export_smart_script_impl(
+    state: State<'_, ScriptManagerState>,
+    script_id: String, 
+    output_path: String
 ) -> Result<(), String> {
     let service = state.0.lock();
     service.export_script(&script_id, &output_path)
@@ -369,6 +412,12 @@ pub async fn export_smart_script(
 #[command]
 pub async fn list_script_templates(
     state: State<'_, ScriptManagerState>
+) -> Result<Vec<SmartScript>, String> {
+    crate::core::plugin_isolation::guard("script_manager", list_script_templates_impl(state)).await
+}
+
+async fn list_script_templates_impl(
+    state: State<'_, ScriptManagerState>
 ) -> Result<Vec<SmartScript>, String> {
     let service = state.0.lock();
     service.list_templates()
@@ -380,6 +429,14 @@ pub async fn create_script_from_template(
     state: State<'_, ScriptManagerState>,
     template_id: String, 
     name: String
+) -> Result<SmartScript, String> {
+    crate::core::plugin_isolation::guard("script_manager", create_script_from_template_impl(state, template_id, name)).await
+}
+
+async fn create_script_from_template_impl(
+    state: State<'_, ScriptManagerState>,
+    template_id: String, 
+    name: String
 ) -> Result<SmartScript, String> {
     let service = state.0.lock();
     service.create_from_template(&template_id, &name)
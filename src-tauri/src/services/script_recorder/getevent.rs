@@ -0,0 +1,235 @@
+// src-tauri/src/services/script_recorder/getevent.rs
+// module: script_recorder | layer: services | role: 输入事件解析
+// summary: 解析 `getevent -lt` 输出与 `getevent -lp` 触摸坐标范围，把单指触摸序列识别为点击 / 长按 / 滑动手势，把物理按键识别为按键手势
+
+use std::collections::HashMap;
+
+use serde::Serialize;
+
+/// 位移不超过该像素数视为点击
+const TAP_SLOP_PX: i32 = 24;
+/// 按住超过该时长视为长按
+const LONG_PRESS_MS: u64 = 500;
+
+/// `getevent -lt` 的一行
+#[derive(Debug, Clone, PartialEq)]
+pub struct RawEvent {
+    /// 内核时间戳（秒）
+    pub time: f64,
+    pub device: String,
+    pub kind: String,
+    pub code: String,
+    pub value: String,
+}
+
+/// 解析 `[   5863.465318] /dev/input/event1: EV_ABS       ABS_MT_POSITION_X    0000021c`
+pub fn parse_line(line: &str) -> Option<RawEvent> {
+    let rest = line.trim().strip_prefix('[')?;
+    let (time, rest) = rest.split_once(']')?;
+    let (device, rest) = rest.trim().split_once(':')?;
+    let mut parts = rest.split_whitespace();
+    Some(RawEvent {
+        time: time.trim().parse().ok()?,
+        device: device.trim().to_string(),
+        kind: parts.next()?.to_string(),
+        code: parts.next()?.to_string(),
+        value: parts.next()?.to_string(),
+    })
+}
+
+/// 从 `getevent -lp` 输出读取各输入设备的触摸坐标上限 (max_x, max_y)
+pub fn parse_abs_ranges(output: &str) -> HashMap<String, (i32, i32)> {
+    let mut ranges: HashMap<String, (i32, i32)> = HashMap::new();
+    let mut device = None;
+    for line in output.lines() {
+        if let Some(path) = line.trim().strip_prefix("add device").and_then(|rest| rest.split_once(':')).map(|(_, p)| p.trim()) {
+            device = Some(path.to_string());
+            continue;
+        }
+        let Some(device) = &device else { continue };
+        let max = line.split("max ").nth(1).and_then(|rest| rest.split(',').next()).and_then(|n| n.trim().parse::<i32>().ok());
+        let Some(max) = max else { continue };
+        if line.contains("ABS_MT_POSITION_X") {
+            ranges.entry(device.clone()).or_default().0 = max;
+        } else if line.contains("ABS_MT_POSITION_Y") {
+            ranges.entry(device.clone()).or_default().1 = max;
+        }
+    }
+    ranges
+}
+
+/// 识别出的手势（坐标已换算为屏幕像素）
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Gesture {
+    Tap { x: i32, y: i32 },
+    LongPress { x: i32, y: i32, duration_ms: u64 },
+    Swipe { start_x: i32, start_y: i32, end_x: i32, end_y: i32, duration_ms: u64 },
+    Key { name: String, key_code: i32 },
+}
+
+/// 录制关心的物理按键 → Android keycode
+fn key_code(name: &str) -> Option<i32> {
+    Some(match name {
+        "KEY_HOME" | "KEY_HOMEPAGE" => 3,
+        "KEY_BACK" => 4,
+        "KEY_VOLUMEUP" => 24,
+        "KEY_VOLUMEDOWN" => 25,
+        "KEY_POWER" => 26,
+        "KEY_ENTER" => 66,
+        "KEY_BACKSPACE" => 67,
+        "KEY_MENU" => 82,
+        "KEY_APPSELECT" => 187,
+        _ => return None,
+    })
+}
+
+/// 单指手势识别（多指触摸只跟踪 0 号触点）
+pub struct GestureDetector {
+    screen: (i32, i32),
+    ranges: HashMap<String, (i32, i32)>,
+    slot: i32,
+    raw: (i32, i32),
+    down_pending: bool,
+    up_pending: bool,
+    /// (按下时间, 起点) 与最近位置
+    start: Option<(f64, (i32, i32))>,
+    last: (i32, i32),
+}
+
+impl GestureDetector {
+    pub fn new(screen: (i32, i32), ranges: HashMap<String, (i32, i32)>) -> Self {
+        Self { screen, ranges, slot: 0, raw: (0, 0), down_pending: false, up_pending: false, start: None, last: (0, 0) }
+    }
+
+    /// 原始坐标换算为屏幕像素；未知坐标范围时按原值处理
+    fn to_screen(&self, device: &str) -> (i32, i32) {
+        let scale = |raw: i32, max: i32, size: i32| if max > 0 && size > 0 { (raw as i64 * size as i64 / (max as i64 + 1)) as i32 } else { raw };
+        let (max_x, max_y) = self.ranges.get(device).copied().unwrap_or_default();
+        (scale(self.raw.0, max_x, self.screen.0), scale(self.raw.1, max_y, self.screen.1))
+    }
+
+    pub fn feed(&mut self, event: &RawEvent) -> Option<Gesture> {
+        let hex = || i64::from_str_radix(&event.value, 16).ok();
+        match (event.kind.as_str(), event.code.as_str()) {
+            ("EV_ABS", "ABS_MT_SLOT") => self.slot = hex().unwrap_or(0) as i32,
+            ("EV_ABS", "ABS_MT_POSITION_X") if self.slot == 0 => self.raw.0 = hex()? as i32,
+            ("EV_ABS", "ABS_MT_POSITION_Y") if self.slot == 0 => self.raw.1 = hex()? as i32,
+            ("EV_ABS", "ABS_MT_TRACKING_ID") if self.slot == 0 => {
+                if event.value.eq_ignore_ascii_case("ffffffff") {
+                    self.up_pending = true;
+                } else {
+                    self.down_pending = true;
+                }
+            }
+            ("EV_KEY", "BTN_TOUCH") => match event.value.as_str() {
+                "DOWN" => self.down_pending = true,
+                "UP" => self.up_pending = true,
+                _ => {}
+            },
+            ("EV_KEY", name) if event.value == "UP" => {
+                return key_code(name).map(|key_code| Gesture::Key { name: name.to_string(), key_code });
+            }
+            ("EV_SYN", "SYN_REPORT") => return self.on_report(event),
+            _ => {}
+        }
+        None
+    }
+
+    fn on_report(&mut self, event: &RawEvent) -> Option<Gesture> {
+        let position = self.to_screen(&event.device);
+        if std::mem::take(&mut self.down_pending) && self.start.is_none() {
+            self.start = Some((event.time, position));
+        }
+        if self.start.is_some() {
+            self.last = position;
+        }
+        if !std::mem::take(&mut self.up_pending) {
+            return None;
+        }
+        let (started, (start_x, start_y)) = self.start.take()?;
+        let (end_x, end_y) = self.last;
+        let duration_ms = ((event.time - started).max(0.0) * 1000.0).round() as u64;
+        let moved = (end_x - start_x).abs().max((end_y - start_y).abs());
+        Some(if moved > TAP_SLOP_PX {
+            Gesture::Swipe { start_x, start_y, end_x, end_y, duration_ms: duration_ms.max(100) }
+        } else if duration_ms >= LONG_PRESS_MS {
+            Gesture::LongPress { x: start_x, y: start_y, duration_ms }
+        } else {
+            Gesture::Tap { x: start_x, y: start_y }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PROBE: &str = r#"add device 1: /dev/input/event2
+  name:     "virtio_input_multi_touch"
+  events:
+    ABS (0003): ABS_MT_SLOT           : value 0, min 0, max 9, fuzz 0, flat 0, resolution 0
+                ABS_MT_POSITION_X     : value 0, min 0, max 2159, fuzz 0, flat 0, resolution 0
+                ABS_MT_POSITION_Y     : value 0, min 0, max 3839, fuzz 0, flat 0, resolution 0
+add device 2: /dev/input/event0
+  name:     "qwerty2"
+"#;
+
+    fn run(detector: &mut GestureDetector, lines: &[&str]) -> Vec<Gesture> {
+        lines.iter().filter_map(|line| parse_line(line)).filter_map(|event| detector.feed(&event)).collect()
+    }
+
+    #[test]
+    fn detects_taps_swipes_long_presses_and_keys() {
+        let ranges = parse_abs_ranges(PROBE);
+        assert_eq!(ranges.get("/dev/input/event2"), Some(&(2159, 3839)));
+        let mut detector = GestureDetector::new((1080, 1920), ranges);
+
+        let tap = run(
+            &mut detector,
+            &[
+                "[  100.000000] /dev/input/event2: EV_ABS       ABS_MT_TRACKING_ID   00000001",
+                "[  100.000000] /dev/input/event2: EV_ABS       ABS_MT_POSITION_X    00000438",
+                "[  100.000000] /dev/input/event2: EV_ABS       ABS_MT_POSITION_Y    00000780",
+                "[  100.000000] /dev/input/event2: EV_SYN       SYN_REPORT           00000000",
+                "[  100.080000] /dev/input/event2: EV_ABS       ABS_MT_TRACKING_ID   ffffffff",
+                "[  100.080000] /dev/input/event2: EV_SYN       SYN_REPORT           00000000",
+            ],
+        );
+        assert_eq!(tap, vec![Gesture::Tap { x: 540, y: 960 }]);
+
+        let swipe = run(
+            &mut detector,
+            &[
+                "[  101.000000] /dev/input/event2: EV_KEY       BTN_TOUCH            DOWN",
+                "[  101.000000] /dev/input/event2: EV_ABS       ABS_MT_POSITION_Y    00000c80",
+                "[  101.000000] /dev/input/event2: EV_SYN       SYN_REPORT           00000000",
+                "[  101.200000] /dev/input/event2: EV_ABS       ABS_MT_POSITION_Y    00000500",
+                "[  101.200000] /dev/input/event2: EV_SYN       SYN_REPORT           00000000",
+                "[  101.300000] /dev/input/event2: EV_KEY       BTN_TOUCH            UP",
+                "[  101.300000] /dev/input/event2: EV_SYN       SYN_REPORT           00000000",
+            ],
+        );
+        assert_eq!(swipe, vec![Gesture::Swipe { start_x: 540, start_y: 1600, end_x: 540, end_y: 640, duration_ms: 300 }]);
+
+        let long_press_and_back = run(
+            &mut detector,
+            &[
+                "[  102.000000] /dev/input/event2: EV_ABS       ABS_MT_TRACKING_ID   00000002",
+                "[  102.000000] /dev/input/event2: EV_SYN       SYN_REPORT           00000000",
+                "[  102.900000] /dev/input/event2: EV_ABS       ABS_MT_TRACKING_ID   ffffffff",
+                "[  102.900000] /dev/input/event2: EV_SYN       SYN_REPORT           00000000",
+                "[  103.000000] /dev/input/event0: EV_KEY       KEY_BACK             DOWN",
+                "[  103.050000] /dev/input/event0: EV_KEY       KEY_BACK             UP",
+                "not a getevent line",
+            ],
+        );
+        assert_eq!(
+            long_press_and_back,
+            vec![
+                Gesture::LongPress { x: 540, y: 640, duration_ms: 900 },
+                Gesture::Key { name: "KEY_BACK".to_string(), key_code: 4 }
+            ]
+        );
+    }
+}
@@ -0,0 +1,325 @@
+// src-tauri/src/services/script_recorder/mod.rs
+// module: script_recorder | layer: services | role: 脚本录制
+// summary: 录制模式：持续读取设备 `getevent` 输入事件识别手势，每个手势前后抓取界面 dump 并登记为快照，转成带选择器的智能脚本步骤；停止后可导出为脚本
+
+pub mod getevent;
+pub mod steps;
+
+use std::collections::HashMap;
+use std::process::Stdio;
+use std::sync::{Mutex, MutexGuard, OnceLock};
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use tauri::{AppHandle, Runtime};
+use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio::process::Command;
+use tokio::sync::mpsc;
+use tracing::{info, warn};
+
+use self::getevent::{Gesture, GestureDetector};
+use self::steps::{RecordingBuilder, Screen};
+use crate::services::execution::model::SmartScriptStep;
+use crate::services::script_manager::SmartScript;
+use crate::utils::adb_utils::get_adb_path;
+
+/// 录制到新步骤（或输入步骤内容更新）时发出，载荷为 [`RecordingStepEvent`]
+pub const RECORDER_STEP_EVENT: &str = "script-recorder://step";
+/// 手势完成后等待界面稳定再抓取
+const SETTLE: Duration = Duration::from_millis(600);
+/// 抓不到屏幕分辨率时的默认值
+const DEFAULT_SCREEN: (i32, i32) = (1080, 1920);
+/// 保留的已结束录制数
+const MAX_FINISHED: usize = 10;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordingStatus {
+    pub recording_id: String,
+    pub device_id: String,
+    pub active: bool,
+    pub started_at: DateTime<Utc>,
+    pub ended_at: Option<DateTime<Utc>>,
+    pub steps: Vec<SmartScriptStep>,
+    /// 录制期间登记的界面快照（debug_xml 下的文件名）
+    pub snapshots: Vec<String>,
+    pub last_error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordingStepEvent {
+    pub recording_id: String,
+    pub step: SmartScriptStep,
+    /// true 表示更新已有步骤（输入内容变化）
+    pub updated: bool,
+}
+
+struct Recording {
+    device_id: String,
+    started_at: DateTime<Utc>,
+    ended_at: Option<DateTime<Utc>>,
+    builder: RecordingBuilder,
+    snapshots: Vec<String>,
+    last_error: Option<String>,
+    /// 读取 getevent 与处理手势的任务；中止读取任务会随之结束 getevent 进程
+    tasks: Vec<tauri::async_runtime::JoinHandle<()>>,
+}
+
+impl Recording {
+    fn status(&self, recording_id: &str) -> RecordingStatus {
+        RecordingStatus {
+            recording_id: recording_id.to_string(),
+            device_id: self.device_id.clone(),
+            active: self.ended_at.is_none(),
+            started_at: self.started_at,
+            ended_at: self.ended_at,
+            steps: self.builder.steps().to_vec(),
+            snapshots: self.snapshots.clone(),
+            last_error: self.last_error.clone(),
+        }
+    }
+
+    fn finish(&mut self) {
+        for task in self.tasks.drain(..) {
+            task.abort();
+        }
+        self.ended_at.get_or_insert_with(Utc::now);
+    }
+}
+
+fn recordings() -> MutexGuard<'static, HashMap<String, Recording>> {
+    static RECORDINGS: OnceLock<Mutex<HashMap<String, Recording>>> = OnceLock::new();
+    RECORDINGS.get_or_init(Mutex::default).lock().unwrap_or_else(|e| e.into_inner())
+}
+
+fn adb_command(device_id: &str, args: &[&str]) -> Command {
+    let mut cmd = Command::new(get_adb_path());
+    cmd.arg("-s").arg(device_id).arg("shell").args(args);
+    #[cfg(windows)]
+    {
+        cmd.creation_flags(0x08000000); // CREATE_NO_WINDOW
+    }
+    cmd
+}
+
+async fn shell_output(device_id: &str, args: &[&str]) -> Result<String, String> {
+    let output = adb_command(device_id, args).output().await.map_err(|e| format!("执行 adb 失败: {}", e))?;
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+async fn screen_size(device_id: &str) -> (i32, i32) {
+    let output = shell_output(device_id, &["wm", "size"]).await.unwrap_or_default();
+    let (physical, overridden) = crate::services::self_test::quirks::parse_wm_size(&output);
+    overridden.or(physical).map(|(w, h)| (w as i32, h as i32)).unwrap_or(DEFAULT_SCREEN)
+}
+
+/// 抓取界面并写入 debug_xml 目录，快照 ID 即文件名
+async fn capture<R: Runtime>(app: &AppHandle<R>, device_id: &str, recording_id: &str, seq: usize) -> Result<Screen, String> {
+    let dump = crate::modules::ui_dump::unified_dump(app, device_id).await?;
+    let xml = dump.xml_content.filter(|_| dump.success).ok_or_else(|| dump.error.unwrap_or_else(|| "界面抓取失败".to_string()))?;
+    let dir = crate::domain::analysis_cache::api::get_debug_xml_dir();
+    std::fs::create_dir_all(&dir).map_err(|e| format!("创建快照目录失败: {}", e))?;
+    let snapshot_id = format!("ui_dump_{}_{:03}.xml", recording_id, seq);
+    std::fs::write(dir.join(&snapshot_id), &xml).map_err(|e| format!("保存快照失败: {}", e))?;
+    Ok(Screen { snapshot_id, xml })
+}
+
+/// 读取 `getevent -lt` 并把识别出的手势送入通道；进程退出时记录错误
+async fn read_events(mut cmd: Command, mut detector: GestureDetector, tx: mpsc::UnboundedSender<Gesture>, recording_id: String) {
+    let spawned = cmd.stdout(Stdio::piped()).stderr(Stdio::null()).kill_on_drop(true).spawn();
+    let mut child = match spawned {
+        Ok(child) => child,
+        Err(e) => return record_error(&recording_id, format!("启动 getevent 失败: {}", e)),
+    };
+    let Some(stdout) = child.stdout.take() else { return };
+    let mut lines = BufReader::new(stdout).lines();
+    while let Ok(Some(line)) = lines.next_line().await {
+        if let Some(gesture) = getevent::parse_line(&line).and_then(|event| detector.feed(&event)) {
+            if tx.send(gesture).is_err() {
+                return;
+            }
+        }
+    }
+    record_error(&recording_id, "getevent 已退出（设备断开？）".to_string());
+}
+
+fn record_error(recording_id: &str, error: String) {
+    warn!("⚠️ 录制 {}: {}", recording_id, error);
+    if let Some(recording) = recordings().get_mut(recording_id) {
+        recording.last_error = Some(error);
+    }
+}
+
+/// 逐个处理手势：等待界面稳定后抓取，结合前一帧生成步骤并推送
+async fn process_gestures<R: Runtime>(app: AppHandle<R>, device_id: String, recording_id: String, mut rx: mpsc::UnboundedReceiver<Gesture>) {
+    let mut seq = 0;
+    let mut before = match capture(&app, &device_id, &recording_id, seq).await {
+        Ok(screen) => Some(screen),
+        Err(e) => {
+            record_error(&recording_id, e);
+            None
+        }
+    };
+    if let Some(screen) = &before {
+        if let Some(recording) = recordings().get_mut(&recording_id) {
+            recording.snapshots.push(screen.snapshot_id.clone());
+        }
+    }
+    while let Some(gesture) = rx.recv().await {
+        tokio::time::sleep(SETTLE).await;
+        // 连续操作（如打字）时跳过中间帧，只在操作停顿后抓取
+        let after = if rx.is_empty() {
+            seq += 1;
+            capture(&app, &device_id, &recording_id, seq).await.map_err(|e| record_error(&recording_id, e)).ok()
+        } else {
+            None
+        };
+        let events = {
+            let mut store = recordings();
+            let Some(recording) = store.get_mut(&recording_id) else { return };
+            if let Some(screen) = &after {
+                recording.snapshots.push(screen.snapshot_id.clone());
+            }
+            let previous: Vec<SmartScriptStep> = recording.builder.steps().to_vec();
+            recording.builder.apply(&gesture, before.as_ref(), after.as_ref());
+            changed_steps(&recording_id, &previous, recording.builder.steps())
+        };
+        for event in events {
+            let _ = crate::services::device_windows::emit_for_device(&app, &device_id, RECORDER_STEP_EVENT, event);
+        }
+        if after.is_some() {
+            before = after;
+        }
+    }
+}
+
+/// 与上一次相比新增或内容变化的步骤
+fn changed_steps(recording_id: &str, previous: &[SmartScriptStep], current: &[SmartScriptStep]) -> Vec<RecordingStepEvent> {
+    current
+        .iter()
+        .enumerate()
+        .filter_map(|(index, step)| {
+            let updated = match previous.get(index) {
+                Some(old) if old.parameters == step.parameters => return None,
+                Some(_) => true,
+                None => false,
+            };
+            Some(RecordingStepEvent { recording_id: recording_id.to_string(), step: step.clone(), updated })
+        })
+        .collect()
+}
+
+/// 开始录制设备上的手动操作（同一设备同时只允许一个录制）
+pub async fn start_recording<R: Runtime>(app: AppHandle<R>, device_id: String) -> Result<RecordingStatus, String> {
+    if let Some((id, _)) = recordings().iter().find(|(_, r)| r.device_id == device_id && r.ended_at.is_none()) {
+        return Err(format!("设备 {} 正在录制中（{}）", device_id, id));
+    }
+    let probe = shell_output(&device_id, &["getevent", "-lp"]).await?;
+    let ranges = getevent::parse_abs_ranges(&probe);
+    if ranges.is_empty() {
+        return Err(format!("设备 {} 没有可读取的触摸输入设备", device_id));
+    }
+    let (width, height) = screen_size(&device_id).await;
+    let recording_id = format!("rec_{}", Utc::now().timestamp_millis());
+    info!("⏺️ 开始录制 {}: 设备 {}, 屏幕 {}x{}", recording_id, device_id, width, height);
+
+    // 先登记再启动任务，处理任务按 ID 找得到录制
+    let status = {
+        let recording = Recording {
+            device_id: device_id.clone(),
+            started_at: Utc::now(),
+            ended_at: None,
+            builder: RecordingBuilder::new(&recording_id, height),
+            snapshots: Vec::new(),
+            last_error: None,
+            tasks: Vec::new(),
+        };
+        let status = recording.status(&recording_id);
+        recordings().insert(recording_id.clone(), recording);
+        status
+    };
+    let (tx, rx) = mpsc::unbounded_channel();
+    let reader = tauri::async_runtime::spawn(read_events(
+        adb_command(&device_id, &["getevent", "-lt"]),
+        GestureDetector::new((width, height), ranges),
+        tx,
+        recording_id.clone(),
+    ));
+    let processor = tauri::async_runtime::spawn(process_gestures(app, device_id, recording_id.clone(), rx));
+    if let Some(recording) = recordings().get_mut(&recording_id) {
+        recording.tasks = vec![reader, processor];
+    }
+    Ok(status)
+}
+
+/// 停止录制并返回录到的步骤
+pub fn stop_recording(recording_id: &str) -> Result<RecordingStatus, String> {
+    let mut store = recordings();
+    let recording = store.get_mut(recording_id).ok_or_else(|| format!("录制不存在: {}", recording_id))?;
+    recording.finish();
+    let status = recording.status(recording_id);
+    info!("⏹️ 停止录制 {}: {} 个步骤", recording_id, status.steps.len());
+
+    // 只保留最近的若干个已结束录制
+    let mut finished: Vec<(String, DateTime<Utc>)> =
+        store.iter().filter_map(|(id, r)| r.ended_at.map(|ended| (id.clone(), ended))).collect();
+    if finished.len() > MAX_FINISHED {
+        finished.sort_by_key(|(_, ended)| *ended);
+        for (id, _) in finished.iter().take(finished.len() - MAX_FINISHED) {
+            store.remove(id);
+        }
+    }
+    Ok(status)
+}
+
+pub fn get_recording(recording_id: &str) -> Result<RecordingStatus, String> {
+    recordings().get(recording_id).map(|r| r.status(recording_id)).ok_or_else(|| format!("录制不存在: {}", recording_id))
+}
+
+/// 把录制转成脚本（录制仍在进行时取当前已录到的步骤）
+pub fn build_script(recording_id: &str, name: Option<String>, description: Option<String>) -> Result<SmartScript, String> {
+    let status = get_recording(recording_id)?;
+    if status.steps.is_empty() {
+        return Err("录制中没有可导出的步骤".to_string());
+    }
+    let mut script = SmartScript::default();
+    script.name = name.filter(|n| !n.trim().is_empty()).unwrap_or_else(|| format!("录制脚本 {}", status.started_at.format("%m-%d %H:%M")));
+    script.description =
+        description.unwrap_or_else(|| format!("在设备 {} 上录制，共 {} 个步骤", status.device_id, status.steps.len()));
+    script.category = "录制".to_string();
+    script.tags = vec!["recorded".to_string()];
+    script.steps = status.steps;
+    script.metadata.insert("recordingId".to_string(), serde_json::json!(recording_id));
+    script.metadata.insert("recordedDevice".to_string(), serde_json::json!(status.device_id));
+    script.metadata.insert("snapshots".to_string(), serde_json::json!(status.snapshots));
+    Ok(script)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::execution::model::SmartActionType;
+
+    fn step(id: &str, text: &str) -> SmartScriptStep {
+        SmartScriptStep {
+            id: id.to_string(),
+            step_type: SmartActionType::Input,
+            name: id.to_string(),
+            description: String::new(),
+            parameters: serde_json::json!({ "text": text }),
+            enabled: true,
+            order: 0,
+        }
+    }
+
+    #[test]
+    fn reports_new_and_updated_steps_only() {
+        let previous = vec![step("a", "x"), step("b", "咖")];
+        let current = vec![step("a", "x"), step("b", "咖啡"), step("c", "")];
+        let events = changed_steps("rec_1", &previous, &current);
+        let summary: Vec<(&str, bool)> = events.iter().map(|e| (e.step.id.as_str(), e.updated)).collect();
+        assert_eq!(summary, vec![("b", true), ("c", false)]);
+    }
+}
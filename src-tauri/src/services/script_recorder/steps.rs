@@ -0,0 +1,311 @@
+// src-tauri/src/services/script_recorder/steps.rs
+// module: script_recorder | layer: services | role: 手势转步骤
+// summary: 把手势结合操作前后的界面 dump 转为智能脚本步骤：点击解析出可点击目标的 resource-id / 文本 / xpath 选择器，输入框内容变化合并为输入步骤，软键盘上的点击不单独成步
+
+use serde_json::{json, Map, Value};
+
+use super::getevent::Gesture;
+use crate::engine::ui_tree::{Flag, NodeRef, UiTree};
+use crate::services::execution::model::{SmartActionType, SmartScriptStep};
+
+/// 输入框保持焦点时，屏幕下方该比例以下的点击视为软键盘输入
+const IME_REGION_RATIO: f64 = 0.6;
+
+/// 一帧界面：dump 内容与它登记的快照 ID
+#[derive(Debug, Clone)]
+pub struct Screen {
+    pub snapshot_id: String,
+    pub xml: String,
+}
+
+/// 元素选择器（写入步骤参数的扁平字段）
+#[derive(Debug, Clone, PartialEq)]
+struct Selector {
+    resource_id: Option<String>,
+    text: Option<String>,
+    content_desc: Option<String>,
+    class_name: Option<String>,
+    bounds: Option<(i32, i32, i32, i32)>,
+    xpath: String,
+}
+
+impl Selector {
+    fn of(node: NodeRef<'_>) -> Self {
+        let non_empty = |s: &str| (!s.trim().is_empty()).then(|| s.to_string());
+        let resource_id = node.resource_id().map(str::to_string);
+        let text = non_empty(node.text());
+        let content_desc = non_empty(node.content_desc());
+        let quote = |value: &str| if value.contains('\'') { format!("\"{}\"", value) } else { format!("'{}'", value) };
+        let xpath = match (&resource_id, &text, &content_desc) {
+            (Some(id), Some(text), _) => format!("//*[@resource-id={}][@text={}]", quote(id), quote(text)),
+            (Some(id), None, _) => format!("//*[@resource-id={}]", quote(id)),
+            (None, Some(text), _) => format!("//*[@text={}]", quote(text)),
+            (None, None, Some(desc)) => format!("//*[@content-desc={}]", quote(desc)),
+            // 没有可用属性时退化为按层级下标定位
+            (None, None, None) => node.index_path().iter().fold("/hierarchy".to_string(), |path, index| format!("{}/node[{}]", path, index + 1)),
+        };
+        Self { resource_id, text, content_desc, class_name: node.class_name().map(str::to_string), bounds: node.bounds(), xpath }
+    }
+
+    fn has_attributes(&self) -> bool {
+        self.resource_id.is_some() || self.text.is_some() || self.content_desc.is_some()
+    }
+
+    fn write(&self, params: &mut Map<String, Value>) {
+        let mut put = |key: &str, value: &Option<String>| {
+            if let Some(value) = value {
+                params.insert(key.to_string(), json!(value));
+            }
+        };
+        put("resource_id", &self.resource_id);
+        put("text", &self.text);
+        put("content_desc", &self.content_desc);
+        put("class_name", &self.class_name);
+        if let Some((l, t, r, b)) = self.bounds {
+            params.insert("bounds".to_string(), json!(format!("[{},{}][{},{}]", l, t, r, b)));
+        }
+        params.insert("xpath".to_string(), json!(self.xpath));
+    }
+
+    /// 在另一帧中找到同一元素：resource-id + 位置，其次位置
+    fn locate<'a>(&self, tree: &'a UiTree) -> Option<NodeRef<'a>> {
+        let bounds = self.bounds?;
+        tree.nodes()
+            .filter(|node| node.bounds() == Some(bounds))
+            .max_by_key(|node| (self.resource_id.is_some() && node.resource_id() == self.resource_id.as_deref()) as u8)
+    }
+}
+
+/// 最近一次点中的输入框
+struct FocusedField {
+    selector: Selector,
+    text: String,
+    /// 已生成的输入步骤下标
+    input_step: Option<usize>,
+}
+
+/// 录制步骤构建器
+pub struct RecordingBuilder {
+    prefix: String,
+    screen_height: i32,
+    steps: Vec<SmartScriptStep>,
+    focused: Option<FocusedField>,
+    next_id: usize,
+}
+
+/// 点中位置最小的节点，向上找到第一个可点击的祖先作为操作目标
+fn target_at(tree: &UiTree, x: i32, y: i32) -> Option<NodeRef<'_>> {
+    let hit = tree.node_at(x, y)?;
+    let mut current = Some(hit);
+    while let Some(node) = current {
+        if node.flag(Flag::Clickable) || node.flag(Flag::LongClickable) {
+            return Some(node);
+        }
+        current = node.parent();
+    }
+    Some(hit)
+}
+
+fn is_edit_text(node: NodeRef<'_>) -> bool {
+    node.class_name().is_some_and(|class| class.contains("EditText"))
+}
+
+impl RecordingBuilder {
+    pub fn new(recording_id: &str, screen_height: i32) -> Self {
+        let prefix = recording_id.chars().filter(|c| c.is_ascii_alphanumeric()).take(8).collect();
+        Self { prefix, screen_height, steps: Vec::new(), focused: None, next_id: 0 }
+    }
+
+    pub fn steps(&self) -> &[SmartScriptStep] {
+        &self.steps
+    }
+
+    pub fn into_steps(self) -> Vec<SmartScriptStep> {
+        self.steps
+    }
+
+    fn push(&mut self, step_type: SmartActionType, name: String, parameters: Map<String, Value>) -> usize {
+        self.next_id += 1;
+        self.steps.push(SmartScriptStep {
+            id: format!("rec_{}_{}", self.prefix, self.next_id),
+            step_type,
+            name,
+            description: "录制生成".to_string(),
+            parameters: Value::Object(parameters),
+            enabled: true,
+            order: self.steps.len() as i32,
+        });
+        self.steps.len() - 1
+    }
+
+    /// 输入框内容变化时新增或更新输入步骤；返回内容是否变化
+    fn sync_input(&mut self, after: Option<&UiTree>) -> bool {
+        let (Some(field), Some(after)) = (self.focused.as_mut(), after) else { return false };
+        let Some(text) = field.selector.locate(after).map(|node| node.text().to_string()) else { return false };
+        if text == field.text {
+            return false;
+        }
+        field.text = text.clone();
+        match field.input_step {
+            Some(index) => {
+                let step = &mut self.steps[index];
+                step.parameters["text"] = json!(text);
+                step.name = format!("输入「{}」", text);
+            }
+            None => {
+                let mut params = Map::new();
+                field.selector.write(&mut params);
+                params.insert("text".to_string(), json!(text));
+                params.insert("clear_before".to_string(), json!(true));
+                let index = self.push(SmartActionType::Input, format!("输入「{}」", text), params);
+                if let Some(field) = self.focused.as_mut() {
+                    field.input_step = Some(index);
+                }
+            }
+        }
+        true
+    }
+
+    /// 处理一个手势；`before` 为手势发生时的界面，`after` 为手势完成后的界面（dump 来不及时为 None）
+    pub fn apply(&mut self, gesture: &Gesture, before: Option<&Screen>, after: Option<&Screen>) {
+        let before_tree = before.map(|screen| UiTree::parse_lenient(&screen.xml));
+        let after_tree = after.map(|screen| UiTree::parse_lenient(&screen.xml));
+        let typed = self.sync_input(after_tree.as_ref());
+
+        match gesture {
+            Gesture::Tap { x, y } | Gesture::LongPress { x, y, .. } => {
+                let target = before_tree.as_ref().and_then(|tree| target_at(tree, *x, *y));
+                let on_field = match (&self.focused, target) {
+                    (Some(field), Some(node)) => node.bounds() == field.selector.bounds,
+                    _ => false,
+                };
+                if let Some(field) = &self.focused {
+                    let still_focused = after_tree
+                        .as_ref()
+                        .and_then(|tree| field.selector.locate(tree))
+                        .is_some_and(|node| node.flag(Flag::Focused));
+                    let in_ime_region = *y as f64 >= self.screen_height as f64 * IME_REGION_RATIO;
+                    // 软键盘不在 dump 中：内容变化、或焦点仍在输入框且点在键盘区域，都视为打字
+                    if !on_field && (typed || (still_focused && in_ime_region)) {
+                        return;
+                    }
+                }
+                let long_press = matches!(gesture, Gesture::LongPress { .. });
+                self.push_tap(*x, *y, gesture, target, before, long_press);
+                self.focused = match target.filter(|node| is_edit_text(*node)) {
+                    Some(node) => {
+                        let selector = Selector::of(node);
+                        let text = after_tree.as_ref().and_then(|tree| selector.locate(tree)).map_or_else(|| node.text().to_string(), |n| n.text().to_string());
+                        Some(FocusedField { selector, text, input_step: None })
+                    }
+                    None if on_field => self.focused.take(),
+                    None => None,
+                };
+            }
+            Gesture::Swipe { start_x, start_y, end_x, end_y, duration_ms } => {
+                let mut params = Map::new();
+                for (key, value) in [("start_x", start_x), ("start_y", start_y), ("end_x", end_x), ("end_y", end_y)] {
+                    params.insert(key.to_string(), json!(value));
+                }
+                params.insert("duration".to_string(), json!(duration_ms));
+                let direction = if (end_y - start_y).abs() >= (end_x - start_x).abs() {
+                    if end_y < start_y { "上" } else { "下" }
+                } else if end_x < start_x {
+                    "左"
+                } else {
+                    "右"
+                };
+                self.push(SmartActionType::Swipe, format!("向{}滑动", direction), params);
+            }
+            Gesture::Key { name, key_code } => {
+                // 实体键盘的退格已体现在输入内容里
+                if typed && *key_code == 67 {
+                    return;
+                }
+                let mut params = Map::new();
+                params.insert("key_code".to_string(), json!(key_code));
+                self.push(SmartActionType::KeyEvent, format!("按键 {}", name.trim_start_matches("KEY_")), params);
+                if *key_code == 4 {
+                    self.focused = None;
+                }
+            }
+        }
+    }
+
+    fn push_tap(&mut self, x: i32, y: i32, gesture: &Gesture, target: Option<NodeRef<'_>>, before: Option<&Screen>, long_press: bool) {
+        let mut params = Map::new();
+        params.insert("x".to_string(), json!(x));
+        params.insert("y".to_string(), json!(y));
+        if let Gesture::LongPress { duration_ms, .. } = gesture {
+            params.insert("duration_ms".to_string(), json!(duration_ms));
+        }
+        let selector = target.map(Selector::of).filter(Selector::has_attributes);
+        if let Some(selector) = &selector {
+            selector.write(&mut params);
+            if let Some(screen) = before {
+                params.insert("xmlCacheId".to_string(), json!(screen.snapshot_id));
+            }
+        }
+        let label = selector
+            .as_ref()
+            .and_then(|s| s.text.clone().or_else(|| s.content_desc.clone()).or_else(|| s.resource_id.clone()))
+            .unwrap_or_else(|| format!("({}, {})", x, y));
+        let (step_type, name) = match (long_press, selector.is_some()) {
+            (true, _) => (SmartActionType::LongPress, format!("长按 {}", label)),
+            (false, true) => (SmartActionType::SmartTap, format!("点击 {}", label)),
+            (false, false) => (SmartActionType::Tap, format!("点击 {}", label)),
+        };
+        self.push(step_type, name, params);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn screen(id: &str, field_text: &str, focused: bool) -> Screen {
+        let xml = format!(
+            r#"<hierarchy rotation="0">
+  <node index="0" text="" class="android.widget.FrameLayout" package="com.app" bounds="[0,0][1080,1920]" clickable="false">
+    <node index="0" text="{field_text}" resource-id="com.app:id/search_box" class="android.widget.EditText" package="com.app" bounds="[40,100][1040,200]" clickable="true" focused="{focused}" />
+    <node index="1" text="" resource-id="com.app:id/search_btn" class="android.widget.FrameLayout" package="com.app" bounds="[40,300][540,400]" clickable="true">
+      <node index="0" text="搜索" class="android.widget.TextView" package="com.app" bounds="[60,320][200,380]" clickable="false" />
+    </node>
+  </node>
+</hierarchy>"#
+        );
+        Screen { snapshot_id: id.to_string(), xml }
+    }
+
+    #[test]
+    fn builds_selector_steps_and_merges_typing_into_input() {
+        let mut builder = RecordingBuilder::new("rec-1234abcd", 1920);
+        let idle = screen("s0", "", false);
+        let focused = screen("s1", "", true);
+        builder.apply(&Gesture::Tap { x: 500, y: 150 }, Some(&idle), Some(&focused));
+        // 软键盘打字：输入框内容变化，点击本身不成步
+        builder.apply(&Gesture::Tap { x: 300, y: 1700 }, Some(&focused), Some(&screen("s2", "咖", true)));
+        builder.apply(&Gesture::Tap { x: 320, y: 1750 }, Some(&focused), Some(&screen("s3", "咖啡", true)));
+        // 键盘区域内未改变内容的点击（如切换大小写）也忽略
+        builder.apply(&Gesture::Tap { x: 100, y: 1800 }, Some(&focused), Some(&screen("s4", "咖啡", true)));
+        // 点中按钮内部的文本，目标提升到可点击的父节点
+        builder.apply(&Gesture::Tap { x: 100, y: 350 }, Some(&screen("s4", "咖啡", true)), Some(&screen("s5", "咖啡", false)));
+        builder.apply(&Gesture::Swipe { start_x: 540, start_y: 1600, end_x: 540, end_y: 600, duration_ms: 300 }, None, None);
+        builder.apply(&Gesture::Key { name: "KEY_BACK".into(), key_code: 4 }, None, None);
+
+        let steps = builder.into_steps();
+        let names: Vec<&str> = steps.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names.len(), 5, "{:?}", names);
+        assert!(matches!(steps[0].step_type, SmartActionType::SmartTap));
+        assert_eq!(steps[0].parameters["xpath"], "//*[@resource-id='com.app:id/search_box']");
+        assert_eq!(steps[0].parameters["xmlCacheId"], "s0");
+        assert!(matches!(steps[1].step_type, SmartActionType::Input));
+        assert_eq!(steps[1].parameters["text"], "咖啡");
+        assert_eq!(steps[1].parameters["resource_id"], "com.app:id/search_box");
+        assert_eq!(steps[2].parameters["resource_id"], "com.app:id/search_btn");
+        assert_eq!(steps[2].parameters["xpath"], "//*[@resource-id='com.app:id/search_btn']");
+        assert_eq!(steps[3].name, "向上滑动");
+        assert_eq!(steps[4].parameters["key_code"], 4);
+        assert_eq!(steps.iter().map(|s| s.order).collect::<Vec<_>>(), vec![0, 1, 2, 3, 4]);
+    }
+}
@@ -0,0 +1,173 @@
+// src-tauri/src/services/script_scheduler/cron.rs
+// module: script_scheduler | layer: services | role: cron 表达式
+// summary: 解析五段式 cron（分 时 日 月 周，支持 * / , - 与 @daily 等别名），按本地时间计算下次触发时间
+
+use chrono::{DateTime, Datelike, Duration, Local, NaiveDate, NaiveTime, TimeZone, Timelike};
+
+/// 向后搜索的最长天数（覆盖 2 月 29 日这类四年一遇的表达式）
+const MAX_SEARCH_DAYS: i64 = 366 * 4 + 1;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CronExpr {
+    minutes: u64,
+    hours: u64,
+    days_of_month: u64,
+    months: u64,
+    /// 0 = 周日
+    days_of_week: u64,
+    /// 日、周两段都被限定时按标准 cron 取并集
+    dom_restricted: bool,
+    dow_restricted: bool,
+}
+
+impl CronExpr {
+    pub fn parse(expr: &str) -> Result<Self, String> {
+        let normalized = match expr.trim() {
+            "@hourly" => "0 * * * *",
+            "@daily" | "@midnight" => "0 0 * * *",
+            "@weekly" => "0 0 * * 0",
+            "@monthly" => "0 0 1 * *",
+            "@yearly" | "@annually" => "0 0 1 1 *",
+            other => other,
+        };
+        let fields: Vec<&str> = normalized.split_whitespace().collect();
+        if fields.len() != 5 {
+            return Err(format!("cron 表达式应为 5 段（分 时 日 月 周）: {}", expr));
+        }
+        let mut days_of_week = parse_field(fields[4], 0, 7, "周")?;
+        // 7 与 0 都表示周日
+        if days_of_week & (1 << 7) != 0 {
+            days_of_week = (days_of_week & !(1 << 7)) | 1;
+        }
+        Ok(Self {
+            minutes: parse_field(fields[0], 0, 59, "分")?,
+            hours: parse_field(fields[1], 0, 23, "时")?,
+            days_of_month: parse_field(fields[2], 1, 31, "日")?,
+            months: parse_field(fields[3], 1, 12, "月")?,
+            days_of_week,
+            dom_restricted: fields[2] != "*",
+            dow_restricted: fields[4] != "*",
+        })
+    }
+
+    fn day_matches(&self, date: NaiveDate) -> bool {
+        if self.months & (1 << date.month()) == 0 {
+            return false;
+        }
+        let dom = self.days_of_month & (1 << date.day()) != 0;
+        let dow = self.days_of_week & (1 << date.weekday().num_days_from_sunday()) != 0;
+        match (self.dom_restricted, self.dow_restricted) {
+            (true, true) => dom || dow,
+            (true, false) => dom,
+            (false, true) => dow,
+            (false, false) => true,
+        }
+    }
+
+    /// 严格晚于 `after` 的下一次触发时间；夏令时跳过的本地时间不触发
+    pub fn next_after(&self, after: DateTime<Local>) -> Option<DateTime<Local>> {
+        let start = after.naive_local().with_second(0)?.with_nanosecond(0)? + Duration::minutes(1);
+        let mut date = start.date();
+        for _ in 0..MAX_SEARCH_DAYS {
+            if self.day_matches(date) {
+                let from = if date == start.date() { start.time() } else { NaiveTime::from_hms_opt(0, 0, 0)? };
+                for hour in from.hour()..24 {
+                    if self.hours & (1 << hour) == 0 {
+                        continue;
+                    }
+                    let first_minute = if hour == from.hour() { from.minute() } else { 0 };
+                    for minute in first_minute..60 {
+                        if self.minutes & (1 << minute) == 0 {
+                            continue;
+                        }
+                        let candidate = date.and_hms_opt(hour, minute, 0)?;
+                        if let Some(at) = Local.from_local_datetime(&candidate).earliest() {
+                            return Some(at);
+                        }
+                    }
+                }
+            }
+            date = date.succ_opt()?;
+        }
+        None
+    }
+
+    /// 接下来的若干次触发时间
+    pub fn upcoming(&self, after: DateTime<Local>, count: usize) -> Vec<DateTime<Local>> {
+        let mut times = Vec::with_capacity(count);
+        let mut cursor = after;
+        while times.len() < count {
+            match self.next_after(cursor) {
+                Some(next) => {
+                    times.push(next);
+                    cursor = next;
+                }
+                None => break,
+            }
+        }
+        times
+    }
+}
+
+/// 单段解析为位图：`*`、`*/n`、`a`、`a-b`、`a-b/n`，以逗号组合
+fn parse_field(field: &str, min: u32, max: u32, name: &str) -> Result<u64, String> {
+    let invalid = || format!("cron 「{}」段无效: {}（取值 {}-{}）", name, field, min, max);
+    let mut bits = 0u64;
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => (range, step.parse::<u32>().map_err(|_| invalid())?),
+            None => (part, 1),
+        };
+        if step == 0 {
+            return Err(invalid());
+        }
+        let (lo, hi) = if range == "*" {
+            (min, max)
+        } else if let Some((lo, hi)) = range.split_once('-') {
+            (lo.parse().map_err(|_| invalid())?, hi.parse().map_err(|_| invalid())?)
+        } else {
+            let value: u32 = range.parse().map_err(|_| invalid())?;
+            // `a/n` 表示从 a 开始到上限
+            (value, if part.contains('/') { max } else { value })
+        };
+        if lo < min || hi > max || lo > hi {
+            return Err(invalid());
+        }
+        for value in (lo..=hi).step_by(step as usize) {
+            bits |= 1 << value;
+        }
+    }
+    Ok(bits)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn local(y: i32, m: u32, d: u32, h: u32, min: u32) -> DateTime<Local> {
+        Local.with_ymd_and_hms(y, m, d, h, min, 0).earliest().unwrap()
+    }
+
+    #[test]
+    fn computes_next_fire_times() {
+        let every_15 = CronExpr::parse("*/15 9-10 * * 1-5").unwrap();
+        // 2024-06-07 是周五
+        assert_eq!(every_15.next_after(local(2024, 6, 7, 9, 20)), Some(local(2024, 6, 7, 9, 30)));
+        assert_eq!(every_15.next_after(local(2024, 6, 7, 10, 45)), Some(local(2024, 6, 10, 9, 0)));
+        assert_eq!(
+            every_15.upcoming(local(2024, 6, 7, 10, 10), 3),
+            vec![local(2024, 6, 7, 10, 15), local(2024, 6, 7, 10, 30), local(2024, 6, 7, 10, 45)]
+        );
+
+        // 日与周同时限定时取并集；7 表示周日
+        let dom_or_sunday = CronExpr::parse("0 8 1 * 7").unwrap();
+        assert_eq!(dom_or_sunday.next_after(local(2024, 6, 7, 12, 0)), Some(local(2024, 6, 9, 8, 0)));
+        assert_eq!(dom_or_sunday.next_after(local(2024, 6, 30, 9, 0)), Some(local(2024, 7, 1, 8, 0)));
+
+        assert_eq!(CronExpr::parse("@daily").unwrap(), CronExpr::parse("0 0 * * *").unwrap());
+        assert_eq!(CronExpr::parse("0 0 29 2 *").unwrap().next_after(local(2024, 3, 1, 0, 0)), Some(local(2028, 2, 29, 0, 0)));
+        for bad in ["* * * *", "60 * * * *", "*/0 * * * *", "5-1 * * * *", "a * * * *"] {
+            assert!(CronExpr::parse(bad).is_err(), "{}", bad);
+        }
+    }
+}
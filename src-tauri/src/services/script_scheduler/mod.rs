@@ -0,0 +1,567 @@
+// src-tauri/src/services/script_scheduler/mod.rs
+// module: script_scheduler | layer: services | role: 智能脚本定时执行
+// summary: 按设备登记脚本的 cron 计划并持久化到 SQLite；后台循环到点执行，运行开始 / 结束 / 失败均推送事件，重启后补跑宽限期内错过的计划
+
+pub mod cron;
+
+use chrono::{DateTime, Duration, Local, Utc};
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::{Mutex, OnceLock};
+use tauri::{AppHandle, Manager, Runtime};
+use tracing::{info, warn};
+
+use crate::services::script_manager::ScriptManagerState;
+use cron::CronExpr;
+
+const DB_FILE_NAME: &str = "script_schedules.db";
+const SCHEDULER_TICK: std::time::Duration = std::time::Duration::from_secs(20);
+/// 应用关闭期间错过的触发，在该宽限期内的启动后补跑一次，更早的记为错过
+const MISFIRE_GRACE_MINUTES: i64 = 60;
+const MAX_RUNS_PER_SCHEDULE: usize = 200;
+/// 晚于计划时间超过该秒数才开始的运行标记为补跑
+const CATCH_UP_AFTER_SECS: i64 = 60;
+
+pub const SCHEDULE_RUN_EVENT: &str = "script-schedule://run";
+
+static STORE: OnceLock<Mutex<ScheduleStore>> = OnceLock::new();
+
+/// 脚本定时计划
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ScriptSchedule {
+    pub id: String,
+    pub script_id: String,
+    pub device_id: String,
+    pub cron: String,
+    pub enabled: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub next_run_at: Option<DateTime<Utc>>,
+    pub last_run_at: Option<DateTime<Utc>>,
+    pub last_status: Option<ScheduleRunStatus>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ScheduleRunStatus {
+    Running,
+    Succeeded,
+    Failed,
+    /// 上次运行尚未结束，本次跳过
+    Skipped,
+    /// 应用关闭期间错过且超出补跑宽限期
+    Missed,
+}
+
+impl ScheduleRunStatus {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Running => "running",
+            Self::Succeeded => "succeeded",
+            Self::Failed => "failed",
+            Self::Skipped => "skipped",
+            Self::Missed => "missed",
+        }
+    }
+
+    fn parse(value: &str) -> Option<Self> {
+        [Self::Running, Self::Succeeded, Self::Failed, Self::Skipped, Self::Missed]
+            .into_iter()
+            .find(|status| status.as_str() == value)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ScheduleTrigger {
+    Cron,
+    /// 启动时补跑
+    CatchUp,
+    Manual,
+}
+
+impl ScheduleTrigger {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Cron => "cron",
+            Self::CatchUp => "catch_up",
+            Self::Manual => "manual",
+        }
+    }
+
+    fn parse(value: &str) -> Self {
+        match value {
+            "catch_up" => Self::CatchUp,
+            "manual" => Self::Manual,
+            _ => Self::Cron,
+        }
+    }
+}
+
+/// 单次运行记录，同时作为 [`SCHEDULE_RUN_EVENT`] 的载荷
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ScheduleRun {
+    pub id: String,
+    pub schedule_id: String,
+    pub script_id: String,
+    pub device_id: String,
+    pub trigger: ScheduleTrigger,
+    pub status: ScheduleRunStatus,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: Option<DateTime<Utc>>,
+    pub message: Option<String>,
+}
+
+/// 计划的可修改字段
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScheduleUpdate {
+    pub device_id: Option<String>,
+    pub cron: Option<String>,
+    pub enabled: Option<bool>,
+}
+
+pub struct ScheduleStore {
+    conn: Connection,
+}
+
+fn ts(value: DateTime<Utc>) -> String {
+    value.to_rfc3339()
+}
+
+fn parse_ts(value: Option<String>) -> Option<DateTime<Utc>> {
+    value.and_then(|v| DateTime::parse_from_rfc3339(&v).ok()).map(|v| v.with_timezone(&Utc))
+}
+
+fn next_fire(cron: &str, after: DateTime<Utc>) -> Result<Option<DateTime<Utc>>, String> {
+    Ok(CronExpr::parse(cron)?.next_after(after.with_timezone(&Local)).map(|t| t.with_timezone(&Utc)))
+}
+
+const SCHEDULE_COLUMNS: &str =
+    "id, script_id, device_id, cron, enabled, created_at, updated_at, next_run_at, last_run_at, last_status";
+const RUN_COLUMNS: &str = "id, schedule_id, script_id, device_id, run_trigger, status, started_at, finished_at, message";
+
+fn schedule_from_row(row: &Row) -> rusqlite::Result<ScriptSchedule> {
+    Ok(ScriptSchedule {
+        id: row.get(0)?,
+        script_id: row.get(1)?,
+        device_id: row.get(2)?,
+        cron: row.get(3)?,
+        enabled: row.get(4)?,
+        created_at: parse_ts(row.get(5)?).unwrap_or_else(Utc::now),
+        updated_at: parse_ts(row.get(6)?).unwrap_or_else(Utc::now),
+        next_run_at: parse_ts(row.get(7)?),
+        last_run_at: parse_ts(row.get(8)?),
+        last_status: row.get::<_, Option<String>>(9)?.as_deref().and_then(ScheduleRunStatus::parse),
+    })
+}
+
+fn run_from_row(row: &Row) -> rusqlite::Result<ScheduleRun> {
+    Ok(ScheduleRun {
+        id: row.get(0)?,
+        schedule_id: row.get(1)?,
+        script_id: row.get(2)?,
+        device_id: row.get(3)?,
+        trigger: ScheduleTrigger::parse(&row.get::<_, String>(4)?),
+        status: ScheduleRunStatus::parse(&row.get::<_, String>(5)?).unwrap_or(ScheduleRunStatus::Failed),
+        started_at: parse_ts(row.get(6)?).unwrap_or_else(Utc::now),
+        finished_at: parse_ts(row.get(7)?),
+        message: row.get(8)?,
+    })
+}
+
+impl ScheduleStore {
+    pub fn global() -> &'static Mutex<ScheduleStore> {
+        STORE.get_or_init(|| {
+            let dir = dirs::data_dir().unwrap_or_else(|| PathBuf::from(".")).join("employee-gui");
+            let opened = std::fs::create_dir_all(&dir)
+                .map_err(|e| e.to_string())
+                .and_then(|_| Connection::open(dir.join(DB_FILE_NAME)).map_err(|e| e.to_string()))
+                .and_then(|conn| Self::new(conn).map_err(|e| e.to_string()));
+            let store = opened.unwrap_or_else(|e| {
+                warn!("⚠️ 定时计划库打开失败，改用内存库: {}", e);
+                Self::in_memory()
+            });
+            Mutex::new(store)
+        })
+    }
+
+    pub fn new(conn: Connection) -> rusqlite::Result<Self> {
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS script_schedules (
+                id TEXT PRIMARY KEY,
+                script_id TEXT NOT NULL,
+                device_id TEXT NOT NULL,
+                cron TEXT NOT NULL,
+                enabled INTEGER NOT NULL DEFAULT 1,
+                created_at TEXT NOT NULL,
+                updated_at TEXT NOT NULL,
+                next_run_at TEXT,
+                last_run_at TEXT,
+                last_status TEXT
+            );
+            CREATE TABLE IF NOT EXISTS schedule_runs (
+                id TEXT PRIMARY KEY,
+                schedule_id TEXT NOT NULL,
+                script_id TEXT NOT NULL,
+                device_id TEXT NOT NULL,
+                run_trigger TEXT NOT NULL,
+                status TEXT NOT NULL,
+                started_at TEXT NOT NULL,
+                finished_at TEXT,
+                message TEXT
+            );
+            CREATE INDEX IF NOT EXISTS idx_schedule_runs_schedule ON schedule_runs(schedule_id, started_at);",
+        )?;
+        Ok(Self { conn })
+    }
+
+    pub fn in_memory() -> Self {
+        Self::new(Connection::open_in_memory().expect("open in-memory sqlite")).expect("init schedule schema")
+    }
+
+    pub fn create(&self, script_id: &str, device_id: &str, cron: &str, enabled: bool, now: DateTime<Utc>) -> Result<ScriptSchedule, String> {
+        let cron = cron.trim();
+        let next_run_at = next_fire(cron, now)?.filter(|_| enabled);
+        let schedule = ScriptSchedule {
+            id: format!("schedule_{}", uuid::Uuid::new_v4().simple()),
+            script_id: script_id.to_string(),
+            device_id: device_id.to_string(),
+            cron: cron.to_string(),
+            enabled,
+            created_at: now,
+            updated_at: now,
+            next_run_at,
+            last_run_at: None,
+            last_status: None,
+        };
+        self.conn
+            .execute(
+                &format!("INSERT INTO script_schedules ({}) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, NULL, NULL)", SCHEDULE_COLUMNS),
+                params![
+                    schedule.id,
+                    schedule.script_id,
+                    schedule.device_id,
+                    schedule.cron,
+                    schedule.enabled,
+                    ts(now),
+                    ts(now),
+                    schedule.next_run_at.map(ts)
+                ],
+            )
+            .map_err(|e| format!("保存定时计划失败: {}", e))?;
+        Ok(schedule)
+    }
+
+    pub fn get(&self, id: &str) -> Result<Option<ScriptSchedule>, String> {
+        self.conn
+            .query_row(&format!("SELECT {} FROM script_schedules WHERE id = ?1", SCHEDULE_COLUMNS), params![id], schedule_from_row)
+            .optional()
+            .map_err(|e| e.to_string())
+    }
+
+    pub fn list(&self) -> Result<Vec<ScriptSchedule>, String> {
+        let mut stmt = self
+            .conn
+            .prepare(&format!("SELECT {} FROM script_schedules ORDER BY created_at", SCHEDULE_COLUMNS))
+            .map_err(|e| e.to_string())?;
+        let rows = stmt.query_map([], schedule_from_row).map_err(|e| e.to_string())?;
+        rows.collect::<rusqlite::Result<Vec<_>>>().map_err(|e| e.to_string())
+    }
+
+    /// 修改计划；cron 或启用状态变化时从 `now` 重新计算下次触发
+    pub fn update(&self, id: &str, update: ScheduleUpdate, now: DateTime<Utc>) -> Result<ScriptSchedule, String> {
+        let mut schedule = self.get(id)?.ok_or_else(|| format!("定时计划不存在: {}", id))?;
+        if let Some(device_id) = update.device_id {
+            schedule.device_id = device_id;
+        }
+        if let Some(cron) = update.cron {
+            schedule.cron = cron.trim().to_string();
+        }
+        if let Some(enabled) = update.enabled {
+            schedule.enabled = enabled;
+        }
+        schedule.next_run_at = if schedule.enabled { next_fire(&schedule.cron, now)? } else { None };
+        schedule.updated_at = now;
+        self.conn
+            .execute(
+                "UPDATE script_schedules SET device_id = ?2, cron = ?3, enabled = ?4, next_run_at = ?5, updated_at = ?6 WHERE id = ?1",
+                params![id, schedule.device_id, schedule.cron, schedule.enabled, schedule.next_run_at.map(ts), ts(now)],
+            )
+            .map_err(|e| e.to_string())?;
+        Ok(schedule)
+    }
+
+    pub fn delete(&self, id: &str) -> Result<bool, String> {
+        self.conn.execute("DELETE FROM schedule_runs WHERE schedule_id = ?1", params![id]).map_err(|e| e.to_string())?;
+        let removed = self.conn.execute("DELETE FROM script_schedules WHERE id = ?1", params![id]).map_err(|e| e.to_string())?;
+        Ok(removed > 0)
+    }
+
+    /// 运行记录（新的在前）
+    pub fn runs(&self, schedule_id: Option<&str>, limit: usize) -> Result<Vec<ScheduleRun>, String> {
+        let mut stmt = self
+            .conn
+            .prepare(&format!(
+                "SELECT {} FROM schedule_runs WHERE ?1 IS NULL OR schedule_id = ?1 ORDER BY started_at DESC LIMIT ?2",
+                RUN_COLUMNS
+            ))
+            .map_err(|e| e.to_string())?;
+        let rows = stmt.query_map(params![schedule_id, limit as i64], run_from_row).map_err(|e| e.to_string())?;
+        rows.collect::<rusqlite::Result<Vec<_>>>().map_err(|e| e.to_string())
+    }
+
+    fn has_running(&self, schedule_id: &str) -> Result<bool, String> {
+        self.conn
+            .query_row(
+                "SELECT COUNT(*) FROM schedule_runs WHERE schedule_id = ?1 AND status = 'running'",
+                params![schedule_id],
+                |row| row.get::<_, i64>(0),
+            )
+            .map(|count| count > 0)
+            .map_err(|e| e.to_string())
+    }
+
+    fn insert_run(&self, schedule: &ScriptSchedule, trigger: ScheduleTrigger, status: ScheduleRunStatus, message: Option<String>, now: DateTime<Utc>) -> Result<ScheduleRun, String> {
+        let finished = status != ScheduleRunStatus::Running;
+        let run = ScheduleRun {
+            id: format!("run_{}", uuid::Uuid::new_v4().simple()),
+            schedule_id: schedule.id.clone(),
+            script_id: schedule.script_id.clone(),
+            device_id: schedule.device_id.clone(),
+            trigger,
+            status,
+            started_at: now,
+            finished_at: finished.then_some(now),
+            message,
+        };
+        self.conn
+            .execute(
+                &format!("INSERT INTO schedule_runs ({}) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)", RUN_COLUMNS),
+                params![
+                    run.id,
+                    run.schedule_id,
+                    run.script_id,
+                    run.device_id,
+                    trigger.as_str(),
+                    status.as_str(),
+                    ts(now),
+                    run.finished_at.map(ts),
+                    run.message
+                ],
+            )
+            .map_err(|e| e.to_string())?;
+        self.conn
+            .execute(
+                "UPDATE script_schedules SET last_run_at = ?2, last_status = ?3 WHERE id = ?1",
+                params![schedule.id, ts(now), status.as_str()],
+            )
+            .map_err(|e| e.to_string())?;
+        self.prune_runs(&schedule.id)?;
+        Ok(run)
+    }
+
+    /// 结束运行并同步计划的最近状态
+    pub fn finish_run(&self, run_id: &str, status: ScheduleRunStatus, message: Option<String>, now: DateTime<Utc>) -> Result<ScheduleRun, String> {
+        self.conn
+            .execute(
+                "UPDATE schedule_runs SET status = ?2, finished_at = ?3, message = ?4 WHERE id = ?1",
+                params![run_id, status.as_str(), ts(now), message],
+            )
+            .map_err(|e| e.to_string())?;
+        let run = self
+            .conn
+            .query_row(&format!("SELECT {} FROM schedule_runs WHERE id = ?1", RUN_COLUMNS), params![run_id], run_from_row)
+            .map_err(|e| format!("运行记录不存在: {} ({})", run_id, e))?;
+        self.conn
+            .execute("UPDATE script_schedules SET last_status = ?2 WHERE id = ?1", params![run.schedule_id, status.as_str()])
+            .map_err(|e| e.to_string())?;
+        Ok(run)
+    }
+
+    fn prune_runs(&self, schedule_id: &str) -> Result<(), String> {
+        self.conn
+            .execute(
+                "DELETE FROM schedule_runs WHERE schedule_id = ?1 AND id NOT IN (
+                    SELECT id FROM schedule_runs WHERE schedule_id = ?1 ORDER BY started_at DESC LIMIT ?2
+                )",
+                params![schedule_id, MAX_RUNS_PER_SCHEDULE as i64],
+            )
+            .map_err(|e| e.to_string())?;
+        Ok(())
+    }
+
+    /// 启动时调用：上次退出时仍在运行的记录标记为失败
+    pub fn recover_interrupted(&self, now: DateTime<Utc>) -> Result<Vec<ScheduleRun>, String> {
+        let interrupted: Vec<String> = {
+            let mut stmt = self.conn.prepare("SELECT id FROM schedule_runs WHERE status = 'running'").map_err(|e| e.to_string())?;
+            let rows = stmt.query_map([], |row| row.get::<_, String>(0)).map_err(|e| e.to_string())?;
+            rows.collect::<rusqlite::Result<Vec<_>>>().map_err(|e| e.to_string())?
+        };
+        interrupted
+            .iter()
+            .map(|id| self.finish_run(id, ScheduleRunStatus::Failed, Some("应用退出导致运行中断".to_string()), now))
+            .collect()
+    }
+
+    /// 开始一次运行：登记运行记录；同一计划已有运行中的记录时记为跳过
+    pub fn begin_run(&self, schedule: &ScriptSchedule, trigger: ScheduleTrigger, now: DateTime<Utc>) -> Result<ScheduleRun, String> {
+        if self.has_running(&schedule.id)? {
+            return self.insert_run(schedule, trigger, ScheduleRunStatus::Skipped, Some("上次运行尚未结束".to_string()), now);
+        }
+        self.insert_run(schedule, trigger, ScheduleRunStatus::Running, None, now)
+    }
+
+    /// 取出到点的计划并推进下次触发时间，返回本轮登记的运行记录
+    ///
+    /// 错过的多次触发只补跑一次；错过超过宽限期的记为 [`ScheduleRunStatus::Missed`]
+    pub fn take_due(&self, now: DateTime<Utc>) -> Result<Vec<ScheduleRun>, String> {
+        let mut runs = Vec::new();
+        for schedule in self.list()? {
+            let Some(due_at) = schedule.next_run_at.filter(|at| schedule.enabled && *at <= now) else {
+                continue;
+            };
+            let next = next_fire(&schedule.cron, now).unwrap_or(None);
+            self.conn
+                .execute("UPDATE script_schedules SET next_run_at = ?2 WHERE id = ?1", params![schedule.id, next.map(ts)])
+                .map_err(|e| e.to_string())?;
+
+            let late = now - due_at;
+            let run = if late > Duration::minutes(MISFIRE_GRACE_MINUTES) {
+                let message = format!("错过 {} 的触发（应用未运行）", due_at.with_timezone(&Local).format("%Y-%m-%d %H:%M"));
+                self.insert_run(&schedule, ScheduleTrigger::CatchUp, ScheduleRunStatus::Missed, Some(message), now)?
+            } else {
+                let trigger = if late > Duration::seconds(CATCH_UP_AFTER_SECS) { ScheduleTrigger::CatchUp } else { ScheduleTrigger::Cron };
+                self.begin_run(&schedule, trigger, now)?
+            };
+            runs.push(run);
+        }
+        Ok(runs)
+    }
+}
+
+fn emit_run<R: Runtime>(app: &AppHandle<R>, run: &ScheduleRun) {
+    crate::services::event_outbox::emit(app, SCHEDULE_RUN_EVENT, &run.schedule_id, run);
+}
+
+fn finish<R: Runtime>(app: &AppHandle<R>, run_id: &str, status: ScheduleRunStatus, message: String) {
+    let finished = ScheduleStore::global()
+        .lock()
+        .map_err(|e| e.to_string())
+        .and_then(|store| store.finish_run(run_id, status, Some(message), Utc::now()));
+    match finished {
+        Ok(run) => emit_run(app, &run),
+        Err(e) => warn!("⚠️ 记录定时运行结果失败: {}", e),
+    }
+}
+
+/// 加载脚本并在计划的设备上执行（设备占用由执行器的并发控制负责）
+async fn execute_run<R: Runtime>(app: AppHandle<R>, run: ScheduleRun) {
+    let script = match app.try_state::<ScriptManagerState>() {
+        Some(state) => state.0.lock().load_script(&run.script_id).map_err(|e| format!("加载脚本失败: {}", e)),
+        None => Err("脚本管理服务未初始化".to_string()),
+    };
+    let script = match script {
+        Ok(script) => script,
+        Err(e) => return finish(&app, &run.id, ScheduleRunStatus::Failed, e),
+    };
+    info!("⏰ 定时执行脚本: schedule={}, script={}, device={}", run.schedule_id, script.name, run.device_id);
+    let outcome = crate::services::commands::execute_smart_automation_script(
+        run.device_id.clone(),
+        script.steps,
+        Some(script.config),
+        None,
+    )
+    .await;
+    match outcome {
+        Ok(result) if result.success => finish(&app, &run.id, ScheduleRunStatus::Succeeded, result.message),
+        Ok(result) => finish(&app, &run.id, ScheduleRunStatus::Failed, result.message),
+        Err(e) => finish(&app, &run.id, ScheduleRunStatus::Failed, e),
+    }
+}
+
+fn dispatch<R: Runtime>(app: &AppHandle<R>, run: ScheduleRun) {
+    emit_run(app, &run);
+    if run.status == ScheduleRunStatus::Running {
+        tauri::async_runtime::spawn(execute_run(app.clone(), run));
+    }
+}
+
+/// 立即执行一次计划（不影响下次触发时间）
+pub fn run_now<R: Runtime>(app: &AppHandle<R>, schedule_id: &str) -> Result<ScheduleRun, String> {
+    let run = {
+        let store = ScheduleStore::global().lock().map_err(|e| e.to_string())?;
+        let schedule = store.get(schedule_id)?.ok_or_else(|| format!("定时计划不存在: {}", schedule_id))?;
+        store.begin_run(&schedule, ScheduleTrigger::Manual, Utc::now())?
+    };
+    dispatch(app, run.clone());
+    Ok(run)
+}
+
+/// 定时循环（插件 setup 时 spawn）：先收尾上次中断的运行并补跑错过的计划，之后定期检查到点计划
+pub async fn run_scheduler<R: Runtime>(app: AppHandle<R>) {
+    match ScheduleStore::global().lock().map_err(|e| e.to_string()).and_then(|s| s.recover_interrupted(Utc::now())) {
+        Ok(interrupted) => interrupted.iter().for_each(|run| emit_run(&app, run)),
+        Err(e) => warn!("⚠️ 恢复中断的定时运行失败: {}", e),
+    }
+    loop {
+        let due = ScheduleStore::global().lock().map_err(|e| e.to_string()).and_then(|s| s.take_due(Utc::now()));
+        match due {
+            Ok(runs) => runs.into_iter().for_each(|run| dispatch(&app, run)),
+            Err(e) => warn!("⚠️ 检查定时计划失败: {}", e),
+        }
+        tokio::time::sleep(SCHEDULER_TICK).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn advances_due_schedules_and_tracks_runs() {
+        let store = ScheduleStore::in_memory();
+        let created = Utc::now();
+        assert!(store.create("script-a", "emulator-5554", "61 * * * *", true, created).is_err());
+        let hourly = store.create("script-a", "emulator-5554", "0 * * * *", true, created).unwrap();
+        let paused = store.create("script-b", "emulator-5556", "* * * * *", false, created).unwrap();
+        assert_eq!(paused.next_run_at, None);
+        let first_due = hourly.next_run_at.unwrap();
+
+        // 未到点不触发；到点后登记运行并推进下次触发
+        assert!(store.take_due(first_due - Duration::seconds(1)).unwrap().is_empty());
+        let runs = store.take_due(first_due).unwrap();
+        assert_eq!((runs.len(), runs[0].status, runs[0].trigger), (1, ScheduleRunStatus::Running, ScheduleTrigger::Cron));
+        let after_first = store.get(&hourly.id).unwrap().unwrap();
+        assert_eq!(after_first.next_run_at, Some(first_due + Duration::hours(1)));
+        assert_eq!(after_first.last_status, Some(ScheduleRunStatus::Running));
+
+        // 上次运行未结束时跳过
+        let skipped = store.take_due(first_due + Duration::hours(1)).unwrap();
+        assert_eq!(skipped[0].status, ScheduleRunStatus::Skipped);
+
+        // 重启：中断的运行记为失败，超出宽限期的触发记为错过
+        let restart = first_due + Duration::hours(5);
+        let interrupted = store.recover_interrupted(restart).unwrap();
+        assert_eq!(interrupted.len(), 1);
+        assert_eq!(interrupted[0].id, runs[0].id);
+        let missed = store.take_due(restart).unwrap();
+        assert_eq!(missed[0].status, ScheduleRunStatus::Missed);
+        assert!(store.get(&hourly.id).unwrap().unwrap().next_run_at.unwrap() > restart);
+
+        let resumed = store.update(&paused.id, ScheduleUpdate { enabled: Some(true), ..Default::default() }, restart).unwrap();
+        assert_eq!(resumed.next_run_at, next_fire("* * * * *", restart).unwrap());
+        let late = store.take_due(restart + Duration::minutes(5)).unwrap();
+        assert_eq!((late[0].script_id.as_str(), late[0].trigger), ("script-b", ScheduleTrigger::CatchUp));
+        let done = store.finish_run(&late[0].id, ScheduleRunStatus::Succeeded, Some("ok".into()), restart).unwrap();
+        assert_eq!(done.finished_at, Some(restart));
+
+        assert_eq!(store.runs(Some(&hourly.id), 10).unwrap().len(), 3);
+        assert_eq!(store.runs(None, 10).unwrap().len(), 4);
+        assert!(store.delete(&hourly.id).unwrap());
+        assert_eq!(store.runs(None, 10).unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,370 @@
+// src-tauri/src/services/selector_precompute/mod.rs
+// module: selector_precompute | layer: services | role: 空闲预计算
+// summary: 应用空闲时为绑定了缓存快照的脚本步骤预先生成选择器候选并缓存；快照内容变化即失效，打开步骤编辑器 / 发起分析时直接复用，附命中率统计
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::sync::{Mutex, MutexGuard, OnceLock};
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use serde_json::Value;
+use tauri::{AppHandle, Manager, Runtime};
+use tracing::{debug, info, warn};
+use xxhash_rust::xxh3::xxh3_64;
+
+use crate::commands::intelligent_analysis::{analyze_selection, calculate_selection_hash, AnalysisResult, ElementSelectionContext};
+use crate::modules::script_manager::prevalidate::bound_snapshot;
+use crate::services::concurrency::ConcurrencyController;
+use crate::services::script_manager::{ScriptManagerState, SmartScript};
+
+/// 最近一次变更类命令后多久视为空闲
+const IDLE_AFTER_SECS: i64 = 60;
+/// 空闲检查间隔
+const WORKER_TICK: Duration = Duration::from_secs(15);
+/// 每轮最多计算的步骤数，避免空闲窗口内长时间占用 CPU
+const MAX_JOBS_PER_PASS: usize = 8;
+/// 缓存上限，超出时淘汰最早计算的条目
+const MAX_ENTRIES: usize = 500;
+
+static LAST_ACTIVITY_MS: AtomicI64 = AtomicI64::new(0);
+
+/// 记录用户活动（插件分发变更类命令时调用）
+pub fn note_activity() {
+    LAST_ACTIVITY_MS.store(Utc::now().timestamp_millis(), Ordering::Relaxed);
+}
+
+/// 空闲：一段时间内没有变更类命令，且没有正在执行的设备 / AI 工作
+pub fn is_idle() -> bool {
+    let quiet_ms = Utc::now().timestamp_millis() - LAST_ACTIVITY_MS.load(Ordering::Relaxed);
+    quiet_ms >= IDLE_AFTER_SECS * 1000 && ConcurrencyController::global().status().running.is_empty()
+}
+
+/// 待预计算的步骤
+#[derive(Debug, Clone)]
+pub struct PrecomputeJob {
+    pub script_id: String,
+    pub step_id: String,
+    pub context: ElementSelectionContext,
+    pub xml_content: String,
+}
+
+struct CacheEntry {
+    snapshot_id: String,
+    selection: String,
+    xml_hash: u64,
+    result: AnalysisResult,
+    computed_at: DateTime<Utc>,
+}
+
+/// 预计算统计
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PrecomputeStats {
+    pub hits: u64,
+    pub misses: u64,
+    /// 快照内容或选中元素已变化而失效的查询
+    pub stale: u64,
+    pub computed: u64,
+    pub failed: u64,
+    pub entries: usize,
+    /// 最近一轮扫描后仍待计算的步骤数
+    pub pending: usize,
+    pub last_pass_at: Option<DateTime<Utc>>,
+    pub hit_rate: f64,
+}
+
+/// 按步骤 ID 缓存的候选
+#[derive(Default)]
+pub struct PrecomputeCache {
+    entries: HashMap<String, CacheEntry>,
+    stats: PrecomputeStats,
+}
+
+impl PrecomputeCache {
+    pub fn global() -> &'static Mutex<PrecomputeCache> {
+        static CACHE: OnceLock<Mutex<PrecomputeCache>> = OnceLock::new();
+        CACHE.get_or_init(|| Mutex::new(PrecomputeCache::default()))
+    }
+
+    /// 缓存是否仍对应该快照内容与选中元素
+    pub fn is_fresh(&self, step_id: &str, snapshot_id: &str, selection: &str, xml: &str) -> bool {
+        self.entries.get(step_id).is_some_and(|entry| {
+            entry.snapshot_id == snapshot_id && entry.selection == selection && entry.xml_hash == xxh3_64(xml.as_bytes())
+        })
+    }
+
+    /// 查询并计入命中率；过期条目顺带删除
+    pub fn lookup(&mut self, step_id: &str, snapshot_id: &str, selection: &str, xml: &str) -> Option<AnalysisResult> {
+        if !self.entries.contains_key(step_id) {
+            self.stats.misses += 1;
+            return None;
+        }
+        if !self.is_fresh(step_id, snapshot_id, selection, xml) {
+            self.entries.remove(step_id);
+            self.stats.stale += 1;
+            return None;
+        }
+        self.stats.hits += 1;
+        self.entries.get(step_id).map(|entry| entry.result.clone())
+    }
+
+    pub fn insert(&mut self, job: &PrecomputeJob, result: AnalysisResult, now: DateTime<Utc>) {
+        self.entries.insert(
+            job.step_id.clone(),
+            CacheEntry {
+                snapshot_id: job.context.snapshot_id.clone(),
+                selection: job.context.element_path.clone(),
+                xml_hash: xxh3_64(job.xml_content.as_bytes()),
+                result,
+                computed_at: now,
+            },
+        );
+        self.stats.computed += 1;
+        while self.entries.len() > MAX_ENTRIES {
+            let Some(oldest) = self.entries.iter().min_by_key(|(_, e)| e.computed_at).map(|(k, _)| k.clone()) else { break };
+            self.entries.remove(&oldest);
+        }
+    }
+
+    /// 删除绑定到该快照的全部条目，返回删除数量
+    pub fn invalidate_snapshot(&mut self, snapshot_id: &str) -> usize {
+        let before = self.entries.len();
+        self.entries.retain(|_, entry| entry.snapshot_id != snapshot_id);
+        before - self.entries.len()
+    }
+
+    pub fn clear(&mut self) -> usize {
+        let removed = self.entries.len();
+        self.entries.clear();
+        removed
+    }
+
+    pub fn stats(&self) -> PrecomputeStats {
+        let lookups = self.stats.hits + self.stats.misses + self.stats.stale;
+        PrecomputeStats {
+            entries: self.entries.len(),
+            hit_rate: if lookups == 0 { 0.0 } else { self.stats.hits as f64 / lookups as f64 },
+            ..self.stats.clone()
+        }
+    }
+}
+
+fn cache() -> MutexGuard<'static, PrecomputeCache> {
+    PrecomputeCache::global().lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// 分析入口查询预计算结果
+pub fn lookup(step_id: &str, snapshot_id: &str, selection: &str, xml: &str) -> Option<AnalysisResult> {
+    cache().lookup(step_id, snapshot_id, selection, xml)
+}
+
+pub fn invalidate_snapshot(snapshot_id: &str) -> usize {
+    cache().invalidate_snapshot(snapshot_id)
+}
+
+pub fn clear() -> usize {
+    cache().clear()
+}
+
+pub fn stats() -> PrecomputeStats {
+    cache().stats()
+}
+
+fn param_str(params: &Value, keys: &[&str]) -> Option<String> {
+    keys.iter()
+        .find_map(|key| params.get(*key).and_then(Value::as_str))
+        .filter(|s| !s.trim().is_empty())
+        .map(str::to_string)
+}
+
+/// 从步骤参数还原分析时的元素选择上下文；未绑定快照或没有 xpath 的步骤跳过
+fn selection_context(params: &Value, snapshot_id: String) -> Option<ElementSelectionContext> {
+    let element_path = param_str(params, &["xpath", "element_path", "elementPath"])?;
+    let mut key_attributes = HashMap::new();
+    for (attr, keys) in [
+        ("resource-id", &["resource_id", "resourceId"][..]),
+        ("content-desc", &["content_desc", "contentDesc"][..]),
+        ("class", &["class_name", "className"][..]),
+    ] {
+        if let Some(value) = param_str(params, keys) {
+            key_attributes.insert(attr.to_string(), value);
+        }
+    }
+    Some(ElementSelectionContext {
+        snapshot_id,
+        element_path,
+        element_text: param_str(params, &["text", "element_text"]),
+        element_bounds: param_str(params, &["bounds"]),
+        element_type: None,
+        key_attributes: (!key_attributes.is_empty()).then_some(key_attributes),
+        container_info: None,
+        sibling_texts: None,
+        parent_element: None,
+        children_texts: None,
+        index_path: None,
+    })
+}
+
+/// 收集所有脚本中启用、绑定了快照且能取到快照 XML 的步骤
+pub fn collect_jobs(scripts: &[SmartScript], load_snapshot: impl Fn(&str) -> Option<String>) -> Vec<PrecomputeJob> {
+    let mut jobs = Vec::new();
+    for script in scripts {
+        for step in script.steps.iter().filter(|step| step.enabled) {
+            let (Some(snapshot_id), embedded) = bound_snapshot(&step.parameters) else { continue };
+            let Some(xml_content) = load_snapshot(&snapshot_id).or(embedded) else { continue };
+            let Some(context) = selection_context(&step.parameters, snapshot_id) else { continue };
+            jobs.push(PrecomputeJob { script_id: script.id.clone(), step_id: step.id.clone(), context, xml_content });
+        }
+    }
+    jobs
+}
+
+fn load_snapshot(snapshot_id: &str) -> Option<String> {
+    crate::domain::analysis_cache::api::get_dom(&snapshot_id.to_string()).map(|dom| dom.xml_content)
+}
+
+/// 扫描一轮：计算尚未缓存或已过期的步骤，空闲结束即停止；返回本轮计算数
+async fn run_pass<R: Runtime>(app: &AppHandle<R>) -> usize {
+    let scripts = match app.try_state::<ScriptManagerState>() {
+        Some(state) => state.0.lock().list_scripts().map_err(|e| e.to_string()),
+        None => return 0,
+    };
+    let scripts = match scripts {
+        Ok(scripts) => scripts,
+        Err(e) => {
+            warn!("⚠️ 预计算读取脚本列表失败: {}", e);
+            return 0;
+        }
+    };
+    let mut pending: Vec<PrecomputeJob> = collect_jobs(&scripts, load_snapshot)
+        .into_iter()
+        .filter(|job| !cache().is_fresh(&job.step_id, &job.context.snapshot_id, &job.context.element_path, &job.xml_content))
+        .collect();
+
+    let mut computed = 0;
+    while computed < MAX_JOBS_PER_PASS && is_idle() {
+        let Some(job) = pending.pop() else { break };
+        let analysis_id = format!("precompute-{}", job.step_id);
+        let selection_hash = calculate_selection_hash(&job.context);
+        let outcome =
+            analyze_selection(&analysis_id, job.xml_content.clone(), &job.context, &selection_hash, Some(job.step_id.clone())).await;
+        match outcome {
+            Ok(result) => {
+                cache().insert(&job, result, Utc::now());
+                computed += 1;
+            }
+            Err(e) => {
+                cache().stats.failed += 1;
+                debug!("预计算失败: script={}, step={}, {}", job.script_id, job.step_id, e);
+            }
+        }
+    }
+
+    let mut cache = cache();
+    cache.stats.pending = pending.len();
+    cache.stats.last_pass_at = Some(Utc::now());
+    computed
+}
+
+/// 后台循环（插件 setup 时 spawn）：仅在空闲时工作，每轮计算量有上限
+pub async fn run_worker<R: Runtime>(app: AppHandle<R>) {
+    note_activity();
+    loop {
+        tokio::time::sleep(WORKER_TICK).await;
+        if !is_idle() {
+            continue;
+        }
+        let computed = run_pass(&app).await;
+        if computed > 0 {
+            info!("🧮 空闲预计算完成 {} 个步骤的选择器候选", computed);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::intelligent_analysis::StrategyCandidate;
+    use crate::services::execution::model::{SmartActionType, SmartScriptStep};
+    use serde_json::json;
+
+    fn step(id: &str, enabled: bool, parameters: Value) -> SmartScriptStep {
+        SmartScriptStep {
+            id: id.to_string(),
+            step_type: SmartActionType::SmartTap,
+            name: id.to_string(),
+            description: String::new(),
+            parameters,
+            enabled,
+            order: 0,
+        }
+    }
+
+    fn result(step_id: &str) -> AnalysisResult {
+        let candidate = StrategyCandidate {
+            key: "self_anchor".to_string(),
+            name: "self_anchor".to_string(),
+            confidence: 0.9,
+            description: String::new(),
+            variant: "smart".to_string(),
+            xpath: None,
+            text: None,
+            resource_id: None,
+            class_name: None,
+            content_desc: None,
+            enabled: true,
+            is_recommended: false,
+            selection_mode: None,
+            batch_config: None,
+            structural_signatures: None,
+        };
+        AnalysisResult {
+            selection_hash: String::new(),
+            step_id: Some(step_id.to_string()),
+            smart_candidates: vec![candidate.clone()],
+            static_candidates: vec![],
+            recommended_key: candidate.key.clone(),
+            recommended_confidence: candidate.confidence,
+            fallback_strategy: candidate,
+        }
+    }
+
+    #[test]
+    fn caches_bound_steps_and_invalidates_on_snapshot_change() {
+        let script = SmartScript {
+            id: "script-a".to_string(),
+            steps: vec![
+                step("bound", true, json!({ "xmlCacheId": "snap-1", "xpath": "//node[@text='关注']", "resource_id": "com.app:id/follow" })),
+                step("embedded", true, json!({ "xmlSnapshot": { "xmlContent": "<hierarchy/>" }, "snapshotId": "snap-gone", "xpath": "//a" })),
+                step("disabled", false, json!({ "xmlCacheId": "snap-1", "xpath": "//b" })),
+                step("unbound", true, json!({ "xpath": "//c" })),
+                step("no-selector", true, json!({ "xmlCacheId": "snap-1" })),
+            ],
+            ..SmartScript::default()
+        };
+        let jobs = collect_jobs(std::slice::from_ref(&script), |id| (id == "snap-1").then(|| "<v1/>".to_string()));
+        assert_eq!(jobs.iter().map(|j| j.step_id.as_str()).collect::<Vec<_>>(), vec!["bound", "embedded"]);
+        let bound = &jobs[0];
+        assert_eq!(bound.context.key_attributes.as_ref().unwrap()["resource-id"], "com.app:id/follow");
+        assert_eq!(jobs[1].xml_content, "<hierarchy/>");
+
+        let mut cache = PrecomputeCache::default();
+        let xpath = "//node[@text='关注']";
+        assert!(cache.lookup("bound", "snap-1", xpath, "<v1/>").is_none());
+        cache.insert(bound, result("bound"), Utc::now());
+        assert!(cache.is_fresh("bound", "snap-1", xpath, "<v1/>"));
+        assert!(cache.lookup("bound", "snap-1", xpath, "<v1/>").is_some());
+        // 快照内容变化：失效并删除
+        assert!(cache.lookup("bound", "snap-1", xpath, "<v2/>").is_none());
+        assert!(cache.lookup("bound", "snap-1", xpath, "<v1/>").is_none());
+
+        cache.insert(bound, result("bound"), Utc::now());
+        cache.insert(&jobs[1], result("embedded"), Utc::now());
+        assert_eq!(cache.invalidate_snapshot("snap-1"), 1);
+        let stats = cache.stats();
+        assert_eq!((stats.hits, stats.misses, stats.stale, stats.computed, stats.entries), (1, 2, 1, 3, 1));
+        assert!((stats.hit_rate - 0.25).abs() < f64::EPSILON);
+    }
+}
@@ -0,0 +1,206 @@
+// src-tauri/src/services/self_test/canary.rs
+// module: self_test | layer: services | role: 金丝雀脚本
+// summary: 在单台设备上执行固定脚本：shell 往返 → 打开设置 → 抓取界面 → 切换开关并校验、再切回 → 回到桌面，逐步计时；另采集 adb / 系统指纹
+
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+use std::time::{Duration, Instant};
+
+use crate::automation::analysis::hierarchy::parse_bounds;
+use crate::services::adb::AdbService;
+use crate::utils::adb_utils::execute_adb_command;
+
+/// 设置应用包名
+const SETTINGS_PACKAGE: &str = "com.android.settings";
+/// 优先打开的设置页（含系统开关，切换后立即恢复）
+const SETTINGS_ACTIONS: &[&str] = &["android.settings.DISPLAY_SETTINGS", "android.settings.SETTINGS"];
+/// 点击后等待界面刷新的时间
+const SETTLE_DELAY: Duration = Duration::from_millis(800);
+
+/// 金丝雀单步结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CanaryStep {
+    pub name: String,
+    pub duration_ms: u64,
+    pub error: Option<String>,
+}
+
+/// 设备运行环境指纹（变化即视为环境漂移）
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct EnvFingerprint {
+    pub adb_version: String,
+    pub android_release: String,
+    pub build_fingerprint: String,
+}
+
+impl EnvFingerprint {
+    /// 与新指纹相比变化的字段，形如 `adb_version: 旧 → 新`
+    pub fn diff(&self, new: &EnvFingerprint) -> Vec<String> {
+        [
+            ("adb_version", &self.adb_version, &new.adb_version),
+            ("android_release", &self.android_release, &new.android_release),
+            ("build_fingerprint", &self.build_fingerprint, &new.build_fingerprint),
+        ]
+        .into_iter()
+        .filter(|(_, old, new)| old != new)
+        .map(|(name, old, new)| format!("{}: {} → {}", name, old, new))
+        .collect()
+    }
+}
+
+pub(super) fn adb_text(args: &[&str]) -> Result<String, String> {
+    let output = execute_adb_command(args).map_err(|e| e.to_string())?;
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+fn getprop(device_id: &str, key: &str) -> String {
+    adb_text(&["-s", device_id, "shell", "getprop", key]).unwrap_or_default()
+}
+
+/// 已连接（状态为 device）的设备；`include_physical` 为 false 时只保留模拟器
+pub fn connected_devices(include_physical: bool) -> Result<Vec<String>, String> {
+    let output = adb_text(&["devices"])?;
+    Ok(output
+        .lines()
+        .filter_map(|line| line.trim_end().strip_suffix("\tdevice"))
+        .map(str::to_string)
+        .filter(|id| include_physical || is_emulator(id))
+        .collect())
+}
+
+pub fn is_emulator(device_id: &str) -> bool {
+    device_id.starts_with("emulator-") || getprop(device_id, "ro.kernel.qemu") == "1"
+}
+
+pub fn fingerprint(device_id: &str) -> EnvFingerprint {
+    let adb_version = adb_text(&["version"])
+        .ok()
+        .and_then(|v| v.lines().next().map(|l| l.trim_start_matches("Android Debug Bridge version ").to_string()))
+        .unwrap_or_default();
+    EnvFingerprint {
+        adb_version,
+        android_release: getprop(device_id, "ro.build.version.release"),
+        build_fingerprint: getprop(device_id, "ro.build.fingerprint"),
+    }
+}
+
+/// 界面上第一个可勾选的开关：(中心坐标, 当前是否开启)
+pub fn find_switch(xml: &str) -> Result<Option<((i32, i32), bool)>, String> {
+    let doc = roxmltree::Document::parse(xml).map_err(|e| format!("解析UI Dump失败: {}", e))?;
+    Ok(doc
+        .descendants()
+        .filter(|n| n.has_tag_name("node") && n.attribute("checkable") == Some("true"))
+        .filter(|n| n.attribute("class").is_some_and(|c| c.contains("Switch")) && n.attribute("enabled") != Some("false"))
+        .find_map(|n| {
+            let (l, t, r, b) = n.attribute("bounds").and_then(parse_bounds)?;
+            Some((((l + r) / 2, (t + b) / 2), n.attribute("checked") == Some("true")))
+        }))
+}
+
+pub(super) async fn dump(device_id: &str) -> Result<String, String> {
+    AdbService::new()
+        .dump_ui_hierarchy(device_id)
+        .await
+        .map_err(|e| format!("Failed to dump hierarchy: {}", e))
+}
+
+/// 点击开关后重新抓取，校验状态已变为 `expected`
+async fn tap_switch(device_id: &str, (x, y): (i32, i32), expected: bool) -> Result<(), String> {
+    AdbService::new().tap_screen(device_id, x, y).await.map_err(|e| format!("点击失败: {}", e))?;
+    tokio::time::sleep(SETTLE_DELAY).await;
+    match find_switch(&dump(device_id).await?)? {
+        Some((_, checked)) if checked == expected => Ok(()),
+        Some(_) => Err(format!("开关状态未变为 {}", expected)),
+        None => Err("切换后开关消失".to_string()),
+    }
+}
+
+async fn timed<T, F: Future<Output = Result<T, String>>>(steps: &mut Vec<CanaryStep>, name: &str, f: F) -> Option<T> {
+    let started = Instant::now();
+    let result = f.await;
+    steps.push(CanaryStep {
+        name: name.to_string(),
+        duration_ms: started.elapsed().as_millis() as u64,
+        error: result.as_ref().err().cloned(),
+    });
+    result.ok()
+}
+
+/// 执行金丝雀脚本；某步失败后跳过依赖它的后续步骤，但总会尝试回到桌面
+pub async fn run_canary(device_id: &str) -> Vec<CanaryStep> {
+    let mut steps = Vec::new();
+    let shell_ok = timed(&mut steps, "adb_shell", async {
+        let echo = adb_text(&["-s", device_id, "shell", "echo", "canary"])?;
+        if echo == "canary" {
+            Ok(())
+        } else {
+            Err(format!("shell 回显异常: {}", echo))
+        }
+    })
+    .await;
+
+    if shell_ok.is_some() {
+        let opened = timed(&mut steps, "open_settings", async {
+            let mut last_error = String::new();
+            for &action in SETTINGS_ACTIONS {
+                match adb_text(&["-s", device_id, "shell", "am", "start", "-W", "-a", action]) {
+                    Ok(out) if !out.contains("Error") => return Ok(()),
+                    Ok(out) => last_error = out,
+                    Err(e) => last_error = e,
+                }
+            }
+            Err(format!("打开设置失败: {}", last_error))
+        })
+        .await;
+
+        let xml = match opened {
+            Some(()) => {
+                timed(&mut steps, "dump_ui", async {
+                    let xml = dump(device_id).await?;
+                    if xml.contains(SETTINGS_PACKAGE) {
+                        Ok(xml)
+                    } else {
+                        Err("前台不是设置应用".to_string())
+                    }
+                })
+                .await
+            }
+            None => None,
+        };
+
+        if let Some(xml) = xml {
+            timed(&mut steps, "toggle_switch", async {
+                let (point, checked) = find_switch(&xml)?.ok_or("设置页未找到可切换的开关")?;
+                tap_switch(device_id, point, !checked).await?;
+                // 恢复原状态
+                tap_switch(device_id, point, checked).await
+            })
+            .await;
+        }
+    }
+
+    timed(&mut steps, "go_home", async {
+        adb_text(&["-s", device_id, "shell", "input", "keyevent", "KEYCODE_HOME"]).map(|_| ())
+    })
+    .await;
+    steps
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_switch_and_diffs_fingerprint() {
+        let xml = r#"<?xml version='1.0' encoding='UTF-8' standalone='yes' ?><hierarchy rotation="0"><node package="com.android.settings" class="android.widget.FrameLayout" bounds="[0,0][1080,2400]"><node class="android.widget.CheckBox" checkable="true" checked="false" bounds="[0,100][100,200]"/><node class="android.widget.Switch" checkable="true" checked="true" bounds="[900,400][1040,480]"/></node></hierarchy>"#;
+        assert_eq!(find_switch(xml).unwrap(), Some(((970, 440), true)));
+
+        let old = EnvFingerprint { adb_version: "1.0.41".into(), android_release: "12".into(), build_fingerprint: "a".into() };
+        let new = EnvFingerprint { android_release: "13".into(), ..old.clone() };
+        assert!(old.diff(&old).is_empty());
+        assert_eq!(old.diff(&new), vec!["android_release: 12 → 13".to_string()]);
+    }
+}
@@ -0,0 +1,442 @@
+// src-tauri/src/services/self_test/mod.rs
+// module: self_test | layer: services | role: 模拟器自检
+// summary: 对已连接的模拟器跑金丝雀脚本，逐步计时并与基线比较，同时比对 adb / 系统指纹，生成健康报告；支持按需执行和每晚定时执行
+
+pub mod canary;
+pub mod quirks;
+
+use chrono::{DateTime, Local, NaiveDate, NaiveTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+use tauri::{AppHandle, Runtime};
+use tracing::{info, warn};
+
+use canary::{CanaryStep, EnvFingerprint};
+use quirks::{QuirkConfig, QuirkFinding, QuirkReport};
+
+const STORE_FILE_NAME: &str = "self_test.json";
+/// 前端通知事件名（每次自检完成后发出报告）
+pub const SELF_TEST_EVENT: &str = "self-test://report";
+/// 保留的历史报告数
+const MAX_REPORTS: usize = 60;
+/// 定时检查间隔
+const SCHEDULER_TICK: Duration = Duration::from_secs(60);
+
+static STORE: OnceLock<Mutex<SelfTestStore>> = OnceLock::new();
+/// 同一时间只跑一轮自检
+static RUNNING: AtomicBool = AtomicBool::new(false);
+
+/// 自检配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SelfTestConfig {
+    /// 是否每晚定时执行
+    pub scheduled: bool,
+    /// 定时执行的本地时间（HH:MM）
+    pub run_at: String,
+    /// 耗时超过基线的倍数视为变慢
+    pub slowdown_ratio: f64,
+    /// 同时至少慢这么多毫秒才报（避免毫秒级步骤的抖动）
+    pub min_slowdown_ms: u64,
+    /// 基线指数平滑系数（0~1）
+    pub baseline_alpha: f64,
+    /// 是否也在真机上执行（默认只跑模拟器）
+    pub include_physical: bool,
+    /// 已知模拟器怪癖的检测与自动修复
+    pub quirks: QuirkConfig,
+}
+
+impl Default for SelfTestConfig {
+    fn default() -> Self {
+        Self {
+            scheduled: false,
+            run_at: "03:00".to_string(),
+            slowdown_ratio: 1.5,
+            min_slowdown_ms: 500,
+            baseline_alpha: 0.2,
+            include_physical: false,
+            quirks: QuirkConfig::default(),
+        }
+    }
+}
+
+impl SelfTestConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        NaiveTime::parse_from_str(&self.run_at, "%H:%M").map_err(|_| format!("执行时间格式应为 HH:MM: {}", self.run_at))?;
+        if self.slowdown_ratio < 1.0 {
+            return Err("变慢倍数不能小于 1".to_string());
+        }
+        if !(0.0..=1.0).contains(&self.baseline_alpha) || self.baseline_alpha == 0.0 {
+            return Err("基线平滑系数需在 (0, 1] 之间".to_string());
+        }
+        self.quirks.validate()
+    }
+}
+
+/// 某设备某步骤的耗时基线
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct StepBaseline {
+    pub mean_ms: f64,
+    pub samples: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SelfTestTrigger {
+    Manual,
+    Scheduled,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HealthStatus {
+    Healthy,
+    /// 全部通过，但有步骤变慢或环境指纹变化
+    Degraded,
+    Failed,
+}
+
+/// 单个步骤的结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StepResult {
+    pub name: String,
+    pub duration_ms: u64,
+    pub baseline_ms: Option<u64>,
+    pub regressed: bool,
+    pub error: Option<String>,
+}
+
+/// 单台设备的健康情况
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeviceHealth {
+    pub device_id: String,
+    pub status: HealthStatus,
+    pub fingerprint: EnvFingerprint,
+    /// 与上次自检相比变化的环境项（如 `adb_version: 1.0.41 → 1.0.42`）
+    pub drift: Vec<String>,
+    pub steps: Vec<StepResult>,
+    /// 本轮检测到的模拟器怪癖（含自动修复结果）
+    #[serde(default)]
+    pub quirks: Vec<QuirkFinding>,
+}
+
+/// 一轮自检的健康报告
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SelfTestReport {
+    pub id: String,
+    pub trigger: SelfTestTrigger,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: DateTime<Utc>,
+    pub status: HealthStatus,
+    pub devices: Vec<DeviceHealth>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct SelfTestData {
+    #[serde(default)]
+    config: SelfTestConfig,
+    /// 设备 → 步骤 → 基线
+    #[serde(default)]
+    baselines: BTreeMap<String, BTreeMap<String, StepBaseline>>,
+    #[serde(default)]
+    fingerprints: BTreeMap<String, EnvFingerprint>,
+    #[serde(default)]
+    reports: Vec<SelfTestReport>,
+    /// 设备 → 最近一次怪癖检测报告
+    #[serde(default)]
+    quirk_reports: BTreeMap<String, QuirkReport>,
+    /// 定时执行最近一次的日期（本地）
+    #[serde(default)]
+    last_scheduled: Option<NaiveDate>,
+}
+
+pub struct SelfTestStore {
+    path: Option<PathBuf>,
+    data: SelfTestData,
+}
+
+/// 耗时是否相对基线变慢
+fn is_regressed(baseline: Option<&StepBaseline>, duration_ms: u64, config: &SelfTestConfig) -> bool {
+    baseline.is_some_and(|b| {
+        let duration = duration_ms as f64;
+        duration > b.mean_ms * config.slowdown_ratio && duration - b.mean_ms >= config.min_slowdown_ms as f64
+    })
+}
+
+fn worst(a: HealthStatus, b: HealthStatus) -> HealthStatus {
+    let rank = |s: HealthStatus| match s {
+        HealthStatus::Healthy => 0,
+        HealthStatus::Degraded => 1,
+        HealthStatus::Failed => 2,
+    };
+    if rank(b) > rank(a) {
+        b
+    } else {
+        a
+    }
+}
+
+impl SelfTestStore {
+    pub fn global() -> &'static Mutex<SelfTestStore> {
+        STORE.get_or_init(|| {
+            let path = dirs::data_dir().unwrap_or_else(|| PathBuf::from(".")).join("employee-gui").join(STORE_FILE_NAME);
+            Mutex::new(Self::open(path))
+        })
+    }
+
+    pub fn open(path: PathBuf) -> Self {
+        let data = std::fs::read_to_string(&path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+        Self { path: Some(path), data }
+    }
+
+    #[cfg(test)]
+    fn in_memory() -> Self {
+        Self { path: None, data: SelfTestData::default() }
+    }
+
+    fn save(&self) -> Result<(), String> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| format!("创建数据目录失败: {}", e))?;
+        }
+        let content = serde_json::to_string_pretty(&self.data).map_err(|e| e.to_string())?;
+        std::fs::write(path, content).map_err(|e| format!("保存自检数据失败: {}", e))
+    }
+
+    pub fn config(&self) -> &SelfTestConfig {
+        &self.data.config
+    }
+
+    pub fn set_config(&mut self, config: SelfTestConfig) -> Result<(), String> {
+        config.validate()?;
+        self.data.config = config;
+        self.save()
+    }
+
+    /// 历史报告（新的在前）
+    pub fn reports(&self, limit: usize) -> Vec<SelfTestReport> {
+        self.data.reports.iter().rev().take(limit).cloned().collect()
+    }
+
+    pub fn baselines(&self) -> &BTreeMap<String, BTreeMap<String, StepBaseline>> {
+        &self.data.baselines
+    }
+
+    /// 清除基线（环境升级确认无误后重新建立）；`device_id` 为空时清除全部
+    pub fn reset_baselines(&mut self, device_id: Option<&str>) -> Result<usize, String> {
+        let removed = match device_id {
+            Some(id) => self.data.baselines.remove(id).map_or(0, |_| 1),
+            None => std::mem::take(&mut self.data.baselines).len(),
+        };
+        self.save()?;
+        Ok(removed)
+    }
+
+    /// 用一台设备的金丝雀结果与基线 / 上次指纹比较，并更新基线（只吸收正常且未变慢的样本）
+    pub fn evaluate_device(&mut self, device_id: &str, fingerprint: EnvFingerprint, steps: Vec<CanaryStep>) -> DeviceHealth {
+        let config = self.data.config.clone();
+        let baselines = self.data.baselines.entry(device_id.to_string()).or_default();
+        let mut status = HealthStatus::Healthy;
+        let results: Vec<StepResult> = steps
+            .into_iter()
+            .map(|step| {
+                let baseline = baselines.get(&step.name);
+                let regressed = step.error.is_none() && is_regressed(baseline, step.duration_ms, &config);
+                let result = StepResult {
+                    baseline_ms: baseline.map(|b| b.mean_ms.round() as u64),
+                    regressed,
+                    duration_ms: step.duration_ms,
+                    error: step.error,
+                    name: step.name,
+                };
+                if result.error.is_some() {
+                    status = HealthStatus::Failed;
+                } else if regressed {
+                    status = worst(status, HealthStatus::Degraded);
+                } else {
+                    let entry = baselines
+                        .entry(result.name.clone())
+                        .or_insert(StepBaseline { mean_ms: result.duration_ms as f64, samples: 0 });
+                    entry.mean_ms += config.baseline_alpha * (result.duration_ms as f64 - entry.mean_ms);
+                    entry.samples += 1;
+                }
+                result
+            })
+            .collect();
+
+        let drift = self.data.fingerprints.get(device_id).map(|old| old.diff(&fingerprint)).unwrap_or_default();
+        if !drift.is_empty() {
+            status = worst(status, HealthStatus::Degraded);
+        }
+        self.data.fingerprints.insert(device_id.to_string(), fingerprint.clone());
+        DeviceHealth { device_id: device_id.to_string(), status, fingerprint, drift, steps: results, quirks: Vec::new() }
+    }
+
+    pub fn push_report(&mut self, report: SelfTestReport) -> Result<(), String> {
+        self.data.reports.push(report);
+        if self.data.reports.len() > MAX_REPORTS {
+            let excess = self.data.reports.len() - MAX_REPORTS;
+            self.data.reports.drain(0..excess);
+        }
+        self.save()
+    }
+
+    /// 各设备最近一次怪癖检测报告
+    pub fn quirk_reports(&self) -> Vec<QuirkReport> {
+        self.data.quirk_reports.values().cloned().collect()
+    }
+
+    pub fn record_quirks(&mut self, report: QuirkReport) -> Result<(), String> {
+        self.data.quirk_reports.insert(report.device_id.clone(), report);
+        self.save()
+    }
+
+    /// 今天是否到了定时执行的时间且还没执行过
+    fn scheduled_due(&self, now: DateTime<Local>) -> bool {
+        let config = &self.data.config;
+        let Ok(at) = NaiveTime::parse_from_str(&config.run_at, "%H:%M") else {
+            return false;
+        };
+        config.scheduled && now.time() >= at && self.data.last_scheduled != Some(now.date_naive())
+    }
+
+    fn mark_scheduled(&mut self, date: NaiveDate) -> Result<(), String> {
+        self.data.last_scheduled = Some(date);
+        self.save()
+    }
+}
+
+/// 对所有目标设备执行一轮自检；`device_ids` 为空时自动选取已连接的模拟器
+pub async fn run(device_ids: Option<Vec<String>>, trigger: SelfTestTrigger) -> Result<SelfTestReport, String> {
+    if RUNNING.swap(true, Ordering::SeqCst) {
+        return Err("自检正在执行".to_string());
+    }
+    let result = run_inner(device_ids, trigger).await;
+    RUNNING.store(false, Ordering::SeqCst);
+    result
+}
+
+async fn run_inner(device_ids: Option<Vec<String>>, trigger: SelfTestTrigger) -> Result<SelfTestReport, String> {
+    let include_physical = SelfTestStore::global().lock().map_err(|e| e.to_string())?.config().include_physical;
+    let devices = match device_ids.filter(|ids| !ids.is_empty()) {
+        Some(ids) => ids,
+        None => canary::connected_devices(include_physical)?,
+    };
+    if devices.is_empty() {
+        return Err("没有已连接的模拟器".to_string());
+    }
+
+    let started_at = Utc::now();
+    info!("🩺 开始自检: {} 台设备 ({:?})", devices.len(), trigger);
+    let mut health = Vec::with_capacity(devices.len());
+    for device_id in &devices {
+        // 先排除已知怪癖（空树、分辨率被改等），否则金丝雀步骤会因环境问题失败
+        let quirks = match quirks::check_and_record(device_id, None).await {
+            Ok(report) => report.findings,
+            Err(e) => {
+                warn!("⚠️ 设备 {} 怪癖检测失败: {}", device_id, e);
+                Vec::new()
+            }
+        };
+        let fingerprint = canary::fingerprint(device_id);
+        let steps = canary::run_canary(device_id).await;
+        let mut device = SelfTestStore::global().lock().map_err(|e| e.to_string())?.evaluate_device(device_id, fingerprint, steps);
+        if quirks.iter().any(|q| !q.resolved) {
+            device.status = worst(device.status, HealthStatus::Degraded);
+        }
+        device.quirks = quirks;
+        if device.status != HealthStatus::Healthy {
+            warn!("⚠️ 自检异常 device={} status={:?} drift={:?}", device_id, device.status, device.drift);
+        }
+        health.push(device);
+    }
+
+    let report = SelfTestReport {
+        id: format!("selftest_{}", started_at.timestamp_millis()),
+        trigger,
+        started_at,
+        finished_at: Utc::now(),
+        status: health.iter().fold(HealthStatus::Healthy, |acc, d| worst(acc, d.status)),
+        devices: health,
+    };
+    SelfTestStore::global().lock().map_err(|e| e.to_string())?.push_report(report.clone())?;
+    info!("🩺 自检完成: {:?}", report.status);
+    Ok(report)
+}
+
+/// 定时执行（应用启动时 spawn）：每分钟检查一次是否到达当天的执行时间
+pub async fn run_scheduler<R: Runtime>(app: AppHandle<R>) {
+    loop {
+        tokio::time::sleep(SCHEDULER_TICK).await;
+        let now = Local::now();
+        let due = SelfTestStore::global().lock().map(|s| s.scheduled_due(now)).unwrap_or(false);
+        if !due {
+            continue;
+        }
+        if let Err(e) = SelfTestStore::global().lock().map_err(|e| e.to_string()).and_then(|mut s| s.mark_scheduled(now.date_naive())) {
+            warn!("⚠️ 记录自检执行日期失败: {}", e);
+        }
+        match run(None, SelfTestTrigger::Scheduled).await {
+            Ok(report) => {
+                crate::services::event_outbox::emit(&app, SELF_TEST_EVENT, &report.id, &report);
+            }
+            Err(e) => warn!("⚠️ 定时自检失败: {}", e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn step(name: &str, duration_ms: u64) -> CanaryStep {
+        CanaryStep { name: name.to_string(), duration_ms, error: None }
+    }
+
+    fn env(adb: &str) -> EnvFingerprint {
+        EnvFingerprint { adb_version: adb.into(), android_release: "12".into(), build_fingerprint: "emu/x86".into() }
+    }
+
+    #[test]
+    fn flags_slowdowns_drift_and_failures() {
+        let mut store = SelfTestStore::in_memory();
+        let first = store.evaluate_device("emulator-5554", env("1.0.41"), vec![step("open_settings", 1000)]);
+        assert_eq!((first.status, first.steps[0].baseline_ms), (HealthStatus::Healthy, None));
+
+        // 小幅波动吸收进基线
+        let jitter = store.evaluate_device("emulator-5554", env("1.0.41"), vec![step("open_settings", 1200)]);
+        assert_eq!(jitter.status, HealthStatus::Healthy);
+        assert_eq!(store.baselines()["emulator-5554"]["open_settings"].mean_ms, 1040.0);
+
+        // 变慢 + adb 升级：降级，且变慢样本不污染基线
+        let slow = store.evaluate_device("emulator-5554", env("1.0.42"), vec![step("open_settings", 4000)]);
+        assert_eq!(slow.status, HealthStatus::Degraded);
+        assert!(slow.steps[0].regressed);
+        assert_eq!(slow.drift, vec!["adb_version: 1.0.41 → 1.0.42".to_string()]);
+        assert_eq!(store.baselines()["emulator-5554"]["open_settings"].samples, 2);
+
+        let failed = store.evaluate_device(
+            "emulator-5554",
+            env("1.0.42"),
+            vec![CanaryStep { name: "toggle_switch".into(), duration_ms: 10, error: Some("未找到开关".into()) }],
+        );
+        assert_eq!(failed.status, HealthStatus::Failed);
+
+        store.data.config.scheduled = true;
+        let at = |h| Local.with_ymd_and_hms(2026, 10, 16, h, 0, 0).unwrap();
+        assert!(!store.scheduled_due(at(2)));
+        assert!(store.scheduled_due(at(3)));
+        store.mark_scheduled(at(3).date_naive()).unwrap();
+        assert!(!store.scheduled_due(at(4)));
+    }
+}
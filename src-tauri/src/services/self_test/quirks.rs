@@ -0,0 +1,459 @@
+// src-tauri/src/services/self_test/quirks.rs
+// module: self_test | layer: services | role: 模拟器已知怪癖检测
+// summary: 设备上线后检测雷电等模拟器的常见问题（界面抓取为空树、wm size 与配置分辨率不符、没有可用输入法），按配置自动修复（重启 uiautomator、重置分辨率、启用输入法）并复查，结果并入自检健康报告
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::time::Duration;
+use tokio::sync::broadcast::error::RecvError;
+use tracing::{info, warn};
+
+use super::canary;
+use super::SelfTestStore;
+use crate::core::shared::event_bus::{self, AppEvent};
+use crate::services::concurrency::{self, WorkRequest};
+
+/// 修复命令执行后等待设备生效的时间
+const REMEDIATION_SETTLE: Duration = Duration::from_secs(2);
+
+/// 期望的显示参数
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DisplayProfile {
+    pub width: u32,
+    pub height: u32,
+    /// 为空表示不检查 / 不修改 dpi
+    #[serde(default)]
+    pub density: Option<u32>,
+}
+
+/// 怪癖检测配置（属于自检配置）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct QuirkConfig {
+    /// 设备上线后自动检测
+    pub check_on_attach: bool,
+    /// 检测到问题时自动修复
+    pub auto_remediate: bool,
+    /// 上线后等待多久再检测（模拟器刚启动时服务尚未就绪）
+    pub attach_delay_secs: u64,
+    /// 所有设备的默认分辨率；为空且未单独配置的设备不检查分辨率
+    pub default_display: Option<DisplayProfile>,
+    /// 按设备单独配置的分辨率
+    pub device_displays: BTreeMap<String, DisplayProfile>,
+    /// 缺少输入法时优先启用的输入法（如 `com.android.adbkeyboard/.AdbIME`）
+    pub preferred_ime: Option<String>,
+}
+
+impl Default for QuirkConfig {
+    fn default() -> Self {
+        Self {
+            check_on_attach: true,
+            auto_remediate: true,
+            attach_delay_secs: 5,
+            default_display: None,
+            device_displays: BTreeMap::new(),
+            preferred_ime: None,
+        }
+    }
+}
+
+impl QuirkConfig {
+    pub fn display_for(&self, device_id: &str) -> Option<&DisplayProfile> {
+        self.device_displays.get(device_id).or(self.default_display.as_ref())
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        for profile in self.default_display.iter().chain(self.device_displays.values()) {
+            if profile.width == 0 || profile.height == 0 || profile.density == Some(0) {
+                return Err(format!("分辨率配置无效: {}x{}", profile.width, profile.height));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// 怪癖类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum QuirkKind {
+    /// uiautomator 抓取返回空树
+    EmptyUiDump,
+    /// wm size 与配置的分辨率不一致
+    ResolutionMismatch,
+    /// 没有启用的默认输入法（文本输入会失败）
+    MissingIme,
+}
+
+/// 一项检测出的问题
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuirkFinding {
+    pub kind: QuirkKind,
+    pub detail: String,
+    /// 已执行的修复命令
+    pub remediation: Vec<String>,
+    /// 修复后复查通过
+    pub resolved: bool,
+    pub error: Option<String>,
+}
+
+/// 单台设备的怪癖报告
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuirkReport {
+    pub device_id: String,
+    pub checked_at: DateTime<Utc>,
+    pub remediate: bool,
+    pub findings: Vec<QuirkFinding>,
+}
+
+impl QuirkReport {
+    /// 仍有未解决的问题
+    pub fn has_unresolved(&self) -> bool {
+        self.findings.iter().any(|f| !f.resolved)
+    }
+}
+
+/// 检测所需的设备操作（默认经 adb，测试中可替换）
+#[async_trait]
+pub trait QuirkProbe: Send + Sync {
+    async fn shell(&self, command: &str) -> Result<String, String>;
+    async fn dump(&self) -> Result<String, String>;
+    /// 修复后等待生效
+    async fn settle(&self) {
+        tokio::time::sleep(REMEDIATION_SETTLE).await;
+    }
+}
+
+pub struct AdbQuirkProbe {
+    device_id: String,
+}
+
+impl AdbQuirkProbe {
+    pub fn new(device_id: &str) -> Self {
+        Self { device_id: device_id.to_string() }
+    }
+}
+
+#[async_trait]
+impl QuirkProbe for AdbQuirkProbe {
+    async fn shell(&self, command: &str) -> Result<String, String> {
+        canary::adb_text(&["-s", &self.device_id, "shell", command])
+    }
+
+    async fn dump(&self) -> Result<String, String> {
+        canary::dump(&self.device_id).await
+    }
+}
+
+/// 解析 `wm size` 输出：(物理分辨率, 覆盖分辨率)
+pub fn parse_wm_size(output: &str) -> (Option<(u32, u32)>, Option<(u32, u32)>) {
+    let parse = |prefix: &str| {
+        output.lines().find_map(|line| {
+            let (w, h) = line.trim().strip_prefix(prefix)?.trim().split_once('x')?;
+            Some((w.trim().parse().ok()?, h.trim().parse().ok()?))
+        })
+    };
+    (parse("Physical size:"), parse("Override size:"))
+}
+
+/// 抓取结果里没有任何节点
+pub fn is_empty_dump(xml: &str) -> bool {
+    !xml.contains("<node")
+}
+
+fn same_size(actual: (u32, u32), profile: &DisplayProfile) -> bool {
+    actual == (profile.width, profile.height) || actual == (profile.height, profile.width)
+}
+
+fn ime_list(output: &str) -> Vec<String> {
+    output.lines().map(str::trim).filter(|l| l.contains('/')).map(str::to_string).collect()
+}
+
+async fn current_ime(probe: &dyn QuirkProbe) -> Option<String> {
+    let current = probe.shell("settings get secure default_input_method").await.ok()?;
+    let current = current.trim();
+    (!current.is_empty() && current != "null").then(|| current.to_string())
+}
+
+/// 执行一条修复命令并记录
+async fn remediate(probe: &dyn QuirkProbe, finding: &mut QuirkFinding, command: String) -> bool {
+    let result = probe.shell(&command).await;
+    finding.remediation.push(command);
+    match result {
+        Ok(_) => true,
+        Err(e) => {
+            finding.error = Some(e);
+            false
+        }
+    }
+}
+
+async fn check_ui_dump(probe: &dyn QuirkProbe, fix: bool) -> Option<QuirkFinding> {
+    let detail = match probe.dump().await {
+        Ok(xml) if !is_empty_dump(&xml) => return None,
+        Ok(_) => "界面抓取返回空树".to_string(),
+        Err(e) => format!("界面抓取失败: {}", e),
+    };
+    let mut finding = QuirkFinding { kind: QuirkKind::EmptyUiDump, detail, remediation: Vec::new(), resolved: false, error: None };
+    if fix {
+        // 杀掉卡住的 uiautomator 进程，下次抓取会重新拉起
+        remediate(probe, &mut finding, "pkill -f uiautomator || killall uiautomator".to_string()).await;
+        probe.settle().await;
+        match probe.dump().await {
+            Ok(xml) if !is_empty_dump(&xml) => finding.resolved = true,
+            Ok(_) => finding.error = Some("重启 uiautomator 后仍为空树".to_string()),
+            Err(e) => finding.error = Some(e),
+        }
+    }
+    Some(finding)
+}
+
+async fn check_resolution(probe: &dyn QuirkProbe, profile: &DisplayProfile, fix: bool) -> Option<QuirkFinding> {
+    let read = || async {
+        let output = probe.shell("wm size").await?;
+        let (physical, overridden) = parse_wm_size(&output);
+        let effective = overridden.or(physical).ok_or_else(|| format!("无法解析 wm size: {}", output.trim()))?;
+        let density = match profile.density {
+            Some(_) => probe.shell("wm density").await.ok().and_then(|out| {
+                let line = out.lines().find(|l| l.contains("Override density:")).or_else(|| out.lines().next())?;
+                line.rsplit(':').next()?.trim().parse::<u32>().ok()
+            }),
+            None => None,
+        };
+        Ok::<_, String>((physical, effective, density))
+    };
+    let matches = |effective: (u32, u32), density: Option<u32>| {
+        same_size(effective, profile) && (profile.density.is_none() || density == profile.density)
+    };
+
+    let (physical, effective, density) = match read().await {
+        Ok(values) if matches(values.1, values.2) => return None,
+        Ok(values) => values,
+        Err(e) => {
+            return Some(QuirkFinding { kind: QuirkKind::ResolutionMismatch, detail: e.clone(), remediation: Vec::new(), resolved: false, error: Some(e) })
+        }
+    };
+    let mut detail = format!("当前 {}x{}，配置 {}x{}", effective.0, effective.1, profile.width, profile.height);
+    if let (Some(expected), actual) = (profile.density, density) {
+        detail.push_str(&format!("，dpi {} / 配置 {}", actual.map_or("未知".to_string(), |d| d.to_string()), expected));
+    }
+    let mut finding = QuirkFinding { kind: QuirkKind::ResolutionMismatch, detail, remediation: Vec::new(), resolved: false, error: None };
+    if fix {
+        // 物理分辨率本就符合时只需清除覆盖值
+        let size_command = match physical {
+            Some(p) if same_size(p, profile) => "wm size reset".to_string(),
+            _ => format!("wm size {}x{}", profile.width, profile.height),
+        };
+        let mut ok = remediate(probe, &mut finding, size_command).await;
+        if let Some(d) = profile.density {
+            ok &= remediate(probe, &mut finding, format!("wm density {}", d)).await;
+        }
+        if ok {
+            probe.settle().await;
+            match read().await {
+                Ok((_, effective, density)) if matches(effective, density) => finding.resolved = true,
+                Ok((_, effective, _)) => finding.error = Some(format!("重置后仍为 {}x{}", effective.0, effective.1)),
+                Err(e) => finding.error = Some(e),
+            }
+        }
+    }
+    Some(finding)
+}
+
+async fn check_ime(probe: &dyn QuirkProbe, preferred: Option<&str>, fix: bool) -> Option<QuirkFinding> {
+    let enabled = ime_list(&probe.shell("ime list -s").await.unwrap_or_default());
+    let current = current_ime(probe).await;
+    let detail = match &current {
+        None => "没有默认输入法".to_string(),
+        Some(ime) if !enabled.contains(ime) => format!("默认输入法 {} 未启用", ime),
+        Some(_) => return None,
+    };
+    let mut finding = QuirkFinding { kind: QuirkKind::MissingIme, detail, remediation: Vec::new(), resolved: false, error: None };
+    if fix {
+        let installed = ime_list(&probe.shell("ime list -a -s").await.unwrap_or_default());
+        let candidate = preferred
+            .filter(|p| installed.iter().any(|i| i == p))
+            .map(str::to_string)
+            .or_else(|| enabled.first().cloned())
+            .or_else(|| installed.first().cloned());
+        match candidate {
+            Some(ime) => {
+                let ok = remediate(probe, &mut finding, format!("ime enable {}", ime)).await
+                    && remediate(probe, &mut finding, format!("ime set {}", ime)).await;
+                if ok {
+                    probe.settle().await;
+                    if current_ime(probe).await.as_deref() == Some(ime.as_str()) {
+                        finding.resolved = true;
+                    } else {
+                        finding.error = Some(format!("设置输入法 {} 未生效", ime));
+                    }
+                }
+            }
+            None => finding.error = Some("设备上没有已安装的输入法".to_string()),
+        }
+    }
+    Some(finding)
+}
+
+/// 依次检测三类已知问题；`remediate` 为 true 时就地修复并复查
+pub async fn check_device(probe: &dyn QuirkProbe, device_id: &str, config: &QuirkConfig, remediate: bool) -> QuirkReport {
+    let mut findings = Vec::new();
+    findings.extend(check_ui_dump(probe, remediate).await);
+    if let Some(profile) = config.display_for(device_id) {
+        findings.extend(check_resolution(probe, profile, remediate).await);
+    }
+    findings.extend(check_ime(probe, config.preferred_ime.as_deref(), remediate).await);
+    QuirkReport { device_id: device_id.to_string(), checked_at: Utc::now(), remediate, findings }
+}
+
+/// 经 adb 检测单台设备并保存报告（等设备上正在执行的工作结束后再做，避免修复时打断脚本）
+pub async fn check_and_record(device_id: &str, remediate: Option<bool>) -> Result<QuirkReport, String> {
+    let config = SelfTestStore::global().lock().map_err(|e| e.to_string())?.config().quirks.clone();
+    let remediate = remediate.unwrap_or(config.auto_remediate);
+    let probe = AdbQuirkProbe::new(device_id);
+    let request = WorkRequest::device(device_id, "self_test", "模拟器怪癖检测");
+    let report = concurrency::run(request, check_device(&probe, device_id, &config, remediate)).await;
+    for finding in &report.findings {
+        if finding.resolved {
+            info!("🩹 设备 {} 已修复 {:?}: {}", device_id, finding.kind, finding.detail);
+        } else {
+            warn!("⚠️ 设备 {} 存在 {:?}: {} {:?}", device_id, finding.kind, finding.detail, finding.error);
+        }
+    }
+    SelfTestStore::global().lock().map_err(|e| e.to_string())?.record_quirks(report.clone())?;
+    Ok(report)
+}
+
+/// 设备上线时自动检测（应用启动时 spawn）
+pub async fn run_on_attach() {
+    let mut events = event_bus::subscribe();
+    let mut online: HashSet<String> = HashSet::new();
+    loop {
+        let device_ids = match events.recv().await {
+            Ok(AppEvent::DevicesOnline { device_ids }) => device_ids,
+            Ok(_) => continue,
+            Err(RecvError::Lagged(_)) => continue,
+            Err(RecvError::Closed) => break,
+        };
+        let current: HashSet<String> = device_ids.into_iter().collect();
+        let settings = match SelfTestStore::global().lock() {
+            Ok(store) => store.config().clone(),
+            Err(_) => continue,
+        };
+        if settings.quirks.check_on_attach {
+            for device_id in current.difference(&online).cloned() {
+                let delay = Duration::from_secs(settings.quirks.attach_delay_secs);
+                let include_physical = settings.include_physical;
+                tokio::spawn(async move {
+                    tokio::time::sleep(delay).await;
+                    if !include_physical && !canary::is_emulator(&device_id) {
+                        return;
+                    }
+                    if let Err(e) = check_and_record(&device_id, None).await {
+                        warn!("⚠️ 设备 {} 怪癖检测失败: {}", device_id, e);
+                    }
+                });
+            }
+        }
+        online = current;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    /// 模拟刚启动的雷电实例：uiautomator 卡死、分辨率被覆盖、没有默认输入法
+    struct BrokenEmulator {
+        uiautomator_stuck: Mutex<bool>,
+        size_override: Mutex<Option<&'static str>>,
+        ime: Mutex<Option<String>>,
+        enabled_imes: Mutex<Vec<String>>,
+        commands: Mutex<Vec<String>>,
+    }
+
+    #[async_trait]
+    impl QuirkProbe for BrokenEmulator {
+        async fn shell(&self, command: &str) -> Result<String, String> {
+            self.commands.lock().unwrap().push(command.to_string());
+            Ok(match command {
+                "wm size" => {
+                    let mut out = "Physical size: 900x1600".to_string();
+                    if let Some(size) = *self.size_override.lock().unwrap() {
+                        out.push_str(&format!("\nOverride size: {}", size));
+                    }
+                    out
+                }
+                "wm size reset" => {
+                    *self.size_override.lock().unwrap() = None;
+                    String::new()
+                }
+                "settings get secure default_input_method" => self.ime.lock().unwrap().clone().unwrap_or_else(|| "null".into()),
+                "ime list -s" => self.enabled_imes.lock().unwrap().join("\n"),
+                "ime list -a -s" => "com.android.inputmethod.latin/.LatinIME\ncom.android.adbkeyboard/.AdbIME".to_string(),
+                cmd if cmd.starts_with("ime enable ") => {
+                    self.enabled_imes.lock().unwrap().push(cmd.trim_start_matches("ime enable ").to_string());
+                    String::new()
+                }
+                cmd if cmd.starts_with("ime set ") => {
+                    *self.ime.lock().unwrap() = Some(cmd.trim_start_matches("ime set ").to_string());
+                    String::new()
+                }
+                cmd if cmd.contains("uiautomator") => {
+                    *self.uiautomator_stuck.lock().unwrap() = false;
+                    String::new()
+                }
+                _ => String::new(),
+            })
+        }
+
+        async fn dump(&self) -> Result<String, String> {
+            Ok(if *self.uiautomator_stuck.lock().unwrap() {
+                "<?xml version='1.0' encoding='UTF-8' standalone='yes' ?><hierarchy rotation=\"0\" />".to_string()
+            } else {
+                "<hierarchy><node text=\"\" /></hierarchy>".to_string()
+            })
+        }
+
+        async fn settle(&self) {}
+    }
+
+    #[tokio::test]
+    async fn detects_and_remediates_known_quirks() {
+        assert_eq!(parse_wm_size("Physical size: 1080x1920\nOverride size: 720x1280"), (Some((1080, 1920)), Some((720, 1280))));
+
+        let emulator = BrokenEmulator {
+            uiautomator_stuck: Mutex::new(true),
+            size_override: Mutex::new(Some("720x1280")),
+            ime: Mutex::new(None),
+            enabled_imes: Mutex::new(Vec::new()),
+            commands: Mutex::new(Vec::new()),
+        };
+        let config = QuirkConfig {
+            default_display: Some(DisplayProfile { width: 900, height: 1600, density: None }),
+            preferred_ime: Some("com.android.adbkeyboard/.AdbIME".into()),
+            ..Default::default()
+        };
+
+        let report = check_device(&emulator, "emulator-5554", &config, false).await;
+        let kinds: Vec<QuirkKind> = report.findings.iter().map(|f| f.kind).collect();
+        assert_eq!(kinds, vec![QuirkKind::EmptyUiDump, QuirkKind::ResolutionMismatch, QuirkKind::MissingIme]);
+        assert!(report.has_unresolved());
+        assert!(emulator.commands.lock().unwrap().iter().all(|c| !c.contains("reset") && !c.starts_with("ime set")));
+
+        let report = check_device(&emulator, "emulator-5554", &config, true).await;
+        assert!(!report.has_unresolved(), "{:?}", report.findings);
+        assert_eq!(report.findings[1].remediation, vec!["wm size reset"]);
+        assert_eq!(
+            report.findings[2].remediation,
+            vec!["ime enable com.android.adbkeyboard/.AdbIME", "ime set com.android.adbkeyboard/.AdbIME"]
+        );
+
+        // 修复后复查无问题；未配置分辨率的设备不检查分辨率
+        assert!(check_device(&emulator, "emulator-5554", &config, false).await.findings.is_empty());
+        *emulator.size_override.lock().unwrap() = Some("720x1280");
+        assert!(check_device(&emulator, "emulator-5554", &QuirkConfig::default(), false).await.findings.is_empty());
+    }
+}
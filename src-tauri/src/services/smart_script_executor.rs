@@ -28,6 +28,7 @@ use crate::services::execution::model::{
     SingleStepTestResult,
 };
 use crate::services::execution::SmartActionDispatcher;
+use crate::services::execution::debug_session::DebugSession;
 
 use crate::services::error_handling::{ErrorHandler, ErrorHandlingConfig};
 use crate::services::script_execution::ScriptPreprocessor;
@@ -250,6 +251,17 @@ impl SmartScriptExecutor {
         orchestrator.execute(steps, config).await
     }
 
+    /// 以调试模式执行整套脚本（断点 / 单步，由调试会话控制）
+    pub async fn execute_smart_script_debug(
+        &self,
+        steps: Vec<SmartScriptStep>,
+        config: Option<SmartExecutorConfig>,
+        session: Arc<DebugSession>,
+    ) -> Result<SmartExecutionResult> {
+        let orchestrator = SmartScriptOrchestrator::new(self, self.preprocessor.clone()).with_debug(session);
+        orchestrator.execute(steps, config).await
+    }
+
     async fn execute_adb_command(&self, args: &[&str]) -> Result<std::process::Output> {
         let mut cmd = std::process::Command::new(&self.adb_path);
         cmd.args(args);
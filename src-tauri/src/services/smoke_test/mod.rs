@@ -0,0 +1,556 @@
+// src-tauri/src/services/smoke_test/mod.rs
+// module: smoke_test | layer: services | role: 目标应用冒烟测试
+// summary: 按声明式定义在设备上快速确认目标应用是否可自动化（启动 → 等关键元素 → 截图），按需或按 cron 执行；保存历史结果并记录应用版本，上次通过本次失败即标为回归（多见于应用自动更新后）
+
+pub mod spec;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Local, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Runtime};
+use tracing::{info, warn};
+
+use crate::screenshot_service::ScreenshotService;
+use crate::services::adb::AdbService;
+use crate::services::artifact_store::{gallery, ArtifactStore};
+use crate::services::script_scheduler::cron::CronExpr;
+use crate::utils::adb_utils::execute_adb_command;
+use spec::{SmokeStep, SmokeTestSpec};
+
+const STORE_FILE_NAME: &str = "smoke_tests.json";
+/// 每次执行完成后发出，载荷为 [`SmokeTestRun`]
+pub const SMOKE_TEST_EVENT: &str = "smoke-test://result";
+/// 每个 (定义, 设备) 保留的历史结果数
+const MAX_HISTORY_PER_TARGET: usize = 100;
+const SCHEDULER_TICK: Duration = Duration::from_secs(30);
+/// 等待元素时的轮询间隔
+const POLL_INTERVAL: Duration = Duration::from_millis(1000);
+
+static STORE: OnceLock<Mutex<SmokeTestStore>> = OnceLock::new();
+/// 同一时间只跑一轮定时冒烟测试
+static SCHEDULED_RUNNING: AtomicBool = AtomicBool::new(false);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SmokeTrigger {
+    Manual,
+    Scheduled,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SmokeStepResult {
+    pub name: String,
+    pub duration_ms: u64,
+    pub error: Option<String>,
+    /// 截图步骤保存的文件
+    pub screenshot: Option<String>,
+}
+
+/// 一次冒烟测试结果，同时作为 [`SMOKE_TEST_EVENT`] 的载荷
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SmokeTestRun {
+    pub id: String,
+    pub spec_id: String,
+    pub app: String,
+    pub device_id: String,
+    pub trigger: SmokeTrigger,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: DateTime<Utc>,
+    pub passed: bool,
+    pub app_version: Option<String>,
+    pub steps: Vec<SmokeStepResult>,
+    /// 上次通过、本次失败
+    pub regression: bool,
+    /// 与上次执行相比应用版本变化（如 `8.12.0 → 8.13.0`）
+    pub version_change: Option<String>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct SmokeTestData {
+    #[serde(default)]
+    specs: Vec<SmokeTestSpec>,
+    /// 是否已写入过内置定义（之后删除内置定义不再恢复）
+    #[serde(default)]
+    seeded: bool,
+    #[serde(default)]
+    runs: Vec<SmokeTestRun>,
+    /// 定义 → 最近一次定时执行的时间
+    #[serde(default)]
+    last_scheduled: BTreeMap<String, DateTime<Utc>>,
+}
+
+pub struct SmokeTestStore {
+    path: Option<PathBuf>,
+    data: SmokeTestData,
+}
+
+impl SmokeTestStore {
+    pub fn global() -> &'static Mutex<SmokeTestStore> {
+        STORE.get_or_init(|| {
+            let path = dirs::data_dir().unwrap_or_else(|| PathBuf::from(".")).join("employee-gui").join(STORE_FILE_NAME);
+            Mutex::new(Self::open(path))
+        })
+    }
+
+    pub fn open(path: PathBuf) -> Self {
+        let data = std::fs::read_to_string(&path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+        let mut store = Self { path: Some(path), data };
+        store.seed();
+        store
+    }
+
+    #[cfg(test)]
+    fn in_memory() -> Self {
+        let mut store = Self { path: None, data: SmokeTestData::default() };
+        store.seed();
+        store
+    }
+
+    fn seed(&mut self) {
+        if !self.data.seeded {
+            self.data.specs.extend(spec::builtin_specs());
+            self.data.seeded = true;
+            if let Err(e) = self.save() {
+                warn!("⚠️ 写入内置冒烟测试失败: {}", e);
+            }
+        }
+    }
+
+    fn save(&self) -> Result<(), String> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| format!("创建数据目录失败: {}", e))?;
+        }
+        let content = serde_json::to_string_pretty(&self.data).map_err(|e| e.to_string())?;
+        std::fs::write(path, content).map_err(|e| format!("保存冒烟测试数据失败: {}", e))
+    }
+
+    pub fn specs(&self) -> &[SmokeTestSpec] {
+        &self.data.specs
+    }
+
+    pub fn spec(&self, id: &str) -> Option<&SmokeTestSpec> {
+        self.data.specs.iter().find(|s| s.id == id)
+    }
+
+    /// 新增或按 id 覆盖
+    pub fn save_spec(&mut self, spec: SmokeTestSpec) -> Result<(), String> {
+        spec.validate()?;
+        match self.data.specs.iter_mut().find(|s| s.id == spec.id) {
+            Some(existing) => *existing = spec,
+            None => self.data.specs.push(spec),
+        }
+        self.save()
+    }
+
+    /// 删除定义（保留历史结果）
+    pub fn delete_spec(&mut self, id: &str) -> Result<bool, String> {
+        let before = self.data.specs.len();
+        self.data.specs.retain(|s| s.id != id);
+        self.data.last_scheduled.remove(id);
+        self.save()?;
+        Ok(self.data.specs.len() < before)
+    }
+
+    /// 历史结果（新的在前），可按定义 / 设备过滤
+    pub fn history(&self, spec_id: Option<&str>, device_id: Option<&str>, limit: usize) -> Vec<SmokeTestRun> {
+        self.data
+            .runs
+            .iter()
+            .rev()
+            .filter(|r| spec_id.is_none_or(|id| r.spec_id == id) && device_id.is_none_or(|id| r.device_id == id))
+            .take(limit)
+            .cloned()
+            .collect()
+    }
+
+    /// 与同一 (定义, 设备) 的上次结果比较后记录
+    pub fn record(&mut self, mut run: SmokeTestRun) -> Result<SmokeTestRun, String> {
+        let previous = self.data.runs.iter().rev().find(|r| r.spec_id == run.spec_id && r.device_id == run.device_id);
+        if let Some(previous) = previous {
+            run.regression = previous.passed && !run.passed;
+            if let (Some(old), Some(new)) = (&previous.app_version, &run.app_version) {
+                if old != new {
+                    run.version_change = Some(format!("{} → {}", old, new));
+                }
+            }
+        }
+        self.data.runs.push(run.clone());
+        let same_target = |r: &SmokeTestRun| r.spec_id == run.spec_id && r.device_id == run.device_id;
+        let count = self.data.runs.iter().filter(|r| same_target(r)).count();
+        if count > MAX_HISTORY_PER_TARGET {
+            let mut excess = count - MAX_HISTORY_PER_TARGET;
+            self.data.runs.retain(|r| {
+                if excess > 0 && same_target(r) {
+                    excess -= 1;
+                    return false;
+                }
+                true
+            });
+        }
+        self.save()?;
+        Ok(run)
+    }
+
+    /// 到了 cron 触发时间的定义；首次检查只记录时间，不补跑
+    fn take_due(&mut self, now: DateTime<Utc>) -> Result<Vec<SmokeTestSpec>, String> {
+        let mut due = Vec::new();
+        let mut changed = false;
+        for spec in self.data.specs.iter().filter(|s| s.enabled) {
+            let Some(cron) = spec.schedule.as_deref() else { continue };
+            let Ok(expr) = CronExpr::parse(cron) else { continue };
+            let Some(last) = self.data.last_scheduled.get(&spec.id).copied() else {
+                self.data.last_scheduled.insert(spec.id.clone(), now);
+                changed = true;
+                continue;
+            };
+            let fire = expr.next_after(last.with_timezone(&Local)).map(|t| t.with_timezone(&Utc));
+            if fire.is_some_and(|fire| fire <= now) {
+                self.data.last_scheduled.insert(spec.id.clone(), now);
+                changed = true;
+                due.push(spec.clone());
+            }
+        }
+        if changed {
+            self.save()?;
+        }
+        Ok(due)
+    }
+}
+
+/// 执行所需的设备操作（默认经 adb，测试中可替换）
+#[async_trait]
+pub trait SmokeDevice: Send + Sync {
+    fn id(&self) -> &str;
+    async fn shell(&self, args: &[&str]) -> Result<String, String>;
+    async fn dump(&self) -> Result<String, String>;
+    async fn screenshot(&self) -> Result<Vec<u8>, String>;
+    async fn pause(&self, duration: Duration) {
+        tokio::time::sleep(duration).await;
+    }
+}
+
+pub struct AdbSmokeDevice {
+    device_id: String,
+}
+
+impl AdbSmokeDevice {
+    pub fn new(device_id: &str) -> Self {
+        Self { device_id: device_id.to_string() }
+    }
+}
+
+#[async_trait]
+impl SmokeDevice for AdbSmokeDevice {
+    fn id(&self) -> &str {
+        &self.device_id
+    }
+
+    async fn shell(&self, args: &[&str]) -> Result<String, String> {
+        let mut full = vec!["-s", self.device_id.as_str(), "shell"];
+        full.extend_from_slice(args);
+        let output = execute_adb_command(&full).map_err(|e| e.to_string())?;
+        if !output.status.success() {
+            return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    }
+
+    async fn dump(&self) -> Result<String, String> {
+        AdbService::new().dump_ui_hierarchy(&self.device_id).await.map_err(|e| format!("界面抓取失败: {}", e))
+    }
+
+    async fn screenshot(&self) -> Result<Vec<u8>, String> {
+        ScreenshotService::capture_png_bytes(&self.device_id)
+    }
+}
+
+fn screenshot_dir() -> PathBuf {
+    dirs::data_dir().unwrap_or_else(|| PathBuf::from(".")).join("employee-gui").join("smoke_tests")
+}
+
+/// 从 `dumpsys package` 输出读取 versionName
+fn parse_version_name(output: &str) -> Option<String> {
+    output.lines().find_map(|line| line.trim().strip_prefix("versionName=").map(|v| v.trim().to_string()))
+}
+
+/// 轮询界面直到任一条件满足，返回其中心点
+async fn wait_for(device: &dyn SmokeDevice, selectors: &[spec::ElementSelector], timeout_secs: u64) -> Result<(i32, i32), String> {
+    let deadline = Instant::now() + Duration::from_secs(timeout_secs);
+    let mut last_error = None;
+    loop {
+        match device.dump().await {
+            Ok(xml) => {
+                if let Some(center) = selectors.iter().find_map(|s| s.find_center(&xml)) {
+                    return Ok(center);
+                }
+            }
+            Err(e) => last_error = Some(e),
+        }
+        if Instant::now() >= deadline {
+            let wanted = selectors.iter().map(|s| s.describe()).collect::<Vec<_>>().join(" | ");
+            return Err(match last_error {
+                Some(e) => format!("{} 秒内未出现 [{}]（{}）", timeout_secs, wanted, e),
+                None => format!("{} 秒内未出现 [{}]", timeout_secs, wanted),
+            });
+        }
+        device.pause(POLL_INTERVAL).await;
+    }
+}
+
+async fn run_step(device: &dyn SmokeDevice, spec: &SmokeTestSpec, step: &SmokeStep, shots: &std::path::Path) -> Result<Option<String>, String> {
+    match step {
+        SmokeStep::Launch { cold_start } => {
+            if *cold_start {
+                device.shell(&["am", "force-stop", &spec.package]).await?;
+            }
+            let output = match &spec.activity {
+                Some(activity) => device.shell(&["am", "start", "-W", "-n", &format!("{}/{}", spec.package, activity)]).await?,
+                None => device.shell(&["monkey", "-p", &spec.package, "-c", "android.intent.category.LAUNCHER", "1"]).await?,
+            };
+            if output.contains("Error") || output.contains("No activities found") {
+                return Err(format!("启动 {} 失败: {}", spec.package, output.trim()));
+            }
+            Ok(None)
+        }
+        SmokeStep::ExpectElement { any_of, timeout_secs } => wait_for(device, any_of, *timeout_secs).await.map(|_| None),
+        SmokeStep::Tap { selector, timeout_secs } => {
+            let (x, y) = wait_for(device, std::slice::from_ref(selector), *timeout_secs).await?;
+            device.shell(&["input", "tap", &x.to_string(), &y.to_string()]).await.map(|_| None)
+        }
+        SmokeStep::Wait { ms } => {
+            device.pause(Duration::from_millis(*ms)).await;
+            Ok(None)
+        }
+        SmokeStep::Back => device.shell(&["input", "keyevent", "KEYCODE_BACK"]).await.map(|_| None),
+        SmokeStep::Screenshot { label } => {
+            let png = device.screenshot().await?;
+            std::fs::create_dir_all(shots).map_err(|e| format!("创建截图目录失败: {}", e))?;
+            let safe: String = label.chars().map(|c| if c.is_ascii_alphanumeric() || c == '-' { c } else { '_' }).collect();
+            let path = shots.join(format!("{}.png", safe));
+            std::fs::write(&path, png).map_err(|e| format!("保存截图失败: {}", e))?;
+            Ok(Some(path.to_string_lossy().into_owned()))
+        }
+    }
+}
+
+/// 在一台设备上执行定义；某步失败后跳过后续步骤（失败时仍尝试截图留证）
+pub async fn run_spec(device: &dyn SmokeDevice, spec: &SmokeTestSpec, trigger: SmokeTrigger, shots_root: &std::path::Path) -> SmokeTestRun {
+    let started_at = Utc::now();
+    let id = format!("smoke_{}_{}", started_at.timestamp_millis(), uuid::Uuid::new_v4().simple().to_string().get(..6).unwrap_or_default());
+    let shots = shots_root.join(&id);
+    let app_version = device.shell(&["dumpsys", "package", &spec.package]).await.ok().as_deref().and_then(parse_version_name);
+
+    let mut steps = Vec::with_capacity(spec.steps.len());
+    for step in &spec.steps {
+        let started = Instant::now();
+        let result = run_step(device, spec, step, &shots).await;
+        let failed = result.is_err();
+        steps.push(SmokeStepResult {
+            name: step.name(),
+            duration_ms: started.elapsed().as_millis() as u64,
+            screenshot: result.as_ref().ok().cloned().flatten(),
+            error: result.err(),
+        });
+        if failed {
+            if let Ok(Some(path)) = run_step(device, spec, &SmokeStep::Screenshot { label: "failure".to_string() }, &shots).await {
+                steps.push(SmokeStepResult { name: "screenshot:failure".to_string(), duration_ms: 0, error: None, screenshot: Some(path) });
+            }
+            break;
+        }
+    }
+
+    SmokeTestRun {
+        id,
+        spec_id: spec.id.clone(),
+        app: spec.app.clone(),
+        device_id: device.id().to_string(),
+        trigger,
+        started_at,
+        finished_at: Utc::now(),
+        passed: steps.len() >= spec.steps.len() && steps.iter().all(|s| s.error.is_none()),
+        app_version,
+        steps,
+        regression: false,
+        version_change: None,
+    }
+}
+
+fn connected_devices() -> Result<Vec<String>, String> {
+    let output = execute_adb_command(&["devices"]).map_err(|e| e.to_string())?;
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| line.trim_end().strip_suffix("\tdevice"))
+        .map(str::to_string)
+        .collect())
+}
+
+/// 在指定设备上执行一个定义并记录结果；`device_ids` 为空时用定义中的设备，仍为空则全部已连接设备
+pub async fn run<R: Runtime>(app: &AppHandle<R>, spec_id: &str, device_ids: Option<Vec<String>>, trigger: SmokeTrigger) -> Result<Vec<SmokeTestRun>, String> {
+    let spec = SmokeTestStore::global()
+        .lock()
+        .map_err(|e| e.to_string())?
+        .spec(spec_id)
+        .cloned()
+        .ok_or_else(|| format!("冒烟测试不存在: {}", spec_id))?;
+    let devices = match device_ids.filter(|ids| !ids.is_empty()) {
+        Some(ids) => ids,
+        None if !spec.devices.is_empty() => spec.devices.clone(),
+        None => connected_devices()?,
+    };
+    if devices.is_empty() {
+        return Err("没有已连接的设备".to_string());
+    }
+
+    let mut runs = Vec::with_capacity(devices.len());
+    for device_id in &devices {
+        let device = AdbSmokeDevice::new(device_id);
+        let run = run_spec(&device, &spec, trigger, &screenshot_dir()).await;
+        index_run_screenshots(&run);
+        let run = SmokeTestStore::global().lock().map_err(|e| e.to_string())?.record(run)?;
+        if run.regression {
+            warn!("🚨 冒烟测试回归: {} @ {} 版本 {:?} ({:?})", spec.id, device_id, run.app_version, run.version_change);
+        } else {
+            info!("🧪 冒烟测试 {} @ {}: {}", spec.id, device_id, if run.passed { "通过" } else { "失败" });
+        }
+        crate::services::event_outbox::emit(app, SMOKE_TEST_EVENT, &run.id, &run);
+        runs.push(run);
+    }
+    Ok(runs)
+}
+
+/// 把本次执行的截图登记为截图制品（runId = 执行 ID，stepId = 步骤名），供截图图库浏览
+fn index_run_screenshots(run: &SmokeTestRun) {
+    let ttl = chrono::Duration::days(gallery::RUN_SCREENSHOT_TTL_DAYS);
+    for step in &run.steps {
+        let Some(path) = &step.screenshot else { continue };
+        let meta = serde_json::json!({
+            "deviceId": run.device_id,
+            "runId": run.id,
+            "stepId": step.name,
+            "source": "smoke_test",
+        });
+        let result = std::fs::read(path)
+            .map_err(|e| format!("读取截图失败: {}", e))
+            .and_then(|png| gallery::index_screenshot(ArtifactStore::global(), &png, ttl, meta));
+        if let Err(e) = result {
+            warn!("⚠️ 冒烟测试截图登记制品失败 {}: {}", path, e);
+        }
+    }
+}
+
+/// 定时执行（应用启动时 spawn）：按各定义的 cron 触发
+pub async fn run_scheduler<R: Runtime>(app: AppHandle<R>) {
+    loop {
+        tokio::time::sleep(SCHEDULER_TICK).await;
+        let due = SmokeTestStore::global().lock().map_err(|e| e.to_string()).and_then(|mut s| s.take_due(Utc::now()));
+        let due = match due {
+            Ok(due) if !due.is_empty() => due,
+            Ok(_) => continue,
+            Err(e) => {
+                warn!("⚠️ 检查冒烟测试计划失败: {}", e);
+                continue;
+            }
+        };
+        if SCHEDULED_RUNNING.swap(true, Ordering::SeqCst) {
+            continue;
+        }
+        for spec in due {
+            if let Err(e) = run(&app, &spec.id, None, SmokeTrigger::Scheduled).await {
+                warn!("⚠️ 定时冒烟测试 {} 失败: {}", spec.id, e);
+            }
+        }
+        SCHEDULED_RUNNING.store(false, Ordering::SeqCst);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex as StdMutex;
+
+    /// 启动后第二次抓取才出现首页；`version` 模拟应用自动更新
+    struct FakeDevice {
+        home_text: &'static str,
+        version: &'static str,
+        dumps: StdMutex<u32>,
+        commands: StdMutex<Vec<String>>,
+    }
+
+    #[async_trait]
+    impl SmokeDevice for FakeDevice {
+        fn id(&self) -> &str {
+            "emulator-5554"
+        }
+
+        async fn shell(&self, args: &[&str]) -> Result<String, String> {
+            self.commands.lock().unwrap().push(args.join(" "));
+            Ok(match args {
+                ["dumpsys", ..] => format!("Packages:\n    versionCode=8130 minSdk=21\n    versionName={}\n", self.version),
+                _ => "Events injected: 1".to_string(),
+            })
+        }
+
+        async fn dump(&self) -> Result<String, String> {
+            let mut dumps = self.dumps.lock().unwrap();
+            *dumps += 1;
+            let text = if *dumps >= 2 { self.home_text } else { "" };
+            Ok(format!(r#"<hierarchy><node text="{}" class="android.widget.TextView" bounds="[0,1800][216,1920]" /></hierarchy>"#, text))
+        }
+
+        async fn screenshot(&self) -> Result<Vec<u8>, String> {
+            Ok(vec![0x89, b'P', b'N', b'G'])
+        }
+
+        async fn pause(&self, _duration: Duration) {
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+    }
+
+    #[tokio::test]
+    async fn runs_builtin_spec_and_flags_regressions_after_update() {
+        let mut store = SmokeTestStore::in_memory();
+        let spec = store.spec("xhs-launch").cloned().unwrap();
+        assert_eq!(store.specs().len(), 3);
+        let shots = std::env::temp_dir().join(format!("smoke-test-{}", uuid::Uuid::new_v4().simple()));
+
+        let healthy = FakeDevice { home_text: "首页", version: "8.12.0", dumps: StdMutex::new(0), commands: StdMutex::new(Vec::new()) };
+        let run = store.record(run_spec(&healthy, &spec, SmokeTrigger::Manual, &shots).await).unwrap();
+        assert!(run.passed, "{:?}", run.steps);
+        assert_eq!(run.app_version.as_deref(), Some("8.12.0"));
+        assert!(run.steps[2].screenshot.as_deref().is_some_and(|p| std::path::Path::new(p).exists()));
+        assert_eq!(healthy.commands.lock().unwrap()[1], "am force-stop com.xingin.xhs");
+
+        // 更新后首页文案变了：失败即回归，并带上版本变化
+        let updated = FakeDevice { home_text: "推荐", version: "8.13.0", dumps: StdMutex::new(0), commands: StdMutex::new(Vec::new()) };
+        let mut short = spec.clone();
+        short.steps[1] = SmokeStep::ExpectElement {
+            any_of: vec![spec::ElementSelector { text: Some("首页".into()), ..Default::default() }],
+            timeout_secs: 1,
+        };
+        let run = store.record(run_spec(&updated, &short, SmokeTrigger::Scheduled, &shots).await).unwrap();
+        assert!(!run.passed && run.regression);
+        assert_eq!(run.version_change.as_deref(), Some("8.12.0 → 8.13.0"));
+        assert_eq!(run.steps.last().map(|s| s.name.as_str()), Some("screenshot:failure"));
+        assert_eq!(store.history(Some("xhs-launch"), None, 10).len(), 2);
+
+        // 继续失败不再重复标记回归
+        let run = store.record(run_spec(&updated, &short, SmokeTrigger::Scheduled, &shots).await).unwrap();
+        assert!(!run.regression);
+
+        assert!(store.save_spec(SmokeTestSpec { steps: vec![], ..spec.clone() }).is_err());
+        let _ = std::fs::remove_dir_all(shots);
+    }
+}
@@ -0,0 +1,211 @@
+// src-tauri/src/services/smoke_test/spec.rs
+// module: smoke_test | layer: services | role: 冒烟测试定义
+// summary: 按目标应用声明的冒烟测试（启动应用 → 限时等待关键元素 → 截图等步骤），以数据形式保存；内置小红书 / 微信 / 通讯录三份默认定义
+
+use serde::{Deserialize, Serialize};
+
+use crate::engine::ui_tree::{NodeRef, UiTree};
+use crate::services::script_scheduler::cron::CronExpr;
+
+const DEFAULT_EXPECT_TIMEOUT_SECS: u64 = 15;
+/// 单个等待步骤的上限
+const MAX_TIMEOUT_SECS: u64 = 120;
+
+fn default_timeout_secs() -> u64 {
+    DEFAULT_EXPECT_TIMEOUT_SECS
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// 元素条件：填写的字段须全部满足
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ElementSelector {
+    pub text: Option<String>,
+    pub text_contains: Option<String>,
+    pub resource_id: Option<String>,
+    pub content_desc: Option<String>,
+    pub class_name: Option<String>,
+}
+
+impl ElementSelector {
+    fn is_empty(&self) -> bool {
+        self.text.is_none()
+            && self.text_contains.is_none()
+            && self.resource_id.is_none()
+            && self.content_desc.is_none()
+            && self.class_name.is_none()
+    }
+
+    pub fn matches(&self, node: NodeRef<'_>) -> bool {
+        self.text.as_deref().is_none_or(|t| node.text() == t)
+            && self.text_contains.as_deref().is_none_or(|t| node.text().contains(t) || node.content_desc().contains(t))
+            && self.resource_id.as_deref().is_none_or(|id| node.resource_id() == Some(id))
+            && self.content_desc.as_deref().is_none_or(|d| node.content_desc() == d)
+            && self.class_name.as_deref().is_none_or(|c| node.class_name().is_some_and(|class| class.ends_with(c)))
+    }
+
+    /// 在界面中找到第一个满足条件的元素，返回其中心点
+    pub fn find_center(&self, xml: &str) -> Option<(i32, i32)> {
+        let tree = UiTree::parse_lenient(xml);
+        let node = tree.nodes().find(|node| self.matches(*node))?;
+        let (l, t, r, b) = node.bounds().unwrap_or_default();
+        Some(((l + r) / 2, (t + b) / 2))
+    }
+
+    pub fn describe(&self) -> String {
+        [
+            ("text", &self.text),
+            ("textContains", &self.text_contains),
+            ("resourceId", &self.resource_id),
+            ("contentDesc", &self.content_desc),
+            ("class", &self.class_name),
+        ]
+        .into_iter()
+        .filter_map(|(key, value)| value.as_ref().map(|v| format!("{}={}", key, v)))
+        .collect::<Vec<_>>()
+        .join(", ")
+    }
+}
+
+/// 冒烟测试步骤
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum SmokeStep {
+    /// 启动定义中的应用；`cold_start` 时先强制停止
+    Launch {
+        #[serde(default, rename = "coldStart")]
+        cold_start: bool,
+    },
+    /// 在限定时间内等到元素出现，任一条件满足即可
+    ExpectElement {
+        #[serde(rename = "anyOf")]
+        any_of: Vec<ElementSelector>,
+        #[serde(default = "default_timeout_secs", rename = "timeoutSecs")]
+        timeout_secs: u64,
+    },
+    /// 等到元素出现后点击
+    Tap {
+        selector: ElementSelector,
+        #[serde(default = "default_timeout_secs", rename = "timeoutSecs")]
+        timeout_secs: u64,
+    },
+    Wait { ms: u64 },
+    Back,
+    Screenshot { label: String },
+}
+
+impl SmokeStep {
+    pub fn name(&self) -> String {
+        match self {
+            Self::Launch { cold_start: true } => "launch(cold)".to_string(),
+            Self::Launch { .. } => "launch".to_string(),
+            Self::ExpectElement { any_of, .. } => {
+                format!("expect[{}]", any_of.iter().map(ElementSelector::describe).collect::<Vec<_>>().join(" | "))
+            }
+            Self::Tap { selector, .. } => format!("tap[{}]", selector.describe()),
+            Self::Wait { ms } => format!("wait {}ms", ms),
+            Self::Back => "back".to_string(),
+            Self::Screenshot { label } => format!("screenshot:{}", label),
+        }
+    }
+}
+
+/// 一个目标应用的冒烟测试定义
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SmokeTestSpec {
+    pub id: String,
+    pub name: String,
+    /// 目标应用标识（xhs / wechat / contacts / 自定义）
+    pub app: String,
+    pub package: String,
+    /// 启动的 Activity；为空时按 LAUNCHER 启动
+    #[serde(default)]
+    pub activity: Option<String>,
+    pub steps: Vec<SmokeStep>,
+    /// 定时执行的 cron 表达式；为空时只按需执行
+    #[serde(default)]
+    pub schedule: Option<String>,
+    /// 定时执行的设备；为空时为全部已连接设备
+    #[serde(default)]
+    pub devices: Vec<String>,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+}
+
+impl SmokeTestSpec {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.id.trim().is_empty() || self.package.trim().is_empty() {
+            return Err("冒烟测试需要 id 与应用包名".to_string());
+        }
+        if !self.steps.iter().any(|s| matches!(s, SmokeStep::Launch { .. })) {
+            return Err(format!("冒烟测试 {} 缺少启动步骤", self.id));
+        }
+        for step in &self.steps {
+            match step {
+                SmokeStep::ExpectElement { any_of, timeout_secs } => {
+                    if any_of.is_empty() || any_of.iter().any(ElementSelector::is_empty) {
+                        return Err(format!("{}: 元素条件不能为空", self.id));
+                    }
+                    if *timeout_secs == 0 || *timeout_secs > MAX_TIMEOUT_SECS {
+                        return Err(format!("{}: 等待时间需在 1~{} 秒之间", self.id, MAX_TIMEOUT_SECS));
+                    }
+                }
+                SmokeStep::Tap { selector, .. } if selector.is_empty() => {
+                    return Err(format!("{}: 点击步骤的元素条件不能为空", self.id));
+                }
+                SmokeStep::Screenshot { label } if label.trim().is_empty() => {
+                    return Err(format!("{}: 截图需要标签", self.id));
+                }
+                _ => {}
+            }
+        }
+        if let Some(cron) = &self.schedule {
+            CronExpr::parse(cron)?;
+        }
+        Ok(())
+    }
+}
+
+fn expect(text: &[&str], resource_id: Option<&str>) -> SmokeStep {
+    let mut any_of: Vec<ElementSelector> = text
+        .iter()
+        .map(|t| ElementSelector { text: Some(t.to_string()), ..Default::default() })
+        .collect();
+    if let Some(id) = resource_id {
+        any_of.push(ElementSelector { resource_id: Some(id.to_string()), ..Default::default() });
+    }
+    SmokeStep::ExpectElement { any_of, timeout_secs: DEFAULT_EXPECT_TIMEOUT_SECS }
+}
+
+fn builtin(id: &str, name: &str, app: &str, package: &str, landing: SmokeStep) -> SmokeTestSpec {
+    SmokeTestSpec {
+        id: id.to_string(),
+        name: name.to_string(),
+        app: app.to_string(),
+        package: package.to_string(),
+        activity: None,
+        steps: vec![SmokeStep::Launch { cold_start: true }, landing, SmokeStep::Screenshot { label: "landing".to_string() }],
+        schedule: None,
+        devices: Vec::new(),
+        enabled: true,
+    }
+}
+
+/// 内置定义：首次使用时写入，之后可自由修改
+pub fn builtin_specs() -> Vec<SmokeTestSpec> {
+    vec![
+        builtin("xhs-launch", "小红书启动到首页", "xhs", "com.xingin.xhs", expect(&["首页", "发现"], None)),
+        builtin("wechat-launch", "微信启动到会话列表", "wechat", "com.tencent.mm", expect(&["微信", "通讯录"], None)),
+        builtin(
+            "contacts-launch",
+            "通讯录启动到联系人列表",
+            "contacts",
+            "com.android.contacts",
+            expect(&["联系人", "Contacts"], Some("com.android.contacts:id/floating_action_button")),
+        ),
+    ]
+}
@@ -0,0 +1,141 @@
+// src-tauri/src/services/support_session/bundle.rs
+// module: support_session | layer: services | role: 加密日志包
+// summary: 未配置支持端点时把日志按块加密写入滚动文件；密钥由会话码与随机盐派生，支持人员凭会话码解密；文件按会话 ID 命名，会话码不落盘
+
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use chacha20poly1305::aead::{Aead, KeyInit};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use rand::RngCore;
+use sha2::{Digest, Sha256};
+
+use crate::services::log_bridge::LogEntry;
+
+const MAGIC: &[u8; 8] = b"MADSUP1\n";
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+/// 密钥派生迭代次数（拖慢对会话码的离线穷举）
+const KDF_ROUNDS: u32 = 100_000;
+pub const MAX_FILE_BYTES: u64 = 1024 * 1024;
+pub const MAX_FILES: usize = 5;
+
+/// 会话码忽略大小写、空格与短横线
+pub fn normalize_code(code: &str) -> String {
+    code.chars().filter(|c| c.is_ascii_alphanumeric()).map(|c| c.to_ascii_uppercase()).collect()
+}
+
+fn derive_key(code: &str, salt: &[u8]) -> [u8; 32] {
+    let mut digest: [u8; 32] = Sha256::new().chain_update(salt).chain_update(normalize_code(code)).finalize().into();
+    for _ in 0..KDF_ROUNDS {
+        digest = Sha256::new().chain_update(digest).chain_update(salt).finalize().into();
+    }
+    digest
+}
+
+/// 滚动写入的加密日志包：文件头为魔数 + 盐，之后每块为 nonce + 长度 + 密文（JSONL）
+pub struct BundleWriter {
+    dir: PathBuf,
+    prefix: String,
+    salt: [u8; SALT_LEN],
+    cipher: ChaCha20Poly1305,
+    seq: u32,
+    current_size: u64,
+    max_file_bytes: u64,
+    max_files: usize,
+}
+
+impl BundleWriter {
+    /// `session_id` 只用于文件命名；会话码只参与密钥派生，不写入文件名或文件内容
+    pub fn new(dir: PathBuf, session_id: &str, code: &str, max_file_bytes: u64, max_files: usize) -> Result<Self, String> {
+        fs::create_dir_all(&dir).map_err(|e| format!("创建日志包目录失败: {}", e))?;
+        let mut salt = [0u8; SALT_LEN];
+        rand::thread_rng().fill_bytes(&mut salt);
+        let key = derive_key(code, &salt);
+        Ok(Self {
+            dir,
+            prefix: format!("support-{}", session_id),
+            salt,
+            cipher: ChaCha20Poly1305::new(Key::from_slice(&key)),
+            seq: 0,
+            current_size: 0,
+            max_file_bytes,
+            max_files: max_files.max(1),
+        })
+    }
+
+    fn file_path(&self, seq: u32) -> PathBuf {
+        self.dir.join(format!("{}-{:04}.log.enc", self.prefix, seq))
+    }
+
+    fn rotate(&mut self) -> Result<(), String> {
+        self.seq += 1;
+        let mut file = File::create(self.file_path(self.seq)).map_err(|e| format!("创建日志包文件失败: {}", e))?;
+        file.write_all(MAGIC).and_then(|_| file.write_all(&self.salt)).map_err(|e| e.to_string())?;
+        self.current_size = (MAGIC.len() + SALT_LEN) as u64;
+        if self.seq as usize > self.max_files {
+            let _ = fs::remove_file(self.file_path(self.seq - self.max_files as u32));
+        }
+        Ok(())
+    }
+
+    /// 追加一块日志；当前文件写满时滚动到新文件并删除最旧的文件
+    pub fn append(&mut self, entries: &[LogEntry]) -> Result<(), String> {
+        if entries.is_empty() {
+            return Ok(());
+        }
+        let mut plaintext = Vec::new();
+        for entry in entries {
+            serde_json::to_writer(&mut plaintext, entry).map_err(|e| e.to_string())?;
+            plaintext.push(b'\n');
+        }
+        let mut nonce = [0u8; NONCE_LEN];
+        rand::thread_rng().fill_bytes(&mut nonce);
+        let ciphertext =
+            self.cipher.encrypt(Nonce::from_slice(&nonce), plaintext.as_slice()).map_err(|_| "日志加密失败".to_string())?;
+        let record_len = (NONCE_LEN + 4 + ciphertext.len()) as u64;
+        if self.seq == 0 || self.current_size + record_len > self.max_file_bytes {
+            self.rotate()?;
+        }
+        let mut file = OpenOptions::new().append(true).open(self.file_path(self.seq)).map_err(|e| e.to_string())?;
+        file.write_all(&nonce)
+            .and_then(|_| file.write_all(&(ciphertext.len() as u32).to_le_bytes()))
+            .and_then(|_| file.write_all(&ciphertext))
+            .map_err(|e| format!("写入日志包失败: {}", e))?;
+        self.current_size += record_len;
+        Ok(())
+    }
+
+    /// 当前保留的日志包文件（旧 → 新）
+    pub fn files(&self) -> Vec<PathBuf> {
+        let first = self.seq.saturating_sub(self.max_files as u32) + 1;
+        (first..=self.seq).map(|seq| self.file_path(seq)).filter(|path| path.exists()).collect()
+    }
+}
+
+/// 用会话码解密一个日志包文件（支持人员排查时使用）
+pub fn decrypt_bundle_file(path: &Path, code: &str) -> Result<Vec<LogEntry>, String> {
+    let data = fs::read(path).map_err(|e| format!("读取日志包失败: {}", e))?;
+    let header_len = MAGIC.len() + SALT_LEN;
+    if data.len() < header_len || &data[..MAGIC.len()] != MAGIC {
+        return Err("不是支持会话日志包".to_string());
+    }
+    let cipher = ChaCha20Poly1305::new(Key::from_slice(&derive_key(code, &data[MAGIC.len()..header_len])));
+    let mut entries = Vec::new();
+    let mut offset = header_len;
+    while offset < data.len() {
+        let body_start = offset + NONCE_LEN + 4;
+        let len_bytes = data.get(offset + NONCE_LEN..body_start).ok_or("日志包已截断")?;
+        let len = u32::from_le_bytes(len_bytes.try_into().map_err(|_| "日志包已截断")?) as usize;
+        let ciphertext = data.get(body_start..body_start + len).ok_or("日志包已截断")?;
+        let plaintext = cipher
+            .decrypt(Nonce::from_slice(&data[offset..offset + NONCE_LEN]), ciphertext)
+            .map_err(|_| "解密失败：会话码不正确或文件已损坏".to_string())?;
+        for line in plaintext.split(|b| *b == b'\n').filter(|line| !line.is_empty()) {
+            entries.push(serde_json::from_slice(line).map_err(|e| e.to_string())?);
+        }
+        offset = body_start + len;
+    }
+    Ok(entries)
+}
@@ -0,0 +1,390 @@
+// src-tauri/src/services/support_session/mod.rs
+// module: support_session | layer: services | role: 支持会话
+// summary: 用户主动开启的支持会话：把结构化日志环按条件过滤、脱敏后实时推送到支持端点，或写入滚动加密日志包；以会话码标识，可随时硬停止，到期自动结束
+
+pub mod bundle;
+
+use std::path::PathBuf;
+use std::sync::{Mutex, MutexGuard, OnceLock};
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use once_cell::sync::Lazy;
+use rand::Rng;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use self::bundle::BundleWriter;
+use crate::services::log_bridge::{LogEntry, LOG_COLLECTOR};
+
+/// 会话码字符集（去掉易混淆的 0/O、1/I/L）
+const CODE_ALPHABET: &[u8] = b"ABCDEFGHJKMNPQRSTUVWXYZ23456789";
+const DEFAULT_DURATION_MINUTES: u32 = 30;
+const MAX_DURATION_MINUTES: u32 = 240;
+const TICK: Duration = Duration::from_secs(3);
+/// 推送失败时最多积压的条数，超出丢弃最旧的
+const MAX_PENDING: usize = 2000;
+const BATCH_SIZE: usize = 200;
+
+/// 日志过滤条件
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct SupportLogFilter {
+    /// 最低级别：DEBUG / INFO / WARN / ERROR
+    pub min_level: Option<String>,
+    /// 为空时不限
+    pub categories: Vec<String>,
+    pub sources: Vec<String>,
+}
+
+impl SupportLogFilter {
+    pub fn matches(&self, entry: &LogEntry) -> bool {
+        let level_ok = self.min_level.as_deref().is_none_or(|min| level_rank(&entry.level) >= level_rank(min));
+        level_ok
+            && (self.categories.is_empty() || self.categories.iter().any(|c| c.eq_ignore_ascii_case(&entry.category)))
+            && (self.sources.is_empty() || self.sources.contains(&entry.source))
+    }
+}
+
+fn level_rank(level: &str) -> u8 {
+    match level.to_ascii_uppercase().as_str() {
+        "DEBUG" => 0,
+        "INFO" => 1,
+        "WARN" | "WARNING" => 2,
+        "ERROR" => 3,
+        _ => 1,
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct SupportSessionOptions {
+    /// 支持端点；为空时写入本地加密日志包
+    pub endpoint: Option<String>,
+    pub filter: SupportLogFilter,
+    /// 有效期（分钟），默认 30，最长 240
+    pub duration_minutes: Option<u32>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SupportSessionMode {
+    Stream,
+    Bundle,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SupportSessionStatus {
+    pub active: bool,
+    /// 会话 ID（日志包目录与文件按它命名）
+    pub session_id: String,
+    /// 用户念给支持人员的会话码（只在内存中，不落盘）
+    pub code: String,
+    pub mode: SupportSessionMode,
+    pub endpoint: Option<String>,
+    pub bundle_dir: Option<String>,
+    pub bundle_files: Vec<String>,
+    pub filter: SupportLogFilter,
+    pub started_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+    /// 已推送 / 已写入的条数
+    pub shipped: u64,
+    /// 推送积压超限而丢弃的条数
+    pub dropped: u64,
+    pub last_error: Option<String>,
+    pub ended_at: Option<DateTime<Utc>>,
+    /// stopped / expired
+    pub end_reason: Option<String>,
+}
+
+/// 推送到支持端点的一批日志
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SupportLogBatch<'a> {
+    session_code: &'a str,
+    app_version: &'static str,
+    entries: &'a [LogEntry],
+}
+
+struct Session {
+    generation: u64,
+    status: SupportSessionStatus,
+    /// 最后一条已处理日志的 (id, 时间戳)
+    cursor: Option<(String, String)>,
+    pending: Vec<LogEntry>,
+    bundle: Option<BundleWriter>,
+    worker: Option<tauri::async_runtime::JoinHandle<()>>,
+}
+
+#[derive(Default)]
+struct SessionSlot {
+    current: Option<Session>,
+    last: Option<SupportSessionStatus>,
+    generation: u64,
+}
+
+fn slot() -> MutexGuard<'static, SessionSlot> {
+    static SLOT: OnceLock<Mutex<SessionSlot>> = OnceLock::new();
+    SLOT.get_or_init(Mutex::default).lock().unwrap_or_else(|e| e.into_inner())
+}
+
+static MASKS: Lazy<Vec<(Regex, &'static str)>> = Lazy::new(|| {
+    [
+        (r"[A-Za-z0-9._%+-]+@[A-Za-z0-9.-]+\.[A-Za-z]{2,}", "<email>"),
+        // 身份证号先于手机号处理，避免只遮住其中一段
+        (r"(^|\D)\d{17}[\dXx](\D|$)", "${1}<id>${2}"),
+        (r"(^|\D)(1[3-9]\d)\d{4}(\d{4})(\D|$)", "${1}${2}****${3}${4}"),
+        // JSON 详情中的反斜杠是转义后的双反斜杠
+        (r#"(?i)([A-Z]:\\{1,2}Users\\{1,2}|/home/|/Users/)[^\\/\s"]+"#, "${1}<user>"),
+        (r#"(?i)(token|api[_-]?key|password|secret|authorization)("?\s*[:=]\s*"?)[^\s",]+"#, "${1}${2}<redacted>"),
+    ]
+    .into_iter()
+    .map(|(pattern, replacement)| (Regex::new(pattern).expect("valid mask pattern"), replacement))
+    .collect()
+});
+
+/// 遮盖邮箱、手机号、身份证号、用户目录名与凭据
+pub fn mask_pii(text: &str) -> String {
+    MASKS.iter().fold(text.to_string(), |acc, (regex, replacement)| regex.replace_all(&acc, *replacement).into_owned())
+}
+
+fn masked(entry: &LogEntry) -> LogEntry {
+    LogEntry {
+        message: mask_pii(&entry.message),
+        details: entry.details.as_deref().map(mask_pii),
+        ..entry.clone()
+    }
+}
+
+/// 游标之后的新日志。游标条目已被日志环淘汰时按时间戳续接
+fn entries_after<'a>(ring: &'a [LogEntry], cursor: Option<&(String, String)>) -> &'a [LogEntry] {
+    let Some((id, timestamp)) = cursor else { return ring };
+    match ring.iter().position(|entry| &entry.id == id) {
+        Some(index) => &ring[index + 1..],
+        None => &ring[ring.partition_point(|entry| &entry.timestamp <= timestamp)..],
+    }
+}
+
+fn generate_code() -> String {
+    let mut rng = rand::thread_rng();
+    let chars: String = (0..12).map(|_| CODE_ALPHABET[rng.gen_range(0..CODE_ALPHABET.len())] as char).collect();
+    format!("{}-{}-{}", &chars[0..4], &chars[4..8], &chars[8..12])
+}
+
+fn bundle_root() -> PathBuf {
+    dirs::data_dir().unwrap_or_else(|| PathBuf::from(".")).join("employee-gui").join("support")
+}
+
+/// 开启支持会话（同一时间只允许一个）
+pub fn start(options: SupportSessionOptions) -> Result<SupportSessionStatus, String> {
+    let endpoint = options.endpoint.map(|e| e.trim().to_string()).filter(|e| !e.is_empty());
+    if let Some(endpoint) = &endpoint {
+        if !endpoint.starts_with("https://") && !endpoint.starts_with("http://") {
+            return Err(format!("支持端点必须是 http(s) 地址: {}", endpoint));
+        }
+    }
+    let minutes = options.duration_minutes.unwrap_or(DEFAULT_DURATION_MINUTES).clamp(1, MAX_DURATION_MINUTES);
+
+    let mut slot = slot();
+    if let Some(current) = &slot.current {
+        return Err(format!("已有进行中的支持会话（会话码 {}）", current.status.code));
+    }
+    let code = generate_code();
+    let session_id = uuid::Uuid::new_v4().simple().to_string();
+    let bundle_dir = bundle_root().join(&session_id);
+    let bundle = match &endpoint {
+        Some(_) => None,
+        None => Some(BundleWriter::new(bundle_dir.clone(), &session_id, &code, bundle::MAX_FILE_BYTES, bundle::MAX_FILES)?),
+    };
+    let now = Utc::now();
+    let status = SupportSessionStatus {
+        active: true,
+        session_id: session_id.clone(),
+        code,
+        mode: if endpoint.is_some() { SupportSessionMode::Stream } else { SupportSessionMode::Bundle },
+        endpoint,
+        bundle_dir: bundle.as_ref().map(|_| bundle_dir.display().to_string()),
+        bundle_files: Vec::new(),
+        filter: options.filter,
+        started_at: now,
+        expires_at: now + chrono::Duration::minutes(minutes as i64),
+        shipped: 0,
+        dropped: 0,
+        last_error: None,
+        ended_at: None,
+        end_reason: None,
+    };
+    slot.generation += 1;
+    let generation = slot.generation;
+    let worker = tauri::async_runtime::spawn(run_session(generation));
+    slot.current = Some(Session { generation, status: status.clone(), cursor: None, pending: Vec::new(), bundle, worker: Some(worker) });
+    info!("🆘 支持会话已开启: id={}, 模式={:?}, 有效期 {} 分钟", session_id, status.mode, minutes);
+    Ok(status)
+}
+
+fn end(slot: &mut SessionSlot, reason: &str) -> Option<SupportSessionStatus> {
+    let mut session = slot.current.take()?;
+    if let Some(worker) = session.worker.take() {
+        worker.abort();
+    }
+    let mut status = session_status(&session);
+    status.active = false;
+    status.ended_at = Some(Utc::now());
+    status.end_reason = Some(reason.to_string());
+    info!("🆘 支持会话已结束: id={}, 原因={}, 共 {} 条", status.session_id, reason, status.shipped);
+    slot.last = Some(status.clone());
+    Some(status)
+}
+
+/// 硬停止：立即中止推送 / 写入，不再发送任何日志
+pub fn stop() -> Result<SupportSessionStatus, String> {
+    end(&mut slot(), "stopped").ok_or_else(|| "没有进行中的支持会话".to_string())
+}
+
+fn session_status(session: &Session) -> SupportSessionStatus {
+    let mut status = session.status.clone();
+    if let Some(bundle) = &session.bundle {
+        status.bundle_files = bundle.files().iter().map(|p| p.display().to_string()).collect();
+    }
+    status
+}
+
+/// 当前会话状态；没有进行中的会话时返回最近结束的一次
+pub fn status() -> Option<SupportSessionStatus> {
+    let slot = slot();
+    slot.current.as_ref().map(session_status).or_else(|| slot.last.clone())
+}
+
+/// 收集游标之后的新日志（过滤 + 脱敏）并前移游标
+fn collect_new(session: &mut Session) -> Vec<LogEntry> {
+    let ring = LOG_COLLECTOR.get_logs();
+    let fresh = entries_after(&ring, session.cursor.as_ref());
+    if let Some(last) = fresh.last() {
+        session.cursor = Some((last.id.clone(), last.timestamp.clone()));
+    }
+    fresh.iter().filter(|entry| session.status.filter.matches(entry)).map(masked).collect()
+}
+
+/// 一个会话周期；返回 false 表示会话已结束
+async fn tick(client: &reqwest::Client, generation: u64) -> bool {
+    let (endpoint, code, batch) = {
+        let mut slot = slot();
+        let expired = match &slot.current {
+            Some(session) if session.generation == generation => Utc::now() >= session.status.expires_at,
+            _ => return false,
+        };
+        if expired {
+            end(&mut slot, "expired");
+            return false;
+        }
+        let Some(session) = slot.current.as_mut() else { return false };
+        let fresh = collect_new(session);
+        if let Some(bundle) = session.bundle.as_mut() {
+            match bundle.append(&fresh) {
+                Ok(()) => session.status.shipped += fresh.len() as u64,
+                Err(e) => session.status.last_error = Some(e),
+            }
+            return true;
+        }
+        session.pending.extend(fresh);
+        if session.pending.len() > MAX_PENDING {
+            let overflow = session.pending.len() - MAX_PENDING;
+            session.pending.drain(..overflow);
+            session.status.dropped += overflow as u64;
+        }
+        let take = session.pending.len().min(BATCH_SIZE);
+        let batch: Vec<LogEntry> = session.pending.drain(..take).collect();
+        (session.status.endpoint.clone().unwrap_or_default(), session.status.code.clone(), batch)
+    };
+    if batch.is_empty() {
+        return true;
+    }
+
+    let payload = SupportLogBatch { session_code: &code, app_version: env!("CARGO_PKG_VERSION"), entries: &batch };
+    let outcome = client.post(&endpoint).json(&payload).send().await.and_then(|r| r.error_for_status());
+    let mut slot = slot();
+    let Some(session) = slot.current.as_mut().filter(|s| s.generation == generation) else { return false };
+    match outcome {
+        Ok(_) => {
+            session.status.shipped += batch.len() as u64;
+            session.status.last_error = None;
+        }
+        Err(e) => {
+            warn!("⚠️ 支持会话日志推送失败: {}", e);
+            session.status.last_error = Some(e.to_string());
+            // 失败的批次放回队首，下个周期重试
+            session.pending.splice(0..0, batch);
+        }
+    }
+    true
+}
+
+async fn run_session(generation: u64) {
+    let client = match reqwest::Client::builder().timeout(Duration::from_secs(10)).build() {
+        Ok(client) => client,
+        Err(e) => {
+            warn!("⚠️ 支持会话无法创建 HTTP 客户端: {}", e);
+            return;
+        }
+    };
+    while tick(&client, generation).await {
+        tokio::time::sleep(TICK).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(id: &str, timestamp: &str, level: &str, message: &str) -> LogEntry {
+        LogEntry {
+            id: id.to_string(),
+            timestamp: timestamp.to_string(),
+            level: level.to_string(),
+            category: "DEVICE".to_string(),
+            source: "adb".to_string(),
+            message: message.to_string(),
+            details: Some(r#"{"password":"hunter2","path":"C:\\Users\\zhang\\a.txt"}"#.to_string()),
+            device_id: Some("emulator-5554".to_string()),
+            session_id: "s".to_string(),
+        }
+    }
+
+    #[test]
+    fn filters_masks_and_round_trips_encrypted_bundle() {
+        assert_eq!(
+            mask_pii("导入 13812345678 失败, 联系 a.b@example.com, 身份证 11010519491231002X"),
+            "导入 138****5678 失败, 联系 <email>, 身份证 <id>"
+        );
+        let masked_entry = masked(&entry("1", "t1", "INFO", "ok"));
+        assert_eq!(masked_entry.details.as_deref(), Some(r#"{"password":"<redacted>","path":"C:\\Users\\<user>\\a.txt"}"#));
+
+        let ring = vec![entry("1", "t1", "DEBUG", "a"), entry("2", "t2", "WARN", "b"), entry("3", "t3", "ERROR", "c")];
+        assert_eq!(entries_after(&ring, Some(&("1".into(), "t1".into()))).len(), 2);
+        // 游标条目已被淘汰：按时间戳续接
+        assert_eq!(entries_after(&ring, Some(&("0".into(), "t2".into())))[0].id, "3");
+        let filter = SupportLogFilter { min_level: Some("warn".into()), ..Default::default() };
+        assert_eq!(ring.iter().filter(|e| filter.matches(e)).count(), 2);
+
+        let dir = tempfile::tempdir().unwrap();
+        let code = generate_code();
+        let mut writer = BundleWriter::new(dir.path().to_path_buf(), "3f2a9c", &code, 400, 2).unwrap();
+        for chunk in ring.chunks(1) {
+            writer.append(chunk).unwrap();
+        }
+        let files = writer.files();
+        assert_eq!(files.len(), 2, "超出文件数上限时删除最旧的文件");
+        let normalized = bundle::normalize_code(&code);
+        for file in &files {
+            let name = file.file_name().unwrap().to_string_lossy().to_string();
+            assert!(name.starts_with("support-3f2a9c-") && !name.contains(&normalized), "{}", name);
+            let bytes = std::fs::read(file).unwrap();
+            assert!(!bytes.windows(normalized.len()).any(|w| w == normalized.as_bytes()), "会话码不能写入日志包");
+        }
+        let decrypted = bundle::decrypt_bundle_file(files.last().unwrap(), &code.to_lowercase()).unwrap();
+        assert_eq!(decrypted.last().unwrap().id, "3");
+        assert!(bundle::decrypt_bundle_file(files.last().unwrap(), "WRONG-CODE-0000").is_err());
+    }
+}
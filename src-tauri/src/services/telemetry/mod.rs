@@ -0,0 +1,301 @@
+// src-tauri/src/services/telemetry/mod.rs
+// module: telemetry | layer: services | role: 匿名使用统计
+// summary: 用户主动开启后在本地按天聚合功能使用计数（不含任何个人信息），离线模式只累计不上传，联网时分批上报到可配置地址并重试；待上报内容可随时查看
+
+use chrono::{NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+use tracing::{debug, info, warn};
+
+const CONFIG_FILE_NAME: &str = "telemetry_config.json";
+const PENDING_FILE_NAME: &str = "telemetry_pending.json";
+/// 功能名最大长度；超长或含非法字符的名称直接丢弃，防止把用户数据当作功能名记录
+const MAX_FEATURE_LEN: usize = 64;
+/// 本地最多保留多少天未上报的计数
+const MAX_PENDING_DAYS: i64 = 30;
+/// 每累计多少次计数落盘一次
+const FLUSH_EVERY: u64 = 20;
+
+static TELEMETRY: OnceLock<Mutex<Telemetry>> = OnceLock::new();
+
+/// 统计配置（默认关闭）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TelemetryConfig {
+    pub enabled: bool,
+    /// 上报地址；为空时只在本地聚合
+    pub endpoint: Option<String>,
+    /// 离线模式：只累计不上传
+    pub offline: bool,
+    pub upload_interval_secs: u64,
+    pub max_retries: u32,
+    /// 匿名安装 ID（随机生成，与设备 / 账号无关）
+    pub install_id: String,
+}
+
+impl Default for TelemetryConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            endpoint: None,
+            offline: false,
+            upload_interval_secs: 6 * 3600,
+            max_retries: 3,
+            install_id: uuid::Uuid::new_v4().to_string(),
+        }
+    }
+}
+
+/// 一天内某功能的使用次数
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct UsageCounter {
+    pub date: NaiveDate,
+    pub feature: String,
+    pub count: u64,
+}
+
+/// 一次上报的完整内容（`get_pending_telemetry` 返回的就是它）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TelemetryBatch {
+    pub install_id: String,
+    pub app_version: String,
+    pub os: String,
+    pub generated_at: String,
+    pub counters: Vec<UsageCounter>,
+}
+
+/// 上报结果
+#[derive(Debug, Clone, Serialize)]
+pub struct UploadOutcome {
+    pub uploaded: usize,
+    pub attempts: u32,
+    pub skipped_reason: Option<String>,
+}
+
+/// 功能名须以小写字母开头，只含小写字母、数字、点、下划线、短横线（排除手机号等纯数字内容）
+fn is_valid_feature(feature: &str) -> bool {
+    feature.starts_with(|c: char| c.is_ascii_lowercase())
+        && feature.len() <= MAX_FEATURE_LEN
+        && feature.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || matches!(c, '.' | '_' | '-'))
+}
+
+#[derive(Debug)]
+pub struct Telemetry {
+    config: TelemetryConfig,
+    /// (日期, 功能) → 次数
+    pending: BTreeMap<(NaiveDate, String), u64>,
+    data_dir: Option<PathBuf>,
+    unflushed: u64,
+}
+
+impl Telemetry {
+    pub fn global() -> &'static Mutex<Telemetry> {
+        TELEMETRY.get_or_init(|| {
+            let base = dirs::data_dir().unwrap_or_else(|| PathBuf::from(".")).join("employee-gui");
+            Mutex::new(Self::load(base))
+        })
+    }
+
+    pub fn load(data_dir: PathBuf) -> Self {
+        let read = |name: &str| std::fs::read_to_string(data_dir.join(name)).ok();
+        let config_exists = data_dir.join(CONFIG_FILE_NAME).exists();
+        let config: TelemetryConfig = read(CONFIG_FILE_NAME).and_then(|c| serde_json::from_str(&c).ok()).unwrap_or_default();
+        let counters: Vec<UsageCounter> = read(PENDING_FILE_NAME).and_then(|c| serde_json::from_str(&c).ok()).unwrap_or_default();
+        let mut telemetry = Self {
+            config,
+            pending: counters.into_iter().map(|c| ((c.date, c.feature), c.count)).collect(),
+            data_dir: Some(data_dir),
+            unflushed: 0,
+        };
+        if !config_exists {
+            // 首次运行固定匿名安装 ID
+            telemetry.save_config();
+        }
+        telemetry
+    }
+
+    /// 不落盘的实例
+    pub fn in_memory(config: TelemetryConfig) -> Self {
+        Self { config, pending: BTreeMap::new(), data_dir: None, unflushed: 0 }
+    }
+
+    pub fn config(&self) -> &TelemetryConfig {
+        &self.config
+    }
+
+    /// 更新配置；关闭统计时同时清空本地未上报的计数
+    pub fn set_config(&mut self, mut config: TelemetryConfig) {
+        config.install_id = self.config.install_id.clone();
+        if !config.enabled {
+            self.pending.clear();
+            self.flush();
+        }
+        self.config = config;
+        self.save_config();
+    }
+
+    pub fn record(&mut self, feature: &str) {
+        self.record_on(Utc::now().date_naive(), feature);
+    }
+
+    pub fn record_on(&mut self, date: NaiveDate, feature: &str) {
+        if !self.config.enabled {
+            return;
+        }
+        if !is_valid_feature(feature) {
+            debug!("忽略非法功能名: {:?}", feature);
+            return;
+        }
+        *self.pending.entry((date, feature.to_string())).or_insert(0) += 1;
+        let oldest = date - chrono::Duration::days(MAX_PENDING_DAYS);
+        self.pending.retain(|(day, _), _| *day >= oldest);
+        self.unflushed += 1;
+        if self.unflushed >= FLUSH_EVERY {
+            self.flush();
+        }
+    }
+
+    /// 当前会被上报的内容
+    pub fn pending_batch(&self) -> TelemetryBatch {
+        TelemetryBatch {
+            install_id: self.config.install_id.clone(),
+            app_version: env!("CARGO_PKG_VERSION").to_string(),
+            os: std::env::consts::OS.to_string(),
+            generated_at: Utc::now().to_rfc3339(),
+            counters: self
+                .pending
+                .iter()
+                .map(|((date, feature), count)| UsageCounter { date: *date, feature: feature.clone(), count: *count })
+                .collect(),
+        }
+    }
+
+    /// 上报成功后扣除已上报的计数（上报期间新增的计数保留）
+    pub fn acknowledge(&mut self, counters: &[UsageCounter]) {
+        for counter in counters {
+            let key = (counter.date, counter.feature.clone());
+            if let Some(count) = self.pending.get_mut(&key) {
+                *count = count.saturating_sub(counter.count);
+                if *count == 0 {
+                    self.pending.remove(&key);
+                }
+            }
+        }
+        self.flush();
+    }
+
+    pub fn flush(&mut self) {
+        self.unflushed = 0;
+        let counters = self.pending_batch().counters;
+        self.save(PENDING_FILE_NAME, &counters);
+    }
+
+    fn save_config(&self) {
+        self.save(CONFIG_FILE_NAME, &self.config);
+    }
+
+    fn save<T: Serialize>(&self, file_name: &str, value: &T) {
+        let Some(dir) = &self.data_dir else { return };
+        let result = std::fs::create_dir_all(dir)
+            .map_err(|e| e.to_string())
+            .and_then(|_| serde_json::to_string_pretty(value).map_err(|e| e.to_string()))
+            .and_then(|content| std::fs::write(dir.join(file_name), content).map_err(|e| e.to_string()));
+        if let Err(e) = result {
+            warn!("⚠️ 使用统计保存失败: {}", e);
+        }
+    }
+}
+
+/// 记录一次功能使用（未开启统计时无操作）
+pub fn record_feature(feature: &str) {
+    if let Ok(mut telemetry) = Telemetry::global().lock() {
+        telemetry.record(feature);
+    }
+}
+
+/// 上报待发送的计数，失败按指数退避重试
+pub async fn upload_pending() -> Result<UploadOutcome, String> {
+    let (config, batch) = {
+        let telemetry = Telemetry::global().lock().map_err(|e| e.to_string())?;
+        (telemetry.config().clone(), telemetry.pending_batch())
+    };
+    let skipped = |reason: &str| Ok(UploadOutcome { uploaded: 0, attempts: 0, skipped_reason: Some(reason.to_string()) });
+    if !config.enabled {
+        return skipped("未开启使用统计");
+    }
+    if config.offline {
+        return skipped("离线模式");
+    }
+    let Some(endpoint) = config.endpoint.filter(|e| !e.trim().is_empty()) else {
+        return skipped("未配置上报地址");
+    };
+    if batch.counters.is_empty() {
+        return skipped("没有待上报的数据");
+    }
+
+    let client = reqwest::Client::builder().timeout(Duration::from_secs(15)).build().map_err(|e| e.to_string())?;
+    let mut last_error = String::new();
+    for attempt in 1..=config.max_retries.max(1) {
+        match client.post(&endpoint).json(&batch).send().await.and_then(|r| r.error_for_status()) {
+            Ok(_) => {
+                Telemetry::global().lock().map_err(|e| e.to_string())?.acknowledge(&batch.counters);
+                info!("📊 使用统计已上报: {} 条", batch.counters.len());
+                return Ok(UploadOutcome { uploaded: batch.counters.len(), attempts: attempt, skipped_reason: None });
+            }
+            Err(e) => {
+                last_error = e.to_string();
+                warn!("⚠️ 使用统计上报失败（第 {} 次）: {}", attempt, last_error);
+                if attempt < config.max_retries {
+                    tokio::time::sleep(Duration::from_secs(2u64.pow(attempt))).await;
+                }
+            }
+        }
+    }
+    Err(format!("使用统计上报失败: {}", last_error))
+}
+
+/// 后台定期上报（应用启动时 spawn）
+pub async fn run_uploader() {
+    loop {
+        let interval = Telemetry::global()
+            .lock()
+            .map(|t| t.config().upload_interval_secs)
+            .unwrap_or(6 * 3600)
+            .max(60);
+        tokio::time::sleep(Duration::from_secs(interval)).await;
+        if let Err(e) = upload_pending().await {
+            debug!("{}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn aggregates_only_when_opted_in_and_keeps_counts_added_during_upload() {
+        let day = NaiveDate::from_ymd_opt(2026, 10, 1).unwrap();
+        let mut telemetry = Telemetry::in_memory(TelemetryConfig::default());
+        telemetry.record_on(day, "agent.start");
+        assert!(telemetry.pending_batch().counters.is_empty());
+
+        telemetry.set_config(TelemetryConfig { enabled: true, ..TelemetryConfig::default() });
+        telemetry.record_on(day, "agent.start");
+        telemetry.record_on(day, "agent.start");
+        telemetry.record_on(day, "13800138000");
+        telemetry.record_on(day, "张三");
+        let batch = telemetry.pending_batch();
+        assert_eq!(batch.counters, vec![UsageCounter { date: day, feature: "agent.start".into(), count: 2 }]);
+
+        telemetry.record_on(day, "agent.start");
+        telemetry.acknowledge(&batch.counters);
+        assert_eq!(telemetry.pending_batch().counters[0].count, 1);
+
+        telemetry.set_config(TelemetryConfig::default());
+        assert!(telemetry.pending_batch().counters.is_empty());
+    }
+}
@@ -0,0 +1,324 @@
+// src-tauri/src/services/trend_rollup/anomaly.rs
+// module: trend_rollup | layer: services | role: 指标异常检测
+// summary: 定期对按天汇总的指标做 z-score / EWMA 检测，失败率、封号数等突增时发出通知事件；每个指标可单独配置灵敏度
+
+use chrono::{Local, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet, VecDeque};
+use std::path::PathBuf;
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Runtime};
+use tracing::{info, warn};
+
+use super::{Granularity, Metric, RollupStore, TimeRange, TOTAL_SERIES};
+
+const CONFIG_FILE_NAME: &str = "anomaly_detector.json";
+/// 前端通知事件名
+pub const ANOMALY_EVENT: &str = "anomaly://detected";
+/// 内存中保留的最近异常条数
+const MAX_RECENT: usize = 200;
+/// 标准差过小时的下限，避免基线完全平稳时任何波动都被放大成异常
+const MIN_STD: f64 = 1e-6;
+
+static DETECTOR: OnceLock<Mutex<AnomalyDetector>> = OnceLock::new();
+
+/// 检测方法
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DetectionMethod {
+    /// 当前值相对基线均值的标准分
+    ZScore,
+    /// 指数加权均值 / 方差
+    Ewma,
+}
+
+/// 关注的变化方向
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Direction {
+    Up,
+    Down,
+    Both,
+}
+
+/// 单个指标的灵敏度
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MetricSensitivity {
+    pub enabled: bool,
+    pub method: DetectionMethod,
+    /// 触发阈值（标准差倍数），越小越灵敏
+    pub threshold: f64,
+    /// EWMA 平滑系数（0~1）
+    pub ewma_alpha: f64,
+    pub direction: Direction,
+    /// 当前值的绝对下限，低于此值不报（如失败率 < 5% 时不报）
+    pub min_value: f64,
+    /// 是否对每个维度（动作 / 设备）单独检测
+    pub per_dimension: bool,
+}
+
+impl Default for MetricSensitivity {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            method: DetectionMethod::ZScore,
+            threshold: 3.0,
+            ewma_alpha: 0.3,
+            direction: Direction::Up,
+            min_value: 0.0,
+            per_dimension: false,
+        }
+    }
+}
+
+/// 检测器配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AnomalyConfig {
+    pub enabled: bool,
+    pub interval_secs: u64,
+    /// 基线天数（不含当天）
+    pub baseline_days: u32,
+    /// 基线中至少有多少个有效点才检测
+    pub min_baseline_points: usize,
+    pub metrics: BTreeMap<Metric, MetricSensitivity>,
+}
+
+impl Default for AnomalyConfig {
+    fn default() -> Self {
+        let mut metrics = BTreeMap::new();
+        metrics.insert(
+            Metric::FailureRate,
+            MetricSensitivity { min_value: 5.0, per_dimension: true, ..Default::default() },
+        );
+        metrics.insert(
+            Metric::AccountRestrictions,
+            MetricSensitivity { threshold: 2.5, min_value: 1.0, ..Default::default() },
+        );
+        metrics.insert(
+            Metric::Actions,
+            MetricSensitivity { method: DetectionMethod::Ewma, direction: Direction::Both, ..Default::default() },
+        );
+        metrics.insert(Metric::DeviceUptime, MetricSensitivity { enabled: false, direction: Direction::Down, ..Default::default() });
+        Self { enabled: true, interval_secs: 900, baseline_days: 14, min_baseline_points: 5, metrics }
+    }
+}
+
+/// 一次异常（即通知事件负载）
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Anomaly {
+    pub metric: Metric,
+    /// 序列名：total 或具体动作 / 设备
+    pub series: String,
+    pub day: NaiveDate,
+    /// 基线窗口
+    pub window_from: NaiveDate,
+    pub window_to: NaiveDate,
+    pub current: f64,
+    pub baseline: f64,
+    pub score: f64,
+    pub threshold: f64,
+    pub method: DetectionMethod,
+    pub detected_at: String,
+}
+
+/// 检测结果：(得分, 基线值)
+pub fn score(baseline: &[f64], current: f64, sensitivity: &MetricSensitivity) -> Option<(f64, f64)> {
+    if baseline.is_empty() {
+        return None;
+    }
+    let (center, variance) = match sensitivity.method {
+        DetectionMethod::ZScore => {
+            let mean = baseline.iter().sum::<f64>() / baseline.len() as f64;
+            let var = baseline.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / baseline.len() as f64;
+            (mean, var)
+        }
+        DetectionMethod::Ewma => {
+            let alpha = sensitivity.ewma_alpha.clamp(0.01, 1.0);
+            let mut mean = baseline[0];
+            let mut var = 0.0;
+            for &v in &baseline[1..] {
+                let diff = v - mean;
+                mean += alpha * diff;
+                var = (1.0 - alpha) * (var + alpha * diff * diff);
+            }
+            (mean, var)
+        }
+    };
+    // 基线平稳时用均值的 10% 作为尺度，避免 0 方差
+    let std = variance.sqrt().max(center.abs() * 0.1).max(MIN_STD);
+    Some(((current - center) / std, center))
+}
+
+fn exceeds(score: f64, sensitivity: &MetricSensitivity) -> bool {
+    match sensitivity.direction {
+        Direction::Up => score >= sensitivity.threshold,
+        Direction::Down => score <= -sensitivity.threshold,
+        Direction::Both => score.abs() >= sensitivity.threshold,
+    }
+}
+
+pub struct AnomalyDetector {
+    config: AnomalyConfig,
+    config_path: Option<PathBuf>,
+    recent: VecDeque<Anomaly>,
+    /// 已通知过的 (指标, 序列, 日期)，同一天同一序列只通知一次
+    notified: HashSet<(Metric, String, NaiveDate)>,
+}
+
+impl AnomalyDetector {
+    pub fn global() -> &'static Mutex<AnomalyDetector> {
+        DETECTOR.get_or_init(|| {
+            let path = dirs::data_dir().unwrap_or_else(|| PathBuf::from(".")).join("employee-gui").join(CONFIG_FILE_NAME);
+            let config = std::fs::read_to_string(&path)
+                .ok()
+                .and_then(|c| serde_json::from_str(&c).ok())
+                .unwrap_or_default();
+            Mutex::new(Self { config, config_path: Some(path), recent: VecDeque::new(), notified: HashSet::new() })
+        })
+    }
+
+    pub fn in_memory(config: AnomalyConfig) -> Self {
+        Self { config, config_path: None, recent: VecDeque::new(), notified: HashSet::new() }
+    }
+
+    pub fn config(&self) -> &AnomalyConfig {
+        &self.config
+    }
+
+    pub fn set_config(&mut self, config: AnomalyConfig) -> Result<(), String> {
+        if let Some(path) = &self.config_path {
+            if let Some(dir) = path.parent() {
+                std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+            }
+            let content = serde_json::to_string_pretty(&config).map_err(|e| e.to_string())?;
+            std::fs::write(path, content).map_err(|e| e.to_string())?;
+        }
+        self.config = config;
+        Ok(())
+    }
+
+    pub fn recent(&self) -> Vec<Anomaly> {
+        self.recent.iter().cloned().collect()
+    }
+
+    /// 检测 `today` 的值，返回新发现（未通知过）的异常
+    pub fn check(&mut self, store: &RollupStore, today: NaiveDate) -> Vec<Anomaly> {
+        if !self.config.enabled {
+            return Vec::new();
+        }
+        let range = TimeRange { from: None, to: Some(today), last_days: Some(self.config.baseline_days.max(1) + 1) };
+        let mut found = Vec::new();
+        for (&metric, sensitivity) in self.config.metrics.iter().filter(|(_, s)| s.enabled) {
+            let series = match store.timeseries(metric, &range, Granularity::Day, today) {
+                Ok(series) => series,
+                Err(e) => {
+                    warn!("⚠️ 异常检测读取 {:?} 失败: {}", metric, e);
+                    continue;
+                }
+            };
+            let window_from = series.from;
+            let window_to = series.to.pred_opt().unwrap_or(series.to);
+            for s in series.series.iter().filter(|s| sensitivity.per_dimension || s.name == TOTAL_SERIES) {
+                let Some((&Some(current), history)) = s.data.split_last() else { continue };
+                let baseline: Vec<f64> = history.iter().flatten().copied().collect();
+                if baseline.len() < self.config.min_baseline_points || current < sensitivity.min_value {
+                    continue;
+                }
+                let Some((score, center)) = score(&baseline, current, sensitivity) else { continue };
+                if !exceeds(score, sensitivity) || !self.notified.insert((metric, s.name.clone(), today)) {
+                    continue;
+                }
+                found.push(Anomaly {
+                    metric,
+                    series: s.name.clone(),
+                    day: today,
+                    window_from,
+                    window_to,
+                    current,
+                    baseline: (center * 100.0).round() / 100.0,
+                    score: (score * 100.0).round() / 100.0,
+                    threshold: sensitivity.threshold,
+                    method: sensitivity.method,
+                    detected_at: Local::now().to_rfc3339(),
+                });
+            }
+        }
+        self.notified.retain(|(_, _, day)| *day >= today);
+        for anomaly in &found {
+            if self.recent.len() >= MAX_RECENT {
+                self.recent.pop_front();
+            }
+            self.recent.push_back(anomaly.clone());
+        }
+        found
+    }
+}
+
+/// 执行一次检测并把新异常作为通知事件发给前端
+pub fn check_now<R: Runtime>(app: &AppHandle<R>) -> Result<Vec<Anomaly>, String> {
+    let store = RollupStore::global().lock().map_err(|e| e.to_string())?;
+    let found = AnomalyDetector::global().lock().map_err(|e| e.to_string())?.check(&store, Local::now().date_naive());
+    drop(store);
+    for anomaly in &found {
+        warn!(
+            "🚨 指标异常: {:?}/{} 当前 {} 基线 {} (score {})",
+            anomaly.metric, anomaly.series, anomaly.current, anomaly.baseline, anomaly.score
+        );
+        if let Err(e) = app.emit(ANOMALY_EVENT, anomaly) {
+            warn!("⚠️ 发送异常通知失败: {}", e);
+        }
+    }
+    Ok(found)
+}
+
+/// 后台定期检测（应用启动时 spawn）
+pub async fn run_detector<R: Runtime>(app: AppHandle<R>) {
+    info!("🚨 指标异常检测已启动");
+    loop {
+        let interval = AnomalyDetector::global().lock().map(|d| d.config().interval_secs).unwrap_or(900).max(60);
+        tokio::time::sleep(Duration::from_secs(interval)).await;
+        if let Err(e) = check_now(&app) {
+            warn!("⚠️ 指标异常检测失败: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::shared::event_bus::AppEvent;
+    use chrono::TimeZone;
+
+    #[test]
+    fn flags_failure_rate_spike_once_per_day() {
+        let mut store = RollupStore::in_memory();
+        for day in 1..=8u32 {
+            let at = Local.with_ymd_and_hms(2026, 10, day, 12, 0, 0).unwrap();
+            // 平时 10% 失败，第 8 天 60% 失败
+            let failures = if day == 8 { 6 } else { 1 };
+            for i in 0..10 {
+                let event = AppEvent::ActionExecuted { action: "follow".into(), device_id: "d1".into(), success: i >= failures };
+                store.apply(&event, at).unwrap();
+            }
+        }
+        let today = NaiveDate::from_ymd_opt(2026, 10, 8).unwrap();
+
+        let mut config = AnomalyConfig::default();
+        config.baseline_days = 7;
+        let mut detector = AnomalyDetector::in_memory(config);
+        let found = detector.check(&store, today);
+        let failure: Vec<_> = found.iter().filter(|a| a.metric == Metric::FailureRate).collect();
+        assert_eq!(failure.iter().map(|a| a.series.as_str()).collect::<Vec<_>>(), vec!["total", "follow"]);
+        assert_eq!((failure[0].current, failure[0].baseline), (60.0, 10.0));
+        assert!(detector.check(&store, today).is_empty());
+
+        let calm = MetricSensitivity::default();
+        assert!(!exceeds(score(&[10.0, 11.0, 9.0], 10.5, &calm).unwrap().0, &calm));
+        let ewma = MetricSensitivity { method: DetectionMethod::Ewma, ..Default::default() };
+        assert!(exceeds(score(&[10.0, 11.0, 9.0, 10.0], 30.0, &ewma).unwrap().0, &ewma));
+    }
+}
@@ -0,0 +1,453 @@
+// src-tauri/src/services/trend_rollup/mod.rs
+// module: trend_rollup | layer: services | role: 历史趋势汇总
+// summary: 订阅事件总线增量维护按天汇总表（导入数、动作数、成功数、账号受限数、设备在线时长），启动时从号码池回填导入数据，按天 / 周 / 月输出可直接绘图的时间序列
+
+use chrono::{DateTime, Datelike, Duration as ChronoDuration, Local, NaiveDate, TimeZone};
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::path::PathBuf;
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+use tokio::sync::broadcast::error::RecvError;
+use tracing::{info, warn};
+
+use crate::core::shared::event_bus::{self, AppEvent};
+
+pub mod anomaly;
+
+const DB_FILE_NAME: &str = "trend_rollups.db";
+/// 在线设备的时长每隔多久累计一次（应用异常退出时最多丢失这么久）
+const UPTIME_FLUSH_SECS: u64 = 300;
+const DEFAULT_RANGE_DAYS: i64 = 30;
+/// 单次查询最多覆盖的天数
+const MAX_RANGE_DAYS: i64 = 3 * 366;
+
+const METRIC_IMPORTS: &str = "imports";
+const METRIC_ACTIONS: &str = "actions";
+const METRIC_ACTIONS_SUCCESS: &str = "actions_success";
+const METRIC_UPTIME_SECS: &str = "device_uptime_secs";
+const METRIC_RESTRICTIONS: &str = "account_restrictions";
+/// 汇总序列名
+const TOTAL_SERIES: &str = "total";
+
+static STORE: OnceLock<Mutex<RollupStore>> = OnceLock::new();
+
+/// 可查询的指标
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Metric {
+    /// 每天导入号码池的号码数
+    Imports,
+    /// 每天执行的设备动作数
+    Actions,
+    /// 动作成功率（%）
+    SuccessRate,
+    /// 动作失败率（%）
+    FailureRate,
+    /// 账号受限 / 封禁次数
+    AccountRestrictions,
+    /// 设备在线时长（小时）
+    DeviceUptime,
+}
+
+impl Metric {
+    fn unit(self) -> &'static str {
+        match self {
+            Metric::Imports => "numbers",
+            Metric::Actions => "actions",
+            Metric::SuccessRate | Metric::FailureRate => "percent",
+            Metric::AccountRestrictions => "accounts",
+            Metric::DeviceUptime => "hours",
+        }
+    }
+}
+
+/// 时间粒度
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum Granularity {
+    #[default]
+    Day,
+    /// 以周一为起点
+    Week,
+    Month,
+}
+
+impl Granularity {
+    fn bucket(self, day: NaiveDate) -> String {
+        match self {
+            Granularity::Day => day.format("%Y-%m-%d").to_string(),
+            Granularity::Week => {
+                let monday = day - ChronoDuration::days(day.weekday().num_days_from_monday() as i64);
+                monday.format("%Y-%m-%d").to_string()
+            }
+            Granularity::Month => day.format("%Y-%m").to_string(),
+        }
+    }
+}
+
+/// 查询区间（本地日期，闭区间）；未指定时取最近 30 天
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TimeRange {
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+    pub last_days: Option<u32>,
+}
+
+impl TimeRange {
+    fn resolve(&self, today: NaiveDate) -> Result<(NaiveDate, NaiveDate), String> {
+        let to = self.to.unwrap_or(today);
+        let from = match (self.from, self.last_days) {
+            (Some(from), _) => from,
+            (None, Some(days)) => to - ChronoDuration::days(days.max(1) as i64 - 1),
+            (None, None) => to - ChronoDuration::days(DEFAULT_RANGE_DAYS - 1),
+        };
+        if from > to {
+            return Err(format!("起始日期 {} 晚于结束日期 {}", from, to));
+        }
+        if (to - from).num_days() >= MAX_RANGE_DAYS {
+            return Err(format!("查询区间不能超过 {} 天", MAX_RANGE_DAYS));
+        }
+        Ok((from, to))
+    }
+}
+
+/// 一条序列（`total` 为全部维度合计，其余按来源 / 动作 / 设备拆分）
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct Series {
+    pub name: String,
+    /// 与 labels 一一对应；成功率在没有动作的时间段为 null
+    pub data: Vec<Option<f64>>,
+}
+
+/// 可直接绘图的时间序列
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TimeSeries {
+    pub metric: Metric,
+    pub granularity: Granularity,
+    pub unit: String,
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub labels: Vec<String>,
+    pub series: Vec<Series>,
+}
+
+pub struct RollupStore {
+    conn: Connection,
+    /// 在线设备 → 已累计到的时间点
+    online: HashMap<String, DateTime<Local>>,
+}
+
+impl RollupStore {
+    pub fn global() -> &'static Mutex<RollupStore> {
+        STORE.get_or_init(|| {
+            let dir = dirs::data_dir().unwrap_or_else(|| PathBuf::from(".")).join("employee-gui");
+            let opened = std::fs::create_dir_all(&dir)
+                .map_err(|e| e.to_string())
+                .and_then(|_| Connection::open(dir.join(DB_FILE_NAME)).map_err(|e| e.to_string()))
+                .and_then(|conn| Self::new(conn).map_err(|e| e.to_string()));
+            let store = opened.unwrap_or_else(|e| {
+                warn!("⚠️ 趋势汇总库打开失败，改用内存库: {}", e);
+                Self::in_memory()
+            });
+            Mutex::new(store)
+        })
+    }
+
+    pub fn new(conn: Connection) -> rusqlite::Result<Self> {
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS daily_rollups (
+                day TEXT NOT NULL,
+                metric TEXT NOT NULL,
+                dimension TEXT NOT NULL DEFAULT '',
+                value REAL NOT NULL DEFAULT 0,
+                PRIMARY KEY (day, metric, dimension)
+            );
+            CREATE INDEX IF NOT EXISTS idx_daily_rollups_metric_day ON daily_rollups(metric, day);",
+        )?;
+        Ok(Self { conn, online: HashMap::new() })
+    }
+
+    pub fn in_memory() -> Self {
+        Self::new(Connection::open_in_memory().expect("open in-memory sqlite")).expect("init rollup schema")
+    }
+
+    fn add(&self, day: NaiveDate, metric: &str, dimension: &str, value: f64) -> rusqlite::Result<()> {
+        self.conn.execute(
+            "INSERT INTO daily_rollups (day, metric, dimension, value) VALUES (?1, ?2, ?3, ?4)
+             ON CONFLICT(day, metric, dimension) DO UPDATE SET value = value + excluded.value",
+            params![day.format("%Y-%m-%d").to_string(), metric, dimension, value],
+        )?;
+        Ok(())
+    }
+
+    /// 把 [from, to) 的在线时长按自然日拆分累计
+    fn accrue_uptime(&self, device_id: &str, from: DateTime<Local>, to: DateTime<Local>) -> rusqlite::Result<()> {
+        let mut cursor = from;
+        while cursor < to {
+            let next_midnight = cursor
+                .date_naive()
+                .succ_opt()
+                .and_then(|d| Local.from_local_datetime(&d.and_hms_opt(0, 0, 0)?).earliest())
+                .unwrap_or(to);
+            let end = next_midnight.min(to);
+            let secs = (end - cursor).num_milliseconds() as f64 / 1000.0;
+            if secs > 0.0 {
+                self.add(cursor.date_naive(), METRIC_UPTIME_SECS, device_id, secs)?;
+            }
+            if end <= cursor {
+                break;
+            }
+            cursor = end;
+        }
+        Ok(())
+    }
+
+    /// 增量应用一条事件
+    pub fn apply(&mut self, event: &AppEvent, at: DateTime<Local>) -> rusqlite::Result<()> {
+        let day = at.date_naive();
+        match event {
+            AppEvent::ContactsImported { source, count } => {
+                if *count > 0 {
+                    self.add(day, METRIC_IMPORTS, source, *count as f64)?;
+                }
+            }
+            AppEvent::ActionExecuted { action, success, .. } => {
+                self.add(day, METRIC_ACTIONS, action, 1.0)?;
+                if *success {
+                    self.add(day, METRIC_ACTIONS_SUCCESS, action, 1.0)?;
+                }
+            }
+            AppEvent::AccountRestricted { device_id, .. } => {
+                self.add(day, METRIC_RESTRICTIONS, device_id, 1.0)?;
+            }
+            AppEvent::DevicesOnline { device_ids } => {
+                let previous: Vec<(String, DateTime<Local>)> = self.online.drain().collect();
+                for (device_id, since) in previous {
+                    self.accrue_uptime(&device_id, since, at)?;
+                }
+                self.online = device_ids.iter().map(|id| (id.clone(), at)).collect();
+            }
+            AppEvent::JobProgress { .. } => {}
+        }
+        Ok(())
+    }
+
+    /// 把在线设备的时长累计到 `at`（定期调用，避免退出时丢失整段在线时间）
+    pub fn flush_uptime(&mut self, at: DateTime<Local>) -> rusqlite::Result<()> {
+        let online: Vec<(String, DateTime<Local>)> = self.online.iter().map(|(k, v)| (k.clone(), *v)).collect();
+        for (device_id, since) in online {
+            self.accrue_uptime(&device_id, since, at)?;
+            self.online.insert(device_id, at);
+        }
+        Ok(())
+    }
+
+    /// 从号码池重建导入汇总（号码池是导入数据的权威来源）
+    pub fn rebuild_imports(&self, contacts: &Connection) -> rusqlite::Result<usize> {
+        let mut stmt = contacts.prepare(
+            "SELECT date(created_at, 'localtime'), COUNT(*) FROM contact_numbers
+             WHERE created_at IS NOT NULL GROUP BY 1",
+        )?;
+        let rows: Vec<(Option<String>, i64)> =
+            stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?.collect::<rusqlite::Result<_>>()?;
+
+        let tx = self.conn.unchecked_transaction()?;
+        tx.execute("DELETE FROM daily_rollups WHERE metric = ?1", params![METRIC_IMPORTS])?;
+        let mut days = 0;
+        for (day, count) in rows {
+            let Some(day) = day else { continue };
+            tx.execute(
+                "INSERT INTO daily_rollups (day, metric, dimension, value) VALUES (?1, ?2, 'contacts', ?3)",
+                params![day, METRIC_IMPORTS, count as f64],
+            )?;
+            days += 1;
+        }
+        tx.commit()?;
+        Ok(days)
+    }
+
+    /// 读取 [from, to] 内某原始指标：(日期, 维度) → 值
+    fn load(&self, metric: &str, from: NaiveDate, to: NaiveDate) -> rusqlite::Result<Vec<(NaiveDate, String, f64)>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT day, dimension, value FROM daily_rollups
+             WHERE metric = ?1 AND day >= ?2 AND day <= ?3",
+        )?;
+        let rows = stmt.query_map(
+            params![metric, from.format("%Y-%m-%d").to_string(), to.format("%Y-%m-%d").to_string()],
+            |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?, row.get::<_, f64>(2)?)),
+        )?;
+        let mut out = Vec::new();
+        for row in rows {
+            let (day, dimension, value) = row?;
+            if let Ok(day) = NaiveDate::parse_from_str(&day, "%Y-%m-%d") {
+                out.push((day, dimension, value));
+            }
+        }
+        Ok(out)
+    }
+
+    pub fn timeseries(
+        &self,
+        metric: Metric,
+        range: &TimeRange,
+        granularity: Granularity,
+        today: NaiveDate,
+    ) -> Result<TimeSeries, String> {
+        let (from, to) = range.resolve(today)?;
+        let mut labels: Vec<String> = Vec::new();
+        let mut day = from;
+        while day <= to {
+            let bucket = granularity.bucket(day);
+            if labels.last() != Some(&bucket) {
+                labels.push(bucket);
+            }
+            day = day.succ_opt().ok_or("日期越界")?;
+        }
+        let index: HashMap<&str, usize> = labels.iter().enumerate().map(|(i, l)| (l.as_str(), i)).collect();
+
+        // 维度 → 各时间段合计
+        let bucketize = |rows: Vec<(NaiveDate, String, f64)>| {
+            let mut by_dim: BTreeMap<String, Vec<f64>> = BTreeMap::new();
+            for (day, dimension, value) in rows {
+                if let Some(&i) = index.get(granularity.bucket(day).as_str()) {
+                    by_dim.entry(dimension).or_insert_with(|| vec![0.0; labels.len()])[i] += value;
+                }
+            }
+            let mut total = vec![0.0; labels.len()];
+            for values in by_dim.values() {
+                for (t, v) in total.iter_mut().zip(values) {
+                    *t += v;
+                }
+            }
+            (total, by_dim)
+        };
+        let load = |m: &str| self.load(m, from, to).map_err(|e| format!("读取趋势数据失败: {}", e));
+        let to_series = |name: &str, values: Vec<Option<f64>>| Series { name: name.to_string(), data: values };
+
+        let series = match metric {
+            Metric::Imports | Metric::Actions | Metric::DeviceUptime | Metric::AccountRestrictions => {
+                let (raw, scale) = match metric {
+                    Metric::Imports => (METRIC_IMPORTS, 1.0),
+                    Metric::Actions => (METRIC_ACTIONS, 1.0),
+                    Metric::AccountRestrictions => (METRIC_RESTRICTIONS, 1.0),
+                    _ => (METRIC_UPTIME_SECS, 1.0 / 3600.0),
+                };
+                let (total, by_dim) = bucketize(load(raw)?);
+                let scaled = |values: Vec<f64>| values.into_iter().map(|v| Some(round2(v * scale))).collect();
+                std::iter::once(to_series(TOTAL_SERIES, scaled(total)))
+                    .chain(by_dim.into_iter().map(|(name, values)| to_series(&name, scaled(values))))
+                    .collect()
+            }
+            Metric::SuccessRate | Metric::FailureRate => {
+                let (total, by_dim) = bucketize(load(METRIC_ACTIONS)?);
+                let (success_total, success_by_dim) = bucketize(load(METRIC_ACTIONS_SUCCESS)?);
+                let failure = metric == Metric::FailureRate;
+                let rate = |actions: &[f64], success: Option<&Vec<f64>>| -> Vec<Option<f64>> {
+                    actions
+                        .iter()
+                        .enumerate()
+                        .map(|(i, &n)| {
+                            (n > 0.0).then(|| {
+                                let success_rate = success.map_or(0.0, |s| s[i]) / n * 100.0;
+                                round2(if failure { 100.0 - success_rate } else { success_rate })
+                            })
+                        })
+                        .collect()
+                };
+                std::iter::once(to_series(TOTAL_SERIES, rate(&total, Some(&success_total))))
+                    .chain(by_dim.iter().map(|(name, values)| to_series(name, rate(values, success_by_dim.get(name)))))
+                    .collect()
+            }
+        };
+
+        Ok(TimeSeries { metric, granularity, unit: metric.unit().to_string(), from, to, labels, series })
+    }
+}
+
+fn round2(value: f64) -> f64 {
+    (value * 100.0).round() / 100.0
+}
+
+/// 查询时间序列
+pub fn get_timeseries(metric: Metric, range: &TimeRange, granularity: Granularity) -> Result<TimeSeries, String> {
+    let store = RollupStore::global().lock().map_err(|e| e.to_string())?;
+    store.timeseries(metric, range, granularity, Local::now().date_naive())
+}
+
+/// 启动汇总：回填导入数据，然后订阅事件总线增量更新（应用启动时 spawn）
+pub async fn run(contacts: Option<Connection>) {
+    // 先订阅，回填期间发生的事件不会丢失
+    let mut events = event_bus::subscribe();
+    if let Some(contacts) = contacts {
+        match RollupStore::global().lock().map_err(|e| e.to_string()).and_then(|s| {
+            s.rebuild_imports(&contacts).map_err(|e| e.to_string())
+        }) {
+            Ok(days) => info!("📈 趋势汇总: 已从号码池回填 {} 天导入数据", days),
+            Err(e) => warn!("⚠️ 趋势汇总回填失败: {}", e),
+        }
+    }
+
+    let mut ticker = tokio::time::interval(Duration::from_secs(UPTIME_FLUSH_SECS));
+    loop {
+        let result = tokio::select! {
+            received = events.recv() => match received {
+                Ok(event) => RollupStore::global()
+                    .lock()
+                    .map_err(|e| e.to_string())
+                    .and_then(|mut s| s.apply(&event, Local::now()).map_err(|e| e.to_string())),
+                Err(RecvError::Lagged(skipped)) => Err(format!("事件处理滞后，丢弃 {} 条", skipped)),
+                Err(RecvError::Closed) => break,
+            },
+            _ = ticker.tick() => RollupStore::global()
+                .lock()
+                .map_err(|e| e.to_string())
+                .and_then(|mut s| s.flush_uptime(Local::now()).map_err(|e| e.to_string())),
+        };
+        if let Err(e) = result {
+            warn!("⚠️ 趋势汇总更新失败: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rolls_up_events_into_chart_ready_series() {
+        let mut store = RollupStore::in_memory();
+        let at = |d: u32, h: u32| Local.with_ymd_and_hms(2026, 10, d, h, 0, 0).unwrap();
+
+        store.apply(&AppEvent::ContactsImported { source: "contacts".into(), count: 40 }, at(5, 9)).unwrap();
+        for success in [true, true, false, true] {
+            let event = AppEvent::ActionExecuted { action: "follow".into(), device_id: "d1".into(), success };
+            store.apply(&event, at(6, 10)).unwrap();
+        }
+        store.apply(&AppEvent::DevicesOnline { device_ids: vec!["d1".into()] }, at(6, 22)).unwrap();
+        store.apply(&AppEvent::DevicesOnline { device_ids: vec![] }, at(7, 1)).unwrap();
+
+        let range = TimeRange { from: NaiveDate::from_ymd_opt(2026, 10, 5), to: NaiveDate::from_ymd_opt(2026, 10, 7), last_days: None };
+        let today = NaiveDate::from_ymd_opt(2026, 10, 7).unwrap();
+
+        let imports = store.timeseries(Metric::Imports, &range, Granularity::Day, today).unwrap();
+        assert_eq!(imports.labels, vec!["2026-10-05", "2026-10-06", "2026-10-07"]);
+        assert_eq!(imports.series[0].data, vec![Some(40.0), Some(0.0), Some(0.0)]);
+
+        let rate = store.timeseries(Metric::SuccessRate, &range, Granularity::Day, today).unwrap();
+        assert_eq!(rate.series[0].data, vec![None, Some(75.0), None]);
+
+        let uptime = store.timeseries(Metric::DeviceUptime, &range, Granularity::Day, today).unwrap();
+        assert_eq!(uptime.series[0].data, vec![Some(0.0), Some(2.0), Some(1.0)]);
+        assert_eq!(uptime.series[1].name, "d1");
+
+        let monthly = store.timeseries(Metric::Actions, &range, Granularity::Month, today).unwrap();
+        assert_eq!((monthly.labels.len(), monthly.series[0].data[0]), (1, Some(4.0)));
+
+        let bad = TimeRange { from: NaiveDate::from_ymd_opt(2026, 10, 8), ..range };
+        assert!(store.timeseries(Metric::Imports, &bad, Granularity::Day, today).is_err());
+    }
+}
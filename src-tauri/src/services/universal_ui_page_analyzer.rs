@@ -129,9 +129,9 @@ pub struct UIElement {
 
     // 保留用于内部处理的字段
     pub children: Vec<UIElement>,  // 移除 skip_serializing，允许传递子元素到前端
-    #[serde(skip_serializing)]
+    #[serde(skip_serializing, default)]
     pub parent: Option<String>,
-    #[serde(skip_serializing)]
+    #[serde(skip_serializing, default)]
     pub depth: u32,
 }
 
@@ -0,0 +1,293 @@
+use anyhow::{Context, Result};
+use regex::{Captures, Regex};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use tracing::info;
+
+use super::vcf_utils::{format_chinese_phone, Contact};
+
+// ==================== 数据结构 ====================
+
+/// vCard 版本
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum VcardVersion {
+    /// 兼容性最好（老款华为 / 小米通讯录）
+    #[default]
+    #[serde(rename = "2.1")]
+    V21,
+    #[serde(rename = "3.0")]
+    V30,
+    #[serde(rename = "4.0")]
+    V40,
+}
+
+impl VcardVersion {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::V21 => "2.1",
+            Self::V30 => "3.0",
+            Self::V40 => "4.0",
+        }
+    }
+}
+
+/// VCF 导出选项
+///
+/// 模板占位符：
+/// - 联系人名称 `name_template`：`{name}` 原名、`{industry}` 行业（Contact.occupation）、`{seq}` 全局序号、`{tail4}` 号码后四位
+/// - 文件名 `file_name_template`：`{base}` 输出文件名（不含扩展名）、`{chunk}` 分片序号、`{total}` 分片数、`{industry}` 分片首个联系人的行业
+/// - 数字占位符可指定补零宽度，如 `{seq:4}` → `0007`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct VcfExportOptions {
+    /// 每个文件的联系人数；为空或 0 时输出单个文件
+    pub chunk_size: Option<usize>,
+    pub version: VcardVersion,
+    pub name_template: Option<String>,
+    pub file_name_template: Option<String>,
+    /// 序号起始值（默认 1）
+    pub sequence_start: Option<usize>,
+}
+
+/// 生成的单个 VCF 分片
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VcfChunkFile {
+    /// 从 1 开始
+    pub index: usize,
+    pub file_path: String,
+    pub contact_count: usize,
+    pub first_sequence: usize,
+    pub last_sequence: usize,
+    /// 分片内联系人的 Contact.id（按写入顺序）
+    pub contact_ids: Vec<String>,
+}
+
+const DEFAULT_CHUNK_FILE_TEMPLATE: &str = "{base}_{chunk:3}";
+
+// ==================== 模板 ====================
+
+fn placeholder_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"\{(\w+)(?::(\d+))?\}").expect("valid placeholder regex"))
+}
+
+/// 渲染模板：`lookup` 返回 (文本值, 是否数字)；数字值按宽度补零，未知占位符原样保留
+fn render_template(template: &str, lookup: impl Fn(&str) -> Option<(String, bool)>) -> String {
+    placeholder_re()
+        .replace_all(template, |caps: &Captures| match lookup(&caps[1]) {
+            Some((value, true)) => match caps.get(2).and_then(|w| w.as_str().parse::<usize>().ok()) {
+                Some(width) => format!("{:0>width$}", value, width = width),
+                None => value,
+            },
+            Some((value, false)) => value,
+            None => caps[0].to_string(),
+        })
+        .into_owned()
+}
+
+fn display_name(contact: &Contact, template: Option<&str>, seq: usize) -> String {
+    let Some(template) = template.filter(|t| !t.trim().is_empty()) else {
+        return contact.name.clone();
+    };
+    let digits: String = contact.phone.chars().filter(|c| c.is_ascii_digit()).collect();
+    let rendered = render_template(template, |key| match key {
+        "name" => Some((contact.name.clone(), false)),
+        "industry" => Some((contact.occupation.clone(), false)),
+        "seq" => Some((seq.to_string(), true)),
+        "tail4" => Some((digits[digits.len().saturating_sub(4)..].to_string(), false)),
+        _ => None,
+    });
+    let rendered = rendered.trim().to_string();
+    if rendered.is_empty() { contact.name.clone() } else { rendered }
+}
+
+/// 文件名中不能出现的字符替换为下划线
+fn sanitize_file_name(name: &str) -> String {
+    name.chars().map(|c| if matches!(c, '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|') { '_' } else { c }).collect()
+}
+
+// ==================== vCard 渲染 ====================
+
+/// 3.0 / 4.0 文本值转义
+fn escape_text(value: &str) -> String {
+    value.replace('\\', "\\\\").replace(',', "\\,").replace(';', "\\;").replace('\n', "\\n")
+}
+
+fn phone_digits(phone: &str) -> String {
+    phone.chars().filter(|c| c.is_ascii_digit()).collect()
+}
+
+/// 4.0 的 tel URI：`tel:+86-139-1234-5678`
+fn tel_uri(phone: &str) -> String {
+    let digits = phone_digits(phone);
+    if digits.len() == 11 && digits.starts_with('1') {
+        format!("tel:+86-{}-{}-{}", &digits[0..3], &digits[3..7], &digits[7..11])
+    } else {
+        format!("tel:{}", phone.trim())
+    }
+}
+
+/// 渲染单个 vCard；2.1 的输出与历史版本 generate_vcf_file 保持一致
+pub fn render_vcard(contact: &Contact, name: &str, version: VcardVersion) -> String {
+    let mut card = String::new();
+    card.push_str("BEGIN:VCARD\n");
+    card.push_str(&format!("VERSION:{}\n", version.as_str()));
+    match version {
+        VcardVersion::V21 => {
+            card.push_str(&format!("FN:{}\n", name));
+            card.push_str(&format!("N:{};;\n", name));
+            if !contact.phone.is_empty() {
+                let formatted = format_chinese_phone(&contact.phone);
+                card.push_str(&format!("TEL;CELL:{}\n", formatted));
+                card.push_str(&format!("TEL;TYPE=CELL:{}\n", formatted));
+            }
+            if !contact.email.is_empty() {
+                card.push_str(&format!("EMAIL:{}\n", contact.email));
+            }
+            if !contact.address.is_empty() {
+                card.push_str(&format!("ADR:;;{};;;;\n", contact.address));
+            }
+            if !contact.occupation.is_empty() {
+                card.push_str(&format!("NOTE:{}\n", contact.occupation));
+            }
+        }
+        VcardVersion::V30 | VcardVersion::V40 => {
+            let name = escape_text(name);
+            card.push_str(&format!("FN:{}\n", name));
+            card.push_str(&format!("N:{};;;;\n", name));
+            if !contact.phone.is_empty() {
+                if version == VcardVersion::V30 {
+                    card.push_str(&format!("TEL;TYPE=CELL:{}\n", format_chinese_phone(&contact.phone)));
+                } else {
+                    card.push_str(&format!("TEL;VALUE=uri;TYPE=cell:{}\n", tel_uri(&contact.phone)));
+                }
+            }
+            if !contact.email.is_empty() {
+                let param = if version == VcardVersion::V30 { ";TYPE=INTERNET" } else { "" };
+                card.push_str(&format!("EMAIL{}:{}\n", param, contact.email));
+            }
+            if !contact.address.is_empty() {
+                card.push_str(&format!("ADR:;;{};;;;\n", escape_text(&contact.address)));
+            }
+            if !contact.occupation.is_empty() {
+                card.push_str(&format!("NOTE:{}\n", escape_text(&contact.occupation)));
+            }
+        }
+    }
+    card.push_str("END:VCARD\n");
+    card
+}
+
+// ==================== 生成 ====================
+
+/// 按选项生成 VCF：不分片时写入 `output_path`，分片时在同目录下按文件名模板输出多个文件
+pub fn generate_vcf_files(contacts: &[Contact], output_path: &str, options: &VcfExportOptions) -> Result<Vec<VcfChunkFile>> {
+    let output = Path::new(output_path);
+    let dir = output.parent().map(Path::to_path_buf).unwrap_or_else(|| PathBuf::from("."));
+    if !dir.as_os_str().is_empty() {
+        fs::create_dir_all(&dir).with_context(|| format!("创建输出目录失败: {}", dir.display()))?;
+    }
+    let base = output.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_else(|| "contacts".to_string());
+
+    let chunk_size = options.chunk_size.filter(|n| *n > 0).unwrap_or(contacts.len().max(1));
+    let chunked = contacts.len() > chunk_size || options.file_name_template.is_some();
+    let total = contacts.len().div_ceil(chunk_size).max(1);
+    let first_seq = options.sequence_start.unwrap_or(1);
+
+    let mut files = Vec::with_capacity(total);
+    for index in 0..total {
+        let chunk = contacts.get(index * chunk_size..((index + 1) * chunk_size).min(contacts.len())).unwrap_or(&[]);
+        let chunk_first = first_seq + index * chunk_size;
+        let content: String = chunk
+            .iter()
+            .enumerate()
+            .map(|(i, contact)| {
+                let name = display_name(contact, options.name_template.as_deref(), chunk_first + i);
+                render_vcard(contact, &name, options.version)
+            })
+            .collect();
+
+        let path = if chunked {
+            let template = options.file_name_template.as_deref().unwrap_or(DEFAULT_CHUNK_FILE_TEMPLATE);
+            let industry = chunk.first().map(|c| c.occupation.clone()).unwrap_or_default();
+            let stem = render_template(template, |key| match key {
+                "base" => Some((base.clone(), false)),
+                "chunk" => Some(((index + 1).to_string(), true)),
+                "total" => Some((total.to_string(), true)),
+                "industry" => Some((industry.clone(), false)),
+                _ => None,
+            });
+            let stem = sanitize_file_name(stem.trim_end_matches(".vcf"));
+            dir.join(format!("{}.vcf", stem))
+        } else {
+            output.to_path_buf()
+        };
+        fs::write(&path, content).with_context(|| format!("写入VCF文件失败: {}", path.display()))?;
+
+        files.push(VcfChunkFile {
+            index: index + 1,
+            file_path: path.to_string_lossy().to_string(),
+            contact_count: chunk.len(),
+            first_sequence: chunk_first,
+            last_sequence: chunk_first + chunk.len().saturating_sub(1),
+            contact_ids: chunk.iter().map(|c| c.id.clone()).collect(),
+        });
+    }
+
+    info!(
+        "VCF导出完成: {} 个联系人, {} 个文件, vCard {}",
+        contacts.len(),
+        files.len(),
+        options.version.as_str()
+    );
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn contact(id: usize, industry: &str) -> Contact {
+        Contact {
+            id: id.to_string(),
+            name: format!("客户{}", id),
+            phone: format!("139000000{:02}", id),
+            email: String::new(),
+            address: String::new(),
+            occupation: industry.to_string(),
+        }
+    }
+
+    #[test]
+    fn chunks_with_templates_and_versions() {
+        let dir = std::env::temp_dir().join(format!("vcf_export_{}", std::process::id()));
+        let contacts: Vec<Contact> = (1..=5).map(|i| contact(i, "餐饮")).collect();
+        let options = VcfExportOptions {
+            chunk_size: Some(2),
+            version: VcardVersion::V40,
+            name_template: Some("{industry}-{seq:3}".into()),
+            file_name_template: Some("{industry}_{base}_{chunk:2}of{total}".into()),
+            sequence_start: Some(10),
+        };
+        let files = generate_vcf_files(&contacts, dir.join("export.vcf").to_str().unwrap(), &options).unwrap();
+
+        assert_eq!(files.iter().map(|f| f.contact_count).collect::<Vec<_>>(), vec![2, 2, 1]);
+        assert_eq!((files[2].first_sequence, files[2].last_sequence, files[2].contact_ids.clone()), (14, 14, vec!["5".to_string()]));
+        assert!(files[0].file_path.ends_with("餐饮_export_01of3.vcf"));
+        let content = std::fs::read_to_string(&files[1].file_path).unwrap();
+        assert!(content.contains("VERSION:4.0\nFN:餐饮-012\n"));
+        assert!(content.contains("TEL;VALUE=uri;TYPE=cell:tel:+86-139-0000-0003\n"));
+
+        // 默认不分片：单文件，2.1 格式与历史输出一致
+        let single = generate_vcf_files(&contacts[..1], dir.join("one.vcf").to_str().unwrap(), &VcfExportOptions::default()).unwrap();
+        assert_eq!(single.len(), 1);
+        assert_eq!(
+            std::fs::read_to_string(&single[0].file_path).unwrap(),
+            "BEGIN:VCARD\nVERSION:2.1\nFN:客户1\nN:客户1;;\nTEL;CELL:+86 139 0000 0001\nTEL;TYPE=CELL:+86 139 0000 0001\nNOTE:餐饮\nEND:VCARD\n"
+        );
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -3,12 +3,11 @@
 #![allow(unused_imports)]
 
 use anyhow::{Context, Result};
-use std::process::Command;
+use std::sync::Arc;
 use tokio::time::{sleep, Duration};
 use tracing::{error, info, warn};
 
-#[cfg(windows)]
-use std::os::windows::process::CommandExt;
+use crate::services::adb::transport::{AdbTransport, CommandAdbTransport};
 
 pub use crate::services::vcf::vcf_types::{
     DeviceBrandInfo,
@@ -22,18 +21,26 @@ pub use crate::services::vcf::vcf_types::{
 /// 多品牌VCF导入器
 pub struct MultiBrandVcfImporter {
     device_id: String,
-    adb_path: String,
+    transport: Arc<dyn AdbTransport>,
     strategies: Vec<VcfImportStrategy>,
     device_info: Option<DeviceBrandInfo>,
+    /// Intent 导入发出后等待系统处理的时间
+    intent_settle_delay: Duration,
 }
 
 impl MultiBrandVcfImporter {
     pub fn new(device_id: String) -> Self {
+        Self::with_transport(device_id, Arc::new(CommandAdbTransport::new(Self::detect_adb_path())))
+    }
+
+    /// 指定 ADB 调用通道（场景回归使用假设备）
+    pub fn with_transport(device_id: String, transport: Arc<dyn AdbTransport>) -> Self {
         let mut importer = Self {
             device_id,
-            adb_path: Self::detect_adb_path(),
+            transport,
             strategies: Vec::new(),
             device_info: None,
+            intent_settle_delay: Duration::from_secs(3),
         };
         
         // 初始化内置策略
@@ -41,6 +48,11 @@ impl MultiBrandVcfImporter {
         importer
     }
 
+    pub fn with_intent_settle_delay(mut self, delay: Duration) -> Self {
+        self.intent_settle_delay = delay;
+        self
+    }
+
     /// 自动检测ADB路径
     fn detect_adb_path() -> String {
         // 检查常见的ADB路径（优先使用项目内的 platform-tools）
@@ -71,15 +83,7 @@ impl MultiBrandVcfImporter {
 
     /// 执行ADB命令
     fn execute_adb_command(&self, args: &[&str]) -> Result<std::process::Output> {
-        let mut cmd = Command::new(&self.adb_path);
-        cmd.args(args);
-        
-        #[cfg(windows)]
-        {
-            cmd.creation_flags(0x08000000); // CREATE_NO_WINDOW
-        }
-        
-        cmd.output().context("ADB命令执行失败")
+        self.transport.execute(args).context("ADB命令执行失败")
     }
 
     /// 获取设备信息
@@ -252,16 +256,33 @@ impl MultiBrandVcfImporter {
         
         // 所有策略都失败了，尝试兜底方法
         warn!("🔧 所有预设策略都失败，尝试简单可靠的兜底方法");
-        
+        self.fallback_import(&normalized_vcf_path, attempts, start_time).await
+    }
+
+    /// 兜底导入：推送 VCF 后通过 VIEW Intent 交给系统通讯录导入
+    pub async fn import_via_intent(&self, vcf_file_path: &str) -> Result<MultiBrandImportResult> {
+        let normalized_vcf_path = super::vcf_utils::ensure_vcf_path(vcf_file_path).unwrap_or_else(|e| {
+            warn!("输入非VCF并转换失败: {}, 将继续尝试原始路径", e);
+            vcf_file_path.to_string()
+        });
+        self.fallback_import(&normalized_vcf_path, Vec::new(), std::time::Instant::now()).await
+    }
+
+    async fn fallback_import(
+        &self,
+        normalized_vcf_path: &str,
+        mut attempts: Vec<ImportAttempt>,
+        start_time: std::time::Instant,
+    ) -> Result<MultiBrandImportResult> {
         // 先确保VCF文件在设备上
         let device_vcf_path = format!("/sdcard/Download/{}", 
-            std::path::Path::new(&normalized_vcf_path)
+            std::path::Path::new(normalized_vcf_path)
                 .file_name()
                 .unwrap_or_default()
                 .to_string_lossy()
         );
         
-        if let Err(e) = self.push_file_to_device(&normalized_vcf_path, &device_vcf_path).await {
+        if let Err(e) = self.push_file_to_device(normalized_vcf_path, &device_vcf_path).await {
             return Ok(MultiBrandImportResult {
                 success: false,
                 used_strategy: None,
@@ -289,7 +310,7 @@ impl MultiBrandVcfImporter {
                 };
                 attempts.push(attempt);
                 
-                let total_contacts = self.count_vcf_contacts(&normalized_vcf_path);
+                let total_contacts = self.count_vcf_contacts(normalized_vcf_path);
                 
                 Ok(MultiBrandImportResult {
                     success: true,
@@ -1030,12 +1051,12 @@ impl MultiBrandVcfImporter {
         if stdout.contains("Starting: Intent") || stdout.contains("Activity") {
             info!("✅ 兜底方法已成功向手机发送联系人导入命令");
             // 等待系统处理
-            sleep(Duration::from_secs(3)).await;
+            sleep(self.intent_settle_delay).await;
             Ok(())
         } else if !stderr.contains("Error") && !stderr.contains("FATAL") {
             // 即使没有明确成功信息，只要没有错误就认为成功
             info!("✅ 兜底方法已成功向手机发送联系人导入命令（无错误输出）");
-            sleep(Duration::from_secs(3)).await;
+            sleep(self.intent_settle_delay).await;
             Ok(())
         } else {
             error!("❌ 兜底方法也失败了: stdout={}, stderr={}", stdout.trim(), stderr.trim());
@@ -1,9 +1,11 @@
-use anyhow::{Context, Result};
+use anyhow::Result;
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::Path;
 use tracing::info;
 
+use super::vcf_export::{generate_vcf_files, VcfExportOptions};
+
 // ==================== 数据结构 ====================
 
 /// 联系人数据结构
@@ -43,47 +45,13 @@ pub struct VcfOpenResult {
 /// 生成VCF文件
 pub async fn generate_vcf_file(contacts: Vec<Contact>, output_path: &str) -> Result<String> {
     info!("开始生成VCF文件: {}", output_path);
-
-    let mut vcf_content = String::new();
-
-    for contact in &contacts {
-        vcf_content.push_str("BEGIN:VCARD\n");
-        vcf_content.push_str("VERSION:2.1\n");
-        vcf_content.push_str(&format!("FN:{}\n", contact.name));
-        vcf_content.push_str(&format!("N:{};;\n", contact.name));
-
-        if !contact.phone.is_empty() {
-            // 格式化中国手机号为+86格式
-            let formatted_phone = format_chinese_phone(&contact.phone);
-            vcf_content.push_str(&format!("TEL;CELL:{}\n", formatted_phone));
-            vcf_content.push_str(&format!("TEL;TYPE=CELL:{}\n", formatted_phone));
-        }
-
-        if !contact.email.is_empty() {
-            vcf_content.push_str(&format!("EMAIL:{}\n", contact.email));
-        }
-
-        if !contact.address.is_empty() {
-            vcf_content.push_str(&format!("ADR:;;{};;;;\n", contact.address));
-        }
-
-        if !contact.occupation.is_empty() {
-            vcf_content.push_str(&format!("NOTE:{}\n", contact.occupation));
-        }
-
-        vcf_content.push_str("END:VCARD\n");
-    }
-
-    // 写入文件
-    fs::write(output_path, vcf_content)
-        .with_context(|| format!("写入VCF文件失败: {}", output_path))?;
-
-    info!("VCF文件生成完成: {} 个联系人", contacts.len());
+    // 默认选项：单文件 + vCard 2.1，分片/模板/版本见 vcf_export::generate_vcf_files
+    generate_vcf_files(&contacts, output_path, &VcfExportOptions::default())?;
     Ok(output_path.to_string())
 }
 
 /// 格式化中国手机号为 +86 格式
-fn format_chinese_phone(phone: &str) -> String {
+pub(super) fn format_chinese_phone(phone: &str) -> String {
     let digits: String = phone.chars().filter(|c| c.is_ascii_digit()).collect();
 
     if digits.len() == 11 && digits.starts_with('1') {
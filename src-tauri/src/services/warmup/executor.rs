@@ -0,0 +1,208 @@
+// src-tauri/src/services/warmup/executor.rs
+// module: warmup | layer: services | role: 养号执行器
+// summary: 在设备当前信息流里按当天目标执行：上滑浏览、随机停留、按最小间隔限速双击点赞，每个动作后落盘进度，可随时停止
+
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+
+use super::{today, WarmupDay, WarmupStatus, WarmupStore};
+use crate::core::shared::event_bus::{self, AppEvent};
+use crate::services::adb::AdbService;
+use crate::utils::adb_utils::execute_adb_command;
+
+/// 每条内容停留时长范围（秒）
+const DWELL_SECS: (u64, u64) = (6, 18);
+/// 读取屏幕尺寸失败时的默认值
+const DEFAULT_SCREEN: (i32, i32) = (1080, 2340);
+/// 单次执行的最长时间（防止卡死的设备无限跑下去）
+const MAX_RUN: Duration = Duration::from_secs(3 * 60 * 60);
+
+/// 正在执行的账号 → 停止标记
+static RUNNING: OnceLock<Mutex<HashMap<String, Arc<AtomicBool>>>> = OnceLock::new();
+
+fn running() -> &'static Mutex<HashMap<String, Arc<AtomicBool>>> {
+    RUNNING.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// 执行进度（每个动作后回调）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WarmupRunProgress {
+    pub account_id: String,
+    pub day_index: usize,
+    pub browse_seconds: u64,
+    pub browse_target_seconds: u64,
+    pub likes: u32,
+    pub likes_target: u32,
+    pub completed: bool,
+}
+
+/// 一次执行的结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WarmupRunReport {
+    pub account_id: String,
+    pub device_id: String,
+    pub day_index: usize,
+    pub browse_seconds: u64,
+    pub likes: u32,
+    pub completed: bool,
+    /// 被 stop_warmup 中断
+    pub stopped: bool,
+}
+
+/// 请求停止账号当前的执行，返回是否在执行
+pub fn stop(account_id: &str) -> bool {
+    match running().lock().ok().and_then(|map| map.get(account_id).cloned()) {
+        Some(flag) => {
+            flag.store(true, Ordering::SeqCst);
+            true
+        }
+        None => false,
+    }
+}
+
+fn screen_size(device_id: &str) -> (i32, i32) {
+    let output = execute_adb_command(&["-s", device_id, "shell", "wm", "size"]).ok();
+    output
+        .map(|o| String::from_utf8_lossy(&o.stdout).to_string())
+        .and_then(|s| {
+            // 有 Override size 时取最后一行
+            let line = s.lines().rfind(|l| l.contains("size:"))?.to_string();
+            let (w, h) = line.rsplit(' ').next()?.trim().split_once('x')?;
+            Some((w.parse().ok()?, h.parse().ok()?))
+        })
+        .unwrap_or(DEFAULT_SCREEN)
+}
+
+/// 当天剩余帖子里应点赞的概率：剩余赞数 / 预计剩余浏览条数
+fn like_probability(likes_left: u32, browse_left_secs: u64) -> f64 {
+    if likes_left == 0 {
+        return 0.0;
+    }
+    let avg_dwell = (DWELL_SECS.0 + DWELL_SECS.1) as f64 / 2.0;
+    let posts_left = (browse_left_secs as f64 / avg_dwell).max(1.0);
+    (likes_left as f64 / posts_left).clamp(0.05, 1.0)
+}
+
+/// 执行账号今天的养号目标（设备需停在目标 App 的信息流页面）
+pub async fn run_today(account_id: &str, mut on_progress: impl FnMut(&WarmupRunProgress)) -> Result<WarmupRunReport, String> {
+    let (enrollment, program) = {
+        let store = WarmupStore::global().lock().map_err(|e| e.to_string())?;
+        let enrollment = store.enrollment(account_id).cloned().ok_or_else(|| format!("账号未加入养号计划: {}", account_id))?;
+        let program = store.program(&enrollment.program_id)?;
+        (enrollment, program)
+    };
+    if enrollment.status != WarmupStatus::Active {
+        return Err(format!("养号计划不在执行状态: {:?}", enrollment.status));
+    }
+    let day_index = enrollment.day_index(today());
+    let target: WarmupDay = program.days.get(day_index).cloned().ok_or("养号计划已结束")?;
+    let (mut browsed, mut liked) = enrollment.progress_for(day_index).map_or((0, 0), |p| (p.browse_seconds, p.likes));
+    let browse_target = target.browse_minutes as u64 * 60;
+
+    let stop_flag = Arc::new(AtomicBool::new(false));
+    {
+        let mut map = running().lock().map_err(|e| e.to_string())?;
+        if map.contains_key(account_id) {
+            return Err(format!("账号 {} 正在执行养号", account_id));
+        }
+        map.insert(account_id.to_string(), stop_flag.clone());
+    }
+    let device_id = enrollment.device_id.clone();
+    info!("🌱 开始养号: account={} device={} 第{}天 目标 浏览{}分钟/点赞{}", account_id, device_id, day_index + 1, target.browse_minutes, target.likes);
+
+    let result = async {
+        let adb = AdbService::new();
+        let (width, height) = screen_size(&device_id);
+        let (cx, cy) = (width / 2, height / 2);
+        let like_interval = Duration::from_secs(program.min_like_interval_secs);
+        let mut last_like: Option<Instant> = None;
+        let started = Instant::now();
+        let mut completed = browsed >= browse_target && liked >= target.likes;
+
+        while !completed && !stop_flag.load(Ordering::SeqCst) && started.elapsed() < MAX_RUN {
+            adb.swipe_screen(&device_id, cx, height * 3 / 4, cx, height / 4, 300)
+                .await
+                .map_err(|e| format!("滑动失败: {}", e))?;
+            let (dwell, roll) = {
+                let mut rng = rand::thread_rng();
+                (rng.gen_range(DWELL_SECS.0..=DWELL_SECS.1), rng.gen::<f64>())
+            };
+            tokio::time::sleep(Duration::from_secs(dwell)).await;
+
+            let pacing_ok = last_like.is_none_or(|t| t.elapsed() >= like_interval);
+            let mut new_likes = 0;
+            if pacing_ok && roll < like_probability(target.likes.saturating_sub(liked), browse_target.saturating_sub(browsed)) {
+                // 双击屏幕中部点赞（短视频类 App 通用手势）
+                adb.tap_screen(&device_id, cx, cy).await.map_err(|e| format!("点赞失败: {}", e))?;
+                tokio::time::sleep(Duration::from_millis(120)).await;
+                adb.tap_screen(&device_id, cx, cy).await.map_err(|e| format!("点赞失败: {}", e))?;
+                last_like = Some(Instant::now());
+                new_likes = 1;
+                event_bus::publish(AppEvent::ActionExecuted { action: "warmup_like".into(), device_id: device_id.clone(), success: true });
+            }
+
+            let day = WarmupStore::global()
+                .lock()
+                .map_err(|e| e.to_string())?
+                .record_progress(account_id, today(), dwell, new_likes)?;
+            browsed = day.browse_seconds;
+            liked = day.likes;
+            completed = day.completed;
+            on_progress(&WarmupRunProgress {
+                account_id: account_id.to_string(),
+                day_index,
+                browse_seconds: browsed,
+                browse_target_seconds: browse_target,
+                likes: liked,
+                likes_target: target.likes,
+                completed,
+            });
+            event_bus::publish(AppEvent::JobProgress {
+                job_id: format!("warmup:{}", account_id),
+                kind: "warmup".into(),
+                device_id: Some(device_id.clone()),
+                completed: browsed,
+                total: browse_target,
+                finished: completed,
+            });
+        }
+        if !completed && started.elapsed() >= MAX_RUN {
+            warn!("⚠️ 养号执行超时结束: account={}", account_id);
+        }
+        Ok::<bool, String>(completed)
+    }
+    .await;
+
+    if let Ok(mut map) = running().lock() {
+        map.remove(account_id);
+    }
+    let completed = result?;
+    Ok(WarmupRunReport {
+        account_id: account_id.to_string(),
+        device_id,
+        day_index,
+        browse_seconds: browsed,
+        likes: liked,
+        completed,
+        stopped: stop_flag.load(Ordering::SeqCst),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_like_probability() {
+        assert_eq!(like_probability(0, 600), 0.0);
+        // 剩 10 分钟约 50 条内容，剩 5 个赞
+        assert!((like_probability(5, 600) - 0.1).abs() < 1e-9);
+        assert_eq!(like_probability(3, 0), 1.0);
+        assert_eq!(like_probability(1, 100_000), 0.05);
+    }
+}
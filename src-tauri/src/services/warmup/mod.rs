@@ -0,0 +1,352 @@
+// src-tauri/src/services/warmup/mod.rs
+// module: warmup | layer: services | role: 新号养号计划
+// summary: 养号计划按天定义动作曲线（浏览分钟数 / 点赞数），账号入组后逐日执行并记录进度；未完成养号的账号不能分配活动任务
+
+pub mod executor;
+
+use chrono::{Local, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::{Mutex, OnceLock};
+
+const STORE_FILE_NAME: &str = "warmup_programs.json";
+
+static STORE: OnceLock<Mutex<WarmupStore>> = OnceLock::new();
+
+/// 某一天的动作目标
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct WarmupDay {
+    pub browse_minutes: u32,
+    pub likes: u32,
+}
+
+/// 养号计划
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct WarmupProgram {
+    pub id: String,
+    pub name: String,
+    /// 第 i 项为入组后第 i+1 天的目标
+    pub days: Vec<WarmupDay>,
+    /// 两次点赞的最小间隔（秒），执行器据此限速
+    #[serde(default = "default_like_interval")]
+    pub min_like_interval_secs: u64,
+}
+
+fn default_like_interval() -> u64 {
+    30
+}
+
+impl WarmupProgram {
+    /// 线性爬坡：第一天 `start`，最后一天 `end`
+    pub fn linear(id: &str, name: &str, days: usize, start: &WarmupDay, end: &WarmupDay) -> Self {
+        let lerp = |a: u32, b: u32, i: usize| {
+            if days <= 1 {
+                return b;
+            }
+            let t = i as f64 / (days - 1) as f64;
+            (a as f64 + (b as f64 - a as f64) * t).round() as u32
+        };
+        Self {
+            id: id.to_string(),
+            name: name.to_string(),
+            days: (0..days)
+                .map(|i| WarmupDay {
+                    browse_minutes: lerp(start.browse_minutes, end.browse_minutes, i),
+                    likes: lerp(start.likes, end.likes, i),
+                })
+                .collect(),
+            min_like_interval_secs: default_like_interval(),
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if self.id.trim().is_empty() || self.name.trim().is_empty() {
+            return Err("养号计划 ID 和名称不能为空".to_string());
+        }
+        if self.days.is_empty() {
+            return Err("养号计划至少需要 1 天".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// 单日执行进度
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct WarmupDayProgress {
+    pub day_index: usize,
+    pub date: NaiveDate,
+    pub browse_seconds: u64,
+    pub likes: u32,
+    pub completed: bool,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum WarmupStatus {
+    Active,
+    Paused,
+    Completed,
+}
+
+/// 账号的养号入组记录
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct WarmupEnrollment {
+    pub account_id: String,
+    pub device_id: String,
+    pub program_id: String,
+    pub started_on: NaiveDate,
+    pub status: WarmupStatus,
+    #[serde(default)]
+    pub progress: Vec<WarmupDayProgress>,
+}
+
+impl WarmupEnrollment {
+    /// `today` 是计划的第几天（0 起）
+    pub fn day_index(&self, today: NaiveDate) -> usize {
+        (today - self.started_on).num_days().max(0) as usize
+    }
+
+    pub fn progress_for(&self, day_index: usize) -> Option<&WarmupDayProgress> {
+        self.progress.iter().find(|p| p.day_index == day_index)
+    }
+}
+
+/// 账号是否可以分配活动
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct WarmupReadiness {
+    pub account_id: String,
+    pub program_id: Option<String>,
+    pub days_total: usize,
+    pub days_completed: usize,
+    /// 错过（日期已过但未完成）的天数
+    pub days_missed: usize,
+    pub ready: bool,
+    pub reason: String,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct StoreFile {
+    #[serde(default)]
+    programs: Vec<WarmupProgram>,
+    #[serde(default)]
+    enrollments: Vec<WarmupEnrollment>,
+}
+
+pub struct WarmupStore {
+    path: PathBuf,
+    data: StoreFile,
+}
+
+impl WarmupStore {
+    pub fn global() -> &'static Mutex<WarmupStore> {
+        STORE.get_or_init(|| {
+            let path = dirs::data_dir().unwrap_or_else(|| PathBuf::from(".")).join("employee-gui").join(STORE_FILE_NAME);
+            Mutex::new(Self::open(path))
+        })
+    }
+
+    pub fn open(path: PathBuf) -> Self {
+        let mut data: StoreFile = std::fs::read_to_string(&path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+        if data.programs.is_empty() {
+            data.programs.push(WarmupProgram::linear(
+                "default_7d",
+                "标准养号（7天）",
+                7,
+                &WarmupDay { browse_minutes: 10, likes: 2 },
+                &WarmupDay { browse_minutes: 30, likes: 15 },
+            ));
+        }
+        Self { path, data }
+    }
+
+    fn save(&self) -> Result<(), String> {
+        if let Some(dir) = self.path.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+        }
+        let content = serde_json::to_string_pretty(&self.data).map_err(|e| e.to_string())?;
+        std::fs::write(&self.path, content).map_err(|e| format!("保存养号计划失败: {}", e))
+    }
+
+    pub fn programs(&self) -> Vec<WarmupProgram> {
+        self.data.programs.clone()
+    }
+
+    pub fn program(&self, id: &str) -> Result<WarmupProgram, String> {
+        self.data.programs.iter().find(|p| p.id == id).cloned().ok_or_else(|| format!("养号计划不存在: {}", id))
+    }
+
+    pub fn save_program(&mut self, program: WarmupProgram) -> Result<(), String> {
+        program.validate()?;
+        match self.data.programs.iter_mut().find(|p| p.id == program.id) {
+            Some(existing) => *existing = program,
+            None => self.data.programs.push(program),
+        }
+        self.save()
+    }
+
+    pub fn delete_program(&mut self, id: &str) -> Result<bool, String> {
+        if self.data.enrollments.iter().any(|e| e.program_id == id && e.status == WarmupStatus::Active) {
+            return Err(format!("仍有账号在执行该养号计划: {}", id));
+        }
+        let before = self.data.programs.len();
+        self.data.programs.retain(|p| p.id != id);
+        if self.data.programs.len() == before {
+            return Ok(false);
+        }
+        self.save()?;
+        Ok(true)
+    }
+
+    pub fn enrollments(&self) -> Vec<WarmupEnrollment> {
+        self.data.enrollments.clone()
+    }
+
+    pub fn enrollment(&self, account_id: &str) -> Option<&WarmupEnrollment> {
+        self.data.enrollments.iter().find(|e| e.account_id == account_id)
+    }
+
+    /// 账号入组（已入组时重新开始）
+    pub fn enroll(&mut self, account_id: &str, device_id: &str, program_id: &str, today: NaiveDate) -> Result<WarmupEnrollment, String> {
+        self.program(program_id)?;
+        self.data.enrollments.retain(|e| e.account_id != account_id);
+        let enrollment = WarmupEnrollment {
+            account_id: account_id.to_string(),
+            device_id: device_id.to_string(),
+            program_id: program_id.to_string(),
+            started_on: today,
+            status: WarmupStatus::Active,
+            progress: Vec::new(),
+        };
+        self.data.enrollments.push(enrollment.clone());
+        self.save()?;
+        Ok(enrollment)
+    }
+
+    pub fn set_status(&mut self, account_id: &str, status: WarmupStatus) -> Result<(), String> {
+        let enrollment = self
+            .data
+            .enrollments
+            .iter_mut()
+            .find(|e| e.account_id == account_id)
+            .ok_or_else(|| format!("账号未加入养号计划: {}", account_id))?;
+        enrollment.status = status;
+        self.save()
+    }
+
+    /// 累加当天进度，返回累加后的进度；达到当天目标时标记完成，最后一天完成时整个计划完成
+    pub fn record_progress(
+        &mut self,
+        account_id: &str,
+        today: NaiveDate,
+        browse_seconds: u64,
+        likes: u32,
+    ) -> Result<WarmupDayProgress, String> {
+        let index = self.data.enrollments.iter().position(|e| e.account_id == account_id)
+            .ok_or_else(|| format!("账号未加入养号计划: {}", account_id))?;
+        let program = self.program(&self.data.enrollments[index].program_id)?;
+        let enrollment = &mut self.data.enrollments[index];
+        let day_index = enrollment.day_index(today);
+        let Some(target) = program.days.get(day_index) else {
+            return Err("养号计划已结束".to_string());
+        };
+
+        if enrollment.progress_for(day_index).is_none() {
+            enrollment.progress.push(WarmupDayProgress { day_index, date: today, ..Default::default() });
+        }
+        let day = enrollment.progress.iter_mut().find(|p| p.day_index == day_index).unwrap();
+        day.browse_seconds += browse_seconds;
+        day.likes += likes;
+        day.completed = day.browse_seconds >= target.browse_minutes as u64 * 60 && day.likes >= target.likes;
+        let day = day.clone();
+        if day.completed && day_index + 1 == program.days.len() {
+            enrollment.status = WarmupStatus::Completed;
+        }
+        self.save()?;
+        Ok(day)
+    }
+
+    /// 养号就绪度：未入组的账号视为就绪（存量账号），入组的账号需完成全部天数
+    pub fn readiness(&self, account_id: &str, today: NaiveDate) -> WarmupReadiness {
+        let Some(enrollment) = self.enrollment(account_id) else {
+            return WarmupReadiness {
+                account_id: account_id.to_string(),
+                program_id: None,
+                days_total: 0,
+                days_completed: 0,
+                days_missed: 0,
+                ready: true,
+                reason: "未加入养号计划".to_string(),
+            };
+        };
+        let days_total = self.program(&enrollment.program_id).map(|p| p.days.len()).unwrap_or(0);
+        let days_completed = enrollment.progress.iter().filter(|p| p.completed).count();
+        let elapsed = enrollment.day_index(today).min(days_total);
+        let days_missed = (0..elapsed).filter(|i| !enrollment.progress_for(*i).is_some_and(|p| p.completed)).count();
+        let ready = enrollment.status == WarmupStatus::Completed || (days_total > 0 && days_completed >= days_total);
+        let reason = if ready {
+            "养号已完成".to_string()
+        } else {
+            format!("养号进行中：已完成 {}/{} 天，错过 {} 天", days_completed, days_total, days_missed)
+        };
+        WarmupReadiness {
+            account_id: account_id.to_string(),
+            program_id: Some(enrollment.program_id.clone()),
+            days_total,
+            days_completed,
+            days_missed,
+            ready,
+            reason,
+        }
+    }
+}
+
+/// 本地日期（养号按本地自然日计）
+pub fn today() -> NaiveDate {
+    Local::now().date_naive()
+}
+
+/// 分配活动前的门禁：入组但未完成养号的账号返回错误
+pub fn ensure_ready_for_campaign(account_id: &str) -> Result<(), String> {
+    let store = WarmupStore::global().lock().map_err(|e| e.to_string())?;
+    let readiness = store.readiness(account_id, today());
+    if readiness.ready {
+        Ok(())
+    } else {
+        Err(format!("账号 {} 尚未完成养号，不能分配活动（{}）", account_id, readiness.reason))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_curve_progress_and_readiness() {
+        let program = WarmupProgram::linear("p3", "三天", 3, &WarmupDay { browse_minutes: 10, likes: 0 }, &WarmupDay { browse_minutes: 20, likes: 4 });
+        assert_eq!(program.days.iter().map(|d| (d.browse_minutes, d.likes)).collect::<Vec<_>>(), vec![(10, 0), (15, 2), (20, 4)]);
+
+        let dir = tempfile::tempdir().unwrap();
+        let mut store = WarmupStore::open(dir.path().join(STORE_FILE_NAME));
+        store.save_program(program).unwrap();
+        let day1 = NaiveDate::from_ymd_opt(2025, 3, 1).unwrap();
+        store.enroll("acc1", "dev1", "p3", day1).unwrap();
+        assert!(store.readiness("legacy", day1).ready);
+        assert!(!store.readiness("acc1", day1).ready);
+
+        assert!(store.record_progress("acc1", day1, 600, 0).unwrap().completed);
+        let day3 = day1 + chrono::Duration::days(2);
+        assert!(!store.record_progress("acc1", day3, 1200, 3).unwrap().completed);
+        let readiness = store.readiness("acc1", day3);
+        assert_eq!((readiness.days_completed, readiness.days_missed, readiness.ready), (1, 1, false));
+
+        assert!(store.record_progress("acc1", day3, 0, 1).unwrap().completed);
+        // 最后一天完成即视为养号结束（错过的天数不再阻塞）
+        assert_eq!(store.enrollment("acc1").unwrap().status, WarmupStatus::Completed);
+        assert!(store.readiness("acc1", day3).ready);
+        assert!(store.record_progress("acc1", day3 + chrono::Duration::days(1), 10, 0).is_err());
+        assert!(store.delete_program("p3").unwrap());
+    }
+}
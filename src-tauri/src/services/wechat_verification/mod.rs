@@ -0,0 +1,711 @@
+// src-tauri/src/services/wechat_verification/mod.rs
+// module: wechat_verification | layer: services | role: 微信注册核验
+// summary: 对已导入设备的号码逐个走微信「添加朋友」搜索流程，判定是否注册微信并写回 contact_numbers；间隔加随机抖动慢速执行，按设备每小时 / 每天限额，识别到频繁操作等风控提示立即中止并进入冷却
+
+use async_trait::async_trait;
+use chrono::{DateTime, Duration as ChronoDuration, Utc};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Duration;
+use tracing::{info, warn};
+
+use crate::services::adb::get_device_session;
+use crate::services::contact_storage::repositories::contact_numbers::wechat_check::{WechatCheckCandidate, WechatStatus};
+use crate::services::universal_ui_page_analyzer::{parse_ui_elements_simple, UIElement};
+
+pub const WECHAT_PACKAGE: &str = "com.tencent.mm";
+/// 前端订阅的核验进度事件
+pub const WECHAT_VERIFICATION_EVENT: &str = "contacts://wechat-verification";
+
+/// 出现即视为触发风控（整段中止，不再继续搜索）
+const RISK_KEYWORDS: &[&str] =
+    &["操作过于频繁", "搜索过于频繁", "请稍后再试", "存在安全风险", "安全验证", "拖动下方滑块", "帐号存在异常", "账号存在异常"];
+const NOT_FOUND_KEYWORDS: &[&str] = &["该用户不存在"];
+const ABNORMAL_KEYWORDS: &[&str] = &["状态异常"];
+const PROFILE_KEYWORDS: &[&str] = &["添加到通讯录", "发消息", "音视频通话"];
+/// 「添加朋友」页上的搜索入口
+const SEARCH_ENTRY_KEYWORDS: &[&str] = &["微信号/手机号", "帐号/手机号", "账号/手机号"];
+
+const KEY_BACK: i32 = 4;
+const KEY_ENTER: i32 = 66;
+const KEY_DEL: i32 = 67;
+const KEY_MOVE_END: i32 = 123;
+
+/// 核验参数
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct WechatVerificationOptions {
+    /// 本次最多核验的号码数
+    pub max_numbers: i64,
+    /// 两个号码之间的最小间隔（秒）与随机抖动上限（秒）
+    pub min_interval_secs: u64,
+    pub jitter_secs: u64,
+    /// 每核验若干个号码额外长休息一次（0 表示不休息）
+    pub long_pause_every: usize,
+    pub long_pause_secs: u64,
+    /// 单设备限额（按数据库中已写入的核验时间统计，0 表示不限）
+    pub max_per_hour: i64,
+    pub max_per_day: i64,
+    /// 核验结论超过该天数的号码重新核验；为空则只核验从未核验过的号码
+    pub recheck_after_days: Option<i64>,
+    /// 连续多少个号码无法识别结果时中止（界面可能已变化）
+    pub max_consecutive_unknown: usize,
+    /// 触发风控后该设备多少小时内拒绝再次启动（0 表示不冷却）
+    pub risk_cooldown_hours: i64,
+    /// 搜索后等待结果的轮询间隔（毫秒）与次数
+    pub result_poll_ms: u64,
+    pub result_poll_attempts: usize,
+}
+
+impl Default for WechatVerificationOptions {
+    fn default() -> Self {
+        Self {
+            max_numbers: 50,
+            min_interval_secs: 40,
+            jitter_secs: 25,
+            long_pause_every: 10,
+            long_pause_secs: 180,
+            max_per_hour: 20,
+            max_per_day: 80,
+            recheck_after_days: None,
+            max_consecutive_unknown: 3,
+            risk_cooldown_hours: 24,
+            result_poll_ms: 1500,
+            result_poll_attempts: 4,
+        }
+    }
+}
+
+impl WechatVerificationOptions {
+    /// 第 `checked` 个号码之后的等待时长
+    pub fn pace_delay(&self, checked: usize, rng: &mut impl Rng) -> Duration {
+        let jitter = if self.jitter_secs > 0 { rng.gen_range(0..=self.jitter_secs * 1000) } else { 0 };
+        let mut delay = Duration::from_secs(self.min_interval_secs) + Duration::from_millis(jitter);
+        if self.long_pause_every > 0 && checked > 0 && checked % self.long_pause_every == 0 {
+            delay += Duration::from_secs(self.long_pause_secs);
+        }
+        delay
+    }
+
+    /// 已达限额时返回原因
+    pub fn budget_exhausted(&self, last_hour: i64, last_day: i64) -> Option<String> {
+        if self.max_per_hour > 0 && last_hour >= self.max_per_hour {
+            return Some(format!("已达每小时核验上限 {}", self.max_per_hour));
+        }
+        if self.max_per_day > 0 && last_day >= self.max_per_day {
+            return Some(format!("已达每日核验上限 {}", self.max_per_day));
+        }
+        None
+    }
+}
+
+/// 当前界面
+#[derive(Debug, Clone, PartialEq)]
+pub enum WechatScreen {
+    /// 风控提示（附提示原文）
+    RiskControl(String),
+    NotFound,
+    Abnormal(String),
+    /// 搜到的用户资料页
+    Profile,
+    /// 「添加朋友」搜索页（有输入框）
+    SearchPage,
+    Other,
+}
+
+fn find<'a>(elements: &'a [UIElement], keywords: &[&str]) -> Option<&'a UIElement> {
+    elements.iter().find(|e| keywords.iter().any(|k| e.text.contains(k) || e.content_desc.contains(k)))
+}
+
+fn search_field(elements: &[UIElement]) -> Option<&UIElement> {
+    elements.iter().find(|e| e.class_name.as_deref().is_some_and(|c| c.ends_with("EditText")))
+}
+
+fn center(element: &UIElement) -> (i32, i32) {
+    let b = &element.bounds;
+    ((b.left + b.right) / 2, (b.top + b.bottom) / 2)
+}
+
+/// 按界面文字判断当前所处状态（风控优先）
+pub fn classify_screen(elements: &[UIElement]) -> WechatScreen {
+    if let Some(e) = find(elements, RISK_KEYWORDS) {
+        return WechatScreen::RiskControl(if e.text.is_empty() { e.content_desc.clone() } else { e.text.clone() });
+    }
+    if find(elements, NOT_FOUND_KEYWORDS).is_some() {
+        return WechatScreen::NotFound;
+    }
+    if let Some(e) = find(elements, ABNORMAL_KEYWORDS) {
+        return WechatScreen::Abnormal(e.text.clone());
+    }
+    if search_field(elements).is_none() && find(elements, PROFILE_KEYWORDS).is_some() {
+        return WechatScreen::Profile;
+    }
+    if search_field(elements).is_some() {
+        return WechatScreen::SearchPage;
+    }
+    WechatScreen::Other
+}
+
+/// 操作设备上的微信（插件层用 adb 实现，测试中可替换）
+#[async_trait]
+pub trait WechatDriver: Send + Sync {
+    async fn launch(&self) -> Result<(), String>;
+    async fn dump(&self) -> Result<String, String>;
+    /// 点击后等待界面稳定
+    async fn tap(&self, x: i32, y: i32) -> Result<(), String>;
+    async fn input_text(&self, text: &str) -> Result<(), String>;
+    async fn keys(&self, keycodes: &[i32]) -> Result<(), String>;
+}
+
+/// 核验结果读写（由插件层基于联系人库实现）
+pub trait WechatCheckStore: Send + Sync {
+    fn candidates(&self, device_id: &str, limit: i64, recheck_after_days: Option<i64>) -> Result<Vec<WechatCheckCandidate>, String>;
+    fn record(&self, id: i64, status: WechatStatus, device_id: &str, note: Option<&str>) -> Result<(), String>;
+    fn checks_since(&self, device_id: &str, hours: i64) -> Result<i64, String>;
+}
+
+/// 经 adb 长连接操作微信
+pub struct AdbWechatDriver {
+    device_id: String,
+    settle: Duration,
+}
+
+impl AdbWechatDriver {
+    pub fn new(device_id: &str) -> Self {
+        Self { device_id: device_id.to_string(), settle: Duration::from_millis(1200) }
+    }
+}
+
+#[async_trait]
+impl WechatDriver for AdbWechatDriver {
+    async fn launch(&self) -> Result<(), String> {
+        let session = get_device_session(&self.device_id).await.map_err(|e| e.to_string())?;
+        session.start_app(WECHAT_PACKAGE).await.map_err(|e| e.to_string())?;
+        tokio::time::sleep(self.settle * 3).await;
+        Ok(())
+    }
+
+    async fn dump(&self) -> Result<String, String> {
+        let session = get_device_session(&self.device_id).await.map_err(|e| e.to_string())?;
+        session.dump_ui().await.map_err(|e| e.to_string())
+    }
+
+    async fn tap(&self, x: i32, y: i32) -> Result<(), String> {
+        let session = get_device_session(&self.device_id).await.map_err(|e| e.to_string())?;
+        session.tap(x, y).await.map_err(|e| e.to_string())?;
+        tokio::time::sleep(self.settle).await;
+        Ok(())
+    }
+
+    async fn input_text(&self, text: &str) -> Result<(), String> {
+        let session = get_device_session(&self.device_id).await.map_err(|e| e.to_string())?;
+        session.input_text(text).await.map_err(|e| e.to_string())?;
+        tokio::time::sleep(self.settle).await;
+        Ok(())
+    }
+
+    async fn keys(&self, keycodes: &[i32]) -> Result<(), String> {
+        let session = get_device_session(&self.device_id).await.map_err(|e| e.to_string())?;
+        let codes: Vec<String> = keycodes.iter().map(|k| k.to_string()).collect();
+        session.execute_command(&format!("input keyevent {}", codes.join(" "))).await.map_err(|e| e.to_string())?;
+        tokio::time::sleep(self.settle).await;
+        Ok(())
+    }
+}
+
+/// 运行状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VerificationState {
+    Running,
+    Completed,
+    Stopped,
+    /// 达到每小时 / 每日限额
+    RateLimited,
+    /// 检测到风控提示后中止
+    RiskControl,
+    Failed,
+}
+
+/// 单设备核验进度（也作为事件负载推送给前端）
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VerificationProgress {
+    pub device_id: String,
+    pub state: VerificationState,
+    pub total: usize,
+    pub checked: usize,
+    pub registered: usize,
+    pub not_registered: usize,
+    pub abnormal: usize,
+    /// 无法识别结果、未写入的号码
+    pub skipped: usize,
+    pub current_phone: Option<String>,
+    pub message: Option<String>,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: Option<DateTime<Utc>>,
+}
+
+impl VerificationProgress {
+    fn new(device_id: &str) -> Self {
+        Self {
+            device_id: device_id.to_string(),
+            state: VerificationState::Running,
+            total: 0,
+            checked: 0,
+            registered: 0,
+            not_registered: 0,
+            abnormal: 0,
+            skipped: 0,
+            current_phone: None,
+            message: None,
+            started_at: Utc::now(),
+            finished_at: None,
+        }
+    }
+
+    fn finish(&mut self, state: VerificationState, message: Option<String>) {
+        self.state = state;
+        self.message = message;
+        self.current_phone = None;
+        self.finished_at = Some(Utc::now());
+    }
+}
+
+struct RunEntry {
+    progress: VerificationProgress,
+    stop: Arc<AtomicBool>,
+}
+
+static RUNS: OnceLock<Mutex<HashMap<String, RunEntry>>> = OnceLock::new();
+
+fn runs() -> std::sync::MutexGuard<'static, HashMap<String, RunEntry>> {
+    RUNS.get_or_init(Default::default).lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// 登记一次运行；同一设备已在运行或仍在风控冷却期时拒绝
+pub fn begin(device_id: &str, options: &WechatVerificationOptions) -> Result<Arc<AtomicBool>, String> {
+    let mut runs = runs();
+    if let Some(entry) = runs.get(device_id) {
+        let last = &entry.progress;
+        if last.state == VerificationState::Running {
+            return Err(format!("设备 {} 正在核验微信注册", device_id));
+        }
+        if last.state == VerificationState::RiskControl && options.risk_cooldown_hours > 0 {
+            let until = last.finished_at.unwrap_or(last.started_at) + ChronoDuration::hours(options.risk_cooldown_hours);
+            if Utc::now() < until {
+                return Err(format!("设备 {} 触发微信风控，{} 前暂停核验", device_id, until.format("%Y-%m-%d %H:%M")));
+            }
+        }
+    }
+    let stop = Arc::new(AtomicBool::new(false));
+    runs.insert(device_id.to_string(), RunEntry { progress: VerificationProgress::new(device_id), stop: stop.clone() });
+    Ok(stop)
+}
+
+/// 请求停止（在当前号码核验完、等待间隔中生效）
+pub fn stop(device_id: &str) -> bool {
+    match runs().get(device_id) {
+        Some(entry) if entry.progress.state == VerificationState::Running => {
+            entry.stop.store(true, Ordering::SeqCst);
+            true
+        }
+        _ => false,
+    }
+}
+
+/// 各设备最近一次核验的进度
+pub fn status() -> Vec<VerificationProgress> {
+    let mut list: Vec<_> = runs().values().map(|e| e.progress.clone()).collect();
+    list.sort_by(|a, b| a.device_id.cmp(&b.device_id));
+    list
+}
+
+fn publish(progress: &VerificationProgress) {
+    if let Some(entry) = runs().get_mut(&progress.device_id) {
+        entry.progress = progress.clone();
+    }
+}
+
+enum CheckError {
+    Risk(String),
+    Ui(String),
+}
+
+impl From<String> for CheckError {
+    fn from(e: String) -> Self {
+        CheckError::Ui(e)
+    }
+}
+
+async fn screen(driver: &dyn WechatDriver) -> Result<Vec<UIElement>, CheckError> {
+    let xml = driver.dump().await?;
+    let elements = parse_ui_elements_simple(&xml).map_err(|e| CheckError::Ui(e.to_string()))?;
+    if let WechatScreen::RiskControl(text) = classify_screen(&elements) {
+        return Err(CheckError::Risk(text));
+    }
+    Ok(elements)
+}
+
+/// 进入「添加朋友」搜索页，返回输入框中心坐标
+async fn ensure_search_page(driver: &dyn WechatDriver) -> Result<(i32, i32), CheckError> {
+    let mut launched = false;
+    for _ in 0..8 {
+        let elements = screen(driver).await?;
+        if classify_screen(&elements) != WechatScreen::Profile {
+            if let Some(field) = search_field(&elements) {
+                return Ok(center(field));
+            }
+        }
+        let next = find(&elements, SEARCH_ENTRY_KEYWORDS)
+            .or_else(|| find(&elements, &["添加朋友"]))
+            .or_else(|| find(&elements, &["更多功能"]));
+        match next {
+            Some(element) => {
+                let (x, y) = center(element);
+                driver.tap(x, y).await?;
+            }
+            None if !launched => {
+                driver.launch().await?;
+                launched = true;
+            }
+            None => driver.keys(&[KEY_BACK]).await?,
+        }
+    }
+    Err(CheckError::Ui("无法进入微信「添加朋友」搜索页".to_string()))
+}
+
+/// 搜索单个号码；Ok(None) 表示未能识别结果
+async fn check_number(
+    driver: &dyn WechatDriver,
+    phone: &str,
+    options: &WechatVerificationOptions,
+) -> Result<Option<(WechatStatus, Option<String>)>, CheckError> {
+    let (x, y) = ensure_search_page(driver).await?;
+    driver.tap(x, y).await?;
+    let mut clear = vec![KEY_MOVE_END];
+    clear.extend(std::iter::repeat(KEY_DEL).take(24));
+    driver.keys(&clear).await?;
+    driver.input_text(phone).await?;
+
+    let elements = screen(driver).await?;
+    let search_row = elements.iter().find(|e| e.text.starts_with("搜索") && e.text.contains(phone));
+    match search_row {
+        Some(row) => {
+            let (x, y) = center(row);
+            driver.tap(x, y).await?;
+        }
+        None => driver.keys(&[KEY_ENTER]).await?,
+    }
+
+    for attempt in 0..options.result_poll_attempts.max(1) {
+        if attempt > 0 {
+            tokio::time::sleep(Duration::from_millis(options.result_poll_ms)).await;
+        }
+        let elements = screen(driver).await?;
+        match classify_screen(&elements) {
+            WechatScreen::NotFound => return Ok(Some((WechatStatus::NotRegistered, Some("该用户不存在".to_string())))),
+            WechatScreen::Abnormal(text) => return Ok(Some((WechatStatus::Abnormal, Some(text)))),
+            WechatScreen::Profile => {
+                // 回到搜索页，下一个号码直接清空重输
+                driver.keys(&[KEY_BACK]).await?;
+                return Ok(Some((WechatStatus::Registered, None)));
+            }
+            _ => {}
+        }
+    }
+    Ok(None)
+}
+
+/// 可被停止请求打断的等待
+async fn pause(delay: Duration, stop: &AtomicBool) {
+    let step = Duration::from_millis(500);
+    let mut waited = Duration::ZERO;
+    while waited < delay && !stop.load(Ordering::SeqCst) {
+        let chunk = step.min(delay - waited);
+        tokio::time::sleep(chunk).await;
+        waited += chunk;
+    }
+}
+
+/// 逐个核验设备上已导入的号码；每个号码核验完即写库并回调进度，返回最终进度
+pub async fn run_verification(
+    driver: &dyn WechatDriver,
+    store: &dyn WechatCheckStore,
+    device_id: &str,
+    options: &WechatVerificationOptions,
+    stop: &AtomicBool,
+    on_progress: &(dyn Fn(&VerificationProgress) + Send + Sync),
+) -> VerificationProgress {
+    let mut progress = VerificationProgress::new(device_id);
+    let report = |progress: &VerificationProgress| {
+        publish(progress);
+        on_progress(progress);
+    };
+    let candidates = match store.candidates(device_id, options.max_numbers, options.recheck_after_days) {
+        Ok(candidates) => candidates,
+        Err(e) => {
+            progress.finish(VerificationState::Failed, Some(e));
+            report(&progress);
+            return progress;
+        }
+    };
+    progress.total = candidates.len();
+    report(&progress);
+    info!("🔎 设备 {} 开始核验微信注册，共 {} 个号码", device_id, candidates.len());
+
+    let mut consecutive_unknown = 0;
+    for (index, candidate) in candidates.iter().enumerate() {
+        if index > 0 {
+            let delay = options.pace_delay(index, &mut rand::thread_rng());
+            pause(delay, stop).await;
+        }
+        if stop.load(Ordering::SeqCst) {
+            progress.finish(VerificationState::Stopped, Some("已手动停止".to_string()));
+            break;
+        }
+        let budget = store
+            .checks_since(device_id, 1)
+            .and_then(|hour| store.checks_since(device_id, 24).map(|day| options.budget_exhausted(hour, day)));
+        match budget {
+            Ok(None) => {}
+            Ok(Some(reason)) => {
+                progress.finish(VerificationState::RateLimited, Some(reason));
+                break;
+            }
+            Err(e) => {
+                progress.finish(VerificationState::Failed, Some(e));
+                break;
+            }
+        }
+
+        progress.current_phone = Some(candidate.phone.clone());
+        report(&progress);
+        match check_number(driver, &candidate.phone, options).await {
+            Ok(Some((status, note))) => {
+                if let Err(e) = store.record(candidate.id, status, device_id, note.as_deref()) {
+                    progress.finish(VerificationState::Failed, Some(e));
+                    break;
+                }
+                consecutive_unknown = 0;
+                progress.checked += 1;
+                match status {
+                    WechatStatus::Registered => progress.registered += 1,
+                    WechatStatus::NotRegistered => progress.not_registered += 1,
+                    WechatStatus::Abnormal => progress.abnormal += 1,
+                }
+            }
+            Ok(None) => {
+                consecutive_unknown += 1;
+                progress.skipped += 1;
+                warn!("⚠️ 号码 {} 未能识别微信搜索结果", candidate.phone);
+                if consecutive_unknown >= options.max_consecutive_unknown.max(1) {
+                    progress.finish(VerificationState::Failed, Some(format!("连续 {} 个号码无法识别搜索结果", consecutive_unknown)));
+                    break;
+                }
+            }
+            Err(CheckError::Risk(text)) => {
+                warn!("🛑 设备 {} 触发微信风控，中止核验: {}", device_id, text);
+                let _ = driver.keys(&[KEY_BACK]).await;
+                progress.finish(VerificationState::RiskControl, Some(text));
+                break;
+            }
+            Err(CheckError::Ui(e)) => {
+                progress.finish(VerificationState::Failed, Some(e));
+                break;
+            }
+        }
+        report(&progress);
+    }
+    if progress.state == VerificationState::Running {
+        progress.finish(VerificationState::Completed, None);
+    }
+    info!(
+        "🔎 设备 {} 微信核验结束 {:?}: 已核验 {} (注册 {} / 未注册 {} / 异常 {})",
+        device_id, progress.state, progress.checked, progress.registered, progress.not_registered, progress.abnormal
+    );
+    report(&progress);
+    progress
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn node(class: &str, text: &str, desc: &str, row: i32) -> String {
+        format!(
+            r#"<node index="0" text="{}" resource-id="" class="{}" content-desc="{}" clickable="true" bounds="[0,{}][1080,{}]" />"#,
+            text,
+            class,
+            desc,
+            row * 100,
+            row * 100 + 50
+        )
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
+    enum Page {
+        Home,
+        Menu,
+        AddFriend,
+        Search { typed: String, result: Option<&'static str> },
+        Profile,
+    }
+
+    /// 模拟微信：号码 139… 已注册，138… 不存在，137… 触发频繁提示
+    struct FakeWechat {
+        page: Mutex<Page>,
+    }
+
+    impl FakeWechat {
+        fn xml(&self) -> String {
+            let body = match &*self.page.lock().unwrap() {
+                Page::Home => node("android.widget.ImageView", "", "更多功能按钮", 0),
+                Page::Menu => node("android.widget.TextView", "添加朋友", "", 1),
+                Page::AddFriend => node("android.widget.TextView", "微信号/手机号", "", 2),
+                Page::Search { typed, result } => {
+                    let mut body = node("android.widget.EditText", typed, "", 3);
+                    if !typed.is_empty() && result.is_none() {
+                        body += &node("android.widget.TextView", &format!("搜索:{}", typed), "", 4);
+                    }
+                    if let Some(text) = result {
+                        body += &node("android.widget.TextView", text, "", 5);
+                    }
+                    body
+                }
+                Page::Profile => node("android.widget.Button", "添加到通讯录", "", 6),
+            };
+            format!(r#"<?xml version="1.0" encoding="UTF-8"?><hierarchy rotation="0">{}</hierarchy>"#, body)
+        }
+
+        fn search(&self, page: &mut Page) {
+            if let Page::Search { typed, result } = page {
+                let prefix = typed[..3].to_string();
+                match prefix.as_str() {
+                    "139" => *page = Page::Profile,
+                    "138" => *result = Some("该用户不存在"),
+                    _ => *result = Some("操作过于频繁，请稍后再试"),
+                }
+            }
+        }
+    }
+
+    #[async_trait]
+    impl WechatDriver for FakeWechat {
+        async fn launch(&self) -> Result<(), String> {
+            *self.page.lock().unwrap() = Page::Home;
+            Ok(())
+        }
+
+        async fn dump(&self) -> Result<String, String> {
+            Ok(self.xml())
+        }
+
+        async fn tap(&self, _x: i32, y: i32) -> Result<(), String> {
+            let mut page = self.page.lock().unwrap();
+            match (y / 100, page.clone()) {
+                (0, Page::Home) => *page = Page::Menu,
+                (1, Page::Menu) => *page = Page::AddFriend,
+                (2, Page::AddFriend) => *page = Page::Search { typed: String::new(), result: None },
+                (4, Page::Search { .. }) => self.search(&mut page),
+                _ => {}
+            }
+            Ok(())
+        }
+
+        async fn input_text(&self, text: &str) -> Result<(), String> {
+            if let Page::Search { typed, result } = &mut *self.page.lock().unwrap() {
+                typed.push_str(text);
+                *result = None;
+            }
+            Ok(())
+        }
+
+        async fn keys(&self, keycodes: &[i32]) -> Result<(), String> {
+            let mut page = self.page.lock().unwrap();
+            for key in keycodes {
+                match (*key, page.clone()) {
+                    (KEY_BACK, Page::Profile) => *page = Page::Search { typed: String::new(), result: None },
+                    (KEY_DEL, Page::Search { .. }) => *page = Page::Search { typed: String::new(), result: None },
+                    (KEY_ENTER, Page::Search { .. }) => self.search(&mut page),
+                    _ => {}
+                }
+            }
+            Ok(())
+        }
+    }
+
+    #[derive(Default)]
+    struct MemoryStore {
+        phones: Vec<&'static str>,
+        records: Mutex<Vec<(i64, WechatStatus)>>,
+    }
+
+    impl WechatCheckStore for MemoryStore {
+        fn candidates(&self, _device_id: &str, limit: i64, _recheck: Option<i64>) -> Result<Vec<WechatCheckCandidate>, String> {
+            let done: Vec<i64> = self.records.lock().unwrap().iter().map(|(id, _)| *id).collect();
+            Ok(self
+                .phones
+                .iter()
+                .enumerate()
+                .map(|(i, phone)| WechatCheckCandidate { id: i as i64, phone: phone.to_string(), name: String::new() })
+                .filter(|c| !done.contains(&c.id))
+                .take(limit as usize)
+                .collect())
+        }
+
+        fn record(&self, id: i64, status: WechatStatus, _device_id: &str, _note: Option<&str>) -> Result<(), String> {
+            self.records.lock().unwrap().push((id, status));
+            Ok(())
+        }
+
+        fn checks_since(&self, _device_id: &str, _hours: i64) -> Result<i64, String> {
+            Ok(self.records.lock().unwrap().len() as i64)
+        }
+    }
+
+    #[tokio::test]
+    async fn verifies_numbers_and_aborts_on_risk_control() {
+        let options = WechatVerificationOptions {
+            min_interval_secs: 0,
+            jitter_secs: 0,
+            result_poll_ms: 0,
+            max_per_hour: 0,
+            max_per_day: 3,
+            ..Default::default()
+        };
+        let driver = FakeWechat { page: Mutex::new(Page::Home) };
+        let store = MemoryStore { phones: vec!["13900000001", "13800000002", "13700000003", "13900000004"], ..Default::default() };
+        let stop = begin("wechat-test-1", &options).unwrap();
+        let seen = Mutex::new(Vec::new());
+        let progress = run_verification(&driver, &store, "wechat-test-1", &options, &stop, &|p| {
+            seen.lock().unwrap().push(p.current_phone.clone())
+        })
+        .await;
+
+        assert_eq!(progress.state, VerificationState::RiskControl);
+        assert_eq!(progress.message.as_deref(), Some("操作过于频繁，请稍后再试"));
+        assert_eq!((progress.checked, progress.registered, progress.not_registered), (2, 1, 1));
+        assert_eq!(*store.records.lock().unwrap(), vec![(0, WechatStatus::Registered), (1, WechatStatus::NotRegistered)]);
+        assert!(seen.lock().unwrap().contains(&Some("13700000003".to_string())));
+        // 风控冷却期内拒绝再次启动
+        assert!(begin("wechat-test-1", &options).unwrap_err().contains("风控"));
+
+        // 每日限额：已核验 2 个，上限 3，只再核验 1 个
+        let store = MemoryStore { phones: vec!["13900000001", "13800000002", "13900000003"], ..Default::default() };
+        store.records.lock().unwrap().push((99, WechatStatus::Registered));
+        store.records.lock().unwrap().push((98, WechatStatus::Registered));
+        let driver = FakeWechat { page: Mutex::new(Page::Home) };
+        let progress = run_verification(&driver, &store, "wechat-test-2", &options, &AtomicBool::new(false), &|_| {}).await;
+        assert_eq!(progress.state, VerificationState::RateLimited);
+        assert_eq!(progress.checked, 1);
+
+        let mut rng = rand::thread_rng();
+        let paced = WechatVerificationOptions::default();
+        assert!(paced.pace_delay(1, &mut rng) >= Duration::from_secs(40));
+        assert!(paced.pace_delay(10, &mut rng) >= Duration::from_secs(220));
+    }
+}
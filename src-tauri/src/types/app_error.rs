@@ -0,0 +1,146 @@
+// src-tauri/src/types/app_error.rs
+// module: types | layer: shared | role: 命令错误类型
+// summary: Tauri 命令统一错误 AppError（错误码 + 分类 + 面向用户的消息 + 调试上下文），序列化为固定结构供前端按错误码分支；兼容旧的 `Result<T, String>` 返回值
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use crate::core::shared::error::CoreError;
+pub use crate::core::shared::error::{ErrorCategory, ErrorCode};
+
+/// Tauri 命令统一错误
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppError {
+    pub code: ErrorCode,
+    pub category: ErrorCategory,
+    /// 面向用户的消息
+    pub message: String,
+    /// 调试上下文（原始错误、设备、参数等），不直接展示给用户
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub context: Option<String>,
+}
+
+pub type AppResult<T> = Result<T, AppError>;
+
+impl AppError {
+    pub fn new(code: ErrorCode, message: impl Into<String>) -> Self {
+        Self { code, category: code.category(), message: message.into(), context: None }
+    }
+
+    pub fn with_context(mut self, context: impl Into<String>) -> Self {
+        self.context = Some(context.into());
+        self
+    }
+
+    pub fn invalid_argument(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::InvalidArgument, message)
+    }
+
+    pub fn not_found(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::NotFound, message)
+    }
+
+    pub fn internal(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::Internal, message)
+    }
+
+    /// UI dump 失败：消息面向用户，原始错误放入上下文
+    pub fn ui_dump_failed(device_id: &str, error: impl fmt::Display) -> Self {
+        Self::new(ErrorCode::UiDumpFailed, "获取界面结构失败，请确认设备已连接且屏幕已解锁")
+            .with_context(format!("device={} error={}", device_id, error))
+    }
+
+    /// 旧式字符串错误：识别 `CODE: 消息` 前缀，其余按 Unknown 原样保留
+    pub fn from_legacy(message: impl Into<String>) -> Self {
+        let message = message.into();
+        if let Some((prefix, rest)) = message.split_once(':') {
+            if let Some(code) = ErrorCode::from_prefix(prefix.trim()) {
+                return Self::new(code, rest.trim());
+            }
+        }
+        Self::new(ErrorCode::Unknown, message)
+    }
+}
+
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let code = serde_json::to_value(self.code).ok().and_then(|v| v.as_str().map(str::to_string)).unwrap_or_default();
+        write!(f, "{}: {}", code, self.message)?;
+        if let Some(context) = &self.context {
+            write!(f, " ({})", context)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for AppError {}
+
+impl From<String> for AppError {
+    fn from(message: String) -> Self {
+        Self::from_legacy(message)
+    }
+}
+
+impl From<&str> for AppError {
+    fn from(message: &str) -> Self {
+        Self::from_legacy(message)
+    }
+}
+
+impl From<std::io::Error> for AppError {
+    fn from(err: std::io::Error) -> Self {
+        let code = if err.kind() == std::io::ErrorKind::NotFound { ErrorCode::NotFound } else { ErrorCode::StorageError };
+        Self::new(code, err.to_string())
+    }
+}
+
+impl From<serde_json::Error> for AppError {
+    fn from(err: serde_json::Error) -> Self {
+        Self::new(ErrorCode::InvalidArgument, "数据格式无效").with_context(err.to_string())
+    }
+}
+
+impl From<rusqlite::Error> for AppError {
+    fn from(err: rusqlite::Error) -> Self {
+        Self::new(ErrorCode::StorageError, "数据库操作失败").with_context(err.to_string())
+    }
+}
+
+impl From<anyhow::Error> for AppError {
+    fn from(err: anyhow::Error) -> Self {
+        Self::from_legacy(format!("{:#}", err))
+    }
+}
+
+impl From<CoreError> for AppError {
+    fn from(err: CoreError) -> Self {
+        let error = Self::new(err.code, err.message);
+        match err.details {
+            Some(details) => error.with_context(details),
+            None => error,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn legacy_prefixes_map_to_codes_and_serialize_flat() {
+        let err = AppError::from("NO_SELECTOR: 必须提供至少一个选择器条件".to_string());
+        assert_eq!((err.code, err.category), (ErrorCode::NoSelector, ErrorCategory::Matching));
+        assert_eq!(err.message, "必须提供至少一个选择器条件");
+        assert_eq!(AppError::from("NOT_UNIQUE: 2 个元素").code, ErrorCode::NonUnique);
+        // 普通消息（含中文冒号 / 非错误码前缀）保持原样
+        let plain = AppError::from("设备 emulator-5554: 离线");
+        assert_eq!((plain.code, plain.message.as_str()), (ErrorCode::Unknown, "设备 emulator-5554: 离线"));
+
+        let json = serde_json::to_value(AppError::ui_dump_failed("emulator-5554", "timeout")).unwrap();
+        assert_eq!(json["code"], "UI_DUMP_FAILED");
+        assert_eq!(json["category"], "device");
+        assert_eq!(json["context"], "device=emulator-5554 error=timeout");
+        assert!(serde_json::to_value(AppError::internal("x")).unwrap().get("context").is_none());
+    }
+}
@@ -0,0 +1,187 @@
+// src-tauri/src/types/dto/contacts.rs
+// module: types | layer: dto | role: 号码库命令 DTO
+// summary: 号码导入结果、文件夹导入会话、号码统计与全库去重报告的 camelCase DTO
+
+use serde::Serialize;
+
+use crate::services::contact_storage::models::{ContactNumberStatsDto, ImportNumbersResult, IndustryCountDto};
+use crate::services::contact_storage::repositories::contact_numbers::global_dedup::{DedupMergeGroup, DedupReport, DedupStrategy};
+use crate::services::contact_storage::repositories::folder_import_sessions_repo::{FolderImportFileDto, FolderImportSessionDto};
+
+/// 号码导入结果（TXT / Excel / vCard）
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportNumbersResultDto {
+    pub success: bool,
+    pub total_files: i64,
+    pub total_numbers: i64,
+    pub inserted: i64,
+    pub duplicates: i64,
+    pub errors: Vec<String>,
+}
+
+impl From<ImportNumbersResult> for ImportNumbersResultDto {
+    fn from(result: ImportNumbersResult) -> Self {
+        let ImportNumbersResult { success, total_files, total_numbers, inserted, duplicates, errors } = result;
+        Self { success, total_files, total_numbers, inserted, duplicates, errors }
+    }
+}
+
+/// 文件夹导入结果：在单文件结果的基础上附带会话与跳过 / 失败明细
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FolderImportResultDto {
+    #[serde(flatten)]
+    pub summary: ImportNumbersResultDto,
+    pub session_id: i64,
+    /// 之前已导入完成、本次跳过的文件数
+    pub skipped_files: i64,
+    pub failed_files: Vec<FolderImportFileErrorDto>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FolderImportFileErrorDto {
+    pub file_path: String,
+    pub error: String,
+}
+
+/// 文件夹导入会话中单个文件的状态
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FolderImportFileStateDto {
+    pub file_path: String,
+    pub file_size: i64,
+    pub modified_at: i64,
+    /// completed | failed
+    pub status: String,
+    pub total_numbers: i64,
+    pub inserted: i64,
+    pub duplicates: i64,
+    pub error_message: Option<String>,
+    pub updated_at: String,
+}
+
+impl From<FolderImportFileDto> for FolderImportFileStateDto {
+    fn from(file: FolderImportFileDto) -> Self {
+        let FolderImportFileDto {
+            file_path,
+            file_size,
+            modified_at,
+            status,
+            total_numbers,
+            inserted,
+            duplicates,
+            error_message,
+            updated_at,
+        } = file;
+        Self { file_path, file_size, modified_at, status, total_numbers, inserted, duplicates, error_message, updated_at }
+    }
+}
+
+/// 文件夹导入会话
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FolderImportSessionStateDto {
+    pub id: i64,
+    pub folder_path: String,
+    /// running | completed | partial
+    pub status: String,
+    pub started_at: String,
+    pub finished_at: Option<String>,
+    pub files: Vec<FolderImportFileStateDto>,
+}
+
+impl From<FolderImportSessionDto> for FolderImportSessionStateDto {
+    fn from(session: FolderImportSessionDto) -> Self {
+        let FolderImportSessionDto { id, folder_path, status, started_at, finished_at, files } = session;
+        Self { id, folder_path, status, started_at, finished_at, files: files.into_iter().map(Into::into).collect() }
+    }
+}
+
+/// 行业号码数
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IndustryCountItemDto {
+    pub industry: String,
+    pub count: i64,
+}
+
+/// 号码库统计
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NumberStatsDto {
+    pub total: i64,
+    pub available: i64,
+    pub assigned: i64,
+    pub imported: i64,
+    pub unclassified: i64,
+    pub per_industry: Vec<IndustryCountItemDto>,
+}
+
+impl From<ContactNumberStatsDto> for NumberStatsDto {
+    fn from(stats: ContactNumberStatsDto) -> Self {
+        let ContactNumberStatsDto { total, available, assigned, imported, unclassified, per_industry } = stats;
+        Self {
+            total,
+            available,
+            assigned,
+            imported,
+            unclassified,
+            per_industry: per_industry
+                .into_iter()
+                .map(|IndustryCountDto { industry, count }| IndustryCountItemDto { industry, count })
+                .collect(),
+        }
+    }
+}
+
+/// 去重合并组
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DedupMergeGroupDto {
+    pub normalized_phone: String,
+    pub kept_id: i64,
+    pub kept_source_file: String,
+    pub removed_ids: Vec<i64>,
+    pub removed_source_files: Vec<String>,
+    /// 从被删除记录合并到保留记录的字段
+    pub merged_fields: Vec<String>,
+}
+
+impl From<DedupMergeGroup> for DedupMergeGroupDto {
+    fn from(group: DedupMergeGroup) -> Self {
+        let DedupMergeGroup { normalized_phone, kept_id, kept_source_file, removed_ids, removed_source_files, merged_fields } = group;
+        Self { normalized_phone, kept_id, kept_source_file, removed_ids, removed_source_files, merged_fields }
+    }
+}
+
+/// 全库去重报告
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DedupReportDto {
+    pub strategy: DedupStrategy,
+    pub dry_run: bool,
+    pub scanned: usize,
+    pub duplicate_groups: usize,
+    pub removed: usize,
+    pub started_at: String,
+    pub finished_at: String,
+    pub groups: Vec<DedupMergeGroupDto>,
+}
+
+impl From<DedupReport> for DedupReportDto {
+    fn from(report: DedupReport) -> Self {
+        let DedupReport { strategy, dry_run, scanned, duplicate_groups, removed, started_at, finished_at, groups } = report;
+        Self {
+            strategy,
+            dry_run,
+            scanned,
+            duplicate_groups,
+            removed,
+            started_at,
+            finished_at,
+            groups: groups.into_iter().map(Into::into).collect(),
+        }
+    }
+}
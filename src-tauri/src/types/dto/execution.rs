@@ -0,0 +1,151 @@
+// src-tauri/src/types/dto/execution.rs
+// module: types | layer: dto | role: 步骤执行命令 DTO
+// summary: run_step_v2 的请求 / 响应 DTO，以及坐标与时长参数的规范化（统一落到执行器读取的 `duration_ms` 等键）
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::commands::run_step_v2::{Bounds, MatchCandidate, RunStepRequestV2, StepResponseV2, StepRunMode, StrategyKind};
+
+/// 单步执行请求
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunStepRequestDto {
+    #[serde(alias = "device_id")]
+    pub device_id: String,
+    pub mode: StepRunMode,
+    pub strategy: StrategyKind,
+    /// 步骤载荷（结构随动作变化），执行前经 [`normalize_step_params`] 规范化
+    pub step: Value,
+    #[serde(default, alias = "run_id")]
+    pub run_id: Option<String>,
+}
+
+impl From<RunStepRequestDto> for RunStepRequestV2 {
+    fn from(dto: RunStepRequestDto) -> Self {
+        let RunStepRequestDto { device_id, mode, strategy, step, run_id } = dto;
+        RunStepRequestV2 { device_id, mode, strategy, step, run_id }
+    }
+}
+
+/// 匹配到的候选元素
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MatchCandidateDto {
+    pub id: String,
+    pub score: f64,
+    pub confidence: f64,
+    pub bounds: Bounds,
+    pub text: Option<String>,
+    pub class_name: Option<String>,
+    pub package_name: Option<String>,
+}
+
+impl From<MatchCandidate> for MatchCandidateDto {
+    fn from(candidate: MatchCandidate) -> Self {
+        let MatchCandidate { id, score, confidence, bounds, text, class_name, package_name } = candidate;
+        Self { id, score, confidence, bounds, text, class_name, package_name }
+    }
+}
+
+/// 单步执行响应
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StepResponseDto {
+    pub ok: bool,
+    pub message: String,
+    pub matched: Option<MatchCandidateDto>,
+    pub executed_action: Option<String>,
+    pub verify_passed: Option<bool>,
+    pub error_code: Option<String>,
+    pub raw_logs: Option<Vec<String>>,
+}
+
+impl From<StepResponseV2> for StepResponseDto {
+    fn from(response: StepResponseV2) -> Self {
+        let StepResponseV2 { ok, message, matched, executed_action, verify_passed, error_code, raw_logs } = response;
+        Self { ok, message, matched: matched.map(Into::into), executed_action, verify_passed, error_code, raw_logs }
+    }
+}
+
+/// 坐标参数（步骤的 `coordinateParams`）；时长 `duration` / `durationMs` / `duration_ms` 均可
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CoordinateParamsDto {
+    #[serde(default, alias = "start_x")]
+    pub start_x: Option<i64>,
+    #[serde(default, alias = "start_y")]
+    pub start_y: Option<i64>,
+    #[serde(default, alias = "end_x")]
+    pub end_x: Option<i64>,
+    #[serde(default, alias = "end_y")]
+    pub end_y: Option<i64>,
+    #[serde(default, alias = "duration", alias = "duration_ms")]
+    pub duration_ms: Option<u64>,
+}
+
+/// 规范化步骤参数：展开 `coordinateParams`，并把各种写法的时长统一为 `duration_ms`
+///
+/// 规范化后 `duration_ms` 即为权威时长，执行器优先读取它。
+pub fn normalize_step_params(step: &Value) -> Value {
+    let mut normalized = step.clone();
+    let Some(obj) = normalized.as_object_mut() else {
+        return normalized;
+    };
+
+    if obj.get("duration_ms").is_none() {
+        if let Some(duration) = obj.get("durationMs").or_else(|| obj.get("duration")).cloned() {
+            obj.insert("duration_ms".to_string(), duration);
+        }
+    }
+
+    if let Some(raw) = obj.get("coordinateParams").cloned() {
+        match serde_json::from_value::<CoordinateParamsDto>(raw) {
+            Ok(params) => {
+                let CoordinateParamsDto { start_x, start_y, end_x, end_y, duration_ms } = params;
+                let fields = [
+                    ("start_x", start_x.map(Value::from)),
+                    ("start_y", start_y.map(Value::from)),
+                    ("end_x", end_x.map(Value::from)),
+                    ("end_y", end_y.map(Value::from)),
+                    ("duration_ms", duration_ms.map(Value::from)),
+                ];
+                for (key, value) in fields {
+                    if let Some(value) = value {
+                        obj.insert(key.to_string(), value);
+                    }
+                }
+            }
+            Err(e) => tracing::warn!("⚠️ coordinateParams 格式无效，已忽略: {}", e),
+        }
+    }
+    normalized
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_normalize_step_params_unifies_duration() {
+        let camel = normalize_step_params(&json!({
+            "action": "swipe",
+            "coordinateParams": { "startX": 100, "startY": 200, "endX": 100, "endY": 50, "durationMs": 400 }
+        }));
+        assert_eq!(camel["start_x"], json!(100));
+        assert_eq!(camel["end_y"], json!(50));
+        assert_eq!(camel["duration_ms"], json!(400));
+
+        let legacy = normalize_step_params(&json!({
+            "action": "swipe",
+            "coordinateParams": { "start_x": 1, "duration": 500 }
+        }));
+        assert_eq!(legacy["start_x"], json!(1));
+        assert_eq!(legacy["duration_ms"], json!(500));
+
+        // 根层级的 duration（长按等）同样落到 duration_ms
+        let long_press = normalize_step_params(&json!({ "action": "long_press", "duration": 1500 }));
+        assert_eq!(long_press["duration_ms"], json!(1500));
+    }
+}
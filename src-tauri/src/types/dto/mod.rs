@@ -0,0 +1,103 @@
+// src-tauri/src/types/dto/mod.rs
+// module: types | layer: dto | role: 命令边界数据传输对象
+// summary: 前后端命令的入参 / 出参统一为 camelCase 的 DTO；入参兼容旧的 snake_case 键，出参由领域类型转换而来
+//
+// 约定：
+// - DTO 一律 `#[serde(rename_all = "camelCase")]`，不在字段上逐个 rename；
+// - 入参 DTO 用 `alias` 接受旧的 snake_case 键，前端迁移期间两种写法都能用；
+// - 与领域类型之间的转换完整解构（不写 `..`），领域类型增删字段时编译失败，必须在 DTO 里明确取舍。
+
+pub mod contacts;
+pub mod execution;
+pub mod prospecting;
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{json, Value};
+
+    /// 递归检查对象键均为 camelCase（不含下划线）
+    fn assert_camel_case(value: &Value, path: &str) {
+        match value {
+            Value::Object(map) => {
+                for (key, child) in map {
+                    assert!(!key.contains('_'), "{} 下的键 {} 不是 camelCase", path, key);
+                    assert_camel_case(child, &format!("{}.{}", path, key));
+                }
+            }
+            Value::Array(items) => items.iter().for_each(|item| assert_camel_case(item, path)),
+            _ => {}
+        }
+    }
+
+    #[test]
+    fn test_command_dtos_serialize_camel_case() {
+        use super::{contacts, execution, prospecting};
+        use crate::commands::run_step_v2::{Bounds, MatchCandidate, StepResponseV2};
+        use crate::services::contact_storage::models::ImportNumbersResult;
+        use crate::services::prospecting::{ReplyPlanStats, Statistics};
+
+        let response = execution::StepResponseDto::from(StepResponseV2 {
+            ok: true,
+            message: "ok".into(),
+            matched: Some(MatchCandidate {
+                id: "c1".into(),
+                score: 0.9,
+                confidence: 0.8,
+                bounds: Bounds { left: 0, top: 0, right: 10, bottom: 10 },
+                text: Some("关注".into()),
+                class_name: Some("android.widget.Button".into()),
+                package_name: None,
+            }),
+            executed_action: Some("tap".into()),
+            verify_passed: Some(true),
+            error_code: None,
+            raw_logs: Some(vec!["log".into()]),
+        });
+        let import = contacts::ImportNumbersResultDto::from(ImportNumbersResult {
+            success: true,
+            total_files: 1,
+            total_numbers: 3,
+            inserted: 2,
+            duplicates: 1,
+            errors: Vec::new(),
+        });
+        let folder = contacts::FolderImportResultDto {
+            summary: import.clone(),
+            session_id: 7,
+            skipped_files: 0,
+            failed_files: vec![contacts::FolderImportFileErrorDto { file_path: "a.txt".into(), error: "x".into() }],
+        };
+        let statistics = prospecting::StatisticsDto::from(Statistics {
+            total_comments: 1,
+            analyzed_comments: 0,
+            intent_distribution: Default::default(),
+            platform_distribution: Default::default(),
+            reply_plans: ReplyPlanStats { total: 0, completed: 0, failed: 0, pending: 0 },
+        });
+
+        for value in [
+            serde_json::to_value(&response).unwrap(),
+            serde_json::to_value(&import).unwrap(),
+            serde_json::to_value(&folder).unwrap(),
+            serde_json::to_value(&statistics).unwrap(),
+        ] {
+            assert_camel_case(&value, "$");
+        }
+        assert_eq!(serde_json::to_value(&response).unwrap()["matched"]["className"], json!("android.widget.Button"));
+        assert_eq!(serde_json::to_value(&folder).unwrap()["totalNumbers"], json!(3));
+    }
+
+    #[test]
+    fn test_request_dtos_accept_legacy_snake_case() {
+        let camel: super::execution::RunStepRequestDto = serde_json::from_value(json!({
+            "deviceId": "d1", "mode": "match-only", "strategy": "intelligent", "step": {}, "runId": "r1"
+        }))
+        .unwrap();
+        let snake: super::execution::RunStepRequestDto = serde_json::from_value(json!({
+            "device_id": "d1", "mode": "match-only", "strategy": "intelligent", "step": {}, "run_id": "r1"
+        }))
+        .unwrap();
+        assert_eq!(camel.device_id, snake.device_id);
+        assert_eq!(camel.run_id, snake.run_id);
+    }
+}
@@ -0,0 +1,279 @@
+// src-tauri/src/types/dto/prospecting.rs
+// module: types | layer: dto | role: 精准获客命令 DTO
+// summary: 评论、AI 分析结果、回复计划与统计的 camelCase DTO；入参同时接受旧的 snake_case 键
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+
+use crate::services::prospecting::{
+    AnalysisResult, Comment, CommentLanguage, Entities, IntentType, RawComment, ReplyPlan, ReplyPlanStats, ReplyPlanStatus,
+    ReplyStep, SocialPlatform, Statistics,
+};
+
+/// 原始评论（入库入参，也是评论出参的基础字段）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RawCommentDto {
+    pub id: String,
+    pub platform: SocialPlatform,
+    #[serde(default, alias = "video_url")]
+    pub video_url: Option<String>,
+    pub author: String,
+    pub content: String,
+    #[serde(default)]
+    pub timestamp: Option<i64>,
+    #[serde(default, alias = "avatar_url")]
+    pub avatar_url: Option<String>,
+    #[serde(default, alias = "like_count")]
+    pub like_count: Option<i32>,
+    #[serde(default)]
+    pub metadata: Option<HashMap<String, Value>>,
+}
+
+impl From<RawComment> for RawCommentDto {
+    fn from(raw: RawComment) -> Self {
+        let RawComment { id, platform, video_url, author, content, timestamp, avatar_url, like_count, metadata } = raw;
+        Self { id, platform, video_url, author, content, timestamp, avatar_url, like_count, metadata }
+    }
+}
+
+impl From<RawCommentDto> for RawComment {
+    fn from(dto: RawCommentDto) -> Self {
+        let RawCommentDto { id, platform, video_url, author, content, timestamp, avatar_url, like_count, metadata } = dto;
+        Self { id, platform, video_url, author, content, timestamp, avatar_url, like_count, metadata }
+    }
+}
+
+/// 分析提取的实体
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EntitiesDto {
+    #[serde(default)]
+    pub product: Option<String>,
+    #[serde(default)]
+    pub quantity: Option<String>,
+    #[serde(default)]
+    pub location: Option<String>,
+    #[serde(default)]
+    pub contact: Option<String>,
+    #[serde(default, alias = "price_range")]
+    pub price_range: Option<String>,
+    #[serde(default)]
+    pub brand: Option<String>,
+    #[serde(default)]
+    pub model: Option<String>,
+}
+
+impl From<Entities> for EntitiesDto {
+    fn from(entities: Entities) -> Self {
+        let Entities { product, quantity, location, contact, price_range, brand, model } = entities;
+        Self { product, quantity, location, contact, price_range, brand, model }
+    }
+}
+
+impl From<EntitiesDto> for Entities {
+    fn from(dto: EntitiesDto) -> Self {
+        let EntitiesDto { product, quantity, location, contact, price_range, brand, model } = dto;
+        Self { product, quantity, location, contact, price_range, brand, model }
+    }
+}
+
+/// AI 分析结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AnalysisResultDto {
+    #[serde(alias = "comment_id")]
+    pub comment_id: String,
+    pub intent: IntentType,
+    pub confidence: f64,
+    #[serde(default)]
+    pub entities: EntitiesDto,
+    #[serde(alias = "suggested_reply")]
+    pub suggested_reply: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(alias = "analyzed_at")]
+    pub analyzed_at: i64,
+}
+
+impl From<AnalysisResult> for AnalysisResultDto {
+    fn from(analysis: AnalysisResult) -> Self {
+        let AnalysisResult { comment_id, intent, confidence, entities, suggested_reply, tags, analyzed_at } = analysis;
+        Self { comment_id, intent, confidence, entities: entities.into(), suggested_reply, tags, analyzed_at }
+    }
+}
+
+impl From<AnalysisResultDto> for AnalysisResult {
+    fn from(dto: AnalysisResultDto) -> Self {
+        let AnalysisResultDto { comment_id, intent, confidence, entities, suggested_reply, tags, analyzed_at } = dto;
+        Self { comment_id, intent, confidence, entities: entities.into(), suggested_reply, tags, analyzed_at }
+    }
+}
+
+/// 评论（含分析与回复状态）
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommentDto {
+    #[serde(flatten)]
+    pub raw: RawCommentDto,
+    pub analysis: Option<AnalysisResultDto>,
+    pub is_replied: Option<bool>,
+    pub replied_at: Option<i64>,
+    pub actual_reply: Option<String>,
+    /// 入库时识别的语言
+    pub language: CommentLanguage,
+}
+
+impl From<Comment> for CommentDto {
+    fn from(comment: Comment) -> Self {
+        let Comment { raw, analysis, is_replied, replied_at, actual_reply, language } = comment;
+        Self { raw: raw.into(), analysis: analysis.map(Into::into), is_replied, replied_at, actual_reply, language }
+    }
+}
+
+/// 回复计划（步骤结构沿用 `ReplyStep`，其字段均为单词）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReplyPlanDto {
+    pub id: String,
+    #[serde(alias = "comment_id")]
+    pub comment_id: String,
+    pub platform: SocialPlatform,
+    #[serde(alias = "video_url")]
+    pub video_url: String,
+    #[serde(alias = "target_author")]
+    pub target_author: String,
+    #[serde(alias = "target_comment")]
+    pub target_comment: String,
+    #[serde(alias = "reply_content")]
+    pub reply_content: String,
+    pub steps: Vec<ReplyStep>,
+    pub status: ReplyPlanStatus,
+    #[serde(alias = "created_at")]
+    pub created_at: i64,
+    #[serde(alias = "updated_at")]
+    pub updated_at: i64,
+    #[serde(default, alias = "executed_at")]
+    pub executed_at: Option<i64>,
+    #[serde(default, alias = "completed_at")]
+    pub completed_at: Option<i64>,
+    #[serde(default)]
+    pub error: Option<String>,
+    #[serde(default, alias = "is_simulation")]
+    pub is_simulation: bool,
+    #[serde(default, alias = "campaign_id")]
+    pub campaign_id: Option<String>,
+    #[serde(default, alias = "scheduled_at")]
+    pub scheduled_at: Option<i64>,
+    #[serde(default, alias = "media_asset_id")]
+    pub media_asset_id: Option<String>,
+}
+
+impl From<ReplyPlan> for ReplyPlanDto {
+    fn from(plan: ReplyPlan) -> Self {
+        let ReplyPlan {
+            id,
+            comment_id,
+            platform,
+            video_url,
+            target_author,
+            target_comment,
+            reply_content,
+            steps,
+            status,
+            created_at,
+            updated_at,
+            executed_at,
+            completed_at,
+            error,
+            is_simulation,
+            campaign_id,
+            scheduled_at,
+            media_asset_id,
+        } = plan;
+        Self {
+            id,
+            comment_id,
+            platform,
+            video_url,
+            target_author,
+            target_comment,
+            reply_content,
+            steps,
+            status,
+            created_at,
+            updated_at,
+            executed_at,
+            completed_at,
+            error,
+            is_simulation,
+            campaign_id,
+            scheduled_at,
+            media_asset_id,
+        }
+    }
+}
+
+impl From<ReplyPlanDto> for ReplyPlan {
+    fn from(dto: ReplyPlanDto) -> Self {
+        let ReplyPlanDto {
+            id,
+            comment_id,
+            platform,
+            video_url,
+            target_author,
+            target_comment,
+            reply_content,
+            steps,
+            status,
+            created_at,
+            updated_at,
+            executed_at,
+            completed_at,
+            error,
+            is_simulation,
+            campaign_id,
+            scheduled_at,
+            media_asset_id,
+        } = dto;
+        Self {
+            id,
+            comment_id,
+            platform,
+            video_url,
+            target_author,
+            target_comment,
+            reply_content,
+            steps,
+            status,
+            created_at,
+            updated_at,
+            executed_at,
+            completed_at,
+            error,
+            is_simulation,
+            campaign_id,
+            scheduled_at,
+            media_asset_id,
+        }
+    }
+}
+
+/// 获客统计（分布的键为意图 / 平台名）
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StatisticsDto {
+    pub total_comments: i64,
+    pub analyzed_comments: i64,
+    pub intent_distribution: HashMap<String, i64>,
+    pub platform_distribution: HashMap<String, i64>,
+    pub reply_plans: ReplyPlanStats,
+}
+
+impl From<Statistics> for StatisticsDto {
+    fn from(statistics: Statistics) -> Self {
+        let Statistics { total_comments, analyzed_comments, intent_distribution, platform_distribution, reply_plans } = statistics;
+        Self { total_comments, analyzed_comments, intent_distribution, platform_distribution, reply_plans }
+    }
+}
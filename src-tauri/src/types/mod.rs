@@ -2,7 +2,10 @@ pub mod action_types;
 pub mod page_analysis;
 pub mod smart_selection;
 pub mod smart_finder; // 新增：智能查找器类型（兼容层）
+pub mod dto; // 命令边界 DTO（统一 camelCase）
+pub mod app_error; // 命令统一错误类型（错误码 + 分类）
 
 // pub use action_types::*; // 操作类型（暂时注释）
 // pub use page_analysis::*;  // 暂时未使用
-pub use smart_selection::*;
\ No newline at end of file
+pub use smart_selection::*;
+pub use app_error::{AppError, AppResult, ErrorCategory, ErrorCode};
\ No newline at end of file
@@ -55,9 +55,9 @@ export class StepExecutionRepositoryV2 {
       console.log(`${result.ok ? '✅' : '❌'} [StepExec V2] 执行结果:`, result);
       
       // 打印详细日志
-      if (result.raw_logs?.length) {
+      if (result.rawLogs?.length) {
         console.log(`📝 [StepExec V2] 后端日志:`);
-        result.raw_logs.forEach((log, i) => console.log(`  ${i + 1}. ${log}`));
+        result.rawLogs.forEach((log, i) => console.log(`  ${i + 1}. ${log}`));
       }
 
       return result;
@@ -69,10 +69,10 @@ export class StepExecutionRepositoryV2 {
         ok: false,
         message: `执行异常: ${error}`,
         matched: undefined,
-        executed_action: undefined,
-        verify_passed: undefined,
-        error_code: 'ADB_ERROR',
-        raw_logs: [`执行异常: ${error}`],
+        executedAction: undefined,
+        verifyPassed: undefined,
+        errorCode: 'ADB_ERROR',
+        rawLogs: [`执行异常: ${error}`],
       };
     }
   }
@@ -21,12 +21,12 @@ export const StatsBar: React.FC<Props> = ({ stats, onRefresh }) => {
         <Tag>未分类：{stats.unclassified}</Tag>
       </Space>
       <Space size={8} wrap>
-        {stats.per_industry.slice(0, 6).map((it) => (
+        {stats.perIndustry.slice(0, 6).map((it) => (
           <Tag key={it.industry} color="geekblue">{it.industry}：{it.count}</Tag>
         ))}
-        {stats.per_industry.length > 6 && (
-          <Tooltip title={stats.per_industry.slice(6).map(i => `${i.industry}：${i.count}`).join('、')}>
-            <Tag>更多 {stats.per_industry.length - 6}</Tag>
+        {stats.perIndustry.length > 6 && (
+          <Tooltip title={stats.perIndustry.slice(6).map(i => `${i.industry}：${i.count}`).join('、')}>
+            <Tag>更多 {stats.perIndustry.length - 6}</Tag>
           </Tooltip>
         )}
         {onRefresh && (
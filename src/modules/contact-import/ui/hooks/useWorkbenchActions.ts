@@ -92,13 +92,13 @@ export const useWorkbenchActions = ({
         
         // 根据实际结果给出不同提示
         if (result.inserted === 0 && result.duplicates === 0) {
-          if (result.total_numbers === 0) {
+          if (result.totalNumbers === 0) {
             message.warning('文件中未找到有效的手机号码');
           } else {
-            message.warning(`文件中有 ${result.total_numbers} 个号码，但全部是重复号码`);
+            message.warning(`文件中有 ${result.totalNumbers} 个号码，但全部是重复号码`);
           }
         } else if (result.inserted === 0) {
-          message.info(`文件中有 ${result.total_numbers} 个号码，但全部已存在（去重 ${result.duplicates} 个）`);
+          message.info(`文件中有 ${result.totalNumbers} 个号码，但全部已存在（去重 ${result.duplicates} 个）`);
         } else {
           message.success(`成功导入 ${result.inserted} 个号码（去重 ${result.duplicates} 个）`);
         }
@@ -120,15 +120,15 @@ export const useWorkbenchActions = ({
         
         // 根据实际结果给出不同提示
         if (result.inserted === 0 && result.duplicates === 0) {
-          if (result.total_numbers === 0) {
+          if (result.totalNumbers === 0) {
             message.warning('文件夹中未找到有效的手机号码');
           } else {
-            message.warning(`文件夹中有 ${result.total_numbers} 个号码，但全部是重复号码`);
+            message.warning(`文件夹中有 ${result.totalNumbers} 个号码，但全部是重复号码`);
           }
         } else if (result.inserted === 0) {
-          message.info(`文件夹中有 ${result.total_numbers} 个号码，但全部已存在（去重 ${result.duplicates} 个）`);
+          message.info(`文件夹中有 ${result.totalNumbers} 个号码，但全部已存在（去重 ${result.duplicates} 个）`);
         } else {
-          message.success(`成功从 ${result.total_files} 个文件导入 ${result.inserted} 个号码（去重 ${result.duplicates} 个）`);
+          message.success(`成功从 ${result.totalFiles} 个文件导入 ${result.inserted} 个号码（去重 ${result.duplicates} 个）`);
         }
         
         await onDataRefresh();
@@ -157,15 +157,15 @@ export const useWorkbenchActions = ({
       
       // 根据实际结果给出不同提示
       if (result.inserted === 0 && result.duplicates === 0) {
-        if (result.total_numbers === 0) {
+        if (result.totalNumbers === 0) {
           message.warning(`已扫描 ${folders.length} 个文件夹，未找到有效的手机号码`);
         } else {
-          message.warning(`已扫描 ${folders.length} 个文件夹，有 ${result.total_numbers} 个号码但全部是重复号码`);
+          message.warning(`已扫描 ${folders.length} 个文件夹，有 ${result.totalNumbers} 个号码但全部是重复号码`);
         }
       } else if (result.inserted === 0) {
-        message.info(`已扫描 ${folders.length} 个文件夹，有 ${result.total_numbers} 个号码但全部已存在（去重 ${result.duplicates} 个）`);
+        message.info(`已扫描 ${folders.length} 个文件夹，有 ${result.totalNumbers} 个号码但全部已存在（去重 ${result.duplicates} 个）`);
       } else {
-        message.success(`成功从 ${folders.length} 个文件夹的 ${result.total_files} 个文件导入 ${result.inserted} 个号码（去重 ${result.duplicates} 个）`);
+        message.success(`成功从 ${folders.length} 个文件夹的 ${result.totalFiles} 个文件导入 ${result.inserted} 个号码（去重 ${result.duplicates} 个）`);
       }
       
       await onDataRefresh();
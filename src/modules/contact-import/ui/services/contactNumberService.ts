@@ -9,8 +9,8 @@ import { invoke } from '@tauri-apps/api/core';
 
 export interface ImportNumbersResult {
   success: boolean;
-  total_files: number;
-  total_numbers: number;
+  totalFiles: number;
+  totalNumbers: number;
   inserted: number;
   duplicates: number;
   errors: string[];
@@ -26,12 +26,12 @@ export async function importNumbersFromFolder(folderPath: string): Promise<Impor
 
 export async function importNumbersFromFolders(folderPaths: string[]): Promise<ImportNumbersResult> {
   // 顺序执行并聚合结果，避免并发导致数据库锁竞争（如使用SQLite）
-  const aggregate: ImportNumbersResult = { success: true, total_files: 0, total_numbers: 0, inserted: 0, duplicates: 0, errors: [] };
+  const aggregate: ImportNumbersResult = { success: true, totalFiles: 0, totalNumbers: 0, inserted: 0, duplicates: 0, errors: [] };
   for (const dir of folderPaths) {
     try {
       const res = await importNumbersFromFolder(dir);
-      aggregate.total_files += res.total_files;
-      aggregate.total_numbers += res.total_numbers;
+      aggregate.totalFiles += res.totalFiles;
+      aggregate.totalNumbers += res.totalNumbers;
       aggregate.inserted += res.inserted;
       aggregate.duplicates += res.duplicates;
       if (!res.success) {
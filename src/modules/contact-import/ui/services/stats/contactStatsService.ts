@@ -13,7 +13,7 @@ export interface ContactNumberStatsDto {
   total: number;
   unclassified: number;
   not_imported: number;
-  per_industry: IndustryCountDto[];
+  perIndustry: IndustryCountDto[];
 }
 
 export async function getContactNumberStats(): Promise<ContactNumberStatsDto> {
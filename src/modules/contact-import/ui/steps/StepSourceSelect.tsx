@@ -125,7 +125,7 @@ export const StepSourceSelect: React.FC<StepSourceSelectProps> = ({ onCompleted
       const result = await importNumbersFromFolders(folders);
       setLastResult(result);
       if (result.success) {
-        message.success(`从 ${result.total_files} 个文件中写入 ${result.inserted} 条号码，重复 ${result.duplicates} 条`);
+        message.success(`从 ${result.totalFiles} 个文件中写入 ${result.inserted} 条号码，重复 ${result.duplicates} 条`);
       } else {
         message.error('导入过程中出现错误');
       }
@@ -195,8 +195,8 @@ export const StepSourceSelect: React.FC<StepSourceSelectProps> = ({ onCompleted
             <Divider />
             <Card size="small" title="导入结果">
               <Descriptions column={2} size="small">
-                <Descriptions.Item label="文件数">{lastResult.total_files}</Descriptions.Item>
-                <Descriptions.Item label="提取号码">{lastResult.total_numbers}</Descriptions.Item>
+                <Descriptions.Item label="文件数">{lastResult.totalFiles}</Descriptions.Item>
+                <Descriptions.Item label="提取号码">{lastResult.totalNumbers}</Descriptions.Item>
                 <Descriptions.Item label="成功写入">{lastResult.inserted}</Descriptions.Item>
                 <Descriptions.Item label="重复跳过">{lastResult.duplicates}</Descriptions.Item>
               </Descriptions>
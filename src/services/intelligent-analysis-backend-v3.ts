@@ -493,12 +493,15 @@ export class IntelligentAnalysisBackendV3 {
     console.log(`🛑 [V3 BackendService] 取消分析: ${jobId}`);
     
     try {
-      // V3使用analysis_id作为取消标识
-      await invoke('plugin:execution_v3|cancel_execution_v3', { analysisId: jobId });
-      console.log('✅ [V3 BackendService] 分析已取消');
+      // V3以任务ID（analysis_id / chain_id）作为取消标识，取消会级联到链与步骤
+      const found = await invoke<boolean>('plugin:execution_v3|cancel_execution_v3', { taskId: jobId });
+      if (found) {
+        console.log('✅ [V3 BackendService] 分析已取消');
+      } else {
+        console.warn(`⚠️ [V3 BackendService] 未找到进行中的任务: ${jobId}`);
+      }
     } catch (error) {
-      // 如果后端未实现cancel_execution_v3命令，降级到空操作
-      console.warn('⚠️ [V3 BackendService] 后端未实现cancel_execution_v3，跳过取消操作');
+      console.warn('⚠️ [V3 BackendService] 取消V3执行失败:', error);
     }
   }
 
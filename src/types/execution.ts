@@ -9,6 +9,16 @@ export interface ExecutorConfig {
   auto_verification_enabled: boolean;
   smart_recovery_enabled: boolean;
   detailed_logging: boolean;
+  /** 运行期间在设备上显示「自动化运行中 – step N/M」，结束或停止时自动清除 */
+  status_indicator?: StatusIndicatorConfig | null;
+}
+
+export interface StatusIndicatorConfig {
+  enabled: boolean;
+  /** notification：通知栏；overlay：设备端代理悬浮层（不可用时回退通知） */
+  style?: 'notification' | 'overlay';
+  minIntervalSecs?: number;
+  label?: string | null;
 }
 
 export interface SmartExecutionResult {
@@ -116,18 +116,18 @@ export interface MatchCandidate {
   confidence: number;      // 置信度（0~1）
   bounds: Bounds;
   text?: string;
-  class_name?: string;
-  package_name?: string;
+  className?: string;
+  packageName?: string;
 }
 
 export interface RunStepResponseV2 {
   ok: boolean;
   message: string;
   matched?: MatchCandidate;     // match-only 返回它；execute-step 也会带上
-  executed_action?: ActionType; // execute-step 成功才有
-  verify_passed?: boolean;      // 若配置 verify_after，会返回
-  error_code?: string;          // 'NO_CANDIDATE' | 'INVALID_ARGS' | 'ADB_ERROR' ...
-  raw_logs?: string[];          // 可选：方便你把后端关键日志抛给前端显示
+  executedAction?: ActionType;  // execute-step 成功才有
+  verifyPassed?: boolean;       // 若配置 verify_after，会返回
+  errorCode?: string;           // 'NO_CANDIDATE' | 'INVALID_ARGS' | 'ADB_ERROR' ...
+  rawLogs?: string[];           // 可选：方便你把后端关键日志抛给前端显示
 }
 
 // 错误码常量